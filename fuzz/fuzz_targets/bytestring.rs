@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Bytestrings read from UDisks: device paths, mount points and options
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use storage_udisks::{decode_c_string, decode_mount_points, encode_bytestring};
+
+fuzz_target!(|data: &[u8]| {
+    let decoded = decode_c_string(data);
+    assert!(!decoded.contains('\0'));
+    assert_eq!(decode_c_string(&encode_bytestring(&decoded)), decoded);
+
+    // A list of mount points, split where the data has newlines
+    let entries: Vec<Vec<u8>> = data.split(|b| *b == b'\n').map(<[u8]>::to_vec).collect();
+    let mount_points = decode_mount_points(entries);
+    assert!(mount_points.iter().all(|point| !point.is_empty()));
+});
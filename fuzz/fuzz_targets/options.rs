@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Mount and crypt options as they come from fstab, crypttab and UDisks
+//! configuration items
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use storage_udisks::{join_options, normalize_options, option_tokens, split_options};
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+
+    let tokens = split_options(&input);
+    assert_eq!(tokens.len(), option_tokens(&input).count());
+    for token in &tokens {
+        assert!(!token.is_empty());
+        assert!(!token.contains(','));
+        assert!(!token.contains(char::is_control));
+        assert_eq!(token.trim(), token);
+    }
+
+    // Joining and splitting again gives the same tokens, and normalizing
+    // is idempotent
+    assert_eq!(split_options(&join_options(&tokens)), tokens);
+    let normalized = normalize_options(&input);
+    assert_eq!(normalize_options(&normalized), normalized);
+});
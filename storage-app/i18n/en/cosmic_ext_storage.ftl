@@ -1,4 +1,5 @@
 app-title = Storage
+app-title-remote = Storage on { $address }
 settings = Settings
 about = About
 
@@ -21,19 +22,164 @@ choose-path = Choose...
 no-file-selected = No file selected
 attach = Attach
 restore-warning = This will overwrite the selected target device. This cannot be undone.
+copy-partition = Copy Partition
+copy = Copy
+copy-partition-target = Target partition
+copy-partition-no-targets = No other partition is large enough to hold a copy of this one.
+copy-partition-warning = This will overwrite the target partition. Both partitions are unmounted while copying; the copy gets a new UUID and grows to fill the target where its filesystem allows.
+migrate-disk = Migrate to a Larger Drive
+migrate = Migrate
+migrate-disk-target = Target drive
+migrate-disk-no-targets = No other drive is large enough to hold the partitions of this one.
+migrate-disk-proportional = Grow all partitions in proportion to their size
+migrate-disk-gpt-only = Only drives with a GPT partition table can be migrated.
+migrate-disk-warning = This will erase everything on the target drive. Partitions keep their UUIDs, so the target can replace this drive; if anything fails, the target is left blank.
+create-live-usb = Create Bootable USB Drive
+live-usb-choose-iso = Choose a distribution ISO image to write to the drive.
+live-usb-no-persistence = This image does not support keeping changes; only a data partition can be added.
+live-usb-persistence = Keep changes made in the live system
+live-usb-persistence-size = Persistence size
+live-usb-data-partition = Add an exFAT partition for files in the remaining space
+live-usb-warning = The image is written to the start of the drive and the extra partitions follow it. If anything fails, the drive is left blank.
+live-usb-erase-consent = Erase everything on { $drive }
+live-usb-boot-hint = The drive is ready. To keep changes, add "{ $parameter }" to the boot options in the drive's boot menu.
+advanced-performance = Advanced Performance
+performance-scheduler = I/O scheduler
+performance-scheduler-help = bfq keeps the desktop responsive while the drive is busy, mq-deadline suits most SSDs and hard disks, and none leaves ordering to fast NVMe drives.
+performance-read-ahead = Readahead
+performance-nr-requests = Requests in flight
+performance-write-cache = Write cache
+performance-write-cache-enabled = Let the drive cache writes
+performance-write-cache-on-warning = Writes are faster, but data the drive has accepted and not yet stored is lost if power fails. Only drives with power-loss protection keep it.
+performance-write-cache-off-warning = Writes are stored before the drive reports them done, so a power failure loses nothing already written, but writing is much slower.
+performance-write-cache-unsupported = This drive does not allow switching its write cache, or the hdparm or nvme tool is missing.
+performance-persistent = Keep these settings after restarting
+performance-persistent-help = Settings are kept in a udev rule that finds the drive by its serial number, so they follow it to any port.
+esp-sync = Sync EFI System Partition
+esp-sync-now = Sync Now
+esp-sync-secondary = Secondary EFI System Partition
+esp-sync-no-secondary = There is no other EFI System Partition to keep in sync with this one.
+esp-sync-no-uuid = This partition has no partition UUID, so it cannot be synced.
+esp-sync-schedule = Sync automatically
+esp-sync-manual = Only when requested
+esp-sync-daily = Every day
+esp-sync-every = Every {$days} days
+esp-sync-help = Files changed on this partition are copied to the secondary one, and files removed here are deleted there, so either drive can boot the system.
+low-space = Low Space Alert
+low-space-off = Off
+low-space-percent = Below { $percent }%
+low-space-min-percent = Warn when the free space drops below
+low-space-min-bytes = Or when less than this is free
+low-space-action = When it does
+low-space-action-warn = Only warn
+low-space-action-cleanup = Remove expired safety snapshots
+low-space-action-scan = Find what takes up the space
+low-space-help = The free space is checked every few minutes. You are warned once, and again only after space was freed in between.
+esp-sync-complete = Sync complete: {$copied} files copied, {$deleted} removed.
+partitions = Partitions
+burn-disc-image = Burn Image to Disc
+burn-image = Burn
+erase-disc = Erase Disc
+erase = Erase
+burn-disc-warning = A written rewritable disc will be erased first.
+erase-disc-warning = This will erase all data on the disc. This cannot be undone.
+verify-after-burning = Verify the disc after burning
+full-erase = Overwrite the whole disc (slower)
+disc = Disc
+disc-none = No disc
+disc-blank = Blank
+disc-appendable = Appendable
+disc-closed = Closed
+disc-usage = { $used } used of { $capacity }
+write-protected = Write-protected
+write-protected-description = This medium cannot be changed. If it is an SD card, slide its lock switch to the unlocked position and insert it again.
 eject = Eject
 eject-failed = Eject failed
 power-off = Power Off
 power-off-failed = Power off failed
 format-disk = Format Disk
 format-disk-failed = Format disk failed
+find-lost-partitions = Find Lost Partitions
+lost-partitions-description = Filesystems found in the free space of the disk. Recreating a partition adds it to the partition table without changing its contents.
+lost-partitions-searching = Searching the free space…
+lost-partitions-none = No lost partitions were found.
+lost-partition-entry = {$fs_type} {$label} — {$size} at {$offset} ({$confidence})
+confidence-high = likely intact
+confidence-medium = size uncertain
+confidence-low = overlaps another partition
+recreate-partitions = Recreate Partitions
+edit-gpt-entries = Edit Table Entries
+gpt-entries-description = The raw entries of the GUID partition table. Changes are checked together and written to both copies of the table at once; partitions in use keep their old bounds until the next boot.
+gpt-entries-usable = Usable sectors { $first } to { $last }, { $sector_size } bytes each
+gpt-entries-damaged = The partition table fails its checksums and cannot be edited here. Repair it first.
+gpt-entries-pending = { $count ->
+    [one] 1 change
+   *[other] { $count } changes
+} ready to write.
+gpt-entries-write = Write Table
+gpt-entries-revert = Revert
+gpt-entry = Partition { $number }
+gpt-entry-first-lba = First sector
+gpt-entry-last-lba = Last sector
+gpt-entry-attributes = Attributes
+gpt-entry-type-guid = Type GUID
+gpt-entry-guid = Partition GUID
+gpt-entry-name = Name
 smart-data-self-tests = SMART Data & Self-Tests
 standby-now = Standby Now
 standby-failed = Standby failed
+physical-devices = Physical Devices
+physical-device-healthy = SMART passed
+physical-device-failing = SMART reports failure
+physical-device-no-data = No SMART data
+physical-device-behind = Behind the { $controller } controller at { $device }
+physical-device-serial = Serial number: { $serial }
+physical-device-read-only = This disk belongs to a hardware RAID array and can only be managed with the controller's own tools.
+identify-drive = Identify Drive
+identify-drive-failed = Identify failed
+identify-drive-blinking = The drive's slot LED blinks for { $seconds } seconds.
+identify-drive-slot-blinking = The LED of { $slot } blinks for { $seconds } seconds.
+trim-filesystems = Trim Filesystems
+trim-filesystems-failed = Trim failed
+trim-filesystems-done = Trimmed { $size } from { $count ->
+    [one] 1 filesystem
+   *[other] { $count } filesystems
+}.
+view-sectors = View Sectors
 wake-up-from-standby = Wake-up From Standby
 wake-up-failed = Wake-up failed
 unmount-failed = Unmount failed
 
+# Sector viewer
+sector-viewer = Sectors
+sector-viewer-position = { $device } at offset { $offset } (sector { $sector }) of { $size }
+sector-viewer-read-only = The device is opened read-only; nothing shown here can be changed.
+sector-viewer-offset-placeholder = Offset, e.g. 4096, 0x1000 or LBA 8
+sector-viewer-jump = Go
+sector-viewer-invalid-offset = "{ $offset }" is not an offset on this device.
+sector-structure-mbr = MBR
+sector-structure-gpt-header = GPT header
+sector-structure-gpt-entry = GPT partition
+sector-structure-luks = LUKS header
+sector-structure-ext = ext superblock
+sector-structure-btrfs = Btrfs superblock
+sector-structure-xfs = XFS superblock
+sector-structure-fat = FAT boot sector
+sector-structure-ntfs = NTFS boot sector
+sector-structure-swap = Swap header
+
+# Volume inspector
+inspect-volume = Inspect
+inspect-structure = { $structure } at { $offset }
+inspect-nothing-found = No superblock or LUKS header was recognized on this volume.
+
+# Storage service and sandbox
+service-unreachable = Storage Service Unreachable
+service-missing = The storage service ({ $service }) is not running. Install it, or start it with "systemctl enable --now cosmic-ext-storage-service.service".
+sandbox-no-system-bus = This Flatpak cannot reach the system bus. Allow it to talk to the storage service with:
+    { $command }
+sandbox-service-missing = The storage service ({ $service }) runs outside the Flatpak and must be installed on the host system.
+
 # Unmount busy dialog
 unmount-busy-title-template = {$device} is Busy
 unmount-busy-message-template = The following processes are accessing {$mount}
@@ -67,6 +213,18 @@ partitioning-dos-mbr = Legacy Compatible (DOS/MBR)
 partitioning-gpt = Modern (GPT)
 partitioning-none = None
 
+# Drive setup wizard
+setup-drive = Set Up Drive
+setup-drive-title = Set Up New Drive
+setup-drive-intro = { $name } ({ $size }) holds no partitions or data yet. Choose how to set it up; it will get one partition spanning the whole drive.
+setup-drive-encryption = Encryption
+setup-drive-review = Review
+setup-drive-no-label = (none)
+setup-drive-encrypted = The partition will be encrypted with the password
+setup-drive-not-encrypted = The partition will not be encrypted
+setup-drive-undo-note = If a step fails, the changes made so far are undone and the drive is left blank.
+setup-drive-failed = Setting up the drive failed and it was left blank: { $error }
+
 # Create partition dialog
 create-partition = Create Partition
 create-partition-failed = Create partition failed
@@ -91,6 +249,7 @@ no-disk-selected = No disk selected
 no-volumes = No volumes available
 partition-number = Partition { $number }
 partition-number-with-name = Partition { $number }: { $name }
+restore-deleted-partition = Restore deleted { $name } ({ $size }) exactly as it was
 volumes = Volumes
 unknown = Unknown
 unresolved = Unresolved
@@ -99,21 +258,49 @@ unresolved = Unresolved
 size = Size
 usage = Usage
 mounted-at = Mounted at
+capacity-full-in = Full in about { $days ->
+    [one] 1 day
+   *[other] { $days } days
+} (grows { $growth } a day)
 contents = Contents
 device = Device
 partition = Partition
 path = Path
 uuid = UUID
+filesystem-advanced = Advanced
+filesystem-features = Features
+filesystem-reflink = Reflink copies
+filesystem-metadata-checksums = Metadata checksums
+filesystem-compression = Compression
+filesystem-can-shrink = Can shrink
+filesystem-can-grow-online = Can grow while mounted
+filesystem-features-unavailable = Features unavailable
+filesystem-feature-yes = Yes
+filesystem-feature-no = No
+filesystem-feature-none = None
 model = Model
 serial = Serial
 partitioning = Partitioning
 backing-file = Backing File
+sector-size = Sector Size
+sector-sizes = { $logical } B logical, { $physical } B physical
+virtual-disk = Virtual Disk
+virtual-disk-discard = Discard supported
+virtual-disk-no-discard = No discard support
+virtual-disk-rotational = Reported as rotational
+virtual-disk-non-rotational = Reported as non-rotational
+misaligned-partitions = { $count ->
+    [one] 1 partition is misaligned
+   *[other] { $count } partitions are misaligned
+}
+misaligned-partitions-description = They do not start on a { $sector }-byte physical sector, which slows down every write, especially on SMR drives. Select one and choose Realign.
 
 # Confirmation dialog
 delete = Delete { $name }
 delete-partition = Delete
 delete-confirmation = Are you sure you wish to delete { $name }?
 delete-failed = Delete failed
+restore-partition-failed = Restore failed
 
 # Volume segments
 free-space-segment = Free Space
@@ -153,15 +340,77 @@ flag-hide-from-firmware = Hide from firmware
 resize-partition = Resize Partition
 resize = Resize
 resize-partition-range = Allowed range: { $min } to { $max }
+resize-partition-preview = Now { $current }, after resizing { $new }, { $free } free after it
+resize-partition-checking = Checking how far the filesystem can shrink…
+resize-partition-grow-only = How far the filesystem can shrink is unknown; the partition can only grow.
+resize-partition-shrink-unsupported = { $fs_type } filesystems can't shrink.
+resize-partition-shrink-mounted = Unmount the filesystem to shrink it.
+resize-partition-shrink-unknown = The filesystem couldn't tell how far it can shrink.
+resize-partition-grow-mounted = Unmount the filesystem for it to grow with the partition.
+resize-partition-grow-unsupported = { $fs_type } filesystems can't grow; the new space stays unused.
+resize-step-shrink-filesystem = Shrink the filesystem to { $size }
+resize-step-resize-partition = Resize the partition to { $size }
+resize-step-grow-filesystem = Grow the filesystem to fill the partition
+realign-partition = Realign
+realign-partition-warning = The partition and its contents will be moved to start on a 1 MiB boundary, which suits the { $sector }-byte physical sectors of this drive. Moving copies the whole partition, so it can take a long time; do not unplug the drive meanwhile. Back up important data first.
+move-partition = Move Partition
+move = Move
+move-partition-checking = Checking where the partition can move…
+move-partition-no-space = There is no free space next to the partition to move it into.
+free-space-before = Free space before
+move-partition-preview = { $before } free before, { $after } free after
+move-partition-warning = Moving copies the whole partition, so it can take a long time and cannot be cancelled. Do not unplug the drive meanwhile; an interrupted move leaves the partition unusable. Back up important data first.
 new-size = New Size
 edit-filesystem = Edit Filesystem
 label = Label
 filesystem-label = Filesystem Label
 check-filesystem = Check Filesystem
 check-filesystem-warning = Checking a filesystem can take a long time. Continue?
+defragment = Defragment
+defrag-target = Directory
+defrag-target-help = Leave empty to defragment the whole filesystem.
+defrag-fragmentation = Fragmentation
+defrag-analyze = Analyze
+defrag-analyzing = Analyzing…
+defrag-score = Fragmentation score: {$score}%
+defrag-score-unavailable = This filesystem does not report a fragmentation score.
+defrag-recommended = Defragmentation is recommended.
+defrag-not-needed = Defragmentation is not needed.
+defrag-progress = {$processed} files processed
+defrag-progress-total = {$processed} of {$total} files processed
+defrag-complete = Defragmentation complete, {$processed} files processed.
+
+# Encrypted folders (fscrypt)
+encrypted-folders = Encrypted Folders
+encrypted-folders-help = Folders on this filesystem can be encrypted one by one, each with its own passphrase, while the rest stays readable.
+encrypted-folders-loading = Looking for encrypted folders…
+encrypted-folders-no-fscrypt = The fscrypt tool is not installed.
+encrypted-folders-none = No encrypted folders found.
+encrypted-folders-unlocked = Unlocked
+encrypted-folders-locked = Locked
+encrypted-folders-new = New Encrypted Folder
+encrypted-folders-path = Folder
+encrypted-folders-path-placeholder = home/user/private
+encrypted-folders-protector = Protector name
+encrypted-folders-protector-placeholder = Defaults to the folder name
+encrypted-folders-setup-note = The filesystem will be set up for encryption first.
+encrypted-folders-create = Create
 repair-filesystem = Repair Filesystem
 repair = Repair
 repair-filesystem-warning = Repairing a filesystem can take a long time and may risk data loss. Continue?
+filesystem-read-only = { $mount_point } is read-only
+filesystem-read-only-description = The kernel switched this filesystem to read-only after an error to protect your data. Repair it to fix the error, or remount it read-write to keep working and repair it later.
+repair-and-remount = Unmount and Repair
+remount-read-write = Remount Read-Write
+remount-read-write-failed = Remount failed
+repair-read-only-warning = { $mount_point } will be unmounted, repaired and mounted again. Close any files open on it first. Repairing may risk data loss. Continue?
+repair-read-only-busy = The filesystem is in use. Close any files open on it and try again.
+mount-filesystem-dirty = The Filesystem Was Not Cleanly Unmounted
+mount-filesystem-corrupt = The Filesystem Is Damaged
+repair-and-mount = Repair and Mount
+windows-hibernated = Windows Is Still Using This Filesystem
+windows-hibernated-body = Windows is hibernated with this filesystem in use. Changing it now corrupts it when Windows resumes. Mount it read-only instead? To write to it, start Windows and shut it down fully first.
+windows-fast-startup-body = Windows was shut down with Fast Startup, which keeps this filesystem in use. Changing it now can corrupt it the next time Windows starts. Mount it read-only instead? To write to it, turn off Fast Startup in Windows, or restart Windows and shut it down fully.
 take-ownership = Take Ownership
 take-ownership-warning = This will change ownership of files to your user. This can take a long time and cannot be easily undone.
 take-ownership-recursive = Apply recursively
@@ -175,6 +424,8 @@ require-auth-to-unlock = Require authorization to unlock
 show-in-ui = Show in user interface
 identify-as = Identify As
 other-options = Other options
+mount-preset = Preset
+mount-preset-none = None
 mount-point = Mount point
 filesystem-type = Filesystem type
 display-name = Display name
@@ -193,6 +444,93 @@ smart-selftest = Self-test
 smart-selftest-short = Short self-test
 smart-selftest-extended = Extended self-test
 smart-selftest-abort = Abort self-test
+smart-backend = SMART source
+smart-backend-auto = Automatic
+smart-backend-udisks = UDisks
+smart-backend-smartctl = smartctl
+smart-backend-active = In use
+smart-backend-smartctl-missing = Install smartctl (smartmontools) to read drives UDisks cannot query.
+smart-schedule = Scheduled self-tests
+smart-schedule-off = Off
+smart-schedule-every = Every {$days ->
+    [one] day
+   *[other] {$days} days
+}
+smart-schedule-conditions = Scheduled tests only start on AC power while the drive is idle.
+smart-selftest-history = Self-test history
+smart-selftest-scheduled = scheduled
+smart-selftest-manual = manual
+smart-selftest-running = running
+smart-selftest-history-entry = {$kind} ({$origin}), {$days} days ago: {$result}
+kernel-errors = Kernel errors
+kernel-error-io = I/O error
+kernel-error-ata = ATA error
+kernel-error-nvme = NVMe error
+kernel-error-entry = {$kind}, {$days} days ago: {$message}
+smart-thresholds = Temperature thresholds
+smart-threshold-warning = Warning from
+smart-threshold-critical = Critical from
+smart-thresholds-invalid = The warning temperature must be below the critical one.
+
+# Disk health
+health-score = Health score: {$score}/100
+health-rising = {$finding}, rising
+health-smart-failed = SMART self-assessment failed
+health-selftest-failed = Last self-test failed: {$result}
+health-reallocated-sectors = {$count} reallocated sectors
+health-pending-sectors = {$count} pending sectors
+health-uncorrectable-sectors = {$count} uncorrectable sectors
+health-media-errors = {$count} media errors
+health-wear = {$percent}% of rated endurance used
+health-temperature = Reached {$temperature}
+health-kernel-errors = {$count} device errors in the kernel log
+health-btrfs-errors = {$io} I/O and {$corruption} corruption errors counted by btrfs
+health-trend = {$attribute}: +{$rate} per week
+health-trend-reallocated = Reallocated sectors
+health-trend-pending = Pending sectors
+health-trend-uncorrectable = Uncorrectable sectors
+health-trend-media-errors = Media errors
+health-trend-wear = Endurance used
+
+# Checking all drives
+check-all-drives = Check All Drives
+health-check-running = Checking drives, filesystems and arrays…
+health-check-overall = Overall: {$level}
+health-check-counts = {$critical} critical, {$warning} warning, {$unknown} not checked, {$good} good
+health-check-finished = Finished {$date}
+health-check-nothing = Nothing to check
+health-check-show = Show
+health-check-smart = SMART
+health-check-filesystem = Filesystem
+health-check-raid = RAID array
+health-check-btrfs = Btrfs device errors
+health-check-good = Good
+health-check-warning = Warning
+health-check-critical = Critical
+health-check-unknown = Not checked
+health-check-filesystem-clean = No errors found
+health-check-filesystem-errors = The check found errors; repair the filesystem
+health-check-raid-state = State: {$state}, sync action: {$action}
+health-check-raid-degraded = {$count} missing members
+health-check-raid-mismatches = {$count} mismatched sectors
+health-check-btrfs-device = {$device}: {$io} I/O, {$corruption} corruption, {$generation} generation errors
+
+# Legacy vaults
+legacy-vaults = Legacy Vaults
+legacy-vaults-help = eCryptfs and gocryptfs encrypt files one by one and are no longer recommended. Mount a vault to reach its files, then move them into an encrypted folder or a LUKS volume.
+legacy-vaults-loading = Looking for vaults…
+legacy-vaults-none = No eCryptfs or gocryptfs vaults found.
+legacy-vault-mounted = Mounted at {$mount_point}
+legacy-vault-not-mounted = Not mounted
+legacy-vault-login-passphrase = Login passphrase of the owner
+legacy-vault-mount-help = The vault is mounted read-only.
+legacy-vault-open = Open
+legacy-vault-migrate = Migrate
+legacy-vault-migrate-help = Copies the files into an empty encrypted folder or an empty folder on a LUKS volume, then compares every copy with its original. The vault is left as it is.
+legacy-vault-target = Target folder
+legacy-vault-target-placeholder = /home/user/private
+legacy-vault-progress = {$copied} of {$total} copied
+legacy-vault-migrated = Migration complete, {$files} files copied and verified.
 
 # Volume types
 lvm-logical-volume = LVM LV
@@ -222,6 +560,25 @@ offset = Offset
 
 # Partition dialog labels
 overwrite-data-slow = Overwrite Data (Slow)
+format-advanced = Advanced
+format-preset = Format preset
+format-preset-none = Default
+format-preset-ext4-fast-commit = Fast commit
+format-preset-ext4-fast-commit-description = Enable the fast_commit journal feature for lower fsync latency
+format-preset-ext4-data-disk = Data disk
+format-preset-ext4-data-disk-description = No root-reserved blocks, suited to non-system storage
+format-preset-xfs-reflink = Reflink
+format-preset-xfs-reflink-description = Enable reflink for copy-on-write file clones
+format-preset-btrfs-zstd = Zstd compression
+format-preset-btrfs-zstd-description = Compress new files with zstd level 3
+format-preset-vfat-32k-clusters = 32 KiB clusters
+format-preset-exfat-128k-clusters = 128 KiB clusters
+format-preset-ntfs-64k-clusters = 64 KiB clusters
+format-preset-large-clusters-description = Larger clusters for big media files
+format-preset-camera-clusters-description = Larger clusters for cameras and big media files
+format-option-default = Default
+format-option-enabled = Enabled
+format-option-disabled = Disabled
 password-protected-luks = Password Protected (LUKS)
 
 # Filesystem type names
@@ -251,6 +608,17 @@ fs-desc-swap = Virtual memory
 # Filesystem tools warning
 fs-tools-warning = Some filesystem types are missing due to missing tools. See Settings for more info.
 
+# Filesystem interoperability
+interop-windows = Windows
+interop-macos = macOS
+interop-linux = Linux
+interop-read-write = read and write
+interop-read-only = read only
+interop-unsupported = cannot read
+interop-suggest-exfat = exFAT is suggested for removable drives, so they also work with Windows and macOS
+interop-max-file-size = Files larger than { $size } cannot be stored
+interop-volume-too-large = This filesystem cannot be larger than { $max }; choose exFAT or make the partition smaller
+
 # Detail Tabs
 volume-info = Volume Info
 
@@ -260,9 +628,10 @@ btrfs = BTRFS
 volume = Volume
 btrfs-placeholder = BTRFS management features coming soon
 btrfs-create-subvolume = Create Subvolume
-btrfs-subvolume-name = Subvolume Name
+btrfs-subvolume-name = Subvolume Name or Path
 btrfs-subvolume-name-required = Subvolume name is required
 btrfs-subvolume-invalid-chars = Subvolume name cannot contain slashes
+btrfs-subvolume-path-invalid = Subvolume path must stay inside the filesystem
 btrfs-create-subvolume-failed = Failed to create subvolume
 btrfs-delete-subvolume = Delete Subvolume
 btrfs-delete-confirm = Delete subvolume '{ $name }'? This action cannot be undone.
@@ -276,6 +645,29 @@ btrfs-used-space = Used space
 btrfs-subvolume-id = ID
 btrfs-subvolume-path = Path
 btrfs-subvolume-actions = Actions
+btrfs-subvolume-properties = Subvolume Properties
+btrfs-subvolume-uuid = UUID
+btrfs-snapshot-of = Snapshot of
+btrfs-nocow = Disable copy-on-write (NOCOW)
+btrfs-nocow-desc = Recommended for VM images and databases. New files in this directory are neither checksummed nor compressed.
+btrfs-nocow-non-empty = This directory is not empty. Existing files keep their current setting; only files created afterwards are affected.
+btrfs-nocow-error = Could not read the NOCOW attribute: { $error }
+btrfs-restore = Restore
+btrfs-restore-files = Restore files from this snapshot into { $target }
+btrfs-restore-path = Path inside the snapshot (e.g. home/alice/report.odt)
+btrfs-restore-skip = Keep existing files
+btrfs-restore-overwrite = Overwrite existing files
+btrfs-restore-keep-both = Keep both
+btrfs-restore-result = Restored { $restored }, skipped { $skipped } existing
+btrfs-rollback = Roll Back to Snapshot
+btrfs-rollback-desc = Replace all of { $target } with the contents of this snapshot.
+btrfs-rollback-confirm = Replace { $target } with the contents of { $snapshot }? A read-only snapshot of the current state is taken first.
+btrfs-rollback-done = { $subvolume } now holds the snapshot contents. The previous state was saved as { $safety }.
+btrfs-rollback-moved = The replaced subvolume was kept at { $path }; delete it once the rollback is confirmed.
+btrfs-rollback-reboot-root = This is the running system's root. Reboot to use the restored state. If your boot loader or /etc/fstab select the root by subvolid, update them to the new subvolume first.
+btrfs-rollback-remount = Reboot or remount { $mounts } to use the restored state.
+btrfs-rollback-default-updated = The default subvolume now points at the restored subvolume.
+btrfs-rollback-failed = Failed to roll back subvolume
 btrfs-set-default-failed = Failed to set default subvolume
 btrfs-readonly-failed = Failed to toggle readonly flag
 btrfs-not-mounted = BTRFS filesystem not mounted
@@ -285,6 +677,26 @@ btrfs-no-subvolumes = No subvolumes found
 btrfs-no-subvolumes-desc = This BTRFS volume may be newly created or not yet have any subvolumes.
 btrfs-loading-usage = Loading usage information...
 btrfs-usage-error = Usage error: { $error }
+btrfs-device-errors = Device Errors
+btrfs-device-errors-counts = { $device }: { $write } write, { $read } read, { $flush } flush, { $corruption } corruption, { $generation } generation errors
+btrfs-reset-device-stats = Reset Error Counters
+btrfs-reset-device-stats-confirm = Reset the error counters of the devices of { $mount_point }? Do this once the cause of the errors has been dealt with, so that new errors stand out. The drive health score no longer counts the old errors.
+btrfs-reset-device-stats-failed = Failed to reset the error counters
+btrfs-compression = Compression
+btrfs-compression-target = Path (empty for the whole filesystem)
+btrfs-compression-mount-option = Mount option: { $value }
+btrfs-compression-property = Property: { $value }
+btrfs-compression-unset = not set
+btrfs-compression-force = Force compression
+btrfs-compression-apply-property = Set Property
+btrfs-compression-apply-mount = Set Mount Option
+btrfs-compression-mount-note = The mount option applies until the next mount; add it to fstab to keep it.
+btrfs-compression-estimate = Estimate Savings
+btrfs-compression-estimate-result = Estimated savings: { $savings } of { $total }
+btrfs-compression-working = Working...
+btrfs-compress-existing = Compress Existing Data
+btrfs-compress-existing-confirm = Rewrite all files under { $path } with { $algorithm } compression? Rewritten data is no longer shared with snapshots and may use more space until they are deleted.
+btrfs-compression-failed = Failed to change compression
 
 # Usage view
 usage-scanning = Scanning disk usage...
@@ -300,6 +712,83 @@ usage-scan-setup = Usage Scan Setup
 usage-choose-mount-points = Choose mount points
 usage-choose-mount-points-desc = Select one or more mount points to include in the scan.
 usage-scan-parallelism-label = Scan Parallelism
+mounting = Mounting
+mount-base-dir-label = Mount directory, inside /run/media/$USER or /media/$USER ($USER is replaced with your username)
+mount-base-dir-placeholder = /run/media/$USER (system default)
+mount-naming-scheme-label = Name mount points by
+mount-naming-label = Label
+mount-naming-uuid = UUID
+mount-naming-device = Device name
+byte-units-label = Show sizes in
+byte-units-si = Decimal units (GB, 1000 bytes per kB)
+byte-units-iec = Binary units (GiB, 1024 bytes per KiB)
+temperature = Temperature
+temperature-unit-label = Unit
+temperature-unit-celsius = Celsius (°C)
+temperature-unit-fahrenheit = Fahrenheit (°F)
+temperature-thresholds-hint = Warning and critical temperatures are set per drive in its SMART data.
+confirmation = Confirmations
+confirmation-level = Ask before destroying data
+confirmation-level-relaxed = Relaxed: OK is enough
+confirmation-level-standard = Standard: type the device name for whole disks
+confirmation-level-paranoid = Paranoid: type the name and a token, then wait
+confirmation-level-description = Applies to formatting, deleting, restoring and other operations that overwrite data. These are safeguards of this app against slips: the storage service and other tools do not ask for them.
+confirm-type-name = Type { $name } to confirm
+confirm-type-token = Type the confirmation token { $token }
+confirm-countdown = OK is available in { $seconds } s
+notifications = Notifications
+notifications-hotplug = Drives connected or disconnected
+notifications-health = Drive health and temperature
+notifications-raid = RAID arrays degraded or recovered
+notifications-backups = Disk image backups finished
+notifications-operations = Long operations finished
+notifications-capacity = Filesystems running full
+capacity-alert-label = Warn when a filesystem will be full within
+capacity-alert-days = { $days } days
+notifications-background-hint = Finished backups and operations are only announced while the window is in the background.
+safety-snapshots = Safety Snapshots
+safety-snapshots-description = Take a read-only btrfs snapshot of affected subvolumes before destructive operations. Space freed by a cleanup is only returned once its safety snapshot expires.
+safety-snapshots-enabled = Snapshot before destructive operations
+safety-snapshots-on-resize = Before resizing a partition or logical volume
+safety-snapshots-on-cleanup = Before deleting files from the usage view
+safety-snapshots-retention = Keep safety snapshots for
+safety-snapshots-retention-days = { $days ->
+    [one] 1 day
+   *[other] { $days } days
+}
+safety-snapshots-save-failed = Failed to save safety snapshot settings
+operation-priorities = Operation Priority
+operation-priorities-description = How much processor and disk time long-running operations take from other programs. Work that runs in the background is best left for when the disk is idle.
+operation-priorities-save-failed = Failed to save operation priorities
+operation-priority = Priority
+operation-priority-override-help = Applies to this operation only.
+operation-kind-image-backup = Disk image backups
+operation-kind-image-restore = Disk image restores
+operation-kind-partition-copy = Partition copies
+operation-kind-usage-scan = Usage scans
+operation-kind-defragment = Defragmentation
+priority-configured = As configured
+priority-normal = Normal
+priority-low = Low
+priority-idle = Only while the disk is idle
+priority-custom = Custom (nice { $nice })
+advanced-options = Advanced options
+time-left = { $time } left
+time-left-range = { $min } to { $max } left
+power-policies = Battery Power
+power-policies-description = Scheduled jobs can wait until the computer is plugged in, so they do not drain the battery.
+power-policies-save-failed = Failed to save battery power settings
+power-defer-on-battery = Wait while on battery
+power-min-charge = Otherwise, wait while the charge is below
+power-min-charge-none = No limit
+power-min-charge-percent = { $percent }%
+scheduled-job-self-test = Scheduled SMART self-tests
+scheduled-job-esp-sync = EFI System Partition syncs
+deferred-jobs = Waiting for power
+deferred-reason-on-battery = On battery
+deferred-reason-low-charge = Battery at { $percent }%
+run-anyway = Run anyway
+run-deferred-job-failed = Failed to run the job
 usage-parallelism-low = Low
 usage-parallelism-balanced = Balanced
 usage-parallelism-high = High
@@ -311,6 +800,7 @@ usage-parallelism = Parallelism
 usage-start-scan = Start Scan
 usage-select-at-least-one-mount-point = Select at least one mount point
 usage-delete-summary = Deleted { $deleted } files; { $failed } failed
+usage-delete-trim-hint = Trim the drive's filesystems to return the space to the virtual machine host.
 
 # Usage categories
 usage-category-documents = Documents
@@ -323,3 +813,179 @@ usage-category-binaries = Binaries
 usage-category-packages = Packages
 usage-category-system = System
 usage-category-other = Other
+
+# RAID health alerts
+raid-alert-title = RAID array needs attention
+raid-alert-degraded = { $array } is degraded and no longer has full redundancy
+raid-alert-member-failed = A member of { $array } has failed
+raid-alert-recovered = { $array } has recovered and is fully redundant again
+temperature-alert-title = Drive temperature
+temperature-alert-warning = { $device } is running hot at { $temperature }
+temperature-alert-critical = { $device } has reached a critical { $temperature }
+temperature-alert-normal = { $device } is back to a normal { $temperature }
+notification-drive-added = Drive connected
+notification-drive-added-body = { $device } was connected
+notification-drive-removed = Drive disconnected
+notification-drive-removed-body = { $device } was disconnected
+notification-drive-needs-setup = New drive ready to set up
+notification-drive-needs-setup-body = { $name } ({ $device }) is empty. Set it up to start using it.
+notification-health-critical = Drive is failing
+notification-health-critical-body = The health of { $device } is critical. Back up its data and replace it.
+notification-smart-failed-body = { $device } reports that its SMART self-assessment failed. Back up its data and replace it.
+notification-backup-finished = Backup finished
+notification-backup-finished-body = The disk image { $image } was written
+notification-backup-failed = Backup failed
+notification-operation-finished = { $operation } finished
+notification-operation-failed = { $operation } failed
+notification-image-restored = { $image } was restored
+notification-disc-burned = { $image } was burned to disc
+notification-disc-erased = The disc was erased
+notification-partition-copied = The partition was copied to { $target }
+notification-disk-migrated = The drive was migrated to { $target }
+notification-live-usb-created = { $drive } is now a bootable USB drive
+notification-filling-up = Filesystem running full
+notification-filling-up-body = { $mount_point } will be full in about { $days } days at its current growth
+notification-low-space = Filesystem low on space
+notification-low-space-body = { $mount_point } has only { $available } free.
+notification-low-space-pruned = { $count ->
+    [0] There were no expired safety snapshots to remove.
+    [one] 1 expired safety snapshot was removed.
+   *[other] { $count } expired safety snapshots were removed.
+}
+notification-low-space-largest = Largest: { $categories }
+agent-open-app = Open Storage
+
+# Inventory report
+report-export = Export storage report
+report-saved = Storage report saved
+report-saved-body = Saved to { $path }
+diagram-export = Export Layout Diagram
+diagram-saved = Layout diagram saved
+diagram-save-failed = Could not save the layout diagram
+report-save-failed = Could not save the storage report
+report-problem = Report Problem…
+logs = Logs
+logs-level = Lowest level shown
+logs-module = Module
+logs-operation = Operation (e.g. mount)
+logs-device = Device (e.g. /dev/sda1)
+logs-app = App
+logs-service = Storage service
+logs-service-description = The service log of the last day, limited to the operation and device entered above. Reading it requires authentication.
+logs-fetch-service = Fetch
+logs-none = No matching log lines.
+logs-older-hidden = { $count } older lines not shown
+statistics = Usage Statistics
+statistics-enable = Keep local usage statistics
+statistics-enable-description = Counts the operations you perform, for the Usage Statistics page. The records stay on this computer and are never sent anywhere.
+statistics-disabled = Usage statistics are off. Turn them on in the settings to start counting.
+statistics-since = Since { $date }
+statistics-operations = Operations performed
+statistics-backups = Backups made
+statistics-bytes-backed-up = Data backed up
+statistics-scans = Scans run
+statistics-snapshots = Snapshots taken
+statistics-by-operation = Operations
+statistics-none = No operations recorded yet.
+statistics-clear = Clear Statistics
+diagnostics-description = A diagnostic report helps developers find the cause of this failure. You can attach it to an issue on GitHub.
+diagnostics-contents = The report contains a summary of the failure and of your system, the layout of all storage devices, UDisks properties, SMART data and kernel errors of the selected drive, recent kernel and UDisks log messages, and the storage service log since shortly before the failure.
+diagnostics-redaction = Serial numbers, UUIDs, labels, and your user and computer names are removed. The report is only saved to a file; nothing is sent anywhere. Reading the system logs requires authentication.
+diagnostics-save = Save Diagnostic Report
+diagnostics-saved = Diagnostic report saved
+diagnostics-saved-body = Saved to { $path }
+report-title = Storage Report
+report-generated = Generated on { $host } at { $date }
+report-none = None
+report-drives = Drives
+report-volumes = Partitions and Filesystems
+report-health = SMART Health
+report-raid = RAID Arrays
+report-lvm = LVM
+report-network = Network Mounts
+report-bus = Bus
+report-kind = Kind
+report-type = Type
+report-used = Used
+report-score = Score
+report-status = Status
+report-findings = Findings
+report-level = Level
+report-active-members = Active members
+report-sync = Sync
+report-members = Members
+report-parent = Parent
+report-scope = Scope
+report-scope-user = User
+report-scope-system = System
+report-mounted = Mounted
+report-not-mounted = Not mounted
+report-health-good = Good
+report-health-warning = Warning
+report-health-critical = Critical
+applet-drives = Removable Drives
+applet-no-drives = No removable drives connected
+applet-remotes = Network Remotes
+applet-mount = Mount
+applet-unmount = Unmount
+applet-eject = Eject
+applet-free = { $size } free
+applet-open-app = Open Storage…
+
+# Bind mounts
+bind-mounts = Bind Mounts
+new-bind-mount = New Bind Mount
+bind-create = Bind
+bind-source = Directory to bind
+bind-target = Where it appears
+bind-read-only = Read-only
+bind-read-only-short = read-only
+bind-persistent = Mount at startup (adds an fstab entry)
+bind-absolute-paths = Both paths must be absolute
+bind-remove = Remove from fstab
+bind-remove-failed = Removing the bind mount failed
+
+# Automount
+automount = Mount on Access
+automount-mount-point = Mount point
+automount-options = Mount options
+automount-idle-timeout = Unmount when idle
+automount-idle-never = Never
+automount-idle-minutes = After { $minutes } { $minutes ->
+    [one] minute
+   *[other] minutes
+}
+automount-help = Uses systemd mount and automount units instead of an fstab entry. The filesystem is mounted when the mount point is first opened.
+automount-units = Created units
+automount-create = Create Units
+automount-remove = Remove
+
+# SELinux labels
+selinux-context = SELinux context: { $context }
+selinux-default-context = Expected by the policy: { $context }
+selinux-unlabeled = none
+selinux-needs-relabel = { $mount_point } needs SELinux labels
+selinux-needs-relabel-description = Its files have no SELinux labels or other ones than the policy expects, so programs confined by SELinux may be denied access even though the permissions allow it. This is common after formatting a volume or moving data such as /home to it.
+selinux-relabel = Relabel
+selinux-relabel-failed = Relabeling failed
+
+# Running operations
+operation-formatting = Formatting
+operation-tuning = Finishing formatting
+operation-checking = Checking
+operation-repairing = Repairing
+operation-resizing = Resizing
+operation-shrinking-filesystem = Shrinking the filesystem on
+operation-growing-filesystem = Growing the filesystem on
+operation-moving = Moving
+operation-relabeling = Relabeling
+operation-backing-up = Backing up to
+operation-restoring = Restoring
+operation-copying = Copying to
+operation-migrating = Migrating to
+operation-burning = Burning
+operation-blanking = Blanking
+operation-creating-live-usb = Creating bootable drive
+operation-self-test-short = Short self-test of
+operation-self-test-extended = Extended self-test of
+cancel-operation-failed = Failed to cancel the operation
@@ -0,0 +1,238 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Background agent
+//!
+//! A lightweight user process, started with the session, that keeps
+//! listening to the storage service for hotplug, health, temperature, RAID,
+//! capacity and low space events while the Storage window is not running,
+//! and announces them with the same per-category switches as the app. Clicking a notification
+//! opens the app, which then takes over until it is closed again; for a
+//! drive that holds nothing yet, it opens at the setup wizard.
+
+#[path = "../config.rs"]
+#[allow(dead_code)]
+mod config;
+#[path = "../i18n.rs"]
+mod i18n;
+#[path = "../notification_policy/events.rs"]
+#[allow(dead_code)]
+mod notification_policy;
+mod notifier;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use storage_contracts::client::{DisksClient, FilesystemsClient, RaidClient};
+use storage_types::{CapacityForecast, DiskHealthSummary, RaidHealthEvent, TemperatureLevel};
+use tracing_subscriber::EnvFilter;
+
+use crate::config::Config;
+use crate::notification_policy::StorageEvent;
+use crate::notifier::Notifier;
+
+/// Must match `APP_ID` of the app, whose settings are shared
+const APP_ID: &str = "com.cosmic.ext.Storage";
+
+/// Executable of the app, launched when a notification is clicked
+const APP_EXECUTABLE: &str = "cosmic-ext-storage";
+
+/// Time between two health checks; reading SMART data can wake drives up
+const HEALTH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+    i18n::init(&i18n_embed::DesktopLanguageRequester::requested_languages());
+    storage_types::set_byte_format(Config::load(APP_ID).byte_format());
+
+    let disks = DisksClient::new().await?;
+    let raid = RaidClient::new().await?;
+    let filesystems = FilesystemsClient::new().await?;
+    let mut notifier = Notifier::new().await?;
+
+    let mut disk_added = disks.proxy().receive_disk_added().await?;
+    let mut disk_removed = disks.proxy().receive_disk_removed().await?;
+    let mut temperature_alerts = disks.proxy().receive_temperature_alert().await?;
+    let mut raid_health = raid.proxy().receive_array_health_changed().await?;
+    let mut low_space = filesystems.proxy().receive_low_space().await?;
+    let mut actions = notifier.receive_actions().await?;
+
+    let mut health: HashMap<String, DiskHealthSummary> = HashMap::new();
+    let mut capacity: HashMap<String, CapacityForecast> = HashMap::new();
+    let mut health_check = tokio::time::interval(HEALTH_INTERVAL);
+    tracing::info!("storage agent started");
+
+    loop {
+        let events = tokio::select! {
+            Some(signal) = disk_added.next() => match signal.args() {
+                Ok(args) => vec![drive_added(&disks, args.device.to_string()).await],
+                Err(_) => Vec::new(),
+            },
+            Some(signal) = disk_removed.next() => signal
+                .args()
+                .map(|args| vec![StorageEvent::DriveRemoved { device: args.device.to_string() }])
+                .unwrap_or_default(),
+            Some(signal) = temperature_alerts.next() => signal
+                .args()
+                .ok()
+                .and_then(|args| {
+                    Some(StorageEvent::Temperature {
+                        device: args.device.to_string(),
+                        level: TemperatureLevel::parse(args.level)?,
+                        temperature: Config::load(APP_ID)
+                            .temperature_unit
+                            .format(args.celsius as i64),
+                    })
+                })
+                .into_iter()
+                .collect(),
+            Some(signal) = raid_health.next() => signal
+                .args()
+                .ok()
+                .and_then(|args| {
+                    Some(StorageEvent::Raid {
+                        array: args.array.to_string(),
+                        event: RaidHealthEvent::parse(args.event)?,
+                    })
+                })
+                .into_iter()
+                .collect(),
+            Some(signal) = low_space.next() => signal
+                .args()
+                .ok()
+                .and_then(|args| serde_json::from_str(args.warning_json).ok())
+                .map(StorageEvent::LowSpace)
+                .into_iter()
+                .collect(),
+            _ = health_check.tick() => {
+                let mut events = check_health(&disks, &mut health).await;
+                events.extend(check_capacity(&filesystems, &mut capacity).await);
+                events
+            }
+            Some(action) = actions.next() => {
+                if let Some(event) = notifier.clicked(&action) {
+                    launch_app(event.setup_device());
+                }
+                Vec::new()
+            }
+            else => break,
+        };
+
+        if events.is_empty() || app_running() {
+            continue;
+        }
+        let settings = Config::load(APP_ID).notifications;
+        for event in events {
+            // The app is closed, so there is no window to have focus
+            if event.wanted(&settings, false) {
+                notifier.show(&event).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Event for an added drive, which offers the setup wizard when the drive
+/// holds nothing yet
+async fn drive_added(disks: &DisksClient, device: String) -> StorageEvent {
+    let blank = match (
+        disks.get_disk_info(&device).await,
+        disks.list_volumes().await,
+    ) {
+        (Ok(disk), Ok(volumes)) => disk.needs_setup(&volumes).then(|| disk.display_name()),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::debug!(%e, device, "failed to inspect the added drive");
+            None
+        }
+    };
+    match blank {
+        Some(name) => StorageEvent::DriveNeedsSetup { device, name },
+        None => StorageEvent::DriveAdded { device },
+    }
+}
+
+/// Refresh the health summaries of all drives and return the drives that
+/// became critical since the last check
+async fn check_health(
+    disks: &DisksClient,
+    health: &mut HashMap<String, DiskHealthSummary>,
+) -> Vec<StorageEvent> {
+    let drives = match disks.list_disks().await {
+        Ok(drives) => drives,
+        Err(e) => {
+            tracing::warn!(%e, "failed to list drives for the health check");
+            return Vec::new();
+        }
+    };
+
+    let mut summaries = Vec::new();
+    for drive in drives.iter().filter(|d| !d.is_loop && !d.optical) {
+        match disks.get_health_summary(&drive.device).await {
+            Ok(summary) => summaries.push(summary),
+            Err(e) => tracing::debug!(%e, device = %drive.device, "no health summary"),
+        }
+    }
+
+    let events = notification_policy::health_events(health, &summaries);
+    *health = summaries
+        .into_iter()
+        .map(|summary| (summary.device.clone(), summary))
+        .collect();
+    events
+}
+
+/// Refresh the capacity forecasts of the mounted filesystems and return the
+/// ones that came within the configured number of days of running full
+async fn check_capacity(
+    filesystems: &FilesystemsClient,
+    capacity: &mut HashMap<String, CapacityForecast>,
+) -> Vec<StorageEvent> {
+    let forecasts = match filesystems.list_capacity_forecasts().await {
+        Ok(forecasts) => forecasts,
+        Err(e) => {
+            tracing::warn!(%e, "failed to load capacity forecasts");
+            return Vec::new();
+        }
+    };
+
+    let threshold_days = Config::load(APP_ID).capacity_alert_days;
+    let events = notification_policy::capacity_events(capacity, &forecasts, threshold_days);
+    *capacity = forecasts
+        .into_iter()
+        .map(|forecast| (forecast.mount_point.clone(), forecast))
+        .collect();
+    events
+}
+
+/// Whether the app runs for this user; its own notifications take over then
+fn app_running() -> bool {
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return false;
+    };
+    processes.flatten().any(|process| {
+        // Processes of other users cannot be inspected and are skipped
+        std::fs::read_link(process.path().join("exe"))
+            .is_ok_and(|exe| exe.file_name().is_some_and(|name| name == APP_EXECUTABLE))
+    })
+}
+
+/// Open the app, at the setup wizard of `setup_device` if given
+fn launch_app(setup_device: Option<&str>) {
+    let mut command = tokio::process::Command::new(APP_EXECUTABLE);
+    match setup_device {
+        // A running app is handed the request by the new process
+        Some(device) => _ = command.args(["--setup", device]),
+        None if app_running() => return,
+        None => {}
+    }
+    // Tokio reaps the child once it exits
+    if let Err(e) = command.spawn() {
+        tracing::error!(%e, "failed to launch {APP_EXECUTABLE}");
+    }
+}
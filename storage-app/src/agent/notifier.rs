@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Clickable desktop notifications through `org.freedesktop.Notifications`
+
+use std::collections::{HashMap, VecDeque};
+
+use zbus::proxy;
+use zbus::zvariant::Value;
+
+use crate::fl;
+use crate::notification_policy::StorageEvent;
+
+/// Notification icon for storage alerts
+const ALERT_ICON: &str = "drive-harddisk-symbolic";
+
+/// Action invoked when the notification itself is clicked
+const DEFAULT_ACTION: &str = "default";
+
+/// How many shown notifications are remembered for clicks
+const REMEMBERED: usize = 32;
+
+#[proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: &HashMap<&str, &Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: &str) -> zbus::Result<()>;
+}
+
+/// Shows notifications that open the app when clicked
+pub struct Notifier {
+    proxy: NotificationsProxy<'static>,
+    /// IDs and events of the latest notifications shown by the agent
+    shown: VecDeque<(u32, StorageEvent)>,
+}
+
+impl Notifier {
+    pub async fn new() -> zbus::Result<Self> {
+        let connection = zbus::Connection::session().await?;
+        let proxy = NotificationsProxy::new(&connection).await?;
+        Ok(Self {
+            proxy,
+            shown: VecDeque::new(),
+        })
+    }
+
+    /// Notification actions invoked by the user, from any application
+    pub async fn receive_actions(&self) -> zbus::Result<ActionInvokedStream> {
+        self.proxy.receive_action_invoked().await
+    }
+
+    /// The event of the notification shown by the agent that `action`
+    /// clicked, if any
+    pub fn clicked(&self, action: &ActionInvoked) -> Option<&StorageEvent> {
+        let args = action.args().ok()?;
+        if args.action_key != DEFAULT_ACTION {
+            return None;
+        }
+        self.shown
+            .iter()
+            .find(|(id, _)| *id == args.id)
+            .map(|(_, event)| event)
+    }
+
+    pub async fn show(&mut self, event: &StorageEvent) {
+        let (summary, body) = event.text();
+        let urgency = Value::U8(2);
+        let hints = HashMap::from([("urgency", &urgency)]);
+        let open = if event.setup_device().is_some() {
+            fl!("setup-drive")
+        } else {
+            fl!("agent-open-app")
+        };
+        let actions = [DEFAULT_ACTION, open.as_str()];
+
+        match self
+            .proxy
+            .notify(
+                &fl!("app-title"),
+                0,
+                ALERT_ICON,
+                &summary,
+                &body,
+                &actions,
+                &hints,
+                -1,
+            )
+            .await
+        {
+            Ok(id) => {
+                if self.shown.len() == REMEMBERED {
+                    self.shown.pop_front();
+                }
+                self.shown.push_back((id, event.clone()));
+            }
+            Err(e) => tracing::warn!(%e, "failed to show notification"),
+        }
+    }
+}
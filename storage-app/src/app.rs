@@ -5,21 +5,40 @@ pub const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
 pub(crate) use crate::message::app::Message;
 pub(crate) use crate::state::app::{AppModel, ContextPage};
 
+use crate::client::BtrfsClient;
+use crate::client::DisksClient;
 use crate::client::FilesystemsClient;
+use crate::client::ImageClient;
 use crate::client::RcloneClient;
+use crate::client::ServiceClient;
 use crate::config::Config;
+use crate::fl;
+use crate::message::statistics::StatisticsMessage;
 use crate::models::load_all_drives;
+use crate::state::bind_mounts::BindMountsState;
+use crate::state::capacity::CapacityState;
+use crate::state::dialogs::ShowDialog;
+use crate::state::health_check::HealthCheckState;
+use crate::state::logs::LogsState;
+use crate::state::mtp::MtpState;
 use crate::state::network::NetworkState;
+use crate::state::optical::OpticalState;
+use crate::state::read_only::ReadOnlyState;
+use crate::state::selinux::SelinuxState;
 use crate::state::sidebar::SidebarState;
+use crate::state::statistics::StatisticsState;
+use crate::state::user_mounts::UserMountsState;
+use crate::utils::sandbox;
 use cosmic::app::{Core, Task};
 use cosmic::widget::nav_bar;
 use cosmic::{Application, Element};
+use storage_types::AllowedActions;
 
 pub(crate) const APP_ID: &str = "com.cosmic.ext.Storage";
 
 impl Application for AppModel {
     type Executor = cosmic::executor::Default;
-    type Flags = ();
+    type Flags = crate::cli::Args;
     type Message = Message;
     const APP_ID: &'static str = APP_ID;
 
@@ -31,34 +50,68 @@ impl Application for AppModel {
         &mut self.core
     }
 
-    fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Self::Message>) {
+    fn init(core: Core, flags: Self::Flags) -> (Self, Task<Self::Message>) {
         let mut app = AppModel {
             core,
             context_page: ContextPage::default(),
             nav: nav_bar::Model::default(),
             sidebar: SidebarState::default(),
             dialog: None,
+            confirmation: None,
             image_op_operation_id: None,
             filesystem_tools: vec![],
+            safety_snapshot_policy: None,
+            operation_priorities: None,
+            power_policies: None,
+            deferred_jobs: Vec::new(),
+            operations: Vec::new(),
             network: NetworkState::new(),
+            user_mounts: UserMountsState::default(),
+            bind_mounts: BindMountsState::default(),
+            mtp: MtpState::default(),
+            physical_devices: Vec::new(),
+            hypervisor: None,
+            allowed_actions: AllowedActions::default(),
+            optical: OpticalState::default(),
+            read_only: ReadOnlyState::default(),
+            selinux: SelinuxState::default(),
+            capacity: CapacityState::default(),
+            logs: LogsState::default(),
+            statistics: StatisticsState::default(),
+            health_check: HealthCheckState::default(),
+            vaults: VaultsState::default(),
+            window_focused: true,
             config: Config::load(Self::APP_ID),
         };
 
         let command = app.update_title();
 
+        // Selected once the drives are loaded
+        let reveal_command = match flags.reveal {
+            Some(target) => Task::done(Message::Reveal(target).into()),
+            None => Task::none(),
+        };
+        let setup_command = match flags.setup {
+            Some(device) => Task::done(Message::SetUpDrive(device).into()),
+            None => Task::none(),
+        };
+
+        // Without the service there is nothing to show; say what to do
+        // about it, which inside Flatpak is usually a sandbox permission
         let nav_command = Task::perform(
             async {
-                match load_all_drives().await {
-                    Ok(drives) => Some(drives),
-                    Err(e) => {
-                        tracing::error!(%e, "failed to load drives");
-                        None
-                    }
-                }
+                load_all_drives().await.map_err(|e| {
+                    tracing::error!(%e, "failed to load drives");
+                    sandbox::service_guidance(&e)
+                })
             },
             |drives| match drives {
-                None => Message::None.into(),
-                Some(drives) => Message::UpdateNav(drives, None).into(),
+                Ok(drives) => Message::UpdateNav(drives, None).into(),
+                Err(guidance) => Message::Dialog(Box::new(ShowDialog::Info {
+                    title: fl!("service-unreachable"),
+                    body: guidance,
+                }))
+                .into(),
             },
         );
 
@@ -84,11 +137,140 @@ impl Application for AppModel {
             },
         );
 
+        let safety_snapshots_command = Task::perform(
+            async {
+                match BtrfsClient::new().await {
+                    Ok(client) => match client.get_safety_snapshot_policy().await {
+                        Ok(policy) => Some(policy),
+                        Err(e) => {
+                            tracing::info!(%e, "safety snapshot policy not available");
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        tracing::info!(%e, "BTRFS client not available");
+                        None
+                    }
+                }
+            },
+            |policy| match policy {
+                None => Message::None.into(),
+                Some(policy) => Message::SafetySnapshotPolicyLoaded(policy).into(),
+            },
+        );
+
+        let priorities_command = Task::perform(
+            async {
+                match ImageClient::new().await {
+                    Ok(client) => match client.get_operation_priorities().await {
+                        Ok(settings) => Some(settings),
+                        Err(e) => {
+                            tracing::info!(%e, "operation priorities not available");
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        tracing::info!(%e, "image client not available");
+                        None
+                    }
+                }
+            },
+            |settings| match settings {
+                None => Message::None.into(),
+                Some(settings) => Message::OperationPrioritiesLoaded(settings).into(),
+            },
+        );
+
+        let power_policies_command = Task::perform(
+            async {
+                match ServiceClient::new().await {
+                    Ok(client) => match client.get_power_policies().await {
+                        Ok(settings) => Some(settings),
+                        Err(e) => {
+                            tracing::info!(%e, "power policies not available");
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        tracing::info!(%e, "service client not available");
+                        None
+                    }
+                }
+            },
+            |settings| match settings {
+                None => Message::None.into(),
+                Some(settings) => Message::PowerPoliciesLoaded(settings).into(),
+            },
+        );
+
+        // Disks behind hardware RAID controllers; the scan opens every
+        // controller, so it runs once in the background
+        let physical_devices_command = Task::perform(
+            async {
+                match DisksClient::new().await {
+                    Ok(client) => match client.list_physical_devices().await {
+                        Ok(devices) => devices,
+                        Err(e) => {
+                            tracing::info!(%e, "physical devices not available");
+                            Vec::new()
+                        }
+                    },
+                    Err(e) => {
+                        tracing::error!(%e, "failed to create disks client");
+                        Vec::new()
+                    }
+                }
+            },
+            |devices| Message::PhysicalDevicesLoaded(devices).into(),
+        );
+
+        // Inside a virtual machine the drive page explains what the
+        // hypervisor passes through to its disks
+        let hypervisor_command = Task::perform(
+            async {
+                match DisksClient::new().await {
+                    Ok(client) => match client.get_hypervisor().await {
+                        Ok(hypervisor) => hypervisor,
+                        Err(e) => {
+                            tracing::info!(%e, "hypervisor not available");
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        tracing::error!(%e, "failed to create disks client");
+                        None
+                    }
+                }
+            },
+            |hypervisor| Message::HypervisorLoaded(hypervisor).into(),
+        );
+
+        // Restriction profiles may leave out actions Polkit would allow;
+        // until they are known, nothing is hidden
+        let allowed_actions_command = Task::perform(
+            async {
+                match ServiceClient::new().await {
+                    Ok(client) => match client.allowed_actions().await {
+                        Ok(allowed) => allowed,
+                        Err(e) => {
+                            tracing::info!(%e, "allowed actions not available");
+                            AllowedActions::default()
+                        }
+                    },
+                    Err(e) => {
+                        tracing::error!(%e, "failed to create service client");
+                        AllowedActions::default()
+                    }
+                }
+            },
+            |allowed| Message::AllowedActionsLoaded(allowed).into(),
+        );
+
         let network_command = Task::perform(
             async {
                 match RcloneClient::new().await {
                     Ok(client) => match client.list_remotes().await {
-                        Ok(list) => Some(list.remotes),
+                        Ok(list) => Some(list),
                         Err(e) => {
                             tracing::warn!(%e, "failed to load network remotes");
                             None
@@ -100,20 +282,44 @@ impl Application for AppModel {
                     }
                 }
             },
-            |remotes| {
+            |list| {
                 Message::NetworkRemotesLoaded(
-                    remotes.ok_or_else(|| "RClone not available".to_string()),
+                    list.ok_or_else(|| "RClone not available".to_string()),
                 )
                 .into()
             },
         );
 
+        // The service decides whose operations it records; tell it the
+        // setting, which may have changed while it was not running
+        let usage_statistics = app.config.usage_statistics;
+        let statistics_command = Task::perform(
+            async move {
+                ServiceClient::new()
+                    .await
+                    .map_err(|e| format!("Failed to create service client: {}", e))?
+                    .set_usage_statistics(usage_statistics)
+                    .await
+                    .map_err(|e| format!("Failed to change usage statistics: {}", e))
+            },
+            |res| Message::Statistics(StatisticsMessage::EnabledSet(res)).into(),
+        );
+
         (
             app,
             command
+                .chain(reveal_command)
+                .chain(setup_command)
                 .chain(nav_command)
                 .chain(tools_command)
-                .chain(network_command),
+                .chain(safety_snapshots_command)
+                .chain(priorities_command)
+                .chain(power_policies_command)
+                .chain(physical_devices_command)
+                .chain(hypervisor_command)
+                .chain(allowed_actions_command)
+                .chain(network_command)
+                .chain(statistics_command),
         )
     }
 
@@ -151,12 +357,18 @@ impl Application for AppModel {
         crate::views::app::view(self)
     }
 
+    fn footer(&self) -> Option<Element<'_, Self::Message>> {
+        crate::views::operations::operations_footer(&self.operations)
+    }
+
     fn subscription(&self) -> cosmic::iced::Subscription<Self::Message> {
         crate::subscriptions::app::subscription(self)
     }
 
     fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
-        crate::update::update(self, message)
+        let task = crate::update::update(self, message);
+        crate::update::confirmation::sync(self);
+        task
     }
 
     fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<Self::Message> {
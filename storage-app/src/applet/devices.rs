@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Removable drives and network remotes shown by the applet
+
+use std::collections::HashSet;
+
+use storage_contracts::client::{ClientError, DisksClient, RcloneClient};
+use storage_types::rclone::{ConfigScope, MountStatus};
+use storage_types::{DiskInfo, VolumeInfo};
+
+/// Everything the applet lists, loaded in one go
+#[derive(Debug, Clone, Default)]
+pub struct Devices {
+    pub drives: Vec<RemovableDrive>,
+    pub remotes: Vec<NetworkRemote>,
+}
+
+/// A drive that can be unplugged, or whose media can be taken out
+#[derive(Debug, Clone)]
+pub struct RemovableDrive {
+    pub device: String,
+    pub name: String,
+    pub volumes: Vec<RemovableVolume>,
+}
+
+/// A filesystem on a removable drive
+#[derive(Debug, Clone)]
+pub struct RemovableVolume {
+    pub device: String,
+    pub label: String,
+    pub mount_point: Option<String>,
+    /// Used share of the filesystem while it is mounted
+    pub used_fraction: Option<f32>,
+    pub free_bytes: Option<u64>,
+}
+
+/// An rclone remote of the user or the system
+#[derive(Debug, Clone)]
+pub struct NetworkRemote {
+    pub name: String,
+    pub scope: ConfigScope,
+    pub mounted: bool,
+}
+
+/// Load the removable drives and network remotes from the service
+///
+/// Remotes are left out when rclone is not available, so that the drives
+/// are still listed.
+pub async fn load() -> Result<Devices, ClientError> {
+    let disks = DisksClient::new().await?;
+    let drives = removable_drives(&disks.list_disks().await?, &disks.list_volumes().await?);
+
+    let remotes = match RcloneClient::new().await {
+        Ok(rclone) => network_remotes(&rclone).await,
+        Err(e) => {
+            tracing::debug!(%e, "rclone is not available");
+            Vec::new()
+        }
+    };
+
+    Ok(Devices { drives, remotes })
+}
+
+async fn network_remotes(rclone: &RcloneClient) -> Vec<NetworkRemote> {
+    let remotes = match rclone.list_remotes().await {
+        Ok(list) => list.remotes,
+        Err(e) => {
+            tracing::warn!(%e, "failed to list rclone remotes");
+            return Vec::new();
+        }
+    };
+
+    let mut listed = Vec::with_capacity(remotes.len());
+    for remote in remotes {
+        let mounted = rclone
+            .get_mount_status(&remote.name, &remote.scope.to_string())
+            .await
+            .is_ok_and(|status| status.status == MountStatus::Mounted);
+        listed.push(NetworkRemote {
+            name: remote.name,
+            scope: remote.scope,
+            mounted,
+        });
+    }
+    listed
+}
+
+/// Pair the removable drives with the filesystems on them
+///
+/// `volumes` is the flat list of the service, whose entries point at their
+/// parent device; a filesystem belongs to a drive when following its
+/// parents ends at that drive, e.g. through an unlocked LUKS container.
+pub fn removable_drives(disks: &[DiskInfo], volumes: &[VolumeInfo]) -> Vec<RemovableDrive> {
+    disks
+        .iter()
+        .filter(|disk| !disk.is_loop && (disk.removable || disk.ejectable))
+        .filter(|disk| disk.media_available)
+        .map(|disk| {
+            let mut on_drive = HashSet::from([disk.device.as_str()]);
+            // Children come after their parents, but a second pass catches
+            // any that do not
+            loop {
+                let before = on_drive.len();
+                for volume in volumes {
+                    if let (Some(device), Some(parent)) = (&volume.device_path, &volume.parent_path)
+                        && on_drive.contains(parent.as_str())
+                    {
+                        on_drive.insert(device.as_str());
+                    }
+                }
+                if on_drive.len() == before {
+                    break;
+                }
+            }
+
+            let volumes = volumes
+                .iter()
+                .filter(|volume| volume.has_filesystem)
+                .filter_map(|volume| {
+                    let device = volume.device_path.as_deref()?;
+                    (device != disk.device && on_drive.contains(device))
+                        .then(|| removable_volume(device, volume))
+                })
+                .collect();
+
+            RemovableDrive {
+                device: disk.device.clone(),
+                name: disk.display_name(),
+                volumes,
+            }
+        })
+        .collect()
+}
+
+fn removable_volume(device: &str, volume: &VolumeInfo) -> RemovableVolume {
+    let label = if volume.label.is_empty() {
+        device.rsplit('/').next().unwrap_or(device).to_string()
+    } else {
+        volume.label.clone()
+    };
+    RemovableVolume {
+        device: device.to_string(),
+        label,
+        mount_point: volume.mount_points.first().cloned(),
+        used_fraction: volume
+            .usage
+            .as_ref()
+            .map(|usage| usage.percent.min(100) as f32 / 100.0),
+        free_bytes: volume.usage.as_ref().map(|usage| usage.available_bytes()),
+    }
+}
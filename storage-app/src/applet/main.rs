@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Panel applet
+//!
+//! Lists removable drives and rclone remotes in a panel popup, with one
+//! click to mount, unmount or eject them and the free space of mounted
+//! filesystems. Everything goes through the storage service, and the list
+//! is reloaded whenever the service reports a change.
+
+#[path = "../config.rs"]
+#[allow(dead_code)]
+mod config;
+mod devices;
+#[path = "../i18n.rs"]
+mod i18n;
+#[path = "../notification_policy/events.rs"]
+#[allow(dead_code)]
+mod notification_policy;
+
+use cosmic::app::{Core, Task};
+use cosmic::iced::platform_specific::shell::commands::popup::{destroy_popup, get_popup};
+use cosmic::iced::window::Id;
+use cosmic::iced::{Alignment, Length, Subscription};
+use cosmic::widget::{self, icon};
+use cosmic::{Element, iced_widget};
+use futures_util::{SinkExt, StreamExt};
+use storage_contracts::client::{
+    ClientError, DisksClient, FilesystemsClient, RcloneClient, service_events,
+};
+use storage_types::bytes_to_pretty;
+use storage_types::rclone::ConfigScope;
+use tracing_subscriber::EnvFilter;
+
+use crate::config::Config;
+use crate::devices::{Devices, NetworkRemote, RemovableDrive, RemovableVolume};
+
+const APPLET_ID: &str = "com.cosmic.ext.Storage.Applet";
+
+/// Must match `APP_ID` of the app, whose settings are shared
+const APP_ID: &str = "com.cosmic.ext.Storage";
+
+/// Executable of the app, opened from the popup
+const APP_EXECUTABLE: &str = "cosmic-ext-storage";
+
+const PANEL_ICON: &str = "drive-removable-media-symbolic";
+
+fn main() -> cosmic::iced::Result {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+    i18n::init(&i18n_embed::DesktopLanguageRequester::requested_languages());
+    storage_types::set_byte_format(Config::load(APP_ID).byte_format());
+
+    cosmic::applet::run::<Applet>(())
+}
+
+struct Applet {
+    core: Core,
+    popup: Option<Id>,
+    devices: Devices,
+    /// Device or remote with an operation in flight
+    busy: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    TogglePopup,
+    PopupClosed(Id),
+    Refresh,
+    Loaded(Result<Devices, String>),
+    Mount(String),
+    Unmount(String),
+    Eject(String),
+    MountRemote(String, ConfigScope),
+    UnmountRemote(String, ConfigScope),
+    Done(Result<(), String>),
+    OpenApp,
+}
+
+impl cosmic::Application for Applet {
+    type Executor = cosmic::executor::Default;
+    type Flags = ();
+    type Message = Message;
+    const APP_ID: &'static str = APPLET_ID;
+
+    fn core(&self) -> &Core {
+        &self.core
+    }
+
+    fn core_mut(&mut self) -> &mut Core {
+        &mut self.core
+    }
+
+    fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Self::Message>) {
+        let applet = Applet {
+            core,
+            popup: None,
+            devices: Devices::default(),
+            busy: None,
+            error: None,
+        };
+        (applet, load())
+    }
+
+    fn on_close_requested(&self, id: Id) -> Option<Message> {
+        Some(Message::PopupClosed(id))
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        struct ServiceEventsSubscription;
+
+        Subscription::run_with_id(
+            std::any::TypeId::of::<ServiceEventsSubscription>(),
+            cosmic::iced::stream::channel(4, |mut output| async move {
+                let events = match service_events().await {
+                    Ok(events) => events,
+                    Err(e) => {
+                        tracing::error!(%e, "failed to follow storage service events");
+                        return;
+                    }
+                };
+                let mut events = std::pin::pin!(events);
+                while let Some(event) = events.next().await {
+                    if event.changes_devices() {
+                        _ = output.send(Message::Refresh).await;
+                    }
+                }
+            }),
+        )
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::TogglePopup => {
+                if let Some(id) = self.popup.take() {
+                    return destroy_popup(id);
+                }
+                let Some(parent) = self.core.main_window_id() else {
+                    return Task::none();
+                };
+                let id = Id::unique();
+                self.popup = Some(id);
+                self.error = None;
+                let settings = self
+                    .core
+                    .applet
+                    .get_popup_settings(parent, id, None, None, None);
+                return Task::batch([get_popup(settings), load()]);
+            }
+            Message::PopupClosed(id) => {
+                if self.popup == Some(id) {
+                    self.popup = None;
+                }
+            }
+            Message::Refresh => return load(),
+            Message::Loaded(Ok(devices)) => self.devices = devices,
+            Message::Loaded(Err(e)) => self.error = Some(e),
+            Message::Mount(device) => {
+                return self.run(device.clone(), async move {
+                    FilesystemsClient::new()
+                        .await?
+                        .mount(&device, "", None)
+                        .await
+                        .map(|_| ())
+                });
+            }
+            Message::Unmount(device) => {
+                return self.run(device.clone(), async move {
+                    let result = FilesystemsClient::new()
+                        .await?
+                        .unmount(&device, false, false)
+                        .await?;
+                    match result.error {
+                        Some(e) if !result.success => Err(ClientError::OperationFailed(e)),
+                        _ => Ok(()),
+                    }
+                });
+            }
+            Message::Eject(device) => {
+                return self.run(device.clone(), async move {
+                    DisksClient::new().await?.remove(&device).await
+                });
+            }
+            Message::MountRemote(name, scope) => {
+                return self.run(name.clone(), async move {
+                    RcloneClient::new()
+                        .await?
+                        .mount(&name, &scope.to_string())
+                        .await
+                });
+            }
+            Message::UnmountRemote(name, scope) => {
+                return self.run(name.clone(), async move {
+                    RcloneClient::new()
+                        .await?
+                        .unmount(&name, &scope.to_string())
+                        .await
+                });
+            }
+            Message::Done(result) => {
+                self.busy = None;
+                if let Err(e) = result {
+                    self.error = Some(e);
+                }
+                // Remotes emit no signals, so reload after every operation
+                return load();
+            }
+            Message::OpenApp => {
+                match std::process::Command::new(APP_EXECUTABLE).spawn() {
+                    // Reap the app once it exits, the applet outlives it
+                    Ok(mut child) => drop(std::thread::spawn(move || child.wait())),
+                    Err(e) => tracing::error!(%e, "failed to launch {APP_EXECUTABLE}"),
+                }
+                if let Some(id) = self.popup.take() {
+                    return destroy_popup(id);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        self.core
+            .applet
+            .icon_button(PANEL_ICON)
+            .on_press(Message::TogglePopup)
+            .into()
+    }
+
+    fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
+        let mut content = iced_widget::column![].spacing(8).padding([8, 0]);
+
+        if let Some(error) = &self.error {
+            content = content.push(
+                cosmic::applet::padded_control(widget::text::caption(error.clone()))
+                    .width(Length::Fill),
+            );
+        }
+
+        content = content.push(section_heading(fl!("applet-drives")));
+        if self.devices.drives.is_empty() {
+            content = content.push(placeholder(fl!("applet-no-drives")));
+        }
+        for drive in &self.devices.drives {
+            content = content.push(self.drive_view(drive));
+        }
+
+        if !self.devices.remotes.is_empty() {
+            content = content
+                .push(cosmic::applet::padded_control(
+                    widget::divider::horizontal::default(),
+                ))
+                .push(section_heading(fl!("applet-remotes")));
+            for remote in &self.devices.remotes {
+                content = content.push(self.remote_view(remote));
+            }
+        }
+
+        content = content
+            .push(cosmic::applet::padded_control(
+                widget::divider::horizontal::default(),
+            ))
+            .push(
+                cosmic::applet::menu_button(widget::text::body(fl!("applet-open-app")))
+                    .on_press(Message::OpenApp),
+            );
+
+        self.core.applet.popup_container(content).into()
+    }
+
+    fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
+        Some(cosmic::applet::style())
+    }
+}
+
+impl Applet {
+    /// Run an operation on `target`, unless another one is still running
+    fn run(
+        &mut self,
+        target: String,
+        operation: impl Future<Output = Result<(), ClientError>> + Send + 'static,
+    ) -> Task<Message> {
+        if self.busy.is_some() {
+            return Task::none();
+        }
+        self.busy = Some(target);
+        self.error = None;
+        Task::perform(operation, |result| {
+            Message::Done(result.map_err(|e| e.to_string())).into()
+        })
+    }
+
+    fn drive_view<'a>(&'a self, drive: &'a RemovableDrive) -> Element<'a, Message> {
+        let mut eject = widget::button::icon(icon::from_name("media-eject-symbolic").size(16));
+        if self.busy.is_none() {
+            eject = eject.on_press(Message::Eject(drive.device.clone()));
+        }
+        let eject = widget::tooltip(
+            eject,
+            widget::text(fl!("applet-eject")),
+            widget::tooltip::Position::Bottom,
+        );
+
+        let header = iced_widget::row![
+            widget::text::body(drive.name.clone()).width(Length::Fill),
+            eject,
+        ]
+        .align_y(Alignment::Center);
+
+        let mut column = iced_widget::column![header].spacing(4);
+        for volume in &drive.volumes {
+            column = column.push(self.volume_view(volume));
+        }
+        cosmic::applet::padded_control(column).into()
+    }
+
+    fn volume_view<'a>(&'a self, volume: &'a RemovableVolume) -> Element<'a, Message> {
+        let (action, label) = match &volume.mount_point {
+            Some(_) => (
+                Message::Unmount(volume.device.clone()),
+                fl!("applet-unmount"),
+            ),
+            None => (Message::Mount(volume.device.clone()), fl!("applet-mount")),
+        };
+
+        let mut info = iced_widget::column![widget::text::body(volume.label.clone())]
+            .spacing(2)
+            .width(Length::Fill);
+        if let (Some(fraction), Some(free)) = (volume.used_fraction, volume.free_bytes) {
+            info = info
+                .push(iced_widget::progress_bar(0.0..=1.0, fraction).width(Length::Fill))
+                .push(widget::text::caption(fl!(
+                    "applet-free",
+                    size = bytes_to_pretty(&free, false)
+                )));
+        }
+
+        iced_widget::row![info, self.action_button(label, action)]
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .into()
+    }
+
+    /// A mount or unmount button, disabled while an operation runs
+    fn action_button(&self, label: String, action: Message) -> Element<'static, Message> {
+        let mut button = widget::button::standard(label);
+        if self.busy.is_none() {
+            button = button.on_press(action);
+        }
+        button.into()
+    }
+
+    fn remote_view<'a>(&'a self, remote: &'a NetworkRemote) -> Element<'a, Message> {
+        let (action, label) = if remote.mounted {
+            (
+                Message::UnmountRemote(remote.name.clone(), remote.scope),
+                fl!("applet-unmount"),
+            )
+        } else {
+            (
+                Message::MountRemote(remote.name.clone(), remote.scope),
+                fl!("applet-mount"),
+            )
+        };
+
+        let row = iced_widget::row![
+            widget::text::body(remote.name.clone()).width(Length::Fill),
+            self.action_button(label, action),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+        cosmic::applet::padded_control(row).into()
+    }
+}
+
+fn section_heading(label: String) -> Element<'static, Message> {
+    cosmic::applet::padded_control(widget::text::heading(label)).into()
+}
+
+fn placeholder(label: String) -> Element<'static, Message> {
+    cosmic::applet::padded_control(widget::text::caption(label)).into()
+}
+
+fn load() -> Task<Message> {
+    Task::perform(devices::load(), |devices| {
+        Message::Loaded(devices.map_err(|e| e.to_string())).into()
+    })
+}
@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Command-line arguments
+//!
+//! The app can be opened at a drive or volume, e.g. from a file manager:
+//!
+//! ```text
+//! cosmic-ext-storage --device /dev/sdb2
+//! cosmic-ext-storage --mount /run/media/user/DISK
+//! cosmic-ext-storage disks:///run/media/user/My%20Disk
+//! ```
+//!
+//! or at the setup wizard of a drive that holds nothing yet, e.g. from the
+//! notification announcing it:
+//!
+//! ```text
+//! cosmic-ext-storage --setup /dev/sdc
+//! ```
+//!
+//! or at the disks of another host, through its system bus forwarded over
+//! SSH (see `storage_contracts::client::connection`):
+//!
+//! ```text
+//! cosmic-ext-storage --remote /tmp/server-bus --remote-uid 1000
+//! ```
+//!
+//! or with made-up drives instead of the service's, for screenshots and
+//! trying the UI out (see `storage_testing::demo`):
+//!
+//! ```text
+//! cosmic-ext-storage --demo
+//! ```
+
+use std::fmt;
+
+/// URI scheme handled by the app; the path names a device or mount point
+const URI_SCHEME: &str = "disks://";
+
+const USAGE: &str = "Usage: cosmic-ext-storage [--device PATH | --mount PATH | disks://PATH] \
+     [--setup DEVICE] [--remote ADDRESS [--remote-uid UID]] [--demo]";
+
+/// What the app was asked to show on startup
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    /// Device path or mount point to select once the drives are loaded
+    pub reveal: Option<String>,
+    /// Drive to open the setup wizard for
+    pub setup: Option<String>,
+    /// D-Bus address or forwarded socket of another host's system bus
+    pub remote: Option<String>,
+    /// UID to authenticate as on the remote host
+    pub remote_uid: Option<u32>,
+    /// Show the demo dataset instead of the system's drives
+    pub demo: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ArgsError {
+    Help,
+    MissingValue(String),
+    NotAbsolute(String),
+    NotUid(String),
+    Unexpected(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Help => write!(f, "{USAGE}"),
+            Self::MissingValue(flag) => write!(f, "{flag} needs a value\n{USAGE}"),
+            Self::NotAbsolute(path) => write!(f, "{path} is not an absolute path"),
+            Self::NotUid(uid) => write!(f, "{uid} is not a UID"),
+            Self::Unexpected(arg) => write!(f, "unexpected argument {arg}\n{USAGE}"),
+        }
+    }
+}
+
+impl Args {
+    /// Parse the arguments after the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ArgsError> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (target, setup) = match arg.as_str() {
+                "-h" | "--help" => return Err(ArgsError::Help),
+                "--demo" => {
+                    parsed.demo = true;
+                    continue;
+                }
+                "--remote" => {
+                    parsed.remote = Some(args.next().ok_or(ArgsError::MissingValue(arg))?);
+                    continue;
+                }
+                "--remote-uid" => {
+                    let uid = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                    parsed.remote_uid = Some(uid.parse().map_err(|_| ArgsError::NotUid(uid))?);
+                    continue;
+                }
+                "--device" | "--mount" => (args.next().ok_or(ArgsError::MissingValue(arg))?, false),
+                "--setup" => (args.next().ok_or(ArgsError::MissingValue(arg))?, true),
+                _ => {
+                    if let Some(value) = arg
+                        .strip_prefix("--device=")
+                        .or_else(|| arg.strip_prefix("--mount="))
+                    {
+                        (value.to_string(), false)
+                    } else if let Some(value) = arg.strip_prefix("--setup=") {
+                        (value.to_string(), true)
+                    } else if let Some(path) = arg.strip_prefix(URI_SCHEME) {
+                        (percent_decode(path), false)
+                    } else {
+                        return Err(ArgsError::Unexpected(arg));
+                    }
+                }
+            };
+            if !target.starts_with('/') {
+                return Err(ArgsError::NotAbsolute(target));
+            }
+            if setup {
+                parsed.setup = Some(target);
+            } else {
+                parsed.reveal = Some(target);
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Decode `%XX` escapes, leaving malformed ones as they are
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, ArgsError> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_reveal_nothing() {
+        assert_eq!(parse(&[]), Ok(Args::default()));
+    }
+
+    #[test]
+    fn device_and_mount_flags_take_a_path() {
+        assert_eq!(
+            parse(&["--device", "/dev/sdb2"]).unwrap().reveal.as_deref(),
+            Some("/dev/sdb2")
+        );
+        assert_eq!(
+            parse(&["--mount=/run/media/u/DISK"])
+                .unwrap()
+                .reveal
+                .as_deref(),
+            Some("/run/media/u/DISK")
+        );
+        assert_eq!(
+            parse(&["--mount"]),
+            Err(ArgsError::MissingValue("--mount".to_string()))
+        );
+    }
+
+    #[test]
+    fn setup_flag_names_a_drive() {
+        let args = parse(&["--setup", "/dev/sdc"]).unwrap();
+        assert_eq!(args.setup.as_deref(), Some("/dev/sdc"));
+        assert_eq!(args.reveal, None);
+        assert_eq!(
+            parse(&["--setup=/dev/sdd"]).unwrap().setup.as_deref(),
+            Some("/dev/sdd")
+        );
+    }
+
+    #[test]
+    fn uri_paths_are_decoded() {
+        assert_eq!(
+            parse(&["disks:///run/media/u/My%20Disk"])
+                .unwrap()
+                .reveal
+                .as_deref(),
+            Some("/run/media/u/My Disk")
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%41"), "%zzA");
+    }
+
+    #[test]
+    fn remote_flags_name_a_bus_and_uid() {
+        let args = parse(&["--remote", "/tmp/server-bus", "--remote-uid", "1000"]).unwrap();
+        assert_eq!(args.remote.as_deref(), Some("/tmp/server-bus"));
+        assert_eq!(args.remote_uid, Some(1000));
+        assert_eq!(
+            parse(&["--remote-uid", "admin"]),
+            Err(ArgsError::NotUid("admin".to_string()))
+        );
+    }
+
+    #[test]
+    fn demo_flag_combines_with_a_target() {
+        let args = parse(&["--demo", "--device", "/dev/sdb"]).unwrap();
+        assert!(args.demo);
+        assert_eq!(args.reveal.as_deref(), Some("/dev/sdb"));
+        assert!(!parse(&[]).unwrap().demo);
+    }
+
+    #[test]
+    fn relative_and_unknown_arguments_are_rejected() {
+        assert_eq!(
+            parse(&["--device", "sdb2"]),
+            Err(ArgsError::NotAbsolute("sdb2".to_string()))
+        );
+        assert_eq!(
+            parse(&["--verbose"]),
+            Err(ArgsError::Unexpected("--verbose".to_string()))
+        );
+    }
+}
@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::notification_policy::NotificationSettings;
 use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
 use serde::{Deserialize, Serialize};
-use storage_types::UsageScanParallelismPreset;
+use storage_types::{
+    ByteFormat, ByteUnits, ConfirmationLevel, MountNamingScheme, MountPathPolicy, TemperatureUnit,
+    UsageScanParallelismPreset,
+};
 
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq)]
 pub enum LoggingLevel {
@@ -55,6 +59,19 @@ pub struct Config {
     pub usage_scan_parallelism: UsageScanParallelismPreset,
     pub log_to_disk: bool,
     pub log_level: LoggingLevel,
+    /// Base directory for service-managed mounts (empty = UDisks default)
+    pub mount_base_dir: String,
+    pub mount_naming: MountNamingScheme,
+    pub temperature_unit: TemperatureUnit,
+    /// SI (GB) or IEC (GiB) units for sizes
+    pub byte_units: ByteUnits,
+    pub notifications: NotificationSettings,
+    /// Notify when a filesystem is projected to run full within this many days
+    pub capacity_alert_days: u64,
+    /// Keep local usage statistics (never transmitted)
+    pub usage_statistics: bool,
+    /// What confirming a destructive operation takes
+    pub confirmation_level: ConfirmationLevel,
 }
 
 impl Default for Config {
@@ -65,6 +82,14 @@ impl Default for Config {
             usage_scan_parallelism: UsageScanParallelismPreset::default(),
             log_to_disk: true,
             log_level: LoggingLevel::Info,
+            mount_base_dir: String::new(),
+            mount_naming: MountNamingScheme::default(),
+            temperature_unit: TemperatureUnit::default(),
+            byte_units: ByteUnits::default(),
+            notifications: NotificationSettings::default(),
+            capacity_alert_days: 14,
+            usage_statistics: false,
+            confirmation_level: ConfirmationLevel::default(),
         }
     }
 }
@@ -78,4 +103,20 @@ impl Config {
             })
             .unwrap_or_default()
     }
+
+    pub fn mount_path_policy(&self) -> MountPathPolicy {
+        MountPathPolicy {
+            base_dir: self.mount_base_dir.clone(),
+            naming: self.mount_naming,
+        }
+    }
+
+    /// Byte format of the preferred units in the user's locale
+    pub fn byte_format(&self) -> ByteFormat {
+        let locale = i18n_embed::DesktopLanguageRequester::requested_languages()
+            .first()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        ByteFormat::new(self.byte_units, &locale)
+    }
 }
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Diagnostic reports of failed operations
+//!
+//! With the user's consent, the service collects the device layout, SMART
+//! data and log excerpts around a failure, starting with the summary of the
+//! failure written here. This module then packs the report into a
+//! compressed tar archive the user can attach to an issue.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use storage_types::DiagnosticBundle;
+
+/// An operation that failed, as shown in the error dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureContext {
+    /// Internal operation name, e.g. "create_partition"
+    pub operation: String,
+    /// The error as shown to the user
+    pub error: String,
+    /// Unix time of the failure, in seconds
+    pub time: u64,
+}
+
+impl FailureContext {
+    /// A failure of `operation` that happened just now
+    pub fn now(operation: &str, error: String) -> Self {
+        Self {
+            operation: operation.to_string(),
+            error,
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Summary of the failure and of the system it happened on
+    pub fn summary(&self, device: Option<&str>) -> String {
+        let os = std::fs::read_to_string("/etc/os-release")
+            .ok()
+            .and_then(|release| {
+                release.lines().find_map(|line| {
+                    line.strip_prefix("PRETTY_NAME=")
+                        .map(|name| name.trim_matches('"').to_string())
+                })
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        let kernel = std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .map(|release| release.trim().to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+
+        format!(
+            "Operation: {}\nDevice: {}\nTime: {}\nError: {}\n\nApp version: {} ({})\nSystem: {}\nKernel: {}\n",
+            self.operation,
+            device.unwrap_or("none"),
+            self.time,
+            self.error,
+            env!("CARGO_PKG_VERSION"),
+            env!("VERGEN_GIT_SHA"),
+            os,
+            kernel
+        )
+    }
+
+    /// Suggested file name of the archive
+    pub fn archive_name(&self) -> String {
+        format!("cosmic-ext-storage-diagnostics-{}.tar.gz", self.time)
+    }
+}
+
+/// Write `bundle` to `path` as a gzip-compressed tar archive
+///
+/// The service redacts with what it knows about the devices; the bundle is
+/// redacted once more with the user's name and home folder.
+pub async fn write_archive(mut bundle: DiagnosticBundle, path: &Path) -> Result<(), String> {
+    let mut secrets = Vec::new();
+    if let Ok(user) = std::env::var("USER") {
+        secrets.push(user);
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        secrets.push(home.to_string_lossy().into_owned());
+    }
+    bundle.redact(&secrets);
+
+    let dir = std::env::temp_dir().join(format!(
+        "cosmic-ext-storage-diagnostics-{}",
+        uuid::Uuid::new_v4()
+    ));
+    let result = pack(&bundle, &dir, path).await;
+    if let Err(e) = tokio::fs::remove_dir_all(&dir).await {
+        tracing::warn!(%e, "failed to remove diagnostics staging directory");
+    }
+    result
+}
+
+async fn pack(bundle: &DiagnosticBundle, dir: &Path, path: &Path) -> Result<(), String> {
+    let root = dir.join("diagnostics");
+    tokio::fs::create_dir_all(&root)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", root.display(), e))?;
+    for file in &bundle.files {
+        // Names come from the service; keep them inside the archive
+        let name: PathBuf = Path::new(&file.name)
+            .file_name()
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("Invalid file name in report: {}", file.name))?;
+        tokio::fs::write(root.join(&name), &file.contents)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", name.display(), e))?;
+    }
+
+    let output = tokio::process::Command::new("tar")
+        .arg("--create")
+        .arg("--gzip")
+        .arg("--file")
+        .arg(path)
+        .arg("--directory")
+        .arg(dir)
+        .arg("diagnostics")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to create the archive: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
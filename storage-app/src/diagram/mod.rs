@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Printable partition layout diagrams
+//!
+//! Draws the segment bar of a drive with the sizes and labels of its
+//! partitions, for documenting physical disks, e.g. on a label taped to the
+//! drive caddy. [`build`] takes the segments the app shows; the diagram is
+//! drawn as SVG independently of the widget tree, and rasterized for PNG.
+
+mod png;
+mod svg;
+
+use std::path::Path;
+
+use storage_types::bytes_to_pretty;
+
+use crate::fl;
+use crate::models::UiDrive;
+use crate::state::volumes::Segment;
+use crate::utils::DiskSegmentKind;
+
+/// File format of an exported diagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    Svg,
+    Png,
+}
+
+impl DiagramFormat {
+    /// Format matching the extension of `path`, if any
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// Layout of one drive
+#[derive(Debug, Clone, Default)]
+pub struct LayoutDiagram {
+    pub title: String,
+    /// Device, size, partition table and serial number
+    pub subtitle: String,
+    pub segments: Vec<DiagramSegment>,
+}
+
+/// A partition, free or reserved space of the bar
+#[derive(Debug, Clone)]
+pub struct DiagramSegment {
+    pub kind: DiskSegmentKind,
+    pub label: String,
+    /// Device, filesystem and size
+    pub detail: String,
+    /// Share of the bar's width, as in the app's segment bar
+    pub portion: u16,
+}
+
+impl LayoutDiagram {
+    pub fn render(&self, format: DiagramFormat) -> anyhow::Result<Vec<u8>> {
+        let svg = svg::render(self);
+        match format {
+            DiagramFormat::Svg => Ok(svg.into_bytes()),
+            DiagramFormat::Png => png::render(&svg),
+        }
+    }
+}
+
+/// Diagram of `drive` laid out as `segments`
+pub fn build(drive: &UiDrive, segments: &[Segment]) -> LayoutDiagram {
+    let disk = &drive.disk;
+    let mut subtitle = vec![disk.device.clone(), bytes_to_pretty(&disk.size, false)];
+    if let Some(table) = disk.partition_table_type.as_deref() {
+        subtitle.push(table.to_uppercase());
+    }
+    if !disk.serial.is_empty() {
+        subtitle.push(format!("{} {}", fl!("serial"), disk.serial));
+    }
+
+    let segments = segments
+        .iter()
+        .map(|segment| {
+            let size = bytes_to_pretty(&segment.size, false);
+            let (label, detail) = match segment.kind {
+                DiskSegmentKind::FreeSpace => (fl!("free-space-caption"), size),
+                DiskSegmentKind::Reserved => (fl!("reserved-space-caption"), size),
+                DiskSegmentKind::Partition => {
+                    let volume = segment.volume.as_ref();
+                    let label = volume
+                        .map(|volume| volume.label.clone())
+                        .filter(|label| !label.trim().is_empty())
+                        .unwrap_or_else(|| segment.label.clone());
+                    let detail = [
+                        segment.name.clone(),
+                        volume
+                            .map(|volume| volume.id_type.clone())
+                            .unwrap_or_default(),
+                        size,
+                    ]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" · ");
+                    (label, detail)
+                }
+            };
+            DiagramSegment {
+                kind: segment.kind,
+                label,
+                detail,
+                portion: segment.width.max(1),
+            }
+        })
+        .collect();
+
+    LayoutDiagram {
+        title: drive.name(),
+        subtitle: subtitle.join(" · "),
+        segments,
+    }
+}
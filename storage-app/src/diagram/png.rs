@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! PNG rendering of a layout diagram, rasterized from its SVG
+
+use resvg::{tiny_skia, usvg};
+
+/// Pixels per SVG unit, enough for printing on a label
+const SCALE: f32 = 2.0;
+
+pub(super) fn render(svg: &str) -> anyhow::Result<Vec<u8>> {
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(svg, &options)?;
+
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(SCALE)
+        .ok_or_else(|| anyhow::anyhow!("The diagram is too large to render"))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| anyhow::anyhow!("The diagram is too large to render"))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(SCALE, SCALE),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap.encode_png()?)
+}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! SVG drawing of a layout diagram
+//!
+//! The bar is numbered and a legend below it spells out each segment, as
+//! small partitions leave no room for their labels inside the bar. Colours
+//! are light enough to print on a monochrome label printer.
+
+use super::LayoutDiagram;
+use crate::utils::DiskSegmentKind;
+
+const WIDTH: f32 = 800.0;
+const MARGIN: f32 = 20.0;
+const BAR_TOP: f32 = 76.0;
+const BAR_HEIGHT: f32 = 64.0;
+const LEGEND_LINE: f32 = 22.0;
+const SWATCH: f32 = 14.0;
+
+/// Fills of partitions, repeated in disk order
+const PARTITION_FILLS: [&str; 6] = [
+    "#9ec5fe", "#a3cfbb", "#ffe69c", "#f1aeb5", "#c5b3e6", "#9eeaf9",
+];
+const FREE_FILL: &str = "#ffffff";
+const RESERVED_FILL: &str = "#dee2e6";
+
+pub(super) fn render(diagram: &LayoutDiagram) -> String {
+    let legend_top = BAR_TOP + BAR_HEIGHT + 28.0;
+    let height = legend_top + diagram.segments.len() as f32 * LEGEND_LINE + MARGIN;
+    let total = diagram
+        .segments
+        .iter()
+        .map(|segment| segment.portion as f32)
+        .sum::<f32>()
+        .max(1.0);
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{height}\" \
+         viewBox=\"0 0 {WIDTH} {height}\" font-family=\"sans-serif\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n\
+         <text x=\"{MARGIN}\" y=\"36\" font-size=\"18\" font-weight=\"bold\">{}</text>\n\
+         <text x=\"{MARGIN}\" y=\"58\" font-size=\"12\">{}</text>\n",
+        escape(&diagram.title),
+        escape(&diagram.subtitle)
+    );
+
+    let bar_width = WIDTH - 2.0 * MARGIN;
+    let mut x = MARGIN;
+    let mut partitions = 0;
+    for (index, segment) in diagram.segments.iter().enumerate() {
+        let width = bar_width * segment.portion as f32 / total;
+        let (fill, dash) = match segment.kind {
+            DiskSegmentKind::Partition => {
+                partitions += 1;
+                (
+                    PARTITION_FILLS[(partitions - 1) % PARTITION_FILLS.len()],
+                    "",
+                )
+            }
+            DiskSegmentKind::FreeSpace => (FREE_FILL, " stroke-dasharray=\"4 3\""),
+            DiskSegmentKind::Reserved => (RESERVED_FILL, ""),
+        };
+        out.push_str(&format!(
+            "<rect x=\"{x:.1}\" y=\"{BAR_TOP}\" width=\"{width:.1}\" height=\"{BAR_HEIGHT}\" \
+             fill=\"{fill}\" stroke=\"#212529\"{dash}/>\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"14\" font-weight=\"bold\" \
+             text-anchor=\"middle\">{}</text>\n",
+            x + width / 2.0,
+            BAR_TOP + BAR_HEIGHT / 2.0 + 5.0,
+            index + 1
+        ));
+
+        let y = legend_top + index as f32 * LEGEND_LINE;
+        out.push_str(&format!(
+            "<rect x=\"{MARGIN}\" y=\"{:.1}\" width=\"{SWATCH}\" height=\"{SWATCH}\" \
+             fill=\"{fill}\" stroke=\"#212529\"{dash}/>\n\
+             <text x=\"{:.1}\" y=\"{y:.1}\" font-size=\"12\"><tspan font-weight=\"bold\">{} \
+             {}</tspan> {}</text>\n",
+            y - SWATCH + 3.0,
+            MARGIN + SWATCH + 8.0,
+            index + 1,
+            escape(&segment.label),
+            escape(&segment.detail)
+        ));
+        x += width;
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagram::DiagramSegment;
+
+    fn segment(kind: DiskSegmentKind, label: &str, portion: u16) -> DiagramSegment {
+        DiagramSegment {
+            kind,
+            label: label.to_string(),
+            detail: "1 GB".to_string(),
+            portion,
+        }
+    }
+
+    #[test]
+    fn segments_share_the_bar_by_portion() {
+        let diagram = LayoutDiagram {
+            title: "Backup <disk>".to_string(),
+            subtitle: "/dev/sdb".to_string(),
+            segments: vec![
+                segment(DiskSegmentKind::Partition, "EFI", 1),
+                segment(DiskSegmentKind::Partition, "Data & more", 3),
+                segment(DiskSegmentKind::FreeSpace, "Free space", 1),
+            ],
+        };
+
+        let svg = render(&diagram);
+        assert!(svg.contains(">Backup &lt;disk&gt;</text>"));
+        assert!(svg.contains("Data &amp; more"));
+        assert!(svg.contains("<rect x=\"20.0\" y=\"76\" width=\"152.0\""));
+        assert!(svg.contains("<rect x=\"172.0\" y=\"76\" width=\"456.0\""));
+        assert!(svg.contains("stroke-dasharray"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
@@ -6,7 +6,8 @@
 //! error sources combine cleanly; convert to UI (e.g. this module) in the completion closure.
 
 use crate::app::Message;
-use crate::state::dialogs::ShowDialog;
+use crate::diagnostics::FailureContext;
+use crate::state::dialogs::{ErrorRecovery, ShowDialog};
 
 pub(crate) struct UiErrorContext<'a> {
     pub(crate) operation: &'static str,
@@ -30,6 +31,16 @@ pub(crate) fn log_error_and_show_dialog(
     title: impl Into<String>,
     err: anyhow::Error,
     ctx: UiErrorContext<'_>,
+) -> Message {
+    log_error_and_offer_recovery(title, err, ctx, None)
+}
+
+/// Like [`log_error_and_show_dialog`], with the dialog offering `recovery`
+pub(crate) fn log_error_and_offer_recovery(
+    title: impl Into<String>,
+    err: anyhow::Error,
+    ctx: UiErrorContext<'_>,
+    recovery: Option<ErrorRecovery>,
 ) -> Message {
     tracing::error!(
         ?err,
@@ -40,8 +51,11 @@ pub(crate) fn log_error_and_show_dialog(
         "error surfaced in UI"
     );
 
-    Message::Dialog(Box::new(ShowDialog::Info {
+    let body = format!("{err:#}");
+    Message::Dialog(Box::new(ShowDialog::Error {
         title: title.into(),
-        body: format!("{err:#}"),
+        failure: FailureContext::now(ctx.operation, body.clone()),
+        body,
+        recovery,
     }))
 }
@@ -141,6 +141,39 @@ fn log_level_allows(level: Level) -> bool {
     severity(level) <= LOG_LEVEL.load(Ordering::Relaxed)
 }
 
+/// The last `max` lines written to the log files, oldest first
+pub(crate) fn recent_lines(max: usize) -> std::io::Result<Vec<String>> {
+    let (dir, prefix) = resolve_log_location();
+    let prefix = prefix.to_string_lossy();
+
+    // The rolling appender appends the date to the prefix, so names sort by age
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)?
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(prefix.as_ref())
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+
+    let mut lines = Vec::new();
+    for file in files.iter().rev() {
+        let contents = fs::read_to_string(file)?;
+        let mut older: Vec<String> = contents.lines().map(str::to_string).collect();
+        older.append(&mut lines);
+        lines = older;
+        if lines.len() >= max {
+            break;
+        }
+    }
+    let excess = lines.len().saturating_sub(max);
+    lines.drain(..excess);
+    Ok(lines)
+}
+
 fn file_writer() -> anyhow::Result<(tracing_appender::non_blocking::NonBlocking, WorkerGuard)> {
     let (dir, prefix) = resolve_log_location();
 
@@ -1,24 +1,70 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 mod app;
-mod client;
+mod cli;
 mod config;
 mod controls;
+mod diagnostics;
+mod diagram;
 mod errors;
 mod i18n;
 mod logging;
 mod message;
 mod models;
+mod notification_policy;
+mod report;
 mod state;
 mod subscriptions;
 mod update;
 mod utils;
 mod views;
 
+use storage_contracts::client;
+
 //#[tokio::main]
 fn main() -> cosmic::iced::Result {
+    let args = match cli::Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(cli::ArgsError::Help) => {
+            println!("{}", cli::ArgsError::Help);
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
     let config = config::Config::load(app::APP_ID);
     logging::init(&config);
+    storage_types::set_byte_format(config.byte_format());
+
+    // Manage another host's disks, chosen here or in the environment
+    let target = match args.remote.as_deref() {
+        Some(address) => client::ConnectionTarget::parse(address, args.remote_uid)
+            .and_then(client::set_connection_target),
+        None => Ok(()),
+    }
+    .and_then(|()| client::connection_target());
+    let target = match target {
+        Ok(target) => target,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
+    if args.demo {
+        models::demo::enable();
+    }
+
+    // A running app manages this host's disks
+    if !args.demo
+        && target.remote_address().is_none()
+        && subscriptions::app_interface::forward_to_running_app(&args)
+    {
+        return Ok(());
+    }
 
     // Get the system's preferred languages.
     let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
@@ -33,6 +79,6 @@ fn main() -> cosmic::iced::Result {
             .min_height(180.0),
     );
 
-    // Starts the application's event loop with `()` as the application's flags.
-    cosmic::app::run::<app::AppModel>(settings, ())
+    // Starts the application's event loop with the command line as its flags.
+    cosmic::app::run::<app::AppModel>(settings, args)
 }
@@ -1,16 +1,29 @@
 use crate::config::Config;
+use crate::diagnostics::FailureContext;
 use crate::message::dialogs::{
-    AttachDiskImageDialogMessage, FormatDiskMessage, ImageOperationDialogMessage,
-    NewDiskImageDialogMessage, SmartDialogMessage, UnmountBusyMessage,
+    AttachDiskImageDialogMessage, AutomountDialogMessage, BindMountDialogMessage,
+    ConfirmationMessage, DefragDialogMessage, DiagnosticsDialogMessage,
+    EncryptedFoldersDialogMessage, EspSyncDialogMessage, FormatDiskMessage,
+    GptEntriesDialogMessage, ImageOperationDialogMessage, InspectDialogMessage,
+    LostPartitionsDialogMessage, LowSpaceDialogMessage, NewDiskImageDialogMessage,
+    PerformanceDialogMessage, SectorViewerDialogMessage, SetUpDriveMessage, SmartDialogMessage,
+    UnmountBusyMessage,
 };
+use crate::message::health_check::HealthCheckMessage;
+use crate::message::logs::LogsMessage;
 use crate::message::network::NetworkMessage;
+use crate::message::statistics::StatisticsMessage;
+use crate::message::vaults::VaultsMessage;
 use crate::message::volumes::VolumesControlMessage;
 use crate::models::UiDrive;
+use crate::notification_policy::NotificationCategory;
 use crate::state::app::ContextPage;
 use crate::state::dialogs::ShowDialog;
+use std::path::PathBuf;
 use storage_types::{
-    FilesystemToolInfo, UsageCategory, UsageDeleteResult, UsageScanParallelismPreset,
-    UsageScanResult,
+    DeferredJob, DiskHealthSummary, FilesystemToolInfo, PowerSettings, PrioritySettings,
+    RaidDetail, SafetySnapshotPolicy, ScheduledJob, UsageCategory, UsageDeleteResult,
+    UsageScanParallelismPreset, UsageScanResult,
 };
 
 /// Messages emitted by the application and its widgets.
@@ -26,6 +39,15 @@ pub enum Message {
     FormatDisk(FormatDiskMessage),
     DriveRemoved(String),
     DriveAdded(String),
+    /// An added drive was looked at; `blank_name` is its name when it holds
+    /// nothing yet and can be set up
+    DriveInspected {
+        device: String,
+        blank_name: Option<String>,
+    },
+    /// Open the setup wizard for a drive that holds nothing yet
+    SetUpDrive(String),
+    SetUpDriveDialog(SetUpDriveMessage),
     None,
     UpdateNav(Vec<UiDrive>, Option<String>),
     UpdateNavWithChildSelection(Vec<UiDrive>, Option<String>),
@@ -35,9 +57,59 @@ pub enum Message {
     PowerOff,
     Format,
     SmartData,
+    FindLostPartitions,
+    /// Edit the raw GPT entries of the selected drive
+    EditGptEntries,
+    ReportProblem(FailureContext),
     StandbyNow,
     Wakeup,
+    /// Blink the enclosure slot LED of the selected drive
+    IdentifyDrive,
+    /// Discard the unused blocks of the selected drive's mounted filesystems
+    TrimFilesystems,
     FilesystemToolsLoaded(Vec<FilesystemToolInfo>),
+    SafetySnapshotPolicyLoaded(SafetySnapshotPolicy),
+    SafetySnapshotPolicyChanged(SafetySnapshotPolicy),
+    OperationPrioritiesLoaded(PrioritySettings),
+    OperationPrioritiesChanged(PrioritySettings),
+    PowerPoliciesLoaded(PowerSettings),
+    PowerPoliciesChanged(PowerSettings),
+    DeferredJobsLoaded(Vec<DeferredJob>),
+    /// Start a job deferred by its power policy now
+    RunDeferredJob(ScheduledJob, String),
+    /// Long operations already running in the service
+    OperationsLoaded(Vec<storage_contracts::OperationProgress>),
+    /// A long operation started, made progress or finished
+    OperationEvent(storage_contracts::OperationEvent),
+    /// Ask a running operation to stop
+    CancelOperation(storage_contracts::OperationId),
+    RaidHealthChanged {
+        array: String,
+        event: String,
+    },
+    DriveHealthLoaded(Vec<DiskHealthSummary>),
+    /// Disc of each optical drive, by device path
+    OpticalMediaLoaded(
+        Vec<(
+            String,
+            Result<Option<storage_types::OpticalMediaInfo>, String>,
+        )>,
+    ),
+    TemperatureAlert {
+        device: String,
+        level: String,
+        celsius: u64,
+    },
+    /// A mounted filesystem dropped below its low space rule
+    LowSpaceWarning(storage_types::LowSpaceWarning),
+    ExportReport,
+    /// Destination of the report and the details of the RAID arrays in it
+    ReportDestinationChosen(Option<(PathBuf, Vec<RaidDetail>)>),
+    ReportSaved(Result<PathBuf, String>),
+    /// Export the segment bar of the selected drive as a diagram
+    ExportLayoutDiagram,
+    LayoutDiagramDestinationChosen(Option<PathBuf>),
+    LayoutDiagramSaved(Result<PathBuf, String>),
     UsageScanLoad {
         scan_id: String,
         top_files_per_category: u32,
@@ -102,6 +174,9 @@ pub enum Message {
         device_path: String,
     },
     SidebarClearChildSelection,
+    /// Select the drive or volume of a device path or mount point, on
+    /// behalf of another program
+    Reveal(String),
     SidebarToggleExpanded(crate::state::sidebar::SidebarNodeKey),
     SidebarDriveEject {
         device_path: String,
@@ -111,12 +186,46 @@ pub enum Message {
         device_path: String,
     },
     SmartDialog(SmartDialogMessage),
+    Confirmation(ConfirmationMessage),
+    DefragDialog(DefragDialogMessage),
+    EncryptedFoldersDialog(EncryptedFoldersDialogMessage),
+    EspSyncDialog(EspSyncDialogMessage),
+    LowSpaceDialog(LowSpaceDialogMessage),
+    AutomountDialog(AutomountDialogMessage),
+    PerformanceDialog(PerformanceDialogMessage),
+    SectorViewerDialog(SectorViewerDialogMessage),
+    InspectDialog(InspectDialogMessage),
+    LostPartitionsDialog(LostPartitionsDialogMessage),
+    GptEntriesDialog(GptEntriesDialogMessage),
+    DiagnosticsDialog(DiagnosticsDialogMessage),
     NewDiskImage,
     AttachDisk,
     CreateDiskFrom,
     RestoreImageTo,
     CreateDiskFromPartition,
     RestoreImageToPartition,
+    CopyPartition,
+    SyncEsp,
+    /// Set the low space alert of the selected mounted filesystem
+    LowSpaceAlerts,
+    /// Mount the selected volume on access through systemd automount units
+    Automount,
+    MigrateDisk,
+    CreateLiveUsb,
+    /// Tune the I/O scheduler and queue of the selected drive
+    AdvancedPerformance,
+    /// Show the raw sectors of a drive or partition
+    ViewSectors {
+        device: String,
+        size: u64,
+    },
+    /// Decode the superblock or LUKS header of a volume
+    InspectVolume {
+        device: String,
+        size: u64,
+    },
+    BurnDiscImage,
+    EraseDisc,
     NewDiskImageDialog(NewDiskImageDialogMessage),
     AttachDiskImageDialog(AttachDiskImageDialogMessage),
     ImageOperationDialog(ImageOperationDialogMessage),
@@ -129,7 +238,17 @@ pub enum Message {
     ToggleShowReserved(bool),
     UsageScanParallelismChanged(usize),
     ToggleLogToDisk(bool),
+    ToggleUsageStatistics(bool),
     LogLevelChanged(usize),
+    MountBaseDirChanged(String),
+    MountNamingSchemeChanged(usize),
+    TemperatureUnitChanged(usize),
+    ByteUnitsChanged(usize),
+    /// Days ahead a filesystem running full is notified
+    CapacityAlertDaysChanged(u64),
+    ConfirmationLevelChanged(usize),
+    NotificationCategoryToggled(NotificationCategory, bool),
+    WindowFocusChanged(bool),
 
     // BTRFS management
     BtrfsLoadSubvolumes {
@@ -158,6 +277,23 @@ pub enum Message {
         mount_point: String,
         used_space: Result<u64, String>,
     },
+    BtrfsLoadDeviceStats {
+        mount_point: String,
+    },
+    BtrfsDeviceStatsLoaded {
+        mount_point: String,
+        result: Result<Vec<storage_types::BtrfsDeviceStats>, String>,
+    },
+    BtrfsResetDeviceStats {
+        mount_point: String,
+    },
+    BtrfsResetDeviceStatsConfirm {
+        mount_point: String,
+    },
+    BtrfsDeviceStatsReset {
+        mount_point: String,
+        result: Result<(), String>,
+    },
     BtrfsToggleSubvolumeExpanded {
         mount_point: String,
         subvolume_id: u64,
@@ -188,6 +324,35 @@ pub enum Message {
     BtrfsCloseProperties {
         mount_point: String,
     },
+    BtrfsNocowLoaded {
+        mount_point: String,
+        result: Result<storage_types::NocowStatus, String>,
+    },
+    BtrfsSetNocow {
+        mount_point: String,
+        enabled: bool,
+    },
+    BtrfsRollback {
+        mount_point: String,
+    },
+    BtrfsRollbackConfirm {
+        mount_point: String,
+        target: String,
+        snapshot: String,
+    },
+    BtrfsRollbackFinished {
+        mount_point: String,
+        result: Result<storage_types::RollbackResult, String>,
+    },
+    BtrfsRestorePathChanged(String),
+    BtrfsRestoreConflictChanged(usize),
+    BtrfsRestoreFromSnapshot {
+        mount_point: String,
+    },
+    BtrfsRestoreFinished {
+        mount_point: String,
+        result: Result<storage_types::RestoreResult, String>,
+    },
     BtrfsLoadDeletedSubvolumes {
         mount_point: String,
     },
@@ -201,11 +366,113 @@ pub enum Message {
     BtrfsRefreshAll {
         mount_point: String,
     },
+    BtrfsLoadCompression {
+        mount_point: String,
+    },
+    BtrfsCompressionLoaded {
+        mount_point: String,
+        result: Result<storage_types::CompressionInfo, String>,
+    },
+    BtrfsCompressionTargetChanged(String),
+    BtrfsCompressionAlgorithmChanged(usize),
+    BtrfsCompressionForceToggled(bool),
+    BtrfsApplyCompressionProperty {
+        mount_point: String,
+    },
+    BtrfsApplyMountCompression {
+        mount_point: String,
+    },
+    BtrfsEstimateCompression {
+        mount_point: String,
+    },
+    BtrfsCompressionEstimated {
+        mount_point: String,
+        result: Result<storage_types::CompressionEstimate, String>,
+    },
+    BtrfsCompressExisting {
+        mount_point: String,
+    },
+    BtrfsCompressExistingConfirm {
+        mount_point: String,
+    },
+    BtrfsCompressionApplied {
+        mount_point: String,
+        result: Result<(), String>,
+    },
+
+    // Log viewer
+    Logs(LogsMessage),
+
+    // Usage statistics
+    Statistics(StatisticsMessage),
+
+    // Check of all drives
+    HealthCheck(HealthCheckMessage),
+
+    // Legacy vaults
+    Vaults(VaultsMessage),
 
     // Network mounts (RClone, Samba, FTP)
     Network(NetworkMessage),
     LoadNetworkRemotes,
-    NetworkRemotesLoaded(Result<Vec<storage_types::rclone::RemoteConfig>, String>),
+    NetworkRemotesLoaded(Result<storage_types::rclone::RemoteConfigList, String>),
+
+    // FUSE and gvfs mounts of the user
+    UserMountsLoaded(Vec<storage_types::UserMount>),
+    /// Disks behind hardware RAID controllers
+    PhysicalDevicesLoaded(Vec<storage_types::PhysicalDevice>),
+    /// Hypervisor the system runs under, None on bare metal
+    HypervisorLoaded(Option<String>),
+    /// What the user's restriction profiles let them do
+    AllowedActionsLoaded(storage_types::AllowedActions),
+    UnmountUserMount(std::path::PathBuf),
+    UserMountUnmounted {
+        mount_point: std::path::PathBuf,
+        result: Result<(), String>,
+    },
+
+    // Bind mounts
+    BindMountsLoaded(Vec<storage_types::BindMount>),
+    NewBindMount,
+    BindMountDialog(BindMountDialogMessage),
+    /// Unmount the bind mount at a target, and with `forget` also remove its
+    /// fstab entry
+    RemoveBindMount {
+        target: std::path::PathBuf,
+        forget: bool,
+    },
+    BindMountRemoved {
+        target: std::path::PathBuf,
+        result: Result<(), String>,
+    },
+
+    // Phones and cameras
+    MtpDevicesLoaded(Vec<storage_types::MtpDevice>),
+    MountMtpDevice(String),
+    EjectMtpDevice(String),
+    MtpOperationCompleted {
+        uri: String,
+        eject: bool,
+        result: Result<(), String>,
+    },
+
+    // Filesystems the kernel made read-only after errors
+    ForcedReadOnlyLoaded(Result<Vec<storage_types::ForcedReadOnly>, String>),
+    RemountReadWrite(storage_types::ForcedReadOnly),
+    RepairForcedReadOnly(storage_types::ForcedReadOnly),
+    RepairForcedReadOnlyConfirm(storage_types::ForcedReadOnly),
+
+    // SELinux labels of mounted filesystems
+    SelinuxStatusesLoaded(Result<Vec<storage_types::SelinuxStatus>, String>),
+    /// Relabel the filesystem at this mount point
+    Relabel(String),
+    Relabeled {
+        mount_point: String,
+        result: Result<(), String>,
+    },
+
+    // Capacity forecasts of mounted filesystems
+    CapacityForecastsLoaded(Result<Vec<storage_types::CapacityForecast>, String>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -222,12 +489,96 @@ impl From<FormatDiskMessage> for Message {
     }
 }
 
+impl From<SetUpDriveMessage> for Message {
+    fn from(val: SetUpDriveMessage) -> Self {
+        Message::SetUpDriveDialog(val)
+    }
+}
+
+impl From<ConfirmationMessage> for Message {
+    fn from(val: ConfirmationMessage) -> Self {
+        Message::Confirmation(val)
+    }
+}
+
 impl From<SmartDialogMessage> for Message {
     fn from(val: SmartDialogMessage) -> Self {
         Message::SmartDialog(val)
     }
 }
 
+impl From<LostPartitionsDialogMessage> for Message {
+    fn from(val: LostPartitionsDialogMessage) -> Self {
+        Message::LostPartitionsDialog(val)
+    }
+}
+
+impl From<GptEntriesDialogMessage> for Message {
+    fn from(val: GptEntriesDialogMessage) -> Self {
+        Message::GptEntriesDialog(val)
+    }
+}
+
+impl From<DiagnosticsDialogMessage> for Message {
+    fn from(val: DiagnosticsDialogMessage) -> Self {
+        Message::DiagnosticsDialog(val)
+    }
+}
+
+impl From<DefragDialogMessage> for Message {
+    fn from(val: DefragDialogMessage) -> Self {
+        Message::DefragDialog(val)
+    }
+}
+
+impl From<EncryptedFoldersDialogMessage> for Message {
+    fn from(val: EncryptedFoldersDialogMessage) -> Self {
+        Message::EncryptedFoldersDialog(val)
+    }
+}
+
+impl From<EspSyncDialogMessage> for Message {
+    fn from(val: EspSyncDialogMessage) -> Self {
+        Message::EspSyncDialog(val)
+    }
+}
+
+impl From<BindMountDialogMessage> for Message {
+    fn from(val: BindMountDialogMessage) -> Self {
+        Message::BindMountDialog(val)
+    }
+}
+
+impl From<AutomountDialogMessage> for Message {
+    fn from(val: AutomountDialogMessage) -> Self {
+        Message::AutomountDialog(val)
+    }
+}
+
+impl From<LowSpaceDialogMessage> for Message {
+    fn from(val: LowSpaceDialogMessage) -> Self {
+        Message::LowSpaceDialog(val)
+    }
+}
+
+impl From<PerformanceDialogMessage> for Message {
+    fn from(val: PerformanceDialogMessage) -> Self {
+        Message::PerformanceDialog(val)
+    }
+}
+
+impl From<SectorViewerDialogMessage> for Message {
+    fn from(val: SectorViewerDialogMessage) -> Self {
+        Message::SectorViewerDialog(val)
+    }
+}
+
+impl From<InspectDialogMessage> for Message {
+    fn from(val: InspectDialogMessage) -> Self {
+        Message::InspectDialog(val)
+    }
+}
+
 impl From<NewDiskImageDialogMessage> for Message {
     fn from(val: NewDiskImageDialogMessage) -> Self {
         Message::NewDiskImageDialog(val)
@@ -252,6 +603,30 @@ impl From<UnmountBusyMessage> for Message {
     }
 }
 
+impl From<LogsMessage> for Message {
+    fn from(val: LogsMessage) -> Self {
+        Message::Logs(val)
+    }
+}
+
+impl From<HealthCheckMessage> for Message {
+    fn from(val: HealthCheckMessage) -> Self {
+        Message::HealthCheck(val)
+    }
+}
+
+impl From<VaultsMessage> for Message {
+    fn from(val: VaultsMessage) -> Self {
+        Message::Vaults(val)
+    }
+}
+
+impl From<StatisticsMessage> for Message {
+    fn from(val: StatisticsMessage) -> Self {
+        Message::Statistics(val)
+    }
+}
+
 impl From<NetworkMessage> for Message {
     fn from(val: NetworkMessage) -> Self {
         Message::Network(val)
@@ -18,6 +18,15 @@ pub enum ResizePartitionMessage {
     NextStep,
     SetStep(crate::state::dialogs::ResizePartitionStep),
     SizeUpdate(u64),
+    BoundsLoaded(Result<storage_types::ResizeBounds, String>),
+    Confirm,
+    Cancel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MovePartitionMessage {
+    FreeBeforeUpdate(u64),
+    BoundsLoaded(Result<storage_types::MoveBounds, String>),
     Confirm,
     Cancel,
 }
@@ -39,6 +48,8 @@ pub enum EditMountOptionsMessage {
     RequireAuthUpdate(bool),
     ShowInUiUpdate(bool),
     OtherOptionsUpdate(String),
+    /// Apply the mount option preset with this id
+    PresetUpdate(Option<String>),
     DisplayNameUpdate(String),
     IconNameUpdate(String),
     SymbolicIconNameUpdate(String),
@@ -95,6 +106,8 @@ pub enum CreateMessage {
     PasswordProtectedUpdate(bool),
     EraseUpdate(bool),
     PartitionTypeUpdate(usize),
+    FormatPresetUpdate(Option<String>),
+    FormatOptionUpdate(String, String),
     Cancel,
     Partition,
 }
@@ -106,6 +119,16 @@ pub enum UnlockMessage {
     Cancel,
 }
 
+/// Steps of a guarded destructive confirmation, see
+/// [`ConfirmationGuard`](crate::state::dialogs::ConfirmationGuard)
+#[derive(Debug, Clone)]
+pub enum ConfirmationMessage {
+    NameUpdate(String),
+    TokenUpdate(String),
+    /// A second of the countdown passed
+    Tick,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FormatDiskMessage {
     EraseUpdate(usize),
@@ -114,6 +137,23 @@ pub enum FormatDiskMessage {
     Confirm,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetUpDriveMessage {
+    PrevStep,
+    NextStep,
+    SetStep(crate::state::dialogs::SetUpDriveStep),
+    TableUpdate(usize),
+    FilesystemUpdate(usize),
+    LabelUpdate(String),
+    EncryptUpdate(bool),
+    PassphraseUpdate(String),
+    ConfirmPassphraseUpdate(String),
+    Confirm,
+    /// Setting up failed and the drive was left blank again
+    Failed(String),
+    Cancel,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SmartDialogMessage {
     Refresh,
@@ -131,6 +171,176 @@ pub enum SmartDialogMessage {
         >,
     ),
     ActionComplete(Result<(), String>),
+    BackendLoaded(Result<storage_types::SmartBackendStatus, String>),
+    /// Index into automatic, UDisks, smartctl
+    BackendSelected(usize),
+    SelfTestsLoaded(
+        Result<
+            (
+                storage_types::SelfTestSchedule,
+                Vec<storage_types::SelfTestRecord>,
+            ),
+            String,
+        >,
+    ),
+    /// Index into `SELFTEST_INTERVAL_DAYS`
+    ShortScheduleSelected(usize),
+    /// Index into `SELFTEST_INTERVAL_DAYS`
+    ExtendedScheduleSelected(usize),
+    ThresholdsLoaded(Result<storage_types::TemperatureThresholds, String>),
+    KernelErrorsLoaded(Result<Vec<storage_types::KernelDeviceError>, String>),
+    /// Index into `TEMPERATURE_THRESHOLD_CHOICES`
+    WarningThresholdSelected(usize),
+    /// Index into `TEMPERATURE_THRESHOLD_CHOICES`
+    CriticalThresholdSelected(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LostPartitionsDialogMessage {
+    Scanned(Result<Vec<storage_types::LostPartition>, String>),
+    /// Index into the found partitions
+    Toggle(usize, bool),
+    Recreate,
+    Recreated(Result<(), String>),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GptEntriesDialogMessage {
+    Loaded(Result<storage_types::GptTable, String>),
+    /// Index into the rows, the field and its new text
+    FieldUpdate(usize, crate::state::dialogs::GptEntryField, String),
+    /// Put the rows back to the table as read
+    Revert,
+    Apply,
+    Applied(Result<(), String>),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticsDialogMessage {
+    Save,
+    /// Where the report was saved, `None` when saving was cancelled
+    Saved(Result<Option<std::path::PathBuf>, String>),
+    Cancel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefragDialogMessage {
+    TargetUpdate(String),
+    Analyze,
+    Analyzed(Result<storage_types::FragmentationReport, String>),
+    Start,
+    Progress {
+        device: String,
+        processed: u64,
+        total: u64,
+    },
+    Complete(Result<storage_types::DefragResult, String>),
+    Close,
+}
+
+#[derive(Debug, Clone)]
+pub enum EncryptedFoldersDialogMessage {
+    Load,
+    Loaded(Result<storage_types::FscryptStatus, String>),
+    StartUnlock(String),
+    CancelUnlock,
+    UnlockPassphraseUpdate(String),
+    Unlock,
+    Lock(String),
+    NewDirectoryUpdate(String),
+    ProtectorNameUpdate(String),
+    PassphraseUpdate(String),
+    ConfirmUpdate(String),
+    Create,
+    Done(Result<(), String>),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EspSyncDialogMessage {
+    PairsLoaded(Result<Vec<storage_types::EspSyncPair>, String>),
+    SetSecondary(usize),
+    /// Index into `ESP_SYNC_INTERVALS`
+    SetInterval(usize),
+    ScheduleSaved(Result<(), String>),
+    Sync,
+    Complete(Result<storage_types::EspSyncResult, String>),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LowSpaceDialogMessage {
+    RulesLoaded(Result<Vec<storage_types::LowSpaceRule>, String>),
+    /// Index into `LOW_SPACE_PERCENT_CHOICES`
+    SetPercent(usize),
+    /// Index into `LOW_SPACE_BYTES_CHOICES`
+    SetBytes(usize),
+    /// Index into `LowSpaceAction::ALL`
+    SetAction(usize),
+    Saved(Result<(), String>),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutomountDialogMessage {
+    UnitsLoaded(Result<Vec<storage_types::AutomountUnit>, String>),
+    MountPointUpdate(String),
+    OptionsUpdate(String),
+    /// Index into `IDLE_TIMEOUT_PRESETS`
+    SetIdleTimeout(usize),
+    Create,
+    /// Remove the units mounting at this mount point
+    Remove(std::path::PathBuf),
+    /// Creating or removing units completed
+    Changed(Result<(), String>),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectorViewerDialogMessage {
+    Loaded(Result<storage_types::SectorRange, String>),
+    PreviousPage,
+    NextPage,
+    /// Offset typed into the jump field
+    OffsetInput(String),
+    Jump,
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InspectDialogMessage {
+    Loaded(Result<Vec<storage_types::SectorRange>, String>),
+    /// Show the raw bytes of the structure at this offset
+    ViewSectors(u64),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PerformanceDialogMessage {
+    Loaded(Result<storage_types::QueueSettings, String>),
+    /// Index into the drive's available schedulers
+    SetScheduler(usize),
+    /// Index into `PerformanceDialog::read_ahead_choices`
+    SetReadAhead(usize),
+    /// Index into `PerformanceDialog::nr_requests_choices`
+    SetRequests(usize),
+    WriteCacheLoaded(Result<storage_types::WriteCacheStatus, String>),
+    SetWriteCache(bool),
+    SetPersistent(bool),
+    Apply,
+    /// Settings of the drive after applying
+    Applied(
+        Result<
+            (
+                storage_types::QueueSettings,
+                storage_types::WriteCacheStatus,
+            ),
+            String,
+        >,
+    ),
+    Close,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -158,11 +368,43 @@ pub struct AttachDiskResult {
 pub enum ImageOperationDialogMessage {
     Start,
     CancelOperation,
-    /// Progress update from subscription (operation_id, bytes_completed, total_bytes, speed_bytes_per_sec).
-    Progress(String, u64, u64, u64),
+    SetVerify(bool),
+    SetFullErase(bool),
+    /// Copy onto the partition at this index of the copy targets
+    SetCopyTarget(usize),
+    /// Migrate onto the drive at this index of the migration targets
+    SetMigrationTarget(usize),
+    /// Grow all partitions in proportion instead of the picked ones
+    SetMigrationProportional(bool),
+    /// Whether the partition at this index grows in a migration
+    SetMigrationGrow(usize, bool),
+    /// What the ISO picked for a live USB drive turned out to be
+    LiveIsoProbed(Result<storage_types::LiveIsoInfo, String>),
+    SetLivePersistence(bool),
+    /// Size of the persistence partition in GiB
+    SetLivePersistenceSize(u64),
+    SetLiveDataPartition(bool),
+    /// The user agreed to erasing the whole drive
+    SetEraseConfirmed(bool),
+    /// Run with the priority preset at this index, or as configured for
+    /// `None`
+    SetPriority(Option<usize>),
+    /// Progress update from subscription (operation_id, bytes_completed, total_bytes, speed_bytes_per_sec, eta).
+    Progress(String, u64, u64, u64, Option<storage_types::EtaRange>),
     Complete(Result<(), String>),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindMountDialogMessage {
+    SourceUpdate(String),
+    TargetUpdate(String),
+    ReadOnlyUpdate(bool),
+    PersistentUpdate(bool),
+    Create,
+    Created(Result<(), String>),
+    Cancel,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum UnmountBusyMessage {
     Cancel,
@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Messages of the health check page
+
+use storage_types::HealthCheckReport;
+
+#[derive(Debug, Clone)]
+pub enum HealthCheckMessage {
+    /// Check all drives, filesystems, arrays and btrfs devices
+    Run,
+    Finished(HealthCheckReport),
+    /// Show or hide the details of an item
+    ToggleItem(usize),
+}
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Messages of the log viewer
+
+use storage_types::LogEntry;
+
+#[derive(Debug, Clone)]
+pub enum LogsMessage {
+    /// Read the app's log files again
+    Load,
+    Loaded(Result<Vec<LogEntry>, String>),
+    /// Index into `LogLevel::ALL`
+    LevelChanged(usize),
+    ModuleChanged(String),
+    OperationChanged(String),
+    DeviceChanged(String),
+    /// Fetch the service's log lines matching the operation and device
+    FetchServiceLog,
+    ServiceLogLoaded(Result<Vec<LogEntry>, String>),
+}
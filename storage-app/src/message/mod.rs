@@ -1,4 +1,8 @@
 pub(crate) mod app;
 pub(crate) mod dialogs;
+pub(crate) mod health_check;
+pub(crate) mod logs;
 pub(crate) mod network;
+pub(crate) mod statistics;
+pub(crate) mod vaults;
 pub(crate) mod volumes;
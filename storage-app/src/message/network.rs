@@ -2,7 +2,9 @@
 
 //! Messages for network mount management
 
-use storage_types::rclone::{ConfigScope, RemoteConfig};
+use storage_types::rclone::{
+    ConfigScope, LoginMountStatus, RemoteConfigList, RemoteUsage, TransferJob,
+};
 
 /// Messages for network mount operations
 #[derive(Debug, Clone)]
@@ -10,7 +12,7 @@ pub enum NetworkMessage {
     /// Load all configured remotes
     LoadRemotes,
     /// Remotes loaded from service
-    RemotesLoaded(Result<Vec<RemoteConfig>, String>),
+    RemotesLoaded(Result<RemoteConfigList, String>),
     /// Select a remote in the sidebar
     SelectRemote { name: String, scope: ConfigScope },
     /// Start creating a new remote (opens wizard)
@@ -23,6 +25,8 @@ pub enum NetworkMessage {
     EditorScopeChanged(usize),
     /// Update a remote option field
     EditorFieldChanged { key: String, value: String },
+    /// Update the custom mount point in editor
+    EditorMountPointChanged(String),
     /// Update the new custom option key
     EditorNewOptionKeyChanged(String),
     /// Update the new custom option value
@@ -81,6 +85,10 @@ pub enum NetworkMessage {
         previous: bool,
         result: Result<(), String>,
     },
+    /// Load the automatic mount state of all remotes
+    LoadLoginMounts,
+    /// Automatic mount states loaded
+    LoginMountsLoaded(Result<Vec<LoginMountStatus>, String>),
     /// Open the mount path in file manager
     OpenMountPath(String),
     /// Mount a remote
@@ -101,6 +109,8 @@ pub enum NetworkMessage {
         scope: ConfigScope,
         result: Result<(), String>,
     },
+    /// Mount a remote on access through systemd automount units
+    OpenAutomount { name: String, scope: ConfigScope },
     /// Test remote configuration
     TestRemote { name: String, scope: ConfigScope },
     /// Test result received
@@ -113,6 +123,19 @@ pub enum NetworkMessage {
         scope: ConfigScope,
         mounted: bool,
     },
+    /// Query quota and usage of a remote, bypassing the service's cache if
+    /// `refresh` is set
+    LoadRemoteUsage {
+        name: String,
+        scope: ConfigScope,
+        refresh: bool,
+    },
+    /// Usage queried (`None` if the remote cannot report it)
+    RemoteUsageLoaded {
+        name: String,
+        scope: ConfigScope,
+        result: Result<Option<RemoteUsage>, String>,
+    },
     /// Delete remote (with confirmation)
     DeleteRemote { name: String, scope: ConfigScope },
     /// Confirm delete remote
@@ -122,4 +145,52 @@ pub enum NetworkMessage {
         name: String,
         result: Result<(), String>,
     },
+    /// Ask for the password of an encrypted configuration
+    UnlockConfig(ConfigScope),
+    /// Forget the password of an encrypted configuration
+    LockConfig(ConfigScope),
+    /// Ask for a password to encrypt a configuration with
+    EncryptConfig(ConfigScope),
+    /// Update the password in the configuration password dialog
+    ConfigPasswordChanged(String),
+    /// Update the repeated password in the configuration password dialog
+    ConfigPasswordConfirmChanged(String),
+    /// Unlock or encrypt with the password entered
+    ConfirmConfigPassword,
+    /// Unlocking, locking or encrypting completed
+    ConfigEncryptionCompleted(Result<(), String>),
+    /// Show the transfers page
+    ShowTransfers,
+    /// Load running and finished transfers
+    LoadTransfers,
+    /// Transfers loaded
+    TransfersLoaded(Result<Vec<TransferJob>, String>),
+    /// Update the scope in the transfer form (by index)
+    TransferScopeChanged(usize),
+    /// Update the source in the transfer form (0 for a local folder, then the
+    /// remotes by index)
+    TransferSourceChanged(usize),
+    /// Update the source path in the transfer form
+    TransferSourcePathChanged(String),
+    /// Update the destination in the transfer form (0 for a local folder,
+    /// then the remotes by index)
+    TransferDestinationChanged(usize),
+    /// Update the destination path in the transfer form
+    TransferDestinationPathChanged(String),
+    /// Update the mode in the transfer form (by index)
+    TransferModeChanged(usize),
+    /// Update the bandwidth limit in the transfer form
+    TransferBandwidthLimitChanged(String),
+    /// Toggle a dry run in the transfer form
+    TransferDryRunToggled(bool),
+    /// Start the transfer described by the form
+    StartTransfer,
+    /// Transfer started (with its ID on success)
+    TransferStarted(Result<String, String>),
+    /// Cancel a running transfer
+    CancelTransfer(String),
+    /// Forget finished transfers
+    ClearTransferHistory,
+    /// Cancelling a transfer or clearing the history completed
+    TransferActionCompleted(Result<(), String>),
 }
@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Messages of the usage statistics page
+
+use storage_types::UsageStatistics;
+
+#[derive(Debug, Clone)]
+pub enum StatisticsMessage {
+    /// Fetch the statistics from the service
+    Load,
+    Loaded(Result<UsageStatistics, String>),
+    /// Turn recording on or off, from the setting
+    SetEnabled(bool),
+    EnabledSet(Result<(), String>),
+    /// Remove the records kept so far
+    Clear,
+    Cleared(Result<(), String>),
+}
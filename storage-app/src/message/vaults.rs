@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Messages of the legacy vaults page
+
+use storage_types::{LegacyVault, VaultMigrationResult};
+
+#[derive(Debug, Clone)]
+pub enum VaultsMessage {
+    /// Look for eCryptfs and gocryptfs vaults
+    Load,
+    Loaded(Result<Vec<LegacyVault>, String>),
+    /// Show or hide the details of the vault in this encrypted directory
+    Select(String),
+    PassphraseUpdate(String),
+    TargetUpdate(String),
+    Mount,
+    Mounted(Result<String, String>),
+    Unmount(String),
+    Unmounted(Result<(), String>),
+    /// Copy the selected vault into the target directory
+    Migrate,
+    MigrationProgress {
+        cipher_dir: String,
+        copied_bytes: u64,
+        total_bytes: u64,
+    },
+    Migrated(Result<VaultMigrationResult, String>),
+}
@@ -2,8 +2,8 @@ use crate::app::Message;
 use crate::message::dialogs::{
     BtrfsCreateSnapshotMessage, BtrfsCreateSubvolumeMessage, ChangePassphraseMessage,
     CreateMessage, EditEncryptionOptionsMessage, EditFilesystemLabelMessage,
-    EditMountOptionsMessage, EditPartitionMessage, ResizePartitionMessage, TakeOwnershipMessage,
-    UnlockMessage,
+    EditMountOptionsMessage, EditPartitionMessage, MovePartitionMessage, ResizePartitionMessage,
+    TakeOwnershipMessage, UnlockMessage,
 };
 use crate::state::volumes::DetailTab;
 
@@ -18,12 +18,21 @@ pub enum VolumesControlMessage {
     Mount,
     Unmount,
     ChildMount(String),
+    /// Mount a device read-only, after Windows was found to have left it
+    /// hibernated
+    MountReadOnly(String),
+    /// Check and repair a device's filesystem, then mount it again, after
+    /// mounting it failed for want of a check
+    RepairAndMount(String),
     ChildUnmount(String),
     LockContainer,
     Delete,
     OpenFormatPartition,
     OpenEditPartition,
     OpenResizePartition,
+    OpenMovePartition,
+    OpenRealignPartition,
+    RealignPartitionConfirm,
     OpenEditFilesystemLabel,
     OpenEditMountOptions,
     OpenCheckFilesystem,
@@ -35,10 +44,24 @@ pub enum VolumesControlMessage {
     OpenEditEncryptionOptions,
     OpenBtrfsCreateSubvolume,
     OpenBtrfsCreateSnapshot,
+    OpenDefragment,
+    OpenEncryptedFolders,
+    ToggleFilesystemFeatures,
+    FilesystemFeaturesLoaded {
+        device: String,
+        result: Result<storage_types::FilesystemFeatures, String>,
+    },
+    DeletedPartitionsLoaded {
+        device: String,
+        result: Result<Vec<storage_types::DeletedPartition>, String>,
+    },
+    /// Put back the partition deleted as this id
+    RestoreDeletedPartition(u64),
     CreateMessage(CreateMessage),
     UnlockMessage(UnlockMessage),
     EditPartitionMessage(EditPartitionMessage),
     ResizePartitionMessage(ResizePartitionMessage),
+    MovePartitionMessage(MovePartitionMessage),
     EditFilesystemLabelMessage(EditFilesystemLabelMessage),
     EditMountOptionsMessage(EditMountOptionsMessage),
     TakeOwnershipMessage(TakeOwnershipMessage),
@@ -96,6 +119,12 @@ impl From<ResizePartitionMessage> for VolumesControlMessage {
     }
 }
 
+impl From<MovePartitionMessage> for VolumesControlMessage {
+    fn from(val: MovePartitionMessage) -> Self {
+        VolumesControlMessage::MovePartitionMessage(val)
+    }
+}
+
 impl From<EditFilesystemLabelMessage> for VolumesControlMessage {
     fn from(val: EditFilesystemLabelMessage) -> Self {
         VolumesControlMessage::EditFilesystemLabelMessage(val)
@@ -138,6 +167,12 @@ impl From<ResizePartitionMessage> for Message {
     }
 }
 
+impl From<MovePartitionMessage> for Message {
+    fn from(val: MovePartitionMessage) -> Self {
+        Message::VolumesMessage(VolumesControlMessage::MovePartitionMessage(val))
+    }
+}
+
 impl From<EditFilesystemLabelMessage> for Message {
     fn from(val: EditFilesystemLabelMessage) -> Self {
         Message::VolumesMessage(VolumesControlMessage::EditFilesystemLabelMessage(val))
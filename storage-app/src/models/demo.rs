@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Demo mode: made-up drives in place of the service's
+//!
+//! With `--demo`, drives, volumes, partitions, health badges and SMART data
+//! come from [`storage_testing::demo_dataset`]. Actions still go to the
+//! service and fail on the made-up devices.
+
+use std::sync::OnceLock;
+
+use storage_testing::DemoDataset;
+
+static DATASET: OnceLock<DemoDataset> = OnceLock::new();
+
+/// Show the demo dataset from now on
+pub fn enable() {
+    DATASET.get_or_init(storage_testing::demo_dataset);
+}
+
+/// The demo dataset, when demo mode is on
+pub fn dataset() -> Option<&'static DemoDataset> {
+    DATASET.get()
+}
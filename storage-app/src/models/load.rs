@@ -17,6 +17,17 @@ use crate::client::{DisksClient, error::ClientError};
 /// }
 /// ```
 pub async fn load_all_drives() -> Result<Vec<UiDrive>, ClientError> {
+    if let Some(dataset) = super::demo::dataset() {
+        let mut drives = Vec::new();
+        for disk in &dataset.disks {
+            let partitions = dataset.partitions_of(&disk.device);
+            drives.push(
+                UiDrive::from_parts(disk.clone(), dataset.volumes.clone(), partitions).await?,
+            );
+        }
+        return Ok(drives);
+    }
+
     let client = DisksClient::new().await?;
     let disks = client.list_disks().await?;
 
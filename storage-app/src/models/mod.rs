@@ -9,6 +9,7 @@
 //! - Helper methods for navigation and querying
 //! - Atomic update support for performance
 
+pub mod demo;
 pub mod helpers;
 pub mod load;
 pub mod ui_drive;
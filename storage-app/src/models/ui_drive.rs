@@ -6,7 +6,7 @@ use super::{UiVolume, build_volume_tree};
 use crate::client::{DisksClient, PartitionsClient, error::ClientError};
 use std::ops::Deref;
 use std::sync::Arc;
-use storage_types::{DiskInfo, PartitionInfo};
+use storage_types::{DiskInfo, PartitionInfo, VolumeInfo};
 
 /// Recursively collect all volumes from a slice of roots into a flat list (each without children).
 fn collect_volumes_flat_slice(
@@ -96,6 +96,29 @@ impl UiDrive {
         Ok(drive)
     }
 
+    /// Create a UiDrive from data at hand, like the demo dataset's
+    ///
+    /// `volumes` is a flat list across all disks, as list_volumes() returns.
+    pub async fn from_parts(
+        disk: DiskInfo,
+        volumes: Vec<VolumeInfo>,
+        partitions: Vec<PartitionInfo>,
+    ) -> Result<Self, ClientError> {
+        let filesystems_client = Arc::new(crate::client::FilesystemsClient::new().await?);
+        let tree = build_volume_tree(&disk.device, volumes, Arc::clone(&filesystems_client))?;
+        let volumes_flat = collect_volumes_flat_slice(&tree, Arc::clone(&filesystems_client));
+
+        Ok(Self {
+            disk,
+            volumes: tree,
+            partitions,
+            volumes_flat,
+            client: Arc::new(DisksClient::new().await?),
+            partitions_client: Arc::new(PartitionsClient::new().await?),
+            filesystems_client,
+        })
+    }
+
     /// Full refresh of all data (disk info, volumes, partitions)
     ///
     /// This is the baseline operation used when atomic updates aren't applicable.
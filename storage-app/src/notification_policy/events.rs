@@ -0,0 +1,482 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Storage events and the switches that silence them
+//!
+//! Kept free of UI types so the background agent can share it with the app.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use storage_types::{
+    CapacityForecast, DiskHealthSummary, HealthFactor, HealthLevel, LowSpaceAction,
+    LowSpaceWarning, RaidHealthEvent, TemperatureLevel, UsageCategory, bytes_to_pretty,
+};
+
+use crate::fl;
+
+/// Group of events that can be switched off together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationCategory {
+    Hotplug,
+    Health,
+    Raid,
+    Capacity,
+    Backups,
+    Operations,
+}
+
+impl NotificationCategory {
+    pub const ALL: [Self; 6] = [
+        Self::Hotplug,
+        Self::Health,
+        Self::Raid,
+        Self::Capacity,
+        Self::Backups,
+        Self::Operations,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Hotplug => fl!("notifications-hotplug"),
+            Self::Health => fl!("notifications-health"),
+            Self::Raid => fl!("notifications-raid"),
+            Self::Capacity => fl!("notifications-capacity"),
+            Self::Backups => fl!("notifications-backups"),
+            Self::Operations => fl!("notifications-operations"),
+        }
+    }
+}
+
+/// Per-category notification switches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub hotplug: bool,
+    pub health: bool,
+    pub raid: bool,
+    pub capacity: bool,
+    pub backups: bool,
+    pub operations: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            hotplug: false,
+            health: true,
+            raid: true,
+            capacity: true,
+            backups: true,
+            operations: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn enabled(&self, category: NotificationCategory) -> bool {
+        match category {
+            NotificationCategory::Hotplug => self.hotplug,
+            NotificationCategory::Health => self.health,
+            NotificationCategory::Raid => self.raid,
+            NotificationCategory::Capacity => self.capacity,
+            NotificationCategory::Backups => self.backups,
+            NotificationCategory::Operations => self.operations,
+        }
+    }
+
+    pub fn set(&mut self, category: NotificationCategory, enabled: bool) {
+        match category {
+            NotificationCategory::Hotplug => self.hotplug = enabled,
+            NotificationCategory::Health => self.health = enabled,
+            NotificationCategory::Raid => self.raid = enabled,
+            NotificationCategory::Capacity => self.capacity = enabled,
+            NotificationCategory::Backups => self.backups = enabled,
+            NotificationCategory::Operations => self.operations = enabled,
+        }
+    }
+}
+
+/// Something that happened to the storage of the machine
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageEvent {
+    DriveAdded {
+        device: String,
+    },
+    DriveRemoved {
+        device: String,
+    },
+    /// A drive without partition table or filesystem was added; clicking
+    /// the notification opens the setup wizard
+    DriveNeedsSetup {
+        device: String,
+        name: String,
+    },
+    /// A drive's health dropped to critical or its SMART self-assessment failed
+    HealthCritical {
+        device: String,
+        smart_failed: bool,
+    },
+    /// A drive crossed a temperature threshold; `temperature` is already
+    /// formatted in the preferred unit
+    Temperature {
+        device: String,
+        level: TemperatureLevel,
+        temperature: String,
+    },
+    Raid {
+        array: String,
+        event: RaidHealthEvent,
+    },
+    /// A filesystem is projected to run full within the configured number
+    /// of days
+    FillingUp {
+        mount_point: String,
+        days: u64,
+    },
+    /// A filesystem dropped below its low space rule
+    LowSpace(LowSpaceWarning),
+    BackupFinished {
+        image: String,
+        result: Result<(), String>,
+    },
+    /// A long operation ended; on success with a description of the outcome
+    OperationFinished {
+        operation: String,
+        result: Result<String, String>,
+    },
+}
+
+impl StorageEvent {
+    pub fn category(&self) -> NotificationCategory {
+        match self {
+            Self::DriveAdded { .. } | Self::DriveRemoved { .. } | Self::DriveNeedsSetup { .. } => {
+                NotificationCategory::Hotplug
+            }
+            Self::HealthCritical { .. } | Self::Temperature { .. } => NotificationCategory::Health,
+            Self::Raid { .. } => NotificationCategory::Raid,
+            Self::FillingUp { .. } | Self::LowSpace(_) => NotificationCategory::Capacity,
+            Self::BackupFinished { .. } => NotificationCategory::Backups,
+            Self::OperationFinished { .. } => NotificationCategory::Operations,
+        }
+    }
+
+    /// Whether the event is only worth a notification while the window is
+    /// not focused
+    fn background_only(&self) -> bool {
+        matches!(
+            self,
+            Self::BackupFinished { .. } | Self::OperationFinished { .. }
+        )
+    }
+
+    /// Drive whose setup wizard a click on the notification opens
+    pub fn setup_device(&self) -> Option<&str> {
+        match self {
+            Self::DriveNeedsSetup { device, .. } => Some(device),
+            _ => None,
+        }
+    }
+
+    /// Whether `settings` and the focus of the window allow a notification
+    pub fn wanted(&self, settings: &NotificationSettings, window_focused: bool) -> bool {
+        settings.enabled(self.category()) && !(self.background_only() && window_focused)
+    }
+
+    /// Notification summary and body
+    pub fn text(&self) -> (String, String) {
+        match self {
+            Self::DriveAdded { device } => (
+                fl!("notification-drive-added"),
+                fl!("notification-drive-added-body", device = device.clone()),
+            ),
+            Self::DriveRemoved { device } => (
+                fl!("notification-drive-removed"),
+                fl!("notification-drive-removed-body", device = device.clone()),
+            ),
+            Self::DriveNeedsSetup { device, name } => (
+                fl!("notification-drive-needs-setup"),
+                fl!(
+                    "notification-drive-needs-setup-body",
+                    name = name.clone(),
+                    device = device.clone()
+                ),
+            ),
+            Self::HealthCritical {
+                device,
+                smart_failed,
+            } => (
+                fl!("notification-health-critical"),
+                if *smart_failed {
+                    fl!("notification-smart-failed-body", device = device.clone())
+                } else {
+                    fl!("notification-health-critical-body", device = device.clone())
+                },
+            ),
+            Self::Temperature {
+                device,
+                level,
+                temperature,
+            } => (
+                fl!("temperature-alert-title"),
+                match level {
+                    TemperatureLevel::Critical => fl!(
+                        "temperature-alert-critical",
+                        device = device.clone(),
+                        temperature = temperature.clone()
+                    ),
+                    TemperatureLevel::Warning => fl!(
+                        "temperature-alert-warning",
+                        device = device.clone(),
+                        temperature = temperature.clone()
+                    ),
+                    TemperatureLevel::Normal => fl!(
+                        "temperature-alert-normal",
+                        device = device.clone(),
+                        temperature = temperature.clone()
+                    ),
+                },
+            ),
+            Self::Raid { array, event } => (
+                fl!("raid-alert-title"),
+                match event {
+                    RaidHealthEvent::Degraded => {
+                        fl!("raid-alert-degraded", array = array.clone())
+                    }
+                    RaidHealthEvent::MemberFailed => {
+                        fl!("raid-alert-member-failed", array = array.clone())
+                    }
+                    RaidHealthEvent::Recovered => {
+                        fl!("raid-alert-recovered", array = array.clone())
+                    }
+                },
+            ),
+            Self::FillingUp { mount_point, days } => (
+                fl!("notification-filling-up"),
+                fl!(
+                    "notification-filling-up-body",
+                    mount_point = mount_point.clone(),
+                    days = *days
+                ),
+            ),
+            Self::LowSpace(warning) => {
+                let body = fl!(
+                    "notification-low-space-body",
+                    mount_point = warning.mount_point.clone(),
+                    available = bytes_to_pretty(&warning.available, false)
+                );
+                let outcome = match warning.action {
+                    LowSpaceAction::Warn => None,
+                    LowSpaceAction::Cleanup => Some(fl!(
+                        "notification-low-space-pruned",
+                        count = warning.pruned_snapshots
+                    )),
+                    LowSpaceAction::UsageScan if warning.largest.is_empty() => None,
+                    LowSpaceAction::UsageScan => Some(fl!(
+                        "notification-low-space-largest",
+                        categories = warning
+                            .largest
+                            .iter()
+                            .map(|(category, bytes)| format!(
+                                "{} ({})",
+                                usage_category_label(*category),
+                                bytes_to_pretty(bytes, false)
+                            ))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )),
+                };
+                (
+                    fl!("notification-low-space"),
+                    match outcome {
+                        Some(outcome) => format!("{body} {outcome}"),
+                        None => body,
+                    },
+                )
+            }
+            Self::BackupFinished { image, result } => match result {
+                Ok(()) => (
+                    fl!("notification-backup-finished"),
+                    fl!("notification-backup-finished-body", image = image.clone()),
+                ),
+                Err(e) => (fl!("notification-backup-failed"), e.clone()),
+            },
+            Self::OperationFinished { operation, result } => match result {
+                Ok(outcome) => (
+                    fl!(
+                        "notification-operation-finished",
+                        operation = operation.clone()
+                    ),
+                    outcome.clone(),
+                ),
+                Err(e) => (
+                    fl!(
+                        "notification-operation-failed",
+                        operation = operation.clone()
+                    ),
+                    e.clone(),
+                ),
+            },
+        }
+    }
+}
+
+/// Drives whose health became critical between the `previous` summaries
+/// (by device) and the `current` ones
+///
+/// Drives seen for the first time are not reported, so that starting the
+/// app does not repeat known failures.
+pub fn health_events(
+    previous: &HashMap<String, DiskHealthSummary>,
+    current: &[DiskHealthSummary],
+) -> Vec<StorageEvent> {
+    current
+        .iter()
+        .filter_map(|summary| {
+            let before = previous.get(&summary.device)?;
+            let smart_failed =
+                |s: &DiskHealthSummary| s.factors.contains(&HealthFactor::SmartFailed);
+            let became_critical =
+                summary.level == HealthLevel::Critical && before.level != HealthLevel::Critical;
+            let failed_now = smart_failed(summary) && !smart_failed(before);
+            (became_critical || failed_now).then(|| StorageEvent::HealthCritical {
+                device: summary.device.clone(),
+                smart_failed: smart_failed(summary),
+            })
+        })
+        .collect()
+}
+
+/// Name of a usage category, as shown in the usage view
+pub fn usage_category_label(category: UsageCategory) -> String {
+    match category {
+        UsageCategory::Documents => fl!("usage-category-documents"),
+        UsageCategory::Images => fl!("usage-category-images"),
+        UsageCategory::Audio => fl!("usage-category-audio"),
+        UsageCategory::Video => fl!("usage-category-video"),
+        UsageCategory::Archives => fl!("usage-category-archives"),
+        UsageCategory::Code => fl!("usage-category-code"),
+        UsageCategory::Binaries => fl!("usage-category-binaries"),
+        UsageCategory::Packages => fl!("usage-category-packages"),
+        UsageCategory::System => fl!("usage-category-system"),
+        UsageCategory::Other => fl!("usage-category-other"),
+    }
+}
+
+/// Filesystems whose projected-full date came within `threshold_days`
+/// between the `previous` forecasts (by mount point) and the `current` ones
+///
+/// As with health, filesystems seen for the first time are not reported.
+pub fn capacity_events(
+    previous: &HashMap<String, CapacityForecast>,
+    current: &[CapacityForecast],
+    threshold_days: u64,
+) -> Vec<StorageEvent> {
+    current
+        .iter()
+        .filter_map(|forecast| {
+            let before = previous.get(&forecast.mount_point)?;
+            (forecast.full_within(threshold_days) && !before.full_within(threshold_days)).then(
+                || StorageEvent::FillingUp {
+                    mount_point: forecast.mount_point.clone(),
+                    days: forecast.days_until_full.unwrap_or_default(),
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(device: &str, level: HealthLevel, factors: Vec<HealthFactor>) -> DiskHealthSummary {
+        DiskHealthSummary {
+            device: device.to_string(),
+            score: 0,
+            level,
+            factors,
+            trends: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reports_drives_that_became_critical() {
+        let previous = HashMap::from([
+            (
+                "/dev/sda".to_string(),
+                summary("/dev/sda", HealthLevel::Good, vec![]),
+            ),
+            (
+                "/dev/sdb".to_string(),
+                summary("/dev/sdb", HealthLevel::Critical, vec![]),
+            ),
+        ]);
+        let current = [
+            summary(
+                "/dev/sda",
+                HealthLevel::Critical,
+                vec![HealthFactor::SmartFailed],
+            ),
+            summary("/dev/sdb", HealthLevel::Critical, vec![]),
+            summary("/dev/sdc", HealthLevel::Critical, vec![]),
+        ];
+
+        assert_eq!(
+            health_events(&previous, &current),
+            vec![StorageEvent::HealthCritical {
+                device: "/dev/sda".to_string(),
+                smart_failed: true,
+            }]
+        );
+    }
+
+    fn forecast(mount_point: &str, days_until_full: Option<u64>) -> CapacityForecast {
+        CapacityForecast {
+            device: "/dev/sda1".to_string(),
+            mount_point: mount_point.to_string(),
+            size: 0,
+            available: 0,
+            growth_per_day: 0,
+            days_until_full,
+        }
+    }
+
+    #[test]
+    fn reports_filesystems_that_came_within_the_threshold() {
+        let previous = HashMap::from([
+            ("/home".to_string(), forecast("/home", Some(40))),
+            ("/".to_string(), forecast("/", Some(5))),
+            ("/srv".to_string(), forecast("/srv", None)),
+        ]);
+        let current = [
+            forecast("/home", Some(12)),
+            forecast("/", Some(4)),
+            forecast("/srv", None),
+            forecast("/data", Some(1)),
+        ];
+
+        assert_eq!(
+            capacity_events(&previous, &current, 14),
+            vec![StorageEvent::FillingUp {
+                mount_point: "/home".to_string(),
+                days: 12,
+            }]
+        );
+    }
+
+    #[test]
+    fn categories_follow_settings() {
+        let mut settings = NotificationSettings::default();
+        assert!(!settings.enabled(NotificationCategory::Hotplug));
+        settings.set(NotificationCategory::Hotplug, true);
+        settings.set(NotificationCategory::Raid, false);
+        assert!(settings.enabled(NotificationCategory::Hotplug));
+        assert!(!settings.enabled(NotificationCategory::Raid));
+        let backup = StorageEvent::BackupFinished {
+            image: String::new(),
+            result: Ok(()),
+        };
+        assert!(backup.wanted(&settings, false));
+        assert!(!backup.wanted(&settings, true));
+    }
+}
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Which storage events become desktop notifications
+//!
+//! Service signals and finished operations are turned into a
+//! [`StorageEvent`], and [`notify`] decides from the user's per-category
+//! switches whether to show it. Finished backups and long operations are
+//! only announced while the window is not focused, since the dialog
+//! already shows the result otherwise.
+
+mod events;
+
+pub use events::*;
+
+use cosmic::app::Task;
+
+use crate::message::app::Message;
+use crate::utils::notifications;
+
+/// Show `event` as a desktop notification if the user wants to see it
+pub fn notify(
+    event: StorageEvent,
+    settings: &NotificationSettings,
+    window_focused: bool,
+) -> Task<Message> {
+    if !event.wanted(settings, window_focused) {
+        return Task::none();
+    }
+
+    let (summary, body) = event.text();
+    Task::perform(
+        async move {
+            if let Err(e) = notifications::notify(&summary, &body).await {
+                tracing::warn!(%e, "failed to show notification");
+            }
+        },
+        |_| Message::None.into(),
+    )
+}
+
+/// Show `event` like [`notify`] with an `action` button, and send
+/// `on_click` once the user clicks the notification or the button
+pub fn notify_clickable(
+    event: StorageEvent,
+    settings: &NotificationSettings,
+    window_focused: bool,
+    action: String,
+    on_click: Message,
+) -> Task<Message> {
+    if !event.wanted(settings, window_focused) {
+        return Task::none();
+    }
+
+    let (summary, body) = event.text();
+    Task::perform(
+        async move {
+            notifications::notify_clickable(&summary, &body, &action)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(%e, "failed to show notification");
+                    false
+                })
+        },
+        move |clicked| {
+            if clicked {
+                on_click.clone().into()
+            } else {
+                Message::None.into()
+            }
+        },
+    )
+}
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Collects the report contents from the app's models
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use storage_types::{
+    ConfigScope, DiskHealthSummary, HealthLevel, RaidDetail, TemperatureUnit, VolumeInfo,
+    VolumeKind, bytes_to_pretty,
+};
+
+use super::{Report, Section, Table};
+use crate::fl;
+use crate::models::{UiDrive, UiVolume};
+use crate::state::network::NetworkMountState;
+use crate::views::sidebar::health_factor_text;
+
+/// Build the inventory report of `drives` with their `health` summaries (by
+/// device), the RAID `arrays` and the configured network `mounts`
+pub fn build<'a>(
+    drives: &[UiDrive],
+    health: &HashMap<String, DiskHealthSummary>,
+    arrays: &[RaidDetail],
+    mounts: impl IntoIterator<Item = &'a NetworkMountState>,
+    unit: TemperatureUnit,
+) -> Report {
+    let host = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .unwrap_or_default();
+
+    Report {
+        title: fl!("report-title"),
+        subtitle: fl!("report-generated", host = host, date = utc_now()),
+        sections: vec![
+            drives_section(drives),
+            volumes_section(drives),
+            health_section(drives, health, unit),
+            raid_section(arrays),
+            lvm_section(drives),
+            network_section(mounts),
+        ],
+    }
+}
+
+fn section(heading: String, tables: Vec<Table>) -> Section {
+    Section {
+        heading,
+        tables,
+        empty_note: fl!("report-none"),
+    }
+}
+
+/// Single uncaptioned table, or none without rows
+fn single_table(columns: Vec<String>, rows: Vec<Vec<String>>) -> Vec<Table> {
+    if rows.is_empty() {
+        return Vec::new();
+    }
+    vec![Table {
+        caption: None,
+        columns,
+        rows,
+    }]
+}
+
+fn drives_section(drives: &[UiDrive]) -> Section {
+    let rows = drives
+        .iter()
+        .map(|drive| {
+            let disk = &drive.disk;
+            vec![
+                disk.device.clone(),
+                disk.display_name(),
+                disk.serial.clone(),
+                bytes_to_pretty(&disk.size, false),
+                disk.connection_bus.clone(),
+                disk.partition_table_type
+                    .as_ref()
+                    .map(|t| t.to_uppercase())
+                    .unwrap_or_else(|| fl!("unknown")),
+            ]
+        })
+        .collect();
+
+    section(
+        fl!("report-drives"),
+        single_table(
+            vec![
+                fl!("device"),
+                fl!("model"),
+                fl!("serial"),
+                fl!("size"),
+                fl!("report-bus"),
+                fl!("partitioning"),
+            ],
+            rows,
+        ),
+    )
+}
+
+fn kind_label(volume: &VolumeInfo) -> String {
+    match volume.kind {
+        VolumeKind::Filesystem => fl!("filesystem"),
+        VolumeKind::LvmLogicalVolume => fl!("lvm-logical-volume"),
+        VolumeKind::LvmPhysicalVolume => fl!("lvm-physical-volume"),
+        VolumeKind::CryptoContainer => fl!("luks-container"),
+        VolumeKind::Partition => fl!("partition-type"),
+        VolumeKind::Block => fl!("block-device"),
+    }
+}
+
+/// Rows of `volume` and its children, indented by depth
+fn volume_rows(volume: &UiVolume, depth: usize, rows: &mut Vec<Vec<String>>) {
+    let info = &volume.volume;
+    rows.push(vec![
+        format!(
+            "{}{}",
+            "  ".repeat(depth),
+            info.device_path.clone().unwrap_or_default()
+        ),
+        kind_label(info),
+        info.id_type.clone(),
+        info.label.clone(),
+        bytes_to_pretty(&info.size, false),
+        info.usage
+            .as_ref()
+            .map(|usage| bytes_to_pretty(&usage.used_bytes(), false))
+            .unwrap_or_default(),
+        info.mount_points.join(", "),
+    ]);
+    for child in &volume.children {
+        volume_rows(child, depth + 1, rows);
+    }
+}
+
+fn volumes_section(drives: &[UiDrive]) -> Section {
+    let tables = drives
+        .iter()
+        .filter(|drive| !drive.volumes.is_empty())
+        .map(|drive| {
+            let mut rows = Vec::new();
+            for volume in &drive.volumes {
+                volume_rows(volume, 0, &mut rows);
+            }
+            Table {
+                caption: Some(format!(
+                    "{} ({})",
+                    drive.disk.display_name(),
+                    drive.disk.device
+                )),
+                columns: vec![
+                    fl!("device"),
+                    fl!("report-kind"),
+                    fl!("report-type"),
+                    fl!("label"),
+                    fl!("size"),
+                    fl!("report-used"),
+                    fl!("mounted-at"),
+                ],
+                rows,
+            }
+        })
+        .collect();
+
+    section(fl!("report-volumes"), tables)
+}
+
+fn health_section(
+    drives: &[UiDrive],
+    health: &HashMap<String, DiskHealthSummary>,
+    unit: TemperatureUnit,
+) -> Section {
+    let rows = drives
+        .iter()
+        .filter_map(|drive| health.get(&drive.disk.device))
+        .map(|summary| {
+            let level = match summary.level {
+                HealthLevel::Good => fl!("report-health-good"),
+                HealthLevel::Warning => fl!("report-health-warning"),
+                HealthLevel::Critical => fl!("report-health-critical"),
+                HealthLevel::Unknown => fl!("unknown"),
+            };
+            let score = if summary.level == HealthLevel::Unknown {
+                String::new()
+            } else {
+                format!("{}/100", summary.score)
+            };
+            vec![
+                summary.device.clone(),
+                score,
+                level,
+                summary
+                    .factors
+                    .iter()
+                    .map(|factor| health_factor_text(factor, unit))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ]
+        })
+        .collect();
+
+    section(
+        fl!("report-health"),
+        single_table(
+            vec![
+                fl!("device"),
+                fl!("report-score"),
+                fl!("report-status"),
+                fl!("report-findings"),
+            ],
+            rows,
+        ),
+    )
+}
+
+fn raid_section(arrays: &[RaidDetail]) -> Section {
+    let rows = arrays
+        .iter()
+        .map(|array| {
+            let sync = match array.sync_completed_percent {
+                Some(percent) if array.is_syncing() => {
+                    format!("{} {percent:.1}%", array.sync_action)
+                }
+                _ => array.sync_action.clone(),
+            };
+            vec![
+                array.device.clone(),
+                array.level.clone(),
+                array.array_state.clone(),
+                format!(
+                    "{}/{}",
+                    array.raid_disks.saturating_sub(array.degraded),
+                    array.raid_disks
+                ),
+                sync,
+                array
+                    .members
+                    .iter()
+                    .map(|member| member.device.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]
+        })
+        .collect();
+
+    section(
+        fl!("report-raid"),
+        single_table(
+            vec![
+                fl!("device"),
+                fl!("report-level"),
+                fl!("report-status"),
+                fl!("report-active-members"),
+                fl!("report-sync"),
+                fl!("report-members"),
+            ],
+            rows,
+        ),
+    )
+}
+
+/// LVM physical and logical volumes anywhere in the volume trees
+fn lvm_rows(volume: &UiVolume, rows: &mut Vec<Vec<String>>) {
+    let info = &volume.volume;
+    if matches!(
+        info.kind,
+        VolumeKind::LvmPhysicalVolume | VolumeKind::LvmLogicalVolume
+    ) {
+        rows.push(vec![
+            info.device_path.clone().unwrap_or_default(),
+            kind_label(info),
+            bytes_to_pretty(&info.size, false),
+            info.parent_path.clone().unwrap_or_default(),
+        ]);
+    }
+    for child in &volume.children {
+        lvm_rows(child, rows);
+    }
+}
+
+fn lvm_section(drives: &[UiDrive]) -> Section {
+    let mut rows = Vec::new();
+    for volume in drives.iter().flat_map(|drive| &drive.volumes) {
+        lvm_rows(volume, &mut rows);
+    }
+
+    section(
+        fl!("report-lvm"),
+        single_table(
+            vec![
+                fl!("device"),
+                fl!("report-kind"),
+                fl!("size"),
+                fl!("report-parent"),
+            ],
+            rows,
+        ),
+    )
+}
+
+fn network_section<'a>(mounts: impl IntoIterator<Item = &'a NetworkMountState>) -> Section {
+    let mut mounts: Vec<_> = mounts.into_iter().collect();
+    mounts.sort_by(|a, b| a.config.name.cmp(&b.config.name));
+
+    let rows = mounts
+        .into_iter()
+        .map(|mount| {
+            vec![
+                mount.config.name.clone(),
+                mount.config.remote_type.clone(),
+                match mount.config.scope {
+                    ConfigScope::User => fl!("report-scope-user"),
+                    ConfigScope::System => fl!("report-scope-system"),
+                },
+                mount.config.mount_point().display().to_string(),
+                if mount.status.is_mounted() {
+                    fl!("report-mounted")
+                } else {
+                    fl!("report-not-mounted")
+                },
+            ]
+        })
+        .collect();
+
+    section(
+        fl!("report-network"),
+        single_table(
+            vec![
+                fl!("name"),
+                fl!("report-type"),
+                fl!("report-scope"),
+                fl!("mount-point"),
+                fl!("report-status"),
+            ],
+            rows,
+        ),
+    )
+}
+
+/// Current time as "YYYY-MM-DD HH:MM UTC"
+fn utc_now() -> String {
+    utc_time(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    )
+}
+
+/// A Unix time as "2026-10-16 09:01 UTC"
+pub(crate) fn utc_time(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let minutes = seconds % 86_400 / 60;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// Calendar date of a day count since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_742), (2026, 10, 16));
+    }
+}
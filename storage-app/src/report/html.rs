@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::{Report, Table};
+
+const TEMPLATE: &str = include_str!("templates/report.html");
+
+pub(super) fn render(report: &Report) -> String {
+    let mut sections = String::new();
+    for section in &report.sections {
+        sections.push_str(&format!("<h2>{}</h2>\n", escape(&section.heading)));
+        if section.tables.is_empty() {
+            sections.push_str(&format!(
+                "<p class=\"empty\">{}</p>\n",
+                escape(&section.empty_note)
+            ));
+        }
+        for table in &section.tables {
+            render_table(&mut sections, table);
+        }
+    }
+
+    fill(
+        TEMPLATE,
+        &[
+            ("title", &escape(&report.title)),
+            ("subtitle", &escape(&report.subtitle)),
+            ("sections", &sections),
+        ],
+    )
+}
+
+/// Replace the `{{name}}` placeholders of `template` in a single pass, so
+/// that inserted text is never taken for a placeholder
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        match values.iter().find(|(key, _)| *key == name) {
+            Some((_, value)) => out.push_str(value),
+            None => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn render_table(out: &mut String, table: &Table) {
+    if let Some(caption) = &table.caption {
+        out.push_str(&format!("<h3>{}</h3>\n", escape(caption)));
+    }
+
+    out.push_str("<table>\n<tr>");
+    for column in &table.columns {
+        out.push_str(&format!("<th>{}</th>", escape(column)));
+    }
+    out.push_str("</tr>\n");
+    for cells in &table.rows {
+        out.push_str("<tr>");
+        for cell in cells {
+            out.push_str(&format!("<td>{}</td>", escape(cell)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    #[test]
+    fn escapes_text_into_the_template() {
+        let report = Report {
+            title: "Storage <host>".to_string(),
+            subtitle: "{{sections}}".to_string(),
+            sections: vec![Section {
+                heading: "Drives".to_string(),
+                tables: vec![Table {
+                    caption: Some("/dev/sda".to_string()),
+                    columns: vec!["Label".to_string()],
+                    rows: vec![vec!["A & B".to_string()]],
+                }],
+                empty_note: String::new(),
+            }],
+        };
+
+        let html = render(&report);
+        assert!(html.contains("<title>Storage &lt;host&gt;</title>"));
+        assert!(html.contains("<p class=\"subtitle\">{{sections}}</p>"));
+        assert!(html.contains("<h3>/dev/sda</h3>"));
+        assert!(html.contains("<td>A &amp; B</td>"));
+    }
+}
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::{Report, Table};
+
+pub(super) fn render(report: &Report) -> String {
+    let mut out = format!("# {}\n\n_{}_\n", report.title, report.subtitle);
+
+    for section in &report.sections {
+        out.push_str(&format!("\n## {}\n", section.heading));
+        if section.tables.is_empty() {
+            out.push_str(&format!("\n{}\n", section.empty_note));
+        }
+        for table in &section.tables {
+            render_table(&mut out, table);
+        }
+    }
+
+    out
+}
+
+fn render_table(out: &mut String, table: &Table) {
+    if let Some(caption) = &table.caption {
+        out.push_str(&format!("\n### {caption}\n"));
+    }
+
+    let row = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|cell| escape(cell)).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    out.push('\n');
+    out.push_str(&row(&table.columns));
+    out.push_str(&format!("|{}\n", "---|".repeat(table.columns.len())));
+    for cells in &table.rows {
+        out.push_str(&row(cells));
+    }
+}
+
+/// Keep a cell on one line and its pipes out of the table syntax
+fn escape(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    #[test]
+    fn renders_tables_and_empty_sections() {
+        let report = Report {
+            title: "Storage".to_string(),
+            subtitle: "host".to_string(),
+            sections: vec![
+                Section {
+                    heading: "Drives".to_string(),
+                    tables: vec![Table {
+                        caption: None,
+                        columns: vec!["Device".to_string(), "Model".to_string()],
+                        rows: vec![vec!["/dev/sda".to_string(), "A|B".to_string()]],
+                    }],
+                    empty_note: "None".to_string(),
+                },
+                Section {
+                    heading: "RAID".to_string(),
+                    tables: Vec::new(),
+                    empty_note: "None".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            render(&report),
+            "# Storage\n\n_host_\n\n## Drives\n\n| Device | Model |\n|---|---|\n\
+             | /dev/sda | A\\|B |\n\n## RAID\n\nNone\n"
+        );
+    }
+}
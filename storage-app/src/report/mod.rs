@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Storage inventory report
+//!
+//! Compiles drives, partitions, filesystems, SMART health, RAID and LVM
+//! topology and network mounts into one document for documenting a machine.
+//! [`build`] turns the app's models into a format-neutral [`Report`], which
+//! renders as Markdown, HTML (from `templates/report.html`) or PDF.
+
+mod build;
+mod html;
+mod markdown;
+mod pdf;
+
+use std::path::Path;
+
+pub use build::build;
+pub(crate) use build::utc_time;
+
+/// File format of an exported report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl ReportFormat {
+    /// Format matching the extension of `path`, if any
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+/// A complete report
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub title: String,
+    /// Host name and generation time
+    pub subtitle: String,
+    pub sections: Vec<Section>,
+}
+
+/// A titled part of the report
+#[derive(Debug, Clone, Default)]
+pub struct Section {
+    pub heading: String,
+    pub tables: Vec<Table>,
+    /// Shown instead of the tables when there are none
+    pub empty_note: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub caption: Option<String>,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Report {
+    pub fn render(&self, format: ReportFormat) -> Vec<u8> {
+        match format {
+            ReportFormat::Markdown => markdown::render(self).into_bytes(),
+            ReportFormat::Html => html::render(self).into_bytes(),
+            ReportFormat::Pdf => pdf::render(self),
+        }
+    }
+}
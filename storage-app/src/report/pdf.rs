@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Minimal PDF writer for reports
+//!
+//! Lays the report out as lines of text on A4 pages using the standard
+//! Helvetica-Bold and Courier fonts, which every PDF reader provides, so no
+//! fonts need to be embedded. Tables are set in Courier with padded columns.
+
+use super::{Report, Table};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 42.0;
+const BODY_SIZE: f32 = 8.0;
+
+/// Courier glyphs are 0.6 em wide
+const BODY_COLUMNS: usize = ((PAGE_WIDTH - 2.0 * MARGIN) / (BODY_SIZE * 0.6)) as usize;
+
+/// Narrowest a table column is shrunk to before cells are cut off
+const MIN_COLUMN_WIDTH: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Title,
+    Heading,
+    Subheading,
+    Body,
+}
+
+impl Style {
+    fn font(self) -> &'static str {
+        match self {
+            Self::Body => "F2",
+            _ => "F1",
+        }
+    }
+
+    fn size(self) -> f32 {
+        match self {
+            Self::Title => 18.0,
+            Self::Heading => 13.0,
+            Self::Subheading => 10.0,
+            Self::Body => BODY_SIZE,
+        }
+    }
+
+    /// Space taken by a line, including the gap above headings
+    fn advance(self) -> f32 {
+        match self {
+            Self::Body => BODY_SIZE * 1.4,
+            style => style.size() * 1.8,
+        }
+    }
+}
+
+pub(super) fn render(report: &Report) -> Vec<u8> {
+    let mut lines = vec![
+        (Style::Title, report.title.clone()),
+        (Style::Body, report.subtitle.clone()),
+    ];
+    for section in &report.sections {
+        lines.push((Style::Heading, section.heading.clone()));
+        if section.tables.is_empty() {
+            lines.push((Style::Body, section.empty_note.clone()));
+        }
+        for table in &section.tables {
+            if let Some(caption) = &table.caption {
+                lines.push((Style::Subheading, caption.clone()));
+            }
+            lines.extend(table_lines(table).into_iter().map(|l| (Style::Body, l)));
+        }
+    }
+
+    write_document(&paginate(&lines))
+}
+
+/// Table as padded text lines, columns shrunk to fit the page width
+fn table_lines(table: &Table) -> Vec<String> {
+    let count = table.columns.len();
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let mut widths: Vec<usize> = (0..count)
+        .map(|i| {
+            table
+                .rows
+                .iter()
+                .filter_map(|row| row.get(i))
+                .chain(std::iter::once(&table.columns[i]))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let available = BODY_COLUMNS.saturating_sub(2 * (count - 1));
+    while widths.iter().sum::<usize>() > available {
+        let Some(widest) = (0..count)
+            .filter(|i| widths[*i] > MIN_COLUMN_WIDTH)
+            .max_by_key(|i| widths[*i])
+        else {
+            break;
+        };
+        widths[widest] -= 1;
+    }
+
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| fit(cells.get(i).map(String::as_str).unwrap_or(""), *width))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![line(&table.columns)];
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("  "),
+    );
+    lines.extend(table.rows.iter().map(|row| line(row)));
+    lines
+}
+
+/// Pad or cut `cell` to exactly `width` characters
+fn fit(cell: &str, width: usize) -> String {
+    let length = cell.chars().count();
+    if length <= width {
+        format!("{cell}{}", " ".repeat(width - length))
+    } else {
+        let cut: String = cell.chars().take(width.saturating_sub(1)).collect();
+        format!("{cut}~")
+    }
+}
+
+/// Lines with their baseline, split into pages
+fn paginate(lines: &[(Style, String)]) -> Vec<Vec<(Style, f32, &str)>> {
+    let mut pages = vec![Vec::new()];
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for (style, text) in lines {
+        y -= style.advance();
+        if y < MARGIN {
+            pages.push(Vec::new());
+            y = PAGE_HEIGHT - MARGIN - style.size();
+        }
+        if let Some(page) = pages.last_mut() {
+            page.push((*style, y, text.as_str()));
+        }
+    }
+    pages
+}
+
+/// Escape `text` as a PDF string in the WinAnsi encoding of the standard
+/// fonts; characters outside Latin-1 become "?"
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => out.extend([b'\\', c as u8]),
+            // Bidi isolation marks Fluent puts around placeables
+            '\u{2066}'..='\u{2069}' => {}
+            c if (c as u32) < 0x20 => out.push(b' '),
+            c if (c as u32) <= 0xFF => out.push(c as u32 as u8),
+            _ => out.push(b'?'),
+        }
+    }
+    out.push(b')');
+    out
+}
+
+fn write_document(pages: &[Vec<(Style, f32, &str)>]) -> Vec<u8> {
+    // 1: catalog, 2: page tree, 3-4: fonts, then a page and its content
+    // stream per page
+    let page_id = |index: usize| 5 + 2 * index;
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|i| format!("{} 0 R", page_id(i)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+
+    for (index, lines) in pages.iter().enumerate() {
+        let mut content = Vec::new();
+        for (style, y, text) in lines {
+            content.extend(
+                format!(
+                    "BT /{} {} Tf {} {:.1} Td ",
+                    style.font(),
+                    style.size(),
+                    MARGIN,
+                    y
+                )
+                .into_bytes(),
+            );
+            content.extend(pdf_string(text));
+            content.extend(b" Tj ET\n");
+        }
+
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                page_id(index) + 1
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend(content);
+        stream.extend(b"endstream");
+        objects.push(stream);
+    }
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+        out.extend(object);
+        out.extend(b"\nendobj\n");
+    }
+
+    let xref = out.len();
+    out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        out.extend(format!("{offset:010} 00000 n \n").into_bytes());
+    }
+    out.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .into_bytes(),
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    #[test]
+    fn writes_paginated_document() {
+        let report = Report {
+            title: "Storage (host)".to_string(),
+            subtitle: "2026-10-16".to_string(),
+            sections: vec![Section {
+                heading: "Drives".to_string(),
+                tables: vec![Table {
+                    caption: None,
+                    columns: vec!["Device".to_string(), "Size".to_string()],
+                    rows: (0..100)
+                        .map(|i| vec![format!("/dev/sd{i}"), "1 TB".to_string()])
+                        .collect(),
+                }],
+                empty_note: String::new(),
+            }],
+        };
+
+        let pdf = render(&report);
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("(Storage \\(host\\))"));
+        assert!(text.contains("/Count 2"));
+        assert_eq!(pdf_string("\u{2068}ok\u{2069} €"), b"(ok ?)");
+    }
+
+    #[test]
+    fn shrinks_wide_tables_to_the_page() {
+        let table = Table {
+            caption: None,
+            columns: vec!["A".to_string(), "B".to_string()],
+            rows: vec![vec!["x".repeat(200), "short".to_string()]],
+        };
+
+        let lines = table_lines(&table);
+        assert!(
+            lines
+                .iter()
+                .all(|line| line.chars().count() <= BODY_COLUMNS)
+        );
+        assert!(lines[2].contains("~  short"));
+        assert_eq!(fit("ab", 4), "ab  ");
+    }
+}
@@ -3,19 +3,37 @@
 use crate::config::Config;
 use crate::fl;
 use crate::message::app::Message;
-use crate::state::dialogs::ShowDialog;
+use crate::state::bind_mounts::BindMountsState;
+use crate::state::capacity::CapacityState;
+use crate::state::dialogs::{ConfirmationGuard, ShowDialog};
+use crate::state::health_check::HealthCheckState;
+use crate::state::logs::LogsState;
+use crate::state::mtp::MtpState;
 use crate::state::network::NetworkState;
+use crate::state::optical::OpticalState;
+use crate::state::read_only::ReadOnlyState;
+use crate::state::selinux::SelinuxState;
 use crate::state::sidebar::SidebarState;
+use crate::state::statistics::StatisticsState;
+use crate::state::user_mounts::UserMountsState;
+use crate::state::vaults::VaultsState;
 use cosmic::ApplicationExt;
 use cosmic::app::{Core, Task};
 use cosmic::widget::nav_bar;
-use storage_types::FilesystemToolInfo;
+use storage_types::{
+    AllowedActions, DeferredJob, FilesystemToolInfo, PhysicalDevice, PowerSettings,
+    PrioritySettings, SafetySnapshotPolicy,
+};
 
 /// The context page to display in the context drawer.
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum ContextPage {
     #[default]
     Settings,
+    Logs,
+    Statistics,
+    HealthCheck,
+    Vaults,
 }
 
 /// The application model stores app-specific state used to describe its interface and
@@ -37,18 +55,78 @@ pub struct AppModel {
     pub(crate) image_op_operation_id: Option<String>,
 
     pub dialog: Option<ShowDialog>,
+    /// What the dialog's destructive OK button still waits for, when the
+    /// confirmation level asks for more than pressing it
+    pub(crate) confirmation: Option<ConfirmationGuard>,
 
     /// Cached filesystem tool availability from service
     pub(crate) filesystem_tools: Vec<FilesystemToolInfo>,
 
+    /// Automatic btrfs safety snapshot policy from service, None when unavailable
+    pub(crate) safety_snapshot_policy: Option<SafetySnapshotPolicy>,
+    /// Priorities of long-running operations, once read from the service
+    pub(crate) operation_priorities: Option<PrioritySettings>,
+    /// When scheduled jobs wait for mains power, once read from the service
+    pub(crate) power_policies: Option<PowerSettings>,
+    /// Scheduled jobs waiting for mains power
+    pub(crate) deferred_jobs: Vec<DeferredJob>,
+    /// Formatting, resizing, checking and image operations running in the
+    /// service
+    pub(crate) operations: Vec<storage_contracts::OperationProgress>,
+
     /// Network mounts state (RClone, Samba, FTP)
     pub(crate) network: NetworkState,
+
+    /// FUSE and gvfs mounts of the user
+    pub(crate) user_mounts: UserMountsState,
+
+    /// Bind mounts, mounted and in fstab
+    pub(crate) bind_mounts: BindMountsState,
+
+    /// Connected phones and cameras
+    pub(crate) mtp: MtpState,
+
+    /// Disks behind hardware RAID controllers
+    pub(crate) physical_devices: Vec<PhysicalDevice>,
+
+    /// Hypervisor the system runs under, e.g. "kvm"; None on bare metal
+    pub(crate) hypervisor: Option<String>,
+
+    /// What the user's restriction profiles let them do
+    pub(crate) allowed_actions: AllowedActions,
+
+    /// Discs in optical drives
+    pub(crate) optical: OpticalState,
+    /// Filesystems the kernel made read-only after errors
+    pub(crate) read_only: ReadOnlyState,
+    /// SELinux labels of mounted filesystems
+    pub(crate) selinux: SelinuxState,
+    /// When mounted filesystems run full
+    pub(crate) capacity: CapacityState,
+    /// Log viewer
+    pub(crate) logs: LogsState,
+    /// Local usage statistics
+    pub(crate) statistics: StatisticsState,
+    /// Last check of all drives
+    pub(crate) health_check: HealthCheckState,
+    /// Legacy eCryptfs and gocryptfs vaults
+    pub(crate) vaults: VaultsState,
+
+    /// Whether the main window has keyboard focus
+    pub(crate) window_focused: bool,
 }
 
 impl AppModel {
     /// Updates the header and window titles.
     pub fn update_title(&mut self) -> Task<Message> {
-        let mut window_title = fl!("app-title");
+        // Say which host's disks are managed over a remote bus
+        let mut window_title = match crate::client::connection_target()
+            .ok()
+            .and_then(|target| target.remote_address())
+        {
+            Some(address) => fl!("app-title-remote", address = address.to_string()),
+            None => fl!("app-title"),
+        };
 
         if let Some(page) = self.nav.text(self.nav.active()) {
             window_title.push_str(" — ");
@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State for bind mounts
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use storage_types::BindMount;
+
+/// Bind mounts listed in the "Bind Mounts" section of the sidebar
+#[derive(Debug, Default)]
+pub struct BindMountsState {
+    /// Bind mounts found at the last look at the mount table and fstab
+    pub mounts: Vec<BindMount>,
+    /// Targets being unmounted or removed
+    pub busy: HashSet<PathBuf>,
+}
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
-use storage_types::{BtrfsSubvolume, DeletedSubvolume};
+use storage_types::{
+    BtrfsDeviceStats, BtrfsSubvolume, CompressionEstimate, CompressionInfo, DeletedSubvolume,
+    NocowStatus, RestoreResult,
+};
 
 use crate::models::UiVolume;
 use crate::state::volumes::find_volume_for_partition;
@@ -32,6 +35,34 @@ pub struct BtrfsState {
     pub selected_subvolume: Option<BtrfsSubvolume>,
     /// Whether to show the properties dialog
     pub show_properties_dialog: bool,
+    /// NOCOW attribute of the selected subvolume's directory
+    pub nocow: Option<Result<NocowStatus, String>>,
+    /// A NOCOW change is in progress
+    pub nocow_busy: bool,
+    /// Path inside the selected snapshot to restore into its origin subvolume
+    pub restore_path: String,
+    /// Index into `RestoreConflictPolicy::ALL`
+    pub restore_conflict: usize,
+    /// A restore is in progress
+    pub restore_busy: bool,
+    /// Outcome of the last restore
+    pub restore_result: Option<Result<RestoreResult, String>>,
+    /// Compression settings for `compression_target`
+    pub compression: Option<Result<CompressionInfo, String>>,
+    /// Directory or subvolume the compression controls act on (empty = mount point)
+    pub compression_target: String,
+    /// Index into `BTRFS_COMPRESSION_ALGORITHMS`
+    pub compression_algorithm: usize,
+    /// Use `compress-force` when changing the mount option
+    pub compression_force: bool,
+    /// Savings estimate for recompressing existing data
+    pub compression_estimate: Option<Result<CompressionEstimate, String>>,
+    /// A compression operation or estimate is running
+    pub compression_busy: bool,
+    /// Error counters of each device of the filesystem
+    pub device_stats: Option<Result<Vec<BtrfsDeviceStats>, String>>,
+    /// The device error counters are being reset
+    pub device_stats_busy: bool,
 }
 
 impl BtrfsState {
@@ -50,6 +81,29 @@ impl BtrfsState {
             show_deleted: false,
             selected_subvolume: None,
             show_properties_dialog: false,
+            nocow: None,
+            nocow_busy: false,
+            restore_path: String::new(),
+            restore_conflict: 0,
+            restore_busy: false,
+            restore_result: None,
+            compression: None,
+            compression_target: String::new(),
+            compression_algorithm: 1,
+            compression_force: false,
+            compression_estimate: None,
+            compression_busy: false,
+            device_stats: None,
+            device_stats_busy: false,
+        }
+    }
+
+    /// Live subvolume a snapshot was taken from, if it still exists
+    pub fn snapshot_origin(&self, snapshot: &BtrfsSubvolume) -> Option<&BtrfsSubvolume> {
+        let parent_uuid = snapshot.parent_uuid.as_deref()?;
+        match &self.subvolumes {
+            Some(Ok(subvolumes)) => subvolumes.iter().find(|subvol| subvol.uuid == parent_uuid),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,31 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State for capacity forecasts of mounted filesystems
+
+use std::collections::HashMap;
+
+use storage_types::{CapacityForecast, VolumeInfo};
+
+/// When mounted filesystems run full, by mount point, shown on the volume
+/// usage card
+#[derive(Debug, Default)]
+pub struct CapacityState {
+    pub forecasts: HashMap<String, CapacityForecast>,
+}
+
+impl CapacityState {
+    /// Forecast of the filesystem mounted from `volume` or a volume inside it
+    /// (e.g., the cleartext of an unlocked LUKS container)
+    pub fn for_volume(&self, volume: &VolumeInfo) -> Option<&CapacityForecast> {
+        volume
+            .mount_points
+            .iter()
+            .find_map(|mount_point| self.forecasts.get(mount_point))
+            .or_else(|| {
+                volume
+                    .children
+                    .iter()
+                    .find_map(|child| self.for_volume(child))
+            })
+    }
+}
@@ -1,7 +1,15 @@
+use crate::diagnostics::FailureContext;
 use crate::models::{UiDrive, UiVolume};
+use std::collections::HashMap;
 use storage_types::{
-    CreatePartitionInfo, FilesystemToolInfo, PartitionTypeInfo, ProcessInfo, SmartAttribute,
-    SmartStatus, VolumeInfo,
+    AutomountUnit, ByteRange, ConfirmationLevel, ConfirmationRequirements, CreatePartitionInfo,
+    DefragResult, DiskInfo, EspSyncResult, EtaRange, FilesystemToolInfo, FragmentationReport,
+    FscryptStatus, GptEntry, GptEntryEdit, GptTable, KernelDeviceError, LiveIsoInfo, LiveUsbPlan,
+    LostPartition, LowSpaceRule, MigrationPlan, MountLintContext, OperationKind, OperationPriority,
+    PartitionInfo, PartitionTypeInfo, ProcessInfo, QueueSettings, QueueTuning, SectorRange,
+    SelfTestRecord, SelfTestSchedule, SmartAttribute, SmartBackendStatus, SmartStatus,
+    TemperatureThresholds, VolumeInfo, WriteCacheStatus, confirmation_name_matches,
+    confirmation_token, confirmation_token_matches,
 };
 
 #[derive(Debug, Clone)]
@@ -11,6 +19,7 @@ pub enum ShowDialog {
     FormatPartition(FormatPartitionDialog),
     EditPartition(EditPartitionDialog),
     ResizePartition(ResizePartitionDialog),
+    MovePartition(MovePartitionDialog),
     EditFilesystemLabel(EditFilesystemLabelDialog),
     EditMountOptions(EditMountOptionsDialog),
     ConfirmAction(ConfirmActionDialog),
@@ -19,21 +28,147 @@ pub enum ShowDialog {
     EditEncryptionOptions(EditEncryptionOptionsDialog),
     UnlockEncrypted(UnlockEncryptedDialog),
     FormatDisk(FormatDiskDialog),
+    SetUpDrive(SetUpDriveDialog),
     SmartData(SmartDataDialog),
+    LostPartitions(LostPartitionsDialog),
+    GptEntries(GptEntriesDialog),
+    Defragment(DefragmentDialog),
+    EncryptedFolders(EncryptedFoldersDialog),
+    EspSync(EspSyncDialog),
+    LowSpace(LowSpaceDialog),
+    Automount(AutomountDialog),
+    Performance(PerformanceDialog),
+    SectorViewer(SectorViewerDialog),
+    Inspect(InspectDialog),
     NewDiskImage(Box<NewDiskImageDialog>),
     AttachDiskImage(Box<AttachDiskImageDialog>),
     ImageOperation(Box<ImageOperationDialog>),
     UnmountBusy(UnmountBusyDialog),
     BtrfsCreateSubvolume(BtrfsCreateSubvolumeDialog),
     BtrfsCreateSnapshot(BtrfsCreateSnapshotDialog),
+    NewBindMount(NewBindMountDialog),
     Info {
         title: String,
         body: String,
     },
+    /// An operation failed; offers to save a diagnostic report, and a way
+    /// out when there is one
+    Error {
+        title: String,
+        body: String,
+        failure: FailureContext,
+        recovery: Option<ErrorRecovery>,
+    },
+    Diagnostics(DiagnosticsDialog),
     ConfirmDeleteRemote {
         name: String,
         scope: storage_types::rclone::ConfigScope,
     },
+    RcloneConfigPassword(RcloneConfigPasswordDialog),
+}
+
+impl ShowDialog {
+    /// What the dialog's OK button destroys, and whether that is a whole
+    /// disk, when it destroys anything
+    ///
+    /// Wizards only name their target on their last step, where the
+    /// operation starts. Resizing only has one when it shrinks.
+    pub fn confirmation_target(&self) -> Option<(String, bool)> {
+        match self {
+            Self::DeletePartition(state) => Some((state.name.clone(), false)),
+            Self::ConfirmAction(state) => state.confirm_target.clone().map(|name| (name, false)),
+            Self::ConfirmDeleteRemote { name, .. } => Some((name.clone(), false)),
+            Self::FormatDisk(state) => Some((state.drive.device().to_string(), true)),
+            Self::FormatPartition(state) if state.step == FormatPartitionStep::Options => {
+                Some((state.volume.device_path.clone()?, false))
+            }
+            Self::ResizePartition(state)
+                if state.step == ResizePartitionStep::Review
+                    && state.new_size_bytes < state.volume.size =>
+            {
+                Some((state.volume.device_path.clone()?, false))
+            }
+            Self::MovePartition(state) => Some((state.volume.device_path.clone()?, false)),
+            Self::EspSync(state) => Some((state.secondary()?.device.clone(), false)),
+            Self::SetUpDrive(state) if state.step == SetUpDriveStep::Review => {
+                Some((state.drive.device().to_string(), true))
+            }
+            Self::GptEntries(state) if state.edits().is_ok_and(|edits| !edits.is_empty()) => {
+                Some((state.drive.device().to_string(), true))
+            }
+            Self::ImageOperation(state) => {
+                let drive = || Some((state.drive.device().to_string(), true));
+                match state.kind {
+                    ImageOperationKind::RestoreToDrive
+                    | ImageOperationKind::BlankDisc
+                    | ImageOperationKind::CreateLiveUsb => drive(),
+                    ImageOperationKind::RestoreToPartition => {
+                        Some((state.partition.as_ref()?.device_path.clone()?, false))
+                    }
+                    ImageOperationKind::CopyPartition => (!state.image_path.trim().is_empty())
+                        .then(|| (state.image_path.trim().to_string(), false)),
+                    ImageOperationKind::MigrateDisk => state
+                        .migration
+                        .as_ref()?
+                        .target()
+                        .map(|disk| (disk.device.clone(), true)),
+                    ImageOperationKind::CreateFromDrive
+                    | ImageOperationKind::CreateFromPartition
+                    | ImageOperationKind::BurnDisc => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Steps a destructive dialog asks for before its OK button works, beyond
+/// the button itself, as the confirmation level in the settings says
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmationGuard {
+    /// What the operation destroys, e.g. "/dev/sdb" or a partition's name
+    pub target: String,
+    pub requirements: ConfirmationRequirements,
+    pub typed_name: String,
+    /// Token to type back, when the level asks for one
+    pub token: Option<String>,
+    pub typed_token: String,
+    /// Seconds until OK works
+    pub countdown: u32,
+}
+
+impl ConfirmationGuard {
+    pub fn new(level: ConfirmationLevel, target: String, whole_disk: bool) -> Self {
+        let requirements = level.requirements(whole_disk);
+        Self {
+            target,
+            requirements,
+            typed_name: String::new(),
+            token: requirements.token.then(confirmation_token),
+            typed_token: String::new(),
+            countdown: requirements.countdown_secs,
+        }
+    }
+
+    /// Name the user is asked to type
+    pub fn name(&self) -> &str {
+        self.target.strip_prefix("/dev/").unwrap_or(&self.target)
+    }
+
+    /// Whether OK can be pressed
+    pub fn satisfied(&self) -> bool {
+        (!self.requirements.type_name || confirmation_name_matches(&self.target, &self.typed_name))
+            && self
+                .token
+                .as_ref()
+                .is_none_or(|token| confirmation_token_matches(token, &self.typed_token))
+            && self.countdown == 0
+    }
+
+    /// Whether the countdown still runs
+    pub fn pending(&self) -> bool {
+        self.countdown > 0
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +178,12 @@ pub struct FormatPartitionDialog {
     pub step: FormatPartitionStep,
     pub running: bool,
     pub filesystem_tools: Vec<FilesystemToolInfo>,
+    /// Id of the selected format preset, if any
+    pub format_preset: Option<String>,
+    /// Filesystem-specific format options, keyed per `format_option_schema`
+    pub fs_specific: HashMap<String, String>,
+    /// Whether the volume is on a removable drive, where exFAT is suggested
+    pub removable: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -97,6 +238,10 @@ pub struct ResizePartitionDialog {
     pub min_size_bytes: u64,
     pub max_size_bytes: u64,
     pub new_size_bytes: u64,
+    /// What the service says the partition and its filesystem allow; None
+    /// while loading, or when it couldn't tell and only growing is offered
+    pub bounds: Option<storage_types::ResizeBounds>,
+    pub loading_bounds: bool,
     pub running: bool,
 }
 
@@ -115,17 +260,37 @@ impl ResizePartitionStep {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct MovePartitionDialog {
+    pub volume: VolumeInfo,
+    /// Where the service says the partition can move; None while loading
+    pub bounds: Option<storage_types::MoveBounds>,
+    /// Free space to leave before the partition
+    pub free_before: u64,
+}
+
 #[derive(Debug, Clone)]
 pub enum FilesystemTarget {
     Volume(VolumeInfo),
     Node(UiVolume),
 }
 
+/// What an error dialog offers to do about the failure
+#[derive(Debug, Clone)]
+pub struct ErrorRecovery {
+    /// Label of the button that does it
+    pub label: String,
+    pub message: crate::app::Message,
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfirmActionDialog {
     pub title: String,
     pub body: String,
     pub target: FilesystemTarget,
+    /// Name of what OK destroys, for the confirmation level to ask for;
+    /// None when nothing is destroyed
+    pub confirm_target: Option<String>,
     pub ok_message: crate::app::Message,
     pub running: bool,
 }
@@ -170,6 +335,11 @@ pub struct EditMountOptionsDialog {
     pub identify_as_options: Vec<String>,
     pub identify_as_index: usize,
     pub filesystem_type: String,
+    /// Id of the mount option preset last applied, if the options were not
+    /// edited since
+    pub preset: Option<String>,
+    /// The drive the filesystem is on, for linting the options
+    pub lint_context: MountLintContext,
     pub error: Option<String>,
     pub running: bool,
 }
@@ -231,6 +401,17 @@ pub struct NewDiskImageDialog {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct NewBindMountDialog {
+    pub source: String,
+    pub target: String,
+    pub read_only: bool,
+    /// Add an fstab entry, so it is mounted again at startup
+    pub persistent: bool,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct AttachDiskImageDialog {
     pub path: String,
@@ -244,6 +425,17 @@ pub enum ImageOperationKind {
     RestoreToDrive,
     CreateFromPartition,
     RestoreToPartition,
+    /// Burn an ISO image to the disc of an optical drive
+    BurnDisc,
+    /// Erase the rewritable disc of an optical drive
+    BlankDisc,
+    /// Copy the partition onto another one; `image_path` holds the target
+    CopyPartition,
+    /// Migrate the drive onto a larger one, as `migration` plans it
+    MigrateDisk,
+    /// Make the drive a bootable copy of an ISO image, with the extra
+    /// partitions of `live_usb`
+    CreateLiveUsb,
 }
 
 #[derive(Debug, Clone)]
@@ -257,17 +449,464 @@ pub struct ImageOperationDialog {
     pub operation_id: Option<String>,
     /// Progress: (bytes_completed, total_bytes, speed_bytes_per_sec).
     pub progress: Option<(u64, u64, u64)>,
+    /// Remaining time, estimated from past runs on the same device
+    pub eta: Option<EtaRange>,
     pub error: Option<String>,
+    /// Read a burned disc back and compare it with the image
+    pub verify: bool,
+    /// Overwrite the whole disc when erasing, not only its table of contents
+    pub full_erase: bool,
+    /// Partitions large enough to take a copy of `partition`
+    pub copy_targets: Vec<VolumeInfo>,
+    pub migration: Option<DiskMigration>,
+    pub live_usb: Option<LiveUsbChoices>,
+    /// CPU and I/O priority for this operation alone; the configured one
+    /// when unset
+    pub priority: Option<OperationPriority>,
+}
+
+impl ImageOperationKind {
+    /// Kind of priority the service runs the operation with, for kinds
+    /// whose priority can be chosen
+    pub fn priority_kind(self) -> Option<OperationKind> {
+        match self {
+            ImageOperationKind::CreateFromDrive | ImageOperationKind::CreateFromPartition => {
+                Some(OperationKind::ImageBackup)
+            }
+            ImageOperationKind::RestoreToDrive | ImageOperationKind::RestoreToPartition => {
+                Some(OperationKind::ImageRestore)
+            }
+            ImageOperationKind::CopyPartition => Some(OperationKind::PartitionCopy),
+            ImageOperationKind::BurnDisc
+            | ImageOperationKind::BlankDisc
+            | ImageOperationKind::MigrateDisk
+            | ImageOperationKind::CreateLiveUsb => None,
+        }
+    }
+}
+
+/// Choices of a live USB drive
+#[derive(Debug, Clone)]
+pub struct LiveUsbChoices {
+    /// What the chosen image supports, once inspected
+    pub iso: Option<LiveIsoInfo>,
+    pub persistence: bool,
+    pub persistence_gib: u64,
+    pub data_partition: bool,
+    /// The user acknowledged that everything on the drive is erased
+    pub erase_confirmed: bool,
+}
+
+impl Default for LiveUsbChoices {
+    fn default() -> Self {
+        Self {
+            iso: None,
+            persistence: true,
+            persistence_gib: 4,
+            data_partition: true,
+            erase_confirmed: false,
+        }
+    }
+}
+
+impl LiveUsbChoices {
+    pub fn plan(&self) -> LiveUsbPlan {
+        let supported = self
+            .iso
+            .is_some_and(|iso| iso.distro.persistence_label().is_some());
+        LiveUsbPlan {
+            persistence_bytes: if self.persistence && supported {
+                self.persistence_gib * 1024 * 1024 * 1024
+            } else {
+                0
+            },
+            data_partition: self.data_partition,
+        }
+    }
+
+    /// Check the plan against `drive`, once the image is inspected
+    pub fn validate(&self, drive: &DiskInfo) -> Option<Result<(), String>> {
+        let iso = self.iso.as_ref()?;
+        Some(
+            self.plan()
+                .layout(iso, drive.size, drive.logical_sector_size)
+                .map(|_| ()),
+        )
+    }
+}
+
+/// Choices of a disk migration
+#[derive(Debug, Clone)]
+pub struct DiskMigration {
+    /// Drives large enough to take the source's partitions
+    pub targets: Vec<DiskInfo>,
+    pub target_index: Option<usize>,
+    /// Partitions of the source, in disk order
+    pub partitions: Vec<PartitionInfo>,
+    /// Share the added space among all partitions, in proportion to their
+    /// sizes, instead of the ones picked in `grow`
+    pub proportional: bool,
+    /// Which partitions grow, aligned with `partitions`
+    pub grow: Vec<bool>,
+}
+
+impl DiskMigration {
+    pub fn target(&self) -> Option<&DiskInfo> {
+        self.target_index.and_then(|index| self.targets.get(index))
+    }
+
+    /// Where the partitions go on the chosen target
+    pub fn plan(&self) -> Option<Result<MigrationPlan, String>> {
+        let grow = if self.proportional {
+            vec![true; self.partitions.len()]
+        } else {
+            self.grow.clone()
+        };
+        Some(self.plan_for(self.target()?, &grow))
+    }
+
+    /// Whether the partitions fit on `target` without growing any of them
+    pub fn fits(&self, target: &DiskInfo) -> bool {
+        self.plan_for(target, &[]).is_ok()
+    }
+
+    fn plan_for(&self, target: &DiskInfo, grow: &[bool]) -> Result<MigrationPlan, String> {
+        let partitions: Vec<(u32, ByteRange)> = self
+            .partitions
+            .iter()
+            .map(|partition| {
+                (
+                    partition.number,
+                    ByteRange {
+                        start: partition.offset,
+                        end: partition.offset + partition.size,
+                    },
+                )
+            })
+            .collect();
+        MigrationPlan::new(&partitions, grow, target.size, target.logical_sector_size)
+    }
 }
 
+/// Self-test intervals offered in the SMART dialog, in days; `None` is off
+pub const SELFTEST_INTERVAL_DAYS: [Option<u32>; 5] = [None, Some(1), Some(7), Some(30), Some(90)];
+
+/// Temperature thresholds offered in the SMART dialog, in degrees Celsius
+pub const TEMPERATURE_THRESHOLD_CHOICES: [u64; 8] = [40, 45, 50, 55, 60, 65, 70, 75];
+
 #[derive(Debug, Clone)]
 pub struct SmartDataDialog {
     pub drive: UiDrive,
     pub running: bool,
     pub info: Option<(SmartStatus, Vec<SmartAttribute>)>,
+    pub backend: Option<SmartBackendStatus>,
+    pub schedule: Option<SelfTestSchedule>,
+    pub history: Vec<SelfTestRecord>,
+    pub thresholds: Option<TemperatureThresholds>,
+    /// Device errors from the kernel log, oldest first
+    pub kernel_errors: Vec<KernelDeviceError>,
+    pub error: Option<String>,
+}
+
+/// Search for deleted partitions on a drive, and their recreation
+#[derive(Debug, Clone)]
+pub struct LostPartitionsDialog {
+    pub drive: UiDrive,
+    /// Partitions found, `None` while the search runs
+    pub found: Option<Vec<LostPartition>>,
+    /// Whether each found partition is to be recreated
+    pub selected: Vec<bool>,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+/// A field of a raw GPT entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GptEntryField {
+    FirstLba,
+    LastLba,
+    TypeGuid,
+    Guid,
+    Attributes,
+    Name,
+}
+
+/// A GPT entry as typed, kept as text until it parses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptEntryRow {
+    pub number: u32,
+    pub first_lba: String,
+    pub last_lba: String,
+    pub type_guid: String,
+    pub guid: String,
+    /// Hexadecimal with a 0x prefix, or decimal
+    pub attributes: String,
+    pub name: String,
+}
+
+impl GptEntryRow {
+    pub fn new(entry: &GptEntry) -> Self {
+        Self {
+            number: entry.number,
+            first_lba: entry.first_lba.to_string(),
+            last_lba: entry.last_lba.to_string(),
+            type_guid: entry.type_guid.clone(),
+            guid: entry.guid.clone(),
+            attributes: format!("{:#018x}", entry.attributes),
+            name: entry.name.clone(),
+        }
+    }
+
+    pub fn field_mut(&mut self, field: GptEntryField) -> &mut String {
+        match field {
+            GptEntryField::FirstLba => &mut self.first_lba,
+            GptEntryField::LastLba => &mut self.last_lba,
+            GptEntryField::TypeGuid => &mut self.type_guid,
+            GptEntryField::Guid => &mut self.guid,
+            GptEntryField::Attributes => &mut self.attributes,
+            GptEntryField::Name => &mut self.name,
+        }
+    }
+
+    /// The entry typed, or why it doesn't parse
+    fn entry(&self) -> Result<GptEntry, String> {
+        let number = self.number;
+        let sector = |text: &str| {
+            text.trim()
+                .parse::<u64>()
+                .map_err(|_| format!("Partition {number}: \"{}\" is not a sector", text.trim()))
+        };
+        let attributes = self.attributes.trim();
+        let attributes = match attributes
+            .strip_prefix("0x")
+            .or_else(|| attributes.strip_prefix("0X"))
+        {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => attributes.parse(),
+        }
+        .map_err(|_| format!("Partition {number}: \"{attributes}\" are not attribute bits"))?;
+        Ok(GptEntry {
+            number,
+            type_guid: self.type_guid.trim().to_uppercase(),
+            guid: self.guid.trim().to_uppercase(),
+            first_lba: sector(&self.first_lba)?,
+            last_lba: sector(&self.last_lba)?,
+            attributes,
+            name: self.name.clone(),
+        })
+    }
+}
+
+/// Raw GPT entries of a drive being edited
+#[derive(Debug, Clone)]
+pub struct GptEntriesDialog {
+    pub drive: UiDrive,
+    /// The table as read, `None` while it loads
+    pub table: Option<GptTable>,
+    pub rows: Vec<GptEntryRow>,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+impl GptEntriesDialog {
+    /// Edits turning the table into the rows, checked together, or why
+    /// they can't be written
+    pub fn edits(&self) -> Result<Vec<GptEntryEdit>, String> {
+        let Some(table) = self.table.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let entries = self
+            .rows
+            .iter()
+            .map(GptEntryRow::entry)
+            .collect::<Result<Vec<_>, _>>()?;
+        storage_types::gpt::check_entries(table, &entries)?;
+        Ok(storage_types::gpt::diff_entries(table, &entries))
+    }
+}
+
+/// Consent to collecting a diagnostic report of a failure, and its saving
+#[derive(Debug, Clone)]
+pub struct DiagnosticsDialog {
+    pub failure: FailureContext,
+    /// Drive selected when the failure happened, whose details are included
+    pub device: Option<String>,
+    pub running: bool,
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct DefragmentDialog {
+    pub device: String,
+    pub fs_type: String,
+    pub mount_point: String,
+    /// Directory to defragment; empty for the whole filesystem
+    pub target: String,
+    pub report: Option<FragmentationReport>,
+    pub result: Option<DefragResult>,
+    /// Files processed and total (0 when the tool does not report one)
+    pub progress: Option<(u64, u64)>,
+    pub analyzing: bool,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptedFoldersDialog {
+    pub device: String,
+    pub mount_point: String,
+    pub status: Option<FscryptStatus>,
+    /// Directory whose passphrase is being entered to unlock it
+    pub unlocking: Option<String>,
+    pub unlock_passphrase: String,
+    /// Path of a new encrypted directory
+    pub new_directory: String,
+    pub protector_name: String,
+    pub passphrase: String,
+    pub confirm: String,
+    pub loading: bool,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+/// Scheduling choices for ESP syncs, in days between syncs
+pub const ESP_SYNC_INTERVALS: [Option<u32>; 3] = [None, Some(1), Some(7)];
+
+#[derive(Debug, Clone)]
+pub struct EspSyncDialog {
+    /// The ESP to copy from
+    pub primary: PartitionInfo,
+    /// Other ESPs the primary can be copied onto
+    pub candidates: Vec<PartitionInfo>,
+    pub secondary_index: usize,
+    /// Days between scheduled syncs; `None` syncs only on request
+    pub interval_days: Option<u32>,
+    pub running: bool,
+    pub result: Option<EspSyncResult>,
+    pub error: Option<String>,
+}
+
+impl EspSyncDialog {
+    pub fn secondary(&self) -> Option<&PartitionInfo> {
+        self.candidates.get(self.secondary_index)
+    }
+}
+
+/// Free space percentages offered for low space alerts; `None` is off
+pub const LOW_SPACE_PERCENT_CHOICES: [Option<u8>; 5] =
+    [None, Some(5), Some(10), Some(15), Some(20)];
+
+/// Free space amounts offered for low space alerts, in bytes; `None` is off
+pub const LOW_SPACE_BYTES_CHOICES: [Option<u64>; 5] = [
+    None,
+    Some(1024 * 1024 * 1024),
+    Some(5 * 1024 * 1024 * 1024),
+    Some(10 * 1024 * 1024 * 1024),
+    Some(50 * 1024 * 1024 * 1024),
+];
+
+#[derive(Debug, Clone)]
+pub struct LowSpaceDialog {
+    pub mount_point: String,
+    /// Rule of the mount point; `None` while it loads
+    pub rule: Option<LowSpaceRule>,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AutomountDialog {
+    /// Units to create, with the mount point, options and idle timeout
+    /// being edited
+    pub unit: AutomountUnit,
+    /// Units created before; `None` while they load
+    pub units: Option<Vec<AutomountUnit>>,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+/// Readahead sizes offered by the performance dialog, in KiB
+pub const READ_AHEAD_CHOICES_KB: [u64; 6] = [128, 256, 512, 1024, 2048, 4096];
+
+/// Request queue depths offered by the performance dialog
+pub const NR_REQUESTS_CHOICES: [u64; 6] = [32, 64, 128, 256, 512, 1024];
+
+#[derive(Debug, Clone)]
+pub struct PerformanceDialog {
+    pub drive: UiDrive,
+    /// Settings read from the drive; `None` while they load
+    pub current: Option<QueueSettings>,
+    /// Settings to apply
+    pub tuning: QueueTuning,
+    /// Write cache read from the drive; `None` while it loads
+    pub write_cache: Option<WriteCacheStatus>,
+    /// Whether the drive should cache writes
+    pub write_cache_enabled: bool,
+    pub persistent: bool,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+impl PerformanceDialog {
+    /// Readahead sizes to offer, including the drive's current one
+    pub fn read_ahead_choices(&self) -> Vec<u64> {
+        with_current(
+            &READ_AHEAD_CHOICES_KB,
+            self.current.as_ref().map(|c| c.read_ahead_kb),
+        )
+    }
+
+    /// Queue depths to offer, including the drive's current one
+    pub fn nr_requests_choices(&self) -> Vec<u64> {
+        with_current(
+            &NR_REQUESTS_CHOICES,
+            self.current.as_ref().map(|c| c.nr_requests),
+        )
+    }
+}
+
+/// Bytes the sector viewer shows at once, unless sectors are larger
+pub const SECTOR_PAGE_BYTES: u64 = 4096;
+
+#[derive(Debug, Clone)]
+pub struct SectorViewerDialog {
+    /// Drive or partition shown
+    pub device: String,
+    pub size: u64,
+    /// Logical sector size of the drive
+    pub sector_size: u64,
+    /// Offset of the page shown or loading
+    pub offset: u64,
+    /// Bytes read at `offset`; `None` while they load
+    pub page: Option<SectorRange>,
+    pub offset_text: String,
+    pub error: Option<String>,
+}
+
+impl SectorViewerDialog {
+    /// Bytes per page, a whole number of sectors
+    pub fn page_bytes(&self) -> u64 {
+        SECTOR_PAGE_BYTES.max(self.sector_size)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InspectDialog {
+    pub device: String,
+    pub size: u64,
+    /// Bytes read from the start of the volume; `None` while they load
+    pub ranges: Option<Vec<SectorRange>>,
+    pub error: Option<String>,
+}
+
+fn with_current(choices: &[u64], current: Option<u64>) -> Vec<u64> {
+    let mut choices = choices.to_vec();
+    choices.extend(current);
+    choices.sort_unstable();
+    choices.dedup();
+    choices
+}
+
 #[derive(Debug, Clone)]
 pub struct DeletePartitionDialog {
     pub name: String,
@@ -308,6 +947,92 @@ pub struct FormatDiskDialog {
     pub running: bool,
 }
 
+/// Partition tables offered by the setup wizard
+pub const SETUP_TABLE_TYPES: [&str; 2] = ["gpt", "dos"];
+
+/// Filesystems offered by the setup wizard, exFAT first as it suits drives
+/// moved between computers
+pub const SETUP_FILESYSTEMS: [&str; 5] = ["exfat", "ext4", "ntfs", "vfat", "btrfs"];
+
+/// Guided setup of a drive without partition table or filesystem: one
+/// partition spanning the drive, optionally encrypted
+#[derive(Debug, Clone)]
+pub struct SetUpDriveDialog {
+    pub drive: UiDrive,
+    pub step: SetUpDriveStep,
+    /// Index into `SETUP_TABLE_TYPES`
+    pub table_index: usize,
+    /// Index into `SETUP_FILESYSTEMS`
+    pub filesystem_index: usize,
+    pub label: String,
+    pub encrypt: bool,
+    pub passphrase: String,
+    pub confirm_passphrase: String,
+    pub filesystem_tools: Vec<FilesystemToolInfo>,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+impl SetUpDriveDialog {
+    pub fn table_type(&self) -> &'static str {
+        SETUP_TABLE_TYPES[self.table_index]
+    }
+
+    pub fn filesystem(&self) -> &'static str {
+        SETUP_FILESYSTEMS[self.filesystem_index]
+    }
+
+    /// Whether the current step is complete
+    pub fn can_advance(&self) -> bool {
+        match self.step {
+            SetUpDriveStep::Table | SetUpDriveStep::Review => true,
+            SetUpDriveStep::Filesystem => self
+                .filesystem_tools
+                .iter()
+                .any(|tool| tool.fs_type == self.filesystem() && tool.available),
+            SetUpDriveStep::Encryption => {
+                !self.encrypt
+                    || (!self.passphrase.is_empty() && self.passphrase == self.confirm_passphrase)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetUpDriveStep {
+    Table,
+    Filesystem,
+    Encryption,
+    Review,
+}
+
+impl SetUpDriveStep {
+    pub const fn number(self) -> usize {
+        match self {
+            Self::Table => 1,
+            Self::Filesystem => 2,
+            Self::Encryption => 3,
+            Self::Review => 4,
+        }
+    }
+
+    pub const fn previous(self) -> Self {
+        match self {
+            Self::Table | Self::Filesystem => Self::Table,
+            Self::Encryption => Self::Filesystem,
+            Self::Review => Self::Encryption,
+        }
+    }
+
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Table => Self::Filesystem,
+            Self::Filesystem => Self::Encryption,
+            Self::Encryption | Self::Review => Self::Review,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UnlockEncryptedDialog {
     pub partition_path: String,
@@ -345,3 +1070,28 @@ pub struct BtrfsCreateSnapshotDialog {
     pub running: bool,
     pub error: Option<String>,
 }
+
+/// Password prompt for an encrypted rclone configuration
+#[derive(Debug, Clone)]
+pub struct RcloneConfigPasswordDialog {
+    pub scope: storage_types::rclone::ConfigScope,
+    /// Encrypt the configuration with the password instead of unlocking it
+    pub encrypt: bool,
+    pub password: String,
+    pub confirm: String,
+    pub error: Option<String>,
+    pub running: bool,
+}
+
+impl RcloneConfigPasswordDialog {
+    pub fn new(scope: storage_types::rclone::ConfigScope, encrypt: bool) -> Self {
+        Self {
+            scope,
+            encrypt,
+            password: String::new(),
+            confirm: String::new(),
+            error: None,
+            running: false,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State of the health check page
+
+use storage_types::HealthCheckReport;
+
+/// Checking all drives at once, shown in the health check context drawer
+#[derive(Debug, Default)]
+pub struct HealthCheckState {
+    /// Report of the last check
+    pub report: Option<HealthCheckReport>,
+    pub running: bool,
+    /// Item whose details are shown
+    pub expanded: Option<usize>,
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State of the log viewer
+
+use storage_types::{LogEntry, LogFilter};
+
+/// Log lines of the app and, on request, of the service, shown in the logs
+/// context drawer
+#[derive(Debug, Default)]
+pub struct LogsState {
+    /// Lines of the app's log files, oldest first
+    pub entries: Vec<LogEntry>,
+    pub error: Option<String>,
+    pub filter: LogFilter,
+    /// Service lines of the filtered operation and device, once fetched
+    pub service_entries: Option<Vec<LogEntry>>,
+    pub service_error: Option<String>,
+    pub loading_service: bool,
+}
+
+impl LogsState {
+    /// App lines that pass the filter
+    pub fn visible(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| self.filter.matches(entry))
+    }
+
+    /// Service lines that pass the filter
+    pub fn visible_service(&self) -> impl Iterator<Item = &LogEntry> {
+        self.service_entries
+            .iter()
+            .flatten()
+            .filter(|entry| self.filter.matches(entry))
+    }
+}
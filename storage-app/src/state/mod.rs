@@ -1,6 +1,17 @@
 pub(crate) mod app;
+pub(crate) mod bind_mounts;
 pub(crate) mod btrfs;
+pub(crate) mod capacity;
 pub(crate) mod dialogs;
+pub(crate) mod health_check;
+pub(crate) mod logs;
+pub(crate) mod mtp;
 pub(crate) mod network;
+pub(crate) mod optical;
+pub(crate) mod read_only;
+pub(crate) mod selinux;
 pub(crate) mod sidebar;
+pub(crate) mod statistics;
+pub(crate) mod user_mounts;
+pub(crate) mod vaults;
 pub(crate) mod volumes;
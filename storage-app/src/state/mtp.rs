@@ -0,0 +1,15 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State for connected phones and cameras
+
+use std::collections::HashSet;
+use storage_types::MtpDevice;
+
+/// Devices listed in the "Phones & Cameras" section of the sidebar
+#[derive(Debug, Default)]
+pub struct MtpState {
+    /// Devices found at the last look at gvfs
+    pub devices: Vec<MtpDevice>,
+    /// Locations of devices being mounted or ejected
+    pub busy: HashSet<String>,
+}
@@ -3,7 +3,11 @@
 //! State for network mount management
 
 use std::collections::{HashMap, HashSet};
-use storage_types::rclone::{ConfigScope, MountStatus, RemoteConfig};
+use std::path::PathBuf;
+use storage_types::rclone::{
+    ConfigEncryptionStatus, ConfigScope, LoginMountStatus, MountStatus, RemoteConfig, RemoteUsage,
+    TransferEndpoint, TransferJob, TransferMode, TransferRequest, TransferState,
+};
 
 /// Runtime state of a network mount
 #[derive(Debug, Clone)]
@@ -16,6 +20,14 @@ pub struct NetworkMountState {
     pub loading: bool,
     /// Last error message if any
     pub error: Option<String>,
+    /// Automatic mount at login (or boot for system remotes), once loaded
+    pub login_mount: Option<LoginMountStatus>,
+    /// Quota and usage, once loaded and if the remote reports them
+    pub usage: Option<RemoteUsage>,
+    /// Whether usage is being queried
+    pub usage_loading: bool,
+    /// Why the last usage query failed
+    pub usage_error: Option<String>,
 }
 
 impl NetworkMountState {
@@ -25,6 +37,10 @@ impl NetworkMountState {
             status: MountStatus::Unmounted,
             loading: false,
             error: None,
+            login_mount: None,
+            usage: None,
+            usage_loading: false,
+            usage_error: None,
         }
     }
 
@@ -212,6 +228,52 @@ impl NetworkWizardState {
     }
 }
 
+/// Form for starting a copy or sync
+#[derive(Debug, Clone, Default)]
+pub struct TransferFormState {
+    /// Configuration the remotes are picked from
+    pub scope: ConfigScope,
+    /// Remote to transfer from; a local folder if `None`
+    pub source_remote: Option<String>,
+    pub source_path: String,
+    /// Remote to transfer to; a local folder if `None`
+    pub destination_remote: Option<String>,
+    pub destination_path: String,
+    pub mode: TransferMode,
+    /// Bandwidth limit as typed; empty for unlimited
+    pub bandwidth_limit: String,
+    pub dry_run: bool,
+    pub error: Option<String>,
+    /// Whether the transfer is being started
+    pub running: bool,
+}
+
+impl TransferFormState {
+    /// The transfer described by the form, if it is complete and valid
+    pub fn to_request(&self) -> Result<TransferRequest, String> {
+        let endpoint = |remote: &Option<String>, path: &str| match remote {
+            Some(name) => TransferEndpoint::Remote {
+                name: name.clone(),
+                path: path.trim().trim_start_matches('/').to_string(),
+            },
+            None => TransferEndpoint::Local {
+                path: PathBuf::from(path.trim()),
+            },
+        };
+        let limit = self.bandwidth_limit.trim();
+        let request = TransferRequest {
+            scope: self.scope,
+            source: endpoint(&self.source_remote, &self.source_path),
+            destination: endpoint(&self.destination_remote, &self.destination_path),
+            mode: self.mode,
+            bandwidth_limit: (!limit.is_empty()).then(|| limit.to_string()),
+            dry_run: self.dry_run,
+        };
+        request.validate()?;
+        Ok(request)
+    }
+}
+
 /// Ordered list of sections for display
 pub const SECTION_ORDER: &[&str] = &[
     "authentication",
@@ -260,6 +322,15 @@ pub struct NetworkState {
 
     /// Active wizard state (for guided creation of new remotes)
     pub wizard: Option<NetworkWizardState>,
+
+    /// Encryption state of the existing configuration files
+    pub config_encryption: Vec<ConfigEncryptionStatus>,
+
+    /// Running and finished transfers, newest first
+    pub transfers: Vec<TransferJob>,
+
+    /// Transfer form, while the transfers page is shown
+    pub transfer_form: Option<TransferFormState>,
 }
 
 impl NetworkState {
@@ -322,6 +393,10 @@ impl NetworkState {
                     status: existing.status.clone(),
                     loading: existing.loading,
                     error: None,
+                    login_mount: existing.login_mount.clone(),
+                    usage: existing.usage.clone(),
+                    usage_loading: existing.usage_loading,
+                    usage_error: existing.usage_error.clone(),
                 }
             } else {
                 NetworkMountState::new(config)
@@ -330,13 +405,55 @@ impl NetworkState {
         }
     }
 
+    /// Scopes whose encrypted configuration needs its password
+    pub fn locked_scopes(&self) -> Vec<ConfigScope> {
+        self.config_encryption
+            .iter()
+            .filter(|status| status.is_locked())
+            .map(|status| status.scope)
+            .collect()
+    }
+
+    /// Whether any transfer is still running, so progress needs polling
+    pub fn has_running_transfers(&self) -> bool {
+        self.transfers
+            .iter()
+            .any(|job| job.state == TransferState::Running)
+    }
+
+    /// Names of the remotes of `scope`, sorted
+    pub fn remote_names(&self, scope: ConfigScope) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .mounts
+            .keys()
+            .filter(|(_, s)| *s == scope)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Set the automatic mount states loaded from the service
+    pub fn set_login_mounts(&mut self, statuses: Vec<LoginMountStatus>) {
+        for mount in self.mounts.values_mut() {
+            mount.login_mount = None;
+        }
+        for status in statuses {
+            if let Some(mount) = self
+                .mounts
+                .get_mut(&(status.remote_name.clone(), status.scope))
+            {
+                mount.login_mount = Some(status);
+            }
+        }
+    }
+
     /// Get a mount by name and scope
     pub fn get_mount(&self, name: &str, scope: ConfigScope) -> Option<&NetworkMountState> {
         self.mounts.get(&(name.to_string(), scope))
     }
 
     /// Get a mutable mount by name and scope
-    #[allow(dead_code)]
     pub fn get_mount_mut(
         &mut self,
         name: &str,
@@ -433,6 +550,8 @@ pub struct NetworkEditorState {
     pub show_advanced: bool,
     pub show_hidden: bool,
     pub mount_on_boot: Option<bool>,
+    /// Custom mount point; empty for the default location of the scope
+    pub mount_point: String,
     /// Which sections are currently expanded in the editor
     pub expanded_sections: HashSet<String>,
 }
@@ -462,6 +581,7 @@ impl NetworkEditorState {
             show_advanced: false,
             show_hidden: false,
             mount_on_boot: None,
+            mount_point: String::new(),
             expanded_sections: Self::default_expanded_sections(),
         }
     }
@@ -482,6 +602,10 @@ impl NetworkEditorState {
             show_advanced: false,
             show_hidden: false,
             mount_on_boot: None,
+            mount_point: config
+                .mount_point
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
             expanded_sections: Self::default_expanded_sections(),
         }
     }
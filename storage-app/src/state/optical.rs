@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State for the discs in optical drives
+
+use std::collections::HashMap;
+use storage_types::OpticalMediaInfo;
+
+/// Disc of each optical drive, shown in the drive panel
+#[derive(Debug, Default)]
+pub struct OpticalState {
+    /// By drive device path; `Ok(None)` for an empty drive, `Err` when the
+    /// disc could not be read (e.g., xorriso is missing)
+    pub media: HashMap<String, Result<Option<OpticalMediaInfo>, String>>,
+}
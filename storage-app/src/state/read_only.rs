@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State for filesystems the kernel made read-only after errors
+
+use storage_types::{ForcedReadOnly, VolumeInfo};
+
+/// Mounted filesystems that went read-only, shown with a warning and
+/// recovery actions in the volume details
+#[derive(Debug, Default)]
+pub struct ReadOnlyState {
+    pub filesystems: Vec<ForcedReadOnly>,
+}
+
+impl ReadOnlyState {
+    /// The forced read-only filesystem mounted from `volume` or a volume
+    /// inside it (e.g., the cleartext of an unlocked LUKS container)
+    pub fn for_volume(&self, volume: &VolumeInfo) -> Option<&ForcedReadOnly> {
+        self.filesystems
+            .iter()
+            .find(|fs| volume.mount_points.contains(&fs.mount_point))
+            .or_else(|| {
+                volume
+                    .children
+                    .iter()
+                    .find_map(|child| self.for_volume(child))
+            })
+    }
+}
@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State for SELinux labels of mounted filesystems
+
+use std::collections::HashSet;
+
+use storage_types::{SelinuxStatus, VolumeInfo};
+
+/// SELinux labels of mounted filesystems, shown in the volume details with a
+/// relabel action when they are missing or wrong
+#[derive(Debug, Default)]
+pub struct SelinuxState {
+    pub statuses: Vec<SelinuxStatus>,
+    /// Mount points being relabeled
+    pub relabeling: HashSet<String>,
+}
+
+impl SelinuxState {
+    /// Labels of the filesystem mounted from `volume` or a volume inside it
+    /// (e.g., the cleartext of an unlocked LUKS container)
+    pub fn for_volume(&self, volume: &VolumeInfo) -> Option<&SelinuxStatus> {
+        self.statuses
+            .iter()
+            .find(|status| volume.mount_points.contains(&status.mount_point))
+            .or_else(|| {
+                volume
+                    .children
+                    .iter()
+                    .find_map(|child| self.for_volume(child))
+            })
+    }
+}
@@ -1,6 +1,7 @@
-use crate::models::UiDrive;
+use crate::models::{UiDrive, UiVolume};
 use cosmic::widget::nav_bar;
 use std::collections::{HashMap, HashSet};
+use storage_types::DiskHealthSummary;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum SidebarNodeKey {
@@ -21,6 +22,15 @@ pub struct SidebarState {
 
     /// Selected (focused) child node. Drive selection is still managed via `app.nav`.
     pub selected_child: Option<SidebarNodeKey>,
+
+    /// Health summaries by drive `block_path`, shown as badges.
+    pub health: HashMap<String, DiskHealthSummary>,
+
+    /// Device path or mount point to reveal once the drives are loaded.
+    pub pending_reveal: Option<String>,
+
+    /// Drive to open the setup wizard for once the drives are loaded.
+    pub pending_setup: Option<String>,
 }
 
 impl SidebarState {
@@ -34,6 +44,13 @@ impl SidebarState {
         self.drives = drives;
     }
 
+    pub fn set_health(&mut self, summaries: Vec<DiskHealthSummary>) {
+        self.health = summaries
+            .into_iter()
+            .map(|summary| (summary.device.clone(), summary))
+            .collect();
+    }
+
     pub fn set_drive_entities(&mut self, entities: HashMap<String, nav_bar::Id>) {
         self.drive_entities = entities;
     }
@@ -51,4 +68,44 @@ impl SidebarState {
     pub fn find_drive(&self, device: &str) -> Option<&UiDrive> {
         self.drives.iter().find(|d| d.device() == device)
     }
+
+    /// The node of a drive or volume by device path, or of the volume
+    /// mounted at `target`.
+    pub fn find_target(&self, target: &str) -> Option<SidebarNodeKey> {
+        if let Some(drive) = self.find_drive(target) {
+            return Some(SidebarNodeKey::Drive(drive.device().to_string()));
+        }
+        self.volumes()
+            .find(|v| v.device() == Some(target) || v.mount_points.iter().any(|m| m == target))
+            .and_then(|v| v.device_path())
+            .map(SidebarNodeKey::Volume)
+    }
+
+    /// Expand the drive and volumes above a volume, so that it shows in the
+    /// tree.
+    pub fn expand_ancestors(&mut self, device_path: &str) {
+        let mut keys = Vec::new();
+        let mut parent = self
+            .find_volume(device_path)
+            .and_then(|v| v.parent_path.clone());
+        while let Some(path) = parent {
+            parent = match self.find_volume(&path) {
+                Some(volume) => volume.parent_path.clone(),
+                None => {
+                    keys.push(SidebarNodeKey::Drive(path));
+                    break;
+                }
+            };
+            keys.push(SidebarNodeKey::Volume(path));
+        }
+        self.expanded.extend(keys);
+    }
+
+    fn find_volume(&self, device_path: &str) -> Option<&UiVolume> {
+        self.volumes().find(|v| v.device() == Some(device_path))
+    }
+
+    fn volumes(&self) -> impl Iterator<Item = &UiVolume> {
+        self.drives.iter().flat_map(|d| &d.volumes_flat)
+    }
 }
@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State of the usage statistics page
+
+use storage_types::UsageStatistics;
+
+/// Local usage statistics, shown in the statistics context drawer
+#[derive(Debug, Default)]
+pub struct StatisticsState {
+    /// Statistics as last fetched from the service
+    pub stats: Option<UsageStatistics>,
+    pub loading: bool,
+    pub error: Option<String>,
+}
@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State for the FUSE and gvfs mounts of the user
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use storage_types::UserMount;
+
+/// Mounts listed in the "Other Mounts" section of the sidebar
+#[derive(Debug, Default)]
+pub struct UserMountsState {
+    /// Mounts found at the last look at the mount table
+    pub mounts: Vec<UserMount>,
+    /// Mount points being unmounted
+    pub unmounting: HashSet<PathBuf>,
+}
@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! State of the legacy vaults page
+
+use storage_types::{LegacyVault, VaultMigrationResult};
+
+/// eCryptfs and gocryptfs vaults, shown in the vaults context drawer
+#[derive(Debug, Default)]
+pub struct VaultsState {
+    pub vaults: Vec<LegacyVault>,
+    pub loading: bool,
+    /// Encrypted directory of the vault whose details are shown
+    pub selected: Option<String>,
+    pub passphrase: String,
+    /// Directory to copy the selected vault into
+    pub target: String,
+    /// Vault with a mount or migration running
+    pub busy: Option<String>,
+    /// Bytes copied and total of the running migration
+    pub progress: Option<(u64, u64)>,
+    pub result: Option<VaultMigrationResult>,
+    pub error: Option<String>,
+}
@@ -5,8 +5,9 @@ use crate::{
     utils::{DiskSegmentKind, PartitionExtent, SegmentAnomaly, compute_disk_segments},
 };
 use storage_types::{
-    ByteRange, CreatePartitionInfo, FilesystemToolInfo, PartitionInfo, UsageCategory,
-    UsageScanParallelismPreset, UsageScanResult, VolumeInfo,
+    ByteRange, CreatePartitionInfo, DeletedPartition, FilesystemFeatures, FilesystemToolInfo,
+    MountLintContext, MountPathPolicy, PartitionInfo, UsageCategory, UsageScanParallelismPreset,
+    UsageScanResult, VolumeInfo,
 };
 
 /// Which detail tab is active below the drive header
@@ -102,6 +103,25 @@ pub struct VolumesControl {
     pub filesystem_tools: Vec<FilesystemToolInfo>,
     /// Usage tab state for global categorized usage scan
     pub usage_state: UsageTabState,
+    /// Mount path preference forwarded to the service on mount
+    pub mount_path_policy: MountPathPolicy,
+    /// Probed features of the selected filesystem, keyed by device path
+    pub filesystem_features: Option<(String, Result<FilesystemFeatures, String>)>,
+    /// Whether the "Advanced" features expander is open
+    pub show_filesystem_features: bool,
+    /// Whether the drive is write-protected, e.g. by the lock switch of an
+    /// SD card
+    pub read_only: bool,
+    /// Whether the drive or its media is removable, so it is likely shared
+    /// with other computers
+    pub removable: bool,
+    /// Physical sector size of the drive, which partitions should start on
+    pub physical_sector_size: u64,
+    /// What the drive is, for linting mount options of its filesystems
+    pub mount_lint_context: MountLintContext,
+    /// Partitions deleted from the drive this session that can still be
+    /// restored, loaded when free space is selected
+    pub deleted_partitions: Vec<DeletedPartition>,
 }
 
 #[derive(Clone, Debug)]
@@ -224,19 +244,9 @@ impl Segment {
         show_reserved: bool,
     ) -> Vec<Segment> {
         let table_type = partition_table_type.clone().unwrap_or_default();
-        const DOS_RESERVED_START_BYTES: u64 = 1024 * 1024;
-
-        let usable_range = match table_type.as_str() {
-            "gpt" => gpt_usable_range.map(|r| (r.start, r.end)),
-            "dos" => {
-                if size > DOS_RESERVED_START_BYTES {
-                    Some((DOS_RESERVED_START_BYTES, size))
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        };
+        // Same rules the backends place new partitions by
+        let usable_range = storage_types::usable_range(size, &table_type, gpt_usable_range)
+            .map(|r| (r.start, r.end));
 
         let extents: Vec<PartitionExtent> = partitions
             .iter()
@@ -510,9 +520,46 @@ impl VolumesControl {
             detail_tab: DetailTab::default(),
             filesystem_tools,
             usage_state: UsageTabState::default(),
+            mount_path_policy: MountPathPolicy::default(),
+            filesystem_features: None,
+            show_filesystem_features: false,
+            read_only: drive.disk.read_only,
+            removable: drive.disk.removable || drive.disk.media_removable,
+            physical_sector_size: drive.disk.physical_sector_size,
+            mount_lint_context: MountLintContext {
+                rotational: drive.disk.rotational,
+                removable: drive.disk.removable || drive.disk.media_removable,
+                discard_supported: drive.disk.discard_supported,
+            },
+            deleted_partitions: Vec::new(),
+        }
+    }
+
+    /// Probed features for `device`, if loaded successfully
+    pub fn features_for(&self, device: &str) -> Option<&FilesystemFeatures> {
+        match &self.filesystem_features {
+            Some((features_device, Ok(features))) if features_device == device => Some(features),
+            _ => None,
         }
     }
 
+    /// Device path of the selected filesystem (selected volume node or segment volume)
+    pub fn selected_filesystem_device(&self) -> Option<String> {
+        if let Some(node) = self.selected_volume_node() {
+            return node
+                .volume
+                .has_filesystem
+                .then(|| node.volume.device_path.clone())
+                .flatten();
+        }
+
+        self.segments
+            .get(self.selected_segment)
+            .and_then(|segment| segment.volume.as_ref())
+            .filter(|volume| volume.has_filesystem)
+            .and_then(|volume| volume.device_path.clone())
+    }
+
     pub fn selected_volume_node(&self) -> Option<&UiVolume> {
         let device_path = self.selected_volume.as_deref()?;
 
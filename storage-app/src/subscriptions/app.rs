@@ -1,14 +1,25 @@
-use crate::client::{DisksClient, FilesystemsClient, ImageClient, LuksClient};
+use crate::client::{
+    DisksClient, FilesystemsClient, ImageClient, LuksClient, OperationsClient, RaidClient,
+    RcloneClient,
+};
 use crate::config::Config;
 use crate::message::app::Message;
-use crate::message::dialogs::ImageOperationDialogMessage;
+use crate::message::dialogs::{
+    ConfirmationMessage, DefragDialogMessage, ImageOperationDialogMessage,
+};
+use crate::message::network::NetworkMessage;
+use crate::message::vaults::VaultsMessage;
+use crate::utils::bind_mounts::list_bind_mounts;
+use crate::utils::mtp::list_mtp_devices;
+use crate::utils::user_mounts::list_user_mounts;
 use cosmic::Application;
 use cosmic::iced::Subscription;
 use cosmic::iced::futures::{SinkExt, StreamExt};
-use cosmic::iced::{Event, event, keyboard};
-use std::time::Duration;
+use cosmic::iced::{Event, event, keyboard, window};
+use std::time::{Duration, Instant};
 
 use crate::state::app::AppModel;
+use crate::subscriptions::app_interface;
 
 /// Subscription for image operation progress and completion.
 struct ImageOperationSubscription;
@@ -16,6 +27,33 @@ struct ImageOperationSubscription;
 /// Subscription for storage-service Filesystems and LUKS signals (format, mount, unmount, container created/unlocked/locked).
 struct StorageEventsSubscription;
 
+/// Subscription for storage-service MD-RAID health signals.
+struct RaidHealthSubscription;
+
+/// Subscription for storage-service drive temperature alerts.
+struct TemperatureAlertSubscription;
+
+/// Subscription for storage-service low space warnings.
+struct LowSpaceSubscription;
+
+/// Subscription for the progress of running rclone transfers.
+struct TransferProgressSubscription;
+
+/// Subscription for the token and countdown of a guarded confirmation.
+struct ConfirmationSubscription;
+
+/// Subscription for the long operations running in the service.
+struct OperationsSubscription;
+
+/// Subscription for the FUSE and gvfs mounts of the user.
+struct UserMountsSubscription;
+
+/// Subscription for bind mounts.
+struct BindMountsSubscription;
+
+/// Subscription for connected phones and cameras.
+struct MtpDevicesSubscription;
+
 /// Register subscriptions for this application.
 ///
 /// Subscriptions are long-running async tasks running in the background which
@@ -28,6 +66,8 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
             Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
                 Some(Message::UsageSelectionModifiersChanged(modifiers))
             }
+            Event::Window(window::Event::Focused) => Some(Message::WindowFocusChanged(true)),
+            Event::Window(window::Event::Unfocused) => Some(Message::WindowFocusChanged(false)),
             _ => None,
         }),
         // Disk hotplug: subscribe to storage-service disk_added/disk_removed and refresh nav.
@@ -90,6 +130,15 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
                 else {
                     return;
                 };
+                let Ok(mut defrag_progress) = fs_client.proxy().receive_defrag_progress().await
+                else {
+                    return;
+                };
+                let Ok(mut vault_migration_progress) =
+                    fs_client.proxy().receive_vault_migration_progress().await
+                else {
+                    return;
+                };
                 let Ok(mut container_created) =
                     luks_client.proxy().receive_container_created().await
                 else {
@@ -120,6 +169,28 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
                                 }).await;
                             }
                         }
+                        item = defrag_progress.next() => {
+                            if let Some(signal) = item
+                                && let Ok(args) = signal.args()
+                            {
+                                _ = output.send(Message::DefragDialog(DefragDialogMessage::Progress {
+                                    device: args.device.to_string(),
+                                    processed: args.processed,
+                                    total: args.total,
+                                })).await;
+                            }
+                        }
+                        item = vault_migration_progress.next() => {
+                            if let Some(signal) = item
+                                && let Ok(args) = signal.args()
+                            {
+                                _ = output.send(Message::Vaults(VaultsMessage::MigrationProgress {
+                                    cipher_dir: args.cipher_dir.to_string(),
+                                    copied_bytes: args.copied_bytes,
+                                    total_bytes: args.total_bytes,
+                                })).await;
+                            }
+                        }
                         _ = container_created.next() => { _ = output.send(Message::DriveAdded(String::new())).await; }
                         _ = container_unlocked.next() => { _ = output.send(Message::DriveAdded(String::new())).await; }
                         _ = container_locked.next() => { _ = output.send(Message::DriveAdded(String::new())).await; }
@@ -127,12 +198,175 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
                 }
             }),
         ),
+        // RAID health: notify when an array degrades, loses a member or recovers.
+        Subscription::run_with_id(
+            std::any::TypeId::of::<RaidHealthSubscription>(),
+            cosmic::iced::stream::channel(4, move |mut output| async move {
+                let Ok(client) = RaidClient::new().await else {
+                    return;
+                };
+                let Ok(mut health_changed) = client.proxy().receive_array_health_changed().await
+                else {
+                    return;
+                };
+                while let Some(signal) = health_changed.next().await {
+                    if let Ok(args) = signal.args() {
+                        _ = output
+                            .send(Message::RaidHealthChanged {
+                                array: args.array.to_string(),
+                                event: args.event.to_string(),
+                            })
+                            .await;
+                    }
+                }
+            }),
+        ),
+        // Drive temperature: notify when a drive crosses its thresholds.
+        Subscription::run_with_id(
+            std::any::TypeId::of::<TemperatureAlertSubscription>(),
+            cosmic::iced::stream::channel(4, move |mut output| async move {
+                let Ok(client) = DisksClient::new().await else {
+                    return;
+                };
+                let Ok(mut alerts) = client.proxy().receive_temperature_alert().await else {
+                    return;
+                };
+                while let Some(signal) = alerts.next().await {
+                    if let Ok(args) = signal.args() {
+                        _ = output
+                            .send(Message::TemperatureAlert {
+                                device: args.device.to_string(),
+                                level: args.level.to_string(),
+                                celsius: args.celsius,
+                            })
+                            .await;
+                    }
+                }
+            }),
+        ),
+        // Low space: notify when a filesystem drops below its alert.
+        Subscription::run_with_id(
+            std::any::TypeId::of::<LowSpaceSubscription>(),
+            cosmic::iced::stream::channel(4, move |mut output| async move {
+                let Ok(client) = FilesystemsClient::new().await else {
+                    return;
+                };
+                let Ok(mut warnings) = client.proxy().receive_low_space().await else {
+                    return;
+                };
+                while let Some(signal) = warnings.next().await {
+                    if let Ok(args) = signal.args()
+                        && let Ok(warning) = serde_json::from_str(args.warning_json)
+                    {
+                        _ = output.send(Message::LowSpaceWarning(warning)).await;
+                    }
+                }
+            }),
+        ),
+        // Long operations: the ones running, then each start, progress and end.
+        Subscription::run_with_id(
+            std::any::TypeId::of::<OperationsSubscription>(),
+            cosmic::iced::stream::channel(16, move |mut output| async move {
+                let Ok(client) = OperationsClient::new().await else {
+                    return;
+                };
+                // Subscribe before listing, so that no operation starts unseen
+                let (Ok(progress), Ok(finished)) = (
+                    client.proxy().receive_operation_progress().await,
+                    client.proxy().receive_operation_finished().await,
+                ) else {
+                    return;
+                };
+                match client.list_operations().await {
+                    Ok(operations) => {
+                        _ = output.send(Message::OperationsLoaded(operations)).await;
+                    }
+                    Err(e) => tracing::debug!("Failed to list running operations: {e}"),
+                }
+
+                let progress =
+                    progress.map(|signal| signal.args().map(|args| args.event_json.to_string()));
+                let finished =
+                    finished.map(|signal| signal.args().map(|args| args.event_json.to_string()));
+                let mut events = cosmic::iced::futures::stream::select(progress, finished);
+                while let Some(json) = events.next().await {
+                    if let Ok(json) = json
+                        && let Ok(event) = serde_json::from_str(&json)
+                    {
+                        _ = output.send(Message::OperationEvent(event)).await;
+                    }
+                }
+            }),
+        ),
         // Watch for application configuration changes.
         app.core
             .watch_config::<Config>(<AppModel as Application>::APP_ID)
             .map(|update| Message::UpdateConfig(update.config)),
     ];
 
+    // Other programs ask the app to open a drive or volume.
+    subs.push(app_interface::subscription());
+
+    // FUSE mounts come and go without an event, so look at the mount table
+    // every few seconds and report changes.
+    subs.push(Subscription::run_with_id(
+        std::any::TypeId::of::<UserMountsSubscription>(),
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let mut last = None;
+            loop {
+                match tokio::task::spawn_blocking(list_user_mounts).await {
+                    Ok(Ok(mounts)) if last.as_ref() != Some(&mounts) => {
+                        last = Some(mounts.clone());
+                        _ = output.send(Message::UserMountsLoaded(mounts)).await;
+                    }
+                    Ok(Err(e)) => tracing::debug!("Failed to read user mounts: {e}"),
+                    _ => {}
+                }
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+        }),
+    ));
+
+    // Bind mounts are made and removed outside the app too; poll them like
+    // the mount table.
+    subs.push(Subscription::run_with_id(
+        std::any::TypeId::of::<BindMountsSubscription>(),
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let mut last = None;
+            loop {
+                match tokio::task::spawn_blocking(list_bind_mounts).await {
+                    Ok(Ok(mounts)) if last.as_ref() != Some(&mounts) => {
+                        last = Some(mounts.clone());
+                        _ = output.send(Message::BindMountsLoaded(mounts)).await;
+                    }
+                    Ok(Err(e)) => tracing::debug!("Failed to read bind mounts: {e}"),
+                    _ => {}
+                }
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+        }),
+    ));
+
+    // Phones and cameras are plugged in, mounted and ejected outside the app
+    // too; poll gvfs like the mount table.
+    subs.push(Subscription::run_with_id(
+        std::any::TypeId::of::<MtpDevicesSubscription>(),
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let mut last = None;
+            loop {
+                match list_mtp_devices().await {
+                    Ok(devices) if last.as_ref() != Some(&devices) => {
+                        last = Some(devices.clone());
+                        _ = output.send(Message::MtpDevicesLoaded(devices)).await;
+                    }
+                    Err(e) => tracing::debug!("Failed to list phones and cameras: {e}"),
+                    _ => {}
+                }
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }),
+    ));
+
     // When an image operation is running, poll progress and wait for operation_completed.
     if let Some(ref operation_id) = app.image_op_operation_id {
         let operation_id = operation_id.clone();
@@ -151,9 +385,26 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
                             .await;
                         return;
                     };
+                    // Progress comes from the shared counter at up to 60
+                    // frames a second, or from polling the service
+                    let counter = client
+                        .progress_counter(&operation_id)
+                        .await
+                        .inspect_err(|e| tracing::debug!("No progress counter: {e}"))
+                        .ok()
+                        .flatten();
+                    // Counters carry no remaining time; it is estimated
+                    // here from the service's past runs
+                    let history = client
+                        .get_throughput_history(&operation_id)
+                        .await
+                        .unwrap_or_default();
+                    let started = Instant::now();
+                    let completion = client.wait_for_operation_completion(&operation_id);
+                    tokio::pin!(completion);
                     loop {
                         tokio::select! {
-                            result = client.wait_for_operation_completion(&operation_id) => {
+                            result = &mut completion => {
                                 let result = result.map_err(|e| e.to_string());
                                 _ = output
                                     .send(Message::ImageOperationDialog(
@@ -162,9 +413,26 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
                                     .await;
                                 return;
                             }
-                            _ = tokio::time::sleep(Duration::from_millis(400)) => {
-                                if let Ok(status) = client.get_operation_status(&operation_id).await
-                                {
+                            status = async {
+                                match &counter {
+                                    Some(counter) => {
+                                        tokio::time::sleep(Duration::from_micros(16_667)).await;
+                                        counter.changed().await
+                                    }
+                                    None => {
+                                        tokio::time::sleep(Duration::from_millis(400)).await;
+                                        client.get_operation_status(&operation_id).await
+                                    }
+                                }
+                            } => {
+                                if let Ok(status) = status {
+                                    let eta = status.eta.or_else(|| {
+                                        history.estimate(
+                                            status.bytes_completed,
+                                            status.total_bytes,
+                                            started.elapsed().as_secs_f64(),
+                                        )
+                                    });
                                     _ = output
                                         .send(Message::ImageOperationDialog(
                                             ImageOperationDialogMessage::Progress(
@@ -172,6 +440,7 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
                                                 status.bytes_completed,
                                                 status.total_bytes,
                                                 status.speed_bytes_per_sec,
+                                                eta,
                                             ),
                                         ))
                                         .await;
@@ -184,5 +453,42 @@ pub(crate) fn subscription(app: &AppModel) -> Subscription<Message> {
         ));
     }
 
+    // A guarded destructive dialog counts down.
+    if let Some(guard) = app.confirmation.as_ref().filter(|guard| guard.pending()) {
+        subs.push(Subscription::run_with_id(
+            (
+                std::any::TypeId::of::<ConfirmationSubscription>(),
+                guard.target.clone(),
+            ),
+            cosmic::iced::stream::channel(4, move |mut output| async move {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    _ = output
+                        .send(Message::Confirmation(ConfirmationMessage::Tick))
+                        .await;
+                }
+            }),
+        ));
+    }
+
+    // While a transfer runs, poll the transfer list until all have finished.
+    if app.network.has_running_transfers() {
+        subs.push(Subscription::run_with_id(
+            std::any::TypeId::of::<TransferProgressSubscription>(),
+            cosmic::iced::stream::channel(32, |mut output| async move {
+                let Ok(client) = RcloneClient::new().await else {
+                    return;
+                };
+                loop {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    let result = client.list_transfers().await.map_err(|e| e.to_string());
+                    _ = output
+                        .send(Message::Network(NetworkMessage::TransfersLoaded(result)))
+                        .await;
+                }
+            }),
+        ));
+    }
+
     Subscription::batch(subs)
 }
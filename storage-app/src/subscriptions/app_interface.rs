@@ -0,0 +1,124 @@
+//! `org.cosmic.ext.Storage.App` on the session bus
+//!
+//! Lets file managers and settings panels open the app at a drive or volume:
+//!
+//! ```text
+//! busctl --user call org.cosmic.ext.Storage.App /org/cosmic/ext/Storage/App \
+//!     org.cosmic.ext.Storage.App Reveal s /run/media/user/DISK
+//! busctl --user call org.cosmic.ext.Storage.App /org/cosmic/ext/Storage/App \
+//!     org.cosmic.ext.Storage.App SetUp s /dev/sdc
+//! ```
+//!
+//! The bus starts the app for the call when it is not running.
+
+use crate::cli::Args;
+use crate::message::app::Message;
+use cosmic::iced::Subscription;
+use cosmic::iced::futures::SinkExt;
+use tokio::sync::mpsc;
+use zbus::interface;
+
+/// Well-known name of the app on the session bus
+const APP_BUS_NAME: &str = "org.cosmic.ext.Storage.App";
+
+const APP_OBJECT_PATH: &str = "/org/cosmic/ext/Storage/App";
+
+/// Subscription serving the app interface.
+struct AppInterfaceSubscription;
+
+struct AppInterface {
+    requests: mpsc::Sender<Message>,
+}
+
+impl AppInterface {
+    async fn request(&self, path: &str, message: Message) -> zbus::fdo::Result<()> {
+        if !path.starts_with('/') {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "{path} is not an absolute path"
+            )));
+        }
+        self.requests
+            .send(message)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+    }
+}
+
+#[interface(name = "org.cosmic.ext.Storage.App")]
+impl AppInterface {
+    /// Select the drive or volume of a device path (e.g. "/dev/sdb2") or a
+    /// mount point, and raise the window
+    async fn reveal(&self, target: &str) -> zbus::fdo::Result<()> {
+        self.request(target, Message::Reveal(target.to_string()))
+            .await
+    }
+
+    /// Open the setup wizard for a drive that holds nothing yet
+    async fn set_up(&self, device: &str) -> zbus::fdo::Result<()> {
+        self.request(device, Message::SetUpDrive(device.to_string()))
+            .await
+    }
+}
+
+/// Hand what `args` ask for to an app that already runs; false when none
+/// runs or nothing was asked for
+pub(crate) fn forward_to_running_app(args: &Args) -> bool {
+    let calls: Vec<(&str, &str)> = [("Reveal", &args.reveal), ("SetUp", &args.setup)]
+        .into_iter()
+        .filter_map(|(method, path)| Some((method, path.as_deref()?)))
+        .collect();
+    if calls.is_empty() {
+        return false;
+    }
+
+    let forward = || -> zbus::Result<bool> {
+        let connection = zbus::blocking::Connection::session()?;
+        // Asking without an owner would have the bus start another app
+        let bus = zbus::blocking::fdo::DBusProxy::new(&connection)?;
+        if !bus.name_has_owner(APP_BUS_NAME.try_into()?)? {
+            return Ok(false);
+        }
+        for (method, path) in &calls {
+            connection.call_method(
+                Some(APP_BUS_NAME),
+                APP_OBJECT_PATH,
+                Some(APP_BUS_NAME),
+                *method,
+                &(*path,),
+            )?;
+        }
+        Ok(true)
+    };
+    forward().unwrap_or_else(|e| {
+        tracing::debug!(%e, "no running app to forward {calls:?} to");
+        false
+    })
+}
+
+pub(crate) fn subscription() -> Subscription<Message> {
+    Subscription::run_with_id(
+        std::any::TypeId::of::<AppInterfaceSubscription>(),
+        cosmic::iced::stream::channel(4, |mut output| async move {
+            let (requests, mut received) = mpsc::channel(4);
+            let connection = match zbus::connection::Builder::session()
+                .and_then(|builder| builder.name(APP_BUS_NAME))
+                .and_then(|builder| builder.serve_at(APP_OBJECT_PATH, AppInterface { requests }))
+            {
+                Ok(builder) => builder.build().await,
+                Err(e) => Err(e),
+            };
+            // Kept alive while requests are forwarded
+            let _connection = match connection {
+                Ok(connection) => connection,
+                Err(e) => {
+                    tracing::warn!(%e, "failed to serve {APP_BUS_NAME}");
+                    return;
+                }
+            };
+
+            while let Some(message) = received.recv().await {
+                _ = output.send(message).await;
+            }
+        }),
+    )
+}
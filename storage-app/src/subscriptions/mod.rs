@@ -1 +1,2 @@
 pub(crate) mod app;
+pub(crate) mod app_interface;
@@ -0,0 +1,165 @@
+use crate::client::FilesystemsClient;
+use crate::message::app::Message;
+use crate::message::dialogs::AutomountDialogMessage;
+use crate::state::app::AppModel;
+use crate::state::dialogs::{AutomountDialog, ShowDialog};
+use crate::state::volumes::VolumesControl;
+use cosmic::app::Task;
+use std::path::{Path, PathBuf};
+use storage_types::{AutomountUnit, IDLE_TIMEOUT_PRESETS};
+
+/// UUID of the filesystem on `device`, from its /dev/disk/by-uuid link
+fn filesystem_uuid(device: &str) -> Option<String> {
+    let device = std::fs::canonicalize(device).ok()?;
+    std::fs::read_dir("/dev/disk/by-uuid")
+        .ok()?
+        .flatten()
+        .find(|entry| std::fs::canonicalize(entry.path()).is_ok_and(|path| path == device))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+}
+
+/// Open the automount dialog for the selected volume
+pub(super) fn open_for_volume(app: &mut AppModel) -> Task<Message> {
+    let Some(volumes_control) = app.nav.active_data::<VolumesControl>() else {
+        return Task::none();
+    };
+    let Some(volume) = volumes_control
+        .selected_volume_node()
+        .map(|node| &node.volume)
+        .or_else(|| {
+            volumes_control
+                .segments
+                .get(volumes_control.selected_segment)
+                .and_then(|segment| segment.volume.as_ref())
+        })
+    else {
+        return Task::none();
+    };
+    let Some(device) = volume.device_path.clone() else {
+        return Task::none();
+    };
+
+    let name = if volume.label.trim().is_empty() {
+        Path::new(&device)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        volume.label.trim().replace('/', "-")
+    };
+    let unit = AutomountUnit::for_volume(
+        &device,
+        filesystem_uuid(&device).as_deref(),
+        &volume.id_type,
+        PathBuf::from("/mnt").join(name),
+    );
+    open(app, unit)
+}
+
+/// Open the automount dialog for `unit`, listing the units created before
+pub(super) fn open(app: &mut AppModel, unit: AutomountUnit) -> Task<Message> {
+    if app.dialog.is_some() {
+        return Task::none();
+    }
+    app.dialog = Some(ShowDialog::Automount(AutomountDialog {
+        unit,
+        units: None,
+        running: false,
+        error: None,
+    }));
+    load_units()
+}
+
+fn load_units() -> Task<Message> {
+    Task::perform(
+        async move {
+            FilesystemsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                .list_automount_units()
+                .await
+                .map_err(|e| format!("Failed to load automount units: {}", e))
+        },
+        |res| AutomountDialogMessage::UnitsLoaded(res).into(),
+    )
+}
+
+pub(super) fn automount_dialog(app: &mut AppModel, msg: AutomountDialogMessage) -> Task<Message> {
+    let Some(ShowDialog::Automount(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        AutomountDialogMessage::UnitsLoaded(Ok(units)) => state.units = Some(units),
+        AutomountDialogMessage::UnitsLoaded(Err(e)) => {
+            tracing::warn!(%e, "could not load automount units");
+            state.error = Some(e);
+        }
+        AutomountDialogMessage::MountPointUpdate(mount_point) => {
+            state.unit.mount_point = PathBuf::from(mount_point);
+        }
+        AutomountDialogMessage::OptionsUpdate(options) => state.unit.options = options,
+        AutomountDialogMessage::SetIdleTimeout(index) => {
+            if let Some(secs) = IDLE_TIMEOUT_PRESETS.get(index) {
+                state.unit.idle_timeout_secs = *secs;
+            }
+        }
+        AutomountDialogMessage::Create => {
+            if state.running {
+                return Task::none();
+            }
+            if let Err(e) = state.unit.validate() {
+                state.error = Some(e);
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+            let unit = state.unit.clone();
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .create_automount_unit(&unit)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |res| AutomountDialogMessage::Changed(res).into(),
+            );
+        }
+        AutomountDialogMessage::Remove(mount_point) => {
+            if state.running {
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+            let mount_point = mount_point.to_string_lossy().to_string();
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .remove_automount_unit(&mount_point)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |res| AutomountDialogMessage::Changed(res).into(),
+            );
+        }
+        AutomountDialogMessage::Changed(res) => {
+            state.running = false;
+            if let Err(e) = res {
+                tracing::error!(%e, "automount unit error");
+                state.error = Some(e);
+            }
+            return load_units();
+        }
+        AutomountDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,112 @@
+use crate::client::FilesystemsClient;
+use crate::fl;
+use crate::message::app::Message;
+use crate::message::dialogs::BindMountDialogMessage;
+use crate::state::app::AppModel;
+use crate::state::dialogs::{NewBindMountDialog, ShowDialog};
+use cosmic::app::Task;
+use std::path::PathBuf;
+
+pub(super) fn open_new_bind_mount(app: &mut AppModel) {
+    app.dialog = Some(ShowDialog::NewBindMount(NewBindMountDialog {
+        source: String::new(),
+        target: String::new(),
+        read_only: false,
+        persistent: true,
+        running: false,
+        error: None,
+    }));
+}
+
+pub(super) fn bind_mount_dialog(app: &mut AppModel, msg: BindMountDialogMessage) -> Task<Message> {
+    let Some(ShowDialog::NewBindMount(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        BindMountDialogMessage::SourceUpdate(source) => state.source = source,
+        BindMountDialogMessage::TargetUpdate(target) => state.target = target,
+        BindMountDialogMessage::ReadOnlyUpdate(read_only) => state.read_only = read_only,
+        BindMountDialogMessage::PersistentUpdate(persistent) => state.persistent = persistent,
+        BindMountDialogMessage::Cancel => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+        BindMountDialogMessage::Create => {
+            if state.running {
+                return Task::none();
+            }
+            let source = state.source.trim().to_string();
+            let target = state.target.trim().to_string();
+            if !source.starts_with('/') || !target.starts_with('/') {
+                state.error = Some(fl!("bind-absolute-paths"));
+                return Task::none();
+            }
+            let (read_only, persistent) = (state.read_only, state.persistent);
+            state.running = true;
+            state.error = None;
+
+            return Task::perform(
+                async move {
+                    let client = FilesystemsClient::new().await.map_err(|e| e.to_string())?;
+                    client
+                        .create_bind_mount(&source, &target, read_only, persistent)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |result| BindMountDialogMessage::Created(result).into(),
+            );
+        }
+        BindMountDialogMessage::Created(Ok(())) => {
+            tracing::info!("Bound {} to {}", state.source, state.target);
+            app.dialog = None;
+        }
+        BindMountDialogMessage::Created(Err(e)) => {
+            tracing::error!("Failed to create bind mount: {e}");
+            state.running = false;
+            state.error = Some(e);
+        }
+    }
+
+    Task::none()
+}
+
+/// Unmount a bind mount, and with `forget` remove its fstab entry
+pub(super) fn remove(app: &mut AppModel, target: PathBuf, forget: bool) -> Task<Message> {
+    if !app.bind_mounts.busy.insert(target.clone()) {
+        return Task::none();
+    }
+
+    let path = target.to_string_lossy().to_string();
+    Task::perform(
+        async move {
+            let client = FilesystemsClient::new().await.map_err(|e| e.to_string())?;
+            client
+                .remove_bind_mount(&path, forget)
+                .await
+                .map_err(|e| e.to_string())
+        },
+        move |result| {
+            Message::BindMountRemoved {
+                target: target.clone(),
+                result,
+            }
+            .into()
+        },
+    )
+}
+
+pub(super) fn removed(app: &mut AppModel, target: PathBuf, result: Result<(), String>) {
+    app.bind_mounts.busy.remove(&target);
+    match result {
+        Ok(()) => tracing::info!("Removed bind mount {}", target.display()),
+        Err(e) => {
+            tracing::error!("Failed to remove bind mount {}: {}", target.display(), e);
+            app.dialog = Some(ShowDialog::Info {
+                title: fl!("bind-remove-failed"),
+                body: e,
+            });
+        }
+    }
+}
@@ -7,6 +7,41 @@ use crate::state::app::AppModel;
 use crate::state::dialogs::{ConfirmActionDialog, FilesystemTarget, ShowDialog};
 use crate::state::volumes::VolumesControl;
 use cosmic::app::Task;
+use storage_types::{BTRFS_COMPRESSION_ALGORITHMS, RestoreConflictPolicy};
+
+/// Target for confirmation dialogs raised from the BTRFS tab
+fn selected_volume_target(app: &AppModel) -> Option<FilesystemTarget> {
+    let volumes_control = app.nav.active_data::<VolumesControl>()?;
+    let segment = volumes_control
+        .segments
+        .get(volumes_control.selected_segment)?;
+    segment.volume.clone().map(FilesystemTarget::Volume)
+}
+
+/// Compression target path and selected algorithm of the active BTRFS tab
+fn compression_selection(app: &AppModel) -> Option<(String, &'static str, bool)> {
+    let btrfs_state = app
+        .nav
+        .active_data::<VolumesControl>()?
+        .btrfs_state
+        .as_ref()?;
+    let algorithm = BTRFS_COMPRESSION_ALGORITHMS
+        .get(btrfs_state.compression_algorithm)
+        .copied()?;
+    Some((
+        btrfs_state.compression_target.clone(),
+        algorithm,
+        btrfs_state.compression_force,
+    ))
+}
+
+fn set_compression_busy(app: &mut AppModel, busy: bool) {
+    if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+        && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+    {
+        btrfs_state.compression_busy = busy;
+    }
+}
 
 /// Handle BTRFS management messages
 pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task<Message> {
@@ -68,20 +103,7 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
             let subvol_name = path.rsplit('/').next().unwrap_or(&path).to_string();
 
             // Get a dummy FilesystemTarget (required by ConfirmActionDialog but not used for BTRFS)
-            let target = if let Some(volumes_control) = app.nav.active_data::<VolumesControl>() {
-                if let Some(segment) = volumes_control
-                    .segments
-                    .get(volumes_control.selected_segment)
-                {
-                    if let Some(volume) = &segment.volume {
-                        FilesystemTarget::Volume(volume.clone())
-                    } else {
-                        return Task::none();
-                    }
-                } else {
-                    return Task::none();
-                }
-            } else {
+            let Some(target) = selected_volume_target(app) else {
                 return Task::none();
             };
 
@@ -89,6 +111,7 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
                 title: fl!("btrfs-delete-subvolume"),
                 body: fl!("btrfs-delete-confirm", name = subvol_name.as_str()),
                 target,
+                confirm_target: Some(subvol_name),
                 ok_message: Message::BtrfsDeleteSubvolumeConfirm {
                     block_path,
                     mount_point,
@@ -148,8 +171,16 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
                 btrfs_state.loading_usage = true;
             }
 
+            // Device error counters are loaded along with the usage
+            let load_device_stats = handle_btrfs_message(
+                app,
+                Message::BtrfsLoadDeviceStats {
+                    mount_point: mount_point.clone(),
+                },
+            );
+
             // Load usage in background task
-            Task::perform(
+            let load_usage = Task::perform(
                 async move {
                     let btrfs_client = match BtrfsClient::new().await {
                         Ok(client) => client,
@@ -175,7 +206,8 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
                     }
                 },
                 |msg| msg.into(),
-            )
+            );
+            Task::batch(vec![load_usage, load_device_stats])
         }
 
         Message::BtrfsUsageLoaded {
@@ -191,6 +223,109 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
             Task::none()
         }
 
+        Message::BtrfsLoadDeviceStats { mount_point } => {
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    let stats = btrfs_client.get_device_stats(&mount_point).await?;
+                    Ok(stats)
+                },
+                move |result: anyhow::Result<Vec<storage_types::BtrfsDeviceStats>>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsDeviceStatsLoaded {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsDeviceStatsLoaded {
+            mount_point,
+            result,
+        } => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && btrfs_state.mount_point.as_deref() == Some(&mount_point)
+            {
+                btrfs_state.device_stats = Some(result);
+            }
+            Task::none()
+        }
+
+        Message::BtrfsResetDeviceStats { mount_point } => {
+            let Some(dialog_target) = selected_volume_target(app) else {
+                return Task::none();
+            };
+
+            app.dialog = Some(ShowDialog::ConfirmAction(ConfirmActionDialog {
+                title: fl!("btrfs-reset-device-stats"),
+                body: fl!(
+                    "btrfs-reset-device-stats-confirm",
+                    mount_point = mount_point.as_str()
+                ),
+                target: dialog_target,
+                confirm_target: None,
+                ok_message: Message::BtrfsResetDeviceStatsConfirm { mount_point },
+                running: false,
+            }));
+
+            Task::none()
+        }
+
+        Message::BtrfsResetDeviceStatsConfirm { mount_point } => {
+            app.dialog = None;
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+            {
+                btrfs_state.device_stats_busy = true;
+            }
+
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    btrfs_client.reset_device_stats(&mount_point).await?;
+                    Ok(())
+                },
+                move |result: anyhow::Result<()>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsDeviceStatsReset {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsDeviceStatsReset {
+            mount_point,
+            result,
+        } => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+            {
+                btrfs_state.device_stats_busy = false;
+            }
+            match result {
+                Ok(()) => handle_btrfs_message(app, Message::BtrfsLoadDeviceStats { mount_point }),
+                Err(e) => {
+                    let ctx = UiErrorContext::new("btrfs_reset_device_stats");
+                    Task::done(
+                        log_error_and_show_dialog(
+                            fl!("btrfs-reset-device-stats-failed"),
+                            anyhow::anyhow!(e),
+                            ctx,
+                        )
+                        .into(),
+                    )
+                }
+            }
+        }
+
         Message::BtrfsToggleSubvolumeExpanded {
             mount_point,
             subvolume_id,
@@ -377,21 +512,45 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
         }
 
         Message::BtrfsShowProperties {
-            mount_point: _,
+            mount_point,
             subvolume_id,
         } => {
             // Find and store the selected subvolume
+            let mut path = None;
             if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
                 && let Some(btrfs_state) = &mut volumes_control.btrfs_state
                 && let Some(Ok(subvolumes)) = &btrfs_state.subvolumes
             {
                 let subvol = subvolumes.iter().find(|s| s.id == subvolume_id).cloned();
                 if let Some(subvol) = subvol {
+                    path = Some(subvol.path.clone());
                     btrfs_state.selected_subvolume = Some(subvol);
                     btrfs_state.show_properties_dialog = true;
+                    btrfs_state.nocow = None;
+                    btrfs_state.restore_path.clear();
+                    btrfs_state.restore_result = None;
                 }
             }
-            Task::none()
+
+            let Some(path) = path else {
+                return Task::none();
+            };
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    let status = btrfs_client.get_nocow(&mount_point, &path).await?;
+                    Ok(status)
+                },
+                move |result: anyhow::Result<storage_types::NocowStatus>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsNocowLoaded {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
         }
 
         Message::BtrfsCloseProperties { mount_point } => {
@@ -401,10 +560,263 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
             {
                 btrfs_state.show_properties_dialog = false;
                 btrfs_state.selected_subvolume = None;
+                btrfs_state.nocow = None;
+            }
+            Task::none()
+        }
+
+        Message::BtrfsNocowLoaded {
+            mount_point,
+            result,
+        } => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && btrfs_state.mount_point.as_deref() == Some(&mount_point)
+            {
+                btrfs_state.nocow_busy = false;
+                btrfs_state.nocow = Some(result);
+            }
+            Task::none()
+        }
+
+        Message::BtrfsRollback { mount_point } => {
+            let Some(btrfs_state) = app
+                .nav
+                .active_data::<VolumesControl>()
+                .and_then(|volumes_control| volumes_control.btrfs_state.as_ref())
+            else {
+                return Task::none();
+            };
+            let Some(snapshot) = &btrfs_state.selected_subvolume else {
+                return Task::none();
+            };
+            let Some(origin) = btrfs_state.snapshot_origin(snapshot) else {
+                return Task::none();
+            };
+            let (target, snapshot) = (origin.path.clone(), snapshot.path.clone());
+            let Some(dialog_target) = selected_volume_target(app) else {
+                return Task::none();
+            };
+
+            app.dialog = Some(ShowDialog::ConfirmAction(ConfirmActionDialog {
+                title: fl!("btrfs-rollback"),
+                body: fl!(
+                    "btrfs-rollback-confirm",
+                    target = target.as_str(),
+                    snapshot = snapshot.as_str()
+                ),
+                target: dialog_target,
+                confirm_target: Some(target.clone()),
+                ok_message: Message::BtrfsRollbackConfirm {
+                    mount_point,
+                    target,
+                    snapshot,
+                },
+                running: false,
+            }));
+
+            Task::none()
+        }
+
+        Message::BtrfsRollbackConfirm {
+            mount_point,
+            target,
+            snapshot,
+        } => {
+            if let Some(ShowDialog::ConfirmAction(state)) = &mut app.dialog {
+                state.running = true;
+            }
+
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    let result = btrfs_client
+                        .rollback(&mount_point, &target, &snapshot)
+                        .await?;
+                    Ok(result)
+                },
+                move |result: anyhow::Result<storage_types::RollbackResult>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsRollbackFinished {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsRollbackFinished {
+            mount_point,
+            result,
+        } => match result {
+            Ok(rollback) => {
+                let mut body = fl!(
+                    "btrfs-rollback-done",
+                    subvolume = rollback.subvolume.as_str(),
+                    safety = rollback.safety_snapshot.as_str()
+                );
+                if let Some(previous) = &rollback.previous_moved_to {
+                    body.push_str("\n\n");
+                    body.push_str(&fl!("btrfs-rollback-moved", path = previous.as_str()));
+                }
+                if rollback.booted_root {
+                    body.push_str("\n\n");
+                    body.push_str(&fl!("btrfs-rollback-reboot-root"));
+                } else if rollback.reboot_required {
+                    body.push_str("\n\n");
+                    body.push_str(&fl!(
+                        "btrfs-rollback-remount",
+                        mounts = rollback.active_mounts.join(", ")
+                    ));
+                }
+                if rollback.default_updated {
+                    body.push_str("\n\n");
+                    body.push_str(&fl!("btrfs-rollback-default-updated"));
+                }
+
+                app.dialog = Some(ShowDialog::Info {
+                    title: fl!("btrfs-rollback"),
+                    body,
+                });
+                Task::batch(vec![
+                    handle_btrfs_message(
+                        app,
+                        Message::BtrfsCloseProperties {
+                            mount_point: mount_point.clone(),
+                        },
+                    ),
+                    handle_btrfs_message(app, Message::BtrfsRefreshAll { mount_point }),
+                ])
+            }
+            Err(e) => {
+                let ctx = UiErrorContext::new("btrfs_rollback");
+                Task::done(
+                    log_error_and_show_dialog(
+                        fl!("btrfs-rollback-failed"),
+                        anyhow::anyhow!(e),
+                        ctx,
+                    )
+                    .into(),
+                )
+            }
+        },
+
+        Message::BtrfsRestorePathChanged(path) => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+            {
+                btrfs_state.restore_path = path;
+            }
+            Task::none()
+        }
+
+        Message::BtrfsRestoreConflictChanged(index) => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && index < RestoreConflictPolicy::ALL.len()
+            {
+                btrfs_state.restore_conflict = index;
+            }
+            Task::none()
+        }
+
+        Message::BtrfsRestoreFromSnapshot { mount_point } => {
+            let mut request = None;
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && let Some(snapshot) = &btrfs_state.selected_subvolume
+                && let Some(origin) = btrfs_state.snapshot_origin(snapshot)
+                && !btrfs_state.restore_path.trim().is_empty()
+            {
+                request = Some((
+                    snapshot.path.clone(),
+                    origin.path.clone(),
+                    btrfs_state.restore_path.trim().to_string(),
+                    RestoreConflictPolicy::ALL[btrfs_state.restore_conflict],
+                ));
+                btrfs_state.restore_busy = true;
+                btrfs_state.restore_result = None;
+            }
+
+            let Some((snapshot, target, path, conflict)) = request else {
+                return Task::none();
+            };
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    let result = btrfs_client
+                        .restore_from_snapshot(
+                            &mount_point,
+                            &snapshot,
+                            &target,
+                            vec![path],
+                            conflict,
+                        )
+                        .await?;
+                    Ok(result)
+                },
+                move |result: anyhow::Result<storage_types::RestoreResult>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsRestoreFinished {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsRestoreFinished {
+            mount_point,
+            result,
+        } => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && btrfs_state.mount_point.as_deref() == Some(&mount_point)
+            {
+                btrfs_state.restore_busy = false;
+                btrfs_state.restore_result = Some(result);
             }
             Task::none()
         }
 
+        Message::BtrfsSetNocow {
+            mount_point,
+            enabled,
+        } => {
+            let mut path = None;
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && let Some(subvol) = &btrfs_state.selected_subvolume
+            {
+                path = Some(subvol.path.clone());
+                btrfs_state.nocow_busy = true;
+            }
+
+            let Some(path) = path else {
+                return Task::none();
+            };
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    let status = btrfs_client.set_nocow(&mount_point, &path, enabled).await?;
+                    Ok(status)
+                },
+                move |result: anyhow::Result<storage_types::NocowStatus>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsNocowLoaded {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
         Message::BtrfsLoadDeletedSubvolumes { mount_point } => {
             let mount_point_for_async = mount_point.clone();
             Task::perform(
@@ -467,6 +879,12 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
         Message::BtrfsRefreshAll { mount_point } => {
             // Reload all BTRFS data
             Task::batch(vec![
+                handle_btrfs_message(
+                    app,
+                    Message::BtrfsLoadCompression {
+                        mount_point: mount_point.clone(),
+                    },
+                ),
                 handle_btrfs_message(
                     app,
                     Message::BtrfsLoadSubvolumes {
@@ -480,9 +898,278 @@ pub(super) fn handle_btrfs_message(app: &mut AppModel, message: Message) -> Task
                         mount_point: mount_point.clone(),
                     },
                 ),
+                handle_btrfs_message(
+                    app,
+                    Message::BtrfsLoadDeviceStats {
+                        mount_point: mount_point.clone(),
+                    },
+                ),
             ])
         }
 
+        Message::BtrfsLoadCompression { mount_point } => {
+            let target = compression_selection(app)
+                .map(|(target, _, _)| target)
+                .unwrap_or_default();
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    let info = btrfs_client.get_compression(&mount_point, &target).await?;
+                    Ok(info)
+                },
+                move |result: anyhow::Result<storage_types::CompressionInfo>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsCompressionLoaded {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsCompressionLoaded {
+            mount_point,
+            result,
+        } => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && btrfs_state.mount_point.as_deref() == Some(&mount_point)
+            {
+                // Preselect the algorithm currently in effect for the target
+                if let Ok(info) = &result
+                    && let Some(current) = info.property.as_deref().or(info
+                        .mount_option
+                        .as_deref()
+                        .and_then(|value| value.split(':').next()))
+                    && let Some(index) = BTRFS_COMPRESSION_ALGORITHMS
+                        .iter()
+                        .position(|algorithm| *algorithm == current)
+                {
+                    btrfs_state.compression_algorithm = index;
+                }
+                if let Ok(info) = &result {
+                    btrfs_state.compression_force = info.force;
+                }
+                btrfs_state.compression = Some(result);
+            }
+            Task::none()
+        }
+
+        Message::BtrfsCompressionTargetChanged(target) => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+            {
+                btrfs_state.compression_target = target;
+                btrfs_state.compression_estimate = None;
+            }
+            Task::none()
+        }
+
+        Message::BtrfsCompressionAlgorithmChanged(index) => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && index < BTRFS_COMPRESSION_ALGORITHMS.len()
+            {
+                btrfs_state.compression_algorithm = index;
+            }
+            Task::none()
+        }
+
+        Message::BtrfsCompressionForceToggled(force) => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+            {
+                btrfs_state.compression_force = force;
+            }
+            Task::none()
+        }
+
+        Message::BtrfsApplyCompressionProperty { mount_point } => {
+            let Some((target, algorithm, _)) = compression_selection(app) else {
+                return Task::none();
+            };
+            set_compression_busy(app, true);
+
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    btrfs_client
+                        .set_compression(&mount_point, &target, algorithm)
+                        .await?;
+                    Ok(())
+                },
+                move |result: anyhow::Result<()>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsCompressionApplied {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsApplyMountCompression { mount_point } => {
+            let Some((_, algorithm, force)) = compression_selection(app) else {
+                return Task::none();
+            };
+            set_compression_busy(app, true);
+
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    btrfs_client
+                        .set_mount_compression(&mount_point, algorithm, force)
+                        .await?;
+                    Ok(())
+                },
+                move |result: anyhow::Result<()>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsCompressionApplied {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsEstimateCompression { mount_point } => {
+            let Some((target, _, _)) = compression_selection(app) else {
+                return Task::none();
+            };
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+            {
+                btrfs_state.compression_busy = true;
+                btrfs_state.compression_estimate = None;
+            }
+
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    let estimate = btrfs_client
+                        .estimate_compression(&mount_point, &target)
+                        .await?;
+                    Ok(estimate)
+                },
+                move |result: anyhow::Result<storage_types::CompressionEstimate>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsCompressionEstimated {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsCompressionEstimated {
+            mount_point,
+            result,
+        } => {
+            if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                && btrfs_state.mount_point.as_deref() == Some(&mount_point)
+            {
+                btrfs_state.compression_busy = false;
+                btrfs_state.compression_estimate = Some(result);
+            }
+            Task::none()
+        }
+
+        Message::BtrfsCompressExisting { mount_point } => {
+            let Some((target, algorithm, _)) = compression_selection(app) else {
+                return Task::none();
+            };
+            if algorithm == "none" {
+                return Task::none();
+            }
+            let Some(dialog_target) = selected_volume_target(app) else {
+                return Task::none();
+            };
+            let path = if target.is_empty() {
+                mount_point.clone()
+            } else {
+                target
+            };
+
+            app.dialog = Some(ShowDialog::ConfirmAction(ConfirmActionDialog {
+                title: fl!("btrfs-compress-existing"),
+                body: fl!(
+                    "btrfs-compress-existing-confirm",
+                    path = path.as_str(),
+                    algorithm = algorithm
+                ),
+                target: dialog_target,
+                confirm_target: None,
+                ok_message: Message::BtrfsCompressExistingConfirm { mount_point },
+                running: false,
+            }));
+
+            Task::none()
+        }
+
+        Message::BtrfsCompressExistingConfirm { mount_point } => {
+            let Some((target, algorithm, _)) = compression_selection(app) else {
+                return Task::none();
+            };
+            app.dialog = None;
+            set_compression_busy(app, true);
+
+            let mount_point_for_callback = mount_point.clone();
+            Task::perform(
+                async move {
+                    let btrfs_client = BtrfsClient::new().await?;
+                    btrfs_client
+                        .compress_existing(&mount_point, &target, algorithm)
+                        .await?;
+                    Ok(())
+                },
+                move |result: anyhow::Result<()>| {
+                    let result = result.map_err(|e| format!("{:#}", e));
+                    Message::BtrfsCompressionApplied {
+                        mount_point: mount_point_for_callback.clone(),
+                        result,
+                    }
+                    .into()
+                },
+            )
+        }
+
+        Message::BtrfsCompressionApplied {
+            mount_point,
+            result,
+        } => {
+            set_compression_busy(app, false);
+            match result {
+                Ok(()) => {
+                    if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>()
+                        && let Some(btrfs_state) = &mut volumes_control.btrfs_state
+                    {
+                        btrfs_state.compression_estimate = None;
+                    }
+                    handle_btrfs_message(app, Message::BtrfsLoadCompression { mount_point })
+                }
+                Err(e) => {
+                    let ctx = UiErrorContext::new("btrfs_compression");
+                    Task::done(
+                        log_error_and_show_dialog(
+                            fl!("btrfs-compression-failed"),
+                            anyhow::anyhow!(e),
+                            ctx,
+                        )
+                        .into(),
+                    )
+                }
+            }
+        }
+
         _ => Task::none(),
     }
 }
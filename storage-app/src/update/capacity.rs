@@ -0,0 +1,48 @@
+use crate::client::FilesystemsClient;
+use crate::message::app::Message;
+use crate::notification_policy;
+use crate::state::app::AppModel;
+use cosmic::app::Task;
+use storage_types::CapacityForecast;
+
+/// Fetch when the mounted filesystems run full, after the drives changed
+pub(super) fn load() -> Task<Message> {
+    Task::perform(
+        async {
+            let client = FilesystemsClient::new().await.map_err(|e| e.to_string())?;
+            client
+                .list_capacity_forecasts()
+                .await
+                .map_err(|e| e.to_string())
+        },
+        |result| Message::CapacityForecastsLoaded(result).into(),
+    )
+}
+
+/// Keep the forecasts and notify of filesystems that came within the
+/// configured number of days of running full
+pub(super) fn loaded(
+    app: &mut AppModel,
+    result: Result<Vec<CapacityForecast>, String>,
+) -> Task<Message> {
+    let forecasts = match result {
+        Ok(forecasts) => forecasts,
+        Err(e) => {
+            tracing::warn!("Failed to load capacity forecasts: {e}");
+            return Task::none();
+        }
+    };
+
+    let events = notification_policy::capacity_events(
+        &app.capacity.forecasts,
+        &forecasts,
+        app.config.capacity_alert_days,
+    );
+    app.capacity.forecasts = forecasts
+        .into_iter()
+        .map(|forecast| (forecast.mount_point.clone(), forecast))
+        .collect();
+    Task::batch(events.into_iter().map(|event| {
+        notification_policy::notify(event, &app.config.notifications, app.window_focused)
+    }))
+}
@@ -0,0 +1,46 @@
+use crate::message::dialogs::ConfirmationMessage;
+use crate::state::dialogs::{ConfirmationGuard, ShowDialog};
+use cosmic::app::Task;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Give the open dialog the confirmation guard its target and the
+/// confirmation level call for
+///
+/// Runs after every message, so that any way of opening a destructive
+/// dialog, or of reaching the step of a wizard that starts its operation,
+/// is guarded. A guard is kept while its target stays the same.
+pub(crate) fn sync(app: &mut AppModel) {
+    let Some((target, whole_disk)) = app
+        .dialog
+        .as_ref()
+        .and_then(ShowDialog::confirmation_target)
+    else {
+        app.confirmation = None;
+        return;
+    };
+    if app
+        .confirmation
+        .as_ref()
+        .is_some_and(|guard| guard.target == target)
+    {
+        return;
+    }
+    let guard = ConfirmationGuard::new(app.config.confirmation_level, target, whole_disk);
+    app.confirmation = (guard.requirements != Default::default()).then_some(guard);
+}
+
+pub(super) fn confirmation(app: &mut AppModel, msg: ConfirmationMessage) -> Task<Message> {
+    let Some(guard) = app.confirmation.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        ConfirmationMessage::NameUpdate(name) => guard.typed_name = name,
+        ConfirmationMessage::TokenUpdate(token) => guard.typed_token = token,
+        ConfirmationMessage::Tick => guard.countdown = guard.countdown.saturating_sub(1),
+    }
+
+    Task::none()
+}
@@ -0,0 +1,117 @@
+use crate::client::FilesystemsClient;
+use crate::fl;
+use crate::message::dialogs::DefragDialogMessage;
+use crate::notification_policy::{self, StorageEvent};
+use crate::state::dialogs::ShowDialog;
+use cosmic::app::Task;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+pub(super) fn defrag_dialog(app: &mut AppModel, msg: DefragDialogMessage) -> Task<Message> {
+    let Some(ShowDialog::Defragment(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        DefragDialogMessage::TargetUpdate(target) => {
+            state.target = target;
+            state.report = None;
+            state.result = None;
+        }
+        DefragDialogMessage::Analyze => {
+            if state.analyzing || state.running {
+                return Task::none();
+            }
+            state.analyzing = true;
+            state.error = None;
+            let device = state.device.clone();
+            let target = state.target.clone();
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .get_fragmentation(&device, &target)
+                        .await
+                        .map_err(|e| format!("Failed to estimate fragmentation: {}", e))
+                },
+                |res| Message::DefragDialog(DefragDialogMessage::Analyzed(res)).into(),
+            );
+        }
+        DefragDialogMessage::Analyzed(res) => {
+            state.analyzing = false;
+            match res {
+                Ok(report) => state.report = Some(report),
+                Err(e) => {
+                    tracing::error!(%e, "fragmentation analysis error");
+                    state.error = Some(e);
+                }
+            }
+        }
+        DefragDialogMessage::Start => {
+            if state.analyzing || state.running {
+                return Task::none();
+            }
+            state.running = true;
+            state.progress = Some((0, 0));
+            state.result = None;
+            state.error = None;
+
+            let device = state.device.clone();
+            let target = state.target.clone();
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .defragment(&device, &target)
+                        .await
+                        .map_err(|e| format!("Defragmentation failed: {}", e))
+                },
+                |res| Message::DefragDialog(DefragDialogMessage::Complete(res)).into(),
+            );
+        }
+        DefragDialogMessage::Progress {
+            device,
+            processed,
+            total,
+        } => {
+            if state.running && state.device == device {
+                state.progress = Some((processed, total));
+            }
+        }
+        DefragDialogMessage::Complete(res) => {
+            state.running = false;
+            let event = StorageEvent::OperationFinished {
+                operation: fl!("defragment"),
+                result: res
+                    .as_ref()
+                    .map(|result| fl!("defrag-complete", processed = result.files_processed))
+                    .map_err(Clone::clone),
+            };
+            let notification =
+                notification_policy::notify(event, &app.config.notifications, app.window_focused);
+            match res {
+                Ok(result) => {
+                    if let Some(report) = state.report.as_mut() {
+                        report.score_percent = result.score_after;
+                    }
+                    state.result = Some(result);
+                }
+                Err(e) => {
+                    tracing::error!(%e, "defragmentation error");
+                    state.error = Some(e);
+                }
+            }
+            return notification;
+        }
+        DefragDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,103 @@
+use crate::client::DisksClient;
+use crate::diagnostics::{self, FailureContext};
+use crate::fl;
+use crate::message::dialogs::DiagnosticsDialogMessage;
+use crate::models::UiDrive;
+use crate::state::dialogs::{DiagnosticsDialog, ShowDialog};
+use crate::utils::{notifications, sandbox};
+use cosmic::app::Task;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Ask for consent to collect a diagnostic report of `failure`
+pub(super) fn report_problem(app: &mut AppModel, failure: FailureContext) {
+    let device = app
+        .nav
+        .active_data::<UiDrive>()
+        .map(|drive| drive.device().to_string());
+    app.dialog = Some(ShowDialog::Diagnostics(DiagnosticsDialog {
+        failure,
+        device,
+        running: false,
+        error: None,
+    }));
+}
+
+pub(super) fn diagnostics_dialog(
+    app: &mut AppModel,
+    msg: DiagnosticsDialogMessage,
+) -> Task<Message> {
+    let Some(ShowDialog::Diagnostics(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        DiagnosticsDialogMessage::Save => {
+            if state.running {
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+
+            let failure = state.failure.clone();
+            let device = state.device.clone();
+            let title = fl!("diagnostics-save");
+            return Task::perform(
+                async move {
+                    let summary = failure.summary(device.as_deref());
+                    let bundle = DisksClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create disks client: {}", e))?
+                        .collect_diagnostics(
+                            device.as_deref().unwrap_or_default(),
+                            failure.time,
+                            &summary,
+                        )
+                        .await
+                        .map_err(|e| format!("Failed to collect diagnostics: {}", e))?;
+
+                    let path = sandbox::choose_save_path(title, &failure.archive_name()).await;
+                    let Some(path) = path else {
+                        return Ok(None);
+                    };
+
+                    diagnostics::write_archive(bundle, &path).await?;
+                    Ok(Some(path))
+                },
+                |res| Message::DiagnosticsDialog(DiagnosticsDialogMessage::Saved(res)).into(),
+            );
+        }
+        DiagnosticsDialogMessage::Saved(res) => {
+            state.running = false;
+            match res {
+                Ok(None) => {}
+                Ok(Some(path)) => {
+                    app.dialog = None;
+                    let body = fl!("diagnostics-saved-body", path = path.display().to_string());
+                    return Task::perform(
+                        async move {
+                            if let Err(e) =
+                                notifications::notify(&fl!("diagnostics-saved"), &body).await
+                            {
+                                tracing::warn!(%e, "failed to show diagnostics notification");
+                            }
+                        },
+                        |_| Message::None.into(),
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(%e, "diagnostic report error");
+                    state.error = Some(e);
+                }
+            }
+        }
+        DiagnosticsDialogMessage::Cancel => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,84 @@
+use crate::diagram::{self, DiagramFormat};
+use crate::fl;
+use crate::message::app::Message;
+use crate::models::UiDrive;
+use crate::state::app::AppModel;
+use crate::state::dialogs::ShowDialog;
+use crate::state::volumes::VolumesControl;
+use crate::utils::{notifications, sandbox};
+use cosmic::app::Task;
+
+/// Suggested name of the exported diagram
+const DIAGRAM_FILE_NAME: &str = "disk-layout.svg";
+
+/// Ask where to save the layout diagram of the selected drive
+pub(super) fn export_layout_diagram() -> Task<Message> {
+    let title = fl!("diagram-export");
+
+    Task::perform(
+        async move { sandbox::choose_save_path(title, DIAGRAM_FILE_NAME).await },
+        |result| Message::LayoutDiagramDestinationChosen(result).into(),
+    )
+}
+
+/// Draw the diagram in the format its file extension asks for (SVG by
+/// default) and write it
+pub(super) fn save_layout_diagram(app: &AppModel, mut path: std::path::PathBuf) -> Task<Message> {
+    let (Some(drive), Some(volumes_control)) = (
+        app.nav.active_data::<UiDrive>(),
+        app.nav.active_data::<VolumesControl>(),
+    ) else {
+        return Task::none();
+    };
+
+    let format = DiagramFormat::from_path(&path).unwrap_or_else(|| {
+        let format = DiagramFormat::Svg;
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(format.extension());
+        path.set_file_name(name);
+        format
+    });
+    let layout = diagram::build(drive, &volumes_control.segments);
+
+    Task::perform(
+        async move {
+            // Rasterizing loads the system fonts, so keep it off the UI thread
+            let contents = tokio::task::spawn_blocking(move || layout.render(format))
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())?;
+            tokio::fs::write(&path, contents)
+                .await
+                .map(|()| path)
+                .map_err(|e| e.to_string())
+        },
+        |result| Message::LayoutDiagramSaved(result).into(),
+    )
+}
+
+pub(super) fn layout_diagram_saved(result: Result<std::path::PathBuf, String>) -> Task<Message> {
+    match result {
+        Ok(path) => {
+            let body = fl!("report-saved-body", path = path.display().to_string());
+            Task::perform(
+                async move {
+                    if let Err(e) = notifications::notify(&fl!("diagram-saved"), &body).await {
+                        tracing::warn!(%e, "failed to show diagram notification");
+                    }
+                },
+                |_| Message::None.into(),
+            )
+        }
+        Err(e) => {
+            tracing::error!(%e, "failed to save layout diagram");
+            Task::done(
+                Message::Dialog(Box::new(ShowDialog::Info {
+                    title: fl!("diagram-save-failed"),
+                    body: e,
+                }))
+                .into(),
+            )
+        }
+    }
+}
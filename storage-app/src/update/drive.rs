@@ -5,6 +5,7 @@ use crate::message::dialogs::FormatDiskMessage;
 use crate::models::{UiDrive, load_all_drives};
 use crate::state::dialogs::{FormatDiskDialog, ShowDialog, SmartDataDialog};
 use cosmic::app::Task;
+use storage_types::bytes_to_pretty;
 
 use crate::message::app::Message;
 use crate::state::app::AppModel;
@@ -201,11 +202,22 @@ pub(super) fn smart_data_for(app: &mut AppModel, drive: UiDrive) -> Task<Message
         drive: drive.clone(),
         running: true,
         info: None,
+        backend: None,
+        schedule: None,
+        history: Vec::new(),
+        thresholds: None,
+        kernel_errors: Vec::new(),
         error: None,
     }));
 
-    Task::perform(
+    let device = drive.device().to_string();
+    let load_data = Task::perform(
         async move {
+            if let Some(smart) =
+                crate::models::demo::dataset().and_then(|dataset| dataset.smart.get(drive.device()))
+            {
+                return Ok((smart.status.clone(), smart.attributes.clone()));
+            }
             let disks_client = DisksClient::new()
                 .await
                 .map_err(|e| format!("Failed to create disks client: {}", e))?;
@@ -220,7 +232,15 @@ pub(super) fn smart_data_for(app: &mut AppModel, drive: UiDrive) -> Task<Message
             Ok((status, attributes))
         },
         |res| Message::SmartDialog(crate::message::dialogs::SmartDialogMessage::Loaded(res)).into(),
-    )
+    );
+
+    Task::batch([
+        load_data,
+        super::smart::load_backend(device.clone()),
+        super::smart::load_selftests(device.clone()),
+        super::smart::load_thresholds(device.clone()),
+        super::smart::load_kernel_errors(device),
+    ])
 }
 
 pub(super) fn standby_now(app: &mut AppModel) -> Task<Message> {
@@ -265,6 +285,95 @@ pub(super) fn standby_now_drive(drive: UiDrive) -> Task<Message> {
     )
 }
 
+/// Seconds the slot LED blinks when identifying a drive
+const IDENTIFY_SECONDS: u32 = 30;
+
+pub(super) fn identify_drive(app: &mut AppModel) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+
+    let device = drive.device().to_string();
+    let device_for_closure = device.clone();
+
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to create disks client: {}", e))?
+                .identify_drive(&device, IDENTIFY_SECONDS)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to identify: {}", e))
+        },
+        move |res| match res {
+            Ok(support) => Message::Dialog(Box::new(ShowDialog::Info {
+                title: fl!("identify-drive"),
+                body: match support.slot {
+                    Some(slot) => fl!(
+                        "identify-drive-slot-blinking",
+                        slot = slot,
+                        seconds = IDENTIFY_SECONDS
+                    ),
+                    None => fl!("identify-drive-blinking", seconds = IDENTIFY_SECONDS),
+                },
+            }))
+            .into(),
+            Err(e) => {
+                let ctx = UiErrorContext {
+                    operation: "identify_drive",
+                    device_path: Some(device_for_closure.as_str()),
+                    device: Some(device_for_closure.as_str()),
+                    drive_path: Some(device_for_closure.as_str()),
+                };
+                log_error_and_show_dialog(fl!("identify-drive-failed"), e, ctx).into()
+            }
+        },
+    )
+}
+
+pub(super) fn trim_filesystems(app: &mut AppModel) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+
+    let device = drive.device().to_string();
+    let device_for_closure = device.clone();
+
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to create disks client: {}", e))?
+                .trim_filesystems(&device)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to trim: {}", e))
+        },
+        move |res| match res {
+            Ok(results) => {
+                let trimmed: u64 = results.iter().map(|result| result.trimmed_bytes).sum();
+                Message::Dialog(Box::new(ShowDialog::Info {
+                    title: fl!("trim-filesystems"),
+                    body: fl!(
+                        "trim-filesystems-done",
+                        size = bytes_to_pretty(&trimmed, false),
+                        count = results.len()
+                    ),
+                }))
+                .into()
+            }
+            Err(e) => {
+                let ctx = UiErrorContext {
+                    operation: "trim_filesystems",
+                    device_path: Some(device_for_closure.as_str()),
+                    device: Some(device_for_closure.as_str()),
+                    drive_path: Some(device_for_closure.as_str()),
+                };
+                log_error_and_show_dialog(fl!("trim-filesystems-failed"), e, ctx).into()
+            }
+        },
+    )
+}
+
 pub(super) fn wakeup(app: &mut AppModel) -> Task<Message> {
     let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
         return Task::none();
@@ -0,0 +1,189 @@
+use crate::client::FilesystemsClient;
+use crate::fl;
+use crate::message::dialogs::EncryptedFoldersDialogMessage;
+use crate::state::dialogs::ShowDialog;
+use cosmic::app::Task;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+pub(super) fn encrypted_folders_dialog(
+    app: &mut AppModel,
+    msg: EncryptedFoldersDialogMessage,
+) -> Task<Message> {
+    let Some(ShowDialog::EncryptedFolders(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        EncryptedFoldersDialogMessage::Load => {
+            if state.loading {
+                return Task::none();
+            }
+            state.loading = true;
+            let device = state.device.clone();
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .get_fscrypt_status(&device)
+                        .await
+                        .map_err(|e| format!("Failed to list encrypted folders: {}", e))
+                },
+                |res| {
+                    Message::EncryptedFoldersDialog(EncryptedFoldersDialogMessage::Loaded(res))
+                        .into()
+                },
+            );
+        }
+        EncryptedFoldersDialogMessage::Loaded(res) => {
+            state.loading = false;
+            match res {
+                Ok(status) => state.status = Some(status),
+                Err(e) => {
+                    tracing::error!(%e, "fscrypt status error");
+                    state.error = Some(e);
+                }
+            }
+        }
+        EncryptedFoldersDialogMessage::StartUnlock(path) => {
+            state.unlocking = Some(path);
+            state.unlock_passphrase.clear();
+            state.error = None;
+        }
+        EncryptedFoldersDialogMessage::CancelUnlock => {
+            state.unlocking = None;
+            state.unlock_passphrase.clear();
+        }
+        EncryptedFoldersDialogMessage::UnlockPassphraseUpdate(passphrase) => {
+            state.unlock_passphrase = passphrase;
+        }
+        EncryptedFoldersDialogMessage::Unlock => {
+            let Some(path) = state.unlocking.clone() else {
+                return Task::none();
+            };
+            if state.running || state.unlock_passphrase.is_empty() {
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+            let device = state.device.clone();
+            let passphrase = std::mem::take(&mut state.unlock_passphrase);
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .unlock_encrypted_directory(&device, &path, &passphrase)
+                        .await
+                        .map_err(|e| format!("Failed to unlock {}: {}", path, e))
+                },
+                |res| {
+                    Message::EncryptedFoldersDialog(EncryptedFoldersDialogMessage::Done(res)).into()
+                },
+            );
+        }
+        EncryptedFoldersDialogMessage::Lock(path) => {
+            if state.running {
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+            let device = state.device.clone();
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .lock_encrypted_directory(&device, &path)
+                        .await
+                        .map_err(|e| format!("Failed to lock {}: {}", path, e))
+                },
+                |res| {
+                    Message::EncryptedFoldersDialog(EncryptedFoldersDialogMessage::Done(res)).into()
+                },
+            );
+        }
+        EncryptedFoldersDialogMessage::NewDirectoryUpdate(directory) => {
+            state.new_directory = directory;
+        }
+        EncryptedFoldersDialogMessage::ProtectorNameUpdate(name) => {
+            state.protector_name = name;
+        }
+        EncryptedFoldersDialogMessage::PassphraseUpdate(passphrase) => {
+            state.passphrase = passphrase;
+        }
+        EncryptedFoldersDialogMessage::ConfirmUpdate(confirm) => {
+            state.confirm = confirm;
+        }
+        EncryptedFoldersDialogMessage::Create => {
+            if state.running || state.new_directory.trim().is_empty() || state.passphrase.is_empty()
+            {
+                return Task::none();
+            }
+            if state.passphrase != state.confirm {
+                state.error = Some(fl!("passphrase-mismatch"));
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+
+            // Relative paths are taken from the root of the filesystem
+            let directory = std::path::Path::new(&state.mount_point)
+                .join(state.new_directory.trim())
+                .to_string_lossy()
+                .into_owned();
+            let name = if state.protector_name.trim().is_empty() {
+                std::path::Path::new(&directory)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            } else {
+                state.protector_name.trim().to_string()
+            };
+            let device = state.device.clone();
+            let passphrase = state.passphrase.clone();
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .create_encrypted_directory(&device, &directory, &name, &passphrase)
+                        .await
+                        .map_err(|e| format!("Failed to create {}: {}", directory, e))
+                },
+                |res| {
+                    Message::EncryptedFoldersDialog(EncryptedFoldersDialogMessage::Done(res)).into()
+                },
+            );
+        }
+        EncryptedFoldersDialogMessage::Done(res) => {
+            state.running = false;
+            match res {
+                Ok(()) => {
+                    state.unlocking = None;
+                    state.new_directory.clear();
+                    state.protector_name.clear();
+                    state.passphrase.clear();
+                    state.confirm.clear();
+                }
+                Err(e) => {
+                    tracing::error!(%e, "fscrypt operation error");
+                    state.error = Some(e);
+                    return Task::none();
+                }
+            }
+            return Task::done(
+                Message::EncryptedFoldersDialog(EncryptedFoldersDialogMessage::Load).into(),
+            );
+        }
+        EncryptedFoldersDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,228 @@
+use crate::client::PartitionsClient;
+use crate::fl;
+use crate::message::dialogs::EspSyncDialogMessage;
+use crate::notification_policy::{self, StorageEvent};
+use crate::state::dialogs::{ESP_SYNC_INTERVALS, EspSyncDialog, ShowDialog};
+use crate::state::volumes::VolumesControl;
+use cosmic::app::Task;
+use storage_types::{EspSyncPair, is_esp};
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Open the ESP sync dialog for the selected EFI System Partition
+pub(super) fn open_esp_sync(app: &mut AppModel) -> Task<Message> {
+    if app.dialog.is_some() {
+        return Task::none();
+    }
+    let Some(volumes_control) = app.nav.active_data::<VolumesControl>() else {
+        return Task::none();
+    };
+    let Some(primary) = volumes_control
+        .segments
+        .get(volumes_control.selected_segment)
+        .and_then(|segment| segment.device_path.as_ref())
+        .and_then(|device| {
+            volumes_control
+                .partitions
+                .iter()
+                .find(|partition| &partition.device == device)
+        })
+        .filter(|partition| is_esp(&partition.type_id))
+        .cloned()
+    else {
+        return Task::none();
+    };
+    if primary.uuid.is_empty() {
+        app.dialog = Some(ShowDialog::Info {
+            title: fl!("esp-sync"),
+            body: fl!("esp-sync-no-uuid"),
+        });
+        return Task::none();
+    }
+
+    let candidates = app
+        .sidebar
+        .drives
+        .iter()
+        .filter(|drive| !drive.disk.read_only)
+        .flat_map(|drive| &drive.partitions)
+        .filter(|partition| {
+            is_esp(&partition.type_id)
+                && partition.device != primary.device
+                && !partition.uuid.is_empty()
+        })
+        .cloned()
+        .collect();
+
+    app.dialog = Some(ShowDialog::EspSync(EspSyncDialog {
+        primary,
+        candidates,
+        secondary_index: 0,
+        interval_days: None,
+        running: false,
+        result: None,
+        error: None,
+    }));
+
+    Task::perform(
+        async move {
+            PartitionsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create partitions client: {}", e))?
+                .esp_sync_pairs()
+                .await
+                .map_err(|e| format!("Failed to load ESP sync schedules: {}", e))
+        },
+        |res| Message::EspSyncDialog(EspSyncDialogMessage::PairsLoaded(res)).into(),
+    )
+}
+
+/// Save the schedule of the chosen pair, and drop the one of `previous`
+/// when the secondary changed
+fn save_schedule(state: &EspSyncDialog, previous: Option<EspSyncPair>) -> Task<Message> {
+    let Some(secondary) = state.secondary() else {
+        return Task::none();
+    };
+    let pair = EspSyncPair {
+        primary: state.primary.uuid.clone(),
+        secondary: secondary.uuid.clone(),
+        interval_days: state.interval_days,
+        last_synced: None,
+    };
+    Task::perform(
+        async move {
+            let client = PartitionsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create partitions client: {}", e))?;
+            if let Some(previous) = previous {
+                client
+                    .set_esp_sync_pair(&previous)
+                    .await
+                    .map_err(|e| format!("Failed to save ESP sync schedule: {}", e))?;
+            }
+            client
+                .set_esp_sync_pair(&pair)
+                .await
+                .map_err(|e| format!("Failed to save ESP sync schedule: {}", e))
+        },
+        |res| Message::EspSyncDialog(EspSyncDialogMessage::ScheduleSaved(res)).into(),
+    )
+}
+
+pub(super) fn esp_sync_dialog(app: &mut AppModel, msg: EspSyncDialogMessage) -> Task<Message> {
+    let Some(ShowDialog::EspSync(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        EspSyncDialogMessage::PairsLoaded(res) => match res {
+            Ok(pairs) => {
+                // Show the schedule already set up for this primary, if any
+                let scheduled = pairs.iter().find_map(|pair| {
+                    let index = state
+                        .candidates
+                        .iter()
+                        .position(|candidate| candidate.uuid == pair.secondary)?;
+                    (pair.primary == state.primary.uuid).then_some((index, pair.interval_days))
+                });
+                if let Some((index, interval_days)) = scheduled {
+                    state.secondary_index = index;
+                    state.interval_days = interval_days;
+                }
+            }
+            Err(e) => {
+                tracing::warn!(%e, "could not load ESP sync schedules");
+            }
+        },
+        EspSyncDialogMessage::SetSecondary(index) => {
+            if state.running || index >= state.candidates.len() || index == state.secondary_index {
+                return Task::none();
+            }
+            let previous = state.secondary().map(|secondary| EspSyncPair {
+                primary: state.primary.uuid.clone(),
+                secondary: secondary.uuid.clone(),
+                interval_days: None,
+                last_synced: None,
+            });
+            state.secondary_index = index;
+            state.result = None;
+            if state.interval_days.is_some() {
+                return save_schedule(state, previous);
+            }
+        }
+        EspSyncDialogMessage::SetInterval(index) => {
+            let Some(interval_days) = ESP_SYNC_INTERVALS.get(index).copied() else {
+                return Task::none();
+            };
+            if state.running || interval_days == state.interval_days {
+                return Task::none();
+            }
+            state.interval_days = interval_days;
+            return save_schedule(state, None);
+        }
+        EspSyncDialogMessage::ScheduleSaved(res) => {
+            if let Err(e) = res {
+                tracing::error!(%e, "ESP sync schedule error");
+                state.error = Some(e);
+            }
+        }
+        EspSyncDialogMessage::Sync => {
+            let Some(secondary) = state.secondary() else {
+                return Task::none();
+            };
+            if state.running {
+                return Task::none();
+            }
+            let primary = state.primary.uuid.clone();
+            let secondary = secondary.uuid.clone();
+            state.running = true;
+            state.result = None;
+            state.error = None;
+            return Task::perform(
+                async move {
+                    PartitionsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create partitions client: {}", e))?
+                        .sync_esp(&primary, &secondary)
+                        .await
+                        .map_err(|e| format!("Syncing the EFI System Partition failed: {}", e))
+                },
+                |res| Message::EspSyncDialog(EspSyncDialogMessage::Complete(res)).into(),
+            );
+        }
+        EspSyncDialogMessage::Complete(res) => {
+            state.running = false;
+            let event = StorageEvent::OperationFinished {
+                operation: fl!("esp-sync"),
+                result: res
+                    .as_ref()
+                    .map(|result| {
+                        fl!(
+                            "esp-sync-complete",
+                            copied = result.copied,
+                            deleted = result.deleted
+                        )
+                    })
+                    .map_err(Clone::clone),
+            };
+            let notification =
+                notification_policy::notify(event, &app.config.notifications, app.window_focused);
+            match res {
+                Ok(result) => state.result = Some(result),
+                Err(e) => {
+                    tracing::error!(%e, "ESP sync error");
+                    state.error = Some(e);
+                }
+            }
+            return notification;
+        }
+        EspSyncDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,130 @@
+use crate::client::PartitionsClient;
+use crate::message::dialogs::GptEntriesDialogMessage;
+use crate::models::{UiDrive, load_all_drives};
+use crate::state::dialogs::{GptEntriesDialog, GptEntryRow, ShowDialog};
+use cosmic::app::Task;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Open the raw GPT entry editor for the active drive and read its table
+pub(super) fn edit_gpt_entries(app: &mut AppModel) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+
+    let device = drive.device().to_string();
+    app.dialog = Some(ShowDialog::GptEntries(GptEntriesDialog {
+        drive,
+        table: None,
+        rows: Vec::new(),
+        running: true,
+        error: None,
+    }));
+
+    Task::perform(
+        async move {
+            PartitionsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create partitions client: {}", e))?
+                .gpt_table(&device)
+                .await
+                .map_err(|e| format!("Failed to read the partition table: {}", e))
+        },
+        |res| Message::GptEntriesDialog(GptEntriesDialogMessage::Loaded(res)).into(),
+    )
+}
+
+pub(super) fn gpt_entries_dialog(
+    app: &mut AppModel,
+    msg: GptEntriesDialogMessage,
+) -> Task<Message> {
+    let Some(ShowDialog::GptEntries(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        GptEntriesDialogMessage::Loaded(res) => {
+            state.running = false;
+            match res {
+                Ok(table) => {
+                    state.rows = table.entries.iter().map(GptEntryRow::new).collect();
+                    state.table = Some(table);
+                }
+                Err(e) => {
+                    tracing::error!(%e, "GPT read error");
+                    state.error = Some(e);
+                }
+            }
+        }
+        GptEntriesDialogMessage::FieldUpdate(index, field, text) => {
+            if !state.running
+                && let Some(row) = state.rows.get_mut(index)
+            {
+                *row.field_mut(field) = text;
+                state.error = None;
+            }
+        }
+        GptEntriesDialogMessage::Revert => {
+            if let Some(table) = state.table.as_ref()
+                && !state.running
+            {
+                state.rows = table.entries.iter().map(GptEntryRow::new).collect();
+                state.error = None;
+            }
+        }
+        GptEntriesDialogMessage::Apply => {
+            if state.running || state.drive.disk.read_only {
+                return Task::none();
+            }
+            let edits = match state.edits() {
+                Ok(edits) if edits.is_empty() => return Task::none(),
+                Ok(edits) => edits,
+                Err(e) => {
+                    state.error = Some(e);
+                    return Task::none();
+                }
+            };
+            state.running = true;
+            state.error = None;
+
+            let device = state.drive.device().to_string();
+            return Task::perform(
+                async move {
+                    PartitionsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create partitions client: {}", e))?
+                        .edit_gpt_entries(&device, &edits)
+                        .await
+                        .map_err(|e| format!("Failed to write the partition table: {}", e))
+                },
+                |res| Message::GptEntriesDialog(GptEntriesDialogMessage::Applied(res)).into(),
+            );
+        }
+        GptEntriesDialogMessage::Applied(res) => {
+            state.running = false;
+            match res {
+                Ok(()) => app.dialog = None,
+                Err(e) => {
+                    tracing::error!(%e, "GPT edit error");
+                    state.error = Some(e);
+                    return Task::none();
+                }
+            }
+            return Task::perform(load_all_drives(), |res| match res {
+                Ok(drives) => Message::UpdateNav(drives, None).into(),
+                Err(e) => {
+                    tracing::error!(?e, "failed to reload drives");
+                    Message::None.into()
+                }
+            });
+        }
+        GptEntriesDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,255 @@
+use crate::client::{BtrfsClient, DisksClient, FilesystemsClient, RaidClient};
+use crate::fl;
+use crate::message::health_check::HealthCheckMessage;
+use crate::views::sidebar::health_factor_text;
+use cosmic::app::Task;
+use futures_util::future::{join_all, join4};
+use storage_types::health_check::{btrfs_level, raid_level};
+use storage_types::{
+    CheckResult, HealthCheckItem, HealthCheckKind, HealthCheckReport, HealthLevel, TemperatureUnit,
+};
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// What a check of all drives looks at
+#[derive(Debug, Default)]
+struct CheckTargets {
+    /// Drives to read SMART data of
+    drives: Vec<String>,
+    /// Unmounted filesystems to check without repairing
+    filesystems: Vec<String>,
+    arrays: Vec<String>,
+    /// One mount point of each mounted btrfs filesystem
+    btrfs_mounts: Vec<String>,
+}
+
+impl CheckTargets {
+    fn of(app: &AppModel) -> Self {
+        let mut targets = Self::default();
+        for drive in &app.sidebar.drives {
+            if drive.device().starts_with("/dev/md") {
+                targets.arrays.push(drive.device().to_string());
+            } else if !drive.disk.is_loop && !drive.disk.optical {
+                targets.drives.push(drive.device().to_string());
+            }
+
+            for volume in &drive.volumes_flat {
+                let volume = &volume.volume;
+                let Some(device) = volume.device_path.as_ref() else {
+                    continue;
+                };
+                if volume.id_type == "btrfs"
+                    && let Some(mount_point) = volume.mount_points.first()
+                {
+                    targets.btrfs_mounts.push(mount_point.clone());
+                } else if volume.has_filesystem
+                    && volume.mount_points.is_empty()
+                    && !volume.locked
+                    && volume.id_type != "swap"
+                {
+                    targets.filesystems.push(device.clone());
+                }
+            }
+        }
+        targets.filesystems.sort();
+        targets.filesystems.dedup();
+        targets.btrfs_mounts.sort();
+        targets.btrfs_mounts.dedup();
+        targets
+    }
+}
+
+fn failed(kind: HealthCheckKind, target: String, error: String) -> HealthCheckItem {
+    HealthCheckItem {
+        kind,
+        target,
+        level: HealthLevel::Unknown,
+        details: vec![error],
+    }
+}
+
+/// Read fresh SMART data and score it
+async fn check_drive(device: String, unit: TemperatureUnit) -> HealthCheckItem {
+    let summary = match DisksClient::new().await {
+        Ok(client) => client.get_health_summary(&device).await,
+        Err(e) => Err(e),
+    };
+    match summary {
+        Ok(summary) => {
+            let mut details = vec![fl!("health-score", score = summary.score)];
+            details.extend(
+                summary
+                    .factors
+                    .iter()
+                    .map(|factor| health_factor_text(factor, unit)),
+            );
+            HealthCheckItem {
+                kind: HealthCheckKind::Smart,
+                target: device,
+                level: summary.level,
+                details,
+            }
+        }
+        Err(e) => failed(HealthCheckKind::Smart, device, e.to_string()),
+    }
+}
+
+/// Check an unmounted filesystem without repairing it
+async fn check_filesystem(client: &FilesystemsClient, device: String) -> HealthCheckItem {
+    let result = match client.check(&device, false).await {
+        Ok(json) => serde_json::from_str::<CheckResult>(&json)
+            .map(|result| result.clean)
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match result {
+        Ok(true) => HealthCheckItem {
+            kind: HealthCheckKind::Filesystem,
+            target: device,
+            level: HealthLevel::Good,
+            details: vec![fl!("health-check-filesystem-clean")],
+        },
+        Ok(false) => HealthCheckItem {
+            kind: HealthCheckKind::Filesystem,
+            target: device,
+            level: HealthLevel::Critical,
+            details: vec![fl!("health-check-filesystem-errors")],
+        },
+        Err(e) => failed(HealthCheckKind::Filesystem, device, e),
+    }
+}
+
+/// Checks read every block, so filesystems are checked one after the
+/// other; this also asks for authorization once rather than for each
+async fn check_filesystems(devices: Vec<String>) -> Vec<HealthCheckItem> {
+    if devices.is_empty() {
+        return Vec::new();
+    }
+    let client = match FilesystemsClient::new().await {
+        Ok(client) => client,
+        Err(e) => {
+            return devices
+                .into_iter()
+                .map(|device| failed(HealthCheckKind::Filesystem, device, e.to_string()))
+                .collect();
+        }
+    };
+    let mut items = Vec::with_capacity(devices.len());
+    for device in devices {
+        items.push(check_filesystem(&client, device).await);
+    }
+    items
+}
+
+async fn check_array(array: String) -> HealthCheckItem {
+    let detail = match RaidClient::new().await {
+        Ok(client) => client.get_raid_detail(&array).await,
+        Err(e) => Err(e),
+    };
+    match detail {
+        Ok(detail) => {
+            let mut details = vec![fl!(
+                "health-check-raid-state",
+                state = detail.array_state.clone(),
+                action = detail.sync_action.clone()
+            )];
+            if detail.degraded > 0 {
+                details.push(fl!("health-check-raid-degraded", count = detail.degraded));
+            }
+            if let Some(count) = detail.mismatch_count.filter(|count| *count > 0) {
+                details.push(fl!("health-check-raid-mismatches", count = count));
+            }
+            HealthCheckItem {
+                kind: HealthCheckKind::Raid,
+                target: array,
+                level: raid_level(&detail),
+                details,
+            }
+        }
+        Err(e) => failed(HealthCheckKind::Raid, array, e.to_string()),
+    }
+}
+
+async fn check_btrfs(mount_point: String) -> HealthCheckItem {
+    let stats = match BtrfsClient::new().await {
+        Ok(client) => client.get_device_stats(&mount_point).await,
+        Err(e) => Err(e),
+    };
+    match stats {
+        Ok(stats) => HealthCheckItem {
+            kind: HealthCheckKind::Btrfs,
+            target: mount_point,
+            level: btrfs_level(&stats),
+            details: stats
+                .iter()
+                .map(|device| {
+                    fl!(
+                        "health-check-btrfs-device",
+                        device = device.device.clone(),
+                        io = device.write_io_errs + device.read_io_errs + device.flush_io_errs,
+                        corruption = device.corruption_errs,
+                        generation = device.generation_errs
+                    )
+                })
+                .collect(),
+        },
+        Err(e) => failed(HealthCheckKind::Btrfs, mount_point, e.to_string()),
+    }
+}
+
+pub(super) fn handle_health_check_message(
+    app: &mut AppModel,
+    msg: HealthCheckMessage,
+) -> Task<Message> {
+    match msg {
+        HealthCheckMessage::Run => {
+            if app.health_check.running {
+                return Task::none();
+            }
+            app.health_check.running = true;
+            let targets = CheckTargets::of(app);
+            let unit = app.config.temperature_unit;
+
+            return Task::perform(
+                async move {
+                    let (drives, filesystems, arrays, btrfs) = join4(
+                        join_all(
+                            targets
+                                .drives
+                                .into_iter()
+                                .map(|device| check_drive(device, unit)),
+                        ),
+                        check_filesystems(targets.filesystems),
+                        join_all(targets.arrays.into_iter().map(check_array)),
+                        join_all(targets.btrfs_mounts.into_iter().map(check_btrfs)),
+                    )
+                    .await;
+
+                    let items = drives
+                        .into_iter()
+                        .chain(filesystems)
+                        .chain(arrays)
+                        .chain(btrfs)
+                        .collect();
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |elapsed| elapsed.as_secs());
+                    HealthCheckReport::new(items, now)
+                },
+                |report| Message::HealthCheck(HealthCheckMessage::Finished(report)).into(),
+            );
+        }
+        HealthCheckMessage::Finished(report) => {
+            app.health_check.running = false;
+            app.health_check.expanded = None;
+            app.health_check.report = Some(report);
+        }
+        HealthCheckMessage::ToggleItem(index) => {
+            let expanded = &mut app.health_check.expanded;
+            *expanded = (*expanded != Some(index)).then_some(index);
+        }
+    }
+
+    Task::none()
+}
@@ -4,8 +4,12 @@ use crate::message::dialogs::{
     NewDiskImageDialogMessage,
 };
 use crate::models::load_all_drives;
-use crate::state::dialogs::{AttachDiskImageDialog, NewDiskImageDialog, ShowDialog};
+use crate::notification_policy::{self, StorageEvent};
+use crate::state::dialogs::{
+    AttachDiskImageDialog, ImageOperationKind, NewDiskImageDialog, ShowDialog,
+};
 use cosmic::app::Task;
+use storage_types::OperationPriority;
 use tokio::fs::OpenOptions;
 
 use super::ops::start_image_operation;
@@ -201,15 +205,11 @@ pub(super) fn image_operation_dialog(
     match msg {
         ImageOperationDialogMessage::CancelOperation => {
             if state.running {
-                if let Some(operation_id) = state.operation_id.clone() {
-                    return Task::perform(
-                        async move {
-                            let client = ImageClient::new().await?;
-                            client.cancel_operation(&operation_id).await?;
-                            Ok::<(), crate::client::error::ClientError>(())
-                        },
-                        |_| Message::None.into(),
-                    );
+                // The copy stops and the operation completes as cancelled
+                if let Some(operation_id) =
+                    state.operation_id.as_deref().and_then(|id| id.parse().ok())
+                {
+                    return Task::done(Message::CancelOperation(operation_id).into());
                 }
             } else {
                 app.dialog = None;
@@ -221,22 +221,89 @@ pub(super) fn image_operation_dialog(
             }
 
             let image_path = state.image_path.clone();
-            if image_path.trim().is_empty() {
+            let migration = match state.migration.as_ref() {
+                Some(migration) => match (migration.target(), migration.plan()) {
+                    (Some(target), Some(Ok(plan))) => Some((target.clone(), plan)),
+                    (_, Some(Err(e))) => {
+                        state.error = Some(e);
+                        return Task::none();
+                    }
+                    _ => {
+                        state.error = Some("A target drive is required".to_string());
+                        return Task::none();
+                    }
+                },
+                None => None,
+            };
+            if image_path.trim().is_empty() && state.kind == ImageOperationKind::CopyPartition {
+                let e = "A target partition is required".to_string();
+                tracing::warn!(%e, "image operation dialog validation error");
+                state.error = Some(e);
+                return Task::none();
+            }
+            if image_path.trim().is_empty()
+                && !matches!(
+                    state.kind,
+                    ImageOperationKind::BlankDisc | ImageOperationKind::MigrateDisk
+                )
+            {
                 let e = "Image path is required".to_string();
                 tracing::warn!(%e, "image operation dialog validation error");
                 state.error = Some(e);
                 return Task::none();
             }
 
+            let live_usb = match state.live_usb.as_ref() {
+                Some(choices) => {
+                    if !choices.erase_confirmed {
+                        state.error = Some("Confirm that the drive may be erased".to_string());
+                        return Task::none();
+                    }
+                    match choices.validate(&state.drive.disk) {
+                        Some(Ok(())) => Some(choices.plan()),
+                        Some(Err(e)) => {
+                            state.error = Some(e);
+                            return Task::none();
+                        }
+                        None => {
+                            state.error = Some("The image has not been inspected yet".to_string());
+                            return Task::none();
+                        }
+                    }
+                }
+                None => None,
+            };
+
             let kind = state.kind;
             let drive = state.drive.clone();
             let partition = state.partition.clone();
+            let copy_target = state
+                .copy_targets
+                .iter()
+                .find(|volume| volume.device_path.as_deref() == Some(image_path.as_str()))
+                .cloned();
+            let (verify, full_erase) = (state.verify, state.full_erase);
+            let priority = state.priority;
 
             state.running = true;
             state.error = None;
 
             return Task::perform(
-                async move { start_image_operation(kind, drive, partition, image_path).await },
+                async move {
+                    start_image_operation(
+                        kind,
+                        drive,
+                        partition,
+                        image_path,
+                        copy_target,
+                        migration,
+                        live_usb,
+                        verify,
+                        full_erase,
+                        priority,
+                    )
+                    .await
+                },
                 |res: anyhow::Result<String>| match res {
                     Ok(operation_id) => Message::ImageOperationStarted(operation_id).into(),
                     Err(e) => Message::ImageOperationDialog(ImageOperationDialogMessage::Complete(
@@ -246,30 +313,202 @@ pub(super) fn image_operation_dialog(
                 },
             );
         }
-        ImageOperationDialogMessage::Progress(op_id, bytes, total, speed) => {
+        ImageOperationDialogMessage::SetVerify(verify) => {
+            if !state.running {
+                state.verify = verify;
+            }
+        }
+        ImageOperationDialogMessage::SetPriority(preset) => {
+            if !state.running {
+                state.priority =
+                    preset.and_then(|index| OperationPriority::PRESETS.get(index).copied());
+            }
+        }
+        ImageOperationDialogMessage::SetFullErase(full_erase) => {
+            if !state.running {
+                state.full_erase = full_erase;
+            }
+        }
+        ImageOperationDialogMessage::SetCopyTarget(index) => {
+            if !state.running
+                && let Some(device) = state
+                    .copy_targets
+                    .get(index)
+                    .and_then(|volume| volume.device_path.clone())
+            {
+                state.image_path = device;
+            }
+        }
+        ImageOperationDialogMessage::SetMigrationTarget(index) => {
+            if !state.running
+                && let Some(migration) = state.migration.as_mut()
+                && index < migration.targets.len()
+            {
+                migration.target_index = Some(index);
+                state.error = None;
+            }
+        }
+        ImageOperationDialogMessage::SetMigrationProportional(proportional) => {
+            if !state.running
+                && let Some(migration) = state.migration.as_mut()
+            {
+                migration.proportional = proportional;
+            }
+        }
+        ImageOperationDialogMessage::SetMigrationGrow(index, grow) => {
+            if !state.running
+                && let Some(migration) = state.migration.as_mut()
+                && let Some(slot) = migration.grow.get_mut(index)
+            {
+                *slot = grow;
+            }
+        }
+        ImageOperationDialogMessage::LiveIsoProbed(res) => {
+            if let Some(choices) = state.live_usb.as_mut() {
+                match res {
+                    Ok(iso) => {
+                        choices.iso = Some(iso);
+                        state.error = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!(%e, "could not inspect live ISO");
+                        choices.iso = None;
+                        state.error = Some(e);
+                    }
+                }
+            }
+        }
+        ImageOperationDialogMessage::SetLivePersistence(persistence) => {
+            if !state.running
+                && let Some(choices) = state.live_usb.as_mut()
+            {
+                choices.persistence = persistence;
+            }
+        }
+        ImageOperationDialogMessage::SetLivePersistenceSize(gib) => {
+            if !state.running
+                && let Some(choices) = state.live_usb.as_mut()
+            {
+                choices.persistence_gib = gib.max(1);
+            }
+        }
+        ImageOperationDialogMessage::SetLiveDataPartition(data_partition) => {
+            if !state.running
+                && let Some(choices) = state.live_usb.as_mut()
+            {
+                choices.data_partition = data_partition;
+            }
+        }
+        ImageOperationDialogMessage::SetEraseConfirmed(confirmed) => {
+            if !state.running
+                && let Some(choices) = state.live_usb.as_mut()
+            {
+                choices.erase_confirmed = confirmed;
+            }
+        }
+        ImageOperationDialogMessage::Progress(op_id, bytes, total, speed, eta) => {
             if state.operation_id.as_deref() == Some(op_id.as_str()) {
                 state.progress = Some((bytes, total, speed));
+                state.eta = eta;
             }
         }
         ImageOperationDialogMessage::Complete(res) => {
             state.running = false;
             state.operation_id = None;
             state.progress = None;
+            state.eta = None;
             app.image_op_operation_id = None;
 
+            let event = match state.kind {
+                ImageOperationKind::CreateFromDrive | ImageOperationKind::CreateFromPartition => {
+                    StorageEvent::BackupFinished {
+                        image: state.image_path.clone(),
+                        result: res.clone(),
+                    }
+                }
+                ImageOperationKind::RestoreToDrive | ImageOperationKind::RestoreToPartition => {
+                    StorageEvent::OperationFinished {
+                        operation: fl!("restore-image"),
+                        result: res.clone().map(|()| {
+                            fl!(
+                                "notification-image-restored",
+                                image = state.image_path.clone()
+                            )
+                        }),
+                    }
+                }
+                ImageOperationKind::BurnDisc => StorageEvent::OperationFinished {
+                    operation: fl!("burn-disc-image"),
+                    result: res.clone().map(|()| {
+                        fl!("notification-disc-burned", image = state.image_path.clone())
+                    }),
+                },
+                ImageOperationKind::BlankDisc => StorageEvent::OperationFinished {
+                    operation: fl!("erase-disc"),
+                    result: res.clone().map(|()| fl!("notification-disc-erased")),
+                },
+                ImageOperationKind::MigrateDisk => StorageEvent::OperationFinished {
+                    operation: fl!("migrate-disk"),
+                    result: res.clone().map(|()| {
+                        fl!(
+                            "notification-disk-migrated",
+                            target = state
+                                .migration
+                                .as_ref()
+                                .and_then(|migration| migration.target())
+                                .map(|target| target.display_name())
+                                .unwrap_or_default()
+                        )
+                    }),
+                },
+                ImageOperationKind::CreateLiveUsb => StorageEvent::OperationFinished {
+                    operation: fl!("create-live-usb"),
+                    result: res.clone().map(|()| {
+                        fl!(
+                            "notification-live-usb-created",
+                            drive = state.drive.disk.display_name()
+                        )
+                    }),
+                },
+                ImageOperationKind::CopyPartition => StorageEvent::OperationFinished {
+                    operation: fl!("copy-partition"),
+                    result: res.clone().map(|()| {
+                        fl!(
+                            "notification-partition-copied",
+                            target = state.image_path.clone()
+                        )
+                    }),
+                },
+            };
+            let notification =
+                notification_policy::notify(event, &app.config.notifications, app.window_focused);
+
             match res {
                 Ok(()) => {
+                    // Persistence only kicks in with the distribution's boot parameter
+                    let boot_parameter = state
+                        .live_usb
+                        .as_ref()
+                        .filter(|choices| choices.plan().persistence_bytes > 0)
+                        .and_then(|choices| choices.iso)
+                        .and_then(|iso| iso.distro.boot_parameter());
                     app.dialog = Some(ShowDialog::Info {
                         title: fl!("app-title"),
-                        body: fl!("ok"),
+                        body: match boot_parameter {
+                            Some(parameter) => fl!("live-usb-boot-hint", parameter = parameter),
+                            None => fl!("ok"),
+                        },
                     });
 
-                    return Task::perform(async { load_all_drives().await.ok() }, |drives| {
-                        match drives {
-                            None => Message::None.into(),
-                            Some(drives) => Message::UpdateNav(drives, None).into(),
-                        }
-                    });
+                    let refresh =
+                        Task::perform(
+                            async { load_all_drives().await.ok() },
+                            |drives| match drives {
+                                None => Message::None.into(),
+                                Some(drives) => Message::UpdateNav(drives, None).into(),
+                            },
+                        );
+                    return Task::batch([notification, refresh]);
                 }
                 Err(e) => {
                     tracing::error!(%e, "image operation dialog error");
@@ -279,6 +518,7 @@ pub(super) fn image_operation_dialog(
                         e
                     };
                     state.error = Some(msg);
+                    return notification;
                 }
             }
         }
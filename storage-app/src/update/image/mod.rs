@@ -1,11 +1,16 @@
 mod dialogs;
 mod ops;
 
+use crate::client::ImageClient;
 use crate::fl;
+use crate::message::dialogs::ImageOperationDialogMessage;
 use crate::models::UiDrive;
-use crate::state::dialogs::{ImageOperationDialog, ImageOperationKind, ShowDialog};
+use crate::state::dialogs::{
+    DiskMigration, ImageOperationDialog, ImageOperationKind, LiveUsbChoices, ShowDialog,
+};
 use crate::state::volumes::VolumesControl;
 use cosmic::app::Task;
+use storage_types::VolumeInfo;
 
 use crate::message::app::Message;
 use crate::state::app::AppModel;
@@ -34,7 +39,7 @@ pub(super) fn attach_disk_image_dialog(
 
 pub(super) fn image_operation_dialog(
     app: &mut AppModel,
-    msg: crate::message::dialogs::ImageOperationDialogMessage,
+    msg: ImageOperationDialogMessage,
 ) -> Task<Message> {
     dialogs::image_operation_dialog(app, msg)
 }
@@ -53,7 +58,14 @@ pub(super) fn create_disk_from(app: &mut AppModel) -> Task<Message> {
             running: false,
             operation_id: None,
             progress: None,
+            eta: None,
             error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets: Vec::new(),
+            migration: None,
+            live_usb: None,
+            priority: None,
         }
         .into(),
     ));
@@ -75,7 +87,48 @@ pub(super) fn restore_image_to(app: &mut AppModel) -> Task<Message> {
             running: false,
             operation_id: None,
             progress: None,
+            eta: None,
             error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets: Vec::new(),
+            migration: None,
+            live_usb: None,
+            priority: None,
+        }
+        .into(),
+    ));
+
+    Task::none()
+}
+
+/// Open the dialog to burn an image to, or erase, the disc of the selected
+/// optical drive
+pub(super) fn disc_operation(app: &mut AppModel, kind: ImageOperationKind) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+    if !drive.disk.optical {
+        return Task::none();
+    }
+
+    app.dialog = Some(ShowDialog::ImageOperation(
+        ImageOperationDialog {
+            kind,
+            drive,
+            partition: None,
+            image_path: String::new(),
+            running: false,
+            operation_id: None,
+            progress: None,
+            eta: None,
+            error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets: Vec::new(),
+            migration: None,
+            live_usb: None,
+            priority: None,
         }
         .into(),
     ));
@@ -118,7 +171,14 @@ pub(super) fn create_disk_from_partition(app: &mut AppModel) -> Task<Message> {
             running: false,
             operation_id: None,
             progress: None,
+            eta: None,
             error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets: Vec::new(),
+            migration: None,
+            live_usb: None,
+            priority: None,
         }
         .into(),
     ));
@@ -161,10 +221,205 @@ pub(super) fn restore_image_to_partition(app: &mut AppModel) -> Task<Message> {
             running: false,
             operation_id: None,
             progress: None,
+            eta: None,
             error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets: Vec::new(),
+            migration: None,
+            live_usb: None,
+            priority: None,
         }
         .into(),
     ));
 
     Task::none()
 }
+
+/// Open the dialog to copy the selected partition onto another, at least as
+/// large, one of any drive
+pub(super) fn copy_partition(app: &mut AppModel) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+
+    let Some(volumes_control) = app.nav.active_data::<VolumesControl>() else {
+        app.dialog = Some(ShowDialog::Info {
+            title: fl!("app-title"),
+            body: fl!("no-disk-selected"),
+        });
+        return Task::none();
+    };
+
+    let Some(partition) = volumes_control
+        .segments
+        .get(volumes_control.selected_segment)
+        .and_then(|s| s.volume.clone())
+    else {
+        app.dialog = Some(ShowDialog::Info {
+            title: fl!("app-title"),
+            body: "Select a partition to copy.".to_string(),
+        });
+        return Task::none();
+    };
+
+    let copy_targets: Vec<VolumeInfo> = app
+        .sidebar
+        .drives
+        .iter()
+        .filter(|drive| !drive.disk.read_only)
+        .flat_map(|drive| &drive.volumes_flat)
+        .map(|volume| &volume.volume)
+        .filter(|volume| {
+            volume.partition_number > 0
+                && volume.device_path.is_some()
+                && volume.device_path != partition.device_path
+                && volume.size >= partition.size
+        })
+        .cloned()
+        .collect();
+
+    app.dialog = Some(ShowDialog::ImageOperation(
+        ImageOperationDialog {
+            kind: ImageOperationKind::CopyPartition,
+            drive,
+            partition: Some(partition),
+            image_path: String::new(),
+            running: false,
+            operation_id: None,
+            progress: None,
+            eta: None,
+            error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets,
+            migration: None,
+            live_usb: None,
+            priority: None,
+        }
+        .into(),
+    ));
+
+    Task::none()
+}
+
+/// Open the migration assistant, to move the selected drive onto a larger one
+pub(super) fn migrate_disk(app: &mut AppModel) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+
+    if drive.disk.partition_table_type.as_deref() != Some("gpt") {
+        app.dialog = Some(ShowDialog::Info {
+            title: fl!("migrate-disk"),
+            body: fl!("migrate-disk-gpt-only"),
+        });
+        return Task::none();
+    }
+
+    let mut partitions = drive.partitions.clone();
+    partitions.sort_by_key(|partition| partition.offset);
+    let grow = partitions
+        .iter()
+        .map(|partition| {
+            partition
+                .filesystem_type
+                .as_deref()
+                .is_some_and(storage_types::partition_copy::grows_after_copy)
+        })
+        .collect();
+    let mut migration = DiskMigration {
+        targets: Vec::new(),
+        target_index: None,
+        partitions,
+        proportional: false,
+        grow,
+    };
+    // Offer the drives the partitions fit on without growing any of them
+    migration.targets = app
+        .sidebar
+        .drives
+        .iter()
+        .map(|target| &target.disk)
+        .filter(|target| {
+            target.device != drive.disk.device
+                && !target.read_only
+                && !target.optical
+                && target.size > drive.disk.size
+        })
+        .filter(|target| migration.fits(target))
+        .cloned()
+        .collect();
+
+    app.dialog = Some(ShowDialog::ImageOperation(
+        ImageOperationDialog {
+            kind: ImageOperationKind::MigrateDisk,
+            drive,
+            partition: None,
+            image_path: String::new(),
+            running: false,
+            operation_id: None,
+            progress: None,
+            eta: None,
+            error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets: Vec::new(),
+            migration: Some(migration),
+            live_usb: None,
+            priority: None,
+        }
+        .into(),
+    ));
+
+    Task::none()
+}
+
+/// Open the dialog to make the selected drive a bootable copy of a
+/// distribution ISO, with persistence and data partitions
+pub(super) fn create_live_usb(app: &mut AppModel) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+    if drive.disk.read_only || drive.disk.optical {
+        return Task::none();
+    }
+
+    app.dialog = Some(ShowDialog::ImageOperation(
+        ImageOperationDialog {
+            kind: ImageOperationKind::CreateLiveUsb,
+            drive,
+            partition: None,
+            image_path: String::new(),
+            running: false,
+            operation_id: None,
+            progress: None,
+            eta: None,
+            error: None,
+            verify: true,
+            full_erase: false,
+            copy_targets: Vec::new(),
+            migration: None,
+            live_usb: Some(LiveUsbChoices::default()),
+            priority: None,
+        }
+        .into(),
+    ));
+
+    Task::none()
+}
+
+/// Inspect the ISO image picked for a live USB drive
+pub(super) fn probe_live_iso(image_path: String) -> Task<Message> {
+    Task::perform(
+        async move {
+            ImageClient::new()
+                .await
+                .map_err(|e| format!("Failed to create image client: {}", e))?
+                .probe_live_iso(&image_path)
+                .await
+                .map_err(|e| format!("Failed to inspect the image: {}", e))
+        },
+        |res| Message::ImageOperationDialog(ImageOperationDialogMessage::LiveIsoProbed(res)).into(),
+    )
+}
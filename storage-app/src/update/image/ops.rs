@@ -4,16 +4,23 @@
 use crate::client::{FilesystemsClient, ImageClient};
 use crate::models::UiDrive;
 use crate::state::dialogs::ImageOperationKind;
-use storage_types::VolumeInfo;
+use storage_types::{DiskInfo, LiveUsbPlan, MigrationPlan, OperationPriority, VolumeInfo};
 
 /// Start a backup or restore operation via the storage-service.
 /// Returns the operation_id for progress tracking and cancel.
 /// Caller is responsible for unmounting before restore (this function does it).
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn start_image_operation(
     kind: ImageOperationKind,
     drive: UiDrive,
     partition: Option<VolumeInfo>,
     image_path: String,
+    copy_target: Option<VolumeInfo>,
+    migration: Option<(DiskInfo, MigrationPlan)>,
+    live_usb: Option<LiveUsbPlan>,
+    verify: bool,
+    full_erase: bool,
+    priority: Option<OperationPriority>,
 ) -> anyhow::Result<String> {
     let image_client = ImageClient::new()
         .await
@@ -23,7 +30,7 @@ pub(super) async fn start_image_operation(
         ImageOperationKind::CreateFromDrive => {
             let device = &drive.disk.device;
             let operation_id = image_client
-                .backup_drive(device, &image_path)
+                .backup_drive(device, &image_path, priority.as_ref())
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to start backup: {}", e))?;
             Ok(operation_id)
@@ -37,31 +44,16 @@ pub(super) async fn start_image_operation(
                 .as_deref()
                 .ok_or_else(|| anyhow::anyhow!("Partition has no device path"))?;
             let operation_id = image_client
-                .backup_partition(device, &image_path)
+                .backup_partition(device, &image_path, priority.as_ref())
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to start backup: {}", e))?;
             Ok(operation_id)
         }
         ImageOperationKind::RestoreToDrive => {
-            let fs_client = FilesystemsClient::new()
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to create filesystems client: {}", e))?;
-            for p in &drive.volumes_flat {
-                if p.is_mounted() {
-                    let device = p
-                        .volume
-                        .device_path
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("Partition has no device path"))?;
-                    fs_client
-                        .unmount(device, false, false)
-                        .await
-                        .map_err(|e| anyhow::anyhow!("Failed to unmount {}: {}", device, e))?;
-                }
-            }
+            unmount_drive_volumes(&drive).await?;
             let device = &drive.disk.device;
             let operation_id = image_client
-                .restore_drive(device, &image_path)
+                .restore_drive(device, &image_path, priority.as_ref())
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to start restore: {}", e))?;
             Ok(operation_id)
@@ -88,10 +80,97 @@ pub(super) async fn start_image_operation(
                 .as_deref()
                 .ok_or_else(|| anyhow::anyhow!("Partition has no device path"))?;
             let operation_id = image_client
-                .restore_partition(device, &image_path)
+                .restore_partition(device, &image_path, priority.as_ref())
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to start restore: {}", e))?;
             Ok(operation_id)
         }
+        ImageOperationKind::BurnDisc => {
+            unmount_drive_volumes(&drive).await?;
+            let operation_id = image_client
+                .burn_disc(&drive.disk.device, &image_path, verify)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to start burning: {}", e))?;
+            Ok(operation_id)
+        }
+        ImageOperationKind::BlankDisc => {
+            unmount_drive_volumes(&drive).await?;
+            let operation_id = image_client
+                .blank_disc(&drive.disk.device, full_erase)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to start erasing: {}", e))?;
+            Ok(operation_id)
+        }
+        ImageOperationKind::CopyPartition => {
+            let (Some(source), Some(target)) = (partition, copy_target) else {
+                anyhow::bail!("No partition selected");
+            };
+            let fs_client = FilesystemsClient::new()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to create filesystems client: {}", e))?;
+            let mut devices = Vec::new();
+            for volume in [&source, &target] {
+                let device = volume
+                    .device_path
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("Partition has no device path"))?;
+                // The copy has to be consistent, so the source is unmounted too
+                if volume.is_mounted() {
+                    fs_client
+                        .unmount(&device, false, false)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to unmount {}: {}", device, e))?;
+                }
+                devices.push(device);
+            }
+            let operation_id = image_client
+                .copy_partition(&devices[0], &devices[1], priority.as_ref())
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to start copying: {}", e))?;
+            Ok(operation_id)
+        }
+        ImageOperationKind::MigrateDisk => {
+            let Some((target, plan)) = migration else {
+                anyhow::bail!("No target drive selected");
+            };
+            unmount_drive_volumes(&drive).await?;
+            let operation_id = image_client
+                .migrate_disk(&drive.disk.device, &target.device, &plan)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to start the migration: {}", e))?;
+            Ok(operation_id)
+        }
+        ImageOperationKind::CreateLiveUsb => {
+            let Some(plan) = live_usb else {
+                anyhow::bail!("No live USB layout chosen");
+            };
+            unmount_drive_volumes(&drive).await?;
+            let operation_id = image_client
+                .create_live_usb(&drive.disk.device, &image_path, &plan)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to start writing the image: {}", e))?;
+            Ok(operation_id)
+        }
+    }
+}
+
+/// Unmount every mounted volume of `drive` before it is overwritten
+async fn unmount_drive_volumes(drive: &UiDrive) -> anyhow::Result<()> {
+    let fs_client = FilesystemsClient::new()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create filesystems client: {}", e))?;
+    for p in &drive.volumes_flat {
+        if p.is_mounted() {
+            let device = p
+                .volume
+                .device_path
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Partition has no device path"))?;
+            fs_client
+                .unmount(device, false, false)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to unmount {}: {}", device, e))?;
+        }
     }
+    Ok(())
 }
@@ -0,0 +1,67 @@
+use crate::client::DisksClient;
+use crate::message::dialogs::InspectDialogMessage;
+use crate::state::dialogs::{InspectDialog, ShowDialog};
+use cosmic::app::Task;
+use storage_types::inspect::INSPECT_READS;
+
+use super::sector_viewer;
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Read the start of a volume and show its decoded superblock or header
+pub(super) fn open_inspect(app: &mut AppModel, device: String, size: u64) -> Task<Message> {
+    if app.dialog.is_some() {
+        return Task::none();
+    }
+    app.dialog = Some(ShowDialog::Inspect(InspectDialog {
+        device: device.clone(),
+        size,
+        ranges: None,
+        error: None,
+    }));
+
+    Task::perform(
+        async move {
+            let client = DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?;
+            let mut ranges = Vec::new();
+            for (offset, length) in INSPECT_READS {
+                if offset >= size {
+                    break;
+                }
+                let range = client
+                    .read_sectors(&device, offset, length)
+                    .await
+                    .map_err(|e| format!("Failed to read sectors: {}", e))?;
+                ranges.push(range);
+            }
+            Ok(ranges)
+        },
+        |res| Message::InspectDialog(InspectDialogMessage::Loaded(res)).into(),
+    )
+}
+
+pub(super) fn inspect_dialog(app: &mut AppModel, msg: InspectDialogMessage) -> Task<Message> {
+    let Some(ShowDialog::Inspect(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        InspectDialogMessage::Loaded(res) => match res {
+            Ok(ranges) => state.ranges = Some(ranges),
+            Err(e) => {
+                tracing::warn!(%e, "could not read the volume header");
+                state.error = Some(e);
+            }
+        },
+        InspectDialogMessage::ViewSectors(offset) => {
+            let device = state.device.clone();
+            let size = state.size;
+            app.dialog = None;
+            return sector_viewer::open_sector_viewer_at(app, device, size, offset);
+        }
+        InspectDialogMessage::Close => app.dialog = None,
+    }
+    Task::none()
+}
@@ -0,0 +1,89 @@
+use crate::client::ServiceClient;
+use crate::logging;
+use crate::message::logs::LogsMessage;
+use cosmic::app::Task;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use storage_types::{LogEntry, LogLevel};
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// App log lines read into the viewer
+const MAX_APP_LINES: usize = 5000;
+
+/// How far back the service log is read
+const SERVICE_LOG_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub(super) fn handle_logs_message(app: &mut AppModel, msg: LogsMessage) -> Task<Message> {
+    let logs = &mut app.logs;
+    match msg {
+        LogsMessage::Load => {
+            return Task::perform(
+                async {
+                    tokio::task::spawn_blocking(|| logging::recent_lines(MAX_APP_LINES))
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .map(|lines| lines.iter().filter_map(|l| LogEntry::parse(l)).collect())
+                        .map_err(|e| format!("Failed to read the log files: {}", e))
+                },
+                |res| Message::Logs(LogsMessage::Loaded(res)).into(),
+            );
+        }
+        LogsMessage::Loaded(res) => match res {
+            Ok(entries) => {
+                logs.entries = entries;
+                logs.error = None;
+            }
+            Err(e) => {
+                tracing::warn!(%e, "failed to load app logs");
+                logs.error = Some(e);
+            }
+        },
+        LogsMessage::LevelChanged(index) => {
+            if let Some(level) = LogLevel::ALL.get(index) {
+                logs.filter.level = *level;
+            }
+        }
+        LogsMessage::ModuleChanged(module) => logs.filter.module = module,
+        LogsMessage::OperationChanged(operation) => logs.filter.operation = operation,
+        LogsMessage::DeviceChanged(device) => logs.filter.device = device,
+        LogsMessage::FetchServiceLog => {
+            if logs.loading_service {
+                return Task::none();
+            }
+            logs.loading_service = true;
+            logs.service_error = None;
+
+            let operation = logs.filter.operation.trim().to_string();
+            let device = logs.filter.device.trim().to_string();
+            let since = SystemTime::now()
+                .checked_sub(SERVICE_LOG_WINDOW)
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            return Task::perform(
+                async move {
+                    ServiceClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create service client: {}", e))?
+                        .get_operation_log(&operation, &device, since)
+                        .await
+                        .map_err(|e| format!("Failed to fetch the service log: {}", e))
+                },
+                |res| Message::Logs(LogsMessage::ServiceLogLoaded(res)).into(),
+            );
+        }
+        LogsMessage::ServiceLogLoaded(res) => {
+            logs.loading_service = false;
+            match res {
+                Ok(entries) => logs.service_entries = Some(entries),
+                Err(e) => {
+                    tracing::warn!(%e, "failed to fetch service log");
+                    logs.service_error = Some(e);
+                }
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,137 @@
+use crate::client::PartitionsClient;
+use crate::message::dialogs::LostPartitionsDialogMessage;
+use crate::models::{UiDrive, load_all_drives};
+use crate::state::dialogs::{LostPartitionsDialog, ShowDialog};
+use cosmic::app::Task;
+use storage_types::LostPartitionConfidence;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Open the lost partitions dialog for the active drive and start searching
+pub(super) fn find_lost_partitions(app: &mut AppModel) -> Task<Message> {
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+
+    let device = drive.device().to_string();
+    app.dialog = Some(ShowDialog::LostPartitions(LostPartitionsDialog {
+        drive,
+        found: None,
+        selected: Vec::new(),
+        running: true,
+        error: None,
+    }));
+
+    Task::perform(
+        async move {
+            PartitionsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create partitions client: {}", e))?
+                .scan_lost_partitions(&device)
+                .await
+                .map_err(|e| format!("Failed to search for lost partitions: {}", e))
+        },
+        |res| Message::LostPartitionsDialog(LostPartitionsDialogMessage::Scanned(res)).into(),
+    )
+}
+
+pub(super) fn lost_partitions_dialog(
+    app: &mut AppModel,
+    msg: LostPartitionsDialogMessage,
+) -> Task<Message> {
+    let Some(ShowDialog::LostPartitions(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        LostPartitionsDialogMessage::Scanned(res) => {
+            state.running = false;
+            match res {
+                Ok(found) => {
+                    state.selected = vec![false; found.len()];
+                    state.found = Some(found);
+                }
+                Err(e) => {
+                    tracing::error!(%e, "lost partition search error");
+                    state.found = Some(Vec::new());
+                    state.error = Some(e);
+                }
+            }
+        }
+        LostPartitionsDialogMessage::Toggle(index, selected) => {
+            // Partitions overlapping one in use cannot be recreated
+            let allowed = state
+                .found
+                .as_ref()
+                .and_then(|found| found.get(index))
+                .is_some_and(|partition| partition.confidence != LostPartitionConfidence::Low);
+            if allowed && !state.running {
+                state.selected[index] = selected;
+            }
+        }
+        LostPartitionsDialogMessage::Recreate => {
+            if state.running {
+                return Task::none();
+            }
+            let partitions: Vec<_> = state
+                .found
+                .iter()
+                .flatten()
+                .zip(&state.selected)
+                .filter(|(_, selected)| **selected)
+                .map(|(partition, _)| partition.clone())
+                .collect();
+            if partitions.is_empty() {
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+
+            let device = state.drive.device().to_string();
+            return Task::perform(
+                async move {
+                    let client = PartitionsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create partitions client: {}", e))?;
+                    for partition in &partitions {
+                        client
+                            .recreate_lost_partition(&device, partition)
+                            .await
+                            .map_err(|e| format!("Failed to recreate partition: {}", e))?;
+                    }
+                    Ok(())
+                },
+                |res| {
+                    Message::LostPartitionsDialog(LostPartitionsDialogMessage::Recreated(res))
+                        .into()
+                },
+            );
+        }
+        LostPartitionsDialogMessage::Recreated(res) => {
+            state.running = false;
+            match res {
+                Ok(()) => app.dialog = None,
+                Err(e) => {
+                    tracing::error!(%e, "partition recreation error");
+                    state.error = Some(e);
+                }
+            }
+            // Show the partitions recreated, also those before a failure
+            return Task::perform(load_all_drives(), |res| match res {
+                Ok(drives) => Message::UpdateNav(drives, None).into(),
+                Err(e) => {
+                    tracing::error!(?e, "failed to reload drives");
+                    Message::None.into()
+                }
+            });
+        }
+        LostPartitionsDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,162 @@
+use crate::client::FilesystemsClient;
+use crate::message::dialogs::LowSpaceDialogMessage;
+use crate::notification_policy::{self, StorageEvent};
+use crate::state::dialogs::{
+    LOW_SPACE_BYTES_CHOICES, LOW_SPACE_PERCENT_CHOICES, LowSpaceDialog, ShowDialog,
+};
+use crate::state::volumes::VolumesControl;
+use cosmic::app::Task;
+use storage_types::{LowSpaceAction, LowSpaceRule, LowSpaceWarning};
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Open the low space alert dialog for the selected mounted filesystem
+pub(super) fn open_low_space(app: &mut AppModel) -> Task<Message> {
+    if app.dialog.is_some() {
+        return Task::none();
+    }
+    let Some(volumes_control) = app.nav.active_data::<VolumesControl>() else {
+        return Task::none();
+    };
+    let Some(mount_point) = volumes_control
+        .selected_volume_node()
+        .map(|node| &node.volume)
+        .or_else(|| {
+            volumes_control
+                .segments
+                .get(volumes_control.selected_segment)
+                .and_then(|segment| segment.volume.as_ref())
+        })
+        .and_then(|volume| volume.mount_points.first().cloned())
+    else {
+        return Task::none();
+    };
+
+    app.dialog = Some(ShowDialog::LowSpace(LowSpaceDialog {
+        mount_point,
+        rule: None,
+        running: false,
+        error: None,
+    }));
+
+    Task::perform(
+        async move {
+            FilesystemsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                .list_low_space_rules()
+                .await
+                .map_err(|e| format!("Failed to load low space alerts: {}", e))
+        },
+        |res| Message::LowSpaceDialog(LowSpaceDialogMessage::RulesLoaded(res)).into(),
+    )
+}
+
+/// Save `rule` as the rule of the dialog's mount point
+fn save_rule(state: &mut LowSpaceDialog, rule: LowSpaceRule) -> Task<Message> {
+    state.rule = Some(rule.clone());
+    state.running = true;
+    state.error = None;
+    Task::perform(
+        async move {
+            FilesystemsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                .set_low_space_rule(&rule)
+                .await
+                .map_err(|e| format!("Failed to save low space alert: {}", e))
+        },
+        |res| Message::LowSpaceDialog(LowSpaceDialogMessage::Saved(res)).into(),
+    )
+}
+
+pub(super) fn low_space_dialog(app: &mut AppModel, msg: LowSpaceDialogMessage) -> Task<Message> {
+    let Some(ShowDialog::LowSpace(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    let rule = match msg {
+        LowSpaceDialogMessage::RulesLoaded(res) => {
+            match res {
+                Ok(rules) => {
+                    let mount_point = state.mount_point.clone();
+                    state.rule = Some(
+                        rules
+                            .into_iter()
+                            .find(|rule| rule.mount_point == mount_point)
+                            .unwrap_or(LowSpaceRule {
+                                mount_point,
+                                ..LowSpaceRule::default()
+                            }),
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!(%e, "could not load low space alerts");
+                    state.error = Some(e);
+                }
+            }
+            return Task::none();
+        }
+        LowSpaceDialogMessage::SetPercent(index) => {
+            let (Some(rule), Some(min_free_percent)) =
+                (state.rule.as_ref(), LOW_SPACE_PERCENT_CHOICES.get(index))
+            else {
+                return Task::none();
+            };
+            LowSpaceRule {
+                min_free_percent: *min_free_percent,
+                ..rule.clone()
+            }
+        }
+        LowSpaceDialogMessage::SetBytes(index) => {
+            let (Some(rule), Some(min_free_bytes)) =
+                (state.rule.as_ref(), LOW_SPACE_BYTES_CHOICES.get(index))
+            else {
+                return Task::none();
+            };
+            LowSpaceRule {
+                min_free_bytes: *min_free_bytes,
+                ..rule.clone()
+            }
+        }
+        LowSpaceDialogMessage::SetAction(index) => {
+            let (Some(rule), Some(action)) = (state.rule.as_ref(), LowSpaceAction::ALL.get(index))
+            else {
+                return Task::none();
+            };
+            LowSpaceRule {
+                action: *action,
+                ..rule.clone()
+            }
+        }
+        LowSpaceDialogMessage::Saved(res) => {
+            state.running = false;
+            if let Err(e) = res {
+                tracing::error!(%e, "low space alert error");
+                state.error = Some(e);
+            }
+            return Task::none();
+        }
+        LowSpaceDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+            return Task::none();
+        }
+    };
+
+    if state.running || state.rule.as_ref() == Some(&rule) {
+        return Task::none();
+    }
+    save_rule(state, rule)
+}
+
+/// Announce a filesystem that dropped below its low space rule
+pub(super) fn warned(app: &AppModel, warning: LowSpaceWarning) -> Task<Message> {
+    notification_policy::notify(
+        StorageEvent::LowSpace(warning),
+        &app.config.notifications,
+        app.window_focused,
+    )
+}
@@ -1,32 +1,72 @@
+mod automount;
+mod bind_mounts;
 mod btrfs;
+mod capacity;
+pub(crate) mod confirmation;
+mod defrag;
+mod diagnostics;
+mod diagram;
 mod drive;
+mod encrypted_folders;
+mod esp_sync;
+mod gpt_entries;
+mod health_check;
 mod image;
+mod inspect;
+mod logs;
+mod lost_partitions;
+mod low_space;
+mod mtp;
 mod nav;
 mod network;
+mod performance;
+mod read_only;
+mod report;
+mod reveal;
+mod sector_viewer;
+mod selinux;
+mod setup;
 mod smart;
+mod statistics;
+mod user_mounts;
+mod vaults;
 pub(crate) mod volumes;
 
 use std::collections::HashSet;
 
 use crate::app::APP_ID;
 use crate::app::REPOSITORY;
+use crate::client::BtrfsClient;
 use crate::client::FilesystemsClient;
+use crate::client::ImageClient;
+use crate::client::OperationsClient;
+use crate::client::ServiceClient;
 use crate::config::{Config, LoggingLevel};
 use crate::errors::ui::{UiErrorContext, log_error_and_show_dialog};
 use crate::fl;
 use crate::logging;
 use crate::message::app::{ImagePathPickerKind, Message};
+use crate::message::health_check::HealthCheckMessage;
+use crate::message::logs::LogsMessage;
 use crate::message::network::NetworkMessage;
+use crate::message::statistics::StatisticsMessage;
+use crate::message::vaults::VaultsMessage;
 use crate::models::load_all_drives;
-use crate::state::app::AppModel;
-use crate::state::dialogs::ShowDialog;
+use crate::notification_policy::{self, StorageEvent};
+use crate::state::app::{AppModel, ContextPage};
+use crate::state::dialogs::{ImageOperationKind, ShowDialog};
 use crate::state::sidebar::SidebarNodeKey;
 use crate::state::volumes::{DetailTab, UsageTabState, VolumesControl};
+use crate::utils::sandbox;
 use cosmic::app::Task;
 use cosmic::cosmic_config::CosmicConfigEntry;
 use cosmic::dialog::file_chooser;
 use cosmic::widget::nav_bar;
-use storage_types::{UsageCategory, UsageScanParallelismPreset};
+use storage_contracts::OperationEvent;
+use storage_types::{
+    ByteUnits, ConfirmationLevel, MountNamingScheme, RaidHealthEvent, TemperatureLevel,
+    TemperatureUnit, UsageCategory, UsageScanParallelismPreset,
+};
 
 const USAGE_TOP_FILES_MIN: u32 = 1;
 const USAGE_TOP_FILES_MAX: u32 = 1000;
@@ -90,6 +130,25 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                 app.context_page = context_page;
                 app.core.window.show_context = true;
             }
+            if context_page == ContextPage::Logs && app.core.window.show_context {
+                return logs::handle_logs_message(app, LogsMessage::Load);
+            }
+            if context_page == ContextPage::Statistics && app.core.window.show_context {
+                return statistics::handle_statistics_message(app, StatisticsMessage::Load);
+            }
+            // The first opening runs a check; later ones show its report
+            if context_page == ContextPage::HealthCheck
+                && app.core.window.show_context
+                && app.health_check.report.is_none()
+            {
+                return health_check::handle_health_check_message(app, HealthCheckMessage::Run);
+            }
+            if context_page == ContextPage::Vaults && app.core.window.show_context {
+                return vaults::handle_vaults_message(app, VaultsMessage::Load);
+            }
+            if context_page == ContextPage::Settings && app.core.window.show_context {
+                return load_deferred_jobs();
+            }
         }
         Message::UpdateConfig(config) => {
             app.config = config;
@@ -97,6 +156,194 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::FilesystemToolsLoaded(tools) => {
             app.filesystem_tools = tools;
         }
+        Message::OpticalMediaLoaded(media) => {
+            app.optical.media.extend(media);
+        }
+        Message::DriveHealthLoaded(summaries) => {
+            let events = notification_policy::health_events(&app.sidebar.health, &summaries);
+            app.sidebar.set_health(summaries);
+            return Task::batch(events.into_iter().map(|event| {
+                notification_policy::notify(event, &app.config.notifications, app.window_focused)
+            }));
+        }
+        Message::TemperatureAlert {
+            device,
+            level,
+            celsius,
+        } => {
+            let Some(level) = TemperatureLevel::parse(&level) else {
+                return Task::none();
+            };
+            return notification_policy::notify(
+                StorageEvent::Temperature {
+                    device,
+                    level,
+                    temperature: app.config.temperature_unit.format(celsius as i64),
+                },
+                &app.config.notifications,
+                app.window_focused,
+            );
+        }
+        Message::LowSpaceWarning(warning) => {
+            return low_space::warned(app, warning);
+        }
+        Message::ExportReport => {
+            return report::export_report(app);
+        }
+        Message::ReportDestinationChosen(Some((path, arrays))) => {
+            return report::save_report(app, path, arrays);
+        }
+        Message::ReportDestinationChosen(None) => {}
+        Message::ReportSaved(result) => {
+            return report::report_saved(result);
+        }
+        Message::ExportLayoutDiagram => {
+            return diagram::export_layout_diagram();
+        }
+        Message::LayoutDiagramDestinationChosen(Some(path)) => {
+            return diagram::save_layout_diagram(app, path);
+        }
+        Message::LayoutDiagramDestinationChosen(None) => {}
+        Message::LayoutDiagramSaved(result) => {
+            return diagram::layout_diagram_saved(result);
+        }
+        Message::RaidHealthChanged { array, event } => {
+            let Some(event) = RaidHealthEvent::parse(&event) else {
+                return Task::none();
+            };
+            return notification_policy::notify(
+                StorageEvent::Raid { array, event },
+                &app.config.notifications,
+                app.window_focused,
+            );
+        }
+        Message::SafetySnapshotPolicyLoaded(policy) => {
+            app.safety_snapshot_policy = Some(policy);
+        }
+        Message::SafetySnapshotPolicyChanged(policy) => {
+            // Applied once the service has stored it (may prompt for authorization)
+            return Task::perform(
+                async move {
+                    let client = BtrfsClient::new().await?;
+                    client.set_safety_snapshot_policy(&policy).await?;
+                    Ok::<_, crate::client::error::ClientError>(policy)
+                },
+                |result| match result {
+                    Ok(policy) => Message::SafetySnapshotPolicyLoaded(policy).into(),
+                    Err(e) => log_error_and_show_dialog(
+                        fl!("safety-snapshots-save-failed"),
+                        e.into(),
+                        UiErrorContext::new("set_safety_snapshot_policy"),
+                    )
+                    .into(),
+                },
+            );
+        }
+        Message::OperationPrioritiesLoaded(settings) => {
+            app.operation_priorities = Some(settings);
+        }
+        Message::OperationPrioritiesChanged(settings) => {
+            // Applied once the service has stored them (may prompt for authorization)
+            return Task::perform(
+                async move {
+                    let client = ImageClient::new().await?;
+                    client.set_operation_priorities(&settings).await?;
+                    Ok::<_, crate::client::error::ClientError>(settings)
+                },
+                |result| match result {
+                    Ok(settings) => Message::OperationPrioritiesLoaded(settings).into(),
+                    Err(e) => log_error_and_show_dialog(
+                        fl!("operation-priorities-save-failed"),
+                        e.into(),
+                        UiErrorContext::new("set_operation_priorities"),
+                    )
+                    .into(),
+                },
+            );
+        }
+        Message::PowerPoliciesLoaded(settings) => {
+            app.power_policies = Some(settings);
+        }
+        Message::PowerPoliciesChanged(settings) => {
+            return Task::perform(
+                async move {
+                    let client = ServiceClient::new().await?;
+                    client.set_power_policies(&settings).await?;
+                    Ok::<_, crate::client::error::ClientError>(settings)
+                },
+                |result| match result {
+                    Ok(settings) => Message::PowerPoliciesLoaded(settings).into(),
+                    Err(e) => log_error_and_show_dialog(
+                        fl!("power-policies-save-failed"),
+                        e.into(),
+                        UiErrorContext::new("set_power_policies"),
+                    )
+                    .into(),
+                },
+            );
+        }
+        Message::DeferredJobsLoaded(jobs) => {
+            app.deferred_jobs = jobs;
+        }
+        Message::OperationsLoaded(operations) => {
+            app.operations = operations;
+        }
+        Message::OperationEvent(event) => match event {
+            OperationEvent::Progress(progress) => {
+                match app
+                    .operations
+                    .iter_mut()
+                    .find(|operation| operation.operation_id == progress.operation_id)
+                {
+                    Some(operation) => *operation = progress,
+                    None => app.operations.push(progress),
+                }
+            }
+            OperationEvent::Completed { operation_id, .. }
+            | OperationEvent::Failed { operation_id, .. }
+            | OperationEvent::Cancelled { operation_id, .. } => {
+                app.operations
+                    .retain(|operation| operation.operation_id != operation_id);
+            }
+        },
+        Message::CancelOperation(operation_id) => {
+            return Task::perform(
+                async move {
+                    let client = OperationsClient::new().await?;
+                    client.cancel_operation(operation_id).await
+                },
+                |result| match result {
+                    Ok(()) => Message::None.into(),
+                    Err(e) => log_error_and_show_dialog(
+                        fl!("cancel-operation-failed"),
+                        e.into(),
+                        UiErrorContext::new("cancel_operation"),
+                    )
+                    .into(),
+                },
+            );
+        }
+        Message::RunDeferredJob(job, target) => {
+            // The service lists the job until the check it wakes up starts it
+            app.deferred_jobs
+                .retain(|entry| entry.job != job || entry.target != target);
+            return Task::perform(
+                async move {
+                    let client = ServiceClient::new().await?;
+                    client.run_deferred_job(job, &target).await?;
+                    Ok::<_, crate::client::error::ClientError>(())
+                },
+                |result| match result {
+                    Ok(()) => Message::None.into(),
+                    Err(e) => log_error_and_show_dialog(
+                        fl!("run-deferred-job-failed"),
+                        e.into(),
+                        UiErrorContext::new("run_deferred_job"),
+                    )
+                    .into(),
+                },
+            );
+        }
         Message::UsageScanLoad {
             scan_id,
             top_files_per_category,
@@ -579,6 +826,8 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
             }
         }
         Message::UsageDeleteCompleted { result } => {
+            // Thin virtual disks only shrink on the host once trimmed
+            let suggest_trim = app.hypervisor.is_some();
             if let Some(volumes_control) = app.nav.active_data_mut::<VolumesControl>() {
                 volumes_control.usage_state.deleting = false;
                 match result {
@@ -656,11 +905,15 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                         }
 
                         volumes_control.usage_state.error = None;
-                        volumes_control.usage_state.operation_status = Some(fl!(
+                        let mut status = fl!(
                             "usage-delete-summary",
                             deleted = delete_result.deleted.len(),
                             failed = delete_result.failed.len(),
-                        ));
+                        );
+                        if suggest_trim && !delete_result.deleted.is_empty() {
+                            status = format!("{status} {}", fl!("usage-delete-trim-hint"));
+                        }
+                        volumes_control.usage_state.operation_status = Some(status);
                     }
                     Err(error) => {
                         volumes_control.usage_state.error = Some(error);
@@ -704,6 +957,18 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
 
             logging::set_log_to_disk(log_to_disk);
         }
+        Message::ToggleUsageStatistics(enabled) => {
+            app.config.usage_statistics = enabled;
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+
+            return statistics::handle_statistics_message(
+                app,
+                StatisticsMessage::SetEnabled(enabled),
+            );
+        }
         Message::LogLevelChanged(index) => {
             let level = LoggingLevel::from_index(index);
             app.config.log_level = level;
@@ -714,6 +979,59 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
 
             logging::set_log_level(level);
         }
+        Message::MountBaseDirChanged(base_dir) => {
+            app.config.mount_base_dir = base_dir;
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+        }
+        Message::MountNamingSchemeChanged(index) => {
+            app.config.mount_naming = MountNamingScheme::from_index(index);
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+        }
+        Message::TemperatureUnitChanged(index) => {
+            app.config.temperature_unit = TemperatureUnit::from_index(index);
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+        }
+        Message::ByteUnitsChanged(index) => {
+            app.config.byte_units = ByteUnits::from_index(index);
+            storage_types::set_byte_format(app.config.byte_format());
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+        }
+        Message::ConfirmationLevelChanged(index) => {
+            app.config.confirmation_level = ConfirmationLevel::from_index(index);
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+        }
+        Message::CapacityAlertDaysChanged(days) => {
+            app.config.capacity_alert_days = days;
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+        }
+        Message::NotificationCategoryToggled(category, enabled) => {
+            app.config.notifications.set(category, enabled);
+
+            if let Ok(helper) = cosmic::cosmic_config::Config::new(APP_ID, Config::VERSION) {
+                let _ = app.config.write_entry(&helper);
+            }
+        }
+        Message::WindowFocusChanged(focused) => {
+            app.window_focused = focused;
+        }
         Message::OpenImagePathPicker(kind) => {
             let title = match kind {
                 ImagePathPickerKind::NewDiskImage | ImagePathPickerKind::ImageOperationCreate => {
@@ -758,6 +1076,12 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                             }
                         }
                     };
+                    // The service opens the image on the host, where
+                    // document portal paths do not exist
+                    let result = match result {
+                        Some(path) => Some(sandbox::host_path(path).await),
+                        None => None,
+                    };
 
                     Message::ImagePathPicked(kind, result)
                 },
@@ -784,7 +1108,11 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                 if let Some(ShowDialog::ImageOperation(state)) = app.dialog.as_mut()
                     && let Some(path) = path
                 {
-                    state.image_path = path;
+                    state.image_path = path.clone();
+                    if let Some(choices) = state.live_usb.as_mut() {
+                        choices.iso = None;
+                        return image::probe_live_iso(path);
+                    }
                 }
             }
         },
@@ -799,6 +1127,7 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                 tracing::warn!("received volumes message with no active VolumesControl");
                 return Task::none();
             };
+            volumes_control.mount_path_policy = app.config.mount_path_policy();
 
             return volumes_control.update(message, &mut app.dialog);
         }
@@ -806,8 +1135,17 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::FormatDisk(msg) => {
             return drive::format_disk(app, msg);
         }
-        Message::DriveRemoved(_drive_model) => {
-            return Task::perform(
+        Message::DriveRemoved(device) => {
+            let notification = if device.is_empty() {
+                Task::none()
+            } else {
+                notification_policy::notify(
+                    StorageEvent::DriveRemoved { device },
+                    &app.config.notifications,
+                    app.window_focused,
+                )
+            };
+            let refresh = Task::perform(
                 async {
                     match load_all_drives().await {
                         Ok(drives) => Some(drives),
@@ -822,9 +1160,15 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                     Some(drives) => Message::UpdateNav(drives, None).into(),
                 },
             );
+            return Task::batch([notification, refresh]);
         }
-        Message::DriveAdded(_drive_model) => {
-            return Task::perform(
+        Message::DriveAdded(device) => {
+            let notification = if device.is_empty() {
+                Task::none()
+            } else {
+                setup::inspect(device)
+            };
+            let refresh = Task::perform(
                 async {
                     match load_all_drives().await {
                         Ok(drives) => Some(drives),
@@ -839,11 +1183,19 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                     Some(drives) => Message::UpdateNav(drives, None).into(),
                 },
             );
+            return Task::batch([notification, refresh]);
         }
         Message::None => {}
         Message::UpdateNav(drive_models, selected) => {
-            return nav::update_nav(app, drive_models, selected);
+            let task = nav::update_nav(app, drive_models, selected);
+            return Task::batch([task, reveal::reveal_pending(app), setup::open_pending(app)]);
+        }
+        Message::Reveal(target) => return reveal::reveal(app, target),
+        Message::DriveInspected { device, blank_name } => {
+            return setup::announce(app, device, blank_name);
         }
+        Message::SetUpDrive(device) => return setup::open(app, device),
+        Message::SetUpDriveDialog(msg) => return setup::set_up_drive_dialog(app, msg),
 
         // BTRFS management
         Message::BtrfsLoadSubvolumes { .. }
@@ -852,6 +1204,11 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         | Message::BtrfsDeleteSubvolumeConfirm { .. }
         | Message::BtrfsLoadUsage { .. }
         | Message::BtrfsUsageLoaded { .. }
+        | Message::BtrfsLoadDeviceStats { .. }
+        | Message::BtrfsDeviceStatsLoaded { .. }
+        | Message::BtrfsResetDeviceStats { .. }
+        | Message::BtrfsResetDeviceStatsConfirm { .. }
+        | Message::BtrfsDeviceStatsReset { .. }
         | Message::BtrfsToggleSubvolumeExpanded { .. }
         | Message::BtrfsLoadDefaultSubvolume { .. }
         | Message::BtrfsDefaultSubvolumeLoaded { .. }
@@ -860,10 +1217,31 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         | Message::BtrfsReadonlyToggled { .. }
         | Message::BtrfsShowProperties { .. }
         | Message::BtrfsCloseProperties { .. }
+        | Message::BtrfsNocowLoaded { .. }
+        | Message::BtrfsSetNocow { .. }
+        | Message::BtrfsRollback { .. }
+        | Message::BtrfsRollbackConfirm { .. }
+        | Message::BtrfsRollbackFinished { .. }
+        | Message::BtrfsRestorePathChanged(_)
+        | Message::BtrfsRestoreConflictChanged(_)
+        | Message::BtrfsRestoreFromSnapshot { .. }
+        | Message::BtrfsRestoreFinished { .. }
         | Message::BtrfsLoadDeletedSubvolumes { .. }
         | Message::BtrfsDeletedSubvolumesLoaded { .. }
         | Message::BtrfsToggleShowDeleted { .. }
-        | Message::BtrfsRefreshAll { .. } => {
+        | Message::BtrfsRefreshAll { .. }
+        | Message::BtrfsLoadCompression { .. }
+        | Message::BtrfsCompressionLoaded { .. }
+        | Message::BtrfsCompressionTargetChanged(_)
+        | Message::BtrfsCompressionAlgorithmChanged(_)
+        | Message::BtrfsCompressionForceToggled(_)
+        | Message::BtrfsApplyCompressionProperty { .. }
+        | Message::BtrfsApplyMountCompression { .. }
+        | Message::BtrfsEstimateCompression { .. }
+        | Message::BtrfsCompressionEstimated { .. }
+        | Message::BtrfsCompressExisting { .. }
+        | Message::BtrfsCompressExistingConfirm { .. }
+        | Message::BtrfsCompressionApplied { .. } => {
             return btrfs::handle_btrfs_message(app, message);
         }
 
@@ -926,6 +1304,12 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
                                     }
                                     .into(),
                                 ),
+                                Task::done(
+                                    Message::BtrfsLoadCompression {
+                                        mount_point: mount_point.clone(),
+                                    }
+                                    .into(),
+                                ),
                             ]);
                             return task.chain(refresh_task);
                         }
@@ -951,9 +1335,24 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::SmartData => {
             return drive::smart_data(app);
         }
+        Message::FindLostPartitions => {
+            return lost_partitions::find_lost_partitions(app);
+        }
+        Message::EditGptEntries => {
+            return gpt_entries::edit_gpt_entries(app);
+        }
+        Message::ReportProblem(failure) => {
+            diagnostics::report_problem(app, failure);
+        }
         Message::StandbyNow => {
             return drive::standby_now(app);
         }
+        Message::IdentifyDrive => {
+            return drive::identify_drive(app);
+        }
+        Message::TrimFilesystems => {
+            return drive::trim_filesystems(app);
+        }
         Message::Wakeup => {
             return drive::wakeup(app);
         }
@@ -962,6 +1361,7 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::SidebarSelectDrive { device_path } => {
             app.network.select(None, None);
             app.network.clear_editor();
+            app.network.transfer_form = None;
             app.sidebar.selected_child = None;
             if let Some(id) = app.sidebar.drive_entities.get(&device_path).copied() {
                 return on_nav_select(app, id);
@@ -973,6 +1373,7 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::SidebarSelectChild { device_path } => {
             app.network.select(None, None);
             app.network.clear_editor();
+            app.network.transfer_form = None;
             app.sidebar.selected_child = Some(SidebarNodeKey::Volume(device_path.clone()));
 
             // Find which drive contains this volume node
@@ -1091,6 +1492,42 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::SmartDialog(msg) => {
             return smart::smart_dialog(app, msg);
         }
+        Message::Confirmation(msg) => {
+            return confirmation::confirmation(app, msg);
+        }
+        Message::DefragDialog(msg) => {
+            return defrag::defrag_dialog(app, msg);
+        }
+        Message::EncryptedFoldersDialog(msg) => {
+            return encrypted_folders::encrypted_folders_dialog(app, msg);
+        }
+        Message::EspSyncDialog(msg) => {
+            return esp_sync::esp_sync_dialog(app, msg);
+        }
+        Message::LowSpaceDialog(msg) => {
+            return low_space::low_space_dialog(app, msg);
+        }
+        Message::AutomountDialog(msg) => {
+            return automount::automount_dialog(app, msg);
+        }
+        Message::PerformanceDialog(msg) => {
+            return performance::performance_dialog(app, msg);
+        }
+        Message::SectorViewerDialog(msg) => {
+            return sector_viewer::sector_viewer_dialog(app, msg);
+        }
+        Message::InspectDialog(msg) => {
+            return inspect::inspect_dialog(app, msg);
+        }
+        Message::LostPartitionsDialog(msg) => {
+            return lost_partitions::lost_partitions_dialog(app, msg);
+        }
+        Message::GptEntriesDialog(msg) => {
+            return gpt_entries::gpt_entries_dialog(app, msg);
+        }
+        Message::DiagnosticsDialog(msg) => {
+            return diagnostics::diagnostics_dialog(app, msg);
+        }
         Message::NewDiskImage => {
             image::new_disk_image(app);
         }
@@ -1109,6 +1546,39 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::RestoreImageToPartition => {
             return image::restore_image_to_partition(app);
         }
+        Message::CopyPartition => {
+            return image::copy_partition(app);
+        }
+        Message::SyncEsp => {
+            return esp_sync::open_esp_sync(app);
+        }
+        Message::LowSpaceAlerts => {
+            return low_space::open_low_space(app);
+        }
+        Message::Automount => {
+            return automount::open_for_volume(app);
+        }
+        Message::MigrateDisk => {
+            return image::migrate_disk(app);
+        }
+        Message::CreateLiveUsb => {
+            return image::create_live_usb(app);
+        }
+        Message::AdvancedPerformance => {
+            return performance::open_performance(app);
+        }
+        Message::ViewSectors { device, size } => {
+            return sector_viewer::open_sector_viewer(app, device, size);
+        }
+        Message::InspectVolume { device, size } => {
+            return inspect::open_inspect(app, device, size);
+        }
+        Message::BurnDiscImage => {
+            return image::disc_operation(app, ImageOperationKind::BurnDisc);
+        }
+        Message::EraseDisc => {
+            return image::disc_operation(app, ImageOperationKind::BlankDisc);
+        }
         Message::NewDiskImageDialog(msg) => {
             return image::new_disk_image_dialog(app, msg);
         }
@@ -1246,6 +1716,22 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
             }
         }
 
+        Message::Logs(msg) => {
+            return logs::handle_logs_message(app, msg);
+        }
+
+        Message::Statistics(msg) => {
+            return statistics::handle_statistics_message(app, msg);
+        }
+
+        Message::HealthCheck(msg) => {
+            return health_check::handle_health_check_message(app, msg);
+        }
+
+        Message::Vaults(msg) => {
+            return vaults::handle_vaults_message(app, msg);
+        }
+
         // Network mounts (RClone, Samba, FTP)
         Message::Network(msg) => {
             return network::handle_network_message(app, msg);
@@ -1256,6 +1742,81 @@ pub(crate) fn update(app: &mut AppModel, message: Message) -> Task<Message> {
         Message::NetworkRemotesLoaded(result) => {
             return network::handle_network_message(app, NetworkMessage::RemotesLoaded(result));
         }
+        Message::UserMountsLoaded(mounts) => {
+            app.user_mounts.mounts = mounts;
+        }
+        Message::PhysicalDevicesLoaded(devices) => {
+            app.physical_devices = devices;
+        }
+        Message::HypervisorLoaded(hypervisor) => {
+            app.hypervisor = hypervisor;
+        }
+        Message::AllowedActionsLoaded(allowed) => {
+            app.allowed_actions = allowed;
+        }
+        Message::UnmountUserMount(mount_point) => {
+            return user_mounts::unmount(app, mount_point);
+        }
+        Message::UserMountUnmounted {
+            mount_point,
+            result,
+        } => {
+            user_mounts::unmounted(app, mount_point, result);
+        }
+        Message::BindMountsLoaded(mounts) => {
+            app.bind_mounts.mounts = mounts;
+        }
+        Message::NewBindMount => {
+            bind_mounts::open_new_bind_mount(app);
+        }
+        Message::BindMountDialog(msg) => {
+            return bind_mounts::bind_mount_dialog(app, msg);
+        }
+        Message::RemoveBindMount { target, forget } => {
+            return bind_mounts::remove(app, target, forget);
+        }
+        Message::BindMountRemoved { target, result } => {
+            bind_mounts::removed(app, target, result);
+        }
+        Message::MtpDevicesLoaded(devices) => {
+            app.mtp.devices = devices;
+        }
+        Message::MountMtpDevice(uri) => {
+            return mtp::mount(app, uri);
+        }
+        Message::EjectMtpDevice(uri) => {
+            return mtp::eject(app, uri);
+        }
+        Message::MtpOperationCompleted { uri, eject, result } => {
+            return mtp::completed(app, uri, eject, result);
+        }
+        Message::CapacityForecastsLoaded(result) => {
+            return capacity::loaded(app, result);
+        }
+        Message::ForcedReadOnlyLoaded(result) => {
+            read_only::loaded(app, result);
+        }
+        Message::RemountReadWrite(filesystem) => {
+            return read_only::remount_read_write(filesystem);
+        }
+        Message::RepairForcedReadOnly(filesystem) => {
+            read_only::repair(app, filesystem);
+        }
+        Message::RepairForcedReadOnlyConfirm(filesystem) => {
+            return read_only::repair_confirm(app, filesystem);
+        }
+        Message::SelinuxStatusesLoaded(result) => {
+            selinux::loaded(app, result);
+        }
+        Message::Relabel(mount_point) => {
+            return selinux::relabel(app, mount_point);
+        }
+        Message::Relabeled {
+            mount_point,
+            result,
+        } => {
+            return selinux::relabeled(app, mount_point, result);
+        }
     }
     Task::none()
 }
@@ -1283,6 +1844,23 @@ pub(crate) fn on_nav_select(app: &mut AppModel, id: nav_bar::Id) -> Task<Message
     }
 }
 
+/// Read the scheduled jobs waiting for mains power
+fn load_deferred_jobs() -> Task<Message> {
+    Task::perform(
+        async {
+            let client = ServiceClient::new().await?;
+            client.get_deferred_jobs().await
+        },
+        |result| match result {
+            Ok(jobs) => Message::DeferredJobsLoaded(jobs).into(),
+            Err(e) => {
+                tracing::info!(%e, "deferred jobs not available");
+                Message::None.into()
+            }
+        },
+    )
+}
+
 /// Helper function to retry unmount operation on a volume by device path
 fn retry_unmount(volumes: &VolumesControl, device_path: String) -> Task<Message> {
     // Find the volume node
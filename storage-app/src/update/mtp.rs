@@ -0,0 +1,81 @@
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+use crate::state::dialogs::ShowDialog;
+use crate::utils::mtp::{eject_mtp_device, list_mtp_devices, mount_mtp_device};
+use cosmic::app::Task;
+
+/// Mount a phone or camera so its storages can be browsed
+pub(super) fn mount(app: &mut AppModel, uri: String) -> Task<Message> {
+    start(app, uri, false)
+}
+
+/// Eject a phone or camera before it is unplugged
+pub(super) fn eject(app: &mut AppModel, uri: String) -> Task<Message> {
+    start(app, uri, true)
+}
+
+fn start(app: &mut AppModel, uri: String, eject: bool) -> Task<Message> {
+    if !app.mtp.busy.insert(uri.clone()) {
+        return Task::none();
+    }
+    Task::perform(
+        {
+            let uri = uri.clone();
+            async move {
+                if eject {
+                    eject_mtp_device(&uri).await
+                } else {
+                    mount_mtp_device(&uri).await
+                }
+            }
+        },
+        move |result| {
+            Message::MtpOperationCompleted {
+                uri: uri.clone(),
+                eject,
+                result,
+            }
+            .into()
+        },
+    )
+}
+
+pub(super) fn completed(
+    app: &mut AppModel,
+    uri: String,
+    eject: bool,
+    result: Result<(), String>,
+) -> Task<Message> {
+    app.mtp.busy.remove(&uri);
+    match result {
+        Ok(()) if eject => tracing::info!("Ejected {uri}"),
+        Ok(()) => tracing::info!("Mounted {uri}"),
+        Err(e) => {
+            tracing::error!(
+                "Failed to {} {uri}: {e}",
+                if eject { "eject" } else { "mount" }
+            );
+            app.dialog = Some(ShowDialog::Info {
+                title: if eject {
+                    "Eject Failed"
+                } else {
+                    "Mount Failed"
+                }
+                .to_string(),
+                body: e,
+            });
+        }
+    }
+    reload()
+}
+
+/// Look at the devices again without waiting for the next poll
+fn reload() -> Task<Message> {
+    Task::perform(list_mtp_devices(), |result| match result {
+        Ok(devices) => Message::MtpDevicesLoaded(devices).into(),
+        Err(e) => {
+            tracing::debug!("Failed to list phones and cameras: {e}");
+            Message::None.into()
+        }
+    })
+}
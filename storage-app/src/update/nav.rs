@@ -1,3 +1,4 @@
+use crate::client::DisksClient;
 use crate::message::app::Message;
 use crate::models::UiDrive;
 use crate::state::app::AppModel;
@@ -22,6 +23,7 @@ pub(super) fn update_nav(
     let should_close = match app.dialog.as_ref() {
         Some(ShowDialog::UnlockEncrypted(s)) => s.running,
         Some(ShowDialog::FormatDisk(s)) => s.running,
+        Some(ShowDialog::SetUpDrive(s)) => s.running,
         Some(ShowDialog::AddPartition(s)) => s.running,
         Some(ShowDialog::FormatPartition(s)) => s.running,
         Some(ShowDialog::EditPartition(s)) => s.running,
@@ -117,14 +119,20 @@ pub(super) fn update_nav(
 
     app.sidebar.set_drive_entities(drive_entities);
 
+    let mut tasks = vec![
+        load_drive_health(&drive_models),
+        load_optical_media(&drive_models),
+        super::read_only::load(),
+        super::selinux::load(),
+        super::capacity::load(),
+    ];
+
     //  Trigger BTRFS data loading for activated drive
     if let Some(volumes_control) = app.nav.active_data::<VolumesControl>()
         && let Some(btrfs_state) = &volumes_control.btrfs_state
         && let Some(mount_point) = &btrfs_state.mount_point
         && let Some(block_path) = &btrfs_state.block_path
     {
-        let mut tasks = Vec::new();
-
         // Load subvolumes if not already loaded/loading
         if btrfs_state.subvolumes.is_none() && !btrfs_state.loading {
             tasks.push(Task::done(
@@ -147,10 +155,92 @@ pub(super) fn update_nav(
             ));
         }
 
-        if !tasks.is_empty() {
-            return Task::batch(tasks);
+        if btrfs_state.compression.is_none() {
+            tasks.push(Task::done(
+                Message::BtrfsLoadCompression {
+                    mount_point: mount_point.clone(),
+                }
+                .into(),
+            ));
         }
     }
 
-    Task::none()
+    Task::batch(tasks)
+}
+
+/// Load the discs in all optical drives.
+fn load_optical_media(drive_models: &[UiDrive]) -> Task<Message> {
+    let devices: Vec<String> = drive_models
+        .iter()
+        .filter(|drive| drive.disk.optical)
+        .map(|drive| drive.device().to_string())
+        .collect();
+    if devices.is_empty() {
+        return Task::none();
+    }
+    if let Some(dataset) = crate::models::demo::dataset() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        return Task::done(Message::DriveHealthLoaded(dataset.health(now)).into());
+    }
+
+    Task::perform(
+        async move {
+            let client = match DisksClient::new().await {
+                Ok(client) => client,
+                Err(e) => {
+                    let e = e.to_string();
+                    return devices.into_iter().map(|d| (d, Err(e.clone()))).collect();
+                }
+            };
+            let mut media = Vec::new();
+            for device in devices {
+                let result = client
+                    .get_optical_media(&device)
+                    .await
+                    .map_err(|e| e.to_string());
+                media.push((device, result));
+            }
+            media
+        },
+        |media| Message::OpticalMediaLoaded(media).into(),
+    )
+}
+
+/// Load the health badges of all drives with SMART support.
+fn load_drive_health(drive_models: &[UiDrive]) -> Task<Message> {
+    let devices: Vec<String> = drive_models
+        .iter()
+        .filter(|drive| !drive.disk.is_loop && !drive.disk.optical)
+        .map(|drive| drive.device().to_string())
+        .collect();
+    if devices.is_empty() {
+        return Task::none();
+    }
+    if let Some(dataset) = crate::models::demo::dataset() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        return Task::done(Message::DriveHealthLoaded(dataset.health(now)).into());
+    }
+
+    Task::perform(
+        async move {
+            let Ok(client) = DisksClient::new().await else {
+                return Vec::new();
+            };
+            let mut summaries = Vec::new();
+            for device in devices {
+                match client.get_health_summary(&device).await {
+                    Ok(summary) => summaries.push(summary),
+                    Err(e) => tracing::debug!(%e, device, "no health summary"),
+                }
+            }
+            summaries
+        },
+        |summaries| Message::DriveHealthLoaded(summaries).into(),
+    )
 }
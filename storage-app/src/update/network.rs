@@ -3,14 +3,19 @@
 //! Network mount message handling
 
 use crate::client::RcloneClient;
+use crate::client::error::ClientError;
 use crate::errors::ui::{UiErrorContext, log_error_and_show_dialog};
 use crate::message::app::Message;
 use crate::message::network::NetworkMessage;
 use crate::state::app::AppModel;
-use crate::state::dialogs::ShowDialog;
+use crate::state::dialogs::{RcloneConfigPasswordDialog, ShowDialog};
+use crate::state::network::TransferFormState;
 use cosmic::app::Task;
+use std::path::PathBuf;
+use storage_types::AutomountUnit;
 use storage_types::rclone::{
-    ConfigScope, MountStatus, RemoteConfig, rclone_provider, supported_remote_types,
+    ConfigScope, MountStatus, RemoteConfig, TransferMode, rclone_provider, supported_remote_types,
+    validate_mount_point,
 };
 
 /// Handle network-related messages
@@ -22,7 +27,7 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
                 async {
                     match RcloneClient::new().await {
                         Ok(client) => match client.list_remotes().await {
-                            Ok(list) => Ok(list.remotes),
+                            Ok(list) => Ok(list),
                             Err(e) => Err(format!("Failed to list remotes: {}", e)),
                         },
                         Err(e) => Err(format!("RClone not available: {}", e)),
@@ -35,21 +40,39 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
         NetworkMessage::RemotesLoaded(result) => {
             app.network.loading = false;
             match result {
-                Ok(remotes) => {
+                Ok(list) => {
                     app.network.rclone_available = true;
-                    let refresh_tasks: Vec<Task<Message>> = remotes
+                    app.network.config_encryption = list.encryption;
+                    let mut refresh_tasks: Vec<Task<Message>> = list
+                        .remotes
                         .iter()
-                        .map(|remote| {
-                            Task::done(
-                                Message::Network(NetworkMessage::RefreshStatus {
-                                    name: remote.name.clone(),
-                                    scope: remote.scope,
-                                })
-                                .into(),
-                            )
+                        .flat_map(|remote| {
+                            [
+                                Task::done(
+                                    Message::Network(NetworkMessage::RefreshStatus {
+                                        name: remote.name.clone(),
+                                        scope: remote.scope,
+                                    })
+                                    .into(),
+                                ),
+                                Task::done(
+                                    Message::Network(NetworkMessage::LoadRemoteUsage {
+                                        name: remote.name.clone(),
+                                        scope: remote.scope,
+                                        refresh: false,
+                                    })
+                                    .into(),
+                                ),
+                            ]
                         })
                         .collect();
-                    app.network.set_remotes(remotes);
+                    refresh_tasks.push(Task::done(
+                        Message::Network(NetworkMessage::LoadLoginMounts).into(),
+                    ));
+                    refresh_tasks.push(Task::done(
+                        Message::Network(NetworkMessage::LoadTransfers).into(),
+                    ));
+                    app.network.set_remotes(list.remotes);
                     return Task::batch(refresh_tasks);
                 }
                 Err(e) => {
@@ -61,6 +84,7 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
 
         NetworkMessage::SelectRemote { name, scope } => {
             app.network.select(Some(name.clone()), Some(scope));
+            app.network.transfer_form = None;
             if let Some(config) = app
                 .network
                 .get_mount(&name, scope)
@@ -98,6 +122,7 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
         NetworkMessage::BeginCreateRemote => {
             app.network.select(None, None);
             app.network.clear_editor();
+            app.network.transfer_form = None;
             app.network.start_wizard();
         }
 
@@ -134,6 +159,13 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
             }
         }
 
+        NetworkMessage::EditorMountPointChanged(value) => {
+            if let Some(editor) = app.network.editor.as_mut() {
+                editor.mount_point = value;
+                editor.error = None;
+            }
+        }
+
         NetworkMessage::EditorNewOptionKeyChanged(value) => {
             if let Some(editor) = app.network.editor.as_mut() {
                 editor.new_option_key = value;
@@ -322,6 +354,7 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
                         scope,
                         options,
                         has_secrets,
+                        mount_point: None,
                     };
                     client
                         .create_remote(&config)
@@ -366,12 +399,28 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
         }
 
         NetworkMessage::SaveRemote => {
+            let original = app
+                .network
+                .editor
+                .as_ref()
+                .and_then(|editor| editor.original_name.clone().zip(editor.original_scope));
+            let other_mount_points: Vec<(String, PathBuf)> = app
+                .network
+                .mounts
+                .iter()
+                .filter(|(key, _)| Some(*key) != original.as_ref())
+                .map(|((name, scope), mount)| {
+                    (format!("{name} ({scope})"), mount.config.mount_point())
+                })
+                .collect();
+
             let (
                 name,
                 remote_type,
                 scope,
                 options,
                 has_secrets,
+                mount_point,
                 is_edit,
                 original_name,
                 original_scope,
@@ -427,6 +476,17 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
                         })
                 });
 
+                let mount_point = Some(editor.mount_point.trim())
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from);
+                let effective_mount_point = mount_point
+                    .clone()
+                    .unwrap_or_else(|| scope.mount_point(&name));
+                if let Err(e) = validate_mount_point(&effective_mount_point, &other_mount_points) {
+                    editor.error = Some(e);
+                    return Task::none();
+                }
+
                 let is_edit = !editor.is_new;
                 let original_name = editor.original_name.clone();
                 let original_scope = editor.original_scope;
@@ -440,6 +500,7 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
                     scope,
                     options,
                     has_secrets,
+                    mount_point,
                     is_edit,
                     original_name,
                     original_scope,
@@ -457,6 +518,7 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
                         scope,
                         options,
                         has_secrets,
+                        mount_point,
                     };
 
                     if is_edit {
@@ -635,6 +697,9 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
                 match result {
                     Ok(()) => {
                         editor.mount_on_boot = Some(enabled);
+                        return Task::done(
+                            Message::Network(NetworkMessage::LoadLoginMounts).into(),
+                        );
                     }
                     Err(e) => {
                         editor.mount_on_boot = Some(previous);
@@ -652,10 +717,39 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
             }
         }
 
+        NetworkMessage::LoadLoginMounts => {
+            return Task::perform(
+                async {
+                    match RcloneClient::new().await {
+                        Ok(client) => client.list_login_mounts().await.map_err(|e| e.to_string()),
+                        Err(e) => Err(e.to_string()),
+                    }
+                },
+                |result| Message::Network(NetworkMessage::LoginMountsLoaded(result)).into(),
+            );
+        }
+
+        NetworkMessage::LoginMountsLoaded(result) => match result {
+            Ok(statuses) => app.network.set_login_mounts(statuses),
+            Err(e) => tracing::warn!(%e, "failed to load login mount states"),
+        },
+
         NetworkMessage::OpenMountPath(path) => {
             return Task::done(Message::OpenPath(path).into());
         }
 
+        NetworkMessage::OpenAutomount { name, scope } => {
+            let Some(mount) = app.network.mounts.get(&(name.clone(), scope)) else {
+                return Task::none();
+            };
+            let unit = AutomountUnit::for_rclone_remote(
+                &name,
+                &scope.config_path(),
+                mount.config.mount_point(),
+            );
+            return super::automount::open(app, unit);
+        }
+
         NetworkMessage::MountRemote { name, scope } => {
             app.network.set_loading(&name, scope, true);
             let name_for_task = name.clone();
@@ -870,6 +964,57 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
             app.network.set_mount_status(&name, scope, status);
         }
 
+        NetworkMessage::LoadRemoteUsage {
+            name,
+            scope,
+            refresh,
+        } => {
+            if let Some(mount) = app.network.get_mount_mut(&name, scope) {
+                if mount.usage_loading {
+                    return Task::none();
+                }
+                mount.usage_loading = true;
+            }
+            let name_for_task = name.clone();
+            return Task::perform(
+                async move {
+                    let client = RcloneClient::new().await.map_err(|e| e.to_string())?;
+                    client
+                        .get_remote_usage(&name_for_task, &scope.to_string(), refresh)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                move |result| {
+                    Message::Network(NetworkMessage::RemoteUsageLoaded {
+                        name: name.clone(),
+                        scope,
+                        result,
+                    })
+                    .into()
+                },
+            );
+        }
+
+        NetworkMessage::RemoteUsageLoaded {
+            name,
+            scope,
+            result,
+        } => {
+            if let Some(mount) = app.network.get_mount_mut(&name, scope) {
+                mount.usage_loading = false;
+                match result {
+                    Ok(usage) => {
+                        mount.usage = usage;
+                        mount.usage_error = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to query usage of {}: {}", name, e);
+                        mount.usage_error = Some(e);
+                    }
+                }
+            }
+        }
+
         NetworkMessage::DeleteRemote { name, scope } => {
             // Show confirmation dialog before deleting
             app.dialog = Some(ShowDialog::ConfirmDeleteRemote { name, scope });
@@ -936,9 +1081,289 @@ pub(crate) fn handle_network_message(app: &mut AppModel, message: NetworkMessage
                 }
             }
         }
+
+        NetworkMessage::UnlockConfig(scope) => {
+            app.dialog = Some(ShowDialog::RcloneConfigPassword(
+                RcloneConfigPasswordDialog::new(scope, false),
+            ));
+        }
+
+        NetworkMessage::EncryptConfig(scope) => {
+            app.dialog = Some(ShowDialog::RcloneConfigPassword(
+                RcloneConfigPasswordDialog::new(scope, true),
+            ));
+        }
+
+        NetworkMessage::ConfigPasswordChanged(password) => {
+            if let Some(ShowDialog::RcloneConfigPassword(dialog)) = app.dialog.as_mut() {
+                dialog.password = password;
+                dialog.error = None;
+            }
+        }
+
+        NetworkMessage::ConfigPasswordConfirmChanged(confirm) => {
+            if let Some(ShowDialog::RcloneConfigPassword(dialog)) = app.dialog.as_mut() {
+                dialog.confirm = confirm;
+                dialog.error = None;
+            }
+        }
+
+        NetworkMessage::ConfirmConfigPassword => {
+            let Some(ShowDialog::RcloneConfigPassword(dialog)) = app.dialog.as_mut() else {
+                return Task::none();
+            };
+            if dialog.running {
+                return Task::none();
+            }
+            if dialog.password.is_empty() {
+                dialog.error = Some("Enter a password".to_string());
+                return Task::none();
+            }
+            if dialog.encrypt && dialog.password != dialog.confirm {
+                dialog.error = Some("The passwords do not match".to_string());
+                return Task::none();
+            }
+            dialog.running = true;
+
+            let scope = dialog.scope.to_string();
+            let password = dialog.password.clone();
+            let encrypt = dialog.encrypt;
+            return Task::perform(
+                async move {
+                    let client = RcloneClient::new().await.map_err(|e| e.to_string())?;
+                    let result = if encrypt {
+                        client.encrypt_config(&scope, &password).await
+                    } else {
+                        client.unlock_config(&scope, &password).await
+                    };
+                    result.map_err(|e| match e {
+                        ClientError::InvalidArgument(_) if !encrypt => {
+                            "Incorrect password".to_string()
+                        }
+                        e => e.to_string(),
+                    })
+                },
+                |result| Message::Network(NetworkMessage::ConfigEncryptionCompleted(result)).into(),
+            );
+        }
+
+        NetworkMessage::LockConfig(scope) => {
+            return Task::perform(
+                async move {
+                    let client = RcloneClient::new().await.map_err(|e| e.to_string())?;
+                    client
+                        .lock_config(&scope.to_string())
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |result| Message::Network(NetworkMessage::ConfigEncryptionCompleted(result)).into(),
+            );
+        }
+
+        NetworkMessage::ConfigEncryptionCompleted(result) => match result {
+            Ok(()) => {
+                if matches!(app.dialog, Some(ShowDialog::RcloneConfigPassword(_))) {
+                    app.dialog = None;
+                }
+                return Task::done(Message::Network(NetworkMessage::LoadRemotes).into());
+            }
+            Err(e) => {
+                if let Some(ShowDialog::RcloneConfigPassword(dialog)) = app.dialog.as_mut() {
+                    dialog.running = false;
+                    dialog.error = Some(e);
+                } else {
+                    tracing::error!("Failed to lock configuration: {}", e);
+                    app.dialog = Some(ShowDialog::Info {
+                        title: "Lock Failed".to_string(),
+                        body: format!("Failed to lock the configuration: {}", e),
+                    });
+                }
+            }
+        },
+
+        NetworkMessage::ShowTransfers => {
+            app.network.select(None, None);
+            app.network.clear_editor();
+            app.network.clear_wizard();
+            if app.network.transfer_form.is_none() {
+                app.network.transfer_form = Some(TransferFormState::default());
+            }
+            return Task::done(Message::Network(NetworkMessage::LoadTransfers).into());
+        }
+
+        NetworkMessage::LoadTransfers => {
+            return Task::perform(
+                async {
+                    let client = RcloneClient::new().await.map_err(|e| e.to_string())?;
+                    client.list_transfers().await.map_err(|e| e.to_string())
+                },
+                |result| Message::Network(NetworkMessage::TransfersLoaded(result)).into(),
+            );
+        }
+
+        NetworkMessage::TransfersLoaded(result) => match result {
+            Ok(transfers) => app.network.transfers = transfers,
+            Err(e) => tracing::warn!("Failed to list transfers: {}", e),
+        },
+
+        NetworkMessage::TransferScopeChanged(index) => {
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                let scope = match index {
+                    0 => ConfigScope::User,
+                    _ => ConfigScope::System,
+                };
+                if form.scope != scope {
+                    // Remotes of one scope are not visible from the other
+                    form.scope = scope;
+                    form.source_remote = None;
+                    form.destination_remote = None;
+                }
+                form.error = None;
+            }
+        }
+
+        NetworkMessage::TransferSourceChanged(index) => {
+            let remotes = transfer_remotes(app);
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.source_remote = index.checked_sub(1).and_then(|i| remotes.get(i).cloned());
+                form.error = None;
+            }
+        }
+
+        NetworkMessage::TransferSourcePathChanged(path) => {
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.source_path = path;
+                form.error = None;
+            }
+        }
+
+        NetworkMessage::TransferDestinationChanged(index) => {
+            let remotes = transfer_remotes(app);
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.destination_remote =
+                    index.checked_sub(1).and_then(|i| remotes.get(i).cloned());
+                form.error = None;
+            }
+        }
+
+        NetworkMessage::TransferDestinationPathChanged(path) => {
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.destination_path = path;
+                form.error = None;
+            }
+        }
+
+        NetworkMessage::TransferModeChanged(index) => {
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.mode = match index {
+                    0 => TransferMode::Copy,
+                    _ => TransferMode::Sync,
+                };
+            }
+        }
+
+        NetworkMessage::TransferBandwidthLimitChanged(limit) => {
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.bandwidth_limit = limit;
+                form.error = None;
+            }
+        }
+
+        NetworkMessage::TransferDryRunToggled(dry_run) => {
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.dry_run = dry_run;
+            }
+        }
+
+        NetworkMessage::StartTransfer => {
+            let Some(form) = app.network.transfer_form.as_mut() else {
+                return Task::none();
+            };
+            if form.running {
+                return Task::none();
+            }
+            let request = match form.to_request() {
+                Ok(request) => request,
+                Err(e) => {
+                    form.error = Some(e);
+                    return Task::none();
+                }
+            };
+            form.running = true;
+            form.error = None;
+
+            return Task::perform(
+                async move {
+                    let client = RcloneClient::new().await.map_err(|e| e.to_string())?;
+                    client
+                        .start_transfer(&request)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |result| Message::Network(NetworkMessage::TransferStarted(result)).into(),
+            );
+        }
+
+        NetworkMessage::TransferStarted(result) => {
+            if let Some(form) = app.network.transfer_form.as_mut() {
+                form.running = false;
+                match &result {
+                    Ok(id) => tracing::info!("Started transfer {}", id),
+                    Err(e) => form.error = Some(e.clone()),
+                }
+            }
+            if result.is_ok() {
+                return Task::done(Message::Network(NetworkMessage::LoadTransfers).into());
+            }
+        }
+
+        NetworkMessage::CancelTransfer(id) => {
+            return Task::perform(
+                async move {
+                    let client = RcloneClient::new().await.map_err(|e| e.to_string())?;
+                    client.cancel_transfer(&id).await.map_err(|e| e.to_string())
+                },
+                |result| Message::Network(NetworkMessage::TransferActionCompleted(result)).into(),
+            );
+        }
+
+        NetworkMessage::ClearTransferHistory => {
+            return Task::perform(
+                async {
+                    let client = RcloneClient::new().await.map_err(|e| e.to_string())?;
+                    client
+                        .clear_transfer_history()
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                |result| Message::Network(NetworkMessage::TransferActionCompleted(result)).into(),
+            );
+        }
+
+        NetworkMessage::TransferActionCompleted(result) => {
+            if let Err(e) = result {
+                tracing::error!("Transfer action failed: {}", e);
+                app.dialog = Some(ShowDialog::Info {
+                    title: "Transfer".to_string(),
+                    body: e,
+                });
+            }
+            return Task::done(Message::Network(NetworkMessage::LoadTransfers).into());
+        }
     }
 
     Task::none()
 }
 
+/// Remotes offered in the transfer form, in dropdown order after "Local folder"
+fn transfer_remotes(app: &AppModel) -> Vec<String> {
+    let scope = app
+        .network
+        .transfer_form
+        .as_ref()
+        .map(|form| form.scope)
+        .unwrap_or_default();
+    app.network.remote_names(scope)
+}
+
 // Remote config dialog handling removed in favor of main view editor
@@ -0,0 +1,183 @@
+use crate::client::DisksClient;
+use crate::message::dialogs::PerformanceDialogMessage;
+use crate::models::UiDrive;
+use crate::state::dialogs::{PerformanceDialog, ShowDialog};
+use cosmic::app::Task;
+use storage_types::{QueueSettings, QueueTuning, WriteCacheStatus};
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Open the performance dialog for the selected drive
+pub(super) fn open_performance(app: &mut AppModel) -> Task<Message> {
+    if app.dialog.is_some() {
+        return Task::none();
+    }
+    let Some(drive) = app.nav.active_data::<UiDrive>().cloned() else {
+        return Task::none();
+    };
+
+    let device = drive.device().to_string();
+    app.dialog = Some(ShowDialog::Performance(PerformanceDialog {
+        drive,
+        current: None,
+        tuning: QueueTuning::default(),
+        write_cache: None,
+        write_cache_enabled: false,
+        persistent: false,
+        running: false,
+        error: None,
+    }));
+
+    let cache_device = device.clone();
+    Task::batch(vec![
+        Task::perform(
+            async move {
+                DisksClient::new()
+                    .await
+                    .map_err(|e| format!("Failed to create disks client: {}", e))?
+                    .get_queue_settings(&device)
+                    .await
+                    .map_err(|e| format!("Failed to read the drive's queue settings: {}", e))
+            },
+            |res| Message::PerformanceDialog(PerformanceDialogMessage::Loaded(res)).into(),
+        ),
+        Task::perform(
+            async move {
+                DisksClient::new()
+                    .await
+                    .map_err(|e| format!("Failed to create disks client: {}", e))?
+                    .get_write_cache(&cache_device)
+                    .await
+                    .map_err(|e| format!("Failed to read the drive's write cache: {}", e))
+            },
+            |res| {
+                Message::PerformanceDialog(PerformanceDialogMessage::WriteCacheLoaded(res)).into()
+            },
+        ),
+    ])
+}
+
+/// Take over the settings read from the drive
+fn load(state: &mut PerformanceDialog, settings: QueueSettings) {
+    state.tuning = settings.tuning();
+    state.persistent |= settings.persistent;
+    state.current = Some(settings);
+}
+
+/// Take over the write cache read from the drive
+fn load_write_cache(state: &mut PerformanceDialog, status: WriteCacheStatus) {
+    state.write_cache_enabled = status.enabled;
+    state.persistent |= status.persistent;
+    state.write_cache = Some(status);
+}
+
+pub(super) fn performance_dialog(
+    app: &mut AppModel,
+    msg: PerformanceDialogMessage,
+) -> Task<Message> {
+    let Some(ShowDialog::Performance(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        PerformanceDialogMessage::Loaded(res) => match res {
+            Ok(settings) => load(state, settings),
+            Err(e) => {
+                tracing::warn!(%e, "could not read queue settings");
+                state.error = Some(e);
+            }
+        },
+        PerformanceDialogMessage::WriteCacheLoaded(res) => match res {
+            Ok(status) => load_write_cache(state, status),
+            Err(e) => {
+                // The queue settings stay usable without it
+                tracing::warn!(%e, "could not read write cache");
+                state.write_cache = Some(WriteCacheStatus::default());
+            }
+        },
+        PerformanceDialogMessage::SetScheduler(index) => {
+            if let Some(scheduler) = state
+                .current
+                .as_ref()
+                .and_then(|current| current.available_schedulers.get(index))
+            {
+                state.tuning.scheduler = scheduler.clone();
+            }
+        }
+        PerformanceDialogMessage::SetReadAhead(index) => {
+            if let Some(read_ahead_kb) = state.read_ahead_choices().get(index) {
+                state.tuning.read_ahead_kb = *read_ahead_kb;
+            }
+        }
+        PerformanceDialogMessage::SetRequests(index) => {
+            if let Some(nr_requests) = state.nr_requests_choices().get(index) {
+                state.tuning.nr_requests = *nr_requests;
+            }
+        }
+        PerformanceDialogMessage::SetWriteCache(enabled) => state.write_cache_enabled = enabled,
+        PerformanceDialogMessage::SetPersistent(persistent) => state.persistent = persistent,
+        PerformanceDialogMessage::Apply => {
+            if state.running || state.current.is_none() {
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+
+            let device = state.drive.device().to_string();
+            let tuning = state.tuning.clone();
+            let write_cache = state
+                .write_cache
+                .is_some_and(|status| status.supported)
+                .then_some(state.write_cache_enabled);
+            let persistent = state.persistent;
+            return Task::perform(
+                async move {
+                    let client = DisksClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create disks client: {}", e))?;
+                    client
+                        .set_queue_tuning(&device, &tuning, persistent)
+                        .await
+                        .map_err(|e| format!("Failed to apply the settings: {}", e))?;
+                    if let Some(enabled) = write_cache {
+                        client
+                            .set_write_cache(&device, enabled, persistent)
+                            .await
+                            .map_err(|e| format!("Failed to set the write cache: {}", e))?;
+                    }
+                    let settings = client
+                        .get_queue_settings(&device)
+                        .await
+                        .map_err(|e| format!("Failed to read the drive's queue settings: {}", e))?;
+                    let status = client
+                        .get_write_cache(&device)
+                        .await
+                        .map_err(|e| format!("Failed to read the drive's write cache: {}", e))?;
+                    Ok((settings, status))
+                },
+                |res| Message::PerformanceDialog(PerformanceDialogMessage::Applied(res)).into(),
+            );
+        }
+        PerformanceDialogMessage::Applied(res) => {
+            state.running = false;
+            match res {
+                Ok((settings, status)) => {
+                    state.persistent = false;
+                    load(state, settings);
+                    load_write_cache(state, status);
+                }
+                Err(e) => {
+                    tracing::error!(%e, "queue tuning error");
+                    state.error = Some(e);
+                }
+            }
+        }
+        PerformanceDialogMessage::Close => {
+            if !state.running {
+                app.dialog = None;
+            }
+        }
+    }
+    Task::none()
+}
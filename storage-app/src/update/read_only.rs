@@ -0,0 +1,121 @@
+use crate::client::FilesystemsClient;
+use crate::errors::ui::{UiErrorContext, log_error_and_show_dialog};
+use crate::fl;
+use crate::message::app::Message;
+use crate::models::load_all_drives;
+use crate::state::app::AppModel;
+use crate::state::dialogs::{ConfirmActionDialog, FilesystemTarget, ShowDialog};
+use crate::state::volumes::VolumesControl;
+use cosmic::app::Task;
+use storage_types::{ForcedReadOnly, MountOptions};
+
+/// Look for filesystems the kernel made read-only, after the drives changed
+pub(super) fn load() -> Task<Message> {
+    Task::perform(
+        async {
+            let client = FilesystemsClient::new().await.map_err(|e| e.to_string())?;
+            client
+                .list_forced_read_only()
+                .await
+                .map_err(|e| e.to_string())
+        },
+        |result| Message::ForcedReadOnlyLoaded(result).into(),
+    )
+}
+
+pub(super) fn loaded(app: &mut AppModel, result: Result<Vec<ForcedReadOnly>, String>) {
+    match result {
+        Ok(filesystems) => app.read_only.filesystems = filesystems,
+        Err(e) => tracing::warn!("Failed to list read-only filesystems: {e}"),
+    }
+}
+
+/// Try to make the filesystem writable again without repairing it
+pub(super) fn remount_read_write(filesystem: ForcedReadOnly) -> Task<Message> {
+    Task::perform(
+        async move {
+            let client = FilesystemsClient::new().await?;
+            client.remount_read_write(&filesystem.mount_point).await?;
+            load_all_drives().await
+        },
+        |result| match result {
+            Ok(drives) => Message::UpdateNav(drives, None).into(),
+            Err(e) => {
+                let ctx = UiErrorContext::new("remount_read_write");
+                log_error_and_show_dialog(fl!("remount-read-write-failed"), e.into(), ctx).into()
+            }
+        },
+    )
+}
+
+/// Ask before unmounting the filesystem to repair it
+pub(super) fn repair(app: &mut AppModel, filesystem: ForcedReadOnly) {
+    if app.dialog.is_some() {
+        return;
+    }
+    // Required by the dialog, which only shows the title and body here
+    let Some(target) = app
+        .nav
+        .active_data::<VolumesControl>()
+        .and_then(|control| control.segments.get(control.selected_segment))
+        .and_then(|segment| segment.volume.clone())
+        .map(FilesystemTarget::Volume)
+    else {
+        return;
+    };
+
+    app.dialog = Some(ShowDialog::ConfirmAction(ConfirmActionDialog {
+        title: fl!("repair-filesystem"),
+        body: fl!(
+            "repair-read-only-warning",
+            mount_point = filesystem.mount_point.as_str()
+        ),
+        target,
+        confirm_target: Some(filesystem.device.clone()),
+        ok_message: Message::RepairForcedReadOnlyConfirm(filesystem),
+        running: false,
+    }));
+}
+
+/// Unmount, repair and mount the filesystem again
+pub(super) fn repair_confirm(app: &mut AppModel, filesystem: ForcedReadOnly) -> Task<Message> {
+    if let Some(ShowDialog::ConfirmAction(state)) = &mut app.dialog {
+        if state.running {
+            return Task::none();
+        }
+        state.running = true;
+    }
+
+    let options = MountOptions {
+        path_policy: Some(app.config.mount_path_policy()),
+        ..Default::default()
+    };
+    let options_json = serde_json::to_string(&options).ok();
+
+    Task::perform(
+        async move {
+            let client = FilesystemsClient::new().await?;
+            let unmounted = client.unmount(&filesystem.device, false, false).await?;
+            if !unmounted.success {
+                anyhow::bail!(
+                    "{}",
+                    unmounted
+                        .error
+                        .unwrap_or_else(|| fl!("repair-read-only-busy"))
+                );
+            }
+            client.check(&filesystem.device, true).await?;
+            client
+                .mount(&filesystem.device, "", options_json.as_deref())
+                .await?;
+            Ok(load_all_drives().await?)
+        },
+        |result: anyhow::Result<_>| match result {
+            Ok(drives) => Message::UpdateNav(drives, None).into(),
+            Err(e) => {
+                let ctx = UiErrorContext::new("repair_read_only");
+                log_error_and_show_dialog(fl!("repair-filesystem"), e, ctx).into()
+            }
+        },
+    )
+}
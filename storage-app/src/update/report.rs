@@ -0,0 +1,111 @@
+use crate::client::RaidClient;
+use crate::fl;
+use crate::message::app::Message;
+use crate::report::{self, ReportFormat};
+use crate::state::app::AppModel;
+use crate::state::dialogs::ShowDialog;
+use crate::utils::{notifications, sandbox};
+use cosmic::app::Task;
+use storage_types::RaidDetail;
+
+/// Suggested name of the exported report
+const REPORT_FILE_NAME: &str = "storage-report.html";
+
+/// Ask where to save the report, and fetch the RAID details it needs meanwhile
+pub(super) fn export_report(app: &AppModel) -> Task<Message> {
+    let arrays: Vec<String> = app
+        .sidebar
+        .drives
+        .iter()
+        .map(|drive| drive.disk.device.clone())
+        .filter(|device| device.starts_with("/dev/md"))
+        .collect();
+    let title = fl!("report-export");
+
+    Task::perform(
+        async move {
+            let path = sandbox::choose_save_path(title, REPORT_FILE_NAME).await?;
+
+            let mut details: Vec<RaidDetail> = Vec::new();
+            if !arrays.is_empty() {
+                match RaidClient::new().await {
+                    Ok(client) => {
+                        for array in &arrays {
+                            match client.get_raid_detail(array).await {
+                                Ok(detail) => details.push(detail),
+                                Err(e) => {
+                                    tracing::warn!(%e, array, "failed to read RAID detail for report")
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!(%e, "failed to connect to RAID service for report"),
+                }
+            }
+            Some((path, details))
+        },
+        |result| Message::ReportDestinationChosen(result).into(),
+    )
+}
+
+/// Build the report in the format its file extension asks for (HTML by
+/// default) and write it
+pub(super) fn save_report(
+    app: &AppModel,
+    mut path: std::path::PathBuf,
+    arrays: Vec<RaidDetail>,
+) -> Task<Message> {
+    let format = ReportFormat::from_path(&path).unwrap_or_else(|| {
+        let format = ReportFormat::Html;
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(format.extension());
+        path.set_file_name(name);
+        format
+    });
+
+    let contents = report::build(
+        &app.sidebar.drives,
+        &app.sidebar.health,
+        &arrays,
+        app.network.mounts.values(),
+        app.config.temperature_unit,
+    )
+    .render(format);
+
+    Task::perform(
+        async move {
+            tokio::fs::write(&path, contents)
+                .await
+                .map(|()| path)
+                .map_err(|e| e.to_string())
+        },
+        |result| Message::ReportSaved(result).into(),
+    )
+}
+
+pub(super) fn report_saved(result: Result<std::path::PathBuf, String>) -> Task<Message> {
+    match result {
+        Ok(path) => {
+            let body = fl!("report-saved-body", path = path.display().to_string());
+            Task::perform(
+                async move {
+                    if let Err(e) = notifications::notify(&fl!("report-saved"), &body).await {
+                        tracing::warn!(%e, "failed to show report notification");
+                    }
+                },
+                |_| Message::None.into(),
+            )
+        }
+        Err(e) => {
+            tracing::error!(%e, "failed to save report");
+            Task::done(
+                Message::Dialog(Box::new(ShowDialog::Info {
+                    title: fl!("report-save-failed"),
+                    body: e,
+                }))
+                .into(),
+            )
+        }
+    }
+}
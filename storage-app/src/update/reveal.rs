@@ -0,0 +1,48 @@
+//! Selecting a drive or volume on behalf of other programs
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+use crate::state::sidebar::SidebarNodeKey;
+use cosmic::app::Task;
+use cosmic::iced::window;
+
+/// Select the drive or volume of a device path or mount point and raise the
+/// window; waits for the drives when they are not loaded yet
+pub(super) fn reveal(app: &mut AppModel, target: String) -> Task<Message> {
+    // Links often name a device by one of its /dev/disk/by-* aliases
+    let target = std::fs::canonicalize(&target)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or(target);
+
+    if app.sidebar.drives.is_empty() {
+        app.sidebar.pending_reveal = Some(target);
+        return Task::none();
+    }
+
+    let select = match app.sidebar.find_target(&target) {
+        Some(SidebarNodeKey::Drive(device_path)) => Message::SidebarSelectDrive { device_path },
+        Some(SidebarNodeKey::Volume(device_path)) => {
+            app.sidebar.expand_ancestors(&device_path);
+            Message::SidebarSelectChild { device_path }
+        }
+        None => {
+            tracing::warn!(target, "no drive or volume to reveal");
+            return focus(app);
+        }
+    };
+    Task::batch([Task::done(select.into()), focus(app)])
+}
+
+/// Reveal what was asked for before the drives were loaded
+pub(super) fn reveal_pending(app: &mut AppModel) -> Task<Message> {
+    match app.sidebar.pending_reveal.take() {
+        Some(target) => reveal(app, target),
+        None => Task::none(),
+    }
+}
+
+pub(super) fn focus(app: &AppModel) -> Task<Message> {
+    app.core
+        .main_window_id()
+        .map_or_else(Task::none, window::gain_focus)
+}
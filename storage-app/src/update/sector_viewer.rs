@@ -0,0 +1,132 @@
+use crate::client::DisksClient;
+use crate::fl;
+use crate::message::dialogs::SectorViewerDialogMessage;
+use crate::models::UiDrive;
+use crate::state::dialogs::{SectorViewerDialog, ShowDialog};
+use cosmic::app::Task;
+use storage_types::sectors::parse_offset;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+/// Sector size assumed for drives that do not report one
+const DEFAULT_SECTOR_SIZE: u64 = 512;
+
+/// Open the sector viewer at the start of a drive or partition
+pub(super) fn open_sector_viewer(app: &mut AppModel, device: String, size: u64) -> Task<Message> {
+    open_sector_viewer_at(app, device, size, 0)
+}
+
+/// Open the sector viewer at the sector holding byte `offset`
+pub(super) fn open_sector_viewer_at(
+    app: &mut AppModel,
+    device: String,
+    size: u64,
+    offset: u64,
+) -> Task<Message> {
+    if app.dialog.is_some() {
+        return Task::none();
+    }
+    let sector_size = app
+        .nav
+        .active_data::<UiDrive>()
+        .map(|drive| drive.disk.logical_sector_size)
+        .filter(|size| *size > 0)
+        .unwrap_or(DEFAULT_SECTOR_SIZE);
+
+    let state = SectorViewerDialog {
+        device,
+        size,
+        sector_size,
+        offset: offset - offset % sector_size,
+        page: None,
+        offset_text: String::new(),
+        error: None,
+    };
+    let task = load(&state);
+    app.dialog = Some(ShowDialog::SectorViewer(state));
+    task
+}
+
+/// Read the page at `state.offset`
+fn load(state: &SectorViewerDialog) -> Task<Message> {
+    let device = state.device.clone();
+    let offset = state.offset;
+    let length = state.page_bytes();
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?
+                .read_sectors(&device, offset, length)
+                .await
+                .map_err(|e| format!("Failed to read sectors: {}", e))
+        },
+        |res| Message::SectorViewerDialog(SectorViewerDialogMessage::Loaded(res)).into(),
+    )
+}
+
+/// Show the page starting at `offset`
+fn go_to(state: &mut SectorViewerDialog, offset: u64) -> Task<Message> {
+    if state.page.is_none() && state.error.is_none() {
+        // A page is still loading
+        return Task::none();
+    }
+    state.offset = offset;
+    state.page = None;
+    state.error = None;
+    load(state)
+}
+
+pub(super) fn sector_viewer_dialog(
+    app: &mut AppModel,
+    msg: SectorViewerDialogMessage,
+) -> Task<Message> {
+    let Some(ShowDialog::SectorViewer(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        SectorViewerDialogMessage::Loaded(res) => match res {
+            Ok(page) if page.offset == state.offset => {
+                state.size = page.device_size;
+                state.page = Some(page);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(%e, "could not read sectors");
+                state.error = Some(e);
+            }
+        },
+        SectorViewerDialogMessage::PreviousPage => {
+            if state.offset > 0 {
+                let offset = state.offset.saturating_sub(state.page_bytes());
+                return go_to(state, offset);
+            }
+        }
+        SectorViewerDialogMessage::NextPage => {
+            let offset = state.offset + state.page_bytes();
+            if offset < state.size {
+                return go_to(state, offset);
+            }
+        }
+        SectorViewerDialogMessage::OffsetInput(text) => state.offset_text = text,
+        SectorViewerDialogMessage::Jump => {
+            match parse_offset(&state.offset_text, state.sector_size) {
+                Some(offset) if offset < state.size => {
+                    // Pages start on a sector boundary
+                    let offset = offset - offset % state.sector_size;
+                    return go_to(state, offset);
+                }
+                _ => {
+                    state.error = Some(fl!(
+                        "sector-viewer-invalid-offset",
+                        offset = state.offset_text.trim().to_string()
+                    ));
+                }
+            }
+        }
+        SectorViewerDialogMessage::Close => app.dialog = None,
+    }
+    Task::none()
+}
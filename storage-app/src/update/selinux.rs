@@ -0,0 +1,77 @@
+use crate::client::FilesystemsClient;
+use crate::fl;
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+use crate::state::dialogs::ShowDialog;
+use cosmic::app::Task;
+use storage_types::SelinuxStatus;
+
+/// Read the SELinux labels of the mounted filesystems, after the drives
+/// changed
+pub(super) fn load() -> Task<Message> {
+    Task::perform(
+        async {
+            let client = FilesystemsClient::new().await.map_err(|e| e.to_string())?;
+            client
+                .list_selinux_statuses()
+                .await
+                .map_err(|e| e.to_string())
+        },
+        |result| Message::SelinuxStatusesLoaded(result).into(),
+    )
+}
+
+pub(super) fn loaded(app: &mut AppModel, result: Result<Vec<SelinuxStatus>, String>) {
+    match result {
+        Ok(statuses) => app.selinux.statuses = statuses,
+        Err(e) => tracing::warn!("Failed to read SELinux labels: {e}"),
+    }
+}
+
+/// Relabel the filesystem at `mount_point`; its progress shows with the
+/// running operations
+pub(super) fn relabel(app: &mut AppModel, mount_point: String) -> Task<Message> {
+    if !app.selinux.relabeling.insert(mount_point.clone()) {
+        return Task::none();
+    }
+
+    Task::perform(
+        async move {
+            let result = async {
+                let client = FilesystemsClient::new().await.map_err(|e| e.to_string())?;
+                client
+                    .relabel(&mount_point)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            .await;
+            (mount_point, result)
+        },
+        |(mount_point, result)| {
+            Message::Relabeled {
+                mount_point,
+                result,
+            }
+            .into()
+        },
+    )
+}
+
+pub(super) fn relabeled(
+    app: &mut AppModel,
+    mount_point: String,
+    result: Result<(), String>,
+) -> Task<Message> {
+    app.selinux.relabeling.remove(&mount_point);
+    match result {
+        Ok(()) => tracing::info!("Relabeled {mount_point}"),
+        Err(e) => {
+            tracing::error!("Failed to relabel {mount_point}: {e}");
+            app.dialog = Some(ShowDialog::Info {
+                title: fl!("selinux-relabel-failed"),
+                body: e,
+            });
+        }
+    }
+    load()
+}
@@ -0,0 +1,278 @@
+//! Setup wizard for drives that hold nothing yet
+
+use crate::client::{ClientError, DisksClient, PartitionsClient};
+use crate::fl;
+use crate::message::app::Message;
+use crate::message::dialogs::SetUpDriveMessage;
+use crate::models::{UiDrive, load_all_drives};
+use crate::notification_policy::{self, StorageEvent};
+use crate::state::app::AppModel;
+use crate::state::dialogs::{
+    SETUP_FILESYSTEMS, SETUP_TABLE_TYPES, SetUpDriveDialog, SetUpDriveStep, ShowDialog,
+};
+use crate::utils::transaction::Transaction;
+use cosmic::app::Task;
+use storage_types::{COMMON_DOS_TYPES, COMMON_GPT_TYPES, CreatePartitionInfo, VolumeInfo};
+
+/// Look at a newly added drive, to offer setting it up when it is blank
+pub(super) fn inspect(device: String) -> Task<Message> {
+    Task::perform(
+        async move {
+            let blank_name = match blank_drive_name(&device).await {
+                Ok(name) => name,
+                Err(e) => {
+                    tracing::warn!(%e, %device, "failed to inspect the added drive");
+                    None
+                }
+            };
+            (device, blank_name)
+        },
+        |(device, blank_name)| Message::DriveInspected { device, blank_name }.into(),
+    )
+}
+
+async fn blank_drive_name(device: &str) -> Result<Option<String>, ClientError> {
+    let disks = DisksClient::new().await?;
+    let disk = disks.get_disk_info(device).await?;
+    let volumes = disks.list_volumes().await?;
+    Ok(disk.needs_setup(&volumes).then(|| disk.display_name()))
+}
+
+/// Announce an added drive; a click on the notification of a blank one opens
+/// the wizard
+pub(super) fn announce(
+    app: &AppModel,
+    device: String,
+    blank_name: Option<String>,
+) -> Task<Message> {
+    let event = match blank_name {
+        Some(name) => StorageEvent::DriveNeedsSetup { device, name },
+        None => StorageEvent::DriveAdded { device },
+    };
+    let settings = &app.config.notifications;
+    match event.setup_device().map(str::to_string) {
+        Some(device) => notification_policy::notify_clickable(
+            event,
+            settings,
+            app.window_focused,
+            fl!("setup-drive"),
+            Message::SetUpDrive(device),
+        ),
+        None => notification_policy::notify(event, settings, app.window_focused),
+    }
+}
+
+/// Open the wizard for `device`; waits for the drives when they are not
+/// loaded yet
+pub(super) fn open(app: &mut AppModel, device: String) -> Task<Message> {
+    let device = std::fs::canonicalize(&device)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or(device);
+
+    if app.sidebar.drives.is_empty() {
+        app.sidebar.pending_setup = Some(device);
+        return Task::none();
+    }
+
+    let Some(drive) = app
+        .sidebar
+        .drives
+        .iter()
+        .find(|drive| drive.device() == device)
+        .cloned()
+    else {
+        tracing::warn!(%device, "no drive to set up");
+        return Task::none();
+    };
+
+    let volumes: Vec<VolumeInfo> = drive
+        .volumes_flat
+        .iter()
+        .map(|volume| volume.volume.clone())
+        .collect();
+    // The drive may have been set up since the notification was shown
+    if !drive.disk.needs_setup(&volumes) {
+        tracing::info!(%device, "drive is no longer blank, showing it instead");
+        return Task::done(Message::Reveal(device).into());
+    }
+    if app.dialog.is_some() {
+        tracing::warn!(%device, "another dialog is open, not setting up the drive");
+        return super::reveal::focus(app);
+    }
+
+    app.dialog = Some(ShowDialog::SetUpDrive(SetUpDriveDialog {
+        drive,
+        step: SetUpDriveStep::Table,
+        table_index: 0,
+        filesystem_index: 0,
+        label: String::new(),
+        encrypt: false,
+        passphrase: String::new(),
+        confirm_passphrase: String::new(),
+        filesystem_tools: app.filesystem_tools.clone(),
+        running: false,
+        error: None,
+    }));
+    Task::batch([
+        Task::done(Message::Reveal(device).into()),
+        super::reveal::focus(app),
+    ])
+}
+
+/// Open the wizard asked for before the drives were loaded
+pub(super) fn open_pending(app: &mut AppModel) -> Task<Message> {
+    match app.sidebar.pending_setup.take() {
+        Some(device) => open(app, device),
+        None => Task::none(),
+    }
+}
+
+pub(super) fn set_up_drive_dialog(app: &mut AppModel, msg: SetUpDriveMessage) -> Task<Message> {
+    let Some(ShowDialog::SetUpDrive(state)) = app.dialog.as_mut() else {
+        return Task::none();
+    };
+
+    if state.running && !matches!(msg, SetUpDriveMessage::Failed(_)) {
+        return Task::none();
+    }
+
+    match msg {
+        SetUpDriveMessage::PrevStep => state.step = state.step.previous(),
+        SetUpDriveMessage::NextStep => {
+            if state.can_advance() {
+                state.step = state.step.next();
+            }
+        }
+        SetUpDriveMessage::SetStep(step) => {
+            if step.number() <= state.step.number() {
+                state.step = step;
+            }
+        }
+        SetUpDriveMessage::TableUpdate(index) => {
+            state.table_index = index.min(SETUP_TABLE_TYPES.len() - 1)
+        }
+        SetUpDriveMessage::FilesystemUpdate(index) => {
+            state.filesystem_index = index.min(SETUP_FILESYSTEMS.len() - 1)
+        }
+        SetUpDriveMessage::LabelUpdate(label) => state.label = label,
+        SetUpDriveMessage::EncryptUpdate(encrypt) => state.encrypt = encrypt,
+        SetUpDriveMessage::PassphraseUpdate(passphrase) => state.passphrase = passphrase,
+        SetUpDriveMessage::ConfirmPassphraseUpdate(passphrase) => {
+            state.confirm_passphrase = passphrase
+        }
+        SetUpDriveMessage::Confirm => {
+            if state.step != SetUpDriveStep::Review {
+                return Task::none();
+            }
+            state.running = true;
+            state.error = None;
+
+            let device = state.drive.device().to_string();
+            let table_type = state.table_type();
+            let info = partition_info(state);
+            let select = device.clone();
+            return Task::perform(
+                async move {
+                    set_up(device, table_type, info).await?;
+                    load_all_drives().await
+                },
+                move |result: Result<Vec<UiDrive>, ClientError>| match result {
+                    Ok(drives) => Message::UpdateNav(drives, Some(select.clone())).into(),
+                    Err(e) => SetUpDriveMessage::Failed(e.to_string()).into(),
+                },
+            );
+        }
+        SetUpDriveMessage::Failed(e) => {
+            tracing::error!(%e, "drive setup failed");
+            state.running = false;
+            state.error = Some(fl!("setup-drive-failed", error = e));
+        }
+        SetUpDriveMessage::Cancel => app.dialog = None,
+    }
+
+    Task::none()
+}
+
+/// The partition the wizard creates, spanning the drive once it is placed
+fn partition_info(state: &SetUpDriveDialog) -> CreatePartitionInfo {
+    let table_type = state.table_type();
+    let filesystem = state.filesystem();
+    let types = match table_type {
+        "dos" => &*COMMON_DOS_TYPES,
+        _ => &*COMMON_GPT_TYPES,
+    };
+    let selected_partition_type_index = types
+        .iter()
+        .position(|info| info.filesystem_type == filesystem)
+        .unwrap_or(0);
+
+    CreatePartitionInfo {
+        name: state.label.clone(),
+        table_type: table_type.to_string(),
+        filesystem_type: filesystem.to_string(),
+        selected_type: types
+            .get(selected_partition_type_index)
+            .map(|info| info.ty.clone())
+            .unwrap_or_default(),
+        selected_partition_type_index,
+        password_protected: state.encrypt,
+        password: if state.encrypt {
+            state.passphrase.clone()
+        } else {
+            String::new()
+        },
+        confirmed_password: String::new(),
+        can_continue: true,
+        ..Default::default()
+    }
+}
+
+/// Create the partition table and one partition over the whole drive
+///
+/// Runs as a transaction: when formatting fails, the new table is wiped so
+/// the drive is blank again rather than half set up.
+async fn set_up(
+    device: String,
+    table_type: &'static str,
+    info: CreatePartitionInfo,
+) -> Result<(), ClientError> {
+    let partitions = PartitionsClient::new().await?;
+    let disks = DisksClient::new().await?;
+    let mut transaction = Transaction::default();
+
+    let result = async {
+        transaction
+            .step(
+                format!("create a {table_type} partition table on {device}"),
+                partitions.create_partition_table(&device, table_type),
+                |_| {
+                    let device = device.clone();
+                    async move {
+                        // The drive was blank, so wiping it undoes everything
+                        PartitionsClient::new()
+                            .await?
+                            .create_partition_table(&device, "empty")
+                            .await
+                    }
+                },
+            )
+            .await?;
+
+        let disk = disks.get_disk_info(&device).await?;
+        let range = storage_types::place_partition(&disk, &[], 0, disk.size)
+            .map_err(ClientError::OperationFailed)?;
+        let info = CreatePartitionInfo {
+            offset: range.start,
+            size: range.end - range.start,
+            max_size: range.end - range.start,
+            ..info
+        };
+        partitions
+            .create_partition_with_filesystem(&device, &info)
+            .await
+            .map(|_| ())
+    }
+    .await;
+
+    transaction.finish(result).await
+}
@@ -1,7 +1,12 @@
-use crate::client::DisksClient;
+use crate::client::{DisksClient, OperationsClient};
+use crate::fl;
 use crate::message::dialogs::SmartDialogMessage;
-use crate::state::dialogs::{ShowDialog, SmartDataDialog};
+use crate::state::dialogs::{
+    SELFTEST_INTERVAL_DAYS, ShowDialog, SmartDataDialog, TEMPERATURE_THRESHOLD_CHOICES,
+};
 use cosmic::app::Task;
+use storage_contracts::OperationKind;
+use storage_types::{SelfTestSchedule, SmartBackendKind, TemperatureThresholds};
 
 use crate::message::app::Message;
 use crate::state::app::AppModel;
@@ -37,10 +42,16 @@ pub(super) fn smart_dialog(app: &mut AppModel, msg: SmartDialogMessage) -> Task<
                 drive: drive.clone(),
                 running: true,
                 info,
+                backend: state.backend.clone(),
+                schedule: state.schedule,
+                history: state.history.clone(),
+                thresholds: state.thresholds,
+                kernel_errors: state.kernel_errors.clone(),
                 error: None,
             }));
 
-            return Task::perform(
+            let device = drive.device().to_string();
+            let load_data = Task::perform(
                 async move {
                     let disks_client = DisksClient::new()
                         .await
@@ -57,6 +68,7 @@ pub(super) fn smart_dialog(app: &mut AppModel, msg: SmartDialogMessage) -> Task<
                 },
                 |res| Message::SmartDialog(SmartDialogMessage::Loaded(res)).into(),
             );
+            return Task::batch([load_data, load_kernel_errors(device)]);
         }
         SmartDialogMessage::SelfTestShort => {
             let drive = state.drive.clone();
@@ -65,6 +77,11 @@ pub(super) fn smart_dialog(app: &mut AppModel, msg: SmartDialogMessage) -> Task<
                 drive: drive.clone(),
                 running: true,
                 info,
+                backend: state.backend.clone(),
+                schedule: state.schedule,
+                history: state.history.clone(),
+                thresholds: state.thresholds,
+                kernel_errors: state.kernel_errors.clone(),
                 error: None,
             }));
             return Task::perform(
@@ -86,6 +103,11 @@ pub(super) fn smart_dialog(app: &mut AppModel, msg: SmartDialogMessage) -> Task<
                 drive: drive.clone(),
                 running: true,
                 info,
+                backend: state.backend.clone(),
+                schedule: state.schedule,
+                history: state.history.clone(),
+                thresholds: state.thresholds,
+                kernel_errors: state.kernel_errors.clone(),
                 error: None,
             }));
             return Task::perform(
@@ -107,14 +129,30 @@ pub(super) fn smart_dialog(app: &mut AppModel, msg: SmartDialogMessage) -> Task<
                 drive: drive.clone(),
                 running: true,
                 info,
+                backend: state.backend.clone(),
+                schedule: state.schedule,
+                history: state.history.clone(),
+                thresholds: state.thresholds,
+                kernel_errors: state.kernel_errors.clone(),
                 error: None,
             }));
+            // The running self-test is listed with the running operations
+            let operation_id = app
+                .operations
+                .iter()
+                .find(|operation| {
+                    operation.operation == OperationKind::SmartSelfTest
+                        && operation.target == drive.device()
+                })
+                .map(|operation| operation.operation_id);
             return Task::perform(
                 async move {
-                    DisksClient::new()
+                    let operation_id =
+                        operation_id.ok_or_else(|| "No self-test is running".to_string())?;
+                    OperationsClient::new()
                         .await
-                        .map_err(|e| format!("Failed to create disks client: {}", e))?
-                        .start_smart_test(drive.device(), "abort")
+                        .map_err(|e| format!("Failed to create operations client: {}", e))?
+                        .cancel_operation(operation_id)
                         .await
                         .map_err(|e| format!("Failed to abort SMART test: {}", e))
                 },
@@ -133,9 +171,10 @@ pub(super) fn smart_dialog(app: &mut AppModel, msg: SmartDialogMessage) -> Task<
             }
             app.dialog = Some(ShowDialog::SmartData(next));
 
-            // After a successful action, refresh SMART data.
+            // After a successful action, refresh SMART data and the backend in use.
             if ok {
-                return Task::perform(
+                let device = drive.device().to_string();
+                let load_data = Task::perform(
                     async move {
                         let disks_client = DisksClient::new()
                             .await
@@ -152,9 +191,219 @@ pub(super) fn smart_dialog(app: &mut AppModel, msg: SmartDialogMessage) -> Task<
                     },
                     |res| Message::SmartDialog(SmartDialogMessage::Loaded(res)).into(),
                 );
+                return Task::batch([
+                    load_data,
+                    load_backend(device.clone()),
+                    load_selftests(device.clone()),
+                    load_thresholds(device.clone()),
+                    load_kernel_errors(device),
+                ]);
+            }
+        }
+        SmartDialogMessage::BackendLoaded(res) => {
+            let mut next = state;
+            match res {
+                Ok(backend) => next.backend = Some(backend),
+                Err(e) => tracing::warn!(%e, "Failed to load SMART backend"),
+            }
+            app.dialog = Some(ShowDialog::SmartData(next));
+        }
+        SmartDialogMessage::BackendSelected(index) => {
+            let backend = match index {
+                0 => "auto",
+                1 => SmartBackendKind::UDisks.as_str(),
+                _ => SmartBackendKind::Smartctl.as_str(),
+            };
+            let drive = state.drive.clone();
+            let mut next = state;
+            next.running = true;
+            app.dialog = Some(ShowDialog::SmartData(next));
+
+            return Task::perform(
+                async move {
+                    DisksClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create disks client: {}", e))?
+                        .set_smart_backend(drive.device(), backend)
+                        .await
+                        .map_err(|e| format!("Failed to set SMART backend: {}", e))
+                },
+                |res| Message::SmartDialog(SmartDialogMessage::ActionComplete(res)).into(),
+            );
+        }
+        SmartDialogMessage::SelfTestsLoaded(res) => {
+            let mut next = state;
+            match res {
+                Ok((schedule, history)) => {
+                    next.schedule = Some(schedule);
+                    next.history = history;
+                }
+                Err(e) => tracing::warn!(%e, "Failed to load self-test schedule"),
+            }
+            app.dialog = Some(ShowDialog::SmartData(next));
+        }
+        SmartDialogMessage::ShortScheduleSelected(index) => {
+            let schedule = SelfTestSchedule {
+                short_interval_days: SELFTEST_INTERVAL_DAYS[index],
+                ..state.schedule.unwrap_or_default()
+            };
+            return set_schedule(app, state, schedule);
+        }
+        SmartDialogMessage::ExtendedScheduleSelected(index) => {
+            let schedule = SelfTestSchedule {
+                extended_interval_days: SELFTEST_INTERVAL_DAYS[index],
+                ..state.schedule.unwrap_or_default()
+            };
+            return set_schedule(app, state, schedule);
+        }
+        SmartDialogMessage::ThresholdsLoaded(res) => {
+            let mut next = state;
+            match res {
+                Ok(thresholds) => next.thresholds = Some(thresholds),
+                Err(e) => tracing::warn!(%e, "Failed to load temperature thresholds"),
             }
+            app.dialog = Some(ShowDialog::SmartData(next));
+        }
+        SmartDialogMessage::KernelErrorsLoaded(res) => {
+            let mut next = state;
+            match res {
+                Ok(errors) => next.kernel_errors = errors,
+                Err(e) => tracing::warn!(%e, "Failed to load kernel errors"),
+            }
+            app.dialog = Some(ShowDialog::SmartData(next));
+        }
+        SmartDialogMessage::WarningThresholdSelected(index) => {
+            let thresholds = TemperatureThresholds {
+                warning_c: TEMPERATURE_THRESHOLD_CHOICES[index],
+                ..state.thresholds.unwrap_or_default()
+            };
+            return set_thresholds(app, state, thresholds);
+        }
+        SmartDialogMessage::CriticalThresholdSelected(index) => {
+            let thresholds = TemperatureThresholds {
+                critical_c: TEMPERATURE_THRESHOLD_CHOICES[index],
+                ..state.thresholds.unwrap_or_default()
+            };
+            return set_thresholds(app, state, thresholds);
         }
     }
 
     Task::none()
 }
+
+fn set_schedule(
+    app: &mut AppModel,
+    state: SmartDataDialog,
+    schedule: SelfTestSchedule,
+) -> Task<Message> {
+    let drive = state.drive.clone();
+    let mut next = state;
+    next.running = true;
+    app.dialog = Some(ShowDialog::SmartData(next));
+
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?
+                .set_selftest_schedule(drive.device(), &schedule)
+                .await
+                .map_err(|e| format!("Failed to set self-test schedule: {}", e))
+        },
+        |res| Message::SmartDialog(SmartDialogMessage::ActionComplete(res)).into(),
+    )
+}
+
+fn set_thresholds(
+    app: &mut AppModel,
+    state: SmartDataDialog,
+    thresholds: TemperatureThresholds,
+) -> Task<Message> {
+    let drive = state.drive.clone();
+    let mut next = state;
+    if !thresholds.is_valid() {
+        next.error = Some(fl!("smart-thresholds-invalid"));
+        app.dialog = Some(ShowDialog::SmartData(next));
+        return Task::none();
+    }
+    next.running = true;
+    app.dialog = Some(ShowDialog::SmartData(next));
+
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?
+                .set_temperature_thresholds(drive.device(), Some(&thresholds))
+                .await
+                .map_err(|e| format!("Failed to set temperature thresholds: {}", e))
+        },
+        |res| Message::SmartDialog(SmartDialogMessage::ActionComplete(res)).into(),
+    )
+}
+
+/// Load the SMART backend selection of `device`
+pub(super) fn load_backend(device: String) -> Task<Message> {
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?
+                .get_smart_backend(&device)
+                .await
+                .map_err(|e| format!("Failed to get SMART backend: {}", e))
+        },
+        |res| Message::SmartDialog(SmartDialogMessage::BackendLoaded(res)).into(),
+    )
+}
+
+/// Load the self-test schedule and history of `device`
+pub(super) fn load_selftests(device: String) -> Task<Message> {
+    Task::perform(
+        async move {
+            let disks_client = DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?;
+            let schedule = disks_client
+                .get_selftest_schedule(&device)
+                .await
+                .map_err(|e| format!("Failed to get self-test schedule: {}", e))?;
+            let history = disks_client
+                .get_selftest_history(&device)
+                .await
+                .map_err(|e| format!("Failed to get self-test history: {}", e))?;
+            Ok((schedule, history))
+        },
+        |res| Message::SmartDialog(SmartDialogMessage::SelfTestsLoaded(res)).into(),
+    )
+}
+
+/// Load the temperature thresholds of `device`
+pub(super) fn load_thresholds(device: String) -> Task<Message> {
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?
+                .get_temperature_thresholds(&device)
+                .await
+                .map_err(|e| format!("Failed to get temperature thresholds: {}", e))
+        },
+        |res| Message::SmartDialog(SmartDialogMessage::ThresholdsLoaded(res)).into(),
+    )
+}
+
+/// Load the device errors the kernel logged for `device`
+pub(super) fn load_kernel_errors(device: String) -> Task<Message> {
+    Task::perform(
+        async move {
+            DisksClient::new()
+                .await
+                .map_err(|e| format!("Failed to create disks client: {}", e))?
+                .get_kernel_errors(&device)
+                .await
+                .map_err(|e| format!("Failed to get kernel errors: {}", e))
+        },
+        |res| Message::SmartDialog(SmartDialogMessage::KernelErrorsLoaded(res)).into(),
+    )
+}
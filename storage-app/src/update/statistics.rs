@@ -0,0 +1,81 @@
+use crate::app::ContextPage;
+use crate::client::ServiceClient;
+use crate::message::statistics::StatisticsMessage;
+use cosmic::app::Task;
+
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+
+pub(super) fn handle_statistics_message(
+    app: &mut AppModel,
+    msg: StatisticsMessage,
+) -> Task<Message> {
+    let state = &mut app.statistics;
+    match msg {
+        StatisticsMessage::Load => {
+            state.loading = true;
+            return Task::perform(
+                async {
+                    ServiceClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create service client: {}", e))?
+                        .get_usage_statistics()
+                        .await
+                        .map_err(|e| format!("Failed to load usage statistics: {}", e))
+                },
+                |res| Message::Statistics(StatisticsMessage::Loaded(res)).into(),
+            );
+        }
+        StatisticsMessage::Loaded(res) => {
+            state.loading = false;
+            match res {
+                Ok(stats) => {
+                    state.stats = Some(stats);
+                    state.error = None;
+                }
+                Err(e) => {
+                    tracing::warn!(%e, "failed to load usage statistics");
+                    state.error = Some(e);
+                }
+            }
+        }
+        StatisticsMessage::SetEnabled(enabled) => {
+            return Task::perform(
+                async move {
+                    ServiceClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create service client: {}", e))?
+                        .set_usage_statistics(enabled)
+                        .await
+                        .map_err(|e| format!("Failed to change usage statistics: {}", e))
+                },
+                |res| Message::Statistics(StatisticsMessage::EnabledSet(res)).into(),
+            );
+        }
+        StatisticsMessage::Clear => {
+            return Task::perform(
+                async {
+                    ServiceClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create service client: {}", e))?
+                        .clear_usage_statistics()
+                        .await
+                        .map_err(|e| format!("Failed to clear usage statistics: {}", e))
+                },
+                |res| Message::Statistics(StatisticsMessage::Cleared(res)).into(),
+            );
+        }
+        StatisticsMessage::EnabledSet(res) | StatisticsMessage::Cleared(res) => {
+            if let Err(e) = res {
+                tracing::warn!(%e, "usage statistics request failed");
+                state.error = Some(e);
+                return Task::none();
+            }
+            if app.context_page == ContextPage::Statistics && app.core.window.show_context {
+                return handle_statistics_message(app, StatisticsMessage::Load);
+            }
+        }
+    }
+
+    Task::none()
+}
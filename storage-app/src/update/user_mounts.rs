@@ -0,0 +1,52 @@
+use crate::message::app::Message;
+use crate::state::app::AppModel;
+use crate::state::dialogs::ShowDialog;
+use crate::utils::user_mounts::unmount_user_mount;
+use cosmic::app::Task;
+use std::path::PathBuf;
+
+/// Unmount a FUSE mount or gvfs share of the user
+pub(super) fn unmount(app: &mut AppModel, mount_point: PathBuf) -> Task<Message> {
+    let Some(mount) = app
+        .user_mounts
+        .mounts
+        .iter()
+        .find(|mount| mount.mount_point == mount_point)
+        .cloned()
+    else {
+        return Task::none();
+    };
+    if !app.user_mounts.unmounting.insert(mount_point.clone()) {
+        return Task::none();
+    }
+
+    Task::perform(
+        async move { unmount_user_mount(&mount).await },
+        move |result| {
+            Message::UserMountUnmounted {
+                mount_point: mount_point.clone(),
+                result,
+            }
+            .into()
+        },
+    )
+}
+
+pub(super) fn unmounted(app: &mut AppModel, mount_point: PathBuf, result: Result<(), String>) {
+    app.user_mounts.unmounting.remove(&mount_point);
+    match result {
+        Ok(()) => {
+            tracing::info!("Unmounted {}", mount_point.display());
+            app.user_mounts
+                .mounts
+                .retain(|mount| mount.mount_point != mount_point);
+        }
+        Err(e) => {
+            tracing::error!("Failed to unmount {}: {}", mount_point.display(), e);
+            app.dialog = Some(ShowDialog::Info {
+                title: "Unmount Failed".to_string(),
+                body: e,
+            });
+        }
+    }
+}
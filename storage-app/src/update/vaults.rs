@@ -0,0 +1,174 @@
+use cosmic::app::Task;
+
+use crate::client::FilesystemsClient;
+use crate::fl;
+use crate::message::app::Message;
+use crate::message::vaults::VaultsMessage;
+use crate::notification_policy::{self, StorageEvent};
+use crate::state::app::AppModel;
+
+pub(super) fn handle_vaults_message(app: &mut AppModel, msg: VaultsMessage) -> Task<Message> {
+    let state = &mut app.vaults;
+    match msg {
+        VaultsMessage::Load => {
+            if state.loading {
+                return Task::none();
+            }
+            state.loading = true;
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .list_legacy_vaults()
+                        .await
+                        .map_err(|e| format!("Failed to look for vaults: {}", e))
+                },
+                |res| Message::Vaults(VaultsMessage::Loaded(res)).into(),
+            );
+        }
+        VaultsMessage::Loaded(res) => {
+            state.loading = false;
+            match res {
+                Ok(vaults) => state.vaults = vaults,
+                Err(e) => {
+                    tracing::error!(%e, "vault search error");
+                    state.error = Some(e);
+                }
+            }
+        }
+        VaultsMessage::Select(cipher_dir) => {
+            if state.busy.is_some() {
+                return Task::none();
+            }
+            let selected = (state.selected.as_ref() != Some(&cipher_dir)).then_some(cipher_dir);
+            state.selected = selected;
+            state.passphrase.clear();
+            state.target.clear();
+            state.result = None;
+            state.error = None;
+        }
+        VaultsMessage::PassphraseUpdate(passphrase) => state.passphrase = passphrase,
+        VaultsMessage::TargetUpdate(target) => state.target = target,
+        VaultsMessage::Mount => {
+            let Some(cipher_dir) = state.selected.clone() else {
+                return Task::none();
+            };
+            if state.busy.is_some() || state.passphrase.is_empty() {
+                return Task::none();
+            }
+            state.busy = Some(cipher_dir.clone());
+            state.error = None;
+            let passphrase = std::mem::take(&mut state.passphrase);
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .mount_legacy_vault(&cipher_dir, &passphrase)
+                        .await
+                        .map_err(|e| format!("Failed to mount {}: {}", cipher_dir, e))
+                },
+                |res| Message::Vaults(VaultsMessage::Mounted(res)).into(),
+            );
+        }
+        VaultsMessage::Mounted(res) => {
+            state.busy = None;
+            match res {
+                Ok(mount_point) => {
+                    return Task::batch([
+                        Task::done(Message::OpenPath(mount_point).into()),
+                        Task::done(Message::Vaults(VaultsMessage::Load).into()),
+                    ]);
+                }
+                Err(e) => {
+                    tracing::error!(%e, "vault mount error");
+                    state.error = Some(e);
+                }
+            }
+        }
+        VaultsMessage::Unmount(cipher_dir) => {
+            if state.busy.is_some() {
+                return Task::none();
+            }
+            state.busy = Some(cipher_dir.clone());
+            state.error = None;
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .unmount_legacy_vault(&cipher_dir)
+                        .await
+                        .map_err(|e| format!("Failed to unmount {}: {}", cipher_dir, e))
+                },
+                |res| Message::Vaults(VaultsMessage::Unmounted(res)).into(),
+            );
+        }
+        VaultsMessage::Unmounted(res) => {
+            state.busy = None;
+            if let Err(e) = res {
+                tracing::error!(%e, "vault unmount error");
+                state.error = Some(e);
+            }
+            return Task::done(Message::Vaults(VaultsMessage::Load).into());
+        }
+        VaultsMessage::Migrate => {
+            let Some(cipher_dir) = state.selected.clone() else {
+                return Task::none();
+            };
+            let target = state.target.trim().to_string();
+            if state.busy.is_some() || target.is_empty() {
+                return Task::none();
+            }
+            state.busy = Some(cipher_dir.clone());
+            state.progress = Some((0, 0));
+            state.result = None;
+            state.error = None;
+            return Task::perform(
+                async move {
+                    FilesystemsClient::new()
+                        .await
+                        .map_err(|e| format!("Failed to create filesystems client: {}", e))?
+                        .migrate_legacy_vault(&cipher_dir, &target)
+                        .await
+                        .map_err(|e| format!("Migration failed: {}", e))
+                },
+                |res| Message::Vaults(VaultsMessage::Migrated(res)).into(),
+            );
+        }
+        VaultsMessage::MigrationProgress {
+            cipher_dir,
+            copied_bytes,
+            total_bytes,
+        } => {
+            if state.busy.as_ref() == Some(&cipher_dir) {
+                state.progress = Some((copied_bytes, total_bytes));
+            }
+        }
+        VaultsMessage::Migrated(res) => {
+            let event = StorageEvent::OperationFinished {
+                operation: fl!("legacy-vault-migrate"),
+                result: res
+                    .as_ref()
+                    .map(|result| fl!("legacy-vault-migrated", files = result.files))
+                    .map_err(Clone::clone),
+            };
+            let notification =
+                notification_policy::notify(event, &app.config.notifications, app.window_focused);
+            let state = &mut app.vaults;
+            state.busy = None;
+            state.progress = None;
+            match res {
+                Ok(result) => state.result = Some(result),
+                Err(e) => {
+                    tracing::error!(%e, "vault migration error");
+                    state.error = Some(e);
+                }
+            }
+            return notification;
+        }
+    }
+
+    Task::none()
+}
@@ -65,19 +65,19 @@ pub(super) fn btrfs_create_subvolume_message(
                 return Task::none();
             }
 
-            // Validate name
+            // Validate name; nested paths are normalized by the service
             let name = state.name.trim();
             if name.is_empty() {
                 state.error = Some(fl!("btrfs-subvolume-name-required"));
                 return Task::none();
             }
 
-            if name.contains('/') {
-                state.error = Some(fl!("btrfs-subvolume-invalid-chars"));
+            if name.split('/').any(|component| component == "..") {
+                state.error = Some(fl!("btrfs-subvolume-path-invalid"));
                 return Task::none();
             }
 
-            if name.len() > 255 {
+            if name.split('/').any(|component| component.len() > 255) {
                 state.error = Some("Subvolume name too long".to_string());
                 return Task::none();
             }
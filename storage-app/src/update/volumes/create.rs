@@ -8,7 +8,7 @@ use crate::fl;
 use crate::message::dialogs::CreateMessage;
 use crate::state::dialogs::{CreatePartitionStep, FormatPartitionStep, ShowDialog};
 
-use storage_types::CreatePartitionInfo;
+use storage_types::{CreatePartitionInfo, FormatOptions};
 
 use crate::state::volumes::VolumesControl;
 
@@ -119,6 +119,7 @@ pub(super) fn create_message(
                 state.info.selected_partition_type_index = p_type;
                 state.error = None;
             }
+            CreateMessage::FormatPresetUpdate(_) | CreateMessage::FormatOptionUpdate(..) => {}
             CreateMessage::Cancel => return Task::done(Message::CloseDialog.into()),
             CreateMessage::Partition => {
                 if state.running {
@@ -215,40 +216,85 @@ pub(super) fn create_message(
             }
             CreateMessage::EraseUpdate(erase) => state.info.erase = erase,
             CreateMessage::PartitionTypeUpdate(p_type) => {
+                if state.info.selected_partition_type_index != p_type {
+                    state.format_preset = None;
+                    state.fs_specific.clear();
+                }
                 state.info.selected_partition_type_index = p_type
             }
+            CreateMessage::FormatPresetUpdate(preset_id) => {
+                let fs_type = crate::utils::partition_types::common_partition_filesystem_type(
+                    &state.info.table_type,
+                    state.info.selected_partition_type_index,
+                )
+                .unwrap_or_default();
+                let mut options = FormatOptions::default();
+                if let Some(preset) = FormatOptions::presets(&fs_type)
+                    .iter()
+                    .find(|preset| Some(&preset.id) == preset_id.as_ref())
+                {
+                    options.apply_preset(preset);
+                }
+                state.fs_specific = options.fs_specific;
+                state.format_preset = preset_id;
+            }
+            CreateMessage::FormatOptionUpdate(key, value) => {
+                if value.is_empty() {
+                    state.fs_specific.remove(&key);
+                } else {
+                    state.fs_specific.insert(key, value);
+                }
+                state.format_preset = None;
+            }
             CreateMessage::Cancel => return Task::done(Message::CloseDialog.into()),
             CreateMessage::Partition => {
                 if state.running {
                     return Task::none();
                 }
-                state.running = true;
+
+                let Some(fs_type) = crate::utils::partition_types::common_partition_filesystem_type(
+                    &state.info.table_type,
+                    state.info.selected_partition_type_index,
+                ) else {
+                    tracing::warn!(
+                        operation = "format_partition",
+                        "invalid filesystem selection"
+                    );
+                    return Task::none();
+                };
+
+                let format_options = FormatOptions {
+                    erase: state.info.erase,
+                    fs_specific: state.fs_specific.clone(),
+                    ..Default::default()
+                };
+                // The dialog shows the validation error inline
+                if let Err(e) = format_options.validate(&fs_type).and_then(|()| {
+                    storage_types::interop::validate_volume_size(&fs_type, state.info.size)
+                }) {
+                    tracing::warn!(
+                        operation = "format_partition",
+                        "invalid format options: {e}"
+                    );
+                    return Task::none();
+                }
 
                 let volume = state.volume.clone();
                 let info = state.info.clone();
+                // The running operations below the main view show how far it got
+                *dialog = None;
                 return Task::perform(
                     async move {
-                        let fs_type =
-                            crate::utils::partition_types::common_partition_filesystem_type(
-                                info.table_type.as_str(),
-                                info.selected_partition_type_index,
-                            )
-                            .ok_or_else(|| anyhow::anyhow!("Invalid filesystem selection"))?;
-
                         let filesystems_client = FilesystemsClient::new().await.map_err(|e| {
                             anyhow::anyhow!("Failed to create filesystems client: {}", e)
                         })?;
-                        let options = if info.erase {
-                            Some("{\"erase\": true}")
-                        } else {
-                            None
-                        };
+                        let options_json = serde_json::to_string(&format_options)?;
                         let device = volume
                             .device_path
                             .as_ref()
                             .ok_or_else(|| anyhow::anyhow!("Volume has no device path"))?;
                         filesystems_client
-                            .format(device, &fs_type, &info.name, options)
+                            .format(device, &fs_type, &info.name, Some(&options_json))
                             .await
                             .map_err(|e| anyhow::anyhow!("Failed to format: {}", e))?;
                         load_all_drives().await.map_err(|e| e.into())
@@ -270,7 +316,7 @@ pub(super) fn create_message(
             tracing::warn!("create message received while an unlock dialog is open; ignoring");
         }
 
-        ShowDialog::FormatDisk(_) => {
+        ShowDialog::FormatDisk(_) | ShowDialog::SetUpDrive(_) => {
             tracing::warn!("create message received while a format disk dialog is open; ignoring");
         }
 
@@ -278,6 +324,40 @@ pub(super) fn create_message(
             tracing::warn!("create message received while a SMART dialog is open; ignoring");
         }
 
+        ShowDialog::Defragment(_) => {
+            tracing::warn!("create message received while a defragment dialog is open; ignoring");
+        }
+
+        ShowDialog::EncryptedFolders(_) => {
+            tracing::warn!(
+                "create message received while an encrypted folders dialog is open; ignoring"
+            );
+        }
+
+        ShowDialog::EspSync(_) => {
+            tracing::warn!("create message received while an ESP sync dialog is open; ignoring");
+        }
+
+        ShowDialog::LowSpace(_) => {
+            tracing::warn!("create message received while a low space dialog is open; ignoring");
+        }
+
+        ShowDialog::Automount(_) => {
+            tracing::warn!("create message received while an automount dialog is open; ignoring");
+        }
+
+        ShowDialog::Performance(_) => {
+            tracing::warn!("create message received while a performance dialog is open; ignoring");
+        }
+
+        ShowDialog::SectorViewer(_) => {
+            tracing::warn!("create message received while a sector viewer is open; ignoring");
+        }
+
+        ShowDialog::Inspect(_) => {
+            tracing::warn!("create message received while an inspect dialog is open; ignoring");
+        }
+
         ShowDialog::NewDiskImage(_)
         | ShowDialog::AttachDiskImage(_)
         | ShowDialog::ImageOperation(_) => {
@@ -286,17 +366,22 @@ pub(super) fn create_message(
 
         ShowDialog::EditPartition(_)
         | ShowDialog::ResizePartition(_)
+        | ShowDialog::MovePartition(_)
         | ShowDialog::EditFilesystemLabel(_)
         | ShowDialog::ConfirmAction(_)
         | ShowDialog::TakeOwnership(_)
         | ShowDialog::ChangePassphrase(_)
         | ShowDialog::UnmountBusy(_)
+        | ShowDialog::LostPartitions(_)
+        | ShowDialog::GptEntries(_)
+        | ShowDialog::Diagnostics(_)
         | ShowDialog::BtrfsCreateSubvolume(_)
-        | ShowDialog::BtrfsCreateSnapshot(_) => {
+        | ShowDialog::BtrfsCreateSnapshot(_)
+        | ShowDialog::RcloneConfigPassword(_) => {
             tracing::warn!("create message received while a different dialog is open; ignoring");
         }
 
-        ShowDialog::Info { .. } => {
+        ShowDialog::Info { .. } | ShowDialog::Error { .. } => {
             tracing::warn!("create message received while an info dialog is open; ignoring");
         }
 
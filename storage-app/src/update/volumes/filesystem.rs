@@ -5,9 +5,12 @@ use crate::app::Message;
 use crate::client::filesystems::FilesystemsClient;
 use crate::errors::ui::{UiErrorContext, log_error_and_show_dialog};
 use crate::fl;
-use crate::message::dialogs::EditFilesystemLabelMessage;
+use crate::message::dialogs::{
+    DefragDialogMessage, EditFilesystemLabelMessage, EncryptedFoldersDialogMessage,
+};
 use crate::state::dialogs::{
-    ConfirmActionDialog, EditFilesystemLabelDialog, FilesystemTarget, ShowDialog,
+    ConfirmActionDialog, DefragmentDialog, EditFilesystemLabelDialog, EncryptedFoldersDialog,
+    FilesystemTarget, ShowDialog,
 };
 
 use crate::message::volumes::VolumesControlMessage;
@@ -139,6 +142,7 @@ pub(super) fn open_check_filesystem(
         title: fl!("check-filesystem").to_string(),
         body: fl!("check-filesystem-warning").to_string(),
         target,
+        confirm_target: None,
         ok_message: VolumesControlMessage::CheckFilesystemConfirm.into(),
         running: false,
     }));
@@ -219,10 +223,15 @@ pub(super) fn open_repair_filesystem(
         FilesystemTarget::Volume(volume)
     };
 
+    let confirm_target = match &target {
+        FilesystemTarget::Volume(volume) => volume.device_path.clone(),
+        FilesystemTarget::Node(node) => node.volume.device_path.clone(),
+    };
     *dialog = Some(ShowDialog::ConfirmAction(ConfirmActionDialog {
         title: fl!("repair-filesystem").to_string(),
         body: fl!("repair-filesystem-warning").to_string(),
         target,
+        confirm_target,
         ok_message: VolumesControlMessage::RepairFilesystemConfirm.into(),
         running: false,
     }));
@@ -274,3 +283,156 @@ pub(super) fn repair_filesystem_confirm(
         },
     )
 }
+
+pub(super) fn open_defragment(
+    control: &mut VolumesControl,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    if dialog.is_some() {
+        return Task::none();
+    }
+
+    let volume = if let Some(node) = control.selected_volume_node() {
+        node.volume.clone()
+    } else {
+        let Some(volume) = control
+            .segments
+            .get(control.selected_segment)
+            .and_then(|segment| segment.volume.clone())
+        else {
+            return Task::none();
+        };
+        volume
+    };
+
+    let (Some(device), Some(mount_point)) = (
+        volume.device_path.clone(),
+        volume.mount_points.first().cloned(),
+    ) else {
+        return Task::none();
+    };
+    if !super::helpers::supports_defrag(&volume.id_type) {
+        return Task::none();
+    }
+
+    *dialog = Some(ShowDialog::Defragment(DefragmentDialog {
+        device,
+        fs_type: volume.id_type.clone(),
+        mount_point,
+        target: String::new(),
+        report: None,
+        result: None,
+        progress: None,
+        analyzing: false,
+        running: false,
+        error: None,
+    }));
+
+    // Score the whole filesystem up front so the user can judge whether to run
+    Task::done(Message::DefragDialog(DefragDialogMessage::Analyze).into())
+}
+
+pub(super) fn open_encrypted_folders(
+    control: &mut VolumesControl,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    if dialog.is_some() {
+        return Task::none();
+    }
+
+    let volume = if let Some(node) = control.selected_volume_node() {
+        node.volume.clone()
+    } else {
+        let Some(volume) = control
+            .segments
+            .get(control.selected_segment)
+            .and_then(|segment| segment.volume.clone())
+        else {
+            return Task::none();
+        };
+        volume
+    };
+
+    let (Some(device), Some(mount_point)) = (
+        volume.device_path.clone(),
+        volume.mount_points.first().cloned(),
+    ) else {
+        return Task::none();
+    };
+    if !storage_types::fscrypt_capable(&volume.id_type) {
+        return Task::none();
+    }
+
+    *dialog = Some(ShowDialog::EncryptedFolders(EncryptedFoldersDialog {
+        device,
+        mount_point,
+        status: None,
+        unlocking: None,
+        unlock_passphrase: String::new(),
+        new_directory: String::new(),
+        protector_name: String::new(),
+        passphrase: String::new(),
+        confirm: String::new(),
+        loading: false,
+        running: false,
+        error: None,
+    }));
+
+    Task::done(Message::EncryptedFoldersDialog(EncryptedFoldersDialogMessage::Load).into())
+}
+
+/// Probe features of the selected filesystem so dependent actions can be gated
+pub(super) fn load_filesystem_features(
+    control: &mut VolumesControl,
+) -> Task<cosmic::Action<Message>> {
+    let Some(device) = control.selected_filesystem_device() else {
+        control.filesystem_features = None;
+        return Task::none();
+    };
+
+    if control
+        .filesystem_features
+        .as_ref()
+        .is_some_and(|(loaded_device, _)| *loaded_device == device)
+    {
+        return Task::none();
+    }
+    control.filesystem_features = None;
+
+    Task::perform(
+        async move {
+            let result = match FilesystemsClient::new().await {
+                Ok(client) => client
+                    .get_filesystem_features(&device)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            (device, result)
+        },
+        |(device, result)| {
+            Message::VolumesMessage(VolumesControlMessage::FilesystemFeaturesLoaded {
+                device,
+                result,
+            })
+            .into()
+        },
+    )
+}
+
+pub(super) fn filesystem_features_loaded(
+    control: &mut VolumesControl,
+    device: String,
+    result: Result<storage_types::FilesystemFeatures, String>,
+) -> Task<cosmic::Action<Message>> {
+    // Ignore results for a selection the user has already moved away from
+    if control.selected_filesystem_device().as_deref() != Some(device.as_str()) {
+        return Task::none();
+    }
+
+    if let Err(e) = &result {
+        tracing::warn!("Failed to probe filesystem features for {device}: {e}");
+    }
+    control.filesystem_features = Some((device, result));
+    Task::none()
+}
@@ -19,3 +19,8 @@ pub(crate) fn collect_mounted_descendants_leaf_first(node: &UiVolume) -> Vec<Str
     visit(node, &mut out);
     out
 }
+
+/// Filesystems with an online defragmentation backend in the service
+pub(crate) fn supports_defrag(fs_type: &str) -> bool {
+    matches!(fs_type, "ext2" | "ext3" | "ext4" | "xfs" | "btrfs")
+}
@@ -23,7 +23,12 @@ impl VolumesControl {
     ) -> Task<cosmic::Action<Message>> {
         match message {
             VolumesControlMessage::SegmentSelected(index) => {
-                selection::segment_selected(self, index, dialog)
+                let task = selection::segment_selected(self, index, dialog);
+                Task::batch(vec![
+                    task,
+                    filesystem::load_filesystem_features(self),
+                    partition::load_deleted_partitions(self),
+                ])
             }
             VolumesControlMessage::SelectDetailTab(tab) => {
                 self.detail_tab = tab;
@@ -55,6 +60,13 @@ impl VolumesControl {
                             mount_point: mp.clone(),
                         })));
                     }
+                    if btrfs_state.compression.is_none() {
+                        tasks.push(Task::done(cosmic::Action::App(
+                            Message::BtrfsLoadCompression {
+                                mount_point: mp.clone(),
+                            },
+                        )));
+                    }
                     if !tasks.is_empty() {
                         return Task::batch(tasks);
                     }
@@ -64,10 +76,19 @@ impl VolumesControl {
             VolumesControlMessage::SelectVolume {
                 segment_index,
                 device_path,
-            } => selection::select_volume(self, segment_index, device_path, dialog),
+            } => {
+                let task = selection::select_volume(self, segment_index, device_path, dialog);
+                Task::batch(vec![task, filesystem::load_filesystem_features(self)])
+            }
             VolumesControlMessage::Mount => mount::mount(self),
             VolumesControlMessage::Unmount => mount::unmount(self),
             VolumesControlMessage::ChildMount(device_path) => mount::child_mount(self, device_path),
+            VolumesControlMessage::MountReadOnly(device) => {
+                mount::mount_read_only(self, device, dialog)
+            }
+            VolumesControlMessage::RepairAndMount(device) => {
+                mount::repair_and_mount(self, device, dialog)
+            }
             VolumesControlMessage::ChildUnmount(device_path) => {
                 mount::child_unmount(self, device_path)
             }
@@ -83,6 +104,15 @@ impl VolumesControl {
             VolumesControlMessage::OpenResizePartition => {
                 partition::open_resize_partition(self, dialog)
             }
+            VolumesControlMessage::OpenMovePartition => {
+                partition::open_move_partition(self, dialog)
+            }
+            VolumesControlMessage::OpenRealignPartition => {
+                partition::open_realign_partition(self, dialog)
+            }
+            VolumesControlMessage::RealignPartitionConfirm => {
+                partition::realign_partition_confirm(self, dialog)
+            }
             VolumesControlMessage::OpenEditFilesystemLabel => {
                 filesystem::open_edit_filesystem_label(self, dialog)
             }
@@ -116,6 +146,23 @@ impl VolumesControl {
             VolumesControlMessage::OpenBtrfsCreateSnapshot => {
                 btrfs::open_create_snapshot(self, dialog)
             }
+            VolumesControlMessage::OpenDefragment => filesystem::open_defragment(self, dialog),
+            VolumesControlMessage::OpenEncryptedFolders => {
+                filesystem::open_encrypted_folders(self, dialog)
+            }
+            VolumesControlMessage::ToggleFilesystemFeatures => {
+                self.show_filesystem_features = !self.show_filesystem_features;
+                Task::none()
+            }
+            VolumesControlMessage::FilesystemFeaturesLoaded { device, result } => {
+                filesystem::filesystem_features_loaded(self, device, result)
+            }
+            VolumesControlMessage::DeletedPartitionsLoaded { device, result } => {
+                partition::deleted_partitions_loaded(self, device, result)
+            }
+            VolumesControlMessage::RestoreDeletedPartition(id) => {
+                partition::restore_deleted_partition(self, id)
+            }
 
             VolumesControlMessage::CreateMessage(msg) => create::create_message(self, msg, dialog),
             VolumesControlMessage::UnlockMessage(unlock_message) => {
@@ -127,6 +174,9 @@ impl VolumesControl {
             VolumesControlMessage::ResizePartitionMessage(msg) => {
                 partition::resize_partition_message(self, msg, dialog)
             }
+            VolumesControlMessage::MovePartitionMessage(msg) => {
+                partition::move_partition_message(self, msg, dialog)
+            }
             VolumesControlMessage::EditFilesystemLabelMessage(msg) => {
                 filesystem::edit_filesystem_label_message(self, msg, dialog)
             }
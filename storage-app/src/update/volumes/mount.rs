@@ -1,13 +1,28 @@
 use crate::models::{UiDrive, load_all_drives};
 use cosmic::Task;
 use std::future::Future;
+use storage_types::{MountFailure, MountOptions, VolumeInfo, WindowsHibernation};
 
 use crate::app::Message;
 use crate::client::FilesystemsClient;
-use crate::state::dialogs::{ShowDialog, UnmountBusyDialog};
+use crate::errors::ui::{UiErrorContext, log_error_and_offer_recovery, log_error_and_show_dialog};
+use crate::fl;
+use crate::message::volumes::VolumesControlMessage;
+use crate::state::dialogs::{
+    ConfirmActionDialog, ErrorRecovery, FilesystemTarget, ShowDialog, UnmountBusyDialog,
+};
 
 use crate::state::volumes::VolumesControl;
 
+/// Serialize mount options carrying the user's mount path preference
+fn mount_options_json(control: &VolumesControl) -> Option<String> {
+    let options = MountOptions {
+        path_policy: Some(control.mount_path_policy.clone()),
+        ..Default::default()
+    };
+    serde_json::to_string(&options).ok()
+}
+
 /// Generic helper for volume mount/unmount operations
 fn perform_volume_operation<F, Fut>(
     operation: F,
@@ -36,6 +51,79 @@ where
     )
 }
 
+/// How a mount ended
+enum MountOutcome {
+    Mounted(Vec<UiDrive>),
+    /// Windows left the filesystem hibernated; it was not mounted
+    Hibernated(WindowsHibernation),
+}
+
+/// Mount `volume` on `device`, unless Windows left it hibernated; then ask
+/// whether to mount it read-only instead
+fn mount_checked(
+    volume: VolumeInfo,
+    device: String,
+    options_json: Option<String>,
+    operation_name: &'static str,
+    preserve_selection: String,
+) -> Task<cosmic::Action<Message>> {
+    let probed_device = device.clone();
+    Task::perform(
+        async move {
+            let client = FilesystemsClient::new().await?;
+            // When the probe fails, the service still refuses such a mount
+            if let Ok(Some(hibernation)) = client.windows_hibernation(&probed_device).await {
+                return Ok(MountOutcome::Hibernated(hibernation));
+            }
+            client
+                .mount(&probed_device, "", options_json.as_deref())
+                .await?;
+            Ok(MountOutcome::Mounted(load_all_drives().await?))
+        },
+        move |result: anyhow::Result<MountOutcome>| match result {
+            Ok(MountOutcome::Mounted(drives)) => {
+                Message::UpdateNavWithChildSelection(drives, Some(preserve_selection.clone()))
+                    .into()
+            }
+            Ok(MountOutcome::Hibernated(hibernation)) => {
+                Message::Dialog(Box::new(ShowDialog::ConfirmAction(ConfirmActionDialog {
+                    title: fl!("windows-hibernated"),
+                    body: match hibernation {
+                        WindowsHibernation::Hibernated => fl!("windows-hibernated-body"),
+                        WindowsHibernation::FastStartup => fl!("windows-fast-startup-body"),
+                    },
+                    target: FilesystemTarget::Volume(volume.clone()),
+                    confirm_target: None,
+                    ok_message: VolumesControlMessage::MountReadOnly(device.clone()).into(),
+                    running: false,
+                })))
+                .into()
+            }
+            Err(e) => {
+                let title = match MountFailure::parse(&format!("{e:#}")) {
+                    MountFailure::Dirty => fl!("mount-filesystem-dirty"),
+                    MountFailure::Corrupt => fl!("mount-filesystem-corrupt"),
+                    MountFailure::Unknown => {
+                        tracing::error!(?e, "{operation_name} failed");
+                        return Message::None.into();
+                    }
+                };
+                let recovery = ErrorRecovery {
+                    label: fl!("repair-and-mount"),
+                    message: VolumesControlMessage::RepairAndMount(device.clone()).into(),
+                };
+                let ctx = UiErrorContext {
+                    operation: operation_name,
+                    device_path: None,
+                    device: Some(device.as_str()),
+                    drive_path: None,
+                };
+                log_error_and_offer_recovery(title, e, ctx, Some(recovery)).into()
+            }
+        },
+    )
+}
+
 pub(super) fn mount(control: &mut VolumesControl) -> Task<cosmic::Action<Message>> {
     let Some(volume) = control
         .segments
@@ -50,18 +138,81 @@ pub(super) fn mount(control: &mut VolumesControl) -> Task<cosmic::Action<Message
         .clone()
         .unwrap_or_else(|| volume.label.clone());
     let device_path_for_selection = device.clone();
+    let options_json = mount_options_json(control);
+
+    mount_checked(
+        volume,
+        device,
+        options_json,
+        "mount",
+        device_path_for_selection,
+    )
+}
+
+/// Mount a filesystem Windows left hibernated read-only, as offered when
+/// mounting it
+pub(super) fn mount_read_only(
+    control: &mut VolumesControl,
+    device: String,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    *dialog = None;
+    let options = MountOptions {
+        read_only: true,
+        path_policy: Some(control.mount_path_policy.clone()),
+        ..Default::default()
+    };
+    let options_json = serde_json::to_string(&options).ok();
+    let device_path_for_selection = device.clone();
 
     perform_volume_operation(
         || async move {
             let client = FilesystemsClient::new().await?;
-            client.mount(&device, "", None).await?;
+            client.mount(&device, "", options_json.as_deref()).await?;
             Ok(())
         },
-        "mount",
+        "read-only mount",
         Some(device_path_for_selection),
     )
 }
 
+/// Check and repair the filesystem on `device`, then retry the mount that
+/// failed for want of a check
+pub(super) fn repair_and_mount(
+    control: &mut VolumesControl,
+    device: String,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    *dialog = None;
+    let options_json = mount_options_json(control);
+    let device_path_for_selection = device.clone();
+
+    Task::perform(
+        async move {
+            let client = FilesystemsClient::new().await?;
+            client.check(&device, true).await?;
+            client.mount(&device, "", options_json.as_deref()).await?;
+            Ok(load_all_drives().await?)
+        },
+        move |result: anyhow::Result<Vec<UiDrive>>| match result {
+            Ok(drives) => Message::UpdateNavWithChildSelection(
+                drives,
+                Some(device_path_for_selection.clone()),
+            )
+            .into(),
+            Err(e) => {
+                let ctx = UiErrorContext {
+                    operation: "repair_and_mount",
+                    device_path: None,
+                    device: Some(device_path_for_selection.as_str()),
+                    drive_path: None,
+                };
+                log_error_and_show_dialog(fl!("repair-and-mount"), e, ctx).into()
+            }
+        },
+    )
+}
+
 // Helper enum to distinguish busy errors from generic errors
 #[derive(Debug)]
 enum UnmountResult {
@@ -184,20 +335,14 @@ pub(super) fn child_mount(
         .clone()
         .unwrap_or_else(|| device_path.clone());
     let device_path_for_selection = device_path.clone();
+    let options_json = mount_options_json(control);
 
-    perform_volume_operation(
-        || async move {
-            let client = FilesystemsClient::new()
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to create client: {}", e))?;
-            let _mount_point = client
-                .mount(&device, "", None)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to mount: {}", e))?;
-            Ok(())
-        },
+    mount_checked(
+        node.volume,
+        device,
+        options_json,
         "child mount",
-        Some(device_path_for_selection),
+        device_path_for_selection,
     )
 }
 
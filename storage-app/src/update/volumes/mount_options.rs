@@ -1,7 +1,7 @@
 use crate::client::FilesystemsClient;
 use crate::models::load_all_drives;
 use cosmic::Task;
-use storage_types::MountOptionsSettings;
+use storage_types::{MountOptionsSettings, mount_option_presets};
 
 use crate::app::Message;
 use crate::errors::ui::{UiErrorContext, log_error_and_show_dialog};
@@ -86,6 +86,7 @@ pub(super) fn open_edit_mount_options(
     };
 
     let identify_as_options = vec![device_path.clone()];
+    let lint_context = control.mount_lint_context;
 
     Task::perform(
         async move {
@@ -194,6 +195,8 @@ pub(super) fn open_edit_mount_options(
                 identify_as_options,
                 identify_as_index,
                 filesystem_type,
+                preset: None,
+                lint_context,
                 error,
                 running: false,
             })
@@ -268,6 +271,17 @@ pub(super) fn edit_mount_options_message(
         }
         EditMountOptionsMessage::OtherOptionsUpdate(v) => {
             state.other_options = v;
+            state.preset = None;
+            state.error = None;
+            Task::none()
+        }
+        EditMountOptionsMessage::PresetUpdate(preset_id) => {
+            if let Some(preset) = mount_option_presets(state.filesystem_type.trim())
+                .find(|preset| Some(preset.id) == preset_id.as_deref())
+            {
+                state.other_options = preset.apply(&state.other_options);
+            }
+            state.preset = preset_id;
             state.error = None;
             Task::none()
         }
@@ -6,12 +6,15 @@ use crate::app::Message;
 use crate::client::{FilesystemsClient, LuksClient, PartitionsClient};
 use crate::errors::ui::{UiErrorContext, log_error_and_show_dialog};
 use crate::fl;
-use crate::message::dialogs::{EditPartitionMessage, ResizePartitionMessage};
+use crate::message::dialogs::{EditPartitionMessage, MovePartitionMessage, ResizePartitionMessage};
+use crate::message::volumes::VolumesControlMessage;
 use crate::state::dialogs::{
-    EditPartitionDialog, EditPartitionStep, FormatPartitionDialog, FormatPartitionStep,
-    ResizePartitionDialog, ResizePartitionStep, ShowDialog,
+    ConfirmActionDialog, EditPartitionDialog, EditPartitionStep, FilesystemTarget,
+    FormatPartitionDialog, FormatPartitionStep, MovePartitionDialog, ResizePartitionDialog,
+    ResizePartitionStep, ShowDialog,
 };
 use crate::utils::DiskSegmentKind;
+use std::collections::HashMap;
 
 use storage_types::{CreatePartitionInfo, VolumeKind};
 
@@ -119,6 +122,92 @@ pub(super) fn delete(
     )
 }
 
+/// Look up the partitions deleted from the drive this session when free
+/// space is selected, so they can be offered for restoring
+pub(super) fn load_deleted_partitions(
+    control: &mut VolumesControl,
+) -> Task<cosmic::Action<Message>> {
+    let free_space_selected = control
+        .segments
+        .get(control.selected_segment)
+        .is_some_and(|segment| segment.kind == DiskSegmentKind::FreeSpace);
+    if !free_space_selected || control.partition_table_type.as_deref() != Some("gpt") {
+        return Task::none();
+    }
+
+    let device = control.device.clone();
+    Task::perform(
+        async move {
+            let result = match PartitionsClient::new().await {
+                Ok(client) => client
+                    .deleted_partitions(&device)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            (device, result)
+        },
+        |(device, result)| {
+            Message::VolumesMessage(VolumesControlMessage::DeletedPartitionsLoaded {
+                device,
+                result,
+            })
+            .into()
+        },
+    )
+}
+
+pub(super) fn deleted_partitions_loaded(
+    control: &mut VolumesControl,
+    device: String,
+    result: Result<Vec<storage_types::DeletedPartition>, String>,
+) -> Task<cosmic::Action<Message>> {
+    if device != control.device {
+        return Task::none();
+    }
+    control.deleted_partitions = result.unwrap_or_else(|e| {
+        tracing::warn!("Failed to list partitions deleted from {device}: {e}");
+        Vec::new()
+    });
+    Task::none()
+}
+
+/// Put the partition deleted as `id` back into the partition table
+pub(super) fn restore_deleted_partition(
+    control: &mut VolumesControl,
+    id: u64,
+) -> Task<cosmic::Action<Message>> {
+    if control.read_only {
+        return Task::none();
+    }
+    control
+        .deleted_partitions
+        .retain(|deleted| deleted.id != id);
+
+    Task::perform(
+        async move {
+            PartitionsClient::new()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to create partitions client: {}", e))?
+                .restore_deleted_partition(id)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to restore partition: {}", e))?;
+            load_all_drives().await.map_err(|e| e.into())
+        },
+        |result: Result<Vec<UiDrive>, anyhow::Error>| match result {
+            Ok(drives) => Message::UpdateNav(drives, None).into(),
+            Err(e) => {
+                tracing::error!(?e, "restore failed");
+                Message::Dialog(Box::new(ShowDialog::Info {
+                    title: fl!("restore-partition-failed"),
+                    body: format!("{e:#}"),
+                }))
+                .into()
+            }
+        },
+    )
+}
+
 pub(super) fn open_format_partition(
     control: &mut VolumesControl,
     dialog: &mut Option<ShowDialog>,
@@ -140,14 +229,19 @@ pub(super) fn open_format_partition(
         segment.table_type.clone()
     };
 
+    // Removable drives default to a filesystem other computers can use too
+    let current_type = volume.id_type.trim();
+    let suggested = storage_types::interop::suggested_filesystem(current_type, control.removable)
+        .filter(|fs_type| {
+            control
+                .filesystem_tools
+                .iter()
+                .any(|tool| tool.fs_type == *fs_type && tool.available)
+        });
     let selected_partition_type_index =
         crate::utils::partition_types::common_partition_type_index_for(
             &table_type,
-            if volume.id_type.trim().is_empty() {
-                None
-            } else {
-                Some(volume.id_type.as_str())
-            },
+            suggested.or((!current_type.is_empty()).then_some(current_type)),
         );
 
     let info = CreatePartitionInfo {
@@ -167,6 +261,9 @@ pub(super) fn open_format_partition(
         step: FormatPartitionStep::Basics,
         running: false,
         filesystem_tools: control.filesystem_tools.clone(),
+        format_preset: None,
+        fs_specific: HashMap::new(),
+        removable: control.removable,
     }));
 
     Task::none()
@@ -263,31 +360,242 @@ pub(super) fn open_resize_partition(
         .unwrap_or(0);
 
     let max_size_bytes = volume.size.saturating_add(right_free_bytes);
-    let min_size_bytes = volume
-        .usage
-        .as_ref()
-        .map(|u| u.used)
-        .unwrap_or(0)
-        .min(max_size_bytes);
+    let can_shrink = volume
+        .device_path
+        .as_deref()
+        .and_then(|device| control.features_for(device))
+        .is_none_or(|features| features.can_shrink);
+    let min_size_bytes = if can_shrink {
+        volume.usage.as_ref().map(|u| u.used).unwrap_or(0)
+    } else {
+        volume.size
+    }
+    .min(max_size_bytes);
 
     if max_size_bytes.saturating_sub(min_size_bytes) < 1024 {
         return Task::none();
     }
 
-    let new_size_bytes = volume.size.clamp(min_size_bytes, max_size_bytes);
+    let Some(device) = volume.device_path.clone() else {
+        return Task::none();
+    };
 
+    // Only growing is offered until the service says how far the
+    // filesystem can shrink
+    let current_size = volume.size.min(max_size_bytes);
     *dialog = Some(ShowDialog::ResizePartition(ResizePartitionDialog {
         volume,
         step: ResizePartitionStep::Sizing,
-        min_size_bytes,
+        min_size_bytes: current_size,
         max_size_bytes,
-        new_size_bytes,
+        new_size_bytes: current_size,
+        bounds: None,
+        loading_bounds: true,
+        running: false,
+    }));
+
+    Task::perform(
+        async move {
+            let partitions_client = PartitionsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create partitions client: {}", e))?;
+            partitions_client
+                .resize_bounds(&device)
+                .await
+                .map_err(|e| e.to_string())
+        },
+        |result| Message::from(ResizePartitionMessage::BoundsLoaded(result)).into(),
+    )
+}
+
+/// Offer to move a partition within the free space on either side of it
+pub(super) fn open_move_partition(
+    control: &mut VolumesControl,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    if dialog.is_some() {
+        return Task::none();
+    }
+
+    let Some(segment) = control.segments.get(control.selected_segment) else {
+        return Task::none();
+    };
+    let Some(volume) = segment.volume.clone() else {
+        return Task::none();
+    };
+    if volume.kind != VolumeKind::Partition {
+        return Task::none();
+    }
+    let Some(device) = volume.device_path.clone() else {
+        return Task::none();
+    };
+
+    *dialog = Some(ShowDialog::MovePartition(MovePartitionDialog {
+        volume,
+        bounds: None,
+        free_before: 0,
+    }));
+
+    Task::perform(
+        async move {
+            let partitions_client = PartitionsClient::new()
+                .await
+                .map_err(|e| format!("Failed to create partitions client: {}", e))?;
+            partitions_client
+                .move_bounds(&device)
+                .await
+                .map_err(|e| e.to_string())
+        },
+        |result| Message::from(MovePartitionMessage::BoundsLoaded(result)).into(),
+    )
+}
+
+/// Ask before moving a misaligned partition onto a 1 MiB boundary
+pub(super) fn open_realign_partition(
+    control: &mut VolumesControl,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    if dialog.is_some() {
+        return Task::none();
+    }
+
+    let Some(segment) = control.segments.get(control.selected_segment) else {
+        return Task::none();
+    };
+    let Some(volume) = segment.volume.clone() else {
+        return Task::none();
+    };
+    if volume.kind != VolumeKind::Partition {
+        return Task::none();
+    }
+
+    let confirm_target = volume.device_path.clone();
+    *dialog = Some(ShowDialog::ConfirmAction(ConfirmActionDialog {
+        title: fl!("realign-partition"),
+        body: fl!(
+            "realign-partition-warning",
+            sector = control.physical_sector_size
+        ),
+        target: FilesystemTarget::Volume(volume),
+        confirm_target,
+        ok_message: VolumesControlMessage::RealignPartitionConfirm.into(),
         running: false,
     }));
 
     Task::none()
 }
 
+pub(super) fn realign_partition_confirm(
+    _control: &mut VolumesControl,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    let Some(ShowDialog::ConfirmAction(state)) = dialog.as_mut() else {
+        return Task::none();
+    };
+    let FilesystemTarget::Volume(volume) = &state.target else {
+        return Task::none();
+    };
+    if state.running {
+        return Task::none();
+    }
+    state.running = true;
+
+    let device = volume.device_path.clone();
+    Task::perform(
+        async move {
+            let device = device.ok_or_else(|| anyhow::anyhow!("Partition has no device path"))?;
+            PartitionsClient::new()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to create partitions client: {}", e))?
+                .realign_partition(&device)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to realign partition: {}", e))?;
+            load_all_drives().await.map_err(|e| e.into())
+        },
+        |result: Result<Vec<UiDrive>, anyhow::Error>| match result {
+            Ok(drives) => Message::UpdateNav(drives, None).into(),
+            Err(e) => {
+                let ctx = UiErrorContext::new("realign_partition");
+                log_error_and_show_dialog(fl!("realign-partition"), e, ctx).into()
+            }
+        },
+    )
+}
+
+pub(super) fn move_partition_message(
+    _control: &mut VolumesControl,
+    msg: MovePartitionMessage,
+    dialog: &mut Option<ShowDialog>,
+) -> Task<cosmic::Action<Message>> {
+    let Some(ShowDialog::MovePartition(state)) = dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        MovePartitionMessage::FreeBeforeUpdate(free_before) => {
+            if let Some(bounds) = &state.bounds {
+                state.free_before = free_before.min(bounds.free_total());
+            }
+        }
+        MovePartitionMessage::BoundsLoaded(result) => match result {
+            // The dialog was reopened for another partition meanwhile
+            Ok(bounds) if state.volume.device_path.as_deref() != Some(&bounds.partition) => {}
+            Ok(bounds) => {
+                state.free_before = bounds.free_before();
+                state.bounds = Some(bounds);
+            }
+            Err(e) => {
+                let ctx = UiErrorContext::new("move_partition_bounds");
+                return Task::done(
+                    log_error_and_show_dialog(
+                        fl!("move-partition"),
+                        anyhow::anyhow!("Failed to get where the partition can move: {}", e),
+                        ctx,
+                    )
+                    .into(),
+                );
+            }
+        },
+        MovePartitionMessage::Cancel => {
+            return Task::done(Message::CloseDialog.into());
+        }
+        MovePartitionMessage::Confirm => {
+            let Some(bounds) = &state.bounds else {
+                return Task::none();
+            };
+            let new_start = bounds.start_after(state.free_before);
+            if new_start == bounds.start {
+                return Task::none();
+            }
+
+            let device = bounds.partition.clone();
+            // The running operations below the main view show how far it got
+            *dialog = None;
+
+            return Task::perform(
+                async move {
+                    PartitionsClient::new()
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to create partitions client: {}", e))?
+                        .move_partition(&device, new_start)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to move partition: {}", e))?;
+                    load_all_drives().await.map_err(|e| e.into())
+                },
+                |result: Result<Vec<UiDrive>, anyhow::Error>| match result {
+                    Ok(drives) => Message::UpdateNav(drives, None).into(),
+                    Err(e) => {
+                        let ctx = UiErrorContext::new("move_partition");
+                        log_error_and_show_dialog(fl!("move-partition"), e, ctx).into()
+                    }
+                },
+            );
+        }
+    }
+
+    Task::none()
+}
+
 pub(super) fn edit_partition_message(
     _control: &mut VolumesControl,
     msg: EditPartitionMessage,
@@ -434,6 +742,27 @@ pub(super) fn resize_partition_message(
         ResizePartitionMessage::SizeUpdate(size) => {
             state.new_size_bytes = size.clamp(state.min_size_bytes, state.max_size_bytes)
         }
+        ResizePartitionMessage::BoundsLoaded(result) => {
+            match result {
+                // The dialog was reopened for another partition meanwhile
+                Ok(bounds) if state.volume.device_path.as_deref() != Some(&bounds.partition) => {
+                    return Task::none();
+                }
+                Ok(bounds) => {
+                    state.min_size_bytes = bounds.min_bytes;
+                    state.max_size_bytes = bounds.max_bytes;
+                    state.new_size_bytes = state
+                        .new_size_bytes
+                        .clamp(bounds.min_bytes, bounds.max_bytes);
+                    state.bounds = Some(bounds);
+                    state.loading_bounds = false;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to get resize bounds, offering to grow only: {e}");
+                    state.loading_bounds = false;
+                }
+            }
+        }
         ResizePartitionMessage::Cancel => {
             return Task::done(Message::CloseDialog.into());
         }
@@ -443,13 +772,17 @@ pub(super) fn resize_partition_message(
             }
 
             // Disable when range is too small.
-            if state.max_size_bytes.saturating_sub(state.min_size_bytes) < 1024 {
+            if state.loading_bounds
+                || state.max_size_bytes.saturating_sub(state.min_size_bytes) < 1024
+            {
                 return Task::none();
             }
 
-            state.running = true;
             let volume = state.volume.clone();
             let new_size = state.new_size_bytes;
+            let with_filesystem = state.bounds.is_some();
+            // The running operations below the main view show how far it got
+            *dialog = None;
 
             return Task::perform(
                 async move {
@@ -460,10 +793,14 @@ pub(super) fn resize_partition_message(
                         .device_path
                         .as_ref()
                         .ok_or_else(|| anyhow::anyhow!("Volume has no device path"))?;
-                    partitions_client
-                        .resize_partition(device, new_size)
-                        .await
-                        .map_err(|e| anyhow::anyhow!("Failed to resize partition: {}", e))?;
+                    if with_filesystem {
+                        partitions_client
+                            .resize_partition_with_filesystem(device, new_size)
+                            .await
+                    } else {
+                        partitions_client.resize_partition(device, new_size).await
+                    }
+                    .map_err(|e| anyhow::anyhow!("Failed to resize partition: {}", e))?;
                     load_all_drives().await.map_err(|e| e.into())
                 },
                 |result: Result<Vec<UiDrive>, anyhow::Error>| match result {
@@ -50,6 +50,9 @@ fn maybe_initialize_btrfs_state_for_segment(
                     })),
                     Task::done(cosmic::Action::App(Message::BtrfsLoadUsage {
                         block_path,
+                        mount_point: mp.clone(),
+                    })),
+                    Task::done(cosmic::Action::App(Message::BtrfsLoadCompression {
                         mount_point: mp,
                     })),
                 ];
@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Bind mounts, read from the mount table and fstab
+//!
+//! Both are readable by every user, so the app lists bind mounts itself and
+//! only asks the storage service to create or remove them.
+
+use storage_types::{BindMount, parse_bind_mounts};
+
+/// Mounted bind mounts and those configured in fstab, sorted by target
+pub fn list_bind_mounts() -> std::io::Result<Vec<BindMount>> {
+    let mountinfo = std::fs::read_to_string("/proc/self/mountinfo")?;
+    let fstab = match std::fs::read_to_string("/etc/fstab") {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        fstab => fstab?,
+    };
+    Ok(parse_bind_mounts(&mountinfo, &fstab))
+}
@@ -1,6 +1,12 @@
+pub mod bind_mounts;
+pub mod mtp;
+pub mod notifications;
 pub mod partition_types;
+pub mod sandbox;
 mod segments;
+pub mod transaction;
 pub mod unit_size_input;
+pub mod user_mounts;
 
 // Explicit exports from unit_size_input module
 pub use unit_size_input::SizeUnit;
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Phones and cameras, through gvfs
+//!
+//! Like the user's FUSE mounts, MTP and PTP devices are mounted by the gvfs
+//! daemons of the user running the app, so they are listed, mounted and
+//! ejected with `gio` from the app rather than by the storage service.
+
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use storage_types::{MtpDevice, MtpStorage, parse_gio_filesystem_info, parse_gio_mount_list};
+use tokio::process::Command;
+
+/// Connected phones and cameras, with the storages of mounted ones
+pub async fn list_mtp_devices() -> Result<Vec<MtpDevice>, String> {
+    let output = gio(&["mount", "-li"]).await?;
+    let mut devices = parse_gio_mount_list(&output);
+
+    let gvfs_root = gvfs_root();
+    for device in devices.iter_mut().filter(|device| device.mounted) {
+        let Some(path) = gvfs_root.as_deref().and_then(|root| device.fuse_path(root)) else {
+            continue;
+        };
+        device.path = Some(path.clone());
+        let Ok(entries) = std::fs::read_dir(&path) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let (size, free) = match gio(&["info", "-f", &path.to_string_lossy()]).await {
+                Ok(info) => parse_gio_filesystem_info(&info),
+                Err(_) => (None, None),
+            };
+            device.storages.push(MtpStorage {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path,
+                size,
+                free,
+            });
+        }
+        device.storages.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    Ok(devices)
+}
+
+/// Mount a device, e.g. after the phone was set to file transfer
+pub async fn mount_mtp_device(uri: &str) -> Result<(), String> {
+    gio(&["mount", uri]).await.map(|_| ())
+}
+
+/// Flush pending writes and release the device so it can be unplugged
+pub async fn eject_mtp_device(uri: &str) -> Result<(), String> {
+    gio(&["mount", "-e", uri]).await.map(|_| ())
+}
+
+/// Where gvfs exposes its mounts for the user running the app
+fn gvfs_root() -> Option<PathBuf> {
+    let uid = std::fs::metadata("/proc/self").ok()?.uid();
+    let root = PathBuf::from(format!("/run/user/{uid}/gvfs"));
+    root.is_dir().then_some(root)
+}
+
+/// Run `gio` with `args`, returning its standard output
+async fn gio(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("gio")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gio: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("gio {} failed", args.join(" "))
+        } else {
+            stderr
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
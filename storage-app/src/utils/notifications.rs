@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Desktop notifications through `org.freedesktop.Notifications`
+
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use zbus::proxy;
+use zbus::zvariant::Value;
+
+/// Notification icon for storage alerts
+const ALERT_ICON: &str = "drive-harddisk-symbolic";
+
+/// Action invoked when the notification itself is clicked
+const DEFAULT_ACTION: &str = "default";
+
+#[proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: &HashMap<&str, &Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn notification_closed(&self, id: u32, reason: u32) -> zbus::Result<()>;
+}
+
+/// Show a desktop notification on the session bus
+pub async fn notify(summary: &str, body: &str) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let hints: HashMap<&str, Value<'_>> = HashMap::from([("urgency", Value::U8(2))]);
+
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                crate::fl!("app-title"),
+                0_u32,
+                ALERT_ICON,
+                summary,
+                body,
+                Vec::<&str>::new(),
+                hints,
+                -1_i32,
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Show a desktop notification offering `action`, and wait until it is
+/// clicked (true) or closed (false)
+pub async fn notify_clickable(summary: &str, body: &str, action: &str) -> zbus::Result<bool> {
+    let connection = zbus::Connection::session().await?;
+    let proxy = NotificationsProxy::new(&connection).await?;
+    // Listen first, a quick click could otherwise be missed
+    let mut actions = proxy.receive_action_invoked().await?;
+    let mut closed = proxy.receive_notification_closed().await?;
+
+    let urgency = Value::U8(1);
+    let hints = HashMap::from([("urgency", &urgency)]);
+    let id = proxy
+        .notify(
+            &crate::fl!("app-title"),
+            0,
+            ALERT_ICON,
+            summary,
+            body,
+            &[DEFAULT_ACTION, action],
+            &hints,
+            -1,
+        )
+        .await?;
+
+    loop {
+        tokio::select! {
+            Some(signal) = actions.next() => {
+                let args = signal.args()?;
+                if args.id == id {
+                    return Ok(args.action_key == DEFAULT_ACTION);
+                }
+            }
+            Some(signal) = closed.next() => {
+                if signal.args()?.id == id {
+                    return Ok(false);
+                }
+            }
+            else => return Ok(false),
+        }
+    }
+}
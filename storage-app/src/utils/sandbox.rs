@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Running inside a Flatpak sandbox
+//!
+//! The sandbox only reaches the system bus names its manifest allows, and
+//! files chosen in the portal file chooser arrive as document portal paths
+//! that the storage service, running on the host as root, cannot open.
+//! Without a portal backend the chooser fails outright, so exports fall
+//! back to a directory the app can always write.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use cosmic::dialog::file_chooser;
+
+use crate::app::APP_ID;
+use crate::client::{ClientError, SERVICE_NAME};
+use crate::fl;
+
+/// File the Flatpak runtime places in every sandbox
+const FLATPAK_INFO: &str = "/.flatpak-info";
+
+/// Whether the app runs inside a Flatpak sandbox
+pub fn is_flatpak() -> bool {
+    Path::new(FLATPAK_INFO).exists()
+}
+
+/// ID the sandbox runs under, which `flatpak override` expects
+fn flatpak_app_id() -> String {
+    std::env::var("FLATPAK_ID").unwrap_or_else(|_| APP_ID.to_string())
+}
+
+/// What to do when the storage service cannot be reached
+pub fn service_guidance(error: &ClientError) -> String {
+    match error {
+        ClientError::Connection(_) if is_flatpak() => fl!(
+            "sandbox-no-system-bus",
+            command = format!(
+                "flatpak override --user --system-talk-name={} {}",
+                SERVICE_NAME,
+                flatpak_app_id()
+            )
+        ),
+        ClientError::ServiceNotAvailable if is_flatpak() => {
+            fl!("sandbox-service-missing", service = SERVICE_NAME)
+        }
+        ClientError::ServiceNotAvailable => fl!("service-missing", service = SERVICE_NAME),
+        other => other.to_string(),
+    }
+}
+
+/// Document ID of a path in the document portal's FUSE mount, e.g. "a1b2c3d4"
+/// for "/run/user/1000/doc/a1b2c3d4/disk.img"
+fn document_id(path: &Path) -> Option<String> {
+    let documents = path.strip_prefix("/run/flatpak/doc").ok().or_else(|| {
+        let mut components = path.strip_prefix("/run/user").ok()?.components();
+        components.next()?;
+        components.as_path().strip_prefix("doc").ok()
+    })?;
+    let id = documents.components().next()?;
+    Some(id.as_os_str().to_string_lossy().into_owned())
+}
+
+/// Path on the host of a file chosen in the portal file chooser, for paths
+/// the storage service opens
+///
+/// Paths outside the document portal are returned unchanged; documents the
+/// portal cannot resolve too, so the service reports why it cannot open
+/// them.
+pub async fn host_path(path: String) -> String {
+    let Some(id) = document_id(Path::new(&path)) else {
+        return path;
+    };
+    let resolved = async {
+        let connection = zbus::Connection::session().await?;
+        let reply = connection
+            .call_method(
+                Some("org.freedesktop.portal.Documents"),
+                "/org/freedesktop/portal/documents",
+                Some("org.freedesktop.portal.Documents"),
+                "GetHostPaths",
+                &(vec![id.as_str()],),
+            )
+            .await?;
+        let mut paths: HashMap<String, Vec<u8>> = reply.body().deserialize()?;
+        zbus::Result::Ok(paths.remove(&id))
+    }
+    .await;
+
+    match resolved {
+        Ok(Some(bytes)) => {
+            let bytes = bytes.strip_suffix(&[0]).unwrap_or(&bytes);
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+        Ok(None) => path,
+        Err(e) => {
+            tracing::warn!(%e, path, "could not resolve document portal path");
+            path
+        }
+    }
+}
+
+/// Where exports go when no file chooser answers: the app's data directory
+/// inside the sandbox, the home directory otherwise
+fn fallback_dir() -> PathBuf {
+    let dir = if is_flatpak() {
+        std::env::var_os("XDG_DATA_HOME")
+    } else {
+        std::env::var_os("HOME")
+    };
+    dir.map(PathBuf::from).unwrap_or_else(std::env::temp_dir)
+}
+
+/// Ask where to save an export, suggesting `name`
+///
+/// None when the user cancels. When the file chooser itself fails, e.g.
+/// without a portal backend, the export is saved under `name` in a
+/// directory the app can write without it.
+pub async fn choose_save_path(title: String, name: &str) -> Option<PathBuf> {
+    let dialog = file_chooser::save::Dialog::new()
+        .title(title)
+        .current_name(name.to_string());
+    match dialog.save_file().await {
+        Ok(response) => response.url().and_then(|url| url.to_file_path().ok()),
+        Err(file_chooser::Error::Cancelled) => None,
+        Err(err) => {
+            let path = fallback_dir().join(name);
+            tracing::warn!(
+                ?err,
+                path = %path.display(),
+                "save file dialog failed, saving to the fallback path"
+            );
+            Some(path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_ids_come_from_portal_paths() {
+        assert_eq!(
+            document_id(Path::new("/run/user/1000/doc/a1b2c3d4/disk.img")),
+            Some("a1b2c3d4".to_string())
+        );
+        assert_eq!(
+            document_id(Path::new("/run/flatpak/doc/a1b2c3d4/disk.img")),
+            Some("a1b2c3d4".to_string())
+        );
+        assert_eq!(document_id(Path::new("/home/user/disk.img")), None);
+        assert_eq!(document_id(Path::new("/run/user/1000/doc")), None);
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Multi-step operations that undo their completed steps on failure
+//!
+//! Each [`Transaction::step`] runs an operation and, once it succeeded,
+//! remembers how to undo it. [`Transaction::finish`] keeps the changes when
+//! the whole operation succeeded, and otherwise undoes the completed steps
+//! in reverse order, so that a failure half-way does not leave a drive in a
+//! state the user did not ask for.
+
+use std::fmt::Display;
+
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+
+struct Undo<E> {
+    description: String,
+    future: BoxFuture<'static, Result<(), E>>,
+}
+
+/// Completed steps of an operation and how to undo them
+pub struct Transaction<E> {
+    completed: Vec<Undo<E>>,
+}
+
+impl<E> Default for Transaction<E> {
+    fn default() -> Self {
+        Self {
+            completed: Vec::new(),
+        }
+    }
+}
+
+impl<E: Display> Transaction<E> {
+    /// Run `operation`, and remember the undo that `undo` builds from its
+    /// output when it succeeds
+    pub async fn step<T, U>(
+        &mut self,
+        description: impl Into<String>,
+        operation: impl Future<Output = Result<T, E>>,
+        undo: impl FnOnce(&T) -> U,
+    ) -> Result<T, E>
+    where
+        U: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        let output = operation.await?;
+        self.completed.push(Undo {
+            description: description.into(),
+            future: undo(&output).boxed(),
+        });
+        Ok(output)
+    }
+
+    /// Keep the changes of a successful operation, or undo its completed
+    /// steps when it failed
+    ///
+    /// The error of the operation is returned either way; failing undos are
+    /// only logged, as there is nothing left to fall back to.
+    pub async fn finish<T>(self, result: Result<T, E>) -> Result<T, E> {
+        if result.is_err() {
+            for undo in self.completed.into_iter().rev() {
+                tracing::info!(step = %undo.description, "undoing");
+                if let Err(e) = undo.future.await {
+                    tracing::error!(%e, step = %undo.description, "failed to undo");
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A step that records its undo in `undone`
+    fn undo(
+        undone: &Arc<Mutex<Vec<u32>>>,
+        step: u32,
+    ) -> impl FnOnce(&u32) -> BoxFuture<'static, Result<(), String>> {
+        let undone = undone.clone();
+        move |_| {
+            async move {
+                undone.lock().unwrap().push(step);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn failures_undo_completed_steps_in_reverse() {
+        let undone = Arc::new(Mutex::new(Vec::new()));
+        let mut transaction = Transaction::default();
+
+        let result = async {
+            transaction
+                .step("first", async { Ok(1) }, undo(&undone, 1))
+                .await?;
+            transaction
+                .step("second", async { Ok(2) }, undo(&undone, 2))
+                .await?;
+            transaction
+                .step("third", async { Err("full".to_string()) }, undo(&undone, 3))
+                .await
+        }
+        .await;
+
+        assert_eq!(transaction.finish(result).await, Err("full".to_string()));
+        assert_eq!(*undone.lock().unwrap(), vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn successes_keep_every_step() {
+        let undone = Arc::new(Mutex::new(Vec::new()));
+        let mut transaction = Transaction::default();
+
+        let result = transaction
+            .step("only", async { Ok(7) }, undo(&undone, 1))
+            .await;
+
+        assert_eq!(transaction.finish(result).await, Ok(7));
+        assert!(undone.lock().unwrap().is_empty());
+    }
+}
@@ -2,13 +2,14 @@
 
 //! Unit-aware size input component for partition size inputs
 //!
-//! Provides conversions between bytes and human-readable units (KB, MB, GB, TB)
-//! and helpers for rendering size input widgets with unit selectors.
+//! Provides conversions between bytes and human-readable units and helpers
+//! for rendering size input widgets with unit selectors. Units follow the
+//! preferred [`ByteUnits`]: powers of 1024 (KiB, MiB, ...) or of 1000 (kB,
+//! MB, ...).
 
 use std::fmt;
 
-/// Cached unit labels for dropdown to avoid repeated allocations
-static UNIT_LABELS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+use storage_types::{ByteUnits, byte_format};
 
 /// Size units for displaying and inputting partition sizes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,7 +21,17 @@ pub enum SizeUnit {
     Terabytes,
 }
 
+/// Units the inputs currently count in
+fn units() -> ByteUnits {
+    byte_format().units
+}
+
 impl SizeUnit {
+    /// Bytes in one of this unit
+    fn factor(self) -> f64 {
+        units().base().powi(self.to_index() as i32)
+    }
+
     /// Convert a value in this unit to bytes
     pub fn to_bytes(self, value: f64) -> u64 {
         // Validate input: require finite value
@@ -29,13 +40,7 @@ impl SizeUnit {
         }
 
         // Compute the value in bytes as f64
-        let bytes = match self {
-            SizeUnit::Bytes => value,
-            SizeUnit::Kilobytes => value * 1024.0,
-            SizeUnit::Megabytes => value * 1024.0 * 1024.0,
-            SizeUnit::Gigabytes => value * 1024.0 * 1024.0 * 1024.0,
-            SizeUnit::Terabytes => value * 1024.0 * 1024.0 * 1024.0 * 1024.0,
-        };
+        let bytes = value * self.factor();
 
         // Clamp to non-negative range
         if bytes <= 0.0 {
@@ -55,29 +60,17 @@ impl SizeUnit {
     /// Convert bytes to a value in this unit
     #[allow(clippy::wrong_self_convention)]
     pub fn from_bytes(&self, bytes: u64) -> f64 {
-        match self {
-            SizeUnit::Bytes => bytes as f64,
-            SizeUnit::Kilobytes => bytes as f64 / 1024.0,
-            SizeUnit::Megabytes => bytes as f64 / (1024.0 * 1024.0),
-            SizeUnit::Gigabytes => bytes as f64 / (1024.0 * 1024.0 * 1024.0),
-            SizeUnit::Terabytes => bytes as f64 / (1024.0 * 1024.0 * 1024.0 * 1024.0),
-        }
+        bytes as f64 / self.factor()
     }
 
     /// Get the display label for this unit
     pub fn label(&self) -> &'static str {
-        match self {
-            SizeUnit::Bytes => "B",
-            SizeUnit::Kilobytes => "KB",
-            SizeUnit::Megabytes => "MB",
-            SizeUnit::Gigabytes => "GB",
-            SizeUnit::Terabytes => "TB",
-        }
+        Self::labels()[self.to_index()]
     }
 
     /// Get all unit labels as a static slice
     pub fn labels() -> &'static [&'static str] {
-        UNIT_LABELS
+        &units().labels()[..=SizeUnit::Terabytes.to_index()]
     }
 
     /// Create a unit from a dropdown index
@@ -105,17 +98,16 @@ impl SizeUnit {
 
     /// Pick an appropriate default unit for a given size in bytes
     pub fn auto_select(bytes: u64) -> Self {
-        if bytes < 1024 {
-            SizeUnit::Bytes
-        } else if bytes < 1024 * 1024 {
-            SizeUnit::Kilobytes
-        } else if bytes < 1024 * 1024 * 1024 {
-            SizeUnit::Megabytes
-        } else if bytes < 1024u64 * 1024 * 1024 * 1024 {
-            SizeUnit::Gigabytes
-        } else {
-            SizeUnit::Terabytes
-        }
+        [
+            SizeUnit::Kilobytes,
+            SizeUnit::Megabytes,
+            SizeUnit::Gigabytes,
+            SizeUnit::Terabytes,
+        ]
+        .into_iter()
+        .take_while(|unit| bytes as f64 >= unit.factor())
+        .last()
+        .unwrap_or(SizeUnit::Bytes)
     }
 }
 
@@ -207,9 +199,9 @@ mod tests {
         let labels = SizeUnit::labels();
         assert_eq!(labels.len(), 5);
         assert_eq!(labels[0], "B");
-        assert_eq!(labels[1], "KB");
-        assert_eq!(labels[2], "MB");
-        assert_eq!(labels[3], "GB");
-        assert_eq!(labels[4], "TB");
+        assert_eq!(labels[1], "KiB");
+        assert_eq!(labels[2], "MiB");
+        assert_eq!(labels[3], "GiB");
+        assert_eq!(labels[4], "TiB");
     }
 }
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! FUSE and gvfs mounts of the user running the app
+//!
+//! These are read and unmounted by the app itself rather than the storage
+//! service: FUSE mounts are closed to other users, root included, and
+//! `fusermount` and `gio` only let the owner unmount them.
+
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use storage_types::{UserMount, UserMountKind, parse_user_mounts};
+use tokio::process::Command;
+
+/// The user's FUSE mounts and gvfs shares, sorted by name
+///
+/// Phones and cameras are left out: they have their own sidebar section.
+pub fn list_user_mounts() -> std::io::Result<Vec<UserMount>> {
+    // /proc/self belongs to the user the process runs as
+    let uid = std::fs::metadata("/proc/self")?.uid();
+    let table = parse_user_mounts(&std::fs::read_to_string("/proc/self/mountinfo")?, uid);
+
+    let mut mounts = table.mounts;
+    if let Some(root) = table.gvfs_root
+        && let Ok(entries) = std::fs::read_dir(&root)
+    {
+        mounts.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.starts_with("mtp:") && !name.starts_with("gphoto2:"))
+                .map(|name| UserMount::gvfs_share(&root, &name)),
+        );
+    }
+    mounts.sort_by_cached_key(|mount| mount.display_name().to_lowercase());
+    Ok(mounts)
+}
+
+/// Unmount one of the user's mounts
+pub async fn unmount_user_mount(mount: &UserMount) -> Result<(), String> {
+    let path = &mount.mount_point;
+    let result = match mount.kind {
+        UserMountKind::Gvfs => run("gio", &["mount", "-u"], path).await,
+        UserMountKind::Fuse => match run("fusermount3", &["-u"], path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                run("fusermount", &["-u"], path).await
+            }
+            result => result,
+        },
+    };
+    result.map_err(|e| format!("Failed to unmount {}: {e}", path.display()))?
+}
+
+/// Run `program` with `args` on `path`; the outer error is a failure to
+/// start it
+async fn run(program: &str, args: &[&str], path: &Path) -> std::io::Result<Result<(), String>> {
+    let output = Command::new(program).args(args).arg(path).output().await?;
+    if output.status.success() {
+        return Ok(Ok(()));
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Ok(Err(if stderr.is_empty() {
+        format!("{program} failed to unmount {}", path.display())
+    } else {
+        stderr
+    }))
+}
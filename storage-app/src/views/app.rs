@@ -4,8 +4,10 @@ use crate::message::app::Message;
 use crate::message::network::NetworkMessage;
 use crate::message::volumes::VolumesControlMessage;
 use crate::models::{UiDrive, UiVolume};
+use crate::notification_policy::usage_category_label;
 use crate::state::app::{AppModel, ContextPage};
-use crate::state::dialogs::{DeletePartitionDialog, ShowDialog};
+use crate::state::capacity::CapacityState;
+use crate::state::dialogs::{ConfirmationGuard, DeletePartitionDialog, ShowDialog};
 use crate::state::volumes::{DetailTab, Segment, VolumesControl};
 use crate::utils::DiskSegmentKind;
 use crate::views::btrfs::btrfs_management_section;
@@ -22,7 +24,11 @@ use cosmic::iced::alignment::{Alignment, Horizontal, Vertical};
 use cosmic::iced::mouse;
 use cosmic::widget::{self, Space, icon, text_input};
 use cosmic::{Apply, Element, iced_widget};
-use storage_types::{UsageCategory, VolumeInfo, VolumeKind, bytes_to_pretty};
+use storage_types::inspect::INSPECTABLE_TYPES;
+use storage_types::{
+    AllowedActions, CapacityForecast, ForcedReadOnly, SelinuxStatus, UsageCategory, VolumeInfo,
+    VolumeKind, bytes_to_pretty, is_esp,
+};
 
 /// Custom button style for header tabs with accent color background.
 fn tab_button_class(active: bool) -> cosmic::theme::Button {
@@ -72,6 +78,41 @@ pub(crate) fn header_start(_app: &AppModel) -> Vec<Element<'_, Message>> {
 /// Elements to pack at the end of the header bar.
 pub(crate) fn header_end(_app: &AppModel) -> Vec<Element<'_, Message>> {
     vec![
+        widget::tooltip(
+            widget::button::icon(icon::from_name("document-save-symbolic"))
+                .on_press(Message::ExportReport),
+            widget::text(fl!("report-export")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+        widget::tooltip(
+            widget::button::icon(icon::from_name("text-x-generic-symbolic"))
+                .on_press(Message::ToggleContextPage(ContextPage::Logs)),
+            widget::text(fl!("logs")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+        widget::tooltip(
+            widget::button::icon(icon::from_name("emblem-ok-symbolic"))
+                .on_press(Message::ToggleContextPage(ContextPage::HealthCheck)),
+            widget::text(fl!("check-all-drives")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+        widget::tooltip(
+            widget::button::icon(icon::from_name("folder-symbolic"))
+                .on_press(Message::ToggleContextPage(ContextPage::Vaults)),
+            widget::text(fl!("legacy-vaults")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+        widget::tooltip(
+            widget::button::icon(icon::from_name("utilities-system-monitor-symbolic"))
+                .on_press(Message::ToggleContextPage(ContextPage::Statistics)),
+            widget::text(fl!("statistics")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
         widget::button::icon(icon::from_name("preferences-system-symbolic"))
             .on_press(Message::ToggleContextPage(ContextPage::Settings))
             .into(),
@@ -156,11 +197,13 @@ pub(crate) fn dialog(app: &AppModel) -> Option<Element<'_, Message>> {
             | crate::state::dialogs::ShowDialog::ChangePassphrase(_)
             | crate::state::dialogs::ShowDialog::EditEncryptionOptions(_)
             | crate::state::dialogs::ShowDialog::FormatDisk(_)
+            | crate::state::dialogs::ShowDialog::SetUpDrive(_)
             | crate::state::dialogs::ShowDialog::NewDiskImage(_)
             | crate::state::dialogs::ShowDialog::AttachDiskImage(_)
             | crate::state::dialogs::ShowDialog::ImageOperation(_)
             | crate::state::dialogs::ShowDialog::BtrfsCreateSubvolume(_)
-            | crate::state::dialogs::ShowDialog::BtrfsCreateSnapshot(_) => None,
+            | crate::state::dialogs::ShowDialog::BtrfsCreateSnapshot(_)
+            | crate::state::dialogs::ShowDialog::NewBindMount(_) => None,
 
             crate::state::dialogs::ShowDialog::DeletePartition(state) => {
                 Some(dialogs::confirmation(
@@ -169,6 +212,7 @@ pub(crate) fn dialog(app: &AppModel) -> Option<Element<'_, Message>> {
                     VolumesControlMessage::Delete.into(),
                     Some(Message::CloseDialog),
                     state.running,
+                    app.confirmation.as_ref(),
                 ))
             }
 
@@ -182,6 +226,7 @@ pub(crate) fn dialog(app: &AppModel) -> Option<Element<'_, Message>> {
                 state.ok_message.clone(),
                 Some(Message::CloseDialog),
                 state.running,
+                app.confirmation.as_ref(),
             )),
 
             crate::state::dialogs::ShowDialog::TakeOwnership(state) => {
@@ -192,10 +237,56 @@ pub(crate) fn dialog(app: &AppModel) -> Option<Element<'_, Message>> {
                 Some(dialogs::unlock_encrypted(state.clone()))
             }
 
-            crate::state::dialogs::ShowDialog::SmartData(state) => {
-                Some(dialogs::smart_data(state.clone()))
+            crate::state::dialogs::ShowDialog::SmartData(state) => Some(dialogs::smart_data(
+                state.clone(),
+                app.config.temperature_unit,
+            )),
+
+            crate::state::dialogs::ShowDialog::LostPartitions(state) => {
+                Some(dialogs::lost_partitions(state.clone()))
+            }
+
+            crate::state::dialogs::ShowDialog::GptEntries(state) => Some(dialogs::gpt_entries(
+                state.clone(),
+                app.confirmation.as_ref(),
+            )),
+
+            crate::state::dialogs::ShowDialog::Defragment(state) => {
+                Some(dialogs::defragment(state.clone()))
+            }
+
+            crate::state::dialogs::ShowDialog::EncryptedFolders(state) => {
+                Some(dialogs::encrypted_folders(state.clone()))
+            }
+
+            crate::state::dialogs::ShowDialog::EspSync(state) => {
+                Some(dialogs::esp_sync(state.clone(), app.confirmation.as_ref()))
+            }
+
+            crate::state::dialogs::ShowDialog::LowSpace(state) => {
+                Some(dialogs::low_space(state.clone()))
             }
 
+            crate::state::dialogs::ShowDialog::Automount(state) => {
+                Some(dialogs::automount(state.clone()))
+            }
+
+            crate::state::dialogs::ShowDialog::Performance(state) => {
+                Some(dialogs::performance(state.clone()))
+            }
+
+            crate::state::dialogs::ShowDialog::SectorViewer(state) => {
+                Some(dialogs::sector_viewer(state.clone()))
+            }
+
+            crate::state::dialogs::ShowDialog::Inspect(state) => {
+                Some(dialogs::inspect(state.clone()))
+            }
+
+            crate::state::dialogs::ShowDialog::MovePartition(state) => Some(
+                dialogs::move_partition(state.clone(), app.confirmation.as_ref()),
+            ),
+
             crate::state::dialogs::ShowDialog::UnmountBusy(state) => {
                 Some(dialogs::unmount_busy(state.clone()))
             }
@@ -204,6 +295,22 @@ pub(crate) fn dialog(app: &AppModel) -> Option<Element<'_, Message>> {
                 Some(dialogs::info(title, body, Message::CloseDialog))
             }
 
+            crate::state::dialogs::ShowDialog::Error {
+                title,
+                body,
+                failure,
+                recovery,
+            } => Some(dialogs::error(
+                title.clone(),
+                body.clone(),
+                failure.clone(),
+                recovery.clone(),
+            )),
+
+            crate::state::dialogs::ShowDialog::Diagnostics(state) => {
+                Some(dialogs::diagnostics(state.clone()))
+            }
+
             crate::state::dialogs::ShowDialog::ConfirmDeleteRemote { name, scope } => {
                 let body = format!(
                     "Are you sure you want to delete the remote '{}'? This action cannot be undone.",
@@ -218,32 +325,64 @@ pub(crate) fn dialog(app: &AppModel) -> Option<Element<'_, Message>> {
                     }),
                     Some(Message::CloseDialog),
                     false,
+                    app.confirmation.as_ref(),
                 ))
             }
+
+            crate::state::dialogs::ShowDialog::RcloneConfigPassword(state) => {
+                Some(dialogs::rclone_config_password(state.clone()))
+            }
         },
-        None => None,
+        None => btrfs_properties_dialog(app),
     }
 }
 
-fn full_page_wizard_view(dialog: &ShowDialog) -> Option<Element<'_, Message>> {
+/// Properties dialog for the subvolume selected in the BTRFS tab
+fn btrfs_properties_dialog(app: &AppModel) -> Option<Element<'_, Message>> {
+    let btrfs_state = app
+        .nav
+        .active_data::<VolumesControl>()?
+        .btrfs_state
+        .as_ref()?;
+    if !btrfs_state.show_properties_dialog {
+        return None;
+    }
+    let mount_point = btrfs_state.mount_point.as_deref()?;
+    let subvolume = btrfs_state.selected_subvolume.as_ref()?;
+
+    Some(dialogs::subvolume_properties(
+        mount_point,
+        subvolume,
+        btrfs_state,
+    ))
+}
+
+fn full_page_wizard_view<'a>(
+    dialog: &'a ShowDialog,
+    guard: Option<&'a ConfirmationGuard>,
+) -> Option<Element<'a, Message>> {
     match dialog {
         ShowDialog::AddPartition(state) => Some(dialogs::create_partition(state.clone())),
-        ShowDialog::FormatPartition(state) => Some(dialogs::format_partition(state.clone())),
+        ShowDialog::FormatPartition(state) => Some(dialogs::format_partition(state.clone(), guard)),
         ShowDialog::EditPartition(state) => Some(dialogs::edit_partition(state.clone())),
-        ShowDialog::ResizePartition(state) => Some(dialogs::resize_partition(state.clone())),
+        ShowDialog::ResizePartition(state) => Some(dialogs::resize_partition(state.clone(), guard)),
         ShowDialog::EditMountOptions(state) => Some(dialogs::edit_mount_options(state.clone())),
         ShowDialog::ChangePassphrase(state) => Some(dialogs::change_passphrase(state.clone())),
         ShowDialog::EditEncryptionOptions(state) => {
             Some(dialogs::edit_encryption_options(state.clone()))
         }
-        ShowDialog::FormatDisk(state) => Some(dialogs::format_disk(state.clone())),
+        ShowDialog::FormatDisk(state) => Some(dialogs::format_disk(state.clone(), guard)),
+        ShowDialog::SetUpDrive(state) => Some(dialogs::set_up_drive(state.clone(), guard)),
         ShowDialog::NewDiskImage(state) => Some(dialogs::new_disk_image(state.as_ref().clone())),
         ShowDialog::AttachDiskImage(state) => {
             Some(dialogs::attach_disk_image(state.as_ref().clone()))
         }
-        ShowDialog::ImageOperation(state) => Some(dialogs::image_operation(state.as_ref().clone())),
+        ShowDialog::ImageOperation(state) => {
+            Some(dialogs::image_operation(state.as_ref().clone(), guard))
+        }
         ShowDialog::BtrfsCreateSubvolume(state) => Some(dialogs::create_subvolume(state.clone())),
         ShowDialog::BtrfsCreateSnapshot(state) => Some(dialogs::create_snapshot(state.clone())),
+        ShowDialog::NewBindMount(state) => Some(dialogs::new_bind_mount(state.clone())),
         _ => None,
     }
 }
@@ -256,14 +395,24 @@ pub(crate) fn nav_bar(app: &AppModel) -> Option<Element<'_, cosmic::Action<Messa
 
     let controls_enabled = app.dialog.is_none();
 
-    let mut nav = sidebar::sidebar(&app.nav, &app.sidebar, &app.network, controls_enabled)
-        .map(Into::into)
-        .apply(widget::container)
-        .padding(8)
-        .class(cosmic::style::Container::Background)
-        // Both width and height must be Shrink for flex layout to respect the max_width constraint
-        .width(cosmic::iced::Length::Shrink)
-        .height(cosmic::iced::Length::Shrink);
+    let mut nav = sidebar::sidebar(
+        &app.nav,
+        &app.sidebar,
+        &app.network,
+        &app.user_mounts,
+        &app.bind_mounts,
+        &app.mtp,
+        &app.physical_devices,
+        app.config.temperature_unit,
+        controls_enabled,
+    )
+    .map(Into::into)
+    .apply(widget::container)
+    .padding(8)
+    .class(cosmic::style::Container::Background)
+    // Both width and height must be Shrink for flex layout to respect the max_width constraint
+    .width(cosmic::iced::Length::Shrink)
+    .height(cosmic::iced::Length::Shrink);
 
     if !app.core.is_condensed() {
         nav = nav.max_width(280);
@@ -287,18 +436,44 @@ pub(crate) fn context_drawer(
 
     Some(match app.context_page {
         ContextPage::Settings => cosmic_context_drawer::context_drawer(
-            settings(&app.config),
+            settings(
+                &app.config,
+                app.safety_snapshot_policy.as_ref(),
+                app.operation_priorities.as_ref(),
+                app.power_policies.as_ref(),
+                &app.deferred_jobs,
+            ),
             Message::ToggleContextPage(ContextPage::Settings),
         )
         .footer(settings_footer(&app.filesystem_tools))
         .title(fl!("settings")),
+        ContextPage::Logs => cosmic_context_drawer::context_drawer(
+            crate::views::logs::logs(&app.logs),
+            Message::ToggleContextPage(ContextPage::Logs),
+        )
+        .title(fl!("logs")),
+        ContextPage::Statistics => cosmic_context_drawer::context_drawer(
+            crate::views::statistics::statistics(&app.statistics),
+            Message::ToggleContextPage(ContextPage::Statistics),
+        )
+        .title(fl!("statistics")),
+        ContextPage::HealthCheck => cosmic_context_drawer::context_drawer(
+            crate::views::health_check::health_check(&app.health_check),
+            Message::ToggleContextPage(ContextPage::HealthCheck),
+        )
+        .title(fl!("check-all-drives")),
+        ContextPage::Vaults => cosmic_context_drawer::context_drawer(
+            crate::views::vaults::vaults(&app.vaults),
+            Message::ToggleContextPage(ContextPage::Vaults),
+        )
+        .title(fl!("legacy-vaults")),
     })
 }
 
 /// Describes the interface based on the current state of the application model.
 pub(crate) fn view(app: &AppModel) -> Element<'_, Message> {
     if let Some(active_dialog) = app.dialog.as_ref()
-        && let Some(wizard_view) = full_page_wizard_view(active_dialog)
+        && let Some(wizard_view) = full_page_wizard_view(active_dialog, app.confirmation.as_ref())
     {
         return widget::container(wizard_view)
             .padding(20)
@@ -310,6 +485,7 @@ pub(crate) fn view(app: &AppModel) -> Element<'_, Message> {
     if app.network.wizard.is_some()
         || app.network.editor.is_some()
         || app.network.selected.is_some()
+        || app.network.transfer_form.is_some()
     {
         let controls_enabled = app.dialog.is_none();
         return network_main_view(&app.network, controls_enabled).map(Message::Network);
@@ -343,11 +519,27 @@ pub(crate) fn view(app: &AppModel) -> Element<'_, Message> {
                     .into();
             }
 
+            let optical_media = app.optical.media.get(drive.device());
             let Some(segment) = volumes_control
                 .segments
                 .get(volumes_control.selected_segment)
                 .or_else(|| volumes_control.segments.first())
             else {
+                // Empty and blank discs have no volumes but can still be burned
+                if drive.disk.optical {
+                    return widget::container(disk_header::disk_header(
+                        drive,
+                        0,
+                        &volumes_control.segments,
+                        &volumes_control.volumes,
+                        optical_media,
+                        app.hypervisor.as_deref(),
+                        &app.allowed_actions,
+                    ))
+                    .padding(20)
+                    .width(Length::Fill)
+                    .into();
+                }
                 return widget::text::title1(fl!("no-volumes"))
                     .apply(widget::container)
                     .width(Length::Fill)
@@ -396,7 +588,10 @@ pub(crate) fn view(app: &AppModel) -> Element<'_, Message> {
                     drive,
                     used,
                     &volumes_control.segments,
-                    &volumes_control.volumes
+                    &volumes_control.volumes,
+                    optical_media,
+                    app.hypervisor.as_deref(),
+                    &app.allowed_actions,
                 ),
                 Space::new(0, 10),
                 volumes_control.view(),
@@ -405,8 +600,35 @@ pub(crate) fn view(app: &AppModel) -> Element<'_, Message> {
             .width(Length::Fill);
 
             // Bottom section: Volume-specific detail view (2/3 of height)
-            let bottom_section =
-                volume_detail_view(volumes_control, segment, &app.filesystem_tools);
+            let mut bottom_section = volume_detail_view(
+                volumes_control,
+                segment,
+                &app.filesystem_tools,
+                &app.capacity,
+                &app.allowed_actions,
+            );
+
+            // Warn when the kernel made the selected filesystem read-only
+            let selected_volume = volumes_control
+                .selected_volume_node()
+                .map(|node| &node.volume)
+                .or(segment.volume.as_ref());
+            if let Some(filesystem) = selected_volume.and_then(|v| app.read_only.for_volume(v)) {
+                bottom_section =
+                    iced_widget::column![forced_read_only_callout(filesystem), bottom_section]
+                        .spacing(10)
+                        .into();
+            }
+
+            // SELinux label of the selected filesystem, with a warning when
+            // it needs relabeling
+            if let Some(status) = selected_volume.and_then(|v| app.selinux.for_volume(v)) {
+                let relabeling = app.selinux.relabeling.contains(&status.mount_point);
+                bottom_section =
+                    iced_widget::column![selinux_label(status, relabeling), bottom_section]
+                        .spacing(10)
+                        .into();
+            }
 
             // Full layout wrapped in a single scrollable
             widget::scrollable(
@@ -428,11 +650,111 @@ pub(crate) fn view(app: &AppModel) -> Element<'_, Message> {
     }
 }
 
+/// Warning for a filesystem the kernel made read-only after an error, with
+/// the error and the ways to recover
+fn forced_read_only_callout<'a>(filesystem: &ForcedReadOnly) -> Element<'a, Message> {
+    let title = widget::text(fl!(
+        "filesystem-read-only",
+        mount_point = filesystem.mount_point.as_str()
+    ))
+    .size(14.0)
+    .font(cosmic::iced::font::Font {
+        weight: cosmic::iced::font::Weight::Semibold,
+        ..Default::default()
+    });
+
+    let mut content = widget::column()
+        .push(
+            iced_widget::row![icon::from_name("dialog-warning-symbolic").size(16), title]
+                .spacing(8)
+                .align_y(Alignment::Center),
+        )
+        .push(widget::text::caption(fl!(
+            "filesystem-read-only-description"
+        )))
+        .spacing(6);
+    if let Some(error) = &filesystem.kernel_error {
+        content = content.push(widget::text::caption(error.clone()));
+    }
+    content = content.push(
+        iced_widget::row![
+            widget::button::standard(fl!("repair-and-remount"))
+                .on_press(Message::RepairForcedReadOnly(filesystem.clone())),
+            widget::button::standard(fl!("remount-read-write"))
+                .on_press(Message::RemountReadWrite(filesystem.clone())),
+        ]
+        .spacing(8),
+    );
+
+    widget::container(content)
+        .padding(12)
+        .width(Length::Fill)
+        .style(crate::controls::status::warning_style)
+        .into()
+}
+
+/// SELinux context of a mounted filesystem, or a warning with a relabel
+/// action when it is unlabeled or labeled otherwise than the policy says
+fn selinux_label<'a>(status: &SelinuxStatus, relabeling: bool) -> Element<'a, Message> {
+    let context = status
+        .mount_context
+        .as_ref()
+        .or(status.current.as_ref())
+        .cloned()
+        .unwrap_or_else(|| fl!("selinux-unlabeled"));
+    if !status.needs_relabel() {
+        return widget::text::caption(fl!("selinux-context", context = context)).into();
+    }
+
+    let title = widget::text(fl!(
+        "selinux-needs-relabel",
+        mount_point = status.mount_point.as_str()
+    ))
+    .size(14.0)
+    .font(cosmic::iced::font::Font {
+        weight: cosmic::iced::font::Weight::Semibold,
+        ..Default::default()
+    });
+
+    let mut content = widget::column()
+        .push(
+            iced_widget::row![icon::from_name("dialog-warning-symbolic").size(16), title]
+                .spacing(8)
+                .align_y(Alignment::Center),
+        )
+        .push(widget::text::caption(fl!(
+            "selinux-needs-relabel-description"
+        )))
+        .push(widget::text::caption(fl!(
+            "selinux-context",
+            context = context
+        )))
+        .spacing(6);
+    if let Some(default) = &status.default {
+        content = content.push(widget::text::caption(fl!(
+            "selinux-default-context",
+            context = default.as_str()
+        )));
+    }
+    content = content.push(
+        widget::button::standard(fl!("selinux-relabel"))
+            .on_press_maybe((!relabeling).then(|| Message::Relabel(status.mount_point.clone()))),
+    );
+
+    widget::container(content)
+        .padding(12)
+        .width(Length::Fill)
+        .style(crate::controls::status::warning_style)
+        .into()
+}
+
 /// Renders the volume detail view for the selected volume with action buttons.
 fn volume_detail_view<'a>(
     volumes_control: &'a VolumesControl,
     segment: &'a Segment,
     filesystem_tools: &'a [storage_types::FilesystemToolInfo],
+    capacity: &'a CapacityState,
+    allowed: &AllowedActions,
 ) -> Element<'a, Message> {
     if segment.kind == DiskSegmentKind::Reserved {
         return widget::container(widget::Row::from_vec(vec![]))
@@ -472,11 +794,29 @@ fn volume_detail_view<'a>(
     } else {
         // Volume Info tab (default)
         if let Some(v) = selected_volume_node {
-            build_volume_node_info(v, volumes_control, segment, selected_volume)
+            build_volume_node_info(
+                v,
+                volumes_control,
+                segment,
+                capacity.for_volume(&v.volume),
+                allowed,
+            )
         } else if let Some(ref p) = segment.volume {
-            build_partition_info(p, selected_volume, volumes_control, segment)
+            build_partition_info(
+                p,
+                selected_volume,
+                volumes_control,
+                segment,
+                capacity.for_volume(p),
+                allowed,
+            )
         } else {
-            build_free_space_info(segment, filesystem_tools)
+            build_free_space_info(
+                segment,
+                filesystem_tools,
+                &volumes_control.deleted_partitions,
+                !volumes_control.read_only && allowed.allows("partition-modify"),
+            )
         }
     };
 
@@ -534,21 +874,6 @@ fn usage_category_icon(category: UsageCategory) -> &'static str {
     }
 }
 
-fn usage_category_label(category: UsageCategory) -> String {
-    match category {
-        UsageCategory::Documents => fl!("usage-category-documents"),
-        UsageCategory::Images => fl!("usage-category-images"),
-        UsageCategory::Audio => fl!("usage-category-audio"),
-        UsageCategory::Video => fl!("usage-category-video"),
-        UsageCategory::Archives => fl!("usage-category-archives"),
-        UsageCategory::Code => fl!("usage-category-code"),
-        UsageCategory::Binaries => fl!("usage-category-binaries"),
-        UsageCategory::Packages => fl!("usage-category-packages"),
-        UsageCategory::System => fl!("usage-category-system"),
-        UsageCategory::Other => fl!("usage-category-other"),
-    }
-}
-
 fn usage_tab_view<'a>(volumes_control: &'a VolumesControl) -> Element<'a, Message> {
     let usage_state = &volumes_control.usage_state;
 
@@ -1057,12 +1382,26 @@ fn usage_row_selection_message(
     }
 }
 
+/// Add an action button, unless the user's restriction profiles leave
+/// out `action`
+pub(super) fn push_allowed<'a>(
+    buttons: &mut Vec<Element<'a, Message>>,
+    allowed: &AllowedActions,
+    action: &str,
+    button: Element<'a, Message>,
+) {
+    if allowed.allows(action) {
+        buttons.push(button);
+    }
+}
+
 /// Build info display for a volume (child filesystem/LV) - mirrors disk header layout
 fn build_volume_node_info<'a>(
     v: &'a UiVolume,
-    _volumes_control: &'a VolumesControl,
+    volumes_control: &'a VolumesControl,
     _segment: &'a Segment,
-    _selected_volume: Option<&'a UiVolume>,
+    forecast: Option<&CapacityForecast>,
+    allowed: &AllowedActions,
 ) -> Element<'a, Message> {
     use crate::controls::usage_pie;
 
@@ -1140,14 +1479,23 @@ fn build_volume_node_info<'a>(
             .spacing(4)
             .width(Length::Fill)
     };
+    let text_column = match forecast.and_then(capacity_caption) {
+        Some(caption) => text_column.push(caption),
+        None => text_column,
+    };
 
     // Action buttons underneath
     let mut action_buttons = Vec::new();
+    // Write-protected media only offers actions that leave it unchanged
+    let writable = !volumes_control.read_only;
 
     // Mount/Unmount
     if v.has_filesystem {
         if v.is_mounted() {
-            action_buttons.push(
+            push_allowed(
+                &mut action_buttons,
+                allowed,
+                "filesystem-mount",
                 widget::tooltip(
                     widget::button::icon(icon::from_name("media-playback-stop-symbolic")).on_press(
                         Message::VolumesMessage(VolumesControlMessage::ChildUnmount(
@@ -1160,7 +1508,10 @@ fn build_volume_node_info<'a>(
                 .into(),
             );
         } else {
-            action_buttons.push(
+            push_allowed(
+                &mut action_buttons,
+                allowed,
+                "filesystem-mount",
                 widget::tooltip(
                     widget::button::icon(icon::from_name("media-playback-start-symbolic"))
                         .on_press(Message::VolumesMessage(VolumesControlMessage::ChildMount(
@@ -1176,10 +1527,15 @@ fn build_volume_node_info<'a>(
 
     // Format (for filesystems, not containers)
     if v.kind == VolumeKind::Filesystem && v.has_filesystem {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-format",
             widget::tooltip(
-                widget::button::icon(icon::from_name("edit-clear-symbolic")).on_press(
-                    Message::VolumesMessage(VolumesControlMessage::OpenFormatPartition),
+                widget::button::icon(icon::from_name("edit-clear-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenFormatPartition,
+                    )),
                 ),
                 widget::text(fl!("format")),
                 widget::tooltip::Position::Bottom,
@@ -1190,10 +1546,15 @@ fn build_volume_node_info<'a>(
 
     // Label (for filesystems with filesystem type)
     if v.kind == VolumeKind::Filesystem && v.has_filesystem {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
             widget::tooltip(
-                widget::button::icon(icon::from_name("tag-symbolic")).on_press(
-                    Message::VolumesMessage(VolumesControlMessage::OpenEditFilesystemLabel),
+                widget::button::icon(icon::from_name("tag-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenEditFilesystemLabel,
+                    )),
                 ),
                 widget::text(fl!("label")),
                 widget::tooltip::Position::Bottom,
@@ -1204,7 +1565,10 @@ fn build_volume_node_info<'a>(
 
     // Check Filesystem (if mounted)
     if v.is_mounted() {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
             widget::tooltip(
                 widget::button::icon(icon::from_name("dialog-question-symbolic")).on_press(
                     Message::VolumesMessage(VolumesControlMessage::OpenCheckFilesystem),
@@ -1218,10 +1582,15 @@ fn build_volume_node_info<'a>(
 
     // Repair Filesystem (if has filesystem)
     if v.has_filesystem {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
             widget::tooltip(
-                widget::button::icon(icon::from_name("emblem-system-symbolic")).on_press(
-                    Message::VolumesMessage(VolumesControlMessage::OpenRepairFilesystem),
+                widget::button::icon(icon::from_name("emblem-system-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenRepairFilesystem,
+                    )),
                 ),
                 widget::text(fl!("repair")),
                 widget::tooltip::Position::Bottom,
@@ -1230,12 +1599,110 @@ fn build_volume_node_info<'a>(
         );
     }
 
+    // Defragment (if mounted and supported)
+    if v.is_mounted() && crate::update::volumes::helpers::supports_defrag(&v.id_type) {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("view-sort-ascending-symbolic"))
+                    .on_press_maybe(writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenDefragment,
+                    ))),
+                widget::text(fl!("defragment")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Encrypted folders (if mounted ext4/f2fs)
+    if v.is_mounted() && storage_types::fscrypt_capable(&v.id_type) {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-mount",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("dialog-password-symbolic")).on_press(
+                    Message::VolumesMessage(VolumesControlMessage::OpenEncryptedFolders),
+                ),
+                widget::text(fl!("encrypted-folders")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Raw sectors, read-only
+    if let Some(device) = v.device_path.clone() {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "disk-inspect",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("text-x-generic-symbolic")).on_press(
+                    Message::ViewSectors {
+                        device,
+                        size: v.size,
+                    },
+                ),
+                widget::text(fl!("view-sectors")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Decoded superblock or LUKS header
+    if let Some(device) = v.device_path.clone()
+        && INSPECTABLE_TYPES.contains(&v.id_type.as_str())
+    {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "disk-inspect",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("dialog-information-symbolic")).on_press(
+                    Message::InspectVolume {
+                        device,
+                        size: v.size,
+                    },
+                ),
+                widget::text(fl!("inspect-volume")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Low space alert (if mounted)
+    if v.is_mounted() {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("dialog-warning-symbolic"))
+                    .on_press(Message::LowSpaceAlerts),
+                widget::text(fl!("low-space")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Take Ownership (if mounted)
     if v.is_mounted() {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystems-take-ownership",
             widget::tooltip(
-                widget::button::icon(icon::from_name("system-users-symbolic")).on_press(
-                    Message::VolumesMessage(VolumesControlMessage::OpenTakeOwnership),
+                widget::button::icon(icon::from_name("system-users-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenTakeOwnership,
+                    )),
                 ),
                 widget::text(fl!("take-ownership")),
                 widget::tooltip::Position::Bottom,
@@ -1246,7 +1713,10 @@ fn build_volume_node_info<'a>(
 
     // Edit Mount Options (if has filesystem)
     if v.has_filesystem {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-mount",
             widget::tooltip(
                 widget::button::icon(icon::from_name("emblem-documents-symbolic")).on_press(
                     Message::VolumesMessage(VolumesControlMessage::OpenEditMountOptions),
@@ -1258,8 +1728,27 @@ fn build_volume_node_info<'a>(
         );
     }
 
+    // Automount through systemd units (if has filesystem)
+    if v.has_filesystem {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-mount",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("appointment-soon-symbolic"))
+                    .on_press(Message::Automount),
+                widget::text(fl!("automount")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Create image from partition (backup via image client)
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-backup",
         widget::tooltip(
             widget::button::icon(icon::from_name("document-save-as-symbolic"))
                 .on_press(Message::CreateDiskFromPartition),
@@ -1270,21 +1759,44 @@ fn build_volume_node_info<'a>(
     );
 
     // Restore image to partition (restore via image client)
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-restore",
         widget::tooltip(
             widget::button::icon(icon::from_name("document-revert-symbolic"))
-                .on_press(Message::RestoreImageToPartition),
+                .on_press_maybe(writable.then_some(Message::RestoreImageToPartition)),
             widget::text(fl!("restore-image")),
             widget::tooltip::Position::Bottom,
         )
         .into(),
     );
 
-    let info_and_actions = iced_widget::column![
+    // Copy partition onto another one (clone via image client)
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-restore",
+        widget::tooltip(
+            widget::button::icon(icon::from_name("edit-copy-symbolic"))
+                .on_press(Message::CopyPartition),
+            widget::text(fl!("copy-partition")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+    );
+
+    let mut info_and_actions = iced_widget::column![
         text_column,
         widget::Row::from_vec(action_buttons).spacing(4)
     ]
     .spacing(8);
+    if v.volume.has_filesystem
+        && let Some(device) = v.volume.device_path.as_deref()
+    {
+        info_and_actions =
+            info_and_actions.push(filesystem_features_section(volumes_control, device));
+    }
 
     // Row layout: info_and_actions | pie_chart (aligned right, shrink to fit)
     iced_widget::Row::new()
@@ -1300,12 +1812,121 @@ fn build_volume_node_info<'a>(
         .into()
 }
 
+/// Collapsible "Advanced" section listing probed filesystem features
+fn filesystem_features_section<'a>(
+    volumes_control: &'a VolumesControl,
+    device: &str,
+) -> Element<'a, Message> {
+    let expanded = volumes_control.show_filesystem_features;
+    let expander_icon = if expanded {
+        "go-down-symbolic"
+    } else {
+        "go-next-symbolic"
+    };
+
+    let header = widget::button::custom(
+        iced_widget::row![
+            icon::from_name(expander_icon).size(16),
+            widget::text::caption(fl!("filesystem-advanced")),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center),
+    )
+    .class(cosmic::theme::Button::Text)
+    .padding(2)
+    .on_press(Message::VolumesMessage(
+        VolumesControlMessage::ToggleFilesystemFeatures,
+    ));
+
+    if !expanded {
+        return header.into();
+    }
+
+    let yes_no = |value: bool| {
+        if value {
+            fl!("filesystem-feature-yes")
+        } else {
+            fl!("filesystem-feature-no")
+        }
+    };
+
+    let body: Element<'a, Message> = match &volumes_control.filesystem_features {
+        Some((features_device, Ok(features))) if features_device == device => {
+            let features_list = if features.features.is_empty() {
+                fl!("filesystem-feature-none")
+            } else {
+                features.features.join(", ")
+            };
+            let compression = features
+                .compression
+                .clone()
+                .unwrap_or_else(|| fl!("filesystem-feature-none"));
+
+            iced_widget::column![
+                widget::text::caption(format!("{}: {}", fl!("filesystem-features"), features_list)),
+                widget::text::caption(format!(
+                    "{}: {}",
+                    fl!("filesystem-reflink"),
+                    yes_no(features.reflink)
+                )),
+                widget::text::caption(format!(
+                    "{}: {}",
+                    fl!("filesystem-metadata-checksums"),
+                    yes_no(features.metadata_checksums)
+                )),
+                widget::text::caption(format!(
+                    "{}: {}",
+                    fl!("filesystem-compression"),
+                    compression
+                )),
+                widget::text::caption(format!(
+                    "{}: {}",
+                    fl!("filesystem-can-shrink"),
+                    yes_no(features.can_shrink)
+                )),
+                widget::text::caption(format!(
+                    "{}: {}",
+                    fl!("filesystem-can-grow-online"),
+                    yes_no(features.can_grow_online)
+                )),
+            ]
+            .spacing(2)
+            .into()
+        }
+        Some((features_device, Err(e))) if features_device == device => {
+            widget::text::caption(format!("{}: {}", fl!("filesystem-features-unavailable"), e))
+                .into()
+        }
+        _ => widget::text::caption(fl!("working")).into(),
+    };
+
+    iced_widget::column![header, widget::container(body).padding([0, 0, 0, 20])]
+        .spacing(4)
+        .into()
+}
+
 /// Build info display for a partition - mirrors disk header layout
+/// When the filesystem of `forecast` runs full at its current growth, if it
+/// grows at all
+fn capacity_caption<'a>(forecast: &CapacityForecast) -> Option<Element<'a, Message>> {
+    let days = forecast.days_until_full?;
+    Some(
+        widget::text::caption(fl!(
+            "capacity-full-in",
+            days = days,
+            growth = bytes_to_pretty(&(forecast.growth_per_day as u64), false)
+        ))
+        .into(),
+    )
+}
+
 fn build_partition_info<'a>(
     v: &'a VolumeInfo,
     volume_node: Option<&'a UiVolume>,
     volumes_control: &'a VolumesControl,
     segment: &'a Segment,
+    forecast: Option<&CapacityForecast>,
+    allowed: &AllowedActions,
 ) -> Element<'a, Message> {
     use crate::controls::usage_pie;
 
@@ -1409,16 +2030,25 @@ fn build_partition_info<'a>(
             .spacing(4)
             .width(Length::Fill)
     };
+    let text_column = match forecast.and_then(capacity_caption) {
+        Some(caption) => text_column.push(caption),
+        None => text_column,
+    };
 
     // Action buttons underneath
     let mut action_buttons = Vec::new();
+    // Write-protected media only offers actions that leave it unchanged
+    let writable = !volumes_control.read_only;
 
     // Lock/Unlock for LUKS containers
     if let Some(v) = volume_node
         && v.volume.kind == VolumeKind::CryptoContainer
     {
         if v.volume.locked {
-            action_buttons.push(
+            push_allowed(
+                &mut action_buttons,
+                allowed,
+                "luks-unlock",
                 widget::tooltip(
                     widget::button::icon(icon::from_name("changes-allow-symbolic")).on_press(
                         Message::Dialog(Box::new(ShowDialog::UnlockEncrypted(
@@ -1437,7 +2067,10 @@ fn build_partition_info<'a>(
                 .into(),
             );
         } else {
-            action_buttons.push(
+            push_allowed(
+                &mut action_buttons,
+                allowed,
+                "luks-lock",
                 widget::tooltip(
                     widget::button::icon(icon::from_name("changes-prevent-symbolic")).on_press(
                         Message::VolumesMessage(VolumesControlMessage::LockContainer),
@@ -1451,11 +2084,15 @@ fn build_partition_info<'a>(
 
         // Change Passphrase (only for unlocked containers)
         if !v.volume.locked {
-            action_buttons.push(
+            push_allowed(
+                &mut action_buttons,
+                allowed,
+                "luks-modify",
                 widget::tooltip(
-                    widget::button::icon(icon::from_name("document-properties-symbolic")).on_press(
-                        Message::VolumesMessage(VolumesControlMessage::OpenChangePassphrase),
-                    ),
+                    widget::button::icon(icon::from_name("document-properties-symbolic"))
+                        .on_press_maybe(writable.then_some(Message::VolumesMessage(
+                            VolumesControlMessage::OpenChangePassphrase,
+                        ))),
                     widget::text(fl!("change-passphrase")),
                     widget::tooltip::Position::Bottom,
                 )
@@ -1464,7 +2101,10 @@ fn build_partition_info<'a>(
         }
 
         // Edit Encryption Options
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "luks-set-options",
             widget::tooltip(
                 widget::button::icon(icon::from_name("preferences-system-symbolic")).on_press(
                     Message::VolumesMessage(VolumesControlMessage::OpenEditEncryptionOptions),
@@ -1479,7 +2119,10 @@ fn build_partition_info<'a>(
     // Mount/Unmount
     if p.has_filesystem {
         if p.is_mounted() {
-            action_buttons.push(
+            push_allowed(
+                &mut action_buttons,
+                allowed,
+                "filesystem-mount",
                 widget::tooltip(
                     widget::button::icon(icon::from_name("media-playback-stop-symbolic"))
                         .on_press(Message::VolumesMessage(VolumesControlMessage::Unmount)),
@@ -1489,7 +2132,10 @@ fn build_partition_info<'a>(
                 .into(),
             );
         } else {
-            action_buttons.push(
+            push_allowed(
+                &mut action_buttons,
+                allowed,
+                "filesystem-mount",
                 widget::tooltip(
                     widget::button::icon(icon::from_name("media-playback-start-symbolic"))
                         .on_press(Message::VolumesMessage(VolumesControlMessage::Mount)),
@@ -1502,10 +2148,15 @@ fn build_partition_info<'a>(
     }
 
     // Format
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "filesystem-format",
         widget::tooltip(
-            widget::button::icon(icon::from_name("edit-clear-all-symbolic")).on_press(
-                Message::VolumesMessage(VolumesControlMessage::OpenFormatPartition),
+            widget::button::icon(icon::from_name("edit-clear-all-symbolic")).on_press_maybe(
+                writable.then_some(Message::VolumesMessage(
+                    VolumesControlMessage::OpenFormatPartition,
+                )),
             ),
             widget::text(fl!("format")),
             widget::tooltip::Position::Bottom,
@@ -1514,10 +2165,15 @@ fn build_partition_info<'a>(
     );
 
     // Edit and Resize
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-modify",
         widget::tooltip(
-            widget::button::icon(icon::from_name("edit-symbolic")).on_press(
-                Message::VolumesMessage(VolumesControlMessage::OpenEditPartition),
+            widget::button::icon(icon::from_name("edit-symbolic")).on_press_maybe(
+                writable.then_some(Message::VolumesMessage(
+                    VolumesControlMessage::OpenEditPartition,
+                )),
             ),
             widget::text(fl!("edit")),
             widget::tooltip::Position::Bottom,
@@ -1533,14 +2189,27 @@ fn build_partition_info<'a>(
         .map(|s| s.size)
         .unwrap_or(0);
     let max_size = p.size.saturating_add(right_free_bytes);
-    let min_size = p.usage.as_ref().map(|u| u.used).unwrap_or(0).min(max_size);
+    let can_shrink = volumes_control
+        .features_for(&p.device)
+        .is_none_or(|features| features.can_shrink);
+    let min_size = if can_shrink {
+        p.usage.as_ref().map(|u| u.used).unwrap_or(0)
+    } else {
+        p.size
+    }
+    .min(max_size);
     let resize_enabled = max_size.saturating_sub(min_size) >= 1024;
 
     if resize_enabled {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "partition-modify",
             widget::tooltip(
-                widget::button::icon(icon::from_name("view-fullscreen-symbolic")).on_press(
-                    Message::VolumesMessage(VolumesControlMessage::OpenResizePartition),
+                widget::button::icon(icon::from_name("view-fullscreen-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenResizePartition,
+                    )),
                 ),
                 widget::text(fl!("resize")),
                 widget::tooltip::Position::Bottom,
@@ -1549,11 +2218,58 @@ fn build_partition_info<'a>(
         );
     }
 
+    // Move a partition that has free space next to it
+    let left_free = volumes_control
+        .selected_segment
+        .checked_sub(1)
+        .and_then(|index| volumes_control.segments.get(index))
+        .is_some_and(|s| s.kind == DiskSegmentKind::FreeSpace);
+    if left_free || right_free_bytes > 0 {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "partition-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("go-jump-symbolic")).on_press_maybe(
+                    (writable && !p.is_mounted()).then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenMovePartition,
+                    )),
+                ),
+                widget::text(fl!("move-partition")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Realign a partition that does not start on a physical sector
+    if !storage_types::alignment::is_aligned(p.offset, volumes_control.physical_sector_size) {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "partition-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("format-justify-left-symbolic"))
+                    .on_press_maybe((writable && !p.is_mounted()).then_some(
+                        Message::VolumesMessage(VolumesControlMessage::OpenRealignPartition),
+                    )),
+                widget::text(fl!("realign-partition")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Label
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "filesystem-modify",
         widget::tooltip(
-            widget::button::icon(icon::from_name("tag-symbolic")).on_press(
-                Message::VolumesMessage(VolumesControlMessage::OpenEditFilesystemLabel),
+            widget::button::icon(icon::from_name("tag-symbolic")).on_press_maybe(
+                writable.then_some(Message::VolumesMessage(
+                    VolumesControlMessage::OpenEditFilesystemLabel,
+                )),
             ),
             widget::text(fl!("label")),
             widget::tooltip::Position::Bottom,
@@ -1563,7 +2279,10 @@ fn build_partition_info<'a>(
 
     // Check Filesystem (if mounted)
     if p.can_mount() && p.is_mounted() {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
             widget::tooltip(
                 widget::button::icon(icon::from_name("dialog-question-symbolic")).on_press(
                     Message::VolumesMessage(VolumesControlMessage::OpenCheckFilesystem),
@@ -1577,10 +2296,15 @@ fn build_partition_info<'a>(
 
     // Repair Filesystem (if filesystem type)
     if p.has_filesystem {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
             widget::tooltip(
-                widget::button::icon(icon::from_name("emblem-system-symbolic")).on_press(
-                    Message::VolumesMessage(VolumesControlMessage::OpenRepairFilesystem),
+                widget::button::icon(icon::from_name("emblem-system-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenRepairFilesystem,
+                    )),
                 ),
                 widget::text(fl!("repair")),
                 widget::tooltip::Position::Bottom,
@@ -1589,12 +2313,116 @@ fn build_partition_info<'a>(
         );
     }
 
+    // Defragment (if mounted and supported)
+    if p.can_mount()
+        && p.is_mounted()
+        && p.filesystem_type
+            .as_deref()
+            .is_some_and(crate::update::volumes::helpers::supports_defrag)
+    {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("view-sort-ascending-symbolic"))
+                    .on_press_maybe(writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenDefragment,
+                    ))),
+                widget::text(fl!("defragment")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Encrypted folders (if mounted ext4/f2fs)
+    if p.can_mount()
+        && p.is_mounted()
+        && p.filesystem_type
+            .as_deref()
+            .is_some_and(storage_types::fscrypt_capable)
+    {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-mount",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("dialog-password-symbolic")).on_press(
+                    Message::VolumesMessage(VolumesControlMessage::OpenEncryptedFolders),
+                ),
+                widget::text(fl!("encrypted-folders")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Raw sectors, read-only
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "disk-inspect",
+        widget::tooltip(
+            widget::button::icon(icon::from_name("text-x-generic-symbolic")).on_press(
+                Message::ViewSectors {
+                    device: p.device.clone(),
+                    size: p.size,
+                },
+            ),
+            widget::text(fl!("view-sectors")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+    );
+
+    // Decoded superblock or LUKS header
+    if INSPECTABLE_TYPES.contains(&v.id_type.as_str()) {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "disk-inspect",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("dialog-information-symbolic")).on_press(
+                    Message::InspectVolume {
+                        device: p.device.clone(),
+                        size: p.size,
+                    },
+                ),
+                widget::text(fl!("inspect-volume")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Low space alert (if mounted)
+    if p.can_mount() && p.is_mounted() {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("dialog-warning-symbolic"))
+                    .on_press(Message::LowSpaceAlerts),
+                widget::text(fl!("low-space")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Take Ownership (if mounted)
     if p.can_mount() && p.is_mounted() {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystems-take-ownership",
             widget::tooltip(
-                widget::button::icon(icon::from_name("system-users-symbolic")).on_press(
-                    Message::VolumesMessage(VolumesControlMessage::OpenTakeOwnership),
+                widget::button::icon(icon::from_name("system-users-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::OpenTakeOwnership,
+                    )),
                 ),
                 widget::text(fl!("take-ownership")),
                 widget::tooltip::Position::Bottom,
@@ -1605,7 +2433,10 @@ fn build_partition_info<'a>(
 
     // Edit Mount Options (if filesystem)
     if p.has_filesystem {
-        action_buttons.push(
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-mount",
             widget::tooltip(
                 widget::button::icon(icon::from_name("emblem-documents-symbolic")).on_press(
                     Message::VolumesMessage(VolumesControlMessage::OpenEditMountOptions),
@@ -1617,8 +2448,27 @@ fn build_partition_info<'a>(
         );
     }
 
+    // Automount through systemd units (if filesystem)
+    if p.has_filesystem {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "filesystem-mount",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("appointment-soon-symbolic"))
+                    .on_press(Message::Automount),
+                widget::text(fl!("automount")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Create image from partition (backup via image client)
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-backup",
         widget::tooltip(
             widget::button::icon(icon::from_name("document-save-as-symbolic"))
                 .on_press(Message::CreateDiskFromPartition),
@@ -1629,26 +2479,64 @@ fn build_partition_info<'a>(
     );
 
     // Restore image to partition (restore via image client)
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-restore",
         widget::tooltip(
             widget::button::icon(icon::from_name("document-revert-symbolic"))
-                .on_press(Message::RestoreImageToPartition),
+                .on_press_maybe(writable.then_some(Message::RestoreImageToPartition)),
             widget::text(fl!("restore-image")),
             widget::tooltip::Position::Bottom,
         )
         .into(),
     );
 
+    // Copy partition onto another one (clone via image client)
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-restore",
+        widget::tooltip(
+            widget::button::icon(icon::from_name("edit-copy-symbolic"))
+                .on_press(Message::CopyPartition),
+            widget::text(fl!("copy-partition")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+    );
+
+    // Keep a secondary EFI System Partition in sync with this one
+    if is_esp(&p.type_id) {
+        push_allowed(
+            &mut action_buttons,
+            allowed,
+            "partition-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("emblem-synchronizing-symbolic"))
+                    .on_press(Message::SyncEsp),
+                widget::text(fl!("esp-sync")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Delete
-    action_buttons.push(
+    push_allowed(
+        &mut action_buttons,
+        allowed,
+        "partition-modify",
         widget::tooltip(
-            widget::button::icon(icon::from_name("edit-delete-symbolic")).on_press(
-                Message::Dialog(Box::new(ShowDialog::DeletePartition(
-                    DeletePartitionDialog {
-                        name: segment.name.clone(),
-                        running: false,
-                    },
-                ))),
+            widget::button::icon(icon::from_name("edit-delete-symbolic")).on_press_maybe(
+                writable.then(|| {
+                    Message::Dialog(Box::new(ShowDialog::DeletePartition(
+                        DeletePartitionDialog {
+                            name: segment.name.clone(),
+                            running: false,
+                        },
+                    )))
+                }),
             ),
             widget::text(fl!("delete-partition")),
             widget::tooltip::Position::Bottom,
@@ -1656,11 +2544,15 @@ fn build_partition_info<'a>(
         .into(),
     );
 
-    let info_and_actions = iced_widget::column![
+    let mut info_and_actions = iced_widget::column![
         text_column,
         widget::Row::from_vec(action_buttons).spacing(4)
     ]
     .spacing(8);
+    if p.has_filesystem {
+        info_and_actions =
+            info_and_actions.push(filesystem_features_section(volumes_control, &p.device));
+    }
 
     // Row layout: info_and_actions | pie_chart (aligned right, shrink to fit)
     iced_widget::Row::new()
@@ -1680,6 +2572,8 @@ fn build_partition_info<'a>(
 fn build_free_space_info<'a>(
     segment: &'a Segment,
     filesystem_tools: &'a [storage_types::FilesystemToolInfo],
+    deleted_partitions: &'a [storage_types::DeletedPartition],
+    writable: bool,
 ) -> Element<'a, Message> {
     use crate::controls::usage_pie;
 
@@ -1719,24 +2613,63 @@ fn build_free_space_info<'a>(
     // Action button for creating a partition in free space
     let filesystem_tools_clone = filesystem_tools.to_vec();
     let add_partition_button = widget::tooltip(
-        widget::button::icon(icon::from_name("list-add-symbolic")).on_press(Message::Dialog(
-            Box::new(ShowDialog::AddPartition(
-                crate::state::dialogs::CreatePartitionDialog {
-                    info: segment.get_create_info(),
-                    step: crate::state::dialogs::CreatePartitionStep::Basics,
-                    running: false,
-                    error: None,
-                    filesystem_tools: filesystem_tools_clone,
-                },
-            )),
+        widget::button::icon(icon::from_name("list-add-symbolic")).on_press_maybe(writable.then(
+            || {
+                Message::Dialog(Box::new(ShowDialog::AddPartition(
+                    crate::state::dialogs::CreatePartitionDialog {
+                        info: segment.get_create_info(),
+                        step: crate::state::dialogs::CreatePartitionStep::Basics,
+                        running: false,
+                        error: None,
+                        filesystem_tools: filesystem_tools_clone,
+                    },
+                )))
+            },
         )),
         widget::text(fl!("create-partition")),
         widget::tooltip::Position::Bottom,
     );
 
+    let mut action_buttons: Vec<Element<'a, Message>> = vec![add_partition_button.into()];
+
+    // Partitions deleted this session whose space this is, put back as
+    // they were
+    let segment_end = segment.offset + segment.size;
+    for deleted in deleted_partitions.iter().filter(|deleted| {
+        let range = deleted.range();
+        range.start >= segment.offset && range.end <= segment_end
+    }) {
+        let number = deleted.entry.number;
+        let name = if deleted.entry.name.is_empty() {
+            fl!("partition-number", number = number)
+        } else {
+            fl!(
+                "partition-number-with-name",
+                number = number,
+                name = deleted.entry.name.clone()
+            )
+        };
+        action_buttons.push(
+            widget::tooltip(
+                widget::button::icon(icon::from_name("edit-undo-symbolic")).on_press_maybe(
+                    writable.then_some(Message::VolumesMessage(
+                        VolumesControlMessage::RestoreDeletedPartition(deleted.id),
+                    )),
+                ),
+                widget::text(fl!(
+                    "restore-deleted-partition",
+                    name = name,
+                    size = bytes_to_pretty(&deleted.range().size(), false)
+                )),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     let info_and_actions = iced_widget::column![
         text_column,
-        widget::Row::with_children(vec![add_partition_button.into()]).spacing(4)
+        widget::Row::with_children(action_buttons).spacing(4)
     ]
     .spacing(8);
 
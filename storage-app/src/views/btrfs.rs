@@ -7,7 +7,7 @@ use cosmic::iced::Length;
 use cosmic::widget;
 use cosmic::{Element, iced_widget};
 use std::collections::HashMap;
-use storage_types::{BtrfsSubvolume, VolumeInfo};
+use storage_types::{BTRFS_COMPRESSION_ALGORITHMS, BtrfsSubvolume, VolumeInfo, bytes_to_pretty};
 
 /// Helper to get expander icon name
 fn expander_icon(expanded: bool) -> &'static str {
@@ -162,12 +162,192 @@ pub fn btrfs_management_section<'a>(
         }
     }
 
+    // === Device Errors Section ===
+    if let Some(mount_point) = mount_point {
+        content_items.push(device_stats_section(mount_point, state));
+    }
+
+    // === Compression Section ===
+    if let Some(mount_point) = mount_point {
+        content_items.push(compression_section(mount_point, state));
+    }
+
     // Spacing at end
     content_items.push(widget::vertical_space().height(8).into());
 
     iced_widget::column(content_items).spacing(8).into()
 }
 
+/// Error counters of each device, which btrfs keeps across mounts
+fn device_stats_section<'a>(mount_point: &'a str, state: &'a BtrfsState) -> Element<'a, Message> {
+    let mut items: Vec<Element<'a, Message>> = vec![
+        widget::text(fl!("btrfs-device-errors"))
+            .font(cosmic::iced::font::Font {
+                weight: cosmic::iced::font::Weight::Semibold,
+                ..Default::default()
+            })
+            .into(),
+    ];
+
+    match &state.device_stats {
+        Some(Ok(stats)) => {
+            for device in stats {
+                items.push(
+                    widget::text::caption(fl!(
+                        "btrfs-device-errors-counts",
+                        device = device.device.as_str(),
+                        write = device.write_io_errs,
+                        read = device.read_io_errs,
+                        flush = device.flush_io_errs,
+                        corruption = device.corruption_errs,
+                        generation = device.generation_errs
+                    ))
+                    .into(),
+                );
+            }
+
+            let mut reset = widget::button::standard(fl!("btrfs-reset-device-stats"));
+            if !state.device_stats_busy && stats.iter().any(|device| device.total_errors() > 0) {
+                reset = reset.on_press(Message::BtrfsResetDeviceStats {
+                    mount_point: mount_point.to_string(),
+                });
+            }
+            items.push(reset.into());
+        }
+        Some(Err(error)) => {
+            items.push(widget::text::caption(format!("Error: {}", error)).into());
+        }
+        None => {}
+    }
+
+    iced_widget::column(items).spacing(8).into()
+}
+
+/// Compression property, mount option and savings estimate controls
+fn compression_section<'a>(mount_point: &'a str, state: &'a BtrfsState) -> Element<'a, Message> {
+    let busy = state.compression_busy;
+    let mut items: Vec<Element<'a, Message>> = vec![
+        widget::text(fl!("btrfs-compression"))
+            .font(cosmic::iced::font::Font {
+                weight: cosmic::iced::font::Weight::Semibold,
+                ..Default::default()
+            })
+            .into(),
+    ];
+
+    items.push(
+        widget::text_input(fl!("btrfs-compression-target"), &state.compression_target)
+            .on_input(Message::BtrfsCompressionTargetChanged)
+            .on_submit(move |_| Message::BtrfsLoadCompression {
+                mount_point: mount_point.to_string(),
+            })
+            .into(),
+    );
+
+    match &state.compression {
+        Some(Ok(info)) => {
+            let unset = fl!("btrfs-compression-unset");
+            let mount_option = match &info.mount_option {
+                Some(value) if info.force => format!("{value} (force)"),
+                Some(value) => value.clone(),
+                None => unset.clone(),
+            };
+            items.push(
+                widget::text::caption(fl!("btrfs-compression-mount-option", value = mount_option))
+                    .into(),
+            );
+            items.push(
+                widget::text::caption(fl!(
+                    "btrfs-compression-property",
+                    value = info.property.clone().unwrap_or(unset)
+                ))
+                .into(),
+            );
+        }
+        Some(Err(error)) => {
+            items.push(widget::text::caption(format!("Error: {}", error)).into());
+        }
+        None => {}
+    }
+
+    let mut apply_property = widget::button::standard(fl!("btrfs-compression-apply-property"));
+    let mut apply_mount = widget::button::standard(fl!("btrfs-compression-apply-mount"));
+    let mut estimate = widget::button::standard(fl!("btrfs-compression-estimate"));
+    let mut compress_existing = widget::button::destructive(fl!("btrfs-compress-existing"));
+    if !busy {
+        let mount_point = mount_point.to_string();
+        apply_property = apply_property.on_press(Message::BtrfsApplyCompressionProperty {
+            mount_point: mount_point.clone(),
+        });
+        apply_mount = apply_mount.on_press(Message::BtrfsApplyMountCompression {
+            mount_point: mount_point.clone(),
+        });
+        estimate = estimate.on_press(Message::BtrfsEstimateCompression {
+            mount_point: mount_point.clone(),
+        });
+        // Recompressing with "none" would only rewrite the data
+        if BTRFS_COMPRESSION_ALGORITHMS
+            .get(state.compression_algorithm)
+            .is_some_and(|algorithm| *algorithm != "none")
+        {
+            compress_existing =
+                compress_existing.on_press(Message::BtrfsCompressExisting { mount_point });
+        }
+    }
+
+    items.push(
+        iced_widget::row![
+            widget::dropdown(
+                BTRFS_COMPRESSION_ALGORITHMS,
+                Some(state.compression_algorithm),
+                Message::BtrfsCompressionAlgorithmChanged,
+            ),
+            widget::checkbox(fl!("btrfs-compression-force"), state.compression_force)
+                .on_toggle(Message::BtrfsCompressionForceToggled),
+        ]
+        .spacing(8)
+        .align_y(cosmic::iced::Alignment::Center)
+        .into(),
+    );
+    items.push(
+        iced_widget::row![apply_property, apply_mount]
+            .spacing(8)
+            .into(),
+    );
+    items.push(
+        widget::text::caption(fl!("btrfs-compression-mount-note"))
+            .size(11)
+            .into(),
+    );
+    items.push(
+        iced_widget::row![estimate, compress_existing]
+            .spacing(8)
+            .into(),
+    );
+
+    match &state.compression_estimate {
+        Some(Ok(estimate)) => {
+            items.push(
+                widget::text::caption(fl!(
+                    "btrfs-compression-estimate-result",
+                    savings = bytes_to_pretty(&estimate.estimated_savings_bytes, false),
+                    total = bytes_to_pretty(&estimate.total_bytes, false)
+                ))
+                .into(),
+            );
+        }
+        Some(Err(error)) => {
+            items.push(widget::text::caption(format!("Error: {}", error)).into());
+        }
+        None if busy => {
+            items.push(widget::text::caption(fl!("btrfs-compression-working")).into());
+        }
+        None => {}
+    }
+
+    iced_widget::column(items).spacing(8).into()
+}
+
 /// Build hierarchical subvolume list with snapshots nested under parents
 fn build_subvolume_hierarchy<'a>(
     subvolumes: &'a [BtrfsSubvolume],
@@ -262,6 +442,19 @@ fn render_subvolume_row<'a>(
             .into(),
     );
 
+    // Properties button
+    let properties_button = if let Some(mp) = mount_point {
+        widget::button::icon(widget::icon::from_name("document-properties-symbolic"))
+            .on_press(Message::BtrfsShowProperties {
+                mount_point: mp.clone(),
+                subvolume_id: subvol.id,
+            })
+            .padding(4)
+    } else {
+        widget::button::icon(widget::icon::from_name("document-properties-symbolic")).padding(4)
+    };
+    row_items.push(properties_button.into());
+
     // Delete button
     let delete_button = if let (Some(bp), Some(mp)) = (&state.block_path, mount_point) {
         widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
@@ -1,12 +1,14 @@
 use cosmic::widget::text::caption;
-use cosmic::widget::{button, checkbox, dropdown, text, text_input};
+use cosmic::widget::{button, checkbox, dialog, dropdown, text, text_input};
 use cosmic::{Element, iced_widget};
 
 use crate::app::Message;
 use crate::controls::wizard::{wizard_action_row, wizard_shell};
 use crate::fl;
 use crate::message::dialogs::{BtrfsCreateSnapshotMessage, BtrfsCreateSubvolumeMessage};
+use crate::state::btrfs::BtrfsState;
 use crate::state::dialogs::{BtrfsCreateSnapshotDialog, BtrfsCreateSubvolumeDialog};
+use storage_types::{BtrfsSubvolume, RestoreConflictPolicy};
 
 pub fn create_subvolume<'a>(state: BtrfsCreateSubvolumeDialog) -> Element<'a, Message> {
     let BtrfsCreateSubvolumeDialog {
@@ -112,3 +114,138 @@ pub fn create_snapshot<'a>(state: BtrfsCreateSnapshotDialog) -> Element<'a, Mess
         footer,
     )
 }
+
+pub fn subvolume_properties<'a>(
+    mount_point: &'a str,
+    subvolume: &'a BtrfsSubvolume,
+    state: &'a BtrfsState,
+) -> Element<'a, Message> {
+    let mut content = iced_widget::column![
+        caption(fl!("btrfs-subvolume-path")),
+        text(subvolume.path.as_str()),
+        caption(fl!("btrfs-subvolume-id")),
+        text(subvolume.id.to_string()),
+        caption(fl!("btrfs-subvolume-uuid")),
+        text(subvolume.uuid.as_str()),
+    ]
+    .spacing(4);
+
+    if let Some(parent_uuid) = &subvolume.parent_uuid {
+        content = content
+            .push(caption(fl!("btrfs-snapshot-of")))
+            .push(text(parent_uuid.as_str()));
+    }
+
+    match &state.nocow {
+        Some(Ok(status)) => {
+            let mut toggle = checkbox(fl!("btrfs-nocow"), status.enabled);
+            if !state.nocow_busy {
+                toggle = toggle.on_toggle(move |enabled| Message::BtrfsSetNocow {
+                    mount_point: mount_point.to_string(),
+                    enabled,
+                });
+            }
+            content = content
+                .push(toggle)
+                .push(text(fl!("btrfs-nocow-desc")).size(11));
+            if status.non_empty {
+                content = content.push(text(fl!("btrfs-nocow-non-empty")).size(11));
+            }
+        }
+        Some(Err(error)) => {
+            content = content.push(text(fl!("btrfs-nocow-error", error = error.as_str())).size(11));
+        }
+        None => {
+            content = content.push(text(fl!("working")).size(11));
+        }
+    }
+
+    if let Some(origin) = state.snapshot_origin(subvolume) {
+        content = content.push(restore_section(mount_point, origin, state));
+    }
+
+    dialog::dialog()
+        .title(fl!("btrfs-subvolume-properties"))
+        .control(content)
+        .primary_action(
+            button::standard(fl!("close")).on_press(Message::BtrfsCloseProperties {
+                mount_point: mount_point.to_string(),
+            }),
+        )
+        .into()
+}
+
+/// Restore a path, or the whole subvolume, from the selected snapshot into the
+/// subvolume it was taken from
+fn restore_section<'a>(
+    mount_point: &'a str,
+    origin: &'a BtrfsSubvolume,
+    state: &'a BtrfsState,
+) -> Element<'a, Message> {
+    let conflict_options: Vec<String> = RestoreConflictPolicy::ALL
+        .iter()
+        .map(|policy| match policy {
+            RestoreConflictPolicy::Skip => fl!("btrfs-restore-skip"),
+            RestoreConflictPolicy::Overwrite => fl!("btrfs-restore-overwrite"),
+            RestoreConflictPolicy::KeepBoth => fl!("btrfs-restore-keep-both"),
+        })
+        .collect();
+
+    let mut restore_button = button::standard(fl!("btrfs-restore"));
+    if !state.restore_busy && !state.restore_path.trim().is_empty() {
+        restore_button = restore_button.on_press(Message::BtrfsRestoreFromSnapshot {
+            mount_point: mount_point.to_string(),
+        });
+    }
+
+    let mut content = iced_widget::column![
+        caption(fl!("btrfs-restore-files", target = origin.path.as_str())),
+        text_input(fl!("btrfs-restore-path"), &state.restore_path)
+            .on_input(Message::BtrfsRestorePathChanged),
+        iced_widget::row![
+            dropdown(
+                conflict_options,
+                Some(state.restore_conflict),
+                Message::BtrfsRestoreConflictChanged,
+            ),
+            restore_button,
+        ]
+        .spacing(8),
+    ]
+    .spacing(8);
+
+    if state.restore_busy {
+        content = content.push(text(fl!("working")).size(11));
+    }
+    match &state.restore_result {
+        Some(Ok(result)) => {
+            content = content.push(
+                text(fl!(
+                    "btrfs-restore-result",
+                    restored = result.restored.len(),
+                    skipped = result.skipped.len()
+                ))
+                .size(11),
+            );
+        }
+        Some(Err(error)) => {
+            content = content.push(text(error.as_str()).size(11));
+        }
+        None => {}
+    }
+
+    let mut rollback_button = button::destructive(fl!("btrfs-rollback"));
+    if !state.restore_busy {
+        rollback_button = rollback_button.on_press(Message::BtrfsRollback {
+            mount_point: mount_point.to_string(),
+        });
+    }
+    content = content
+        .push(caption(fl!(
+            "btrfs-rollback-desc",
+            target = origin.path.as_str()
+        )))
+        .push(rollback_button);
+
+    content.into()
+}
@@ -1,8 +1,10 @@
 use crate::app::Message;
 use crate::fl;
+use crate::message::dialogs::ConfirmationMessage;
+use crate::state::dialogs::ConfirmationGuard;
 use cosmic::{
-    Element,
-    widget::{button, dialog},
+    Element, iced_widget,
+    widget::{button, dialog, text::caption, text_input},
 };
 use std::borrow::Cow;
 
@@ -12,12 +14,13 @@ pub fn confirmation<'a>(
     ok_message: Message,
     cancel_message: Option<Message>,
     running: bool,
+    guard: Option<&ConfirmationGuard>,
 ) -> Element<'a, Message> {
     let mut dialog = dialog::dialog().title(title).body(prompt);
 
     let mut ok_button = button::destructive(fl!("ok"));
-    if !running {
-        ok_button = ok_button.on_press(ok_message);
+    if !running && let Some(message) = guarded(guard, ok_message) {
+        ok_button = ok_button.on_press(message);
     }
 
     dialog = dialog.primary_action(ok_button);
@@ -26,6 +29,10 @@ pub fn confirmation<'a>(
         dialog = dialog.secondary_action(button::standard(fl!("cancel")).on_press(c))
     };
 
+    if let Some(guard) = guard.filter(|_| !running) {
+        dialog = dialog.control(confirmation_guard(guard));
+    }
+
     if running {
         dialog = dialog.body(fl!("working"));
     }
@@ -33,14 +40,37 @@ pub fn confirmation<'a>(
     dialog.into()
 }
 
-pub fn info<'a>(
-    title: impl Into<Cow<'a, str>>,
-    body: impl Into<Cow<'a, str>>,
-    ok_message: Message,
-) -> Element<'a, Message> {
-    dialog::dialog()
-        .title(title)
-        .body(body)
-        .primary_action(button::standard(fl!("ok")).on_press(ok_message))
-        .into()
+/// The message of a destructive OK button: `ok_message` once `guard` is
+/// satisfied
+pub fn guarded(guard: Option<&ConfirmationGuard>, ok_message: Message) -> Option<Message> {
+    guard
+        .is_none_or(ConfirmationGuard::satisfied)
+        .then_some(ok_message)
+}
+
+/// Fields for the steps `guard` asks for beyond pressing OK
+pub fn confirmation_guard<'a>(guard: &ConfirmationGuard) -> Element<'a, Message> {
+    let mut content = iced_widget::column![].spacing(6);
+
+    if guard.requirements.type_name {
+        content = content.push(caption(fl!("confirm-type-name", name = guard.name())));
+        content = content.push(
+            text_input(guard.name().to_string(), guard.typed_name.clone())
+                .on_input(|name| ConfirmationMessage::NameUpdate(name).into()),
+        );
+    }
+
+    if let Some(token) = &guard.token {
+        content = content
+            .push(caption(fl!("confirm-type-token", token = token.as_str())))
+            .push(
+                text_input("", guard.typed_token.clone())
+                    .on_input(|token| ConfirmationMessage::TokenUpdate(token).into()),
+            );
+    }
+    if guard.countdown > 0 {
+        content = content.push(caption(fl!("confirm-countdown", seconds = guard.countdown)));
+    }
+
+    content.into()
 }
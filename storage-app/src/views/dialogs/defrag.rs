@@ -0,0 +1,102 @@
+use crate::app::Message;
+use crate::fl;
+use crate::message::dialogs::DefragDialogMessage;
+use crate::state::dialogs::DefragmentDialog;
+use cosmic::{
+    Element,
+    iced::Length,
+    iced_widget,
+    widget::text::{caption, caption_heading},
+    widget::{button, dialog, text_input},
+};
+
+pub fn defragment<'a>(state: DefragmentDialog) -> Element<'a, Message> {
+    let busy = state.analyzing || state.running;
+
+    let mut target_input =
+        text_input(state.mount_point.clone(), state.target.clone()).label(fl!("defrag-target"));
+    if !busy {
+        target_input = target_input.on_input(|t| DefragDialogMessage::TargetUpdate(t).into());
+    }
+
+    let mut content = iced_widget::column![
+        caption(format!(
+            "{} ({})",
+            state.mount_point,
+            state.fs_type.to_uppercase()
+        )),
+        target_input,
+        caption(fl!("defrag-target-help")),
+    ]
+    .spacing(8)
+    .width(Length::Fill);
+
+    content = content.push(caption_heading(fl!("defrag-fragmentation")));
+    if state.analyzing {
+        content = content.push(caption(fl!("defrag-analyzing")));
+    } else if let Some(report) = state.report.as_ref() {
+        let score = match report.score_percent {
+            Some(score) => fl!("defrag-score", score = score),
+            None => fl!("defrag-score-unavailable"),
+        };
+        content = content.push(caption(score));
+        if report.recommended() {
+            content = content.push(caption(fl!("defrag-recommended")));
+        } else if report.score_percent.is_some() {
+            content = content.push(caption(fl!("defrag-not-needed")));
+        }
+    }
+
+    if state.running {
+        content = content.push(caption(fl!("working")));
+        if let Some((processed, total)) = state.progress {
+            let fraction = if total > 0 {
+                (processed as f64 / total as f64).min(1.0) as f32
+            } else {
+                0.0_f32
+            };
+            content =
+                content.push(iced_widget::progress_bar(0.0..=1.0, fraction).width(Length::Fill));
+            content = content.push(caption(if total > 0 {
+                fl!(
+                    "defrag-progress-total",
+                    processed = processed,
+                    total = total
+                )
+            } else {
+                fl!("defrag-progress", processed = processed)
+            }));
+        }
+    }
+
+    if let Some(result) = state.result.as_ref() {
+        content = content.push(caption(fl!(
+            "defrag-complete",
+            processed = result.files_processed
+        )));
+    }
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    let mut analyze = button::standard(fl!("defrag-analyze"));
+    let mut start = button::suggested(fl!("defragment"));
+    let mut close = button::standard(fl!("close"));
+
+    if !busy {
+        analyze = analyze.on_press(DefragDialogMessage::Analyze.into());
+        start = start.on_press(DefragDialogMessage::Start.into());
+    }
+    if !state.running {
+        close = close.on_press(DefragDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("defragment"))
+        .control(content)
+        .primary_action(start)
+        .secondary_action(close)
+        .tertiary_action(analyze)
+        .into()
+}
@@ -0,0 +1,63 @@
+use crate::app::Message;
+use crate::diagnostics::FailureContext;
+use crate::fl;
+use crate::message::dialogs::DiagnosticsDialogMessage;
+use crate::state::dialogs::{DiagnosticsDialog, ErrorRecovery};
+use cosmic::{
+    Element, iced_widget,
+    widget::text::caption,
+    widget::{button, dialog},
+};
+
+/// An operation failed; like [`super::info`], with a way to report it and
+/// the `recovery` offered, if any
+pub fn error<'a>(
+    title: String,
+    body: String,
+    failure: FailureContext,
+    recovery: Option<ErrorRecovery>,
+) -> Element<'a, Message> {
+    let report = button::text(fl!("report-problem")).on_press(Message::ReportProblem(failure));
+    let dlg = dialog::dialog().title(title).body(body);
+    match recovery {
+        Some(recovery) => dlg
+            .primary_action(button::suggested(recovery.label).on_press(recovery.message))
+            .secondary_action(button::standard(fl!("cancel")).on_press(Message::CloseDialog))
+            .tertiary_action(report),
+        None => dlg
+            .primary_action(button::standard(fl!("ok")).on_press(Message::CloseDialog))
+            .secondary_action(report),
+    }
+    .into()
+}
+
+pub fn diagnostics<'a>(state: DiagnosticsDialog) -> Element<'a, Message> {
+    let mut content = iced_widget::column![
+        caption(fl!("diagnostics-description")),
+        caption(fl!("diagnostics-contents")),
+        caption(fl!("diagnostics-redaction")),
+    ]
+    .spacing(8)
+    .width(cosmic::iced::Length::Fill);
+
+    if state.running {
+        content = content.push(caption(fl!("working")));
+    }
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    let mut save = button::suggested(fl!("diagnostics-save"));
+    let mut cancel = button::standard(fl!("cancel"));
+    if !state.running {
+        save = save.on_press(DiagnosticsDialogMessage::Save.into());
+        cancel = cancel.on_press(DiagnosticsDialogMessage::Cancel.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("report-problem"))
+        .control(content)
+        .primary_action(save)
+        .secondary_action(cancel)
+        .into()
+}
@@ -1,15 +1,34 @@
+use super::common::{confirmation_guard, guarded};
 use crate::app::Message;
-use crate::controls::wizard::{wizard_action_row, wizard_shell};
+use crate::controls::wizard::{
+    WizardBreadcrumbStatus, WizardBreadcrumbStep, wizard_action_row, wizard_breadcrumb,
+    wizard_shell, wizard_step_is_clickable, wizard_step_nav, wizard_step_shell,
+};
 use crate::fl;
-use crate::message::dialogs::{FormatDiskMessage, SmartDialogMessage};
-use crate::state::dialogs::{FormatDiskDialog, SmartDataDialog};
+use crate::message::dialogs::{
+    FormatDiskMessage, GptEntriesDialogMessage, LostPartitionsDialogMessage, SetUpDriveMessage,
+    SmartDialogMessage,
+};
+use crate::state::dialogs::{
+    ConfirmationGuard, FormatDiskDialog, GptEntriesDialog, GptEntryField, LostPartitionsDialog,
+    SELFTEST_INTERVAL_DAYS, SETUP_FILESYSTEMS, SetUpDriveDialog, SetUpDriveStep, SmartDataDialog,
+    TEMPERATURE_THRESHOLD_CHOICES,
+};
 use cosmic::{
     Element, iced_widget,
     widget::text::{caption, caption_heading},
-    widget::{button, dialog, dropdown},
+    widget::{button, checkbox, dialog, dropdown, scrollable, text_input},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+use storage_types::{
+    KernelErrorKind, LostPartitionConfidence, SmartBackendKind, SmartSelfTestKind, TemperatureUnit,
+    bytes_to_pretty,
 };
 
-pub fn format_disk<'a>(state: FormatDiskDialog) -> Element<'a, Message> {
+pub fn format_disk<'a>(
+    state: FormatDiskDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
     let erase_options = vec![
         fl!("erase-dont-overwrite-quick").to_string(),
         fl!("erase-overwrite-slow").to_string(),
@@ -35,11 +54,15 @@ pub fn format_disk<'a>(state: FormatDiskDialog) -> Element<'a, Message> {
 
     if state.running {
         content = content.push(caption(fl!("working")));
+    } else if let Some(guard) = guard {
+        content = content.push(confirmation_guard(guard));
     }
 
     let mut confirm = button::destructive(fl!("format-disk"));
-    if !state.running {
-        confirm = confirm.on_press(FormatDiskMessage::Confirm.into());
+    if !state.running
+        && let Some(message) = guarded(guard, FormatDiskMessage::Confirm.into())
+    {
+        confirm = confirm.on_press(message);
     }
 
     let footer = wizard_action_row(
@@ -55,7 +78,176 @@ pub fn format_disk<'a>(state: FormatDiskDialog) -> Element<'a, Message> {
     wizard_shell(caption(fl!("format-disk")).into(), content.into(), footer)
 }
 
-pub fn smart_data<'a>(state: SmartDataDialog) -> Element<'a, Message> {
+pub fn set_up_drive<'a>(
+    state: SetUpDriveDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
+    let current_step = state.step;
+    let filesystem = state.filesystem();
+    let mut content = iced_widget::column![].spacing(12);
+
+    match current_step {
+        SetUpDriveStep::Table => {
+            content = content
+                .push(caption(fl!(
+                    "setup-drive-intro",
+                    name = state.drive.name(),
+                    size = bytes_to_pretty(&state.drive.disk.size, false)
+                )))
+                .push(caption_heading(fl!("partitioning")))
+                .push(dropdown(
+                    vec![fl!("partitioning-gpt"), fl!("partitioning-dos-mbr")],
+                    Some(state.table_index),
+                    |v| SetUpDriveMessage::TableUpdate(v).into(),
+                ));
+        }
+        SetUpDriveStep::Filesystem => {
+            let labels = SETUP_FILESYSTEMS
+                .into_iter()
+                .map(filesystem_label)
+                .collect::<Vec<_>>();
+            content = content
+                .push(caption_heading(fl!("filesystem-type")))
+                .push(dropdown(labels, Some(state.filesystem_index), |v| {
+                    SetUpDriveMessage::FilesystemUpdate(v).into()
+                }))
+                .push(
+                    text_input(fl!("volume-name"), state.label.clone())
+                        .label(fl!("volume-name"))
+                        .on_input(|t| SetUpDriveMessage::LabelUpdate(t).into()),
+                );
+            if !state.can_advance() {
+                content = content.push(caption(fl!("fs-tools-warning")));
+            }
+        }
+        SetUpDriveStep::Encryption => {
+            content = content.push(
+                checkbox(fl!("password-protected-luks"), state.encrypt)
+                    .on_toggle(|v| SetUpDriveMessage::EncryptUpdate(v).into()),
+            );
+            if state.encrypt {
+                content = content
+                    .push(
+                        text_input::secure_input("", state.passphrase.clone(), None, true)
+                            .label(fl!("password"))
+                            .on_input(|v| SetUpDriveMessage::PassphraseUpdate(v).into()),
+                    )
+                    .push(
+                        text_input::secure_input("", state.confirm_passphrase.clone(), None, true)
+                            .label(fl!("confirm"))
+                            .on_input(|v| SetUpDriveMessage::ConfirmPassphraseUpdate(v).into()),
+                    );
+            }
+        }
+        SetUpDriveStep::Review => {
+            let table = if state.table_type() == "dos" {
+                fl!("partitioning-dos-mbr")
+            } else {
+                fl!("partitioning-gpt")
+            };
+            let label = if state.label.is_empty() {
+                fl!("setup-drive-no-label")
+            } else {
+                state.label.clone()
+            };
+            content = content
+                .push(caption(format!("{}: {}", fl!("partitioning"), table)))
+                .push(caption(format!(
+                    "{}: {}",
+                    fl!("filesystem-type"),
+                    filesystem_label(filesystem)
+                )))
+                .push(caption(format!("{}: {}", fl!("volume-name"), label)))
+                .push(caption(if state.encrypt {
+                    fl!("setup-drive-encrypted")
+                } else {
+                    fl!("setup-drive-not-encrypted")
+                }))
+                .push(caption(fl!("setup-drive-undo-note")));
+            if let Some(guard) = guard.filter(|_| !state.running) {
+                content = content.push(confirmation_guard(guard));
+            }
+        }
+    }
+
+    if let Some(error) = &state.error {
+        content = content.push(caption(error.clone()));
+    }
+    if state.running {
+        content = content.push(caption(fl!("working")));
+    }
+
+    let current_number = current_step.number();
+    let steps = [
+        (SetUpDriveStep::Table, fl!("partitioning")),
+        (SetUpDriveStep::Filesystem, fl!("filesystem-type")),
+        (SetUpDriveStep::Encryption, fl!("setup-drive-encryption")),
+        (SetUpDriveStep::Review, fl!("setup-drive-review")),
+    ];
+    let breadcrumb = wizard_breadcrumb(
+        steps
+            .into_iter()
+            .map(|(step, label)| {
+                let number = step.number();
+                let status = if number == current_number {
+                    WizardBreadcrumbStatus::Current
+                } else if number < current_number {
+                    WizardBreadcrumbStatus::Completed
+                } else {
+                    WizardBreadcrumbStatus::Upcoming
+                };
+                let on_press = (!state.running && wizard_step_is_clickable(number, current_number))
+                    .then(|| SetUpDriveMessage::SetStep(step).into());
+                WizardBreadcrumbStep {
+                    label,
+                    status,
+                    on_press,
+                }
+            })
+            .collect(),
+    );
+
+    let back_message = (current_step != SetUpDriveStep::Table && !state.running)
+        .then(|| SetUpDriveMessage::PrevStep.into());
+    let (primary_label, primary_message) = if current_step == SetUpDriveStep::Review {
+        (
+            fl!("setup-drive"),
+            guarded(guard, SetUpDriveMessage::Confirm.into()).filter(|_| !state.running),
+        )
+    } else {
+        (
+            fl!("next"),
+            (state.can_advance() && !state.running).then(|| SetUpDriveMessage::NextStep.into()),
+        )
+    };
+
+    let footer = wizard_step_nav(
+        SetUpDriveMessage::Cancel.into(),
+        back_message,
+        primary_label,
+        primary_message,
+    );
+
+    wizard_step_shell(
+        caption(fl!("setup-drive-title")).into(),
+        breadcrumb,
+        content.into(),
+        footer,
+    )
+}
+
+fn filesystem_label(fs_type: &str) -> String {
+    match fs_type {
+        "exfat" => format!("{} — {}", fl!("fs-name-exfat"), fl!("fs-desc-exfat")),
+        "ext4" => format!("{} — {}", fl!("fs-name-ext4"), fl!("fs-desc-ext4")),
+        "ntfs" => format!("{} — {}", fl!("fs-name-ntfs"), fl!("fs-desc-ntfs")),
+        "vfat" => format!("{} — {}", fl!("fs-name-vfat"), fl!("fs-desc-vfat")),
+        "btrfs" => format!("{} — {}", fl!("fs-name-btrfs"), fl!("fs-desc-btrfs")),
+        fs => fs.to_string(),
+    }
+}
+
+pub fn smart_data<'a>(state: SmartDataDialog, unit: TemperatureUnit) -> Element<'a, Message> {
     let mut content = iced_widget::column![]
         .spacing(6)
         .width(cosmic::iced::Length::Fill);
@@ -76,9 +268,9 @@ pub fn smart_data<'a>(state: SmartDataDialog) -> Element<'a, Message> {
 
         if let Some(temp_c) = status.temperature_celsius {
             content = content.push(caption(format!(
-                "{}: {} °C",
+                "{}: {}",
                 fl!("smart-temperature"),
-                temp_c
+                unit.format(temp_c.into())
             )));
         }
 
@@ -115,6 +307,147 @@ pub fn smart_data<'a>(state: SmartDataDialog) -> Element<'a, Message> {
         content = content.push(caption(fl!("smart-no-data")));
     }
 
+    if let Some(backend) = state.backend.as_ref() {
+        let backend_options = vec![
+            fl!("smart-backend-auto"),
+            fl!("smart-backend-udisks"),
+            fl!("smart-backend-smartctl"),
+        ];
+        let selected = match backend.override_backend {
+            None => 0,
+            Some(SmartBackendKind::UDisks) => 1,
+            Some(SmartBackendKind::Smartctl) => 2,
+        };
+        let active = match backend.active {
+            Some(SmartBackendKind::UDisks) => fl!("smart-backend-udisks"),
+            Some(SmartBackendKind::Smartctl) => fl!("smart-backend-smartctl"),
+            None => fl!("unknown"),
+        };
+
+        content = content
+            .push(caption_heading(fl!("smart-backend")))
+            .push(dropdown(backend_options, Some(selected), |v| {
+                SmartDialogMessage::BackendSelected(v).into()
+            }))
+            .push(caption(format!(
+                "{}: {active}",
+                fl!("smart-backend-active")
+            )));
+        if !backend.available.contains(&SmartBackendKind::Smartctl) {
+            content = content.push(caption(fl!("smart-backend-smartctl-missing")));
+        }
+    }
+
+    if let Some(schedule) = state.schedule {
+        let interval_options: Vec<String> = SELFTEST_INTERVAL_DAYS
+            .iter()
+            .map(|days| match days {
+                None => fl!("smart-schedule-off"),
+                Some(days) => fl!("smart-schedule-every", days = *days),
+            })
+            .collect();
+        let position = |days: Option<u32>| {
+            SELFTEST_INTERVAL_DAYS
+                .iter()
+                .position(|choice| *choice == days)
+        };
+
+        content = content
+            .push(caption_heading(fl!("smart-schedule")))
+            .push(caption(fl!("smart-selftest-short")))
+            .push(dropdown(
+                interval_options.clone(),
+                position(schedule.short_interval_days),
+                |v| SmartDialogMessage::ShortScheduleSelected(v).into(),
+            ))
+            .push(caption(fl!("smart-selftest-extended")))
+            .push(dropdown(
+                interval_options,
+                position(schedule.extended_interval_days),
+                |v| SmartDialogMessage::ExtendedScheduleSelected(v).into(),
+            ))
+            .push(caption(fl!("smart-schedule-conditions")));
+    }
+
+    if let Some(thresholds) = state.thresholds {
+        let threshold_options: Vec<String> = TEMPERATURE_THRESHOLD_CHOICES
+            .iter()
+            .map(|celsius| unit.format(*celsius as i64))
+            .collect();
+        let position = |celsius: u64| {
+            TEMPERATURE_THRESHOLD_CHOICES
+                .iter()
+                .position(|choice| *choice == celsius)
+        };
+
+        content = content
+            .push(caption_heading(fl!("smart-thresholds")))
+            .push(caption(fl!("smart-threshold-warning")))
+            .push(dropdown(
+                threshold_options.clone(),
+                position(thresholds.warning_c),
+                |v| SmartDialogMessage::WarningThresholdSelected(v).into(),
+            ))
+            .push(caption(fl!("smart-threshold-critical")))
+            .push(dropdown(
+                threshold_options,
+                position(thresholds.critical_c),
+                |v| SmartDialogMessage::CriticalThresholdSelected(v).into(),
+            ));
+    }
+
+    if !state.history.is_empty() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        content = content.push(caption_heading(fl!("smart-selftest-history")));
+        for record in state.history.iter().rev() {
+            let kind = match record.kind {
+                SmartSelfTestKind::Short => fl!("smart-selftest-short"),
+                SmartSelfTestKind::Extended => fl!("smart-selftest-extended"),
+            };
+            let origin = if record.scheduled {
+                fl!("smart-selftest-scheduled")
+            } else {
+                fl!("smart-selftest-manual")
+            };
+            content = content.push(caption(fl!(
+                "smart-selftest-history-entry",
+                kind = kind,
+                origin = origin,
+                days = now.saturating_sub(record.started_at) / (24 * 60 * 60),
+                result = record
+                    .result
+                    .clone()
+                    .unwrap_or_else(|| fl!("smart-selftest-running"))
+            )));
+        }
+    }
+
+    if !state.kernel_errors.is_empty() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        content = content.push(caption_heading(fl!("kernel-errors")));
+        for error in state.kernel_errors.iter().rev().take(10) {
+            let kind = match error.kind {
+                KernelErrorKind::Io => fl!("kernel-error-io"),
+                KernelErrorKind::Ata => fl!("kernel-error-ata"),
+                KernelErrorKind::Nvme => fl!("kernel-error-nvme"),
+            };
+            content = content.push(caption(fl!(
+                "kernel-error-entry",
+                kind = kind,
+                days = now.saturating_sub(error.timestamp) / (24 * 60 * 60),
+                message = error.message.clone()
+            )));
+        }
+    }
+
     let mut refresh = button::standard(fl!("refresh"));
     let mut short = button::standard(fl!("smart-selftest-short"));
     let mut extended = button::standard(fl!("smart-selftest-extended"));
@@ -141,3 +474,182 @@ pub fn smart_data<'a>(state: SmartDataDialog) -> Element<'a, Message> {
         .primary_action(close)
         .into()
 }
+
+pub fn lost_partitions<'a>(state: LostPartitionsDialog) -> Element<'a, Message> {
+    let mut content = iced_widget::column![caption(fl!("lost-partitions-description"))]
+        .spacing(8)
+        .width(cosmic::iced::Length::Fill);
+
+    match state.found.as_ref() {
+        None => content = content.push(caption(fl!("lost-partitions-searching"))),
+        Some(found) if found.is_empty() => {
+            content = content.push(caption(fl!("lost-partitions-none")));
+        }
+        Some(found) => {
+            for (index, partition) in found.iter().enumerate() {
+                let confidence = match partition.confidence {
+                    LostPartitionConfidence::High => fl!("confidence-high"),
+                    LostPartitionConfidence::Medium => fl!("confidence-medium"),
+                    LostPartitionConfidence::Low => fl!("confidence-low"),
+                };
+                let label = fl!(
+                    "lost-partition-entry",
+                    fs_type = partition.fs_type.clone(),
+                    label = partition.label.clone().unwrap_or_default(),
+                    size = bytes_to_pretty(&partition.size, false),
+                    offset = bytes_to_pretty(&partition.offset, false),
+                    confidence = confidence
+                );
+                let checked = state.selected.get(index).copied().unwrap_or(false);
+                let mut entry = checkbox(label, checked);
+                if !state.running && partition.confidence != LostPartitionConfidence::Low {
+                    entry = entry
+                        .on_toggle(move |v| LostPartitionsDialogMessage::Toggle(index, v).into());
+                }
+                content = content.push(entry);
+            }
+        }
+    }
+
+    if state.running && state.found.is_some() {
+        content = content.push(caption(fl!("working")));
+    }
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    let mut recreate = button::suggested(fl!("recreate-partitions"));
+    if !state.running
+        && !state.drive.disk.read_only
+        && state.selected.iter().any(|selected| *selected)
+    {
+        recreate = recreate.on_press(LostPartitionsDialogMessage::Recreate.into());
+    }
+    let mut close = button::standard(fl!("close"));
+    if !state.running {
+        close = close.on_press(LostPartitionsDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("find-lost-partitions"))
+        .control(content)
+        .primary_action(recreate)
+        .secondary_action(close)
+        .into()
+}
+
+pub fn gpt_entries<'a>(
+    state: GptEntriesDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
+    let mut content = iced_widget::column![caption(fl!("gpt-entries-description"))]
+        .spacing(8)
+        .width(cosmic::iced::Length::Fill);
+
+    let editable = !state.running && state.table.as_ref().is_some_and(|t| t.is_valid());
+    match state.table.as_ref() {
+        None if state.running => content = content.push(caption(fl!("working"))),
+        None => {}
+        Some(table) => {
+            content = content.push(caption(fl!(
+                "gpt-entries-usable",
+                first = table.first_usable_lba,
+                last = table.last_usable_lba,
+                sector_size = table.sector_size
+            )));
+            if !table.is_valid() {
+                content = content.push(caption(fl!("gpt-entries-damaged")));
+            }
+
+            let mut rows = iced_widget::column![].spacing(12);
+            for (index, row) in state.rows.iter().enumerate() {
+                let input = |label: String, value: &String, field: GptEntryField| {
+                    let input = text_input(label.clone(), value.clone()).label(label);
+                    if editable {
+                        input.on_input(move |text| {
+                            GptEntriesDialogMessage::FieldUpdate(index, field, text).into()
+                        })
+                    } else {
+                        input
+                    }
+                };
+                rows = rows.push(
+                    iced_widget::column![
+                        caption_heading(fl!("gpt-entry", number = row.number)),
+                        iced_widget::row![
+                            input(
+                                fl!("gpt-entry-first-lba"),
+                                &row.first_lba,
+                                GptEntryField::FirstLba
+                            ),
+                            input(
+                                fl!("gpt-entry-last-lba"),
+                                &row.last_lba,
+                                GptEntryField::LastLba
+                            ),
+                            input(
+                                fl!("gpt-entry-attributes"),
+                                &row.attributes,
+                                GptEntryField::Attributes
+                            ),
+                        ]
+                        .spacing(8),
+                        input(
+                            fl!("gpt-entry-type-guid"),
+                            &row.type_guid,
+                            GptEntryField::TypeGuid
+                        ),
+                        input(fl!("gpt-entry-guid"), &row.guid, GptEntryField::Guid),
+                        input(fl!("gpt-entry-name"), &row.name, GptEntryField::Name),
+                    ]
+                    .spacing(4),
+                );
+            }
+            content = content.push(scrollable(rows).height(360));
+        }
+    }
+
+    // Validation runs on every keystroke, so nothing invalid reaches Apply
+    let edits = state.edits();
+    match &edits {
+        Ok(edits) if edits.is_empty() => {}
+        Ok(edits) => {
+            content = content.push(caption(fl!("gpt-entries-pending", count = edits.len())))
+        }
+        Err(e) => content = content.push(caption(e.clone())),
+    }
+    if state.running && state.table.is_some() {
+        content = content.push(caption(fl!("working")));
+    }
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+    if let Some(guard) = guard.filter(|_| editable) {
+        content = content.push(confirmation_guard(guard));
+    }
+
+    let mut apply = button::destructive(fl!("gpt-entries-write"));
+    if editable
+        && !state.drive.disk.read_only
+        && edits.as_ref().is_ok_and(|edits| !edits.is_empty())
+        && let Some(message) = guarded(guard, GptEntriesDialogMessage::Apply.into())
+    {
+        apply = apply.on_press(message);
+    }
+    let mut revert = button::standard(fl!("gpt-entries-revert"));
+    if editable {
+        revert = revert.on_press(GptEntriesDialogMessage::Revert.into());
+    }
+    let mut close = button::standard(fl!("close"));
+    if !state.running {
+        close = close.on_press(GptEntriesDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("edit-gpt-entries"))
+        .control(content)
+        .primary_action(apply)
+        .secondary_action(close)
+        .tertiary_action(revert)
+        .into()
+}
@@ -0,0 +1,158 @@
+use crate::app::Message;
+use crate::fl;
+use crate::message::dialogs::EncryptedFoldersDialogMessage;
+use crate::state::dialogs::EncryptedFoldersDialog;
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    iced_widget,
+    widget::text::{body, caption, caption_heading},
+    widget::{button, dialog, text_input},
+};
+use storage_types::EncryptedDirectory;
+
+pub fn encrypted_folders<'a>(state: EncryptedFoldersDialog) -> Element<'a, Message> {
+    let busy = state.loading || state.running;
+    let available = state.status.as_ref().is_none_or(|status| status.available);
+
+    let mut content = iced_widget::column![
+        caption(state.mount_point.clone()),
+        caption(fl!("encrypted-folders-help")),
+    ]
+    .spacing(8)
+    .width(Length::Fill);
+
+    content = content.push(caption_heading(fl!("encrypted-folders")));
+    match state.status.as_ref() {
+        None => content = content.push(caption(fl!("encrypted-folders-loading"))),
+        Some(status) if !status.available => {
+            content = content.push(caption(fl!("encrypted-folders-no-fscrypt")));
+        }
+        Some(status) if status.directories.is_empty() => {
+            content = content.push(caption(fl!("encrypted-folders-none")));
+        }
+        Some(status) => {
+            for directory in &status.directories {
+                content = content.push(directory_row(&state, directory, busy));
+            }
+        }
+    }
+
+    content = content.push(caption_heading(fl!("encrypted-folders-new")));
+    let mut directory_input = text_input(
+        fl!("encrypted-folders-path-placeholder"),
+        state.new_directory.clone(),
+    )
+    .label(fl!("encrypted-folders-path"));
+    let mut name_input = text_input(
+        fl!("encrypted-folders-protector-placeholder"),
+        state.protector_name.clone(),
+    )
+    .label(fl!("encrypted-folders-protector"));
+    let mut passphrase_input =
+        text_input::secure_input("", state.passphrase.clone(), None, true).label(fl!("passphrase"));
+    let mut confirm_input =
+        text_input::secure_input("", state.confirm.clone(), None, true).label(fl!("confirm"));
+    if !busy && available {
+        directory_input = directory_input
+            .on_input(|v| EncryptedFoldersDialogMessage::NewDirectoryUpdate(v).into());
+        name_input =
+            name_input.on_input(|v| EncryptedFoldersDialogMessage::ProtectorNameUpdate(v).into());
+        passphrase_input = passphrase_input
+            .on_input(|v| EncryptedFoldersDialogMessage::PassphraseUpdate(v).into());
+        confirm_input =
+            confirm_input.on_input(|v| EncryptedFoldersDialogMessage::ConfirmUpdate(v).into());
+    }
+    content = content
+        .push(directory_input)
+        .push(name_input)
+        .push(passphrase_input)
+        .push(confirm_input);
+    if state.status.as_ref().is_some_and(|status| !status.set_up) {
+        content = content.push(caption(fl!("encrypted-folders-setup-note")));
+    }
+
+    if state.running {
+        content = content.push(caption(fl!("working")));
+    }
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    let mut create = button::suggested(fl!("encrypted-folders-create"));
+    let mut close = button::standard(fl!("close"));
+    if !busy && available && !state.new_directory.trim().is_empty() && !state.passphrase.is_empty()
+    {
+        create = create.on_press(EncryptedFoldersDialogMessage::Create.into());
+    }
+    if !state.running {
+        close = close.on_press(EncryptedFoldersDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("encrypted-folders"))
+        .control(content)
+        .primary_action(create)
+        .secondary_action(close)
+        .into()
+}
+
+/// An encrypted directory with its key state, and the passphrase entry
+/// while it is being unlocked
+fn directory_row<'a>(
+    state: &EncryptedFoldersDialog,
+    directory: &EncryptedDirectory,
+    busy: bool,
+) -> Element<'a, Message> {
+    let key_state = if directory.unlocked {
+        fl!("encrypted-folders-unlocked")
+    } else {
+        fl!("encrypted-folders-locked")
+    };
+    let mut details = iced_widget::column![body(directory.path.clone()), caption(key_state)];
+    for protector in &directory.protectors {
+        details = details.push(caption(protector.clone()));
+    }
+
+    let unlocking = state.unlocking.as_deref() == Some(directory.path.as_str());
+    let mut action = if directory.unlocked {
+        button::standard(fl!("lock"))
+    } else {
+        button::standard(fl!("unlock-button"))
+    };
+    if !busy && !unlocking {
+        action = action.on_press(if directory.unlocked {
+            EncryptedFoldersDialogMessage::Lock(directory.path.clone()).into()
+        } else {
+            EncryptedFoldersDialogMessage::StartUnlock(directory.path.clone()).into()
+        });
+    }
+
+    let header = iced_widget::row![details.width(Length::Fill), action]
+        .spacing(8)
+        .align_y(Alignment::Center);
+    if !unlocking {
+        return header.into();
+    }
+
+    let mut passphrase_input =
+        text_input::secure_input("", state.unlock_passphrase.clone(), None, true)
+            .label(fl!("passphrase"));
+    let mut unlock = button::suggested(fl!("unlock-button"));
+    let mut cancel = button::standard(fl!("cancel"));
+    if !busy {
+        passphrase_input = passphrase_input
+            .on_input(|v| EncryptedFoldersDialogMessage::UnlockPassphraseUpdate(v).into())
+            .on_submit(|_| EncryptedFoldersDialogMessage::Unlock.into());
+        unlock = unlock.on_press(EncryptedFoldersDialogMessage::Unlock.into());
+        cancel = cancel.on_press(EncryptedFoldersDialogMessage::CancelUnlock.into());
+    }
+
+    iced_widget::column![
+        header,
+        passphrase_input,
+        iced_widget::row![cancel, unlock].spacing(8),
+    ]
+    .spacing(8)
+    .into()
+}
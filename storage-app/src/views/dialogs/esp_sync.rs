@@ -0,0 +1,102 @@
+use super::common::{confirmation_guard, guarded};
+use crate::app::Message;
+use crate::fl;
+use crate::message::dialogs::EspSyncDialogMessage;
+use crate::state::dialogs::{ConfirmationGuard, ESP_SYNC_INTERVALS, EspSyncDialog};
+use cosmic::{
+    Element,
+    iced::Length,
+    iced_widget,
+    widget::dropdown,
+    widget::text::{caption, caption_heading},
+    widget::{button, dialog},
+};
+use storage_types::bytes_to_pretty;
+
+pub fn esp_sync<'a>(
+    state: EspSyncDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
+    let mut content = iced_widget::column![caption(format!(
+        "{} ({})",
+        state.primary.device,
+        bytes_to_pretty(&state.primary.size, false)
+    ))]
+    .spacing(8)
+    .width(Length::Fill);
+
+    content = content.push(caption_heading(fl!("esp-sync-secondary")));
+    if state.candidates.is_empty() {
+        content = content.push(caption(fl!("esp-sync-no-secondary")));
+    } else {
+        let labels = state
+            .candidates
+            .iter()
+            .map(|partition| {
+                format!(
+                    "{} ({})",
+                    partition.device,
+                    bytes_to_pretty(&partition.size, false)
+                )
+            })
+            .collect::<Vec<_>>();
+        content = content.push(dropdown(labels, Some(state.secondary_index), |index| {
+            EspSyncDialogMessage::SetSecondary(index).into()
+        }));
+
+        content = content.push(caption_heading(fl!("esp-sync-schedule")));
+        let intervals = ESP_SYNC_INTERVALS
+            .iter()
+            .map(|interval| match interval {
+                None => fl!("esp-sync-manual"),
+                Some(1) => fl!("esp-sync-daily"),
+                Some(days) => fl!("esp-sync-every", days = *days),
+            })
+            .collect::<Vec<_>>();
+        let selected = ESP_SYNC_INTERVALS
+            .iter()
+            .position(|interval| *interval == state.interval_days);
+        content = content.push(dropdown(intervals, selected, |index| {
+            EspSyncDialogMessage::SetInterval(index).into()
+        }));
+    }
+    content = content.push(caption(fl!("esp-sync-help")));
+
+    if state.running {
+        content = content.push(caption(fl!("working")));
+    } else if let Some(guard) = guard {
+        content = content.push(confirmation_guard(guard));
+    }
+
+    if let Some(result) = state.result.as_ref() {
+        content = content.push(caption(fl!(
+            "esp-sync-complete",
+            copied = result.copied,
+            deleted = result.deleted
+        )));
+    }
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    let mut sync = button::suggested(fl!("esp-sync-now"));
+    let mut close = button::standard(fl!("close"));
+
+    if !state.running
+        && !state.candidates.is_empty()
+        && let Some(message) = guarded(guard, EspSyncDialogMessage::Sync.into())
+    {
+        sync = sync.on_press(message);
+    }
+    if !state.running {
+        close = close.on_press(EspSyncDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("esp-sync"))
+        .control(content)
+        .primary_action(sync)
+        .secondary_action(close)
+        .into()
+}
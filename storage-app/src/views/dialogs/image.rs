@@ -1,3 +1,4 @@
+use super::common::{confirmation_guard, guarded};
 use crate::app::Message;
 use crate::controls::fields::labelled_spinner;
 use crate::controls::wizard::{wizard_action_row, wizard_shell};
@@ -7,16 +8,22 @@ use crate::message::dialogs::{
     AttachDiskImageDialogMessage, ImageOperationDialogMessage, NewDiskImageDialogMessage,
 };
 use crate::state::dialogs::{
-    AttachDiskImageDialog, ImageOperationDialog, ImageOperationKind, NewDiskImageDialog,
+    AttachDiskImageDialog, ConfirmationGuard, DiskMigration, ImageOperationDialog,
+    ImageOperationKind, LiveUsbChoices, NewDiskImageDialog,
 };
+use crate::views::network::format_duration;
+use crate::views::settings::priority_label;
 use cosmic::{
     Element,
     iced::{Alignment, Length},
     iced_widget,
     widget::button,
-    widget::text::caption,
+    widget::checkbox,
+    widget::dropdown,
+    widget::text::{caption, caption_heading},
 };
 use storage_types::bytes_to_pretty;
+use storage_types::{DiskInfo, LiveUsbDistro, OperationPriority};
 
 pub fn new_disk_image<'a>(state: NewDiskImageDialog) -> Element<'a, Message> {
     let size_pretty = bytes_to_pretty(&state.size_bytes, false);
@@ -133,21 +140,33 @@ pub fn attach_disk_image<'a>(state: AttachDiskImageDialog) -> Element<'a, Messag
     )
 }
 
-pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message> {
+pub fn image_operation<'a>(
+    state: ImageOperationDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
     let title = match state.kind {
         ImageOperationKind::CreateFromDrive => fl!("create-disk-from-drive"),
         ImageOperationKind::RestoreToDrive => fl!("restore-image-to-drive"),
         ImageOperationKind::CreateFromPartition => fl!("create-disk-from-partition"),
         ImageOperationKind::RestoreToPartition => fl!("restore-image-to-partition"),
+        ImageOperationKind::BurnDisc => fl!("burn-disc-image"),
+        ImageOperationKind::BlankDisc => fl!("erase-disc"),
+        ImageOperationKind::CopyPartition => fl!("copy-partition"),
+        ImageOperationKind::MigrateDisk => fl!("migrate-disk"),
+        ImageOperationKind::CreateLiveUsb => fl!("create-live-usb"),
     };
 
     let path_label = match state.kind {
         ImageOperationKind::CreateFromDrive | ImageOperationKind::CreateFromPartition => {
             fl!("image-destination-path")
         }
-        ImageOperationKind::RestoreToDrive | ImageOperationKind::RestoreToPartition => {
-            fl!("image-source-path")
-        }
+        ImageOperationKind::RestoreToDrive
+        | ImageOperationKind::RestoreToPartition
+        | ImageOperationKind::BurnDisc
+        | ImageOperationKind::BlankDisc
+        | ImageOperationKind::CopyPartition
+        | ImageOperationKind::MigrateDisk
+        | ImageOperationKind::CreateLiveUsb => fl!("image-source-path"),
     };
 
     let mut content = iced_widget::column![caption(format!(
@@ -159,7 +178,9 @@ pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message>
 
     if matches!(
         state.kind,
-        ImageOperationKind::CreateFromPartition | ImageOperationKind::RestoreToPartition
+        ImageOperationKind::CreateFromPartition
+            | ImageOperationKind::RestoreToPartition
+            | ImageOperationKind::CopyPartition
     ) {
         match state.partition.as_ref() {
             Some(partition) => {
@@ -191,6 +212,58 @@ pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message>
         content = content.push(caption(fl!("restore-warning")));
     }
 
+    match state.kind {
+        ImageOperationKind::BurnDisc => {
+            content = content.push(caption(fl!("burn-disc-warning")));
+            let mut verify = checkbox(fl!("verify-after-burning"), state.verify);
+            if !state.running {
+                verify = verify.on_toggle(|v| ImageOperationDialogMessage::SetVerify(v).into());
+            }
+            content = content.push(verify);
+        }
+        ImageOperationKind::BlankDisc => {
+            content = content.push(caption(fl!("erase-disc-warning")));
+            let mut full_erase = checkbox(fl!("full-erase"), state.full_erase);
+            if !state.running {
+                full_erase =
+                    full_erase.on_toggle(|v| ImageOperationDialogMessage::SetFullErase(v).into());
+            }
+            content = content.push(full_erase);
+        }
+        ImageOperationKind::CopyPartition => {
+            content = content.push(caption_heading(fl!("copy-partition-target")));
+            if state.copy_targets.is_empty() {
+                content = content.push(caption(fl!("copy-partition-no-targets")));
+            } else {
+                let labels = state
+                    .copy_targets
+                    .iter()
+                    .map(|volume| {
+                        format!(
+                            "{} ({}, {})",
+                            volume.name(),
+                            volume.device_path.clone().unwrap_or_default(),
+                            bytes_to_pretty(&volume.size, false)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let selected = state.copy_targets.iter().position(|volume| {
+                    volume.device_path.as_deref() == Some(state.image_path.as_str())
+                });
+                content = content.push(dropdown(labels, selected, |index| {
+                    ImageOperationDialogMessage::SetCopyTarget(index).into()
+                }));
+            }
+            content = content.push(caption(fl!("copy-partition-warning")));
+        }
+        ImageOperationKind::MigrateDisk => {
+            if let Some(migration) = state.migration.as_ref() {
+                content = migration_choices(content, migration, state.running);
+            }
+        }
+        _ => {}
+    }
+
     let path_text = if state.image_path.trim().is_empty() {
         fl!("no-file-selected")
     } else {
@@ -201,9 +274,13 @@ pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message>
         ImageOperationKind::CreateFromDrive | ImageOperationKind::CreateFromPartition => {
             ImagePathPickerKind::ImageOperationCreate
         }
-        ImageOperationKind::RestoreToDrive | ImageOperationKind::RestoreToPartition => {
-            ImagePathPickerKind::ImageOperationRestore
-        }
+        ImageOperationKind::RestoreToDrive
+        | ImageOperationKind::RestoreToPartition
+        | ImageOperationKind::BurnDisc
+        | ImageOperationKind::BlankDisc
+        | ImageOperationKind::CopyPartition
+        | ImageOperationKind::MigrateDisk
+        | ImageOperationKind::CreateLiveUsb => ImagePathPickerKind::ImageOperationRestore,
     };
 
     let path_row = iced_widget::row![
@@ -213,7 +290,23 @@ pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message>
     .align_y(Alignment::Center)
     .spacing(12);
 
-    content = content.push(caption(path_label)).push(path_row);
+    // Erasing, copying and migrating take no image
+    if !matches!(
+        state.kind,
+        ImageOperationKind::BlankDisc
+            | ImageOperationKind::CopyPartition
+            | ImageOperationKind::MigrateDisk
+    ) {
+        content = content.push(caption(path_label)).push(path_row);
+    }
+
+    if let Some(choices) = state.live_usb.as_ref() {
+        content = live_usb_choices(content, choices, &state.drive.disk, state.running);
+    }
+
+    if state.kind.priority_kind().is_some() {
+        content = priority_choice(content, state.priority);
+    }
 
     if let Some(err) = state.error.as_ref() {
         content = content.push(caption(err.clone()));
@@ -233,7 +326,20 @@ pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message>
                 let done = storage_types::bytes_to_pretty(&bytes_completed, false);
                 let total = storage_types::bytes_to_pretty(&total_bytes, false);
                 let speed = storage_types::bytes_to_pretty(&speed_bytes_per_sec, false);
-                content = content.push(caption(format!("{} / {} · {}/s", done, total, speed)));
+                let mut details = format!("{} / {} · {}/s", done, total, speed);
+                if let Some(eta) = state.eta {
+                    let left = if eta.min_secs == eta.max_secs {
+                        fl!("time-left", time = format_duration(eta.max_secs))
+                    } else {
+                        fl!(
+                            "time-left-range",
+                            min = format_duration(eta.min_secs),
+                            max = format_duration(eta.max_secs)
+                        )
+                    };
+                    details = format!("{details} · {left}");
+                }
+                content = content.push(caption(details));
             }
         }
     }
@@ -245,11 +351,27 @@ pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message>
         ImageOperationKind::RestoreToDrive | ImageOperationKind::RestoreToPartition => {
             fl!("restore-image")
         }
+        ImageOperationKind::BurnDisc => fl!("burn-image"),
+        ImageOperationKind::BlankDisc => fl!("erase"),
+        ImageOperationKind::CopyPartition => fl!("copy"),
+        ImageOperationKind::MigrateDisk => fl!("migrate"),
+        ImageOperationKind::CreateLiveUsb => fl!("create-live-usb"),
     };
 
+    // A live USB drive is only made once the user agreed to erasing it
+    let confirmed = state
+        .live_usb
+        .as_ref()
+        .is_none_or(|choices| choices.erase_confirmed);
+    if let Some(guard) = guard.filter(|_| !state.running) {
+        content = content.push(confirmation_guard(guard));
+    }
     let mut start_button = button::destructive(primary_label);
-    if !state.running {
-        start_button = start_button.on_press(ImageOperationDialogMessage::Start.into());
+    if !state.running
+        && confirmed
+        && let Some(message) = guarded(guard, ImageOperationDialogMessage::Start.into())
+    {
+        start_button = start_button.on_press(message);
     }
 
     let cancel_msg = ImageOperationDialogMessage::CancelOperation;
@@ -266,3 +388,162 @@ pub fn image_operation<'a>(state: ImageOperationDialog) -> Element<'a, Message>
 
     wizard_shell(caption(title.clone()).into(), content.into(), footer)
 }
+
+/// Target drive and partition sizes of a disk migration
+fn migration_choices<'a>(
+    mut content: iced_widget::Column<'a, Message>,
+    migration: &DiskMigration,
+    running: bool,
+) -> iced_widget::Column<'a, Message> {
+    content = content.push(caption_heading(fl!("migrate-disk-target")));
+    if migration.targets.is_empty() {
+        return content.push(caption(fl!("migrate-disk-no-targets")));
+    }
+    let labels = migration
+        .targets
+        .iter()
+        .map(|target| {
+            format!(
+                "{} ({}, {})",
+                target.display_name(),
+                target.device,
+                bytes_to_pretty(&target.size, false)
+            )
+        })
+        .collect::<Vec<_>>();
+    content = content.push(dropdown(labels, migration.target_index, |index| {
+        ImageOperationDialogMessage::SetMigrationTarget(index).into()
+    }));
+
+    let mut proportional = checkbox(fl!("migrate-disk-proportional"), migration.proportional);
+    if !running {
+        proportional = proportional
+            .on_toggle(|v| ImageOperationDialogMessage::SetMigrationProportional(v).into());
+    }
+    content = content
+        .push(proportional)
+        .push(caption_heading(fl!("partitions")));
+
+    let plan = migration.plan();
+    if let Some(Err(e)) = &plan {
+        content = content.push(caption(e.clone()));
+    }
+    let plan = plan.and_then(Result::ok);
+    for (index, partition) in migration.partitions.iter().enumerate() {
+        let name = if partition.name.trim().is_empty() {
+            format!("{} {}", fl!("partition"), partition.number)
+        } else {
+            partition.name.clone()
+        };
+        let old_size = bytes_to_pretty(&partition.size, false);
+        let size = match plan.as_ref().and_then(|plan| {
+            plan.partitions
+                .iter()
+                .find(|migrated| migrated.number == partition.number)
+        }) {
+            Some(migrated) if migrated.grows() => format!(
+                "{} → {}",
+                old_size,
+                bytes_to_pretty(&migrated.target.size(), false)
+            ),
+            _ => old_size,
+        };
+        let label = match partition.filesystem_type.as_deref() {
+            Some(fs_type) => format!("{name} · {fs_type} · {size}"),
+            None => format!("{name} · {size}"),
+        };
+        let grow = migration.proportional || migration.grow.get(index).copied().unwrap_or(false);
+        let mut row = checkbox(label, grow);
+        if !running && !migration.proportional {
+            row = row
+                .on_toggle(move |v| ImageOperationDialogMessage::SetMigrationGrow(index, v).into());
+        }
+        content = content.push(row);
+    }
+    content.push(caption(fl!("migrate-disk-warning")))
+}
+
+/// Advanced options: the priority of this operation alone
+fn priority_choice<'a>(
+    content: iced_widget::Column<'a, Message>,
+    priority: Option<OperationPriority>,
+) -> iced_widget::Column<'a, Message> {
+    let labels = std::iter::once(fl!("priority-configured"))
+        .chain(OperationPriority::PRESETS.iter().map(priority_label))
+        .collect::<Vec<_>>();
+    let selected = match priority {
+        Some(priority) => OperationPriority::PRESETS
+            .iter()
+            .position(|preset| *preset == priority)
+            .map(|index| index + 1),
+        None => Some(0),
+    };
+    content
+        .push(caption_heading(fl!("advanced-options")))
+        .push(caption(fl!("operation-priority")))
+        .push(dropdown(labels, selected, |index| {
+            ImageOperationDialogMessage::SetPriority(index.checked_sub(1)).into()
+        }))
+        .push(caption(fl!("operation-priority-override-help")))
+}
+
+/// Extra partitions of a live USB drive, and consent to erasing it
+fn live_usb_choices<'a>(
+    mut content: iced_widget::Column<'a, Message>,
+    choices: &LiveUsbChoices,
+    disk: &DiskInfo,
+    running: bool,
+) -> iced_widget::Column<'a, Message> {
+    let supported = match choices.iso.map(|iso| iso.distro) {
+        None => {
+            content = content.push(caption(fl!("live-usb-choose-iso")));
+            false
+        }
+        Some(LiveUsbDistro::Unsupported) => {
+            content = content.push(caption(fl!("live-usb-no-persistence")));
+            false
+        }
+        Some(_) => true,
+    };
+
+    let mut persistence = checkbox(
+        fl!("live-usb-persistence"),
+        choices.persistence && supported,
+    );
+    if !running && supported {
+        persistence =
+            persistence.on_toggle(|v| ImageOperationDialogMessage::SetLivePersistence(v).into());
+    }
+    content = content.push(persistence);
+    if choices.persistence && supported {
+        content = content.push(labelled_spinner(
+            fl!("live-usb-persistence-size"),
+            format!("{} GiB", choices.persistence_gib),
+            choices.persistence_gib as f64,
+            1.0,
+            1.0,
+            (disk.size / 1024_u64.pow(3)).max(1) as f64,
+            |v| ImageOperationDialogMessage::SetLivePersistenceSize(v as u64).into(),
+        ));
+    }
+
+    let mut data = checkbox(fl!("live-usb-data-partition"), choices.data_partition);
+    if !running {
+        data = data.on_toggle(|v| ImageOperationDialogMessage::SetLiveDataPartition(v).into());
+    }
+    content = content.push(data);
+
+    if let Some(Err(e)) = choices.validate(disk) {
+        content = content.push(caption(e));
+    }
+
+    content = content.push(caption(fl!("live-usb-warning")));
+    let mut consent = checkbox(
+        fl!("live-usb-erase-consent", drive = disk.display_name()),
+        choices.erase_confirmed,
+    );
+    if !running {
+        consent = consent.on_toggle(|v| ImageOperationDialogMessage::SetEraseConfirmed(v).into());
+    }
+    content.push(consent)
+}
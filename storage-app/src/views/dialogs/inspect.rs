@@ -0,0 +1,70 @@
+use super::sector_viewer::structure_name;
+use crate::app::Message;
+use crate::fl;
+use crate::message::dialogs::InspectDialogMessage;
+use crate::state::dialogs::InspectDialog;
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    iced_widget,
+    widget::text::{caption, caption_heading},
+    widget::{button, dialog, scrollable},
+};
+use storage_types::inspect::inspect as decode;
+
+pub fn inspect<'a>(state: InspectDialog) -> Element<'a, Message> {
+    let mut content = iced_widget::column![caption(state.device.clone())]
+        .spacing(8)
+        .width(Length::Fill);
+
+    match state.ranges.as_ref() {
+        Some(ranges) => {
+            let structures = decode(ranges);
+            if structures.is_empty() {
+                content = content.push(caption(fl!("inspect-nothing-found")));
+            }
+
+            let mut sections = iced_widget::column![].spacing(12);
+            for structure in structures {
+                let heading = iced_widget::row![
+                    caption_heading(fl!(
+                        "inspect-structure",
+                        structure = structure_name(structure.structure),
+                        offset = format!("{:#x}", structure.offset)
+                    ))
+                    .width(Length::Fill),
+                    button::text(fl!("view-sectors"))
+                        .on_press(InspectDialogMessage::ViewSectors(structure.offset).into()),
+                ]
+                .align_y(Alignment::Center);
+
+                let mut fields = iced_widget::column![heading].spacing(2);
+                for field in structure.fields {
+                    fields = fields.push(
+                        iced_widget::row![
+                            caption(field.name).width(Length::FillPortion(2)),
+                            caption(field.value).width(Length::FillPortion(3)),
+                        ]
+                        .spacing(12),
+                    );
+                }
+                sections = sections.push(fields);
+            }
+            content = content.push(scrollable(sections).height(360));
+        }
+        None if state.error.is_none() => content = content.push(caption(fl!("working"))),
+        None => {}
+    }
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    dialog::dialog()
+        .title(fl!("inspect-volume"))
+        .control(content)
+        .secondary_action(
+            button::standard(fl!("close")).on_press(InspectDialogMessage::Close.into()),
+        )
+        .into()
+}
@@ -0,0 +1,94 @@
+use crate::app::Message;
+use crate::fl;
+use crate::message::dialogs::LowSpaceDialogMessage;
+use crate::state::dialogs::{LOW_SPACE_BYTES_CHOICES, LOW_SPACE_PERCENT_CHOICES, LowSpaceDialog};
+use cosmic::{
+    Element,
+    iced::Length,
+    iced_widget,
+    widget::dropdown,
+    widget::text::{caption, caption_heading},
+    widget::{button, dialog},
+};
+use storage_types::{LowSpaceAction, bytes_to_pretty};
+
+pub fn low_space<'a>(state: LowSpaceDialog) -> Element<'a, Message> {
+    let mut content = iced_widget::column![caption(state.mount_point.clone())]
+        .spacing(8)
+        .width(Length::Fill);
+
+    match state.rule.as_ref() {
+        Some(rule) => {
+            let percents = LOW_SPACE_PERCENT_CHOICES
+                .iter()
+                .map(|percent| match percent {
+                    None => fl!("low-space-off"),
+                    Some(percent) => fl!("low-space-percent", percent = *percent),
+                })
+                .collect::<Vec<_>>();
+            let selected = LOW_SPACE_PERCENT_CHOICES
+                .iter()
+                .position(|percent| *percent == rule.min_free_percent);
+            content = content
+                .push(caption_heading(fl!("low-space-min-percent")))
+                .push(dropdown(percents, selected, |index| {
+                    LowSpaceDialogMessage::SetPercent(index).into()
+                }));
+
+            let amounts = LOW_SPACE_BYTES_CHOICES
+                .iter()
+                .map(|bytes| match bytes {
+                    None => fl!("low-space-off"),
+                    Some(bytes) => bytes_to_pretty(bytes, false),
+                })
+                .collect::<Vec<_>>();
+            let selected = LOW_SPACE_BYTES_CHOICES
+                .iter()
+                .position(|bytes| *bytes == rule.min_free_bytes);
+            content = content
+                .push(caption_heading(fl!("low-space-min-bytes")))
+                .push(dropdown(amounts, selected, |index| {
+                    LowSpaceDialogMessage::SetBytes(index).into()
+                }));
+
+            let actions = LowSpaceAction::ALL
+                .iter()
+                .map(|action| match action {
+                    LowSpaceAction::Warn => fl!("low-space-action-warn"),
+                    LowSpaceAction::Cleanup => fl!("low-space-action-cleanup"),
+                    LowSpaceAction::UsageScan => fl!("low-space-action-scan"),
+                })
+                .collect::<Vec<_>>();
+            let selected = LowSpaceAction::ALL
+                .iter()
+                .position(|action| *action == rule.action);
+            content = content
+                .push(caption_heading(fl!("low-space-action")))
+                .push(dropdown(actions, selected, |index| {
+                    LowSpaceDialogMessage::SetAction(index).into()
+                }))
+                .push(caption(fl!("low-space-help")));
+        }
+        None if state.error.is_none() => content = content.push(caption(fl!("working"))),
+        None => {}
+    }
+
+    if state.running {
+        content = content.push(caption(fl!("working")));
+    }
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    let mut close = button::standard(fl!("close"));
+    if !state.running {
+        close = close.on_press(LowSpaceDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("low-space"))
+        .control(content)
+        .primary_action(close)
+        .into()
+}
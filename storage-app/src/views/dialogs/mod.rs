@@ -1,19 +1,38 @@
 mod btrfs;
 mod common;
+mod defrag;
+mod diagnostics;
 mod disk;
+mod encrypted_folders;
 mod encryption;
+mod esp_sync;
 mod image;
+mod inspect;
+mod low_space;
 mod mount;
+mod network;
 mod partition;
+mod performance;
+mod sector_viewer;
 
-pub use btrfs::{create_snapshot, create_subvolume};
-pub use common::{confirmation, info};
-pub use disk::{format_disk, smart_data};
+pub use btrfs::{create_snapshot, create_subvolume, subvolume_properties};
+pub use common::{confirmation, confirmation_guard, guarded, info};
+pub use defrag::defragment;
+pub use diagnostics::{diagnostics, error};
+pub use disk::{format_disk, gpt_entries, lost_partitions, set_up_drive, smart_data};
+pub use encrypted_folders::encrypted_folders;
 pub use encryption::{
     change_passphrase, edit_encryption_options, take_ownership, unlock_encrypted,
 };
+pub use esp_sync::esp_sync;
 pub use image::{attach_disk_image, image_operation, new_disk_image};
-pub use mount::{edit_mount_options, unmount_busy};
+pub use inspect::inspect;
+pub use low_space::low_space;
+pub use mount::{automount, edit_mount_options, new_bind_mount, unmount_busy};
+pub use network::rclone_config_password;
 pub use partition::{
-    create_partition, edit_filesystem_label, edit_partition, format_partition, resize_partition,
+    create_partition, edit_filesystem_label, edit_partition, format_partition, move_partition,
+    resize_partition,
 };
+pub use performance::performance;
+pub use sector_viewer::sector_viewer;
@@ -1,15 +1,25 @@
 use crate::app::Message;
 use crate::controls::wizard::{
-    WizardBreadcrumbStatus, WizardBreadcrumbStep, wizard_breadcrumb, wizard_step_is_clickable,
-    wizard_step_nav, wizard_step_shell,
+    WizardBreadcrumbStatus, WizardBreadcrumbStep, wizard_action_row, wizard_breadcrumb,
+    wizard_shell, wizard_step_is_clickable, wizard_step_nav, wizard_step_shell,
 };
 use crate::fl;
-use crate::message::dialogs::{EditMountOptionsMessage, UnmountBusyMessage};
-use crate::state::dialogs::{EditMountOptionsDialog, EditMountOptionsStep, UnmountBusyDialog};
+use crate::message::dialogs::{
+    AutomountDialogMessage, BindMountDialogMessage, EditMountOptionsMessage, UnmountBusyMessage,
+};
+use crate::state::dialogs::{
+    AutomountDialog, EditMountOptionsDialog, EditMountOptionsStep, NewBindMountDialog,
+    UnmountBusyDialog,
+};
 use cosmic::{
-    Element, iced_widget,
+    Element, Theme,
+    iced::{Alignment, Length},
+    iced_widget,
     widget::text::{caption, caption_heading},
-    widget::{button, checkbox, dialog, dropdown, scrollable, text_input},
+    widget::{button, checkbox, container, dialog, dropdown, scrollable, text_input},
+};
+use storage_types::{
+    IDLE_TIMEOUT_PRESETS, MountLint, MountLintSeverity, lint_mount_options, mount_option_presets,
 };
 
 pub fn edit_mount_options<'a>(state: EditMountOptionsDialog) -> Element<'a, Message> {
@@ -28,10 +38,14 @@ pub fn edit_mount_options<'a>(state: EditMountOptionsDialog) -> Element<'a, Mess
         identify_as_options,
         identify_as_index,
         filesystem_type,
+        preset,
+        lint_context,
         error,
         running,
     } = state;
 
+    let lints = lint_mount_options(filesystem_type.trim(), &other_options, &lint_context);
+
     let other_options_for_input = other_options.clone();
     let mount_point_for_input = mount_point.clone();
     let filesystem_type_for_input = filesystem_type.clone();
@@ -126,7 +140,37 @@ pub fn edit_mount_options<'a>(state: EditMountOptionsDialog) -> Element<'a, Mess
             content = content
                 .push(caption_heading(fl!("identify-as")))
                 .push(identify_dropdown)
-                .push(other_opts_input)
+                .push(other_opts_input);
+            let presets: Vec<_> = mount_option_presets(filesystem_type.trim()).collect();
+            if controls_enabled && !running && !presets.is_empty() {
+                let selected = preset
+                    .as_deref()
+                    .and_then(|id| presets.iter().position(|preset| preset.id == id))
+                    .map_or(0, |index| index + 1);
+                let mut labels = vec![fl!("mount-preset-none")];
+                labels.extend(presets.iter().map(|preset| preset.name.to_string()));
+                let ids: Vec<&'static str> = presets.iter().map(|preset| preset.id).collect();
+                content = content.push(caption(fl!("mount-preset"))).push(dropdown(
+                    labels,
+                    Some(selected),
+                    move |index| {
+                        EditMountOptionsMessage::PresetUpdate(
+                            index
+                                .checked_sub(1)
+                                .and_then(|index| ids.get(index))
+                                .map(|id| id.to_string()),
+                        )
+                        .into()
+                    },
+                ));
+                if let Some(preset) = selected.checked_sub(1).and_then(|index| presets.get(index)) {
+                    content = content.push(caption(preset.description));
+                }
+            }
+            if controls_enabled {
+                content = push_lints(content, &lints);
+            }
+            content = content
                 .push(mount_point_input)
                 .push(fs_type_input)
                 .push(display_name_input)
@@ -162,6 +206,9 @@ pub fn edit_mount_options<'a>(state: EditMountOptionsDialog) -> Element<'a, Mess
                     fl!("filesystem-type"),
                     filesystem_type
                 )));
+            if !use_defaults {
+                content = push_lints(content, &lints);
+            }
         }
     }
 
@@ -176,7 +223,10 @@ pub fn edit_mount_options<'a>(state: EditMountOptionsDialog) -> Element<'a, Mess
         || (!running
             && !mount_point.trim().is_empty()
             && !filesystem_type.trim().is_empty()
-            && !other_options.trim().is_empty());
+            && !other_options.trim().is_empty()
+            && lints
+                .iter()
+                .all(|lint| lint.severity != MountLintSeverity::Error));
 
     let current_number = step.number();
     let steps = [
@@ -253,6 +303,33 @@ pub fn edit_mount_options<'a>(state: EditMountOptionsDialog) -> Element<'a, Mess
     )
 }
 
+/// Problems found in the mount options, errors first
+fn push_lints<'a>(
+    mut content: iced_widget::Column<'a, Message>,
+    lints: &[MountLint],
+) -> iced_widget::Column<'a, Message> {
+    let mut lints = lints.to_vec();
+    lints.sort_by(|a, b| b.severity.cmp(&a.severity));
+    for lint in lints {
+        let error = lint.severity == MountLintSeverity::Error;
+        let prefix = if error { "✕" } else { "⚠" };
+        content = content.push(
+            container(caption(format!("{prefix} {}", lint.message))).style(move |theme: &Theme| {
+                let cosmic = theme.cosmic();
+                container::Style {
+                    text_color: Some(if error {
+                        cosmic.destructive_color().into()
+                    } else {
+                        cosmic.warning_color().into()
+                    }),
+                    ..Default::default()
+                }
+            }),
+        );
+    }
+    content
+}
+
 pub fn unmount_busy<'a>(state: UnmountBusyDialog) -> Element<'a, Message> {
     let UnmountBusyDialog {
         device,
@@ -340,3 +417,147 @@ pub fn unmount_busy<'a>(state: UnmountBusyDialog) -> Element<'a, Message> {
 
     dlg.into()
 }
+
+pub fn new_bind_mount<'a>(state: NewBindMountDialog) -> Element<'a, Message> {
+    let NewBindMountDialog {
+        source,
+        target,
+        read_only,
+        persistent,
+        running,
+        error,
+    } = state;
+
+    let can_create = !running && !source.trim().is_empty() && !target.trim().is_empty();
+
+    let mut content = iced_widget::column![
+        text_input("/home/user/Music", source)
+            .label(fl!("bind-source"))
+            .on_input(|t| BindMountDialogMessage::SourceUpdate(t).into()),
+        text_input("/srv/music", target)
+            .label(fl!("bind-target"))
+            .on_input(|t| BindMountDialogMessage::TargetUpdate(t).into()),
+        checkbox(fl!("bind-read-only"), read_only)
+            .on_toggle(|v| BindMountDialogMessage::ReadOnlyUpdate(v).into()),
+        checkbox(fl!("bind-persistent"), persistent)
+            .on_toggle(|v| BindMountDialogMessage::PersistentUpdate(v).into()),
+    ]
+    .spacing(12);
+
+    if running {
+        content = content.push(caption(fl!("working")));
+    }
+
+    if let Some(error) = error {
+        content = content.push(caption(error));
+    }
+
+    let mut create_button = button::suggested(fl!("bind-create"));
+    if can_create {
+        create_button = create_button.on_press(BindMountDialogMessage::Create.into());
+    }
+
+    let footer = wizard_action_row(
+        vec![],
+        vec![
+            button::standard(fl!("cancel"))
+                .on_press(BindMountDialogMessage::Cancel.into())
+                .into(),
+            create_button.into(),
+        ],
+    );
+
+    wizard_shell(
+        caption(fl!("new-bind-mount")).into(),
+        content.into(),
+        footer,
+    )
+}
+
+pub fn automount<'a>(state: AutomountDialog) -> Element<'a, Message> {
+    let AutomountDialog {
+        unit,
+        units,
+        running,
+        error,
+    } = state;
+
+    let timeouts = IDLE_TIMEOUT_PRESETS
+        .iter()
+        .map(|secs| match secs {
+            0 => fl!("automount-idle-never"),
+            secs => fl!("automount-idle-minutes", minutes = secs / 60),
+        })
+        .collect::<Vec<_>>();
+    let selected = IDLE_TIMEOUT_PRESETS
+        .iter()
+        .position(|secs| *secs == unit.idle_timeout_secs);
+
+    let mut content = iced_widget::column![
+        caption(unit.what.clone()),
+        text_input("/mnt/data", unit.mount_point.to_string_lossy().to_string())
+            .label(fl!("automount-mount-point"))
+            .on_input(|t| AutomountDialogMessage::MountPointUpdate(t).into()),
+        text_input("nofail", unit.options.clone())
+            .label(fl!("automount-options"))
+            .on_input(|t| AutomountDialogMessage::OptionsUpdate(t).into()),
+        caption_heading(fl!("automount-idle-timeout")),
+        dropdown(timeouts, selected, |index| {
+            AutomountDialogMessage::SetIdleTimeout(index).into()
+        }),
+        caption(fl!("automount-help")),
+    ]
+    .spacing(8);
+
+    match units {
+        Some(units) if !units.is_empty() => {
+            content = content.push(caption_heading(fl!("automount-units")));
+            for existing in units {
+                let mut remove = button::destructive(fl!("automount-remove"));
+                if !running {
+                    remove = remove.on_press(
+                        AutomountDialogMessage::Remove(existing.mount_point.clone()).into(),
+                    );
+                }
+                content = content.push(
+                    iced_widget::row![
+                        caption(format!(
+                            "{} → {}",
+                            existing.what,
+                            existing.mount_point.display()
+                        ))
+                        .width(Length::Fill),
+                        remove,
+                    ]
+                    .spacing(8)
+                    .align_y(Alignment::Center),
+                );
+            }
+        }
+        Some(_) => {}
+        None if error.is_none() => content = content.push(caption(fl!("working"))),
+        None => {}
+    }
+
+    if running {
+        content = content.push(caption(fl!("working")));
+    }
+
+    if let Some(error) = error {
+        content = content.push(caption(error));
+    }
+
+    let mut create = button::suggested(fl!("automount-create"));
+    let mut close = button::standard(fl!("close"));
+    if !running {
+        create = create.on_press(AutomountDialogMessage::Create.into());
+        close = close.on_press(AutomountDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("automount"))
+        .control(content)
+        .primary_action(create)
+        .secondary_action(close)
+        .into()
+}
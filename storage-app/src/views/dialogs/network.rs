@@ -0,0 +1,68 @@
+use crate::app::Message;
+use crate::controls::icons::scope_label;
+use crate::message::network::NetworkMessage;
+use crate::state::dialogs::RcloneConfigPasswordDialog;
+use cosmic::{
+    Element, iced_widget,
+    widget::text::{body, caption},
+    widget::{button, dialog, text_input},
+};
+
+/// Password prompt to unlock or encrypt an rclone configuration
+pub fn rclone_config_password<'a>(state: RcloneConfigPasswordDialog) -> Element<'a, Message> {
+    let scope = scope_label(state.scope);
+    let (title, description, confirm_label) = if state.encrypt {
+        (
+            format!("Encrypt {scope} Configuration"),
+            "Remotes in this configuration can only be used after entering the password, \
+             so they cannot be mounted automatically.",
+            "Encrypt",
+        )
+    } else {
+        (
+            format!("Unlock {scope} Configuration"),
+            "The configuration is encrypted. The password is kept until you lock it again \
+             or the storage service restarts.",
+            "Unlock",
+        )
+    };
+
+    let mut content = iced_widget::column![
+        body(description),
+        text_input::secure_input("", state.password.clone(), None, true)
+            .label("Password")
+            .on_input(|v| Message::Network(NetworkMessage::ConfigPasswordChanged(v)))
+            .on_submit(|_| Message::Network(NetworkMessage::ConfirmConfigPassword)),
+    ]
+    .spacing(12);
+
+    if state.encrypt {
+        content = content.push(
+            text_input::secure_input("", state.confirm.clone(), None, true)
+                .label("Repeat Password")
+                .on_input(|v| Message::Network(NetworkMessage::ConfigPasswordConfirmChanged(v)))
+                .on_submit(|_| Message::Network(NetworkMessage::ConfirmConfigPassword)),
+        );
+    }
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    if state.running {
+        content = content.push(caption("Working..."));
+    }
+
+    let mut confirm_button = button::suggested(confirm_label);
+    if !state.running {
+        confirm_button =
+            confirm_button.on_press(Message::Network(NetworkMessage::ConfirmConfigPassword));
+    }
+
+    dialog::dialog()
+        .title(title)
+        .control(content)
+        .primary_action(confirm_button)
+        .secondary_action(button::standard("Cancel").on_press(Message::CloseDialog))
+        .into()
+}
@@ -1,3 +1,4 @@
+use super::common::{confirmation_guard, guarded};
 use crate::app::Message;
 use crate::controls::fields::labelled_spinner;
 use crate::controls::wizard::{
@@ -6,12 +7,13 @@ use crate::controls::wizard::{
 };
 use crate::fl;
 use crate::message::dialogs::{
-    CreateMessage, EditFilesystemLabelMessage, EditPartitionMessage, ResizePartitionMessage,
+    CreateMessage, EditFilesystemLabelMessage, EditPartitionMessage, MovePartitionMessage,
+    ResizePartitionMessage,
 };
 use crate::state::dialogs::{
-    CreatePartitionDialog, CreatePartitionStep, EditFilesystemLabelDialog, EditPartitionDialog,
-    EditPartitionStep, FormatPartitionDialog, FormatPartitionStep, ResizePartitionDialog,
-    ResizePartitionStep,
+    ConfirmationGuard, CreatePartitionDialog, CreatePartitionStep, EditFilesystemLabelDialog,
+    EditPartitionDialog, EditPartitionStep, FormatPartitionDialog, FormatPartitionStep,
+    MovePartitionDialog, ResizePartitionDialog, ResizePartitionStep,
 };
 use crate::utils::SizeUnit;
 use cosmic::{
@@ -20,7 +22,10 @@ use cosmic::{
     widget::{button, checkbox, container, dialog, divider, dropdown, slider, text, text_input},
 };
 use storage_types::{
-    COMMON_DOS_TYPES, COMMON_GPT_TYPES, FilesystemToolInfo, PartitionTypeInfo, bytes_to_pretty,
+    COMMON_DOS_TYPES, COMMON_GPT_TYPES, FilesystemToolInfo, FormatOptionKind, FormatOptionSpec,
+    FormatOptions, GPT_ALIGNMENT_BYTES, MoveBounds, PartitionTypeInfo, ResizeBlocker, ResizeBounds,
+    ResizeStep, bytes_to_pretty, format_option_schema,
+    interop::{self, Access, OperatingSystem},
 };
 
 /// Check if a filesystem tool is available from the tools list
@@ -330,13 +335,171 @@ pub fn create_partition<'a>(state: CreatePartitionDialog) -> Element<'a, Message
     )
 }
 
-pub fn format_partition<'a>(state: FormatPartitionDialog) -> Element<'a, Message> {
+/// Input for one filesystem-specific format option; unset means mkfs default
+fn format_option_field<'a>(
+    spec: &'static FormatOptionSpec,
+    value: Option<&String>,
+) -> Element<'a, Message> {
+    let key = spec.key;
+    let value = value.cloned().unwrap_or_default();
+
+    let field: Element<'a, Message> = match spec.kind {
+        FormatOptionKind::Flag => {
+            let selected = match value.as_str() {
+                "1" => 1,
+                "0" => 2,
+                _ => 0,
+            };
+            dropdown(
+                vec![
+                    fl!("format-option-default"),
+                    fl!("format-option-enabled"),
+                    fl!("format-option-disabled"),
+                ],
+                Some(selected),
+                move |index| {
+                    let value = match index {
+                        1 => "1",
+                        2 => "0",
+                        _ => "",
+                    };
+                    CreateMessage::FormatOptionUpdate(key.to_string(), value.to_string()).into()
+                },
+            )
+            .into()
+        }
+        FormatOptionKind::Choice(choices) => {
+            let selected = choices
+                .iter()
+                .position(|choice| *choice == value)
+                .map_or(0, |index| index + 1);
+            let mut labels = vec![fl!("format-option-default")];
+            labels.extend(choices.iter().map(|choice| choice.to_string()));
+            dropdown(labels, Some(selected), move |index| {
+                let value = index
+                    .checked_sub(1)
+                    .and_then(|index| choices.get(index))
+                    .copied()
+                    .unwrap_or_default();
+                CreateMessage::FormatOptionUpdate(key.to_string(), value.to_string()).into()
+            })
+            .into()
+        }
+        FormatOptionKind::Integer { .. } | FormatOptionKind::FeatureList(_) => {
+            text_input(fl!("format-option-default"), value)
+                .on_input(move |v| CreateMessage::FormatOptionUpdate(key.to_string(), v).into())
+                .into()
+        }
+    };
+
+    iced_widget::column![text(spec.label), field, caption(spec.help)]
+        .spacing(4)
+        .into()
+}
+
+/// Translated name and description of a built-in format preset
+fn format_preset_text(id: &str) -> (String, String) {
+    match id {
+        "ext4-fast-commit" => (
+            fl!("format-preset-ext4-fast-commit"),
+            fl!("format-preset-ext4-fast-commit-description"),
+        ),
+        "ext4-data-disk" => (
+            fl!("format-preset-ext4-data-disk"),
+            fl!("format-preset-ext4-data-disk-description"),
+        ),
+        "xfs-reflink" => (
+            fl!("format-preset-xfs-reflink"),
+            fl!("format-preset-xfs-reflink-description"),
+        ),
+        "btrfs-zstd" => (
+            fl!("format-preset-btrfs-zstd"),
+            fl!("format-preset-btrfs-zstd-description"),
+        ),
+        "vfat-32k-clusters" => (
+            fl!("format-preset-vfat-32k-clusters"),
+            fl!("format-preset-large-clusters-description"),
+        ),
+        "exfat-128k-clusters" => (
+            fl!("format-preset-exfat-128k-clusters"),
+            fl!("format-preset-camera-clusters-description"),
+        ),
+        "ntfs-64k-clusters" => (
+            fl!("format-preset-ntfs-64k-clusters"),
+            fl!("format-preset-large-clusters-description"),
+        ),
+        _ => (id.to_string(), String::new()),
+    }
+}
+
+/// Which systems can use `fs_type`, what limits it has, and a better choice
+/// for removable drives
+fn interop_hints<'a>(fs_type: &str, size: u64, removable: bool) -> Element<'a, Message> {
+    let warning = |message: String| {
+        container(caption(format!("⚠ {message}"))).style(|theme: &Theme| container::Style {
+            text_color: Some(theme.cosmic().warning_color().into()),
+            ..Default::default()
+        })
+    };
+
+    let mut hints = iced_widget::column![].spacing(4);
+
+    let access: Vec<String> = OperatingSystem::ALL
+        .iter()
+        .filter_map(|&os| {
+            let name = match os {
+                OperatingSystem::Windows => fl!("interop-windows"),
+                OperatingSystem::MacOs => fl!("interop-macos"),
+                OperatingSystem::Linux => fl!("interop-linux"),
+            };
+            let access = match interop::access(fs_type, os)? {
+                Access::ReadWrite => fl!("interop-read-write"),
+                Access::ReadOnly => fl!("interop-read-only"),
+                Access::None => fl!("interop-unsupported"),
+            };
+            Some(format!("{name}: {access}"))
+        })
+        .collect();
+    if !access.is_empty() {
+        hints = hints.push(caption(access.join(" · ")));
+    }
+
+    if interop::suggested_filesystem(fs_type, removable).is_some() {
+        hints = hints.push(caption(fl!("interop-suggest-exfat")));
+    }
+
+    if let Some(max) = interop::max_file_size(fs_type) {
+        hints = hints.push(warning(fl!(
+            "interop-max-file-size",
+            size = bytes_to_pretty(&(max + 1), false)
+        )));
+    }
+
+    if let Some(max) = interop::max_volume_size(fs_type)
+        && size > max
+    {
+        hints = hints.push(warning(fl!(
+            "interop-volume-too-large",
+            max = bytes_to_pretty(&max, false)
+        )));
+    }
+
+    hints.into()
+}
+
+pub fn format_partition<'a>(
+    state: FormatPartitionDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
     let FormatPartitionDialog {
         volume: _,
         info: create,
         step,
         running,
         filesystem_tools,
+        format_preset,
+        fs_specific,
+        removable,
     } = state;
 
     let size_pretty = bytes_to_pretty(&create.size, false);
@@ -396,6 +559,12 @@ pub fn format_partition<'a>(state: FormatPartitionDialog) -> Element<'a, Message
         original_idx == Some(create.selected_partition_type_index)
     });
 
+    let fs_type = partition_types
+        .get(create.selected_partition_type_index)
+        .map(|p_type| p_type.filesystem_type.clone())
+        .unwrap_or_default();
+    let fits_volume = interop::validate_volume_size(&fs_type, create.size).is_ok();
+
     if step == FormatPartitionStep::Basics {
         if create.table_type != "dos" {
             content = content.push(
@@ -429,17 +598,82 @@ pub fn format_partition<'a>(state: FormatPartitionDialog) -> Element<'a, Message
             );
             content = content.push(warning_text);
         }
+
+        content = content.push(interop_hints(&fs_type, create.size, removable));
     } else {
         content = content.push(
             checkbox(fl!("overwrite-data-slow"), create.erase)
                 .on_toggle(|v| CreateMessage::EraseUpdate(v).into()),
         );
+
+        let presets = FormatOptions::presets(&fs_type);
+        let schema = format_option_schema(&fs_type);
+
+        if !presets.is_empty() || !schema.is_empty() {
+            content = content.push(divider::horizontal::default());
+            content = content.push(caption(fl!("format-advanced")));
+        }
+
+        if !presets.is_empty() {
+            let selected_preset = format_preset
+                .as_ref()
+                .and_then(|id| presets.iter().position(|preset| &preset.id == id))
+                .map_or(0, |index| index + 1);
+            let description = selected_preset
+                .checked_sub(1)
+                .and_then(|index| presets.get(index))
+                .map(|preset| format_preset_text(&preset.id).1);
+
+            let mut preset_labels = vec![fl!("format-preset-none")];
+            preset_labels.extend(
+                presets
+                    .iter()
+                    .map(|preset| format_preset_text(&preset.id).0),
+            );
+            let preset_ids: Vec<String> = presets.into_iter().map(|preset| preset.id).collect();
+
+            content = content.push(text(fl!("format-preset")));
+            content = content.push(dropdown(
+                preset_labels,
+                Some(selected_preset),
+                move |index| {
+                    CreateMessage::FormatPresetUpdate(
+                        index
+                            .checked_sub(1)
+                            .and_then(|index| preset_ids.get(index).cloned()),
+                    )
+                    .into()
+                },
+            ));
+            if let Some(description) = description {
+                content = content.push(caption(description));
+            }
+        }
+
+        for spec in schema {
+            content = content.push(format_option_field(spec, fs_specific.get(spec.key)));
+        }
+
+        let options = FormatOptions {
+            fs_specific,
+            ..Default::default()
+        };
+        if let Err(e) = options.validate(&fs_type) {
+            content = content.push(container(caption(format!("⚠ {e}"))).style(|theme: &Theme| {
+                container::Style {
+                    text_color: Some(theme.cosmic().warning_color().into()),
+                    ..Default::default()
+                }
+            }));
+        }
     }
 
     content = content.spacing(12);
 
     if running {
         content = content.push(caption(fl!("working")));
+    } else if let Some(guard) = guard.filter(|_| step == FormatPartitionStep::Options) {
+        content = content.push(confirmation_guard(guard));
     }
 
     let current_number = step.number();
@@ -484,7 +718,7 @@ pub fn format_partition<'a>(state: FormatPartitionDialog) -> Element<'a, Message
     let (primary_label, primary_message) = if step == FormatPartitionStep::Basics {
         (
             "Next".to_string(),
-            if selected_in_filtered.is_some() && !running {
+            if selected_in_filtered.is_some() && fits_volume && !running {
                 Some(CreateMessage::NextStep.into())
             } else {
                 None
@@ -493,8 +727,8 @@ pub fn format_partition<'a>(state: FormatPartitionDialog) -> Element<'a, Message
     } else {
         (
             fl!("apply"),
-            if !running {
-                Some(CreateMessage::Partition.into())
+            if fits_volume && !running {
+                guarded(guard, CreateMessage::Partition.into())
             } else {
                 None
             },
@@ -671,13 +905,157 @@ pub fn edit_partition<'a>(state: EditPartitionDialog) -> Element<'a, Message> {
     )
 }
 
-pub fn resize_partition<'a>(state: ResizePartitionDialog) -> Element<'a, Message> {
+/// The partition at its new size within the space it can take: what it
+/// keeps, what it gains or gives up, and the free space left after it
+fn resize_preview<'a>(current: u64, new: u64, max: u64) -> Element<'a, Message> {
+    let total = max.max(current).max(new).max(1);
+    let portion = |bytes: u64| ((bytes as f64 / total as f64) * 1000.0).round().max(1.0) as u16;
+    let segment = |bytes: u64, color: fn(&Theme) -> iced::Color| {
+        container(iced_widget::Space::new(
+            iced::Length::Fill,
+            iced::Length::Fixed(24.0),
+        ))
+        .style(move |theme: &Theme| container::Style {
+            background: Some(iced::Background::Color(color(theme))),
+            ..Default::default()
+        })
+        .width(iced::Length::FillPortion(portion(bytes)))
+    };
+
+    let mut bar = iced_widget::row![].spacing(0).width(iced::Length::Fill);
+    let kept = current.min(new);
+    if kept > 0 {
+        bar = bar.push(segment(kept, |theme| theme.cosmic().accent_color().into()));
+    }
+    if new > current {
+        bar = bar.push(segment(new - current, |theme| {
+            theme.cosmic().success_color().into()
+        }));
+    } else if current > new {
+        bar = bar.push(segment(current - new, |theme| {
+            theme.cosmic().warning_color().into()
+        }));
+    }
+    let free = total - current.max(new);
+    if free > 0 {
+        bar = bar.push(segment(free, |theme| {
+            theme.cosmic().background.component.divider.into()
+        }));
+    }
+
+    iced_widget::column![
+        bar,
+        caption(fl!(
+            "resize-partition-preview",
+            current = bytes_to_pretty(&current, false),
+            new = bytes_to_pretty(&new, false),
+            free = bytes_to_pretty(&(max.saturating_sub(new)), false)
+        )),
+    ]
+    .spacing(4)
+    .into()
+}
+
+/// Why the partition can't shrink, or its filesystem won't grow with it
+fn resize_notes<'a>(bounds: Option<&ResizeBounds>, loading: bool) -> Element<'a, Message> {
+    let warning = |message: String| {
+        container(caption(format!("⚠ {message}"))).style(|theme: &Theme| container::Style {
+            text_color: Some(theme.cosmic().warning_color().into()),
+            ..Default::default()
+        })
+    };
+
+    let mut notes = iced_widget::column![].spacing(4);
+    match bounds {
+        _ if loading => notes = notes.push(caption(fl!("resize-partition-checking"))),
+        None => notes = notes.push(warning(fl!("resize-partition-grow-only"))),
+        Some(bounds) => {
+            let fs_type = bounds.filesystem_type.clone().unwrap_or_default();
+            match bounds.shrink_blocker {
+                Some(ResizeBlocker::Unsupported) => {
+                    notes = notes.push(warning(fl!(
+                        "resize-partition-shrink-unsupported",
+                        fs_type = fs_type.clone()
+                    )))
+                }
+                Some(ResizeBlocker::Mounted) => {
+                    notes = notes.push(warning(fl!("resize-partition-shrink-mounted")))
+                }
+                Some(ResizeBlocker::UnknownMinimum) => {
+                    notes = notes.push(warning(fl!("resize-partition-shrink-unknown")))
+                }
+                None => {}
+            }
+            match bounds.grow_blocker {
+                Some(ResizeBlocker::Mounted) => {
+                    notes = notes.push(warning(fl!("resize-partition-grow-mounted")))
+                }
+                Some(_) => {
+                    notes = notes.push(warning(fl!(
+                        "resize-partition-grow-unsupported",
+                        fs_type = fs_type
+                    )))
+                }
+                None => {}
+            }
+        }
+    }
+    notes.into()
+}
+
+/// What the resize will do, in order
+fn resize_plan<'a>(bounds: Option<&ResizeBounds>, new_size: u64) -> Element<'a, Message> {
+    let steps = match bounds {
+        Some(bounds) => bounds.plan(new_size),
+        None => Ok(vec![ResizeStep::ResizePartition { size: new_size }]),
+    };
+    let steps = match steps {
+        Ok(steps) => steps,
+        Err(e) => {
+            return container(caption(format!("⚠ {e}")))
+                .style(|theme: &Theme| container::Style {
+                    text_color: Some(theme.cosmic().warning_color().into()),
+                    ..Default::default()
+                })
+                .into();
+        }
+    };
+
+    steps
+        .iter()
+        .enumerate()
+        .fold(
+            iced_widget::column![].spacing(4),
+            |column, (index, step)| {
+                let label = match step {
+                    ResizeStep::ShrinkFilesystem { size } => fl!(
+                        "resize-step-shrink-filesystem",
+                        size = bytes_to_pretty(size, false)
+                    ),
+                    ResizeStep::ResizePartition { size } => fl!(
+                        "resize-step-resize-partition",
+                        size = bytes_to_pretty(size, false)
+                    ),
+                    ResizeStep::GrowFilesystem => fl!("resize-step-grow-filesystem"),
+                };
+                column.push(caption(format!("{}. {label}", index + 1)))
+            },
+        )
+        .into()
+}
+
+pub fn resize_partition<'a>(
+    state: ResizePartitionDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
     let ResizePartitionDialog {
-        volume: _,
+        volume,
         step: wizard_step,
         min_size_bytes,
         max_size_bytes,
         new_size_bytes,
+        bounds,
+        loading_bounds,
         running,
     } = state;
 
@@ -690,7 +1068,7 @@ pub fn resize_partition<'a>(state: ResizePartitionDialog) -> Element<'a, Message
     let max_pretty = bytes_to_pretty(&max_size_bytes, false);
     let value_pretty = bytes_to_pretty(&new_size_bytes, false);
 
-    let can_resize = max_size_bytes.saturating_sub(min_size_bytes) >= 1024;
+    let can_resize = !loading_bounds && max_size_bytes.saturating_sub(min_size_bytes) >= 1024;
 
     let mut content = iced_widget::column![].spacing(12);
 
@@ -712,7 +1090,9 @@ pub fn resize_partition<'a>(state: ResizePartitionDialog) -> Element<'a, Message
                 min,
                 max,
                 |v| ResizePartitionMessage::SizeUpdate(v as u64).into(),
-            ));
+            ))
+            .push(resize_preview(volume.size, new_size_bytes, max_size_bytes))
+            .push(resize_notes(bounds.as_ref(), loading_bounds));
     } else {
         content = content
             .push(caption(fl!(
@@ -720,11 +1100,17 @@ pub fn resize_partition<'a>(state: ResizePartitionDialog) -> Element<'a, Message
                 min = min_pretty,
                 max = max_pretty
             )))
-            .push(caption(format!("{}: {}", fl!("new-size"), value_pretty)));
+            .push(caption(format!("{}: {}", fl!("new-size"), value_pretty)))
+            .push(resize_preview(volume.size, new_size_bytes, max_size_bytes))
+            .push(resize_plan(bounds.as_ref(), new_size_bytes));
     }
 
     if running {
         content = content.push(caption(fl!("working")));
+    } else if wizard_step == ResizePartitionStep::Review
+        && let Some(guard) = guard
+    {
+        content = content.push(confirmation_guard(guard));
     }
 
     let current_number = wizard_step.number();
@@ -770,7 +1156,7 @@ pub fn resize_partition<'a>(state: ResizePartitionDialog) -> Element<'a, Message
         (
             fl!("apply"),
             if !running && can_resize {
-                Some(ResizePartitionMessage::Confirm.into())
+                guarded(guard, ResizePartitionMessage::Confirm.into())
             } else {
                 None
             },
@@ -801,6 +1187,105 @@ pub fn resize_partition<'a>(state: ResizePartitionDialog) -> Element<'a, Message
     )
 }
 
+/// Where the partition would lie in the free space around it
+fn move_preview<'a>(bounds: &MoveBounds, free_before: u64) -> Element<'a, Message> {
+    let before = bounds.start_after(free_before) - bounds.min_start;
+    let after = bounds.free_total() - before;
+    let total = (bounds.free_total() + bounds.size).max(1);
+    let portion = |bytes: u64| ((bytes as f64 / total as f64) * 1000.0).round().max(1.0) as u16;
+    let segment = |bytes: u64, color: fn(&Theme) -> iced::Color| {
+        container(iced_widget::Space::new(
+            iced::Length::Fill,
+            iced::Length::Fixed(24.0),
+        ))
+        .style(move |theme: &Theme| container::Style {
+            background: Some(iced::Background::Color(color(theme))),
+            ..Default::default()
+        })
+        .width(iced::Length::FillPortion(portion(bytes)))
+    };
+    let free: fn(&Theme) -> iced::Color =
+        |theme| theme.cosmic().background.component.divider.into();
+
+    let mut bar = iced_widget::row![].spacing(0).width(iced::Length::Fill);
+    if before > 0 {
+        bar = bar.push(segment(before, free));
+    }
+    bar = bar.push(segment(bounds.size, |theme| {
+        theme.cosmic().accent_color().into()
+    }));
+    if after > 0 {
+        bar = bar.push(segment(after, free));
+    }
+
+    iced_widget::column![
+        bar,
+        caption(fl!(
+            "move-partition-preview",
+            before = bytes_to_pretty(&before, false),
+            after = bytes_to_pretty(&after, false)
+        )),
+    ]
+    .spacing(4)
+    .into()
+}
+
+pub fn move_partition<'a>(
+    state: MovePartitionDialog,
+    guard: Option<&ConfirmationGuard>,
+) -> Element<'a, Message> {
+    let MovePartitionDialog {
+        volume: _,
+        bounds,
+        free_before,
+    } = state;
+
+    let mut content = iced_widget::column![].spacing(12);
+    let mut apply = button::suggested(fl!("move"));
+    match &bounds {
+        None => content = content.push(caption(fl!("move-partition-checking"))),
+        Some(bounds) if bounds.free_total() == 0 => {
+            content = content.push(caption(fl!("move-partition-no-space")))
+        }
+        Some(bounds) => {
+            let max = bounds.free_total() as f64;
+            let value = free_before as f64;
+            content = content
+                .push(slider(0.0..=max, value, |v| {
+                    MovePartitionMessage::FreeBeforeUpdate(v as u64).into()
+                }))
+                .push(labelled_spinner(
+                    fl!("free-space-before"),
+                    bytes_to_pretty(&free_before, false),
+                    value,
+                    GPT_ALIGNMENT_BYTES as f64,
+                    0.0,
+                    max,
+                    |v| MovePartitionMessage::FreeBeforeUpdate(v as u64).into(),
+                ))
+                .push(move_preview(bounds, free_before));
+            if bounds.start_after(free_before) != bounds.start
+                && let Some(message) = guarded(guard, MovePartitionMessage::Confirm.into())
+            {
+                apply = apply.on_press(message);
+            }
+        }
+    }
+    content = content.push(caption(fl!("move-partition-warning")));
+    if let Some(guard) = guard {
+        content = content.push(confirmation_guard(guard));
+    }
+
+    dialog::dialog()
+        .title(fl!("move-partition"))
+        .control(content)
+        .primary_action(apply)
+        .secondary_action(
+            button::standard(fl!("cancel")).on_press(MovePartitionMessage::Cancel.into()),
+        )
+        .into()
+}
+
 pub fn edit_filesystem_label<'a>(state: EditFilesystemLabelDialog) -> Element<'a, Message> {
     let EditFilesystemLabelDialog {
         target: _,
@@ -0,0 +1,134 @@
+use crate::app::Message;
+use crate::fl;
+use crate::message::dialogs::PerformanceDialogMessage;
+use crate::state::dialogs::PerformanceDialog;
+use cosmic::{
+    Element,
+    iced::Length,
+    iced_widget,
+    widget::dropdown,
+    widget::text::{caption, caption_heading},
+    widget::{button, checkbox, dialog},
+};
+use storage_types::bytes_to_pretty;
+
+pub fn performance<'a>(state: PerformanceDialog) -> Element<'a, Message> {
+    let mut content = iced_widget::column![caption(format!(
+        "{} ({})",
+        state.drive.name(),
+        state.drive.device()
+    ))]
+    .spacing(8)
+    .width(Length::Fill);
+
+    match state.current.as_ref() {
+        Some(current) => {
+            let selected = current
+                .available_schedulers
+                .iter()
+                .position(|scheduler| *scheduler == state.tuning.scheduler);
+            content = content
+                .push(caption_heading(fl!("performance-scheduler")))
+                .push(dropdown(
+                    current.available_schedulers.clone(),
+                    selected,
+                    |index| PerformanceDialogMessage::SetScheduler(index).into(),
+                ))
+                .push(caption(fl!("performance-scheduler-help")));
+
+            let read_ahead = state.read_ahead_choices();
+            let selected = read_ahead
+                .iter()
+                .position(|kb| *kb == state.tuning.read_ahead_kb);
+            let labels = read_ahead
+                .iter()
+                .map(|kb| bytes_to_pretty(&(kb * 1024), false))
+                .collect::<Vec<_>>();
+            content = content
+                .push(caption_heading(fl!("performance-read-ahead")))
+                .push(dropdown(labels, selected, |index| {
+                    PerformanceDialogMessage::SetReadAhead(index).into()
+                }));
+
+            let requests = state.nr_requests_choices();
+            let selected = requests
+                .iter()
+                .position(|count| *count == state.tuning.nr_requests);
+            let labels = requests
+                .iter()
+                .map(|count| count.to_string())
+                .collect::<Vec<_>>();
+            content = content
+                .push(caption_heading(fl!("performance-nr-requests")))
+                .push(dropdown(labels, selected, |index| {
+                    PerformanceDialogMessage::SetRequests(index).into()
+                }));
+
+            match state.write_cache {
+                Some(status) if status.supported => {
+                    let mut write_cache = checkbox(
+                        fl!("performance-write-cache-enabled"),
+                        state.write_cache_enabled,
+                    );
+                    if !state.running {
+                        write_cache = write_cache.on_toggle(|enabled| {
+                            PerformanceDialogMessage::SetWriteCache(enabled).into()
+                        });
+                    }
+                    let warning = if state.write_cache_enabled {
+                        fl!("performance-write-cache-on-warning")
+                    } else {
+                        fl!("performance-write-cache-off-warning")
+                    };
+                    content = content
+                        .push(caption_heading(fl!("performance-write-cache")))
+                        .push(write_cache)
+                        .push(caption(warning));
+                }
+                Some(_) => {
+                    content = content
+                        .push(caption_heading(fl!("performance-write-cache")))
+                        .push(caption(fl!("performance-write-cache-unsupported")));
+                }
+                None => {}
+            }
+
+            let mut persistent = checkbox(fl!("performance-persistent"), state.persistent);
+            if !state.running {
+                persistent = persistent.on_toggle(|persistent| {
+                    PerformanceDialogMessage::SetPersistent(persistent).into()
+                });
+            }
+            content = content
+                .push(persistent)
+                .push(caption(fl!("performance-persistent-help")));
+        }
+        None if state.error.is_none() => content = content.push(caption(fl!("working"))),
+        None => {}
+    }
+
+    if state.running {
+        content = content.push(caption(fl!("working")));
+    }
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    let mut apply = button::suggested(fl!("apply"));
+    let mut close = button::standard(fl!("close"));
+
+    if !state.running && state.current.is_some() {
+        apply = apply.on_press(PerformanceDialogMessage::Apply.into());
+    }
+    if !state.running {
+        close = close.on_press(PerformanceDialogMessage::Close.into());
+    }
+
+    dialog::dialog()
+        .title(fl!("advanced-performance"))
+        .control(content)
+        .primary_action(apply)
+        .secondary_action(close)
+        .into()
+}
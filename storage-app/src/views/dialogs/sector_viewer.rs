@@ -0,0 +1,137 @@
+use crate::app::Message;
+use crate::fl;
+use crate::message::dialogs::SectorViewerDialogMessage;
+use crate::state::dialogs::SectorViewerDialog;
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    iced_widget,
+    widget::text::{caption, monotext},
+    widget::{button, container, dialog, icon, scrollable, text_input},
+};
+use storage_types::sectors::{HEX_ROW_BYTES, ascii_row, hex_row};
+use storage_types::{SectorAnnotation, SectorStructure, bytes_to_pretty};
+
+pub(super) fn structure_name(structure: SectorStructure) -> String {
+    match structure {
+        SectorStructure::Mbr => fl!("sector-structure-mbr"),
+        SectorStructure::GptHeader => fl!("sector-structure-gpt-header"),
+        SectorStructure::GptEntry => fl!("sector-structure-gpt-entry"),
+        SectorStructure::Luks => fl!("sector-structure-luks"),
+        SectorStructure::Ext => fl!("sector-structure-ext"),
+        SectorStructure::Btrfs => fl!("sector-structure-btrfs"),
+        SectorStructure::Xfs => fl!("sector-structure-xfs"),
+        SectorStructure::Fat => fl!("sector-structure-fat"),
+        SectorStructure::Ntfs => fl!("sector-structure-ntfs"),
+        SectorStructure::Swap => fl!("sector-structure-swap"),
+    }
+}
+
+/// Fields starting on a row, e.g. "GPT header: signature, revision"
+fn row_label(fields: &[&SectorAnnotation]) -> String {
+    let mut label = String::new();
+    let mut structure = None;
+    for field in fields {
+        if structure == Some(field.structure) {
+            label.push_str(", ");
+        } else {
+            if structure.is_some() {
+                label.push_str("; ");
+            }
+            label.push_str(&structure_name(field.structure));
+            label.push_str(": ");
+            structure = Some(field.structure);
+        }
+        label.push_str(&field.field);
+    }
+    label
+}
+
+pub fn sector_viewer<'a>(state: SectorViewerDialog) -> Element<'a, Message> {
+    let mut content = iced_widget::column![
+        caption(fl!(
+            "sector-viewer-position",
+            device = state.device.clone(),
+            offset = format!("{:#x}", state.offset),
+            sector = state.offset / state.sector_size,
+            size = bytes_to_pretty(&state.size, false)
+        )),
+        caption(fl!("sector-viewer-read-only")),
+    ]
+    .spacing(8)
+    .width(Length::Fill);
+
+    let loaded = state.page.is_some() || state.error.is_some();
+    let previous = button::icon(icon::from_name("go-previous-symbolic")).on_press_maybe(
+        (loaded && state.offset > 0).then_some(SectorViewerDialogMessage::PreviousPage.into()),
+    );
+    let next = button::icon(icon::from_name("go-next-symbolic")).on_press_maybe(
+        (loaded && state.offset + state.page_bytes() < state.size)
+            .then_some(SectorViewerDialogMessage::NextPage.into()),
+    );
+    let offset_input = text_input(fl!("sector-viewer-offset-placeholder"), state.offset_text)
+        .on_input(|text| SectorViewerDialogMessage::OffsetInput(text).into())
+        .on_submit(|_| SectorViewerDialogMessage::Jump.into());
+    let jump = button::standard(fl!("sector-viewer-jump"))
+        .on_press_maybe(loaded.then_some(SectorViewerDialogMessage::Jump.into()));
+    content = content.push(
+        iced_widget::row![previous, next, offset_input, jump]
+            .spacing(8)
+            .align_y(Alignment::Center),
+    );
+
+    match state.page.as_ref() {
+        Some(page) => {
+            let annotations = page.annotations();
+            let mut rows = iced_widget::column![].spacing(2);
+            for (index, bytes) in page.data.chunks(HEX_ROW_BYTES).enumerate() {
+                let offset = page.offset + (index * HEX_ROW_BYTES) as u64;
+                let row_bytes = bytes.len() as u64;
+                let line = monotext(format!(
+                    "{offset:010x}  {}  {}",
+                    hex_row(bytes),
+                    ascii_row(bytes)
+                ))
+                .size(11);
+
+                // Fields starting on this row, or before the page on its first
+                let starting = annotations
+                    .iter()
+                    .filter(|field| {
+                        field.overlaps(offset, row_bytes) && (index == 0 || field.offset >= offset)
+                    })
+                    .collect::<Vec<_>>();
+                let highlighted = annotations
+                    .iter()
+                    .any(|field| field.overlaps(offset, row_bytes));
+
+                let mut row = iced_widget::row![line].spacing(12);
+                if !starting.is_empty() {
+                    row = row.push(caption(row_label(&starting)));
+                }
+                rows = rows.push(if highlighted {
+                    container(row)
+                        .class(cosmic::style::Container::Card)
+                        .width(Length::Fill)
+                } else {
+                    container(row).width(Length::Fill)
+                });
+            }
+            content = content.push(scrollable(rows).height(360));
+        }
+        None if state.error.is_none() => content = content.push(caption(fl!("working"))),
+        None => {}
+    }
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(caption(err.clone()));
+    }
+
+    dialog::dialog()
+        .title(fl!("sector-viewer"))
+        .control(content)
+        .secondary_action(
+            button::standard(fl!("close")).on_press(SectorViewerDialogMessage::Close.into()),
+        )
+        .into()
+}
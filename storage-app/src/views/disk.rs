@@ -8,6 +8,11 @@ use crate::fl;
 use crate::models::{UiDrive, UiVolume};
 use crate::state::volumes::Segment;
 use crate::utils::DiskSegmentKind;
+use storage_types::{
+    AllowedActions, OpticalMediaInfo, OpticalMediaStatus, bytes_to_pretty, hypervisor_name,
+};
+
+use super::app::push_allowed;
 
 /// Renders the disk info header with icon, name/partitioning/serial, and multi-partition pie chart.
 pub fn disk_header<'a>(
@@ -15,6 +20,9 @@ pub fn disk_header<'a>(
     used: u64,
     segments: &'a [Segment],
     volumes: &'a [UiVolume],
+    optical_media: Option<&Result<Option<OpticalMediaInfo>, String>>,
+    hypervisor: Option<&str>,
+    allowed: &AllowedActions,
 ) -> Element<'a, Message> {
     let partition_type = match &drive.disk.partition_table_type {
         Some(t) => t.to_uppercase(),
@@ -52,16 +60,106 @@ pub fn disk_header<'a>(
         widget::text::caption(format!("{}: {}", fl!("serial"), &drive.disk.serial))
     };
 
-    let text_column = iced_widget::column![name_text, partitioning_text, serial_text]
+    let mut text_column = iced_widget::column![name_text, partitioning_text, serial_text]
         .spacing(4)
         .width(Length::Fill);
 
+    // Sector sizes, e.g. "512 B logical, 4096 B physical (512e)"
+    if !drive.disk.is_loop && !drive.disk.optical {
+        let mut sectors = fl!(
+            "sector-sizes",
+            logical = drive.disk.logical_sector_size,
+            physical = drive.disk.physical_sector_size
+        );
+        if let Some(format) = drive.disk.sector_format().name() {
+            sectors = format!("{sectors} ({format})");
+        }
+        text_column = text_column.push(widget::text::caption(format!(
+            "{}: {}",
+            fl!("sector-size"),
+            sectors
+        )));
+    }
+
+    // What the hypervisor passes through to a virtual disk, e.g.
+    // "KVM · Discard supported · Reported as non-rotational"
+    if drive.disk.virtual_disk {
+        let mut hints = Vec::new();
+        if let Some(hypervisor) = hypervisor {
+            hints.push(hypervisor_name(hypervisor));
+        }
+        hints.push(if drive.disk.discard_supported {
+            fl!("virtual-disk-discard")
+        } else {
+            fl!("virtual-disk-no-discard")
+        });
+        hints.push(if drive.disk.rotational {
+            fl!("virtual-disk-rotational")
+        } else {
+            fl!("virtual-disk-non-rotational")
+        });
+        text_column = text_column.push(widget::text::caption(format!(
+            "{}: {}",
+            fl!("virtual-disk"),
+            hints.join(" · ")
+        )));
+    }
+
+    // Loaded disc of optical drives
+    let media = optical_media
+        .and_then(|media| media.as_ref().ok())
+        .cloned()
+        .flatten();
+    if drive.disk.optical {
+        let description = match optical_media {
+            None => fl!("working"),
+            Some(Err(e)) => e.clone(),
+            Some(Ok(None)) => fl!("disc-none"),
+            Some(Ok(Some(media))) => optical_media_description(media),
+        };
+        text_column = text_column.push(widget::text::caption(format!(
+            "{}: {}",
+            fl!("disc"),
+            description
+        )));
+    }
+
+    // Write-protected media (e.g., an SD card with its lock switch set)
+    let writable = !drive.disk.read_only;
+    if !writable {
+        text_column = text_column.push(warning_callout(
+            "changes-prevent-symbolic",
+            fl!("write-protected"),
+            fl!("write-protected-description"),
+        ));
+    }
+
+    // Partitions that straddle physical sectors slow down every write
+    let misaligned = drive
+        .partitions
+        .iter()
+        .filter(|partition| !drive.disk.is_aligned(partition.offset))
+        .count();
+    if misaligned > 0 {
+        text_column = text_column.push(warning_callout(
+            "dialog-warning-symbolic",
+            fl!("misaligned-partitions", count = misaligned),
+            fl!(
+                "misaligned-partitions-description",
+                sector = drive.disk.physical_sector_size
+            ),
+        ));
+    }
+
     // Drive action buttons underneath icon and text (left-aligned, spanning both columns)
     let mut drive_actions = Vec::new();
 
     // Eject (for removable/ejectable drives - use this instead of power off)
     if drive.disk.removable || drive.disk.ejectable {
-        drive_actions.push(
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-eject",
             widget::tooltip(
                 widget::button::icon(icon::from_name("media-eject-symbolic"))
                     .on_press(Message::Eject),
@@ -73,7 +171,10 @@ pub fn disk_header<'a>(
     }
     // Power Off (only for non-removable drives that support it)
     else if drive.disk.can_power_off {
-        drive_actions.push(
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-power-off",
             widget::tooltip(
                 widget::button::icon(icon::from_name("system-shutdown-symbolic"))
                     .on_press(Message::PowerOff),
@@ -84,20 +185,92 @@ pub fn disk_header<'a>(
         );
     }
 
+    // Burn an image to the disc, or erase it (optical drives)
+    if drive.disk.optical {
+        let can_burn = media.as_ref().is_some_and(|media| {
+            media.status == OpticalMediaStatus::Blank || media.is_rewritable()
+        });
+        let can_erase = media.as_ref().is_some_and(|media| {
+            media.is_rewritable() && media.status != OpticalMediaStatus::Blank
+        });
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disc-write",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("media-optical-symbolic"))
+                    .on_press_maybe((writable && can_burn).then_some(Message::BurnDiscImage)),
+                widget::text(fl!("burn-disc-image")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disc-write",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("edit-clear-symbolic"))
+                    .on_press_maybe((writable && can_erase).then_some(Message::EraseDisc)),
+                widget::text(fl!("erase-disc")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Format (wipe disk)
-    drive_actions.push(
+    push_allowed(
+        &mut drive_actions,
+        allowed,
+        "partition-modify",
         widget::tooltip(
             widget::button::icon(icon::from_name("edit-clear-all-symbolic"))
-                .on_press(Message::Format),
+                .on_press_maybe(writable.then_some(Message::Format)),
             widget::text(fl!("format-disk")),
             widget::tooltip::Position::Bottom,
         )
         .into(),
     );
 
-    // SMART Data (not for loop devices)
-    if !drive.disk.is_loop {
-        drive_actions.push(
+    // Search the free space for deleted partitions (not for optical discs)
+    if !drive.disk.optical {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "partition-read",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("edit-find-symbolic"))
+                    .on_press(Message::FindLostPartitions),
+                widget::text(fl!("find-lost-partitions")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Raw GPT entries, for expert edits the partition dialogs don't offer
+    if drive.disk.partition_table_type.as_deref() == Some("gpt") {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "partition-modify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("document-edit-symbolic"))
+                    .on_press(Message::EditGptEntries),
+                widget::text(fl!("edit-gpt-entries")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // SMART Data (not for loop devices and virtual disks)
+    if drive.disk.supports_smart() {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "smart-read",
             widget::tooltip(
                 widget::button::icon(icon::from_name("emblem-system-symbolic"))
                     .on_press(Message::SmartData),
@@ -110,7 +283,10 @@ pub fn disk_header<'a>(
 
     // Standby (only for drives that support power management - spinning disks)
     if drive.disk.supports_power_management() {
-        drive_actions.push(
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-standby",
             widget::tooltip(
                 widget::button::icon(icon::from_name("media-playback-pause-symbolic"))
                     .on_press(Message::StandbyNow),
@@ -123,7 +299,10 @@ pub fn disk_header<'a>(
 
     // Wake Up (only for drives that support power management - spinning disks)
     if drive.disk.supports_power_management() {
-        drive_actions.push(
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-standby",
             widget::tooltip(
                 widget::button::icon(icon::from_name("alarm-symbolic")).on_press(Message::Wakeup),
                 widget::text(fl!("wake-up-from-standby")),
@@ -133,8 +312,80 @@ pub fn disk_header<'a>(
         );
     }
 
+    // Hand space freed by deleted files back to the disk, which a thin
+    // virtual disk passes on to its host
+    if drive.disk.discard_supported && !drive.disk.optical && any_mounted(volumes) {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-trim",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("edit-cut-symbolic"))
+                    .on_press(Message::TrimFilesystems),
+                widget::text(fl!("trim-filesystems")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Blink the enclosure slot LED (not for loop devices and virtual disks)
+    if !drive.disk.is_loop && !drive.disk.virtual_disk {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-identify",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("find-location-symbolic"))
+                    .on_press(Message::IdentifyDrive),
+                widget::text(fl!("identify-drive")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // I/O scheduler, readahead and queue depth (not for optical drives)
+    if !drive.disk.optical {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-tune",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("preferences-system-symbolic"))
+                    .on_press(Message::AdvancedPerformance),
+                widget::text(fl!("advanced-performance")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    // Raw sectors, read-only
+    if drive.disk.media_available {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-inspect",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("text-x-generic-symbolic")).on_press(
+                    Message::ViewSectors {
+                        device: drive.device().to_string(),
+                        size: drive.disk.size,
+                    },
+                ),
+                widget::text(fl!("view-sectors")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Create image from drive (backup whole drive via image client)
-    drive_actions.push(
+    push_allowed(
+        &mut drive_actions,
+        allowed,
+        "disk-backup",
         widget::tooltip(
             widget::button::icon(icon::from_name("document-save-as-symbolic"))
                 .on_press(Message::CreateDiskFrom),
@@ -145,16 +396,60 @@ pub fn disk_header<'a>(
     );
 
     // Restore image to drive (restore whole drive via image client)
-    drive_actions.push(
+    push_allowed(
+        &mut drive_actions,
+        allowed,
+        "disk-restore",
         widget::tooltip(
             widget::button::icon(icon::from_name("document-revert-symbolic"))
-                .on_press(Message::RestoreImageTo),
+                .on_press_maybe(writable.then_some(Message::RestoreImageTo)),
             widget::text(fl!("restore-image")),
             widget::tooltip::Position::Bottom,
         )
         .into(),
     );
 
+    // Export the segment bar as a printable diagram
+    drive_actions.push(
+        widget::tooltip(
+            widget::button::icon(icon::from_name("document-print-symbolic"))
+                .on_press(Message::ExportLayoutDiagram),
+            widget::text(fl!("diagram-export")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+    );
+
+    // Migrate the drive onto a larger one (clone and grow via image client)
+    push_allowed(
+        &mut drive_actions,
+        allowed,
+        "disk-restore",
+        widget::tooltip(
+            widget::button::icon(icon::from_name("go-next-symbolic"))
+                .on_press(Message::MigrateDisk),
+            widget::text(fl!("migrate-disk")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+    );
+
+    // Make a bootable live USB drive from a distribution ISO
+    if drive.disk.removable && !drive.disk.optical {
+        push_allowed(
+            &mut drive_actions,
+            allowed,
+            "disk-restore",
+            widget::tooltip(
+                widget::button::icon(icon::from_name("media-removable-symbolic"))
+                    .on_press_maybe(writable.then_some(Message::CreateLiveUsb)),
+                widget::text(fl!("create-live-usb")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
     // Multi-partition pie chart (right-aligned)
     let pie_segments: Vec<PieSegmentData> = segments
         .iter()
@@ -221,3 +516,71 @@ pub fn disk_header<'a>(
         .width(Length::Fill)
         .into()
 }
+
+/// Whether any of the volumes, or the volumes inside them, is mounted
+fn any_mounted(volumes: &[UiVolume]) -> bool {
+    volumes
+        .iter()
+        .any(|volume| volume.volume.is_mounted() || any_mounted(&volume.children))
+}
+
+/// Media type, state and usage of a disc, e.g. "DVD+RW · Appendable · 1.2 GB
+/// used of 4.7 GB"
+fn optical_media_description(media: &OpticalMediaInfo) -> String {
+    let status = match media.status {
+        OpticalMediaStatus::Blank => fl!("disc-blank"),
+        OpticalMediaStatus::Appendable => fl!("disc-appendable"),
+        OpticalMediaStatus::Closed => fl!("disc-closed"),
+    };
+    let mut parts = vec![media.media_type.clone(), status];
+    if let Some(capacity) = media.capacity_bytes() {
+        parts.push(fl!(
+            "disc-usage",
+            used = bytes_to_pretty(&media.used_bytes, false),
+            capacity = bytes_to_pretty(&capacity, false)
+        ));
+    }
+    parts.join(" · ")
+}
+
+/// Bordered warning about the drive, e.g. that it cannot be changed and
+/// how to unlock it
+fn warning_callout<'a>(
+    icon_name: &'static str,
+    title: String,
+    description: String,
+) -> Element<'a, Message> {
+    let title = widget::text(title)
+        .size(14.0)
+        .font(cosmic::iced::font::Font {
+            weight: cosmic::iced::font::Weight::Semibold,
+            ..Default::default()
+        });
+    let content = iced_widget::row![
+        icon::from_name(icon_name).size(16),
+        iced_widget::column![title, widget::text::caption(description)]
+            .spacing(2)
+            .width(Length::Fill),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    widget::container(content)
+        .padding(8)
+        .width(Length::Fill)
+        .style(|theme| {
+            let cosmic = theme.cosmic();
+            widget::container::Style {
+                icon_color: Some(cosmic.warning_color().into()),
+                text_color: Some(cosmic.warning_color().into()),
+                background: None,
+                border: cosmic::iced::Border {
+                    color: cosmic.warning_color().into(),
+                    width: 1.0,
+                    radius: cosmic.corner_radii.radius_s.into(),
+                },
+                shadow: cosmic::iced::Shadow::default(),
+            }
+        })
+        .into()
+}
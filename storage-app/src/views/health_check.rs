@@ -0,0 +1,111 @@
+use cosmic::{Element, cosmic_theme, iced::Length, theme, widget, widget::icon};
+use storage_types::{HealthCheckItem, HealthCheckKind, HealthLevel};
+
+use crate::{
+    app::Message, fl, message::health_check::HealthCheckMessage, report::utc_time,
+    state::health_check::HealthCheckState,
+};
+
+pub fn health_check(state: &HealthCheckState) -> Element<'_, Message> {
+    let cosmic_theme::Spacing {
+        space_xxs, space_s, ..
+    } = theme::active().cosmic().spacing;
+
+    let mut run = widget::button::standard(fl!("check-all-drives"));
+    if !state.running {
+        run = run.on_press(HealthCheckMessage::Run.into());
+    }
+    let mut content = widget::column()
+        .push(widget::row().push(widget::horizontal_space()).push(run))
+        .spacing(space_s)
+        .width(Length::Fill);
+
+    if state.running {
+        content = content.push(widget::text::caption(fl!("health-check-running")));
+    }
+    let Some(report) = state.report.as_ref() else {
+        return content.into();
+    };
+
+    content = content
+        .push(widget::text::title4(fl!(
+            "health-check-overall",
+            level = level_label(report.overall())
+        )))
+        .push(widget::text::caption(fl!(
+            "health-check-counts",
+            critical = report.count(HealthLevel::Critical),
+            warning = report.count(HealthLevel::Warning),
+            unknown = report.count(HealthLevel::Unknown),
+            good = report.count(HealthLevel::Good)
+        )))
+        .push(widget::text::caption(fl!(
+            "health-check-finished",
+            date = utc_time(report.finished_at)
+        )));
+
+    if report.items.is_empty() {
+        return content
+            .push(widget::text::caption(fl!("health-check-nothing")))
+            .into();
+    }
+
+    let mut list = widget::column().spacing(space_xxs);
+    for (index, item) in report.items.iter().enumerate() {
+        list = list.push(item_row(item, index, state.expanded == Some(index)));
+    }
+    content.push(list).into()
+}
+
+/// One finding, with its details shown when expanded
+fn item_row(item: &HealthCheckItem, index: usize, expanded: bool) -> Element<'_, Message> {
+    let expander = if expanded {
+        "go-down-symbolic"
+    } else {
+        "go-next-symbolic"
+    };
+    let header = widget::row()
+        .push(
+            widget::button::icon(icon::from_name(expander))
+                .on_press(HealthCheckMessage::ToggleItem(index).into()),
+        )
+        .push(
+            widget::column()
+                .push(widget::text::body(item.target.clone()))
+                .push(widget::text::caption(kind_label(item.kind))),
+        )
+        .push(widget::horizontal_space())
+        .push(widget::text::body(level_label(item.level)))
+        .spacing(8)
+        .align_y(cosmic::iced::Alignment::Center);
+
+    let mut row = widget::column().push(header).spacing(4);
+    if expanded {
+        for detail in &item.details {
+            row = row.push(widget::text::caption(detail.clone()));
+        }
+        row = row.push(
+            widget::button::link(fl!("health-check-show"))
+                .on_press(Message::Reveal(item.target.clone())),
+        );
+    }
+    row.into()
+}
+
+fn kind_label(kind: HealthCheckKind) -> String {
+    match kind {
+        HealthCheckKind::Smart => fl!("health-check-smart"),
+        HealthCheckKind::Filesystem => fl!("health-check-filesystem"),
+        HealthCheckKind::Raid => fl!("health-check-raid"),
+        HealthCheckKind::Btrfs => fl!("health-check-btrfs"),
+    }
+}
+
+fn level_label(level: HealthLevel) -> String {
+    match level {
+        HealthLevel::Good => fl!("health-check-good"),
+        HealthLevel::Warning => fl!("health-check-warning"),
+        HealthLevel::Critical => fl!("health-check-critical"),
+        HealthLevel::Unknown => fl!("health-check-unknown"),
+    }
+}
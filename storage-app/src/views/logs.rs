@@ -0,0 +1,110 @@
+use cosmic::{Element, cosmic_theme, iced::Length, theme, widget};
+use storage_types::{LogEntry, LogLevel};
+
+use crate::{app::Message, fl, message::logs::LogsMessage, state::logs::LogsState};
+
+/// Most lines listed per log; the newest are shown
+const MAX_SHOWN_LINES: usize = 500;
+
+pub fn logs(state: &LogsState) -> Element<'_, Message> {
+    let cosmic_theme::Spacing {
+        space_xxs, space_s, ..
+    } = theme::active().cosmic().spacing;
+
+    let levels: Vec<String> = LogLevel::ALL
+        .iter()
+        .map(|level| level.as_str().to_string())
+        .collect();
+    let selected_level = LogLevel::ALL
+        .iter()
+        .position(|level| *level == state.filter.level);
+
+    let filters = widget::column()
+        .push(widget::text::caption_heading(fl!("logs-level")))
+        .push(widget::dropdown(levels, selected_level, |index| {
+            LogsMessage::LevelChanged(index).into()
+        }))
+        .push(
+            widget::text_input(fl!("logs-module"), &state.filter.module)
+                .on_input(|text| LogsMessage::ModuleChanged(text).into()),
+        )
+        .push(
+            widget::text_input(fl!("logs-operation"), &state.filter.operation)
+                .on_input(|text| LogsMessage::OperationChanged(text).into()),
+        )
+        .push(
+            widget::text_input(fl!("logs-device"), &state.filter.device)
+                .on_input(|text| LogsMessage::DeviceChanged(text).into()),
+        )
+        .spacing(space_xxs);
+
+    let mut content = widget::column()
+        .push(filters)
+        .push(
+            widget::row()
+                .push(widget::text::title4(fl!("logs-app")))
+                .push(widget::horizontal_space())
+                .push(widget::button::standard(fl!("refresh")).on_press(LogsMessage::Load.into())),
+        )
+        .spacing(space_s)
+        .width(Length::Fill);
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(widget::text::caption(err.clone()));
+    }
+    content = content.push(entry_list(state.visible().collect()));
+
+    let mut fetch = widget::button::standard(fl!("logs-fetch-service"));
+    if !state.loading_service {
+        fetch = fetch.on_press(LogsMessage::FetchServiceLog.into());
+    }
+    content = content
+        .push(
+            widget::row()
+                .push(widget::text::title4(fl!("logs-service")))
+                .push(widget::horizontal_space())
+                .push(fetch),
+        )
+        .push(widget::text::caption(fl!("logs-service-description")));
+
+    if state.loading_service {
+        content = content.push(widget::text::caption(fl!("working")));
+    }
+    if let Some(err) = state.service_error.as_ref() {
+        content = content.push(widget::text::caption(err.clone()));
+    }
+    if state.service_entries.is_some() {
+        content = content.push(entry_list(state.visible_service().collect()));
+    }
+
+    content.into()
+}
+
+/// The newest of `entries`, one line each
+fn entry_list<'a>(entries: Vec<&LogEntry>) -> Element<'a, Message> {
+    if entries.is_empty() {
+        return widget::text::caption(fl!("logs-none")).into();
+    }
+
+    let skipped = entries.len().saturating_sub(MAX_SHOWN_LINES);
+    let mut list = widget::column().spacing(2);
+    if skipped > 0 {
+        list = list.push(widget::text::caption(fl!(
+            "logs-older-hidden",
+            count = skipped
+        )));
+    }
+    for entry in &entries[skipped..] {
+        list = list.push(
+            widget::text::monotext(format!(
+                "{} {:5} {}: {}",
+                entry.timestamp,
+                entry.level.as_str(),
+                entry.target,
+                entry.message
+            ))
+            .size(11),
+        );
+    }
+    list.into()
+}
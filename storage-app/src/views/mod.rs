@@ -2,7 +2,12 @@ pub(crate) mod app;
 pub(crate) mod btrfs;
 pub(crate) mod dialogs;
 pub(crate) mod disk;
+pub(crate) mod health_check;
+pub(crate) mod logs;
 pub(crate) mod network;
+pub(crate) mod operations;
 pub(crate) mod settings;
 pub(crate) mod sidebar;
+pub(crate) mod statistics;
+pub(crate) mod vaults;
 pub(crate) mod volumes;
@@ -13,14 +13,18 @@ use crate::controls::wizard::{
 use crate::message::network::NetworkMessage;
 use crate::state::network::{
     NetworkEditorState, NetworkMountState, NetworkState, NetworkWizardState, QUICK_SETUP_PROVIDERS,
-    SECTION_ORDER, WizardStep, section_display_name,
+    SECTION_ORDER, TransferFormState, WizardStep, section_display_name,
 };
 use cosmic::cosmic_theme::palette::WithAlpha;
 use cosmic::iced::Length;
 use cosmic::widget::{self, button, dropdown, icon, text_input};
 use cosmic::{Apply, Element, iced_widget};
 use std::collections::BTreeMap;
-use storage_types::rclone::{ConfigScope, MountStatus, rclone_provider, supported_remote_types};
+use storage_types::bytes_to_pretty;
+use storage_types::rclone::{
+    ConfigScope, LoginMountState, LoginMountStatus, MountStatus, RemoteUsage, TransferJob,
+    TransferMode, TransferState, rclone_provider, supported_remote_types,
+};
 
 // ─── Sidebar helpers ─────────────────────────────────────────────────────────
 
@@ -67,8 +71,15 @@ pub fn network_mount_item(
     let loading = mount.loading;
     let config_name = mount.config.name.clone();
 
-    // Name text
-    let name_text = widget::text::body(config_name).font(cosmic::font::semibold());
+    // Name text, with the usage below once known
+    let mut name_text = widget::column::with_children(vec![
+        widget::text::body(config_name)
+            .font(cosmic::font::semibold())
+            .into(),
+    ]);
+    if let Some(caption) = mount.usage.as_ref().and_then(usage_caption) {
+        name_text = name_text.push(widget::text::caption(caption));
+    }
 
     // Scope icon (left aligned)
     let provider_icon_widget = provider_logo_widget(&mount.config.remote_type, 16);
@@ -79,16 +90,28 @@ pub fn network_mount_item(
         widget::tooltip::Position::FollowCursor,
     );
 
+    let mut children: Vec<Element<'static, NetworkMessage>> =
+        vec![provider_icon_widget, name_text.into()];
+    if let Some(status) = &mount.login_mount
+        && status.state == LoginMountState::Failed
+    {
+        children.push(
+            widget::tooltip(
+                icon::from_name("dialog-warning-symbolic").size(14),
+                widget::text::body(login_mount_caption(status).unwrap_or_default()),
+                widget::tooltip::Position::FollowCursor,
+            )
+            .into(),
+        );
+    }
+    children.push(scope_icon_widget.into());
+
     // Main select button
     let mut select_button = widget::button::custom(
-        widget::Row::with_children(vec![
-            provider_icon_widget,
-            name_text.into(),
-            scope_icon_widget.into(),
-        ])
-        .spacing(8)
-        .align_y(cosmic::iced::Alignment::Center)
-        .width(Length::Fill),
+        widget::Row::with_children(children)
+            .spacing(8)
+            .align_y(cosmic::iced::Alignment::Center)
+            .width(Length::Fill),
     )
     .padding(0)
     .width(Length::Fill)
@@ -113,6 +136,66 @@ pub fn network_mount_item(
     row_container(row, selected, controls_enabled)
 }
 
+/// Sidebar row for an encrypted configuration that still needs its password
+fn locked_config_item(
+    scope: ConfigScope,
+    controls_enabled: bool,
+) -> Element<'static, NetworkMessage> {
+    let content = widget::Row::with_children(vec![
+        icon::from_name("changes-prevent-symbolic").size(16).into(),
+        widget::text::body(format!("{} remotes locked", scope_label(scope))).into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center)
+    .width(Length::Fill);
+
+    let mut unlock_button = widget::button::custom(content)
+        .padding(0)
+        .width(Length::Fill)
+        .class(transparent_button_class(false));
+    if controls_enabled {
+        unlock_button = unlock_button.on_press(NetworkMessage::UnlockConfig(scope));
+    }
+
+    let row =
+        widget::Row::with_children(vec![widget::Space::new(20, 0).into(), unlock_button.into()])
+            .spacing(8)
+            .align_y(cosmic::iced::Alignment::Center)
+            .width(Length::Fill);
+
+    row_container(row, false, controls_enabled)
+}
+
+/// Describe the quota and usage of a remote, if it reports any sizes
+fn usage_caption(usage: &RemoteUsage) -> Option<String> {
+    let pretty = |bytes: u64| bytes_to_pretty(&bytes, false);
+    Some(match (usage.used_bytes(), usage.total, usage.free) {
+        (Some(used), Some(total), _) => format!("{} of {} used", pretty(used), pretty(total)),
+        (Some(used), None, _) => format!("{} used", pretty(used)),
+        (None, _, Some(free)) => format!("{} free", pretty(free)),
+        (None, _, None) => return None,
+    })
+}
+
+/// Describe the automatic mount of a remote, if it has one
+fn login_mount_caption(status: &LoginMountStatus) -> Option<String> {
+    let next_start = match status.scope {
+        ConfigScope::User => "login",
+        ConfigScope::System => "boot",
+    };
+    Some(match status.state {
+        LoginMountState::Disabled => return None,
+        LoginMountState::Inactive => format!("Not running; mounts again at next {next_start}"),
+        LoginMountState::Starting => "Automatic mount starting...".to_string(),
+        LoginMountState::Active => "Mounted automatically".to_string(),
+        LoginMountState::Stopping => "Automatic mount stopping...".to_string(),
+        LoginMountState::Failed => match &status.detail {
+            Some(detail) => format!("Automatic mount failed: {detail}"),
+            None => "Automatic mount failed".to_string(),
+        },
+    })
+}
+
 /// Section header for sidebar
 fn sidebar_section_header(label: &str, controls_enabled: bool) -> Element<'static, NetworkMessage> {
     let label_widget = widget::text::caption_heading(label.to_string());
@@ -120,11 +203,17 @@ fn sidebar_section_header(label: &str, controls_enabled: bool) -> Element<'stati
     let mut children: Vec<Element<'static, NetworkMessage>> = vec![label_widget.into()];
 
     if controls_enabled {
+        let transfers_btn =
+            widget::button::custom(icon::from_name("emblem-synchronizing-symbolic").size(20))
+                .padding(4)
+                .class(cosmic::theme::Button::Link)
+                .on_press(NetworkMessage::ShowTransfers);
         let add_btn = widget::button::custom(icon::from_name("list-add-symbolic").size(20))
             .padding(4)
             .class(cosmic::theme::Button::Link)
             .on_press(NetworkMessage::BeginCreateRemote);
         children.push(widget::Space::new(Length::Fill, 0).into());
+        children.push(transfers_btn.into());
         children.push(add_btn.into());
     }
 
@@ -157,7 +246,7 @@ pub fn network_section(
                 .padding([4, 12])
                 .into(),
         );
-    } else if state.mounts.is_empty() {
+    } else if state.mounts.is_empty() && state.locked_scopes().is_empty() {
         // Empty state
         children.push(
             widget::container(widget::text::body("No network mounts configured"))
@@ -191,6 +280,10 @@ pub fn network_section(
                 children.push(network_mount_item(state, name, *scope, controls_enabled));
             }
         }
+
+        for scope in state.locked_scopes() {
+            children.push(locked_config_item(scope, controls_enabled));
+        }
     }
 
     widget::column::with_children(children)
@@ -373,6 +466,20 @@ fn editor_header(
             }),
             can_control,
         ));
+
+        // Automount units run as root, so only remotes of the system
+        // configuration can use them
+        if mount.config.scope == ConfigScope::System {
+            actions.push(icon_tooltip_action(
+                "appointment-soon-symbolic",
+                "Automount",
+                Some(NetworkMessage::OpenAutomount {
+                    name: mount.config.name.clone(),
+                    scope: mount.config.scope,
+                }),
+                controls_enabled && !editor.running,
+            ));
+        }
     } else {
         actions.push(icon_tooltip_action(
             "media-playback-start-symbolic",
@@ -488,7 +595,13 @@ fn editor_form(
         dropdown(scopes, Some(scope_index), |idx| {
             NetworkMessage::EditorScopeChanged(idx)
         })
-        .width(Length::Fill)
+        .width(Length::Fill),
+        text_input(
+            editor.scope.mount_point(&editor.name).display().to_string(),
+            editor.mount_point.clone(),
+        )
+        .label("Mount Point")
+        .on_input(NetworkMessage::EditorMountPointChanged)
     ]
     .spacing(10);
 
@@ -704,13 +817,28 @@ fn editor_view(
         }
     }
 
+    if let Some(mount) = selected_mount {
+        layout = layout.push(usage_row(mount, controls_enabled));
+    }
+
     if !editor.is_new {
         let checked = editor.mount_on_boot.unwrap_or(false);
-        let mut mount_on_boot = widget::checkbox("Mount on boot", checked);
+        let label = match editor.scope {
+            ConfigScope::User => "Mount at login",
+            ConfigScope::System => "Mount on boot",
+        };
+        let mut mount_on_boot = widget::checkbox(label, checked);
         if controls_enabled && !editor.running && editor.mount_on_boot.is_some() {
             mount_on_boot = mount_on_boot.on_toggle(NetworkMessage::ToggleMountOnBoot);
         }
         layout = layout.push(mount_on_boot);
+
+        if let Some(caption) = selected_mount
+            .and_then(|mount| mount.login_mount.as_ref())
+            .and_then(login_mount_caption)
+        {
+            layout = layout.push(widget::text::caption(caption));
+        }
     }
 
     layout = layout.push(form);
@@ -733,6 +861,50 @@ fn editor_view(
         .into()
 }
 
+/// Quota and usage of a remote, with a button to query them again
+fn usage_row(
+    mount: &NetworkMountState,
+    controls_enabled: bool,
+) -> Element<'static, NetworkMessage> {
+    let caption = if mount.usage_loading {
+        "Checking storage usage...".to_string()
+    } else if let Some(error) = &mount.usage_error {
+        format!("Storage usage unavailable: {error}")
+    } else {
+        match &mount.usage {
+            Some(usage) => {
+                usage_caption(usage).unwrap_or_else(|| "Storage usage not reported".to_string())
+            }
+            None => "This remote does not report storage usage".to_string(),
+        }
+    };
+
+    let mut info = iced_widget::column![widget::text::caption(caption)]
+        .spacing(4)
+        .width(Length::Fill);
+    if let Some(fraction) = mount.usage.as_ref().and_then(RemoteUsage::used_fraction) {
+        info = info.push(iced_widget::progress_bar(0.0..=1.0, fraction as f32).width(Length::Fill));
+    }
+
+    let refresh = icon_tooltip_action(
+        "view-refresh-symbolic",
+        "Refresh Usage",
+        Some(NetworkMessage::LoadRemoteUsage {
+            name: mount.config.name.clone(),
+            scope: mount.config.scope,
+            refresh: true,
+        }),
+        controls_enabled && !mount.usage_loading,
+    );
+
+    bounded_form(
+        iced_widget::row![info, refresh]
+            .spacing(12)
+            .align_y(cosmic::iced::Alignment::Center),
+        720,
+    )
+}
+
 // ─── Wizard ──────────────────────────────────────────────────────────────────
 
 /// Progress indicator showing dots for each step
@@ -1131,6 +1303,10 @@ pub fn network_main_view(
         return editor_view(state, editor, controls_enabled);
     }
 
+    if let Some(form) = &state.transfer_form {
+        return transfers_view(state, form, controls_enabled);
+    }
+
     // Empty state - no editor or wizard active
     let mut empty = iced_widget::column![
         widget::text::title2("Network Mounts"),
@@ -1144,9 +1320,311 @@ pub fn network_main_view(
             empty.push(button::standard("New Remote").on_press(NetworkMessage::BeginCreateRemote));
     }
 
+    if !state.config_encryption.is_empty() {
+        empty = empty.push(config_encryption_section(state, controls_enabled));
+    }
+
     widget::container(empty)
         .padding(20)
         .width(Length::Fill)
         .height(Length::Fill)
         .into()
 }
+
+// ─── Configuration encryption ────────────────────────────────────────────────
+
+/// Encryption state of each configuration file, with the action it allows
+fn config_encryption_section(
+    state: &NetworkState,
+    controls_enabled: bool,
+) -> Element<'static, NetworkMessage> {
+    let mut section = iced_widget::column![widget::text::heading("Configuration Encryption")]
+        .spacing(8)
+        .padding([16, 0, 0, 0]);
+
+    for status in &state.config_encryption {
+        let (caption, label, message) = if !status.encrypted {
+            (
+                "Not encrypted",
+                "Encrypt...",
+                NetworkMessage::EncryptConfig(status.scope),
+            )
+        } else if status.unlocked {
+            (
+                "Encrypted, unlocked for this session",
+                "Lock",
+                NetworkMessage::LockConfig(status.scope),
+            )
+        } else {
+            (
+                "Encrypted and locked",
+                "Unlock...",
+                NetworkMessage::UnlockConfig(status.scope),
+            )
+        };
+
+        let mut action = button::standard(label);
+        if controls_enabled {
+            action = action.on_press(message);
+        }
+        section = section.push(
+            iced_widget::row![
+                icon::from_name(scope_icon(status.scope)).size(16),
+                iced_widget::column![
+                    widget::text::body(format!("{} remotes", scope_label(status.scope))),
+                    widget::text::caption(caption),
+                ]
+                .width(Length::Fill),
+                action,
+            ]
+            .spacing(12)
+            .align_y(cosmic::iced::Alignment::Center),
+        );
+    }
+
+    bounded_form(section, 720)
+}
+
+// ─── Transfers ───────────────────────────────────────────────────────────────
+
+/// Format seconds as "1h 05m", "4m 12s" or "9s"
+pub(crate) fn format_duration(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Endpoint picker: a dropdown of "Local folder" and the remotes, and the path
+fn transfer_endpoint(
+    label: &str,
+    remotes: &[String],
+    remote: &Option<String>,
+    path: &str,
+    on_select: fn(usize) -> NetworkMessage,
+    on_path: fn(String) -> NetworkMessage,
+) -> Element<'static, NetworkMessage> {
+    let mut options = vec!["Local folder".to_string()];
+    options.extend(remotes.iter().cloned());
+    let selected = match remote {
+        Some(name) => remotes.iter().position(|r| r == name).map(|i| i + 1),
+        None => Some(0),
+    };
+    let placeholder = if remote.is_some() {
+        "Folder in the remote; empty for its root"
+    } else {
+        "/home/user/Documents"
+    };
+
+    iced_widget::column![
+        widget::text::caption(label.to_string()),
+        iced_widget::row![
+            dropdown(options, selected, on_select).width(Length::FillPortion(1)),
+            text_input(placeholder, path.to_string())
+                .on_input(on_path)
+                .width(Length::FillPortion(2)),
+        ]
+        .spacing(8)
+        .align_y(cosmic::iced::Alignment::Center),
+    ]
+    .spacing(4)
+    .into()
+}
+
+/// Form for starting a copy or sync
+fn transfer_form(
+    state: &NetworkState,
+    form: &TransferFormState,
+    controls_enabled: bool,
+) -> Element<'static, NetworkMessage> {
+    let scopes = vec!["User".to_string(), "System".to_string()];
+    let scope_index = match form.scope {
+        ConfigScope::User => 0,
+        ConfigScope::System => 1,
+    };
+    let modes = vec![
+        "Copy new and changed files".to_string(),
+        "Sync (delete files missing from the source)".to_string(),
+    ];
+    let mode_index = match form.mode {
+        TransferMode::Copy => 0,
+        TransferMode::Sync => 1,
+    };
+    let remotes = state.remote_names(form.scope);
+
+    let mut start = button::suggested("Start Transfer");
+    if controls_enabled && !form.running {
+        start = start.on_press(NetworkMessage::StartTransfer);
+    }
+
+    let mut col = iced_widget::column![
+        widget::text::heading("New Transfer"),
+        widget::text::caption("Configuration Scope"),
+        dropdown(
+            scopes,
+            Some(scope_index),
+            NetworkMessage::TransferScopeChanged
+        )
+        .width(Length::Fill),
+        transfer_endpoint(
+            "Source",
+            &remotes,
+            &form.source_remote,
+            &form.source_path,
+            NetworkMessage::TransferSourceChanged,
+            NetworkMessage::TransferSourcePathChanged,
+        ),
+        transfer_endpoint(
+            "Destination",
+            &remotes,
+            &form.destination_remote,
+            &form.destination_path,
+            NetworkMessage::TransferDestinationChanged,
+            NetworkMessage::TransferDestinationPathChanged,
+        ),
+        widget::text::caption("Mode"),
+        dropdown(modes, Some(mode_index), NetworkMessage::TransferModeChanged).width(Length::Fill),
+        text_input("Unlimited", form.bandwidth_limit.clone())
+            .label("Bandwidth Limit")
+            .on_input(NetworkMessage::TransferBandwidthLimitChanged),
+        widget::text::caption(
+            "Bytes per second, e.g. 10M, or 4M:1M for separate upload and download limits."
+                .to_string()
+        ),
+        widget::checkbox("Dry run (only report what would change)", form.dry_run)
+            .on_toggle(NetworkMessage::TransferDryRunToggled),
+        start,
+    ]
+    .spacing(8)
+    .width(Length::Fill);
+
+    if form.running {
+        col = col.push(widget::text::caption("Starting transfer..."));
+    }
+    if let Some(error) = &form.error {
+        col = col.push(widget::text::caption(error.clone()));
+    }
+
+    bounded_form(col, 720)
+}
+
+/// One running or finished transfer with its progress
+fn transfer_item(job: &TransferJob, controls_enabled: bool) -> Element<'static, NetworkMessage> {
+    let progress = &job.progress;
+    let mode = match job.request.mode {
+        TransferMode::Copy => "Copy",
+        TransferMode::Sync => "Sync",
+    };
+    let dry_run = if job.request.dry_run { ", dry run" } else { "" };
+    let state = match job.state {
+        TransferState::Running => "Running",
+        TransferState::Completed => "Completed",
+        TransferState::Failed => "Failed",
+        TransferState::Cancelled => "Cancelled",
+    };
+
+    let mut details = format!(
+        "{state} · {}/{} files · {} of {}",
+        progress.files,
+        progress.total_files,
+        bytes_to_pretty(&progress.bytes, false),
+        bytes_to_pretty(&progress.total_bytes, false),
+    );
+    if job.state == TransferState::Running {
+        details.push_str(&format!(
+            " · {}/s",
+            bytes_to_pretty(&progress.speed_bytes_per_sec, false)
+        ));
+        if let Some(eta) = progress.eta_secs {
+            details.push_str(&format!(" · {} left", format_duration(eta)));
+        }
+    } else if let Some(finished_at) = job.finished_at {
+        details.push_str(&format!(
+            " · took {}",
+            format_duration(finished_at.saturating_sub(job.started_at))
+        ));
+    }
+    if progress.errors > 0 {
+        details.push_str(&format!(" · {} errors", progress.errors));
+    }
+
+    let mut info = iced_widget::column![
+        widget::text::body(format!(
+            "{mode}{dry_run}: {} → {}",
+            job.request.source, job.request.destination
+        )),
+        widget::text::caption(details),
+    ]
+    .spacing(4)
+    .width(Length::Fill);
+
+    if job.state == TransferState::Running {
+        info = info.push(
+            iced_widget::progress_bar(0.0..=1.0, progress.fraction().unwrap_or(0.0) as f32)
+                .width(Length::Fill),
+        );
+    }
+    if let Some(error) = &job.error {
+        info = info.push(widget::text::caption(error.clone()));
+    }
+
+    let mut row = iced_widget::row![info]
+        .spacing(12)
+        .align_y(cosmic::iced::Alignment::Center);
+    if job.state == TransferState::Running {
+        let mut cancel = button::destructive("Cancel");
+        if controls_enabled {
+            cancel = cancel.on_press(NetworkMessage::CancelTransfer(job.id.clone()));
+        }
+        row = row.push(cancel);
+    }
+
+    row.into()
+}
+
+/// Transfers page: the form for a new transfer and the transfer history
+fn transfers_view(
+    state: &NetworkState,
+    form: &TransferFormState,
+    controls_enabled: bool,
+) -> Element<'static, NetworkMessage> {
+    let mut clear = button::standard("Clear History");
+    if controls_enabled && state.transfers.iter().any(|job| job.state.is_finished()) {
+        clear = clear.on_press(NetworkMessage::ClearTransferHistory);
+    }
+
+    let mut history = iced_widget::column![
+        iced_widget::row![
+            widget::text::heading("Transfers").width(Length::Fill),
+            clear,
+        ]
+        .align_y(cosmic::iced::Alignment::Center),
+    ]
+    .spacing(12)
+    .width(Length::Fill);
+
+    if state.transfers.is_empty() {
+        history = history.push(widget::text::caption("No transfers yet"));
+    }
+    for job in &state.transfers {
+        history = history.push(transfer_item(job, controls_enabled));
+    }
+
+    let layout = iced_widget::column![
+        widget::text::title2("Copy & Sync"),
+        transfer_form(state, form, controls_enabled),
+        bounded_form(history, 720),
+    ]
+    .spacing(20)
+    .width(Length::Fill);
+
+    widget::scrollable(layout)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .apply(widget::container)
+        .padding(20)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
@@ -0,0 +1,81 @@
+use crate::fl;
+use crate::message::app::Message;
+use cosmic::iced::Length;
+use cosmic::iced::alignment::Alignment;
+use cosmic::widget::{self, text::caption};
+use cosmic::{Element, iced_widget};
+use storage_contracts::OperationProgress;
+use storage_types::bytes_to_pretty;
+
+/// What an operation does, from the phase the service reports
+fn phase_label(phase: &str) -> String {
+    match phase {
+        "formatting" => fl!("operation-formatting"),
+        "tuning" => fl!("operation-tuning"),
+        "checking" => fl!("operation-checking"),
+        "repairing" => fl!("operation-repairing"),
+        "resizing" => fl!("operation-resizing"),
+        "shrinking_filesystem" => fl!("operation-shrinking-filesystem"),
+        "growing_filesystem" => fl!("operation-growing-filesystem"),
+        "moving" => fl!("operation-moving"),
+        "relabeling" => fl!("operation-relabeling"),
+        "backup_drive" | "backup_partition" => fl!("operation-backing-up"),
+        "restore_drive" | "restore_partition" => fl!("operation-restoring"),
+        "copy_partition" => fl!("operation-copying"),
+        "migrate_disk" => fl!("operation-migrating"),
+        "burn_disc" => fl!("operation-burning"),
+        "blank_disc" => fl!("operation-blanking"),
+        "create_live_usb" => fl!("operation-creating-live-usb"),
+        "self_test_short" => fl!("operation-self-test-short"),
+        "self_test_extended" => fl!("operation-self-test-extended"),
+        other => other.replace('_', " "),
+    }
+}
+
+fn operation_row(operation: &OperationProgress) -> Element<'static, Message> {
+    let mut label = format!("{} {}", phase_label(&operation.phase), operation.target);
+    if let Some(total) = operation.bytes_total {
+        label = format!(
+            "{label} · {} / {}",
+            bytes_to_pretty(&operation.bytes_processed, false),
+            bytes_to_pretty(&total, false)
+        );
+    }
+
+    // Operations that can't tell how far they got show an empty bar
+    let fraction = operation
+        .percent
+        .map_or(0.0, |percent| percent as f32 / 100.0);
+
+    let mut row = iced_widget::row![
+        caption(label).width(Length::FillPortion(2)),
+        iced_widget::progress_bar(0.0..=1.0, fraction)
+            .width(Length::FillPortion(1))
+            .height(6),
+    ]
+    .spacing(12)
+    .align_y(Alignment::Center);
+    if operation.cancellable {
+        row = row.push(
+            widget::button::text(fl!("cancel"))
+                .on_press(Message::CancelOperation(operation.operation_id)),
+        );
+    }
+    row.into()
+}
+
+/// Progress of the long operations running in the service, below the
+/// main view; None when there are none
+pub(crate) fn operations_footer(operations: &[OperationProgress]) -> Option<Element<'_, Message>> {
+    if operations.is_empty() {
+        return None;
+    }
+
+    let rows = operations.iter().map(operation_row);
+    Some(
+        widget::container(widget::Column::with_children(rows).spacing(6))
+            .padding([8, 12])
+            .width(Length::Fill)
+            .into(),
+    )
+}
@@ -1,13 +1,33 @@
 use cosmic::{Element, cosmic_theme, iced::Alignment, iced::Length, theme, widget};
-use storage_types::FilesystemToolInfo;
+use storage_types::{
+    ConfirmationLevel, DeferralReason, DeferredJob, FilesystemToolInfo, OperationKind,
+    OperationPriority, PowerPolicy, PowerSettings, PrioritySettings, SafetySnapshotPolicy,
+    ScheduledJob,
+};
 
 use crate::{
     app::{Message, REPOSITORY},
     config::Config,
     fl,
+    notification_policy::NotificationCategory,
 };
 
-pub fn settings<'a>(config: &Config) -> Element<'a, Message> {
+/// Retention choices offered for safety snapshots, in days
+const SAFETY_SNAPSHOT_RETENTION_DAYS: [u32; 5] = [1, 3, 7, 14, 30];
+
+/// Projected-full horizons offered for capacity notifications, in days
+const CAPACITY_ALERT_DAYS: [u64; 5] = [7, 14, 30, 60, 90];
+
+/// Battery charge thresholds offered for scheduled jobs, in percent
+const MIN_CHARGE_PERCENTS: [u8; 4] = [20, 30, 50, 80];
+
+pub fn settings<'a>(
+    config: &Config,
+    safety_snapshot_policy: Option<&SafetySnapshotPolicy>,
+    operation_priorities: Option<&PrioritySettings>,
+    power_policies: Option<&PowerSettings>,
+    deferred_jobs: &[DeferredJob],
+) -> Element<'a, Message> {
     let cosmic_theme::Spacing {
         space_s, space_m, ..
     } = theme::active().cosmic().spacing;
@@ -15,10 +35,130 @@ pub fn settings<'a>(config: &Config) -> Element<'a, Message> {
     let show_reserved_toggle = widget::checkbox("Show Reserved Space", config.show_reserved)
         .on_toggle(Message::ToggleShowReserved);
 
+    let byte_units_dropdown = widget::dropdown(
+        vec![fl!("byte-units-si"), fl!("byte-units-iec")],
+        Some(config.byte_units.to_index()),
+        Message::ByteUnitsChanged,
+    )
+    .width(cosmic::iced::Length::Shrink);
+
     let volumes_section = widget::container(
         widget::column()
             .push(widget::text::title4("Volumes"))
             .push(show_reserved_toggle)
+            .push(widget::text::caption(fl!("byte-units-label")))
+            .push(byte_units_dropdown)
+            .spacing(space_s)
+            .align_x(Alignment::Start),
+    )
+    .width(Length::Fill);
+
+    let mount_naming_options = vec![
+        fl!("mount-naming-label"),
+        fl!("mount-naming-uuid"),
+        fl!("mount-naming-device"),
+    ];
+
+    let mount_naming_dropdown = widget::dropdown(
+        mount_naming_options,
+        Some(config.mount_naming.to_index()),
+        Message::MountNamingSchemeChanged,
+    )
+    .width(cosmic::iced::Length::Shrink);
+
+    let mounting_section = widget::container(
+        widget::column()
+            .push(widget::text::title4(fl!("mounting")))
+            .push(widget::text::caption(fl!("mount-base-dir-label")))
+            .push(
+                widget::text_input(
+                    fl!("mount-base-dir-placeholder"),
+                    config.mount_base_dir.clone(),
+                )
+                .on_input(Message::MountBaseDirChanged),
+            )
+            .push(widget::text::caption(fl!("mount-naming-scheme-label")))
+            .push(mount_naming_dropdown)
+            .spacing(space_s)
+            .align_x(Alignment::Start),
+    )
+    .width(Length::Fill);
+
+    let temperature_unit_dropdown = widget::dropdown(
+        vec![
+            fl!("temperature-unit-celsius"),
+            fl!("temperature-unit-fahrenheit"),
+        ],
+        Some(config.temperature_unit.to_index()),
+        Message::TemperatureUnitChanged,
+    )
+    .width(cosmic::iced::Length::Shrink);
+
+    let temperature_section = widget::container(
+        widget::column()
+            .push(widget::text::title4(fl!("temperature")))
+            .push(widget::text::caption(fl!("temperature-unit-label")))
+            .push(temperature_unit_dropdown)
+            .push(widget::text::caption(fl!("temperature-thresholds-hint")))
+            .spacing(space_s)
+            .align_x(Alignment::Start),
+    )
+    .width(Length::Fill);
+
+    let confirmation_dropdown = widget::dropdown(
+        ConfirmationLevel::ALL
+            .iter()
+            .map(|level| match level {
+                ConfirmationLevel::Relaxed => fl!("confirmation-level-relaxed"),
+                ConfirmationLevel::Standard => fl!("confirmation-level-standard"),
+                ConfirmationLevel::Paranoid => fl!("confirmation-level-paranoid"),
+            })
+            .collect::<Vec<_>>(),
+        Some(config.confirmation_level.to_index()),
+        Message::ConfirmationLevelChanged,
+    )
+    .width(cosmic::iced::Length::Shrink);
+
+    let confirmation_section = widget::container(
+        widget::column()
+            .push(widget::text::title4(fl!("confirmation")))
+            .push(widget::text::caption(fl!("confirmation-level")))
+            .push(confirmation_dropdown)
+            .push(widget::text::caption(fl!("confirmation-level-description")))
+            .spacing(space_s)
+            .align_x(Alignment::Start),
+    )
+    .width(Length::Fill);
+
+    let capacity_alert_dropdown = widget::dropdown(
+        CAPACITY_ALERT_DAYS
+            .iter()
+            .map(|days| fl!("capacity-alert-days", days = *days))
+            .collect::<Vec<_>>(),
+        CAPACITY_ALERT_DAYS
+            .iter()
+            .position(|days| *days == config.capacity_alert_days),
+        |index| Message::CapacityAlertDaysChanged(CAPACITY_ALERT_DAYS[index]),
+    )
+    .width(cosmic::iced::Length::Shrink);
+
+    let notifications_section = widget::container(
+        NotificationCategory::ALL
+            .into_iter()
+            .fold(
+                widget::column().push(widget::text::title4(fl!("notifications"))),
+                |column, category| {
+                    column.push(
+                        widget::checkbox(category.label(), config.notifications.enabled(category))
+                            .on_toggle(move |enabled| {
+                                Message::NotificationCategoryToggled(category, enabled)
+                            }),
+                    )
+                },
+            )
+            .push(widget::text::caption(fl!("capacity-alert-label")))
+            .push(capacity_alert_dropdown)
+            .push(widget::text::caption(fl!("notifications-background-hint")))
             .spacing(space_s)
             .align_x(Alignment::Start),
     )
@@ -71,20 +211,259 @@ pub fn settings<'a>(config: &Config) -> Element<'a, Message> {
                 widget::checkbox("Log to disk", config.log_to_disk)
                     .on_toggle(Message::ToggleLogToDisk),
             )
+            .push(
+                widget::checkbox(fl!("statistics-enable"), config.usage_statistics)
+                    .on_toggle(Message::ToggleUsageStatistics),
+            )
+            .push(widget::text::caption(fl!("statistics-enable-description")))
             .spacing(space_s)
             .align_x(Alignment::Start),
     )
     .width(Length::Fill);
 
-    widget::column()
+    let mut sections = widget::column()
         .push(volumes_section)
-        .push(usage_section)
+        .push(mounting_section)
+        .push(temperature_section)
+        .push(confirmation_section)
+        .push(notifications_section)
+        .push(usage_section);
+    if let Some(policy) = safety_snapshot_policy {
+        sections = sections.push(safety_snapshots_section(policy));
+    }
+    if let Some(priorities) = operation_priorities {
+        sections = sections.push(operation_priorities_section(priorities));
+    }
+    if let Some(policies) = power_policies {
+        sections = sections.push(power_policies_section(policies, deferred_jobs));
+    }
+
+    sections
         .push(logging_section)
         .spacing(space_m)
         .width(Length::Fill)
         .into()
 }
 
+fn safety_snapshots_section<'a>(policy: &SafetySnapshotPolicy) -> Element<'a, Message> {
+    let space_s = theme::active().cosmic().spacing.space_s;
+
+    let enabled = {
+        let policy = policy.clone();
+        widget::checkbox(fl!("safety-snapshots-enabled"), policy.enabled).on_toggle(
+            move |enabled| {
+                Message::SafetySnapshotPolicyChanged(SafetySnapshotPolicy {
+                    enabled,
+                    ..policy.clone()
+                })
+            },
+        )
+    };
+
+    let mut on_resize = widget::checkbox(fl!("safety-snapshots-on-resize"), policy.on_resize);
+    let mut on_cleanup = widget::checkbox(fl!("safety-snapshots-on-cleanup"), policy.on_cleanup);
+    if policy.enabled {
+        let resize_policy = policy.clone();
+        on_resize = on_resize.on_toggle(move |on_resize| {
+            Message::SafetySnapshotPolicyChanged(SafetySnapshotPolicy {
+                on_resize,
+                ..resize_policy.clone()
+            })
+        });
+        let cleanup_policy = policy.clone();
+        on_cleanup = on_cleanup.on_toggle(move |on_cleanup| {
+            Message::SafetySnapshotPolicyChanged(SafetySnapshotPolicy {
+                on_cleanup,
+                ..cleanup_policy.clone()
+            })
+        });
+    }
+
+    let retention_options: Vec<String> = SAFETY_SNAPSHOT_RETENTION_DAYS
+        .iter()
+        .map(|days| fl!("safety-snapshots-retention-days", days = days))
+        .collect();
+    let selected = SAFETY_SNAPSHOT_RETENTION_DAYS
+        .iter()
+        .position(|days| *days == policy.retention_days);
+    let retention_policy = policy.clone();
+    let retention_dropdown = widget::dropdown(retention_options, selected, move |index| {
+        Message::SafetySnapshotPolicyChanged(SafetySnapshotPolicy {
+            retention_days: SAFETY_SNAPSHOT_RETENTION_DAYS[index],
+            ..retention_policy.clone()
+        })
+    })
+    .width(Length::Shrink);
+
+    widget::container(
+        widget::column()
+            .push(widget::text::title4(fl!("safety-snapshots")))
+            .push(widget::text::caption(fl!("safety-snapshots-description")))
+            .push(enabled)
+            .push(on_resize)
+            .push(on_cleanup)
+            .push(widget::text::caption(fl!("safety-snapshots-retention")))
+            .push(retention_dropdown)
+            .spacing(space_s)
+            .align_x(Alignment::Start),
+    )
+    .width(Length::Fill)
+    .into()
+}
+
+/// Name of a priority for the UI
+pub(crate) fn priority_label(priority: &OperationPriority) -> String {
+    match *priority {
+        OperationPriority::NORMAL => fl!("priority-normal"),
+        OperationPriority::LOW => fl!("priority-low"),
+        OperationPriority::IDLE => fl!("priority-idle"),
+        OperationPriority { nice, .. } => fl!("priority-custom", nice = nice),
+    }
+}
+
+fn operation_priorities_section<'a>(priorities: &PrioritySettings) -> Element<'a, Message> {
+    let space_s = theme::active().cosmic().spacing.space_s;
+
+    let labels: Vec<String> = OperationPriority::PRESETS
+        .iter()
+        .map(priority_label)
+        .collect();
+    let mut column = widget::column()
+        .push(widget::text::title4(fl!("operation-priorities")))
+        .push(widget::text::caption(fl!(
+            "operation-priorities-description"
+        )));
+    for kind in OperationKind::ALL {
+        let label = match kind {
+            OperationKind::ImageBackup => fl!("operation-kind-image-backup"),
+            OperationKind::ImageRestore => fl!("operation-kind-image-restore"),
+            OperationKind::PartitionCopy => fl!("operation-kind-partition-copy"),
+            OperationKind::UsageScan => fl!("operation-kind-usage-scan"),
+            OperationKind::Defragment => fl!("operation-kind-defragment"),
+        };
+        let current = priorities.priority(kind);
+        let selected = OperationPriority::PRESETS
+            .iter()
+            .position(|preset| *preset == current);
+        let settings = priorities.clone();
+        let dropdown = widget::dropdown(labels.clone(), selected, move |index| {
+            let mut settings = settings.clone();
+            settings.set_priority(kind, OperationPriority::PRESETS[index]);
+            Message::OperationPrioritiesChanged(settings)
+        })
+        .width(Length::Shrink);
+        column = column.push(widget::text::caption(label)).push(dropdown);
+    }
+
+    widget::container(column.spacing(space_s).align_x(Alignment::Start))
+        .width(Length::Fill)
+        .into()
+}
+
+fn scheduled_job_label(job: ScheduledJob) -> String {
+    match job {
+        ScheduledJob::SelfTest => fl!("scheduled-job-self-test"),
+        ScheduledJob::EspSync => fl!("scheduled-job-esp-sync"),
+    }
+}
+
+fn power_policies_section<'a>(
+    settings: &PowerSettings,
+    deferred_jobs: &[DeferredJob],
+) -> Element<'a, Message> {
+    let space_s = theme::active().cosmic().spacing.space_s;
+
+    let threshold_labels: Vec<String> = std::iter::once(fl!("power-min-charge-none"))
+        .chain(
+            MIN_CHARGE_PERCENTS
+                .iter()
+                .map(|percent| fl!("power-min-charge-percent", percent = percent)),
+        )
+        .collect();
+    let mut column = widget::column()
+        .push(widget::text::title4(fl!("power-policies")))
+        .push(widget::text::caption(fl!("power-policies-description")));
+    for job in ScheduledJob::ALL {
+        let policy = settings.policy(job);
+        let with_policy = {
+            let settings = settings.clone();
+            move |policy: PowerPolicy| {
+                let mut settings = settings.clone();
+                settings.policies.insert(job, policy);
+                Message::PowerPoliciesChanged(settings)
+            }
+        };
+
+        let defer = {
+            let with_policy = with_policy.clone();
+            widget::checkbox(fl!("power-defer-on-battery"), policy.defer_on_battery).on_toggle(
+                move |defer_on_battery| {
+                    with_policy(PowerPolicy {
+                        defer_on_battery,
+                        ..policy
+                    })
+                },
+            )
+        };
+        let selected = match policy.min_charge_percent {
+            None => Some(0),
+            Some(min) => MIN_CHARGE_PERCENTS
+                .iter()
+                .position(|percent| *percent == min)
+                .map(|index| index + 1),
+        };
+        let threshold = widget::dropdown(threshold_labels.clone(), selected, move |index| {
+            with_policy(PowerPolicy {
+                min_charge_percent: index.checked_sub(1).map(|i| MIN_CHARGE_PERCENTS[i]),
+                ..policy
+            })
+        })
+        .width(Length::Shrink);
+
+        column = column
+            .push(widget::text::body(scheduled_job_label(job)))
+            .push(defer);
+        if !policy.defer_on_battery {
+            column = column
+                .push(widget::text::caption(fl!("power-min-charge")))
+                .push(threshold);
+        }
+    }
+
+    if !deferred_jobs.is_empty() {
+        column = column.push(widget::text::title4(fl!("deferred-jobs")));
+        for entry in deferred_jobs {
+            let reason = match entry.reason {
+                DeferralReason::OnBattery => fl!("deferred-reason-on-battery"),
+                DeferralReason::LowCharge { percentage } => {
+                    fl!("deferred-reason-low-charge", percent = percentage)
+                }
+            };
+            let details = widget::column()
+                .push(widget::text::body(format!(
+                    "{}: {}",
+                    scheduled_job_label(entry.job),
+                    entry.label
+                )))
+                .push(widget::text::caption(reason))
+                .width(Length::Fill);
+            let run = widget::button::standard(fl!("run-anyway"))
+                .on_press(Message::RunDeferredJob(entry.job, entry.target.clone()));
+            column = column.push(
+                widget::row()
+                    .push(details)
+                    .push(run)
+                    .spacing(space_s)
+                    .align_y(Alignment::Center),
+            );
+        }
+    }
+
+    widget::container(column.spacing(space_s).align_x(Alignment::Start))
+        .width(Length::Fill)
+        .into()
+}
+
 pub fn settings_footer<'a>(filesystem_tools: &[FilesystemToolInfo]) -> Element<'a, Message> {
     let cosmic_theme::Spacing {
         space_xxs,
@@ -1,13 +1,21 @@
 use crate::app::Message;
 use crate::controls::layout::{row_container, transparent_button_class};
 use crate::models::{UiDrive, UiVolume};
+use crate::state::bind_mounts::BindMountsState;
+use crate::state::dialogs::ShowDialog;
+use crate::state::mtp::MtpState;
 use crate::state::network::NetworkState;
 use crate::state::sidebar::{SidebarNodeKey, SidebarState};
+use crate::state::user_mounts::UserMountsState;
 use crate::views::network::network_section;
 use cosmic::iced::Length;
 use cosmic::widget::{self, icon};
 use cosmic::{Apply, Element};
-use storage_types::VolumeKind;
+use storage_types::{
+    BindMount, DiskHealthSummary, HealthFactor, HealthLevel, MtpDevice, MtpProtocol,
+    PhysicalDevice, SmartTrend, TemperatureUnit, TrendAttribute, UserMount, UserMountKind,
+    VolumeKind, bytes_to_pretty,
+};
 
 /// Fixed width for expander button (icon 16px + padding 2px * 2)
 const EXPANDER_WIDTH: u16 = 20;
@@ -136,10 +144,353 @@ fn image_section_header(controls_enabled: bool) -> Element<'static, Message> {
         .into()
 }
 
+fn bind_mount_section_header(controls_enabled: bool) -> Element<'static, Message> {
+    let mut children: Vec<Element<'static, Message>> = vec![
+        widget::text::caption_heading(crate::fl!("bind-mounts")).into(),
+        widget::Space::new(Length::Fill, 0).into(),
+    ];
+
+    if controls_enabled {
+        let new_bind_button = widget::tooltip(
+            widget::button::custom(icon::from_name("list-add-symbolic").size(20))
+                .padding(4)
+                .class(cosmic::theme::Button::Link)
+                .on_press(Message::NewBindMount),
+            widget::text(crate::fl!("new-bind-mount")),
+            widget::tooltip::Position::Bottom,
+        );
+        children.push(new_bind_button.into());
+    }
+
+    widget::Row::with_children(children)
+        .padding([8, 12, 4, 12])
+        .spacing(6)
+        .align_y(cosmic::iced::Alignment::Center)
+        .into()
+}
+
+/// Sidebar row for a bind mount: opens its target in the file manager, with
+/// a button to unmount it when mounted and one to remove it from fstab when
+/// it is configured there
+fn bind_mount_row(
+    bind: &BindMount,
+    busy: bool,
+    controls_enabled: bool,
+) -> Element<'static, Message> {
+    let mut caption = bind.source.to_string_lossy().to_string();
+    if bind.read_only {
+        caption = format!("{caption} · {}", crate::fl!("bind-read-only-short"));
+    }
+    if bind.persistent {
+        caption = format!("{caption} · fstab");
+    }
+
+    let content = widget::Row::with_children(vec![
+        icon::from_name("folder-symbolic").size(16).into(),
+        widget::column::with_children(vec![
+            widget::text::body(bind.target.to_string_lossy().to_string()).into(),
+            widget::text::caption(caption).into(),
+        ])
+        .into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center)
+    .width(Length::Fill);
+
+    let enabled = controls_enabled && !busy;
+    let mut open_button = widget::button::custom(content)
+        .padding(0)
+        .width(Length::Fill)
+        .class(transparent_button_class(false));
+    if enabled && bind.mounted {
+        open_button =
+            open_button.on_press(Message::OpenPath(bind.target.to_string_lossy().to_string()));
+    }
+
+    let mut children: Vec<Element<'static, Message>> = vec![
+        widget::Space::new(EXPANDER_WIDTH, 0).into(),
+        open_button.into(),
+    ];
+    if bind.mounted {
+        let mut unmount_btn =
+            widget::button::custom(icon::from_name("media-eject-symbolic").size(16))
+                .padding(4)
+                .class(transparent_button_class(false));
+        if enabled {
+            unmount_btn = unmount_btn.on_press(Message::RemoveBindMount {
+                target: bind.target.clone(),
+                forget: false,
+            });
+        }
+        children.push(
+            widget::tooltip(
+                unmount_btn,
+                widget::text(crate::fl!("unmount")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+    if bind.persistent {
+        let mut remove_btn =
+            widget::button::custom(icon::from_name("edit-delete-symbolic").size(16))
+                .padding(4)
+                .class(transparent_button_class(false));
+        if enabled {
+            remove_btn = remove_btn.on_press(Message::RemoveBindMount {
+                target: bind.target.clone(),
+                forget: true,
+            });
+        }
+        children.push(
+            widget::tooltip(
+                remove_btn,
+                widget::text(crate::fl!("bind-remove")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        );
+    }
+
+    let row = widget::Row::with_children(children)
+        .spacing(8)
+        .align_y(cosmic::iced::Alignment::Center)
+        .width(Length::Fill);
+
+    row_container(row, false, enabled)
+}
+
+/// Sidebar row for a phone or camera: opens it in the file manager, or
+/// mounts it first, with a button to eject it
+fn mtp_device_row(
+    device: &MtpDevice,
+    busy: bool,
+    controls_enabled: bool,
+) -> Element<'static, Message> {
+    let icon_name = match device.protocol {
+        MtpProtocol::Mtp => "phone-symbolic",
+        MtpProtocol::Ptp => "camera-photo-symbolic",
+    };
+    let caption = match device.capacity() {
+        Some((size, free)) => format!(
+            "{} of {} used",
+            bytes_to_pretty(&size.saturating_sub(free), false),
+            bytes_to_pretty(&size, false)
+        ),
+        None if device.mounted => "Connected".to_string(),
+        None => "Not mounted".to_string(),
+    };
+
+    let content = widget::Row::with_children(vec![
+        icon::from_name(icon_name).size(16).into(),
+        widget::column::with_children(vec![
+            widget::text::body(device.name.clone()).into(),
+            widget::text::caption(caption).into(),
+        ])
+        .into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center)
+    .width(Length::Fill);
+
+    let mut open_button = widget::button::custom(content)
+        .padding(0)
+        .width(Length::Fill)
+        .class(transparent_button_class(false));
+    if controls_enabled && !busy {
+        open_button = open_button.on_press(match &device.path {
+            Some(path) => Message::OpenPath(path.to_string_lossy().to_string()),
+            None => Message::MountMtpDevice(device.uri.clone()),
+        });
+    }
+
+    let mut children = vec![
+        widget::Space::new(EXPANDER_WIDTH, 0).into(),
+        open_button.into(),
+    ];
+    if device.mounted && device.can_eject {
+        let mut eject_btn =
+            widget::button::custom(icon::from_name("media-eject-symbolic").size(16))
+                .padding(4)
+                .class(transparent_button_class(false));
+        if controls_enabled && !busy {
+            eject_btn = eject_btn.on_press(Message::EjectMtpDevice(device.uri.clone()));
+        }
+        children.push(eject_btn.into());
+    }
+
+    let row = widget::Row::with_children(children)
+        .spacing(8)
+        .align_y(cosmic::iced::Alignment::Center)
+        .width(Length::Fill);
+
+    row_container(row, false, controls_enabled && !busy)
+}
+
+/// Sidebar row for a disk behind a hardware RAID controller: read-only,
+/// shows its SMART data when clicked
+fn physical_device_row(
+    device: &PhysicalDevice,
+    unit: TemperatureUnit,
+    controls_enabled: bool,
+) -> Element<'static, Message> {
+    let health = match device.health_passed() {
+        Some(true) => crate::fl!("physical-device-healthy"),
+        Some(false) => crate::fl!("physical-device-failing"),
+        None => crate::fl!("physical-device-no-data"),
+    };
+    let caption = match device.size {
+        Some(size) => format!("{} · {}", bytes_to_pretty(&size, false), health),
+        None => health,
+    };
+    let icon_name = if device.health_passed() == Some(false) {
+        "dialog-warning-symbolic"
+    } else {
+        "drive-harddisk-symbolic"
+    };
+
+    let content = widget::Row::with_children(vec![
+        icon::from_name(icon_name).size(16).into(),
+        widget::column::with_children(vec![
+            widget::text::body(device.display_name()).into(),
+            widget::text::caption(caption).into(),
+        ])
+        .into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center)
+    .width(Length::Fill);
+
+    let mut details_button = widget::button::custom(content)
+        .padding(0)
+        .width(Length::Fill)
+        .class(transparent_button_class(false));
+    if controls_enabled {
+        details_button = details_button.on_press(Message::Dialog(Box::new(ShowDialog::Info {
+            title: device.display_name(),
+            body: physical_device_details(device, unit),
+        })));
+    }
+
+    let row = widget::Row::with_children(vec![
+        widget::Space::new(EXPANDER_WIDTH, 0).into(),
+        details_button.into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center)
+    .width(Length::Fill);
+
+    row_container(row, false, controls_enabled)
+}
+
+fn physical_device_details(device: &PhysicalDevice, unit: TemperatureUnit) -> String {
+    let mut lines = vec![crate::fl!(
+        "physical-device-behind",
+        controller = device.controller(),
+        device = device.device.clone()
+    )];
+    if let Some(serial) = &device.serial {
+        lines.push(crate::fl!(
+            "physical-device-serial",
+            serial = serial.clone()
+        ));
+    }
+    match &device.smart {
+        Some(smart) => {
+            lines.push(format!(
+                "{}: {}",
+                crate::fl!("smart-type"),
+                smart.device_type
+            ));
+            if let Some(passed) = device.health_passed() {
+                lines.push(if passed {
+                    crate::fl!("physical-device-healthy")
+                } else {
+                    crate::fl!("physical-device-failing")
+                });
+            }
+            if let Some(celsius) = smart.temperature_c {
+                lines.push(format!(
+                    "{}: {}",
+                    crate::fl!("smart-temperature"),
+                    unit.format(celsius as i64)
+                ));
+            }
+            if let Some(hours) = smart.power_on_hours {
+                lines.push(format!("{}: {}", crate::fl!("smart-power-on-hours"), hours));
+            }
+            if let Some(status) = &smart.selftest_status {
+                lines.push(format!("{}: {}", crate::fl!("smart-selftest"), status));
+            }
+        }
+        None => lines.push(crate::fl!("smart-no-data")),
+    }
+    if let Some(error) = &device.error {
+        lines.push(error.clone());
+    }
+    lines.push(crate::fl!("physical-device-read-only"));
+    lines.join("\n")
+}
+
+/// Sidebar row for a FUSE mount or gvfs share: opens it in the file manager,
+/// with a button to unmount it
+fn user_mount_row(
+    mount: &UserMount,
+    unmounting: bool,
+    controls_enabled: bool,
+) -> Element<'static, Message> {
+    let caption = match mount.kind {
+        UserMountKind::Gvfs => "gvfs".to_string(),
+        UserMountKind::Fuse => mount
+            .fs_type
+            .strip_prefix("fuse.")
+            .unwrap_or(&mount.fs_type)
+            .to_string(),
+    };
+
+    let content = widget::Row::with_children(vec![
+        icon::from_name("folder-remote-symbolic").size(16).into(),
+        widget::column::with_children(vec![
+            widget::text::body(mount.display_name()).into(),
+            widget::text::caption(caption).into(),
+        ])
+        .into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center)
+    .width(Length::Fill);
+
+    let mut open_button = widget::button::custom(content)
+        .padding(0)
+        .width(Length::Fill)
+        .class(transparent_button_class(false));
+    let mut unmount_btn =
+        widget::button::custom(icon::from_name("media-eject-symbolic").size(16)).padding(4);
+    unmount_btn = unmount_btn.class(transparent_button_class(false));
+    if controls_enabled && !unmounting {
+        open_button = open_button.on_press(Message::OpenPath(
+            mount.mount_point.to_string_lossy().to_string(),
+        ));
+        unmount_btn = unmount_btn.on_press(Message::UnmountUserMount(mount.mount_point.clone()));
+    }
+
+    let row = widget::Row::with_children(vec![
+        widget::Space::new(EXPANDER_WIDTH, 0).into(),
+        open_button.into(),
+        unmount_btn.into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center)
+    .width(Length::Fill);
+
+    row_container(row, false, controls_enabled && !unmounting)
+}
+
 fn drive_row(
     sidebar: &SidebarState,
     drive: &UiDrive,
     active_drive: Option<&str>,
+    unit: TemperatureUnit,
     controls_enabled: bool,
 ) -> Element<'static, Message> {
     let key = SidebarNodeKey::Drive(drive.device().to_string());
@@ -190,6 +541,14 @@ fn drive_row(
 
     let mut actions: Vec<Element<'static, Message>> = Vec::new();
 
+    if let Some(badge) = sidebar
+        .health
+        .get(drive.device())
+        .and_then(|summary| health_badge(summary, unit))
+    {
+        actions.push(badge);
+    }
+
     // Primary action: eject/remove for removable drives and loop-backed images.
     if drive.disk.is_loop || drive.disk.removable || drive.disk.ejectable {
         let mut eject_btn =
@@ -215,6 +574,100 @@ fn drive_row(
     row_container(row, selected, controls_enabled)
 }
 
+/// Warning icon for drives whose health score dropped, with the findings
+/// behind it as tooltip.
+fn health_badge(
+    summary: &DiskHealthSummary,
+    unit: TemperatureUnit,
+) -> Option<Element<'static, Message>> {
+    let icon_name = match summary.level {
+        HealthLevel::Warning => "dialog-warning-symbolic",
+        HealthLevel::Critical => "dialog-error-symbolic",
+        HealthLevel::Good | HealthLevel::Unknown => return None,
+    };
+
+    let mut lines = vec![crate::fl!("health-score", score = summary.score)];
+    lines.extend(
+        summary
+            .factors
+            .iter()
+            .map(|factor| health_factor_text(factor, unit)),
+    );
+    lines.extend(
+        summary
+            .trends
+            .iter()
+            .filter(|trend| trend.is_rising())
+            .map(health_trend_text),
+    );
+
+    Some(
+        widget::tooltip(
+            widget::container(icon::from_name(icon_name).size(16)).padding(4),
+            widget::text(lines.join("\n")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+    )
+}
+
+pub(crate) fn health_factor_text(factor: &HealthFactor, unit: TemperatureUnit) -> String {
+    let rising = |text: String, rising: bool| {
+        if rising {
+            crate::fl!("health-rising", finding = text)
+        } else {
+            text
+        }
+    };
+    match factor {
+        HealthFactor::SmartFailed => crate::fl!("health-smart-failed"),
+        HealthFactor::SelfTestFailed { result } => {
+            crate::fl!("health-selftest-failed", result = result.clone())
+        }
+        HealthFactor::ReallocatedSectors { count, rising: r } => {
+            rising(crate::fl!("health-reallocated-sectors", count = *count), *r)
+        }
+        HealthFactor::PendingSectors { count, rising: r } => {
+            rising(crate::fl!("health-pending-sectors", count = *count), *r)
+        }
+        HealthFactor::UncorrectableSectors { count, rising: r } => rising(
+            crate::fl!("health-uncorrectable-sectors", count = *count),
+            *r,
+        ),
+        HealthFactor::MediaErrors { count, rising: r } => {
+            rising(crate::fl!("health-media-errors", count = *count), *r)
+        }
+        HealthFactor::Wear { percent } => crate::fl!("health-wear", percent = *percent),
+        HealthFactor::Temperature { celsius } => {
+            crate::fl!(
+                "health-temperature",
+                temperature = unit.format(*celsius as i64)
+            )
+        }
+        HealthFactor::KernelErrors { count } => {
+            crate::fl!("health-kernel-errors", count = *count)
+        }
+        HealthFactor::BtrfsErrors { io, corruption } => {
+            crate::fl!("health-btrfs-errors", io = *io, corruption = *corruption)
+        }
+    }
+}
+
+fn health_trend_text(trend: &SmartTrend) -> String {
+    let attribute = match trend.attribute {
+        TrendAttribute::ReallocatedSectors => crate::fl!("health-trend-reallocated"),
+        TrendAttribute::PendingSectors => crate::fl!("health-trend-pending"),
+        TrendAttribute::UncorrectableSectors => crate::fl!("health-trend-uncorrectable"),
+        TrendAttribute::MediaErrors => crate::fl!("health-trend-media-errors"),
+        TrendAttribute::PercentageUsed => crate::fl!("health-trend-wear"),
+    };
+    crate::fl!(
+        "health-trend",
+        attribute = attribute,
+        rate = format!("{:.1}", trend.per_week)
+    )
+}
+
 fn volume_row(
     sidebar: &SidebarState,
     drive_block_path: &str,
@@ -357,10 +810,16 @@ fn push_volume_tree(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn sidebar(
     app_nav: &cosmic::widget::nav_bar::Model,
     sidebar: &SidebarState,
     network: &NetworkState,
+    user_mounts: &UserMountsState,
+    bind_mounts: &BindMountsState,
+    mtp: &MtpState,
+    physical_devices: &[PhysicalDevice],
+    unit: TemperatureUnit,
     controls_enabled: bool,
 ) -> Element<'static, Message> {
     let active_drive = sidebar.active_drive_block_path(app_nav);
@@ -397,6 +856,7 @@ pub(crate) fn sidebar(
                     sidebar,
                     drive,
                     active_drive.as_deref(),
+                    unit,
                     controls_enabled,
                 ));
 
@@ -436,9 +896,42 @@ pub(crate) fn sidebar(
     add_section(&mut rows, Section::Internal, internal);
     add_section(&mut rows, Section::External, external);
 
+    // Disks behind hardware RAID controllers, read-only
+    if !physical_devices.is_empty() {
+        rows.push(section_header(crate::fl!("physical-devices")));
+        for device in physical_devices {
+            rows.push(physical_device_row(device, unit, controls_enabled));
+        }
+    }
+
     // Network section (RClone, Samba, FTP)
     rows.push(network_section(network, controls_enabled).map(Message::Network));
 
+    // Phones and cameras
+    if !mtp.devices.is_empty() {
+        rows.push(section_header("Phones & Cameras".to_string()));
+        for device in &mtp.devices {
+            let busy = mtp.busy.contains(&device.uri);
+            rows.push(mtp_device_row(device, busy, controls_enabled));
+        }
+    }
+
+    // FUSE mounts and gvfs shares of the user
+    if !user_mounts.mounts.is_empty() {
+        rows.push(section_header("Other Mounts".to_string()));
+        for mount in &user_mounts.mounts {
+            let unmounting = user_mounts.unmounting.contains(&mount.mount_point);
+            rows.push(user_mount_row(mount, unmounting, controls_enabled));
+        }
+    }
+
+    // Bind mounts, always shown so that one can be added
+    rows.push(bind_mount_section_header(controls_enabled));
+    for bind in &bind_mounts.mounts {
+        let busy = bind_mounts.busy.contains(&bind.target);
+        rows.push(bind_mount_row(bind, busy, controls_enabled));
+    }
+
     // Images must remain the bottom-most section.
     add_section(&mut rows, Section::Images, images);
 
@@ -0,0 +1,89 @@
+use cosmic::{Element, cosmic_theme, iced::Length, theme, widget};
+use storage_types::bytes_to_pretty;
+
+use crate::{
+    app::Message, fl, message::statistics::StatisticsMessage, report::utc_time,
+    state::statistics::StatisticsState,
+};
+
+pub fn statistics(state: &StatisticsState) -> Element<'_, Message> {
+    let cosmic_theme::Spacing {
+        space_xxs, space_s, ..
+    } = theme::active().cosmic().spacing;
+
+    let mut refresh = widget::button::standard(fl!("refresh"));
+    if !state.loading {
+        refresh = refresh.on_press(StatisticsMessage::Load.into());
+    }
+    let mut content = widget::column()
+        .push(widget::row().push(widget::horizontal_space()).push(refresh))
+        .spacing(space_s)
+        .width(Length::Fill);
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(widget::text::caption(err.clone()));
+    }
+    let Some(stats) = state.stats.as_ref() else {
+        if state.loading {
+            content = content.push(widget::text::caption(fl!("working")));
+        }
+        return content.into();
+    };
+
+    if !stats.enabled {
+        content = content.push(widget::text::caption(fl!("statistics-disabled")));
+    }
+    if let Some(since) = stats.since {
+        content = content.push(widget::text::caption(fl!(
+            "statistics-since",
+            date = utc_time(since)
+        )));
+    }
+
+    let totals = widget::column()
+        .push(count_row(
+            fl!("statistics-operations"),
+            stats.operations.to_string(),
+        ))
+        .push(count_row(
+            fl!("statistics-backups"),
+            stats.backups.to_string(),
+        ))
+        .push(count_row(
+            fl!("statistics-bytes-backed-up"),
+            bytes_to_pretty(&stats.bytes_backed_up, false),
+        ))
+        .push(count_row(fl!("statistics-scans"), stats.scans.to_string()))
+        .push(count_row(
+            fl!("statistics-snapshots"),
+            stats.snapshots.to_string(),
+        ))
+        .spacing(space_xxs);
+    content = content
+        .push(totals)
+        .push(widget::text::title4(fl!("statistics-by-operation")));
+
+    if stats.by_operation.is_empty() {
+        content = content.push(widget::text::caption(fl!("statistics-none")));
+    } else {
+        let mut list = widget::column().spacing(2);
+        for (operation, count) in &stats.by_operation {
+            list = list.push(count_row(operation.clone(), count.to_string()));
+        }
+        content = content.push(list);
+    }
+
+    let mut clear = widget::button::destructive(fl!("statistics-clear"));
+    if stats.since.is_some() {
+        clear = clear.on_press(StatisticsMessage::Clear.into());
+    }
+    content.push(clear).into()
+}
+
+fn count_row<'a>(label: String, value: String) -> Element<'a, Message> {
+    widget::row()
+        .push(widget::text::body(label))
+        .push(widget::horizontal_space())
+        .push(widget::text::body(value))
+        .into()
+}
@@ -0,0 +1,155 @@
+use cosmic::{
+    Element, cosmic_theme, iced::Length, iced_widget, theme, widget, widget::icon,
+    widget::text_input,
+};
+use storage_types::{LegacyVault, VaultKind, bytes_to_pretty};
+
+use crate::{app::Message, fl, message::vaults::VaultsMessage, state::vaults::VaultsState};
+
+pub fn vaults(state: &VaultsState) -> Element<'_, Message> {
+    let cosmic_theme::Spacing {
+        space_xxs, space_s, ..
+    } = theme::active().cosmic().spacing;
+
+    let mut refresh = widget::button::standard(fl!("refresh"));
+    if !state.loading && state.busy.is_none() {
+        refresh = refresh.on_press(VaultsMessage::Load.into());
+    }
+    let mut content = widget::column()
+        .push(widget::text::caption(fl!("legacy-vaults-help")))
+        .push(widget::row().push(widget::horizontal_space()).push(refresh))
+        .spacing(space_s)
+        .width(Length::Fill);
+
+    if state.loading {
+        content = content.push(widget::text::caption(fl!("legacy-vaults-loading")));
+    } else if state.vaults.is_empty() {
+        content = content.push(widget::text::caption(fl!("legacy-vaults-none")));
+    }
+
+    let mut list = widget::column().spacing(space_xxs);
+    for vault in &state.vaults {
+        list = list.push(vault_row(state, vault));
+    }
+    content = content.push(list);
+
+    if let Some(err) = state.error.as_ref() {
+        content = content.push(widget::text::caption(err.clone()));
+    }
+    content.into()
+}
+
+/// One vault, with mounting and migration when selected
+fn vault_row<'a>(state: &'a VaultsState, vault: &'a LegacyVault) -> Element<'a, Message> {
+    let selected = state.selected.as_ref() == Some(&vault.cipher_dir);
+    let busy = state.busy.is_some();
+    let kind = match vault.kind {
+        VaultKind::Ecryptfs => "eCryptfs",
+        VaultKind::Gocryptfs => "gocryptfs",
+    };
+    let owner = vault
+        .owner
+        .clone()
+        .unwrap_or_else(|| vault.owner_uid.to_string());
+    let status = match &vault.mount_point {
+        Some(mount_point) => fl!("legacy-vault-mounted", mount_point = mount_point.clone()),
+        None => fl!("legacy-vault-not-mounted"),
+    };
+
+    let expander = if selected {
+        "go-down-symbolic"
+    } else {
+        "go-next-symbolic"
+    };
+    let mut expand = widget::button::icon(icon::from_name(expander));
+    if !busy {
+        expand = expand.on_press(VaultsMessage::Select(vault.cipher_dir.clone()).into());
+    }
+    let header = widget::row()
+        .push(expand)
+        .push(
+            widget::column()
+                .push(widget::text::body(vault.cipher_dir.clone()))
+                .push(widget::text::caption(format!("{kind} · {owner}")))
+                .push(widget::text::caption(status)),
+        )
+        .spacing(8)
+        .align_y(cosmic::iced::Alignment::Center);
+
+    let mut row = widget::column().push(header).spacing(8);
+    if !selected {
+        return row.into();
+    }
+
+    let Some(mount_point) = vault.mount_point.clone() else {
+        let label = match vault.kind {
+            VaultKind::Ecryptfs => fl!("legacy-vault-login-passphrase"),
+            VaultKind::Gocryptfs => fl!("passphrase"),
+        };
+        let mut passphrase =
+            text_input::secure_input("", state.passphrase.clone(), None, true).label(label);
+        let mut mount = widget::button::standard(fl!("mount"));
+        if !busy {
+            passphrase = passphrase
+                .on_input(|v| VaultsMessage::PassphraseUpdate(v).into())
+                .on_submit(|_| VaultsMessage::Mount.into());
+            if !state.passphrase.is_empty() {
+                mount = mount.on_press(VaultsMessage::Mount.into());
+            }
+        }
+        return row
+            .push(passphrase)
+            .push(widget::text::caption(fl!("legacy-vault-mount-help")))
+            .push(widget::row().push(widget::horizontal_space()).push(mount))
+            .into();
+    };
+
+    let mut open = widget::button::standard(fl!("legacy-vault-open"));
+    let mut unmount = widget::button::standard(fl!("unmount"));
+    let mut target = text_input(fl!("legacy-vault-target-placeholder"), state.target.clone())
+        .label(fl!("legacy-vault-target"));
+    let mut migrate = widget::button::suggested(fl!("legacy-vault-migrate"));
+    open = open.on_press(Message::OpenPath(mount_point));
+    if !busy {
+        unmount = unmount.on_press(VaultsMessage::Unmount(vault.cipher_dir.clone()).into());
+        target = target.on_input(|v| VaultsMessage::TargetUpdate(v).into());
+        if !state.target.trim().is_empty() {
+            migrate = migrate.on_press(VaultsMessage::Migrate.into());
+        }
+    }
+
+    row = row
+        .push(
+            widget::row()
+                .push(widget::horizontal_space())
+                .push(open)
+                .push(unmount)
+                .spacing(8),
+        )
+        .push(widget::text::caption_heading(fl!("legacy-vault-migrate")))
+        .push(widget::text::caption(fl!("legacy-vault-migrate-help")))
+        .push(target)
+        .push(widget::row().push(widget::horizontal_space()).push(migrate));
+
+    if let Some((copied, total)) = state.progress {
+        let fraction = if total > 0 {
+            (copied as f64 / total as f64).min(1.0) as f32
+        } else {
+            0.0_f32
+        };
+        row = row
+            .push(iced_widget::progress_bar(0.0..=1.0, fraction).width(Length::Fill))
+            .push(widget::text::caption(fl!(
+                "legacy-vault-progress",
+                copied = bytes_to_pretty(&copied, false),
+                total = bytes_to_pretty(&total, false)
+            )));
+    }
+    if let Some(result) = state.result.as_ref() {
+        row = row.push(widget::text::caption(fl!(
+            "legacy-vault-migrated",
+            files = result.files
+        )));
+    }
+    row.into()
+}
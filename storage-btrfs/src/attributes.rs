@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! File attribute helpers
+//!
+//! Reads and changes the no-copy-on-write attribute (`C`) with
+//! `lsattr`/`chattr`.
+
+use crate::error::{BtrfsError, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Whether the `C` attribute is set, from `lsattr -d` output
+pub(crate) fn parse_lsattr_nocow(output: &str) -> Option<bool> {
+    // ---------------C------ /mnt/data/vm-images
+    let flags = output.lines().next()?.split_whitespace().next()?;
+    Some(flags.contains('C'))
+}
+
+pub(crate) fn get_nocow(path: &Path) -> Result<bool> {
+    let output = Command::new("lsattr")
+        .arg("-d")
+        .arg(path)
+        .output()
+        .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run lsattr: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(BtrfsError::OperationFailed(format!(
+            "Failed to read attributes of {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    parse_lsattr_nocow(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+        BtrfsError::OperationFailed(format!("Unexpected lsattr output for {}", path.display()))
+    })
+}
+
+pub(crate) fn set_nocow(path: &Path, enabled: bool) -> Result<()> {
+    let output = Command::new("chattr")
+        .arg(if enabled { "+C" } else { "-C" })
+        .arg("--")
+        .arg(path)
+        .output()
+        .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run chattr: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(BtrfsError::OperationFailed(format!(
+            "Failed to change attributes of {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lsattr_output() {
+        assert_eq!(
+            parse_lsattr_nocow("---------------C------ /mnt/data/vm images\n"),
+            Some(true)
+        );
+        assert_eq!(
+            parse_lsattr_nocow("--------------e------- /mnt/data\n"),
+            Some(false)
+        );
+        assert_eq!(parse_lsattr_nocow(""), None);
+    }
+}
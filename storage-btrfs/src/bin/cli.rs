@@ -27,7 +27,7 @@ enum Commands {
     Create {
         /// Mount point of the BTRFS filesystem
         mount_point: PathBuf,
-        /// Name of the subvolume to create
+        /// Path of the subvolume to create, relative to the mount point
         name: String,
     },
     /// Delete a subvolume
@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Compression helpers
+//!
+//! Parsing of `btrfs property get`, `/proc/self/mounts` and `compsize` output,
+//! plus the sample-based savings estimate used before recompressing data.
+
+use crate::error::{BtrfsError, Result};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Upper bound on data fed to the test compressor
+const SAMPLE_LIMIT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Validate a `compress=` value: "none", or an algorithm with an optional level
+pub(crate) fn validate_mount_value(value: &str) -> Result<()> {
+    let (algorithm, level) = match value.split_once(':') {
+        Some((algorithm, level)) => (algorithm, Some(level)),
+        None => (value, None),
+    };
+
+    let max_level = match algorithm {
+        "none" if level.is_none() => 0,
+        "lzo" if level.is_none() => 0,
+        "zlib" => 9,
+        "zstd" => 15,
+        _ => {
+            return Err(BtrfsError::OperationFailed(format!(
+                "Unsupported compression option: {}",
+                value
+            )));
+        }
+    };
+
+    if let Some(level) = level
+        && !level
+            .parse::<u32>()
+            .is_ok_and(|n| (1..=max_level).contains(&n))
+    {
+        return Err(BtrfsError::OperationFailed(format!(
+            "Invalid compression level: {}",
+            value
+        )));
+    }
+
+    Ok(())
+}
+
+/// Value of `compression=...` from `btrfs property get`, None when unset
+pub(crate) fn parse_property(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("compression="))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// `(algorithm, force)` from the mount options of `mount_point` in a mounts table
+pub(crate) fn parse_mount_compression(mounts: &str, mount_point: &str) -> Option<(String, bool)> {
+    let options = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _source = fields.next()?;
+            let target = fields.next()?.replace("\\040", " ");
+            let _fs_type = fields.next()?;
+            let options = fields.next()?;
+            (target == mount_point).then(|| options.to_string())
+        })
+        .next_back()?;
+
+    options.split(',').find_map(|option| {
+        if let Some(value) = option.strip_prefix("compress-force=") {
+            Some((value.to_string(), true))
+        } else {
+            option
+                .strip_prefix("compress=")
+                .map(|value| (value.to_string(), false))
+        }
+    })
+}
+
+/// Current on-disk size from the `TOTAL` row of `compsize -b`
+pub(crate) fn parse_compsize_disk_bytes(output: &str) -> Option<u64> {
+    // Type       Perc     Disk Usage   Uncompressed Referenced
+    // TOTAL       62%      123456789    198765432    198765432
+    output
+        .lines()
+        .find(|line| line.trim_start().starts_with("TOTAL"))
+        .and_then(|line| line.split_whitespace().nth(2))
+        .and_then(|value| value.parse().ok())
+}
+
+/// Run `compsize` on `path` if it is installed
+pub(crate) fn compsize_disk_bytes(path: &Path) -> Option<u64> {
+    let output = Command::new("compsize")
+        .args(["-b", "-x"])
+        .arg(path)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| parse_compsize_disk_bytes(&String::from_utf8_lossy(&output.stdout)))
+        .flatten()
+}
+
+/// Regular files below `path` on the same filesystem, with their total size
+pub(crate) fn collect_files(path: &Path) -> Result<(Vec<(PathBuf, u64)>, u64)> {
+    let root_dev = fs::metadata(path)?.dev();
+    let mut files = Vec::new();
+    let mut total = 0_u64;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.dev() != root_dev {
+                continue;
+            }
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total = total.saturating_add(metadata.len());
+                files.push((entry.path(), metadata.len()));
+            }
+        }
+    }
+
+    Ok((files, total))
+}
+
+/// Compress a sample of `files` with zstd and return `(sampled, compressed)` bytes
+///
+/// At most [`SAMPLE_LIMIT_BYTES`] are read, the last file only in part, and
+/// fed to zstd through its stdin.
+pub(crate) fn sample_compression(files: &[(PathBuf, u64)]) -> Result<(u64, u64)> {
+    if files.iter().all(|(_, size)| *size == 0) {
+        return Ok((0, 0));
+    }
+
+    let mut child = Command::new("zstd")
+        .args(["-q", "-c", "-3"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run zstd: {}", e)))?;
+
+    let (Some(mut stdin), Some(mut stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(BtrfsError::CommandFailed(
+            "Failed to connect to zstd".to_string(),
+        ));
+    };
+    // Feed zstd from another thread so that its output never fills the pipe
+    let (sampled, compressed) = std::thread::scope(|scope| {
+        let writer = scope.spawn(move || write_sample(files, SAMPLE_LIMIT_BYTES, &mut stdin));
+        let compressed = io::copy(&mut stdout, &mut io::sink());
+        let sampled = writer
+            .join()
+            .unwrap_or_else(|_| Err(io::ErrorKind::Other.into()));
+        (sampled, compressed)
+    });
+    let _ = child.wait()?;
+
+    Ok((sampled?, compressed?))
+}
+
+/// Write the contents of `files` to `sink`, `limit` bytes at most, and return
+/// how many were written
+///
+/// Files that can't be read, or stop being readable, are left out from there on.
+fn write_sample(files: &[(PathBuf, u64)], limit: u64, sink: &mut impl Write) -> io::Result<u64> {
+    let mut buffer = vec![0_u8; 64 * 1024];
+    let mut sampled = 0_u64;
+    for (path, size) in files {
+        if sampled >= limit {
+            break;
+        }
+        if *size == 0 {
+            continue;
+        }
+        let Ok(file) = fs::File::open(path) else {
+            continue;
+        };
+        let mut reader = file.take(limit - sampled);
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            sink.write_all(&buffer[..read])?;
+            sampled += read as u64;
+        }
+    }
+    Ok(sampled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_property_mounts_and_compsize() {
+        assert_eq!(
+            parse_property("compression=zstd\n"),
+            Some("zstd".to_string())
+        );
+        assert_eq!(parse_property("compression=\n"), None);
+        assert_eq!(parse_property(""), None);
+
+        let mounts = "/dev/sda2 / btrfs rw,relatime,compress=zstd:3,space_cache=v2 0 0\n/dev/sdb1 /mnt/data btrfs rw,compress-force=lzo 0 0\n";
+        assert_eq!(
+            parse_mount_compression(mounts, "/"),
+            Some(("zstd:3".to_string(), false))
+        );
+        assert_eq!(
+            parse_mount_compression(mounts, "/mnt/data"),
+            Some(("lzo".to_string(), true))
+        );
+        assert_eq!(parse_mount_compression(mounts, "/home"), None);
+
+        let compsize = "Processed 1204 files, 980 regular extents (1010 refs), 230 inline.\nType       Perc     Disk Usage   Uncompressed Referenced\nTOTAL       62%      123456789    198765432    198765432\nnone       100%       23456789     23456789     23456789\n";
+        assert_eq!(parse_compsize_disk_bytes(compsize), Some(123456789));
+    }
+
+    #[test]
+    fn sample_stops_at_the_limit() {
+        let dir = std::env::temp_dir().join(format!(
+            "storage-btrfs-compression-sample-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let files: Vec<(PathBuf, u64)> = [("a", 600_u64), ("b", 600), ("c", 600)]
+            .into_iter()
+            .map(|(name, size)| {
+                let path = dir.join(name);
+                fs::write(&path, vec![b'x'; size as usize]).unwrap();
+                (path, size)
+            })
+            .collect();
+
+        let mut sink = Vec::new();
+        let sampled = write_sample(&files, 1000, &mut sink).unwrap();
+        let mut all = Vec::new();
+        let everything = write_sample(&files, u64::MAX, &mut all).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(sampled, 1000);
+        assert_eq!(sink.len(), 1000);
+        assert_eq!(everything, 1800);
+        assert_eq!(all.len(), 1800);
+    }
+
+    #[test]
+    fn validates_mount_values() {
+        for value in ["none", "lzo", "zlib", "zlib:9", "zstd", "zstd:1", "zstd:15"] {
+            assert!(validate_mount_value(value).is_ok(), "{value}");
+        }
+        for value in ["zstd:0", "zstd:16", "lzo:1", "none:1", "gzip", "zstd,ssd"] {
+            assert!(validate_mount_value(value).is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Per-device error counters
+//!
+//! Reads `btrfs device stats <mountpoint>`, which prints one line per
+//! device and counter, e.g. `[/dev/sda1].corruption_errs 0`. With
+//! `--reset` it prints the same before zeroing the counters.
+
+use crate::error::{BtrfsError, Result};
+use std::path::Path;
+use std::process::Command;
+use storage_types::btrfs::BtrfsDeviceStats;
+
+/// Error counters of each device of the filesystem mounted at `mount_point`
+pub fn device_stats(mount_point: &Path) -> Result<Vec<BtrfsDeviceStats>> {
+    run_device_stats(mount_point, false)
+}
+
+/// Zero the error counters of each device, returning their values from
+/// before the reset
+///
+/// The counters persist across mounts, so once an error has been dealt
+/// with (e.g. a scrub repaired the blocks or a cable was replaced) they
+/// are reset to notice new errors.
+pub fn reset_device_stats(mount_point: &Path) -> Result<Vec<BtrfsDeviceStats>> {
+    run_device_stats(mount_point, true)
+}
+
+fn run_device_stats(mount_point: &Path, reset: bool) -> Result<Vec<BtrfsDeviceStats>> {
+    let mut command = Command::new("btrfs");
+    command.args(["device", "stats"]);
+    if reset {
+        command.arg("--reset");
+    }
+    let output = command
+        .arg(mount_point)
+        .output()
+        .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run btrfs: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(BtrfsError::OperationFailed(format!(
+            "Failed to {} device stats of {}: {}",
+            if reset { "reset" } else { "read" },
+            mount_point.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(parse_device_stats(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Devices in the order they are first listed; unknown counters are skipped
+fn parse_device_stats(text: &str) -> Vec<BtrfsDeviceStats> {
+    let mut devices: Vec<BtrfsDeviceStats> = Vec::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        let Some((device, counter)) = key.strip_prefix('[').and_then(|key| key.split_once("]."))
+        else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+
+        let index = match devices.iter().position(|stats| stats.device == device) {
+            Some(index) => index,
+            None => {
+                devices.push(BtrfsDeviceStats {
+                    device: device.to_string(),
+                    ..BtrfsDeviceStats::default()
+                });
+                devices.len() - 1
+            }
+        };
+        let stats = &mut devices[index];
+        match counter {
+            "write_io_errs" => stats.write_io_errs = value,
+            "read_io_errs" => stats.read_io_errs = value,
+            "flush_io_errs" => stats.flush_io_errs = value,
+            "corruption_errs" => stats.corruption_errs = value,
+            "generation_errs" => stats.generation_errs = value,
+            _ => {}
+        }
+    }
+    devices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_counters_per_device() {
+        let output = "\
+[/dev/sda1].write_io_errs    0
+[/dev/sda1].read_io_errs     3
+[/dev/sda1].flush_io_errs    0
+[/dev/sda1].corruption_errs  0
+[/dev/sda1].generation_errs  0
+[/dev/sdb1].write_io_errs    0
+[/dev/sdb1].read_io_errs     0
+[/dev/sdb1].flush_io_errs    0
+[/dev/sdb1].corruption_errs  12
+[/dev/sdb1].generation_errs  1
+";
+        let devices = parse_device_stats(output);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].device, "/dev/sda1");
+        assert_eq!(devices[0].read_io_errs, 3);
+        assert_eq!(devices[0].total_errors(), 3);
+        assert_eq!(devices[1].corruption_errs, 12);
+        assert_eq!(devices[1].generation_errs, 1);
+    }
+}
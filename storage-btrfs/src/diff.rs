@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Snapshot comparison
+//!
+//! Runs `btrfs send --no-data -p <old> <new>` into `btrfs receive --dump` and
+//! folds the resulting stream of operations into per-path changes.
+
+use crate::error::{BtrfsError, Result};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_types::btrfs::{SnapshotChangeKind, SnapshotDiffEntry};
+
+/// Changed paths between two read-only snapshots of the same subvolume, sorted by path
+pub(crate) fn snapshot_changes(old: &Path, new: &Path) -> Result<Vec<SnapshotDiffEntry>> {
+    let mut send = Command::new("btrfs")
+        .args(["send", "--no-data", "-q", "-p"])
+        .arg(old)
+        .arg(new)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run btrfs send: {}", e)))?;
+
+    let stream = send
+        .stdout
+        .take()
+        .ok_or_else(|| BtrfsError::CommandFailed("btrfs send has no output".to_string()))?;
+
+    let dump = Command::new("btrfs")
+        .args(["receive", "--dump"])
+        .stdin(stream)
+        .output()
+        .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run btrfs receive: {}", e)))?;
+
+    let mut send_stderr = String::new();
+    if let Some(mut stderr) = send.stderr.take() {
+        let _ = stderr.read_to_string(&mut send_stderr);
+    }
+    let send_status = send.wait()?;
+
+    if !send_status.success() {
+        return Err(BtrfsError::OperationFailed(format!(
+            "Failed to compare {} with {}: {}",
+            old.display(),
+            new.display(),
+            send_stderr.trim()
+        )));
+    }
+    if !dump.status.success() {
+        return Err(BtrfsError::OperationFailed(format!(
+            "Failed to read send stream: {}",
+            String::from_utf8_lossy(&dump.stderr).trim()
+        )));
+    }
+
+    Ok(parse_dump(&String::from_utf8_lossy(&dump.stdout)))
+}
+
+/// Fold `btrfs receive --dump` output into per-path changes
+pub(crate) fn parse_dump(output: &str) -> Vec<SnapshotDiffEntry> {
+    let mut changes: BTreeMap<String, SnapshotChangeKind> = BTreeMap::new();
+
+    for line in output.lines() {
+        let fields = split_fields(line);
+        let mut fields = fields.iter().map(String::as_str);
+        let (Some(command), Some(path)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let path = strip_snapshot_root(&unescape(path));
+        let dest = fields
+            .find_map(|field| field.strip_prefix("dest="))
+            .map(|dest| strip_snapshot_root(&unescape(dest)));
+
+        match command {
+            // New inodes get a temporary name and are renamed into place
+            "mkfile" | "mkdir" | "mknod" | "mkfifo" | "mksock" | "symlink"
+                if is_temporary(&path) => {}
+            "mkfile" | "mkdir" | "mknod" | "mkfifo" | "mksock" | "symlink" => {
+                record(&mut changes, path, SnapshotChangeKind::Added)
+            }
+            "rename" => {
+                let Some(dest) = dest else { continue };
+                if !is_temporary(&path) {
+                    record(&mut changes, path, SnapshotChangeKind::Deleted);
+                }
+                if !is_temporary(&dest) {
+                    record(&mut changes, dest, SnapshotChangeKind::Added);
+                }
+            }
+            // "link <new name> dest=<existing name>"
+            "link" => record(&mut changes, path, SnapshotChangeKind::Added),
+            "unlink" | "rmdir" => record(&mut changes, path, SnapshotChangeKind::Deleted),
+            "write" | "update_extent" | "clone" | "truncate" | "chmod" | "chown" | "set_xattr"
+            | "remove_xattr" | "fallocate" | "fileattr"
+                if !is_temporary(&path) =>
+            {
+                record(&mut changes, path, SnapshotChangeKind::Modified)
+            }
+            // utimes follows every change to a directory entry; snapshot, end
+            // and the rest carry no content changes
+            _ => {}
+        }
+    }
+
+    changes
+        .into_iter()
+        .filter(|(path, _)| !path.is_empty())
+        .map(|(path, kind)| SnapshotDiffEntry { path, kind })
+        .collect()
+}
+
+/// Merge a new operation on `path` with what is already known about it
+fn record(
+    changes: &mut BTreeMap<String, SnapshotChangeKind>,
+    path: String,
+    kind: SnapshotChangeKind,
+) {
+    use SnapshotChangeKind::{Added, Deleted, Modified};
+
+    match (changes.get(&path).copied(), kind) {
+        // Created and removed again between the snapshots
+        (Some(Added), Deleted) => {
+            changes.remove(&path);
+        }
+        (Some(Added), _) => {}
+        // Replaced by a new file of the same name
+        (Some(Deleted), Added) => {
+            changes.insert(path, Modified);
+        }
+        _ => {
+            changes.insert(path, kind);
+        }
+    }
+}
+
+/// Drop the leading `./<snapshot name>` the dump prefixes to every path
+fn strip_snapshot_root(path: &str) -> String {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    match path.split_once('/') {
+        Some((_, rest)) => rest.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Whether the last component is a send-stream temporary name (`o<ino>-<gen>-<n>`)
+fn is_temporary(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.strip_prefix('o').is_some_and(|rest| {
+        let parts: Vec<&str> = rest.split('-').collect();
+        parts.len() == 3
+            && parts
+                .iter()
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
+
+/// Split a dump line on whitespace that is not escaped with a backslash
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    fields.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        fields.push(current);
+    }
+    fields
+}
+
+/// Undo the backslash escaping `btrfs receive --dump` applies to paths
+fn unescape(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 1 < bytes.len() {
+            let octal = bytes
+                .get(i + 1..i + 4)
+                .filter(|digits| digits.iter().all(|b| (b'0'..=b'7').contains(b)));
+            if let Some(digits) = octal {
+                let value = digits
+                    .iter()
+                    .fold(0_u32, |acc, b| acc * 8 + u32::from(b - b'0'));
+                out.push(value as u8);
+                i += 4;
+            } else {
+                out.push(bytes[i + 1]);
+                i += 2;
+            }
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_dump_into_changes() {
+        let dump = "\
+snapshot        ./snap2                         uuid=1 transid=20 parent_uuid=0 parent_transid=10
+utimes          ./snap2/                        atime=x mtime=x ctime=x
+mkfile          ./snap2/o257-21-0
+rename          ./snap2/o257-21-0               dest=./snap2/new\\ file.txt
+update_extent   ./snap2/new\\ file.txt           offset=0 len=4096
+update_extent   ./snap2/docs/report.odt         offset=0 len=8192
+unlink          ./snap2/old.log
+mkdir           ./snap2/o258-21-0
+rename          ./snap2/o258-21-0               dest=./snap2/photos
+rename          ./snap2/a.txt                   dest=./snap2/b.txt
+mkfile          ./snap2/o259-21-0
+rename          ./snap2/o259-21-0               dest=./snap2/tmp
+unlink          ./snap2/tmp
+rmdir           ./snap2/cache
+utimes          ./snap2/docs                    atime=x mtime=x ctime=x
+";
+        let entries = parse_dump(dump);
+        let changes: Vec<(&str, SnapshotChangeKind)> = entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.kind))
+            .collect();
+
+        use SnapshotChangeKind::*;
+        assert_eq!(
+            changes,
+            [
+                ("a.txt", Deleted),
+                ("b.txt", Added),
+                ("cache", Deleted),
+                ("docs/report.odt", Modified),
+                ("new file.txt", Added),
+                ("old.log", Deleted),
+                ("photos", Added),
+            ]
+        );
+    }
+}
@@ -5,14 +5,27 @@
 //! This library provides a safe Rust interface for BTRFS subvolume management
 //! operations including creation, deletion, snapshots, and metadata queries.
 
+mod attributes;
+mod compression;
+mod device_stats;
+mod diff;
 pub mod error;
+mod paths;
+mod restore;
+mod rollback;
+pub mod safety;
 pub mod subvolume;
 pub mod usage;
 
 // Re-export commonly used types
+pub use device_stats::{device_stats, reset_device_stats};
 pub use error::{BtrfsError, Result};
 pub use subvolume::SubvolumeManager;
 pub use usage::get_filesystem_usage;
 
 // Re-export shared models
-pub use storage_types::btrfs::{BtrfsSubvolume, DeletedSubvolume, FilesystemUsage, SubvolumeList};
+pub use storage_types::btrfs::{
+    BtrfsDeviceStats, BtrfsSubvolume, CompressionEstimate, CompressionInfo, DeletedSubvolume,
+    FilesystemUsage, NocowStatus, RestoreConflictPolicy, RestoreResult, RollbackResult,
+    SafetySnapshotPolicy, SnapshotChangeKind, SnapshotDiff, SnapshotDiffEntry, SubvolumeList,
+};
@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Path normalization for paths supplied relative to a mount point
+
+use crate::error::{BtrfsError, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Lexically normalize a path inside a filesystem.
+///
+/// A leading `/` refers to the mount point, `.` and empty components are
+/// dropped and `..` removes the previous component. Paths that would climb
+/// above the mount point, or that normalize to the mount point itself, are
+/// rejected.
+pub(crate) fn normalize_relative(path: &str) -> Result<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in Path::new(path).components() {
+        match component {
+            Component::RootDir | Component::CurDir => {}
+            Component::Normal(name) => normalized.push(name),
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(BtrfsError::InvalidPath(format!(
+                        "{} escapes the mount point",
+                        path
+                    )));
+                }
+            }
+            Component::Prefix(_) => {
+                return Err(BtrfsError::InvalidPath(path.to_string()));
+            }
+        }
+    }
+
+    if normalized.as_os_str().is_empty() {
+        return Err(BtrfsError::InvalidPath(format!(
+            "'{}' does not name a path below the mount point",
+            path
+        )));
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_and_rejects_escapes() {
+        let ok = |path: &str| normalize_relative(path).unwrap();
+        assert_eq!(ok("data"), PathBuf::from("data"));
+        assert_eq!(ok("/@home//alice/./vm"), PathBuf::from("@home/alice/vm"));
+        assert_eq!(ok("a/b/../c"), PathBuf::from("a/c"));
+
+        for path in ["", "/", ".", "a/..", "../etc", "a/../../b", "/.."] {
+            assert!(normalize_relative(path).is_err(), "{path}");
+        }
+    }
+}
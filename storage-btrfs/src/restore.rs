@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Restoring individual files and directories from a snapshot
+//!
+//! Copies are made with `cp -a --reflink=auto`, so data is shared with the
+//! snapshot instead of duplicated whenever both live on the same filesystem.
+
+use crate::error::{BtrfsError, Result};
+use crate::paths;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use storage_types::btrfs::{RestoreConflictPolicy, RestoreResult};
+
+/// Copy `paths` (relative to the snapshot root) from `snapshot` into `live`.
+///
+/// Both roots must already be canonical. Requested paths are normalized and
+/// may not leave either root, including through symlinked parent directories.
+pub(crate) fn restore_paths(
+    snapshot: &Path,
+    live: &Path,
+    requested: &[String],
+    policy: RestoreConflictPolicy,
+) -> Result<RestoreResult> {
+    let mut result = RestoreResult::default();
+
+    for requested_path in requested {
+        let relative = paths::normalize_relative(requested_path)?;
+        let source = snapshot.join(&relative);
+        source.symlink_metadata().map_err(|e| {
+            BtrfsError::InvalidPath(format!("{} in snapshot: {}", relative.display(), e))
+        })?;
+        ensure_within(source.parent().unwrap_or(snapshot), snapshot)?;
+
+        let mut dest = live.join(&relative);
+        let parent = dest.parent().unwrap_or(live).to_path_buf();
+        ensure_within(&parent, live)?;
+        fs::create_dir_all(&parent)?;
+
+        if dest.symlink_metadata().is_ok() {
+            match policy {
+                RestoreConflictPolicy::Skip => {
+                    result.skipped.push(requested_path.clone());
+                    continue;
+                }
+                RestoreConflictPolicy::Overwrite => remove(&dest)?,
+                RestoreConflictPolicy::KeepBoth => dest = restored_name(&dest),
+            }
+        }
+
+        copy(&source, &dest)?;
+        result.restored.push(dest.to_string_lossy().into_owned());
+    }
+
+    Ok(result)
+}
+
+/// Require the deepest existing ancestor of `path` to resolve inside `root`
+fn ensure_within(path: &Path, root: &Path) -> Result<()> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(root);
+    let resolved = existing.canonicalize()?;
+
+    if !resolved.starts_with(root) {
+        return Err(BtrfsError::InvalidPath(format!(
+            "{} is not inside {}",
+            path.display(),
+            root.display()
+        )));
+    }
+    Ok(())
+}
+
+/// First free `<name>.restored[.N]` next to `dest`
+fn restored_name(dest: &Path) -> PathBuf {
+    let name = dest
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    (0..)
+        .map(|n| match n {
+            0 => dest.with_file_name(format!("{name}.restored")),
+            n => dest.with_file_name(format!("{name}.restored.{n}")),
+        })
+        .find(|candidate| candidate.symlink_metadata().is_err())
+        .unwrap_or_else(|| dest.to_path_buf())
+}
+
+fn remove(path: &Path) -> Result<()> {
+    let metadata = path.symlink_metadata()?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn copy(source: &Path, dest: &Path) -> Result<()> {
+    let output = Command::new("cp")
+        .args(["-a", "--reflink=auto", "--"])
+        .arg(source)
+        .arg(dest)
+        .output()
+        .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run cp: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(BtrfsError::OperationFailed(format!(
+            "Failed to restore {}: {}",
+            dest.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// Snapshot and live roots side by side, next to an `outside` directory
+    struct Roots {
+        base: PathBuf,
+        snapshot: PathBuf,
+        live: PathBuf,
+        outside: PathBuf,
+    }
+
+    impl Roots {
+        fn new() -> Self {
+            let unique = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let base = std::env::temp_dir().join(format!(
+                "storage-btrfs-restore-{}-{unique}",
+                std::process::id()
+            ));
+            let [snapshot, live, outside] =
+                ["snapshot", "live", "outside"].map(|name| base.join(name));
+            for dir in [&snapshot, &live, &outside] {
+                fs::create_dir_all(dir).expect("create temp dir");
+            }
+            Self {
+                snapshot: snapshot.canonicalize().unwrap(),
+                live: live.canonicalize().unwrap(),
+                outside: outside.canonicalize().unwrap(),
+                base,
+            }
+        }
+
+        fn restore(
+            &self,
+            requested: &[&str],
+            policy: RestoreConflictPolicy,
+        ) -> Result<RestoreResult> {
+            let requested: Vec<String> = requested.iter().map(|path| path.to_string()).collect();
+            restore_paths(&self.snapshot, &self.live, &requested, policy)
+        }
+    }
+
+    impl Drop for Roots {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.base);
+        }
+    }
+
+    #[test]
+    fn rejects_paths_leaving_the_roots() {
+        let roots = Roots::new();
+        fs::write(roots.outside.join("secret"), "outside").unwrap();
+        fs::write(roots.snapshot.join("file"), "snapshot").unwrap();
+
+        for path in ["../outside/secret", "dir/../../outside/secret", ".."] {
+            assert!(
+                roots
+                    .restore(&[path], RestoreConflictPolicy::Overwrite)
+                    .is_err(),
+                "{path}"
+            );
+        }
+
+        // An absolute path names a path inside the snapshot, not on the host
+        let outside_secret = roots.outside.join("secret");
+        assert!(
+            roots
+                .restore(
+                    &[outside_secret.to_str().unwrap()],
+                    RestoreConflictPolicy::Overwrite
+                )
+                .is_err()
+        );
+        let restored = roots
+            .restore(&["/file"], RestoreConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(
+            restored.restored,
+            vec![roots.live.join("file").to_string_lossy().into_owned()]
+        );
+        assert_eq!(
+            fs::read_to_string(roots.outside.join("secret")).unwrap(),
+            "outside"
+        );
+    }
+
+    #[test]
+    fn rejects_symlinked_parents() {
+        let roots = Roots::new();
+        fs::write(roots.outside.join("secret"), "outside").unwrap();
+
+        // Reading through a symlink in the snapshot
+        symlink(&roots.outside, roots.snapshot.join("escape")).unwrap();
+        assert!(
+            roots
+                .restore(&["escape/secret"], RestoreConflictPolicy::Skip)
+                .is_err()
+        );
+
+        // Writing through a symlink in the live tree
+        fs::create_dir(roots.snapshot.join("dir")).unwrap();
+        fs::write(roots.snapshot.join("dir/secret"), "snapshot").unwrap();
+        symlink(&roots.outside, roots.live.join("dir")).unwrap();
+        assert!(
+            roots
+                .restore(&["dir/secret"], RestoreConflictPolicy::Overwrite)
+                .is_err()
+        );
+        assert_eq!(
+            fs::read_to_string(roots.outside.join("secret")).unwrap(),
+            "outside"
+        );
+    }
+
+    #[test]
+    fn resolves_conflicts_by_policy() {
+        let roots = Roots::new();
+        fs::write(roots.snapshot.join("file"), "snapshot").unwrap();
+        let live_file = roots.live.join("file");
+        let read = |path: &Path| fs::read_to_string(path).unwrap();
+
+        fs::write(&live_file, "live").unwrap();
+        let skipped = roots
+            .restore(&["file"], RestoreConflictPolicy::Skip)
+            .unwrap();
+        assert_eq!(skipped.skipped, vec!["file".to_string()]);
+        assert!(skipped.restored.is_empty());
+        assert_eq!(read(&live_file), "live");
+
+        let kept = roots
+            .restore(&["file", "file"], RestoreConflictPolicy::KeepBoth)
+            .unwrap();
+        let renamed = [
+            roots.live.join("file.restored"),
+            roots.live.join("file.restored.1"),
+        ];
+        assert_eq!(
+            kept.restored,
+            renamed
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(read(&live_file), "live");
+        assert!(renamed.iter().all(|path| read(path) == "snapshot"));
+
+        let overwritten = roots
+            .restore(&["file"], RestoreConflictPolicy::Overwrite)
+            .unwrap();
+        assert_eq!(
+            overwritten.restored,
+            vec![live_file.to_string_lossy().into_owned()]
+        );
+        assert_eq!(read(&live_file), "snapshot");
+    }
+}
@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Mount table helpers for subvolume rollback
+//!
+//! A rollback must know whether the subvolume being replaced is mounted
+//! anywhere (in particular as the running root), because those mounts keep
+//! using the old subvolume until they are remounted or the system reboots.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A btrfs entry of a mounts table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BtrfsMount {
+    pub source: String,
+    pub target: String,
+    pub subvolid: Option<u64>,
+}
+
+/// Btrfs entries of a `/proc/self/mounts` style table
+pub(crate) fn parse_btrfs_mounts(mounts: &str) -> Vec<BtrfsMount> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let source = fields.next()?;
+            let target = fields.next()?.replace("\\040", " ");
+            if fields.next()? != "btrfs" {
+                return None;
+            }
+            let subvolid = fields.next()?.split(',').find_map(|option| {
+                option
+                    .strip_prefix("subvolid=")
+                    .and_then(|id| id.parse().ok())
+            });
+            Some(BtrfsMount {
+                source: source.to_string(),
+                target,
+                subvolid,
+            })
+        })
+        .collect()
+}
+
+/// Mount points of subvolume `subvolid` on the filesystem mounted at `mount_point`
+pub(crate) fn subvolume_mounts(
+    mounts: &[BtrfsMount],
+    mount_point: &str,
+    subvolid: u64,
+) -> Vec<String> {
+    let Some(source) = mounts
+        .iter()
+        .rfind(|mount| mount.target == mount_point)
+        .map(|mount| mount.source.as_str())
+    else {
+        return Vec::new();
+    };
+
+    mounts
+        .iter()
+        .filter(|mount| mount.source == source && mount.subvolid == Some(subvolid))
+        .map(|mount| mount.target.clone())
+        .collect()
+}
+
+/// Seconds since the epoch, used to name rollback snapshots
+pub(crate) fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_mounts_of_a_subvolume() {
+        let mounts = parse_btrfs_mounts(
+            "/dev/nvme0n1p2 / btrfs rw,relatime,ssd,subvolid=256,subvol=/@ 0 0\n\
+             proc /proc proc rw 0 0\n\
+             /dev/nvme0n1p2 /home btrfs rw,subvolid=257,subvol=/@home 0 0\n\
+             /dev/nvme0n1p2 /mnt/top btrfs rw,subvolid=5,subvol=/ 0 0\n\
+             /dev/sdb1 /mnt/backup btrfs rw,subvolid=256,subvol=/data 0 0\n",
+        );
+        assert_eq!(mounts.len(), 4);
+
+        assert_eq!(subvolume_mounts(&mounts, "/mnt/top", 256), ["/"]);
+        assert_eq!(subvolume_mounts(&mounts, "/mnt/top", 257), ["/home"]);
+        assert!(subvolume_mounts(&mounts, "/mnt/top", 300).is_empty());
+        assert_eq!(
+            subvolume_mounts(&mounts, "/mnt/backup", 256),
+            ["/mnt/backup"]
+        );
+    }
+}
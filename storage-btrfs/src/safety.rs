@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Safety snapshots before destructive operations
+//!
+//! A safety snapshot is a read-only snapshot of the subvolume an operation
+//! is about to touch. It is stored in a `.safety-snapshots` directory at the
+//! root of that subvolume, named `<reason>-<unix time>`, and pruned once it
+//! is older than the configured retention.
+
+use crate::error::{BtrfsError, Result};
+use crate::rollback;
+use crate::subvolume::SubvolumeManager;
+use btrfsutil::subvolume::Subvolume;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Directory at a subvolume root holding its safety snapshots
+pub const SAFETY_SNAPSHOT_DIR: &str = ".safety-snapshots";
+
+/// Inode number of every btrfs subvolume root directory
+const SUBVOLUME_ROOT_INODE: u64 = 256;
+
+/// Root of the btrfs subvolume containing `path`
+pub fn containing_subvolume(path: &Path) -> Result<PathBuf> {
+    let path = path.canonicalize()?;
+    path.ancestors()
+        .find(|dir| {
+            fs::metadata(dir).is_ok_and(|metadata| metadata.ino() == SUBVOLUME_ROOT_INODE)
+                && Subvolume::try_from(*dir).is_ok()
+        })
+        .map(Path::to_path_buf)
+        .ok_or_else(|| BtrfsError::InvalidPath(format!("{} is not on btrfs", path.display())))
+}
+
+/// Mount points of the btrfs filesystem on `device`, one per mounted subvolume
+pub fn device_mount_points(device: &str) -> Result<Vec<PathBuf>> {
+    let device = fs::canonicalize(device).unwrap_or_else(|_| PathBuf::from(device));
+    let mounts = rollback::parse_btrfs_mounts(&fs::read_to_string("/proc/self/mounts")?);
+
+    let mut seen = Vec::new();
+    let mut targets = Vec::new();
+    for mount in mounts {
+        let source =
+            fs::canonicalize(&mount.source).unwrap_or_else(|_| PathBuf::from(&mount.source));
+        if source != device || seen.contains(&mount.subvolid) {
+            continue;
+        }
+        seen.push(mount.subvolid);
+        targets.push(PathBuf::from(mount.target));
+    }
+
+    Ok(targets)
+}
+
+/// Take a read-only snapshot of the subvolume containing `path`
+///
+/// Returns the snapshot path. A snapshot taken for the same reason within
+/// the same second is reused.
+pub fn take_safety_snapshot(path: &Path, reason: &str) -> Result<PathBuf> {
+    let root = containing_subvolume(path)?;
+    let dir = root.join(SAFETY_SNAPSHOT_DIR);
+    fs::create_dir_all(&dir)?;
+
+    let dest = dir.join(format!("{reason}-{}", rollback::timestamp()));
+    if !dest.exists() {
+        SubvolumeManager::new(&root)?.snapshot(&root, &dest, true, false)?;
+    }
+
+    Ok(dest)
+}
+
+/// Delete safety snapshots of `subvolume` older than `max_age`
+///
+/// Returns the deleted snapshot paths.
+pub fn prune_safety_snapshots(subvolume: &Path, max_age: Duration) -> Result<Vec<PathBuf>> {
+    let dir = subvolume.join(SAFETY_SNAPSHOT_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let manager = SubvolumeManager::new(subvolume)?;
+    let now = rollback::timestamp();
+    let mut pruned = Vec::new();
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let Some(taken) = snapshot_time(&entry.file_name().to_string_lossy()) else {
+            continue;
+        };
+        if now.saturating_sub(taken) <= max_age.as_secs() {
+            continue;
+        }
+
+        let path = entry.path();
+        match manager.delete(&path, false) {
+            Ok(()) => pruned.push(path),
+            Err(e) => tracing::warn!("Failed to prune {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(pruned)
+}
+
+/// Creation time from a `<reason>-<unix time>` snapshot name
+fn snapshot_time(name: &str) -> Option<u64> {
+    let (reason, time) = name.rsplit_once('-')?;
+    if reason.is_empty() {
+        return None;
+    }
+    time.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_snapshot_names() {
+        assert_eq!(snapshot_time("resize-1760572800"), Some(1760572800));
+        assert_eq!(snapshot_time("pre-cleanup-42"), Some(42));
+        assert_eq!(snapshot_time("-42"), None);
+        assert_eq!(snapshot_time("resize"), None);
+        assert_eq!(snapshot_time("resize-latest"), None);
+    }
+}
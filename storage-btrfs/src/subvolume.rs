@@ -1,10 +1,19 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::attributes;
+use crate::compression;
+use crate::diff;
 use crate::error::{BtrfsError, Result};
+use crate::paths;
+use crate::restore;
+use crate::rollback;
 use btrfsutil::subvolume::{DeleteFlags, SnapshotFlags, Subvolume};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use storage_types::btrfs::BtrfsSubvolume;
+use storage_types::btrfs::{
+    BtrfsSubvolume, CompressionEstimate, CompressionInfo, NocowStatus, RestoreConflictPolicy,
+    RestoreResult, RollbackResult, SnapshotChangeKind, SnapshotDiff,
+};
 
 /// Manager for BTRFS subvolume operations
 pub struct SubvolumeManager {
@@ -145,14 +154,37 @@ impl SubvolumeManager {
     }
 
     /// Create a new subvolume
-    pub fn create(&self, name: &str) -> Result<()> {
-        let subvol_path = self.mount_point.join(name);
+    ///
+    /// `path` is relative to the mount point and may be nested inside existing
+    /// directories or subvolumes (e.g. `@home/alice/vms`). The parent directory
+    /// must already exist and, after resolving symlinks, lie on this mount.
+    pub fn create(&self, path: &str) -> Result<PathBuf> {
+        let relative = paths::normalize_relative(path)?;
+        let name = relative
+            .file_name()
+            .ok_or_else(|| BtrfsError::InvalidPath(path.to_string()))?;
+
+        let parent = match relative.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => self.resolve_directory(parent)?,
+            _ => self.mount_point.canonicalize()?,
+        };
+        let subvol_path = parent.join(name);
+        if subvol_path.symlink_metadata().is_ok() {
+            return Err(BtrfsError::InvalidPath(format!(
+                "{} already exists",
+                subvol_path.display()
+            )));
+        }
 
         Subvolume::create(subvol_path.as_path(), None).map_err(|e| {
-            BtrfsError::OperationFailed(format!("Failed to create subvolume '{}': {}", name, e))
+            BtrfsError::OperationFailed(format!(
+                "Failed to create subvolume '{}': {}",
+                subvol_path.display(),
+                e
+            ))
         })?;
 
-        Ok(())
+        Ok(subvol_path)
     }
 
     /// Delete a subvolume
@@ -226,6 +258,347 @@ impl SubvolumeManager {
         Ok(())
     }
 
+    /// Set the compression property on a subvolume or directory
+    ///
+    /// `algorithm` is one of "zstd", "lzo", "zlib", or "none". `path` must lie
+    /// on this filesystem. New files created below `path` inherit the
+    /// property; existing data is untouched.
+    pub fn set_compression(&self, path: &Path, algorithm: &str) -> Result<()> {
+        if !matches!(algorithm, "zstd" | "lzo" | "zlib" | "none") {
+            return Err(BtrfsError::OperationFailed(format!(
+                "Unsupported compression algorithm: {}",
+                algorithm
+            )));
+        }
+        let path = self.resolve_path(path)?;
+
+        let output = Command::new("btrfs")
+            .args(["property", "set"])
+            .arg(&path)
+            .args(["compression", algorithm])
+            .output()
+            .map_err(|e| {
+                BtrfsError::CommandFailed(format!("Failed to run btrfs command: {}", e))
+            })?;
+
+        if !output.status.success() {
+            return Err(BtrfsError::OperationFailed(format!(
+                "Failed to set compression on {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Get the compression property of `path` and the mount-wide compression option
+    pub fn get_compression(&self, path: &Path) -> Result<CompressionInfo> {
+        let path = self.resolve_path(path)?;
+
+        let output = Command::new("btrfs")
+            .args(["property", "get"])
+            .arg(&path)
+            .arg("compression")
+            .output()
+            .map_err(|e| {
+                BtrfsError::CommandFailed(format!("Failed to run btrfs command: {}", e))
+            })?;
+
+        if !output.status.success() {
+            return Err(BtrfsError::OperationFailed(format!(
+                "Failed to get compression of {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let mounts = std::fs::read_to_string("/proc/self/mounts")?;
+        let mount_option =
+            compression::parse_mount_compression(&mounts, &self.mount_point.to_string_lossy());
+
+        Ok(CompressionInfo {
+            path: path.to_string_lossy().into_owned(),
+            force: mount_option.as_ref().is_some_and(|(_, force)| *force),
+            mount_option: mount_option.map(|(value, _)| value),
+            property: compression::parse_property(&String::from_utf8_lossy(&output.stdout)),
+        })
+    }
+
+    /// Change the mount-wide compression by remounting
+    ///
+    /// `value` is "none" or an algorithm with an optional level ("zstd:3").
+    /// The change lasts until the filesystem is next mounted.
+    pub fn set_mount_compression(&self, value: &str, force: bool) -> Result<()> {
+        compression::validate_mount_value(value)?;
+
+        let option = match (value, force) {
+            ("none", _) => "remount,compress=no".to_string(),
+            (value, true) => format!("remount,compress-force={}", value),
+            (value, false) => format!("remount,compress={}", value),
+        };
+
+        let output = Command::new("mount")
+            .args(["-o", &option])
+            .arg(&self.mount_point)
+            .output()
+            .map_err(|e| BtrfsError::CommandFailed(format!("Failed to run mount: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(BtrfsError::OperationFailed(format!(
+                "Failed to remount {}: {}",
+                self.mount_point.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Estimate how much space recompressing existing data below `path` would save
+    ///
+    /// A sample of up to 64 MiB is compressed with zstd to get a ratio, which
+    /// is projected onto the total size of the files.
+    pub fn estimate_compression(&self, path: &Path) -> Result<CompressionEstimate> {
+        let path = self.resolve_path(path)?;
+        let (files, total_bytes) = compression::collect_files(&path)?;
+        let (sampled_bytes, sampled_compressed_bytes) = compression::sample_compression(&files)?;
+        let disk_bytes = compression::compsize_disk_bytes(&path);
+
+        let projected = if sampled_bytes == 0 {
+            total_bytes
+        } else {
+            (total_bytes as u128 * sampled_compressed_bytes as u128 / sampled_bytes as u128) as u64
+        };
+
+        Ok(CompressionEstimate {
+            path: path.to_string_lossy().into_owned(),
+            total_bytes,
+            disk_bytes,
+            sampled_bytes,
+            sampled_compressed_bytes,
+            estimated_savings_bytes: disk_bytes.unwrap_or(total_bytes).saturating_sub(projected),
+        })
+    }
+
+    /// Recompress existing data below `path` with `algorithm`
+    ///
+    /// Runs `btrfs filesystem defragment -r -c<algorithm>`. Rewriting extents
+    /// breaks reflinks and snapshot sharing, so space use can grow on
+    /// filesystems with many snapshots.
+    pub fn compress_existing(&self, path: &Path, algorithm: &str) -> Result<()> {
+        if !matches!(algorithm, "zstd" | "lzo" | "zlib") {
+            return Err(BtrfsError::OperationFailed(format!(
+                "Unsupported compression algorithm: {}",
+                algorithm
+            )));
+        }
+        let path = self.resolve_path(path)?;
+
+        let output = Command::new("btrfs")
+            .args(["filesystem", "defragment", "-r"])
+            .arg(format!("-c{}", algorithm))
+            .arg(&path)
+            .output()
+            .map_err(|e| {
+                BtrfsError::CommandFailed(format!("Failed to run btrfs command: {}", e))
+            })?;
+
+        if !output.status.success() {
+            return Err(BtrfsError::OperationFailed(format!(
+                "Failed to compress {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Resolve `path` (absolute, or relative to the mount point) and check it
+    /// lies on this filesystem
+    /// List the files added, modified and deleted between two snapshots
+    ///
+    /// Both snapshots must be read-only and `new` must descend from the same
+    /// subvolume as `old`. Returns `limit` changes starting at `offset`,
+    /// along with totals for the full list.
+    pub fn diff_snapshots(
+        &self,
+        old: &Path,
+        new: &Path,
+        offset: usize,
+        limit: usize,
+    ) -> Result<SnapshotDiff> {
+        let old = self.resolve_path(old)?;
+        let new = self.resolve_path(new)?;
+        for snapshot in [&old, &new] {
+            let subvol = Subvolume::try_from(snapshot.as_path()).map_err(|e| {
+                BtrfsError::SubvolumeNotFound(format!("{}: {}", snapshot.display(), e))
+            })?;
+            let readonly = subvol.is_ro().map_err(|e| {
+                BtrfsError::OperationFailed(format!("{}: {}", snapshot.display(), e))
+            })?;
+            if !readonly {
+                return Err(BtrfsError::OperationFailed(format!(
+                    "{} is not a read-only snapshot",
+                    snapshot.display()
+                )));
+            }
+        }
+
+        let changes = diff::snapshot_changes(&old, &new)?;
+        let count = |kind| changes.iter().filter(|entry| entry.kind == kind).count();
+
+        Ok(SnapshotDiff {
+            old_snapshot: old.to_string_lossy().into_owned(),
+            new_snapshot: new.to_string_lossy().into_owned(),
+            offset,
+            total: changes.len(),
+            added: count(SnapshotChangeKind::Added),
+            modified: count(SnapshotChangeKind::Modified),
+            deleted: count(SnapshotChangeKind::Deleted),
+            entries: changes.iter().skip(offset).take(limit).cloned().collect(),
+        })
+    }
+
+    /// Copy files or directories out of a snapshot back into a live subvolume
+    ///
+    /// `paths` are relative to the snapshot root and land at the same
+    /// relative location below `target`. Existing files are handled according
+    /// to `policy`; data is reflinked where possible.
+    pub fn restore_from_snapshot(
+        &self,
+        snapshot: &Path,
+        target: &Path,
+        paths: &[String],
+        policy: RestoreConflictPolicy,
+    ) -> Result<RestoreResult> {
+        let snapshot = self.resolve_directory(snapshot)?;
+        let target = self.resolve_directory(target)?;
+        if target.starts_with(&snapshot) {
+            return Err(BtrfsError::InvalidPath(format!(
+                "Cannot restore into the snapshot {}",
+                snapshot.display()
+            )));
+        }
+
+        restore::restore_paths(&snapshot, &target, paths, policy)
+    }
+
+    /// Replace the subvolume at `target` with a writable copy of `snapshot`
+    ///
+    /// A read-only safety snapshot of `target` is taken first. If `target` is
+    /// mounted anywhere (including as the running root) or contains nested
+    /// subvolumes it is renamed aside instead of deleted; mounted targets need
+    /// a reboot or remount before the restored contents are used.
+    /// When `target` was the default subvolume, the default follows the
+    /// restored copy.
+    pub fn rollback(&self, target: &Path, snapshot: &Path) -> Result<RollbackResult> {
+        let target = self.resolve_path(target)?;
+        let snapshot = self.resolve_path(snapshot)?;
+        let mount_point = self.mount_point.canonicalize()?;
+        if target == mount_point || snapshot.starts_with(&target) {
+            return Err(BtrfsError::InvalidPath(format!(
+                "Cannot roll back {} to {}",
+                target.display(),
+                snapshot.display()
+            )));
+        }
+
+        let target_subvol = Subvolume::try_from(target.as_path())
+            .map_err(|e| BtrfsError::SubvolumeNotFound(format!("{}: {}", target.display(), e)))?;
+        let target_id = target_subvol.id();
+        let was_default = self.get_default().is_ok_and(|id| id == target_id);
+
+        let mounts = rollback::parse_btrfs_mounts(&std::fs::read_to_string("/proc/self/mounts")?);
+        let active_mounts =
+            rollback::subvolume_mounts(&mounts, &self.mount_point.to_string_lossy(), target_id);
+        let booted_root = active_mounts.iter().any(|mount| mount == "/");
+
+        let name = target
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| BtrfsError::InvalidPath(target.display().to_string()))?;
+        let stamp = rollback::timestamp();
+        let safety_snapshot = target.with_file_name(format!("{name}.pre-rollback-{stamp}"));
+        self.snapshot(&target, &safety_snapshot, true, false)?;
+
+        // Non-recursive delete fails when nested subvolumes exist; those stay
+        // with the renamed original instead of being lost
+        let previous_moved_to = if active_mounts.is_empty() && self.delete(&target, false).is_ok() {
+            None
+        } else {
+            let aside = target.with_file_name(format!("{name}.replaced-{stamp}"));
+            std::fs::rename(&target, &aside)?;
+            Some(aside.to_string_lossy().into_owned())
+        };
+
+        self.snapshot(&snapshot, &target, false, false)?;
+
+        let default_updated = was_default && self.set_default(&target).is_ok();
+
+        Ok(RollbackResult {
+            safety_snapshot: safety_snapshot.to_string_lossy().into_owned(),
+            subvolume: target.to_string_lossy().into_owned(),
+            previous_moved_to,
+            booted_root,
+            reboot_required: !active_mounts.is_empty(),
+            active_mounts,
+            default_updated,
+        })
+    }
+
+    /// Get the no-copy-on-write attribute of a directory
+    pub fn get_nocow(&self, path: &Path) -> Result<NocowStatus> {
+        let path = self.resolve_directory(path)?;
+
+        Ok(NocowStatus {
+            enabled: attributes::get_nocow(&path)?,
+            non_empty: std::fs::read_dir(&path)?.next().is_some(),
+            path: path.to_string_lossy().into_owned(),
+        })
+    }
+
+    /// Set or clear the no-copy-on-write attribute (`chattr +C`) on a directory
+    ///
+    /// Only files created in the directory afterwards inherit the attribute;
+    /// existing files keep their setting. NOCOW files are neither checksummed
+    /// nor compressed.
+    pub fn set_nocow(&self, path: &Path, enabled: bool) -> Result<NocowStatus> {
+        let resolved = self.resolve_directory(path)?;
+        attributes::set_nocow(&resolved, enabled)?;
+        self.get_nocow(path)
+    }
+
+    fn resolve_directory(&self, path: &Path) -> Result<PathBuf> {
+        let resolved = self.resolve_path(path)?;
+        if !resolved.is_dir() {
+            return Err(BtrfsError::InvalidPath(format!(
+                "{} is not a directory",
+                resolved.display()
+            )));
+        }
+        Ok(resolved)
+    }
+
+    fn resolve_path(&self, path: &Path) -> Result<PathBuf> {
+        let mount_point = self.mount_point.canonicalize()?;
+        let resolved = mount_point
+            .join(path)
+            .canonicalize()
+            .map_err(|e| BtrfsError::InvalidPath(format!("{}: {}", path.display(), e)))?;
+
+        if !resolved.starts_with(&mount_point) {
+            return Err(BtrfsError::InvalidPath(format!(
+                "{} is not on {}",
+                resolved.display(),
+                mount_point.display()
+            )));
+        }
+
+        Ok(resolved)
+    }
+
     /// Set a subvolume as the default
     pub fn set_default(&self, path: &Path) -> Result<()> {
         let subvol = Subvolume::try_from(path)
@@ -0,0 +1,373 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use storage_types::btrfs::{
+    BtrfsDeviceStats, CompressionEstimate, CompressionInfo, DeletedSubvolume, FilesystemUsage,
+    NocowStatus, RestoreConflictPolicy, RestoreResult, RollbackResult, SafetySnapshotPolicy,
+    SubvolumeList,
+};
+use zbus::proxy;
+
+/// D-Bus proxy interface for BTRFS operations
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.Btrfs",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/btrfs"
+)]
+trait BtrfsInterface {
+    /// List all subvolumes in a BTRFS filesystem
+    async fn list_subvolumes(&self, mountpoint: &str) -> zbus::Result<String>;
+
+    /// Create a new subvolume at a path relative to the mount point
+    async fn create_subvolume(&self, mountpoint: &str, path: &str) -> zbus::Result<()>;
+
+    /// Create a snapshot of a subvolume
+    async fn create_snapshot(
+        &self,
+        mountpoint: &str,
+        source_path: &str,
+        dest_path: &str,
+        readonly: bool,
+    ) -> zbus::Result<()>;
+
+    /// Delete a subvolume
+    async fn delete_subvolume(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        recursive: bool,
+    ) -> zbus::Result<()>;
+
+    /// Set or unset the read-only flag on a subvolume
+    async fn set_readonly(&self, mountpoint: &str, path: &str, readonly: bool) -> zbus::Result<()>;
+
+    /// Set a subvolume as the default
+    async fn set_default(&self, mountpoint: &str, path: &str) -> zbus::Result<()>;
+
+    /// Get the default subvolume ID
+    async fn get_default(&self, mountpoint: &str) -> zbus::Result<u64>;
+
+    /// List deleted subvolumes pending cleanup
+    async fn list_deleted(&self, mountpoint: &str) -> zbus::Result<String>;
+
+    /// Get filesystem usage information
+    async fn get_usage(&self, mountpoint: &str) -> zbus::Result<String>;
+
+    /// Get the error counters of each device of a filesystem
+    async fn get_device_stats(&self, mountpoint: &str) -> zbus::Result<String>;
+
+    /// Reset the error counters of each device of a filesystem
+    async fn reset_device_stats(&self, mountpoint: &str) -> zbus::Result<String>;
+
+    /// Get the compression property of a path and the mount-wide compression option
+    async fn get_compression(&self, mountpoint: &str, path: &str) -> zbus::Result<String>;
+
+    /// Copy paths out of a snapshot back into a live subvolume
+    async fn restore_from_snapshot(
+        &self,
+        mountpoint: &str,
+        snapshot: &str,
+        target: &str,
+        paths: Vec<String>,
+        conflict: &str,
+    ) -> zbus::Result<String>;
+
+    /// Replace a subvolume with a writable copy of a snapshot
+    async fn rollback(
+        &self,
+        mountpoint: &str,
+        target: &str,
+        snapshot: &str,
+    ) -> zbus::Result<String>;
+
+    /// Get the no-copy-on-write attribute of a directory
+    async fn get_nocow(&self, mountpoint: &str, path: &str) -> zbus::Result<String>;
+
+    /// Set or clear the no-copy-on-write attribute on a directory
+    async fn set_nocow(&self, mountpoint: &str, path: &str, enabled: bool) -> zbus::Result<String>;
+
+    /// Get the automatic safety snapshot policy
+    async fn get_safety_snapshot_policy(&self) -> zbus::Result<String>;
+
+    /// Set the automatic safety snapshot policy
+    async fn set_safety_snapshot_policy(&self, policy_json: &str) -> zbus::Result<()>;
+
+    /// Set the compression property on a subvolume or directory
+    async fn set_compression(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        algorithm: &str,
+    ) -> zbus::Result<()>;
+
+    /// Change the mount-wide compression by remounting
+    async fn set_mount_compression(
+        &self,
+        mountpoint: &str,
+        value: &str,
+        force: bool,
+    ) -> zbus::Result<()>;
+
+    /// Estimate space saved by recompressing existing data
+    async fn estimate_compression(&self, mountpoint: &str, path: &str) -> zbus::Result<String>;
+
+    /// Recompress existing data below a path
+    async fn compress_existing(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        algorithm: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Client for BTRFS operations via D-Bus
+pub struct BtrfsClient {
+    proxy: BtrfsInterfaceProxy<'static>,
+}
+
+impl std::fmt::Debug for BtrfsClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BtrfsClient").finish_non_exhaustive()
+    }
+}
+
+impl BtrfsClient {
+    /// Create a new BTRFS client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = BtrfsInterfaceProxy::new(conn)
+            .await
+            .map_err(|e| ClientError::Connection(format!("Failed to create proxy: {}", e)))?;
+
+        Ok(Self { proxy })
+    }
+
+    /// List all subvolumes in a BTRFS filesystem
+    pub async fn list_subvolumes(&self, mountpoint: &str) -> Result<SubvolumeList, ClientError> {
+        let json = self.proxy.list_subvolumes(mountpoint).await?;
+        let list: SubvolumeList = serde_json::from_str(&json)?;
+        Ok(list)
+    }
+
+    /// Create a new subvolume
+    pub async fn create_subvolume(&self, mountpoint: &str, path: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.create_subvolume(mountpoint, path).await?)
+    }
+
+    /// Create a snapshot of a subvolume
+    pub async fn create_snapshot(
+        &self,
+        mountpoint: &str,
+        source_path: &str,
+        dest_path: &str,
+        readonly: bool,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .create_snapshot(mountpoint, source_path, dest_path, readonly)
+            .await?)
+    }
+
+    /// Delete a subvolume
+    pub async fn delete_subvolume(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        recursive: bool,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .delete_subvolume(mountpoint, path, recursive)
+            .await?)
+    }
+
+    /// Set or unset the read-only flag on a subvolume
+    pub async fn set_readonly(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        readonly: bool,
+    ) -> Result<(), ClientError> {
+        Ok(self.proxy.set_readonly(mountpoint, path, readonly).await?)
+    }
+
+    /// Set a subvolume as the default
+    pub async fn set_default(&self, mountpoint: &str, path: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.set_default(mountpoint, path).await?)
+    }
+
+    /// Get the default subvolume ID
+    pub async fn get_default(&self, mountpoint: &str) -> Result<u64, ClientError> {
+        Ok(self.proxy.get_default(mountpoint).await?)
+    }
+
+    /// List deleted subvolumes pending cleanup
+    pub async fn list_deleted(
+        &self,
+        mountpoint: &str,
+    ) -> Result<Vec<DeletedSubvolume>, ClientError> {
+        let json = self.proxy.list_deleted(mountpoint).await?;
+        let deleted: Vec<DeletedSubvolume> = serde_json::from_str(&json)?;
+        Ok(deleted)
+    }
+
+    /// Get filesystem usage information
+    pub async fn get_usage(&self, mountpoint: &str) -> Result<FilesystemUsage, ClientError> {
+        let json = self.proxy.get_usage(mountpoint).await?;
+        let usage: FilesystemUsage = serde_json::from_str(&json)?;
+        Ok(usage)
+    }
+
+    /// Get the error counters of each device of a filesystem
+    pub async fn get_device_stats(
+        &self,
+        mountpoint: &str,
+    ) -> Result<Vec<BtrfsDeviceStats>, ClientError> {
+        let json = self.proxy.get_device_stats(mountpoint).await?;
+        let stats: Vec<BtrfsDeviceStats> = serde_json::from_str(&json)?;
+        Ok(stats)
+    }
+
+    /// Reset the error counters of each device of a filesystem, returning
+    /// their values from before the reset
+    pub async fn reset_device_stats(
+        &self,
+        mountpoint: &str,
+    ) -> Result<Vec<BtrfsDeviceStats>, ClientError> {
+        let json = self.proxy.reset_device_stats(mountpoint).await?;
+        let stats: Vec<BtrfsDeviceStats> = serde_json::from_str(&json)?;
+        Ok(stats)
+    }
+
+    /// Get compression settings for `path` (empty for the mount point)
+    pub async fn get_compression(
+        &self,
+        mountpoint: &str,
+        path: &str,
+    ) -> Result<CompressionInfo, ClientError> {
+        let json = self.proxy.get_compression(mountpoint, path).await?;
+        let info: CompressionInfo = serde_json::from_str(&json)?;
+        Ok(info)
+    }
+
+    /// Set the compression property on a subvolume or directory
+    pub async fn set_compression(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        algorithm: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .set_compression(mountpoint, path, algorithm)
+            .await?)
+    }
+
+    /// Change the mount-wide compression until the next mount
+    pub async fn set_mount_compression(
+        &self,
+        mountpoint: &str,
+        value: &str,
+        force: bool,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .set_mount_compression(mountpoint, value, force)
+            .await?)
+    }
+
+    /// Estimate space saved by recompressing existing data below `path`
+    pub async fn estimate_compression(
+        &self,
+        mountpoint: &str,
+        path: &str,
+    ) -> Result<CompressionEstimate, ClientError> {
+        let json = self.proxy.estimate_compression(mountpoint, path).await?;
+        let estimate: CompressionEstimate = serde_json::from_str(&json)?;
+        Ok(estimate)
+    }
+
+    /// Recompress existing data below `path` with `algorithm`
+    pub async fn compress_existing(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        algorithm: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .compress_existing(mountpoint, path, algorithm)
+            .await?)
+    }
+
+    /// Get the no-copy-on-write attribute of a directory
+    pub async fn get_nocow(
+        &self,
+        mountpoint: &str,
+        path: &str,
+    ) -> Result<NocowStatus, ClientError> {
+        let json = self.proxy.get_nocow(mountpoint, path).await?;
+        let status: NocowStatus = serde_json::from_str(&json)?;
+        Ok(status)
+    }
+
+    /// Set or clear the no-copy-on-write attribute on a directory
+    pub async fn set_nocow(
+        &self,
+        mountpoint: &str,
+        path: &str,
+        enabled: bool,
+    ) -> Result<NocowStatus, ClientError> {
+        let json = self.proxy.set_nocow(mountpoint, path, enabled).await?;
+        let status: NocowStatus = serde_json::from_str(&json)?;
+        Ok(status)
+    }
+
+    /// Copy `paths` (relative to the snapshot root) from `snapshot` into `target`
+    pub async fn restore_from_snapshot(
+        &self,
+        mountpoint: &str,
+        snapshot: &str,
+        target: &str,
+        paths: Vec<String>,
+        conflict: RestoreConflictPolicy,
+    ) -> Result<RestoreResult, ClientError> {
+        let json = self
+            .proxy
+            .restore_from_snapshot(mountpoint, snapshot, target, paths, conflict.as_str())
+            .await?;
+        let result: RestoreResult = serde_json::from_str(&json)?;
+        Ok(result)
+    }
+
+    /// Replace `target` with a writable copy of `snapshot`, keeping a safety snapshot
+    pub async fn rollback(
+        &self,
+        mountpoint: &str,
+        target: &str,
+        snapshot: &str,
+    ) -> Result<RollbackResult, ClientError> {
+        let json = self.proxy.rollback(mountpoint, target, snapshot).await?;
+        let result: RollbackResult = serde_json::from_str(&json)?;
+        Ok(result)
+    }
+
+    /// Get the automatic safety snapshot policy for destructive operations
+    pub async fn get_safety_snapshot_policy(&self) -> Result<SafetySnapshotPolicy, ClientError> {
+        let json = self.proxy.get_safety_snapshot_policy().await?;
+        let policy: SafetySnapshotPolicy = serde_json::from_str(&json)?;
+        Ok(policy)
+    }
+
+    /// Set the automatic safety snapshot policy for destructive operations
+    pub async fn set_safety_snapshot_policy(
+        &self,
+        policy: &SafetySnapshotPolicy,
+    ) -> Result<(), ClientError> {
+        let json = serde_json::to_string(policy)?;
+        self.proxy.set_safety_snapshot_policy(&json).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Shared D-Bus connection management
+//!
+//! This module provides a cached D-Bus system bus connection that is reused
+//! across all client instances, improving performance by avoiding repeated
+//! connection establishment.
+//!
+//! Proxies address the service by its well-known name rather than the
+//! unique name of one process, so calls and signal streams made over the
+//! shared connection reach the service again after it restarts.
+//!
+//! The bus is the local system bus unless a [`ConnectionTarget::Remote`]
+//! is chosen before the first connection, to manage another host's disks
+//! through its system bus socket forwarded over SSH:
+//!
+//! ```text
+//! ssh -N -o StreamLocalBindUnlink=yes \
+//!     -L /tmp/server-bus:/run/dbus/system_bus_socket admin@server
+//! COSMIC_EXT_STORAGE_BUS_ADDRESS=unix:path=/tmp/server-bus cosmic-ext-storage
+//! ```
+//!
+//! The remote bus authenticates the connection as the SSH user, so when
+//! their UID on the server differs from the local one it has to be given
+//! too. Polkit on the server decides what they may do. The SSH user has no
+//! active session there, so the policy's defaults refuse every action,
+//! reads included; the rules file shipped beside the policy lets members of
+//! the server's `cosmic-storage` group read. Actions needing
+//! authentication stay refused, as no agent can answer for a remote
+//! caller.
+
+use std::sync::OnceLock;
+
+use zbus::Connection;
+
+use super::error::ClientError;
+
+/// Well-known bus name of storage-service
+pub const SERVICE_NAME: &str = "org.cosmic.ext.Storage.Service";
+
+/// Environment variable with the D-Bus address of a remote system bus
+pub const BUS_ADDRESS_ENV: &str = "COSMIC_EXT_STORAGE_BUS_ADDRESS";
+
+/// Environment variable with the caller's UID on the remote host
+pub const BUS_UID_ENV: &str = "COSMIC_EXT_STORAGE_BUS_UID";
+
+/// Bus that storage-service is reached on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ConnectionTarget {
+    /// The system bus of this host
+    #[default]
+    System,
+    /// The system bus of another host, e.g. a socket forwarded over SSH
+    Remote {
+        /// D-Bus address, e.g. "unix:path=/tmp/server-bus" or
+        /// "tcp:host=server,port=55556"
+        address: String,
+        /// UID to authenticate as, when it differs from the local one
+        user_id: Option<u32>,
+    },
+}
+
+impl ConnectionTarget {
+    /// Target named by a D-Bus address, or by the path of a forwarded
+    /// socket; "system" or nothing is the local system bus
+    pub fn parse(address: &str, user_id: Option<u32>) -> Result<Self, ClientError> {
+        let address = address.trim();
+        if address.is_empty() || address == "system" {
+            return Ok(Self::System);
+        }
+        let address = if address.starts_with('/') {
+            format!("unix:path={address}")
+        } else if address.contains(':') {
+            address.to_string()
+        } else {
+            return Err(ClientError::InvalidArgument(format!(
+                "{address} is neither a D-Bus address nor a socket path"
+            )));
+        };
+        Ok(Self::Remote { address, user_id })
+    }
+
+    /// Target from [`BUS_ADDRESS_ENV`] and [`BUS_UID_ENV`]
+    pub fn from_env() -> Result<Self, ClientError> {
+        let address = std::env::var(BUS_ADDRESS_ENV).unwrap_or_default();
+        let user_id = match std::env::var(BUS_UID_ENV) {
+            Ok(uid) => Some(uid.trim().parse().map_err(|_| {
+                ClientError::InvalidArgument(format!("{BUS_UID_ENV} is not a UID: {uid}"))
+            })?),
+            Err(_) => None,
+        };
+        Self::parse(&address, user_id)
+    }
+
+    /// The address of a remote bus, for showing which host is managed
+    pub fn remote_address(&self) -> Option<&str> {
+        match self {
+            Self::System => None,
+            Self::Remote { address, .. } => Some(address),
+        }
+    }
+
+    async fn connect(&self) -> Result<Connection, ClientError> {
+        match self {
+            Self::System => Connection::system().await.map_err(|e| {
+                ClientError::Connection(format!("Failed to connect to system bus: {}", e))
+            }),
+            Self::Remote { address, user_id } => {
+                let mut builder =
+                    zbus::connection::Builder::address(address.as_str()).map_err(|e| {
+                        ClientError::InvalidArgument(format!("Invalid bus address {address}: {e}"))
+                    })?;
+                if let Some(uid) = user_id {
+                    builder = builder.user_id(*uid);
+                }
+                builder.build().await.map_err(|e| {
+                    ClientError::Connection(format!("Failed to connect to {address}: {}", e))
+                })
+            }
+        }
+    }
+}
+
+/// Bus chosen for the shared connection
+static TARGET: OnceLock<ConnectionTarget> = OnceLock::new();
+
+/// Cached D-Bus system bus connection
+static SYSTEM_CONNECTION: OnceLock<Connection> = OnceLock::new();
+
+/// Choose the bus of the shared connection; fails once it is chosen,
+/// including by a first connection to the default from the environment
+pub fn set_connection_target(target: ConnectionTarget) -> Result<(), ClientError> {
+    TARGET
+        .set(target)
+        .map_err(|_| ClientError::Connection("The bus to connect to is already chosen".to_string()))
+}
+
+/// Bus of the shared connection: the one set with
+/// [`set_connection_target`], else the one in the environment, else the
+/// local system bus
+pub fn connection_target() -> Result<&'static ConnectionTarget, ClientError> {
+    if let Some(target) = TARGET.get() {
+        return Ok(target);
+    }
+    let _ = TARGET.set(ConnectionTarget::from_env()?);
+    TARGET
+        .get()
+        .ok_or_else(|| ClientError::Connection("Failed to choose the bus".to_string()))
+}
+
+/// Get or create the shared system bus connection
+///
+/// The connection is established lazily on first use and cached for
+/// subsequent calls. This avoids the overhead of creating multiple
+/// D-Bus connections across different client instances.
+pub async fn shared_connection() -> Result<&'static Connection, ClientError> {
+    if let Some(conn) = SYSTEM_CONNECTION.get() {
+        return Ok(conn);
+    }
+
+    // Race condition is acceptable - multiple connections during startup is fine
+    // The OnceLock ensures only one connection is retained
+    let conn = connection_target()?.connect().await?;
+
+    // Ignore error if already set (another task won the race)
+    let _ = SYSTEM_CONNECTION.set(conn);
+
+    SYSTEM_CONNECTION.get().ok_or_else(|| {
+        ClientError::Connection("Failed to initialize shared bus connection".to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_are_parsed() {
+        assert_eq!(
+            ConnectionTarget::parse("", None).unwrap(),
+            ConnectionTarget::System
+        );
+        assert_eq!(
+            ConnectionTarget::parse("/tmp/server-bus", Some(1000)).unwrap(),
+            ConnectionTarget::Remote {
+                address: "unix:path=/tmp/server-bus".to_string(),
+                user_id: Some(1000),
+            }
+        );
+        assert_eq!(
+            ConnectionTarget::parse("tcp:host=server,port=55556", None)
+                .unwrap()
+                .remote_address(),
+            Some("tcp:host=server,port=55556")
+        );
+        assert!(ConnectionTarget::parse("server", None).is_err());
+    }
+}
@@ -0,0 +1,500 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use crate::client::service::collect_paged_reply;
+use storage_types::{
+    DiagnosticBundle, DiskHealthSummary, DiskInfo, IdentifySupport, KernelDeviceError,
+    OpticalMediaInfo, PhysicalDevice, QueueSettings, QueueTuning, SectorRange, SelfTestRecord,
+    SelfTestSchedule, SmartAttribute, SmartBackendStatus, SmartStatus, TemperatureThresholds,
+    TrimResult, VolumeInfo, WriteCacheStatus,
+};
+use zbus::proxy;
+
+/// D-Bus proxy interface for disk discovery and SMART operations
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.Disks",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/disks"
+)]
+pub trait DisksInterface {
+    /// List all disks in the system
+    async fn list_disks(&self) -> zbus::Result<String>;
+
+    /// Get detailed information about a specific disk
+    async fn get_disk_info(&self, device: &str) -> zbus::Result<String>;
+
+    /// List all volumes across all disks with parent_path references
+    async fn list_volumes(&self) -> zbus::Result<String>;
+
+    /// Get information about a specific volume
+    async fn get_volume_info(&self, device: &str) -> zbus::Result<String>;
+
+    /// Get SMART status for a disk
+    async fn get_smart_status(&self, device: &str) -> zbus::Result<String>;
+
+    /// Get detailed SMART attributes
+    async fn get_smart_attributes(&self, device: &str) -> zbus::Result<String>;
+
+    /// Start a SMART self-test
+    async fn start_smart_test(&self, device: &str, test_type: &str) -> zbus::Result<()>;
+
+    /// Get the SMART backend selection of a disk
+    async fn get_smart_backend(&self, device: &str) -> zbus::Result<String>;
+
+    /// Force a SMART backend ("udisks", "smartctl") or "auto"
+    async fn set_smart_backend(&self, device: &str, backend: &str) -> zbus::Result<()>;
+
+    /// List the disks behind hardware RAID controllers
+    async fn list_physical_devices(&self) -> zbus::Result<String>;
+
+    /// Get the periodic self-test schedule of a disk
+    async fn get_selftest_schedule(&self, device: &str) -> zbus::Result<String>;
+
+    /// Set the periodic self-test schedule of a disk
+    async fn set_selftest_schedule(&self, device: &str, schedule_json: &str) -> zbus::Result<()>;
+
+    /// Get the self-tests run on a disk
+    async fn get_selftest_history(&self, device: &str) -> zbus::Result<String>;
+
+    /// Get the health score of a disk
+    async fn get_health_summary(&self, device: &str) -> zbus::Result<String>;
+
+    /// Get the device errors the kernel logged for a disk
+    async fn get_kernel_errors(&self, device: &str) -> zbus::Result<String>;
+
+    /// Collect a redacted diagnostic report of a failed operation, as a
+    /// paged reply
+    async fn collect_diagnostics(
+        &self,
+        device: &str,
+        since: u64,
+        summary: &str,
+    ) -> zbus::Result<String>;
+
+    /// Compare key metrics of several disks
+    async fn compare_drives(&self, devices: &[&str]) -> zbus::Result<String>;
+
+    /// Get the temperature thresholds of a disk
+    async fn get_temperature_thresholds(&self, device: &str) -> zbus::Result<String>;
+
+    /// Set the temperature thresholds of a disk ("" for the defaults)
+    async fn set_temperature_thresholds(
+        &self,
+        device: &str,
+        thresholds_json: &str,
+    ) -> zbus::Result<()>;
+
+    /// Get the I/O scheduler, readahead and queue depth of a disk
+    async fn get_queue_settings(&self, device: &str) -> zbus::Result<String>;
+
+    /// Set the I/O scheduler, readahead and queue depth of a disk
+    async fn set_queue_tuning(
+        &self,
+        device: &str,
+        tuning_json: &str,
+        persistent: bool,
+    ) -> zbus::Result<()>;
+
+    /// Get the volatile write cache state of a disk
+    async fn get_write_cache(&self, device: &str) -> zbus::Result<String>;
+
+    /// Turn the volatile write cache of a disk on or off
+    async fn set_write_cache(
+        &self,
+        device: &str,
+        enabled: bool,
+        persistent: bool,
+    ) -> zbus::Result<()>;
+
+    /// Blink the enclosure slot LED of a disk for some seconds
+    async fn identify_drive(&self, device: &str, seconds: u32) -> zbus::Result<String>;
+
+    /// Get the hypervisor the system runs under, empty on bare metal
+    async fn get_hypervisor(&self) -> zbus::Result<String>;
+
+    /// Discard the unused blocks of every mounted filesystem on a disk
+    async fn trim_filesystems(&self, device: &str) -> zbus::Result<String>;
+
+    /// Read raw bytes of a drive or partition, read-only
+    async fn read_sectors(&self, device: &str, offset: u64, length: u64) -> zbus::Result<String>;
+
+    /// Get the disc loaded in an optical drive
+    async fn get_optical_media(&self, device: &str) -> zbus::Result<String>;
+
+    /// Eject removable media
+    async fn eject(&self, device: &str) -> zbus::Result<()>;
+
+    /// Power off a drive
+    async fn power_off(&self, device: &str) -> zbus::Result<()>;
+
+    /// Put drive in low-power standby mode
+    async fn standby_now(&self, device: &str) -> zbus::Result<()>;
+
+    /// Wake drive from standby mode
+    async fn wakeup(&self, device: &str) -> zbus::Result<()>;
+
+    /// Safely remove a drive (unmount all, lock LUKS, eject, power off)
+    async fn remove(&self, device: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a disk is added (hotplug)
+    #[zbus(signal)]
+    async fn disk_added(&self, device: &str, info_json: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a disk is removed
+    #[zbus(signal)]
+    async fn disk_removed(&self, device: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a SMART test completes
+    #[zbus(signal)]
+    async fn smart_test_completed(&self, device: &str, success: bool) -> zbus::Result<()>;
+
+    /// Signal emitted when a disk's temperature crosses one of its thresholds
+    #[zbus(signal)]
+    async fn temperature_alert(&self, device: &str, level: &str, celsius: u64) -> zbus::Result<()>;
+}
+
+/// Client for disk discovery and SMART operations
+pub struct DisksClient {
+    proxy: DisksInterfaceProxy<'static>,
+}
+
+impl std::fmt::Debug for DisksClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DisksClient").finish_non_exhaustive()
+    }
+}
+
+impl DisksClient {
+    /// Create a new disks client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = DisksInterfaceProxy::new(conn)
+            .await
+            .map_err(|e| ClientError::Connection(format!("Failed to create disks proxy: {}", e)))?;
+
+        Ok(Self { proxy })
+    }
+
+    /// List all disks in the system
+    pub async fn list_disks(&self) -> Result<Vec<DiskInfo>, ClientError> {
+        let json = self.proxy.list_disks().await?;
+        let disks: Vec<DiskInfo> = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse disk list: {}", e)))?;
+        Ok(disks)
+    }
+
+    /// Get detailed information about a specific disk
+    pub async fn get_disk_info(&self, device: &str) -> Result<DiskInfo, ClientError> {
+        let json = self.proxy.get_disk_info(device).await?;
+        let disk: DiskInfo = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse disk info: {}", e)))?;
+        Ok(disk)
+    }
+
+    /// List all volumes across all disks
+    ///
+    /// Returns a flat list of all volumes with parent_path populated for building hierarchies.
+    pub async fn list_volumes(&self) -> Result<Vec<VolumeInfo>, ClientError> {
+        let json = self.proxy.list_volumes().await?;
+        let volumes: Vec<VolumeInfo> = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse volume list: {}", e)))?;
+        Ok(volumes)
+    }
+
+    /// Get SMART status for a disk
+    pub async fn get_smart_status(&self, device: &str) -> Result<SmartStatus, ClientError> {
+        let json = self.proxy.get_smart_status(device).await?;
+        let status: SmartStatus = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse SMART status: {}", e)))?;
+        Ok(status)
+    }
+
+    /// Get detailed SMART attributes
+    pub async fn get_smart_attributes(
+        &self,
+        device: &str,
+    ) -> Result<Vec<SmartAttribute>, ClientError> {
+        let json = self.proxy.get_smart_attributes(device).await?;
+        let attributes: Vec<SmartAttribute> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse SMART attributes: {}", e))
+        })?;
+        Ok(attributes)
+    }
+
+    /// Start a SMART self-test (short, long, or conveyance)
+    pub async fn start_smart_test(&self, device: &str, test_type: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.start_smart_test(device, test_type).await?)
+    }
+
+    /// Get the SMART backend selection of a disk
+    pub async fn get_smart_backend(&self, device: &str) -> Result<SmartBackendStatus, ClientError> {
+        let json = self.proxy.get_smart_backend(device).await?;
+        let status: SmartBackendStatus = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse SMART backend status: {}", e))
+        })?;
+        Ok(status)
+    }
+
+    /// Force a SMART backend ("udisks", "smartctl") or return to "auto" selection
+    pub async fn set_smart_backend(&self, device: &str, backend: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.set_smart_backend(device, backend).await?)
+    }
+
+    /// Get the periodic self-test schedule of a disk
+    pub async fn get_selftest_schedule(
+        &self,
+        device: &str,
+    ) -> Result<SelfTestSchedule, ClientError> {
+        let json = self.proxy.get_selftest_schedule(device).await?;
+        let schedule: SelfTestSchedule = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse self-test schedule: {}", e))
+        })?;
+        Ok(schedule)
+    }
+
+    /// Set the periodic self-test schedule of a disk
+    pub async fn set_selftest_schedule(
+        &self,
+        device: &str,
+        schedule: &SelfTestSchedule,
+    ) -> Result<(), ClientError> {
+        let json = serde_json::to_string(schedule).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize self-test schedule: {}", e))
+        })?;
+        Ok(self.proxy.set_selftest_schedule(device, &json).await?)
+    }
+
+    /// Get the self-tests run on a disk, oldest first
+    pub async fn get_selftest_history(
+        &self,
+        device: &str,
+    ) -> Result<Vec<SelfTestRecord>, ClientError> {
+        let json = self.proxy.get_selftest_history(device).await?;
+        let history: Vec<SelfTestRecord> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse self-test history: {}", e))
+        })?;
+        Ok(history)
+    }
+
+    /// Get the health score of a disk with the findings behind it
+    pub async fn get_health_summary(&self, device: &str) -> Result<DiskHealthSummary, ClientError> {
+        let json = self.proxy.get_health_summary(device).await?;
+        let summary: DiskHealthSummary = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse health summary: {}", e))
+        })?;
+        Ok(summary)
+    }
+
+    /// Get the I/O, ATA and NVMe errors the kernel logged for a disk,
+    /// oldest first
+    pub async fn get_kernel_errors(
+        &self,
+        device: &str,
+    ) -> Result<Vec<KernelDeviceError>, ClientError> {
+        let json = self.proxy.get_kernel_errors(device).await?;
+        let errors: Vec<KernelDeviceError> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse kernel errors: {}", e))
+        })?;
+        Ok(errors)
+    }
+
+    /// Collect a diagnostic report of an operation that failed at `since`
+    /// (Unix time) on `device`, or on no device when it is empty, starting
+    /// with `summary`
+    pub async fn collect_diagnostics(
+        &self,
+        device: &str,
+        since: u64,
+        summary: &str,
+    ) -> Result<DiagnosticBundle, ClientError> {
+        let json = self
+            .proxy
+            .collect_diagnostics(device, since, summary)
+            .await?;
+        let json = collect_paged_reply(&json).await?;
+        let bundle: DiagnosticBundle = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse diagnostics: {}", e)))?;
+        Ok(bundle)
+    }
+
+    /// Get the disc loaded in an optical drive, `None` when it is empty
+    pub async fn get_optical_media(
+        &self,
+        device: &str,
+    ) -> Result<Option<OpticalMediaInfo>, ClientError> {
+        let json = self.proxy.get_optical_media(device).await?;
+        let media = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse optical media: {}", e))
+        })?;
+        Ok(media)
+    }
+
+    /// Power off an external drive
+    ///
+    /// Requires administrator authentication (cached for session).
+    pub async fn power_off(&self, device: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.power_off(device).await?)
+    }
+
+    /// Put drive in low-power standby mode (ATA drives)
+    ///
+    /// Requires no authentication for active sessions.
+    pub async fn standby_now(&self, device: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.standby_now(device).await?)
+    }
+
+    /// Wake drive from standby mode (ATA drives)
+    ///
+    /// Requires no authentication for active sessions.
+    pub async fn wakeup(&self, device: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.wakeup(device).await?)
+    }
+
+    /// Safely remove a drive
+    ///
+    /// This performs a complete safe removal workflow:
+    /// 1. Unmount all filesystems on the drive
+    /// 2. Lock any LUKS encrypted containers
+    /// 3. Eject the media if ejectable
+    /// 4. Power off the drive if possible
+    ///
+    /// Requires administrator authentication (cached for session).
+    pub async fn remove(&self, device: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.remove(device).await?)
+    }
+
+    /// Get the temperature thresholds of a disk
+    pub async fn get_temperature_thresholds(
+        &self,
+        device: &str,
+    ) -> Result<TemperatureThresholds, ClientError> {
+        let json = self.proxy.get_temperature_thresholds(device).await?;
+        let thresholds: TemperatureThresholds = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse temperature thresholds: {}", e))
+        })?;
+        Ok(thresholds)
+    }
+
+    /// Set the temperature thresholds of a disk, or restore the defaults with `None`
+    pub async fn set_temperature_thresholds(
+        &self,
+        device: &str,
+        thresholds: Option<&TemperatureThresholds>,
+    ) -> Result<(), ClientError> {
+        let json = match thresholds {
+            Some(thresholds) => serde_json::to_string(thresholds).map_err(|e| {
+                ClientError::ParseError(format!("Failed to serialize thresholds: {}", e))
+            })?,
+            None => String::new(),
+        };
+        Ok(self.proxy.set_temperature_thresholds(device, &json).await?)
+    }
+
+    /// Get the I/O scheduler, readahead and queue depth of a disk
+    pub async fn get_queue_settings(&self, device: &str) -> Result<QueueSettings, ClientError> {
+        let json = self.proxy.get_queue_settings(device).await?;
+        let settings: QueueSettings = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse queue settings: {}", e))
+        })?;
+        Ok(settings)
+    }
+
+    /// Apply `tuning` to a disk, keeping it across reboots when `persistent`
+    pub async fn set_queue_tuning(
+        &self,
+        device: &str,
+        tuning: &QueueTuning,
+        persistent: bool,
+    ) -> Result<(), ClientError> {
+        let json = serde_json::to_string(tuning).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize queue tuning: {}", e))
+        })?;
+        Ok(self
+            .proxy
+            .set_queue_tuning(device, &json, persistent)
+            .await?)
+    }
+
+    /// Get the volatile write cache state of a disk
+    pub async fn get_write_cache(&self, device: &str) -> Result<WriteCacheStatus, ClientError> {
+        let json = self.proxy.get_write_cache(device).await?;
+        let status: WriteCacheStatus = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse write cache status: {}", e))
+        })?;
+        Ok(status)
+    }
+
+    /// Turn the write cache of a disk on or off, keeping the setting across
+    /// reboots when `persistent`
+    pub async fn set_write_cache(
+        &self,
+        device: &str,
+        enabled: bool,
+        persistent: bool,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .set_write_cache(device, enabled, persistent)
+            .await?)
+    }
+
+    /// Blink the enclosure slot LED of a disk for `seconds`, returning the
+    /// LED used
+    pub async fn identify_drive(
+        &self,
+        device: &str,
+        seconds: u32,
+    ) -> Result<IdentifySupport, ClientError> {
+        let json = self.proxy.identify_drive(device, seconds).await?;
+        let support: IdentifySupport = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse identify support: {}", e))
+        })?;
+        Ok(support)
+    }
+
+    /// Hypervisor the system runs under (e.g. "kvm"), None on bare metal
+    pub async fn get_hypervisor(&self) -> Result<Option<String>, ClientError> {
+        let hypervisor = self.proxy.get_hypervisor().await?;
+        Ok(Some(hypervisor).filter(|hypervisor| !hypervisor.is_empty()))
+    }
+
+    /// Trim every mounted filesystem on a disk, returning the space each
+    /// handed back
+    pub async fn trim_filesystems(&self, device: &str) -> Result<Vec<TrimResult>, ClientError> {
+        let json = self.proxy.trim_filesystems(device).await?;
+        let results: Vec<TrimResult> = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse trim results: {}", e)))?;
+        Ok(results)
+    }
+
+    /// Read `length` bytes at byte `offset` of a drive or partition, for the
+    /// sector viewer
+    pub async fn read_sectors(
+        &self,
+        device: &str,
+        offset: u64,
+        length: u64,
+    ) -> Result<SectorRange, ClientError> {
+        let json = self.proxy.read_sectors(device, offset, length).await?;
+        let range: SectorRange = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse sectors: {}", e)))?;
+        Ok(range)
+    }
+
+    /// List the disks behind hardware RAID controllers, with their SMART
+    /// data
+    pub async fn list_physical_devices(&self) -> Result<Vec<PhysicalDevice>, ClientError> {
+        let json = self.proxy.list_physical_devices().await?;
+        let devices: Vec<PhysicalDevice> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse physical devices: {}", e))
+        })?;
+        Ok(devices)
+    }
+
+    /// Get the underlying proxy for signal subscriptions
+    pub fn proxy(&self) -> &DisksInterfaceProxy<'static> {
+        &self.proxy
+    }
+}
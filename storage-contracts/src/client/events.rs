@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! One stream of the storage service's change signals
+//!
+//! Applets that only want to know when to refresh can follow
+//! [`service_events`] instead of subscribing to each interface. The
+//! signal streams address the service by its well-known name, so they keep
+//! delivering after the service restarts; [`ServiceEvent::ServiceStarted`]
+//! tells the listener to reload anything it fetched before.
+
+use crate::client::connection::{SERVICE_NAME, shared_connection};
+use crate::client::disks::DisksInterfaceProxy;
+use crate::client::error::ClientError;
+use crate::client::filesystems::FilesystemsInterfaceProxy;
+use crate::client::luks::LuksInterfaceProxy;
+use futures_util::stream::{BoxStream, Stream, StreamExt, select_all};
+use zbus::fdo::DBusProxy;
+
+/// A change reported by the storage service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceEvent {
+    DiskAdded {
+        device: String,
+    },
+    DiskRemoved {
+        device: String,
+    },
+    Formatted {
+        device: String,
+        fs_type: String,
+    },
+    Mounted {
+        device: String,
+        mount_point: String,
+    },
+    Unmounted {
+        device_or_mount: String,
+    },
+    ContainerCreated {
+        device: String,
+    },
+    ContainerUnlocked {
+        device: String,
+        cleartext_device: String,
+    },
+    ContainerLocked {
+        device: String,
+    },
+    UsageScanProgress {
+        scan_id: String,
+        processed_bytes: u64,
+        estimated_total_bytes: u64,
+    },
+    DefragProgress {
+        device: String,
+        processed: u64,
+        total: u64,
+    },
+    /// The service started, or restarted; earlier replies may be stale
+    ServiceStarted,
+    /// The service stopped; calls fail until it is started again
+    ServiceStopped,
+}
+
+impl ServiceEvent {
+    /// Whether disks, volumes or mounts may have changed, so that a view of
+    /// them should be reloaded
+    pub fn changes_devices(&self) -> bool {
+        !matches!(
+            self,
+            Self::UsageScanProgress { .. } | Self::DefragProgress { .. } | Self::ServiceStopped
+        )
+    }
+}
+
+/// Every change signal of the disks, filesystems and LUKS interfaces, and
+/// the service starting and stopping, as one stream
+pub async fn service_events() -> Result<impl Stream<Item = ServiceEvent> + Send, ClientError> {
+    let conn = shared_connection().await?;
+    let disks = DisksInterfaceProxy::new(conn).await?;
+    let filesystems = FilesystemsInterfaceProxy::new(conn).await?;
+    let luks = LuksInterfaceProxy::new(conn).await?;
+    let bus = DBusProxy::new(conn).await?;
+
+    let streams: Vec<BoxStream<'static, ServiceEvent>> = vec![
+        disks
+            .receive_disk_added()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::DiskAdded {
+                    device: args.device.to_string(),
+                })
+            })
+            .boxed(),
+        disks
+            .receive_disk_removed()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::DiskRemoved {
+                    device: args.device.to_string(),
+                })
+            })
+            .boxed(),
+        filesystems
+            .receive_formatted()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::Formatted {
+                    device: args.device.to_string(),
+                    fs_type: args.fs_type.to_string(),
+                })
+            })
+            .boxed(),
+        filesystems
+            .receive_mounted()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::Mounted {
+                    device: args.device.to_string(),
+                    mount_point: args.mount_point.to_string(),
+                })
+            })
+            .boxed(),
+        filesystems
+            .receive_unmounted()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::Unmounted {
+                    device_or_mount: args.device_or_mount.to_string(),
+                })
+            })
+            .boxed(),
+        filesystems
+            .receive_usage_scan_progress()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::UsageScanProgress {
+                    scan_id: args.scan_id.to_string(),
+                    processed_bytes: args.processed_bytes,
+                    estimated_total_bytes: args.estimated_total_bytes,
+                })
+            })
+            .boxed(),
+        filesystems
+            .receive_defrag_progress()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::DefragProgress {
+                    device: args.device.to_string(),
+                    processed: args.processed,
+                    total: args.total,
+                })
+            })
+            .boxed(),
+        luks.receive_container_created()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::ContainerCreated {
+                    device: args.device.to_string(),
+                })
+            })
+            .boxed(),
+        luks.receive_container_unlocked()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::ContainerUnlocked {
+                    device: args.device.to_string(),
+                    cleartext_device: args.cleartext_device.to_string(),
+                })
+            })
+            .boxed(),
+        luks.receive_container_locked()
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(ServiceEvent::ContainerLocked {
+                    device: args.device.to_string(),
+                })
+            })
+            .boxed(),
+        bus.receive_name_owner_changed_with_args(&[(0, SERVICE_NAME)])
+            .await?
+            .filter_map(|signal| async move {
+                let args = signal.args().ok()?;
+                Some(if args.new_owner.is_some() {
+                    ServiceEvent::ServiceStarted
+                } else {
+                    ServiceEvent::ServiceStopped
+                })
+            })
+            .boxed(),
+    ];
+    Ok(select_all(streams))
+}
@@ -0,0 +1,685 @@
+// SPDX-License-Identifier: GPL-3.0-only
+#![allow(clippy::too_many_arguments)]
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use crate::client::service::collect_paged_reply;
+use storage_types::{
+    AutomountUnit, CapacityForecast, DefragResult, FilesystemFeatures, FilesystemToolInfo,
+    ForcedReadOnly, FragmentationReport, FscryptStatus, LegacyVault, LowSpaceRule,
+    MountOptionsSettings, SelinuxStatus, UnmountResult, UsageDeleteResult,
+    UsageScanParallelismPreset, UsageScanResult, VaultMigrationResult, WindowsHibernation,
+};
+use zbus::proxy;
+
+/// D-Bus proxy interface for filesystem operations
+#[allow(clippy::too_many_arguments)]
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.Filesystems",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/filesystems"
+)]
+pub trait FilesystemsInterface {
+    /// List all filesystems
+    async fn list_filesystems(&self) -> zbus::Result<String>;
+
+    /// Get list of supported filesystem types
+    async fn get_supported_filesystems(&self) -> zbus::Result<String>;
+
+    /// Get detailed filesystem tool information
+    async fn get_filesystem_tools(&self) -> zbus::Result<String>;
+
+    /// Get on-disk filesystem features
+    async fn get_filesystem_features(&self, device: &str) -> zbus::Result<String>;
+
+    /// Format a device with a filesystem
+    async fn format(
+        &self,
+        device: &str,
+        fs_type: &str,
+        label: &str,
+        options_json: &str,
+    ) -> zbus::Result<()>;
+
+    /// Mount a filesystem
+    async fn mount(
+        &self,
+        device: &str,
+        mount_point: &str,
+        options_json: &str,
+    ) -> zbus::Result<String>;
+
+    /// Whether Windows left the NTFS filesystem on a device hibernated
+    async fn get_windows_hibernation(&self, device: &str) -> zbus::Result<String>;
+
+    /// Unmount a filesystem
+    async fn unmount(
+        &self,
+        device_or_mount: &str,
+        force: bool,
+        kill_processes: bool,
+    ) -> zbus::Result<String>;
+
+    /// Get processes blocking unmount, as a paged reply
+    async fn get_blocking_processes(&self, device_or_mount: &str) -> zbus::Result<String>;
+
+    /// Check and repair a filesystem
+    async fn check(&self, device: &str, repair: bool) -> zbus::Result<String>;
+
+    /// List filesystems the kernel made read-only after an error
+    async fn list_forced_read_only(&self) -> zbus::Result<String>;
+
+    /// Make a filesystem the kernel made read-only writable again
+    async fn remount_read_write(&self, mount_point: &str) -> zbus::Result<()>;
+
+    /// Bind a directory to a second place
+    async fn create_bind_mount(
+        &self,
+        source: &str,
+        target: &str,
+        read_only: bool,
+        persistent: bool,
+    ) -> zbus::Result<()>;
+
+    /// Unmount a bind mount, optionally removing its fstab entry
+    async fn remove_bind_mount(&self, target: &str, forget: bool) -> zbus::Result<()>;
+
+    /// List the systemd automount units created by the service
+    async fn list_automount_units(&self) -> zbus::Result<String>;
+
+    /// Mount on access through systemd automount units
+    async fn create_automount_unit(&self, unit_json: &str) -> zbus::Result<()>;
+
+    /// Stop and remove the automount units of a mount point
+    async fn remove_automount_unit(&self, mount_point: &str) -> zbus::Result<()>;
+
+    /// List the SELinux labels of mounted filesystems
+    async fn list_selinux_statuses(&self) -> zbus::Result<String>;
+
+    /// Relabel a mounted filesystem with restorecon
+    async fn relabel(&self, mount_point: &str) -> zbus::Result<()>;
+
+    /// Project when mounted filesystems run full
+    async fn list_capacity_forecasts(&self) -> zbus::Result<String>;
+
+    /// List the low space rules of mount points
+    async fn list_low_space_rules(&self) -> zbus::Result<String>;
+
+    /// Set the low space rule of a mount point
+    async fn set_low_space_rule(&self, rule_json: &str) -> zbus::Result<()>;
+
+    /// Set filesystem label
+    async fn set_label(&self, device: &str, label: &str) -> zbus::Result<()>;
+
+    /// Estimate fragmentation of a mounted filesystem or directory
+    async fn get_fragmentation(&self, device: &str, target: &str) -> zbus::Result<String>;
+
+    /// Defragment a mounted filesystem or directory
+    async fn defragment(&self, device: &str, target: &str) -> zbus::Result<String>;
+
+    /// List the encrypted directories of a mounted filesystem
+    async fn get_fscrypt_status(&self, device: &str) -> zbus::Result<String>;
+
+    /// Create an encrypted directory protected with a passphrase
+    async fn create_encrypted_directory(
+        &self,
+        device: &str,
+        directory: &str,
+        protector_name: &str,
+        passphrase: &str,
+    ) -> zbus::Result<()>;
+
+    /// Unlock an encrypted directory with its passphrase
+    async fn unlock_encrypted_directory(
+        &self,
+        device: &str,
+        directory: &str,
+        passphrase: &str,
+    ) -> zbus::Result<()>;
+
+    /// Lock an encrypted directory
+    async fn lock_encrypted_directory(&self, device: &str, directory: &str) -> zbus::Result<()>;
+
+    /// List eCryptfs and gocryptfs vaults (JSON Vec<LegacyVault>)
+    async fn list_legacy_vaults(&self) -> zbus::Result<String>;
+
+    /// Mount a vault read-only; returns its mount point
+    async fn mount_legacy_vault(&self, cipher_dir: &str, passphrase: &str) -> zbus::Result<String>;
+
+    /// Unmount a vault mounted with `mount_legacy_vault`
+    async fn unmount_legacy_vault(&self, cipher_dir: &str) -> zbus::Result<()>;
+
+    /// Copy the files of a mounted vault into an encrypted directory (JSON VaultMigrationResult)
+    async fn migrate_legacy_vault(&self, cipher_dir: &str, target: &str) -> zbus::Result<String>;
+
+    /// Get filesystem usage statistics
+    async fn get_usage(&self, mount_point: &str) -> zbus::Result<String>;
+
+    /// Run a global usage scan and return categorized usage with top files,
+    /// as a paged reply.
+    async fn get_usage_scan(
+        &self,
+        scan_id: &str,
+        top_files_per_category: u32,
+        mount_points_json: &str,
+        show_all_files: bool,
+        parallelism_preset: &str,
+    ) -> zbus::Result<String>;
+
+    /// List local mount points available for usage scans.
+    async fn list_usage_mount_points(&self) -> zbus::Result<String>;
+
+    /// Delete selected usage files and return per-path outcomes.
+    async fn delete_usage_files(&self, paths_json: &str) -> zbus::Result<String>;
+
+    /// Authorize session usage of Show All Files.
+    async fn authorize_usage_show_all_files(&self) -> zbus::Result<bool>;
+
+    /// Get persistent mount options (fstab) for a device
+    async fn get_mount_options(&self, device: &str) -> zbus::Result<String>;
+
+    /// Clear persistent mount options for a device
+    async fn default_mount_options(&self, device: &str) -> zbus::Result<()>;
+
+    /// Set persistent mount options for a device
+    async fn edit_mount_options(
+        &self,
+        device: &str,
+        mount_at_startup: bool,
+        show_in_ui: bool,
+        require_auth: bool,
+        display_name: &str,
+        icon_name: &str,
+        symbolic_icon_name: &str,
+        other_options: &str,
+        mount_point: &str,
+        identify_as: &str,
+        filesystem_type: &str,
+    ) -> zbus::Result<()>;
+
+    /// Take ownership of a mounted filesystem (e.g. for fstab)
+    async fn take_ownership(&self, device: &str, recursive: bool) -> zbus::Result<()>;
+
+    /// Signal emitted during format operation with progress
+    #[zbus(signal)]
+    async fn format_progress(&self, device: &str, percent: u8) -> zbus::Result<()>;
+
+    /// Signal emitted when format completes
+    #[zbus(signal)]
+    async fn formatted(&self, device: &str, fs_type: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when filesystem is mounted
+    #[zbus(signal)]
+    async fn mounted(&self, device: &str, mount_point: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when filesystem is unmounted
+    #[zbus(signal)]
+    async fn unmounted(&self, device_or_mount: &str) -> zbus::Result<()>;
+
+    /// Signal emitted during usage scan with processed and estimated total bytes.
+    #[zbus(signal)]
+    async fn usage_scan_progress(
+        &self,
+        scan_id: &str,
+        processed_bytes: u64,
+        estimated_total_bytes: u64,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted during defragmentation with files processed and total (0 if unknown)
+    #[zbus(signal)]
+    async fn defrag_progress(&self, device: &str, processed: u64, total: u64) -> zbus::Result<()>;
+
+    /// Signal emitted while migrating a legacy vault with bytes copied and the total
+    #[zbus(signal)]
+    async fn vault_migration_progress(
+        &self,
+        cipher_dir: &str,
+        copied_bytes: u64,
+        total_bytes: u64,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted when a mounted filesystem drops below its low space rule
+    #[zbus(signal)]
+    async fn low_space(&self, warning_json: &str) -> zbus::Result<()>;
+}
+
+/// Client for filesystem operations
+pub struct FilesystemsClient {
+    proxy: FilesystemsInterfaceProxy<'static>,
+}
+
+impl std::fmt::Debug for FilesystemsClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilesystemsClient").finish_non_exhaustive()
+    }
+}
+
+impl FilesystemsClient {
+    /// Create a new filesystems client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = FilesystemsInterfaceProxy::new(conn).await.map_err(|e| {
+            ClientError::Connection(format!("Failed to create filesystems proxy: {}", e))
+        })?;
+
+        Ok(Self { proxy })
+    }
+
+    /// Get detailed filesystem tool information
+    pub async fn get_filesystem_tools(&self) -> Result<Vec<FilesystemToolInfo>, ClientError> {
+        let json = self.proxy.get_filesystem_tools().await?;
+        let tools: Vec<FilesystemToolInfo> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse filesystem tools: {}", e))
+        })?;
+        Ok(tools)
+    }
+
+    /// Get on-disk filesystem features (feature flags, reflink, compression, resize support)
+    pub async fn get_filesystem_features(
+        &self,
+        device: &str,
+    ) -> Result<FilesystemFeatures, ClientError> {
+        let json = self.proxy.get_filesystem_features(device).await?;
+        let features: FilesystemFeatures = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse filesystem features: {}", e))
+        })?;
+        Ok(features)
+    }
+
+    /// Format a device with a filesystem
+    pub async fn format(
+        &self,
+        device: &str,
+        fs_type: &str,
+        label: &str,
+        options: Option<&str>,
+    ) -> Result<(), ClientError> {
+        let options_json = options.unwrap_or("{}");
+        Ok(self
+            .proxy
+            .format(device, fs_type, label, options_json)
+            .await?)
+    }
+
+    /// Mount a filesystem, returns actual mount point used
+    pub async fn mount(
+        &self,
+        device: &str,
+        mount_point: &str,
+        options: Option<&str>,
+    ) -> Result<String, ClientError> {
+        let options_json = options.unwrap_or("{}");
+        Ok(self.proxy.mount(device, mount_point, options_json).await?)
+    }
+
+    /// Whether Windows left the NTFS filesystem on `device` hibernated, in
+    /// which case the service only mounts it read-only
+    pub async fn windows_hibernation(
+        &self,
+        device: &str,
+    ) -> Result<Option<WindowsHibernation>, ClientError> {
+        let json = self.proxy.get_windows_hibernation(device).await?;
+        serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse hibernation state: {}", e))
+        })
+    }
+
+    /// Unmount a filesystem
+    pub async fn unmount(
+        &self,
+        device_or_mount: &str,
+        force: bool,
+        kill_processes: bool,
+    ) -> Result<UnmountResult, ClientError> {
+        let json = self
+            .proxy
+            .unmount(device_or_mount, force, kill_processes)
+            .await?;
+        let result: UnmountResult = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse unmount result: {}", e))
+        })?;
+        Ok(result)
+    }
+
+    /// Check and repair a filesystem
+    pub async fn check(&self, device: &str, repair: bool) -> Result<String, ClientError> {
+        Ok(self.proxy.check(device, repair).await?)
+    }
+
+    /// Mounted filesystems the kernel made read-only after an error
+    pub async fn list_forced_read_only(&self) -> Result<Vec<ForcedReadOnly>, ClientError> {
+        let json = self.proxy.list_forced_read_only().await?;
+        let filesystems: Vec<ForcedReadOnly> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse read-only filesystems: {}", e))
+        })?;
+        Ok(filesystems)
+    }
+
+    /// Remount a filesystem the kernel made read-only as read-write
+    pub async fn remount_read_write(&self, mount_point: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.remount_read_write(mount_point).await?)
+    }
+
+    /// Bind `source` to `target`, with `persistent` also in fstab
+    pub async fn create_bind_mount(
+        &self,
+        source: &str,
+        target: &str,
+        read_only: bool,
+        persistent: bool,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .create_bind_mount(source, target, read_only, persistent)
+            .await?)
+    }
+
+    /// Unmount the bind mount at `target`, with `forget` also removing it
+    /// from fstab
+    pub async fn remove_bind_mount(&self, target: &str, forget: bool) -> Result<(), ClientError> {
+        Ok(self.proxy.remove_bind_mount(target, forget).await?)
+    }
+
+    /// Automount units created by the service, by mount point
+    pub async fn list_automount_units(&self) -> Result<Vec<AutomountUnit>, ClientError> {
+        let json = self.proxy.list_automount_units().await?;
+        let units: Vec<AutomountUnit> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse automount units: {}", e))
+        })?;
+        Ok(units)
+    }
+
+    /// Write the `.mount` and `.automount` units of `unit` and enable them
+    pub async fn create_automount_unit(&self, unit: &AutomountUnit) -> Result<(), ClientError> {
+        let unit_json = serde_json::to_string(unit).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize automount unit: {}", e))
+        })?;
+        Ok(self.proxy.create_automount_unit(&unit_json).await?)
+    }
+
+    /// Stop and remove the automount units at `mount_point`
+    pub async fn remove_automount_unit(&self, mount_point: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.remove_automount_unit(mount_point).await?)
+    }
+
+    /// SELinux labels of mounted filesystems; empty when SELinux is disabled
+    pub async fn list_selinux_statuses(&self) -> Result<Vec<SelinuxStatus>, ClientError> {
+        let json = self.proxy.list_selinux_statuses().await?;
+        let statuses: Vec<SelinuxStatus> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse SELinux labels: {}", e))
+        })?;
+        Ok(statuses)
+    }
+
+    /// Give the files at `mount_point` the labels the SELinux policy gives
+    /// them
+    pub async fn relabel(&self, mount_point: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.relabel(mount_point).await?)
+    }
+
+    /// When mounted filesystems run full at their current growth
+    pub async fn list_capacity_forecasts(&self) -> Result<Vec<CapacityForecast>, ClientError> {
+        let json = self.proxy.list_capacity_forecasts().await?;
+        let forecasts: Vec<CapacityForecast> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse capacity forecasts: {}", e))
+        })?;
+        Ok(forecasts)
+    }
+
+    /// Low space rules of mount points
+    pub async fn list_low_space_rules(&self) -> Result<Vec<LowSpaceRule>, ClientError> {
+        let json = self.proxy.list_low_space_rules().await?;
+        let rules: Vec<LowSpaceRule> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse low space rules: {}", e))
+        })?;
+        Ok(rules)
+    }
+
+    /// Set the low space rule of `rule.mount_point`; a rule without
+    /// thresholds removes it
+    pub async fn set_low_space_rule(&self, rule: &LowSpaceRule) -> Result<(), ClientError> {
+        let rule_json = serde_json::to_string(rule).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize low space rule: {}", e))
+        })?;
+        Ok(self.proxy.set_low_space_rule(&rule_json).await?)
+    }
+
+    /// Set filesystem label
+    pub async fn set_label(&self, device: &str, label: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.set_label(device, label).await?)
+    }
+
+    /// Estimate fragmentation of `target` (empty for the whole filesystem)
+    pub async fn get_fragmentation(
+        &self,
+        device: &str,
+        target: &str,
+    ) -> Result<FragmentationReport, ClientError> {
+        let json = self.proxy.get_fragmentation(device, target).await?;
+        let report: FragmentationReport = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse fragmentation report: {}", e))
+        })?;
+        Ok(report)
+    }
+
+    /// Defragment `target` (empty for the whole filesystem)
+    pub async fn defragment(
+        &self,
+        device: &str,
+        target: &str,
+    ) -> Result<DefragResult, ClientError> {
+        let json = self.proxy.defragment(device, target).await?;
+        let result: DefragResult = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse defrag result: {}", e))
+        })?;
+        Ok(result)
+    }
+
+    /// List the encrypted directories of the filesystem on `device`
+    pub async fn get_fscrypt_status(&self, device: &str) -> Result<FscryptStatus, ClientError> {
+        let json = self.proxy.get_fscrypt_status(device).await?;
+        let status: FscryptStatus = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse fscrypt status: {}", e))
+        })?;
+        Ok(status)
+    }
+
+    /// Create `directory` encrypted, with a passphrase protector named
+    /// `protector_name`
+    pub async fn create_encrypted_directory(
+        &self,
+        device: &str,
+        directory: &str,
+        protector_name: &str,
+        passphrase: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .create_encrypted_directory(device, directory, protector_name, passphrase)
+            .await?)
+    }
+
+    /// Unlock the encrypted `directory`
+    pub async fn unlock_encrypted_directory(
+        &self,
+        device: &str,
+        directory: &str,
+        passphrase: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .unlock_encrypted_directory(device, directory, passphrase)
+            .await?)
+    }
+
+    /// Lock the encrypted `directory`
+    pub async fn lock_encrypted_directory(
+        &self,
+        device: &str,
+        directory: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .lock_encrypted_directory(device, directory)
+            .await?)
+    }
+
+    /// eCryptfs and gocryptfs vaults below /home
+    pub async fn list_legacy_vaults(&self) -> Result<Vec<LegacyVault>, ClientError> {
+        let json = self.proxy.list_legacy_vaults().await?;
+        let vaults: Vec<LegacyVault> = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse vaults: {}", e)))?;
+        Ok(vaults)
+    }
+
+    /// Mount the vault in `cipher_dir` read-only and return where
+    pub async fn mount_legacy_vault(
+        &self,
+        cipher_dir: &str,
+        passphrase: &str,
+    ) -> Result<String, ClientError> {
+        Ok(self
+            .proxy
+            .mount_legacy_vault(cipher_dir, passphrase)
+            .await?)
+    }
+
+    /// Unmount the vault in `cipher_dir`
+    pub async fn unmount_legacy_vault(&self, cipher_dir: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.unmount_legacy_vault(cipher_dir).await?)
+    }
+
+    /// Copy the files of the mounted vault in `cipher_dir` into the empty
+    /// encrypted directory `target`
+    pub async fn migrate_legacy_vault(
+        &self,
+        cipher_dir: &str,
+        target: &str,
+    ) -> Result<VaultMigrationResult, ClientError> {
+        let json = self.proxy.migrate_legacy_vault(cipher_dir, target).await?;
+        let result: VaultMigrationResult = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse vault migration result: {}", e))
+        })?;
+        Ok(result)
+    }
+
+    /// Run a global usage scan and return categorized usage with top files.
+    pub async fn get_usage_scan(
+        &self,
+        scan_id: &str,
+        top_files_per_category: u32,
+        mount_points: &[String],
+        show_all_files: bool,
+        parallelism_preset: UsageScanParallelismPreset,
+    ) -> Result<UsageScanResult, ClientError> {
+        let mount_points_json = serde_json::to_string(mount_points).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize mount points: {}", e))
+        })?;
+
+        let json = self
+            .proxy
+            .get_usage_scan(
+                scan_id,
+                top_files_per_category,
+                &mount_points_json,
+                show_all_files,
+                parallelism_preset.as_str(),
+            )
+            .await?;
+        let json = collect_paged_reply(&json).await?;
+        let result: UsageScanResult = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse usage scan result: {}", e))
+        })?;
+        Ok(result)
+    }
+
+    /// Delete selected usage files and return structured result.
+    pub async fn delete_usage_files(
+        &self,
+        paths: &[String],
+    ) -> Result<UsageDeleteResult, ClientError> {
+        let paths_json = serde_json::to_string(paths).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize usage delete request: {}", e))
+        })?;
+
+        let json = self.proxy.delete_usage_files(&paths_json).await?;
+        let result: UsageDeleteResult = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse usage delete result: {}", e))
+        })?;
+        Ok(result)
+    }
+
+    /// List local mount points available for usage scans.
+    pub async fn list_usage_mount_points(&self) -> Result<Vec<String>, ClientError> {
+        let json = self.proxy.list_usage_mount_points().await?;
+        serde_json::from_str::<Vec<String>>(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse mount points: {}", e)))
+    }
+
+    /// Request authorization for Show All Files session toggle.
+    pub async fn authorize_usage_show_all_files(&self) -> Result<bool, ClientError> {
+        Ok(self.proxy.authorize_usage_show_all_files().await?)
+    }
+
+    /// Get persistent mount options (fstab) for a device
+    pub async fn get_mount_options(
+        &self,
+        device: &str,
+    ) -> Result<Option<MountOptionsSettings>, ClientError> {
+        let json = self.proxy.get_mount_options(device).await?;
+        let opt: Option<MountOptionsSettings> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse mount options: {}", e))
+        })?;
+        Ok(opt)
+    }
+
+    /// Clear persistent mount options for a device
+    pub async fn default_mount_options(&self, device: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.default_mount_options(device).await?)
+    }
+
+    /// Set persistent mount options for a device
+    #[allow(clippy::too_many_arguments)]
+    pub async fn edit_mount_options(
+        &self,
+        device: &str,
+        mount_at_startup: bool,
+        show_in_ui: bool,
+        require_auth: bool,
+        display_name: Option<&str>,
+        icon_name: Option<&str>,
+        symbolic_icon_name: Option<&str>,
+        other_options: &str,
+        mount_point: &str,
+        identify_as: &str,
+        filesystem_type: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .edit_mount_options(
+                device,
+                mount_at_startup,
+                show_in_ui,
+                require_auth,
+                display_name.unwrap_or(""),
+                icon_name.unwrap_or(""),
+                symbolic_icon_name.unwrap_or(""),
+                other_options,
+                mount_point,
+                identify_as,
+                filesystem_type,
+            )
+            .await?)
+    }
+
+    /// Take ownership of a mounted filesystem
+    pub async fn take_ownership(&self, device: &str, recursive: bool) -> Result<(), ClientError> {
+        Ok(self.proxy.take_ownership(device, recursive).await?)
+    }
+
+    /// Get the underlying proxy for signal subscriptions
+    pub fn proxy(&self) -> &FilesystemsInterfaceProxy<'static> {
+        &self.proxy
+    }
+}
@@ -0,0 +1,523 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::ServiceClient;
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use crate::{PROGRESS_FDS, PROGRESS_RECORD_BYTES, ProgressRecord};
+use futures_util::StreamExt;
+use std::fs::File;
+use std::io::Read;
+use std::os::fd::OwnedFd;
+use std::os::unix::fs::FileExt;
+use storage_types::{
+    EtaRange, LiveIsoInfo, LiveUsbPlan, MigrationPlan, OperationPriority, PrioritySettings,
+    ThroughputHistory,
+};
+use tokio::io::unix::AsyncFd;
+use zbus::proxy;
+
+/// D-Bus proxy interface for disk imaging operations
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.Image",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/image"
+)]
+trait ImageInterface {
+    /// Backup entire drive to an image file (priority as JSON, or empty)
+    async fn backup_drive(
+        &self,
+        device: &str,
+        output_path: &str,
+        priority: &str,
+    ) -> zbus::Result<String>;
+
+    /// Backup a single partition to an image file (priority as JSON, or empty)
+    async fn backup_partition(
+        &self,
+        device: &str,
+        output_path: &str,
+        priority: &str,
+    ) -> zbus::Result<String>;
+
+    /// Restore entire drive from an image file (priority as JSON, or empty)
+    async fn restore_drive(
+        &self,
+        device: &str,
+        image_path: &str,
+        priority: &str,
+    ) -> zbus::Result<String>;
+
+    /// Restore a single partition from an image file (priority as JSON, or
+    /// empty)
+    async fn restore_partition(
+        &self,
+        device: &str,
+        image_path: &str,
+        priority: &str,
+    ) -> zbus::Result<String>;
+
+    /// Copy a partition onto another one at least as large (priority as
+    /// JSON, or empty)
+    async fn copy_partition(
+        &self,
+        source: &str,
+        target: &str,
+        priority: &str,
+    ) -> zbus::Result<String>;
+
+    /// Migrate a disk onto a larger one (plan as JSON)
+    async fn migrate_disk(&self, source: &str, target: &str, plan: &str) -> zbus::Result<String>;
+
+    /// Inspect an ISO image for making a live USB drive (info as JSON)
+    async fn probe_live_iso(&self, image_path: &str) -> zbus::Result<String>;
+
+    /// Make a bootable USB drive with extra partitions (plan as JSON)
+    async fn create_live_usb(
+        &self,
+        device: &str,
+        image_path: &str,
+        plan: &str,
+    ) -> zbus::Result<String>;
+
+    /// Burn an ISO image to the disc in an optical drive
+    async fn burn_disc(&self, device: &str, image_path: &str, verify: bool)
+    -> zbus::Result<String>;
+
+    /// Erase the rewritable disc in an optical drive
+    async fn blank_disc(&self, device: &str, full: bool) -> zbus::Result<String>;
+
+    /// Mount an image file as a loop device
+    async fn loop_setup(&self, image_path: &str) -> zbus::Result<String>;
+
+    /// Get the priorities of long-running operations (settings as JSON)
+    async fn get_operation_priorities(&self) -> zbus::Result<String>;
+
+    /// Set the priorities of long-running operations (settings as JSON)
+    async fn set_operation_priorities(&self, settings_json: &str) -> zbus::Result<()>;
+
+    /// Cancel a running operation
+    async fn cancel_operation(&self, operation_id: &str) -> zbus::Result<()>;
+
+    /// Get status of an operation
+    async fn get_operation_status(&self, operation_id: &str) -> zbus::Result<String>;
+
+    /// List all active operations
+    async fn list_active_operations(&self) -> zbus::Result<String>;
+
+    /// Get the recorded speed of past operations like a running one
+    async fn get_throughput_history(&self, operation_id: &str) -> zbus::Result<String>;
+
+    /// Get the shared progress counter of an operation
+    async fn get_progress_fds(
+        &self,
+        operation_id: &str,
+    ) -> zbus::Result<(zbus::zvariant::OwnedFd, zbus::zvariant::OwnedFd)>;
+
+    /// Signal emitted when an operation starts
+    #[zbus(signal)]
+    async fn operation_started(
+        &self,
+        operation_id: &str,
+        operation_type: &str,
+        source: &str,
+        destination: &str,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted periodically during operation with progress updates
+    #[zbus(signal)]
+    async fn operation_progress(
+        &self,
+        operation_id: &str,
+        bytes_completed: u64,
+        total_bytes: u64,
+        speed_bytes_per_sec: u64,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted when an operation completes
+    #[zbus(signal)]
+    async fn operation_completed(
+        &self,
+        operation_id: &str,
+        success: bool,
+        error_message: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Operation status information
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct OperationStatus {
+    pub bytes_completed: u64,
+    pub total_bytes: u64,
+    pub speed_bytes_per_sec: u64,
+    /// Remaining time estimated from past runs; not in progress counters,
+    /// see [`ImageClient::get_throughput_history`]
+    #[serde(default)]
+    pub eta: Option<EtaRange>,
+}
+
+/// The shared progress counter of a running operation
+///
+/// Reading it costs no D-Bus traffic, so progress can be redrawn every
+/// frame.
+pub struct ProgressCounter {
+    record: File,
+    event: AsyncFd<File>,
+}
+
+impl ProgressCounter {
+    /// Wait for the service to update the counter and read it
+    ///
+    /// Updates made since the last call are coalesced into one.
+    pub async fn changed(&self) -> Result<OperationStatus, ClientError> {
+        loop {
+            let mut guard = self.event.readable().await.map_err(counter_error)?;
+            let mut count = [0; 8];
+            if let Ok(read) = guard.try_io(|event| event.get_ref().read(&mut count)) {
+                read.map_err(counter_error)?;
+                break;
+            }
+        }
+        self.read()
+    }
+
+    /// The counter as it is now, read until two reads agree so that a
+    /// concurrent update isn't seen half written
+    pub fn read(&self) -> Result<OperationStatus, ClientError> {
+        let mut previous = [0; PROGRESS_RECORD_BYTES];
+        let mut bytes = [0; PROGRESS_RECORD_BYTES];
+        self.record
+            .read_exact_at(&mut previous, 0)
+            .map_err(counter_error)?;
+        for _ in 0..8 {
+            self.record
+                .read_exact_at(&mut bytes, 0)
+                .map_err(counter_error)?;
+            if bytes == previous {
+                break;
+            }
+            previous = bytes;
+        }
+        let record = ProgressRecord::from_bytes(&bytes);
+        Ok(OperationStatus {
+            bytes_completed: record.bytes_completed,
+            total_bytes: record.total_bytes,
+            speed_bytes_per_sec: record.speed_bytes_per_sec,
+            eta: None,
+        })
+    }
+}
+
+fn counter_error(e: std::io::Error) -> ClientError {
+    ClientError::OperationFailed(format!("Failed to read progress counter: {}", e))
+}
+
+/// A priority override as the service takes it; empty for the configured one
+fn priority_json(priority: Option<&OperationPriority>) -> Result<String, ClientError> {
+    Ok(match priority {
+        Some(priority) => serde_json::to_string(priority)?,
+        None => String::new(),
+    })
+}
+
+/// Client for disk imaging operations
+pub struct ImageClient {
+    proxy: ImageInterfaceProxy<'static>,
+}
+
+impl ImageClient {
+    /// Create a new image client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = ImageInterfaceProxy::new(conn)
+            .await
+            .map_err(|e| ClientError::Connection(format!("Failed to create image proxy: {}", e)))?;
+
+        Ok(Self { proxy })
+    }
+
+    /// Backup entire drive to an image file
+    ///
+    /// `priority` overrides the configured one for this backup.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (cached for session).
+    pub async fn backup_drive(
+        &self,
+        device: &str,
+        output_path: &str,
+        priority: Option<&OperationPriority>,
+    ) -> Result<String, ClientError> {
+        let priority = priority_json(priority)?;
+        Ok(self
+            .proxy
+            .backup_drive(device, output_path, &priority)
+            .await?)
+    }
+
+    /// Backup a single partition to an image file
+    ///
+    /// `priority` overrides the configured one for this backup.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (cached for session).
+    pub async fn backup_partition(
+        &self,
+        device: &str,
+        output_path: &str,
+        priority: Option<&OperationPriority>,
+    ) -> Result<String, ClientError> {
+        let priority = priority_json(priority)?;
+        Ok(self
+            .proxy
+            .backup_partition(device, output_path, &priority)
+            .await?)
+    }
+
+    /// Restore entire drive from an image file
+    ///
+    /// **WARNING: This will DESTROY ALL DATA on the target drive!**
+    ///
+    /// `priority` overrides the configured one for this restore.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (always prompts, never cached).
+    pub async fn restore_drive(
+        &self,
+        device: &str,
+        image_path: &str,
+        priority: Option<&OperationPriority>,
+    ) -> Result<String, ClientError> {
+        let priority = priority_json(priority)?;
+        Ok(self
+            .proxy
+            .restore_drive(device, image_path, &priority)
+            .await?)
+    }
+
+    /// Restore a single partition from an image file
+    ///
+    /// **WARNING: This will DESTROY ALL DATA on the target partition!**
+    ///
+    /// `priority` overrides the configured one for this restore.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (always prompts, never cached).
+    pub async fn restore_partition(
+        &self,
+        device: &str,
+        image_path: &str,
+        priority: Option<&OperationPriority>,
+    ) -> Result<String, ClientError> {
+        let priority = priority_json(priority)?;
+        Ok(self
+            .proxy
+            .restore_partition(device, image_path, &priority)
+            .await?)
+    }
+
+    /// Copy the partition `source` onto `target`, which must be at least as
+    /// large
+    ///
+    /// **WARNING: This will DESTROY ALL DATA on the target partition!**
+    ///
+    /// The copy gets a new filesystem UUID and grows to fill `target` where
+    /// its filesystem allows that. `priority` overrides the configured one for
+    /// this copy.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (always prompts, never cached).
+    pub async fn copy_partition(
+        &self,
+        source: &str,
+        target: &str,
+        priority: Option<&OperationPriority>,
+    ) -> Result<String, ClientError> {
+        let priority = priority_json(priority)?;
+        Ok(self.proxy.copy_partition(source, target, &priority).await?)
+    }
+
+    /// Migrate the disk `source` onto the larger `target`, laying its
+    /// partitions out as `plan` says and growing their filesystems
+    ///
+    /// **WARNING: This will DESTROY ALL DATA on the target disk!**
+    ///
+    /// When a step fails, the target is left blank; the source is only read.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (always prompts, never cached).
+    pub async fn migrate_disk(
+        &self,
+        source: &str,
+        target: &str,
+        plan: &MigrationPlan,
+    ) -> Result<String, ClientError> {
+        let json = serde_json::to_string(plan)?;
+        Ok(self.proxy.migrate_disk(source, target, &json).await?)
+    }
+
+    /// Size of an ISO image and how its live system keeps changes, to plan
+    /// a live USB drive
+    ///
+    /// Requires no authentication for active sessions.
+    pub async fn probe_live_iso(&self, image_path: &str) -> Result<LiveIsoInfo, ClientError> {
+        let json = self.proxy.probe_live_iso(image_path).await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse image info: {}", e)))
+    }
+
+    /// Write the hybrid ISO image at `image_path` to `device` and add the
+    /// persistence and data partitions of `plan` after it
+    ///
+    /// **WARNING: This will DESTROY ALL DATA on the drive!**
+    ///
+    /// When a step fails, the drive is left blank.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (always prompts, never cached).
+    pub async fn create_live_usb(
+        &self,
+        device: &str,
+        image_path: &str,
+        plan: &LiveUsbPlan,
+    ) -> Result<String, ClientError> {
+        let json = serde_json::to_string(plan)?;
+        Ok(self
+            .proxy
+            .create_live_usb(device, image_path, &json)
+            .await?)
+    }
+
+    /// Burn an ISO image to the disc in an optical drive, erasing a written
+    /// rewritable disc first
+    ///
+    /// With `verify`, the disc is read back and compared with the image.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (cached for session).
+    pub async fn burn_disc(
+        &self,
+        device: &str,
+        image_path: &str,
+        verify: bool,
+    ) -> Result<String, ClientError> {
+        Ok(self.proxy.burn_disc(device, image_path, verify).await?)
+    }
+
+    /// Erase the rewritable disc in an optical drive
+    ///
+    /// A full erase overwrites the whole disc instead of only its table of
+    /// contents.
+    ///
+    /// Returns an operation ID for tracking progress via signals.
+    ///
+    /// Requires administrator authentication (cached for session).
+    pub async fn blank_disc(&self, device: &str, full: bool) -> Result<String, ClientError> {
+        Ok(self.proxy.blank_disc(device, full).await?)
+    }
+
+    /// Mount an image file (ISO, IMG, etc.) as a loop device
+    ///
+    /// Returns the loop device name (e.g., "loop0").
+    ///
+    /// Requires no authentication for active sessions.
+    pub async fn loop_setup(&self, image_path: &str) -> Result<String, ClientError> {
+        Ok(self.proxy.loop_setup(image_path).await?)
+    }
+
+    /// Get the CPU and I/O priorities long-running operations run with
+    pub async fn get_operation_priorities(&self) -> Result<PrioritySettings, ClientError> {
+        let json = self.proxy.get_operation_priorities().await?;
+        let settings: PrioritySettings = serde_json::from_str(&json)?;
+        Ok(settings)
+    }
+
+    /// Set the CPU and I/O priorities long-running operations run with
+    pub async fn set_operation_priorities(
+        &self,
+        settings: &PrioritySettings,
+    ) -> Result<(), ClientError> {
+        let json = serde_json::to_string(settings)?;
+        self.proxy.set_operation_priorities(&json).await?;
+        Ok(())
+    }
+
+    /// Cancel a running backup or restore operation
+    pub async fn cancel_operation(&self, operation_id: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.cancel_operation(operation_id).await?)
+    }
+
+    /// Get the current status of an operation
+    ///
+    /// Returns detailed progress information including bytes completed, speed, etc.
+    pub async fn get_operation_status(
+        &self,
+        operation_id: &str,
+    ) -> Result<OperationStatus, ClientError> {
+        let json = self.proxy.get_operation_status(operation_id).await?;
+        let status: OperationStatus = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse operation status: {}", e))
+        })?;
+        Ok(status)
+    }
+
+    /// The recorded speed of past operations like the running
+    /// `operation_id`, for estimating its remaining time with
+    /// [`ThroughputHistory::estimate`]
+    pub async fn get_throughput_history(
+        &self,
+        operation_id: &str,
+    ) -> Result<ThroughputHistory, ClientError> {
+        let json = self.proxy.get_throughput_history(operation_id).await?;
+        let history: ThroughputHistory = serde_json::from_str(&json)?;
+        Ok(history)
+    }
+
+    /// The shared progress counter of an operation, or None when the
+    /// service doesn't offer counters and progress has to be polled with
+    /// [`Self::get_operation_status`]
+    pub async fn progress_counter(
+        &self,
+        operation_id: &str,
+    ) -> Result<Option<ProgressCounter>, ClientError> {
+        if !ServiceClient::new().await?.supports(PROGRESS_FDS).await {
+            return Ok(None);
+        }
+        let (record, event) = self.proxy.get_progress_fds(operation_id).await?;
+        let event = AsyncFd::new(File::from(OwnedFd::from(event))).map_err(counter_error)?;
+        Ok(Some(ProgressCounter {
+            record: File::from(OwnedFd::from(record)),
+            event,
+        }))
+    }
+
+    /// Wait for an operation to complete (success or failure).
+    /// Subscribes to the operation_completed signal and returns when the given operation_id is seen.
+    pub async fn wait_for_operation_completion(
+        &self,
+        operation_id: &str,
+    ) -> Result<(), ClientError> {
+        let mut stream = self.proxy.receive_operation_completed().await?;
+        while let Some(signal) = stream.next().await {
+            let args = signal
+                .args()
+                .map_err(|e| ClientError::Connection(e.to_string()))?;
+            if args.operation_id == operation_id {
+                return if args.success {
+                    Ok(())
+                } else {
+                    Err(ClientError::OperationFailed(args.error_message.to_string()))
+                };
+            }
+        }
+        Ok(())
+    }
+}
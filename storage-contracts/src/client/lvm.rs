@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use crate::client::service::collect_paged_reply;
+use storage_types::{LogicalVolumeInfo, PhysicalVolumeInfo, VolumeGroupInfo};
+use zbus::proxy;
+
+/// D-Bus proxy interface for LVM operations
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.LVM",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/lvm"
+)]
+pub trait LvmInterface {
+    /// List all volume groups
+    async fn list_volume_groups(&self) -> zbus::Result<String>;
+
+    /// List all logical volumes, as a paged reply
+    async fn list_logical_volumes(&self) -> zbus::Result<String>;
+
+    /// List all physical volumes
+    async fn list_physical_volumes(&self) -> zbus::Result<String>;
+
+    /// Create a volume group from devices (JSON array of paths)
+    async fn create_volume_group(&self, vg_name: &str, devices_json: &str) -> zbus::Result<()>;
+
+    /// Create a logical volume, returning its device path
+    async fn create_logical_volume(
+        &self,
+        vg_name: &str,
+        lv_name: &str,
+        size_bytes: u64,
+    ) -> zbus::Result<String>;
+
+    /// Resize a logical volume
+    async fn resize_logical_volume(&self, lv_path: &str, new_size_bytes: u64) -> zbus::Result<()>;
+
+    /// Delete a volume group
+    async fn delete_volume_group(&self, vg_name: &str) -> zbus::Result<()>;
+
+    /// Delete a logical volume
+    async fn delete_logical_volume(&self, lv_path: &str) -> zbus::Result<()>;
+
+    /// Remove a physical volume from a volume group
+    async fn remove_physical_volume(&self, vg_name: &str, pv_device: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a volume group is created
+    #[zbus(signal)]
+    async fn volume_group_created(&self, vg_name: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a volume group is removed
+    #[zbus(signal)]
+    async fn volume_group_removed(&self, vg_name: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a logical volume is created
+    #[zbus(signal)]
+    async fn logical_volume_created(&self, vg_name: &str, lv_name: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a logical volume is removed
+    #[zbus(signal)]
+    async fn logical_volume_removed(&self, vg_name: &str, lv_name: &str) -> zbus::Result<()>;
+}
+
+/// Client for LVM volume groups, logical and physical volumes
+pub struct LogicalClient {
+    proxy: LvmInterfaceProxy<'static>,
+}
+
+impl LogicalClient {
+    /// Create a new LVM client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = LvmInterfaceProxy::new(conn)
+            .await
+            .map_err(|e| ClientError::Connection(format!("Failed to create LVM proxy: {}", e)))?;
+
+        Ok(Self { proxy })
+    }
+
+    /// List all volume groups
+    pub async fn list_volume_groups(&self) -> Result<Vec<VolumeGroupInfo>, ClientError> {
+        let json = self.proxy.list_volume_groups().await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse volume groups: {}", e)))
+    }
+
+    /// List all logical volumes
+    pub async fn list_logical_volumes(&self) -> Result<Vec<LogicalVolumeInfo>, ClientError> {
+        let first_page = self.proxy.list_logical_volumes().await?;
+        let json = collect_paged_reply(&first_page).await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse logical volumes: {}", e)))
+    }
+
+    /// List all physical volumes
+    pub async fn list_physical_volumes(&self) -> Result<Vec<PhysicalVolumeInfo>, ClientError> {
+        let json = self.proxy.list_physical_volumes().await?;
+        serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse physical volumes: {}", e))
+        })
+    }
+
+    /// Create a volume group named `vg_name` from `devices`
+    pub async fn create_volume_group(
+        &self,
+        vg_name: &str,
+        devices: &[String],
+    ) -> Result<(), ClientError> {
+        let devices_json = serde_json::to_string(devices)?;
+        Ok(self
+            .proxy
+            .create_volume_group(vg_name, &devices_json)
+            .await?)
+    }
+
+    /// Create a logical volume, returning its device path
+    pub async fn create_logical_volume(
+        &self,
+        vg_name: &str,
+        lv_name: &str,
+        size_bytes: u64,
+    ) -> Result<String, ClientError> {
+        Ok(self
+            .proxy
+            .create_logical_volume(vg_name, lv_name, size_bytes)
+            .await?)
+    }
+
+    /// Resize a logical volume (e.g. "/dev/vg0/lv0" or "vg0/lv0")
+    pub async fn resize_logical_volume(
+        &self,
+        lv_path: &str,
+        new_size_bytes: u64,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .resize_logical_volume(lv_path, new_size_bytes)
+            .await?)
+    }
+
+    /// Delete a volume group
+    pub async fn delete_volume_group(&self, vg_name: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.delete_volume_group(vg_name).await?)
+    }
+
+    /// Delete a logical volume
+    pub async fn delete_logical_volume(&self, lv_path: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.delete_logical_volume(lv_path).await?)
+    }
+
+    /// Remove a physical volume from a volume group
+    pub async fn remove_physical_volume(
+        &self,
+        vg_name: &str,
+        pv_device: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .remove_physical_volume(vg_name, pv_device)
+            .await?)
+    }
+
+    /// Get the underlying proxy for signal subscriptions
+    pub fn proxy(&self) -> &LvmInterfaceProxy<'static> {
+        &self.proxy
+    }
+}
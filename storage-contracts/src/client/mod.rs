@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Async D-Bus client of storage-service
+//!
+//! Enabled with the `client` feature. Each client wraps the proxy of one
+//! service interface, converts its JSON replies into `storage_types`
+//! models and maps D-Bus errors to [`ClientError`]. All clients share one
+//! connection, to the system bus or a remote host's (see [`connection`]),
+//! and [`service_events`] merges the change signals for applets that only
+//! need to know when to refresh:
+//!
+//! ```no_run
+//! # async fn applet() -> Result<(), storage_contracts::client::ClientError> {
+//! use futures_util::StreamExt;
+//! use storage_contracts::client::{DisksClient, service_events};
+//!
+//! let disks = DisksClient::new().await?;
+//! let mut events = std::pin::pin!(service_events().await?);
+//! while let Some(event) = events.next().await {
+//!     if event.changes_devices() {
+//!         let volumes = disks.list_volumes().await?;
+//!         println!("{} volumes", volumes.len());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod btrfs;
+pub mod connection;
+pub mod disks;
+pub mod error;
+pub mod events;
+pub mod filesystems;
+pub mod image;
+pub mod luks;
+pub mod lvm;
+pub mod operations;
+pub mod partitions;
+pub mod raid;
+pub mod rclone;
+pub mod service;
+
+pub use btrfs::BtrfsClient;
+pub use connection::{
+    ConnectionTarget, SERVICE_NAME, connection_target, set_connection_target, shared_connection,
+};
+pub use disks::DisksClient;
+pub use error::ClientError;
+pub use events::{ServiceEvent, service_events};
+pub use filesystems::FilesystemsClient;
+pub use image::ImageClient;
+pub use luks::LuksClient;
+pub use lvm::LogicalClient;
+pub use operations::OperationsClient;
+pub use partitions::PartitionsClient;
+pub use raid::RaidClient;
+pub use rclone::RcloneClient;
+pub use service::ServiceClient;
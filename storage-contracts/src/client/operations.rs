@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use zbus::proxy;
+
+/// D-Bus proxy interface for running long operations
+// The signal types generated here take the names OperationProgress and
+// OperationFinished, so the protocol type is named by its path
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.Operations",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/operations"
+)]
+pub trait OperationsInterface {
+    /// List the running operations
+    async fn list_operations(&self) -> zbus::Result<String>;
+
+    /// Ask a running operation to stop
+    async fn cancel_operation(&self, operation_id: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when an operation starts or makes progress
+    #[zbus(signal)]
+    async fn operation_progress(&self, event_json: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when an operation completed, failed or was cancelled
+    #[zbus(signal)]
+    async fn operation_finished(&self, event_json: &str) -> zbus::Result<()>;
+}
+
+/// Client for the formatting, resizing, checking and image operations
+/// running in the service
+pub struct OperationsClient {
+    proxy: OperationsInterfaceProxy<'static>,
+}
+
+impl OperationsClient {
+    /// Create a new operations client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = OperationsInterfaceProxy::new(conn).await.map_err(|e| {
+            ClientError::Connection(format!("Failed to create operations proxy: {}", e))
+        })?;
+
+        Ok(Self { proxy })
+    }
+
+    /// List the running operations
+    pub async fn list_operations(&self) -> Result<Vec<crate::OperationProgress>, ClientError> {
+        let json = self.proxy.list_operations().await?;
+        let operations: Vec<crate::OperationProgress> = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse operations: {}", e)))?;
+        Ok(operations)
+    }
+
+    /// Ask a running operation to stop; it is reported as cancelled once it
+    /// stopped
+    pub async fn cancel_operation(
+        &self,
+        operation_id: crate::OperationId,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .cancel_operation(&operation_id.as_uuid().to_string())
+            .await?)
+    }
+
+    /// Get the underlying proxy for signal subscriptions
+    pub fn proxy(&self) -> &OperationsInterfaceProxy<'static> {
+        &self.proxy
+    }
+}
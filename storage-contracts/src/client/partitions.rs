@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use storage_types::{
+    CreatePartitionInfo, DeletedPartition, EspSyncPair, EspSyncResult, GptEntryEdit, GptTable,
+    LostPartition, MoveBounds, PartitionInfo, ResizeBounds,
+};
+use zbus::proxy;
+
+/// D-Bus proxy interface for partition management
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.Partitions",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/partitions"
+)]
+pub trait PartitionsInterface {
+    /// List all partitions on a disk
+    async fn list_partitions(&self, disk: &str) -> zbus::Result<String>;
+
+    /// Create a new partition table (destroys existing partitions)
+    async fn create_partition_table(&self, disk: &str, table_type: &str) -> zbus::Result<()>;
+
+    /// Create a new partition (low-level, no formatting)
+    async fn create_partition(
+        &self,
+        disk: &str,
+        offset: u64,
+        size: u64,
+        type_id: &str,
+    ) -> zbus::Result<String>;
+
+    /// Create a new partition with filesystem formatting (all-in-one)
+    async fn create_partition_with_filesystem(
+        &self,
+        disk: &str,
+        info_json: &str,
+    ) -> zbus::Result<String>;
+
+    /// Delete a partition
+    async fn delete_partition(&self, partition: &str) -> zbus::Result<()>;
+
+    /// Resize a partition
+    async fn resize_partition(&self, partition: &str, new_size: u64) -> zbus::Result<()>;
+
+    /// Sizes a partition can be resized to together with its filesystem
+    async fn get_resize_bounds(&self, partition: &str) -> zbus::Result<String>;
+
+    /// Resize a partition, shrinking or growing its filesystem with it
+    async fn resize_partition_with_filesystem(
+        &self,
+        partition: &str,
+        new_size: u64,
+    ) -> zbus::Result<()>;
+
+    /// Move a partition and its contents onto a 1 MiB boundary
+    async fn realign_partition(&self, partition: &str) -> zbus::Result<u64>;
+
+    /// Where a partition can move to on its disk
+    async fn get_move_bounds(&self, partition: &str) -> zbus::Result<String>;
+
+    /// Move a partition and its contents to another place on its disk
+    async fn move_partition(&self, partition: &str, new_start: u64) -> zbus::Result<()>;
+
+    /// Set partition type (GPT GUID or MBR code)
+    async fn set_partition_type(&self, partition: &str, type_id: &str) -> zbus::Result<()>;
+
+    /// Set partition flags
+    async fn set_partition_flags(&self, partition: &str, flags: u64) -> zbus::Result<()>;
+
+    /// Set partition name (GPT only)
+    async fn set_partition_name(&self, partition: &str, name: &str) -> zbus::Result<()>;
+
+    /// Read the GPT of a disk directly, with the state of its checksums
+    async fn get_gpt_table(&self, disk: &str) -> zbus::Result<String>;
+
+    /// Edit several GPT entries at once, written together
+    async fn edit_gpt_entries(&self, disk: &str, edits_json: &str) -> zbus::Result<()>;
+
+    /// GPT partitions deleted from a disk this session that can be restored
+    async fn list_deleted_partitions(&self, disk: &str) -> zbus::Result<String>;
+
+    /// Put a partition deleted this session back into its table
+    async fn restore_deleted_partition(&self, id: u64) -> zbus::Result<()>;
+
+    /// Search the free space of a disk for deleted partitions
+    async fn scan_lost_partitions(&self, disk: &str) -> zbus::Result<String>;
+
+    /// Recreate a deleted partition without touching its contents
+    async fn recreate_lost_partition(&self, disk: &str, partition_json: &str) -> zbus::Result<()>;
+
+    /// Make a secondary EFI System Partition a copy of the primary one
+    async fn sync_esp(&self, primary: &str, secondary: &str) -> zbus::Result<String>;
+
+    /// Get the ESP pairs that are synced on a schedule
+    async fn get_esp_sync_pairs(&self) -> zbus::Result<String>;
+
+    /// Sync a secondary ESP on a schedule, or stop doing so
+    async fn set_esp_sync_pair(&self, pair_json: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a partition table is created
+    #[zbus(signal)]
+    async fn partition_table_created(&self, disk: &str, table_type: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a partition is created
+    #[zbus(signal)]
+    async fn partition_created(&self, disk: &str, partition: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a partition is deleted
+    #[zbus(signal)]
+    async fn partition_deleted(&self, disk: &str, partition: &str) -> zbus::Result<()>;
+
+    /// Signal emitted when a partition is modified
+    #[zbus(signal)]
+    async fn partition_modified(&self, partition: &str) -> zbus::Result<()>;
+}
+
+/// Client for partition management operations
+pub struct PartitionsClient {
+    proxy: PartitionsInterfaceProxy<'static>,
+}
+
+impl std::fmt::Debug for PartitionsClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PartitionsClient").finish_non_exhaustive()
+    }
+}
+
+impl PartitionsClient {
+    /// Create a new partitions client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = PartitionsInterfaceProxy::new(conn).await.map_err(|e| {
+            ClientError::Connection(format!("Failed to create partitions proxy: {}", e))
+        })?;
+
+        Ok(Self { proxy })
+    }
+
+    /// List all partitions on a disk
+    pub async fn list_partitions(&self, disk: &str) -> Result<Vec<PartitionInfo>, ClientError> {
+        let json = self.proxy.list_partitions(disk).await?;
+        let partitions: Vec<PartitionInfo> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse partition list: {}", e))
+        })?;
+        Ok(partitions)
+    }
+
+    /// Create a new partition table (gpt or dos/mbr)
+    pub async fn create_partition_table(
+        &self,
+        disk: &str,
+        table_type: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self.proxy.create_partition_table(disk, table_type).await?)
+    }
+
+    /// Create a new partition with filesystem formatting (all-in-one)
+    /// This handles partition creation, optional LUKS encryption, and filesystem formatting.
+    pub async fn create_partition_with_filesystem(
+        &self,
+        disk: &str,
+        info: &CreatePartitionInfo,
+    ) -> Result<String, ClientError> {
+        let info_json = serde_json::to_string(info).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize partition info: {}", e))
+        })?;
+        Ok(self
+            .proxy
+            .create_partition_with_filesystem(disk, &info_json)
+            .await?)
+    }
+
+    /// Delete a partition
+    pub async fn delete_partition(&self, partition: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.delete_partition(partition).await?)
+    }
+
+    /// Resize a partition
+    pub async fn resize_partition(
+        &self,
+        partition: &str,
+        new_size: u64,
+    ) -> Result<(), ClientError> {
+        Ok(self.proxy.resize_partition(partition, new_size).await?)
+    }
+
+    /// Sizes a partition can be resized to, from what its filesystem can
+    /// shrink to up to the next partition
+    pub async fn resize_bounds(&self, partition: &str) -> Result<ResizeBounds, ClientError> {
+        let json = self.proxy.get_resize_bounds(partition).await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse resize bounds: {}", e)))
+    }
+
+    /// Resize a partition together with its filesystem: the filesystem
+    /// shrinks first, or grows to fill the partition afterwards
+    pub async fn resize_partition_with_filesystem(
+        &self,
+        partition: &str,
+        new_size: u64,
+    ) -> Result<(), ClientError> {
+        Ok(self
+            .proxy
+            .resize_partition_with_filesystem(partition, new_size)
+            .await?)
+    }
+
+    /// Move a partition and its contents to start on a 1 MiB boundary,
+    /// returning its new start in bytes
+    pub async fn realign_partition(&self, partition: &str) -> Result<u64, ClientError> {
+        Ok(self.proxy.realign_partition(partition).await?)
+    }
+
+    /// Where a partition can move to, within the free space on either side
+    /// of it
+    pub async fn move_bounds(&self, partition: &str) -> Result<MoveBounds, ClientError> {
+        let json = self.proxy.get_move_bounds(partition).await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse move bounds: {}", e)))
+    }
+
+    /// Move a partition and its contents to start at byte `new_start`;
+    /// the service reports the progress as a running operation
+    pub async fn move_partition(&self, partition: &str, new_start: u64) -> Result<(), ClientError> {
+        Ok(self.proxy.move_partition(partition, new_start).await?)
+    }
+
+    /// Set partition type (GPT GUID or MBR hex code)
+    pub async fn set_partition_type(
+        &self,
+        partition: &str,
+        type_id: &str,
+    ) -> Result<(), ClientError> {
+        Ok(self.proxy.set_partition_type(partition, type_id).await?)
+    }
+
+    /// Set partition flags (e.g., bootable)
+    pub async fn set_partition_flags(
+        &self,
+        partition: &str,
+        flags: u64,
+    ) -> Result<(), ClientError> {
+        Ok(self.proxy.set_partition_flags(partition, flags).await?)
+    }
+
+    /// Set partition name (GPT only)
+    pub async fn set_partition_name(&self, partition: &str, name: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.set_partition_name(partition, name).await?)
+    }
+
+    /// The GPT of a disk as read by the service, with whether both copies
+    /// match their checksums
+    pub async fn gpt_table(&self, disk: &str) -> Result<GptTable, ClientError> {
+        let json = self.proxy.get_gpt_table(disk).await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse GPT: {}", e)))
+    }
+
+    /// Apply `edits` to the GPT of a disk; the service refuses the whole
+    /// batch if the table it would produce has overlapping or out of range
+    /// entries
+    pub async fn edit_gpt_entries(
+        &self,
+        disk: &str,
+        edits: &[GptEntryEdit],
+    ) -> Result<(), ClientError> {
+        let edits_json = serde_json::to_string(edits).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize GPT edits: {}", e))
+        })?;
+        Ok(self.proxy.edit_gpt_entries(disk, &edits_json).await?)
+    }
+
+    /// GPT partitions deleted from a disk this session whose space is still
+    /// free, most recent first
+    pub async fn deleted_partitions(
+        &self,
+        disk: &str,
+    ) -> Result<Vec<DeletedPartition>, ClientError> {
+        let json = self.proxy.list_deleted_partitions(disk).await?;
+        serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse deleted partitions: {}", e))
+        })
+    }
+
+    /// Put the partition deleted as `id` back, exactly as it was
+    pub async fn restore_deleted_partition(&self, id: u64) -> Result<(), ClientError> {
+        Ok(self.proxy.restore_deleted_partition(id).await?)
+    }
+
+    /// Search the free space of a disk for deleted partitions, with how
+    /// likely each is worth restoring
+    pub async fn scan_lost_partitions(
+        &self,
+        disk: &str,
+    ) -> Result<Vec<LostPartition>, ClientError> {
+        let json = self.proxy.scan_lost_partitions(disk).await?;
+        let found: Vec<LostPartition> = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse lost partitions: {}", e))
+        })?;
+        Ok(found)
+    }
+
+    /// Recreate a partition found by `scan_lost_partitions`
+    pub async fn recreate_lost_partition(
+        &self,
+        disk: &str,
+        partition: &LostPartition,
+    ) -> Result<(), ClientError> {
+        let partition_json = serde_json::to_string(partition).map_err(|e| {
+            ClientError::ParseError(format!("Failed to serialize partition: {}", e))
+        })?;
+        Ok(self
+            .proxy
+            .recreate_lost_partition(disk, &partition_json)
+            .await?)
+    }
+
+    /// Make the EFI System Partition with partition UUID `secondary` a copy
+    /// of the one with partition UUID `primary`
+    pub async fn sync_esp(
+        &self,
+        primary: &str,
+        secondary: &str,
+    ) -> Result<EspSyncResult, ClientError> {
+        let json = self.proxy.sync_esp(primary, secondary).await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse ESP sync result: {}", e)))
+    }
+
+    /// ESP pairs that are synced on a schedule
+    pub async fn esp_sync_pairs(&self) -> Result<Vec<EspSyncPair>, ClientError> {
+        let json = self.proxy.get_esp_sync_pairs().await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse ESP sync pairs: {}", e)))
+    }
+
+    /// Sync `pair` on its schedule; a pair without an interval stops being
+    /// synced
+    pub async fn set_esp_sync_pair(&self, pair: &EspSyncPair) -> Result<(), ClientError> {
+        let pair_json = serde_json::to_string(pair)
+            .map_err(|e| ClientError::ParseError(format!("Failed to serialize ESP pair: {}", e)))?;
+        Ok(self.proxy.set_esp_sync_pair(&pair_json).await?)
+    }
+}
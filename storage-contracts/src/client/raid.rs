@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use storage_types::RaidDetail;
+use zbus::proxy;
+
+/// D-Bus proxy interface for MD-RAID operations
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service.Raid",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service/raid"
+)]
+pub trait RaidInterface {
+    /// Get sync progress, mismatch count, bitmap and member states of an array
+    async fn get_raid_detail(&self, array: &str) -> zbus::Result<String>;
+
+    /// Signal emitted when an array degrades, loses a member or recovers
+    #[zbus(signal)]
+    async fn array_health_changed(
+        &self,
+        array: &str,
+        event: &str,
+        detail_json: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Client for MD-RAID operations
+pub struct RaidClient {
+    proxy: RaidInterfaceProxy<'static>,
+}
+
+impl RaidClient {
+    /// Create a new RAID client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = RaidInterfaceProxy::new(conn)
+            .await
+            .map_err(|e| ClientError::Connection(format!("Failed to create RAID proxy: {}", e)))?;
+
+        Ok(Self { proxy })
+    }
+
+    /// Get sync progress, mismatch count, bitmap and member states of an array
+    pub async fn get_raid_detail(&self, array: &str) -> Result<RaidDetail, ClientError> {
+        let json = self.proxy.get_raid_detail(array).await?;
+        let detail: RaidDetail = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse RAID detail: {}", e)))?;
+        Ok(detail)
+    }
+
+    /// Get the underlying proxy for signal subscriptions
+    pub fn proxy(&self) -> &RaidInterfaceProxy<'static> {
+        &self.proxy
+    }
+}
@@ -2,7 +2,10 @@
 
 use crate::client::connection::shared_connection;
 use crate::client::error::ClientError;
-use storage_types::rclone::{MountStatusResult, RemoteConfig, RemoteConfigList, TestResult};
+use storage_types::rclone::{
+    LoginMountStatus, MountStatusResult, RemoteConfig, RemoteConfigList, RemoteUsage, TestResult,
+    TransferJob, TransferRequest,
+};
 use zbus::proxy;
 
 /// D-Bus proxy interface for RClone operations
@@ -30,12 +33,23 @@ trait RcloneInterface {
     /// Get current mount status for a remote
     async fn get_mount_status(&self, name: &str, scope: &str) -> zbus::Result<String>;
 
+    /// Get quota and usage of a remote
+    async fn get_remote_usage(
+        &self,
+        name: &str,
+        scope: &str,
+        refresh: bool,
+    ) -> zbus::Result<String>;
+
     /// Check if a remote is set to mount on boot
     async fn get_mount_on_boot(&self, name: &str, scope: &str) -> zbus::Result<bool>;
 
     /// Enable or disable mount on boot
     async fn set_mount_on_boot(&self, name: &str, scope: &str, enabled: bool) -> zbus::Result<()>;
 
+    /// List the automatic mounts of all remotes and their state
+    async fn list_login_mounts(&self) -> zbus::Result<String>;
+
     /// Create a new remote configuration
     async fn create_remote(&self, config: &str, scope: &str) -> zbus::Result<()>;
 
@@ -45,6 +59,27 @@ trait RcloneInterface {
     /// Delete a remote configuration
     async fn delete_remote(&self, name: &str, scope: &str) -> zbus::Result<()>;
 
+    /// Unlock an encrypted configuration for this session
+    async fn unlock_config(&self, scope: &str, password: &str) -> zbus::Result<()>;
+
+    /// Forget the password of an encrypted configuration
+    async fn lock_config(&self, scope: &str) -> zbus::Result<()>;
+
+    /// Encrypt a configuration with a password
+    async fn encrypt_config(&self, scope: &str, password: &str) -> zbus::Result<()>;
+
+    /// Start copying or syncing between a local path and a remote
+    async fn start_transfer(&self, request: &str) -> zbus::Result<String>;
+
+    /// Cancel a running transfer
+    async fn cancel_transfer(&self, id: &str) -> zbus::Result<()>;
+
+    /// List running and finished transfers
+    async fn list_transfers(&self) -> zbus::Result<String>;
+
+    /// Forget finished transfers
+    async fn clear_transfer_history(&self) -> zbus::Result<()>;
+
     /// List of supported remote types
     async fn supported_remote_types(&self) -> zbus::Result<Vec<String>>;
 }
@@ -107,6 +142,20 @@ impl RcloneClient {
         Ok(status)
     }
 
+    /// Get quota and usage of a remote, `None` if it cannot report them
+    ///
+    /// The service caches usage for a while unless `refresh` is set.
+    pub async fn get_remote_usage(
+        &self,
+        name: &str,
+        scope: &str,
+        refresh: bool,
+    ) -> Result<Option<RemoteUsage>, ClientError> {
+        let json = self.proxy.get_remote_usage(name, scope, refresh).await?;
+        let usage: Option<RemoteUsage> = serde_json::from_str(&json)?;
+        Ok(usage)
+    }
+
     /// Check if a remote is set to mount on boot
     pub async fn get_mount_on_boot(&self, name: &str, scope: &str) -> Result<bool, ClientError> {
         Ok(self.proxy.get_mount_on_boot(name, scope).await?)
@@ -122,6 +171,13 @@ impl RcloneClient {
         Ok(self.proxy.set_mount_on_boot(name, scope, enabled).await?)
     }
 
+    /// List the automatic mounts of all remotes and their state
+    pub async fn list_login_mounts(&self) -> Result<Vec<LoginMountStatus>, ClientError> {
+        let json = self.proxy.list_login_mounts().await?;
+        let statuses: Vec<LoginMountStatus> = serde_json::from_str(&json)?;
+        Ok(statuses)
+    }
+
     /// Create a new remote configuration
     pub async fn create_remote(&self, config: &RemoteConfig) -> Result<(), ClientError> {
         let json = serde_json::to_string(config)?;
@@ -144,4 +200,43 @@ impl RcloneClient {
     pub async fn delete_remote(&self, name: &str, scope: &str) -> Result<(), ClientError> {
         Ok(self.proxy.delete_remote(name, scope).await?)
     }
+
+    /// Unlock an encrypted configuration for this session
+    pub async fn unlock_config(&self, scope: &str, password: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.unlock_config(scope, password).await?)
+    }
+
+    /// Forget the password of an encrypted configuration
+    pub async fn lock_config(&self, scope: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.lock_config(scope).await?)
+    }
+
+    /// Encrypt a configuration with a password
+    pub async fn encrypt_config(&self, scope: &str, password: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.encrypt_config(scope, password).await?)
+    }
+
+    /// Start copying or syncing between a local path and a remote, returning
+    /// the ID of the transfer
+    pub async fn start_transfer(&self, request: &TransferRequest) -> Result<String, ClientError> {
+        let json = serde_json::to_string(request)?;
+        Ok(self.proxy.start_transfer(&json).await?)
+    }
+
+    /// Cancel a running transfer
+    pub async fn cancel_transfer(&self, id: &str) -> Result<(), ClientError> {
+        Ok(self.proxy.cancel_transfer(id).await?)
+    }
+
+    /// List running and finished transfers, newest first
+    pub async fn list_transfers(&self) -> Result<Vec<TransferJob>, ClientError> {
+        let json = self.proxy.list_transfers().await?;
+        let jobs: Vec<TransferJob> = serde_json::from_str(&json)?;
+        Ok(jobs)
+    }
+
+    /// Forget finished transfers
+    pub async fn clear_transfer_history(&self) -> Result<(), ClientError> {
+        Ok(self.proxy.clear_transfer_history().await?)
+    }
 }
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::client::connection::shared_connection;
+use crate::client::error::ClientError;
+use crate::{FD_REPLIES, ResultPage, collect_pages};
+use std::os::fd::OwnedFd;
+use storage_types::{
+    AllowedActions, DeferredJob, LogEntry, PowerSettings, ScheduledJob, UsageStatistics,
+};
+use zbus::proxy;
+
+/// D-Bus proxy interface for the main storage service object
+#[proxy(
+    interface = "org.cosmic.ext.Storage.Service",
+    default_service = "org.cosmic.ext.Storage.Service",
+    default_path = "/org/cosmic/ext/Storage/Service"
+)]
+pub trait ServiceInterface {
+    /// Get the service's log lines of an operation
+    async fn get_operation_log(
+        &self,
+        operation: &str,
+        device: &str,
+        since: u64,
+    ) -> zbus::Result<String>;
+
+    /// Features of the running service
+    #[zbus(property)]
+    fn supported_features(&self) -> zbus::Result<Vec<String>>;
+
+    /// Get a further page of a paged reply
+    async fn get_page(&self, handle: &str, index: u32) -> zbus::Result<String>;
+
+    /// Get all further pages of a paged reply as a sealed memory file
+    async fn get_rest_fd(&self, handle: &str) -> zbus::Result<zbus::zvariant::OwnedFd>;
+
+    /// Get the caller's local usage statistics
+    async fn get_usage_statistics(&self) -> zbus::Result<String>;
+
+    /// Turn recording of the caller's operations on or off
+    async fn set_usage_statistics(&self, enabled: bool) -> zbus::Result<()>;
+
+    /// Remove the caller's usage statistics records
+    async fn clear_usage_statistics(&self) -> zbus::Result<()>;
+
+    /// Get what the caller's restriction profiles let them do
+    async fn get_allowed_actions(&self) -> zbus::Result<String>;
+
+    /// Get when scheduled jobs wait for mains power
+    async fn get_power_policies(&self) -> zbus::Result<String>;
+
+    /// Set when scheduled jobs wait for mains power
+    async fn set_power_policies(&self, settings_json: &str) -> zbus::Result<()>;
+
+    /// Get the scheduled jobs waiting for mains power
+    async fn get_deferred_jobs(&self) -> zbus::Result<String>;
+
+    /// Run a deferred job now, whatever the power supply
+    async fn run_deferred_job(&self, job: &str, target: &str) -> zbus::Result<()>;
+}
+
+/// Client for the main storage service object
+pub struct ServiceClient {
+    proxy: ServiceInterfaceProxy<'static>,
+}
+
+impl ServiceClient {
+    /// Create a new service client connected to the storage service
+    pub async fn new() -> Result<Self, ClientError> {
+        let conn = shared_connection().await?;
+
+        let proxy = ServiceInterfaceProxy::new(conn).await.map_err(|e| {
+            ClientError::Connection(format!("Failed to create service proxy: {}", e))
+        })?;
+
+        Ok(Self { proxy })
+    }
+
+    /// Get the service's log lines since `since` (Unix time) of the
+    /// `operation` (a method name such as "mount"), mentioning `device`;
+    /// empty texts match all lines
+    pub async fn get_operation_log(
+        &self,
+        operation: &str,
+        device: &str,
+        since: u64,
+    ) -> Result<Vec<LogEntry>, ClientError> {
+        let json = self
+            .proxy
+            .get_operation_log(operation, device, since)
+            .await?;
+        let entries: Vec<LogEntry> = serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse service log: {}", e)))?;
+        Ok(entries)
+    }
+
+    /// Page `index` of the paged reply `handle`
+    pub async fn get_page(&self, handle: &str, index: u32) -> Result<ResultPage, ClientError> {
+        let json = self.proxy.get_page(handle, index).await?;
+        serde_json::from_str(&json)
+            .map_err(|e| ClientError::ParseError(format!("Failed to parse result page: {}", e)))
+    }
+
+    /// The pages of the paged reply `handle` after the first, joined, read
+    /// from the memory file the service hands out
+    pub async fn get_rest(&self, handle: &str) -> Result<String, ClientError> {
+        let fd = OwnedFd::from(self.proxy.get_rest_fd(handle).await?);
+        let read = tokio::task::spawn_blocking(move || {
+            use std::io::{Read, Seek, SeekFrom};
+            let mut file = std::fs::File::from(fd);
+            let mut text = String::new();
+            file.seek(SeekFrom::Start(0))?;
+            file.read_to_string(&mut text)?;
+            Ok::<_, std::io::Error>(text)
+        })
+        .await
+        .map_err(|e| ClientError::OperationFailed(format!("Failed to read reply: {}", e)))?;
+        read.map_err(|e| ClientError::OperationFailed(format!("Failed to read reply: {}", e)))
+    }
+
+    /// Whether the service offers `feature`, false for services that
+    /// predate it
+    pub async fn supports(&self, feature: &str) -> bool {
+        self.proxy
+            .supported_features()
+            .await
+            .is_ok_and(|features| features.iter().any(|f| f == feature))
+    }
+
+    /// Get the user's local usage statistics
+    pub async fn get_usage_statistics(&self) -> Result<UsageStatistics, ClientError> {
+        let json = self.proxy.get_usage_statistics().await?;
+        let stats: UsageStatistics = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse usage statistics: {}", e))
+        })?;
+        Ok(stats)
+    }
+
+    /// Turn recording of the user's operations on or off
+    pub async fn set_usage_statistics(&self, enabled: bool) -> Result<(), ClientError> {
+        Ok(self.proxy.set_usage_statistics(enabled).await?)
+    }
+
+    /// Remove the user's usage statistics records
+    pub async fn clear_usage_statistics(&self) -> Result<(), ClientError> {
+        Ok(self.proxy.clear_usage_statistics().await?)
+    }
+
+    /// Get what the user's restriction profiles let them do
+    pub async fn allowed_actions(&self) -> Result<AllowedActions, ClientError> {
+        let json = self.proxy.get_allowed_actions().await?;
+        let allowed: AllowedActions = serde_json::from_str(&json).map_err(|e| {
+            ClientError::ParseError(format!("Failed to parse allowed actions: {}", e))
+        })?;
+        Ok(allowed)
+    }
+
+    /// Get when scheduled jobs wait for mains power
+    pub async fn get_power_policies(&self) -> Result<PowerSettings, ClientError> {
+        let json = self.proxy.get_power_policies().await?;
+        let settings: PowerSettings = serde_json::from_str(&json)?;
+        Ok(settings)
+    }
+
+    /// Set when scheduled jobs wait for mains power
+    pub async fn set_power_policies(&self, settings: &PowerSettings) -> Result<(), ClientError> {
+        let json = serde_json::to_string(settings)?;
+        self.proxy.set_power_policies(&json).await?;
+        Ok(())
+    }
+
+    /// Get the scheduled jobs waiting for mains power
+    pub async fn get_deferred_jobs(&self) -> Result<Vec<DeferredJob>, ClientError> {
+        let json = self.proxy.get_deferred_jobs().await?;
+        let jobs: Vec<DeferredJob> = serde_json::from_str(&json)?;
+        Ok(jobs)
+    }
+
+    /// Run the deferred `job` on `target` now, whatever the power supply
+    pub async fn run_deferred_job(
+        &self,
+        job: ScheduledJob,
+        target: &str,
+    ) -> Result<(), ClientError> {
+        let job = serde_json::to_string(&job)?;
+        self.proxy.run_deferred_job(&job, target).await?;
+        Ok(())
+    }
+}
+
+/// The JSON of a paged reply, given its first page, fetching any further
+/// pages over the same connection: in one memory file when the service
+/// offers it, page by page otherwise
+pub async fn collect_paged_reply(first_page_json: &str) -> Result<String, ClientError> {
+    let first: ResultPage = serde_json::from_str(first_page_json)
+        .map_err(|e| ClientError::ParseError(format!("Failed to parse result page: {}", e)))?;
+    if first.is_last() {
+        return Ok(first.data);
+    }
+    let client = ServiceClient::new().await?;
+    if client.supports(FD_REPLIES).await {
+        let rest = client.get_rest(&first.handle).await?;
+        return Ok(first.data + &rest);
+    }
+    let client = &client;
+    collect_pages(first, |handle, index| async move {
+        client.get_page(&handle, index).await
+    })
+    .await
+}
@@ -1,10 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+#[cfg(feature = "client")]
+pub mod client;
+pub mod mock;
 pub mod protocol;
 pub mod traits;
 
+pub use mock::MockDiskBackend;
 pub use protocol::{
-    OperationEvent, OperationId, OperationKind, OperationProgress, StorageError, StorageErrorKind,
+    FD_REPLIES, OperationEvent, OperationId, OperationKind, OperationProgress, PROGRESS_FDS,
+    PROGRESS_RECORD_BYTES, PageStore, ProgressRecord, ResultPage, StorageError, StorageErrorKind,
+    collect_pages,
 };
 pub use traits::{
     DiskDiscovery, DiskOpsAdapter, DiskQueryAdapter, FilesystemDiscovery, FilesystemOpsAdapter,
@@ -0,0 +1,428 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! In-memory partitioning backend
+//!
+//! [`MockDiskBackend`] keeps disks and partition tables in memory and
+//! places partitions with the rules the real backends follow
+//! ([`storage_types::place_partition`]), so callers of
+//! [`PartitionOpsAdapter`] can be tested without block devices or root.
+
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use storage_types::{
+    ByteRange, CreatePartitionInfo, DiskInfo, PartitionInfo, place_partition, usable_range,
+};
+
+use crate::{PartitionOpsAdapter, StorageError, StorageErrorKind};
+
+/// A disk and its partitions, by partition number
+#[derive(Debug, Clone)]
+struct MockDisk {
+    info: DiskInfo,
+    partitions: Vec<PartitionInfo>,
+}
+
+/// Partitioning backend over disks that exist only in memory
+#[derive(Debug, Default)]
+pub struct MockDiskBackend {
+    disks: Mutex<Vec<MockDisk>>,
+}
+
+impl MockDiskBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an empty disk of `size` bytes, partitioned with `table_type`
+    /// ("gpt", "dos" or none)
+    ///
+    /// `gpt_usable_range` stands in for the range read from the GPT
+    /// header; without it the 1 MiB fallback applies.
+    pub fn add_disk(
+        &self,
+        device: &str,
+        size: u64,
+        table_type: Option<&str>,
+        gpt_usable_range: Option<ByteRange>,
+    ) {
+        let info = DiskInfo {
+            device: device.to_string(),
+            id: device.trim_start_matches("/dev/").to_string(),
+            model: "Mock Disk".to_string(),
+            serial: String::new(),
+            vendor: String::new(),
+            revision: String::new(),
+            size,
+            connection_bus: String::new(),
+            rotation_rate: None,
+            logical_sector_size: 512,
+            physical_sector_size: 512,
+            removable: false,
+            ejectable: false,
+            media_removable: false,
+            media_available: true,
+            optical: false,
+            optical_blank: false,
+            read_only: false,
+            can_power_off: false,
+            virtual_disk: false,
+            discard_supported: false,
+            rotational: false,
+            is_loop: false,
+            backing_file: None,
+            partition_table_type: table_type.map(str::to_string),
+            gpt_usable_range,
+        };
+        self.lock().push(MockDisk {
+            info,
+            partitions: Vec::new(),
+        });
+    }
+
+    /// Partitions of `device`, ordered by number
+    pub fn partitions(&self, device: &str) -> Vec<PartitionInfo> {
+        self.lock()
+            .iter()
+            .find(|disk| disk.info.device == device)
+            .map(|disk| disk.partitions.clone())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<MockDisk>> {
+        self.disks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `edit` on the partition `partition_path`
+    fn edit_partition(
+        &self,
+        partition_path: &str,
+        edit: impl FnOnce(&MockDisk, &mut PartitionInfo) -> Result<(), StorageError>,
+    ) -> Result<(), StorageError> {
+        let mut disks = self.lock();
+        for disk in disks.iter_mut() {
+            if let Some(index) = disk
+                .partitions
+                .iter()
+                .position(|p| p.device == partition_path)
+            {
+                let mut partition = disk.partitions[index].clone();
+                edit(disk, &mut partition)?;
+                disk.partitions[index] = partition;
+                return Ok(());
+            }
+        }
+        Err(not_found(partition_path))
+    }
+}
+
+fn not_found(device: &str) -> StorageError {
+    StorageError::new(StorageErrorKind::NotFound, device)
+}
+
+/// Device node of partition `number`, e.g. "/dev/sda2" or "/dev/nvme0n1p2"
+fn partition_device(disk: &str, number: u32) -> String {
+    if disk.ends_with(|c: char| c.is_ascii_digit()) {
+        format!("{disk}p{number}")
+    } else {
+        format!("{disk}{number}")
+    }
+}
+
+#[async_trait]
+impl PartitionOpsAdapter for MockDiskBackend {
+    async fn list_disks_with_partitions(
+        &self,
+    ) -> Result<Vec<(DiskInfo, Vec<PartitionInfo>)>, StorageError> {
+        Ok(self
+            .lock()
+            .iter()
+            .map(|disk| (disk.info.clone(), disk.partitions.clone()))
+            .collect())
+    }
+
+    async fn resolve_block_path_for_device(&self, device: &str) -> Result<String, StorageError> {
+        let disks = self.lock();
+        let known = disks.iter().any(|disk| {
+            disk.info.device == device || disk.partitions.iter().any(|p| p.device == device)
+        });
+        if known {
+            Ok(device.to_string())
+        } else {
+            Err(not_found(device))
+        }
+    }
+
+    async fn create_partition_table(
+        &self,
+        block_path: &str,
+        table_type: &str,
+    ) -> Result<(), StorageError> {
+        if !matches!(table_type, "gpt" | "dos") {
+            return Err(StorageError::new(
+                StorageErrorKind::InvalidInput,
+                format!("Unknown partition table type {table_type}"),
+            ));
+        }
+        let mut disks = self.lock();
+        let disk = disks
+            .iter_mut()
+            .find(|disk| disk.info.device == block_path)
+            .ok_or_else(|| not_found(block_path))?;
+        disk.info.partition_table_type = Some(table_type.to_string());
+        disk.info.gpt_usable_range = None;
+        disk.partitions.clear();
+        Ok(())
+    }
+
+    async fn create_partition(
+        &self,
+        block_path: &str,
+        offset: u64,
+        size: u64,
+        type_id: &str,
+    ) -> Result<String, StorageError> {
+        let mut disks = self.lock();
+        let disk = disks
+            .iter_mut()
+            .find(|disk| disk.info.device == block_path)
+            .ok_or_else(|| not_found(block_path))?;
+        let placed = place_partition(&disk.info, &disk.partitions, offset, size)
+            .map_err(|e| StorageError::new(StorageErrorKind::Conflict, e))?;
+
+        let number = (1..)
+            .find(|n| disk.partitions.iter().all(|p| p.number != *n))
+            .unwrap_or_default();
+        let partition = PartitionInfo {
+            device: partition_device(block_path, number),
+            number,
+            parent_path: block_path.to_string(),
+            size: placed.end - placed.start,
+            offset: placed.start,
+            type_id: type_id.to_string(),
+            type_name: String::new(),
+            flags: 0,
+            name: String::new(),
+            uuid: String::new(),
+            table_type: disk.info.partition_table_type.clone().unwrap_or_default(),
+            has_filesystem: false,
+            filesystem_type: None,
+            mount_points: Vec::new(),
+            usage: None,
+        };
+        let device = partition.device.clone();
+        disk.partitions.push(partition);
+        disk.partitions.sort_by_key(|p| p.number);
+        Ok(device)
+    }
+
+    async fn create_partition_with_filesystem(
+        &self,
+        block_path: &str,
+        info: &CreatePartitionInfo,
+    ) -> Result<String, StorageError> {
+        let device = self
+            .create_partition(block_path, info.offset, info.size, &info.selected_type)
+            .await?;
+        let fs_type = info.filesystem_type.trim().to_string();
+        let name = info.name.clone();
+        self.edit_partition(&device, |_, partition| {
+            partition.has_filesystem = !fs_type.is_empty();
+            partition.filesystem_type = Some(fs_type).filter(|t| !t.is_empty());
+            partition.name = name;
+            Ok(())
+        })?;
+        Ok(device)
+    }
+
+    async fn delete_partition(&self, partition_path: &str) -> Result<(), StorageError> {
+        let mut disks = self.lock();
+        for disk in disks.iter_mut() {
+            let count = disk.partitions.len();
+            disk.partitions.retain(|p| p.device != partition_path);
+            if disk.partitions.len() != count {
+                return Ok(());
+            }
+        }
+        Err(not_found(partition_path))
+    }
+
+    async fn resize_partition(
+        &self,
+        partition_path: &str,
+        new_size: u64,
+    ) -> Result<(), StorageError> {
+        self.edit_partition(partition_path, |disk, partition| {
+            let usable = usable_range(
+                disk.info.size,
+                disk.info
+                    .partition_table_type
+                    .as_deref()
+                    .unwrap_or_default(),
+                disk.info.gpt_usable_range,
+            )
+            .ok_or_else(|| StorageError::new(StorageErrorKind::Internal, "No usable range"))?;
+            let limit = disk
+                .partitions
+                .iter()
+                .map(|p| p.offset)
+                .filter(|offset| *offset > partition.offset)
+                .fold(usable.end, u64::min);
+            let end = partition.offset.saturating_add(new_size);
+            if new_size == 0 || end > limit {
+                return Err(StorageError::new(
+                    StorageErrorKind::InvalidInput,
+                    format!("{partition_path} cannot grow to {new_size} bytes"),
+                ));
+            }
+            partition.size = new_size;
+            Ok(())
+        })
+    }
+
+    async fn set_partition_type(
+        &self,
+        partition_path: &str,
+        type_id: &str,
+    ) -> Result<(), StorageError> {
+        self.edit_partition(partition_path, |_, partition| {
+            partition.type_id = type_id.to_string();
+            Ok(())
+        })
+    }
+
+    async fn set_partition_flags(
+        &self,
+        partition_path: &str,
+        flags: u64,
+    ) -> Result<(), StorageError> {
+        self.edit_partition(partition_path, |_, partition| {
+            partition.flags = flags;
+            Ok(())
+        })
+    }
+
+    async fn set_partition_name(
+        &self,
+        partition_path: &str,
+        name: &str,
+    ) -> Result<(), StorageError> {
+        self.edit_partition(partition_path, |_, partition| {
+            partition.name = name.to_string();
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use proptest::prelude::*;
+    use storage_types::GPT_ALIGNMENT_BYTES;
+    use storage_types::placement::{
+        DOS_MAX_END_BYTES, DOS_MAX_PARTITIONS, PARTITION_SIZE_GRANULARITY,
+    };
+
+    const MIB: u64 = 1024 * 1024;
+    const DISK: &str = "/dev/sdz";
+
+    /// A disk of 4 MiB to 4 TiB, with a GPT usable range as gdisk and
+    /// parted write it, the fallback, or a DOS table
+    fn geometry() -> impl Strategy<Value = (u64, &'static str, Option<ByteRange>)> {
+        (4 * MIB..4 * 1024 * 1024 * MIB, 0..3u8).prop_map(|(size, kind)| {
+            let size = size / 512 * 512;
+            match kind {
+                0 => (
+                    size,
+                    "gpt",
+                    Some(ByteRange {
+                        start: 34 * 512,
+                        end: size - 33 * 512,
+                    }),
+                ),
+                1 => (size, "gpt", None),
+                _ => (size, "dos", None),
+            }
+        })
+    }
+
+    /// Creation requests as fractions of the disk, deletions in between
+    fn requests() -> impl Strategy<Value = Vec<(f64, f64, bool)>> {
+        prop::collection::vec(
+            (0.0..1.0f64, 0.0..0.5f64, prop::bool::weighted(0.15)),
+            1..24,
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn placed_partitions_never_overlap(
+            (size, table_type, gpt_range) in geometry(),
+            requests in requests(),
+        ) {
+            let backend = MockDiskBackend::new();
+            backend.add_disk(DISK, size, Some(table_type), gpt_range);
+            let usable = usable_range(size, table_type, gpt_range).unwrap();
+
+            for (offset, length, delete) in requests {
+                if delete {
+                    if let Some(partition) = backend.partitions(DISK).first() {
+                        block_on(backend.delete_partition(&partition.device)).unwrap();
+                    }
+                    continue;
+                }
+                let offset = (offset * size as f64) as u64;
+                let length = (length * size as f64) as u64;
+                let _ = block_on(backend.create_partition(DISK, offset, length, "0x83"));
+
+                let mut partitions = backend.partitions(DISK);
+                if table_type == "dos" {
+                    prop_assert!(partitions.len() <= DOS_MAX_PARTITIONS);
+                    prop_assert!(partitions.iter().all(|p| p.offset + p.size <= DOS_MAX_END_BYTES));
+                }
+                for p in &partitions {
+                    prop_assert!(p.size > 0);
+                    prop_assert!(p.offset.is_multiple_of(GPT_ALIGNMENT_BYTES));
+                    prop_assert!(p.size.is_multiple_of(PARTITION_SIZE_GRANULARITY));
+                    prop_assert!(usable.start <= p.offset && p.offset + p.size <= usable.end);
+                }
+                partitions.sort_by_key(|p| p.offset);
+                for pair in partitions.windows(2) {
+                    prop_assert!(pair[0].offset + pair[0].size <= pair[1].offset);
+                }
+            }
+        }
+
+        #[test]
+        fn free_disks_take_a_partition(
+            (size, table_type, gpt_range) in geometry(),
+            offset in 0..2 * MIB,
+        ) {
+            let backend = MockDiskBackend::new();
+            backend.add_disk(DISK, size, Some(table_type), gpt_range);
+            let device = block_on(backend.create_partition(DISK, offset, u64::MAX, "0x83")).unwrap();
+            let partitions = backend.partitions(DISK);
+            prop_assert_eq!(&partitions[0].device, &device);
+            prop_assert_eq!(partitions[0].number, 1);
+            // Only the alignment at either end is lost
+            prop_assert!(partitions[0].size + 4 * MIB >= size.min(DOS_MAX_END_BYTES));
+        }
+    }
+
+    #[test]
+    fn numbers_reuse_deleted_partitions() {
+        let backend = MockDiskBackend::new();
+        backend.add_disk("/dev/nvme0n1", 100 * MIB, Some("gpt"), None);
+        for offset in [MIB, 10 * MIB, 20 * MIB] {
+            block_on(backend.create_partition("/dev/nvme0n1", offset, MIB, "")).unwrap();
+        }
+        block_on(backend.delete_partition("/dev/nvme0n1p2")).unwrap();
+        let device = block_on(backend.create_partition("/dev/nvme0n1", 30 * MIB, MIB, ""));
+        assert_eq!(device.unwrap(), "/dev/nvme0n1p2");
+
+        // Growing into the next partition is refused
+        assert!(block_on(backend.resize_partition("/dev/nvme0n1p1", 20 * MIB)).is_err());
+        block_on(backend.resize_partition("/dev/nvme0n1p1", 9 * MIB)).unwrap();
+    }
+}
@@ -17,6 +17,14 @@ impl OperationId {
     }
 }
 
+impl std::str::FromStr for OperationId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
 impl Default for OperationId {
     fn default() -> Self {
         Self::new()
@@ -3,7 +3,11 @@
 pub mod error;
 pub mod id;
 pub mod operation;
+pub mod paging;
+pub mod progress;
 
 pub use error::{StorageError, StorageErrorKind};
 pub use id::OperationId;
 pub use operation::{OperationEvent, OperationKind, OperationProgress};
+pub use paging::{FD_REPLIES, PageStore, ResultPage, collect_pages};
+pub use progress::{PROGRESS_FDS, PROGRESS_RECORD_BYTES, ProgressRecord};
@@ -19,16 +19,23 @@ pub enum OperationKind {
     Btrfs,
     Rclone,
     UsageScan,
+    SmartSelfTest,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OperationProgress {
     pub operation_id: OperationId,
     pub operation: OperationKind,
+    /// Device or file the operation works on
+    #[serde(default)]
+    pub target: String,
     pub phase: String,
     pub bytes_processed: u64,
     pub bytes_total: Option<u64>,
     pub percent: Option<u8>,
+    /// The operation can be stopped with `CancelOperation`
+    #[serde(default)]
+    pub cancellable: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,6 +51,11 @@ pub enum OperationEvent {
         operation: OperationKind,
         error: StorageError,
     },
+    /// Stopped on request before it finished
+    Cancelled {
+        operation_id: OperationId,
+        operation: OperationKind,
+    },
 }
 
 #[cfg(test)]
@@ -63,10 +75,12 @@ mod tests {
         let event = OperationEvent::Progress(OperationProgress {
             operation_id: OperationId::new(),
             operation: OperationKind::UsageScan,
+            target: "/home".to_string(),
             phase: "enumerating".to_string(),
             bytes_processed: 1024,
             bytes_total: Some(4096),
             percent: Some(25),
+            cancellable: false,
         });
 
         let json = serde_json::to_string(&event).expect("serialize event");
@@ -74,6 +88,23 @@ mod tests {
         assert_eq!(parsed, event);
     }
 
+    #[test]
+    fn progress_from_older_services_is_not_cancellable() {
+        let json = r#"{"operation_id":"7d0b1ac4-3a5c-4a8e-9e83-6f5b0d9f8f11","operation":"image","phase":"restore_drive","bytes_processed":0,"bytes_total":null,"percent":null}"#;
+        let progress: OperationProgress = serde_json::from_str(json).expect("deserialize progress");
+        assert!(!progress.cancellable);
+
+        let event = OperationEvent::Cancelled {
+            operation_id: progress.operation_id,
+            operation: OperationKind::SmartSelfTest,
+        };
+        let json = serde_json::to_string(&event).expect("serialize event");
+        assert!(json.contains(r#""type":"cancelled""#));
+        assert!(json.contains(r#""operation":"smart_self_test""#));
+        let parsed: OperationEvent = serde_json::from_str(&json).expect("deserialize event");
+        assert_eq!(parsed, event);
+    }
+
     #[test]
     fn storage_error_kind_http_family_codes_are_stable() {
         assert_eq!(StorageErrorKind::InvalidInput.code(), 400);
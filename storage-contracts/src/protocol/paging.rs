@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Paged replies
+//!
+//! Replies that grow with the system, such as usage scan results, the
+//! logical volumes of a large server or process lists, are split into
+//! pages rather than sent as one D-Bus message. The method returns the
+//! first [`ResultPage`]; the client fetches the remaining pages with
+//! `GetPage(handle, index)` on the service interface and joins their data
+//! into the JSON the method used to return. Services that list
+//! [`FD_REPLIES`] in their features also hand out all remaining pages at
+//! once as a sealed memory file, saving a round trip per page.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{StorageError, StorageErrorKind};
+
+/// Bytes of JSON per page, well below the D-Bus message limit
+pub const PAGE_BYTES: usize = 512 * 1024;
+
+/// Service feature of handing out the rest of a reply as a file
+/// descriptor
+pub const FD_REPLIES: &str = "fd-replies";
+
+/// How long unfetched pages are kept
+pub const PAGE_TTL: Duration = Duration::from_secs(300);
+
+/// One page of a reply
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultPage {
+    /// Handle to fetch the other pages with; empty when there is only one
+    pub handle: String,
+    pub index: u32,
+    pub page_count: u32,
+    /// This page's part of the JSON reply
+    pub data: String,
+}
+
+impl ResultPage {
+    pub fn is_last(&self) -> bool {
+        self.index + 1 >= self.page_count
+    }
+}
+
+/// Split `text` into pages of at most `page_bytes` bytes (more only when a
+/// single character is larger), on character boundaries
+///
+/// There is always at least one page, empty for empty text.
+pub fn split_pages(text: &str, page_bytes: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = page_bytes.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        let (page, tail) = rest.split_at(end);
+        pages.push(page.to_string());
+        rest = tail;
+    }
+    if pages.is_empty() {
+        pages.push(String::new());
+    }
+    pages
+}
+
+/// Join the pages of a reply, fetching those after `first` with `fetch`
+/// (the client side of GetPage)
+pub async fn collect_pages<F, Fut, E>(first: ResultPage, mut fetch: F) -> Result<String, E>
+where
+    F: FnMut(String, u32) -> Fut,
+    Fut: Future<Output = Result<ResultPage, E>>,
+{
+    let mut text = first.data;
+    for index in first.index + 1..first.page_count {
+        let page = fetch(first.handle.clone(), index).await?;
+        text.push_str(&page.data);
+    }
+    Ok(text)
+}
+
+struct StoredResult {
+    owner: String,
+    pages: Vec<String>,
+    expires: Instant,
+}
+
+/// Pages the service has yet to hand out, by handle
+///
+/// Pages belong to the bus name that made the call and are dropped once
+/// the last one is fetched or after [`PAGE_TTL`].
+pub struct PageStore {
+    results: Mutex<HashMap<String, StoredResult>>,
+    page_bytes: usize,
+    ttl: Duration,
+}
+
+impl Default for PageStore {
+    fn default() -> Self {
+        Self::new(PAGE_BYTES, PAGE_TTL)
+    }
+}
+
+impl PageStore {
+    pub fn new(page_bytes: usize, ttl: Duration) -> Self {
+        Self {
+            results: Mutex::new(HashMap::new()),
+            page_bytes: page_bytes.max(1),
+            ttl,
+        }
+    }
+
+    /// Split `text` into pages for `owner` and return the first
+    pub fn paginate(&self, owner: &str, text: &str) -> ResultPage {
+        let mut pages = split_pages(text, self.page_bytes);
+        let page_count = pages.len() as u32;
+        // The first page goes out now; its empty slot keeps indexes and
+        // page numbers equal
+        let first = std::mem::take(&mut pages[0]);
+        let handle = if page_count == 1 {
+            String::new()
+        } else {
+            let handle = Uuid::new_v4().to_string();
+            let mut results = self.lock();
+            Self::evict_expired(&mut results);
+            results.insert(
+                handle.clone(),
+                StoredResult {
+                    owner: owner.to_string(),
+                    pages,
+                    expires: Instant::now() + self.ttl,
+                },
+            );
+            handle
+        };
+        ResultPage {
+            handle,
+            index: 0,
+            page_count,
+            data: first,
+        }
+    }
+
+    /// Serialize `value` and return its first page as JSON, the reply of a
+    /// paged method
+    pub fn paginate_json<T: Serialize>(
+        &self,
+        owner: &str,
+        value: &T,
+    ) -> Result<String, StorageError> {
+        let text = serde_json::to_string(value)
+            .map_err(|e| StorageError::new(StorageErrorKind::Internal, e.to_string()))?;
+        serde_json::to_string(&self.paginate(owner, &text))
+            .map_err(|e| StorageError::new(StorageErrorKind::Internal, e.to_string()))
+    }
+
+    /// Page `index` of the result `handle`, for its owner
+    pub fn page(&self, owner: &str, handle: &str, index: u32) -> Result<ResultPage, StorageError> {
+        let mut results = self.lock();
+        Self::evict_expired(&mut results);
+        let result = results
+            .get_mut(handle)
+            .filter(|result| result.owner == owner)
+            .ok_or_else(|| {
+                StorageError::new(
+                    StorageErrorKind::NotFound,
+                    format!("No result with handle {handle}"),
+                )
+            })?;
+        let page_count = result.pages.len() as u32;
+        if index == 0 || index >= page_count {
+            return Err(StorageError::new(
+                StorageErrorKind::InvalidInput,
+                format!("Page {index} is not one of pages 1 to {}", page_count - 1),
+            ));
+        }
+        let data = std::mem::take(&mut result.pages[index as usize]);
+        if index + 1 == page_count {
+            results.remove(handle);
+        }
+        Ok(ResultPage {
+            handle: handle.to_string(),
+            index,
+            page_count,
+            data,
+        })
+    }
+
+    /// All pages of the result `handle` after the first, joined, for its
+    /// owner; the result is released
+    pub fn take_rest(&self, owner: &str, handle: &str) -> Result<String, StorageError> {
+        let mut results = self.lock();
+        Self::evict_expired(&mut results);
+        match results.entry(handle.to_string()) {
+            Entry::Occupied(entry) if entry.get().owner == owner => {
+                Ok(entry.remove().pages.concat())
+            }
+            _ => Err(StorageError::new(
+                StorageErrorKind::NotFound,
+                format!("No result with handle {handle}"),
+            )),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, StoredResult>> {
+        self.results.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn evict_expired(results: &mut HashMap<String, StoredResult>) {
+        let now = Instant::now();
+        results.retain(|_, result| result.expires > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn splits_on_character_boundaries() {
+        assert_eq!(split_pages("", 4), [""]);
+        assert_eq!(split_pages("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        // "é" is two bytes and stays whole
+        assert_eq!(split_pages("aébc", 2), ["a", "é", "bc"]);
+        assert_eq!(split_pages("€", 1), ["€"]);
+    }
+
+    #[test]
+    fn pages_round_trip_for_their_owner() {
+        let store = PageStore::new(3, PAGE_TTL);
+        let text = r#"["a","b","c"]"#;
+        let first = store.paginate(":1.42", text);
+        assert_eq!(first.page_count, 5);
+        assert!(!first.is_last());
+
+        assert_eq!(
+            store.page(":1.43", &first.handle, 1).unwrap_err().kind,
+            StorageErrorKind::NotFound
+        );
+        assert_eq!(
+            store.page(":1.42", &first.handle, 0).unwrap_err().kind,
+            StorageErrorKind::InvalidInput
+        );
+
+        let joined = block_on(collect_pages(first.clone(), |handle, index| {
+            std::future::ready(store.page(":1.42", &handle, index))
+        }))
+        .unwrap();
+        assert_eq!(joined, text);
+        // Fetching the last page releases the result
+        assert!(store.page(":1.42", &first.handle, 1).is_err());
+
+        let first = store.paginate(":1.42", text);
+        assert!(store.take_rest(":1.43", &first.handle).is_err());
+        assert_eq!(
+            first.data + &store.take_rest(":1.42", &first.handle).unwrap(),
+            text
+        );
+        assert!(store.take_rest(":1.42", &first.handle).is_err());
+
+        let single = store.paginate(":1.42", "[]");
+        assert!(single.handle.is_empty() && single.is_last());
+    }
+
+    #[test]
+    fn expired_results_are_dropped() {
+        let store = PageStore::new(1, Duration::ZERO);
+        let first = store.paginate(":1.42", "[1]");
+        assert_eq!(
+            store.page(":1.42", &first.handle, 1).unwrap_err().kind,
+            StorageErrorKind::NotFound
+        );
+    }
+}
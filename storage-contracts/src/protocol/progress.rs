@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Shared progress counters
+//!
+//! Services that list [`PROGRESS_FDS`] in their features hand out a memory
+//! file and an eventfd for a running image operation. The service
+//! overwrites the file with a [`ProgressRecord`] as bytes are copied and
+//! bumps the eventfd; the client waits on the eventfd and reads the record
+//! at its own frame rate, so progress bars move smoothly without a D-Bus
+//! signal per update. The OperationProgress signal and GetOperationStatus
+//! remain for clients and services without the feature.
+
+/// Service feature of handing out progress counters for image operations
+pub const PROGRESS_FDS: &str = "progress-fds";
+
+/// Size of a [`ProgressRecord`] in the shared memory file
+pub const PROGRESS_RECORD_BYTES: usize = 32;
+
+/// The state of an operation as written to its shared memory file
+///
+/// Four native-endian u64 fields: bytes completed, total bytes, speed in
+/// bytes per second and a finished flag. Writes aren't atomic, so readers
+/// retry until two reads agree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgressRecord {
+    pub bytes_completed: u64,
+    pub total_bytes: u64,
+    pub speed_bytes_per_sec: u64,
+    pub finished: bool,
+}
+
+impl ProgressRecord {
+    pub fn to_bytes(&self) -> [u8; PROGRESS_RECORD_BYTES] {
+        let mut bytes = [0; PROGRESS_RECORD_BYTES];
+        let fields = [
+            self.bytes_completed,
+            self.total_bytes,
+            self.speed_bytes_per_sec,
+            u64::from(self.finished),
+        ];
+        for (chunk, field) in bytes.chunks_exact_mut(8).zip(fields) {
+            chunk.copy_from_slice(&field.to_ne_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; PROGRESS_RECORD_BYTES]) -> Self {
+        let mut fields = bytes
+            .chunks_exact(8)
+            .map(|chunk| u64::from_ne_bytes(chunk.try_into().unwrap_or_default()));
+        let mut next = || fields.next().unwrap_or_default();
+        Self {
+            bytes_completed: next(),
+            total_bytes: next(),
+            speed_bytes_per_sec: next(),
+            finished: next() != 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_round_trip() {
+        let record = ProgressRecord {
+            bytes_completed: 3 << 30,
+            total_bytes: 8 << 30,
+            speed_bytes_per_sec: 120 << 20,
+            finished: true,
+        };
+        assert_eq!(ProgressRecord::from_bytes(&record.to_bytes()), record);
+        assert_eq!(
+            ProgressRecord::from_bytes(&[0; PROGRESS_RECORD_BYTES]),
+            ProgressRecord::default()
+        );
+    }
+}
@@ -45,6 +45,16 @@ pub(crate) fn emit_authorized_method(
 
             tracing::debug!("Caller {} has UID {}", __sender, __caller_uid);
 
+            // Restriction profiles are checked before Polkit, which could
+            // otherwise grant the action
+            if !crate::restrictions::is_allowed(__caller_uid, #action_id) {
+                crate::metrics::record_operation(#action_id, false);
+                return Err(zbus::fdo::Error::AccessDenied(format!(
+                    "Action not allowed by restriction profile: {}",
+                    #action_id
+                )));
+            }
+
             let __caller_pid = __dbus_proxy
                 .get_connection_unix_process_id(__bus_name).await
                 .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to get caller PID: {}", e)))?;
@@ -75,6 +85,8 @@ pub(crate) fn emit_authorized_method(
                 __auth_result.is_challenge
             );
 
+            crate::metrics::record_operation(#action_id, __auth_result.is_authorized);
+
             if !__auth_result.is_authorized {
                 tracing::warn!("Authorization denied for action: {}", #action_id);
                 return Err(zbus::fdo::Error::AccessDenied(format!(
@@ -83,7 +95,14 @@ pub(crate) fn emit_authorized_method(
                 )));
             }
 
-            tracing::info!("Authorization granted for action: {}", #action_id);
+            // The operation field marks where the method's log lines start,
+            // for excerpts of the service log (see GetOperationLog)
+            tracing::info!(
+                operation = stringify!(#method_name),
+                "Authorization granted for action: {}",
+                #action_id
+            );
+            crate::statistics::record_operation(__caller_uid, stringify!(#method_name));
 
             let __caller_username = unsafe {
                 let __pw = libc::getpwuid(__caller_uid);
@@ -28,9 +28,12 @@
 //! ```
 //!
 //! The macro will:
-//! 1. Check Polkit authorization against the actual caller
-//! 2. Create a `caller: CallerInfo` variable with the caller's uid, username, and sender
-//! 3. Execute the original method body
+//! 1. Refuse actions the caller's restriction profiles leave out, with
+//!    `crate::restrictions::is_allowed`
+//! 2. Check Polkit authorization against the actual caller, counting the
+//!    outcome with `crate::metrics::record_operation`
+//! 3. Create a `caller: CallerInfo` variable with the caller's uid, username, and sender
+//! 4. Execute the original method body
 
 mod emit;
 mod parse;
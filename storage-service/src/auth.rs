@@ -10,8 +10,10 @@
 //! The macro:
 //! 1. Gets the actual caller's sender from `header.sender()` (NOT `connection.unique_name()`)
 //! 2. Looks up the caller's UID and PID via D-Bus
-//! 3. Checks Polkit authorization with the correct subject
-//! 4. Injects a `caller: CallerInfo` variable into the method body
+//! 3. Refuses actions left out of the caller's restriction profiles (see
+//!    `crate::restrictions`)
+//! 4. Checks Polkit authorization with the correct subject
+//! 5. Injects a `caller: CallerInfo` variable into the method body
 //!
 //! For secondary authorization checks within a method (e.g., checking additional
 //! permissions for destructive operations), use `check_authorization()`.
@@ -23,6 +25,8 @@ use zbus_polkit::policykit1::AuthorityProxy;
 ///
 /// This is used for secondary authorization checks within methods that need
 /// to verify additional permissions (e.g., killing processes during unmount).
+/// Like the primary check, it refuses actions left out of the caller's
+/// restriction profiles before asking Polkit.
 ///
 /// For primary method authorization, use `#[authorized_interface]` macro instead.
 pub async fn check_authorization(
@@ -44,6 +48,14 @@ pub async fn check_authorization(
     let bus_name: zbus::names::BusName = sender
         .try_into()
         .map_err(|e| zbus::Error::Failure(format!("Invalid bus name: {}", e)))?;
+    let uid = dbus_proxy
+        .get_connection_unix_user(bus_name.clone())
+        .await?;
+    if !crate::restrictions::is_allowed(uid, action_id) {
+        crate::metrics::record_operation(action_id, false);
+        return Ok(false);
+    }
+
     let pid = dbus_proxy.get_connection_unix_process_id(bus_name).await?;
 
     tracing::debug!("Sender {} has PID {}", sender, pid);
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Storage backend selection
+//!
+//! Partition editing and disk imaging go through the storage-contracts
+//! adapters. UDisks2 implements them by default; systems without UDisks2,
+//! such as minimal containers and some immutable distributions, can switch
+//! to the direct backend of storage-sys (lsblk, sfdisk, mkfs, losetup) in
+//! [`CONFIG_PATH`]:
+//!
+//! ```json
+//! { "backend": "direct" }
+//! ```
+//!
+//! The other interfaces still need UDisks2.
+
+use std::sync::{Arc, LazyLock};
+
+use serde::Deserialize;
+use storage_contracts::{ImageOpsAdapter, PartitionOpsAdapter};
+use storage_sys::DirectBackend;
+use storage_udisks::UdisksBackend;
+
+/// Backend settings, written by the administrator
+const CONFIG_PATH: &str = "/etc/cosmic-ext-storage/backend.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendKind {
+    #[default]
+    Udisks,
+    Direct,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BackendConfig {
+    #[serde(default)]
+    backend: BackendKind,
+}
+
+/// Adapters of the configured backend
+pub struct Backend {
+    pub kind: BackendKind,
+    pub partitions: Arc<dyn PartitionOpsAdapter>,
+    pub images: Arc<dyn ImageOpsAdapter>,
+}
+
+static BACKEND: LazyLock<Backend> = LazyLock::new(|| {
+    let kind = load_config().backend;
+    match kind {
+        BackendKind::Udisks => Backend {
+            kind,
+            partitions: Arc::new(UdisksBackend),
+            images: Arc::new(UdisksBackend),
+        },
+        BackendKind::Direct => Backend {
+            kind,
+            partitions: Arc::new(DirectBackend),
+            images: Arc::new(DirectBackend),
+        },
+    }
+});
+
+fn load_config() -> BackendConfig {
+    match std::fs::read_to_string(CONFIG_PATH) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid backend config: {e}");
+            BackendConfig::default()
+        }),
+        Err(_) => BackendConfig::default(),
+    }
+}
+
+/// The configured backend, read once
+pub fn current() -> &'static Backend {
+    &BACKEND
+}
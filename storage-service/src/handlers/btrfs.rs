@@ -1,16 +1,19 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use disks_btrfs::SubvolumeManager;
+use disks_btrfs::{BtrfsError, SubvolumeManager};
 use std::path::PathBuf;
 use std::sync::Arc;
 use storage_macros::authorized_interface;
-use storage_types::btrfs::SubvolumeList;
+use storage_types::btrfs::{RestoreConflictPolicy, SafetySnapshotPolicy, SubvolumeList};
 use zbus::message::Header as MessageHeader;
 use zbus::object_server::SignalEmitter;
 use zbus::{Connection, interface};
 
 use crate::policies::btrfs::{BtrfsDomain, BtrfsPolicy};
 
+/// Largest page of snapshot changes returned by one `diff_snapshots` call
+const MAX_DIFF_PAGE: usize = 1000;
+
 /// BTRFS operations handler
 pub struct BtrfsHandler {
     domain: Arc<dyn BtrfsDomain>,
@@ -59,6 +62,10 @@ impl BtrfsHandler {
     }
 
     /// Create a new subvolume
+    ///
+    /// `path` is relative to the mount point and may be nested under existing
+    /// directories or subvolumes. It is normalized here; paths that escape the
+    /// mount point (via `..` or symlinks) are rejected.
     #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
     async fn create_subvolume(
         &self,
@@ -66,12 +73,12 @@ impl BtrfsHandler {
         #[zbus(header)] _header: MessageHeader<'_>,
         #[zbus(signal_context)] ctx: SignalEmitter<'_>,
         mountpoint: &str,
-        name: &str,
+        path: &str,
     ) -> zbus::fdo::Result<()> {
         self.domain.require_available()?;
         tracing::info!(
             "Creating subvolume {} at {} (UID {})",
-            name,
+            path,
             mountpoint,
             caller.uid
         );
@@ -79,12 +86,13 @@ impl BtrfsHandler {
         let manager = SubvolumeManager::new(mountpoint)
             .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
 
-        manager
-            .create(name)
-            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        let created = manager.create(path).map_err(|e| match e {
+            BtrfsError::InvalidPath(_) => zbus::fdo::Error::InvalidArgs(e.to_string()),
+            _ => zbus::fdo::Error::Failed(e.to_string()),
+        })?;
 
         // Emit signal
-        Self::subvolume_changed(&ctx, mountpoint, "created")
+        Self::subvolume_changed(&ctx, &created.to_string_lossy(), "created")
             .await
             .ok();
 
@@ -290,6 +298,485 @@ impl BtrfsHandler {
         Ok(json)
     }
 
+    /// Get the error counters of each device of a filesystem
+    ///
+    /// Returns: JSON-serialized Vec<BtrfsDeviceStats>
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-read")]
+    async fn get_device_stats(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mountpoint: &str,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::debug!(
+            "Getting device stats for {} (UID {})",
+            mountpoint,
+            caller.uid
+        );
+
+        let stats = disks_btrfs::device_stats(&PathBuf::from(mountpoint))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let json = serde_json::to_string(&stats)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Reset the error counters of each device of a filesystem
+    ///
+    /// Returns: JSON-serialized Vec<BtrfsDeviceStats> with the counters from
+    /// before the reset
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn reset_device_stats(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mountpoint: &str,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+
+        let stats = disks_btrfs::reset_device_stats(&PathBuf::from(mountpoint))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        tracing::info!(
+            "Reset device stats of {} with {} errors (UID {})",
+            mountpoint,
+            stats
+                .iter()
+                .map(|device| device.total_errors())
+                .sum::<u64>(),
+            caller.uid
+        );
+
+        let json = serde_json::to_string(&stats)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Get the compression property of a path and the mount-wide compression option
+    ///
+    /// `path` is absolute or relative to the mount point (empty for the mount point).
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-read")]
+    async fn get_compression(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mountpoint: &str,
+        path: &str,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::debug!(
+            "Getting compression of {} on {} (UID {})",
+            path,
+            mountpoint,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let info = manager
+            .get_compression(&PathBuf::from(path))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let json = serde_json::to_string(&info)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Set the compression property on a subvolume or directory
+    ///
+    /// Applies to files written afterwards; use `compress_existing` for existing data.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn set_compression(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctx: SignalEmitter<'_>,
+        mountpoint: &str,
+        path: &str,
+        algorithm: &str,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_available()?;
+        tracing::info!(
+            "Setting compression={} on {} (UID {})",
+            algorithm,
+            path,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        manager
+            .set_compression(&PathBuf::from(path), algorithm)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        // Emit signal
+        Self::subvolume_changed(&ctx, path, "modified").await.ok();
+
+        Ok(())
+    }
+
+    /// Get the no-copy-on-write attribute of a directory
+    ///
+    /// `path` is absolute or relative to the mount point (empty for the mount point).
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-read")]
+    async fn get_nocow(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mountpoint: &str,
+        path: &str,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::debug!(
+            "Getting NOCOW attribute of {} on {} (UID {})",
+            path,
+            mountpoint,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let status = manager
+            .get_nocow(&PathBuf::from(path))
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let json = serde_json::to_string(&status)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Set or clear the no-copy-on-write attribute (`chattr +C`) on a directory
+    ///
+    /// Only files created afterwards inherit it. Returns the new state as JSON.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn set_nocow(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctx: SignalEmitter<'_>,
+        mountpoint: &str,
+        path: &str,
+        enabled: bool,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::info!("Setting NOCOW={} on {} (UID {})", enabled, path, caller.uid);
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let status = manager
+            .set_nocow(&PathBuf::from(path), enabled)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        // Emit signal
+        Self::subvolume_changed(&ctx, path, "modified").await.ok();
+
+        let json = serde_json::to_string(&status)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Change the mount-wide compression (`compress=`/`compress-force=`) by remounting
+    ///
+    /// The change lasts until the filesystem is next mounted.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn set_mount_compression(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mountpoint: &str,
+        value: &str,
+        force: bool,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_available()?;
+        tracing::info!(
+            "Remounting {} with compression={} force={} (UID {})",
+            mountpoint,
+            value,
+            force,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        manager
+            .set_mount_compression(value, force)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Estimate space saved by recompressing existing data below a path
+    ///
+    /// Takes btrfs-modify, not btrfs-read: the service reads and compresses
+    /// a sample of the files below the path as root, so the compressed size
+    /// tells something about files the caller may not be able to read.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn estimate_compression(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mountpoint: &str,
+        path: &str,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::info!(
+            "Estimating compression savings for {} on {} (UID {})",
+            path,
+            mountpoint,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let path = PathBuf::from(path);
+        let estimate = tokio::task::spawn_blocking(move || manager.estimate_compression(&path))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Estimate task failed: {}", e)))?
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let json = serde_json::to_string(&estimate)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// List files added, modified and deleted between two read-only snapshots
+    ///
+    /// Returns a page of at most `limit` entries (capped at
+    /// [`MAX_DIFF_PAGE`]) starting at `offset`, as a JSON `SnapshotDiff`.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-read")]
+    #[allow(clippy::too_many_arguments)]
+    async fn diff_snapshots(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mountpoint: &str,
+        old_snapshot: &str,
+        new_snapshot: &str,
+        offset: u32,
+        limit: u32,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::info!(
+            "Comparing snapshots {} and {} on {} (UID {})",
+            old_snapshot,
+            new_snapshot,
+            mountpoint,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let old = PathBuf::from(old_snapshot);
+        let new = PathBuf::from(new_snapshot);
+        let limit = (limit as usize).min(MAX_DIFF_PAGE);
+        let diff = tokio::task::spawn_blocking(move || {
+            manager.diff_snapshots(&old, &new, offset as usize, limit)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Diff task failed: {}", e)))?
+        .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let json = serde_json::to_string(&diff)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Copy files or directories out of a snapshot back into a live subvolume
+    ///
+    /// `paths` are relative to the snapshot root. `conflict` is "skip",
+    /// "overwrite" or "keep-both". Returns a JSON `RestoreResult`.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    #[allow(clippy::too_many_arguments)]
+    async fn restore_from_snapshot(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctx: SignalEmitter<'_>,
+        mountpoint: &str,
+        snapshot: &str,
+        target: &str,
+        paths: Vec<String>,
+        conflict: &str,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        let policy = RestoreConflictPolicy::parse(conflict).ok_or_else(|| {
+            zbus::fdo::Error::InvalidArgs(format!("Unknown conflict policy: {}", conflict))
+        })?;
+        tracing::info!(
+            "Restoring {} path(s) from {} into {} ({:?}) (UID {})",
+            paths.len(),
+            snapshot,
+            target,
+            policy,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let snapshot = PathBuf::from(snapshot);
+        let target_path = PathBuf::from(target);
+        let result = tokio::task::spawn_blocking(move || {
+            manager.restore_from_snapshot(&snapshot, &target_path, &paths, policy)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Restore task failed: {}", e)))?
+        .map_err(|e| match e {
+            BtrfsError::InvalidPath(_) => zbus::fdo::Error::InvalidArgs(e.to_string()),
+            _ => zbus::fdo::Error::Failed(e.to_string()),
+        })?;
+
+        // Emit signal
+        Self::subvolume_changed(&ctx, target, "modified").await.ok();
+
+        let json = serde_json::to_string(&result)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Replace a subvolume with a writable copy of one of its snapshots
+    ///
+    /// Takes a read-only safety snapshot first. Returns a JSON
+    /// `RollbackResult`, which reports whether a reboot is required.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn rollback(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctx: SignalEmitter<'_>,
+        mountpoint: &str,
+        target: &str,
+        snapshot: &str,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::info!(
+            "Rolling back {} to {} on {} (UID {})",
+            target,
+            snapshot,
+            mountpoint,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let target_path = PathBuf::from(target);
+        let snapshot = PathBuf::from(snapshot);
+        let result = tokio::task::spawn_blocking(move || manager.rollback(&target_path, &snapshot))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Rollback task failed: {}", e)))?
+            .map_err(|e| match e {
+                BtrfsError::InvalidPath(_) => zbus::fdo::Error::InvalidArgs(e.to_string()),
+                _ => zbus::fdo::Error::Failed(e.to_string()),
+            })?;
+
+        // Emit signal
+        Self::subvolume_changed(&ctx, target, "modified").await.ok();
+
+        let json = serde_json::to_string(&result)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))?;
+
+        Ok(json)
+    }
+
+    /// Recompress existing data below a path (defragment with compression)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn compress_existing(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] ctx: SignalEmitter<'_>,
+        mountpoint: &str,
+        path: &str,
+        algorithm: &str,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_available()?;
+        tracing::info!(
+            "Compressing existing data in {} on {} with {} (UID {})",
+            path,
+            mountpoint,
+            algorithm,
+            caller.uid
+        );
+
+        let manager = SubvolumeManager::new(mountpoint)
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        let target = PathBuf::from(path);
+        let algorithm_owned = algorithm.to_string();
+        tokio::task::spawn_blocking(move || manager.compress_existing(&target, &algorithm_owned))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Compression task failed: {}", e)))?
+            .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+
+        // Emit signal
+        Self::subvolume_changed(&ctx, path, "modified").await.ok();
+
+        Ok(())
+    }
+
+    /// Get the automatic safety snapshot policy as JSON `SafetySnapshotPolicy`
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-read")]
+    async fn get_safety_snapshot_policy(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        tracing::debug!("Reading safety snapshot policy (UID {})", caller.uid);
+
+        serde_json::to_string(&crate::hooks::load_policy())
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))
+    }
+
+    /// Set the automatic safety snapshot policy
+    ///
+    /// When enabled, resizing a partition or logical volume with a mounted
+    /// btrfs filesystem, and deleting files from the usage view, first take a
+    /// read-only snapshot of each affected subvolume. Snapshots are pruned
+    /// after `retention_days`.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.btrfs-modify")]
+    async fn set_safety_snapshot_policy(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        policy_json: &str,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_available()?;
+
+        let policy: SafetySnapshotPolicy = serde_json::from_str(policy_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid policy: {}", e)))?;
+        if policy.retention_days == 0 {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "Retention must be at least one day".to_string(),
+            ));
+        }
+        tracing::info!(
+            "Setting safety snapshot policy {:?} (UID {})",
+            policy,
+            caller.uid
+        );
+
+        crate::hooks::save_policy(&policy)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save policy: {}", e)))
+    }
+
     /// Signal: Subvolume was modified
     #[zbus(signal)]
     async fn subvolume_changed(
@@ -5,19 +5,41 @@
 //! This module provides D-Bus methods for listing disks, getting disk information,
 //! and monitoring disk hotplug events.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use storage_macros::authorized_interface;
-use storage_types::SmartSelfTestKind;
+use storage_types::{
+    DiskHealthSummary, DriveComparison, QueueTuning, SelfTestSchedule, SmartBackendKind,
+    SmartSample, SmartSelfTestKind, TemperatureThresholds, TrimResult,
+    enclosure::identify_duration, mounted_used_bytes, virtualization::trim_targets,
+};
 use zbus::message::Header as MessageHeader;
 use zbus::{Connection, interface};
 
 use crate::policies::disk::{DisksDomain, DisksPolicy};
+use health::SampleStore;
+use kernel_log::KernelErrorStore;
+use smart::SmartBackends;
 
+pub mod btrfs_errors;
+pub mod diagnostics;
+pub mod health;
 pub mod hotplug;
+pub mod kernel_log;
+pub mod optical;
+pub mod selftest;
+pub mod smart;
+pub mod temperature;
 
 /// D-Bus interface for disk discovery and SMART operations
 pub struct DiskHandler {
     domain: Arc<dyn DisksDomain>,
+    smart: Arc<SmartBackends>,
+    samples: Arc<SampleStore>,
+    kernel_errors: Arc<KernelErrorStore>,
+    /// Latest identify request per device, so an earlier request's timer
+    /// does not stop a later blink early
+    identify: Arc<Mutex<HashMap<String, u64>>>,
 }
 
 impl DiskHandler {
@@ -25,6 +47,10 @@ impl DiskHandler {
     pub fn new() -> Self {
         Self {
             domain: Arc::new(DisksPolicy),
+            smart: Arc::new(SmartBackends::new()),
+            samples: Arc::new(SampleStore::new()),
+            kernel_errors: Arc::new(KernelErrorStore::new()),
+            identify: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -42,7 +68,7 @@ impl DiskHandler {
             })
     }
 
-    async fn list_disks_with_volumes_raw(
+    pub(crate) async fn list_disks_with_volumes_raw(
         &self,
     ) -> zbus::fdo::Result<Vec<(storage_types::DiskInfo, Vec<storage_types::VolumeInfo>)>> {
         let manager = storage_udisks::DiskManager::new().await.map_err(|e| {
@@ -57,6 +83,86 @@ impl DiskHandler {
                 zbus::fdo::Error::Failed(format!("Failed to enumerate drives: {e}"))
             })
     }
+
+    /// UDisks drive ID of `device_path`, the key of per-drive SMART settings
+    ///
+    /// Falls back to the device path for devices UDisks does not list.
+    async fn drive_id(&self, device_path: &str) -> String {
+        self.list_disks_raw()
+            .await
+            .ok()
+            .and_then(|disks| disks.into_iter().find(|d| d.device == device_path))
+            .map(|disk| disk.id)
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| device_path.to_string())
+    }
+
+    /// Device path of the listed disk `device` names, so callers cannot
+    /// point sysfs writes elsewhere
+    async fn disk_device(&self, device: &str) -> zbus::fdo::Result<String> {
+        let device_path = if device.starts_with("/dev/") {
+            device.to_string()
+        } else {
+            format!("/dev/{}", device)
+        };
+        self.list_disks_raw()
+            .await?
+            .into_iter()
+            .map(|disk| disk.device)
+            .find(|path| *path == device_path)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {device}")))
+    }
+
+    /// Health summary of a drive with the SMART data it is based on
+    ///
+    /// Drives without SMART support get an Unknown summary and no data,
+    /// unless the kernel logged errors for them or btrfs counted errors on
+    /// them.
+    pub(crate) async fn health_summary(
+        &self,
+        device_path: &str,
+        drive_id: &str,
+    ) -> zbus::fdo::Result<(DiskHealthSummary, Option<storage_types::SmartInfo>)> {
+        let now = selftest::now();
+        let kernel_errors = self.kernel_errors.errors(device_path);
+        let btrfs_errors = btrfs_errors::btrfs_errors(device_path).await;
+        let info = match self.smart.smart_info(device_path, drive_id).await {
+            Ok(info) => info,
+            Err(zbus::fdo::Error::NotSupported(_)) => {
+                let mut summary = DiskHealthSummary::unknown(device_path);
+                summary.add_kernel_errors(&kernel_errors, now);
+                summary.add_btrfs_errors(&btrfs_errors);
+                return Ok((summary, None));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let samples = self.samples.samples(drive_id);
+        self.samples
+            .record(drive_id, SmartSample::from_info(&info, now));
+        let mut summary = DiskHealthSummary::compute(
+            device_path,
+            &info,
+            &samples,
+            &selftest::history(drive_id),
+            &temperature::config().thresholds(drive_id),
+            now,
+        );
+        summary.add_kernel_errors(&kernel_errors, now);
+        summary.add_btrfs_errors(&btrfs_errors);
+        Ok((summary, Some(info)))
+    }
+
+    /// SMART data of `device_path` from the drive's SMART backend
+    ///
+    /// Each read is kept as a sample for the health summary.
+    async fn smart_info(&self, device_path: &str) -> zbus::fdo::Result<storage_types::SmartInfo> {
+        let drive_id = self.drive_id(device_path).await;
+        let info = self.smart.smart_info(device_path, &drive_id).await?;
+        self.samples
+            .record(&drive_id, SmartSample::from_info(&info, selftest::now()));
+        Ok(info)
+    }
 }
 
 #[interface(name = "org.cosmic.ext.Storage.Service.Disks")]
@@ -82,6 +188,21 @@ impl DiskHandler {
         signal_ctxt: &zbus::object_server::SignalEmitter<'_>,
         device: &str,
     ) -> zbus::Result<()>;
+
+    /// Signal emitted when a disk's temperature crosses one of its thresholds
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda")
+    /// - level: "normal", "warning" or "critical"
+    /// - celsius: Current temperature in degrees Celsius
+    #[zbus(signal)]
+    pub(crate) async fn temperature_alert(
+        signal_ctxt: &zbus::object_server::SignalEmitter<'_>,
+        device: &str,
+        level: &str,
+        celsius: u64,
+    ) -> zbus::Result<()>;
+
     /// List all disks on the system
     ///
     /// Returns a JSON-serialized array of DiskInfo objects.
@@ -369,20 +490,7 @@ impl DiskHandler {
         };
 
         // Get SMART info using the device path
-        let smart_info = storage_udisks::get_smart_info_by_device(&device_path)
-            .await
-            .map_err(|e| {
-                let err_str = e.to_string().to_lowercase();
-                if err_str.contains("not supported") || err_str.contains("device not found") {
-                    tracing::debug!("SMART not supported or device not found for {device}");
-                    zbus::fdo::Error::NotSupported(
-                        "SMART not supported for this device".to_string(),
-                    )
-                } else {
-                    tracing::error!("Failed to get SMART info: {e}");
-                    zbus::fdo::Error::Failed(format!("Failed to get SMART info: {e}"))
-                }
-            })?;
+        let smart_info = self.smart_info(&device_path).await?;
 
         // Convert to storage_types::SmartStatus
         let smart_status = storage_types::SmartStatus {
@@ -445,20 +553,7 @@ impl DiskHandler {
         };
 
         // Get SMART info by device
-        let smart_info = storage_udisks::get_smart_info_by_device(&device_path)
-            .await
-            .map_err(|e| {
-                let err_str = e.to_string().to_lowercase();
-                if err_str.contains("not supported") {
-                    tracing::debug!("SMART not supported for {device}");
-                    zbus::fdo::Error::NotSupported(
-                        "SMART not supported for this device".to_string(),
-                    )
-                } else {
-                    tracing::error!("Failed to get SMART info: {e}");
-                    zbus::fdo::Error::Failed(format!("Failed to get SMART info: {e}"))
-                }
-            })?;
+        let smart_info = self.smart_info(&device_path).await?;
 
         // Convert BTreeMap<String, String> to Vec<SmartAttribute>
         let mut attributes = Vec::new();
@@ -537,6 +632,37 @@ impl DiskHandler {
         Ok(())
     }
 
+    /// Get the disc loaded in an optical drive
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sr0" or "sr0")
+    ///
+    /// Returns: JSON-serialized Option<OpticalMediaInfo>, null when the drive is empty
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn get_optical_media(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting optical media of {device} (UID {})", caller.uid);
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        optical::require_optical_drive(&device_path).await?;
+        let media = optical::media_info(&device_path).await?;
+
+        serde_json::to_string(&media).map_err(|e| {
+            tracing::error!("Failed to serialize optical media: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize optical media: {e}"))
+        })
+    }
+
     /// Power off a drive (external USB drives)
     ///
     /// Args:
@@ -780,7 +906,7 @@ impl DiskHandler {
         })?;
 
         let device_name = device_path.strip_prefix("/dev/").unwrap_or(&device_path);
-        let _disk = disk_volumes
+        let (disk, _) = disk_volumes
             .into_iter()
             .find(|(d, _)| {
                 d.device == device_path
@@ -793,20 +919,17 @@ impl DiskHandler {
                 zbus::fdo::Error::Failed(format!("Device not found: {device}"))
             })?;
 
-        storage_udisks::start_drive_smart_selftest_by_device(&device_path, test_kind)
-            .await
-            .map_err(|e| {
-                let err_str = e.to_string().to_lowercase();
-                if err_str.contains("not supported") {
-                    tracing::debug!("SMART self-test not supported for {device}");
-                    zbus::fdo::Error::NotSupported(
-                        "SMART self-test not supported for this device".to_string(),
-                    )
-                } else {
-                    tracing::error!("Failed to start SMART self-test: {e}");
-                    zbus::fdo::Error::Failed(format!("Failed to start SMART self-test: {e}"))
-                }
-            })?;
+        self.smart
+            .start_selftest(&device_path, &disk.id, test_kind)
+            .await?;
+        selftest::record_started(&disk.id, test_kind, false);
+        selftest::follow(
+            self.smart.clone(),
+            device_path,
+            disk.id,
+            test_kind,
+            Some(caller.uid),
+        );
 
         tracing::info!(
             "SMART {} test started successfully for {}",
@@ -815,4 +938,832 @@ impl DiskHandler {
         );
         Ok(())
     }
+
+    /// Get the SMART backend selection of a disk
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized SmartBackendStatus
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn get_smart_backend(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting SMART backend for {device} (UID {})", caller.uid);
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        let drive_id = self.drive_id(&device_path).await;
+
+        let status = storage_types::SmartBackendStatus {
+            device: device_path,
+            override_backend: smart::load_overrides().get(&drive_id).copied(),
+            active: self.smart.active(&drive_id),
+            available: self.smart.available(),
+        };
+        serde_json::to_string(&status).map_err(|e| {
+            tracing::error!("Failed to serialize SMART backend status: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize SMART backend status: {e}"))
+        })
+    }
+
+    /// Force a SMART backend for a disk, or return it to automatic selection
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    /// - backend: "udisks", "smartctl", or "auto"
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-configure (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-configure")]
+    async fn set_smart_backend(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        backend: String,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Setting SMART backend of {device} to {backend} (UID {})",
+            caller.uid
+        );
+
+        let backend = match backend.as_str() {
+            "" | "auto" => None,
+            name => Some(SmartBackendKind::parse(name).ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs(format!(
+                    "Invalid SMART backend: {name}. Must be 'udisks', 'smartctl' or 'auto'"
+                ))
+            })?),
+        };
+        if let Some(kind) = backend
+            && !self.smart.available().contains(&kind)
+        {
+            return Err(zbus::fdo::Error::NotSupported(format!(
+                "SMART backend {} is not installed",
+                kind.as_str()
+            )));
+        }
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        let drive_id = self.drive_id(&device_path).await;
+        smart::set_override(&drive_id, backend).map_err(|e| {
+            tracing::error!("Failed to save SMART backend override: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to save SMART backend override: {e}"))
+        })
+    }
+
+    /// List the disks behind hardware RAID controllers, read-only
+    ///
+    /// Empty when smartctl is not installed or no controller offers
+    /// pass-through.
+    ///
+    /// Returns: JSON-serialized Vec<PhysicalDevice>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn list_physical_devices(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Listing physical devices (UID {})", caller.uid);
+
+        let devices = if storage_sys::smartctl_available() {
+            tokio::task::spawn_blocking(storage_sys::passthrough_devices)
+                .await
+                .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+                .map_err(|e| {
+                    tracing::error!("Failed to scan for physical devices: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to scan for physical devices: {e}"))
+                })?
+        } else {
+            Vec::new()
+        };
+        serde_json::to_string(&devices).map_err(|e| {
+            tracing::error!("Failed to serialize physical devices: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize physical devices: {e}"))
+        })
+    }
+
+    /// Get the periodic self-test schedule of a disk
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized SelfTestSchedule
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn get_selftest_schedule(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Getting self-test schedule for {device} (UID {})",
+            caller.uid
+        );
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        let schedule = selftest::schedule(&self.drive_id(&device_path).await);
+        serde_json::to_string(&schedule).map_err(|e| {
+            tracing::error!("Failed to serialize self-test schedule: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize self-test schedule: {e}"))
+        })
+    }
+
+    /// Set the periodic self-test schedule of a disk
+    ///
+    /// Scheduled tests only start while the disk is idle and the power
+    /// policy of self-tests allows it (by default, on AC power).
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    /// - schedule_json: JSON-serialized SelfTestSchedule
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-configure (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-configure")]
+    async fn set_selftest_schedule(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        schedule_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let schedule: SelfTestSchedule = serde_json::from_str(&schedule_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid schedule: {e}")))?;
+        if schedule.short_interval_days == Some(0) || schedule.extended_interval_days == Some(0) {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "Self-test intervals must be at least one day".to_string(),
+            ));
+        }
+        tracing::info!(
+            "Setting self-test schedule of {device} to {schedule:?} (UID {})",
+            caller.uid
+        );
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        selftest::set_schedule(&self.drive_id(&device_path).await, schedule).map_err(|e| {
+            tracing::error!("Failed to save self-test schedule: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to save self-test schedule: {e}"))
+        })
+    }
+
+    /// Get the self-tests run on a disk, oldest first
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized Vec<SelfTestRecord>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn get_selftest_history(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Getting self-test history for {device} (UID {})",
+            caller.uid
+        );
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        let history = selftest::history(&self.drive_id(&device_path).await);
+        serde_json::to_string(&history).map_err(|e| {
+            tracing::error!("Failed to serialize self-test history: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize self-test history: {e}"))
+        })
+    }
+
+    /// Get the health score of a disk with the findings behind it
+    ///
+    /// Combines the current SMART data with the persisted samples of the
+    /// drive, including per-week trends of its sector counters, and the
+    /// self-test history.
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized DiskHealthSummary (level Unknown without SMART)
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn get_health_summary(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting health summary for {device} (UID {})", caller.uid);
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        let drive_id = self.drive_id(&device_path).await;
+        let (summary, _) = self.health_summary(&device_path, &drive_id).await?;
+
+        serde_json::to_string(&summary).map_err(|e| {
+            tracing::error!("Failed to serialize health summary: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize health summary: {e}"))
+        })
+    }
+
+    /// Get the device errors the kernel logged for a drive
+    ///
+    /// I/O errors, ATA exceptions and NVMe timeouts since boot, resolved
+    /// to the drive they occurred on, at most the last 100.
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized Vec<KernelDeviceError>, oldest first
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn get_kernel_errors(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting kernel errors for {device} (UID {})", caller.uid);
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        let errors = self.kernel_errors.errors(&device_path);
+
+        serde_json::to_string(&errors).map_err(|e| {
+            tracing::error!("Failed to serialize kernel errors: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize kernel errors: {e}"))
+        })
+    }
+
+    /// Collect a diagnostic report of a failed operation
+    ///
+    /// The device layout, the UDisks properties, SMART data and kernel
+    /// errors of the device involved, the last kernel and UDisks journal
+    /// lines and the service log since shortly before the failure. Serial
+    /// numbers, UUIDs, labels and the user and host names are redacted.
+    ///
+    /// Args:
+    /// - device: Device the operation failed on (e.g., "/dev/sda"), or
+    ///   empty when it involved none
+    /// - since: Unix time of the failure, in seconds
+    /// - summary: Description of the failure written by the app, redacted
+    ///   and included as is
+    ///
+    /// Returns: JSON-serialized ResultPage of a DiagnosticBundle, see
+    /// GetPage
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.diagnostics-collect (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.diagnostics-collect")]
+    async fn collect_diagnostics(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        since: u64,
+        summary: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::info!("Collecting diagnostics for {device:?} (UID {})", caller.uid);
+
+        let device_path = match device.as_str() {
+            "" => None,
+            d if d.starts_with("/dev/") => Some(device.clone()),
+            d => Some(format!("/dev/{}", d)),
+        };
+        let bundle = self
+            .diagnostic_bundle(
+                device_path.as_deref(),
+                since,
+                summary,
+                caller.username.as_deref(),
+            )
+            .await;
+
+        crate::paging::reply(&caller.sender, &bundle)
+    }
+
+    /// Compare key metrics of several disks side by side
+    ///
+    /// Capacity, space used by mounted filesystems, health score,
+    /// temperature, power-on hours and negotiated interface speed, in the
+    /// order of `devices`. Disks without SMART support are listed without
+    /// the SMART-based metrics.
+    ///
+    /// Args:
+    /// - devices: Device identifiers (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized Vec<DriveComparison>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn compare_drives(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        devices: Vec<String>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Comparing drives {devices:?} (UID {})", caller.uid);
+
+        let drives = self.list_disks_with_volumes_raw().await?;
+        let mut comparison = Vec::with_capacity(devices.len());
+        for device in devices {
+            let device_path = if device.starts_with("/dev/") {
+                device.clone()
+            } else {
+                format!("/dev/{}", device)
+            };
+            let Some((disk, volumes)) = drives.iter().find(|(disk, _)| disk.device == device_path)
+            else {
+                return Err(zbus::fdo::Error::InvalidArgs(format!(
+                    "Disk not found: {device_path}"
+                )));
+            };
+
+            let drive_id = if disk.id.is_empty() {
+                device_path.clone()
+            } else {
+                disk.id.clone()
+            };
+            let (summary, info) = self.health_summary(&device_path, &drive_id).await?;
+            let link_device = device_path.clone();
+            let interface =
+                tokio::task::spawn_blocking(move || storage_sys::interface_speed(&link_device))
+                    .await
+                    .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?;
+
+            comparison.push(DriveComparison {
+                device: device_path,
+                model: disk.display_name(),
+                serial: disk.serial.clone(),
+                capacity_bytes: disk.size,
+                used_bytes: mounted_used_bytes(volumes),
+                health_score: info.as_ref().map(|_| summary.score),
+                health_level: summary.level,
+                temperature_c: info.as_ref().and_then(|info| info.temperature_c),
+                power_on_hours: info.as_ref().and_then(|info| info.power_on_hours),
+                interface,
+            });
+        }
+
+        serde_json::to_string(&comparison).map_err(|e| {
+            tracing::error!("Failed to serialize drive comparison: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize drive comparison: {e}"))
+        })
+    }
+
+    /// Get the temperature thresholds of a disk
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized TemperatureThresholds (defaults unless set)
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-read")]
+    async fn get_temperature_thresholds(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Getting temperature thresholds for {device} (UID {})",
+            caller.uid
+        );
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        let thresholds = temperature::config().thresholds(&self.drive_id(&device_path).await);
+        serde_json::to_string(&thresholds).map_err(|e| {
+            tracing::error!("Failed to serialize temperature thresholds: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize temperature thresholds: {e}"))
+        })
+    }
+
+    /// Set the temperature thresholds of a disk
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    /// - thresholds_json: JSON-serialized TemperatureThresholds, or "" for the defaults
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.smart-configure (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.smart-configure")]
+    async fn set_temperature_thresholds(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        thresholds_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let thresholds = if thresholds_json.is_empty() {
+            None
+        } else {
+            let thresholds: TemperatureThresholds = serde_json::from_str(&thresholds_json)
+                .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid thresholds: {e}")))?;
+            if !thresholds.is_valid() {
+                return Err(zbus::fdo::Error::InvalidArgs(
+                    "The warning temperature must be below the critical one".to_string(),
+                ));
+            }
+            Some(thresholds)
+        };
+        tracing::info!(
+            "Setting temperature thresholds of {device} to {thresholds:?} (UID {})",
+            caller.uid
+        );
+
+        let device_path = if device.starts_with("/dev/") {
+            device.clone()
+        } else {
+            format!("/dev/{}", device)
+        };
+        temperature::set_thresholds(&self.drive_id(&device_path).await, thresholds).map_err(|e| {
+            tracing::error!("Failed to save temperature thresholds: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to save temperature thresholds: {e}"))
+        })
+    }
+
+    /// Get the I/O scheduler, readahead and queue depth of a disk
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized QueueSettings
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn get_queue_settings(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting queue settings of {device} (UID {})", caller.uid);
+
+        let device_path = self.disk_device(&device).await?;
+        let settings =
+            tokio::task::spawn_blocking(move || storage_sys::queue_settings(&device_path))
+                .await
+                .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+                .map_err(|e| {
+                    tracing::error!("Failed to read queue settings: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to read queue settings: {e}"))
+                })?;
+        serde_json::to_string(&settings).map_err(|e| {
+            tracing::error!("Failed to serialize queue settings: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize queue settings: {e}"))
+        })
+    }
+
+    /// Set the I/O scheduler, readahead and queue depth of a disk
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    /// - tuning_json: JSON-serialized QueueTuning
+    /// - persistent: Keep the settings in a udev rule, or drop the disk's rule
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-tune (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-tune")]
+    async fn set_queue_tuning(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        tuning_json: String,
+        persistent: bool,
+    ) -> zbus::fdo::Result<()> {
+        let tuning: QueueTuning = serde_json::from_str(&tuning_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid tuning: {e}")))?;
+        if tuning.nr_requests == 0 || tuning.scheduler.is_empty() {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "A scheduler and a queue depth of at least one request are required".to_string(),
+            ));
+        }
+        tracing::info!(
+            "Setting queue tuning of {device} to {tuning:?}, persistent {persistent} (UID {})",
+            caller.uid
+        );
+
+        let device_path = self.disk_device(&device).await?;
+        tokio::task::spawn_blocking(move || {
+            storage_sys::set_queue_tuning(&device_path, &tuning, persistent)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to tune queue: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to tune queue: {e}"))
+        })
+    }
+
+    /// Get the volatile write cache state of a disk
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    ///
+    /// Returns: JSON-serialized WriteCacheStatus
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn get_write_cache(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting write cache of {device} (UID {})", caller.uid);
+
+        let device_path = self.disk_device(&device).await?;
+        let status =
+            tokio::task::spawn_blocking(move || storage_sys::write_cache_status(&device_path))
+                .await
+                .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?;
+        serde_json::to_string(&status).map_err(|e| {
+            tracing::error!("Failed to serialize write cache status: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize write cache status: {e}"))
+        })
+    }
+
+    /// Turn the volatile write cache of a disk on or off
+    ///
+    /// With the cache on, data the drive acknowledged but has not stored yet
+    /// is lost on power failure.
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    /// - enabled: Whether the drive caches writes
+    /// - persistent: Keep the setting in a udev rule, or drop the disk's rule
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-tune (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-tune")]
+    async fn set_write_cache(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        enabled: bool,
+        persistent: bool,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Setting write cache of {device} to {enabled}, persistent {persistent} (UID {})",
+            caller.uid
+        );
+
+        let device_path = self.disk_device(&device).await?;
+        tokio::task::spawn_blocking(move || {
+            storage_sys::set_write_cache(&device_path, enabled, persistent)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to set write cache: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to set write cache: {e}"))
+        })
+    }
+
+    /// Blink the enclosure slot LED of a disk so it can be found
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/sda" or "sda")
+    /// - seconds: How long to blink, at most IDENTIFY_MAX_SECONDS
+    ///
+    /// Returns: JSON-serialized IdentifySupport of the LED used
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-identify (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-identify")]
+    async fn identify_drive(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        seconds: u32,
+    ) -> zbus::fdo::Result<String> {
+        let seconds = identify_duration(seconds);
+        tracing::info!(
+            "Identifying {device} for {seconds} seconds (UID {})",
+            caller.uid
+        );
+
+        let device_path = self.disk_device(&device).await?;
+        let lookup_path = device_path.clone();
+        let support = tokio::task::spawn_blocking(move || {
+            storage_sys::identify_support(&lookup_path)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .ok_or_else(|| {
+            zbus::fdo::Error::NotSupported(format!(
+                "{device} is not in an enclosure with slot LEDs, and ledctl is not installed"
+            ))
+        })?;
+
+        let on_path = device_path.clone();
+        tokio::task::spawn_blocking(move || storage_sys::set_locate(&on_path, true))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to blink slot LED: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to blink slot LED: {e}"))
+            })?;
+
+        let generation = {
+            let mut identify = self.identify.lock().unwrap_or_else(|e| e.into_inner());
+            let generation = identify.entry(device_path.clone()).or_default();
+            *generation += 1;
+            *generation
+        };
+        let identify = self.identify.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(u64::from(seconds))).await;
+            let latest = identify
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&device_path)
+                .copied();
+            if latest != Some(generation) {
+                return;
+            }
+            let off_path = device_path.clone();
+            match tokio::task::spawn_blocking(move || storage_sys::set_locate(&off_path, false))
+                .await
+            {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::warn!("Failed to stop blinking {device_path}: {e}"),
+                Err(e) => tracing::warn!("Failed to stop blinking {device_path}: {e}"),
+            }
+        });
+
+        serde_json::to_string(&support).map_err(|e| {
+            tracing::error!("Failed to serialize identify support: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize identify support: {e}"))
+        })
+    }
+
+    /// Get the hypervisor the system runs under
+    ///
+    /// Returns: Hypervisor identifier as systemd-detect-virt names it (e.g.
+    /// "kvm"), or an empty string on bare metal
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn get_hypervisor(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Detecting hypervisor (UID {})", caller.uid);
+
+        let hypervisor = tokio::task::spawn_blocking(storage_sys::hypervisor)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?;
+        Ok(hypervisor.unwrap_or_default())
+    }
+
+    /// Discard the unused blocks of every mounted filesystem on a disk, so
+    /// a thin virtual disk hands the space back to its host
+    ///
+    /// Args:
+    /// - device: Device identifier (e.g., "/dev/vda" or "vda")
+    ///
+    /// Returns: JSON-serialized Vec<TrimResult>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-trim (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-trim")]
+    async fn trim_filesystems(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::info!("Trimming filesystems of {device} (UID {})", caller.uid);
+
+        let device_path = self.disk_device(&device).await?;
+        let (disk, volumes) = self
+            .list_disks_with_volumes_raw()
+            .await?
+            .into_iter()
+            .find(|(disk, _)| disk.device == device_path)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {device}")))?;
+        if !disk.discard_supported {
+            return Err(zbus::fdo::Error::NotSupported(format!(
+                "{device} does not accept discard requests"
+            )));
+        }
+        let targets = trim_targets(&volumes);
+        if targets.is_empty() {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "{device} has no mounted filesystems to trim"
+            )));
+        }
+
+        let results = tokio::task::spawn_blocking(move || {
+            targets
+                .into_iter()
+                .map(|mount_point| {
+                    let trimmed_bytes = storage_sys::trim_filesystem(&mount_point)?;
+                    Ok(TrimResult {
+                        mount_point,
+                        trimmed_bytes,
+                    })
+                })
+                .collect::<storage_sys::Result<Vec<_>>>()
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to trim filesystems: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to trim filesystems: {e}"))
+        })?;
+        serde_json::to_string(&results).map_err(|e| {
+            tracing::error!("Failed to serialize trim results: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize trim results: {e}"))
+        })
+    }
+
+    /// Read raw bytes of a drive or partition for the sector viewer
+    ///
+    /// The device is opened read-only; at most 64 KiB are read per call.
+    ///
+    /// Args:
+    /// - device: Block device (e.g., "/dev/sda", "sda1")
+    /// - offset: Byte offset to read from
+    /// - length: Bytes to read
+    ///
+    /// Returns: JSON-serialized SectorRange
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-inspect (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-inspect")]
+    async fn read_sectors(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        offset: u64,
+        length: u64,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Reading {length} bytes at {offset} of {device} (UID {})",
+            caller.uid
+        );
+
+        let device_path = if device.starts_with("/dev/") {
+            device
+        } else if device.contains('/') {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Not a device: {device}"
+            )));
+        } else {
+            format!("/dev/{device}")
+        };
+        let range = tokio::task::spawn_blocking(move || {
+            storage_sys::read_sectors(&device_path, offset, length)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to read sectors: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to read sectors: {e}"))
+        })?;
+        serde_json::to_string(&range).map_err(|e| {
+            tracing::error!("Failed to serialize sectors: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize sectors: {e}"))
+        })
+    }
 }
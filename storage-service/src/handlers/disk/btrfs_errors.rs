@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! btrfs error counters for health scoring
+//!
+//! btrfs checksums all data and metadata and counts, per device, the
+//! blocks that failed their checksum or could not be read or written.
+//! These catch silent corruption that SMART misses, so the counters of
+//! the devices on a drive lower its health score until they are reset.
+
+use std::path::Path;
+
+use storage_types::{BtrfsDeviceStats, btrfs_mount_points};
+
+/// Error counters of the btrfs devices on the drive `disk_path`, from all
+/// mounted btrfs filesystems
+pub async fn btrfs_errors(disk_path: &str) -> Vec<BtrfsDeviceStats> {
+    let disk_path = disk_path.to_string();
+    tokio::task::spawn_blocking(move || {
+        let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+            return Vec::new();
+        };
+        let disk_name = disk_path.trim_start_matches("/dev/");
+        btrfs_mount_points(&mounts)
+            .into_iter()
+            .filter_map(
+                |mount_point| match disks_btrfs::device_stats(Path::new(&mount_point)) {
+                    Ok(stats) => Some(stats),
+                    Err(e) => {
+                        tracing::debug!("No device stats for {mount_point}: {e}");
+                        None
+                    }
+                },
+            )
+            .flatten()
+            .filter(|stats| on_disk(disk_name, &stats.device))
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Whether `device` is the disk `disk_name` (e.g. "sda"), one of its
+/// partitions, or a mapping (e.g. LUKS) on top of either
+fn on_disk(disk_name: &str, device: &str) -> bool {
+    let Some(name) = std::fs::canonicalize(device)
+        .ok()
+        .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+    else {
+        return false;
+    };
+    name_on_disk(disk_name, &name)
+}
+
+fn name_on_disk(disk_name: &str, name: &str) -> bool {
+    if name == disk_name
+        || Path::new("/sys/class/block")
+            .join(disk_name)
+            .join(name)
+            .exists()
+    {
+        return true;
+    }
+    std::fs::read_dir(Path::new("/sys/class/block").join(name).join("slaves"))
+        .map(|slaves| {
+            slaves
+                .flatten()
+                .any(|slave| name_on_disk(disk_name, &slave.file_name().to_string_lossy()))
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Diagnostic reports of failed operations
+//!
+//! The service collects what only it can read (the system journal, SMART
+//! data, the kernel errors it kept) so the app can save it for a bug
+//! report. Everything is redacted here, before it is sent over D-Bus.
+
+use storage_types::{DiagnosticBundle, layout_secrets};
+
+use crate::handlers::disk::DiskHandler;
+
+/// Kernel and UDisks journal lines included
+const JOURNAL_LINES: usize = 200;
+
+/// Service log included from this long before the failure, in seconds
+const SERVICE_LOG_LEAD: u64 = 5 * 60;
+
+impl DiskHandler {
+    /// `summary` of a failure with the device layout, the UDisks properties
+    /// and SMART data of `device` and the logs since shortly before
+    /// `since`, redacted
+    ///
+    /// `username` is the user the report is collected for; their name
+    /// appears in mount points and is redacted too.
+    pub(crate) async fn diagnostic_bundle(
+        &self,
+        device: Option<&str>,
+        since: u64,
+        summary: String,
+        username: Option<&str>,
+    ) -> DiagnosticBundle {
+        let mut bundle = DiagnosticBundle::default();
+        bundle.add("summary.txt", Ok(summary));
+
+        let layout = tokio::task::spawn_blocking(storage_sys::device_layout)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|res| res.map_err(|e| e.to_string()));
+        let mut secrets = layout.as_deref().map(layout_secrets).unwrap_or_default();
+        bundle.add("layout.json", layout);
+
+        if let Some(device) = device {
+            let owned = device.to_string();
+            let properties =
+                tokio::task::spawn_blocking(move || storage_sys::udisks_properties(&owned))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|res| res.map_err(|e| e.to_string()));
+            bundle.add("udisks.txt", properties);
+
+            let smart = self.smart_info(device).await.and_then(|info| {
+                serde_json::to_string_pretty(&info)
+                    .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+            });
+            bundle.add("smart.json", smart.map_err(|e| e.to_string()));
+
+            let kernel_errors = serde_json::to_string_pretty(&self.kernel_errors.errors(device))
+                .map_err(|e| e.to_string());
+            bundle.add("kernel-errors.json", kernel_errors);
+        }
+
+        let logs = tokio::task::spawn_blocking(move || {
+            (
+                storage_sys::recent_journal(JOURNAL_LINES),
+                storage_sys::unit_log_since(
+                    storage_sys::diagnostics::SERVICE_UNIT,
+                    since.saturating_sub(SERVICE_LOG_LEAD),
+                ),
+            )
+        })
+        .await;
+        match logs {
+            Ok((journal, service_log)) => {
+                bundle.add("journal.log", journal.map_err(|e| e.to_string()));
+                bundle.add("service.log", service_log.map_err(|e| e.to_string()));
+            }
+            Err(e) => bundle.add("journal.log", Err(e.to_string())),
+        }
+
+        secrets.extend(username.map(str::to_string));
+        secrets.extend(storage_sys::diagnostics::hostname());
+        bundle.redact(&secrets);
+        bundle
+    }
+}
@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! SMART samples for health scoring
+//!
+//! Every SMART read is kept as a [`SmartSample`] so that the health summary
+//! can tell growing sector counts from stable ones, compute their per-week
+//! trends and report the highest recent temperature. Samples are persisted
+//! per drive ID and survive service restarts.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use storage_types::SmartSample;
+
+use super::selftest::{load, save};
+
+/// Persisted samples, by drive ID
+const SAMPLES_PATH: &str = "/var/lib/cosmic-ext-storage/smart-samples.json";
+
+/// Minimum time between two kept samples of a drive, in seconds
+const MIN_SAMPLE_SPACING: u64 = 60 * 60;
+
+/// Samples older than this are dropped, in seconds
+const RETENTION: u64 = 180 * 24 * 60 * 60;
+
+/// SMART samples by drive ID, oldest first
+#[derive(Default)]
+pub struct SampleStore {
+    samples: Mutex<BTreeMap<String, Vec<SmartSample>>>,
+}
+
+impl SampleStore {
+    /// Store holding the persisted samples
+    pub fn new() -> Self {
+        Self {
+            samples: Mutex::new(load(SAMPLES_PATH)),
+        }
+    }
+
+    /// Keep `sample` unless the drive was sampled less than
+    /// [`MIN_SAMPLE_SPACING`] ago
+    pub fn record(&self, drive_id: &str, sample: SmartSample) {
+        let Ok(mut all) = self.samples.lock() else {
+            return;
+        };
+        let samples = all.entry(drive_id.to_string()).or_default();
+        if samples
+            .last()
+            .is_some_and(|last| sample.taken_at < last.taken_at + MIN_SAMPLE_SPACING)
+        {
+            return;
+        }
+
+        let cutoff = sample.taken_at.saturating_sub(RETENTION);
+        samples.retain(|s| s.taken_at >= cutoff);
+        samples.push(sample);
+
+        if let Err(e) = save(SAMPLES_PATH, &*all) {
+            tracing::warn!("Failed to save SMART samples: {e}");
+        }
+    }
+
+    /// Samples of a drive, oldest first
+    pub fn samples(&self, drive_id: &str) -> Vec<SmartSample> {
+        self.samples
+            .lock()
+            .ok()
+            .and_then(|samples| samples.get(drive_id).cloned())
+            .unwrap_or_default()
+    }
+}
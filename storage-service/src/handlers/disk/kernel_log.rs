@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Device errors from the kernel log
+//!
+//! A watcher follows `/dev/kmsg` and keeps the I/O, ATA and NVMe errors the
+//! kernel reports for each drive, so the health summary can count hardware
+//! errors SMART does not record. Errors are kept in memory only; the
+//! watcher reads the whole ring buffer again when the service starts.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use storage_types::KernelDeviceError;
+
+use crate::handlers::disk::DiskHandler;
+
+/// Errors kept per drive; older ones are dropped first
+const MAX_ERRORS_PER_DRIVE: usize = 100;
+
+/// Recent kernel errors by drive device path, oldest first
+#[derive(Default)]
+pub struct KernelErrorStore {
+    errors: Mutex<HashMap<String, VecDeque<KernelDeviceError>>>,
+}
+
+impl KernelErrorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `error` of the drive `device_path`
+    pub fn record(&self, device_path: String, error: KernelDeviceError) {
+        let Ok(mut all) = self.errors.lock() else {
+            return;
+        };
+        let errors = all.entry(device_path).or_default();
+        if errors.len() == MAX_ERRORS_PER_DRIVE {
+            errors.pop_front();
+        }
+        errors.push_back(error);
+    }
+
+    /// Kept errors of a drive, oldest first
+    pub fn errors(&self, device_path: &str) -> Vec<KernelDeviceError> {
+        self.errors
+            .lock()
+            .ok()
+            .and_then(|errors| errors.get(device_path).map(|e| e.iter().cloned().collect()))
+            .unwrap_or_default()
+    }
+}
+
+/// Collect device errors from the kernel log into the handler's store.
+pub(crate) async fn monitor_kernel_log(
+    connection: zbus::Connection,
+    object_path: &str,
+) -> Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, DiskHandler>(object_path)
+        .await?;
+    let store: Arc<KernelErrorStore> = iface_ref.get().await.kernel_errors.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let result = storage_sys::watch_kernel_errors(|device_path, error| {
+            tracing::warn!("Kernel error on {device_path}: {}", error.message);
+            store.record(device_path, error);
+        });
+        if let Err(e) = result {
+            tracing::warn!("Kernel log watcher stopped: {e}");
+        }
+    });
+    Ok(())
+}
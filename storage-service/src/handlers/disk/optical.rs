@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Optical drives
+//!
+//! The loaded disc is inspected with xorriso, which also erases and burns
+//! it for the image operations. UDisks only tells optical drives apart and
+//! whether their disc is blank.
+
+use storage_types::{DiskInfo, OpticalMediaInfo};
+
+/// The optical drive `device_path`, failing for other drives
+pub(crate) async fn require_optical_drive(device_path: &str) -> zbus::fdo::Result<DiskInfo> {
+    let manager = storage_udisks::DiskManager::new().await.map_err(|e| {
+        tracing::error!("Failed to initialize disk manager: {e}");
+        zbus::fdo::Error::Failed(format!("Failed to initialize disk manager: {e}"))
+    })?;
+    let disks = storage_udisks::disk::get_disks(&manager)
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to enumerate disks: {e}")))?;
+
+    let disk = disks
+        .into_iter()
+        .find(|disk| disk.device == device_path)
+        .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {device_path}")))?;
+    if !disk.optical {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "{device_path} is not an optical drive"
+        )));
+    }
+    Ok(disk)
+}
+
+/// Fail unless xorriso is installed
+pub(crate) fn require_xorriso() -> zbus::fdo::Result<()> {
+    if storage_sys::xorriso_available() {
+        Ok(())
+    } else {
+        Err(zbus::fdo::Error::Failed(
+            "xorriso is not installed. Install it with your package manager to erase and burn discs"
+                .to_string(),
+        ))
+    }
+}
+
+/// The disc in the optical drive `device_path`, `None` when it is empty
+pub(crate) async fn media_info(device_path: &str) -> zbus::fdo::Result<Option<OpticalMediaInfo>> {
+    require_xorriso()?;
+    let device = device_path.to_string();
+    tokio::task::spawn_blocking(move || storage_sys::optical_media_info(&device))
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to read the disc in {device_path}: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to read the disc: {e}"))
+        })
+}
@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Scheduled SMART self-tests
+//!
+//! Drives with a [`SelfTestSchedule`] get their due short or extended
+//! self-test started by a timer loop, but only while the power policy of
+//! self-tests allows it (by default, on AC power) and the drive has no I/O
+//! in flight. Every self-test, scheduled or started by the user, is kept in
+//! a per-drive history together with the status the drive reported once it
+//! finished. A running self-test is listed with the running operations and
+//! can be aborted from there.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use storage_contracts::OperationKind;
+use storage_types::{
+    ScheduledJob, SelfTestRecord, SelfTestSchedule, SmartSample, SmartSelfTestKind,
+};
+use tokio::sync::Notify;
+
+use crate::handlers::disk::DiskHandler;
+use crate::handlers::disk::smart::SmartBackends;
+use crate::handlers::operations::TrackedOperation;
+
+/// Persisted schedules, by drive ID
+const SCHEDULES_PATH: &str = "/var/lib/cosmic-ext-storage/smart-selftest-schedules.json";
+
+/// Persisted self-test history, by drive ID
+const HISTORY_PATH: &str = "/var/lib/cosmic-ext-storage/smart-selftest-history.json";
+
+/// Records kept per drive
+const HISTORY_LIMIT: usize = 50;
+
+/// Time between two schedule checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A drive is idle when it completed no I/O over this period
+const IDLE_PROBE: Duration = Duration::from_secs(30);
+
+/// Time between two looks at a running self-test
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+pub(crate) fn load<T: DeserializeOwned + Default>(path: &str) -> T {
+    match std::fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid {path}: {e}");
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+pub(crate) fn save<T: Serialize>(path: &str, value: &T) -> std::io::Result<()> {
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Seconds since epoch
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Self-test schedule of a drive
+pub fn schedule(drive_id: &str) -> SelfTestSchedule {
+    load::<BTreeMap<String, SelfTestSchedule>>(SCHEDULES_PATH)
+        .get(drive_id)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Replace the self-test schedule of a drive
+pub fn set_schedule(drive_id: &str, schedule: SelfTestSchedule) -> std::io::Result<()> {
+    let mut schedules: BTreeMap<String, SelfTestSchedule> = load(SCHEDULES_PATH);
+    if schedule.is_enabled() {
+        schedules.insert(drive_id.to_string(), schedule);
+    } else {
+        schedules.remove(drive_id);
+    }
+    save(SCHEDULES_PATH, &schedules)
+}
+
+/// Self-test history of a drive, oldest first
+pub fn history(drive_id: &str) -> Vec<SelfTestRecord> {
+    load::<BTreeMap<String, Vec<SelfTestRecord>>>(HISTORY_PATH)
+        .remove(drive_id)
+        .unwrap_or_default()
+}
+
+/// Record a self-test just started on a drive
+pub fn record_started(drive_id: &str, kind: SmartSelfTestKind, scheduled: bool) {
+    let mut all: BTreeMap<String, Vec<SelfTestRecord>> = load(HISTORY_PATH);
+    let records = all.entry(drive_id.to_string()).or_default();
+    records.push(SelfTestRecord {
+        kind,
+        started_at: now(),
+        scheduled,
+        result: None,
+    });
+    if records.len() > HISTORY_LIMIT {
+        records.drain(..records.len() - HISTORY_LIMIT);
+    }
+
+    if let Err(e) = save(HISTORY_PATH, &all) {
+        tracing::warn!("Failed to save self-test history: {e}");
+    }
+}
+
+/// Store `result` on the unfinished records of a drive
+fn record_finished(drive_id: &str, result: &str) {
+    let mut all: BTreeMap<String, Vec<SelfTestRecord>> = load(HISTORY_PATH);
+    let Some(records) = all.get_mut(drive_id) else {
+        return;
+    };
+    let mut unfinished = records.iter_mut().filter(|r| r.result.is_none()).peekable();
+    if unfinished.peek().is_none() {
+        return;
+    }
+    for record in unfinished {
+        record.result = Some(result.to_string());
+    }
+
+    if let Err(e) = save(HISTORY_PATH, &all) {
+        tracing::warn!("Failed to save self-test history: {e}");
+    }
+}
+
+fn is_running(selftest_status: &str) -> bool {
+    let status = selftest_status.to_lowercase();
+    status.contains("progress") || status.contains("running")
+}
+
+/// List the self-test just started on `device` with the running operations
+/// until the drive no longer reports it in progress, aborting it when the
+/// operation is cancelled; `owner` started it, or None for a scheduled test
+pub(crate) fn follow(
+    smart: Arc<SmartBackends>,
+    device: String,
+    drive_id: String,
+    kind: SmartSelfTestKind,
+    owner: Option<u32>,
+) {
+    let mut operation = TrackedOperation::begin(
+        OperationKind::SmartSelfTest,
+        &device,
+        &format!("self_test_{}", kind.as_udisks_str()),
+        owner,
+    );
+    let abort = Arc::new(Notify::new());
+    operation.on_cancel({
+        let abort = abort.clone();
+        move || abort.notify_one()
+    });
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                _ = abort.notified() => {
+                    match smart.abort_selftest(&device, &drive_id).await {
+                        Ok(()) => {
+                            tracing::info!("Aborted SMART self-test on {device}");
+                            record_finished(&drive_id, "Aborted");
+                            operation.finish(Err("Self-test aborted".to_string()));
+                            return;
+                        }
+                        Err(e) => tracing::warn!("Failed to abort self-test on {device}: {e}"),
+                    }
+                }
+            }
+
+            match smart.smart_info(&device, &drive_id).await {
+                Ok(info) if info.selftest_status.as_deref().is_some_and(is_running) => {}
+                Ok(info) => {
+                    record_finished(&drive_id, &info.selftest_status.unwrap_or_default());
+                    operation.finish(Ok(()));
+                    return;
+                }
+                Err(e) => {
+                    operation.finish(Err(format!("Lost track of the self-test: {e}")));
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Completed I/O counters and in-flight requests from `/sys/block/<name>/stat`
+fn io_stat(device: &str) -> Option<(u64, u64, u64)> {
+    let name = device.strip_prefix("/dev/").unwrap_or(device);
+    let stat = std::fs::read_to_string(Path::new("/sys/block").join(name).join("stat")).ok()?;
+    let fields: Vec<u64> = stat
+        .split_whitespace()
+        .filter_map(|field| field.parse().ok())
+        .collect();
+    // reads completed, writes completed, I/Os in flight
+    Some((*fields.first()?, *fields.get(4)?, *fields.get(8)?))
+}
+
+async fn drive_is_idle(device: &str) -> bool {
+    let Some(before) = io_stat(device) else {
+        return false;
+    };
+    tokio::time::sleep(IDLE_PROBE).await;
+    let Some(after) = io_stat(device) else {
+        return false;
+    };
+    before == after && after.2 == 0
+}
+
+/// Start due self-tests on scheduled drives and record finished ones.
+pub(crate) async fn run_self_test_scheduler(
+    connection: zbus::Connection,
+    object_path: &str,
+) -> Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, DiskHandler>(object_path)
+        .await?;
+
+    tokio::spawn(async move {
+        loop {
+            crate::scheduler::wait(CHECK_INTERVAL).await;
+
+            let schedules: BTreeMap<String, SelfTestSchedule> = load(SCHEDULES_PATH);
+            let histories: BTreeMap<String, Vec<SelfTestRecord>> = load(HISTORY_PATH);
+            let unfinished = |drive_id: &str| {
+                histories
+                    .get(drive_id)
+                    .is_some_and(|records| records.iter().any(|r| r.result.is_none()))
+            };
+            if schedules.is_empty() && !histories.keys().any(|id| unfinished(id)) {
+                continue;
+            }
+            let smart = iface_ref.get().await.smart.clone();
+            let samples = iface_ref.get().await.samples.clone();
+            let disks = match iface_ref.get().await.list_disks_raw().await {
+                Ok(disks) => disks,
+                Err(e) => {
+                    tracing::warn!("Self-test scheduler could not list disks: {e}");
+                    continue;
+                }
+            };
+
+            for disk in disks {
+                let schedule = schedules.get(&disk.id);
+                if schedule.is_none() && !unfinished(&disk.id) {
+                    continue;
+                }
+
+                let status = match smart.smart_info(&disk.device, &disk.id).await {
+                    Ok(info) => {
+                        samples.record(&disk.id, SmartSample::from_info(&info, now()));
+                        info.selftest_status.unwrap_or_default()
+                    }
+                    Err(e) => {
+                        tracing::debug!("No SMART data for {}: {e}", disk.device);
+                        continue;
+                    }
+                };
+                if is_running(&status) {
+                    continue;
+                }
+                record_finished(&disk.id, &status);
+
+                let Some(kind) = schedule.and_then(|s| s.due(&history(&disk.id), now())) else {
+                    continue;
+                };
+                if !crate::scheduler::may_run(
+                    ScheduledJob::SelfTest,
+                    &disk.id,
+                    &disk.display_name(),
+                )
+                .await
+                {
+                    continue;
+                }
+                if !drive_is_idle(&disk.device).await {
+                    tracing::debug!("Deferring self-test of {}: drive busy", disk.device);
+                    continue;
+                }
+
+                match smart.start_selftest(&disk.device, &disk.id, kind).await {
+                    Ok(()) => {
+                        tracing::info!(
+                            "Started scheduled {} self-test on {}",
+                            kind.as_udisks_str(),
+                            disk.device
+                        );
+                        record_started(&disk.id, kind, true);
+                        follow(
+                            smart.clone(),
+                            disk.device.clone(),
+                            disk.id.clone(),
+                            kind,
+                            None,
+                        );
+                    }
+                    Err(e) => tracing::warn!(
+                        "Failed to start scheduled self-test on {}: {e}",
+                        disk.device
+                    ),
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
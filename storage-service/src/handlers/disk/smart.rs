@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! SMART backends
+//!
+//! SMART data can come from UDisks or from `smartctl --json`. Each drive is
+//! queried through the backends in [`SmartBackendKind::ALL`] order and
+//! remembers the first one that answered; a per-drive override, keyed by the
+//! UDisks drive ID, pins a single backend instead.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use storage_types::{SmartBackendKind, SmartInfo, SmartSelfTestKind};
+
+/// Persisted per-drive backend overrides
+const OVERRIDES_PATH: &str = "/var/lib/cosmic-ext-storage/smart-backends.json";
+
+/// A source of SMART data
+#[async_trait]
+pub trait SmartBackend: Send + Sync {
+    fn kind(&self) -> SmartBackendKind;
+
+    /// Whether the backend is installed on this system
+    fn available(&self) -> bool;
+
+    /// Read SMART data; `NotSupported` lets the next backend try
+    async fn smart_info(&self, device: &str) -> zbus::fdo::Result<SmartInfo>;
+
+    async fn start_selftest(&self, device: &str, kind: SmartSelfTestKind) -> zbus::fdo::Result<()>;
+
+    async fn abort_selftest(&self, device: &str) -> zbus::fdo::Result<()>;
+}
+
+/// SMART through the UDisks2 ATA and NVMe interfaces
+pub struct UDisksSmart;
+
+/// SMART through `smartctl --json`
+pub struct SmartctlJson;
+
+fn not_supported(message: impl std::fmt::Display) -> zbus::fdo::Error {
+    zbus::fdo::Error::NotSupported(format!("SMART not supported for this device: {message}"))
+}
+
+#[async_trait]
+impl SmartBackend for UDisksSmart {
+    fn kind(&self) -> SmartBackendKind {
+        SmartBackendKind::UDisks
+    }
+
+    fn available(&self) -> bool {
+        true
+    }
+
+    async fn smart_info(&self, device: &str) -> zbus::fdo::Result<SmartInfo> {
+        storage_udisks::get_smart_info_by_device(device)
+            .await
+            .map_err(|e| {
+                let err_str = e.to_string().to_lowercase();
+                if err_str.contains("not supported") || err_str.contains("device not found") {
+                    not_supported(e)
+                } else {
+                    tracing::error!("Failed to get SMART info: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to get SMART info: {e}"))
+                }
+            })
+    }
+
+    async fn start_selftest(&self, device: &str, kind: SmartSelfTestKind) -> zbus::fdo::Result<()> {
+        storage_udisks::start_drive_smart_selftest_by_device(device, kind)
+            .await
+            .map_err(|e| {
+                if e.to_string().to_lowercase().contains("not supported") {
+                    not_supported(e)
+                } else {
+                    tracing::error!("Failed to start SMART self-test: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to start SMART self-test: {e}"))
+                }
+            })
+    }
+
+    async fn abort_selftest(&self, device: &str) -> zbus::fdo::Result<()> {
+        storage_udisks::abort_drive_smart_selftest_by_device(device)
+            .await
+            .map_err(|e| {
+                if e.to_string().to_lowercase().contains("not supported") {
+                    not_supported(e)
+                } else {
+                    tracing::error!("Failed to abort SMART self-test: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to abort SMART self-test: {e}"))
+                }
+            })
+    }
+}
+
+#[async_trait]
+impl SmartBackend for SmartctlJson {
+    fn kind(&self) -> SmartBackendKind {
+        SmartBackendKind::Smartctl
+    }
+
+    fn available(&self) -> bool {
+        storage_sys::smartctl_available()
+    }
+
+    async fn smart_info(&self, device: &str) -> zbus::fdo::Result<SmartInfo> {
+        let device = device.to_string();
+        tokio::task::spawn_blocking(move || storage_sys::smartctl_info(&device))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(not_supported)
+    }
+
+    async fn start_selftest(&self, device: &str, kind: SmartSelfTestKind) -> zbus::fdo::Result<()> {
+        let device = device.to_string();
+        tokio::task::spawn_blocking(move || storage_sys::smartctl_start_selftest(&device, kind))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(not_supported)
+    }
+
+    async fn abort_selftest(&self, device: &str) -> zbus::fdo::Result<()> {
+        let device = device.to_string();
+        tokio::task::spawn_blocking(move || storage_sys::smartctl_abort_selftest(&device))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(not_supported)
+    }
+}
+
+/// All SMART backends with per-drive selection
+pub struct SmartBackends {
+    backends: Vec<Box<dyn SmartBackend>>,
+    /// Backend that last answered, by drive ID
+    active: Mutex<HashMap<String, SmartBackendKind>>,
+}
+
+impl SmartBackends {
+    pub fn new() -> Self {
+        Self {
+            backends: vec![Box::new(UDisksSmart), Box::new(SmartctlJson)],
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Installed backends
+    pub fn available(&self) -> Vec<SmartBackendKind> {
+        self.backends
+            .iter()
+            .filter(|backend| backend.available())
+            .map(|backend| backend.kind())
+            .collect()
+    }
+
+    /// Backend that last returned SMART data for `drive_id`
+    pub fn active(&self, drive_id: &str) -> Option<SmartBackendKind> {
+        self.active.lock().ok()?.get(drive_id).copied()
+    }
+
+    /// Backends to try for a drive: the override alone, or the last working
+    /// backend followed by the others
+    fn candidates(&self, drive_id: &str) -> Vec<&dyn SmartBackend> {
+        let order: Vec<SmartBackendKind> = match load_overrides().get(drive_id) {
+            Some(kind) => vec![*kind],
+            None => {
+                let mut order = SmartBackendKind::ALL.to_vec();
+                if let Some(active) = self.active(drive_id) {
+                    order.retain(|kind| *kind != active);
+                    order.insert(0, active);
+                }
+                order
+            }
+        };
+
+        order
+            .into_iter()
+            .filter_map(|kind| self.backends.iter().find(|b| b.kind() == kind))
+            .filter(|backend| backend.available())
+            .map(|backend| backend.as_ref())
+            .collect()
+    }
+
+    /// Read SMART data of `device` through the first backend that supports it
+    pub async fn smart_info(&self, device: &str, drive_id: &str) -> zbus::fdo::Result<SmartInfo> {
+        let mut last_error = not_supported("no SMART backend available");
+        for backend in self.candidates(drive_id) {
+            match backend.smart_info(device).await {
+                Ok(info) => {
+                    if let Ok(mut active) = self.active.lock() {
+                        active.insert(drive_id.to_string(), backend.kind());
+                    }
+                    return Ok(info);
+                }
+                Err(e @ zbus::fdo::Error::NotSupported(_)) => {
+                    tracing::debug!(
+                        "{} has no SMART data for {device}: {e}",
+                        backend.kind().as_str()
+                    );
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Start a self-test on `device` through the first backend that supports it
+    pub async fn start_selftest(
+        &self,
+        device: &str,
+        drive_id: &str,
+        kind: SmartSelfTestKind,
+    ) -> zbus::fdo::Result<()> {
+        let mut last_error = not_supported("no SMART backend available");
+        for backend in self.candidates(drive_id) {
+            match backend.start_selftest(device, kind).await {
+                Ok(()) => return Ok(()),
+                Err(e @ zbus::fdo::Error::NotSupported(_)) => last_error = e,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Abort the self-test running on `device` through the first backend
+    /// that supports it
+    pub async fn abort_selftest(&self, device: &str, drive_id: &str) -> zbus::fdo::Result<()> {
+        let mut last_error = not_supported("no SMART backend available");
+        for backend in self.candidates(drive_id) {
+            match backend.abort_selftest(device).await {
+                Ok(()) => return Ok(()),
+                Err(e @ zbus::fdo::Error::NotSupported(_)) => last_error = e,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Per-drive backend overrides, by drive ID
+pub fn load_overrides() -> BTreeMap<String, SmartBackendKind> {
+    match std::fs::read_to_string(OVERRIDES_PATH) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid SMART backend overrides: {e}");
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+/// Pin `drive_id` to `backend`, or return it to automatic selection with `None`
+pub fn set_override(drive_id: &str, backend: Option<SmartBackendKind>) -> std::io::Result<()> {
+    let mut overrides = load_overrides();
+    match backend {
+        Some(backend) => overrides.insert(drive_id.to_string(), backend),
+        None => overrides.remove(drive_id),
+    };
+
+    let path = Path::new(OVERRIDES_PATH);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&overrides).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
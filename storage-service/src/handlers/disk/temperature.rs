@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Drive temperature monitoring
+//!
+//! Polls the temperature of every drive with SMART data and emits
+//! `TemperatureAlert` when it crosses the drive's warning or critical
+//! threshold, or falls back below them. Thresholds are kept per drive ID.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Result;
+use storage_types::{SmartSample, TemperatureConfig, TemperatureLevel, TemperatureThresholds};
+
+use super::selftest::{load, now, save};
+use crate::handlers::disk::DiskHandler;
+
+/// Persisted thresholds, by drive ID
+const THRESHOLDS_PATH: &str = "/var/lib/cosmic-ext-storage/temperature-thresholds.json";
+
+/// Time between two temperature reads
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Temperature thresholds of all drives
+pub fn config() -> TemperatureConfig {
+    load(THRESHOLDS_PATH)
+}
+
+/// Replace the thresholds of a drive, or return it to the defaults with `None`
+pub fn set_thresholds(
+    drive_id: &str,
+    thresholds: Option<TemperatureThresholds>,
+) -> std::io::Result<()> {
+    let mut config = config();
+    match thresholds {
+        Some(thresholds) => config.drives.insert(drive_id.to_string(), thresholds),
+        None => config.drives.remove(drive_id),
+    };
+    save(THRESHOLDS_PATH, &config)
+}
+
+/// Emit temperature alerts when drives cross their thresholds.
+pub(crate) async fn monitor_temperatures(
+    connection: zbus::Connection,
+    object_path: &str,
+) -> Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, DiskHandler>(object_path)
+        .await?;
+
+    tokio::spawn(async move {
+        let mut known: HashMap<String, TemperatureLevel> = HashMap::new();
+        loop {
+            let smart = iface_ref.get().await.smart.clone();
+            let samples = iface_ref.get().await.samples.clone();
+            let disks = match iface_ref.get().await.list_disks_raw().await {
+                Ok(disks) => disks,
+                Err(e) => {
+                    tracing::warn!("Temperature monitor could not list disks: {e}");
+                    Vec::new()
+                }
+            };
+            let config = config();
+
+            for disk in disks.iter().filter(|d| !d.is_loop && !d.optical) {
+                let Ok(info) = smart.smart_info(&disk.device, &disk.id).await else {
+                    continue;
+                };
+                samples.record(&disk.id, SmartSample::from_info(&info, now()));
+                let Some(celsius) = info.temperature_c else {
+                    continue;
+                };
+
+                let level = config.thresholds(&disk.id).level(celsius);
+                let previous = known
+                    .insert(disk.id.clone(), level)
+                    .unwrap_or(TemperatureLevel::Normal);
+                if level == previous {
+                    continue;
+                }
+
+                tracing::warn!(
+                    "{} temperature {} °C is {}",
+                    disk.device,
+                    celsius,
+                    level.as_str()
+                );
+                if let Err(e) = DiskHandler::temperature_alert(
+                    iface_ref.signal_emitter(),
+                    &disk.device,
+                    level.as_str(),
+                    celsius,
+                )
+                .await
+                {
+                    tracing::error!("Failed to emit temperature_alert signal: {}", e);
+                }
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    tracing::info!("Drive temperature monitoring started");
+    Ok(())
+}
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Free space samples for capacity planning
+//!
+//! The used space of every mounted filesystem is sampled hourly and kept
+//! per filesystem UUID, so that samples follow a filesystem across mount
+//! points and device renames. Forecasts are computed from them on request.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use storage_types::{CapacityForecast, CapacitySample, VolumeInfo};
+
+use super::FilesystemHandler;
+use crate::handlers::disk::selftest::{load, now, save};
+
+/// Persisted samples, by filesystem UUID
+const SAMPLES_PATH: &str = "/var/lib/cosmic-ext-storage/capacity-samples.json";
+
+/// Time between two samples of all filesystems
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Minimum time between two kept samples of a filesystem, in seconds
+const MIN_SAMPLE_SPACING: u64 = 30 * 60;
+
+/// Samples older than this are dropped, in seconds
+const RETENTION: u64 = 90 * 24 * 60 * 60;
+
+/// A mounted filesystem and the key its samples are kept under
+pub(super) struct MountedFilesystem {
+    pub(super) key: String,
+    pub(super) device: String,
+    pub(super) mount_point: String,
+}
+
+/// Capacity samples by filesystem UUID, oldest first
+#[derive(Default)]
+pub struct CapacityStore {
+    samples: Mutex<BTreeMap<String, Vec<CapacitySample>>>,
+}
+
+impl CapacityStore {
+    /// Store holding the persisted samples
+    pub fn new() -> Self {
+        Self {
+            samples: Mutex::new(load(SAMPLES_PATH)),
+        }
+    }
+
+    /// Keep `sample` unless the filesystem was sampled less than
+    /// [`MIN_SAMPLE_SPACING`] ago
+    fn record(&self, key: &str, sample: CapacitySample) {
+        let Ok(mut all) = self.samples.lock() else {
+            return;
+        };
+        let samples = all.entry(key.to_string()).or_default();
+        if samples
+            .last()
+            .is_some_and(|last| sample.taken_at < last.taken_at + MIN_SAMPLE_SPACING)
+        {
+            return;
+        }
+
+        let cutoff = sample.taken_at.saturating_sub(RETENTION);
+        samples.retain(|s| s.taken_at >= cutoff);
+        samples.push(sample);
+
+        if let Err(e) = save(SAMPLES_PATH, &*all) {
+            tracing::warn!("Failed to save capacity samples: {e}");
+        }
+    }
+
+    fn samples(&self, key: &str) -> Vec<CapacitySample> {
+        self.samples
+            .lock()
+            .ok()
+            .and_then(|samples| samples.get(key).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Forecasts of the mounted filesystems with enough samples for a trend
+    pub async fn forecasts(&self) -> Result<Vec<CapacityForecast>> {
+        Ok(mounted_filesystems()
+            .await?
+            .iter()
+            .filter_map(|fs| {
+                CapacityForecast::from_samples(&fs.device, &fs.mount_point, &self.samples(&fs.key))
+            })
+            .collect())
+    }
+
+    /// Sample the used space of every mounted filesystem
+    async fn sample_all(&self) -> Result<()> {
+        for fs in mounted_filesystems().await? {
+            let (size, available) = match space(&fs.mount_point).await {
+                Ok(space) => space,
+                Err(e) => {
+                    tracing::debug!("Could not sample {}: {e}", fs.mount_point);
+                    continue;
+                }
+            };
+            // Space reserved for root counts as used, as users cannot fill it
+            let used = size.saturating_sub(available);
+            self.record(
+                &fs.key,
+                CapacitySample {
+                    taken_at: now(),
+                    used,
+                    size,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Size of the filesystem mounted at `mount_point` and the bytes available
+/// to unprivileged users, in bytes
+pub(super) async fn space(mount_point: &str) -> Result<(u64, u64)> {
+    let mount_point = mount_point.to_string();
+    let stats =
+        tokio::task::spawn_blocking(move || nix::sys::statvfs::statvfs(mount_point.as_str()))
+            .await??;
+    let block_size = stats.fragment_size();
+    Ok((
+        stats.blocks() * block_size,
+        stats.blocks_available() * block_size,
+    ))
+}
+
+/// Mounted filesystems of all drives
+pub(super) async fn mounted_filesystems() -> Result<Vec<MountedFilesystem>> {
+    fn collect(volumes: &[VolumeInfo], out: &mut Vec<(String, String)>) {
+        for volume in volumes {
+            if volume.has_filesystem
+                && let (Some(device), Some(mount_point)) =
+                    (&volume.device_path, volume.mount_points.first())
+            {
+                out.push((device.clone(), mount_point.clone()));
+            }
+            collect(&volume.children, out);
+        }
+    }
+
+    let manager = storage_udisks::DiskManager::new().await?;
+    let drives = storage_udisks::disk::get_disks_with_volumes(&manager).await?;
+    let mut mounted = Vec::new();
+    for (_disk, volumes) in &drives {
+        collect(volumes, &mut mounted);
+    }
+
+    let mut filesystems = Vec::new();
+    for (device, mount_point) in mounted {
+        let key = storage_udisks::get_filesystem_uuid(&device)
+            .await
+            .ok()
+            .filter(|uuid| !uuid.is_empty())
+            .unwrap_or_else(|| device.clone());
+        filesystems.push(MountedFilesystem {
+            key,
+            device,
+            mount_point,
+        });
+    }
+    Ok(filesystems)
+}
+
+/// Start sampling the used space of mounted filesystems
+pub(crate) async fn monitor_capacity(
+    connection: zbus::Connection,
+    object_path: &str,
+) -> Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, FilesystemHandler>(object_path)
+        .await?;
+
+    tokio::spawn(async move {
+        loop {
+            let capacity = iface_ref.get().await.capacity.clone();
+            if let Err(e) = capacity.sample_all().await {
+                tracing::warn!("Capacity sampling failed: {e}");
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    tracing::info!("Filesystem capacity sampling started");
+    Ok(())
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+
+/// A mounted filesystem and a defragmentation target inside it
+pub(super) struct DefragTarget {
+    pub fs_type: String,
+    pub mount_point: PathBuf,
+    pub target: PathBuf,
+}
+
+/// Resolve the filesystem on `device` and the path to defragment.
+///
+/// Defragmentation runs online, so the filesystem must be mounted. An empty
+/// `target` selects the whole filesystem; otherwise the canonicalized path
+/// must lie on that mount so a client cannot point the tools elsewhere.
+pub(super) async fn resolve_target(device: &str, target: &str) -> zbus::fdo::Result<DefragTarget> {
+    let fs_type = storage_udisks::get_filesystem_type(device)
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read filesystem type: {e}")))?;
+    if fs_type.is_empty() {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "No filesystem found on {device}"
+        )));
+    }
+
+    let mount_point = storage_udisks::get_mount_point(device)
+        .await
+        .ok()
+        .filter(|mount_point| !mount_point.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!("{device} must be mounted to defragment"))
+        })?;
+
+    let target = if target.is_empty() {
+        mount_point.clone()
+    } else {
+        Path::new(target)
+            .canonicalize()
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid target {target}: {e}")))?
+    };
+    if !target.starts_with(&mount_point) {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "{} is not on {}",
+            target.display(),
+            mount_point.display()
+        )));
+    }
+
+    Ok(DefragTarget {
+        fs_type,
+        mount_point,
+        target,
+    })
+}
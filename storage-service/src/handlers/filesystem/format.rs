@@ -1 +1,161 @@
+// SPDX-License-Identifier: GPL-3.0-only
 
+use std::path::Path;
+
+use storage_types::{FormatOptions, MountOptions};
+
+/// Translate the symbolic filesystem-specific options into mkfs arguments.
+///
+/// Options are first validated against [`storage_types::format_option_schema`],
+/// so only known keys with well-formed values are accepted and nothing the
+/// client sends reaches mkfs verbatim. Options that mkfs cannot express
+/// (btrfs compression) are left in `fs_specific` for [`apply_post_format`].
+pub(super) fn translate_mkfs_args(
+    fs_type: &str,
+    options: &mut FormatOptions,
+) -> zbus::fdo::Result<()> {
+    options
+        .validate(fs_type)
+        .map_err(zbus::fdo::Error::InvalidArgs)?;
+
+    let mut keys: Vec<&String> = options.fs_specific.keys().collect();
+    keys.sort();
+
+    let mut args = Vec::new();
+    for key in keys {
+        let value = options.fs_specific[key].as_str();
+        match (fs_type, key.as_str()) {
+            (_, "features") => {
+                args.push("-O".to_string());
+                args.push(value.to_string());
+            }
+            (_, "reserved_percent") => {
+                args.push("-m".to_string());
+                args.push(value.to_string());
+            }
+            ("xfs", "reflink" | "crc") => {
+                args.push("-m".to_string());
+                args.push(format!("{key}={value}"));
+            }
+            ("btrfs", "csum") => {
+                args.push("--csum".to_string());
+                args.push(value.to_string());
+            }
+            ("vfat", "cluster_size") => {
+                // mkfs.fat takes sectors per cluster
+                let bytes: u64 = value.parse().unwrap_or(512);
+                args.push("-s".to_string());
+                args.push((bytes / 512).to_string());
+            }
+            (_, "cluster_size") => {
+                args.push("-c".to_string());
+                args.push(value.to_string());
+            }
+            // Applied after formatting
+            _ => {}
+        }
+    }
+
+    options.mkfs_args = args;
+    Ok(())
+}
+
+/// Apply options that mkfs cannot set, by briefly mounting the new filesystem.
+pub(super) async fn apply_post_format(
+    device: &str,
+    fs_type: &str,
+    options: &FormatOptions,
+) -> zbus::fdo::Result<()> {
+    if fs_type != "btrfs" {
+        return Ok(());
+    }
+    // Validated against the schema already; the level ("zstd:3") only takes
+    // effect as a mount option, the property stores the algorithm alone.
+    let Some(algorithm) = options
+        .fs_specific
+        .get("compress")
+        .and_then(|value| value.split(':').next())
+    else {
+        return Ok(());
+    };
+
+    let mount_point = storage_udisks::mount_filesystem(device, "", MountOptions::default(), None)
+        .await
+        .map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to mount {device} to set compression: {e}"))
+        })?;
+
+    let result = disks_btrfs::SubvolumeManager::new(&mount_point)
+        .and_then(|manager| manager.set_compression(Path::new(&mount_point), algorithm))
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to set compression: {e}")));
+
+    if let Err(e) = storage_udisks::unmount_filesystem(&mount_point, false).await {
+        tracing::warn!("Failed to unmount {mount_point} after setting compression: {e}");
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mkfs_args(fs_type: &str, fs_specific: &[(&str, &str)]) -> zbus::fdo::Result<Vec<String>> {
+        let mut options = FormatOptions {
+            fs_specific: fs_specific
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        translate_mkfs_args(fs_type, &mut options)?;
+        Ok(options.mkfs_args)
+    }
+
+    #[test]
+    fn translates_options_to_mkfs_args() {
+        assert_eq!(
+            mkfs_args(
+                "ext4",
+                &[("reserved_percent", "0"), ("features", "fast_commit")]
+            )
+            .unwrap(),
+            ["-O", "fast_commit", "-m", "0"]
+        );
+        assert_eq!(
+            mkfs_args("xfs", &[("reflink", "1"), ("crc", "0")]).unwrap(),
+            ["-m", "crc=0", "-m", "reflink=1"]
+        );
+        assert_eq!(
+            mkfs_args("btrfs", &[("csum", "xxhash")]).unwrap(),
+            ["--csum", "xxhash"]
+        );
+        assert_eq!(
+            mkfs_args("vfat", &[("cluster_size", "32768")]).unwrap(),
+            ["-s", "64"]
+        );
+        assert_eq!(
+            mkfs_args("exfat", &[("cluster_size", "131072")]).unwrap(),
+            ["-c", "131072"]
+        );
+    }
+
+    #[test]
+    fn leaves_post_format_options_out_of_mkfs_args() {
+        let mut options = FormatOptions::default();
+        options
+            .fs_specific
+            .insert("compress".to_string(), "zstd:3".to_string());
+        translate_mkfs_args("btrfs", &mut options).unwrap();
+        assert!(options.mkfs_args.is_empty());
+        assert_eq!(options.fs_specific["compress"], "zstd:3");
+    }
+
+    #[test]
+    fn rejects_options_outside_the_schema() {
+        assert!(mkfs_args("ext4", &[("features", "fast_commit,-F")]).is_err());
+        assert!(mkfs_args("ext4", &[("cluster_size", "4096")]).is_err());
+        assert!(mkfs_args("vfat", &[("cluster_size", "3000")]).is_err());
+        assert!(mkfs_args("xfs", &[("reflink", "yes")]).is_err());
+    }
+}
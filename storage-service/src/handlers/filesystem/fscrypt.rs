@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+
+/// A mounted filesystem and a directory on it
+pub(super) struct FscryptTarget {
+    pub fs_type: String,
+    pub mount_point: PathBuf,
+    pub directory: PathBuf,
+}
+
+/// Resolve the filesystem on `device` and `directory` on it.
+///
+/// A directory to be created must not exist yet; its parent is
+/// canonicalized instead. Either way the path must lie on the mount so a
+/// client cannot point fscrypt elsewhere.
+pub(super) async fn resolve_target(
+    device: &str,
+    directory: &str,
+    create: bool,
+) -> zbus::fdo::Result<FscryptTarget> {
+    let fs_type = storage_udisks::get_filesystem_type(device)
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read filesystem type: {e}")))?;
+
+    let mount_point = storage_udisks::get_mount_point(device)
+        .await
+        .ok()
+        .filter(|mount_point| !mount_point.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| zbus::fdo::Error::Failed(format!("{device} is not mounted")))?;
+
+    if directory.is_empty() {
+        return Ok(FscryptTarget {
+            fs_type,
+            mount_point,
+            directory: PathBuf::new(),
+        });
+    }
+
+    let invalid = |e: std::io::Error| {
+        zbus::fdo::Error::InvalidArgs(format!("Invalid directory {directory}: {e}"))
+    };
+    let path = Path::new(directory);
+    let resolved = if create {
+        let name = path
+            .file_name()
+            .filter(|name| *name != "." && *name != "..")
+            .ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs(format!("Invalid directory {directory}"))
+            })?;
+        let parent = path.parent().unwrap_or(Path::new("/"));
+        parent.canonicalize().map_err(invalid)?.join(name)
+    } else {
+        path.canonicalize().map_err(invalid)?
+    };
+    if !resolved.starts_with(&mount_point) || resolved == mount_point {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "{} is not a directory on {}",
+            resolved.display(),
+            mount_point.display()
+        )));
+    }
+
+    Ok(FscryptTarget {
+        fs_type,
+        mount_point,
+        directory: resolved,
+    })
+}
@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Low free space monitoring
+//!
+//! Polls the free space of every mount point with a rule and emits
+//! `LowSpace` once it drops below the rule's thresholds, after running the
+//! rule's action. The warning is repeated only once the filesystem had
+//! recovered in between. Rules are kept per mount point.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use storage_types::{LowSpaceAction, LowSpaceRule, LowSpaceWarning, OperationKind, UsageCategory};
+
+use super::FilesystemHandler;
+use super::capacity::{mounted_filesystems, space};
+use crate::handlers::disk::selftest::{load, save};
+
+/// Persisted rules
+const RULES_PATH: &str = "/var/lib/cosmic-ext-storage/low-space-rules.json";
+
+/// Time between two checks of all rules
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Categories reported after a usage scan
+const LARGEST_CATEGORIES: usize = 3;
+
+/// Configured rules
+pub fn rules() -> Vec<LowSpaceRule> {
+    load(RULES_PATH)
+}
+
+/// Add or replace the rule of `rule.mount_point`; a rule without thresholds
+/// is removed
+pub fn set_rule(rule: LowSpaceRule) -> std::io::Result<()> {
+    let mut rules = rules();
+    rules.retain(|existing| existing.mount_point != rule.mount_point);
+    if rule.is_set() {
+        rules.push(rule);
+    }
+    save(RULES_PATH, &rules)
+}
+
+/// Largest categories of the filesystem mounted at `mount_point`
+async fn largest_categories(mount_point: &str) -> Result<Vec<(UsageCategory, u64)>> {
+    let roots = vec![PathBuf::from(mount_point)];
+    let config = storage_sys::usage::ScanConfig {
+        // Keep a background scan from hogging the machine
+        threads: Some(1),
+        top_files_per_category: 0,
+        // Only totals leave the service, never paths
+        show_all_files: true,
+        ..Default::default()
+    };
+    let priority = crate::priority::load_settings().priority(OperationKind::UsageScan);
+    let mut result = tokio::task::spawn_blocking(move || {
+        let _priority = storage_sys::apply_priority(&priority);
+        storage_sys::usage::scan_paths_with_progress(&roots, &config, None)
+    })
+    .await??;
+
+    result
+        .categories
+        .sort_by(|left, right| right.bytes.cmp(&left.bytes));
+    Ok(result
+        .categories
+        .into_iter()
+        .filter(|entry| entry.bytes > 0)
+        .take(LARGEST_CATEGORIES)
+        .map(|entry| (entry.category, entry.bytes))
+        .collect())
+}
+
+/// Run the action of `rule`, whose filesystem has `available` of `size`
+/// bytes free
+async fn act(rule: &LowSpaceRule, available: u64, size: u64) -> LowSpaceWarning {
+    let mut warning = LowSpaceWarning {
+        mount_point: rule.mount_point.clone(),
+        available,
+        size,
+        action: rule.action,
+        pruned_snapshots: 0,
+        largest: Vec::new(),
+    };
+    match rule.action {
+        LowSpaceAction::Warn => {}
+        LowSpaceAction::Cleanup => {
+            warning.pruned_snapshots =
+                crate::hooks::prune_expired(PathBuf::from(&rule.mount_point)).await;
+            if warning.pruned_snapshots > 0 {
+                tracing::info!(
+                    "Pruned {} expired safety snapshots of {}",
+                    warning.pruned_snapshots,
+                    rule.mount_point
+                );
+            }
+        }
+        LowSpaceAction::UsageScan => match largest_categories(&rule.mount_point).await {
+            Ok(largest) => warning.largest = largest,
+            Err(e) => tracing::warn!("Usage scan of {} failed: {e}", rule.mount_point),
+        },
+    }
+    warning
+}
+
+/// Emit low space warnings when mounted filesystems drop below their rules
+pub(crate) async fn monitor_low_space(
+    connection: zbus::Connection,
+    object_path: &str,
+) -> Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, FilesystemHandler>(object_path)
+        .await?;
+
+    tokio::spawn(async move {
+        let mut low: HashSet<String> = HashSet::new();
+        loop {
+            let rules = rules();
+            let mounted: HashSet<String> = if rules.is_empty() {
+                HashSet::new()
+            } else {
+                match mounted_filesystems().await {
+                    Ok(filesystems) => filesystems.into_iter().map(|fs| fs.mount_point).collect(),
+                    Err(e) => {
+                        tracing::warn!("Low space monitor could not list filesystems: {e}");
+                        HashSet::new()
+                    }
+                }
+            };
+            // Unmounted filesystems start over once mounted again
+            low.retain(|mount_point| mounted.contains(mount_point));
+
+            for rule in rules.iter().filter(|r| mounted.contains(&r.mount_point)) {
+                let (size, available) = match space(&rule.mount_point).await {
+                    Ok(space) => space,
+                    Err(e) => {
+                        tracing::debug!("Could not check {}: {e}", rule.mount_point);
+                        continue;
+                    }
+                };
+                if !rule.is_low(available, size) {
+                    low.remove(&rule.mount_point);
+                    continue;
+                }
+                if !low.insert(rule.mount_point.clone()) {
+                    continue;
+                }
+
+                tracing::warn!(
+                    "{} is low on space: {} of {} bytes free",
+                    rule.mount_point,
+                    available,
+                    size
+                );
+                let warning = act(rule, available, size).await;
+                let json = match serde_json::to_string(&warning) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        tracing::error!("Failed to serialize low space warning: {e}");
+                        continue;
+                    }
+                };
+                if let Err(e) =
+                    FilesystemHandler::low_space(iface_ref.signal_emitter(), &json).await
+                {
+                    tracing::error!("Failed to emit low_space signal: {}", e);
+                }
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    tracing::info!("Low space monitoring started");
+    Ok(())
+}
@@ -5,33 +5,42 @@
 //! This module provides D-Bus methods for managing filesystems,
 //! including formatting, mounting, unmounting, and process management.
 
+pub(crate) mod capacity;
+mod defrag;
 mod format;
+mod fscrypt;
+pub(crate) mod low_space;
 mod mount;
 mod ownership;
 mod query;
-mod support;
+pub(crate) mod support;
 mod usage;
+mod vault;
 
 use std::collections::HashSet;
 use std::fs;
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::mpsc;
 use std::time::Duration;
 use storage_macros::authorized_interface;
 use storage_types::{
-    CheckResult, FilesystemInfo, FilesystemToolInfo, FormatOptions, MountOptions,
-    MountOptionsSettings, UnmountResult, UsageCategory, UsageDeleteFailure, UsageDeleteResult,
-    UsageScanParallelismPreset, UsageScanResult,
+    AutomountUnit, CheckResult, DefragResult, FilesystemInfo, FilesystemToolInfo, FormatOptions,
+    LowSpaceRule, MountFailure, MountLintContext, MountLintSeverity, MountOptions,
+    MountOptionsSettings, OperationKind, UnmountResult, UsageCategory, UsageDeleteFailure,
+    UsageDeleteResult, UsageScanParallelismPreset, UsageScanResult,
 };
 use zbus::message::Header as MessageHeader;
 use zbus::{Connection, interface};
 
+use crate::handlers::filesystem::capacity::CapacityStore;
 use crate::handlers::filesystem::support::fs_permissions::{
     caller_can_unlink, is_owned_tree, path_requires_admin_delete,
 };
-use crate::handlers::filesystem::support::uid_groups::resolve_caller_groups;
-use crate::handlers::filesystem::support::usage_threads::map_parallelism_threads;
+use crate::handlers::filesystem::support::uid_groups::{primary_gid, resolve_caller_groups};
+use crate::handlers::operations::{TrackedOperation, track};
+use crate::hooks::Operation;
 use crate::policies::filesystem::{FilesystemsDomain, FilesystemsPolicy};
 
 /// D-Bus interface for filesystem management operations
@@ -42,6 +51,8 @@ pub struct FilesystemHandler {
     filesystem_tools: Vec<FilesystemToolInfo>,
     /// Domain delegate for feature/capability policies
     domain: Arc<dyn FilesystemsDomain>,
+    /// Used space samples of mounted filesystems
+    capacity: Arc<CapacityStore>,
 }
 
 impl FilesystemHandler {
@@ -58,6 +69,7 @@ impl FilesystemHandler {
             supported_tools,
             filesystem_tools,
             domain,
+            capacity: Arc::new(CapacityStore::new()),
         })
     }
 }
@@ -104,6 +116,37 @@ impl FilesystemHandler {
         estimated_total_bytes: u64,
     ) -> zbus::Result<()>;
 
+    /// Signal emitted during defragmentation with files processed and the
+    /// total if known (0 otherwise)
+    #[zbus(signal)]
+    async fn defrag_progress(
+        signal_ctxt: &zbus::object_server::SignalEmitter<'_>,
+        device: &str,
+        processed: u64,
+        total: u64,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted while migrating a legacy vault with bytes copied and
+    /// the total
+    #[zbus(signal)]
+    async fn vault_migration_progress(
+        signal_ctxt: &zbus::object_server::SignalEmitter<'_>,
+        cipher_dir: &str,
+        copied_bytes: u64,
+        total_bytes: u64,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted when a mounted filesystem drops below its low space
+    /// rule, after the rule's action ran
+    ///
+    /// Args:
+    /// - warning_json: JSON-serialized LowSpaceWarning
+    #[zbus(signal)]
+    async fn low_space(
+        signal_ctxt: &zbus::object_server::SignalEmitter<'_>,
+        warning_json: &str,
+    ) -> zbus::Result<()>;
+
     /// List all filesystems on the system
     ///
     /// Returns: JSON-serialized Vec<FilesystemInfo>
@@ -224,6 +267,36 @@ impl FilesystemHandler {
         Ok(json)
     }
 
+    /// Get on-disk features of a filesystem (ext features, XFS reflink/crc,
+    /// btrfs flags and compression) and which resize operations it supports
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    ///
+    /// Returns: JSON-serialized FilesystemFeatures
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn get_filesystem_features(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Getting filesystem features for {} (UID {})",
+            device,
+            caller.uid
+        );
+
+        let features = query::probe_filesystem_features(&device).await?;
+
+        serde_json::to_string(&features).map_err(|e| {
+            tracing::error!("Failed to serialize filesystem features: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
     /// Format a device with a filesystem
     ///
     /// Args:
@@ -257,15 +330,42 @@ impl FilesystemHandler {
             .require_filesystem_format_support(&fs_type, &self.supported_tools)?;
 
         // Parse options
-        let options: FormatOptions = serde_json::from_str(&options_json).unwrap_or_default();
+        let mut options: FormatOptions = serde_json::from_str(&options_json).unwrap_or_default();
+        format::translate_mkfs_args(&fs_type, &mut options)?;
 
-        // Delegate to storage-udisks operation
-        storage_udisks::format_filesystem(&device, &fs_type, &label, options)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to format device: {e}");
-                zbus::fdo::Error::Failed(format!("Failed to format device: {e}"))
-            })?;
+        let mut operation = TrackedOperation::begin(
+            storage_contracts::OperationKind::Filesystem,
+            &device,
+            "formatting",
+            Some(caller.uid),
+        );
+        // Stopping the UDisks job matters for formats that erase the device
+        operation.on_cancel({
+            let device = device.clone();
+            move || {
+                let device = device.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = storage_udisks::cancel_device_jobs(&device).await {
+                        tracing::warn!("Failed to cancel the format of {device}: {e}");
+                    }
+                });
+            }
+        });
+        let result = async {
+            // Delegate to storage-udisks operation
+            storage_udisks::format_filesystem(&device, &fs_type, &label, options.clone())
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to format device: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to format device: {e}"))
+                })?;
+
+            operation.set_phase("tuning");
+            format::apply_post_format(&device, &fs_type, &options).await
+        }
+        .await;
+        operation.finish(result.as_ref().map_err(ToString::to_string).cloned());
+        result?;
 
         tracing::info!("Successfully formatted {} as {}", device, fs_type);
         let _ = Self::formatted(&signal_ctx, &device, &fs_type).await;
@@ -305,6 +405,30 @@ impl FilesystemHandler {
 
         // Parse options
         let mount_opts: MountOptions = serde_json::from_str(&options_json).unwrap_or_default();
+        mount_opts
+            .check_other()
+            .map_err(zbus::fdo::Error::InvalidArgs)?;
+
+        // Explicit mount points must be inside the caller's media directory;
+        // otherwise apply the caller's mount path policy
+        let username = caller
+            .username
+            .clone()
+            .unwrap_or_else(|| caller.uid.to_string());
+        let policy = mount_opts.path_policy.as_ref().filter(|p| !p.is_default());
+        let mount_point = if !mount_point.is_empty() {
+            mount::check_mount_point(Path::new(&mount_point), &username)?;
+            mount_point
+        } else if let Some(policy) = policy {
+            mount::resolve_policy_mount_point(&device, policy, &username)
+                .await?
+                .to_string_lossy()
+                .to_string()
+        } else {
+            mount_point
+        };
+
+        mount::check_windows_hibernation(&device, &mount_opts).await?;
 
         // Delegate to storage-udisks operation with caller UID
         let actual_mount_point =
@@ -312,6 +436,10 @@ impl FilesystemHandler {
                 .await
                 .map_err(|e| {
                     tracing::error!("Failed to mount filesystem: {e}");
+                    let failure = MountFailure::parse(&e.to_string());
+                    if failure.needs_check() {
+                        tracing::warn!("Filesystem on {device} needs checking ({failure:?})");
+                    }
                     zbus::fdo::Error::Failed(format!("Failed to mount filesystem: {e}"))
                 })?;
 
@@ -320,6 +448,35 @@ impl FilesystemHandler {
         Ok(actual_mount_point)
     }
 
+    /// Whether Windows left the NTFS filesystem on a device hibernated, so
+    /// that it can only be mounted read-only
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    ///
+    /// Returns: JSON-serialized Option<WindowsHibernation>; null for other
+    /// filesystems and when it can't be told
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn get_windows_hibernation(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Probing {device} for Windows hibernation (UID {})",
+            caller.uid
+        );
+
+        let hibernation = mount::windows_hibernation(&device).await;
+        serde_json::to_string(&hibernation).map_err(|e| {
+            tracing::error!("Failed to serialize hibernation state: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
     /// Unmount a filesystem with optional process killing
     ///
     /// Args:
@@ -450,188 +607,1108 @@ impl FilesystemHandler {
 
                         let _ = storage_udisks::kill_processes(&pids);
 
-                        // Wait a moment for processes to die
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                        // Wait a moment for processes to die
+                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+                        // Retry unmount via storage-udisks
+                        match storage_udisks::unmount_filesystem(&device_or_mount, force).await {
+                            Ok(_) => {
+                                tracing::info!("Successfully unmounted after killing processes");
+
+                                let result = UnmountResult {
+                                    success: true,
+                                    error: None,
+                                    blocking_processes: Vec::new(),
+                                };
+
+                                let json = serde_json::to_string(&result).map_err(|e| {
+                                    zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+                                })?;
+
+                                Ok(json)
+                            }
+                            Err(retry_err) => {
+                                tracing::error!(
+                                    "Unmount failed even after killing processes: {}",
+                                    retry_err
+                                );
+
+                                let result = UnmountResult {
+                                    success: false,
+                                    error: Some(retry_err.to_string()),
+                                    blocking_processes: Vec::new(),
+                                };
+
+                                let json = serde_json::to_string(&result).map_err(|e| {
+                                    zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+                                })?;
+
+                                Ok(json)
+                            }
+                        }
+                    } else {
+                        // Return error with blocking processes
+                        let result = UnmountResult {
+                            success: false,
+                            error: Some("Device is busy".to_string()),
+                            blocking_processes: processes,
+                        };
+
+                        let json = serde_json::to_string(&result).map_err(|e| {
+                            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+                        })?;
+
+                        Ok(json)
+                    }
+                } else {
+                    // Other error
+                    tracing::error!("Unmount failed: {}", e);
+
+                    let result = UnmountResult {
+                        success: false,
+                        error: Some(e.to_string()),
+                        blocking_processes: Vec::new(),
+                    };
+
+                    let json = serde_json::to_string(&result).map_err(|e| {
+                        zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+                    })?;
+
+                    Ok(json)
+                }
+            }
+        }
+    }
+
+    /// Get processes blocking unmount of a filesystem
+    ///
+    /// Args:
+    /// - device_or_mount: Device path or mount point
+    ///
+    /// Returns: JSON-serialized ResultPage of a Vec<ProcessInfo>, see GetPage
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn get_blocking_processes(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device_or_mount: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Getting blocking processes for {} (UID {})",
+            device_or_mount,
+            caller.uid
+        );
+
+        // Determine mount point via storage-udisks
+        let mount_point = if device_or_mount.starts_with("/dev/") {
+            storage_udisks::get_mount_point(&device_or_mount)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to get mount point: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to get mount point: {e}"))
+                })?
+        } else {
+            device_or_mount.clone()
+        };
+
+        // Find blocking processes
+        let processes = storage_udisks::find_processes_using_mount(&mount_point)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to find processes: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to find processes: {e}"))
+            })?;
+
+        tracing::debug!("Found {} blocking processes", processes.len());
+
+        crate::paging::reply(&caller.sender, &processes)
+    }
+
+    // Note: Process killing is intentionally only available through Unmount with kill_processes=true
+    // to limit the security surface. A standalone KillProcesses method could be exploited to kill
+    // arbitrary processes. The Unmount workflow (try unmount → get blocking processes → unmount with
+    // kill_processes=true) provides the necessary context and safety.
+
+    /// Check and optionally repair filesystem
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    /// - repair: Whether to repair errors (requires unmount)
+    ///
+    /// Returns: JSON-serialized CheckResult
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn check(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        repair: bool,
+    ) -> zbus::fdo::Result<String> {
+        tracing::info!(
+            "Checking filesystem on {} (repair={}) for UID {}",
+            device,
+            repair,
+            caller.uid
+        );
+
+        self.domain.require_filesystem_check_support()?;
+
+        // Delegate to storage-udisks operation
+        let clean = track(
+            storage_contracts::OperationKind::Filesystem,
+            &device,
+            if repair { "repairing" } else { "checking" },
+            Some(caller.uid),
+            storage_udisks::check_filesystem(&device, repair),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Filesystem check failed: {e}");
+            zbus::fdo::Error::Failed(format!("Filesystem check failed: {e}"))
+        })?;
+
+        let result = CheckResult {
+            device: device.clone(),
+            clean,
+            errors_corrected: if repair && !clean { 1 } else { 0 },
+            errors_uncorrected: 0,
+            output: if clean {
+                "Filesystem is clean".to_string()
+            } else {
+                "Filesystem has errors".to_string()
+            },
+        };
+
+        let json = serde_json::to_string(&result).map_err(|e| {
+            tracing::error!("Failed to serialize result: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })?;
+
+        Ok(json)
+    }
+
+    /// List mounted filesystems the kernel made read-only after an error
+    /// (e.g., ext4 with errors=remount-ro), with the kernel error that caused it
+    ///
+    /// Returns: JSON-serialized Vec<ForcedReadOnly>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn list_forced_read_only(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Listing filesystems forced read-only for UID {}",
+            caller.uid
+        );
+
+        let filesystems = tokio::task::spawn_blocking(storage_sys::forced_read_only_filesystems)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to read the mount table: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to read the mount table: {e}"))
+            })?;
+
+        serde_json::to_string(&filesystems).map_err(|e| {
+            tracing::error!("Failed to serialize read-only filesystems: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Make a filesystem the kernel switched to read-only writable again
+    ///
+    /// Args:
+    /// - mount_point: Mount point of a filesystem listed by ListForcedReadOnly
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn remount_read_write(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mount_point: String,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Remounting {} read-write for UID {}",
+            mount_point,
+            caller.uid
+        );
+
+        tokio::task::spawn_blocking(move || {
+            // Only mounts the kernel made read-only, not ones mounted that way on purpose
+            let forced = storage_sys::forced_read_only_filesystems()?;
+            if !forced.iter().any(|fs| fs.mount_point == mount_point) {
+                return Err(storage_sys::SysError::OperationFailed(format!(
+                    "{mount_point} was not made read-only by the kernel"
+                )));
+            }
+            storage_sys::remount_read_write(Path::new(&mount_point))
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Remount failed: {e}");
+            zbus::fdo::Error::Failed(e.to_string())
+        })
+    }
+
+    /// Bind a directory to a second place
+    ///
+    /// Args:
+    /// - source: Directory to bind
+    /// - target: Where it appears; created when missing, never a system
+    ///   directory such as /usr or /etc
+    /// - read_only: Make the bind read-only
+    /// - persistent: Add an fstab entry so it is mounted at startup
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn create_bind_mount(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        source: String,
+        target: String,
+        read_only: bool,
+        persistent: bool,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Binding {} to {} for UID {}", source, target, caller.uid);
+
+        let (source, target) = (PathBuf::from(source), PathBuf::from(target));
+        if !source.is_absolute() || !target.is_absolute() {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "Bind mount paths must be absolute".to_string(),
+            ));
+        }
+        if crate::protected_paths::is_protected_target(&target) {
+            return Err(zbus::fdo::Error::AccessDenied(format!(
+                "{} is a system directory",
+                target.display()
+            )));
+        }
+
+        tokio::task::spawn_blocking(move || {
+            storage_sys::create_bind_mount(&source, &target, read_only, persistent)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Bind mount failed: {e}");
+            zbus::fdo::Error::Failed(e.to_string())
+        })
+    }
+
+    /// Unmount a bind mount and, with `forget`, remove its fstab entry
+    ///
+    /// Args:
+    /// - target: Where the bind mount appears
+    /// - forget: Also remove the fstab entry, so it is not mounted at startup
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn remove_bind_mount(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        target: String,
+        forget: bool,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Removing bind mount {} for UID {}", target, caller.uid);
+
+        tokio::task::spawn_blocking(move || {
+            storage_sys::remove_bind_mount(Path::new(&target), forget)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Removing bind mount failed: {e}");
+            zbus::fdo::Error::Failed(e.to_string())
+        })
+    }
+
+    /// List the systemd automount units created by this service
+    ///
+    /// Returns: JSON-serialized Vec<AutomountUnit>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn list_automount_units(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Listing automount units for UID {}", caller.uid);
+
+        let units = tokio::task::spawn_blocking(storage_sys::list_automount_units)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to list automount units: {e}");
+                zbus::fdo::Error::Failed(e.to_string())
+            })?;
+
+        serde_json::to_string(&units).map_err(|e| {
+            tracing::error!("Failed to serialize automount units: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Mount a volume or network remote on access through systemd
+    /// `.mount` and `.automount` units instead of an fstab entry
+    ///
+    /// Args:
+    /// - unit_json: JSON-serialized AutomountUnit; its mount point is
+    ///   created when missing, never a system directory such as /usr or /etc
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn create_automount_unit(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        unit_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let unit: AutomountUnit = serde_json::from_str(&unit_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid automount unit: {e}")))?;
+        tracing::info!(
+            "Automounting {} at {} for UID {}",
+            unit.what,
+            unit.mount_point.display(),
+            caller.uid
+        );
+
+        unit.validate().map_err(zbus::fdo::Error::InvalidArgs)?;
+        if crate::protected_paths::is_protected_target(&unit.mount_point) {
+            return Err(zbus::fdo::Error::AccessDenied(format!(
+                "{} is a system directory",
+                unit.mount_point.display()
+            )));
+        }
+
+        tokio::task::spawn_blocking(move || storage_sys::create_automount_unit(&unit))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Creating automount units failed: {e}");
+                zbus::fdo::Error::Failed(e.to_string())
+            })
+    }
+
+    /// Stop and remove the automount units created for a mount point
+    ///
+    /// Args:
+    /// - mount_point: Where the units mount
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn remove_automount_unit(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mount_point: String,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Removing automount units of {} for UID {}",
+            mount_point,
+            caller.uid
+        );
+
+        tokio::task::spawn_blocking(move || {
+            storage_sys::remove_automount_unit(Path::new(&mount_point))
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Removing automount units failed: {e}");
+            zbus::fdo::Error::Failed(e.to_string())
+        })
+    }
+
+    /// List the SELinux labels of mounted filesystems
+    ///
+    /// Returns: JSON-serialized Vec<SelinuxStatus>, empty when SELinux is
+    /// disabled
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn list_selinux_statuses(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Listing SELinux labels for UID {}", caller.uid);
+
+        let statuses = tokio::task::spawn_blocking(storage_sys::selinux_statuses)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to read SELinux labels: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to read SELinux labels: {e}"))
+            })?;
+
+        serde_json::to_string(&statuses).map_err(|e| {
+            tracing::error!("Failed to serialize SELinux labels: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Give the files of a mounted filesystem the SELinux labels the policy
+    /// gives them, with restorecon
+    ///
+    /// Progress is reported as a running operation.
+    ///
+    /// Args:
+    /// - mount_point: Mount point of a filesystem listed by ListSelinuxStatuses
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn relabel(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        mount_point: String,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Relabeling {} for UID {}", mount_point, caller.uid);
+
+        let mut operation = TrackedOperation::begin(
+            storage_contracts::OperationKind::Filesystem,
+            &mount_point,
+            "relabeling",
+            Some(caller.uid),
+        );
+        let (operation, result) = tokio::task::spawn_blocking(move || {
+            let statuses = match storage_sys::selinux_statuses() {
+                Ok(statuses) => statuses,
+                Err(e) => return (operation, Err(e)),
+            };
+            if !statuses
+                .iter()
+                .any(|status| status.mount_point == mount_point)
+            {
+                let e = storage_sys::SysError::OperationFailed(format!(
+                    "{mount_point} is not a mounted filesystem with SELinux labels"
+                ));
+                return (operation, Err(e));
+            }
+            let result = storage_sys::relabel(Path::new(&mount_point), |done, total| {
+                operation.update_count(done, total)
+            });
+            (operation, result)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?;
+
+        operation.finish(result.as_ref().map(|_| ()).map_err(ToString::to_string));
+        result.map_err(|e| {
+            tracing::error!("Relabel failed: {e}");
+            zbus::fdo::Error::Failed(e.to_string())
+        })
+    }
+
+    /// Project when mounted filesystems run full at their current growth
+    ///
+    /// Filesystems sampled for less than a day are left out.
+    ///
+    /// Returns: JSON-serialized Vec<CapacityForecast>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn list_capacity_forecasts(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Listing capacity forecasts for UID {}", caller.uid);
+
+        let forecasts = self.capacity.forecasts().await.map_err(|e| {
+            tracing::error!("Failed to compute capacity forecasts: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to compute capacity forecasts: {e}"))
+        })?;
+
+        serde_json::to_string(&forecasts).map_err(|e| {
+            tracing::error!("Failed to serialize capacity forecasts: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// List the low space rules of mount points
+    ///
+    /// Returns: JSON-serialized Vec<LowSpaceRule>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn list_low_space_rules(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Listing low space rules for UID {}", caller.uid);
+
+        serde_json::to_string(&low_space::rules()).map_err(|e| {
+            tracing::error!("Failed to serialize low space rules: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Set the low space rule of a mount point, replacing its previous one;
+    /// a rule without thresholds is removed
+    ///
+    /// Args:
+    /// - rule_json: JSON-serialized LowSpaceRule
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn set_low_space_rule(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        rule_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let rule: LowSpaceRule = serde_json::from_str(&rule_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid low space rule: {e}")))?;
+        if !Path::new(&rule.mount_point).is_absolute() {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "Mount points must be absolute paths".to_string(),
+            ));
+        }
+        if rule.min_free_percent.is_some_and(|percent| percent > 100) {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "Free space percentages cannot exceed 100".to_string(),
+            ));
+        }
+        tracing::info!("Setting low space rule {rule:?} (UID {})", caller.uid);
+
+        low_space::set_rule(rule).map_err(|e| {
+            tracing::error!("Failed to save low space rule: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to save low space rule: {e}"))
+        })
+    }
+
+    /// Estimate fragmentation of a mounted filesystem or a directory on it
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    /// - target: Directory on the filesystem (empty string for the whole filesystem)
+    ///
+    /// Returns: JSON-serialized FragmentationReport
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn get_fragmentation(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        target: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Estimating fragmentation of {} on {} (UID {})",
+            target,
+            device,
+            caller.uid
+        );
+
+        let resolved = defrag::resolve_target(&device, &target).await?;
+        self.domain.require_defrag_support(&resolved.fs_type)?;
+
+        let report = tokio::task::spawn_blocking(move || {
+            storage_sys::fragmentation_report(&resolved.fs_type, &device, &resolved.target)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Fragmentation task failed: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to estimate fragmentation: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to estimate fragmentation: {e}"))
+        })?;
+
+        serde_json::to_string(&report).map_err(|e| {
+            tracing::error!("Failed to serialize fragmentation report: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Defragment a mounted filesystem or a directory on it
+    ///
+    /// Uses e4defrag (ext2/3/4), xfs_fsr (XFS) or `btrfs filesystem defragment`.
+    /// Emits `defrag_progress` while running.
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    /// - target: Directory on the filesystem (empty string for the whole filesystem)
+    ///
+    /// Returns: JSON-serialized DefragResult
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn defragment(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        device: String,
+        target: String,
+    ) -> zbus::fdo::Result<String> {
+        let resolved = defrag::resolve_target(&device, &target).await?;
+        self.domain.require_defrag_support(&resolved.fs_type)?;
+
+        tracing::info!(
+            "Defragmenting {} on {} ({}, mounted at {}) for UID {}",
+            resolved.target.display(),
+            device,
+            resolved.fs_type,
+            resolved.mount_point.display(),
+            caller.uid
+        );
+
+        let (progress_tx, progress_rx) = mpsc::channel::<(u64, u64)>();
+        let fs_type = resolved.fs_type.clone();
+        let defrag_target = resolved.target.clone();
+        let priority = crate::priority::load_settings().priority(OperationKind::Defragment);
+        let defrag_task = tokio::task::spawn_blocking(move || {
+            let _priority = storage_sys::apply_priority(&priority);
+            storage_sys::defragment(&fs_type, &defrag_target, Some(progress_tx))
+        });
+
+        let mut progress = (0_u64, 0_u64);
+
+        loop {
+            let mut updated = false;
+            while let Ok(update) = progress_rx.try_recv() {
+                progress = update;
+                updated = true;
+            }
+
+            if updated {
+                let _ = Self::defrag_progress(&signal_ctx, &device, progress.0, progress.1).await;
+            }
+
+            if defrag_task.is_finished() {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+
+        let mut result: DefragResult = defrag_task
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Defragmentation join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Defragmentation failed: {e}");
+                zbus::fdo::Error::Failed(format!("Defragmentation failed: {e}"))
+            })?;
+
+        let _ = Self::defrag_progress(
+            &signal_ctx,
+            &device,
+            result.files_processed,
+            result.files_processed,
+        )
+        .await;
+
+        // Re-score so the UI can show the improvement; failure here is not fatal
+        let fs_type = resolved.fs_type.clone();
+        let score_device = device.clone();
+        let score_target = resolved.target.clone();
+        result.score_after = tokio::task::spawn_blocking(move || {
+            storage_sys::fragmentation_report(&fs_type, &score_device, &score_target)
+        })
+        .await
+        .ok()
+        .and_then(|report| report.ok())
+        .and_then(|report| report.score_percent);
+
+        tracing::info!(
+            "Defragmented {} ({} files)",
+            resolved.target.display(),
+            result.files_processed
+        );
+
+        serde_json::to_string(&result).map_err(|e| {
+            tracing::error!("Failed to serialize defrag result: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// List the encrypted directories of a mounted ext4 or f2fs filesystem
+    ///
+    /// Directories are searched a few levels below the mount point.
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    ///
+    /// Returns: JSON-serialized FscryptStatus
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn get_fscrypt_status(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Listing encrypted directories on {} (UID {})",
+            device,
+            caller.uid
+        );
+
+        let resolved = fscrypt::resolve_target(&device, "", false).await?;
+        self.domain.require_fscrypt_support(&resolved.fs_type)?;
+
+        let status =
+            tokio::task::spawn_blocking(move || storage_sys::fscrypt_status(&resolved.mount_point))
+                .await
+                .map_err(|e| zbus::fdo::Error::Failed(format!("fscrypt task failed: {e}")))?
+                .map_err(|e| {
+                    tracing::error!("Failed to list encrypted directories: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to list encrypted directories: {e}"))
+                })?;
+
+        serde_json::to_string(&status).map_err(|e| {
+            tracing::error!("Failed to serialize fscrypt status: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
 
-                        // Retry unmount via storage-udisks
-                        match storage_udisks::unmount_filesystem(&device_or_mount, force).await {
-                            Ok(_) => {
-                                tracing::info!("Successfully unmounted after killing processes");
+    /// Create an encrypted directory owned by the caller
+    ///
+    /// The directory is protected with a passphrase; the filesystem is set
+    /// up for fscrypt first if needed.
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    /// - directory: Path of the new directory on the mounted filesystem
+    /// - protector_name: Name of the new passphrase protector
+    /// - passphrase: Passphrase unlocking the directory
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
+    async fn create_encrypted_directory(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        directory: String,
+        protector_name: String,
+        passphrase: String,
+    ) -> zbus::fdo::Result<()> {
+        let resolved = fscrypt::resolve_target(&device, &directory, true).await?;
+        self.domain.require_fscrypt_support(&resolved.fs_type)?;
+        if passphrase.is_empty() {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "The passphrase must not be empty".to_string(),
+            ));
+        }
+        let user = caller.username.clone().ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!("No user name for UID {}", caller.uid))
+        })?;
+        let gid = primary_gid(caller.uid).unwrap_or(caller.uid);
 
-                                let result = UnmountResult {
-                                    success: true,
-                                    error: None,
-                                    blocking_processes: Vec::new(),
-                                };
+        tracing::info!(
+            "Creating encrypted directory {} on {} for UID {}",
+            resolved.directory.display(),
+            device,
+            caller.uid
+        );
 
-                                let json = serde_json::to_string(&result).map_err(|e| {
-                                    zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
-                                })?;
+        let uid = caller.uid;
+        tokio::task::spawn_blocking(move || {
+            storage_sys::create_encrypted_directory(
+                &resolved.mount_point,
+                &resolved.directory,
+                &protector_name,
+                &passphrase,
+                uid,
+                gid,
+                &user,
+            )
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("fscrypt task failed: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to create encrypted directory: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to create encrypted directory: {e}"))
+        })
+    }
 
-                                Ok(json)
-                            }
-                            Err(retry_err) => {
-                                tracing::error!(
-                                    "Unmount failed even after killing processes: {}",
-                                    retry_err
-                                );
+    /// Unlock an encrypted directory with its passphrase
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    /// - directory: Encrypted directory on the mounted filesystem
+    /// - passphrase: Passphrase of its protector
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-mount (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-mount")]
+    async fn unlock_encrypted_directory(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        directory: String,
+        passphrase: String,
+    ) -> zbus::fdo::Result<()> {
+        let resolved = fscrypt::resolve_target(&device, &directory, false).await?;
+        self.domain.require_fscrypt_support(&resolved.fs_type)?;
+        let user = caller.username.clone().ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!("No user name for UID {}", caller.uid))
+        })?;
 
-                                let result = UnmountResult {
-                                    success: false,
-                                    error: Some(retry_err.to_string()),
-                                    blocking_processes: Vec::new(),
-                                };
+        tracing::info!(
+            "Unlocking encrypted directory {} for UID {}",
+            resolved.directory.display(),
+            caller.uid
+        );
 
-                                let json = serde_json::to_string(&result).map_err(|e| {
-                                    zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
-                                })?;
+        tokio::task::spawn_blocking(move || {
+            storage_sys::unlock_directory(&resolved.directory, &passphrase, &user)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("fscrypt task failed: {e}")))?
+        .map_err(|e| {
+            tracing::warn!("Failed to unlock encrypted directory: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to unlock: {e}"))
+        })
+    }
 
-                                Ok(json)
-                            }
-                        }
-                    } else {
-                        // Return error with blocking processes
-                        let result = UnmountResult {
-                            success: false,
-                            error: Some("Device is busy".to_string()),
-                            blocking_processes: processes,
-                        };
+    /// Lock an encrypted directory, removing its key
+    ///
+    /// Args:
+    /// - device: Device path (e.g., "/dev/sda1")
+    /// - directory: Encrypted directory on the mounted filesystem
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-mount (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-mount")]
+    async fn lock_encrypted_directory(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        device: String,
+        directory: String,
+    ) -> zbus::fdo::Result<()> {
+        let resolved = fscrypt::resolve_target(&device, &directory, false).await?;
+        self.domain.require_fscrypt_support(&resolved.fs_type)?;
+        let user = caller.username.clone().ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!("No user name for UID {}", caller.uid))
+        })?;
 
-                        let json = serde_json::to_string(&result).map_err(|e| {
-                            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
-                        })?;
+        tracing::info!(
+            "Locking encrypted directory {} for UID {}",
+            resolved.directory.display(),
+            caller.uid
+        );
 
-                        Ok(json)
-                    }
-                } else {
-                    // Other error
-                    tracing::error!("Unmount failed: {}", e);
+        tokio::task::spawn_blocking(move || storage_sys::lock_directory(&resolved.directory, &user))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("fscrypt task failed: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to lock encrypted directory: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to lock: {e}"))
+            })
+    }
 
-                    let result = UnmountResult {
-                        success: false,
-                        error: Some(e.to_string()),
-                        blocking_processes: Vec::new(),
-                    };
+    /// List eCryptfs and gocryptfs vaults below /home
+    ///
+    /// Returns: JSON-serialized Vec<LegacyVault>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
+    async fn list_legacy_vaults(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Listing legacy vaults (UID {})", caller.uid);
 
-                    let json = serde_json::to_string(&result).map_err(|e| {
-                        zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
-                    })?;
+        let vaults = tokio::task::spawn_blocking(storage_sys::find_legacy_vaults)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Vault search failed: {e}")))?;
 
-                    Ok(json)
-                }
-            }
-        }
+        serde_json::to_string(&vaults).map_err(|e| {
+            tracing::error!("Failed to serialize vaults: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
     }
 
-    /// Get processes blocking unmount of a filesystem
+    /// Mount a vault of the caller read-only, to get at its files
     ///
     /// Args:
-    /// - device_or_mount: Device path or mount point
+    /// - cipher_dir: Directory holding the encrypted files
+    /// - passphrase: Login passphrase for eCryptfs, the vault password for gocryptfs
     ///
-    /// Returns: JSON-serialized Vec<ProcessInfo>
+    /// Returns: Mount point of the decrypted files
     ///
-    /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
-    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
-    async fn get_blocking_processes(
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-mount (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-mount")]
+    async fn mount_legacy_vault(
         &self,
         #[zbus(connection)] _connection: &Connection,
         #[zbus(header)] _header: MessageHeader<'_>,
-        device_or_mount: String,
+        cipher_dir: String,
+        passphrase: String,
     ) -> zbus::fdo::Result<String> {
-        tracing::debug!(
-            "Getting blocking processes for {} (UID {})",
-            device_or_mount,
+        let vault = vault::find_vault(&cipher_dir, caller.uid).await?;
+        if let Some(mount_point) = vault.mount_point {
+            return Ok(mount_point);
+        }
+        let mount_point = vault::vault_mount_point(&vault);
+
+        tracing::info!(
+            "Mounting vault {} at {} for UID {}",
+            cipher_dir,
+            mount_point.display(),
             caller.uid
         );
 
-        // Determine mount point via storage-udisks
-        let mount_point = if device_or_mount.starts_with("/dev/") {
-            storage_udisks::get_mount_point(&device_or_mount)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to get mount point: {e}");
-                    zbus::fdo::Error::Failed(format!("Failed to get mount point: {e}"))
-                })?
-        } else {
-            device_or_mount.clone()
-        };
-
-        // Find blocking processes
-        let processes = storage_udisks::find_processes_using_mount(&mount_point)
+        let target = mount_point.clone();
+        tokio::task::spawn_blocking(move || storage_sys::mount_vault(&vault, &passphrase, &target))
             .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Vault mount task failed: {e}")))?
             .map_err(|e| {
-                tracing::error!("Failed to find processes: {e}");
-                zbus::fdo::Error::Failed(format!("Failed to find processes: {e}"))
+                tracing::warn!("Failed to mount vault: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to mount vault: {e}"))
             })?;
 
-        tracing::debug!("Found {} blocking processes", processes.len());
+        Ok(mount_point.to_string_lossy().into_owned())
+    }
 
-        // Serialize to JSON
-        let json = serde_json::to_string(&processes).map_err(|e| {
-            tracing::error!("Failed to serialize processes: {e}");
-            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
-        })?;
+    /// Unmount a vault mounted with `mount_legacy_vault`
+    ///
+    /// Vaults mounted otherwise, like an eCryptfs home in use, are left
+    /// alone.
+    ///
+    /// Args:
+    /// - cipher_dir: Directory holding the encrypted files
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.filesystem-mount (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-mount")]
+    async fn unmount_legacy_vault(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        cipher_dir: String,
+    ) -> zbus::fdo::Result<()> {
+        let vault = vault::find_vault(&cipher_dir, caller.uid).await?;
+        let Some(mount_point) = vault.mount_point.as_deref().map(PathBuf::from) else {
+            return Ok(());
+        };
+        if !mount_point.starts_with(vault::VAULT_MOUNT_ROOT) {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "{} was not mounted by the storage service",
+                mount_point.display()
+            )));
+        }
 
-        Ok(json)
-    }
+        tracing::info!("Unmounting vault {} for UID {}", cipher_dir, caller.uid);
 
-    // Note: Process killing is intentionally only available through Unmount with kill_processes=true
-    // to limit the security surface. A standalone KillProcesses method could be exploited to kill
-    // arbitrary processes. The Unmount workflow (try unmount → get blocking processes → unmount with
-    // kill_processes=true) provides the necessary context and safety.
+        tokio::task::spawn_blocking(move || storage_sys::unmount_vault(&mount_point))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Vault unmount task failed: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to unmount vault: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to unmount vault: {e}"))
+            })
+    }
 
-    /// Check and optionally repair filesystem
+    /// Copy the files of a mounted vault into an empty encrypted directory
+    ///
+    /// The target must be an fscrypt directory or a directory on a LUKS
+    /// volume, owned by the caller. Every copied file is compared with its
+    /// original. Emits `vault_migration_progress` while running.
     ///
     /// Args:
-    /// - device: Device path (e.g., "/dev/sda1")
-    /// - repair: Whether to repair errors (requires unmount)
+    /// - cipher_dir: Directory holding the encrypted files
+    /// - target: Empty directory to copy the decrypted files into
     ///
-    /// Returns: JSON-serialized CheckResult
+    /// Returns: JSON-serialized VaultMigrationResult
     ///
     /// Authorization: org.cosmic.ext.storage.service.filesystem-modify (auth_admin_keep)
     #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-modify")]
-    async fn check(
+    async fn migrate_legacy_vault(
         &self,
         #[zbus(connection)] _connection: &Connection,
         #[zbus(header)] _header: MessageHeader<'_>,
-        device: String,
-        repair: bool,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        cipher_dir: String,
+        target: String,
     ) -> zbus::fdo::Result<String> {
+        let vault = vault::find_vault(&cipher_dir, caller.uid).await?;
+        let source = vault
+            .mount_point
+            .clone()
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!("Vault {cipher_dir} is not mounted"))
+            })?;
+        let target = std::fs::canonicalize(&target)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid target {target}: {e}")))?;
+        let owner = std::fs::metadata(&target)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid target: {e}")))?
+            .uid();
+        if caller.uid != 0 && owner != caller.uid {
+            return Err(zbus::fdo::Error::AccessDenied(format!(
+                "{} belongs to another user",
+                target.display()
+            )));
+        }
+
         tracing::info!(
-            "Checking filesystem on {} (repair={}) for UID {}",
-            device,
-            repair,
+            "Migrating vault {} into {} for UID {}",
+            cipher_dir,
+            target.display(),
             caller.uid
         );
 
-        self.domain.require_filesystem_check_support()?;
+        let (progress_tx, progress_rx) = mpsc::channel::<(u64, u64)>();
+        let migrate_target = target.clone();
+        let migrate_task = tokio::task::spawn_blocking(move || {
+            storage_sys::migrate_vault(&source, &migrate_target, Some(progress_tx))
+        });
 
-        // Delegate to storage-udisks operation
-        let clean = storage_udisks::check_filesystem(&device, repair)
+        loop {
+            let mut progress = None;
+            while let Ok(update) = progress_rx.try_recv() {
+                progress = Some(update);
+            }
+            if let Some((copied, total)) = progress {
+                let _ =
+                    Self::vault_migration_progress(&signal_ctx, &cipher_dir, copied, total).await;
+            }
+
+            if migrate_task.is_finished() {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+
+        let result = migrate_task
             .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Vault migration join error: {e}")))?
             .map_err(|e| {
-                tracing::error!("Filesystem check failed: {e}");
-                zbus::fdo::Error::Failed(format!("Filesystem check failed: {e}"))
+                tracing::error!("Vault migration failed: {e}");
+                zbus::fdo::Error::Failed(format!("Vault migration failed: {e}"))
             })?;
 
-        let result = CheckResult {
-            device: device.clone(),
-            clean,
-            errors_corrected: if repair && !clean { 1 } else { 0 },
-            errors_uncorrected: 0,
-            output: if clean {
-                "Filesystem is clean".to_string()
-            } else {
-                "Filesystem has errors".to_string()
-            },
-        };
+        tracing::info!(
+            "Migrated vault {} into {} ({} files, {} bytes)",
+            cipher_dir,
+            target.display(),
+            result.files,
+            result.bytes
+        );
 
-        let json = serde_json::to_string(&result).map_err(|e| {
-            tracing::error!("Failed to serialize result: {e}");
+        serde_json::to_string(&result).map_err(|e| {
+            tracing::error!("Failed to serialize vault migration result: {e}");
             zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
-        })?;
-
-        Ok(json)
+        })
     }
 
     /// Set filesystem label
@@ -740,6 +1817,8 @@ impl FilesystemHandler {
     ///
     /// Emits `usage_scan_progress` while the scan is running.
     ///
+    /// Returns: JSON-serialized ResultPage of a UsageScanResult, see GetPage
+    ///
     /// Authorization: org.cosmic.ext.storage.service.filesystem-read (allow_active)
     #[authorized_interface(action = "org.cosmic.ext.storage.service.filesystem-read")]
     async fn get_usage_scan(
@@ -761,7 +1840,7 @@ impl FilesystemHandler {
         let cpu_count = std::thread::available_parallelism()
             .map(|parallelism| parallelism.get())
             .unwrap_or(1);
-        let scan_threads = map_parallelism_threads(parallelism_preset, cpu_count);
+        let scan_threads = parallelism_preset.threads(cpu_count);
 
         tracing::info!(
             "Starting usage scan id={} top_files_per_category={} show_all_files={} preset={} threads={} (UID {})",
@@ -852,7 +1931,9 @@ impl FilesystemHandler {
 
         let (progress_tx, progress_rx) = mpsc::channel::<u64>();
         let scan_mounts = mounts.clone();
+        let priority = crate::priority::load_settings().priority(OperationKind::UsageScan);
         let scan_task = tokio::task::spawn_blocking(move || {
+            let _priority = storage_sys::apply_priority(&priority);
             storage_sys::usage::scan_paths_with_progress(
                 &scan_mounts,
                 &scan_config,
@@ -929,12 +2010,7 @@ impl FilesystemHandler {
         )
         .await;
 
-        let json = serde_json::to_string::<UsageScanResult>(&scan_result).map_err(|e| {
-            tracing::error!("Failed to serialize usage scan result: {e}");
-            zbus::fdo::Error::Failed(format!("Failed to serialize usage scan result: {e}"))
-        })?;
-
-        Ok(json)
+        crate::paging::reply::<UsageScanResult>(&caller.sender, &scan_result)
     }
 
     /// List local mount points available for usage scans.
@@ -1026,6 +2102,13 @@ impl FilesystemHandler {
             }
         }
 
+        let existing: Vec<PathBuf> = paths
+            .iter()
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute() && path != Path::new("/") && path.exists())
+            .collect();
+        crate::hooks::before_paths(Operation::Cleanup, existing).await?;
+
         for path_str in paths {
             let path = Path::new(&path_str);
 
@@ -1214,6 +2297,21 @@ impl FilesystemHandler {
     ) -> zbus::fdo::Result<()> {
         tracing::debug!("Setting mount options for {} (UID {})", device, caller.uid);
 
+        // The drive is not known here; only contradictory and invalid
+        // options, which do not depend on it, are rejected
+        let errors: Vec<String> = storage_types::lint_mount_options(
+            filesystem_type.trim(),
+            &other_options,
+            &MountLintContext::default(),
+        )
+        .into_iter()
+        .filter(|lint| lint.severity == MountLintSeverity::Error)
+        .map(|lint| lint.message)
+        .collect();
+        if !errors.is_empty() {
+            return Err(zbus::fdo::Error::InvalidArgs(errors.join("; ")));
+        }
+
         let display_opt = if display_name.trim().is_empty() {
             None
         } else {
@@ -1 +1,135 @@
+// SPDX-License-Identifier: GPL-3.0-only
 
+use std::os::unix::fs::MetadataExt;
+use std::path::{Component, Path, PathBuf};
+
+use storage_types::windows_hibernation::mounts_read_only;
+use storage_types::{MountOptions, MountPathPolicy, WindowsHibernation};
+
+use crate::protected_paths::{is_protected_target, resolve_existing_ancestor};
+
+/// Resolve the mount point for a device from the caller's mount path policy.
+///
+/// The policy comes from the caller, so the resolved mount point goes
+/// through the same [`check_mount_point`] as an explicit one.
+/// Candidates that exist as a mount point, a non-directory, or a non-empty
+/// directory are treated as taken and get a numeric suffix instead.
+pub(super) async fn resolve_policy_mount_point(
+    device: &str,
+    policy: &MountPathPolicy,
+    username: &str,
+) -> zbus::fdo::Result<PathBuf> {
+    let base_dir = policy.expand_base_dir(username);
+    if !base_dir.is_absolute()
+        || base_dir
+            .components()
+            .any(|component| component == Component::ParentDir)
+    {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "Mount base directory must be an absolute path: {}",
+            base_dir.display()
+        )));
+    }
+
+    let label = storage_udisks::get_filesystem_label(device)
+        .await
+        .unwrap_or_default();
+    let uuid = storage_udisks::get_filesystem_uuid(device)
+        .await
+        .unwrap_or_default();
+
+    let mount_point = policy
+        .resolve_mount_point(&base_dir, &label, &uuid, device, is_mount_point_taken)
+        .ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!(
+                "No free mount point available under {}",
+                base_dir.display()
+            ))
+        })?;
+
+    check_mount_point(&mount_point, username)?;
+    Ok(mount_point)
+}
+
+/// Check a mount point the caller gave or chose through its policy
+///
+/// It must lie inside the caller's media base directory, also once symlinks
+/// in the part of it that exists are resolved, and never inside one of the
+/// protected system paths.
+pub(super) fn check_mount_point(mount_point: &Path, username: &str) -> zbus::fdo::Result<()> {
+    let resolved = resolve_existing_ancestor(mount_point);
+    let inside_media_base = [mount_point, resolved.as_path()]
+        .iter()
+        .all(|path| MountPathPolicy::is_in_media_base(path, username));
+    if !inside_media_base {
+        let bases = MountPathPolicy::media_base_dirs(username);
+        return Err(zbus::fdo::Error::AccessDenied(format!(
+            "Mount points must be inside {} or {}: {}",
+            bases[0].display(),
+            bases[1].display(),
+            mount_point.display()
+        )));
+    }
+    if is_protected_target(mount_point) {
+        return Err(zbus::fdo::Error::AccessDenied(format!(
+            "Refusing to mount over protected path: {}",
+            mount_point.display()
+        )));
+    }
+    Ok(())
+}
+
+fn is_mount_point_taken(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return false;
+    };
+
+    if !metadata.is_dir() {
+        return true;
+    }
+
+    // A directory on a different device than its parent is an active mount point
+    let is_mounted = path
+        .parent()
+        .and_then(|parent| std::fs::metadata(parent).ok())
+        .is_none_or(|parent| parent.dev() != metadata.dev());
+    let is_empty = std::fs::read_dir(path)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+
+    is_mounted || !is_empty
+}
+
+/// Whether Windows left the NTFS filesystem on `device` hibernated
+///
+/// None when it isn't, or when that can't be told, e.g. for lack of
+/// `ntfs-3g.probe`.
+pub(super) async fn windows_hibernation(device: &str) -> Option<WindowsHibernation> {
+    let probed = device.to_string();
+    tokio::task::spawn_blocking(move || storage_sys::windows_hibernation(&probed))
+        .await
+        .ok()?
+        .inspect_err(|e| tracing::debug!("Hibernation probe of {device} failed: {e}"))
+        .ok()
+        .flatten()
+}
+
+/// Refuse to mount a filesystem Windows left hibernated read-write, as
+/// Windows corrupts it when it resumes
+pub(super) async fn check_windows_hibernation(
+    device: &str,
+    options: &MountOptions,
+) -> zbus::fdo::Result<()> {
+    if mounts_read_only(options.read_only, &options.other) {
+        return Ok(());
+    }
+    let reason = match windows_hibernation(device).await {
+        None => return Ok(()),
+        Some(WindowsHibernation::Hibernated) => "Windows is hibernated",
+        Some(WindowsHibernation::FastStartup) => "Windows was shut down with Fast Startup",
+    };
+    tracing::warn!("Refusing to mount {device} read-write: {reason}");
+    Err(zbus::fdo::Error::Failed(format!(
+        "{reason} and still uses {device}; mount it read-only, or shut Windows down fully first"
+    )))
+}
@@ -1 +1,36 @@
+// SPDX-License-Identifier: GPL-3.0-only
 
+use std::path::PathBuf;
+
+use storage_types::FilesystemFeatures;
+
+/// Probe the on-disk features of the filesystem on `device`.
+///
+/// The filesystem type and current mount point come from udisks; the probe
+/// itself shells out to the filesystem's tools on a blocking thread.
+pub(super) async fn probe_filesystem_features(
+    device: &str,
+) -> zbus::fdo::Result<FilesystemFeatures> {
+    let fs_type = storage_udisks::get_filesystem_type(device)
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read filesystem type: {e}")))?;
+    if fs_type.is_empty() {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "No filesystem found on {device}"
+        )));
+    }
+
+    let mount_point = storage_udisks::get_mount_point(device)
+        .await
+        .ok()
+        .filter(|mount_point| !mount_point.is_empty())
+        .map(PathBuf::from);
+
+    let device = device.to_string();
+    tokio::task::spawn_blocking(move || {
+        storage_sys::get_filesystem_features(&device, &fs_type, mount_point.as_deref())
+    })
+    .await
+    .map_err(|e| zbus::fdo::Error::Failed(format!("Feature probe task failed: {e}")))?
+    .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to probe filesystem features: {e}")))
+}
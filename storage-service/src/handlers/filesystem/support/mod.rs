@@ -1,3 +1,2 @@
 pub mod fs_permissions;
 pub mod uid_groups;
-pub mod usage_threads;
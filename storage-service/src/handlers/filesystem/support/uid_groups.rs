@@ -20,6 +20,28 @@ pub(crate) fn current_process_groups() -> Vec<u32> {
     gids
 }
 
+/// Primary group of `uid`, from its passwd entry
+pub(crate) fn primary_gid(uid: u32) -> Option<u32> {
+    let mut pwd = std::mem::MaybeUninit::<libc::passwd>::uninit();
+    let mut pwd_ptr: *mut libc::passwd = std::ptr::null_mut();
+    let mut buffer = vec![0_u8; 4096];
+
+    let lookup_result = unsafe {
+        libc::getpwuid_r(
+            uid,
+            pwd.as_mut_ptr(),
+            buffer.as_mut_ptr() as *mut libc::c_char,
+            buffer.len(),
+            &mut pwd_ptr,
+        )
+    };
+
+    if lookup_result != 0 || pwd_ptr.is_null() {
+        return None;
+    }
+    Some(unsafe { pwd.assume_init() }.pw_gid)
+}
+
 pub(crate) fn resolve_caller_groups(uid: u32, username: Option<&str>) -> Vec<u32> {
     let process_uid = unsafe { libc::geteuid() } as u32;
     if uid == process_uid {
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+
+use storage_types::LegacyVault;
+
+/// Where vaults are mounted for access, one directory per vault
+pub(super) const VAULT_MOUNT_ROOT: &str = "/run/cosmic-ext-storage/vaults";
+
+/// The vault with encrypted files in `cipher_dir`, if `caller_uid` owns it
+///
+/// Root may reach every vault; other users only their own, even with the
+/// passphrase of another one.
+pub(super) async fn find_vault(
+    cipher_dir: &str,
+    caller_uid: u32,
+) -> zbus::fdo::Result<LegacyVault> {
+    let vaults = tokio::task::spawn_blocking(storage_sys::find_legacy_vaults)
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Vault search failed: {e}")))?;
+    let vault = vaults
+        .into_iter()
+        .find(|vault| vault.cipher_dir == cipher_dir)
+        .ok_or_else(|| zbus::fdo::Error::InvalidArgs(format!("No vault at {cipher_dir}")))?;
+    if caller_uid != 0 && caller_uid != vault.owner_uid {
+        return Err(zbus::fdo::Error::AccessDenied(format!(
+            "{cipher_dir} belongs to another user"
+        )));
+    }
+    Ok(vault)
+}
+
+/// Where `vault` is mounted for access
+pub(super) fn vault_mount_point(vault: &LegacyVault) -> PathBuf {
+    Path::new(VAULT_MOUNT_ROOT).join(vault.mount_name())
+}
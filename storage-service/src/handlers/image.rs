@@ -4,12 +4,20 @@
 //!
 //! This module handles long-running disk imaging operations with progress tracking.
 //! Operations run in background tasks and emit D-Bus signals for progress updates.
+//! Clients can also follow an operation through a shared progress counter
+//! (see [`storage_contracts::ProgressRecord`]) from GetProgressFds.
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use storage_contracts::{OperationKind as ContractKind, PROGRESS_RECORD_BYTES, ProgressRecord};
 use storage_macros::authorized_interface;
+use storage_sys::ProgressCounter;
+use storage_types::{
+    JournalEvent, OperationKind, OperationPriority, PrioritySettings, THROUGHPUT_SEGMENTS,
+    ThroughputRun,
+};
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
@@ -17,6 +25,7 @@ use zbus::message::Header as MessageHeader;
 use zbus::object_server::SignalEmitter;
 use zbus::{Connection, interface};
 
+use crate::handlers::operations::TrackedOperation;
 use crate::policies::image::{ImageDomain, ImagePolicy};
 
 /// Operation type for tracking
@@ -26,6 +35,11 @@ pub enum OperationType {
     BackupPartition,
     RestoreDrive,
     RestorePartition,
+    BurnDisc,
+    BlankDisc,
+    CopyPartition,
+    MigrateDisk,
+    CreateLiveUsb,
 }
 
 impl std::fmt::Display for OperationType {
@@ -35,6 +49,11 @@ impl std::fmt::Display for OperationType {
             Self::BackupPartition => write!(f, "backup_partition"),
             Self::RestoreDrive => write!(f, "restore_drive"),
             Self::RestorePartition => write!(f, "restore_partition"),
+            Self::BurnDisc => write!(f, "burn_disc"),
+            Self::BlankDisc => write!(f, "blank_disc"),
+            Self::CopyPartition => write!(f, "copy_partition"),
+            Self::MigrateDisk => write!(f, "migrate_disk"),
+            Self::CreateLiveUsb => write!(f, "create_live_usb"),
         }
     }
 }
@@ -46,6 +65,79 @@ pub struct ProgressInfo {
     pub total_bytes: u64,
     pub speed_bytes_per_sec: u64,
     pub started_at: Instant,
+    /// Seconds from the start at which each tenth of the bytes was done
+    pub segment_ends: Vec<f64>,
+    /// Shared counter mirroring the fields above, when one could be created
+    pub counter: Option<Arc<ProgressCounter>>,
+}
+
+impl ProgressInfo {
+    fn new() -> Self {
+        let counter = ProgressCounter::new("cosmic-ext-storage-progress", PROGRESS_RECORD_BYTES)
+            .inspect_err(|e| tracing::warn!("Failed to create progress counter: {e}"))
+            .ok()
+            .map(Arc::new);
+        Self {
+            bytes_completed: 0,
+            total_bytes: 0,
+            speed_bytes_per_sec: 0,
+            started_at: Instant::now(),
+            segment_ends: Vec::with_capacity(THROUGHPUT_SEGMENTS),
+            counter,
+        }
+    }
+
+    fn set_total(&mut self, total_bytes: u64) {
+        self.total_bytes = total_bytes;
+        self.publish(false);
+    }
+
+    fn set_completed(&mut self, bytes_completed: u64, speed_bytes_per_sec: u64) {
+        self.bytes_completed = bytes_completed;
+        self.speed_bytes_per_sec = speed_bytes_per_sec;
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        while self.total_bytes > 0
+            && self.segment_ends.len() < THROUGHPUT_SEGMENTS
+            && u128::from(bytes_completed) * THROUGHPUT_SEGMENTS as u128
+                >= u128::from(self.total_bytes) * (self.segment_ends.len() + 1) as u128
+        {
+            self.segment_ends.push(elapsed);
+        }
+        self.publish(false);
+    }
+
+    /// Mark the operation finished, keeping its speed profile under
+    /// `throughput_key` when it succeeded
+    fn finish(&self, throughput_key: &str, succeeded: bool) {
+        self.publish(true);
+        if !succeeded {
+            return;
+        }
+        let run = ThroughputRun::from_segment_ends(
+            self.total_bytes,
+            &self.segment_ends,
+            crate::handlers::disk::selftest::now(),
+        );
+        if let Some(run) = run {
+            crate::throughput::record(throughput_key, run);
+        }
+    }
+
+    /// Write the progress to the shared counter, if there is one
+    fn publish(&self, finished: bool) {
+        let Some(counter) = &self.counter else {
+            return;
+        };
+        let record = ProgressRecord {
+            bytes_completed: self.bytes_completed,
+            total_bytes: self.total_bytes,
+            speed_bytes_per_sec: self.speed_bytes_per_sec,
+            finished,
+        };
+        if let Err(e) = counter.publish(&record.to_bytes()) {
+            tracing::debug!("Failed to update progress counter: {e}");
+        }
+    }
 }
 
 /// State of an active operation
@@ -53,7 +145,6 @@ struct OperationState {
     kind: OperationType,
     source: String,
     destination: String,
-    cancel_token: CancellationToken,
     handle: JoinHandle<Result<(), String>>,
     progress: Arc<Mutex<ProgressInfo>>,
 }
@@ -72,25 +163,49 @@ impl ImageHandler {
         }
     }
 
-    /// Generate unique operation ID
-    fn generate_operation_id() -> String {
-        uuid::Uuid::new_v4().to_string()
+    /// Run `task` as the tracked `operation`, mirroring its progress into
+    /// the running operations every second
+    async fn follow(
+        mut operation: TrackedOperation,
+        progress: Arc<Mutex<ProgressInfo>>,
+        task: impl Future<Output = Result<(), String>>,
+    ) -> Result<(), String> {
+        let mut task = std::pin::pin!(task);
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        let result = loop {
+            tokio::select! {
+                result = &mut task => break result,
+                _ = ticker.tick() => {
+                    let progress = progress.lock().await;
+                    let total = (progress.total_bytes > 0).then_some(progress.total_bytes);
+                    operation.update(progress.bytes_completed, total);
+                }
+            }
+        };
+        operation.finish(result.clone());
+        result
     }
 
     /// Background task for backup operation
+    ///
+    /// A completed backup counts toward the usage statistics of `uid`.
     async fn backup_task(
         device_path: String,
         output_path: String,
         cancel_token: CancellationToken,
         progress: Arc<Mutex<ProgressInfo>>,
+        priority: OperationPriority,
+        uid: u32,
     ) -> Result<(), String> {
         // Check for cancellation before starting
         if cancel_token.is_cancelled() {
             return Err("Operation cancelled".to_string());
         }
 
-        // Open source device (privileged) via storage-udisks
-        let source_fd = storage_udisks::open_for_backup_by_device(&device_path)
+        // Open source device (privileged) via the configured backend
+        let source_fd = crate::backend::current()
+            .images
+            .open_for_backup_by_device(&device_path)
             .await
             .map_err(|e| format!("Failed to open source device: {e}"))?;
 
@@ -105,10 +220,7 @@ impl ImageHandler {
         .len();
 
         // Initialize progress
-        {
-            let mut prog = progress.lock().await;
-            prog.total_bytes = total_size;
-        }
+        progress.lock().await.set_total(total_size);
 
         let output_path_buf = PathBuf::from(output_path);
         let start_time = Instant::now();
@@ -117,16 +229,11 @@ impl ImageHandler {
 
         // Perform the copy in a blocking task (storage_sys uses sync I/O)
         let _result = tokio::task::spawn_blocking(move || {
+            let _priority = storage_sys::apply_priority(&priority);
             storage_sys::copy_image_to_file(
                 source_fd,
                 &output_path_buf,
                 Some(|bytes_copied: u64| {
-                    // Check cancellation in callback
-                    if cancel_clone.is_cancelled() {
-                        // Note: can't stop mid-operation gracefully with current API
-                        return;
-                    }
-
                     // Update progress (blocking mutex)
                     let mut prog = progress_clone.blocking_lock();
                     let elapsed = start_time.elapsed();
@@ -135,19 +242,22 @@ impl ImageHandler {
                     } else {
                         0
                     };
-                    prog.bytes_completed = bytes_copied;
-                    prog.speed_bytes_per_sec = speed;
+                    prog.set_completed(bytes_copied, speed);
                 }),
+                || cancel_clone.is_cancelled(),
             )
         })
         .await
         .map_err(|e| format!("Task join error: {e}"))?
-        .map_err(|e| format!("Copy failed: {e}"))?;
+        .map_err(|e| {
+            if cancel_token.is_cancelled() {
+                "Operation cancelled".to_string()
+            } else {
+                format!("Copy failed: {e}")
+            }
+        })?;
 
-        // Check if operation was cancelled
-        if cancel_token.is_cancelled() {
-            return Err("Operation cancelled".to_string());
-        }
+        crate::statistics::record(uid, JournalEvent::BackedUp { bytes: total_size });
 
         Ok(())
     }
@@ -158,6 +268,7 @@ impl ImageHandler {
         device_path: String,
         cancel_token: CancellationToken,
         progress: Arc<Mutex<ProgressInfo>>,
+        priority: OperationPriority,
     ) -> Result<(), String> {
         // Check for cancellation before starting
         if cancel_token.is_cancelled() {
@@ -171,13 +282,12 @@ impl ImageHandler {
             .len();
 
         // Initialize progress
-        {
-            let mut prog = progress.lock().await;
-            prog.total_bytes = total_size;
-        }
+        progress.lock().await.set_total(total_size);
 
-        // Open destination device (privileged) via storage-udisks
-        let dest_fd = storage_udisks::open_for_restore_by_device(&device_path)
+        // Open destination device (privileged) via the configured backend
+        let dest_fd = crate::backend::current()
+            .images
+            .open_for_restore_by_device(&device_path)
             .await
             .map_err(|e| format!("Failed to open destination device: {e}"))?;
 
@@ -187,16 +297,11 @@ impl ImageHandler {
 
         // Perform the copy in a blocking task (storage_sys uses sync I/O)
         let _result = tokio::task::spawn_blocking(move || {
+            let _priority = storage_sys::apply_priority(&priority);
             storage_sys::copy_file_to_image(
                 &source_path,
                 dest_fd,
                 Some(|bytes_copied: u64| {
-                    // Check cancellation in callback
-                    if cancel_clone.is_cancelled() {
-                        // Note: can't stop mid-operation gracefully with current API
-                        return;
-                    }
-
                     // Update progress (blocking mutex)
                     let mut prog = progress_clone.blocking_lock();
                     let elapsed = start_time.elapsed();
@@ -205,21 +310,339 @@ impl ImageHandler {
                     } else {
                         0
                     };
-                    prog.bytes_completed = bytes_copied;
-                    prog.speed_bytes_per_sec = speed;
+                    prog.set_completed(bytes_copied, speed);
                 }),
+                || cancel_clone.is_cancelled(),
             )
         })
         .await
         .map_err(|e| format!("Task join error: {e}"))?
-        .map_err(|e| format!("Copy failed: {e}"))?;
+        .map_err(|e| {
+            if cancel_token.is_cancelled() {
+                "Operation cancelled".to_string()
+            } else {
+                format!("Copy failed: {e}")
+            }
+        })?;
 
-        // Check if operation was cancelled
-        if cancel_token.is_cancelled() {
-            return Err("Operation cancelled".to_string());
+        Ok(())
+    }
+
+    /// Background task for burning an ISO image to a disc, then reading it
+    /// back when `verify` is set
+    ///
+    /// Progress counts the image once for burning and once more for the
+    /// verification.
+    async fn burn_task(
+        image_path: String,
+        device_path: String,
+        verify: bool,
+        cancel_token: CancellationToken,
+        progress: Arc<Mutex<ProgressInfo>>,
+    ) -> Result<(), String> {
+        let image_size = std::fs::metadata(&image_path)
+            .map_err(|e| format!("Failed to get image file size: {e}"))?
+            .len();
+        progress
+            .lock()
+            .await
+            .set_total(if verify { image_size * 2 } else { image_size });
+
+        let start_time = Instant::now();
+        let update = {
+            let progress = progress.clone();
+            move |bytes_completed: u64| {
+                let elapsed = start_time.elapsed().as_secs();
+                let speed = if elapsed > 0 {
+                    bytes_completed / elapsed
+                } else {
+                    0
+                };
+                progress
+                    .blocking_lock()
+                    .set_completed(bytes_completed, speed);
+            }
+        };
+
+        let burn_update = update.clone();
+        let burn_cancel = cancel_token.clone();
+        let burn_image = PathBuf::from(&image_path);
+        let burn_device = device_path.clone();
+        tokio::task::spawn_blocking(move || {
+            storage_sys::burn_optical_image(
+                &burn_device,
+                &burn_image,
+                |fraction| burn_update((fraction * image_size as f64) as u64),
+                || burn_cancel.is_cancelled(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Burning failed: {e}"))?;
+
+        if !verify {
+            return Ok(());
         }
+        let verify_cancel = cancel_token.clone();
+        tokio::task::spawn_blocking(move || {
+            storage_sys::verify_optical_image(
+                &device_path,
+                Path::new(&image_path),
+                |verified| update(image_size + verified),
+                || verify_cancel.is_cancelled(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Verification failed: {e}"))
+    }
 
-        Ok(())
+    /// Background task for erasing a rewritable disc
+    async fn blank_task(
+        device_path: String,
+        full: bool,
+        cancel_token: CancellationToken,
+        progress: Arc<Mutex<ProgressInfo>>,
+    ) -> Result<(), String> {
+        // xorriso only reports a percentage; count it against the disc size
+        let total_bytes = crate::handlers::disk::optical::media_info(&device_path)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|media| media.capacity_bytes())
+            .filter(|capacity| *capacity > 0)
+            .unwrap_or(100);
+        progress.lock().await.set_total(total_bytes);
+
+        let progress_clone = progress.clone();
+        tokio::task::spawn_blocking(move || {
+            storage_sys::blank_optical_media(
+                &device_path,
+                full,
+                |fraction| {
+                    progress_clone
+                        .blocking_lock()
+                        .set_completed((fraction * total_bytes as f64) as u64, 0);
+                },
+                || cancel_token.is_cancelled(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Erasing failed: {e}"))
+    }
+
+    /// Background task for copying a partition onto another, then giving
+    /// the copy a new UUID and growing it to fill the target
+    async fn copy_task(
+        source_path: String,
+        target_path: String,
+        cancel_token: CancellationToken,
+        progress: Arc<Mutex<ProgressInfo>>,
+        priority: OperationPriority,
+    ) -> Result<(), String> {
+        let total_size = std::fs::File::open(&source_path)
+            .and_then(|mut source| std::io::Seek::seek(&mut source, std::io::SeekFrom::End(0)))
+            .map_err(|e| format!("Failed to get partition size: {e}"))?;
+        progress.lock().await.set_total(total_size);
+
+        let start_time = Instant::now();
+        let progress_clone = progress.clone();
+        tokio::task::spawn_blocking(move || {
+            let _priority = storage_sys::apply_priority(&priority);
+            storage_sys::copy_partition(
+                &source_path,
+                &target_path,
+                |bytes_copied| {
+                    let elapsed = start_time.elapsed().as_secs();
+                    let speed = if elapsed > 0 {
+                        bytes_copied / elapsed
+                    } else {
+                        0
+                    };
+                    progress_clone
+                        .blocking_lock()
+                        .set_completed(bytes_copied, speed);
+                },
+                || cancel_token.is_cancelled(),
+            )?;
+            storage_sys::finish_partition_copy(&target_path)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Copy failed: {e}"))
+    }
+
+    /// Background task for migrating a disk onto a larger one
+    async fn migrate_task(
+        source_path: String,
+        target_path: String,
+        plan: storage_types::MigrationPlan,
+        cancel_token: CancellationToken,
+        progress: Arc<Mutex<ProgressInfo>>,
+    ) -> Result<(), String> {
+        progress.lock().await.set_total(plan.total_bytes());
+
+        let start_time = Instant::now();
+        let progress_clone = progress.clone();
+        tokio::task::spawn_blocking(move || {
+            storage_sys::migrate_disk(
+                &source_path,
+                &target_path,
+                &plan,
+                |bytes_copied| {
+                    let elapsed = start_time.elapsed().as_secs();
+                    let speed = if elapsed > 0 {
+                        bytes_copied / elapsed
+                    } else {
+                        0
+                    };
+                    progress_clone
+                        .blocking_lock()
+                        .set_completed(bytes_copied, speed);
+                },
+                || cancel_token.is_cancelled(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Migration failed: {e}"))
+    }
+
+    /// Background task for making a bootable USB drive from an ISO image,
+    /// with the extra partitions of `plan`
+    async fn live_usb_task(
+        image_path: String,
+        device_path: String,
+        plan: storage_types::LiveUsbPlan,
+        cancel_token: CancellationToken,
+        progress: Arc<Mutex<ProgressInfo>>,
+    ) -> Result<(), String> {
+        let total_size = std::fs::metadata(&image_path)
+            .map_err(|e| format!("Failed to get image file size: {e}"))?
+            .len();
+        progress.lock().await.set_total(total_size);
+
+        let start_time = Instant::now();
+        let progress_clone = progress.clone();
+        tokio::task::spawn_blocking(move || {
+            storage_sys::create_live_usb(
+                &image_path,
+                &device_path,
+                &plan,
+                |bytes_written| {
+                    let elapsed = start_time.elapsed().as_secs();
+                    let speed = if elapsed > 0 {
+                        bytes_written / elapsed
+                    } else {
+                        0
+                    };
+                    progress_clone
+                        .blocking_lock()
+                        .set_completed(bytes_written, speed);
+                },
+                || cancel_token.is_cancelled(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Creating the live USB drive failed: {e}"))
+    }
+
+    /// Check that `device` is an optical drive holding a disc, returning its
+    /// device path and the disc
+    async fn optical_target(
+        device: &str,
+    ) -> zbus::fdo::Result<(String, storage_types::OpticalMediaInfo)> {
+        let device_path = if device.starts_with("/dev/") {
+            device.to_string()
+        } else {
+            format!("/dev/{}", device)
+        };
+        crate::handlers::disk::optical::require_optical_drive(&device_path).await?;
+        let media = crate::handlers::disk::optical::media_info(&device_path)
+            .await?
+            .ok_or_else(|| zbus::fdo::Error::Failed("There is no disc in the drive".to_string()))?;
+        Ok((device_path, media))
+    }
+
+    /// Start and track an operation, reporting its end with
+    /// `OperationCompleted`
+    async fn start_operation<F>(
+        &self,
+        signal_ctx: &SignalEmitter<'_>,
+        kind: OperationType,
+        owner: u32,
+        source: String,
+        destination: String,
+        task: impl FnOnce(CancellationToken, Arc<Mutex<ProgressInfo>>) -> F,
+    ) -> zbus::fdo::Result<String>
+    where
+        F: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let mut operation = TrackedOperation::begin(
+            ContractKind::Image,
+            &destination,
+            &kind.to_string(),
+            Some(owner),
+        );
+        let operation_id = operation.id().as_uuid().to_string();
+        let progress = Arc::new(Mutex::new(ProgressInfo::new()));
+        let cancel_token = CancellationToken::new();
+        operation.on_cancel({
+            let cancel_token = cancel_token.clone();
+            move || cancel_token.cancel()
+        });
+        let task = task(cancel_token.clone(), progress.clone());
+
+        let emitter = signal_ctx.to_owned();
+        let task_operation_id = operation_id.clone();
+        let task_progress = progress.clone();
+        let throughput_key = crate::throughput::key(&kind, &source, &destination);
+        let handle = tokio::spawn(async move {
+            let result = Self::follow(operation, task_progress.clone(), task).await;
+            task_progress
+                .lock()
+                .await
+                .finish(&throughput_key, result.is_ok());
+            if let Err(e) = &result {
+                tracing::error!("Operation {task_operation_id} failed: {e}");
+            }
+            let error = result
+                .as_ref()
+                .err()
+                .map(String::as_str)
+                .unwrap_or_default();
+            if let Err(e) =
+                Self::operation_completed(&emitter, &task_operation_id, result.is_ok(), error).await
+            {
+                tracing::warn!("Failed to emit OperationCompleted: {e}");
+            }
+            result
+        });
+
+        let operation_type = kind.to_string();
+        self.active_operations.lock().await.insert(
+            operation_id.clone(),
+            OperationState {
+                kind,
+                source: source.clone(),
+                destination: destination.clone(),
+                handle,
+                progress,
+            },
+        );
+
+        Self::operation_started(
+            signal_ctx,
+            &operation_id,
+            &operation_type,
+            &source,
+            &destination,
+        )
+        .await?;
+
+        Ok(operation_id)
     }
 }
 
@@ -230,6 +653,8 @@ impl ImageHandler {
     /// Args:
     /// - device: Device identifier (e.g., "/dev/sda", "sda")
     /// - output_path: Path to write image file
+    /// - priority: JSON `OperationPriority` for this backup, or empty for the
+    ///   configured one
     ///
     /// Returns: operation_id for tracking progress
     ///
@@ -242,6 +667,7 @@ impl ImageHandler {
         #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
         device: String,
         output_path: String,
+        priority: String,
     ) -> zbus::fdo::Result<String> {
         tracing::info!(
             "Starting drive backup: {device} → {output_path} (UID {})",
@@ -250,6 +676,7 @@ impl ImageHandler {
 
         self.domain
             .validate_output_path_parent_exists(&output_path)?;
+        let priority = crate::priority::resolve(OperationKind::ImageBackup, &priority)?;
 
         // Normalize device path
         let device_path = if device.starts_with("/dev/") {
@@ -259,18 +686,23 @@ impl ImageHandler {
         };
 
         // Generate operation ID
-        let operation_id = Self::generate_operation_id();
+        let mut operation = TrackedOperation::begin(
+            ContractKind::Image,
+            &device_path,
+            "backup_drive",
+            Some(caller.uid),
+        );
+        let operation_id = operation.id().as_uuid().to_string();
 
         // Create progress tracker
-        let progress = Arc::new(Mutex::new(ProgressInfo {
-            bytes_completed: 0,
-            total_bytes: 0,
-            speed_bytes_per_sec: 0,
-            started_at: Instant::now(),
-        }));
+        let progress = Arc::new(Mutex::new(ProgressInfo::new()));
 
         // Create cancellation token
         let cancel_token = CancellationToken::new();
+        operation.on_cancel({
+            let cancel_token = cancel_token.clone();
+            move || cancel_token.cancel()
+        });
 
         // Spawn background task
         let task_cancel = cancel_token.clone();
@@ -278,14 +710,30 @@ impl ImageHandler {
         let task_output_path = output_path.clone();
         let task_device_path = device_path.clone();
 
+        let task_uid = caller.uid;
+
+        let throughput_key =
+            crate::throughput::key(&OperationType::BackupDrive, &device, &output_path);
+
         let handle = tokio::spawn(async move {
-            Self::backup_task(
-                task_device_path,
-                task_output_path,
-                task_cancel,
-                task_progress,
+            let result = Self::follow(
+                operation,
+                task_progress.clone(),
+                Self::backup_task(
+                    task_device_path,
+                    task_output_path,
+                    task_cancel,
+                    task_progress.clone(),
+                    priority,
+                    task_uid,
+                ),
             )
-            .await
+            .await;
+            task_progress
+                .lock()
+                .await
+                .finish(&throughput_key, result.is_ok());
+            result
         });
 
         // Track operation
@@ -293,7 +741,6 @@ impl ImageHandler {
             kind: OperationType::BackupDrive,
             source: device.clone(),
             destination: output_path.clone(),
-            cancel_token,
             handle,
             progress,
         };
@@ -321,6 +768,8 @@ impl ImageHandler {
     /// Args:
     /// - device: Partition identifier (e.g., "/dev/sda1")
     /// - output_path: Path to write image file
+    /// - priority: JSON `OperationPriority` for this backup, or empty for the
+    ///   configured one
     ///
     /// Returns: operation_id for tracking progress
     ///
@@ -333,6 +782,7 @@ impl ImageHandler {
         #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
         device: String,
         output_path: String,
+        priority: String,
     ) -> zbus::fdo::Result<String> {
         tracing::info!(
             "Starting partition backup: {device} → {output_path} (UID {})",
@@ -341,6 +791,7 @@ impl ImageHandler {
 
         self.domain
             .validate_output_path_parent_exists(&output_path)?;
+        let priority = crate::priority::resolve(OperationKind::ImageBackup, &priority)?;
 
         // Normalize device path
         let device_path = if device.starts_with("/dev/") {
@@ -349,37 +800,59 @@ impl ImageHandler {
             format!("/dev/{}", device)
         };
 
-        let operation_id = Self::generate_operation_id();
+        let mut operation = TrackedOperation::begin(
+            ContractKind::Image,
+            &device_path,
+            "backup_partition",
+            Some(caller.uid),
+        );
+        let operation_id = operation.id().as_uuid().to_string();
 
-        let progress = Arc::new(Mutex::new(ProgressInfo {
-            bytes_completed: 0,
-            total_bytes: 0,
-            speed_bytes_per_sec: 0,
-            started_at: Instant::now(),
-        }));
+        let progress = Arc::new(Mutex::new(ProgressInfo::new()));
 
         let cancel_token = CancellationToken::new();
 
+        operation.on_cancel({
+            let cancel_token = cancel_token.clone();
+
+            move || cancel_token.cancel()
+        });
+
         let task_progress = progress.clone();
         let task_cancel = cancel_token.clone();
         let task_output_path = output_path.clone();
         let task_device_path = device_path.clone();
 
+        let task_uid = caller.uid;
+
+        let throughput_key =
+            crate::throughput::key(&OperationType::BackupPartition, &device, &output_path);
+
         let handle = tokio::spawn(async move {
-            Self::backup_task(
-                task_device_path,
-                task_output_path,
-                task_cancel,
-                task_progress,
+            let result = Self::follow(
+                operation,
+                task_progress.clone(),
+                Self::backup_task(
+                    task_device_path,
+                    task_output_path,
+                    task_cancel,
+                    task_progress.clone(),
+                    priority,
+                    task_uid,
+                ),
             )
-            .await
+            .await;
+            task_progress
+                .lock()
+                .await
+                .finish(&throughput_key, result.is_ok());
+            result
         });
 
         let op_state = OperationState {
             kind: OperationType::BackupPartition,
             source: device.clone(),
             destination: output_path.clone(),
-            cancel_token,
             handle,
             progress,
         };
@@ -406,6 +879,8 @@ impl ImageHandler {
     /// Args:
     /// - device: Device identifier (e.g., "/dev/sda")
     /// - image_path: Path to image file
+    /// - priority: JSON `OperationPriority` for this restore, or empty for the
+    ///   configured one
     ///
     /// Returns: operation_id for tracking progress
     ///
@@ -418,6 +893,7 @@ impl ImageHandler {
         #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
         device: String,
         image_path: String,
+        priority: String,
     ) -> zbus::fdo::Result<String> {
         tracing::warn!(
             "Starting DESTRUCTIVE drive restore: {image_path} → {device} (UID {})",
@@ -430,6 +906,7 @@ impl ImageHandler {
                 "Image file does not exist: {image_path}"
             )));
         }
+        let priority = crate::priority::resolve(OperationKind::ImageRestore, &priority)?;
 
         // Normalize device path
         let device_path = if device.starts_with("/dev/") {
@@ -438,37 +915,56 @@ impl ImageHandler {
             format!("/dev/{}", device)
         };
 
-        let operation_id = Self::generate_operation_id();
+        let mut operation = TrackedOperation::begin(
+            ContractKind::Image,
+            &device_path,
+            "restore_drive",
+            Some(caller.uid),
+        );
+        let operation_id = operation.id().as_uuid().to_string();
 
-        let progress = Arc::new(Mutex::new(ProgressInfo {
-            bytes_completed: 0,
-            total_bytes: 0,
-            speed_bytes_per_sec: 0,
-            started_at: Instant::now(),
-        }));
+        let progress = Arc::new(Mutex::new(ProgressInfo::new()));
 
         let cancel_token = CancellationToken::new();
 
+        operation.on_cancel({
+            let cancel_token = cancel_token.clone();
+
+            move || cancel_token.cancel()
+        });
+
         let task_progress = progress.clone();
         let task_cancel = cancel_token.clone();
         let task_image_path = image_path.clone();
         let task_device_path = device_path.clone();
 
+        let throughput_key =
+            crate::throughput::key(&OperationType::RestoreDrive, &image_path, &device);
+
         let handle = tokio::spawn(async move {
-            Self::restore_task(
-                task_image_path,
-                task_device_path,
-                task_cancel,
-                task_progress,
+            let result = Self::follow(
+                operation,
+                task_progress.clone(),
+                Self::restore_task(
+                    task_image_path,
+                    task_device_path,
+                    task_cancel,
+                    task_progress.clone(),
+                    priority,
+                ),
             )
-            .await
+            .await;
+            task_progress
+                .lock()
+                .await
+                .finish(&throughput_key, result.is_ok());
+            result
         });
 
         let op_state = OperationState {
             kind: OperationType::RestoreDrive,
             source: image_path.clone(),
             destination: device.clone(),
-            cancel_token,
             handle,
             progress,
         };
@@ -495,6 +991,8 @@ impl ImageHandler {
     /// Args:
     /// - device: Partition identifier (e.g., "/dev/sda1")
     /// - image_path: Path to image file
+    /// - priority: JSON `OperationPriority` for this restore, or empty for the
+    ///   configured one
     ///
     /// Returns: operation_id for tracking progress
     ///
@@ -507,6 +1005,7 @@ impl ImageHandler {
         #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
         device: String,
         image_path: String,
+        priority: String,
     ) -> zbus::fdo::Result<String> {
         tracing::warn!(
             "Starting DESTRUCTIVE partition restore: {image_path} → {device} (UID {})",
@@ -519,6 +1018,7 @@ impl ImageHandler {
                 "Image file does not exist: {image_path}"
             )));
         }
+        let priority = crate::priority::resolve(OperationKind::ImageRestore, &priority)?;
 
         // Normalize device path
         let device_path = if device.starts_with("/dev/") {
@@ -527,37 +1027,56 @@ impl ImageHandler {
             format!("/dev/{}", device)
         };
 
-        let operation_id = Self::generate_operation_id();
+        let mut operation = TrackedOperation::begin(
+            ContractKind::Image,
+            &device_path,
+            "restore_partition",
+            Some(caller.uid),
+        );
+        let operation_id = operation.id().as_uuid().to_string();
 
-        let progress = Arc::new(Mutex::new(ProgressInfo {
-            bytes_completed: 0,
-            total_bytes: 0,
-            speed_bytes_per_sec: 0,
-            started_at: Instant::now(),
-        }));
+        let progress = Arc::new(Mutex::new(ProgressInfo::new()));
 
         let cancel_token = CancellationToken::new();
 
+        operation.on_cancel({
+            let cancel_token = cancel_token.clone();
+
+            move || cancel_token.cancel()
+        });
+
         let task_progress = progress.clone();
         let task_cancel = cancel_token.clone();
         let task_image_path = image_path.clone();
         let task_device_path = device_path.clone();
 
+        let throughput_key =
+            crate::throughput::key(&OperationType::RestorePartition, &image_path, &device);
+
         let handle = tokio::spawn(async move {
-            Self::restore_task(
-                task_image_path,
-                task_device_path,
-                task_cancel,
-                task_progress,
+            let result = Self::follow(
+                operation,
+                task_progress.clone(),
+                Self::restore_task(
+                    task_image_path,
+                    task_device_path,
+                    task_cancel,
+                    task_progress.clone(),
+                    priority,
+                ),
             )
-            .await
+            .await;
+            task_progress
+                .lock()
+                .await
+                .finish(&throughput_key, result.is_ok());
+            result
         });
 
         let op_state = OperationState {
             kind: OperationType::RestorePartition,
             source: image_path.clone(),
             destination: device.clone(),
-            cancel_token,
             handle,
             progress,
         };
@@ -579,6 +1098,182 @@ impl ImageHandler {
         Ok(operation_id)
     }
 
+    /// Copy a partition onto another one at least as large
+    ///
+    /// The copy gets a new filesystem UUID and grows to fill the target,
+    /// where its filesystem allows that.
+    ///
+    /// Args:
+    /// - source: Partition to copy (e.g., "/dev/sda1")
+    /// - target: Partition to overwrite (e.g., "/dev/sdb1")
+    /// - priority: JSON `OperationPriority` for this copy, or empty for the
+    ///   configured one
+    ///
+    /// Returns: operation_id for tracking progress
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-restore (always prompts)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-restore")]
+    async fn copy_partition(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
+        source: String,
+        target: String,
+        priority: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::warn!(
+            "Starting DESTRUCTIVE partition copy: {source} → {target} (UID {})",
+            caller.uid
+        );
+
+        let device_path = |device: &str| format!("/dev/{}", device.trim_start_matches("/dev/"));
+        let source_path = device_path(&source);
+        let target_path = device_path(&target);
+        if source_path == target_path {
+            return Err(zbus::fdo::Error::Failed(
+                "A partition cannot be copied onto itself".to_string(),
+            ));
+        }
+        let priority = crate::priority::resolve(OperationKind::PartitionCopy, &priority)?;
+
+        self.start_operation(
+            &signal_ctx,
+            OperationType::CopyPartition,
+            caller.uid,
+            source,
+            target,
+            move |cancel_token, progress| {
+                Self::copy_task(source_path, target_path, cancel_token, progress, priority)
+            },
+        )
+        .await
+    }
+
+    /// Migrate a disk onto a larger one
+    ///
+    /// The target gets the source's partition table laid out as `plan`
+    /// says, then every partition is copied and grown. When a step fails,
+    /// the target is left blank.
+    ///
+    /// Args:
+    /// - source: Disk to migrate (e.g., "/dev/sda")
+    /// - target: Disk to overwrite (e.g., "/dev/nvme0n1")
+    /// - plan: JSON-serialized MigrationPlan
+    ///
+    /// Returns: operation_id for tracking progress
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-restore (always prompts)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-restore")]
+    async fn migrate_disk(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
+        source: String,
+        target: String,
+        plan: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::warn!(
+            "Starting DESTRUCTIVE disk migration: {source} → {target} (UID {})",
+            caller.uid
+        );
+
+        let plan: storage_types::MigrationPlan = serde_json::from_str(&plan)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid migration plan: {e}")))?;
+        let device_path = |device: &str| format!("/dev/{}", device.trim_start_matches("/dev/"));
+        let source_path = device_path(&source);
+        let target_path = device_path(&target);
+
+        self.start_operation(
+            &signal_ctx,
+            OperationType::MigrateDisk,
+            caller.uid,
+            source,
+            target,
+            move |cancel_token, progress| {
+                Self::migrate_task(source_path, target_path, plan, cancel_token, progress)
+            },
+        )
+        .await
+    }
+
+    /// Inspect an ISO image for making a live USB drive from it
+    ///
+    /// Args:
+    /// - image_path: Path to the ISO image
+    ///
+    /// Returns: JSON-serialized LiveIsoInfo
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-loop-setup
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-loop-setup")]
+    async fn probe_live_iso(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        image_path: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Probing live ISO {image_path} (UID {})", caller.uid);
+
+        let info = tokio::task::spawn_blocking(move || storage_sys::probe_live_iso(&image_path))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to inspect the image: {e}")))?;
+        serde_json::to_string(&info)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to serialize image info: {e}")))
+    }
+
+    /// Make a bootable USB drive from an ISO image, with a persistence
+    /// partition and an exFAT data partition after the image
+    ///
+    /// When a step fails, the drive is left blank.
+    ///
+    /// Args:
+    /// - device: Drive to overwrite (e.g., "/dev/sdb")
+    /// - image_path: Path to the hybrid ISO image
+    /// - plan: JSON-serialized LiveUsbPlan
+    ///
+    /// Returns: operation_id for tracking progress
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-restore (always prompts)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-restore")]
+    async fn create_live_usb(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
+        device: String,
+        image_path: String,
+        plan: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::warn!(
+            "Starting DESTRUCTIVE live USB creation: {image_path} → {device} (UID {})",
+            caller.uid
+        );
+
+        let plan: storage_types::LiveUsbPlan = serde_json::from_str(&plan)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid live USB plan: {e}")))?;
+        if !Path::new(&image_path).is_file() {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "Image file does not exist: {image_path}"
+            )));
+        }
+        let device_path = format!("/dev/{}", device.trim_start_matches("/dev/"));
+
+        let task_image_path = image_path.clone();
+        self.start_operation(
+            &signal_ctx,
+            OperationType::CreateLiveUsb,
+            caller.uid,
+            image_path,
+            device,
+            move |cancel_token, progress| {
+                Self::live_usb_task(task_image_path, device_path, plan, cancel_token, progress)
+            },
+        )
+        .await
+    }
+
     /// Mount an image file as a loop device
     ///
     /// Args:
@@ -606,8 +1301,10 @@ impl ImageHandler {
             )));
         }
 
-        // Call storage-udisks loop_setup_device_path (returns device path directly)
-        let device_path = storage_udisks::loop_setup_device_path(&image_path)
+        // Attach through the configured backend (returns device path directly)
+        let device_path = crate::backend::current()
+            .images
+            .loop_setup_device_path(&image_path)
             .await
             .map_err(|e| {
                 tracing::error!("Loop setup failed: {e}");
@@ -621,6 +1318,139 @@ impl ImageHandler {
         Ok(device_name.to_string())
     }
 
+    /// Burn an ISO image to the disc in an optical drive
+    ///
+    /// A written rewritable disc is erased first.
+    ///
+    /// Args:
+    /// - device: Optical drive (e.g., "/dev/sr0")
+    /// - image_path: Path to the ISO image
+    /// - verify: Read the disc back and compare it with the image
+    ///
+    /// Returns: operation_id for tracking progress
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disc-write (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disc-write")]
+    async fn burn_disc(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
+        device: String,
+        image_path: String,
+        verify: bool,
+    ) -> zbus::fdo::Result<String> {
+        tracing::info!(
+            "Starting disc burn: {image_path} → {device} (UID {})",
+            caller.uid
+        );
+
+        let image_size = std::fs::metadata(&image_path)
+            .map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Image file does not exist: {image_path}: {e}"))
+            })?
+            .len();
+        let (device_path, media) = Self::optical_target(&device).await?;
+        media
+            .can_burn(image_size)
+            .map_err(zbus::fdo::Error::Failed)?;
+
+        let task_image_path = image_path.clone();
+        self.start_operation(
+            &signal_ctx,
+            OperationType::BurnDisc,
+            caller.uid,
+            image_path,
+            device,
+            move |cancel_token, progress| {
+                Self::burn_task(task_image_path, device_path, verify, cancel_token, progress)
+            },
+        )
+        .await
+    }
+
+    /// Erase the rewritable disc in an optical drive
+    ///
+    /// Args:
+    /// - device: Optical drive (e.g., "/dev/sr0")
+    /// - full: Overwrite the whole disc instead of only its table of contents
+    ///
+    /// Returns: operation_id for tracking progress
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disc-write (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disc-write")]
+    async fn blank_disc(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: SignalEmitter<'_>,
+        device: String,
+        full: bool,
+    ) -> zbus::fdo::Result<String> {
+        tracing::warn!(
+            "Starting DESTRUCTIVE disc erase of {device} (full: {full}, UID {})",
+            caller.uid
+        );
+
+        let (device_path, media) = Self::optical_target(&device).await?;
+        if !media.is_rewritable() {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "A {} cannot be erased",
+                media.media_type
+            )));
+        }
+
+        self.start_operation(
+            &signal_ctx,
+            OperationType::BlankDisc,
+            caller.uid,
+            device.clone(),
+            device,
+            move |cancel_token, progress| {
+                Self::blank_task(device_path, full, cancel_token, progress)
+            },
+        )
+        .await
+    }
+
+    /// Get the configured CPU and I/O priority of long-running operations
+    /// as JSON `PrioritySettings`
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn get_operation_priorities(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Reading operation priorities (UID {})", caller.uid);
+
+        serde_json::to_string(&crate::priority::load_settings())
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {e}")))
+    }
+
+    /// Set the CPU and I/O priority of long-running operations
+    ///
+    /// Operations started from then on run with them, unless their caller
+    /// passes a priority of its own.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-tune")]
+    async fn set_operation_priorities(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        settings_json: &str,
+    ) -> zbus::fdo::Result<()> {
+        let settings: PrioritySettings = serde_json::from_str(settings_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid settings: {e}")))?;
+        settings.validate().map_err(zbus::fdo::Error::InvalidArgs)?;
+        tracing::info!(
+            "Setting operation priorities {:?} (UID {})",
+            settings,
+            caller.uid
+        );
+
+        crate::priority::save_settings(&settings)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save settings: {e}")))
+    }
+
     /// Cancel a running operation
     ///
     /// Args:
@@ -628,10 +1458,13 @@ impl ImageHandler {
     async fn cancel_operation(&self, operation_id: String) -> zbus::fdo::Result<()> {
         let ops = self.active_operations.lock().await;
 
-        if let Some(op) = ops.get(&operation_id) {
+        if ops.contains_key(&operation_id) {
             tracing::info!("Cancelling operation: {operation_id}");
-            op.cancel_token.cancel();
-            Ok(())
+            // Through the running operations, so that it ends as cancelled
+            let id = operation_id.parse().map_err(|_| {
+                zbus::fdo::Error::InvalidArgs(format!("Invalid operation ID: {operation_id}"))
+            })?;
+            crate::handlers::operations::cancel(id)
         } else {
             Err(zbus::fdo::Error::Failed(format!(
                 "Operation not found: {operation_id}"
@@ -651,6 +1484,16 @@ impl ImageHandler {
         if let Some(op) = ops.get(&operation_id) {
             let progress = op.progress.lock().await;
             let elapsed = progress.started_at.elapsed().as_secs();
+            let eta = crate::throughput::history(&crate::throughput::key(
+                &op.kind,
+                &op.source,
+                &op.destination,
+            ))
+            .estimate(
+                progress.bytes_completed,
+                progress.total_bytes,
+                progress.started_at.elapsed().as_secs_f64(),
+            );
 
             let status = serde_json::json!({
                 "operation_id": operation_id,
@@ -661,6 +1504,7 @@ impl ImageHandler {
                 "total_bytes": progress.total_bytes,
                 "speed_bytes_per_sec": progress.speed_bytes_per_sec,
                 "elapsed_seconds": elapsed,
+                "eta": eta,
                 "is_finished": op.handle.is_finished(),
             });
 
@@ -672,6 +1516,54 @@ impl ImageHandler {
         }
     }
 
+    /// Get the recorded speed of past operations like a running one, so
+    /// that clients following its progress counter can estimate its
+    /// remaining time
+    ///
+    /// Args:
+    /// - operation_id: ID returned from backup/restore methods
+    ///
+    /// Returns: JSON-serialized ThroughputHistory, without runs when none
+    /// were recorded yet
+    async fn get_throughput_history(&self, operation_id: String) -> zbus::fdo::Result<String> {
+        let key = {
+            let ops = self.active_operations.lock().await;
+            let op = ops.get(&operation_id).ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!("Operation not found: {operation_id}"))
+            })?;
+            crate::throughput::key(&op.kind, &op.source, &op.destination)
+        };
+
+        serde_json::to_string(&crate::throughput::history(&key))
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {e}")))
+    }
+
+    /// Get the shared progress counter of an operation, for clients that
+    /// redraw progress more often than polling the bus allows
+    ///
+    /// Args:
+    /// - operation_id: ID returned from backup/restore methods
+    ///
+    /// Returns: A memory file holding the operation's ProgressRecord, and an
+    /// eventfd that is signalled each time the record changes
+    async fn get_progress_fds(
+        &self,
+        operation_id: String,
+    ) -> zbus::fdo::Result<(zbus::zvariant::OwnedFd, zbus::zvariant::OwnedFd)> {
+        let ops = self.active_operations.lock().await;
+        let op = ops.get(&operation_id).ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!("Operation not found: {operation_id}"))
+        })?;
+        let progress = op.progress.lock().await;
+        let counter = progress.counter.as_ref().ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!("Operation {operation_id} has no progress counter"))
+        })?;
+        let (record, event) = counter.fds().map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to share progress counter: {e}"))
+        })?;
+        Ok((record.into(), event.into()))
+    }
+
     /// List all active operations
     ///
     /// Returns: JSON array of operation status
@@ -12,6 +12,7 @@ use storage_types::{LogicalVolumeInfo, PhysicalVolumeInfo, VolumeGroupInfo};
 use zbus::message::Header as MessageHeader;
 use zbus::{Connection, interface};
 
+use crate::hooks::Operation;
 use crate::policies::lvm::{LvmDomain, LvmPolicy};
 
 /// D-Bus interface for LVM management operations
@@ -148,7 +149,8 @@ impl LvmHandler {
 
     /// List all logical volumes
     ///
-    /// Returns: JSON-serialized Vec<LogicalVolumeInfo>
+    /// Returns: JSON-serialized ResultPage of a Vec<LogicalVolumeInfo>, see
+    /// GetPage
     ///
     /// Authorization: org.cosmic.ext.storage.service.lvm-read (allow_active)
     #[authorized_interface(action = "org.cosmic.ext.storage.service.lvm-read")]
@@ -220,10 +222,7 @@ impl LvmHandler {
 
         tracing::debug!("Found {} logical volumes", lvs.len());
 
-        let json = serde_json::to_string(&lvs)
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialize error: {e}")))?;
-
-        Ok(json)
+        crate::paging::reply(&caller.sender, &lvs)
     }
 
     /// List all physical volumes
@@ -440,6 +439,13 @@ impl LvmHandler {
             caller.uid
         );
 
+        let device = if lv_path.starts_with("/dev/") {
+            lv_path.clone()
+        } else {
+            format!("/dev/{lv_path}")
+        };
+        crate::hooks::before_device(Operation::Resize, &device).await?;
+
         // Run lvresize with new size in bytes
         let size_arg = format!("{}B", new_size_bytes);
         let output = Command::new("lvresize")
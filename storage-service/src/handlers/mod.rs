@@ -6,6 +6,8 @@ pub mod filesystem;
 pub mod image;
 pub mod luks;
 pub mod lvm;
+pub mod operations;
 pub mod partition;
+pub mod raid;
 pub mod rclone;
 pub mod service;
@@ -0,0 +1,389 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Running long operations D-Bus interface
+//!
+//! Formatting, resizing, checking and image operations register here while
+//! they run, so that clients can show the progress of all of them in one
+//! place instead of waiting on each method call. Every change is reported
+//! with an `OperationProgress` signal and the end with `OperationFinished`;
+//! `ListOperations` gives the ones already running when a client starts.
+//! Operations that know how to stop register a canceller, which
+//! `CancelOperation` calls; they end with `Cancelled` instead of `Failed`.
+//! Only the user who started an operation can cancel it without
+//! authenticating as an administrator.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use storage_contracts::{
+    OperationEvent, OperationId, OperationKind, OperationProgress, StorageError, StorageErrorKind,
+};
+use storage_macros::authorized_interface;
+use tokio::sync::mpsc;
+use zbus::message::Header as MessageHeader;
+use zbus::object_server::SignalEmitter;
+use zbus::{Connection, interface};
+
+/// Object path of the interface
+pub const OPERATIONS_PATH: &str = "/org/cosmic/ext/Storage/Service/operations";
+
+/// Running operations by ID
+static RUNNING: LazyLock<Mutex<HashMap<OperationId, OperationProgress>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Stops a running operation
+type Canceller = Box<dyn Fn() + Send>;
+
+/// How to stop the running operations that can be cancelled, with the
+/// UID of the user who started them, by ID
+static CANCELLERS: LazyLock<Mutex<HashMap<OperationId, (Option<u32>, Canceller)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Queue of events to emit, in order, once the connection is up
+static EVENTS: OnceLock<mpsc::UnboundedSender<OperationEvent>> = OnceLock::new();
+
+fn report(event: OperationEvent) {
+    if let Some(events) = EVENTS.get() {
+        let _ = events.send(event);
+    }
+}
+
+/// A running operation, listed until it is finished or dropped
+pub struct TrackedOperation {
+    progress: OperationProgress,
+    /// UID of the user who started it, None when the service did
+    owner: Option<u32>,
+    finished: bool,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TrackedOperation {
+    /// Register an operation of `kind` on `target`, starting with `phase`,
+    /// on behalf of the user `owner`
+    pub fn begin(kind: OperationKind, target: &str, phase: &str, owner: Option<u32>) -> Self {
+        let progress = OperationProgress {
+            operation_id: OperationId::new(),
+            operation: kind,
+            target: target.to_string(),
+            phase: phase.to_string(),
+            bytes_processed: 0,
+            bytes_total: None,
+            percent: None,
+            cancellable: false,
+        };
+        tracing::debug!(
+            "Operation {} started: {phase} {target}",
+            progress.operation_id.as_uuid()
+        );
+        let operation = Self {
+            progress,
+            owner,
+            finished: false,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        operation.publish();
+        operation
+    }
+
+    pub fn id(&self) -> OperationId {
+        self.progress.operation_id
+    }
+
+    /// Let clients stop the operation with `CancelOperation`, which calls
+    /// `cancel`; the operation still has to wind down and `finish`
+    pub fn on_cancel(&mut self, cancel: impl Fn() + Send + 'static) {
+        let cancelled = self.cancelled.clone();
+        CANCELLERS.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            self.progress.operation_id,
+            (
+                self.owner,
+                Box::new(move || {
+                    cancelled.store(true, Ordering::SeqCst);
+                    cancel();
+                }),
+            ),
+        );
+        self.progress.cancellable = true;
+        self.publish();
+    }
+
+    /// Whether a client asked to stop the operation
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Report the bytes processed so far, out of `bytes_total` when known
+    pub fn update(&mut self, bytes_processed: u64, bytes_total: Option<u64>) {
+        let percent = bytes_total
+            .filter(|total| *total > 0)
+            .map(|total| (bytes_processed.min(total) * 100 / total) as u8);
+        if self.progress.bytes_processed == bytes_processed
+            && self.progress.bytes_total == bytes_total
+        {
+            return;
+        }
+        self.progress.bytes_processed = bytes_processed;
+        self.progress.bytes_total = bytes_total;
+        self.progress.percent = percent;
+        self.publish();
+    }
+
+    /// Report progress counted in something else than bytes, e.g. files
+    pub fn update_count(&mut self, done: u64, total: Option<u64>) {
+        let percent = total
+            .filter(|total| *total > 0)
+            .map(|total| (done.min(total) * 100 / total) as u8);
+        if self.progress.percent == percent {
+            return;
+        }
+        self.progress.percent = percent;
+        self.publish();
+    }
+
+    /// Move on to the next phase, e.g. from "formatting" to "tuning"
+    pub fn set_phase(&mut self, phase: &str) {
+        self.progress.phase = phase.to_string();
+        self.publish();
+    }
+
+    /// Report the end of the operation; a failure after a cancel request is
+    /// reported as cancelled
+    pub fn finish(mut self, result: Result<(), String>) {
+        self.finished = true;
+        self.remove();
+        let (operation_id, operation) = (self.progress.operation_id, self.progress.operation);
+        report(match result {
+            Ok(()) => OperationEvent::Completed {
+                operation_id,
+                operation,
+            },
+            Err(_) if self.is_cancelled() => OperationEvent::Cancelled {
+                operation_id,
+                operation,
+            },
+            Err(message) => OperationEvent::Failed {
+                operation_id,
+                operation,
+                error: StorageError::new(StorageErrorKind::Internal, message),
+            },
+        });
+    }
+
+    fn publish(&self) {
+        RUNNING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(self.progress.operation_id, self.progress.clone());
+        report(OperationEvent::Progress(self.progress.clone()));
+    }
+
+    fn remove(&self) {
+        RUNNING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.progress.operation_id);
+        CANCELLERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.progress.operation_id);
+    }
+}
+
+impl Drop for TrackedOperation {
+    fn drop(&mut self) {
+        // The method call was dropped, e.g. as its client went away
+        if !self.finished {
+            self.remove();
+            if self.is_cancelled() {
+                report(OperationEvent::Cancelled {
+                    operation_id: self.progress.operation_id,
+                    operation: self.progress.operation,
+                });
+                return;
+            }
+            report(OperationEvent::Failed {
+                operation_id: self.progress.operation_id,
+                operation: self.progress.operation,
+                error: StorageError::new(StorageErrorKind::Internal, "Operation abandoned"),
+            });
+        }
+    }
+}
+
+/// Run `operation` as a tracked operation of `kind` on `target`, on behalf
+/// of the user `owner`
+pub async fn track<T, E: Display>(
+    kind: OperationKind,
+    target: &str,
+    phase: &str,
+    owner: Option<u32>,
+    operation: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let tracked = TrackedOperation::begin(kind, target, phase, owner);
+    let result = operation.await;
+    tracked.finish(result.as_ref().map(|_| ()).map_err(ToString::to_string));
+    result
+}
+
+/// UID of the user who started the cancellable operation `operation_id`,
+/// None when the service started it or it is not running
+fn owner(operation_id: OperationId) -> Option<u32> {
+    CANCELLERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&operation_id)
+        .and_then(|(owner, _)| *owner)
+}
+
+/// Call the canceller of the running operation `operation_id`
+pub fn cancel(operation_id: OperationId) -> zbus::fdo::Result<()> {
+    let cancellers = CANCELLERS.lock().unwrap_or_else(|e| e.into_inner());
+    match cancellers.get(&operation_id) {
+        Some((_, cancel)) => {
+            cancel();
+            Ok(())
+        }
+        None if RUNNING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(&operation_id) =>
+        {
+            Err(zbus::fdo::Error::NotSupported(
+                "This operation can't be cancelled".to_string(),
+            ))
+        }
+        None => Err(zbus::fdo::Error::Failed(format!(
+            "Operation not found: {}",
+            operation_id.as_uuid()
+        ))),
+    }
+}
+
+/// Start emitting the events of tracked operations on `connection`
+pub async fn report_operations(connection: Connection) -> anyhow::Result<()> {
+    let emitter = SignalEmitter::new(&connection, OPERATIONS_PATH)?.into_owned();
+    let (sender, mut events) = mpsc::unbounded_channel();
+    if EVENTS.set(sender).is_err() {
+        return Ok(());
+    }
+
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            let json = match serde_json::to_string(&event) {
+                Ok(json) => json,
+                Err(e) => {
+                    tracing::warn!("Failed to serialize operation event: {e}");
+                    continue;
+                }
+            };
+            let result = match event {
+                OperationEvent::Progress(_) => {
+                    OperationsHandler::operation_progress(&emitter, &json).await
+                }
+                OperationEvent::Completed { .. }
+                | OperationEvent::Failed { .. }
+                | OperationEvent::Cancelled { .. } => {
+                    OperationsHandler::operation_finished(&emitter, &json).await
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to emit operation event: {e}");
+            }
+        }
+    });
+    Ok(())
+}
+
+/// D-Bus interface listing running long operations
+pub struct OperationsHandler;
+
+#[interface(name = "org.cosmic.ext.Storage.Service.Operations")]
+impl OperationsHandler {
+    /// Signal emitted when an operation starts or makes progress, with a
+    /// JSON `OperationEvent::Progress`
+    #[zbus(signal)]
+    async fn operation_progress(
+        signal_ctxt: &SignalEmitter<'_>,
+        event_json: &str,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted when an operation completed, failed or was cancelled,
+    /// with a JSON `OperationEvent`
+    #[zbus(signal)]
+    async fn operation_finished(
+        signal_ctxt: &SignalEmitter<'_>,
+        event_json: &str,
+    ) -> zbus::Result<()>;
+
+    /// List the running operations
+    ///
+    /// Returns: JSON-serialized Vec<OperationProgress>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn list_operations(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Listing running operations for UID {}", caller.uid);
+
+        let mut operations: Vec<OperationProgress> = RUNNING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        operations.sort_by(|a, b| a.target.cmp(&b.target));
+
+        serde_json::to_string(&operations).map_err(|e| {
+            tracing::error!("Failed to serialize operations: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Ask a running operation to stop
+    ///
+    /// Args:
+    /// - operation_id: ID of the operation, as listed
+    ///
+    /// The operation ends with a `Cancelled` event once it stopped.
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.operation-cancel (allow_active);
+    /// operations started by another user or by the service itself also need
+    /// org.cosmic.ext.storage.service.operation-cancel-any (auth_admin)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.operation-cancel")]
+    async fn cancel_operation(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        operation_id: String,
+    ) -> zbus::fdo::Result<()> {
+        let operation_id: OperationId = operation_id.parse().map_err(|_| {
+            zbus::fdo::Error::InvalidArgs(format!("Invalid operation ID: {operation_id}"))
+        })?;
+        tracing::info!(
+            "Cancelling operation {} (UID {})",
+            operation_id.as_uuid(),
+            caller.uid
+        );
+
+        if owner(operation_id) != Some(caller.uid) {
+            let authorized = crate::auth::check_authorization(
+                connection,
+                &caller.sender,
+                "org.cosmic.ext.storage.service.operation-cancel-any",
+            )
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Authorization check failed: {e}")))?;
+            if !authorized {
+                return Err(zbus::fdo::Error::AccessDenied(
+                    "Not authorized to cancel an operation started by someone else".to_string(),
+                ));
+            }
+        }
+
+        cancel(operation_id)
+    }
+}
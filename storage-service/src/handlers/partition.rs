@@ -6,15 +6,25 @@
 //! including creating/deleting partitions and partition tables.
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use storage_contracts::{OperationKind, PartitionOpsAdapter};
 use storage_macros::authorized_interface;
+use storage_types::EspSyncPair;
+use storage_types::resize::{ResizeBounds, ResizeStep, can_shrink, resize_bounds};
 use zbus::message::Header as MessageHeader;
 use zbus::{Connection, interface};
 
+use crate::handlers::operations::{TrackedOperation, track};
+use crate::hooks::Operation;
 use crate::policies::partition::{PartitionsDomain, PartitionsPolicy};
 
+pub mod esp;
+pub mod undelete;
+
 /// D-Bus interface for partition management operations
 pub struct PartitionHandler {
     domain: Arc<dyn PartitionsDomain>,
+    ops: Arc<dyn PartitionOpsAdapter>,
 }
 
 impl PartitionHandler {
@@ -22,6 +32,7 @@ impl PartitionHandler {
     pub fn new() -> Self {
         Self {
             domain: Arc::new(PartitionsPolicy),
+            ops: crate::backend::current().partitions.clone(),
         }
     }
 }
@@ -97,18 +108,11 @@ impl PartitionHandler {
     ) -> zbus::fdo::Result<String> {
         tracing::debug!("Listing partitions for disk: {disk} (UID {})", caller.uid);
 
-        let manager = storage_udisks::DiskManager::new().await.map_err(|e| {
-            tracing::error!("Failed to initialize disk manager: {e}");
-            zbus::fdo::Error::Failed(format!("Failed to initialize disk manager: {e}"))
+        let disk_volumes = self.ops.list_disks_with_partitions().await.map_err(|e| {
+            tracing::error!("Failed to get drives: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to enumerate drives: {e}"))
         })?;
 
-        let disk_volumes = storage_udisks::disk::get_disks_with_partitions(&manager)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to get drives: {e}");
-                zbus::fdo::Error::Failed(format!("Failed to enumerate drives: {e}"))
-            })?;
-
         let device_name = disk.strip_prefix("/dev/").unwrap_or(&disk);
         let (_disk_info, partitions) = disk_volumes
             .into_iter()
@@ -161,14 +165,17 @@ impl PartitionHandler {
 
         let disk_device = self.domain.normalize_disk_device(&disk);
 
-        let block_path = storage_udisks::block_object_path_for_device(&disk_device)
+        let block_path = self
+            .ops
+            .resolve_block_path_for_device(&disk_device)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to resolve device: {e}");
                 zbus::fdo::Error::Failed(format!("Device not found: {e}"))
             })?;
 
-        storage_udisks::create_partition_table(block_path.as_str(), &normalized_type)
+        self.ops
+            .create_partition_table(block_path.as_str(), &normalized_type)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to create partition table: {e}");
@@ -217,20 +224,23 @@ impl PartitionHandler {
 
         let disk_device = self.domain.normalize_disk_device(&disk);
 
-        let block_path = storage_udisks::block_object_path_for_device(&disk_device)
+        let block_path = self
+            .ops
+            .resolve_block_path_for_device(&disk_device)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to resolve device: {e}");
                 zbus::fdo::Error::Failed(format!("Device not found: {e}"))
             })?;
 
-        let device_path =
-            storage_udisks::create_partition(block_path.as_str(), offset, size, &type_id)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to create partition: {e}");
-                    zbus::fdo::Error::Failed(format!("Failed to create partition: {e}"))
-                })?;
+        let device_path = self
+            .ops
+            .create_partition(block_path.as_str(), offset, size, &type_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to create partition: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to create partition: {e}"))
+            })?;
 
         tracing::info!("Successfully created partition: {}", device_path);
         let partition_info_json = serde_json::to_string(&storage_types::PartitionInfo {
@@ -293,20 +303,23 @@ impl PartitionHandler {
 
         let disk_device = self.domain.normalize_disk_device(&disk);
 
-        let block_path = storage_udisks::block_object_path_for_device(&disk_device)
+        let block_path = self
+            .ops
+            .resolve_block_path_for_device(&disk_device)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to resolve device: {e}");
                 zbus::fdo::Error::Failed(format!("Device not found: {e}"))
             })?;
 
-        let device_path =
-            storage_udisks::create_partition_with_filesystem(block_path.as_str(), &info)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to create partition with filesystem: {e}");
-                    zbus::fdo::Error::Failed(format!("Failed to create partition: {e}"))
-                })?;
+        let device_path = self
+            .ops
+            .create_partition_with_filesystem(block_path.as_str(), &info)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to create partition with filesystem: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to create partition: {e}"))
+            })?;
 
         tracing::info!(
             "Successfully created partition with filesystem: {}",
@@ -359,8 +372,12 @@ impl PartitionHandler {
         // Find partition path from device
         let partition_path = self.find_partition_path(&partition).await?;
 
-        // Delegate to storage-udisks operation
-        storage_udisks::delete_partition(&partition_path)
+        // Kept so the partition can be restored this session
+        let entry = undelete::entry_of(&partition).await;
+
+        // Delegate to the backend
+        self.ops
+            .delete_partition(&partition_path)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to delete partition: {e}");
@@ -368,6 +385,9 @@ impl PartitionHandler {
             })?;
 
         tracing::info!("Successfully deleted partition: {}", partition);
+        if let Some(entry) = entry {
+            undelete::remember(entry);
+        }
         let disk = partition
             .trim_end_matches(|c: char| c.is_ascii_digit())
             .to_string();
@@ -375,6 +395,405 @@ impl PartitionHandler {
         Ok(())
     }
 
+    /// GPT partitions deleted from a disk this session whose number,
+    /// GUID and sectors are still free
+    ///
+    /// Args:
+    /// - disk: Device identifier (e.g., "/dev/sda", "sda")
+    ///
+    /// Returns: JSON-serialized Vec<DeletedPartition>, most recent first
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-read")]
+    async fn list_deleted_partitions(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        disk: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Listing partitions deleted from {disk} (UID {})",
+            caller.uid
+        );
+
+        let disk_device = self.domain.normalize_disk_device(&disk);
+        let deleted = undelete::restorable(&disk_device).await.map_err(|e| {
+            tracing::error!("Failed to list partitions deleted from {disk}: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to list deleted partitions: {e}"))
+        })?;
+
+        serde_json::to_string(&deleted).map_err(|e| {
+            tracing::error!("Failed to serialize deleted partitions: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize deleted partitions: {e}"))
+        })
+    }
+
+    /// Put a partition deleted this session back into its table, with the
+    /// GUIDs, sectors, attributes and name it had
+    ///
+    /// Args:
+    /// - id: Id of the deletion, from list_deleted_partitions
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn restore_deleted_partition(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        id: u64,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Restoring deleted partition {id} (UID {})", caller.uid);
+
+        let deleted = undelete::restore(id).await.map_err(|e| {
+            tracing::error!("Failed to restore deleted partition {id}: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to restore partition: {e}"))
+        })?;
+        tracing::info!(
+            "Restored partition {} of {}",
+            deleted.entry.number,
+            deleted.disk
+        );
+        Ok(())
+    }
+
+    /// Search the free space of a disk for deleted partitions
+    ///
+    /// Filesystem superblocks and LUKS headers without a partition are
+    /// returned with a confidence level for review. Nothing is written.
+    ///
+    /// Args:
+    /// - disk: Device identifier (e.g., "/dev/sda", "sda")
+    ///
+    /// Returns: JSON-serialized Vec<LostPartition>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-read")]
+    async fn scan_lost_partitions(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        disk: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::info!("Searching {disk} for lost partitions (UID {})", caller.uid);
+
+        let disk_device = self.domain.normalize_disk_device(&disk);
+        let device = disk_device.clone();
+        let found = tokio::task::spawn_blocking(move || storage_sys::scan_lost_partitions(&device))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to search {disk_device} for lost partitions: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to search for lost partitions: {e}"))
+            })?;
+
+        serde_json::to_string(&found).map_err(|e| {
+            tracing::error!("Failed to serialize lost partitions: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize lost partitions: {e}"))
+        })
+    }
+
+    /// Recreate a partition found by `ScanLostPartitions`
+    ///
+    /// Only the partition table entry is written; the contents of the
+    /// partition are left as they are.
+    ///
+    /// Args:
+    /// - disk: Device identifier (e.g., "/dev/sda", "sda")
+    /// - partition_json: JSON-serialized LostPartition
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn recreate_lost_partition(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        disk: String,
+        partition_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let partition: storage_types::LostPartition = serde_json::from_str(&partition_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid partition: {e}")))?;
+        tracing::info!(
+            "Recreating {} partition on {} at offset {} (UID {})",
+            partition.fs_type,
+            disk,
+            partition.offset,
+            caller.uid
+        );
+
+        let disk_device = self.domain.normalize_disk_device(&disk);
+        tokio::task::spawn_blocking(move || {
+            storage_sys::recreate_partition(&disk_device, &partition)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to recreate partition: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to recreate partition: {e}"))
+        })
+    }
+
+    /// Read the GPT of a disk directly, with the state of its checksums
+    ///
+    /// Args:
+    /// - disk: Device identifier (e.g., "/dev/sda", "sda")
+    ///
+    /// Returns: JSON-serialized GptTable
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-read")]
+    async fn get_gpt_table(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        disk: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Reading the GPT of {disk} (UID {})", caller.uid);
+
+        let disk_device = self.domain.normalize_disk_device(&disk);
+        let table = tokio::task::spawn_blocking(move || storage_sys::read_gpt(&disk_device))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to read the GPT of {disk}: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to read the GPT: {e}"))
+            })?;
+
+        serde_json::to_string(&table).map_err(|e| {
+            tracing::error!("Failed to serialize GPT: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize GPT: {e}"))
+        })
+    }
+
+    /// Edit a GPT entry directly: all 64 attribute bits, the name, the type
+    /// or its first and last sector
+    ///
+    /// The table is checked against its CRC32s before it is written.
+    ///
+    /// Args:
+    /// - disk: Device identifier (e.g., "/dev/sda", "sda")
+    /// - number: Partition number, counted from 1
+    /// - edit_json: JSON-serialized GptEdit
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn edit_gpt_entry(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        disk: String,
+        number: u32,
+        edit_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let edit: storage_types::GptEdit = serde_json::from_str(&edit_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid GPT edit: {e}")))?;
+        tracing::info!(
+            "Editing GPT entry {number} of {disk}: {edit:?} (UID {})",
+            caller.uid
+        );
+
+        let disk_device = self.domain.normalize_disk_device(&disk);
+        tokio::task::spawn_blocking(move || storage_sys::edit_gpt(&disk_device, number, &edit))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to edit GPT entry: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to edit GPT entry: {e}"))
+            })
+    }
+
+    /// Edit several GPT entries at once, checked against the table they
+    /// produce and written together
+    ///
+    /// Args:
+    /// - disk: Device identifier (e.g., "/dev/sda", "sda")
+    /// - edits_json: JSON-serialized Vec<GptEntryEdit>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn edit_gpt_entries(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        disk: String,
+        edits_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let edits: Vec<storage_types::GptEntryEdit> = serde_json::from_str(&edits_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid GPT edits: {e}")))?;
+        tracing::info!(
+            "Editing {} GPT entries of {disk}: {edits:?} (UID {})",
+            edits.len(),
+            caller.uid
+        );
+
+        let disk_device = self.domain.normalize_disk_device(&disk);
+        tokio::task::spawn_blocking(move || storage_sys::edit_gpt_entries(&disk_device, &edits))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to edit GPT entries: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to edit GPT entries: {e}"))
+            })
+    }
+
+    /// Mirror up to three GPT partitions in the MBR, for firmware that only
+    /// boots from MBR disks
+    ///
+    /// Args:
+    /// - disk: Device identifier (e.g., "/dev/sda", "sda")
+    /// - numbers: Partition numbers; empty restores the protective MBR
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn set_hybrid_mbr(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        disk: String,
+        numbers: Vec<u32>,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Setting the hybrid MBR of {disk} to {numbers:?} (UID {})",
+            caller.uid
+        );
+
+        let disk_device = self.domain.normalize_disk_device(&disk);
+        tokio::task::spawn_blocking(move || {
+            storage_sys::set_disk_hybrid_mbr(&disk_device, &numbers)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to set hybrid MBR: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to set hybrid MBR: {e}"))
+        })
+    }
+
+    /// Move a partition and its contents to start on a 1 MiB boundary, so
+    /// that it is aligned to the physical sectors of the drive
+    ///
+    /// The partition keeps its size; it must not be in use.
+    ///
+    /// Args:
+    /// - partition: Partition device path (e.g., "/dev/sda1")
+    ///
+    /// Returns: New start of the partition in bytes
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn realign_partition(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        partition: String,
+    ) -> zbus::fdo::Result<u64> {
+        tracing::info!("Realigning partition {partition} (UID {})", caller.uid);
+
+        let device = format!("/dev/{}", partition.trim_start_matches("/dev/"));
+        let offset = tokio::task::spawn_blocking(move || storage_sys::realign_partition(&device))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to realign partition: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to realign partition: {e}"))
+            })?;
+
+        tracing::info!("Partition {partition} now starts at byte {offset}");
+        let disk = partition
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_string();
+        let _ = Self::partition_modified(&signal_ctx, &disk, &partition, "").await;
+        Ok(offset)
+    }
+
+    /// Make a secondary EFI System Partition a copy of the primary one
+    ///
+    /// Changed files are copied and files the primary no longer has are
+    /// deleted from the secondary. The primary is only read.
+    ///
+    /// Args:
+    /// - primary: Partition UUID of the ESP to copy from
+    /// - secondary: Partition UUID of the ESP to copy onto
+    ///
+    /// Returns: JSON-serialized EspSyncResult
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn sync_esp(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        primary: String,
+        secondary: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::info!(
+            "Syncing ESP {secondary} from {primary} (UID {})",
+            caller.uid
+        );
+
+        let result = esp::sync(&primary, &secondary).await.map_err(|e| {
+            tracing::error!("Failed to sync ESP: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to sync ESP: {e}"))
+        })?;
+        serde_json::to_string(&result).map_err(|e| {
+            tracing::error!("Failed to serialize ESP sync result: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize ESP sync result: {e}"))
+        })
+    }
+
+    /// Get the ESP pairs that are synced on a schedule
+    ///
+    /// Returns: JSON-serialized Vec<EspSyncPair>
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-read")]
+    async fn get_esp_sync_pairs(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting ESP sync pairs (UID {})", caller.uid);
+
+        serde_json::to_string(&esp::pairs()).map_err(|e| {
+            tracing::error!("Failed to serialize ESP sync pairs: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize ESP sync pairs: {e}"))
+        })
+    }
+
+    /// Sync a secondary ESP from a primary one on a schedule, replacing the
+    /// schedule of that secondary; a pair without an interval is removed
+    ///
+    /// Args:
+    /// - pair_json: JSON-serialized EspSyncPair
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn set_esp_sync_pair(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        pair_json: String,
+    ) -> zbus::fdo::Result<()> {
+        let pair: EspSyncPair = serde_json::from_str(&pair_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid ESP pair: {e}")))?;
+        if pair.interval_days == Some(0) {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "ESP sync intervals must be at least one day".to_string(),
+            ));
+        }
+        if pair.primary.eq_ignore_ascii_case(&pair.secondary) {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "An ESP cannot be synced with itself".to_string(),
+            ));
+        }
+        tracing::info!("Setting ESP sync pair {pair:?} (UID {})", caller.uid);
+
+        esp::set_pair(pair).map_err(|e| {
+            tracing::error!("Failed to save ESP sync pair: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to save ESP sync pair: {e}"))
+        })
+    }
+
     /// Resize an existing partition
     ///
     /// Args:
@@ -401,13 +820,111 @@ impl PartitionHandler {
         // Find partition path
         let partition_path = self.find_partition_path(&partition).await?;
 
-        // Delegate to storage-udisks operation
-        storage_udisks::resize_partition(&partition_path, new_size)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to resize partition: {e}");
-                zbus::fdo::Error::Failed(format!("Failed to resize partition: {e}"))
-            })?;
+        crate::hooks::before_device(Operation::Resize, &partition).await?;
+
+        // Delegate to the backend
+        track(
+            OperationKind::Partitioning,
+            &partition_path,
+            "resizing",
+            Some(caller.uid),
+            self.ops.resize_partition(&partition_path, new_size),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to resize partition: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to resize partition: {e}"))
+        })?;
+
+        tracing::info!("Successfully resized partition: {}", partition);
+        let disk = partition
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_string();
+        let _ = Self::partition_modified(&signal_ctx, &disk, &partition, "").await;
+        Ok(())
+    }
+
+    /// Sizes a partition can be resized to together with its filesystem
+    ///
+    /// The smallest size is what the filesystem can shrink to, with some
+    /// headroom; the largest reaches the next partition or the end of the
+    /// area the partition table allows.
+    ///
+    /// Args:
+    /// - partition: Partition device path (e.g., "/dev/sda1")
+    ///
+    /// Returns: JSON-serialized ResizeBounds
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-read")]
+    async fn get_resize_bounds(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        partition: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting resize bounds of {partition} (UID {})", caller.uid);
+
+        let bounds = self.resize_bounds(&partition).await?;
+        serde_json::to_string(&bounds).map_err(|e| {
+            tracing::error!("Failed to serialize resize bounds: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Resize a partition together with its filesystem
+    ///
+    /// When shrinking, the filesystem shrinks before the partition; when
+    /// growing, it grows to fill the partition afterwards. Filesystems that
+    /// can't be resized keep their size, and the partition can't shrink
+    /// below them.
+    ///
+    /// Args:
+    /// - partition: Partition device path (e.g., "/dev/sda1")
+    /// - new_size: New size in bytes, within the bounds of `GetResizeBounds`
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn resize_partition_with_filesystem(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        partition: String,
+        new_size: u64,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Resizing partition {} and its filesystem to {} bytes (UID {})",
+            partition,
+            new_size,
+            caller.uid
+        );
+
+        let bounds = self.resize_bounds(&partition).await?;
+        let steps = bounds
+            .plan(new_size)
+            .map_err(zbus::fdo::Error::InvalidArgs)?;
+        if steps.is_empty() {
+            return Ok(());
+        }
+        let partition_path = self.find_partition_path(&partition).await?;
+
+        crate::hooks::before_device(Operation::Resize, &partition).await?;
+
+        let mut operation = TrackedOperation::begin(
+            OperationKind::Partitioning,
+            &bounds.partition,
+            steps[0].phase(),
+            Some(caller.uid),
+        );
+        let result = self
+            .run_resize_steps(&bounds, &partition_path, &steps, &mut operation)
+            .await;
+        operation.finish(result.clone());
+        result.map_err(|e| {
+            tracing::error!("Failed to resize partition: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to resize partition: {e}"))
+        })?;
 
         tracing::info!("Successfully resized partition: {}", partition);
         let disk = partition
@@ -417,6 +934,110 @@ impl PartitionHandler {
         Ok(())
     }
 
+    /// Where a partition can move to on its disk: anywhere within the free
+    /// space on either side of it
+    ///
+    /// Args:
+    /// - partition: Partition device path (e.g., "/dev/sda1")
+    ///
+    /// Returns: JSON-serialized MoveBounds
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-read")]
+    async fn get_move_bounds(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        partition: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting move bounds of {partition} (UID {})", caller.uid);
+
+        let device = format!("/dev/{}", partition.trim_start_matches("/dev/"));
+        let bounds =
+            tokio::task::spawn_blocking(move || storage_sys::partition_move_bounds(&device))
+                .await
+                .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+                .map_err(|e| {
+                    tracing::error!("Failed to get move bounds: {e}");
+                    zbus::fdo::Error::Failed(format!("Failed to get move bounds: {e}"))
+                })?;
+        serde_json::to_string(&bounds).map_err(|e| {
+            tracing::error!("Failed to serialize move bounds: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+        })
+    }
+
+    /// Move a partition and its contents to another place on its disk
+    ///
+    /// The partition keeps its size; it must not be in use. Moving copies
+    /// the whole partition and reports its progress as a running operation,
+    /// which can't be cancelled as that would leave the contents split.
+    ///
+    /// Args:
+    /// - partition: Partition device path (e.g., "/dev/sda1")
+    /// - new_start: New start in bytes, within the bounds of `GetMoveBounds`
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.partition-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.partition-modify")]
+    async fn move_partition(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        partition: String,
+        new_start: u64,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Moving partition {} to byte {} (UID {})",
+            partition,
+            new_start,
+            caller.uid
+        );
+
+        let device = format!("/dev/{}", partition.trim_start_matches("/dev/"));
+        let mut operation = TrackedOperation::begin(
+            OperationKind::Partitioning,
+            &device,
+            "moving",
+            Some(caller.uid),
+        );
+        let moved = Arc::new((AtomicU64::new(0), AtomicU64::new(0)));
+        let progress = moved.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            storage_sys::move_partition(&device, new_start, |bytes, total| {
+                progress.0.store(bytes, Ordering::Relaxed);
+                progress.1.store(total, Ordering::Relaxed);
+            })
+        });
+        let mut task = std::pin::pin!(task);
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(1));
+        let result = loop {
+            tokio::select! {
+                result = &mut task => {
+                    break result
+                        .map_err(|e| format!("Task join error: {e}"))
+                        .and_then(|result| result.map_err(|e| e.to_string()));
+                }
+                _ = ticker.tick() => {
+                    let total = moved.1.load(Ordering::Relaxed);
+                    operation.update(moved.0.load(Ordering::Relaxed), (total > 0).then_some(total));
+                }
+            }
+        };
+        operation.finish(result.clone());
+        result.map_err(|e| {
+            tracing::error!("Failed to move partition: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to move partition: {e}"))
+        })?;
+
+        tracing::info!("Partition {partition} now starts at byte {new_start}");
+        let disk = partition
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_string();
+        let _ = Self::partition_modified(&signal_ctx, &disk, &partition, "").await;
+        Ok(())
+    }
+
     /// Set partition type (GPT GUID or MBR type code)
     ///
     /// Args:
@@ -443,8 +1064,9 @@ impl PartitionHandler {
         // Find partition path
         let partition_path = self.find_partition_path(&partition).await?;
 
-        // Delegate to storage-udisks operation
-        storage_udisks::set_partition_type(&partition_path, &type_id)
+        // Delegate to the backend
+        self.ops
+            .set_partition_type(&partition_path, &type_id)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to set partition type: {e}");
@@ -485,8 +1107,9 @@ impl PartitionHandler {
         // Find partition path
         let partition_path = self.find_partition_path(&partition).await?;
 
-        // Delegate to storage-udisks operation
-        storage_udisks::set_partition_flags(&partition_path, flags)
+        // Delegate to the backend
+        self.ops
+            .set_partition_flags(&partition_path, flags)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to set partition flags: {e}");
@@ -536,8 +1159,9 @@ impl PartitionHandler {
         // Find partition path
         let partition_path = self.find_partition_path(&partition).await?;
 
-        // Delegate to storage-udisks operation
-        storage_udisks::set_partition_name(&partition_path, &name)
+        // Delegate to the backend
+        self.ops
+            .set_partition_name(&partition_path, &name)
             .await
             .map_err(|e| {
                 tracing::error!("Failed to set partition name: {e}");
@@ -555,6 +1179,79 @@ impl PartitionHandler {
 
 /// Helper methods
 impl PartitionHandler {
+    /// Bounds of `partition`, asking its filesystem how small it can get
+    /// when it can shrink at all
+    async fn resize_bounds(&self, partition: &str) -> zbus::fdo::Result<ResizeBounds> {
+        let device = format!("/dev/{}", partition.trim_start_matches("/dev/"));
+        let disk_volumes = self.ops.list_disks_with_partitions().await.map_err(|e| {
+            tracing::error!("Failed to get drives: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to enumerate drives: {e}"))
+        })?;
+        let (disk, partitions, info) = disk_volumes
+            .into_iter()
+            .find_map(|(disk, partitions)| {
+                let info = partitions.iter().find(|p| p.device == device)?.clone();
+                Some((disk, partitions, info))
+            })
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Partition not found: {partition}")))?;
+
+        let mount_point = info.mount_points.first().cloned();
+        let filesystem_min = match info.filesystem_type.clone() {
+            Some(fs_type) if can_shrink(&fs_type, mount_point.is_some()) => {
+                let fs_device = device.clone();
+                tokio::task::spawn_blocking(move || {
+                    storage_sys::filesystem_min_size(&fs_device, &fs_type, mount_point.as_deref())
+                })
+                .await
+                .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+                .inspect_err(|e| tracing::warn!("Minimum size of {device} unknown: {e}"))
+                .ok()
+            }
+            _ => None,
+        };
+
+        Ok(resize_bounds(&disk, &partitions, &info, filesystem_min))
+    }
+
+    /// Run the `steps` of a resize of the partition at `partition_path`,
+    /// reporting each as a phase of `operation`
+    async fn run_resize_steps(
+        &self,
+        bounds: &ResizeBounds,
+        partition_path: &str,
+        steps: &[ResizeStep],
+        operation: &mut TrackedOperation,
+    ) -> Result<(), String> {
+        let device = bounds.partition.clone();
+        let fs_type = bounds.filesystem_type.clone().unwrap_or_default();
+        let mount_point = bounds.mount_point.clone();
+        for step in steps {
+            operation.set_phase(step.phase());
+            let (device, fs_type, mount_point) =
+                (device.clone(), fs_type.clone(), mount_point.clone());
+            match *step {
+                ResizeStep::ShrinkFilesystem { size } => tokio::task::spawn_blocking(move || {
+                    storage_sys::shrink_filesystem(&device, &fs_type, size, mount_point.as_deref())
+                })
+                .await
+                .map_err(|e| format!("Task join error: {e}"))?
+                .map_err(|e| format!("Failed to shrink the filesystem: {e}"))?,
+                ResizeStep::ResizePartition { size } => self
+                    .ops
+                    .resize_partition(partition_path, size)
+                    .await
+                    .map_err(|e| e.to_string())?,
+                ResizeStep::GrowFilesystem => tokio::task::spawn_blocking(move || {
+                    storage_sys::grow_filesystem(&device, &fs_type, mount_point.as_deref())
+                })
+                .await
+                .map_err(|e| format!("Task join error: {e}"))?
+                .map_err(|e| format!("Failed to grow the filesystem: {e}"))?,
+            }
+        }
+        Ok(())
+    }
+
     /// Find UDisks2 partition object path from device path
     async fn find_partition_path(&self, partition: &str) -> zbus::fdo::Result<String> {
         let device = if partition.starts_with("/dev/") {
@@ -562,7 +1259,8 @@ impl PartitionHandler {
         } else {
             format!("/dev/{}", partition)
         };
-        storage_udisks::block_object_path_for_device(&device)
+        self.ops
+            .resolve_block_path_for_device(&device)
             .await
             .map_err(|e| {
                 tracing::warn!("Partition not found: {} - {}", partition, e);
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Syncing secondary EFI System Partitions
+//!
+//! Pairs of ESPs are kept by partition UUID. Pairs with an interval are
+//! synced by a timer loop once due and their power policy allows it; a
+//! failed sync is retried on the next check, as the secondary is only
+//! bootable once a sync went through.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use storage_types::{EspSyncPair, EspSyncResult, ScheduledJob};
+
+use crate::handlers::disk::selftest::{load, now, save};
+
+/// Persisted sync pairs
+const PAIRS_PATH: &str = "/var/lib/cosmic-ext-storage/esp-sync.json";
+
+/// Time between two schedule checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Configured sync pairs
+pub fn pairs() -> Vec<EspSyncPair> {
+    load(PAIRS_PATH)
+}
+
+/// Add or replace the pair of `pair.secondary`; a pair without an interval
+/// is removed, as there is nothing to schedule for it
+pub fn set_pair(pair: EspSyncPair) -> std::io::Result<()> {
+    let mut pairs = pairs();
+    pairs.retain(|existing| existing.secondary != pair.secondary);
+    if pair.interval_days.is_some() {
+        pairs.push(pair);
+    }
+    save(PAIRS_PATH, &pairs)
+}
+
+/// Device node of the partition with UUID `uuid`
+fn device_of(uuid: &str) -> Result<String> {
+    let link = Path::new("/dev/disk/by-partuuid").join(uuid.to_lowercase());
+    std::fs::canonicalize(&link)
+        .map(|device| device.to_string_lossy().into_owned())
+        .map_err(|_| anyhow!("No partition with UUID {uuid}"))
+}
+
+/// Sync the ESP with partition UUID `secondary` from `primary`, and note
+/// the time on its pair
+pub async fn sync(primary: &str, secondary: &str) -> Result<EspSyncResult> {
+    let primary_device = device_of(primary)?;
+    let secondary_device = device_of(secondary)?;
+    let result = tokio::task::spawn_blocking(move || {
+        storage_sys::sync_esp(&primary_device, &secondary_device)
+    })
+    .await??;
+
+    let mut pairs = pairs();
+    if let Some(pair) = pairs
+        .iter_mut()
+        .find(|pair| pair.primary == primary && pair.secondary == secondary)
+    {
+        pair.last_synced = Some(now());
+        save(PAIRS_PATH, &pairs)?;
+    }
+    Ok(result)
+}
+
+/// Start the loop that syncs ESP pairs once they are due
+pub(crate) fn run_esp_sync_scheduler() {
+    tokio::spawn(async move {
+        loop {
+            crate::scheduler::wait(CHECK_INTERVAL).await;
+
+            for pair in pairs().into_iter().filter(|pair| pair.is_due(now())) {
+                let label = format!("{} → {}", pair.primary, pair.secondary);
+                if !crate::scheduler::may_run(ScheduledJob::EspSync, &pair.secondary, &label).await
+                {
+                    continue;
+                }
+                match sync(&pair.primary, &pair.secondary).await {
+                    Ok(result) => tracing::info!(
+                        "Synced ESP {} from {}: {} copied, {} deleted",
+                        pair.secondary,
+                        pair.primary,
+                        result.copied,
+                        result.deleted
+                    ),
+                    Err(e) => tracing::warn!(
+                        "Scheduled sync of ESP {} from {} failed: {e}",
+                        pair.secondary,
+                        pair.primary
+                    ),
+                }
+            }
+        }
+    });
+}
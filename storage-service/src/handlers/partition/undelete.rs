@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Restoring partitions deleted this session
+//!
+//! Before a GPT partition is deleted, its entry is read and kept in memory
+//! until the service stops. Deleting an entry leaves the partition's data
+//! alone, so putting the entry back byte for byte brings the partition
+//! back, as long as nothing has taken its number or sectors since. MBR
+//! partitions are not kept.
+
+use std::sync::{LazyLock, Mutex};
+
+use anyhow::Result;
+use storage_types::DeletedPartition;
+use storage_types::gpt::check_restore;
+
+use crate::handlers::disk::selftest::now;
+
+/// Entries deleted this session, oldest first
+static DELETED: LazyLock<Mutex<Vec<DeletedPartition>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Read the GPT entry of `partition`, to be kept once its deletion went
+/// through; `None` for partitions that aren't in a readable GPT
+pub async fn entry_of(partition: &str) -> Option<DeletedPartition> {
+    let device = partition.to_string();
+    let read = tokio::task::spawn_blocking(move || storage_sys::partition_entry(&device)).await;
+    match read {
+        Ok(Ok((disk, table, entry))) => Some(DeletedPartition {
+            id: 0,
+            disk,
+            disk_guid: table.disk_guid,
+            sector_size: table.sector_size,
+            entry,
+            deleted_at: 0,
+        }),
+        Ok(Err(e)) => {
+            tracing::debug!("Not keeping the entry of {partition} for restoring: {e}");
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Task join error: {e}");
+            None
+        }
+    }
+}
+
+/// Keep `deleted`, now that its partition is gone
+pub fn remember(mut deleted: DeletedPartition) {
+    let mut kept = DELETED.lock().unwrap_or_else(|e| e.into_inner());
+    deleted.id = kept.last().map_or(1, |last| last.id + 1);
+    deleted.deleted_at = now();
+    tracing::info!(
+        "Keeping partition {} of {} for restoring",
+        deleted.entry.number,
+        deleted.disk
+    );
+    kept.push(deleted);
+}
+
+/// Partitions deleted from `disk` this session that can still be restored,
+/// most recent first
+pub async fn restorable(disk: &str) -> Result<Vec<DeletedPartition>> {
+    let candidates: Vec<_> = DELETED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|deleted| deleted.disk == disk)
+        .cloned()
+        .collect();
+    if candidates.is_empty() {
+        return Ok(candidates);
+    }
+
+    let device = disk.to_string();
+    let table = tokio::task::spawn_blocking(move || storage_sys::read_gpt(&device)).await??;
+    Ok(candidates
+        .into_iter()
+        .rev()
+        .filter(|deleted| check_restore(&table, deleted).is_ok())
+        .collect())
+}
+
+/// Put the partition deleted as `id` back into its table
+pub async fn restore(id: u64) -> Result<DeletedPartition> {
+    let deleted = DELETED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|deleted| deleted.id == id)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("No partition was deleted as {id} this session"))?;
+
+    let entry = deleted.clone();
+    tokio::task::spawn_blocking(move || storage_sys::restore_gpt_entry(&entry)).await??;
+
+    DELETED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|kept| kept.id != id);
+    Ok(deleted)
+}
@@ -0,0 +1,463 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! MD-RAID D-Bus interface
+//!
+//! This module provides D-Bus methods for inspecting MD-RAID arrays and
+//! managing their member devices with `mdadm`.
+
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use storage_macros::authorized_interface;
+use storage_types::{RaidDetail, RaidMemberCapabilities, RaidReshape};
+use zbus::message::Header as MessageHeader;
+use zbus::{Connection, interface};
+
+use crate::policies::raid::{RaidDomain, RaidPolicy};
+
+pub mod monitor;
+
+/// D-Bus interface for MD-RAID operations
+pub struct RaidHandler {
+    domain: Arc<dyn RaidDomain>,
+}
+
+impl RaidHandler {
+    /// Create a new RaidHandler
+    pub fn new() -> Self {
+        let domain: Arc<dyn RaidDomain> = Arc::new(RaidPolicy::new());
+        if let Err(error) = domain.require_mdadm() {
+            tracing::warn!("MD-RAID operations will be disabled: {error}");
+        }
+        Self { domain }
+    }
+
+    /// Read the array, failing with InvalidArgs if it is not an MD array
+    fn detail(array: &str) -> zbus::fdo::Result<RaidDetail> {
+        storage_sys::raid_detail(array).map_err(|e| match e {
+            storage_sys::SysError::DeviceNotFound(_) => {
+                zbus::fdo::Error::InvalidArgs(e.to_string())
+            }
+            _ => {
+                tracing::error!("Failed to read RAID detail: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to read RAID detail: {e}"))
+            }
+        })
+    }
+
+    /// Capabilities of `member` in `array`, failing if it is not a member
+    fn member_capabilities(
+        detail: &RaidDetail,
+        member: &str,
+    ) -> zbus::fdo::Result<RaidMemberCapabilities> {
+        detail
+            .member(member)
+            .map(|m| detail.member_capabilities(m))
+            .ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs(format!(
+                    "{} is not a member of {}",
+                    member, detail.device
+                ))
+            })
+    }
+
+    /// Check that a reshape backup file can be created and does not live on the array
+    fn check_backup_file(array: &str, backup_file: &str) -> zbus::fdo::Result<()> {
+        let path = Path::new(backup_file);
+        if path.exists() {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Backup file already exists: {}",
+                backup_file
+            )));
+        }
+        let parent = path
+            .parent()
+            .filter(|parent| parent.is_dir())
+            .ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs(format!(
+                    "Backup file directory does not exist: {}",
+                    backup_file
+                ))
+            })?;
+
+        let array_rdev = std::fs::metadata(array).map(|m| m.rdev()).ok();
+        let parent_dev = std::fs::metadata(parent).map(|m| m.dev()).ok();
+        if array_rdev.is_some() && array_rdev == parent_dev {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "The backup file must not be stored on {}",
+                array
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Run `mdadm --manage <array>` with the given operation arguments
+    fn run_mdadm(array: &str, args: &[&str]) -> zbus::fdo::Result<()> {
+        let output = Command::new("mdadm")
+            .args(["--manage", array])
+            .args(args)
+            .output()
+            .map_err(|e| {
+                tracing::error!("Failed to run mdadm: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to run mdadm: {e}"))
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            tracing::error!("mdadm failed: {stderr}");
+            return Err(zbus::fdo::Error::Failed(format!("mdadm failed: {stderr}")));
+        }
+
+        Ok(())
+    }
+}
+
+#[interface(name = "org.cosmic.ext.Storage.Service.Raid")]
+impl RaidHandler {
+    /// Signal emitted when the members of an array change
+    #[zbus(signal)]
+    async fn array_changed(
+        signal_ctxt: &zbus::object_server::SignalEmitter<'_>,
+        array: &str,
+        member: &str,
+        change: &str,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted when an array degrades, loses a member or recovers
+    ///
+    /// `event` is "degraded", "member-failed" or "recovered"; `detail_json`
+    /// is the JSON-serialized RaidDetail at the time of the change.
+    #[zbus(signal)]
+    pub(crate) async fn array_health_changed(
+        signal_ctxt: &zbus::object_server::SignalEmitter<'_>,
+        array: &str,
+        event: &str,
+        detail_json: &str,
+    ) -> zbus::Result<()>;
+
+    /// Get sync progress, mismatch count, bitmap and member states of an array
+    ///
+    /// Args:
+    /// - array: Array device (e.g., "/dev/md0", "md0" or "/dev/md/name")
+    ///
+    /// Returns: JSON-serialized RaidDetail
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-read")]
+    async fn get_raid_detail(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        array: String,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting RAID detail for {array} (UID {})", caller.uid);
+
+        let detail = Self::detail(&array)?;
+        serde_json::to_string(&detail).map_err(|e| {
+            tracing::error!("Failed to serialize RAID detail: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize RAID detail: {e}"))
+        })
+    }
+
+    /// Add a device to an array
+    ///
+    /// The device becomes a spare, or starts rebuilding right away when the
+    /// array is degraded.
+    ///
+    /// Args:
+    /// - array: Array device (e.g., "/dev/md0")
+    /// - device: Device to add (e.g., "/dev/sdd1")
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-modify")]
+    async fn add_member(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        array: String,
+        device: String,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_mdadm()?;
+        tracing::info!("Adding {device} to {array} (UID {})", caller.uid);
+
+        let detail = Self::detail(&array)?;
+        if !detail.can_add_member() {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Cannot add members to {} ({}, {})",
+                detail.device, detail.level, detail.array_state
+            )));
+        }
+
+        Self::run_mdadm(&detail.device, &["--add", &device])?;
+
+        tracing::info!("Added {device} to {}", detail.device);
+        let _ = Self::array_changed(&signal_ctx, &detail.device, &device, "added").await;
+        Ok(())
+    }
+
+    /// Mark a member faulty
+    ///
+    /// Refused when it would leave the array without redundancy to survive it.
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-modify")]
+    async fn fail_member(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        array: String,
+        device: String,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_mdadm()?;
+        tracing::info!("Failing {device} in {array} (UID {})", caller.uid);
+
+        let detail = Self::detail(&array)?;
+        if !Self::member_capabilities(&detail, &device)?.can_fail {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Failing {} would leave {} without enough redundancy",
+                device, detail.device
+            )));
+        }
+
+        Self::run_mdadm(&detail.device, &["--fail", &device])?;
+
+        tracing::info!("Marked {device} faulty in {}", detail.device);
+        let _ = Self::array_changed(&signal_ctx, &detail.device, &device, "failed").await;
+        Ok(())
+    }
+
+    /// Remove a faulty or spare member from an array
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-modify")]
+    async fn remove_member(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        array: String,
+        device: String,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_mdadm()?;
+        tracing::info!("Removing {device} from {array} (UID {})", caller.uid);
+
+        let detail = Self::detail(&array)?;
+        if !Self::member_capabilities(&detail, &device)?.can_remove {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Only faulty or spare members can be removed from {}",
+                detail.device
+            )));
+        }
+
+        Self::run_mdadm(&detail.device, &["--remove", &device])?;
+
+        tracing::info!("Removed {device} from {}", detail.device);
+        let _ = Self::array_changed(&signal_ctx, &detail.device, &device, "removed").await;
+        Ok(())
+    }
+
+    /// Replace an in-sync member with a new device
+    ///
+    /// The replacement is added as a spare and the data copied onto it while
+    /// the old member stays active; the old member is marked faulty once the
+    /// copy completes and can then be removed.
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-modify")]
+    async fn replace_member(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        array: String,
+        device: String,
+        replacement: String,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_mdadm()?;
+        tracing::info!(
+            "Replacing {device} with {replacement} in {array} (UID {})",
+            caller.uid
+        );
+
+        let detail = Self::detail(&array)?;
+        if !Self::member_capabilities(&detail, &device)?.can_replace {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "{} cannot be replaced while {} is {} ({})",
+                device, detail.device, detail.array_state, detail.sync_action
+            )));
+        }
+
+        if detail.member(&replacement).is_none() {
+            Self::run_mdadm(&detail.device, &["--add-spare", &replacement])?;
+        }
+        Self::run_mdadm(
+            &detail.device,
+            &["--replace", &device, "--with", &replacement],
+        )?;
+
+        tracing::info!("Replacing {device} with {replacement} in {}", detail.device);
+        let _ = Self::array_changed(&signal_ctx, &detail.device, &device, "replacing").await;
+        Ok(())
+    }
+
+    /// Reshape an array: change the member count, RAID level or chunk size
+    ///
+    /// Growing the member count uses spares added beforehand. Level and
+    /// chunk size changes restripe all data and require a backup file
+    /// outside the array. The reshape runs in the kernel after this returns;
+    /// its progress is reported by `get_raid_detail` (`sync_action` "reshape").
+    ///
+    /// Args:
+    /// - array: Array device (e.g., "/dev/md0")
+    /// - reshape_json: JSON-serialized RaidReshape
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-modify")]
+    async fn reshape(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        array: String,
+        reshape_json: String,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_mdadm()?;
+
+        let reshape: RaidReshape = serde_json::from_str(&reshape_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid reshape: {e}")))?;
+        tracing::info!("Reshaping {array}: {reshape:?} (UID {})", caller.uid);
+
+        let detail = Self::detail(&array)?;
+        detail
+            .validate_reshape(&reshape)
+            .map_err(zbus::fdo::Error::InvalidArgs)?;
+
+        let mut args = vec!["--grow".to_string(), detail.device.clone()];
+        if let Some(raid_devices) = reshape.raid_devices {
+            args.push(format!("--raid-devices={raid_devices}"));
+        }
+        if let Some(level) = reshape.level.as_deref() {
+            args.push(format!("--level={level}"));
+        }
+        if let Some(chunk_kib) = reshape.chunk_kib {
+            args.push(format!("--chunk={chunk_kib}"));
+        }
+        if let Some(backup_file) = reshape.backup_file.as_deref().filter(|f| !f.is_empty()) {
+            Self::check_backup_file(&detail.device, backup_file)?;
+            args.push(format!("--backup-file={backup_file}"));
+        }
+
+        let output = Command::new("mdadm").args(&args).output().map_err(|e| {
+            tracing::error!("Failed to run mdadm: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to run mdadm: {e}"))
+        })?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            tracing::error!("mdadm --grow failed: {stderr}");
+            return Err(zbus::fdo::Error::Failed(format!(
+                "mdadm --grow failed: {stderr}"
+            )));
+        }
+
+        tracing::info!("Started reshape of {}", detail.device);
+        let _ = Self::array_changed(&signal_ctx, &detail.device, "", "reshaping").await;
+        Ok(())
+    }
+
+    /// Get spare groups, the arrays each shared spare can serve, and the
+    /// auto-spare policy
+    ///
+    /// Returns: JSON-serialized SparePoolConfig
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-read")]
+    async fn get_spare_pool(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Getting RAID spare pool (UID {})", caller.uid);
+
+        let config = tokio::task::spawn_blocking(storage_sys::spare_pool_config)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to read spare pool: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to read spare pool: {e}"))
+            })?;
+        serde_json::to_string(&config).map_err(|e| {
+            tracing::error!("Failed to serialize spare pool: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize spare pool: {e}"))
+        })
+    }
+
+    /// Put an array into a spare group, sharing its spares with the other
+    /// arrays of the group
+    ///
+    /// Spares move between arrays only while `mdadm --monitor` runs.
+    ///
+    /// Args:
+    /// - array: Array device (e.g., "/dev/md0")
+    /// - group: Spare group name, empty to take the array out of its group
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-modify")]
+    async fn set_spare_group(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        #[zbus(signal_context)] signal_ctx: zbus::object_server::SignalEmitter<'_>,
+        array: String,
+        group: String,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_mdadm()?;
+        tracing::info!(
+            "Setting spare group of {array} to {group:?} (UID {})",
+            caller.uid
+        );
+
+        let detail = Self::detail(&array)?;
+        let device = detail.device.clone();
+        tokio::task::spawn_blocking(move || {
+            storage_sys::set_spare_group(&device, Some(group.as_str()).filter(|g| !g.is_empty()))
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to set spare group: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to set spare group: {e}"))
+        })?;
+
+        let _ = Self::array_changed(&signal_ctx, &detail.device, "", "spare-group").await;
+        Ok(())
+    }
+
+    /// Enable or disable adding newly attached blank disks as spares
+    ///
+    /// Args:
+    /// - enabled: Whether new blank disks become spares
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.raid-modify (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.raid-modify")]
+    async fn set_auto_add_spares(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        enabled: bool,
+    ) -> zbus::fdo::Result<()> {
+        self.domain.require_mdadm()?;
+        tracing::info!("Setting automatic spares to {enabled} (UID {})", caller.uid);
+
+        tokio::task::spawn_blocking(move || storage_sys::set_auto_add_spares(enabled))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Task join error: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to update auto-spare policy: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to update auto-spare policy: {e}"))
+            })
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! MD-RAID health monitoring
+//!
+//! Polls every array in sysfs and, on a degraded, member-failed or recovered
+//! transition, emits `ArrayHealthChanged` and runs the administrator's alert
+//! hook (see [`ALERT_HOOK`]) if one is installed.
+
+use std::collections::HashMap;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::Result;
+use storage_types::{RaidDetail, RaidHealthEvent};
+
+use crate::handlers::raid::RaidHandler;
+
+/// Time between two reads of the array states
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Executable run as `raid-alert <array> <event>` on every health event.
+///
+/// Only used when owned by root and not writable by group or others, since
+/// the service runs it as root.
+const ALERT_HOOK: &str = "/etc/cosmic-ext-storage/raid-alert";
+
+/// Monitor MD arrays for health changes and emit D-Bus signals.
+pub(crate) async fn monitor_raid_health(
+    connection: zbus::Connection,
+    object_path: &str,
+) -> Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, RaidHandler>(object_path)
+        .await?;
+
+    tokio::spawn(async move {
+        let mut known: HashMap<String, RaidDetail> = HashMap::new();
+        loop {
+            let details = tokio::task::spawn_blocking(read_arrays)
+                .await
+                .unwrap_or_default();
+
+            for detail in &details {
+                let Some(previous) = known.get(&detail.device) else {
+                    if detail.degraded > 0 {
+                        tracing::warn!(
+                            "{} is degraded ({} members missing)",
+                            detail.device,
+                            detail.degraded
+                        );
+                    }
+                    continue;
+                };
+
+                for event in detail.health_events(previous) {
+                    tracing::warn!("RAID {}: {}", detail.device, event.as_str());
+                    let json = serde_json::to_string(detail).unwrap_or_default();
+                    if let Err(e) = RaidHandler::array_health_changed(
+                        iface_ref.signal_emitter(),
+                        &detail.device,
+                        event.as_str(),
+                        &json,
+                    )
+                    .await
+                    {
+                        tracing::error!("Failed to emit array_health_changed signal: {}", e);
+                    }
+
+                    let device = detail.device.clone();
+                    tokio::task::spawn_blocking(move || run_alert_hook(&device, event));
+                }
+            }
+
+            known = details
+                .into_iter()
+                .map(|detail| (detail.device.clone(), detail))
+                .collect();
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+
+    tracing::info!("RAID health monitoring started");
+    Ok(())
+}
+
+fn read_arrays() -> Vec<RaidDetail> {
+    storage_sys::list_arrays()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|array| storage_sys::raid_detail(array).ok())
+        .collect()
+}
+
+fn run_alert_hook(array: &str, event: RaidHealthEvent) {
+    let Ok(metadata) = std::fs::metadata(ALERT_HOOK) else {
+        return;
+    };
+    let mode = metadata.permissions().mode();
+    if metadata.uid() != 0 || mode & 0o022 != 0 || mode & 0o100 == 0 {
+        tracing::warn!(
+            "Ignoring {}: must be an executable owned by root and not writable by others",
+            ALERT_HOOK
+        );
+        return;
+    }
+
+    match Command::new(Path::new(ALERT_HOOK))
+        .args([array, event.as_str()])
+        .status()
+    {
+        Ok(status) if status.success() => {}
+        Ok(status) => tracing::warn!("{} exited with {}", ALERT_HOOK, status),
+        Err(e) => tracing::warn!("Failed to run {}: {}", ALERT_HOOK, e),
+    }
+}
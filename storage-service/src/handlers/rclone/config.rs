@@ -1 +1,68 @@
+// SPDX-License-Identifier: GPL-3.0-only
 
+//! Encrypted configuration files
+//!
+//! The password of an encrypted rclone configuration is given once by the
+//! app and kept in memory, per caller and scope, until the caller locks the
+//! configuration again or the service exits. It is never written to disk.
+
+use storage_sys::{RCloneCli, SysError};
+use storage_types::rclone::{ConfigEncryptionStatus, ConfigScope};
+
+use super::RcloneHandler;
+
+impl RcloneHandler {
+    /// CLI wrapper that can read the caller's configuration of `scope`
+    pub(super) fn cli_for(&self, scope: ConfigScope, uid: Option<u32>) -> RCloneCli {
+        let password = uid.and_then(|uid| {
+            self.config_passwords
+                .lock()
+                .ok()?
+                .get(&(uid, scope))
+                .cloned()
+        });
+        self.cli.with_config_password(password)
+    }
+
+    /// Keep the password of the caller's configuration of `scope`, or forget
+    /// it
+    pub(super) fn remember_config_password(
+        &self,
+        scope: ConfigScope,
+        uid: u32,
+        password: Option<String>,
+    ) {
+        if let Ok(mut passwords) = self.config_passwords.lock() {
+            match password {
+                Some(password) => passwords.insert((uid, scope), password),
+                None => passwords.remove(&(uid, scope)),
+            };
+        }
+    }
+
+    pub(super) fn encryption_status(&self, scope: ConfigScope, uid: u32) -> ConfigEncryptionStatus {
+        let encrypted = Self::get_existing_config_path(scope, Some(uid))
+            .is_some_and(|path| RCloneCli::is_config_encrypted(&path));
+        let unlocked = encrypted
+            && self
+                .config_passwords
+                .lock()
+                .is_ok_and(|passwords| passwords.contains_key(&(uid, scope)));
+        ConfigEncryptionStatus {
+            scope,
+            encrypted,
+            unlocked,
+        }
+    }
+}
+
+/// D-Bus error for a failed configuration read, telling a locked
+/// configuration apart so the app can ask for its password
+pub(super) fn config_error(context: &str, error: SysError) -> zbus::fdo::Error {
+    match error {
+        SysError::RCloneConfigLocked(_) | SysError::RCloneConfigPassword => {
+            zbus::fdo::Error::AccessDenied(error.to_string())
+        }
+        error => zbus::fdo::Error::Failed(format!("{context}: {error}")),
+    }
+}
@@ -9,24 +9,35 @@ mod authz;
 mod config;
 mod mount;
 mod query;
+mod transfer;
+mod usage;
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use storage_macros::authorized_interface;
-use storage_sys::{RCloneCli, is_mount_on_boot_enabled, set_mount_on_boot};
+use storage_sys::{
+    RCloneCli, SysError, is_mount_on_boot_enabled, login_mount_status, set_mount_on_boot,
+    set_mount_point_override,
+};
 use storage_types::rclone::{
-    ConfigScope, MountStatus, MountStatusResult, RemoteConfig, RemoteConfigList, TestResult,
-    rclone_provider, supported_remote_types,
+    ConfigScope, MOUNT_POINT_OPTION, MountStatus, MountStatusResult, RemoteConfig,
+    RemoteConfigList, TestResult, TransferRequest, rclone_provider, supported_remote_types,
 };
 use zbus::message::Header as MessageHeader;
 use zbus::object_server::SignalEmitter;
 use zbus::{Connection, interface};
 
 use crate::policies::rclone::{RcloneDomain, RclonePolicy};
+use config::config_error;
 
 /// D-Bus interface for RClone mount management operations
 pub struct RcloneHandler {
     cli: RCloneCli,
     domain: Arc<dyn RcloneDomain>,
+    /// Passwords of unlocked encrypted configurations, by caller UID and scope
+    config_passwords: Mutex<HashMap<(u32, ConfigScope), String>>,
+    transfers: transfer::RunningTransfers,
+    usage: usage::UsageCache,
 }
 
 impl RcloneHandler {
@@ -37,6 +48,9 @@ impl RcloneHandler {
         Ok(Self {
             cli,
             domain: Arc::new(RclonePolicy),
+            config_passwords: Mutex::new(HashMap::new()),
+            transfers: Arc::new(Mutex::new(HashMap::new())),
+            usage: Mutex::new(HashMap::new()),
         })
     }
 
@@ -132,14 +146,17 @@ impl RcloneHandler {
         tracing::info!("Listing RClone remotes (UID {})", caller.uid);
 
         let mut remotes = Vec::new();
+        let mut encryption = Vec::new();
         let user_config_path = Self::get_existing_config_path(ConfigScope::User, Some(caller.uid));
         let system_config_path = Self::get_existing_config_path(ConfigScope::System, None);
 
         // Read user remotes
         if let Some(ref path) = user_config_path {
-            match self.cli.list_remotes(path) {
+            let cli = self.cli_for(ConfigScope::User, Some(caller.uid));
+            let mut status = self.encryption_status(ConfigScope::User, caller.uid);
+            match cli.list_remotes(path) {
                 Ok(names) => {
-                    let config = self.cli.read_config(path);
+                    let config = cli.read_config(path);
                     for name in names {
                         let options = config
                             .as_ref()
@@ -160,30 +177,40 @@ impl RcloneHandler {
                             })
                         });
 
-                        remotes.push(RemoteConfig {
-                            name,
-                            remote_type,
-                            scope: ConfigScope::User,
-                            options: options
-                                .into_iter()
-                                .filter(|(k, _)| k != "type")
-                                .filter_map(|(k, v)| v.map(|v| (k, v)))
-                                .collect(),
-                            has_secrets,
-                        });
+                        remotes.push(
+                            RemoteConfig {
+                                name,
+                                remote_type,
+                                scope: ConfigScope::User,
+                                options: options
+                                    .into_iter()
+                                    .filter(|(k, _)| k != "type")
+                                    .filter_map(|(k, v)| v.map(|v| (k, v)))
+                                    .collect(),
+                                has_secrets,
+                                mount_point: None,
+                            }
+                            .with_mount_point_option(),
+                        );
                     }
                 }
+                Err(SysError::RCloneConfigLocked(_) | SysError::RCloneConfigPassword) => {
+                    status.unlocked = false;
+                }
                 Err(e) => {
                     tracing::warn!("Failed to list user remotes: {}", e);
                 }
             }
+            encryption.push(status);
         }
 
         // Read system remotes
         if let Some(ref path) = system_config_path {
-            match self.cli.list_remotes(path) {
+            let cli = self.cli_for(ConfigScope::System, Some(caller.uid));
+            let mut status = self.encryption_status(ConfigScope::System, caller.uid);
+            match cli.list_remotes(path) {
                 Ok(names) => {
-                    let config = self.cli.read_config(path);
+                    let config = cli.read_config(path);
                     for name in names {
                         let options = config
                             .as_ref()
@@ -204,29 +231,38 @@ impl RcloneHandler {
                             })
                         });
 
-                        remotes.push(RemoteConfig {
-                            name,
-                            remote_type,
-                            scope: ConfigScope::System,
-                            options: options
-                                .into_iter()
-                                .filter(|(k, _)| k != "type")
-                                .filter_map(|(k, v)| v.map(|v| (k, v)))
-                                .collect(),
-                            has_secrets,
-                        });
+                        remotes.push(
+                            RemoteConfig {
+                                name,
+                                remote_type,
+                                scope: ConfigScope::System,
+                                options: options
+                                    .into_iter()
+                                    .filter(|(k, _)| k != "type")
+                                    .filter_map(|(k, v)| v.map(|v| (k, v)))
+                                    .collect(),
+                                has_secrets,
+                                mount_point: None,
+                            }
+                            .with_mount_point_option(),
+                        );
                     }
                 }
+                Err(SysError::RCloneConfigLocked(_) | SysError::RCloneConfigPassword) => {
+                    status.unlocked = false;
+                }
                 Err(e) => {
                     tracing::warn!("Failed to list system remotes: {}", e);
                 }
             }
+            encryption.push(status);
         }
 
         let list = RemoteConfigList {
             remotes,
             user_config_path,
             system_config_path,
+            encryption,
         };
 
         serde_json::to_string(&list)
@@ -254,9 +290,9 @@ impl RcloneHandler {
             .ok_or_else(|| zbus::fdo::Error::Failed("Config file not found".to_string()))?;
 
         let config = self
-            .cli
+            .cli_for(scope, Some(caller.uid))
             .read_config(&config_path)
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read config: {}", e)))?;
+            .map_err(|e| config_error("Failed to read config", e))?;
 
         let options = config
             .get(name)
@@ -286,7 +322,9 @@ impl RcloneHandler {
                 .filter_map(|(k, v)| v.map(|v| (k, v)))
                 .collect(),
             has_secrets,
-        };
+            mount_point: None,
+        }
+        .with_mount_point_option();
 
         serde_json::to_string(&remote)
             .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))
@@ -313,7 +351,7 @@ impl RcloneHandler {
             .ok_or_else(|| zbus::fdo::Error::Failed("Config file not found".to_string()))?;
 
         let (success, message, latency_ms) = self
-            .cli
+            .cli_for(scope, Some(caller.uid))
             .test_remote(name, &config_path)
             .map_err(|e| zbus::fdo::Error::Failed(format!("Test failed: {}", e)))?;
 
@@ -366,9 +404,10 @@ impl RcloneHandler {
         }
 
         let config_path = Self::get_config_path_for_uid(scope_enum, Some(caller_uid));
-        let mount_point = Self::get_mount_point_for_uid(name, scope_enum, Some(caller_uid));
+        let mount_point = self.remote_mount_point(name, scope_enum, Some(caller_uid));
+        self.check_mount_point(name, scope_enum, caller_uid, &mount_point)?;
 
-        self.cli
+        self.cli_for(scope_enum, Some(caller_uid))
             .mount(
                 name,
                 &mount_point,
@@ -424,7 +463,7 @@ impl RcloneHandler {
             }
         }
 
-        let mount_point = Self::get_mount_point_for_uid(name, scope_enum, Some(caller_uid));
+        let mount_point = self.remote_mount_point(name, scope_enum, Some(caller_uid));
 
         self.cli
             .unmount(&mount_point)
@@ -455,7 +494,7 @@ impl RcloneHandler {
         );
 
         let scope = self.parse_scope(scope)?;
-        let mount_point = Self::get_mount_point_for_uid(name, scope, Some(caller.uid));
+        let mount_point = self.remote_mount_point(name, scope, Some(caller.uid));
 
         let status = if RCloneCli::is_mounted(&mount_point)
             .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to check mount status: {}", e)))?
@@ -471,6 +510,34 @@ impl RcloneHandler {
             .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))
     }
 
+    /// Get quota and usage of a remote, "null" if its backend cannot report
+    /// them
+    ///
+    /// Results are cached for a while; `refresh` queries the remote again.
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-read")]
+    async fn get_remote_usage(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        name: &str,
+        scope: &str,
+        refresh: bool,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Getting usage of {} (scope: {}, UID {}, refresh: {})",
+            name,
+            scope,
+            caller.uid,
+            refresh
+        );
+
+        let scope = self.parse_scope(scope)?;
+        let usage = self.remote_usage(name, scope, caller.uid, refresh)?;
+
+        serde_json::to_string(&usage)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))
+    }
+
     /// Check if a remote is enabled to mount on boot
     #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-read")]
     async fn get_mount_on_boot(
@@ -542,17 +609,24 @@ impl RcloneHandler {
             None
         };
 
-        let result = set_mount_on_boot(
-            scope_enum,
-            name,
-            enabled,
-            if scope_enum == ConfigScope::User {
-                Some(caller_uid)
-            } else {
-                None
-            },
-            home.as_deref(),
-        );
+        let uid = if scope_enum == ConfigScope::User {
+            Some(caller_uid)
+        } else {
+            None
+        };
+        let custom_mount_point = self.custom_mount_point(name, scope_enum, Some(caller_uid));
+        let result = if enabled {
+            set_mount_point_override(
+                scope_enum,
+                name,
+                custom_mount_point.as_deref(),
+                uid,
+                home.as_deref(),
+            )
+        } else {
+            Ok(())
+        }
+        .and_then(|()| set_mount_on_boot(scope_enum, name, enabled, uid, home.as_deref()));
 
         if let Err(ref err) = result {
             tracing::error!(
@@ -570,6 +644,50 @@ impl RcloneHandler {
             .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to update mount on boot: {}", e)))
     }
 
+    /// List the automatic mounts of all remotes: at login for the caller's
+    /// remotes, at boot for system ones
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-read")]
+    async fn list_login_mounts(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        self.domain.require_available()?;
+        let home = Self::get_home_for_uid(caller.uid);
+
+        let mut statuses = Vec::new();
+        for scope in [ConfigScope::User, ConfigScope::System] {
+            let Some(config_path) = Self::get_existing_config_path(scope, Some(caller.uid)) else {
+                continue;
+            };
+            let names = match self
+                .cli_for(scope, Some(caller.uid))
+                .list_remotes(&config_path)
+            {
+                Ok(names) => names,
+                Err(e) => {
+                    tracing::warn!("Failed to list {} remotes: {}", scope, e);
+                    continue;
+                }
+            };
+            let (uid, home) = match scope {
+                ConfigScope::User => (Some(caller.uid), home.as_deref()),
+                ConfigScope::System => (None, None),
+            };
+            for name in names {
+                match login_mount_status(scope, &name, uid, home) {
+                    Ok(status) => statuses.push(status),
+                    Err(e) => {
+                        tracing::warn!(remote = name, ?scope, "Failed to read login mount: {}", e)
+                    }
+                }
+            }
+        }
+
+        serde_json::to_string(&statuses)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))
+    }
+
     /// Create a new remote configuration
     #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-config")]
     async fn create_remote(
@@ -611,14 +729,17 @@ impl RcloneHandler {
             .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid config JSON: {}", e)))?;
 
         self.validate_remote_config(&remote_config)?;
+        if let Some(mount_point) = &remote_config.mount_point {
+            self.check_mount_point(&remote_config.name, scope_enum, caller_uid, mount_point)?;
+        }
 
         let config_path = Self::get_config_path_for_uid(scope_enum, Some(caller_uid));
 
         // Read existing config
         let mut existing = if config_path.exists() {
-            self.cli
+            self.cli_for(scope_enum, Some(caller_uid))
                 .read_config(&config_path)
-                .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read config: {}", e)))?
+                .map_err(|e| config_error("Failed to read config", e))?
         } else {
             std::collections::HashMap::new()
         };
@@ -635,17 +756,23 @@ impl RcloneHandler {
         let mut options = std::collections::HashMap::new();
         options.insert("type".to_string(), Some(remote_config.remote_type.clone()));
         for (k, v) in &remote_config.options {
-            if k.eq_ignore_ascii_case("type") {
+            if k.eq_ignore_ascii_case("type") || k == MOUNT_POINT_OPTION {
                 continue;
             }
             options.insert(k.clone(), Some(v.clone()));
         }
+        if let Some(mount_point) = &remote_config.mount_point {
+            options.insert(
+                MOUNT_POINT_OPTION.to_string(),
+                Some(mount_point.display().to_string()),
+            );
+        }
         existing.insert(remote_config.name, options);
 
         // Write config
-        self.cli
+        self.cli_for(scope_enum, Some(caller_uid))
             .write_config(&config_path, &existing)
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to write config: {}", e)))?;
+            .map_err(|e| config_error("Failed to write config", e))?;
 
         Ok(())
     }
@@ -692,14 +819,19 @@ impl RcloneHandler {
             .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid config JSON: {}", e)))?;
 
         self.validate_remote_config(&remote_config)?;
+        let previous_mount_point = self.custom_mount_point(name, scope_enum, Some(caller_uid));
+        let mount_point_changed = remote_config.mount_point != previous_mount_point;
+        if mount_point_changed && let Some(mount_point) = &remote_config.mount_point {
+            self.check_mount_point(name, scope_enum, caller_uid, mount_point)?;
+        }
 
         let config_path = Self::get_config_path_for_uid(scope_enum, Some(caller_uid));
 
         // Read existing config
         let mut existing = self
-            .cli
+            .cli_for(scope_enum, Some(caller_uid))
             .read_config(&config_path)
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read config: {}", e)))?;
+            .map_err(|e| config_error("Failed to read config", e))?;
 
         // Check if remote exists
         if !existing.contains_key(name) {
@@ -713,17 +845,44 @@ impl RcloneHandler {
         let mut options = std::collections::HashMap::new();
         options.insert("type".to_string(), Some(remote_config.remote_type.clone()));
         for (k, v) in &remote_config.options {
-            if k.eq_ignore_ascii_case("type") {
+            if k.eq_ignore_ascii_case("type") || k == MOUNT_POINT_OPTION {
                 continue;
             }
             options.insert(k.clone(), Some(v.clone()));
         }
+        if let Some(mount_point) = &remote_config.mount_point {
+            options.insert(
+                MOUNT_POINT_OPTION.to_string(),
+                Some(mount_point.display().to_string()),
+            );
+        }
         existing.insert(name.to_string(), options);
 
         // Write config
-        self.cli
+        self.cli_for(scope_enum, Some(caller_uid))
             .write_config(&config_path, &existing)
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to write config: {}", e)))?;
+            .map_err(|e| config_error("Failed to write config", e))?;
+        self.forget_usage(name, scope_enum);
+
+        // Move the automatic mount along
+        if mount_point_changed {
+            let (uid, home) = match scope_enum {
+                ConfigScope::User => (Some(caller_uid), Self::get_home_for_uid(caller_uid)),
+                ConfigScope::System => (None, None),
+            };
+            if is_mount_on_boot_enabled(scope_enum, name, uid, home.as_deref()).unwrap_or(false) {
+                set_mount_point_override(
+                    scope_enum,
+                    name,
+                    remote_config.mount_point.as_deref(),
+                    uid,
+                    home.as_deref(),
+                )
+                .map_err(|e| {
+                    zbus::fdo::Error::Failed(format!("Failed to move automatic mount: {}", e))
+                })?;
+            }
+        }
 
         Ok(())
     }
@@ -769,9 +928,9 @@ impl RcloneHandler {
 
         // Read existing config
         let mut existing = self
-            .cli
+            .cli_for(scope_enum, Some(caller_uid))
             .read_config(&config_path)
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read config: {}", e)))?;
+            .map_err(|e| config_error("Failed to read config", e))?;
 
         // Remove remote
         if existing.remove(name).is_none() {
@@ -782,13 +941,199 @@ impl RcloneHandler {
         }
 
         // Write config
-        self.cli
+        self.cli_for(scope_enum, Some(caller_uid))
             .write_config(&config_path, &existing)
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to write config: {}", e)))?;
+            .map_err(|e| config_error("Failed to write config", e))?;
+        self.forget_usage(name, scope_enum);
+
+        Ok(())
+    }
+
+    /// Unlock an encrypted configuration for the caller until it is locked
+    /// again or the service exits
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-read")]
+    async fn unlock_config(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        scope: &str,
+        password: &str,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Unlocking {} config (UID {})", scope, caller.uid);
+
+        let scope = self.parse_scope(scope)?;
+        let config_path = Self::get_existing_config_path(scope, Some(caller.uid))
+            .ok_or_else(|| zbus::fdo::Error::Failed("Config file not found".to_string()))?;
+        if !RCloneCli::is_config_encrypted(&config_path) {
+            return Err(zbus::fdo::Error::Failed(
+                "The configuration is not encrypted".to_string(),
+            ));
+        }
+
+        self.cli
+            .with_config_password(Some(password.to_string()))
+            .check_config_password(&config_path)
+            .map_err(|e| match e {
+                SysError::RCloneConfigPassword => zbus::fdo::Error::InvalidArgs(e.to_string()),
+                e => zbus::fdo::Error::Failed(format!("Failed to unlock config: {}", e)),
+            })?;
+        self.remember_config_password(scope, caller.uid, Some(password.to_string()));
+
+        Ok(())
+    }
+
+    /// Forget the password of an encrypted configuration
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-read")]
+    async fn lock_config(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        scope: &str,
+    ) -> zbus::fdo::Result<()> {
+        let scope = self.parse_scope(scope)?;
+        self.remember_config_password(scope, caller.uid, None);
+        Ok(())
+    }
+
+    /// Encrypt the configuration of a scope with a password, leaving it
+    /// unlocked for the caller
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-config")]
+    async fn encrypt_config(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        scope: &str,
+        password: &str,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Encrypting config (scope: {})", scope);
+
+        let scope_enum = self.parse_scope(scope)?;
+        let caller_uid = caller.uid;
+
+        // For system scope, check polkit authorization
+        if scope_enum == ConfigScope::System {
+            let sender = header
+                .sender()
+                .ok_or_else(|| zbus::fdo::Error::Failed("No sender in message header".to_string()))?
+                .as_str()
+                .to_string();
+
+            let authorized = crate::auth::check_authorization(
+                connection,
+                &sender,
+                "org.cosmic.ext.storage.service.rclone-config",
+            )
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Authorization check failed: {}", e)))?;
+
+            if !authorized {
+                return Err(zbus::fdo::Error::AccessDenied(
+                    "Not authorized for system-wide config operations".to_string(),
+                ));
+            }
+        }
+
+        if password.is_empty() {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "The password may not be empty".to_string(),
+            ));
+        }
+        let config_path = Self::get_existing_config_path(scope_enum, Some(caller_uid))
+            .ok_or_else(|| zbus::fdo::Error::Failed("Config file not found".to_string()))?;
+
+        self.cli
+            .encrypt_config(&config_path, password)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to encrypt config: {}", e)))?;
+        self.remember_config_password(scope_enum, caller_uid, Some(password.to_string()));
 
         Ok(())
     }
 
+    /// Start copying or syncing between a local path and a remote, or
+    /// between two remotes, in the background
+    ///
+    /// Returns the ID of the transfer
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-transfer")]
+    async fn start_transfer(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+        request: &str,
+    ) -> zbus::fdo::Result<String> {
+        let request: TransferRequest = serde_json::from_str(request)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid transfer JSON: {}", e)))?;
+        request.validate().map_err(zbus::fdo::Error::InvalidArgs)?;
+        tracing::info!(
+            "Starting transfer from {} to {} (scope: {}, UID {})",
+            request.source,
+            request.destination,
+            request.scope,
+            caller.uid
+        );
+
+        // System remotes are transferred as root
+        if request.scope == ConfigScope::System {
+            let sender = header
+                .sender()
+                .ok_or_else(|| zbus::fdo::Error::Failed("No sender in message header".to_string()))?
+                .as_str()
+                .to_string();
+
+            let authorized = crate::auth::check_authorization(
+                connection,
+                &sender,
+                "org.cosmic.ext.storage.service.rclone-config",
+            )
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Authorization check failed: {}", e)))?;
+
+            if !authorized {
+                return Err(zbus::fdo::Error::AccessDenied(
+                    "Not authorized for system-wide transfers".to_string(),
+                ));
+            }
+        }
+
+        self.spawn_transfer(request, caller.uid)
+    }
+
+    /// Stop a running transfer
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-transfer")]
+    async fn cancel_transfer(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        id: &str,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Cancelling transfer {} (UID {})", id, caller.uid);
+        self.cancel_transfer_for(id, caller.uid)
+    }
+
+    /// Running and finished transfers of the caller, newest first
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-read")]
+    async fn list_transfers(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        let jobs = self.list_transfers_for(caller.uid);
+
+        serde_json::to_string(&jobs)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {}", e)))
+    }
+
+    /// Forget the finished transfers of the caller
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.rclone-transfer")]
+    async fn clear_transfer_history(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        self.clear_transfer_history_for(caller.uid).map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to clear transfer history: {}", e))
+        })
+    }
+
     /// Get list of supported remote types
     async fn supported_remote_types(&self) -> zbus::fdo::Result<Vec<String>> {
         self.domain.require_available()?;
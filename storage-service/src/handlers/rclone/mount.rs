@@ -1 +1,146 @@
+// SPDX-License-Identifier: GPL-3.0-only
 
+//! Mount points of remotes
+//!
+//! A remote is mounted at the default location of its scope unless its
+//! configuration names a custom mount point. Custom mount points are checked
+//! before they are saved and every mount point before it is used: it may not
+//! overlap the mount point of another remote, must be an empty directory if
+//! it exists, and for user remotes has to stay inside the user's home.
+
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use storage_sys::RCloneCli;
+use storage_types::rclone::{ConfigScope, MOUNT_POINT_OPTION, validate_mount_point};
+
+use super::RcloneHandler;
+use crate::protected_paths::PROTECTED_SYSTEM_PATHS;
+
+impl RcloneHandler {
+    /// Custom mount point of a remote, if its configuration has one
+    pub(super) fn custom_mount_point(
+        &self,
+        name: &str,
+        scope: ConfigScope,
+        uid: Option<u32>,
+    ) -> Option<PathBuf> {
+        let config_path = Self::get_existing_config_path(scope, uid)?;
+        let config = self.cli_for(scope, uid).read_config(&config_path).ok()?;
+        config
+            .get(name)?
+            .get(MOUNT_POINT_OPTION)?
+            .as_ref()
+            .map(PathBuf::from)
+    }
+
+    /// Where a remote is mounted
+    pub(super) fn remote_mount_point(
+        &self,
+        name: &str,
+        scope: ConfigScope,
+        uid: Option<u32>,
+    ) -> PathBuf {
+        self.custom_mount_point(name, scope, uid)
+            .unwrap_or_else(|| Self::get_mount_point_for_uid(name, scope, uid))
+    }
+
+    /// Mount points of the remotes the caller sees, except `name` in `scope`
+    fn other_mount_points(
+        &self,
+        name: &str,
+        scope: ConfigScope,
+        uid: Option<u32>,
+    ) -> Vec<(String, PathBuf)> {
+        let mut mount_points = Vec::new();
+        for other_scope in [ConfigScope::User, ConfigScope::System] {
+            let Some(config_path) = Self::get_existing_config_path(other_scope, uid) else {
+                continue;
+            };
+            let Ok(config) = self.cli_for(other_scope, uid).read_config(&config_path) else {
+                continue;
+            };
+            for (other, options) in config {
+                if other_scope == scope && other == name {
+                    continue;
+                }
+                let mount_point = options
+                    .get(MOUNT_POINT_OPTION)
+                    .and_then(|value| value.as_ref())
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| Self::get_mount_point_for_uid(&other, other_scope, uid));
+                mount_points.push((format!("{other} ({other_scope})"), mount_point));
+            }
+        }
+        mount_points
+    }
+
+    /// Check that `mount_point` can be used by `name` in `scope`
+    pub(super) fn check_mount_point(
+        &self,
+        name: &str,
+        scope: ConfigScope,
+        uid: u32,
+        mount_point: &Path,
+    ) -> zbus::fdo::Result<()> {
+        let others = self.other_mount_points(name, scope, Some(uid));
+        validate_mount_point(mount_point, &others).map_err(zbus::fdo::Error::InvalidArgs)?;
+
+        if PROTECTED_SYSTEM_PATHS
+            .iter()
+            .any(|protected| mount_point == Path::new(protected))
+        {
+            return Err(zbus::fdo::Error::AccessDenied(format!(
+                "Refusing to mount over protected path: {}",
+                mount_point.display()
+            )));
+        }
+
+        // The service creates the mount point and hands it to the user
+        if scope == ConfigScope::User {
+            let inside_home = Self::get_home_for_uid(uid)
+                .is_some_and(|home| mount_point.starts_with(&home) && mount_point != home);
+            if !inside_home {
+                return Err(zbus::fdo::Error::AccessDenied(format!(
+                    "Mount points of user remotes must be inside the home directory: {}",
+                    mount_point.display()
+                )));
+            }
+        }
+
+        check_mount_point_free(mount_point)
+    }
+}
+
+/// An existing mount point must be an empty directory, unless something is
+/// mounted on it already (which mounting reports more precisely)
+fn check_mount_point_free(mount_point: &Path) -> zbus::fdo::Result<()> {
+    let Ok(metadata) = std::fs::symlink_metadata(mount_point) else {
+        return Ok(());
+    };
+    if !metadata.is_dir() {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "Mount point is not a directory: {}",
+            mount_point.display()
+        )));
+    }
+
+    let on_other_device = mount_point
+        .parent()
+        .and_then(|parent| std::fs::metadata(parent).ok())
+        .is_none_or(|parent| parent.dev() != metadata.dev());
+    if on_other_device || RCloneCli::is_mounted(&mount_point.to_path_buf()).unwrap_or(false) {
+        return Ok(());
+    }
+
+    let is_empty = std::fs::read_dir(mount_point)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+    if !is_empty {
+        return Err(zbus::fdo::Error::InvalidArgs(format!(
+            "Mount point is not empty: {}",
+            mount_point.display()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Copy and sync jobs
+//!
+//! Every transfer runs one rclone process in the background, as the caller
+//! for user remotes. Callers see the progress of their own running
+//! transfers and can cancel them; finished transfers are kept in a history
+//! per user.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use storage_sys::SysError;
+use storage_types::rclone::{
+    ConfigScope, TransferJob, TransferProgress, TransferRequest, TransferState,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::RcloneHandler;
+use super::config::config_error;
+use crate::handlers::disk::selftest::{load, now, save};
+
+/// Persisted finished transfers, by user ID
+const HISTORY_PATH: &str = "/var/lib/cosmic-ext-storage/rclone-transfers.json";
+
+/// Finished transfers kept per user
+const HISTORY_LIMIT: usize = 50;
+
+pub(super) struct RunningTransfer {
+    uid: u32,
+    job: TransferJob,
+    cancel: CancellationToken,
+}
+
+/// Running transfers, by ID
+pub(super) type RunningTransfers = Arc<Mutex<HashMap<String, RunningTransfer>>>;
+
+impl RcloneHandler {
+    /// Start `request` for `uid` in the background and return its ID
+    pub(super) fn spawn_transfer(
+        &self,
+        request: TransferRequest,
+        uid: u32,
+    ) -> Result<String, zbus::fdo::Error> {
+        let config_path = Self::get_existing_config_path(request.scope, Some(uid))
+            .ok_or_else(|| zbus::fdo::Error::Failed("Config file not found".to_string()))?;
+        let (run_as, home) = match request.scope {
+            ConfigScope::User => (Some(uid), Self::get_home_for_uid(uid)),
+            ConfigScope::System => (None, None),
+        };
+
+        // Fail early on a locked configuration instead of in the background
+        let cli = self.cli_for(request.scope, Some(uid));
+        cli.list_remotes(&config_path)
+            .map_err(|e| config_error("Failed to read config", e))?;
+        let transfer = cli
+            .start_transfer(&request, &config_path, run_as, home.as_deref())
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to start transfer: {}", e)))?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let cancel = CancellationToken::new();
+        if let Ok(mut running) = self.transfers.lock() {
+            running.insert(
+                id.clone(),
+                RunningTransfer {
+                    uid,
+                    job: TransferJob {
+                        id: id.clone(),
+                        request,
+                        state: TransferState::Running,
+                        progress: TransferProgress::default(),
+                        started_at: now(),
+                        finished_at: None,
+                        error: None,
+                    },
+                    cancel: cancel.clone(),
+                },
+            );
+        }
+
+        let transfers = Arc::clone(&self.transfers);
+        let transfer_id = id.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = transfer.follow(
+                |progress| {
+                    if let Ok(mut running) = transfers.lock()
+                        && let Some(entry) = running.get_mut(&transfer_id)
+                    {
+                        entry.job.progress = progress;
+                    }
+                },
+                || cancel.is_cancelled(),
+            );
+            finish_transfer(&transfers, &transfer_id, result);
+        });
+
+        Ok(id)
+    }
+
+    /// Running and finished transfers of `uid`, newest first
+    pub(super) fn list_transfers_for(&self, uid: u32) -> Vec<TransferJob> {
+        let running = self.transfers.lock();
+        let mut jobs = load::<BTreeMap<u32, Vec<TransferJob>>>(HISTORY_PATH)
+            .remove(&uid)
+            .unwrap_or_default();
+        if let Ok(running) = running {
+            jobs.extend(
+                running
+                    .values()
+                    .filter(|entry| entry.uid == uid)
+                    .map(|entry| entry.job.clone()),
+            );
+        }
+        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs
+    }
+
+    /// Stop a running transfer of `uid`
+    pub(super) fn cancel_transfer_for(&self, id: &str, uid: u32) -> Result<(), zbus::fdo::Error> {
+        let running = self
+            .transfers
+            .lock()
+            .map_err(|_| zbus::fdo::Error::Failed("Transfer state unavailable".to_string()))?;
+        match running.get(id) {
+            Some(entry) if entry.uid == uid => {
+                entry.cancel.cancel();
+                Ok(())
+            }
+            _ => Err(zbus::fdo::Error::Failed(format!(
+                "Transfer not found: {id}"
+            ))),
+        }
+    }
+
+    /// Forget the finished transfers of `uid`
+    pub(super) fn clear_transfer_history_for(&self, uid: u32) -> std::io::Result<()> {
+        let _running = self.transfers.lock();
+        let mut history: BTreeMap<u32, Vec<TransferJob>> = load(HISTORY_PATH);
+        if history.remove(&uid).is_some() {
+            save(HISTORY_PATH, &history)?;
+        }
+        Ok(())
+    }
+}
+
+/// Move a finished transfer to the history of its user
+fn finish_transfer(transfers: &RunningTransfers, id: &str, result: storage_sys::Result<()>) {
+    // Holding the lock also keeps concurrent finishes from overwriting each
+    // other's history
+    let Ok(mut running) = transfers.lock() else {
+        return;
+    };
+    let Some(RunningTransfer { uid, mut job, .. }) = running.remove(id) else {
+        return;
+    };
+
+    job.finished_at = Some(now());
+    match result {
+        Ok(()) => job.state = TransferState::Completed,
+        Err(SysError::RCloneTransferCancelled) => job.state = TransferState::Cancelled,
+        Err(e) => {
+            tracing::warn!(id, "Transfer failed: {}", e);
+            job.state = TransferState::Failed;
+            job.error = Some(e.to_string());
+        }
+    }
+
+    let mut history: BTreeMap<u32, Vec<TransferJob>> = load(HISTORY_PATH);
+    let jobs = history.entry(uid).or_default();
+    jobs.push(job);
+    if jobs.len() > HISTORY_LIMIT {
+        jobs.drain(..jobs.len() - HISTORY_LIMIT);
+    }
+    if let Err(e) = save(HISTORY_PATH, &history) {
+        tracing::warn!("Failed to save transfer history: {e}");
+    }
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Quota and usage of remotes
+//!
+//! `rclone about` asks the provider over the network, which can take seconds,
+//! so results are cached per caller and remote for [`CACHE_TTL`]. The app can
+//! ask for a fresh query, and changing or deleting a remote drops its entry.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use storage_types::rclone::{ConfigScope, RemoteUsage};
+
+use super::RcloneHandler;
+use super::config::config_error;
+use crate::handlers::disk::selftest::now;
+
+/// How long a queried usage is served from the cache
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Queried usage by caller UID, remote name and scope; `None` for remotes
+/// that cannot report usage
+pub(super) type UsageCache =
+    Mutex<HashMap<(u32, String, ConfigScope), (Instant, Option<RemoteUsage>)>>;
+
+impl RcloneHandler {
+    /// Usage of a remote of `uid`, from the cache unless it is stale or
+    /// `refresh` is set
+    pub(super) fn remote_usage(
+        &self,
+        name: &str,
+        scope: ConfigScope,
+        uid: u32,
+        refresh: bool,
+    ) -> Result<Option<RemoteUsage>, zbus::fdo::Error> {
+        let key = (uid, name.to_string(), scope);
+        if !refresh
+            && let Some((queried, usage)) =
+                self.usage.lock().ok().and_then(|c| c.get(&key).cloned())
+            && queried.elapsed() < CACHE_TTL
+        {
+            return Ok(usage);
+        }
+
+        let config_path = Self::get_existing_config_path(scope, Some(uid))
+            .ok_or_else(|| zbus::fdo::Error::Failed("Config file not found".to_string()))?;
+        let usage = self
+            .cli_for(scope, Some(uid))
+            .about(name, &config_path)
+            .map_err(|e| config_error("Failed to query usage", e))?
+            .map(|usage| RemoteUsage {
+                checked_at: now(),
+                ..usage
+            });
+
+        if let Ok(mut cache) = self.usage.lock() {
+            cache.insert(key, (Instant::now(), usage.clone()));
+        }
+        Ok(usage)
+    }
+
+    /// Drop the cached usage of a remote for every caller
+    pub(super) fn forget_usage(&self, name: &str, scope: ConfigScope) {
+        if let Ok(mut cache) = self.usage.lock() {
+            cache.retain(|(_, n, s), _| !(n == name && *s == scope));
+        }
+    }
+}
@@ -1,6 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use zbus::interface;
+use storage_macros::authorized_interface;
+use storage_types::{
+    LogEntry, LogFilter, LogLevel, PowerSettings, ScheduledJob, operation_excerpt,
+};
+use zbus::message::Header as MessageHeader;
+use zbus::{Connection, interface};
+
+use crate::{paging, restrictions, scheduler, statistics};
+
+/// Most service log lines returned by one GetOperationLog call
+const MAX_OPERATION_LOG_LINES: usize = 1000;
 
 /// Main storage service interface
 pub struct StorageService {
@@ -46,6 +56,313 @@ impl StorageService {
             features.push("lvm".to_string());
         }
 
+        features.push(storage_contracts::FD_REPLIES.to_string());
+        features.push(storage_contracts::PROGRESS_FDS.to_string());
+
         features
     }
+    /// Get the service's log lines of an operation, so that a failure can
+    /// be looked into without journalctl
+    ///
+    /// Each call of a method is logged as starting an operation named
+    /// after the method (e.g. "mount"); its excerpt runs up to the start of
+    /// the next operation. The newest lines are returned when there are
+    /// more than 1000.
+    ///
+    /// Args:
+    /// - operation: Method name, or empty for all lines
+    /// - device: Only lines mentioning this device (e.g., "/dev/sda1"), or
+    ///   empty for all
+    /// - since: Unix time in seconds to read the log from
+    ///
+    /// Returns: JSON-serialized Vec<LogEntry>, oldest first
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.diagnostics-collect (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.diagnostics-collect")]
+    async fn get_operation_log(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        operation: String,
+        device: String,
+        since: u64,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!(
+            "Getting log of operation {operation:?} on {device:?} (UID {})",
+            caller.uid
+        );
+
+        let log = tokio::task::spawn_blocking(move || {
+            storage_sys::unit_log_since(storage_sys::diagnostics::SERVICE_UNIT, since)
+        })
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read the service log: {e}")))?
+        .map_err(|e| {
+            tracing::error!("Failed to read the service log: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to read the service log: {e}"))
+        })?;
+
+        let mut entries: Vec<LogEntry> = log.lines().filter_map(LogEntry::parse_journal).collect();
+        if !operation.is_empty() {
+            entries = operation_excerpt(&entries, &operation);
+        }
+        let filter = LogFilter {
+            level: LogLevel::Trace,
+            device,
+            ..LogFilter::default()
+        };
+        entries.retain(|entry| filter.matches(entry));
+        let excess = entries.len().saturating_sub(MAX_OPERATION_LOG_LINES);
+        entries.drain(..excess);
+
+        serde_json::to_string(&entries).map_err(|e| {
+            tracing::error!("Failed to serialize log entries: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize log entries: {e}"))
+        })
+    }
+
+    /// Get a further page of a paged reply
+    ///
+    /// Methods documented as returning a ResultPage send the first page of
+    /// their JSON; while it is not the last, the rest is fetched here and
+    /// joined in order. Pages are kept for the calling connection only,
+    /// for five minutes or until the last one is fetched.
+    ///
+    /// Args:
+    /// - handle: Handle from the first page
+    /// - index: Page number, from 1 to page_count - 1
+    ///
+    /// Returns: JSON-serialized ResultPage
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.result-pages (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.result-pages")]
+    async fn get_page(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        handle: String,
+        index: u32,
+    ) -> zbus::fdo::Result<String> {
+        let page = paging::page(&caller.sender, &handle, index)?;
+        serde_json::to_string(&page).map_err(|e| {
+            tracing::error!("Failed to serialize page: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize page: {e}"))
+        })
+    }
+
+    /// Get all further pages of a paged reply at once
+    ///
+    /// The pages after the first, joined, in a sealed memory file read
+    /// from its start; the reply is released. Offered when SupportedFeatures
+    /// lists "fd-replies".
+    ///
+    /// Args:
+    /// - handle: Handle from the first page
+    ///
+    /// Returns: File descriptor of the memory file
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.result-pages (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.result-pages")]
+    async fn get_rest_fd(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        handle: String,
+    ) -> zbus::fdo::Result<zbus::zvariant::OwnedFd> {
+        paging::rest_fd(&caller.sender, &handle).await
+    }
+
+    /// Get the caller's local usage statistics
+    ///
+    /// Returns: JSON-serialized UsageStatistics; counts are zero until
+    /// statistics are turned on with SetUsageStatistics
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.usage-statistics (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.usage-statistics")]
+    async fn get_usage_statistics(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        let uid = caller.uid;
+        let stats = tokio::task::spawn_blocking(move || statistics::statistics(uid))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read statistics: {e}")))?;
+
+        serde_json::to_string(&stats).map_err(|e| {
+            tracing::error!("Failed to serialize usage statistics: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize usage statistics: {e}"))
+        })
+    }
+
+    /// Turn recording of the caller's operations on or off
+    ///
+    /// Records are kept only on this machine. Turning statistics off keeps
+    /// the records made so far; ClearUsageStatistics removes them.
+    ///
+    /// Args:
+    /// - enabled: Whether to record the caller's operations
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.usage-statistics (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.usage-statistics")]
+    async fn set_usage_statistics(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        enabled: bool,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!(
+            "Turning usage statistics {} (UID {})",
+            if enabled { "on" } else { "off" },
+            caller.uid
+        );
+
+        let uid = caller.uid;
+        tokio::task::spawn_blocking(move || statistics::set_enabled(uid, enabled))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save setting: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to save usage statistics setting: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to save setting: {e}"))
+            })
+    }
+
+    /// Remove the caller's usage statistics records
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.usage-statistics (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.usage-statistics")]
+    async fn clear_usage_statistics(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<()> {
+        tracing::info!("Clearing usage statistics (UID {})", caller.uid);
+
+        let uid = caller.uid;
+        tokio::task::spawn_blocking(move || statistics::clear(uid))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to clear statistics: {e}")))?
+            .map_err(|e| {
+                tracing::error!("Failed to clear usage statistics: {e}");
+                zbus::fdo::Error::Failed(format!("Failed to clear statistics: {e}"))
+            })
+    }
+
+    /// Get when scheduled jobs wait for mains power
+    ///
+    /// Returns: JSON-serialized PowerSettings
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn get_power_policies(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Reading power policies (UID {})", caller.uid);
+
+        serde_json::to_string(&scheduler::load_settings())
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {e}")))
+    }
+
+    /// Set when scheduled jobs wait for mains power
+    ///
+    /// Args:
+    /// - settings_json: JSON-serialized PowerSettings
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-tune (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-tune")]
+    async fn set_power_policies(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        settings_json: &str,
+    ) -> zbus::fdo::Result<()> {
+        let settings: PowerSettings = serde_json::from_str(settings_json)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid settings: {e}")))?;
+        settings.validate().map_err(zbus::fdo::Error::InvalidArgs)?;
+        tracing::info!("Setting power policies {:?} (UID {})", settings, caller.uid);
+
+        scheduler::save_settings(&settings)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save settings: {e}")))
+    }
+
+    /// Get the scheduled jobs that are due but wait for mains power
+    ///
+    /// Returns: JSON-serialized list of DeferredJob
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-read (allow_active)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-read")]
+    async fn get_deferred_jobs(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        tracing::debug!("Reading deferred jobs (UID {})", caller.uid);
+
+        serde_json::to_string(&scheduler::deferred_jobs())
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Serialization error: {e}")))
+    }
+
+    /// Run a deferred job now, whatever the power supply
+    ///
+    /// Args:
+    /// - job: JSON-serialized ScheduledJob
+    /// - target: Target of the job, as listed by GetDeferredJobs
+    ///
+    /// Authorization: org.cosmic.ext.storage.service.disk-tune (auth_admin_keep)
+    #[authorized_interface(action = "org.cosmic.ext.storage.service.disk-tune")]
+    async fn run_deferred_job(
+        &self,
+        #[zbus(connection)] _connection: &Connection,
+        #[zbus(header)] _header: MessageHeader<'_>,
+        job: &str,
+        target: &str,
+    ) -> zbus::fdo::Result<()> {
+        let job: ScheduledJob = serde_json::from_str(job)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid job: {e}")))?;
+        tracing::info!(
+            "Running deferred {job:?} of {target} anyway (UID {})",
+            caller.uid
+        );
+
+        if scheduler::run_anyway(job, target) {
+            Ok(())
+        } else {
+            Err(zbus::fdo::Error::InvalidArgs(format!(
+                "No deferred {job:?} job for {target}"
+            )))
+        }
+    }
+
+    /// Get what the caller's restriction profiles let them do, so that the
+    /// app can hide the rest
+    ///
+    /// Returns: JSON-serialized AllowedActions
+    ///
+    /// Authorization: none; any user may ask about themselves
+    async fn get_allowed_actions(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: MessageHeader<'_>,
+    ) -> zbus::fdo::Result<String> {
+        let sender = header
+            .sender()
+            .ok_or_else(|| zbus::fdo::Error::Failed("No sender in message header".to_string()))?;
+        let uid = zbus::fdo::DBusProxy::new(connection)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("D-Bus connection error: {e}")))?
+            .get_connection_unix_user(sender.clone().into())
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to get caller UID: {e}")))?;
+
+        let allowed = tokio::task::spawn_blocking(move || restrictions::allowed_actions(uid))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read profiles: {e}")))?;
+
+        serde_json::to_string(&allowed).map_err(|e| {
+            tracing::error!("Failed to serialize allowed actions: {e}");
+            zbus::fdo::Error::Failed(format!("Failed to serialize allowed actions: {e}"))
+        })
+    }
 }
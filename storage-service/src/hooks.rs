@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Pre-operation hooks for destructive operations
+//!
+//! Handlers call [`before_device`] or [`before_paths`] right before they
+//! resize or delete anything. When the safety snapshot policy covers the
+//! operation, each affected btrfs subvolume is snapshotted first and older
+//! safety snapshots past their retention are pruned. Targets that are not on
+//! btrfs are ignored, but a failed snapshot aborts the operation.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use disks_btrfs::safety;
+use storage_types::btrfs::SafetySnapshotPolicy;
+
+/// Persisted safety snapshot policy
+const POLICY_PATH: &str = "/var/lib/cosmic-ext-storage/safety-snapshots.json";
+
+/// Destructive operation a hook runs for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Growing or shrinking a partition or logical volume
+    Resize,
+    /// Deleting files to free space
+    Cleanup,
+}
+
+impl Operation {
+    fn reason(self) -> &'static str {
+        match self {
+            Self::Resize => "resize",
+            Self::Cleanup => "cleanup",
+        }
+    }
+
+    fn covered_by(self, policy: &SafetySnapshotPolicy) -> bool {
+        policy.enabled
+            && match self {
+                Self::Resize => policy.on_resize,
+                Self::Cleanup => policy.on_cleanup,
+            }
+    }
+}
+
+/// Load the safety snapshot policy, falling back to the (disabled) default
+pub fn load_policy() -> SafetySnapshotPolicy {
+    match std::fs::read_to_string(POLICY_PATH) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid safety snapshot policy: {e}");
+            SafetySnapshotPolicy::default()
+        }),
+        Err(_) => SafetySnapshotPolicy::default(),
+    }
+}
+
+/// Persist the safety snapshot policy
+pub fn save_policy(policy: &SafetySnapshotPolicy) -> std::io::Result<()> {
+    let path = Path::new(POLICY_PATH);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(policy).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Run the hooks for an operation on a whole block device
+///
+/// Every mounted btrfs subvolume of `device` is snapshotted.
+pub async fn before_device(operation: Operation, device: &str) -> zbus::fdo::Result<()> {
+    if !operation.covered_by(&load_policy()) {
+        return Ok(());
+    }
+
+    let device = device.to_string();
+    let mount_points = tokio::task::spawn_blocking(move || safety::device_mount_points(&device))
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Safety snapshot task failed: {e}")))?
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read mounts: {e}")))?;
+
+    before_paths(operation, mount_points).await
+}
+
+/// Run the hooks for an operation on paths of mounted filesystems
+///
+/// The subvolume containing each path is snapshotted once.
+pub async fn before_paths(operation: Operation, paths: Vec<PathBuf>) -> zbus::fdo::Result<()> {
+    let policy = load_policy();
+    if !operation.covered_by(&policy) || paths.is_empty() {
+        return Ok(());
+    }
+
+    tokio::task::spawn_blocking(move || snapshot_subvolumes(operation, &policy, &paths))
+        .await
+        .map_err(|e| zbus::fdo::Error::Failed(format!("Safety snapshot task failed: {e}")))?
+}
+
+/// Prune the safety snapshots past their retention of the subvolume holding
+/// `path`, whichever operations the policy covers
+///
+/// Returns the number of snapshots deleted; none when `path` is not on btrfs.
+pub async fn prune_expired(path: PathBuf) -> usize {
+    let retention = retention(&load_policy());
+    tokio::task::spawn_blocking(move || {
+        let Ok(subvolume) = safety::containing_subvolume(&path) else {
+            return 0;
+        };
+        match safety::prune_safety_snapshots(&subvolume, retention) {
+            Ok(pruned) => pruned.len(),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to prune safety snapshots of {}: {e}",
+                    subvolume.display()
+                );
+                0
+            }
+        }
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn retention(policy: &SafetySnapshotPolicy) -> Duration {
+    Duration::from_secs(u64::from(policy.retention_days) * 24 * 60 * 60)
+}
+
+fn snapshot_subvolumes(
+    operation: Operation,
+    policy: &SafetySnapshotPolicy,
+    paths: &[PathBuf],
+) -> zbus::fdo::Result<()> {
+    let subvolumes: BTreeSet<PathBuf> = paths
+        .iter()
+        .filter_map(|path| safety::containing_subvolume(path).ok())
+        .collect();
+
+    let retention = retention(policy);
+    for subvolume in subvolumes {
+        let snapshot =
+            safety::take_safety_snapshot(&subvolume, operation.reason()).map_err(|e| {
+                zbus::fdo::Error::Failed(format!(
+                    "Safety snapshot of {} failed, operation aborted: {e}",
+                    subvolume.display()
+                ))
+            })?;
+        tracing::info!("Took safety snapshot {}", snapshot.display());
+
+        match safety::prune_safety_snapshots(&subvolume, retention) {
+            Ok(pruned) if !pruned.is_empty() => tracing::info!(
+                "Pruned {} expired safety snapshots of {}",
+                pruned.len(),
+                subvolume.display()
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(
+                "Failed to prune safety snapshots of {}: {e}",
+                subvolume.display()
+            ),
+        }
+    }
+
+    Ok(())
+}
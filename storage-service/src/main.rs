@@ -10,10 +10,19 @@ use tracing_subscriber::{EnvFilter, fmt};
 use zbus::connection::Builder as ConnectionBuilder;
 
 mod auth;
+mod backend;
 mod error;
 mod handlers;
+mod hooks;
+mod metrics;
+mod paging;
 mod policies;
+mod priority;
 mod protected_paths;
+mod restrictions;
+mod scheduler;
+mod statistics;
+mod throughput;
 
 use handlers::btrfs::BtrfsHandler;
 use handlers::disk::DiskHandler;
@@ -21,7 +30,9 @@ use handlers::filesystem::FilesystemHandler;
 use handlers::image::ImageHandler;
 use handlers::luks::LuksHandler;
 use handlers::lvm::LvmHandler;
+use handlers::operations::{OPERATIONS_PATH, OperationsHandler};
 use handlers::partition::PartitionHandler;
+use handlers::raid::RaidHandler;
 use handlers::rclone::RcloneHandler;
 use handlers::service::StorageService;
 
@@ -47,6 +58,8 @@ async fn main() -> Result<()> {
         anyhow::bail!("Service must run with root privileges");
     }
 
+    tracing::info!("Using the {:?} storage backend", backend::current().kind);
+
     // Build D-Bus connection with socket activation support
     let disk_handler = DiskHandler::new();
 
@@ -73,8 +86,10 @@ async fn main() -> Result<()> {
             FilesystemHandler::new()?,
         )?
         .serve_at("/org/cosmic/ext/Storage/Service/lvm", LvmHandler::new())?
+        .serve_at("/org/cosmic/ext/Storage/Service/raid", RaidHandler::new())?
         .serve_at("/org/cosmic/ext/Storage/Service/luks", LuksHandler::new())?
-        .serve_at("/org/cosmic/ext/Storage/Service/image", ImageHandler::new())?;
+        .serve_at("/org/cosmic/ext/Storage/Service/image", ImageHandler::new())?
+        .serve_at(OPERATIONS_PATH, OperationsHandler)?;
 
     // Conditionally serve RClone interface if available
     if let Some(handler) = rclone_handler {
@@ -91,9 +106,14 @@ async fn main() -> Result<()> {
     tracing::info!("  - Partitions interface at /org/cosmic/ext/Storage/Service/partitions");
     tracing::info!("  - Filesystems interface at /org/cosmic/ext/Storage/Service/filesystems");
     tracing::info!("  - LVM interface at /org/cosmic/ext/Storage/Service/lvm");
+    tracing::info!("  - RAID interface at /org/cosmic/ext/Storage/Service/raid");
     tracing::info!("  - LUKS interface at /org/cosmic/ext/Storage/Service/luks");
     tracing::info!("  - Image interface at /org/cosmic/ext/Storage/Service/image");
     tracing::info!("  - RClone interface at /org/cosmic/ext/Storage/Service/rclone");
+    tracing::info!("  - Operations interface at {OPERATIONS_PATH}");
+
+    // Report the progress of long operations
+    handlers::operations::report_operations(connection.clone()).await?;
 
     // Start disk hotplug monitoring
     handlers::disk::hotplug::monitor_hotplug_events(
@@ -103,6 +123,58 @@ async fn main() -> Result<()> {
     .await?;
     tracing::info!("Disk hotplug monitoring enabled");
 
+    // Start scheduled SMART self-tests
+    handlers::disk::selftest::run_self_test_scheduler(
+        connection.clone(),
+        "/org/cosmic/ext/Storage/Service/disks",
+    )
+    .await?;
+
+    // Start scheduled syncs of secondary EFI System Partitions
+    handlers::partition::esp::run_esp_sync_scheduler();
+
+    // Start drive temperature monitoring
+    handlers::disk::temperature::monitor_temperatures(
+        connection.clone(),
+        "/org/cosmic/ext/Storage/Service/disks",
+    )
+    .await?;
+
+    // Start collecting device errors from the kernel log
+    handlers::disk::kernel_log::monitor_kernel_log(
+        connection.clone(),
+        "/org/cosmic/ext/Storage/Service/disks",
+    )
+    .await?;
+
+    // Start sampling free space for capacity forecasts
+    handlers::filesystem::capacity::monitor_capacity(
+        connection.clone(),
+        "/org/cosmic/ext/Storage/Service/filesystems",
+    )
+    .await?;
+
+    // Start warning about filesystems low on free space
+    handlers::filesystem::low_space::monitor_low_space(
+        connection.clone(),
+        "/org/cosmic/ext/Storage/Service/filesystems",
+    )
+    .await?;
+
+    // Start MD-RAID health monitoring
+    handlers::raid::monitor::monitor_raid_health(
+        connection.clone(),
+        "/org/cosmic/ext/Storage/Service/raid",
+    )
+    .await?;
+
+    // Start the metrics exporter if the administrator enabled it
+    if let Err(e) =
+        metrics::export_metrics(connection.clone(), "/org/cosmic/ext/Storage/Service/disks").await
+    {
+        tracing::warn!("Failed to start the metrics exporter: {e}");
+    }
+
     // Keep service running until shutdown signal
     tracing::info!("Service ready, waiting for requests...");
     tokio::signal::ctrl_c().await?;
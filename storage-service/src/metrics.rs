@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Metrics exporter
+//!
+//! When enabled in [`CONFIG_PATH`], periodically collects disk capacity and
+//! filesystem usage, SMART health, MD-RAID state and the operation counters
+//! kept by `#[authorized_interface]`, and publishes them in the OpenMetrics
+//! text format: served over HTTP at `/metrics`, written to a textfile for
+//! node_exporter, or both. The endpoint is read-only and needs no
+//! authorization, so it only exposes data the app shows to any active user.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use storage_types::{
+    HealthLevel, MetricFamily, MetricKind, MetricsConfig, RaidDetail, VolumeInfo,
+    encode_openmetrics,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+
+use crate::handlers::disk::DiskHandler;
+
+/// Exporter settings, written by the administrator
+const CONFIG_PATH: &str = "/etc/cosmic-ext-storage/metrics.json";
+
+/// Shortest time between two collections
+const MIN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Requests answered at once; further connections are closed right away
+const MAX_CONNECTIONS: usize = 16;
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Authorization outcomes of D-Bus methods, by (action ID, outcome)
+static OPERATIONS: LazyLock<Mutex<BTreeMap<(String, &'static str), u64>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Count a D-Bus method call by its Polkit action and whether it was granted
+pub fn record_operation(action_id: &str, granted: bool) {
+    let outcome = if granted { "granted" } else { "denied" };
+    if let Ok(mut operations) = OPERATIONS.lock() {
+        *operations
+            .entry((action_id.to_string(), outcome))
+            .or_default() += 1;
+    }
+}
+
+fn load_config() -> MetricsConfig {
+    match std::fs::read_to_string(CONFIG_PATH) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid metrics config: {e}");
+            MetricsConfig::default()
+        }),
+        Err(_) => MetricsConfig::default(),
+    }
+}
+
+/// Start the exporter if it is enabled.
+pub(crate) async fn export_metrics(connection: zbus::Connection, disks_path: &str) -> Result<()> {
+    let config = load_config();
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, DiskHandler>(disks_path)
+        .await?;
+    let latest = Arc::new(RwLock::new(encode_openmetrics(&[])));
+
+    // The textfile is still written when the address can't be bound
+    if let Some(address) = &config.listen {
+        match TcpListener::bind(address).await {
+            Ok(listener) => {
+                tracing::info!("Serving metrics at http://{address}/metrics");
+                serve(listener, Arc::clone(&latest));
+            }
+            Err(e) => tracing::warn!("Not serving metrics, failed to listen on {address}: {e}"),
+        }
+    }
+
+    let interval = Duration::from_secs(config.interval_secs).max(MIN_INTERVAL);
+    tokio::spawn(async move {
+        loop {
+            let handler = iface_ref.get().await;
+            let families = collect(&handler).await;
+            drop(handler);
+            let text = encode_openmetrics(&families);
+
+            if let Some(path) = &config.textfile
+                && let Err(e) = write_textfile(path, &text)
+            {
+                tracing::warn!("Failed to write metrics to {path}: {e}");
+            }
+            if let Ok(mut latest) = latest.write() {
+                *latest = text;
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    });
+
+    tracing::info!("Metrics export started");
+    Ok(())
+}
+
+/// Answer connections to `listener` with the latest metrics
+fn serve(listener: TcpListener, latest: Arc<RwLock<String>>) {
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let Ok(permit) = Arc::clone(&connections).try_acquire_owned() else {
+                        tracing::debug!(
+                            "Too many metrics connections, closing the one from {peer}"
+                        );
+                        continue;
+                    };
+                    let body = latest.read().map(|m| m.clone()).unwrap_or_default();
+                    tokio::spawn(async move {
+                        if let Err(e) = respond(stream, body).await {
+                            tracing::debug!("Metrics request failed: {e}");
+                        }
+                        drop(permit);
+                    });
+                }
+                Err(e) => tracing::warn!("Failed to accept metrics connection: {e}"),
+            }
+        }
+    });
+}
+
+/// Answer one HTTP request; only `GET /metrics` is served
+async fn respond(mut stream: TcpStream, body: String) -> std::io::Result<()> {
+    let mut request = [0u8; 1024];
+    let read = tokio::time::timeout(REQUEST_TIMEOUT, stream.read(&mut request))
+        .await
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??;
+    let request_line = String::from_utf8_lossy(&request[..read]);
+    let mut parts = request_line.split_whitespace();
+
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        ),
+        (Some("GET"), _) => {
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        }
+        _ => "HTTP/1.1 405 Method Not Allowed\r\nAllow: GET\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Replace the textfile atomically, so collectors never read half of it
+fn write_textfile(path: &str, text: &str) -> std::io::Result<()> {
+    let tmp = format!("{path}.tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
+
+async fn collect(handler: &DiskHandler) -> Vec<MetricFamily> {
+    let mut families = disk_metrics(handler).await;
+
+    let arrays = tokio::task::spawn_blocking(|| {
+        storage_sys::list_arrays()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|array| storage_sys::raid_detail(array).ok())
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    families.extend(raid_metrics(&arrays));
+
+    let mut operations = MetricFamily::new(
+        "cosmic_storage_operations",
+        MetricKind::Counter,
+        "D-Bus method calls by Polkit action and authorization outcome",
+    );
+    if let Ok(counts) = OPERATIONS.lock() {
+        for ((action, outcome), count) in counts.iter() {
+            operations.push(
+                vec![("action", action.clone()), ("outcome", outcome.to_string())],
+                *count as f64,
+            );
+        }
+    }
+    families.push(operations);
+    families
+}
+
+async fn disk_metrics(handler: &DiskHandler) -> Vec<MetricFamily> {
+    let mut size = MetricFamily::new(
+        "cosmic_storage_disk_size_bytes",
+        MetricKind::Gauge,
+        "Capacity of the disk",
+    );
+    let mut fs_size = MetricFamily::new(
+        "cosmic_storage_filesystem_size_bytes",
+        MetricKind::Gauge,
+        "Size of the mounted filesystem",
+    );
+    let mut fs_used = MetricFamily::new(
+        "cosmic_storage_filesystem_used_bytes",
+        MetricKind::Gauge,
+        "Space used on the mounted filesystem",
+    );
+    let mut score = MetricFamily::new(
+        "cosmic_storage_disk_health_score",
+        MetricKind::Gauge,
+        "Health score of the disk from 0 (failing) to 100",
+    );
+    let mut status = MetricFamily::new(
+        "cosmic_storage_disk_health_status",
+        MetricKind::Gauge,
+        "1 for the current health status of the disk",
+    );
+    let mut temperature = MetricFamily::new(
+        "cosmic_storage_disk_temperature_celsius",
+        MetricKind::Gauge,
+        "Current temperature of the disk",
+    );
+    let mut power_on = MetricFamily::new(
+        "cosmic_storage_disk_power_on_hours",
+        MetricKind::Gauge,
+        "Hours the disk has been powered on",
+    );
+
+    let drives = match handler.list_disks_with_volumes_raw().await {
+        Ok(drives) => drives,
+        Err(e) => {
+            tracing::warn!("Metrics could not list disks: {e}");
+            Vec::new()
+        }
+    };
+
+    for (disk, volumes) in drives.iter().filter(|(d, _)| !d.optical) {
+        let device = || ("device", disk.device.clone());
+        size.push(
+            vec![
+                device(),
+                ("model", disk.display_name()),
+                ("serial", disk.serial.clone()),
+            ],
+            disk.size as f64,
+        );
+        filesystem_metrics(volumes, &mut fs_size, &mut fs_used);
+
+        if disk.is_loop {
+            continue;
+        }
+        let drive_id = if disk.id.is_empty() {
+            disk.device.clone()
+        } else {
+            disk.id.clone()
+        };
+        let Ok((summary, Some(info))) = handler.health_summary(&disk.device, &drive_id).await
+        else {
+            continue;
+        };
+
+        score.push(vec![device()], summary.score as f64);
+        for (level, label) in [
+            (HealthLevel::Good, "good"),
+            (HealthLevel::Warning, "warning"),
+            (HealthLevel::Critical, "critical"),
+        ] {
+            status.push(
+                vec![device(), ("status", label.to_string())],
+                if summary.level == level { 1.0 } else { 0.0 },
+            );
+        }
+        if let Some(celsius) = info.temperature_c {
+            temperature.push(vec![device()], celsius as f64);
+        }
+        if let Some(hours) = info.power_on_hours {
+            power_on.push(vec![device()], hours as f64);
+        }
+    }
+
+    vec![size, fs_size, fs_used, score, status, temperature, power_on]
+}
+
+fn filesystem_metrics(volumes: &[VolumeInfo], size: &mut MetricFamily, used: &mut MetricFamily) {
+    for volume in volumes {
+        if let Some(usage) = &volume.usage {
+            let labels = vec![
+                ("device", volume.device_path.clone().unwrap_or_default()),
+                ("mountpoint", usage.mount_point.clone()),
+                ("fstype", usage.filesystem.clone()),
+            ];
+            size.push(labels.clone(), usage.total_bytes() as f64);
+            used.push(labels, usage.used_bytes() as f64);
+        }
+        filesystem_metrics(&volume.children, size, used);
+    }
+}
+
+fn raid_metrics(arrays: &[RaidDetail]) -> Vec<MetricFamily> {
+    let mut members = MetricFamily::new(
+        "cosmic_storage_raid_members",
+        MetricKind::Gauge,
+        "Members the MD-RAID array should have",
+    );
+    let mut degraded = MetricFamily::new(
+        "cosmic_storage_raid_degraded_members",
+        MetricKind::Gauge,
+        "Missing or failed members of the MD-RAID array",
+    );
+    let mut syncing = MetricFamily::new(
+        "cosmic_storage_raid_sync_ratio",
+        MetricKind::Gauge,
+        "Progress of the running sync action from 0 to 1, 1 when idle",
+    );
+    let mut mismatches = MetricFamily::new(
+        "cosmic_storage_raid_mismatch_sectors",
+        MetricKind::Gauge,
+        "Sectors found inconsistent by the last check or repair",
+    );
+    let mut state = MetricFamily::new(
+        "cosmic_storage_raid_info",
+        MetricKind::Gauge,
+        "Level, array state and sync action of the MD-RAID array",
+    );
+
+    for array in arrays {
+        let device = || ("array", array.device.clone());
+        members.push(vec![device()], array.raid_disks as f64);
+        degraded.push(vec![device()], array.degraded as f64);
+        let ratio = match array.sync_completed_percent {
+            Some(percent) if array.is_syncing() => percent / 100.0,
+            _ => 1.0,
+        };
+        syncing.push(vec![device(), ("action", array.sync_action.clone())], ratio);
+        if let Some(count) = array.mismatch_count {
+            mismatches.push(vec![device()], count as f64);
+        }
+        state.push(
+            vec![
+                device(),
+                ("level", array.level.clone()),
+                ("state", array.array_state.clone()),
+            ],
+            1.0,
+        );
+    }
+
+    vec![members, degraded, syncing, mismatches, state]
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Paged replies
+//!
+//! Methods whose replies grow with the system return the first
+//! [`ResultPage`](storage_contracts::ResultPage) of their JSON; callers
+//! fetch the rest with GetPage, or all at once as a sealed memory file
+//! with GetRestFd, on the service interface. Pages belong to the bus name
+//! of the call.
+
+use std::sync::LazyLock;
+
+use serde::Serialize;
+use storage_contracts::{PageStore, ResultPage};
+
+static PAGES: LazyLock<PageStore> = LazyLock::new(PageStore::default);
+
+/// Reply of a paged method: the first page of `value` for `sender`
+pub fn reply<T: Serialize>(sender: &str, value: &T) -> zbus::fdo::Result<String> {
+    PAGES.paginate_json(sender, value).map_err(|e| {
+        tracing::error!("Failed to page reply: {e}");
+        zbus::fdo::Error::Failed(format!("Failed to serialize: {e}"))
+    })
+}
+
+/// Page `index` of an earlier reply to `sender`
+pub fn page(sender: &str, handle: &str, index: u32) -> zbus::fdo::Result<ResultPage> {
+    PAGES
+        .page(sender, handle, index)
+        .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))
+}
+
+/// The pages of an earlier reply to `sender` after the first, in a sealed
+/// memory file
+pub async fn rest_fd(sender: &str, handle: &str) -> zbus::fdo::Result<zbus::zvariant::OwnedFd> {
+    let rest = PAGES
+        .take_rest(sender, handle)
+        .map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+    let fd = tokio::task::spawn_blocking(move || {
+        storage_sys::sealed_memfd("cosmic-ext-storage-reply", rest.as_bytes())
+    })
+    .await
+    .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to write reply: {e}")))?
+    .map_err(|e| {
+        tracing::error!("Failed to write reply to a memory file: {e}");
+        zbus::fdo::Error::Failed(format!("Failed to write reply: {e}"))
+    })?;
+    Ok(fd.into())
+}
@@ -10,6 +10,8 @@ pub trait FilesystemsDomain: Send + Sync {
         supported_tools: &[String],
     ) -> zbus::fdo::Result<()>;
     fn require_filesystem_check_support(&self) -> zbus::fdo::Result<()>;
+    fn require_defrag_support(&self, fs_type: &str) -> zbus::fdo::Result<()>;
+    fn require_fscrypt_support(&self, fs_type: &str) -> zbus::fdo::Result<()>;
 }
 
 pub struct FilesystemsPolicy;
@@ -96,4 +98,38 @@ impl FilesystemsDomain for FilesystemsPolicy {
 
         Ok(())
     }
+
+    fn require_defrag_support(&self, fs_type: &str) -> zbus::fdo::Result<()> {
+        let base_type = if fs_type.starts_with("ext") {
+            "ext4"
+        } else {
+            fs_type
+        };
+        if !Self::feature_enabled_for_fs(base_type) {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "Defragmentation of '{}' unavailable: compile-time feature disabled",
+                fs_type
+            )));
+        }
+
+        if !storage_sys::defrag_supported(fs_type) {
+            return Err(zbus::fdo::Error::Failed(format!(
+                "Defragmentation is not supported for '{}'",
+                fs_type
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn require_fscrypt_support(&self, fs_type: &str) -> zbus::fdo::Result<()> {
+        if !storage_types::fscrypt_capable(fs_type) {
+            return Err(zbus::fdo::Error::NotSupported(format!(
+                "Directory encryption is not supported for '{}'",
+                fs_type
+            )));
+        }
+
+        Ok(())
+    }
 }
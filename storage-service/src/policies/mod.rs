@@ -7,4 +7,5 @@ pub mod image;
 pub mod luks;
 pub mod lvm;
 pub mod partition;
+pub mod raid;
 pub mod rclone;
@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+pub trait RaidDomain: Send + Sync {
+    fn require_mdadm(&self) -> zbus::fdo::Result<()>;
+}
+
+pub struct RaidPolicy {
+    mdadm_available: bool,
+}
+
+impl RaidPolicy {
+    pub fn new() -> Self {
+        let mdadm_available = cfg!(feature = "raid-tools") && which::which("mdadm").is_ok();
+
+        Self { mdadm_available }
+    }
+}
+
+impl RaidDomain for RaidPolicy {
+    fn require_mdadm(&self) -> zbus::fdo::Result<()> {
+        if !cfg!(feature = "raid-tools") {
+            return Err(zbus::fdo::Error::Failed(
+                "MD-RAID unavailable: compile-time feature disabled".to_string(),
+            ));
+        }
+
+        if !self.mdadm_available {
+            return Err(zbus::fdo::Error::Failed(
+                "mdadm not available on this system".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Configured CPU and I/O priority of long-running operations
+//!
+//! Handlers look up the priority of an operation with [`resolve`] before
+//! starting it, and apply it on the worker thread with
+//! [`storage_sys::apply_priority`].
+
+use std::path::Path;
+
+use storage_types::{OperationKind, OperationPriority, PrioritySettings};
+
+/// Persisted priority settings
+const SETTINGS_PATH: &str = "/var/lib/cosmic-ext-storage/priorities.json";
+
+/// Load the priority settings, falling back to the defaults
+pub fn load_settings() -> PrioritySettings {
+    match std::fs::read_to_string(SETTINGS_PATH) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid priority settings: {e}");
+            PrioritySettings::default()
+        }),
+        Err(_) => PrioritySettings::default(),
+    }
+}
+
+/// Persist the priority settings
+pub fn save_settings(settings: &PrioritySettings) -> std::io::Result<()> {
+    let path = Path::new(SETTINGS_PATH);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
+}
+
+/// Priority of an operation of `kind`: `override_json` when the caller
+/// passed an `OperationPriority` for this one operation, the configured one
+/// when it is empty
+pub fn resolve(kind: OperationKind, override_json: &str) -> zbus::fdo::Result<OperationPriority> {
+    if override_json.trim().is_empty() {
+        return Ok(load_settings().priority(kind));
+    }
+    let priority: OperationPriority = serde_json::from_str(override_json)
+        .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid priority: {e}")))?;
+    priority.validate().map_err(zbus::fdo::Error::InvalidArgs)?;
+    Ok(priority)
+}
@@ -5,7 +5,7 @@
 //! This module provides safety checks to prevent users from accidentally
 //! killing processes on critical system paths during unmount operations.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Critical system paths that should not have their processes killed during unmount
 ///
@@ -79,6 +79,62 @@ pub fn is_protected_path(mount_point: &Path) -> bool {
     false
 }
 
+/// System directories nothing may be mounted or bound anywhere inside,
+/// unlike `/home`, `/srv` and `/tmp`, of which only the directory itself
+/// is protected
+const SYSTEM_DIRECTORIES: &[&str] = &[
+    "/boot", "/efi", "/usr", "/var", "/etc", "/opt", "/root", "/run", "/proc", "/sys", "/dev",
+    "/bin", "/sbin", "/lib", "/lib64",
+];
+
+/// Directories inside [`SYSTEM_DIRECTORIES`] that hold removable media
+const MEDIA_DIRECTORIES: &[&str] = &["/run/media"];
+
+/// Check if a path is one of the protected system paths, or lies inside a
+/// system directory other than where removable media are mounted
+///
+/// Unlike [`is_protected_path`] this compares the path as given, so it also
+/// covers paths that don't exist yet; see [`is_protected_target`] for paths
+/// whose parents may be symlinks.
+pub fn is_within_protected_path(path: &Path) -> bool {
+    let inside = |dirs: &[&str]| dirs.iter().any(|dir| path.starts_with(dir));
+    let inside_media = MEDIA_DIRECTORIES
+        .iter()
+        .any(|dir| path.starts_with(dir) && path != Path::new(dir));
+    PROTECTED_SYSTEM_PATHS
+        .iter()
+        .any(|protected| path == Path::new(protected))
+        || (inside(SYSTEM_DIRECTORIES) && !inside_media)
+}
+
+/// Check if a path someone wants to mount or bind something at is
+/// protected, as given or once symlinks along its existing part are resolved
+pub fn is_protected_target(path: &Path) -> bool {
+    is_within_protected_path(path) || is_within_protected_path(&resolve_existing_ancestor(path))
+}
+
+/// `path` with its longest existing ancestor canonicalized, so a symlink
+/// along it can't lead elsewhere
+pub fn resolve_existing_ancestor(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut ancestor = path;
+    loop {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |resolved, name| resolved.join(name));
+        }
+        match (ancestor.file_name(), ancestor.parent()) {
+            (Some(name), Some(parent)) => {
+                missing.push(name);
+                ancestor = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,6 +151,36 @@ mod tests {
         assert!(is_protected_path(Path::new("/")));
     }
 
+    #[test]
+    fn test_paths_inside_protected_paths_are_protected() {
+        assert!(is_within_protected_path(Path::new("/")));
+        assert!(is_within_protected_path(Path::new("/etc")));
+        assert!(is_within_protected_path(Path::new("/etc/cron.d")));
+        assert!(is_within_protected_path(Path::new("/usr/lib/nonexistent")));
+        assert!(is_within_protected_path(Path::new("/run/systemd/system/x")));
+        assert!(is_within_protected_path(Path::new("/home")));
+        assert!(is_within_protected_path(Path::new("/run/media")));
+        assert!(!is_within_protected_path(Path::new("/run/media/user/DISK")));
+        assert!(!is_within_protected_path(Path::new("/home/user/Music")));
+        assert!(!is_within_protected_path(Path::new("/etcetera")));
+    }
+
+    #[test]
+    fn test_symlinked_parents_are_resolved() {
+        let dir = std::env::temp_dir().join(format!("protected-paths-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let link = dir.join("link");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink("/etc", &link).unwrap();
+
+        let protected = is_protected_target(&link.join("new/unit"));
+        let unprotected = is_protected_target(&dir.join("new/unit"));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(protected);
+        assert!(!unprotected);
+    }
+
     #[test]
     fn test_nonexistent_path_not_protected() {
         // Non-existent paths should return false (not protected)
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Restriction profiles
+//!
+//! `#[authorized_interface]` asks [`is_allowed`] before Polkit, so that an
+//! action left out of the caller's profiles is refused even when Polkit
+//! would grant it. The policy is read on every check, so edits to
+//! [`CONFIG_PATH`] apply without restarting the service. A policy that
+//! can't be read or parsed restricts everyone but root to read actions,
+//! rather than lifting all restrictions.
+
+use std::ffi::CStr;
+
+use storage_types::{AllowedActions, RestrictionPolicy};
+
+use crate::handlers::filesystem::support::uid_groups::resolve_caller_groups;
+
+/// Restriction profiles, written by the administrator
+const CONFIG_PATH: &str = "/etc/cosmic-ext-storage/restrictions.json";
+
+/// The restriction profiles; None when [`CONFIG_PATH`] exists but is invalid
+fn load_config() -> Option<RestrictionPolicy> {
+    let json = match std::fs::read_to_string(CONFIG_PATH) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Some(RestrictionPolicy::default());
+        }
+        Err(e) => {
+            tracing::error!("Cannot read restriction profiles, allowing read actions only: {e}");
+            return None;
+        }
+    };
+    serde_json::from_str(&json)
+        .inspect_err(|e| {
+            tracing::error!("Invalid restriction profiles, allowing read actions only: {e}");
+        })
+        .ok()
+}
+
+/// What everyone but root may do while the profiles are invalid
+fn read_actions_only() -> AllowedActions {
+    AllowedActions {
+        restricted: true,
+        actions: Vec::new(),
+    }
+}
+
+/// Name of the group `gid`, if it has one
+fn group_name(gid: u32) -> Option<String> {
+    let mut group = std::mem::MaybeUninit::<libc::group>::uninit();
+    let mut group_ptr: *mut libc::group = std::ptr::null_mut();
+    let mut buffer = vec![0_u8; 4096];
+
+    let result = unsafe {
+        libc::getgrgid_r(
+            gid,
+            group.as_mut_ptr(),
+            buffer.as_mut_ptr() as *mut libc::c_char,
+            buffer.len(),
+            &mut group_ptr,
+        )
+    };
+    if result != 0 || group_ptr.is_null() {
+        return None;
+    }
+
+    let group = unsafe { group.assume_init() };
+    unsafe { CStr::from_ptr(group.gr_name) }
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
+/// Names of the groups `uid` is a member of
+fn group_names(uid: u32) -> Vec<String> {
+    resolve_caller_groups(uid, None)
+        .into_iter()
+        .filter_map(group_name)
+        .collect()
+}
+
+/// Whether the profiles let `uid` run `action`; root is never restricted
+pub fn is_allowed(uid: u32, action: &str) -> bool {
+    if uid == 0 {
+        return true;
+    }
+    let Some(policy) = load_config() else {
+        return read_actions_only().allows(action);
+    };
+    if policy.profiles.is_empty() {
+        return true;
+    }
+
+    let groups = group_names(uid);
+    let allowed = policy.allows(&groups, action);
+    if !allowed {
+        let profiles: Vec<&str> = policy
+            .profiles_for(&groups)
+            .map(|profile| profile.name.as_str())
+            .collect();
+        tracing::warn!(
+            "Action {action} is not allowed to UID {uid} by restriction profiles {}",
+            profiles.join(", ")
+        );
+    }
+    allowed
+}
+
+/// What the profiles let `uid` do
+pub fn allowed_actions(uid: u32) -> AllowedActions {
+    if uid == 0 {
+        return AllowedActions::default();
+    }
+    let Some(policy) = load_config() else {
+        return read_actions_only();
+    };
+    if policy.profiles.is_empty() {
+        return AllowedActions::default();
+    }
+    policy.allowed_actions(&group_names(uid))
+}
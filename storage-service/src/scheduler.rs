@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Power checks shared by the scheduled jobs
+//!
+//! Timer loops wait with [`wait`] between their checks and ask
+//! [`may_run`] before starting a due job. A job the power policy holds
+//! back is listed as deferred until it runs; [`run_anyway`] lets it run on
+//! the next check, which it starts right away.
+
+use std::collections::BTreeSet;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use storage_types::{DeferredJob, PowerSettings, PowerState, ScheduledJob};
+use tokio::sync::Notify;
+
+use crate::handlers::disk::selftest::{load, now, save};
+
+/// Persisted power policies
+const SETTINGS_PATH: &str = "/var/lib/cosmic-ext-storage/power-policies.json";
+
+/// Jobs currently held back by their power policy
+static DEFERRED: LazyLock<Mutex<Vec<DeferredJob>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Deferred jobs the user chose to run anyway, until they ran
+static RUN_ANYWAY: LazyLock<Mutex<BTreeSet<(ScheduledJob, String)>>> =
+    LazyLock::new(|| Mutex::new(BTreeSet::new()));
+
+/// Wakes the timer loops before their interval is over
+static WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Load the power policies, falling back to the defaults
+pub fn load_settings() -> PowerSettings {
+    load(SETTINGS_PATH)
+}
+
+/// Persist the power policies
+pub fn save_settings(settings: &PowerSettings) -> std::io::Result<()> {
+    save(SETTINGS_PATH, settings)
+}
+
+/// Sleep for `interval`, or until a deferred job is to run anyway
+pub async fn wait(interval: Duration) {
+    tokio::select! {
+        _ = tokio::time::sleep(interval) => {}
+        _ = WAKE.notified() => {}
+    }
+}
+
+/// Whether the due `job` on `target` may start now
+///
+/// When its power policy holds it back, it is listed as deferred, with
+/// `label` naming `target` for the user.
+pub async fn may_run(job: ScheduledJob, target: &str, label: &str) -> bool {
+    let key = (job, target.to_string());
+    let forced = RUN_ANYWAY.lock().unwrap().remove(&key);
+    let deferral = if forced {
+        None
+    } else {
+        load_settings().policy(job).deferral(&power_state().await)
+    };
+
+    let mut deferred = DEFERRED.lock().unwrap();
+    let position = deferred
+        .iter()
+        .position(|entry| entry.job == job && entry.target == target);
+    match (deferral, position) {
+        (Some(reason), Some(index)) => deferred[index].reason = reason,
+        (Some(reason), None) => deferred.push(DeferredJob {
+            job,
+            target: target.to_string(),
+            label: label.to_string(),
+            reason,
+            since: now(),
+        }),
+        (None, Some(index)) => {
+            deferred.remove(index);
+        }
+        (None, None) => {}
+    }
+    if let Some(reason) = deferral {
+        tracing::debug!("Deferring {job:?} of {label}: {reason:?}");
+    }
+    deferral.is_none()
+}
+
+/// Jobs held back by their power policy
+pub fn deferred_jobs() -> Vec<DeferredJob> {
+    DEFERRED.lock().unwrap().clone()
+}
+
+/// Run the deferred `job` on `target` whatever the power supply, on a
+/// check started right away
+///
+/// Returns false when no such job is deferred.
+pub fn run_anyway(job: ScheduledJob, target: &str) -> bool {
+    let deferred = DEFERRED
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|entry| entry.job == job && entry.target == target);
+    if deferred {
+        RUN_ANYWAY.lock().unwrap().insert((job, target.to_string()));
+        WAKE.notify_waiters();
+    }
+    deferred
+}
+
+/// Power supply of the system, from UPower or, without it, from sysfs
+pub async fn power_state() -> PowerState {
+    match upower_state().await {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::debug!("UPower not available, reading sysfs: {e}");
+            sysfs_power_state()
+        }
+    }
+}
+
+async fn upower_state() -> zbus::Result<PowerState> {
+    let connection = zbus::Connection::system().await?;
+    let upower = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.UPower",
+        "/org/freedesktop/UPower",
+        "org.freedesktop.UPower",
+    )
+    .await?;
+    let on_battery: bool = upower.get_property("OnBattery").await?;
+
+    let display = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.UPower",
+        "/org/freedesktop/UPower/devices/DisplayDevice",
+        "org.freedesktop.UPower.Device",
+    )
+    .await?;
+    let present: bool = display.get_property("IsPresent").await.unwrap_or(false);
+    let percentage = if present {
+        display.get_property::<f64>("Percentage").await.ok()
+    } else {
+        None
+    };
+
+    Ok(PowerState {
+        on_battery,
+        percentage,
+    })
+}
+
+/// Power supply from `/sys/class/power_supply`; systems without a battery
+/// count as on AC
+fn sysfs_power_state() -> PowerState {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerState::default();
+    };
+
+    let mut on_ac = false;
+    let mut has_battery = false;
+    let mut capacities = Vec::new();
+    for entry in entries.flatten() {
+        let read = |name: &str| {
+            std::fs::read_to_string(entry.path().join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" if read("online") == "1" => on_ac = true,
+            "Battery" => {
+                has_battery = true;
+                capacities.extend(read("capacity").parse::<f64>().ok());
+            }
+            _ => {}
+        }
+    }
+    let percentage =
+        (!capacities.is_empty()).then(|| capacities.iter().sum::<f64>() / capacities.len() as f64);
+    PowerState {
+        on_battery: has_battery && !on_ac,
+        percentage,
+    }
+}
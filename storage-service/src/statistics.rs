@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Local usage statistics
+//!
+//! For users who turned statistics on in the app, `#[authorized_interface]`
+//! appends each authorized method call to the operation journal, and the
+//! image handler appends the size of each completed backup. The journal
+//! stays in [`JOURNAL_PATH`]; nothing is ever sent anywhere. Users who did
+//! not turn statistics on are not recorded at all.
+
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use storage_types::{JournalEvent, OperationRecord, UsageStatistics};
+
+/// Operation journal, one JSON record per line
+const JOURNAL_PATH: &str = "/var/lib/cosmic-ext-storage/operations.jsonl";
+
+/// UIDs of the users who turned statistics on
+const USERS_PATH: &str = "/var/lib/cosmic-ext-storage/usage-statistics-users.json";
+
+/// Methods that are not counted as operations: looking at or managing the
+/// statistics is not using the tool, and fetching pages of a reply is part
+/// of the call that made it
+const UNCOUNTED: [&str; 5] = [
+    "get_usage_statistics",
+    "set_usage_statistics",
+    "clear_usage_statistics",
+    "get_page",
+    "get_rest_fd",
+];
+
+/// Serializes changes to the journal and the user list
+static LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn load_users() -> BTreeSet<u32> {
+    std::fs::read_to_string(USERS_PATH)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write_file(path: &str, contents: &str) -> std::io::Result<()> {
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)
+}
+
+fn read_journal() -> Vec<OperationRecord> {
+    std::fs::read_to_string(JOURNAL_PATH)
+        .map(|text| OperationRecord::parse_journal(&text))
+        .unwrap_or_default()
+}
+
+/// Whether operations of `uid` are recorded
+pub fn is_enabled(uid: u32) -> bool {
+    load_users().contains(&uid)
+}
+
+/// Turn recording of the operations of `uid` on or off; records kept so
+/// far stay until [`clear`]
+pub fn set_enabled(uid: u32, enabled: bool) -> std::io::Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut users = load_users();
+    let changed = if enabled {
+        users.insert(uid)
+    } else {
+        users.remove(&uid)
+    };
+    if !changed {
+        return Ok(());
+    }
+    let json = serde_json::to_string(&users).map_err(std::io::Error::other)?;
+    write_file(USERS_PATH, &json)
+}
+
+/// Append `event` of `uid` to the journal if they turned statistics on
+///
+/// Failures are logged and otherwise ignored: statistics never get in the
+/// way of an operation.
+pub fn record(uid: u32, event: JournalEvent) {
+    if !is_enabled(uid) {
+        return;
+    }
+
+    let record = OperationRecord {
+        time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        uid,
+        event,
+    };
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let result = serde_json::to_string(&record)
+        .map_err(std::io::Error::other)
+        .and_then(|line| {
+            if let Some(parent) = Path::new(JOURNAL_PATH).parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(JOURNAL_PATH)?;
+            writeln!(file, "{line}")
+        });
+    if let Err(e) = result {
+        tracing::warn!("Failed to record usage statistics: {e}");
+    }
+}
+
+/// Record that `uid` called the service method `operation`
+pub fn record_operation(uid: u32, operation: &str) {
+    if UNCOUNTED.contains(&operation) {
+        return;
+    }
+    record(
+        uid,
+        JournalEvent::Started {
+            operation: operation.to_string(),
+        },
+    );
+}
+
+/// Statistics of `uid` from the journal
+pub fn statistics(uid: u32) -> UsageStatistics {
+    UsageStatistics::from_records(&read_journal(), uid, is_enabled(uid))
+}
+
+/// Remove the records of `uid` from the journal
+pub fn clear(uid: u32) -> std::io::Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut records = read_journal();
+    let before = records.len();
+    records.retain(|record| record.uid != uid);
+    if records.len() == before {
+        return Ok(());
+    }
+
+    let mut text = String::new();
+    for record in &records {
+        text.push_str(&serde_json::to_string(record).map_err(std::io::Error::other)?);
+        text.push('\n');
+    }
+    write_file(JOURNAL_PATH, &text)
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Measured throughput of image operations, by kind and device
+//!
+//! Operations record their speed profile once they complete; running ones
+//! estimate their remaining time from the runs recorded for the same kind
+//! of operation on the same device.
+
+use std::collections::BTreeMap;
+
+use storage_types::{ThroughputHistory, ThroughputRun};
+
+use crate::handlers::disk::selftest::{load, save};
+use crate::handlers::image::OperationType;
+
+/// Persisted runs, by [`key`]
+const STORE_PATH: &str = "/var/lib/cosmic-ext-storage/throughput.json";
+
+/// Key of the runs of `kind` between `source` and `destination`
+///
+/// Backups are limited by the drive they read, every other operation by
+/// the device it writes. The device is named by its `/dev/disk/by-id` link
+/// when it has one, so that runs are found again after it was renamed.
+pub fn key(kind: &OperationType, source: &str, destination: &str) -> String {
+    let device = match kind {
+        OperationType::BackupDrive | OperationType::BackupPartition => source,
+        _ => destination,
+    };
+    format!("{kind}:{}", stable_device_name(device))
+}
+
+fn stable_device_name(device: &str) -> String {
+    let path = if device.starts_with('/') {
+        device.to_string()
+    } else {
+        format!("/dev/{device}")
+    };
+    let Ok(target) = std::fs::canonicalize(&path) else {
+        return path;
+    };
+    let Ok(entries) = std::fs::read_dir("/dev/disk/by-id") else {
+        return path;
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| std::fs::canonicalize(entry.path()).is_ok_and(|link| link == target))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    // WWN links are shared by the paths of multipath drives; prefer model
+    // and serial names
+    names.sort_by_key(|name| (name.starts_with("wwn-"), name.clone()));
+    names.into_iter().next().unwrap_or(path)
+}
+
+/// Recorded runs under `key`
+pub fn history(key: &str) -> ThroughputHistory {
+    load::<BTreeMap<String, ThroughputHistory>>(STORE_PATH)
+        .remove(key)
+        .unwrap_or_default()
+}
+
+/// Add a completed run under `key`
+pub fn record(key: &str, run: ThroughputRun) {
+    let mut all: BTreeMap<String, ThroughputHistory> = load(STORE_PATH);
+    all.entry(key.to_string()).or_default().push(run);
+    if let Err(e) = save(STORE_PATH, &all) {
+        tracing::warn!("Failed to save operation throughput: {e}");
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Building disk and partition models from recorded lsblk output: a
+//! workstation, and the same devices repeated as on a storage server
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use serde_json::Value;
+use std::hint::black_box;
+use storage_sys::direct::disks_from_lsblk;
+
+const WORKSTATION: &str = include_str!("fixtures/lsblk.json");
+
+/// The recorded devices repeated `copies` times
+fn repeated(copies: usize) -> String {
+    let mut value: Value = serde_json::from_str(WORKSTATION).expect("valid fixture");
+    let devices = value["blockdevices"]
+        .as_array()
+        .expect("block devices")
+        .clone();
+    value["blockdevices"] = Value::Array(std::iter::repeat_n(devices, copies).flatten().collect());
+    value.to_string()
+}
+
+fn discovery(c: &mut Criterion) {
+    let mut group = c.benchmark_group("disks_from_lsblk");
+    for copies in [1, 16, 64] {
+        let json = repeated(copies);
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(copies), &json, |b, json| {
+            b.iter(|| disks_from_lsblk(black_box(json)).expect("fixture parses"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, discovery);
+criterion_main!(benches);
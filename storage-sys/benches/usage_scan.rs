@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Walking a generated directory tree with the thread counts of each
+//! parallelism preset on this machine
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use storage_sys::usage::{ScanConfig, scan_paths};
+use storage_types::UsageScanParallelismPreset;
+
+const DIRECTORIES: usize = 64;
+const FILES_PER_DIRECTORY: usize = 128;
+const EXTENSIONS: [&str; 8] = ["rs", "jpg", "mp3", "mkv", "pdf", "tar.gz", "so", "bin"];
+
+/// A tree of nested directories with files of every category, removed
+/// when dropped
+struct Tree(PathBuf);
+
+impl Tree {
+    fn generate() -> Self {
+        let root = std::env::temp_dir().join(format!("storage-usage-bench-{}", std::process::id()));
+        for dir in 0..DIRECTORIES {
+            // Nest every fourth directory to give the walker some depth
+            let path = root.join(format!("d{}", dir / 4)).join(format!("e{dir}"));
+            fs::create_dir_all(&path).expect("create directory");
+            for file in 0..FILES_PER_DIRECTORY {
+                let extension = EXTENSIONS[file % EXTENSIONS.len()];
+                fs::write(
+                    path.join(format!("f{file}.{extension}")),
+                    vec![0; file * 64],
+                )
+                .expect("write file");
+            }
+        }
+        Self(root)
+    }
+}
+
+impl Drop for Tree {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn usage_scan(c: &mut Criterion) {
+    let tree = Tree::generate();
+    let roots = [tree.0.clone()];
+    let cpus = std::thread::available_parallelism().map_or(1, usize::from);
+
+    let mut group = c.benchmark_group("scan_paths");
+    group.sample_size(20);
+    group.measurement_time(Duration::from_secs(10));
+    group.throughput(Throughput::Elements(
+        (DIRECTORIES * FILES_PER_DIRECTORY) as u64,
+    ));
+    for preset in [
+        UsageScanParallelismPreset::Low,
+        UsageScanParallelismPreset::Balanced,
+        UsageScanParallelismPreset::High,
+    ] {
+        let threads = preset.threads(cpus);
+        let config = ScanConfig {
+            threads: Some(threads),
+            ..ScanConfig::default()
+        };
+        group.bench_function(
+            BenchmarkId::new(preset.as_str(), format!("{threads} threads")),
+            |b| b.iter(|| scan_paths(&roots, &config).expect("scan succeeds")),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, usage_scan);
+criterion_main!(benches);
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Moving misaligned partitions onto a 1 MiB boundary
+//!
+//! sfdisk moves the partition's contents before it rewrites the table
+//! entry, so the partition keeps its data and size and only changes place.
+
+use crate::direct::partition_location;
+use crate::error::{Result, SysError};
+use crate::lost_partition::partition_layout;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_types::{ByteRange, PartitionLayout, realigned_start};
+use tracing::info;
+
+/// Start of `partition` in bytes, from sysfs
+fn partition_start(partition: &str) -> Result<u64> {
+    let name = partition.strip_prefix("/dev/").unwrap_or(partition);
+    std::fs::read_to_string(Path::new("/sys/class/block").join(name).join("start"))
+        .ok()
+        .and_then(|start| start.trim().parse::<u64>().ok())
+        // sysfs counts in 512-byte units whatever the sector size
+        .map(|start| start * 512)
+        .ok_or_else(|| SysError::DeviceNotFound(format!("{} is not a partition", partition)))
+}
+
+/// The disk and number of `partition`, the layout of that disk, and where
+/// the partition lies in it
+pub(crate) fn partition_in_layout(
+    partition: &str,
+) -> Result<(String, u32, PartitionLayout, ByteRange)> {
+    let (disk, number) = partition_location(partition)?;
+    let start = partition_start(partition)?;
+    let layout = partition_layout(&disk)?;
+    let range = layout
+        .partitions
+        .iter()
+        .find(|range| range.start == start)
+        .copied()
+        .ok_or_else(|| {
+            SysError::OperationFailed(format!("{} is not in the partition table", partition))
+        })?;
+    Ok((disk, number, layout, range))
+}
+
+/// Whether `partition` is mounted, used for swap, or holds another device
+/// such as an unlocked LUKS container
+pub(crate) fn in_use(partition: &str) -> bool {
+    let name = partition.strip_prefix("/dev/").unwrap_or(partition);
+    let held = std::fs::read_dir(Path::new("/sys/class/block").join(name).join("holders"))
+        .is_ok_and(|mut holders| holders.next().is_some());
+    let listed = |table: &str| {
+        std::fs::read_to_string(table).is_ok_and(|text| {
+            text.lines()
+                .any(|line| line.split_whitespace().next() == Some(partition))
+        })
+    };
+    held || listed("/proc/mounts") || listed("/proc/swaps")
+}
+
+/// Move `partition` and its contents to start on a 1 MiB boundary, and
+/// return the new start in bytes
+///
+/// The partition must not be in use. Moving copies the whole partition,
+/// which takes as long as reading and writing it once.
+pub fn realign_partition(partition: &str) -> Result<u64> {
+    if in_use(partition) {
+        return Err(SysError::OperationFailed(format!(
+            "{} is in use; unmount or lock it first",
+            partition
+        )));
+    }
+    let (disk, number, layout, range) = partition_in_layout(partition)?;
+    let start = range.start;
+    let target = realigned_start(&layout, &range).ok_or_else(|| {
+        SysError::OperationFailed(
+            "There is no free space next to the partition to align it".to_string(),
+        )
+    })?;
+    if target == start {
+        return Ok(start);
+    }
+
+    info!(
+        "Moving partition {} of {} from byte {} to {}",
+        number, disk, start, target
+    );
+    set_partition_start(
+        &disk,
+        number,
+        target / layout.sector_size,
+        range.size() / layout.sector_size,
+        true,
+    )?;
+    Ok(target)
+}
+
+/// Rewrite the table entry of partition `number` of `disk` to start at
+/// sector `start` and span `sectors`; with `move_data`, sfdisk moves the
+/// contents along
+pub(crate) fn set_partition_start(
+    disk: &str,
+    number: u32,
+    start: u64,
+    sectors: u64,
+    move_data: bool,
+) -> Result<()> {
+    let number = number.to_string();
+    let mut args = vec!["-N", &number, disk];
+    if move_data {
+        args.insert(0, "--move-data");
+    }
+    let mut child = Command::new("sfdisk")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute sfdisk: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "{},{}", start, sectors)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "Failed to move the partition: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! systemd automount units in /etc/systemd/system
+//!
+//! Only units starting with the marker written by
+//! [`AutomountUnit::mount_unit`] are listed or removed, so units written by
+//! hand or by packages are never touched.
+
+use crate::error::{Result, SysError};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use storage_types::{AUTOMOUNT_MARKER, AutomountUnit, escape_unit_path};
+use tracing::info;
+
+const UNIT_DIR: &str = "/etc/systemd/system";
+
+/// Automount units this tool created
+pub fn list_automount_units() -> Result<Vec<AutomountUnit>> {
+    let entries = match std::fs::read_dir(UNIT_DIR) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut units = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "automount") {
+            continue;
+        }
+        let (Ok(automount), Ok(mount)) = (
+            std::fs::read_to_string(&path),
+            std::fs::read_to_string(path.with_extension("mount")),
+        ) else {
+            continue;
+        };
+        if let Some(unit) = AutomountUnit::parse(&mount, &automount) {
+            units.push(unit);
+        }
+    }
+    units.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    Ok(units)
+}
+
+/// Write the units of `unit` and start mounting on access
+///
+/// Units already at that mount point are replaced only when this tool
+/// wrote them; the mount point is created when missing.
+pub fn create_automount_unit(unit: &AutomountUnit) -> Result<()> {
+    unit.validate().map_err(SysError::OperationFailed)?;
+    let (mount_path, automount_path) = unit_paths(&unit.mount_point);
+    for path in [&mount_path, &automount_path] {
+        if path.exists() && !is_ours(path) {
+            return Err(SysError::OperationFailed(format!(
+                "{} exists and was not created by this tool",
+                path.display()
+            )));
+        }
+    }
+
+    std::fs::create_dir_all(&unit.mount_point)?;
+    info!(
+        "Writing automount units for {} at {}",
+        unit.what,
+        unit.mount_point.display()
+    );
+    std::fs::write(&mount_path, unit.mount_unit())?;
+    std::fs::write(&automount_path, unit.automount_unit())?;
+
+    let automount = unit_file_name(&automount_path);
+    systemctl(&["daemon-reload"])?;
+    // A mount left over from the units being replaced would keep the old
+    // options; the automount mounts it again on the next access
+    let _ = systemctl(&["stop", &unit_file_name(&mount_path)]);
+    systemctl(&["enable", "--now", &automount])?;
+    Ok(())
+}
+
+/// Stop and remove the units this tool created for `mount_point`
+pub fn remove_automount_unit(mount_point: &Path) -> Result<()> {
+    let (mount_path, automount_path) = unit_paths(mount_point);
+    if !is_ours(&automount_path) {
+        return Err(SysError::OperationFailed(format!(
+            "No automount unit created by this tool at {}",
+            mount_point.display()
+        )));
+    }
+
+    info!("Removing automount units of {}", mount_point.display());
+    systemctl(&["disable", "--now", &unit_file_name(&automount_path)])?;
+    systemctl(&["stop", &unit_file_name(&mount_path)])?;
+    std::fs::remove_file(&automount_path)?;
+    if is_ours(&mount_path) {
+        std::fs::remove_file(&mount_path)?;
+    }
+    systemctl(&["daemon-reload"])?;
+    Ok(())
+}
+
+fn unit_paths(mount_point: &Path) -> (PathBuf, PathBuf) {
+    let name = escape_unit_path(mount_point);
+    let dir = Path::new(UNIT_DIR);
+    (
+        dir.join(format!("{name}.mount")),
+        dir.join(format!("{name}.automount")),
+    )
+}
+
+fn unit_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn is_ours(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|unit| unit.starts_with(AUTOMOUNT_MARKER))
+}
+
+fn systemctl(args: &[&str]) -> Result<()> {
+    let output = Command::new("systemctl")
+        .args(args)
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to run systemctl: {e}")))?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "systemctl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
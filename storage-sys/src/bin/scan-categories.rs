@@ -11,6 +11,7 @@ use storage_sys::usage::{
     ScanConfig, compute_progress_percent, discover_local_mounts_under,
     estimate_used_bytes_for_mounts, format_bytes, scan_paths, scan_paths_with_progress,
 };
+use storage_types::{ByteFormat, ByteUnits, set_byte_format};
 
 #[derive(Debug, Parser)]
 #[command(name = "scan-categories")]
@@ -27,10 +28,18 @@ struct Args {
 
     #[arg(long, default_value_t = 20)]
     top_files_per_category: usize,
+
+    /// Units for sizes: si (GB) or iec (GiB)
+    #[arg(long, default_value_t = ByteUnits::Iec)]
+    units: ByteUnits,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    let locale = std::env::var("LC_NUMERIC")
+        .or_else(|_| std::env::var("LANG"))
+        .unwrap_or_default();
+    set_byte_format(ByteFormat::new(args.units, &locale));
     let config = ScanConfig {
         threads: args.threads,
         top_files_per_category: args.top_files_per_category,
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Bind mounts and their fstab entries
+//!
+//! Bind mounts have no block device, so udisks cannot configure them; their
+//! fstab entries are written here directly.
+
+use crate::error::{Result, SysError};
+use crate::migration::run;
+use std::path::Path;
+use storage_types::{BindMount, fstab_bind_line, parse_bind_mounts, remove_fstab_bind};
+use tracing::info;
+
+const FSTAB_PATH: &str = "/etc/fstab";
+
+/// Mounted bind mounts and those configured in fstab
+pub fn list_bind_mounts() -> Result<Vec<BindMount>> {
+    let mountinfo = std::fs::read_to_string("/proc/self/mountinfo")?;
+    let fstab = read_fstab()?;
+    Ok(parse_bind_mounts(&mountinfo, &fstab))
+}
+
+/// Bind `source` to `target`, creating `target` when missing
+///
+/// A read-only bind is remounted read-only after binding, as older kernels
+/// ignore "ro" on the bind itself. With `persistent`, an fstab entry mounts
+/// it again at startup.
+pub fn create_bind_mount(
+    source: &Path,
+    target: &Path,
+    read_only: bool,
+    persistent: bool,
+) -> Result<()> {
+    if !source.is_dir() {
+        return Err(SysError::OperationFailed(format!(
+            "{} is not a directory",
+            source.display()
+        )));
+    }
+    let fstab = read_fstab()?;
+    if persistent && remove_fstab_bind(&fstab, target).is_some() {
+        return Err(SysError::OperationFailed(format!(
+            "fstab already has a bind mount at {}",
+            target.display()
+        )));
+    }
+    let (source_arg, target_arg) = (path_arg(source)?, path_arg(target)?);
+    std::fs::create_dir_all(target)?;
+
+    info!(
+        "Binding {} to {}{}",
+        source.display(),
+        target.display(),
+        if read_only { " read-only" } else { "" }
+    );
+    run("mount", &["--bind", source_arg, target_arg], None)?;
+    if read_only && let Err(e) = run("mount", &["-o", "remount,bind,ro", target_arg], None) {
+        // Never leave a writable bind behind when a read-only one was asked for
+        let _ = run("umount", &[target_arg], None);
+        return Err(e);
+    }
+
+    if persistent {
+        let mut fstab = fstab;
+        if !fstab.is_empty() && !fstab.ends_with('\n') {
+            fstab.push('\n');
+        }
+        fstab.push_str(&fstab_bind_line(source, target, read_only));
+        fstab.push('\n');
+        write_fstab(&fstab)?;
+    }
+    Ok(())
+}
+
+/// Unmount the bind mount at `target` if mounted, and with `forget` remove
+/// its fstab entry
+pub fn remove_bind_mount(target: &Path, forget: bool) -> Result<()> {
+    let bind = list_bind_mounts()?
+        .into_iter()
+        .find(|bind| bind.target == target)
+        .ok_or_else(|| {
+            SysError::OperationFailed(format!("No bind mount at {}", target.display()))
+        })?;
+
+    if bind.mounted {
+        info!("Unmounting bind mount {}", target.display());
+        run("umount", &[path_arg(target)?], None)?;
+    }
+    if forget
+        && bind.persistent
+        && let Some(fstab) = remove_fstab_bind(&read_fstab()?, target)
+    {
+        info!("Removing the fstab entry of {}", target.display());
+        write_fstab(&fstab)?;
+    }
+    Ok(())
+}
+
+fn read_fstab() -> Result<String> {
+    match std::fs::read_to_string(FSTAB_PATH) {
+        Ok(fstab) => Ok(fstab),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_fstab(fstab: &str) -> Result<()> {
+    // Write atomically; a truncated fstab can break startup
+    let path = Path::new(FSTAB_PATH);
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, fstab)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// `path` as a command argument; mount(8) takes paths that aren't UTF-8,
+/// but `run` doesn't
+fn path_arg(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| SysError::OperationFailed(format!("{} is not valid UTF-8", path.display())))
+}
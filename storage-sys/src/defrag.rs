@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Filesystem defragmentation
+//!
+//! Per-filesystem backends for fragmentation scoring and online
+//! defragmentation of a mounted filesystem or one of its directories:
+//!
+//! | Filesystem | Score              | Defragment                         |
+//! |------------|--------------------|------------------------------------|
+//! | ext2/3/4   | `e4defrag -c`      | `e4defrag`                         |
+//! | XFS        | `xfs_db -r -c frag`| `xfs_fsr -v`                       |
+//! | Btrfs      | (none)             | `btrfs filesystem defragment -r -v`|
+
+use crate::error::{Result, SysError};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+use storage_types::{DefragResult, FragmentationReport};
+use tracing::debug;
+
+/// Whether a defragmentation backend exists for `fs_type`
+pub fn defrag_supported(fs_type: &str) -> bool {
+    matches!(fs_type, "ext2" | "ext3" | "ext4" | "xfs" | "btrfs")
+}
+
+/// Estimate fragmentation of `target` (a mounted path) on `device`.
+///
+/// XFS can only be scored for the whole filesystem; btrfs has no scorer and
+/// returns a report without a score.
+pub fn fragmentation_report(
+    fs_type: &str,
+    device: &str,
+    target: &Path,
+) -> Result<FragmentationReport> {
+    let mut report = FragmentationReport {
+        fs_type: fs_type.to_string(),
+        target: target.to_string_lossy().into_owned(),
+        ..Default::default()
+    };
+
+    match fs_type {
+        "ext2" | "ext3" | "ext4" => {
+            let output = run_tool("e4defrag", &["-c".as_ref(), target.as_os_str()])?;
+            report.score_percent = parse_e4defrag_score(&output);
+            report.summary = output
+                .lines()
+                .find(|line| line.contains("Fragmentation score"))
+                .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default();
+        }
+        "xfs" => {
+            let output = run_tool(
+                "xfs_db",
+                &[
+                    "-r".as_ref(),
+                    "-c".as_ref(),
+                    "frag".as_ref(),
+                    device.as_ref(),
+                ],
+            )?;
+            report.score_percent = parse_xfs_frag_factor(&output);
+            report.summary = output.trim().to_string();
+        }
+        "btrfs" => {}
+        _ => {
+            return Err(SysError::OperationFailed(format!(
+                "Defragmentation is not supported for {}",
+                fs_type
+            )));
+        }
+    }
+
+    Ok(report)
+}
+
+/// Defragment `target` (a mounted filesystem root or directory).
+///
+/// Progress is reported as `(files_processed, files_total)`; the total is 0
+/// when the tool does not announce it up front (btrfs, XFS).
+pub fn defragment(
+    fs_type: &str,
+    target: &Path,
+    progress: Option<Sender<(u64, u64)>>,
+) -> Result<DefragResult> {
+    let (program, args): (&str, Vec<&std::ffi::OsStr>) = match fs_type {
+        "ext2" | "ext3" | "ext4" => ("e4defrag", vec![target.as_os_str()]),
+        "xfs" => ("xfs_fsr", vec!["-v".as_ref(), target.as_os_str()]),
+        "btrfs" => (
+            "btrfs",
+            vec![
+                "filesystem".as_ref(),
+                "defragment".as_ref(),
+                "-r".as_ref(),
+                "-v".as_ref(),
+                target.as_os_str(),
+            ],
+        ),
+        _ => {
+            return Err(SysError::OperationFailed(format!(
+                "Defragmentation is not supported for {}",
+                fs_type
+            )));
+        }
+    };
+
+    debug!("Running {} {:?}", program, args);
+    let mut child = Command::new(program)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+
+    let mut files_processed = 0_u64;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+            let Some((processed, total)) = parse_progress_line(fs_type, &line, files_processed)
+            else {
+                continue;
+            };
+            files_processed = processed;
+            if let Some(tx) = &progress {
+                let _ = tx.send((processed, total));
+            }
+        }
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(DefragResult {
+        fs_type: fs_type.to_string(),
+        target: target.to_string_lossy().into_owned(),
+        files_processed,
+        score_after: None,
+    })
+}
+
+fn run_tool(program: &str, args: &[&std::ffi::OsStr]) -> Result<String> {
+    debug!("Running {} {:?}", program, args);
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `Fragmentation score  N` from `e4defrag -c`
+fn parse_e4defrag_score(output: &str) -> Option<u32> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Fragmentation score"))
+        .and_then(|rest| rest.trim().parse().ok())
+}
+
+/// `fragmentation factor N.NN%` from `xfs_db -c frag`, rounded
+fn parse_xfs_frag_factor(output: &str) -> Option<u32> {
+    let (_, rest) = output.split_once("fragmentation factor")?;
+    let factor: f64 = rest
+        .split_whitespace()
+        .next()?
+        .trim_end_matches('%')
+        .parse()
+        .ok()?;
+    Some(factor.round().clamp(0.0, 100.0) as u32)
+}
+
+/// Progress from one line of defrag tool output
+fn parse_progress_line(fs_type: &str, line: &str, processed: u64) -> Option<(u64, u64)> {
+    let line = line.trim();
+    match fs_type {
+        // "[12/345]/mnt/file:	100%	[ OK ]"
+        "ext2" | "ext3" | "ext4" => {
+            let (counts, _) = line.strip_prefix('[')?.split_once(']')?;
+            let (done, total) = counts.split_once('/')?;
+            Some((done.trim().parse().ok()?, total.trim().parse().ok()?))
+        }
+        // "ino=1234" per file
+        "xfs" => line.contains("ino=").then_some((processed + 1, 0)),
+        // One path per file
+        _ => (!line.is_empty()).then_some((processed + 1, 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fragmentation_scores() {
+        let e4defrag = " Total/best extents\t\t\t\t1234/1200\n Average size per extent\t\t\t1234 KB\n Fragmentation score\t\t\t\t42\n [0-30 no problem: 31-55 a little bit fragmented: 56- needs defrag]\n";
+        assert_eq!(parse_e4defrag_score(e4defrag), Some(42));
+
+        let xfs_db = "actual 3345, ideal 3210, fragmentation factor 4.04%\nNote, this number is largely meaningless.\n";
+        assert_eq!(parse_xfs_frag_factor(xfs_db), Some(4));
+    }
+
+    #[test]
+    fn parses_progress_lines() {
+        assert_eq!(
+            parse_progress_line("ext4", "[12/345]/mnt/file:\t100%\t[ OK ]", 0),
+            Some((12, 345))
+        );
+        assert_eq!(
+            parse_progress_line("ext4", "ext4 defragmentation for directory(/mnt)", 0),
+            None
+        );
+        assert_eq!(parse_progress_line("xfs", "ino=1234", 3), Some((4, 0)));
+        assert_eq!(parse_progress_line("btrfs", "/mnt/a/b", 9), Some((10, 0)));
+    }
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Sources of diagnostic reports
+//!
+//! Each function returns the raw output of one system tool. The output
+//! still contains identifying details; it is redacted before it leaves
+//! the service.
+
+use crate::error::{Result, SysError};
+use std::process::Command;
+use tracing::debug;
+
+/// Systemd unit of the storage service, whose log is included in reports
+pub const SERVICE_UNIT: &str = "cosmic-ext-storage-service.service";
+
+/// All block devices with all lsblk columns, as JSON
+pub fn device_layout() -> Result<String> {
+    run_tool("lsblk", &["--json", "--output-all", "--bytes"])
+}
+
+/// What UDisks knows about `device`: its block, partition and filesystem
+/// objects and its drive
+pub fn udisks_properties(device: &str) -> Result<String> {
+    run_tool("udisksctl", &["info", "--block-device", device])
+}
+
+/// The last `lines` messages of the kernel and of UDisks from the system
+/// journal
+pub fn recent_journal(lines: usize) -> Result<String> {
+    let lines = lines.to_string();
+    run_tool(
+        "journalctl",
+        &[
+            "--no-pager",
+            "--output",
+            "short-iso",
+            "--lines",
+            &lines,
+            "_TRANSPORT=kernel",
+            "+",
+            "_COMM=udisksd",
+        ],
+    )
+}
+
+/// Messages of the systemd unit `unit` since the Unix time `since`
+pub fn unit_log_since(unit: &str, since: u64) -> Result<String> {
+    let since = format!("@{since}");
+    run_tool(
+        "journalctl",
+        &[
+            "--no-pager",
+            "--output",
+            "short-iso",
+            "--unit",
+            unit,
+            "--since",
+            &since,
+        ],
+    )
+}
+
+/// Name of this machine
+pub fn hostname() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn run_tool(program: &str, args: &[&str]) -> Result<String> {
+    debug!("Running {} {:?}", program, args);
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
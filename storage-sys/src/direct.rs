@@ -0,0 +1,698 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Storage backend without UDisks
+//!
+//! [`DirectBackend`] implements the discovery, partitioning and imaging
+//! contracts with the tools UDisks uses underneath: block devices are
+//! listed with lsblk, partition tables are edited with sfdisk (GPT entries
+//! with [`crate::gpt_native`]), filesystems are made with mkfs, and images are read and written through the device
+//! nodes and attached with losetup. It serves systems without UDisks2, such
+//! as minimal containers and some immutable distributions.
+//!
+//! Device paths double as block paths, so
+//! [`PartitionOpsAdapter::resolve_block_path_for_device`] only checks that
+//! the device exists. Encrypted partitions and erasing while creating need
+//! UDisks.
+
+use crate::error::{Result, SysError};
+use crate::gpt_native;
+use crate::lost_partition::partition_layout;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::io::Write;
+use std::os::fd::OwnedFd;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_contracts::{
+    DiskDiscovery, ImageOpsAdapter, PartitionOpsAdapter, Partitioning, StorageError,
+    StorageErrorKind,
+};
+use storage_types::{CreatePartitionInfo, DiskInfo, GptEdit, PartitionInfo, place_partition};
+use tracing::{info, warn};
+
+/// Columns read from lsblk
+const LSBLK_COLUMNS: &str = "NAME,PATH,TYPE,SIZE,MODEL,SERIAL,VENDOR,REV,TRAN,RM,RO,PTTYPE,\
+                             LOG-SEC,PHY-SEC,ROTA,DISC-MAX,PARTN,START,PARTTYPE,PARTTYPENAME,PARTFLAGS,\
+                             PARTLABEL,PARTUUID,FSTYPE,MOUNTPOINTS";
+
+/// DOS partition flag of the bootable ("active") partition, as UDisks
+/// reports it
+const DOS_BOOTABLE: u64 = 0x80;
+
+/// Partitioning and imaging through lsblk, sfdisk, mkfs and losetup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectBackend;
+
+impl From<SysError> for StorageError {
+    fn from(err: SysError) -> Self {
+        let kind = match &err {
+            SysError::PermissionDenied(_) => StorageErrorKind::PermissionDenied,
+            SysError::DeviceNotFound(_) => StorageErrorKind::NotFound,
+            _ => StorageErrorKind::Internal,
+        };
+        StorageError::new(kind, err.to_string())
+    }
+}
+
+/// Run blocking work off the async runtime
+async fn blocking<T, F>(work: F) -> std::result::Result<T, StorageError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| StorageError::new(StorageErrorKind::Internal, e.to_string()))?
+        .map_err(StorageError::from)
+}
+
+fn unsupported<T>(message: &str) -> std::result::Result<T, StorageError> {
+    Err(StorageError::new(StorageErrorKind::Unsupported, message))
+}
+
+/// Run `program`, feeding it `input`; returns its output
+fn run(program: &str, args: &[&str], input: Option<&str>) -> Result<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Wait for udev to create or remove the device nodes of a change
+fn settle() {
+    if let Err(e) = run("udevadm", &["settle"], None) {
+        warn!("{e}");
+    }
+}
+
+fn list_devices() -> Result<Vec<(DiskInfo, Vec<PartitionInfo>)>> {
+    let json = run(
+        "lsblk",
+        &["--json", "--bytes", "--tree", "--output", LSBLK_COLUMNS],
+        None,
+    )?;
+    let mut disks = disks_from_lsblk(&json).map_err(SysError::OperationFailed)?;
+
+    for (disk, _) in &mut disks {
+        if disk.partition_table_type.as_deref() == Some("gpt") {
+            disk.gpt_usable_range = partition_layout(&disk.device).ok().map(|l| l.usable);
+        }
+        if disk.is_loop {
+            disk.backing_file =
+                std::fs::read_to_string(format!("/sys/class/block/{}/loop/backing_file", disk.id))
+                    .ok()
+                    .map(|file| file.trim().to_string());
+        }
+    }
+    Ok(disks)
+}
+
+/// Disks and loop devices with their partitions, from
+/// `lsblk --json --bytes --tree --output <LSBLK_COLUMNS>`
+pub fn disks_from_lsblk(
+    json: &str,
+) -> std::result::Result<Vec<(DiskInfo, Vec<PartitionInfo>)>, String> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid lsblk output: {e}"))?;
+    let devices = value["blockdevices"]
+        .as_array()
+        .ok_or("lsblk output without block devices")?;
+
+    let mut disks = Vec::new();
+    for device in devices {
+        let kind = text(&device["type"]);
+        if !matches!(kind.as_str(), "disk" | "loop" | "rom") {
+            continue;
+        }
+        let is_loop = kind == "loop";
+        let size = number(&device["size"]);
+        if is_loop && size == 0 {
+            // Unattached loop device
+            continue;
+        }
+        let removable = flag(&device["rm"]);
+        let table_type = Some(text(&device["pttype"])).filter(|t| !t.is_empty());
+        let disk = DiskInfo {
+            device: text(&device["path"]),
+            id: text(&device["name"]),
+            model: text(&device["model"]),
+            serial: text(&device["serial"]),
+            vendor: text(&device["vendor"]),
+            revision: text(&device["rev"]),
+            size,
+            connection_bus: if is_loop {
+                "loop".to_string()
+            } else {
+                text(&device["tran"])
+            },
+            rotation_rate: None,
+            logical_sector_size: sector_size(&device["log-sec"]),
+            physical_sector_size: sector_size(&device["phy-sec"]),
+            removable,
+            ejectable: removable,
+            media_removable: removable,
+            media_available: size > 0,
+            optical: kind == "rom",
+            optical_blank: false,
+            read_only: flag(&device["ro"]),
+            can_power_off: false,
+            virtual_disk: !is_loop
+                && storage_types::is_virtual_disk(
+                    None,
+                    &text(&device["vendor"]),
+                    &text(&device["model"]),
+                ),
+            discard_supported: number(&device["disc-max"]) > 0,
+            rotational: flag(&device["rota"]),
+            is_loop,
+            backing_file: None,
+            partition_table_type: table_type.clone(),
+            gpt_usable_range: None,
+        };
+
+        let partitions = device["children"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|child| text(&child["type"]) == "part")
+            .map(|child| {
+                let name = text(&child["name"]);
+                let fs_type = Some(text(&child["fstype"])).filter(|t| !t.is_empty());
+                PartitionInfo {
+                    device: text(&child["path"]),
+                    number: match number(&child["partn"]) {
+                        0 => trailing_number(&name),
+                        n => n as u32,
+                    },
+                    parent_path: disk.device.clone(),
+                    size: number(&child["size"]),
+                    // Partition starts are in 512-byte units whatever the
+                    // sector size
+                    offset: number(&child["start"]) * 512,
+                    type_id: text(&child["parttype"]),
+                    type_name: text(&child["parttypename"]),
+                    flags: hex(&child["partflags"]),
+                    name: text(&child["partlabel"]),
+                    uuid: text(&child["partuuid"]),
+                    table_type: table_type.clone().unwrap_or_default(),
+                    has_filesystem: fs_type.is_some(),
+                    filesystem_type: fs_type,
+                    mount_points: mount_points(child),
+                    usage: None,
+                }
+            })
+            .collect();
+        disks.push((disk, partitions));
+    }
+    Ok(disks)
+}
+
+/// A string column; lsblk writes null for missing values
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// A numeric column, written as a number or a string depending on the
+/// lsblk version
+fn number(value: &Value) -> u64 {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+        .unwrap_or(0)
+}
+
+/// A sector size column, 512 bytes when missing
+fn sector_size(value: &Value) -> u64 {
+    match number(value) {
+        0 => storage_types::alignment::DEFAULT_SECTOR_SIZE,
+        size => size,
+    }
+}
+
+/// A boolean column, written as true/false or "0"/"1" depending on the
+/// lsblk version
+fn flag(value: &Value) -> bool {
+    value.as_bool().unwrap_or_else(|| number(value) != 0)
+}
+
+/// A hexadecimal column such as "0x8000000000000000"
+fn hex(value: &Value) -> u64 {
+    let text = text(value);
+    u64::from_str_radix(text.trim_start_matches("0x"), 16).unwrap_or(0)
+}
+
+fn mount_points(device: &Value) -> Vec<String> {
+    match &device["mountpoints"] {
+        Value::Array(points) => points
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => device["mountpoint"]
+            .as_str()
+            .map(|point| vec![point.to_string()])
+            .unwrap_or_default(),
+    }
+}
+
+/// Number at the end of a partition name, e.g. 2 for "sda2" or "nvme0n1p2"
+fn trailing_number(name: &str) -> u32 {
+    let digits = name.len() - name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    name[name.len() - digits..].parse().unwrap_or(0)
+}
+
+/// The disk holding `partition` and the partition's number, from sysfs
+pub(crate) fn partition_location(partition: &str) -> Result<(String, u32)> {
+    let name = partition.strip_prefix("/dev/").unwrap_or(partition);
+    let sys = Path::new("/sys/class/block").join(name);
+    let number = std::fs::read_to_string(sys.join("partition"))
+        .map_err(|_| SysError::DeviceNotFound(format!("{} is not a partition", partition)))?
+        .trim()
+        .parse()
+        .map_err(|_| SysError::OperationFailed(format!("Bad partition number of {}", partition)))?;
+    let disk = std::fs::canonicalize(&sys)?
+        .parent()
+        .and_then(Path::file_name)
+        .map(|disk| format!("/dev/{}", disk.to_string_lossy()))
+        .ok_or_else(|| SysError::DeviceNotFound(format!("Disk of {}", partition)))?;
+    Ok((disk, number))
+}
+
+/// sfdisk type of a GPT GUID or DOS code such as "0x83"
+fn sfdisk_type(type_id: &str) -> &str {
+    type_id.trim_start_matches("0x")
+}
+
+/// Device node of the partition starting at sector `start`, from
+/// `sfdisk --json`
+fn partition_node_at(sfdisk_json: &str, start: u64) -> Option<String> {
+    let value: Value = serde_json::from_str(sfdisk_json).ok()?;
+    value["partitiontable"]["partitions"]
+        .as_array()?
+        .iter()
+        .find(|partition| partition["start"].as_u64() == Some(start))
+        .and_then(|partition| partition["node"].as_str())
+        .map(str::to_string)
+}
+
+/// Numbers of the bootable partitions in `sfdisk --json`
+fn bootable_partitions(sfdisk_json: &str) -> Vec<u32> {
+    let Ok(value) = serde_json::from_str::<Value>(sfdisk_json) else {
+        return Vec::new();
+    };
+    value["partitiontable"]["partitions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|partition| partition["bootable"].as_bool() == Some(true))
+        .filter_map(|partition| partition["node"].as_str())
+        .map(trailing_number)
+        .collect()
+}
+
+fn create_table(disk: &str, table_type: &str) -> Result<()> {
+    info!("Creating {} partition table on {}", table_type, disk);
+    run(
+        "sfdisk",
+        &["--wipe", "always", disk],
+        Some(&format!("label: {table_type}\n")),
+    )?;
+    settle();
+    Ok(())
+}
+
+fn create(disk: &str, offset: u64, size: u64, type_id: &str) -> Result<String> {
+    // Align and trim the request the way UDisks does, rather than leaving
+    // sfdisk to fail on overlaps or the end of the table
+    let (disk_info, partitions) = list_devices()?
+        .into_iter()
+        .find(|(info, _)| info.device == disk)
+        .ok_or_else(|| SysError::DeviceNotFound(disk.to_string()))?;
+    let placed = place_partition(&disk_info, &partitions, offset, size)
+        .map_err(SysError::OperationFailed)?;
+    let sector_size = partition_layout(disk)?.sector_size;
+    let start = placed.start / sector_size;
+    let sectors = (placed.end - placed.start) / sector_size;
+    info!(
+        "Creating partition on {} at sector {} ({} sectors)",
+        disk, start, sectors
+    );
+    run(
+        "sfdisk",
+        &["--append", "--wipe-partitions", "always", disk],
+        Some(&format!(
+            "start={start}, size={sectors}, type={}\n",
+            sfdisk_type(type_id)
+        )),
+    )?;
+    settle();
+
+    let json = run("sfdisk", &["--json", disk], None)?;
+    partition_node_at(&json, start).ok_or_else(|| {
+        SysError::OperationFailed(format!("The new partition on {} was not found", disk))
+    })
+}
+
+fn make_filesystem(device: &str, fs_type: &str, label: &str) -> Result<()> {
+    let mut args: Vec<&str> = Vec::new();
+    match fs_type {
+        // Skip zeroing the whole partition
+        "ntfs" => args.push("--quick"),
+        "xfs" | "btrfs" => args.push("-f"),
+        _ => {}
+    }
+    if !label.is_empty() {
+        args.push(if fs_type == "vfat" { "-n" } else { "-L" });
+        args.push(label);
+    }
+    args.push(device);
+    info!("Making {} filesystem on {}", fs_type, device);
+    run(&format!("mkfs.{fs_type}"), &args, None)?;
+    settle();
+    Ok(())
+}
+
+fn set_flags(partition: &str, flags: u64) -> Result<()> {
+    let (disk, number) = partition_location(partition)?;
+    if is_gpt(&disk)? {
+        return gpt_native::edit_disk(&disk, number, &GptEdit::Attributes(flags));
+    }
+
+    // DOS tables have one bootable partition; --activate switches off the
+    // ones not listed
+    let mut bootable = bootable_partitions(&run("sfdisk", &["--json", &disk], None)?);
+    bootable.retain(|n| *n != number);
+    if flags & DOS_BOOTABLE != 0 {
+        bootable.push(number);
+    }
+    let numbers: Vec<String> = bootable.iter().map(u32::to_string).collect();
+    let mut args = vec!["--activate", disk.as_str()];
+    if numbers.is_empty() {
+        args.push("-");
+    } else {
+        args.extend(numbers.iter().map(String::as_str));
+    }
+    run("sfdisk", &args, None)?;
+    Ok(())
+}
+
+fn is_gpt(disk: &str) -> Result<bool> {
+    Ok(partition_layout(disk)?.table_type.as_deref() == Some("gpt"))
+}
+
+fn set_type(partition: &str, type_id: &str) -> Result<()> {
+    let (disk, number) = partition_location(partition)?;
+    if is_gpt(&disk)? {
+        return gpt_native::edit_disk(&disk, number, &GptEdit::TypeGuid(type_id.to_string()));
+    }
+    edit_partition(&disk, number, "--part-type", Some(sfdisk_type(type_id)))
+}
+
+/// Run `sfdisk <option> <disk> <number> [value]`
+fn edit_partition(disk: &str, number: u32, option: &str, value: Option<&str>) -> Result<()> {
+    let number = number.to_string();
+    let mut args = vec![option, disk, number.as_str()];
+    args.extend(value);
+    run("sfdisk", &args, None)?;
+    settle();
+    Ok(())
+}
+
+#[async_trait]
+impl DiskDiscovery for DirectBackend {
+    async fn list_disks(&self) -> std::result::Result<Vec<DiskInfo>, StorageError> {
+        let disks = blocking(list_devices).await?;
+        Ok(disks.into_iter().map(|(disk, _)| disk).collect())
+    }
+}
+
+#[async_trait]
+impl Partitioning for DirectBackend {
+    async fn list_partitions(
+        &self,
+        disk_path: &str,
+    ) -> std::result::Result<Vec<PartitionInfo>, StorageError> {
+        let disks = blocking(list_devices).await?;
+        disks
+            .into_iter()
+            .find(|(disk, _)| disk.device == disk_path)
+            .map(|(_, partitions)| partitions)
+            .ok_or_else(|| StorageError::new(StorageErrorKind::NotFound, disk_path))
+    }
+}
+
+#[async_trait]
+impl PartitionOpsAdapter for DirectBackend {
+    async fn list_disks_with_partitions(
+        &self,
+    ) -> std::result::Result<Vec<(DiskInfo, Vec<PartitionInfo>)>, StorageError> {
+        blocking(list_devices).await
+    }
+
+    async fn resolve_block_path_for_device(
+        &self,
+        device: &str,
+    ) -> std::result::Result<String, StorageError> {
+        let name = device.strip_prefix("/dev/").unwrap_or(device);
+        if Path::new("/sys/class/block").join(name).exists() {
+            Ok(format!("/dev/{}", name))
+        } else {
+            Err(StorageError::new(StorageErrorKind::NotFound, device))
+        }
+    }
+
+    async fn create_partition_table(
+        &self,
+        block_path: &str,
+        table_type: &str,
+    ) -> std::result::Result<(), StorageError> {
+        let disk = block_path.to_string();
+        let table_type = table_type.to_string();
+        blocking(move || create_table(&disk, &table_type)).await
+    }
+
+    async fn create_partition(
+        &self,
+        block_path: &str,
+        offset: u64,
+        size: u64,
+        type_id: &str,
+    ) -> std::result::Result<String, StorageError> {
+        let disk = block_path.to_string();
+        let type_id = type_id.to_string();
+        blocking(move || create(&disk, offset, size, &type_id)).await
+    }
+
+    async fn create_partition_with_filesystem(
+        &self,
+        block_path: &str,
+        info: &CreatePartitionInfo,
+    ) -> std::result::Result<String, StorageError> {
+        if info.password_protected {
+            return unsupported("Encrypted partitions need UDisks");
+        }
+        if info.erase {
+            return unsupported("Erasing partitions needs UDisks");
+        }
+
+        let partition = self
+            .create_partition(block_path, info.offset, info.size, &info.selected_type)
+            .await?;
+        let fs_type = info.filesystem_type.trim().to_string();
+        if !fs_type.is_empty() {
+            let device = partition.clone();
+            let label = info.name.clone();
+            blocking(move || make_filesystem(&device, &fs_type, &label)).await?;
+        }
+        Ok(partition)
+    }
+
+    async fn delete_partition(
+        &self,
+        partition_path: &str,
+    ) -> std::result::Result<(), StorageError> {
+        let partition = partition_path.to_string();
+        blocking(move || {
+            let (disk, number) = partition_location(&partition)?;
+            edit_partition(&disk, number, "--delete", None)
+        })
+        .await
+    }
+
+    async fn resize_partition(
+        &self,
+        partition_path: &str,
+        new_size: u64,
+    ) -> std::result::Result<(), StorageError> {
+        let partition = partition_path.to_string();
+        blocking(move || {
+            let (disk, number) = partition_location(&partition)?;
+            let sectors = new_size / partition_layout(&disk)?.sector_size;
+            info!("Resizing {} to {} sectors", partition, sectors);
+            // An empty start keeps the partition where it is
+            run(
+                "sfdisk",
+                &["-N", &number.to_string(), &disk],
+                Some(&format!(", {sectors}\n")),
+            )?;
+            settle();
+            Ok(())
+        })
+        .await
+    }
+
+    async fn set_partition_type(
+        &self,
+        partition_path: &str,
+        type_id: &str,
+    ) -> std::result::Result<(), StorageError> {
+        let partition = partition_path.to_string();
+        let type_id = type_id.to_string();
+        blocking(move || set_type(&partition, &type_id)).await
+    }
+
+    async fn set_partition_flags(
+        &self,
+        partition_path: &str,
+        flags: u64,
+    ) -> std::result::Result<(), StorageError> {
+        let partition = partition_path.to_string();
+        blocking(move || set_flags(&partition, flags)).await
+    }
+
+    async fn set_partition_name(
+        &self,
+        partition_path: &str,
+        name: &str,
+    ) -> std::result::Result<(), StorageError> {
+        let partition = partition_path.to_string();
+        let name = name.to_string();
+        // Only GPT entries have names
+        blocking(move || {
+            let (disk, number) = partition_location(&partition)?;
+            gpt_native::edit_disk(&disk, number, &GptEdit::Name(name))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ImageOpsAdapter for DirectBackend {
+    async fn open_for_backup_by_device(
+        &self,
+        device: &str,
+    ) -> std::result::Result<OwnedFd, StorageError> {
+        let device = device.to_string();
+        blocking(move || crate::image::open_for_backup(&device)).await
+    }
+
+    async fn open_for_restore_by_device(
+        &self,
+        device: &str,
+    ) -> std::result::Result<OwnedFd, StorageError> {
+        let device = device.to_string();
+        blocking(move || crate::image::open_for_restore(&device)).await
+    }
+
+    async fn loop_setup_device_path(
+        &self,
+        image_path: &str,
+    ) -> std::result::Result<String, StorageError> {
+        let image = image_path.to_string();
+        blocking(move || {
+            let device = run("losetup", &["--find", "--show", "--partscan", &image], None)?;
+            settle();
+            Ok(device.trim().to_string())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_lsblk_tree() {
+        let json = r#"{"blockdevices": [
+            {"name": "sda", "path": "/dev/sda", "type": "disk", "size": 500107862016,
+             "model": "Samsung SSD 860 ", "serial": "S3Z", "vendor": "ATA     ",
+             "rev": "4B6Q", "tran": "sata", "rm": false, "ro": false, "pttype": "gpt",
+             "log-sec": 512, "phy-sec": 4096,
+             "partn": null, "start": null, "parttype": null, "parttypename": null,
+             "partflags": null, "partlabel": null, "partuuid": null, "fstype": null,
+             "mountpoints": [null],
+             "children": [
+                {"name": "sda1", "path": "/dev/sda1", "type": "part", "size": 536870912,
+                 "rm": false, "ro": false, "partn": 1, "start": 2048,
+                 "parttype": "c12a7328-f81f-11d2-ba4b-00a0c93ec93b",
+                 "parttypename": "EFI System", "partflags": "0x8000000000000000",
+                 "partlabel": "EFI", "partuuid": "1111", "fstype": "vfat",
+                 "mountpoints": ["/boot/efi"]}
+             ]},
+            {"name": "loop0", "path": "/dev/loop0", "type": "loop", "size": "0",
+             "rm": "0", "ro": "0"},
+            {"name": "sr0", "path": "/dev/sr0", "type": "rom", "size": 1073741312,
+             "rm": "1", "ro": "0", "tran": "usb"}
+        ]}"#;
+        let disks = disks_from_lsblk(json).unwrap();
+        assert_eq!(disks.len(), 2);
+
+        let (disk, partitions) = &disks[0];
+        assert_eq!(disk.device, "/dev/sda");
+        assert_eq!(disk.vendor, "ATA");
+        assert_eq!(disk.model, "Samsung SSD 860");
+        assert_eq!(disk.partition_table_type.as_deref(), Some("gpt"));
+        assert!(!disk.removable);
+        assert_eq!(
+            disk.sector_format(),
+            storage_types::SectorFormat::Emulated512
+        );
+
+        let partition = &partitions[0];
+        assert_eq!(partition.number, 1);
+        assert_eq!(partition.offset, 1024 * 1024);
+        assert_eq!(partition.flags, 1 << 63);
+        assert_eq!(partition.table_type, "gpt");
+        assert_eq!(partition.filesystem_type.as_deref(), Some("vfat"));
+        assert_eq!(partition.mount_points, ["/boot/efi"]);
+
+        let (rom, _) = &disks[1];
+        assert!(rom.optical && rom.removable);
+    }
+
+    #[test]
+    fn finds_partitions_in_sfdisk_json() {
+        let json = r#"{"partitiontable": {"label": "dos", "partitions": [
+            {"node": "/dev/sdb1", "start": 2048, "size": 2048, "type": "83", "bootable": true},
+            {"node": "/dev/sdb2", "start": 4096, "size": 2048, "type": "83"}
+        ]}}"#;
+        assert_eq!(partition_node_at(json, 4096).as_deref(), Some("/dev/sdb2"));
+        assert_eq!(partition_node_at(json, 1), None);
+        assert_eq!(bootable_partitions(json), [1]);
+        assert_eq!(trailing_number("nvme0n1p12"), 12);
+    }
+}
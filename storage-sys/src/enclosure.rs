@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Blinking the enclosure slot LED of drives
+//!
+//! SES slots are preferred since sysfs ties them to the drive; `ledctl`
+//! covers the backplanes ledmon knows. Drives with neither cannot be
+//! identified.
+
+use crate::error::{Result, SysError};
+use crate::migration::run;
+use crate::write_cache::program_path;
+use std::path::{Path, PathBuf};
+use storage_types::enclosure::enclosure_slot;
+use storage_types::{IdentifyMethod, IdentifySupport};
+use tracing::info;
+
+/// Enclosure slot directory and name of a whole-disk `device`
+fn ses_slot(device: &str) -> Option<(PathBuf, String)> {
+    let name = device.strip_prefix("/dev/").unwrap_or(device);
+    let device_dir = Path::new("/sys/block").join(name).join("device");
+    std::fs::read_dir(&device_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .find_map(|entry| {
+            let file_name = entry.file_name();
+            let slot = enclosure_slot(&file_name.to_string_lossy())?.to_string();
+            let dir = std::fs::canonicalize(entry.path()).ok()?;
+            dir.join("locate").exists().then_some((dir, slot))
+        })
+}
+
+/// How the slot LED of `device` can be blinked, if at all
+pub fn identify_support(device: &str) -> Option<IdentifySupport> {
+    if let Some((_, slot)) = ses_slot(device) {
+        return Some(IdentifySupport {
+            method: IdentifyMethod::Ses,
+            slot: Some(slot),
+        });
+    }
+    program_path("ledctl").map(|_| IdentifySupport {
+        method: IdentifyMethod::Ledctl,
+        slot: None,
+    })
+}
+
+/// Start or stop blinking the slot LED of `device`
+pub fn set_locate(device: &str, on: bool) -> Result<()> {
+    let support = identify_support(device).ok_or_else(|| {
+        SysError::OperationFailed(format!(
+            "{} is not in an enclosure with slot LEDs, and ledctl is not installed",
+            device
+        ))
+    })?;
+
+    match support.method {
+        IdentifyMethod::Ses => {
+            let (dir, slot) =
+                ses_slot(device).ok_or_else(|| SysError::DeviceNotFound(device.to_string()))?;
+            std::fs::write(dir.join("locate"), if on { "1" } else { "0" })?;
+            info!(
+                "Turned the locate LED of {} ({}) {}",
+                device,
+                slot,
+                if on { "on" } else { "off" }
+            );
+        }
+        IdentifyMethod::Ledctl => {
+            let pattern = if on { "locate" } else { "locate_off" };
+            run("ledctl", &[&format!("{pattern}={device}")], None)?;
+            info!("Set the ledctl pattern of {} to {}", device, pattern);
+        }
+    }
+    Ok(())
+}
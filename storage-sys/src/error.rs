@@ -31,6 +31,12 @@ pub enum SysError {
     )]
     RCloneConfigParse(String),
 
+    #[error("RClone configuration {0} is encrypted; unlock it with its password first")]
+    RCloneConfigLocked(String),
+
+    #[error("Incorrect password for the encrypted RClone configuration")]
+    RCloneConfigPassword,
+
     #[error("RClone remote not found: {0}")]
     RCloneRemoteNotFound(String),
 
@@ -43,6 +49,12 @@ pub enum SysError {
     #[error("RClone test failed: {0}")]
     RCloneTestFailed(String),
 
+    #[error("RClone transfer failed: {0}")]
+    RCloneTransferFailed(String),
+
+    #[error("RClone transfer cancelled")]
+    RCloneTransferCancelled,
+
     #[error("RClone remote already mounted: {0}")]
     RCloneAlreadyMounted(String),
 
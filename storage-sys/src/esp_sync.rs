@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Syncing a secondary EFI System Partition with the primary one
+//!
+//! Both ESPs are used where they are already mounted; otherwise they are
+//! mounted at a temporary directory for the sync, the primary read-only.
+//! Files are written next to their destination and renamed into place, so
+//! an interrupted sync never leaves a half-written bootloader behind.
+
+use crate::error::{Result, SysError};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+use storage_types::EspSyncResult;
+use storage_types::esp::{EspEntry, EspSyncAction, sync_actions};
+use tracing::{debug, info, warn};
+
+/// Where `device` is mounted, from `/proc/mounts`
+fn mount_point(device: &str) -> Option<PathBuf> {
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    mounts.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        (fields.next() == Some(device))
+            .then(|| fields.next())
+            .flatten()
+            // Spaces in mount points are escaped as octal
+            .map(|path| PathBuf::from(path.replace("\\040", " ")))
+    })
+}
+
+/// An ESP mounted for the sync, unmounted again on drop when the sync
+/// mounted it
+struct EspMount {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl EspMount {
+    fn new(device: &str, read_only: bool) -> Result<Self> {
+        if let Some(path) = mount_point(device) {
+            return Ok(Self {
+                path,
+                temporary: false,
+            });
+        }
+        let name = device.rsplit('/').next().unwrap_or(device);
+        let path = std::env::temp_dir().join(format!(
+            "cosmic-ext-storage-esp-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&path)?;
+        let options = if read_only { "ro" } else { "rw" };
+        let output = Command::new("mount")
+            .args(["-o", options, device])
+            .arg(&path)
+            .output()
+            .map_err(|e| SysError::OperationFailed(format!("Failed to execute mount: {}", e)))?;
+        if !output.status.success() {
+            let _ = std::fs::remove_dir(&path);
+            return Err(SysError::OperationFailed(format!(
+                "Failed to mount {}: {}",
+                device,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        debug!("Mounted {} at {}", device, path.display());
+        Ok(Self {
+            path,
+            temporary: true,
+        })
+    }
+}
+
+impl Drop for EspMount {
+    fn drop(&mut self) {
+        if !self.temporary {
+            return;
+        }
+        match Command::new("umount").arg(&self.path).status() {
+            Ok(status) if status.success() => {
+                let _ = std::fs::remove_dir(&self.path);
+            }
+            _ => warn!("Failed to unmount {}", self.path.display()),
+        }
+    }
+}
+
+/// Every file and directory below `root`, by path relative to it
+fn scan(root: &Path) -> Result<BTreeMap<PathBuf, EspEntry>> {
+    let mut entries = BTreeMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let path = entry.path();
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |time| time.as_secs());
+            let relative = path
+                .strip_prefix(root)
+                .map_err(|_| SysError::OperationFailed(format!("{} escaped", path.display())))?
+                .to_path_buf();
+            entries.insert(
+                relative,
+                EspEntry {
+                    is_dir: metadata.is_dir(),
+                    size: metadata.len(),
+                    modified,
+                },
+            );
+            if metadata.is_dir() {
+                pending.push(path);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Copy `from` to `to` through a temporary file beside it, keeping the
+/// modification time so the next sync sees the file as unchanged
+fn copy_file(from: &Path, to: &Path) -> Result<u64> {
+    let name = to.file_name().unwrap_or_default().to_string_lossy();
+    let partial = to.with_file_name(format!(".{}.esp-sync", name));
+    let bytes = std::fs::copy(from, &partial)?;
+    let modified = std::fs::metadata(from)?.modified()?;
+    let file = File::options().write(true).open(&partial)?;
+    file.set_modified(modified)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&partial, to)?;
+    Ok(bytes)
+}
+
+/// Make the ESP on `secondary` a copy of the one on `primary`
+///
+/// Files that differ in size or modification time are copied, and files
+/// the primary no longer has are deleted. The primary is only read.
+pub fn sync_esp(primary: &str, secondary: &str) -> Result<EspSyncResult> {
+    if primary == secondary {
+        return Err(SysError::OperationFailed(
+            "An EFI System Partition cannot be synced with itself".to_string(),
+        ));
+    }
+    let source = EspMount::new(primary, true)?;
+    let target = EspMount::new(secondary, false)?;
+    info!(
+        "Syncing EFI System Partition {} onto {}",
+        primary, secondary
+    );
+
+    let actions = sync_actions(&scan(&source.path)?, &scan(&target.path)?);
+    let mut result = EspSyncResult::default();
+    for action in actions {
+        match action {
+            EspSyncAction::Delete(path) => {
+                let path = target.path.join(path);
+                if path.is_dir() {
+                    std::fs::remove_dir(&path)?;
+                } else {
+                    std::fs::remove_file(&path)?;
+                }
+                result.deleted += 1;
+            }
+            EspSyncAction::CreateDir(path) => std::fs::create_dir(target.path.join(path))?,
+            EspSyncAction::Copy(path) => {
+                result.bytes += copy_file(&source.path.join(&path), &target.path.join(&path))?;
+                result.copied += 1;
+            }
+        }
+    }
+    // Flush the whole filesystem before reporting the secondary as bootable
+    if !Command::new("sync")
+        .arg("-f")
+        .arg(&target.path)
+        .status()
+        .is_ok_and(|status| status.success())
+    {
+        warn!("Failed to flush {}", target.path.display());
+    }
+    Ok(result)
+}
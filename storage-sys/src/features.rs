@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Filesystem feature probing
+//!
+//! Reads on-disk feature flags with each filesystem's own tooling
+//! (`dumpe2fs -h`, `xfs_info`, `btrfs inspect-internal dump-super`) and
+//! condenses them into [`FilesystemFeatures`].
+
+use crate::error::{Result, SysError};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use storage_types::FilesystemFeatures;
+use tracing::debug;
+
+/// XFS geometry keys that are on/off feature flags
+const XFS_FLAG_KEYS: &[&str] = &[
+    "crc",
+    "finobt",
+    "sparse",
+    "rmapbt",
+    "reflink",
+    "bigtime",
+    "inobtcount",
+    "nrext64",
+    "exchange",
+    "parent",
+];
+
+/// Probe the features of the filesystem on `device`.
+///
+/// `mount_point` is used for tools that prefer a mounted path (`xfs_info`)
+/// and to read the active compression mount option.
+pub fn get_filesystem_features(
+    device: &str,
+    fs_type: &str,
+    mount_point: Option<&Path>,
+) -> Result<FilesystemFeatures> {
+    let mut features = FilesystemFeatures {
+        fs_type: fs_type.to_string(),
+        can_shrink: matches!(fs_type, "ext2" | "ext3" | "ext4" | "btrfs" | "ntfs"),
+        can_grow_online: matches!(fs_type, "ext3" | "ext4" | "xfs" | "btrfs"),
+        ..Default::default()
+    };
+
+    match fs_type {
+        "ext2" | "ext3" | "ext4" => {
+            let output = run_tool("dumpe2fs", &["-h", device])?;
+            features.features = parse_ext_features(&output);
+            features.metadata_checksums = features.has("metadata_csum") || features.has("gdt_csum");
+        }
+        "xfs" => {
+            let target = mount_point
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_else(|| device.to_string());
+            let output = run_tool("xfs_info", &[&target])?;
+            features.features = parse_xfs_flags(&output);
+            features.reflink = features.has("reflink");
+            features.metadata_checksums = features.has("crc");
+        }
+        "btrfs" => {
+            let output = run_tool("btrfs", &["inspect-internal", "dump-super", device])?;
+            features.features = parse_btrfs_flags(&output);
+            features.reflink = true;
+            features.metadata_checksums = true;
+            features.compression = ["compress_zstd", "compress_lzo"]
+                .iter()
+                .find(|flag| features.has(flag))
+                .map(|flag| flag.trim_start_matches("compress_").to_string());
+        }
+        _ => {}
+    }
+
+    if let Some(mount_point) = mount_point
+        && let Some(compression) = mounted_compression(mount_point)
+    {
+        features.compression = Some(compression);
+    }
+
+    features.features.sort();
+    features.features.dedup();
+    Ok(features)
+}
+
+fn run_tool(program: &str, args: &[&str]) -> Result<String> {
+    debug!("Running {} {:?}", program, args);
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Feature names from the `Filesystem features:` line of `dumpe2fs -h`
+fn parse_ext_features(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Filesystem features:"))
+        .map(|list| list.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Enabled `key=1` feature flags from `xfs_info` geometry output
+fn parse_xfs_flags(output: &str) -> Vec<String> {
+    output
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|token| token.split_once('='))
+        .filter(|(key, value)| XFS_FLAG_KEYS.contains(key) && *value == "1")
+        .map(|(key, _)| key.to_string())
+        .collect()
+}
+
+/// Lowercased compat_ro/incompat flag names from `btrfs inspect-internal dump-super`
+fn parse_btrfs_flags(output: &str) -> Vec<String> {
+    let mut flags = Vec::new();
+    // Set after a flags header line; the list itself must open with '('
+    let mut expect_list = false;
+    let mut in_list = false;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("compat_ro_flags") || line.starts_with("incompat_flags") {
+            expect_list = true;
+            continue;
+        }
+
+        if expect_list && line.starts_with('(') {
+            in_list = true;
+        }
+        expect_list = false;
+        if !in_list {
+            continue;
+        }
+
+        let names = line.trim_start_matches('(').trim_end_matches(')');
+        flags.extend(
+            names
+                .split('|')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_lowercase),
+        );
+
+        if line.ends_with(')') {
+            in_list = false;
+        }
+    }
+
+    flags
+}
+
+/// Compression algorithm from the `compress=`/`compress-force=` mount option
+fn mounted_compression(mount_point: &Path) -> Option<String> {
+    let mounts = fs::read_to_string("/proc/self/mounts").ok()?;
+    parse_mount_compression(&mounts, &mount_point.to_string_lossy())
+}
+
+fn parse_mount_compression(mounts: &str, mount_point: &str) -> Option<String> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _source = fields.next()?;
+            let target = fields.next()?.replace("\\040", " ");
+            let _fs_type = fields.next()?;
+            let options = fields.next()?;
+            (target == mount_point).then(|| options.to_string())
+        })
+        .next_back()?
+        .split(',')
+        .find_map(|option| {
+            option
+                .strip_prefix("compress=")
+                .or_else(|| option.strip_prefix("compress-force="))
+                .map(str::to_string)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ext_and_xfs_features() {
+        let dumpe2fs = "Filesystem volume name:   root\nFilesystem features:      has_journal ext_attr dir_index extent 64bit metadata_csum\nInode count:              65536\n";
+        assert_eq!(
+            parse_ext_features(dumpe2fs),
+            [
+                "has_journal",
+                "ext_attr",
+                "dir_index",
+                "extent",
+                "64bit",
+                "metadata_csum"
+            ]
+        );
+
+        let xfs_info = "meta-data=/dev/sda1 isize=512 agcount=4, agsize=65536 blks\n         =  sectsz=512 attr=2, projid32bit=1\n         =  crc=1 finobt=1, sparse=1, rmapbt=0\n         =  reflink=1 bigtime=1 inobtcount=1 nrext64=0\n";
+        assert_eq!(
+            parse_xfs_flags(xfs_info),
+            [
+                "crc",
+                "finobt",
+                "sparse",
+                "reflink",
+                "bigtime",
+                "inobtcount"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_btrfs_flags_and_mount_compression() {
+        let dump_super = "compat_flags\t\t0x0\ncompat_ro_flags\t\t0x3\n\t\t\t( FREE_SPACE_TREE |\n\t\t\t  FREE_SPACE_TREE_VALID )\nincompat_flags\t\t0x371\n\t\t\t( MIXED_BACKREF |\n\t\t\t  COMPRESS_ZSTD |\n\t\t\t  NO_HOLES )\ncache_generation\t0\n";
+        assert_eq!(
+            parse_btrfs_flags(dump_super),
+            [
+                "free_space_tree",
+                "free_space_tree_valid",
+                "mixed_backref",
+                "compress_zstd",
+                "no_holes"
+            ]
+        );
+
+        assert!(parse_btrfs_flags("compat_ro_flags\t\t0x0\ncache_generation\t0\n").is_empty());
+
+        let mounts = "/dev/sda2 / btrfs rw,relatime,compress=zstd:3,space_cache=v2 0 0\n/dev/sdb1 /mnt/my\\040disk btrfs rw,compress-force=lzo 0 0\n";
+        assert_eq!(
+            parse_mount_compression(mounts, "/"),
+            Some("zstd:3".to_string())
+        );
+        assert_eq!(
+            parse_mount_compression(mounts, "/mnt/my disk"),
+            Some("lzo".to_string())
+        );
+        assert_eq!(parse_mount_compression(mounts, "/home"), None);
+    }
+}
@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Shrinking and growing filesystems in place
+//!
+//! ext and NTFS filesystems are resized through their device while
+//! unmounted; btrfs and XFS only while mounted, so they are mounted at a
+//! temporary directory when they aren't already. Which filesystem supports
+//! what is decided by `storage_types::resize`.
+
+use crate::error::{Result, SysError};
+use crate::partition_copy::{run_tool, with_temporary_mount};
+use std::path::Path;
+use std::process::Command;
+use storage_types::resize::{
+    parse_btrfs_devid, parse_btrfs_min_dev_size, parse_ext_block_size, parse_ntfsresize_minimum,
+    parse_resize2fs_minimum,
+};
+use tracing::info;
+
+/// Run `program` and return what it printed
+fn tool_output(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn unparsed(program: &str) -> SysError {
+    SysError::OperationFailed(format!("Unexpected output from {}", program))
+}
+
+/// Run `run` with the mount point of the btrfs or XFS filesystem on
+/// `device`, mounting it for the time being when `mount_point` is None
+fn with_mount_point<T>(
+    device: &str,
+    mount_point: Option<&str>,
+    run: impl FnOnce(&str) -> Result<T>,
+) -> Result<T> {
+    match mount_point {
+        Some(mount_point) => run(mount_point),
+        None => with_temporary_mount(device, |mount_point: &Path| {
+            run(&mount_point.to_string_lossy())
+        }),
+    }
+}
+
+/// ID of `device` within its btrfs filesystem, which may span several
+fn btrfs_devid(device: &str) -> Result<u64> {
+    parse_btrfs_devid(&tool_output(
+        "btrfs",
+        &["inspect-internal", "dump-super", device],
+    )?)
+    .ok_or_else(|| unparsed("btrfs inspect-internal dump-super"))
+}
+
+/// Smallest size in bytes the `fs_type` filesystem on `device` can shrink to
+///
+/// `mount_point` is where it is mounted, if it is.
+pub fn filesystem_min_size(device: &str, fs_type: &str, mount_point: Option<&str>) -> Result<u64> {
+    match fs_type {
+        "ext2" | "ext3" | "ext4" => {
+            let blocks = parse_resize2fs_minimum(&tool_output("resize2fs", &["-P", device])?)
+                .ok_or_else(|| unparsed("resize2fs"))?;
+            let block_size = parse_ext_block_size(&tool_output("dumpe2fs", &["-h", device])?)
+                .ok_or_else(|| unparsed("dumpe2fs"))?;
+            Ok(blocks * block_size)
+        }
+        "ntfs" => parse_ntfsresize_minimum(&tool_output(
+            "ntfsresize",
+            &["--info", "--force", "--no-action", device],
+        )?)
+        .ok_or_else(|| unparsed("ntfsresize")),
+        "btrfs" => {
+            let devid = btrfs_devid(device)?.to_string();
+            with_mount_point(device, mount_point, |mount_point| {
+                parse_btrfs_min_dev_size(&tool_output(
+                    "btrfs",
+                    &[
+                        "inspect-internal",
+                        "min-dev-size",
+                        "--id",
+                        &devid,
+                        mount_point,
+                    ],
+                )?)
+                .ok_or_else(|| unparsed("btrfs inspect-internal min-dev-size"))
+            })
+        }
+        _ => Err(SysError::OperationFailed(format!(
+            "{} filesystems cannot be shrunk",
+            fs_type
+        ))),
+    }
+}
+
+/// Shrink the `fs_type` filesystem on `device` to `size` bytes
+///
+/// ext and NTFS filesystems must be unmounted; `mount_point` is where a
+/// btrfs filesystem is mounted, if it is.
+pub fn shrink_filesystem(
+    device: &str,
+    fs_type: &str,
+    size: u64,
+    mount_point: Option<&str>,
+) -> Result<()> {
+    info!(
+        "Shrinking {} filesystem on {} to {} bytes",
+        fs_type, device, size
+    );
+    match fs_type {
+        "ext2" | "ext3" | "ext4" => {
+            // resize2fs refuses to shrink a filesystem not freshly checked
+            run_tool("e2fsck", &["-f", "-y", device], None)?;
+            run_tool("resize2fs", &[device, &format!("{}K", size / 1024)], None)
+        }
+        // ntfsresize asks for confirmation even with --force
+        "ntfs" => run_tool(
+            "ntfsresize",
+            &["--force", "--size", &size.to_string(), device],
+            Some("y\n"),
+        ),
+        "btrfs" => {
+            let target = format!("{}:{}", btrfs_devid(device)?, size);
+            with_mount_point(device, mount_point, |mount_point| {
+                run_tool(
+                    "btrfs",
+                    &["filesystem", "resize", &target, mount_point],
+                    None,
+                )
+            })
+        }
+        _ => Err(SysError::OperationFailed(format!(
+            "{} filesystems cannot be shrunk",
+            fs_type
+        ))),
+    }
+}
+
+/// Grow the `fs_type` filesystem on `device` to fill its partition
+///
+/// ext2 and NTFS filesystems must be unmounted; `mount_point` is where the
+/// filesystem is mounted, if it is.
+pub fn grow_filesystem(device: &str, fs_type: &str, mount_point: Option<&str>) -> Result<()> {
+    info!("Growing {} filesystem on {}", fs_type, device);
+    match fs_type {
+        "ext2" | "ext3" | "ext4" => {
+            if mount_point.is_none() {
+                run_tool("e2fsck", &["-f", "-y", device], None)?;
+            }
+            run_tool("resize2fs", &[device], None)
+        }
+        "ntfs" => run_tool("ntfsresize", &["--force", device], Some("y\n")),
+        "xfs" => with_mount_point(device, mount_point, |mount_point| {
+            run_tool("xfs_growfs", &[mount_point], None)
+        }),
+        "btrfs" => {
+            let target = format!("{}:max", btrfs_devid(device)?);
+            with_mount_point(device, mount_point, |mount_point| {
+                run_tool(
+                    "btrfs",
+                    &["filesystem", "resize", &target, mount_point],
+                    None,
+                )
+            })
+        }
+        _ => Err(SysError::OperationFailed(format!(
+            "{} filesystems cannot be grown",
+            fs_type
+        ))),
+    }
+}
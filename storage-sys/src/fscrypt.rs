@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Directory encryption with fscrypt
+//!
+//! Protectors, policies and keys are handled by the `fscrypt` tool, which
+//! reads passphrases from stdin when it is not a terminal. Encrypted
+//! directories are found with statx, whose encrypted attribute needs no
+//! key and no open of the directory.
+
+use crate::error::{Result, SysError};
+use std::ffi::CString;
+use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_types::{EncryptedDirectory, FscryptStatus, parse_directory_status};
+use tracing::debug;
+
+/// Directory levels below the mount point searched for encrypted
+/// directories, enough for e.g. `/home/<user>/<dir>`
+const SEARCH_DEPTH: usize = 3;
+
+/// Whether the `fscrypt` tool is installed
+pub fn fscrypt_available() -> bool {
+    Command::new("fscrypt")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Encrypted directories on the filesystem mounted at `mount_point`
+pub fn fscrypt_status(mount_point: &Path) -> Result<FscryptStatus> {
+    let available = fscrypt_available();
+    let mut paths = Vec::new();
+    let device = std::fs::metadata(mount_point)?.dev();
+    find_encrypted(mount_point, device, SEARCH_DEPTH, &mut paths);
+
+    let directories = paths
+        .into_iter()
+        .map(|path| {
+            let path = path.to_string_lossy().into_owned();
+            let status = available
+                .then(|| run_fscrypt(&["status".as_ref(), path.as_ref()], None).ok())
+                .flatten()
+                .and_then(|output| parse_directory_status(&path, &output));
+            status.unwrap_or(EncryptedDirectory {
+                path,
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    Ok(FscryptStatus {
+        mount_point: mount_point.to_string_lossy().into_owned(),
+        available,
+        set_up: mount_point.join(".fscrypt").is_dir(),
+        directories,
+    })
+}
+
+/// Create `path` as an empty directory owned by `uid`:`gid` and encrypt it
+/// with a new passphrase protector named `protector_name`
+///
+/// The filesystem is set up for fscrypt first if needed. The directory is
+/// removed again if encrypting it fails.
+pub fn create_encrypted_directory(
+    mount_point: &Path,
+    path: &Path,
+    protector_name: &str,
+    passphrase: &str,
+    uid: u32,
+    gid: u32,
+    user: &str,
+) -> Result<()> {
+    if !Path::new("/etc/fscrypt.conf").exists() {
+        run_fscrypt(&["setup".as_ref(), "--quiet".as_ref()], None)?;
+    }
+    if !mount_point.join(".fscrypt").is_dir() {
+        run_fscrypt(
+            &[
+                "setup".as_ref(),
+                mount_point.as_os_str(),
+                "--quiet".as_ref(),
+            ],
+            None,
+        )?;
+    }
+
+    std::fs::create_dir(path)?;
+    std::os::unix::fs::chown(path, Some(uid), Some(gid))?;
+
+    let name = format!("--name={protector_name}");
+    let user = format!("--user={user}");
+    let encrypted = run_fscrypt(
+        &[
+            "encrypt".as_ref(),
+            path.as_os_str(),
+            "--source=custom_passphrase".as_ref(),
+            name.as_ref(),
+            user.as_ref(),
+            "--quiet".as_ref(),
+        ],
+        Some(passphrase),
+    );
+    if let Err(e) = encrypted {
+        let _ = std::fs::remove_dir(path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Load the key of the encrypted directory `path` for `user`
+pub fn unlock_directory(path: &Path, passphrase: &str, user: &str) -> Result<()> {
+    let user = format!("--user={user}");
+    run_fscrypt(
+        &[
+            "unlock".as_ref(),
+            path.as_os_str(),
+            user.as_ref(),
+            "--quiet".as_ref(),
+        ],
+        Some(passphrase),
+    )
+    .map(drop)
+}
+
+/// Remove the key of the encrypted directory `path`; files still open keep
+/// it partially unlocked until they are closed
+pub fn lock_directory(path: &Path, user: &str) -> Result<()> {
+    let user = format!("--user={user}");
+    run_fscrypt(
+        &[
+            "lock".as_ref(),
+            path.as_os_str(),
+            user.as_ref(),
+            "--quiet".as_ref(),
+        ],
+        None,
+    )
+    .map(drop)
+}
+
+/// Collect encrypted directories below `dir` on the filesystem `device`,
+/// without descending into them or into other mounts
+fn find_encrypted(dir: &Path, device: u64, depth: usize, out: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name();
+        if !file_type.is_dir() || name == ".fscrypt" || name == "lost+found" {
+            continue;
+        }
+        let path = entry.path();
+        if is_encrypted(&path) {
+            out.push(path);
+        } else if depth > 1
+            && entry
+                .metadata()
+                .is_ok_and(|metadata| metadata.dev() == device)
+        {
+            find_encrypted(&path, device, depth - 1, out);
+        }
+    }
+}
+
+pub(crate) fn is_encrypted(path: &Path) -> bool {
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    let mut stx = std::mem::MaybeUninit::<libc::statx>::zeroed();
+    let ret = unsafe {
+        libc::statx(
+            libc::AT_FDCWD,
+            c_path.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW,
+            0,
+            stx.as_mut_ptr(),
+        )
+    };
+    if ret != 0 {
+        return false;
+    }
+    let stx = unsafe { stx.assume_init() };
+    let encrypted = libc::STATX_ATTR_ENCRYPTED as u64;
+    stx.stx_attributes_mask & encrypted != 0 && stx.stx_attributes & encrypted != 0
+}
+
+fn run_fscrypt(args: &[&std::ffi::OsStr], passphrase: Option<&str>) -> Result<String> {
+    debug!("Running fscrypt {:?}", args);
+
+    let mut child = Command::new("fscrypt")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute fscrypt: {}", e)))?;
+
+    // Dropping stdin closes it, so a prompt without a passphrase fails
+    // instead of waiting
+    if let Some(mut stdin) = child.stdin.take()
+        && let Some(passphrase) = passphrase
+    {
+        stdin.write_all(passphrase.as_bytes())?;
+        stdin.write_all(b"\n")?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "fscrypt failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
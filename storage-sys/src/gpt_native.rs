@@ -0,0 +1,592 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! GPT tables edited in Rust
+//!
+//! Reads and writes GPT headers, partition arrays and the MBR in front of
+//! them with the gpt crate, for the edits UDisks and sfdisk handle poorly:
+//! all 64 attribute bits, hybrid MBRs and sector-level boundary moves, and
+//! for putting back entries deleted earlier in the session exactly as they
+//! were.
+//!
+//! The CRC32s of both headers and both partition arrays are checked on
+//! every read, and nothing is written to a table whose checks fail. The
+//! gpt crate writes the used entries to the front of the array, so tables
+//! with gaps in their partition numbers are refused as well: writing them
+//! would renumber partitions.
+//!
+//! Everything works on any seekable device, so disk images in memory serve
+//! as fixtures.
+
+use crate::direct::partition_location;
+use crate::error::{Result, SysError};
+use gpt::disk::LogicalBlockSize;
+use gpt::header::Header;
+use gpt::mbr::{PartRecord, ProtectiveMBR};
+use gpt::partition::Partition;
+use gpt::partition_types::Type;
+use gpt::{DiskDevice, GptConfig, GptDisk};
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Command;
+use storage_types::gpt::{
+    DeletedPartition, GptEdit, GptEntry, GptEntryEdit, GptTable, LEGACY_BIOS_BOOTABLE,
+    MAX_HYBRID_PARTITIONS, check_edits, check_restore, hybrid_mbr_type,
+};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Entries in the partition array of the tables the gpt crate writes
+const ARRAY_ENTRIES: u32 = 128;
+const ENTRY_SIZE: u32 = 128;
+
+/// MBR type of the record that protects the GPT
+const PROTECTIVE_TYPE: u8 = 0xEE;
+
+fn block_size(sector_size: u64) -> Result<LogicalBlockSize> {
+    LogicalBlockSize::try_from(sector_size).map_err(|_| {
+        SysError::OperationFailed(format!("Unsupported sector size of {} bytes", sector_size))
+    })
+}
+
+fn open_table<D: DiskDevice>(device: D, sector_size: u64, writable: bool) -> Result<GptDisk<D>> {
+    GptConfig::new()
+        .writable(writable)
+        .logical_block_size(block_size(sector_size)?)
+        .open_from_device(device)
+        .map_err(|e| SysError::OperationFailed(format!("Failed to read the GPT: {}", e)))
+}
+
+/// Whether the partition array of `header` matches its CRC32
+fn array_matches<D: Read + Seek>(
+    device: &mut D,
+    header: &Header,
+    sector_size: u64,
+) -> Result<bool> {
+    let mut array = vec![0u8; header.num_parts as usize * header.part_size as usize];
+    device.seek(SeekFrom::Start(header.part_start * sector_size))?;
+    device.read_exact(&mut array)?;
+    Ok(crc32fast::hash(&array) == header.crc32_parts)
+}
+
+/// The GPT of `device`, with the state of its checksums
+pub fn read_table<D: DiskDevice>(device: &mut D, sector_size: u64) -> Result<GptTable> {
+    let disk = open_table(&mut *device, sector_size, false)?;
+    let primary = disk.primary_header().ok().cloned();
+    let backup = disk.backup_header().ok().cloned();
+    let header = disk.header();
+    if header.num_parts != ARRAY_ENTRIES || header.part_size != ENTRY_SIZE {
+        return Err(SysError::OperationFailed(format!(
+            "Only tables of {} entries of {} bytes are supported",
+            ARRAY_ENTRIES, ENTRY_SIZE
+        )));
+    }
+    let mut table = GptTable {
+        disk_guid: disk.guid().hyphenated().to_string().to_uppercase(),
+        sector_size,
+        first_usable_lba: header.first_usable,
+        last_usable_lba: header.last_usable,
+        primary_valid: false,
+        backup_valid: false,
+        entries: disk
+            .partitions()
+            .iter()
+            .filter(|(_, partition)| partition.is_used())
+            .map(|(number, partition)| GptEntry {
+                number: *number,
+                type_guid: partition
+                    .part_type_guid
+                    .guid
+                    .hyphenated()
+                    .to_string()
+                    .to_uppercase(),
+                guid: partition.part_guid.hyphenated().to_string().to_uppercase(),
+                first_lba: partition.first_lba,
+                last_lba: partition.last_lba,
+                attributes: partition.flags,
+                name: partition.name.clone(),
+            })
+            .collect(),
+    };
+    drop(disk);
+
+    if let Some(header) = primary {
+        table.primary_valid = array_matches(device, &header, sector_size)?;
+    }
+    if let Some(header) = backup {
+        table.backup_valid = array_matches(device, &header, sector_size)?;
+    }
+    Ok(table)
+}
+
+/// Apply `edit` to partition `number` and write both copies of the table
+pub fn edit_table<D: DiskDevice>(
+    device: &mut D,
+    sector_size: u64,
+    number: u32,
+    edit: &GptEdit,
+) -> Result<()> {
+    edit_entries(
+        device,
+        sector_size,
+        &[GptEntryEdit {
+            number,
+            edit: edit.clone(),
+        }],
+    )
+}
+
+/// Apply `edits` in order and write both copies of the table once
+///
+/// The edits are checked against the table they produce, not one by one,
+/// so boundaries can be traded between neighbouring partitions.
+pub fn edit_entries<D: DiskDevice>(
+    device: &mut D,
+    sector_size: u64,
+    edits: &[GptEntryEdit],
+) -> Result<()> {
+    let table = read_table(device, sector_size)?;
+    check_edits(&table, edits).map_err(SysError::OperationFailed)?;
+    if let Some((index, entry)) = table
+        .entries
+        .iter()
+        .enumerate()
+        .find(|(index, entry)| entry.number != *index as u32 + 1)
+    {
+        return Err(SysError::OperationFailed(format!(
+            "Partition {} follows an unused entry {}; writing the table would renumber it",
+            entry.number,
+            index + 1
+        )));
+    }
+
+    let mut disk = open_table(&mut *device, sector_size, true)?;
+    let mut partitions = disk.partitions().clone();
+    for GptEntryEdit { number, edit } in edits {
+        let partition = partitions.get_mut(number).ok_or_else(|| {
+            SysError::OperationFailed(format!("There is no partition {}", number))
+        })?;
+        match edit {
+            GptEdit::Attributes(attributes) => partition.flags = *attributes,
+            GptEdit::Name(name) => partition.name = name.clone(),
+            GptEdit::TypeGuid(guid) => {
+                partition.part_type_guid = guid
+                    .parse::<Type>()
+                    .map_err(|e| SysError::OperationFailed(format!("{}: {}", e, guid)))?;
+            }
+            GptEdit::Guid(guid) => {
+                partition.part_guid = Uuid::parse_str(guid)
+                    .map_err(|e| SysError::OperationFailed(format!("{}: {}", e, guid)))?;
+            }
+            GptEdit::Bounds {
+                first_lba,
+                last_lba,
+            } => {
+                partition.first_lba = *first_lba;
+                partition.last_lba = *last_lba;
+            }
+        }
+    }
+    disk.update_partitions(partitions)
+        .and_then(|()| disk.write_inplace())
+        .map_err(|e| SysError::OperationFailed(format!("Failed to write the GPT: {}", e)))
+}
+
+/// Put the entry of `deleted` back into the table of `device`, with its
+/// number, GUIDs, sectors, attributes and name as they were
+pub fn restore_entry<D: DiskDevice>(device: &mut D, deleted: &DeletedPartition) -> Result<()> {
+    let table = read_table(device, deleted.sector_size)?;
+    check_restore(&table, deleted).map_err(SysError::OperationFailed)?;
+    let entry = &deleted.entry;
+    // The gpt crate writes the used entries to the front of the array, so
+    // only a table numbered without gaps keeps its numbers
+    let mut numbers: Vec<u32> = table.entries.iter().map(|entry| entry.number).collect();
+    numbers.push(entry.number);
+    numbers.sort_unstable();
+    if let Some((index, _)) = numbers
+        .iter()
+        .enumerate()
+        .find(|(index, number)| **number != *index as u32 + 1)
+    {
+        return Err(SysError::OperationFailed(format!(
+            "Restoring partition {} would renumber the partitions after unused entry {}",
+            entry.number,
+            index + 1
+        )));
+    }
+
+    let partition = Partition {
+        part_type_guid: entry
+            .type_guid
+            .parse::<Type>()
+            .map_err(|e| SysError::OperationFailed(format!("{}: {}", e, entry.type_guid)))?,
+        part_guid: Uuid::parse_str(&entry.guid)
+            .map_err(|e| SysError::OperationFailed(format!("{}: {}", e, entry.guid)))?,
+        first_lba: entry.first_lba,
+        last_lba: entry.last_lba,
+        flags: entry.attributes,
+        name: entry.name.clone(),
+    };
+
+    let mut disk = open_table(&mut *device, deleted.sector_size, true)?;
+    let mut partitions = disk.partitions().clone();
+    partitions.insert(entry.number, partition);
+    disk.update_partitions(partitions)
+        .and_then(|()| disk.write_inplace())
+        .map_err(|e| SysError::OperationFailed(format!("Failed to write the GPT: {}", e)))
+}
+
+/// MBR record in front of GPT partition `entry`
+fn hybrid_record(entry: &GptEntry) -> Result<PartRecord> {
+    let beyond_mbr = || {
+        SysError::OperationFailed(format!(
+            "Partition {} lies beyond what an MBR can address",
+            entry.number
+        ))
+    };
+    Ok(PartRecord {
+        boot_indicator: if entry.attributes & LEGACY_BIOS_BOOTABLE != 0 {
+            0x80
+        } else {
+            0x00
+        },
+        // CHS addresses are unused; these values mark them as too large
+        start_head: 0xFE,
+        start_sector: 0xFF,
+        start_track: 0xFF,
+        os_type: hybrid_mbr_type(&entry.type_guid),
+        end_head: 0xFE,
+        end_sector: 0xFF,
+        end_track: 0xFF,
+        lb_start: u32::try_from(entry.first_lba).map_err(|_| beyond_mbr())?,
+        lb_size: u32::try_from(entry.last_lba - entry.first_lba + 1).map_err(|_| beyond_mbr())?,
+    })
+}
+
+/// Mirror GPT partitions `numbers` in the MBR, for firmware that boots only
+/// from MBR disks; no numbers restores the plain protective MBR
+///
+/// The boot code and disk signature of the MBR are kept.
+pub fn set_hybrid_mbr<D: DiskDevice>(
+    device: &mut D,
+    sector_size: u64,
+    numbers: &[u32],
+) -> Result<()> {
+    let table = read_table(device, sector_size)?;
+    if !table.is_valid() {
+        return Err(SysError::OperationFailed(
+            "The partition table fails its checksums; repair it first".to_string(),
+        ));
+    }
+    if numbers.len() > MAX_HYBRID_PARTITIONS {
+        return Err(SysError::OperationFailed(format!(
+            "A hybrid MBR holds at most {} partitions",
+            MAX_HYBRID_PARTITIONS
+        )));
+    }
+
+    let mut mbr = ProtectiveMBR::new();
+    if numbers.is_empty() {
+        let sectors = device.seek(SeekFrom::End(0))? / sector_size;
+        let protected = u32::try_from(sectors.saturating_sub(1)).unwrap_or(u32::MAX);
+        mbr.set_partition(0, PartRecord::new_protective(Some(protected)));
+    } else {
+        // The protective record covers the GPT itself, up to the first
+        // usable sector
+        let protected = u32::try_from(table.first_usable_lba - 1).unwrap_or(u32::MAX);
+        mbr.set_partition(0, PartRecord::new_protective(Some(protected)));
+        for (slot, number) in numbers.iter().enumerate() {
+            let entry = table.entry(*number).ok_or_else(|| {
+                SysError::OperationFailed(format!("There is no partition {}", number))
+            })?;
+            mbr.set_partition(slot + 1, hybrid_record(entry)?);
+        }
+    }
+    mbr.update_conservative(device)
+        .map_err(|e| SysError::OperationFailed(format!("Failed to write the MBR: {}", e)))?;
+    Ok(())
+}
+
+/// Numbers of the GPT partitions the MBR of `device` mirrors
+pub fn hybrid_partitions<D: DiskDevice>(device: &mut D, sector_size: u64) -> Result<Vec<u32>> {
+    let table = read_table(device, sector_size)?;
+    let mbr = ProtectiveMBR::from_disk(device, block_size(sector_size)?)
+        .map_err(|e| SysError::OperationFailed(format!("Failed to read the MBR: {}", e)))?;
+    Ok((0..4)
+        .filter_map(|slot| mbr.partition(slot))
+        .filter(|record| record.os_type != 0 && record.os_type != PROTECTIVE_TYPE)
+        .filter_map(|record| {
+            table
+                .entries
+                .iter()
+                .find(|entry| entry.first_lba == u64::from(record.lb_start))
+                .map(|entry| entry.number)
+        })
+        .collect())
+}
+
+/// Logical sector size of the disk `disk` in sysfs
+fn sector_size(disk: &str) -> u64 {
+    Path::new(disk)
+        .file_name()
+        .map(|name| {
+            Path::new("/sys/class/block")
+                .join(name)
+                .join("queue/logical_block_size")
+        })
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|size| size.trim().parse().ok())
+        .unwrap_or(512)
+}
+
+fn open_disk(disk: &str, writable: bool) -> Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(writable)
+        .open(disk)
+        .map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => {
+                SysError::PermissionDenied(format!("Cannot open {}", disk))
+            }
+            ErrorKind::NotFound => SysError::DeviceNotFound(disk.to_string()),
+            _ => SysError::Io(e),
+        })
+}
+
+/// Have the kernel pick up the changed partitions of `disk`; partitions in
+/// use keep their old bounds until the next boot
+fn update_kernel(disk: &str) {
+    match Command::new("partx").args(["--update", disk]).output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => warn!(
+            "partx could not update {}: {}",
+            disk,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => warn!("Failed to execute partx: {}", e),
+    }
+}
+
+/// The GPT of the disk `disk`
+pub fn read_disk(disk: &str) -> Result<GptTable> {
+    read_table(&mut open_disk(disk, false)?, sector_size(disk))
+}
+
+/// Apply `edit` to partition `number` of the disk `disk`
+pub fn edit_disk(disk: &str, number: u32, edit: &GptEdit) -> Result<()> {
+    info!("Editing partition {} of {}: {:?}", number, disk, edit);
+    let mut file = open_disk(disk, true)?;
+    edit_table(&mut file, sector_size(disk), number, edit)?;
+    file.sync_all()?;
+    update_kernel(disk);
+    Ok(())
+}
+
+/// The disk holding `partition`, its GPT and the entry of `partition`,
+/// read before it is deleted so it can be restored
+pub fn partition_entry(partition: &str) -> Result<(String, GptTable, GptEntry)> {
+    let (disk, number) = partition_location(partition)?;
+    let table = read_disk(&disk)?;
+    let entry = table
+        .entry(number)
+        .cloned()
+        .ok_or_else(|| SysError::OperationFailed(format!("There is no partition {}", number)))?;
+    Ok((disk, table, entry))
+}
+
+/// Put `deleted` back into the table of its disk
+pub fn restore_disk_entry(deleted: &DeletedPartition) -> Result<()> {
+    info!(
+        "Restoring partition {} of {}: {:?}",
+        deleted.entry.number, deleted.disk, deleted.entry
+    );
+    let mut file = open_disk(&deleted.disk, true)?;
+    restore_entry(&mut file, deleted)?;
+    file.sync_all()?;
+    update_kernel(&deleted.disk);
+    Ok(())
+}
+
+/// Apply `edits` to the disk `disk` together
+pub fn edit_disk_entries(disk: &str, edits: &[GptEntryEdit]) -> Result<()> {
+    info!("Editing GPT entries of {}: {:?}", disk, edits);
+    let mut file = open_disk(disk, true)?;
+    edit_entries(&mut file, sector_size(disk), edits)?;
+    file.sync_all()?;
+    update_kernel(disk);
+    Ok(())
+}
+
+/// Mirror GPT partitions `numbers` in the MBR of the disk `disk`
+pub fn set_disk_hybrid_mbr(disk: &str, numbers: &[u32]) -> Result<()> {
+    info!("Setting the hybrid MBR of {} to {:?}", disk, numbers);
+    let mut file = open_disk(disk, true)?;
+    set_hybrid_mbr(&mut file, sector_size(disk), numbers)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpt::partition_types::{EFI, LINUX_FS};
+    use std::io::{Cursor, Write};
+
+    const SECTOR: u64 = 512;
+
+    /// 8 MiB disk image with an EFI partition and a Linux one
+    fn image() -> Cursor<Vec<u8>> {
+        let mut device = Cursor::new(vec![0u8; 8 * 1024 * 1024]);
+        ProtectiveMBR::with_lb_size(8 * 2048 - 1)
+            .overwrite_lba0(&mut device)
+            .unwrap();
+        let mut disk = GptConfig::new()
+            .writable(true)
+            .create_from_device(&mut device, None)
+            .unwrap();
+        disk.add_partition("EFI", 1024 * 1024, EFI, 0, Some(2048))
+            .unwrap();
+        disk.add_partition("root", 4 * 1024 * 1024, LINUX_FS, 0, Some(2048))
+            .unwrap();
+        disk.write().unwrap();
+        device
+    }
+
+    #[test]
+    fn reads_a_valid_table() {
+        let mut device = image();
+        let table = read_table(&mut device, SECTOR).unwrap();
+        assert!(table.is_valid());
+        assert_eq!(table.entries.len(), 2);
+        let efi = table.entry(1).unwrap();
+        assert_eq!(efi.type_guid, "C12A7328-F81F-11D2-BA4B-00A0C93EC93B");
+        assert_eq!(efi.first_lba, 2048);
+        assert_eq!(efi.name, "EFI");
+    }
+
+    #[test]
+    fn detects_a_damaged_backup_array() {
+        let mut device = image();
+        let table = read_table(&mut device, SECTOR).unwrap();
+        // The backup array sits right after the last usable sector
+        device
+            .seek(SeekFrom::Start((table.last_usable_lba + 1) * SECTOR))
+            .unwrap();
+        device.write_all(&[0xFF; 16]).unwrap();
+
+        let table = read_table(&mut device, SECTOR).unwrap();
+        assert!(table.primary_valid);
+        assert!(!table.backup_valid);
+        assert!(edit_table(&mut device, SECTOR, 1, &GptEdit::Attributes(1)).is_err());
+    }
+
+    #[test]
+    fn edits_attributes_and_bounds() {
+        let mut device = image();
+        let attributes = (1 << 60) | LEGACY_BIOS_BOOTABLE;
+        edit_table(&mut device, SECTOR, 2, &GptEdit::Attributes(attributes)).unwrap();
+        let root = read_table(&mut device, SECTOR)
+            .unwrap()
+            .entry(2)
+            .unwrap()
+            .clone();
+        let bounds = GptEdit::Bounds {
+            first_lba: root.first_lba,
+            last_lba: root.last_lba + 1,
+        };
+        edit_table(&mut device, SECTOR, 2, &bounds).unwrap();
+
+        let table = read_table(&mut device, SECTOR).unwrap();
+        assert!(table.is_valid());
+        let edited = table.entry(2).unwrap();
+        assert_eq!(edited.attributes, attributes);
+        assert_eq!(edited.last_lba, root.last_lba + 1);
+        assert_eq!(edited.guid, root.guid);
+    }
+
+    #[test]
+    fn trades_a_boundary_between_neighbours() {
+        let mut device = image();
+        let table = read_table(&mut device, SECTOR).unwrap();
+        let efi = table.entry(1).unwrap().clone();
+        let root = table.entry(2).unwrap().clone();
+        let guid = "8E2D0B3A-6C1F-4C55-9D1E-1F0A4B7E2C90";
+        let edits = [
+            GptEntryEdit {
+                number: 2,
+                edit: GptEdit::Bounds {
+                    first_lba: root.first_lba + 8,
+                    last_lba: root.last_lba,
+                },
+            },
+            GptEntryEdit {
+                number: 1,
+                edit: GptEdit::Bounds {
+                    first_lba: efi.first_lba,
+                    last_lba: root.first_lba + 7,
+                },
+            },
+            GptEntryEdit {
+                number: 2,
+                edit: GptEdit::Guid(guid.to_string()),
+            },
+        ];
+        // Growing the first partition alone would overlap the second
+        assert!(edit_table(&mut device, SECTOR, 1, &edits[1].edit).is_err());
+        edit_entries(&mut device, SECTOR, &edits).unwrap();
+
+        let table = read_table(&mut device, SECTOR).unwrap();
+        assert!(table.is_valid());
+        assert_eq!(table.entry(1).unwrap().last_lba, root.first_lba + 7);
+        assert_eq!(table.entry(2).unwrap().first_lba, root.first_lba + 8);
+        assert_eq!(table.entry(2).unwrap().guid, guid);
+    }
+
+    #[test]
+    fn restores_a_deleted_entry_exactly() {
+        let mut device = image();
+        let table = read_table(&mut device, SECTOR).unwrap();
+        let mut root = table.entry(2).unwrap().clone();
+        // Bits an ordinary partition tool would not carry over
+        let attributes = (1 << 63) | (1 << 47) | LEGACY_BIOS_BOOTABLE;
+        edit_table(&mut device, SECTOR, 2, &GptEdit::Attributes(attributes)).unwrap();
+        root.attributes = attributes;
+
+        let mut disk = open_table(&mut device, SECTOR, true).unwrap();
+        let mut partitions = disk.partitions().clone();
+        partitions.remove(&2);
+        disk.update_partitions(partitions).unwrap();
+        disk.write_inplace().unwrap();
+        assert!(read_table(&mut device, SECTOR).unwrap().entry(2).is_none());
+
+        let deleted = DeletedPartition {
+            id: 1,
+            disk: String::new(),
+            disk_guid: table.disk_guid.clone(),
+            sector_size: SECTOR,
+            entry: root.clone(),
+            deleted_at: 0,
+        };
+        restore_entry(&mut device, &deleted).unwrap();
+        let restored = read_table(&mut device, SECTOR).unwrap();
+        assert!(restored.is_valid());
+        assert_eq!(restored.entry(2), Some(&root));
+
+        // Once restored, its number and space are taken
+        assert!(restore_entry(&mut device, &deleted).is_err());
+    }
+
+    #[test]
+    fn writes_and_clears_a_hybrid_mbr() {
+        let mut device = image();
+        set_hybrid_mbr(&mut device, SECTOR, &[1]).unwrap();
+        assert_eq!(hybrid_partitions(&mut device, SECTOR).unwrap(), vec![1]);
+        let mbr = ProtectiveMBR::from_disk(&mut device, LogicalBlockSize::Lb512).unwrap();
+        assert_eq!(mbr.partition(0).unwrap().os_type, PROTECTIVE_TYPE);
+        assert_eq!(mbr.partition(1).unwrap().os_type, 0xEF);
+
+        set_hybrid_mbr(&mut device, SECTOR, &[]).unwrap();
+        assert!(hybrid_partitions(&mut device, SECTOR).unwrap().is_empty());
+        assert!(set_hybrid_mbr(&mut device, SECTOR, &[1, 2, 3, 4]).is_err());
+    }
+}
@@ -50,10 +50,13 @@ pub fn open_for_restore(device: &str) -> Result<OwnedFd> {
 /// * `source_fd` - File descriptor to read from (typically a block device)
 /// * `dest_path` - Destination file path
 /// * `progress_callback` - Optional callback for progress updates (bytes copied)
+/// * `cancelled` - Stops the copy with an error as soon as it returns true;
+///   the image written so far is removed
 pub fn copy_image_to_file<F>(
     source_fd: OwnedFd,
     dest_path: &Path,
     mut progress_callback: Option<F>,
+    cancelled: impl Fn() -> bool,
 ) -> Result<u64>
 where
     F: FnMut(u64),
@@ -69,6 +72,11 @@ where
     let mut total_copied: u64 = 0;
 
     loop {
+        if cancelled() {
+            drop(dest);
+            let _ = std::fs::remove_file(dest_path);
+            return Err(SysError::OperationFailed("Copy cancelled".to_string()));
+        }
         let bytes_read = source.read(&mut buffer)?;
         if bytes_read == 0 {
             break;
@@ -92,10 +100,13 @@ where
 /// * `source_path` - Source file path
 /// * `dest_fd` - File descriptor to write to (typically a block device)
 /// * `progress_callback` - Optional callback for progress updates (bytes copied)
+/// * `cancelled` - Stops the copy with an error as soon as it returns true,
+///   leaving the device partly written
 pub fn copy_file_to_image<F>(
     source_path: &Path,
     dest_fd: OwnedFd,
     mut progress_callback: Option<F>,
+    cancelled: impl Fn() -> bool,
 ) -> Result<u64>
 where
     F: FnMut(u64),
@@ -107,6 +118,9 @@ where
     let mut total_copied: u64 = 0;
 
     loop {
+        if cancelled() {
+            return Err(SysError::OperationFailed("Copy cancelled".to_string()));
+        }
         let bytes_read = source.read(&mut buffer)?;
         if bytes_read == 0 {
             break;
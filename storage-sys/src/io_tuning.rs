@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! I/O scheduler, readahead and queue depth of drives
+//!
+//! Settings are written to `/sys/block/<name>/queue` right away. Persistent
+//! ones are also kept in a udev rule per drive, matched by WWN or serial so
+//! it follows the drive across device name changes.
+
+use crate::error::{Result, SysError};
+use crate::migration::run;
+use std::path::{Path, PathBuf};
+use storage_types::io_tuning::{parse_scheduler, queue_rule_file_name};
+use storage_types::{QueueSettings, QueueTuning};
+use tracing::info;
+
+/// Where the udev rules of tuned drives are written
+pub(crate) const RULES_DIR: &str = "/etc/udev/rules.d";
+
+fn queue_dir(device: &str) -> PathBuf {
+    let name = device.strip_prefix("/dev/").unwrap_or(device);
+    Path::new("/sys/block").join(name).join("queue")
+}
+
+fn read(path: &Path) -> Result<String> {
+    Ok(std::fs::read_to_string(path)?.trim().to_string())
+}
+
+fn read_number(path: &Path) -> Result<u64> {
+    let value = read(path)?;
+    value.parse().map_err(|_| {
+        SysError::OperationFailed(format!(
+            "Unexpected value {:?} in {}",
+            value,
+            path.display()
+        ))
+    })
+}
+
+fn write(path: &Path, value: &str) -> Result<()> {
+    std::fs::write(path, value).map_err(|e| {
+        SysError::OperationFailed(format!(
+            "Failed to write {} to {}: {}",
+            value,
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// udev property and value identifying `device`, preferring the WWN
+pub(crate) fn udev_id(device: &str) -> Option<(&'static str, String)> {
+    let properties = run(
+        "udevadm",
+        &["info", "--query=property", "--name", device],
+        None,
+    )
+    .ok()?;
+    ["ID_WWN", "ID_SERIAL"].into_iter().find_map(|key| {
+        properties
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .filter(|value| !value.is_empty())
+            .map(|value| (key, value.to_string()))
+    })
+}
+
+/// Rules file of `device`, when it has a stable identifier
+fn rule_path(device: &str) -> Option<(PathBuf, &'static str, String)> {
+    let (key, value) = udev_id(device)?;
+    let path = Path::new(RULES_DIR).join(queue_rule_file_name(&value));
+    Some((path, key, value))
+}
+
+/// Queue settings of a whole-disk `device` (e.g., "/dev/sda")
+pub fn queue_settings(device: &str) -> Result<QueueSettings> {
+    let dir = queue_dir(device);
+    if !dir.is_dir() {
+        return Err(SysError::DeviceNotFound(device.to_string()));
+    }
+    let (scheduler, available_schedulers) = parse_scheduler(&read(&dir.join("scheduler"))?);
+    Ok(QueueSettings {
+        scheduler,
+        available_schedulers,
+        read_ahead_kb: read_number(&dir.join("read_ahead_kb"))?,
+        nr_requests: read_number(&dir.join("nr_requests"))?,
+        persistent: rule_path(device).is_some_and(|(path, _, _)| path.exists()),
+    })
+}
+
+/// Apply `tuning` to a whole-disk `device`, and keep it in a udev rule when
+/// `persistent`, or drop the drive's rule otherwise
+pub fn set_queue_tuning(device: &str, tuning: &QueueTuning, persistent: bool) -> Result<()> {
+    let current = queue_settings(device)?;
+    if !current.available_schedulers.contains(&tuning.scheduler) {
+        return Err(SysError::OperationFailed(format!(
+            "{} does not offer the {} scheduler",
+            device, tuning.scheduler
+        )));
+    }
+
+    let dir = queue_dir(device);
+    // Switching the scheduler resets the queue depth, so it goes first
+    write(&dir.join("scheduler"), &tuning.scheduler)?;
+    write(
+        &dir.join("read_ahead_kb"),
+        &tuning.read_ahead_kb.to_string(),
+    )?;
+    write(&dir.join("nr_requests"), &tuning.nr_requests.to_string())?;
+    info!(
+        "Tuned {}: scheduler {}, readahead {} KiB, {} requests",
+        device, tuning.scheduler, tuning.read_ahead_kb, tuning.nr_requests
+    );
+
+    let rule = rule_path(device);
+    if persistent {
+        let (path, key, value) = rule.ok_or_else(|| {
+            SysError::OperationFailed(format!(
+                "{} has no WWN or serial number to keep its settings by",
+                device
+            ))
+        })?;
+        std::fs::write(&path, tuning.udev_rule(key, &value))?;
+        info!("Kept the settings of {} in {}", device, path.display());
+    } else if let Some((path, _, _)) = rule.filter(|(path, _, _)| path.exists()) {
+        std::fs::remove_file(&path)?;
+        info!("Removed {}", path.display());
+    } else {
+        return Ok(());
+    }
+    run("udevadm", &["control", "--reload"], None)?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Watching the kernel log for device errors
+//!
+//! `/dev/kmsg` is read from the start of the ring buffer, so errors logged
+//! since boot are picked up as well as new ones. The devices the messages
+//! name are resolved to their drive through sysfs.
+
+use crate::error::{Result, SysError};
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::path::Path;
+use storage_types::{
+    KernelDeviceError, KernelErrorSource, classify_kernel_error, parse_kmsg_record,
+};
+use tracing::{debug, warn};
+
+/// Report each device error in the kernel log, with the device path of its
+/// drive (e.g., "/dev/sda"), until the log cannot be read any longer
+///
+/// This blocks while waiting for new messages.
+pub fn watch_kernel_errors(mut on_error: impl FnMut(String, KernelDeviceError)) -> Result<()> {
+    let kmsg = File::open("/dev/kmsg").map_err(|e| match e.kind() {
+        ErrorKind::PermissionDenied => {
+            SysError::PermissionDenied("Cannot open /dev/kmsg for reading".to_string())
+        }
+        _ => SysError::Io(e),
+    })?;
+    let boot_time = boot_time()?;
+
+    for record in BufReader::new(kmsg).lines() {
+        let record = match record {
+            Ok(record) => record,
+            // Records were overwritten before they could be read
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => continue,
+            Err(e) => return Err(SysError::Io(e)),
+        };
+        let Some((since_boot, message)) = parse_kmsg_record(&record) else {
+            continue;
+        };
+        let Some((source, kind)) = classify_kernel_error(message) else {
+            continue;
+        };
+        let Some(disk) = resolve_disk(&source) else {
+            debug!("No drive found for kernel error: {}", message);
+            continue;
+        };
+        on_error(
+            format!("/dev/{}", disk),
+            KernelDeviceError {
+                timestamp: boot_time + since_boot / 1_000_000,
+                kind,
+                message: message.to_string(),
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Unix time of the boot, from `/proc/stat`
+fn boot_time() -> Result<u64> {
+    let stat = std::fs::read_to_string("/proc/stat")?;
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime ")?.trim().parse().ok())
+        .ok_or_else(|| SysError::OperationFailed("No boot time in /proc/stat".to_string()))
+}
+
+/// Kernel name of the drive (e.g., "sda") that `source` belongs to
+fn resolve_disk(source: &KernelErrorSource) -> Option<String> {
+    match source {
+        KernelErrorSource::Block(name) => {
+            let sysfs = Path::new("/sys/class/block").join(name);
+            if !sysfs.exists() {
+                warn!("Kernel error for unknown device {}", name);
+                return None;
+            }
+            if !sysfs.join("partition").exists() {
+                return Some(name.clone());
+            }
+            // A partition's sysfs directory is inside that of its drive
+            let path = std::fs::canonicalize(&sysfs).ok()?;
+            Some(path.parent()?.file_name()?.to_string_lossy().to_string())
+        }
+        // The drive's sysfs path runs through its port, e.g.
+        // ".../ata3/host2/target2:0:0/2:0:0:0/block/sda"
+        KernelErrorSource::AtaPort(port) => {
+            let port = format!("ata{}", port);
+            find_disk(|_, path| path.components().any(|c| c.as_os_str() == port.as_str()))
+        }
+        // Namespaces of controller "nvme0" are "nvme0n1", "nvme0n2", ...
+        KernelErrorSource::NvmeController(controller) => find_disk(|name, _| {
+            name.strip_prefix(controller.as_str())
+                .and_then(|rest| rest.strip_prefix('n'))
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        }),
+    }
+}
+
+/// The first drive in `/sys/block` for which `matches` holds, given its
+/// name and resolved sysfs path
+fn find_disk(matches: impl Fn(&str, &Path) -> bool) -> Option<String> {
+    std::fs::read_dir("/sys/block")
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = std::fs::canonicalize(entry.path()).ok()?;
+            matches(&name, &path).then_some(name)
+        })
+}
@@ -4,19 +4,127 @@
 //!
 //! This crate provides direct system call interfaces for operations that
 //! don't go through D-Bus, such as:
-//! - File descriptor management
+//! - File descriptor management, sealed memory files for bulk replies and
+//!   shared progress counters
 //! - Direct file I/O for disk imaging
+//! - Rescue imaging of failing drives, ddrescue style
 //! - Process management utilities
 //! - RClone CLI operations
+//! - Filesystem feature probing and defragmentation
+//! - Encrypted directories on ext4 and f2fs, through fscrypt
+//! - Mounting legacy eCryptfs and gocryptfs vaults and migrating their files
+//! - MD-RAID array details from sysfs and spare groups in mdadm.conf
+//! - SMART data through smartctl for drives UDisks cannot query, and for
+//!   disks behind hardware RAID controllers
+//! - CPU and I/O priority of the threads running long operations
+//! - I/O scheduler, readahead and queue depth of drives, kept by udev rules
+//! - Negotiated SATA, NVMe and USB link speeds of drives
+//! - Filesystems the kernel made read-only after errors
+//! - NTFS filesystems Windows left hibernated, probed before mounting
+//! - Searching free space for deleted partitions and recreating them
+//! - Moving misaligned partitions onto a 1 MiB boundary, and partitions to
+//!   another place on their disk
+//! - Shrinking and growing filesystems when their partition is resized
+//! - Copying a partition onto a larger one, and a disk onto a larger one
+//! - Keeping a secondary EFI System Partition in sync with the primary
+//! - Bootable USB drives with persistence from distribution ISOs
+//! - Blinking enclosure slot LEDs to find drives, through SES or ledctl
+//! - Device errors in the kernel log, by drive
+//! - Device layout and log excerpts for diagnostic reports
+//! - Partitioning and imaging without UDisks, for the direct backend
+//! - Volatile write cache of ATA and NVMe drives, kept by udev rules
+//! - Hypervisor detection, and trimming filesystems on virtual disks
+//! - GPT attribute, hybrid MBR and sector-level edits in Rust
+//! - Read-only access to raw sectors for the sector viewer
 //!
 //! These operations require elevated privileges and should only be called
 //! from privileged services (like storage-service).
 
+pub mod alignment;
+pub mod automount;
+pub mod bind_mount;
+pub mod defrag;
+pub mod diagnostics;
+pub mod direct;
+pub mod enclosure;
 pub mod error;
+pub mod esp_sync;
+pub mod features;
+pub mod fs_resize;
+pub mod fscrypt;
+pub mod gpt_native;
 pub mod image;
+pub mod io_tuning;
+pub mod kernel_log;
+pub mod link;
+pub mod live_usb;
+pub mod lost_partition;
+pub mod memfd;
+pub mod migration;
+pub mod optical;
+pub mod partition_copy;
+pub mod partition_move;
+pub mod priority;
+pub mod raid;
 pub mod rclone;
+pub mod read_only;
+pub mod rescue;
+pub mod sectors;
+pub mod selinux;
+pub mod smart;
 pub mod usage;
+pub mod vault;
+pub mod virtualization;
+pub mod windows_hibernation;
+pub mod write_cache;
 
+pub use alignment::realign_partition;
+pub use automount::{create_automount_unit, list_automount_units, remove_automount_unit};
+pub use bind_mount::{create_bind_mount, list_bind_mounts, remove_bind_mount};
+pub use defrag::{defrag_supported, defragment, fragmentation_report};
+pub use diagnostics::{device_layout, recent_journal, udisks_properties, unit_log_since};
+pub use direct::DirectBackend;
+pub use enclosure::{identify_support, set_locate};
 pub use error::{Result, SysError};
+pub use esp_sync::sync_esp;
+pub use features::get_filesystem_features;
+pub use fs_resize::{filesystem_min_size, grow_filesystem, shrink_filesystem};
+pub use fscrypt::{
+    create_encrypted_directory, fscrypt_available, fscrypt_status, lock_directory, unlock_directory,
+};
+pub use gpt_native::{
+    edit_disk as edit_gpt, edit_disk_entries as edit_gpt_entries, partition_entry,
+    read_disk as read_gpt, restore_disk_entry as restore_gpt_entry, set_disk_hybrid_mbr,
+};
 pub use image::{copy_file_to_image, copy_image_to_file, open_for_backup, open_for_restore};
-pub use rclone::{RCloneCli, is_mount_on_boot_enabled, set_mount_on_boot};
+pub use io_tuning::{queue_settings, set_queue_tuning};
+pub use kernel_log::watch_kernel_errors;
+pub use link::interface_speed;
+pub use live_usb::{create_live_usb, probe_live_iso};
+pub use lost_partition::{partition_layout, recreate_partition, scan_lost_partitions};
+pub use memfd::{ProgressCounter, read_counter, read_memfd, sealed_memfd};
+pub use migration::migrate_disk;
+pub use optical::{
+    blank_optical_media, burn_optical_image, optical_media_info, verify_optical_image,
+    xorriso_available,
+};
+pub use partition_copy::{copy_partition, finish_partition_copy};
+pub use partition_move::{move_partition, partition_move_bounds};
+pub use priority::{PriorityGuard, apply_priority};
+pub use raid::{list_arrays, raid_detail, set_auto_add_spares, set_spare_group, spare_pool_config};
+pub use rclone::{
+    RCloneCli, RCloneTransfer, is_mount_on_boot_enabled, login_mount_status, set_mount_on_boot,
+    set_mount_point_override,
+};
+pub use read_only::{forced_read_only_filesystems, remount_read_write};
+pub use rescue::rescue_image;
+pub use sectors::read_sectors;
+pub use selinux::{relabel, selinux_statuses};
+pub use smart::{
+    passthrough_devices, smartctl_abort_selftest, smartctl_available, smartctl_info,
+    smartctl_start_selftest,
+};
+pub use vault::{find_legacy_vaults, migrate_vault, mount_vault, unmount_vault};
+pub use virtualization::{hypervisor, trim_filesystem};
+pub use windows_hibernation::windows_hibernation;
+pub use write_cache::{set_write_cache, write_cache_status};
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Negotiated interface speed of drives from sysfs
+//!
+//! SATA links report their speed under the ATA port, NVMe drives through
+//! the PCIe link of their controller, and USB drives through the USB device
+//! they hang off. All are normalized to megabits per second.
+
+use std::path::{Path, PathBuf};
+use storage_types::InterfaceSpeed;
+
+/// Negotiated interface speed of a whole-disk `device` (e.g., "/dev/sda")
+///
+/// Returns `None` for drives whose link speed sysfs does not expose, such
+/// as loop devices, or while a SATA link is down.
+pub fn interface_speed(device: &str) -> Option<InterfaceSpeed> {
+    let name = device.strip_prefix("/dev/").unwrap_or(device);
+    let sys_path = std::fs::canonicalize(Path::new("/sys/block").join(name)).ok()?;
+
+    usb_speed(&sys_path)
+        .or_else(|| nvme_speed(&sys_path))
+        .or_else(|| sata_speed(&sys_path))
+}
+
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Closest USB device above the block device
+fn usb_speed(sys_path: &Path) -> Option<InterfaceSpeed> {
+    let usb_device = sys_path
+        .ancestors()
+        .find(|dir| dir.join("idVendor").exists() && dir.join("speed").exists())?;
+    let mbps = read(&usb_device.join("speed"))?.parse::<f64>().ok()? as u64;
+
+    Some(InterfaceSpeed {
+        bus: "usb".to_string(),
+        description: format!("USB {mbps} Mb/s"),
+        mbps,
+    })
+}
+
+/// PCIe link of the NVMe controller
+fn nvme_speed(sys_path: &Path) -> Option<InterfaceSpeed> {
+    let pci_device = sys_path
+        .ancestors()
+        .find(|dir| dir.join("current_link_speed").exists())?;
+    let speed = read(&pci_device.join("current_link_speed"))?;
+    let width: u64 = read(&pci_device.join("current_link_width"))?.parse().ok()?;
+    let mbps = pcie_mbps(&speed, width)?;
+
+    Some(InterfaceSpeed {
+        bus: "nvme".to_string(),
+        description: format!("{speed} x{width}"),
+        mbps,
+    })
+}
+
+/// SATA link of the ATA port the drive is attached to
+fn sata_speed(sys_path: &Path) -> Option<InterfaceSpeed> {
+    let port = sys_path.ancestors().find(|dir| {
+        dir.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("ata"))
+            .is_some_and(|number| number.parse::<u32>().is_ok())
+    })?;
+    let number = port.file_name()?.to_str()?.strip_prefix("ata")?;
+    let link: PathBuf = port
+        .join(format!("link{number}"))
+        .join("ata_link")
+        .join(format!("link{number}"))
+        .join("sata_spd");
+    let speed = read(&link)?;
+    let mbps = sata_mbps(&speed)?;
+
+    Some(InterfaceSpeed {
+        bus: "sata".to_string(),
+        description: format!("SATA {speed}"),
+        mbps,
+    })
+}
+
+/// Megabits per second of a SATA speed such as "6.0 Gb/s"
+fn sata_mbps(speed: &str) -> Option<u64> {
+    let gbps: f64 = speed.strip_suffix("Gb/s")?.trim().parse().ok()?;
+    Some((gbps * 1000.0) as u64)
+}
+
+/// Usable megabits per second of a PCIe link such as "8.0 GT/s PCIe" with
+/// `width` lanes, after line encoding
+fn pcie_mbps(speed: &str, width: u64) -> Option<u64> {
+    let gts: f64 = speed.split_whitespace().next()?.parse().ok()?;
+    // PCIe 1 and 2 use 8b/10b, later generations 128b/130b
+    let encoding = if gts < 8.0 { 0.8 } else { 128.0 / 130.0 };
+    Some((gts * 1000.0 * encoding) as u64 * width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_link_speeds() {
+        assert_eq!(sata_mbps("6.0 Gb/s"), Some(6000));
+        assert_eq!(sata_mbps("<unknown>"), None);
+        assert_eq!(pcie_mbps("8.0 GT/s PCIe", 4), Some(7876 * 4));
+        assert_eq!(pcie_mbps("5.0 GT/s PCIe", 2), Some(8000));
+        assert_eq!(pcie_mbps("Unknown", 4), None);
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Making bootable USB drives with persistence from distribution ISOs
+//!
+//! The drive is prepared as one transaction: the image is written, the
+//! partitions are added after it and formatted. When any step fails, the
+//! drive is wiped so it is left blank rather than half made.
+
+use crate::error::{Result, SysError};
+use crate::migration::{disk_geometry, disk_in_use, run, settle};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+use storage_types::live_usb::{DATA_LABEL, is_hybrid_image};
+use storage_types::migration::partition_device;
+use storage_types::{LiveIsoInfo, LiveUsbDistro, LiveUsbPlan};
+use tracing::{info, warn};
+
+/// Run `f` on `source` mounted with `options` at a temporary directory
+fn with_mounted<T>(source: &str, options: &str, f: impl FnOnce(&Path) -> T) -> Result<T> {
+    let mount_point = std::env::temp_dir().join(format!(
+        "cosmic-ext-storage-live-usb-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&mount_point)?;
+    let mount_path = mount_point.to_string_lossy().into_owned();
+    let result = run("mount", &["-o", options, source, &mount_path], None).map(|_| {
+        let value = f(&mount_point);
+        if let Err(e) = run("umount", &[&mount_path], None) {
+            warn!("{e}");
+        }
+        value
+    });
+    let _ = std::fs::remove_dir(&mount_point);
+    result
+}
+
+/// Size of the ISO image at `iso_path`, and how its live system keeps
+/// changes
+pub fn probe_live_iso(iso_path: &str) -> Result<LiveIsoInfo> {
+    let mut file = File::open(iso_path)?;
+    let size = file.metadata()?.len();
+    let mut first_sector = [0u8; 512];
+    file.read_exact(&mut first_sector)?;
+    if !is_hybrid_image(&first_sector) {
+        return Err(SysError::OperationFailed(format!(
+            "{} cannot boot from a USB drive; only hybrid ISO images can",
+            iso_path
+        )));
+    }
+
+    let distro = with_mounted(iso_path, "loop,ro", |root| {
+        if root.join("casper").is_dir() {
+            LiveUsbDistro::Casper
+        } else if root.join("live").is_dir() {
+            LiveUsbDistro::DebianLive
+        } else {
+            LiveUsbDistro::Unsupported
+        }
+    })?;
+    Ok(LiveIsoInfo { size, distro })
+}
+
+/// Write `iso_path` to the start of `device`, reporting the bytes written
+fn write_image(
+    iso_path: &str,
+    device: &str,
+    on_progress: &mut impl FnMut(u64),
+    cancelled: &impl Fn() -> bool,
+) -> Result<()> {
+    let mut input = File::open(iso_path)?;
+    let mut output = OpenOptions::new().write(true).open(device)?;
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut written = 0;
+    loop {
+        if cancelled() {
+            return Err(SysError::OperationFailed("Cancelled".to_string()));
+        }
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        output.write_all(&buffer[..read])?;
+        written += read as u64;
+        on_progress(written);
+    }
+    output.sync_all()?;
+    Ok(())
+}
+
+/// Label and highest partition number of the table on `device`
+fn partition_table(device: &str) -> Result<(String, u32)> {
+    let dump = run("sfdisk", &["--dump", device], None)?;
+    let label = dump
+        .lines()
+        .find_map(|line| line.strip_prefix("label:"))
+        .map(|label| label.trim().to_string())
+        .ok_or_else(|| SysError::OperationFailed("The image has no partition table".to_string()))?;
+    let last = dump
+        .lines()
+        .filter_map(|line| line.split_once(" : "))
+        .filter_map(|(device, _)| {
+            let device = device.trim();
+            let digits = device.len() - device.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+            device[device.len() - digits..].parse::<u32>().ok()
+        })
+        .max()
+        .unwrap_or(0);
+    Ok((label, last))
+}
+
+/// Make `device` a bootable copy of `iso_path` with the extra partitions
+/// of `plan`
+///
+/// Everything on `device` is lost; it may not be in use. Progress counts
+/// the bytes of the image written. The drive is wiped when a step fails or
+/// `cancelled` returns true.
+pub fn create_live_usb(
+    iso_path: &str,
+    device: &str,
+    plan: &LiveUsbPlan,
+    mut on_progress: impl FnMut(u64),
+    cancelled: impl Fn() -> bool,
+) -> Result<()> {
+    if disk_in_use(device) {
+        return Err(SysError::OperationFailed(format!(
+            "{} is in use; unmount or lock its partitions first",
+            device
+        )));
+    }
+    let iso = probe_live_iso(iso_path)?;
+    let (device_size, sector_size) = disk_geometry(device)?;
+    let layout = plan
+        .layout(&iso, device_size, sector_size)
+        .map_err(SysError::OperationFailed)?;
+
+    info!(
+        "Writing {} to {} with {} extra partitions",
+        iso_path,
+        device,
+        layout.partitions().count()
+    );
+    let result: Result<()> = (|| {
+        write_image(iso_path, device, &mut on_progress, &cancelled)?;
+        run("blockdev", &["--rereadpt", device], None)?;
+        settle();
+
+        let (label, last) = partition_table(device)?;
+        let added = layout.partitions().count() as u32;
+        if label == "dos" && last + added > 4 {
+            return Err(SysError::OperationFailed(
+                "The image's partition table has no room for more partitions".to_string(),
+            ));
+        }
+        if label == "gpt" {
+            // The image's backup GPT sits where the new partitions go
+            run("sfdisk", &["--relocate", "gpt-bak-std", device], None)?;
+        }
+        run(
+            "sfdisk",
+            &["--append", device],
+            Some(&layout.sfdisk_script(&label, sector_size)),
+        )?;
+        settle();
+
+        let mut number = last;
+        if layout.persistence.is_some() {
+            number += 1;
+            let partition = partition_device(device, number);
+            let fs_label = iso.distro.persistence_label().unwrap_or_default();
+            run("mkfs.ext4", &["-F", "-L", fs_label, &partition], None)?;
+            if let Some(conf) = iso.distro.persistence_conf() {
+                with_mounted(&partition, "rw", |root| {
+                    std::fs::write(root.join("persistence.conf"), conf)
+                })??;
+            }
+        }
+        if layout.data.is_some() {
+            number += 1;
+            let partition = partition_device(device, number);
+            run("mkfs.exfat", &["-L", DATA_LABEL, &partition], None)?;
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = &result {
+        warn!(
+            "Making a live USB drive on {} failed, wiping it: {}",
+            device, e
+        );
+        if let Err(e) = run("wipefs", &["--all", device], None) {
+            warn!("Failed to wipe {}: {}", device, e);
+        }
+    }
+    result
+}
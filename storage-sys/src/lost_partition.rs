@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Searching free space for deleted partitions and recreating them
+//!
+//! The partition table is read with sfdisk, and each free area is probed
+//! for filesystem signatures at the starts partitions usually have.
+//! Recreated partitions are added with sfdisk, which is told not to wipe
+//! them: UDisks clears the signatures of partitions it creates, which
+//! would destroy the filesystem being recovered.
+
+use crate::error::{Result, SysError};
+use std::fs::File;
+use std::io::{ErrorKind, Seek, SeekFrom, Write};
+use std::os::unix::fs::FileExt;
+use std::process::{Command, Stdio};
+use storage_types::{
+    LostPartition, PartitionLayout, next_candidate_offset, probe_filesystem_signature,
+};
+use tracing::{debug, info};
+
+/// Bytes read at each candidate start
+const PROBE_SIZE: usize = storage_types::SIGNATURE_PROBE_SIZE;
+
+/// Partitions of the disk `device`; a disk without a partition table has
+/// no partitions and is all free
+pub fn partition_layout(device: &str) -> Result<PartitionLayout> {
+    let size = open_disk(device)?.seek(SeekFrom::End(0))?;
+    let output = Command::new("sfdisk")
+        .args(["--json", device])
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute sfdisk: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("does not contain a recognized partition table") {
+            return Ok(PartitionLayout::unpartitioned(size));
+        }
+        return Err(SysError::OperationFailed(format!(
+            "Failed to read the partition table of {}: {}",
+            device,
+            stderr.trim()
+        )));
+    }
+    PartitionLayout::from_sfdisk_json(&String::from_utf8_lossy(&output.stdout), size)
+        .map_err(SysError::OperationFailed)
+}
+
+/// Filesystems and LUKS containers in the free space of `device` that have
+/// no partition, in order
+///
+/// Nothing is written to the disk.
+pub fn scan_lost_partitions(device: &str) -> Result<Vec<LostPartition>> {
+    let layout = partition_layout(device)?;
+    let disk = open_disk(device)?;
+    let mut buffer = vec![0u8; PROBE_SIZE];
+    let mut found = Vec::new();
+
+    for region in layout.free_regions() {
+        debug!(
+            "Searching {} from byte {} to {}",
+            device, region.start, region.end
+        );
+        let mut next = next_candidate_offset(None, &region);
+        while let Some(offset) = next {
+            next = next_candidate_offset(Some(offset), &region);
+
+            // Unreadable sectors of a damaged disk do not end the search
+            let len = match read_at_most(&disk, &mut buffer, offset) {
+                Ok(len) => len,
+                Err(e) => {
+                    debug!("Failed to read byte {} of {}: {}", offset, device, e);
+                    continue;
+                }
+            };
+            let Some(signature) = probe_filesystem_signature(&buffer[..len]) else {
+                continue;
+            };
+            let sized = signature.size.is_some();
+            let partition = LostPartition::assess(offset, signature, region.end);
+            info!(
+                "Found {} at byte {} of {} ({} bytes)",
+                partition.fs_type, offset, device, partition.size
+            );
+            // What lies inside a filesystem that fits is its own data; one
+            // of unknown size may be followed by others
+            if sized && partition.range().end <= region.end {
+                next = next_candidate_offset(Some(partition.range().end - 1), &region);
+            }
+            found.push(partition);
+        }
+    }
+    Ok(found)
+}
+
+/// Add a partition entry for `partition` to the partition table of
+/// `device`, leaving its contents untouched
+///
+/// Fails when the area is no longer free or the disk has no partition
+/// table.
+pub fn recreate_partition(device: &str, partition: &LostPartition) -> Result<()> {
+    let layout = partition_layout(device)?;
+    let Some(table_type) = layout.table_type.as_deref() else {
+        return Err(SysError::OperationFailed(format!(
+            "{} has no partition table; create one first",
+            device
+        )));
+    };
+    let range = partition.range();
+    if !layout.is_free(&range) {
+        return Err(SysError::OperationFailed(
+            "The partition overlaps a partition in use".to_string(),
+        ));
+    }
+    if !range.start.is_multiple_of(layout.sector_size) {
+        return Err(SysError::OperationFailed(format!(
+            "The partition does not start on a {}-byte sector",
+            layout.sector_size
+        )));
+    }
+    let type_id = partition.partition_type(table_type).ok_or_else(|| {
+        SysError::OperationFailed(format!("Unsupported partition table {}", table_type))
+    })?;
+
+    let start = range.start / layout.sector_size;
+    let sectors = range.size().div_ceil(layout.sector_size);
+    info!(
+        "Recreating {} partition on {} at sector {} ({} sectors)",
+        partition.fs_type, device, start, sectors
+    );
+
+    let mut child = Command::new("sfdisk")
+        .args(["--append", "--wipe-partitions", "never", device])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute sfdisk: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "start={start}, size={sectors}, type={type_id}")?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "Failed to recreate the partition: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn open_disk(device: &str) -> Result<File> {
+    File::open(device).map_err(|e| match e.kind() {
+        ErrorKind::PermissionDenied => {
+            SysError::PermissionDenied(format!("Cannot open {} for reading", device))
+        }
+        ErrorKind::NotFound => SysError::DeviceNotFound(device.to_string()),
+        _ => SysError::Io(e),
+    })
+}
+
+/// Fill `buffer` from `offset`, stopping early at the end of the disk;
+/// returns the bytes read
+fn read_at_most(disk: &File, buffer: &mut [u8], offset: u64) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match disk.read_at(&mut buffer[filled..], offset + filled as u64) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(SysError::Io(e)),
+        }
+    }
+    Ok(filled)
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Sealed memory files for bulk replies and progress counters
+//!
+//! Large replies can be handed to a client as the file descriptor of an
+//! anonymous memory file instead of being copied through D-Bus messages.
+//! The file is sealed before it is sent, so its contents can no longer
+//! change, and its offset is left at the start for the reader.
+//!
+//! A [`ProgressCounter`] is a memory file of fixed size that stays
+//! writable, paired with an eventfd that is bumped on every update, for
+//! clients that follow an operation without polling the bus.
+
+use crate::error::{Result, SysError};
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::fs::FileExt;
+
+/// Seals that freeze the size and contents of a memory file
+const SEALS: libc::c_int =
+    libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE | libc::F_SEAL_SEAL;
+
+/// Seals that keep the size of a memory file but leave it writable
+const SIZE_SEALS: libc::c_int = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_SEAL;
+
+/// An anonymous memory file named `name` holding `data`, sealed against
+/// changes
+pub fn sealed_memfd(name: &str, data: &[u8]) -> Result<OwnedFd> {
+    let mut file = create_memfd(name)?;
+    file.write_all(data)?;
+    file.seek(SeekFrom::Start(0))?;
+    add_seals(&file, SEALS)?;
+    Ok(file.into())
+}
+
+fn create_memfd(name: &str) -> Result<File> {
+    let name = CString::new(name)
+        .map_err(|_| SysError::OperationFailed("Memory file name contains NUL".to_string()))?;
+    // SAFETY: name is a valid NUL-terminated string; the returned fd is
+    // checked before it is owned
+    let fd =
+        unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    // SAFETY: fd was just created and is owned by nothing else
+    Ok(File::from(unsafe { OwnedFd::from_raw_fd(fd) }))
+}
+
+fn add_seals(file: &File, seals: libc::c_int) -> Result<()> {
+    // SAFETY: fcntl on an fd we own
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_ADD_SEALS, seals) } < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// A memory file of fixed size the service rewrites, and an eventfd that
+/// wakes readers after each write
+#[derive(Debug)]
+pub struct ProgressCounter {
+    file: File,
+    event: File,
+}
+
+impl ProgressCounter {
+    /// A counter named `name` holding `size` zero bytes
+    pub fn new(name: &str, size: usize) -> Result<Self> {
+        let file = create_memfd(name)?;
+        file.set_len(size as u64)?;
+        add_seals(&file, SIZE_SEALS)?;
+
+        // SAFETY: plain syscall; the returned fd is checked before it is
+        // owned
+        let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // SAFETY: fd was just created and is owned by nothing else
+        let event = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+        Ok(Self { file, event })
+    }
+
+    /// Overwrite the counter with `record` and wake readers
+    pub fn publish(&self, record: &[u8]) -> Result<()> {
+        self.file.write_all_at(record, 0)?;
+        // A full eventfd already has readers to wake
+        match (&self.event).write(&1u64.to_ne_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::WouldBlock => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Duplicates of the memory file and eventfd, to hand to a client
+    pub fn fds(&self) -> Result<(OwnedFd, OwnedFd)> {
+        Ok((
+            self.file.as_fd().try_clone_to_owned()?,
+            self.event.as_fd().try_clone_to_owned()?,
+        ))
+    }
+}
+
+/// The first `buf.len()` bytes of the counter `fd`, read until two reads
+/// agree so that a concurrent write isn't seen half done
+pub fn read_counter(fd: &impl AsFd, buf: &mut [u8]) -> Result<()> {
+    let file = File::from(fd.as_fd().try_clone_to_owned()?);
+    let mut previous = vec![0; buf.len()];
+    file.read_exact_at(&mut previous, 0)?;
+    for _ in 0..8 {
+        file.read_exact_at(buf, 0)?;
+        if *buf == *previous {
+            return Ok(());
+        }
+        previous.copy_from_slice(buf);
+    }
+    Ok(())
+}
+
+/// Everything in the memory file `fd`, from its start
+pub fn read_memfd(fd: OwnedFd) -> Result<Vec<u8>> {
+    let mut file = File::from(fd);
+    file.seek(SeekFrom::Start(0))?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_memfd_round_trips_and_refuses_writes() {
+        let fd = sealed_memfd("storage-test", b"{\"pages\":2}").unwrap();
+        let mut file = File::from(fd.try_clone().unwrap());
+        assert!(file.write_all(b"x").is_err());
+        assert_eq!(read_memfd(fd).unwrap(), b"{\"pages\":2}");
+    }
+
+    #[test]
+    fn progress_counter_is_rewritable_at_a_fixed_size() {
+        let counter = ProgressCounter::new("storage-test", 8).unwrap();
+        let (fd, event) = counter.fds().unwrap();
+        counter.publish(&7u64.to_ne_bytes()).unwrap();
+        counter.publish(&9u64.to_ne_bytes()).unwrap();
+
+        let mut buf = [0; 8];
+        read_counter(&fd, &mut buf).unwrap();
+        assert_eq!(u64::from_ne_bytes(buf), 9);
+        // Both updates are pending on the eventfd
+        File::from(event).read_exact(&mut buf).unwrap();
+        assert_eq!(u64::from_ne_bytes(buf), 2);
+        assert!(File::from(fd).set_len(64).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Migrating a disk onto a larger one, following a [`MigrationPlan`]
+//!
+//! The plan runs as one operation: the target gets the source's partition
+//! table laid out again, each partition is copied, and its filesystem grows
+//! to fill the new space. When any step fails, the target's table is wiped
+//! so it is left blank rather than half migrated; the source is only read.
+
+use crate::alignment::in_use;
+use crate::error::{Result, SysError};
+use crate::partition_copy::{copy_partition, grow_copied_filesystem};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_types::MigrationPlan;
+use storage_types::migration::partition_device;
+use tracing::{info, warn};
+
+pub(crate) fn run(program: &str, args: &[&str], input: Option<&str>) -> Result<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take()
+        && let Some(input) = input
+    {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether `disk` or any of its partitions is in use
+pub(crate) fn disk_in_use(disk: &str) -> bool {
+    let name = disk.strip_prefix("/dev/").unwrap_or(disk);
+    let partitions = std::fs::read_dir(Path::new("/sys/class/block").join(name))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .filter(|entry| entry.starts_with(name) && entry != name)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    in_use(disk)
+        || partitions
+            .iter()
+            .any(|partition| in_use(&format!("/dev/{}", partition)))
+}
+
+/// Size in bytes and logical sector size of `disk`
+pub(crate) fn disk_geometry(disk: &str) -> Result<(u64, u64)> {
+    let name = disk.strip_prefix("/dev/").unwrap_or(disk);
+    let sys = Path::new("/sys/class/block").join(name);
+    let read = |attribute: &str| {
+        std::fs::read_to_string(sys.join(attribute))
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+    // sysfs counts the size in 512-byte units whatever the sector size
+    let size = read("size").ok_or_else(|| SysError::DeviceNotFound(disk.to_string()))? * 512;
+    let sector_size = read("queue/logical_block_size").unwrap_or(512);
+    Ok((size, sector_size))
+}
+
+/// Wait for udev to create the nodes of new partitions
+pub(crate) fn settle() {
+    if let Err(e) = run("udevadm", &["settle"], None) {
+        warn!("{e}");
+    }
+}
+
+/// Migrate `source` onto the larger `target` disk following `plan`
+///
+/// Everything on `target` is lost; neither disk may be in use. Progress
+/// counts the bytes copied, up to [`MigrationPlan::total_bytes`]. The
+/// migration stops with an error as soon as `cancelled` returns true.
+pub fn migrate_disk(
+    source: &str,
+    target: &str,
+    plan: &MigrationPlan,
+    mut on_progress: impl FnMut(u64),
+    cancelled: impl Fn() -> bool,
+) -> Result<()> {
+    if source == target {
+        return Err(SysError::OperationFailed(
+            "A disk cannot be migrated onto itself".to_string(),
+        ));
+    }
+    for disk in [source, target] {
+        if disk_in_use(disk) {
+            return Err(SysError::OperationFailed(format!(
+                "{} is in use; unmount or lock its partitions first",
+                disk
+            )));
+        }
+    }
+    let (target_size, target_sector_size) = disk_geometry(target)?;
+    plan.validate(target_size, target_sector_size)
+        .map_err(SysError::OperationFailed)?;
+    let dump = run("sfdisk", &["--dump", source], None)?;
+    let script = plan
+        .sfdisk_script(&dump, target, target_sector_size)
+        .map_err(SysError::OperationFailed)?;
+
+    info!(
+        "Migrating {} onto {} ({} partitions)",
+        source,
+        target,
+        plan.partitions.len()
+    );
+    run(
+        "sfdisk",
+        &["--wipe", "always", "--wipe-partitions", "always", target],
+        Some(&script),
+    )?;
+    settle();
+
+    let result: Result<()> = (|| {
+        let mut done = 0;
+        for partition in &plan.partitions {
+            let from = partition_device(source, partition.number);
+            let to = partition_device(target, partition.number);
+            copy_partition(&from, &to, |copied| on_progress(done + copied), &cancelled)?;
+            done += partition.source.size();
+            if partition.grows() {
+                grow_copied_filesystem(&to)?;
+            }
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = &result {
+        warn!("Migration onto {} failed, wiping its table: {}", target, e);
+        if let Err(e) = run("wipefs", &["--all", target], None) {
+            warn!("Failed to wipe {}: {}", target, e);
+        }
+    }
+    result
+}
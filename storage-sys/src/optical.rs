@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Blanking and burning optical discs with xorriso
+//!
+//! xorriso reports its progress on stderr while it works, which is passed
+//! on as the share of the work done. Burned images are verified by reading
+//! them back from the disc.
+
+use crate::error::{Result, SysError};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_types::{OpticalMediaInfo, parse_xorriso_media_info, parse_xorriso_progress};
+use tracing::{info, warn};
+
+/// Whether xorriso is installed
+pub fn xorriso_available() -> bool {
+    which::which("xorriso").is_ok()
+}
+
+/// The disc in the optical drive `device` (e.g., "/dev/sr0"), or `None`
+/// when the drive is empty
+pub fn optical_media_info(device: &str) -> Result<Option<OpticalMediaInfo>> {
+    let output = xorriso()
+        .args(["-outdev", device, "-toc", "-tell_media_space"])
+        .output()?;
+    // xorriso reports the medium on stderr when it acquires the drive
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(parse_xorriso_media_info(&text))
+}
+
+/// Erase the rewritable disc in `device`
+///
+/// A fast erase only invalidates the table of contents; a full one
+/// overwrites the whole disc.
+pub fn blank_optical_media(
+    device: &str,
+    full: bool,
+    on_progress: impl FnMut(f64),
+    cancelled: impl Fn() -> bool,
+) -> Result<()> {
+    info!(
+        "Erasing disc in {} ({})",
+        device,
+        if full { "full" } else { "fast" }
+    );
+    let mode = if full { "all" } else { "as_needed" };
+    run_with_progress(
+        xorriso().args(["-outdev", device, "-blank", mode]),
+        on_progress,
+        cancelled,
+    )
+}
+
+/// Burn the ISO image `image` to the disc in `device`, erasing a written
+/// rewritable disc first
+pub fn burn_optical_image(
+    device: &str,
+    image: &Path,
+    on_progress: impl FnMut(f64),
+    cancelled: impl Fn() -> bool,
+) -> Result<()> {
+    info!("Burning {} to {}", image.display(), device);
+    run_with_progress(
+        xorriso()
+            .args(["-as", "cdrecord", "-v", "blank=as_needed"])
+            .arg(format!("dev={device}"))
+            .arg(image),
+        on_progress,
+        cancelled,
+    )
+}
+
+/// Compare the disc in `device` with the image burned to it
+///
+/// Only the length of the image is read back, as discs may be padded past
+/// its end.
+pub fn verify_optical_image(
+    device: &str,
+    image: &Path,
+    mut on_progress: impl FnMut(u64),
+    cancelled: impl Fn() -> bool,
+) -> Result<()> {
+    let mut image_file = File::open(image)?;
+    let size = image_file.metadata()?.len();
+    let mut disc = File::open(device).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => {
+            SysError::PermissionDenied(format!("Cannot open {} for reading", device))
+        }
+        std::io::ErrorKind::NotFound => SysError::DeviceNotFound(device.to_string()),
+        _ => SysError::Io(e),
+    })?;
+
+    let mut expected = vec![0u8; 1024 * 1024];
+    let mut actual = vec![0u8; 1024 * 1024];
+    let mut verified: u64 = 0;
+    while verified < size {
+        if cancelled() {
+            return Err(SysError::OperationFailed(
+                "Verification cancelled".to_string(),
+            ));
+        }
+        let chunk = (size - verified).min(expected.len() as u64) as usize;
+        image_file.read_exact(&mut expected[..chunk])?;
+        disc.read_exact(&mut actual[..chunk]).map_err(|e| {
+            SysError::OperationFailed(format!(
+                "Failed to read the disc at byte {}: {}",
+                verified, e
+            ))
+        })?;
+        if expected[..chunk] != actual[..chunk] {
+            let offset = expected[..chunk]
+                .iter()
+                .zip(&actual[..chunk])
+                .position(|(a, b)| a != b)
+                .unwrap_or_default();
+            return Err(SysError::OperationFailed(format!(
+                "The disc differs from the image at byte {}",
+                verified + offset as u64
+            )));
+        }
+        verified += chunk as u64;
+        on_progress(verified);
+    }
+    Ok(())
+}
+
+/// xorriso, stopping at the first failure; further options are taken in
+/// order, so this has to come first
+fn xorriso() -> Command {
+    let mut command = Command::new("xorriso");
+    command.args(["-abort_on", "FAILURE"]).stdin(Stdio::null());
+    command
+}
+
+/// Run xorriso, passing on its progress until it exits
+///
+/// xorriso is killed once `cancelled` returns true, which is checked
+/// whenever it reports.
+fn run_with_progress(
+    command: &mut Command,
+    mut on_progress: impl FnMut(f64),
+    cancelled: impl Fn() -> bool,
+) -> Result<()> {
+    let mut child = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute xorriso: {}", e)))?;
+
+    let mut last_error = None;
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if cancelled() {
+                let _ = child.kill();
+                break;
+            }
+            if let Some(fraction) = parse_xorriso_progress(&line) {
+                on_progress(fraction);
+            } else if line.contains(": FAILURE :") || line.contains(": SORRY :") {
+                warn!("xorriso: {}", line);
+                last_error = Some(line.trim().to_string());
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    if cancelled() {
+        return Err(SysError::OperationFailed("Operation cancelled".to_string()));
+    }
+    if !status.success() {
+        return Err(SysError::OperationFailed(
+            last_error.unwrap_or_else(|| format!("xorriso exited with {}", status)),
+        ));
+    }
+    Ok(())
+}
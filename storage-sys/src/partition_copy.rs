@@ -0,0 +1,226 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Copying a partition onto another, at least as large, one
+//!
+//! The copy is made block by block, then the target's filesystem gets a new
+//! UUID so both can be attached at once, and grows to fill the target.
+
+use crate::alignment::in_use;
+use crate::error::{Result, SysError};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_types::partition_copy::{gets_new_uuid, grows_after_copy, validate_copy_target};
+use tracing::{debug, info, warn};
+
+/// Size of `device` in bytes
+fn device_size(file: &mut File) -> Result<u64> {
+    let size = file.seek(SeekFrom::End(0))?;
+    file.seek(SeekFrom::Start(0))?;
+    Ok(size)
+}
+
+fn open(device: &str, write: bool) -> Result<File> {
+    OpenOptions::new()
+        .read(!write)
+        .write(write)
+        .open(device)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                SysError::PermissionDenied(format!("Cannot open {}", device))
+            }
+            std::io::ErrorKind::NotFound => SysError::DeviceNotFound(device.to_string()),
+            _ => SysError::Io(e),
+        })
+}
+
+pub(crate) fn run_tool(program: &str, args: &[&str], input: Option<&str>) -> Result<()> {
+    debug!("Running {} {:?}", program, args);
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute {}: {}", program, e)))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    // e2fsck exits with 1 when it fixed something, which is fine here
+    let fixed = program == "e2fsck" && output.status.code() == Some(1);
+    if !output.status.success() && !fixed {
+        return Err(SysError::OperationFailed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Filesystem type on `device`, from blkid
+pub(crate) fn filesystem_type(device: &str) -> Option<String> {
+    let output = Command::new("blkid")
+        .args(["-o", "value", "-s", "TYPE", device])
+        .output()
+        .ok()?;
+    let fs_type = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!fs_type.is_empty()).then_some(fs_type)
+}
+
+/// Run `run` on `device` mounted at a temporary directory, for filesystems
+/// whose tools only work on them while mounted
+pub(crate) fn with_temporary_mount<T>(
+    device: &str,
+    run: impl FnOnce(&Path) -> Result<T>,
+) -> Result<T> {
+    let mount_point =
+        std::env::temp_dir().join(format!("cosmic-ext-storage-copy-{}", std::process::id()));
+    std::fs::create_dir_all(&mount_point)?;
+    let mount_path = mount_point.to_string_lossy();
+    let result = run_tool("mount", &[device, &mount_path], None).and_then(|()| {
+        let result = run(&mount_point);
+        run_tool("umount", &[&mount_path], None).and(result)
+    });
+    let _ = std::fs::remove_dir(&mount_point);
+    result
+}
+
+/// Copy `source` onto `target` block by block and return the bytes copied
+///
+/// `target` must be at least as large as `source`, and neither may be in
+/// use. The copy stops with an error as soon as `cancelled` returns true,
+/// leaving the target partly overwritten.
+pub fn copy_partition(
+    source: &str,
+    target: &str,
+    mut on_progress: impl FnMut(u64),
+    cancelled: impl Fn() -> bool,
+) -> Result<u64> {
+    for device in [source, target] {
+        if in_use(device) {
+            return Err(SysError::OperationFailed(format!(
+                "{} is in use; unmount or lock it first",
+                device
+            )));
+        }
+    }
+    let mut input = open(source, false)?;
+    let mut output = open(target, true)?;
+    let size = device_size(&mut input)?;
+    validate_copy_target(size, device_size(&mut output)?).map_err(SysError::OperationFailed)?;
+
+    info!(
+        "Copying partition {} onto {} ({} bytes)",
+        source, target, size
+    );
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut copied: u64 = 0;
+    while copied < size {
+        if cancelled() {
+            return Err(SysError::OperationFailed("Copy cancelled".to_string()));
+        }
+        let chunk = (size - copied).min(buffer.len() as u64) as usize;
+        input.read_exact(&mut buffer[..chunk])?;
+        output.write_all(&buffer[..chunk])?;
+        copied += chunk as u64;
+        on_progress(copied);
+    }
+    output.sync_all()?;
+    Ok(copied)
+}
+
+/// Check the filesystem on `device` where changing it needs that first, and
+/// return its type
+fn check_copied(device: &str) -> Result<Option<String>> {
+    let Some(fs_type) = filesystem_type(device) else {
+        warn!("No filesystem found on {} after copying", device);
+        return Ok(None);
+    };
+    if matches!(fs_type.as_str(), "ext2" | "ext3" | "ext4") {
+        // Both tune2fs -U and resize2fs want a freshly checked filesystem
+        run_tool("e2fsck", &["-f", "-y", device], None)?;
+    }
+    Ok(Some(fs_type))
+}
+
+/// Give the `fs_type` filesystem on `device` a new UUID
+fn renew_uuid(device: &str, fs_type: &str) -> Result<()> {
+    if !gets_new_uuid(fs_type) {
+        warn!(
+            "{} on {} keeps the UUID of the partition it was copied from",
+            fs_type, device
+        );
+        return Ok(());
+    }
+    match fs_type {
+        "ext2" | "ext3" | "ext4" => run_tool("tune2fs", &["-U", "random", device], None),
+        "xfs" => run_tool("xfs_admin", &["-U", "generate", device], None),
+        "btrfs" => run_tool("btrfstune", &["-f", "-u", device], None),
+        "ntfs" => run_tool("ntfslabel", &["--new-serial", device], None),
+        "vfat" => run_tool("fatlabel", &["-i", "-r", device], None),
+        "exfat" => run_tool("tune.exfat", &["-I", "random", device], None),
+        "swap" => run_tool("swaplabel", &["-U", "random", device], None),
+        _ => Ok(()),
+    }
+}
+
+/// Grow the `fs_type` filesystem on `device` to fill it
+fn grow_filesystem(device: &str, fs_type: &str) -> Result<()> {
+    if !grows_after_copy(fs_type) {
+        warn!(
+            "{} on {} cannot be grown and keeps its original size",
+            fs_type, device
+        );
+        return Ok(());
+    }
+    match fs_type {
+        "xfs" => with_temporary_mount(device, |mount_point| {
+            run_tool("xfs_growfs", &[&mount_point.to_string_lossy()], None)
+        }),
+        "btrfs" => with_temporary_mount(device, |mount_point| {
+            run_tool(
+                "btrfs",
+                &[
+                    "filesystem",
+                    "resize",
+                    "max",
+                    &mount_point.to_string_lossy(),
+                ],
+                None,
+            )
+        }),
+        // ntfsresize asks for confirmation even with --force
+        "ntfs" => run_tool("ntfsresize", &["--force", device], Some("y\n")),
+        _ => run_tool("resize2fs", &[device], None),
+    }
+}
+
+/// Grow the copied filesystem on `device` to fill it, keeping its UUID
+pub(crate) fn grow_copied_filesystem(device: &str) -> Result<()> {
+    match check_copied(device)? {
+        Some(fs_type) => grow_filesystem(device, &fs_type),
+        None => Ok(()),
+    }
+}
+
+/// Give the copied filesystem on `target` a new UUID and grow it to fill
+/// the partition
+///
+/// Filesystems that cannot be grown keep the size of the source; that is
+/// logged rather than failing the copy, which has already succeeded.
+pub fn finish_partition_copy(target: &str) -> Result<()> {
+    let Some(fs_type) = check_copied(target)? else {
+        return Ok(());
+    };
+    info!("Finishing copied {} filesystem on {}", fs_type, target);
+    renew_uuid(target, &fs_type)?;
+    grow_filesystem(target, &fs_type)
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Moving a partition to another place on its disk
+//!
+//! The contents are copied through the whole disk, piece by piece in the
+//! order `storage_types::move_pieces` gives, then sfdisk rewrites the table
+//! entry. A move that stops halfway leaves the contents split between both
+//! places, so it cannot be cancelled.
+
+use crate::alignment::{in_use, partition_in_layout, set_partition_start};
+use crate::error::{Result, SysError};
+use std::fs::OpenOptions;
+use std::os::unix::fs::FileExt;
+use storage_types::{MoveBounds, move_pieces};
+use tracing::info;
+
+/// Bytes copied at a time
+const PIECE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Where `partition` can move to on its disk
+pub fn partition_move_bounds(partition: &str) -> Result<MoveBounds> {
+    let (_, _, layout, range) = partition_in_layout(partition)?;
+    Ok(MoveBounds::new(partition, &layout, &range))
+}
+
+/// Move `partition` and its contents to start at byte `new_start`, within
+/// the bounds of [`partition_move_bounds`]
+///
+/// The partition must not be in use. `on_progress` gets the bytes moved so
+/// far and the size of the partition.
+pub fn move_partition(
+    partition: &str,
+    new_start: u64,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<()> {
+    if in_use(partition) {
+        return Err(SysError::OperationFailed(format!(
+            "{} is in use; unmount or lock it first",
+            partition
+        )));
+    }
+    let (disk, number, layout, range) = partition_in_layout(partition)?;
+    let bounds = MoveBounds::new(partition, &layout, &range);
+    bounds
+        .validate(new_start)
+        .map_err(SysError::OperationFailed)?;
+    if new_start == range.start {
+        return Ok(());
+    }
+
+    info!(
+        "Moving partition {} of {} from byte {} to {}",
+        number, disk, range.start, new_start
+    );
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&disk)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                SysError::PermissionDenied(format!("Cannot open {}", disk))
+            }
+            _ => SysError::Io(e),
+        })?;
+    let mut buffer = vec![0u8; PIECE_SIZE as usize];
+    let mut moved: u64 = 0;
+    for piece in move_pieces(range.start, new_start, bounds.size, PIECE_SIZE) {
+        let chunk = &mut buffer[..piece.size() as usize];
+        file.read_exact_at(chunk, range.start + piece.start)?;
+        file.write_all_at(chunk, new_start + piece.start)?;
+        moved += piece.size();
+        on_progress(moved, bounds.size);
+    }
+    file.sync_all()?;
+
+    set_partition_start(
+        &disk,
+        number,
+        new_start / layout.sector_size,
+        bounds.size / layout.sector_size,
+        false,
+    )
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! CPU and I/O priority of worker threads
+//!
+//! Operations run on threads of a shared blocking pool, so the priority is
+//! set on the calling thread alone and put back once the operation is over.
+//! Threads and tools the operation starts inherit it.
+
+use crate::error::Result;
+use storage_types::OperationPriority;
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+/// Runs the current thread with an operation's priority until dropped
+pub struct PriorityGuard {
+    tid: libc::pid_t,
+    nice: i32,
+    ioprio: i32,
+}
+
+impl PriorityGuard {
+    /// Apply `priority` to the current thread
+    pub fn apply(priority: &OperationPriority) -> Result<Self> {
+        let tid = unsafe { libc::gettid() };
+        let guard = Self {
+            tid,
+            nice: get_nice(tid)?,
+            ioprio: get_ioprio(tid)?,
+        };
+        set_nice(tid, priority.nice)?;
+        set_ioprio(tid, priority.ioprio())?;
+        tracing::debug!(
+            "Thread {tid} runs with nice {} and ioprio {:#x}",
+            priority.nice,
+            priority.ioprio()
+        );
+        Ok(guard)
+    }
+}
+
+impl Drop for PriorityGuard {
+    fn drop(&mut self) {
+        if let Err(e) = set_nice(self.tid, self.nice).and(set_ioprio(self.tid, self.ioprio)) {
+            tracing::warn!("Failed to restore the priority of thread {}: {e}", self.tid);
+        }
+    }
+}
+
+/// Apply `priority` to the current thread, logging instead of failing
+///
+/// A priority that cannot be set is no reason to fail the operation.
+pub fn apply_priority(priority: &OperationPriority) -> Option<PriorityGuard> {
+    PriorityGuard::apply(priority)
+        .inspect_err(|e| tracing::warn!("Failed to set the operation priority: {e}"))
+        .ok()
+}
+
+fn get_nice(tid: libc::pid_t) -> Result<i32> {
+    // -1 is a valid nice value, so errors are told apart through errno
+    unsafe { *libc::__errno_location() = 0 };
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, tid as libc::id_t) };
+    if nice == -1 {
+        let error = std::io::Error::last_os_error();
+        if error.raw_os_error() != Some(0) {
+            return Err(error.into());
+        }
+    }
+    Ok(nice)
+}
+
+fn set_nice(tid: libc::pid_t, nice: i32) -> Result<()> {
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+fn get_ioprio(tid: libc::pid_t) -> Result<i32> {
+    let ioprio = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, tid) };
+    if ioprio < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(ioprio as i32)
+}
+
+fn set_ioprio(tid: libc::pid_t, ioprio: i32) -> Result<()> {
+    if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, tid, ioprio) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
@@ -0,0 +1,432 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! MD-RAID array details
+//!
+//! Reads the kernel's view of an MD array from `/sys/block/mdX/md`: sync
+//! progress, mismatch count, bitmap location and the state of every member
+//! (`dev-*/state`, `dev-*/slot`).
+//!
+//! Spare groups and the auto-spare policy live in `mdadm.conf`, where
+//! `mdadm --monitor` picks them up.
+
+use crate::error::{Result, SysError};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use storage_types::{RaidDetail, RaidMember, RaidMemberState, SparePoolConfig, spare_groups};
+
+/// mdadm.conf locations, Debian-style first
+const MDADM_CONF_PATHS: [&str; 2] = ["/etc/mdadm/mdadm.conf", "/etc/mdadm.conf"];
+
+/// POLICY line written when new blank disks should become spares
+const AUTO_SPARE_POLICY: &str = "POLICY domain=cosmic-ext-storage path=* action=spare";
+
+/// Read the details of the MD array `device` (e.g., "/dev/md0" or "md0")
+pub fn raid_detail(device: &str) -> Result<RaidDetail> {
+    let device_path = if device.starts_with("/dev/") {
+        PathBuf::from(device)
+    } else {
+        Path::new("/dev").join(device)
+    };
+    // Resolves /dev/md/<name> links to the kernel name
+    let device_path = fs::canonicalize(&device_path).unwrap_or(device_path);
+    let name = device_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| SysError::DeviceNotFound(device.to_string()))?;
+
+    let md = Path::new("/sys/block").join(&name).join("md");
+    if !md.is_dir() {
+        return Err(SysError::DeviceNotFound(format!(
+            "{} is not an MD array",
+            device
+        )));
+    }
+
+    let mut members: Vec<RaidMember> = fs::read_dir(&md)?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("dev-"))
+        .filter_map(|entry| read_member(&entry.path()))
+        .collect();
+    members.sort_by_key(|member| (member.slot.is_none(), member.slot, member.device.clone()));
+
+    Ok(RaidDetail {
+        device: device_path.to_string_lossy().into_owned(),
+        level: read_attr(&md, "level").unwrap_or_default(),
+        array_state: read_attr(&md, "array_state").unwrap_or_default(),
+        sync_completed_percent: read_attr(&md, "sync_completed")
+            .and_then(|value| parse_sync_completed(&value)),
+        sync_action: read_attr(&md, "sync_action").unwrap_or_default(),
+        mismatch_count: read_attr(&md, "mismatch_cnt").and_then(|value| value.parse().ok()),
+        bitmap: read_attr(&md, "bitmap/location").and_then(|value| parse_bitmap_location(&value)),
+        raid_disks: read_attr(&md, "raid_disks")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        chunk_size: read_attr(&md, "chunk_size")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        // KiB
+        component_size: read_attr(&md, "component_size")
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(|kib| kib * 1024)
+            .unwrap_or_default(),
+        degraded: read_attr(&md, "degraded")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        members,
+    })
+}
+
+/// Device paths of all MD arrays known to the kernel
+pub fn list_arrays() -> Result<Vec<String>> {
+    let mut arrays: Vec<String> = fs::read_dir("/sys/block")?
+        .flatten()
+        .filter(|entry| entry.path().join("md").is_dir())
+        .map(|entry| format!("/dev/{}", entry.file_name().to_string_lossy()))
+        .collect();
+    arrays.sort();
+    Ok(arrays)
+}
+
+/// Spare groups of all arrays and the auto-spare policy from mdadm.conf
+pub fn spare_pool_config() -> Result<SparePoolConfig> {
+    let conf = fs::read_to_string(mdadm_conf_path()).unwrap_or_default();
+    let details: Vec<RaidDetail> = list_arrays()?
+        .iter()
+        .filter_map(|array| raid_detail(array).ok())
+        .collect();
+    let assignments: Vec<(String, String)> = parse_spare_groups(&conf)
+        .into_iter()
+        .map(|(array, group)| (canonical_device(&array), group))
+        .collect();
+
+    Ok(SparePoolConfig {
+        groups: spare_groups(&details, &assignments),
+        auto_add_new_disks: conf.lines().any(is_auto_spare_policy),
+    })
+}
+
+/// Put `array` into spare group `group`, or take it out of its group with `None`
+///
+/// Arrays missing from mdadm.conf get their `mdadm --detail --brief` line
+/// appended first.
+pub fn set_spare_group(array: &str, group: Option<&str>) -> Result<()> {
+    if group.is_some_and(|group| group.is_empty() || group.contains(char::is_whitespace)) {
+        return Err(SysError::OperationFailed(
+            "Spare group names must be non-empty and contain no whitespace".to_string(),
+        ));
+    }
+
+    let path = mdadm_conf_path();
+    let mut conf = fs::read_to_string(&path).unwrap_or_default();
+    let array = canonical_device(array);
+    let matches = |device: &str| canonical_device(device) == array;
+
+    if !has_array_line(&conf, matches) {
+        let output = Command::new("mdadm")
+            .args(["--detail", "--brief", &array])
+            .output()?;
+        if !output.status.success() {
+            return Err(SysError::OperationFailed(format!(
+                "mdadm --detail failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        if !conf.is_empty() && !conf.ends_with('\n') {
+            conf.push('\n');
+        }
+        conf.push_str(String::from_utf8_lossy(&output.stdout).trim());
+        conf.push('\n');
+    }
+
+    write_mdadm_conf(&path, &with_spare_group(&conf, matches, group))
+}
+
+/// Enable or disable turning new blank disks into spares
+pub fn set_auto_add_spares(enabled: bool) -> Result<()> {
+    let path = mdadm_conf_path();
+    let conf = fs::read_to_string(&path).unwrap_or_default();
+    write_mdadm_conf(&path, &with_auto_spare_policy(&conf, enabled))
+}
+
+/// The mdadm.conf in use, or where a new one should be created
+fn mdadm_conf_path() -> PathBuf {
+    MDADM_CONF_PATHS
+        .iter()
+        .map(Path::new)
+        .find(|path| path.exists())
+        .or_else(|| {
+            MDADM_CONF_PATHS
+                .iter()
+                .map(Path::new)
+                .find(|path| path.parent().is_some_and(Path::is_dir))
+        })
+        .unwrap_or(Path::new(MDADM_CONF_PATHS[1]))
+        .to_path_buf()
+}
+
+fn write_mdadm_conf(path: &Path, conf: &str) -> Result<()> {
+    // Write atomically; a truncated mdadm.conf can break assembly at boot
+    let tmp = path.with_extension("conf.tmp");
+    fs::write(&tmp, conf)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn canonical_device(device: &str) -> String {
+    fs::canonicalize(device)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| device.to_string())
+}
+
+/// Logical lines of mdadm.conf as (first, last) physical line indices;
+/// lines starting with whitespace continue the previous one
+fn logical_lines(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        match ranges.last_mut() {
+            Some(range) if line.starts_with([' ', '\t']) && !line.trim().is_empty() => {
+                range.1 = index
+            }
+            _ => ranges.push((index, index)),
+        }
+    }
+    ranges
+}
+
+/// Words of a logical line, without the comment
+fn words(lines: &[&str], (first, last): (usize, usize)) -> Vec<String> {
+    lines[first..=last]
+        .iter()
+        .flat_map(|line| {
+            line.split('#')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether `words` form an ARRAY line for a device `matches` accepts
+fn is_array_line(words: &[String], matches: impl Fn(&str) -> bool) -> bool {
+    words.first().is_some_and(|word| word == "ARRAY") && words.get(1).is_some_and(|d| matches(d))
+}
+
+fn has_array_line(conf: &str, matches: impl Fn(&str) -> bool) -> bool {
+    let lines: Vec<&str> = conf.lines().collect();
+    logical_lines(&lines)
+        .into_iter()
+        .any(|range| is_array_line(&words(&lines, range), &matches))
+}
+
+/// `(array, group)` pairs of the ARRAY lines with a `spare-group=`
+fn parse_spare_groups(conf: &str) -> Vec<(String, String)> {
+    let lines: Vec<&str> = conf.lines().collect();
+    logical_lines(&lines)
+        .into_iter()
+        .filter_map(|range| {
+            let words = words(&lines, range);
+            if !is_array_line(&words, |_| true) {
+                return None;
+            }
+            let group = words
+                .iter()
+                .find_map(|word| word.strip_prefix("spare-group="))?;
+            Some((words[1].clone(), group.to_string()))
+        })
+        .collect()
+}
+
+/// mdadm.conf with the `spare-group=` of the matching ARRAY lines set or removed
+fn with_spare_group(conf: &str, matches: impl Fn(&str) -> bool, group: Option<&str>) -> String {
+    let lines: Vec<&str> = conf.lines().collect();
+    let mut out = String::new();
+    for range in logical_lines(&lines) {
+        let mut words = words(&lines, range);
+        if is_array_line(&words, &matches) {
+            words.retain(|word| !word.starts_with("spare-group="));
+            if let Some(group) = group {
+                words.push(format!("spare-group={}", group));
+            }
+            out.push_str(&words.join(" "));
+            out.push('\n');
+        } else {
+            for line in &lines[range.0..=range.1] {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn is_auto_spare_policy(line: &str) -> bool {
+    line.trim() == AUTO_SPARE_POLICY
+}
+
+/// mdadm.conf with our auto-spare POLICY line added or removed
+fn with_auto_spare_policy(conf: &str, enabled: bool) -> String {
+    let mut out: String = conf
+        .lines()
+        .filter(|line| !is_auto_spare_policy(line))
+        .flat_map(|line| [line, "\n"])
+        .collect();
+    if enabled {
+        out.push_str(AUTO_SPARE_POLICY);
+        out.push('\n');
+    }
+    out
+}
+
+fn read_attr(dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+fn read_member(dir: &Path) -> Option<RaidMember> {
+    let block = fs::read_link(dir.join("block")).ok()?;
+    let name = block.file_name()?.to_string_lossy().into_owned();
+    // 512-byte sectors
+    let size = read_attr(&Path::new("/sys/class/block").join(&name), "size")
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(|sectors| sectors * 512)
+        .unwrap_or_default();
+    let device = format!("/dev/{}", name);
+    let flags: Vec<String> = read_attr(dir, "state")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|flag| !flag.is_empty())
+        .map(str::to_string)
+        .collect();
+    let slot = read_attr(dir, "slot").and_then(|value| value.parse().ok());
+
+    Some(RaidMember {
+        device,
+        slot,
+        state: member_state(&flags, slot),
+        errors: read_attr(dir, "errors")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        size,
+        flags,
+    })
+}
+
+/// Percentage from `sync_completed` ("<done> / <total>" sectors, or "none")
+fn parse_sync_completed(value: &str) -> Option<f64> {
+    let (done, total) = value.split_once('/')?;
+    let done: u64 = done.trim().parse().ok()?;
+    let total: u64 = total.trim().parse().ok()?;
+    (total > 0).then(|| (done as f64 / total as f64 * 100.0).min(100.0))
+}
+
+/// Bitmap location, None for "none"
+fn parse_bitmap_location(value: &str) -> Option<String> {
+    match value {
+        "" | "none" => None,
+        // Offset in sectors from the superblock
+        offset if offset.starts_with(['+', '-']) => Some(format!("internal ({})", offset)),
+        location => Some(location.to_string()),
+    }
+}
+
+/// Summarise the `state` flags of a member with its slot
+fn member_state(flags: &[String], slot: Option<u32>) -> RaidMemberState {
+    let has = |flag: &str| flags.iter().any(|f| f == flag);
+    if has("faulty") {
+        RaidMemberState::Faulty
+    } else if has("in_sync") {
+        RaidMemberState::InSync
+    } else if slot.is_some() {
+        RaidMemberState::Rebuilding
+    } else if has("spare") {
+        RaidMemberState::Spare
+    } else {
+        RaidMemberState::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(state: &str) -> Vec<String> {
+        state.split(',').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_sync_and_bitmap() {
+        assert_eq!(parse_sync_completed("none"), None);
+        assert_eq!(parse_sync_completed("500 / 1000"), Some(50.0));
+        assert_eq!(parse_sync_completed("0 / 0"), None);
+        assert_eq!(parse_bitmap_location("none"), None);
+        assert_eq!(
+            parse_bitmap_location("+8"),
+            Some("internal (+8)".to_string())
+        );
+        assert_eq!(parse_bitmap_location("file"), Some("file".to_string()));
+    }
+
+    #[test]
+    fn summarises_member_state() {
+        assert_eq!(
+            member_state(&flags("in_sync"), Some(0)),
+            RaidMemberState::InSync
+        );
+        assert_eq!(
+            member_state(&flags("faulty,in_sync"), Some(1)),
+            RaidMemberState::Faulty
+        );
+        assert_eq!(member_state(&flags("spare"), None), RaidMemberState::Spare);
+        assert_eq!(
+            member_state(&flags("spare"), Some(1)),
+            RaidMemberState::Rebuilding
+        );
+        assert_eq!(
+            member_state(&flags("blocked"), None),
+            RaidMemberState::Unknown
+        );
+    }
+
+    const CONF: &str = "# mdadm.conf
+DEVICE partitions
+ARRAY /dev/md0 metadata=1.2 UUID=a:b:c:d
+ARRAY /dev/md1 metadata=1.2
+    UUID=e:f:g:h spare-group=pool # shared
+ARRAY /dev/md2 UUID=i:j:k:l spare-group=pool
+";
+
+    #[test]
+    fn parses_spare_groups() {
+        assert_eq!(
+            parse_spare_groups(CONF),
+            [
+                ("/dev/md1".to_string(), "pool".to_string()),
+                ("/dev/md2".to_string(), "pool".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn edits_spare_groups() {
+        let conf = with_spare_group(CONF, |device| device == "/dev/md0", Some("pool"));
+        assert!(conf.contains("ARRAY /dev/md0 metadata=1.2 UUID=a:b:c:d spare-group=pool\n"));
+        assert!(conf.starts_with("# mdadm.conf\nDEVICE partitions\n"));
+
+        let conf = with_spare_group(&conf, |device| device == "/dev/md1", None);
+        assert!(conf.contains("ARRAY /dev/md1 metadata=1.2 UUID=e:f:g:h\n"));
+        assert_eq!(parse_spare_groups(&conf).len(), 2);
+        assert!(has_array_line(&conf, |device| device == "/dev/md2"));
+        assert!(!has_array_line(&conf, |device| device == "/dev/md3"));
+    }
+
+    #[test]
+    fn toggles_auto_spare_policy() {
+        let enabled = with_auto_spare_policy(CONF, true);
+        assert!(enabled.lines().any(is_auto_spare_policy));
+        assert_eq!(with_auto_spare_policy(&enabled, true), enabled);
+        assert_eq!(with_auto_spare_policy(&enabled, false), CONF);
+    }
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Encrypted configuration files
+//!
+//! rclone can encrypt its configuration file with a password. Such a file is
+//! read through `rclone config show`, which decrypts it with the password
+//! passed in `RCLONE_CONFIG_PASS`. It is written by saving the plain
+//! configuration to a private temporary file next to it, encrypting that
+//! with `rclone config encryption set` and moving it over the original.
+
+use super::RCloneCli;
+use crate::error::{Result, SysError};
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use storage_types::is_encrypted_config;
+use tracing::{info, warn};
+
+/// Variable rclone reads the configuration password from
+pub(super) const PASSWORD_ENV: &str = "RCLONE_CONFIG_PASS";
+
+/// Variable the `--password-command` of `rclone config encryption set`
+/// reads the new password from
+const NEW_PASSWORD_ENV: &str = "COSMIC_STORAGE_RCLONE_NEW_PASS";
+
+impl RCloneCli {
+    /// Whether the configuration file is encrypted
+    pub fn is_config_encrypted(config_path: &Path) -> bool {
+        std::fs::read_to_string(config_path).is_ok_and(|contents| is_encrypted_config(&contents))
+    }
+
+    /// Check the password of an encrypted configuration file
+    pub fn check_config_password(&self, config_path: &Path) -> Result<()> {
+        self.decrypt_config(config_path).map(|_| ())
+    }
+
+    /// Encrypt a plain configuration file with `password`
+    pub fn encrypt_config(&self, config_path: &Path, password: &str) -> Result<()> {
+        info!("Encrypting config {:?}", config_path);
+
+        let contents = std::fs::read_to_string(config_path).map_err(|e| {
+            SysError::RCloneConfigParse(format!("Failed to read configuration file: {}", e))
+        })?;
+        if is_encrypted_config(&contents) {
+            return Err(SysError::OperationFailed(
+                "The configuration is already encrypted".to_string(),
+            ));
+        }
+
+        self.replace_encrypted(config_path, &contents, password)
+    }
+
+    /// Error for a configuration file that could not be decrypted
+    pub(super) fn locked_error(&self, config_path: &Path) -> SysError {
+        match self.config_password {
+            Some(_) => SysError::RCloneConfigPassword,
+            None => SysError::RCloneConfigLocked(config_path.display().to_string()),
+        }
+    }
+
+    /// Plain contents of an encrypted configuration file
+    pub(super) fn decrypt_config(&self, config_path: &Path) -> Result<String> {
+        if self.config_password.is_none() {
+            return Err(self.locked_error(config_path));
+        }
+
+        let output = self
+            .command(&["config", "show"])
+            .arg("--config")
+            .arg(config_path)
+            .output()
+            .map_err(|e| SysError::OperationFailed(format!("Failed to execute rclone: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!("rclone config show failed: {}", stderr);
+            if stderr.to_lowercase().contains("password") {
+                return Err(SysError::RCloneConfigPassword);
+            }
+            return Err(SysError::RCloneConfigParse(format!(
+                "Failed to decrypt configuration: {}",
+                stderr
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Replace an encrypted configuration file with `contents`, encrypted
+    /// with the password it was unlocked with
+    pub(super) fn write_encrypted(&self, config_path: &Path, contents: &str) -> Result<()> {
+        let password = self
+            .config_password
+            .as_deref()
+            .ok_or_else(|| self.locked_error(config_path))?;
+        self.check_config_password(config_path)?;
+        self.replace_encrypted(config_path, contents, password)
+    }
+
+    fn replace_encrypted(&self, config_path: &Path, contents: &str, password: &str) -> Result<()> {
+        let mut name = config_path.as_os_str().to_os_string();
+        name.push(".cosmic-tmp");
+        let tmp = PathBuf::from(name);
+
+        let result = self.encrypt_to(&tmp, contents, password).and_then(|()| {
+            // Keep the owner of the original, e.g. the user of a user config
+            if let Ok(metadata) = std::fs::metadata(config_path) {
+                std::os::unix::fs::chown(&tmp, Some(metadata.uid()), Some(metadata.gid()))?;
+            }
+            std::fs::rename(&tmp, config_path)?;
+            Ok(())
+        });
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        result
+    }
+
+    /// Write `contents` to the new file `path` and encrypt it
+    fn encrypt_to(&self, path: &Path, contents: &str, password: &str) -> Result<()> {
+        let _ = std::fs::remove_file(path);
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)?;
+        std::io::Write::write_all(&mut file, contents.as_bytes())?;
+        drop(file);
+
+        let output = Command::new(&self.binary_path)
+            .args(["config", "encryption", "set", "--config"])
+            .arg(path)
+            .arg("--password-command")
+            .arg(format!("printenv {NEW_PASSWORD_ENV}"))
+            .env(NEW_PASSWORD_ENV, password)
+            .env_remove(PASSWORD_ENV)
+            .output()
+            .map_err(|e| SysError::OperationFailed(format!("Failed to execute rclone: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!("rclone config encryption set failed: {}", stderr);
+            return Err(SysError::OperationFailed(format!(
+                "Failed to encrypt configuration: {}",
+                stderr
+            )));
+        }
+        Ok(())
+    }
+}
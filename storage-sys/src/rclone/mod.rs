@@ -11,21 +11,29 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Command;
 use std::time::Instant;
-use storage_types::ConfigScope;
+use storage_types::{ConfigScope, LoginMountStatus, RemoteUsage, is_encrypted_config};
 use tracing::{debug, info, warn};
 use which::which;
 
+mod encryption;
 mod mount_state;
 mod systemd;
-mod unix_user;
+mod transfer;
+pub(crate) mod unix_user;
+
+pub use transfer::RCloneTransfer;
 
 use mount_state::is_mounted;
 use unix_user::{chown_path, uid_gid_for_uid};
 
 /// RClone CLI wrapper for low-level operations
+#[derive(Clone)]
 pub struct RCloneCli {
     /// Path to the rclone binary
     binary_path: PathBuf,
+
+    /// Password of an encrypted configuration file
+    config_password: Option<String>,
 }
 
 impl RCloneCli {
@@ -35,7 +43,30 @@ impl RCloneCli {
     pub fn new() -> Result<Self> {
         let binary_path = Self::find_rclone_binary()?;
         info!("Found rclone binary at {:?}", binary_path);
-        Ok(Self { binary_path })
+        Ok(Self {
+            binary_path,
+            config_password: None,
+        })
+    }
+
+    /// Copy of this wrapper that decrypts encrypted configuration files with
+    /// `password`
+    pub fn with_config_password(&self, password: Option<String>) -> Self {
+        Self {
+            binary_path: self.binary_path.clone(),
+            config_password: password,
+        }
+    }
+
+    /// rclone command running `subcommand`, never prompting for the
+    /// configuration password
+    fn command(&self, subcommand: &[&str]) -> Command {
+        let mut command = Command::new(&self.binary_path);
+        command.args(subcommand).arg("--ask-password=false");
+        if let Some(password) = &self.config_password {
+            command.env(encryption::PASSWORD_ENV, password);
+        }
+        command
     }
 
     /// Find the rclone binary in PATH
@@ -52,8 +83,8 @@ impl RCloneCli {
     pub fn list_remotes(&self, config_path: &PathBuf) -> Result<Vec<String>> {
         debug!("Listing remotes from {:?}", config_path);
 
-        let output = Command::new(&self.binary_path)
-            .arg("listremotes")
+        let output = self
+            .command(&["listremotes"])
             .arg("--config")
             .arg(config_path)
             .output()
@@ -62,6 +93,9 @@ impl RCloneCli {
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             warn!("rclone listremotes failed: {}", stderr);
+            if Self::is_config_encrypted(config_path) {
+                return Err(self.locked_error(config_path));
+            }
             return Err(SysError::RCloneConfigParse(format!(
                 "Failed to list remotes: {}",
                 stderr
@@ -95,6 +129,12 @@ impl RCloneCli {
             SysError::RCloneConfigParse(format!("Failed to read configuration file: {}", e))
         })?;
 
+        let content = if is_encrypted_config(&content) {
+            self.decrypt_config(config_path)?
+        } else {
+            content
+        };
+
         // Check if the file is empty
         if content.trim().is_empty() {
             warn!("Configuration file is empty: {:?}", config_path);
@@ -179,8 +219,8 @@ impl RCloneCli {
 
         let remote_path = format!("{}:", remote_name);
 
-        let output = Command::new(&self.binary_path)
-            .arg("mount")
+        let output = self
+            .command(&["mount"])
             .arg(&remote_path)
             .arg(mount_point)
             .arg("--config")
@@ -254,8 +294,8 @@ impl RCloneCli {
         let remote_path = format!("{}:", remote_name);
         let start = Instant::now();
 
-        let output = Command::new(&self.binary_path)
-            .arg("ls")
+        let output = self
+            .command(&["ls"])
             .arg(&remote_path)
             .arg("--config")
             .arg(config_path)
@@ -283,6 +323,40 @@ impl RCloneCli {
         }
     }
 
+    /// Query quota and usage of a remote using `rclone about`
+    ///
+    /// Returns `None` if the backend of the remote cannot report usage.
+    pub fn about(&self, remote_name: &str, config_path: &PathBuf) -> Result<Option<RemoteUsage>> {
+        debug!("Querying usage of remote {}", remote_name);
+
+        let output = self
+            .command(&["about"])
+            .arg(format!("{}:", remote_name))
+            .arg("--json")
+            .arg("--config")
+            .arg(config_path)
+            .output()
+            .map_err(|e| SysError::OperationFailed(format!("Failed to execute rclone: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("doesn't support about") {
+                debug!("Remote {} does not report usage", remote_name);
+                return Ok(None);
+            }
+            warn!("rclone about {} failed: {}", remote_name, stderr);
+            return Err(SysError::OperationFailed(format!(
+                "Failed to query usage of {}: {}",
+                remote_name,
+                stderr.trim()
+            )));
+        }
+
+        RemoteUsage::from_about_json(&String::from_utf8_lossy(&output.stdout))
+            .map(Some)
+            .map_err(|e| SysError::OperationFailed(format!("Invalid rclone about output: {}", e)))
+    }
+
     /// Write configuration back to file
     pub fn write_config(
         &self,
@@ -308,8 +382,13 @@ impl RCloneCli {
             })?;
         }
 
-        conf.write(config_path)
-            .map_err(|e| SysError::RCloneConfigParse(format!("Failed to write config: {}", e)))?;
+        if Self::is_config_encrypted(config_path) {
+            self.write_encrypted(config_path, &conf.writes())?;
+        } else {
+            conf.write(config_path).map_err(|e| {
+                SysError::RCloneConfigParse(format!("Failed to write config: {}", e))
+            })?;
+        }
 
         info!("Successfully wrote config to {:?}", config_path);
         Ok(())
@@ -320,7 +399,7 @@ impl std::fmt::Debug for RCloneCli {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RCloneCli")
             .field("binary_path", &self.binary_path)
-            .finish()
+            .finish_non_exhaustive()
     }
 }
 
@@ -342,3 +421,26 @@ pub fn is_mount_on_boot_enabled(
 ) -> Result<bool> {
     systemd::is_mount_on_boot_enabled(scope, remote_name, uid, home)
 }
+
+/// Whether `remote_name` is mounted automatically, at login for user
+/// remotes and at boot for system ones, and the state of that mount
+pub fn login_mount_status(
+    scope: ConfigScope,
+    remote_name: &str,
+    uid: Option<u32>,
+    home: Option<&std::path::Path>,
+) -> Result<LoginMountStatus> {
+    systemd::login_mount_status(scope, remote_name, uid, home)
+}
+
+/// Mount `remote_name` at a custom mount point when it is mounted
+/// automatically, or at the default one when `mount_point` is `None`
+pub fn set_mount_point_override(
+    scope: ConfigScope,
+    remote_name: &str,
+    mount_point: Option<&std::path::Path>,
+    uid: Option<u32>,
+    home: Option<&std::path::Path>,
+) -> Result<()> {
+    systemd::set_mount_point_override(scope, remote_name, mount_point, uid, home)
+}
@@ -1,9 +1,9 @@
 use crate::error::{Result, SysError};
 use crate::rclone::RCloneCli;
 use crate::rclone::unix_user::username_for_uid;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use storage_types::ConfigScope;
+use storage_types::{ConfigScope, LoginMountState, LoginMountStatus};
 use which::which;
 
 const SYSTEMD_UNIT_PREFIX: &str = "storage-rclone-mount";
@@ -16,6 +16,17 @@ fn systemd_template_name() -> String {
     format!("{}@.service", SYSTEMD_UNIT_PREFIX)
 }
 
+/// Drop-in of a remote's unit that moves its mount to a custom mount point
+fn mount_point_drop_in(
+    scope: ConfigScope,
+    remote_name: &str,
+    home: Option<&std::path::Path>,
+) -> Result<PathBuf> {
+    Ok(systemd_unit_dir(scope, home)?
+        .join(format!("{}.d", systemd_unit_name(remote_name)))
+        .join("mount-point.conf"))
+}
+
 fn systemd_unit_dir(scope: ConfigScope, home: Option<&std::path::Path>) -> Result<PathBuf> {
     match scope {
         ConfigScope::User => {
@@ -71,24 +82,44 @@ fn run_systemctl(
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// Default mount prefix, rclone config and install target of the units of
+/// a scope, in systemd specifier syntax
+fn scope_unit_paths(scope: ConfigScope) -> (&'static str, &'static str, &'static str) {
+    match scope {
+        ConfigScope::User => ("%h/mnt", "%h/.config/rclone/rclone.conf", "default.target"),
+        ConfigScope::System => ("/mnt/rclone", "/etc/rclone.conf", "multi-user.target"),
+    }
+}
+
+/// Binaries run by the units: rclone, mkdir and fusermount
+fn unit_tools() -> Result<(PathBuf, PathBuf, PathBuf)> {
+    let rclone_path = RCloneCli::find_rclone_binary()?;
+    let mkdir_path = which("mkdir")
+        .map_err(|e| SysError::OperationFailed(format!("Failed to locate mkdir: {}", e)))?;
+    let fusermount_path = which("fusermount3")
+        .or_else(|_| which("fusermount"))
+        .map_err(|e| SysError::OperationFailed(format!("Failed to locate fusermount: {}", e)))?;
+    Ok((rclone_path, mkdir_path, fusermount_path))
+}
+
+/// Quote a path for an `Exec` line, escaping specifiers
+fn quote_exec_path(path: &Path) -> String {
+    let escaped = path
+        .display()
+        .to_string()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{escaped}\"")
+}
+
 fn build_unit_contents(
     scope: ConfigScope,
     rclone_path: &std::path::Path,
     mkdir_path: &std::path::Path,
     fusermount_path: &std::path::Path,
 ) -> String {
-    let (mount_prefix, config_path, wanted_by) = match scope {
-        ConfigScope::User => (
-            "%h/mnt".to_string(),
-            "%h/.config/rclone/rclone.conf".to_string(),
-            "default.target".to_string(),
-        ),
-        ConfigScope::System => (
-            "/mnt/rclone".to_string(),
-            "/etc/rclone.conf".to_string(),
-            "multi-user.target".to_string(),
-        ),
-    };
+    let (mount_prefix, config_path, wanted_by) = scope_unit_paths(scope);
 
     format!(
         "[Unit]\n\
@@ -127,12 +158,7 @@ fn ensure_systemd_template(
 
     let unit_path = unit_dir.join(systemd_template_name());
 
-    let rclone_path = RCloneCli::find_rclone_binary()?;
-    let mkdir_path = which("mkdir")
-        .map_err(|e| SysError::OperationFailed(format!("Failed to locate mkdir: {}", e)))?;
-    let fusermount_path = which("fusermount3")
-        .or_else(|_| which("fusermount"))
-        .map_err(|e| SysError::OperationFailed(format!("Failed to locate fusermount: {}", e)))?;
+    let (rclone_path, mkdir_path, fusermount_path) = unit_tools()?;
 
     let contents = build_unit_contents(scope, &rclone_path, &mkdir_path, &fusermount_path);
 
@@ -149,6 +175,79 @@ fn ensure_systemd_template(
     Ok(unit_path)
 }
 
+fn build_mount_point_drop_in(
+    scope: ConfigScope,
+    mount_point: &Path,
+    rclone_path: &Path,
+    mkdir_path: &Path,
+    fusermount_path: &Path,
+) -> String {
+    let (_, config_path, _) = scope_unit_paths(scope);
+    let mount_point = quote_exec_path(mount_point);
+
+    format!(
+        "[Service]\n\
+ExecStartPre=\n\
+ExecStartPre={} -p {mount_point}\n\
+ExecStart=\n\
+ExecStart={} mount %i: {mount_point} --config {config_path} --vfs-cache-mode writes\n\
+ExecStop=\n\
+ExecStop={} -u {mount_point}\n",
+        mkdir_path.display(),
+        rclone_path.display(),
+        fusermount_path.display(),
+    )
+}
+
+/// Point the unit of `remote_name` at a custom mount point, or back at the
+/// default one when `mount_point` is `None`
+pub(crate) fn set_mount_point_override(
+    scope: ConfigScope,
+    remote_name: &str,
+    mount_point: Option<&Path>,
+    uid: Option<u32>,
+    home: Option<&std::path::Path>,
+) -> Result<()> {
+    let drop_in = mount_point_drop_in(scope, remote_name, home)?;
+    let existing = std::fs::read_to_string(&drop_in).ok();
+
+    let changed = match mount_point {
+        Some(mount_point) => {
+            let (rclone_path, mkdir_path, fusermount_path) = unit_tools()?;
+            let contents = build_mount_point_drop_in(
+                scope,
+                mount_point,
+                &rclone_path,
+                &mkdir_path,
+                &fusermount_path,
+            );
+            if existing.as_deref() == Some(contents.as_str()) {
+                false
+            } else {
+                if let Some(dir) = drop_in.parent() {
+                    std::fs::create_dir_all(dir).map_err(SysError::Io)?;
+                }
+                std::fs::write(&drop_in, contents).map_err(SysError::Io)?;
+                true
+            }
+        }
+        None if existing.is_some() => {
+            std::fs::remove_file(&drop_in).map_err(SysError::Io)?;
+            if let Some(dir) = drop_in.parent() {
+                // Only succeeds if no other drop-ins are left
+                let _ = std::fs::remove_dir(dir);
+            }
+            true
+        }
+        None => false,
+    };
+
+    if changed {
+        run_systemctl(scope, uid, home, &["daemon-reload"])?;
+    }
+    Ok(())
+}
+
 pub(crate) fn set_mount_on_boot(
     scope: ConfigScope,
     remote_name: &str,
@@ -188,3 +287,88 @@ pub(crate) fn is_mount_on_boot_enabled(
     let status = stdout.trim();
     Ok(status == "enabled" || status == "enabled-runtime")
 }
+
+/// Whether the unit of `remote_name` is enabled and how it is doing
+pub(crate) fn login_mount_status(
+    scope: ConfigScope,
+    remote_name: &str,
+    uid: Option<u32>,
+    home: Option<&std::path::Path>,
+) -> Result<LoginMountStatus> {
+    let unit_name = systemd_unit_name(remote_name);
+    let output = run_systemctl(
+        scope,
+        uid,
+        home,
+        &["show", "--property=UnitFileState,ActiveState", &unit_name],
+    )?;
+
+    let property = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .unwrap_or_default()
+    };
+    let state = LoginMountState::from_systemd(property("UnitFileState"), property("ActiveState"));
+    let detail = if state == LoginMountState::Failed {
+        last_log_message(scope, &unit_name, uid)
+    } else {
+        None
+    };
+
+    Ok(LoginMountStatus {
+        remote_name: remote_name.to_string(),
+        scope,
+        state,
+        detail,
+    })
+}
+
+/// Last line rclone logged in the unit, which usually says why it failed
+fn last_log_message(scope: ConfigScope, unit_name: &str, uid: Option<u32>) -> Option<String> {
+    let mut command = Command::new("journalctl");
+    match (scope, uid) {
+        (ConfigScope::User, Some(uid)) => {
+            command.arg(format!("_UID={uid}"));
+            command.arg(format!("_SYSTEMD_USER_UNIT={unit_name}"));
+        }
+        (ConfigScope::User, None) => {
+            command.arg(format!("_SYSTEMD_USER_UNIT={unit_name}"));
+        }
+        (ConfigScope::System, _) => {
+            command.arg(format!("_SYSTEMD_UNIT={unit_name}"));
+        }
+    }
+
+    let output = command
+        .args(["--lines=1", "--output=cat", "--no-pager", "--quiet"])
+        .output()
+        .ok()?;
+    let message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !message.is_empty()).then_some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_in_quotes_custom_mount_point() {
+        let contents = build_mount_point_drop_in(
+            ConfigScope::User,
+            Path::new("/home/u/My Cloud 100%"),
+            Path::new("/usr/bin/rclone"),
+            Path::new("/usr/bin/mkdir"),
+            Path::new("/usr/bin/fusermount3"),
+        );
+
+        assert!(contents.starts_with("[Service]\nExecStartPre=\n"));
+        assert!(contents.contains(
+            "ExecStart=/usr/bin/rclone mount %i: \"/home/u/My Cloud 100%%\" \
+             --config %h/.config/rclone/rclone.conf --vfs-cache-mode writes\n"
+        ));
+        assert!(
+            contents.ends_with("ExecStop=/usr/bin/fusermount3 -u \"/home/u/My Cloud 100%%\"\n")
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Copy and sync jobs
+//!
+//! Transfers run `rclone copy` or `rclone sync` with a JSON log and
+//! statistics every second, so their progress and errors are read from the
+//! log while they run.
+
+use super::RCloneCli;
+use super::unix_user::uid_gid_for_uid;
+use crate::error::{Result, SysError};
+use serde::Deserialize;
+use std::io::{BufRead, BufReader};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Stdio};
+use storage_types::{TransferProgress, TransferRequest};
+use tracing::{info, warn};
+
+/// A running `rclone copy` or `rclone sync`
+pub struct RCloneTransfer {
+    child: Child,
+}
+
+#[derive(Deserialize)]
+struct LogLine {
+    level: String,
+    msg: String,
+}
+
+/// Message of an error line of rclone's JSON log
+fn log_error(line: &str) -> Option<String> {
+    let line: LogLine = serde_json::from_str(line).ok()?;
+    matches!(line.level.as_str(), "error" | "critical").then_some(line.msg)
+}
+
+impl RCloneCli {
+    /// Start a transfer between the endpoints of `request`, with the remotes
+    /// configured in `config_path`
+    ///
+    /// With a `uid`, rclone runs as that user, so it can only read and write
+    /// the local files the user can.
+    pub fn start_transfer(
+        &self,
+        request: &TransferRequest,
+        config_path: &Path,
+        uid: Option<u32>,
+        home: Option<&Path>,
+    ) -> Result<RCloneTransfer> {
+        info!(
+            "Starting rclone {} from {} to {}",
+            request.mode.subcommand(),
+            request.source,
+            request.destination
+        );
+
+        let mut command = self.command(&[request.mode.subcommand()]);
+        command
+            .arg(request.source.to_rclone_arg())
+            .arg(request.destination.to_rclone_arg())
+            .arg("--config")
+            .arg(config_path)
+            .args([
+                "--use-json-log",
+                "--stats",
+                "1s",
+                "--stats-log-level",
+                "NOTICE",
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        if let Some(limit) = &request.bandwidth_limit {
+            command.arg("--bwlimit").arg(limit);
+        }
+        if request.dry_run {
+            command.arg("--dry-run");
+        }
+        if let Some(uid) = uid {
+            let (uid, gid) = uid_gid_for_uid(uid)
+                .ok_or_else(|| SysError::OperationFailed(format!("Unknown user {uid}")))?;
+            command.uid(uid).gid(gid);
+            if let Some(home) = home {
+                command.env("HOME", home).current_dir(home);
+            }
+        }
+
+        let child = command.spawn().map_err(|e| {
+            SysError::RCloneTransferFailed(format!("Failed to execute rclone: {}", e))
+        })?;
+        Ok(RCloneTransfer { child })
+    }
+}
+
+impl RCloneTransfer {
+    /// Follow the transfer until rclone exits, passing on its progress
+    ///
+    /// rclone is stopped once `cancelled` returns true, which is checked
+    /// whenever rclone logs, so at least every second.
+    pub fn follow(
+        mut self,
+        mut on_progress: impl FnMut(TransferProgress),
+        cancelled: impl Fn() -> bool,
+    ) -> Result<()> {
+        let mut last_error = None;
+        if let Some(stderr) = self.child.stderr.take() {
+            for line in BufReader::new(stderr).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if cancelled() {
+                    let _ = self.child.kill();
+                    break;
+                }
+                if let Some(progress) = TransferProgress::from_json_log(&line) {
+                    on_progress(progress);
+                } else if let Some(message) = log_error(&line) {
+                    warn!("rclone transfer error: {}", message);
+                    last_error = Some(message);
+                }
+            }
+        }
+
+        let status = self.child.wait()?;
+        if cancelled() {
+            return Err(SysError::RCloneTransferCancelled);
+        }
+        if !status.success() {
+            return Err(SysError::RCloneTransferFailed(
+                last_error.unwrap_or_else(|| format!("rclone exited with {}", status)),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_errors_from_json_log() {
+        let error = r#"{"level":"error","msg":"photo.jpg: Failed to copy: permission denied","source":"operations/copy.go:322"}"#;
+        assert_eq!(
+            log_error(error).as_deref(),
+            Some("photo.jpg: Failed to copy: permission denied")
+        );
+        assert_eq!(
+            log_error(r#"{"level":"notice","msg":"Transferred: 0 B"}"#),
+            None
+        );
+        assert_eq!(log_error("not json"), None);
+    }
+}
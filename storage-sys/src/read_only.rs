@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Filesystems the kernel switched to read-only after errors
+//!
+//! Read-only mounts are taken from `/proc/mounts` and kept when the kernel
+//! log shows the kernel made them read-only, rather than them being mounted
+//! that way.
+
+use crate::error::{Result, SysError};
+use std::path::Path;
+use std::process::Command;
+use storage_types::{ForcedReadOnly, find_forced_read_only, parse_read_only_mounts};
+use tracing::{info, warn};
+
+/// Mounted filesystems that went read-only after an error
+pub fn forced_read_only_filesystems() -> Result<Vec<ForcedReadOnly>> {
+    let mounts = std::fs::read_to_string("/proc/mounts")?;
+    let candidates = parse_read_only_mounts(&mounts);
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let log = kernel_log();
+    Ok(candidates
+        .into_iter()
+        .filter_map(|mut filesystem| {
+            // Device mapper devices are logged by their kernel name ("dm-0")
+            let device = std::fs::canonicalize(&filesystem.device).ok()?;
+            let name = device.file_name()?.to_string_lossy().to_string();
+            filesystem.kernel_error = Some(find_forced_read_only(&log, &name)?);
+            Some(filesystem)
+        })
+        .collect())
+}
+
+/// Make the filesystem at `mount_point` writable again
+///
+/// The kernel refuses while the error that made it read-only still stands,
+/// which usually needs a repair first.
+pub fn remount_read_write(mount_point: &Path) -> Result<()> {
+    info!("Remounting {} read-write", mount_point.display());
+    let output = Command::new("mount")
+        .args(["-o", "remount,rw"])
+        .arg(mount_point)
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute mount: {}", e)))?;
+    if !output.status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "Failed to remount {} read-write: {}",
+            mount_point.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// The kernel ring buffer, empty when it cannot be read
+fn kernel_log() -> String {
+    match Command::new("dmesg").output() {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        }
+        Ok(output) => {
+            warn!(
+                "dmesg failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            String::new()
+        }
+        Err(e) => {
+            warn!("Failed to execute dmesg: {}", e);
+            String::new()
+        }
+    }
+}
@@ -0,0 +1,335 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Rescue imaging of failing drives
+//!
+//! Unlike a plain image copy, a rescue does not stop at read errors. It
+//! works like GNU ddrescue, reading the parts of the drive that are easy
+//! to read first so that as much as possible is saved before the drive
+//! degrades further:
+//!
+//! 1. Copying: large blocks are read forward, skipping ahead further after
+//!    each error, then backward over what was skipped, then forward once
+//!    more without skipping.
+//! 2. Trimming: the edges of blocks that failed are read sector by sector
+//!    up to the first bad sector from either side.
+//! 3. Scraping: what remains of failed blocks is read sector by sector.
+//! 4. Retrying: bad sectors are read again, alternating direction.
+//!
+//! The map of what was read is saved next to the image as the rescue goes,
+//! in the ddrescue mapfile format, so a rescue can be resumed.
+
+use crate::error::{Result, SysError};
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use storage_types::{RescueMap, RescueOptions, RescuePhase, RescueProgress, RescueStatus};
+use tracing::{debug, info};
+
+/// Read size while copying
+const CLUSTER_SIZE: u64 = 64 * 1024;
+
+/// Read size while trimming, scraping and retrying
+const SECTOR_SIZE: u64 = 512;
+
+/// Bytes skipped after the first read error of a run; doubled on each
+/// further error up to [`MAX_SKIP`]
+const MIN_SKIP: u64 = 64 * 1024;
+
+/// Most bytes skipped after a read error
+const MAX_SKIP: u64 = 1024 * 1024 * 1024;
+
+/// Time between two saves of the map
+const MAP_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Time between two progress reports
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Copy the failing drive `device` to `image`, keeping the map of the
+/// rescue in `map_path`
+///
+/// An existing map is resumed from, reading only what it does not list as
+/// rescued. Returns the final state, whose unreadable bytes are lost.
+pub fn rescue_image(
+    device: &str,
+    image: &Path,
+    map_path: &Path,
+    options: &RescueOptions,
+    on_progress: impl FnMut(&RescueProgress),
+    cancelled: impl Fn() -> bool,
+) -> Result<RescueProgress> {
+    let mut source = File::open(device).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => {
+            SysError::PermissionDenied(format!("Cannot open {} for reading", device))
+        }
+        std::io::ErrorKind::NotFound => SysError::DeviceNotFound(device.to_string()),
+        _ => SysError::Io(e),
+    })?;
+    let size = source.seek(SeekFrom::End(0))?;
+
+    let map = if map_path.exists() {
+        let map = RescueMap::parse(&std::fs::read_to_string(map_path)?).map_err(|e| {
+            SysError::OperationFailed(format!("Invalid map {}: {}", map_path.display(), e))
+        })?;
+        if map.size() != size {
+            return Err(SysError::OperationFailed(format!(
+                "The map {} is for a device of {} bytes, {} has {}",
+                map_path.display(),
+                map.size(),
+                device,
+                size
+            )));
+        }
+        info!("Resuming rescue of {} from {}", device, map_path.display());
+        map
+    } else {
+        info!("Rescuing {} to {}", device, image.display());
+        RescueMap::new(size)
+    };
+
+    // A resumed rescue writes into the image it made before
+    let dest = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(image)?;
+    if dest.metadata()?.len() < size {
+        // Areas that cannot be read stay zero
+        dest.set_len(size)?;
+    }
+
+    let mut rescue = Rescue {
+        source,
+        dest,
+        map,
+        map_path,
+        buffer: vec![0u8; CLUSTER_SIZE as usize],
+        on_progress,
+        cancelled,
+        last_save: Instant::now(),
+        last_progress: Instant::now(),
+    };
+    let result = rescue.run(options);
+    // Keep what was rescued so far, also when stopped by an error
+    let saved = rescue.save_map();
+    rescue.dest.sync_all()?;
+    result?;
+    saved?;
+
+    let progress = rescue.map.progress();
+    (rescue.on_progress)(&progress);
+    Ok(progress)
+}
+
+/// A rescue in progress
+struct Rescue<'a, P, C> {
+    source: File,
+    dest: File,
+    map: RescueMap,
+    map_path: &'a Path,
+    buffer: Vec<u8>,
+    on_progress: P,
+    cancelled: C,
+    last_save: Instant,
+    last_progress: Instant,
+}
+
+impl<P, C> Rescue<'_, P, C>
+where
+    P: FnMut(&RescueProgress),
+    C: Fn() -> bool,
+{
+    fn run(&mut self, options: &RescueOptions) -> Result<()> {
+        // Phases before the one of a resumed map are done; their areas
+        // have moved on to a later status
+        if self.map.phase == RescuePhase::Copying {
+            let start = self.map.pass;
+            for pass in start..=3 {
+                self.map.pass = pass;
+                // Backward on the second pass, without skipping on the third
+                self.copy_pass(pass == 2, pass < 3)?;
+            }
+            self.begin(RescuePhase::Trimming, 1);
+        }
+
+        if self.map.phase == RescuePhase::Trimming {
+            for (pos, size) in self.map.areas(RescueStatus::NonTrimmed) {
+                self.trim(pos, pos + size)?;
+            }
+            self.begin(RescuePhase::Scraping, 1);
+        }
+
+        if self.map.phase == RescuePhase::Scraping {
+            if options.scrape {
+                for (pos, size) in self.map.areas(RescueStatus::NonScraped) {
+                    self.read_sectors(pos, pos + size, false, RescueStatus::BadSector)?;
+                }
+            }
+            self.begin(RescuePhase::Retrying, 1);
+        }
+
+        if self.map.phase == RescuePhase::Retrying {
+            let start = self.map.pass;
+            for pass in start..=options.retry_passes {
+                self.map.pass = pass;
+                let reverse = pass % 2 == 0;
+                let mut areas = self.map.areas(RescueStatus::BadSector);
+                if reverse {
+                    areas.reverse();
+                }
+                for (pos, size) in areas {
+                    self.read_sectors(pos, pos + size, reverse, RescueStatus::BadSector)?;
+                }
+            }
+            self.begin(RescuePhase::Finished, 1);
+        }
+        Ok(())
+    }
+
+    fn begin(&mut self, phase: RescuePhase, pass: u32) {
+        self.map.phase = phase;
+        self.map.pass = pass;
+        self.map.current_pos = 0;
+    }
+
+    /// Read the areas not tried yet in clusters
+    ///
+    /// A failed cluster is left for trimming. With `skip`, the area after
+    /// it is left for a later pass too, as errors tend to cluster.
+    fn copy_pass(&mut self, reverse: bool, skip: bool) -> Result<()> {
+        let mut areas = self.map.areas(RescueStatus::NonTried);
+        if reverse {
+            areas.reverse();
+        }
+
+        for (start, size) in areas {
+            let end = start + size;
+            let mut skip_size = MIN_SKIP;
+            // Bounds of what is left of the area
+            let (mut low, mut high) = (start, end);
+            while low < high {
+                let len = (high - low).min(CLUSTER_SIZE);
+                let pos = if reverse { high - len } else { low };
+                let ok = self.read_block(pos, len)?;
+                if reverse {
+                    high = pos;
+                } else {
+                    low = pos + len;
+                }
+                if ok {
+                    self.map.set(pos, len, RescueStatus::Finished);
+                    skip_size = MIN_SKIP;
+                    continue;
+                }
+
+                self.map.set(pos, len, RescueStatus::NonTrimmed);
+                if skip {
+                    let skipped = (high - low).min(skip_size);
+                    if reverse {
+                        high -= skipped;
+                    } else {
+                        low += skipped;
+                    }
+                    skip_size = (skip_size * 2).min(MAX_SKIP);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a failed block from both edges up to the first bad sector, and
+    /// leave the middle for scraping
+    fn trim(&mut self, start: u64, end: u64) -> Result<()> {
+        let low = self.read_sectors(start, end, false, RescueStatus::BadSector)?;
+        let high = if low < end {
+            self.read_sectors(low, end, true, RescueStatus::BadSector)?
+        } else {
+            end
+        };
+        self.map
+            .set(low, high.saturating_sub(low), RescueStatus::NonScraped);
+        Ok(())
+    }
+
+    /// Read `start..end` sector by sector, marking sectors that fail with
+    /// `failed`
+    ///
+    /// When trimming, this stops at the first bad sector and returns how far
+    /// it got: the end of the read part when going forward, its start when
+    /// going backward. Otherwise it reads every sector.
+    fn read_sectors(
+        &mut self,
+        start: u64,
+        end: u64,
+        reverse: bool,
+        failed: RescueStatus,
+    ) -> Result<u64> {
+        let trimming = self.map.phase == RescuePhase::Trimming;
+        let (mut low, mut high) = (start, end);
+        while low < high {
+            let len = if reverse {
+                match high % SECTOR_SIZE {
+                    0 => SECTOR_SIZE,
+                    partial => partial,
+                }
+                .min(high - low)
+            } else {
+                (high - low).min(SECTOR_SIZE)
+            };
+            let pos = if reverse { high - len } else { low };
+            let ok = self.read_block(pos, len)?;
+            if reverse {
+                high = pos;
+            } else {
+                low = pos + len;
+            }
+            let status = if ok { RescueStatus::Finished } else { failed };
+            self.map.set(pos, len, status);
+            if !ok && trimming {
+                break;
+            }
+        }
+        Ok(if reverse { high } else { low })
+    }
+
+    /// Copy `len` bytes at `pos` to the image, returning false when the
+    /// drive failed to read them
+    fn read_block(&mut self, pos: u64, len: u64) -> Result<bool> {
+        if (self.cancelled)() {
+            return Err(SysError::OperationFailed("Rescue cancelled".to_string()));
+        }
+        self.map.current_pos = pos;
+
+        let buffer = &mut self.buffer[..len as usize];
+        let ok = match self.source.read_exact_at(buffer, pos) {
+            Ok(()) => {
+                self.dest.write_all_at(buffer, pos)?;
+                true
+            }
+            Err(e) => {
+                debug!("Read error at byte {}: {}", pos, e);
+                false
+            }
+        };
+
+        if self.last_progress.elapsed() >= PROGRESS_INTERVAL {
+            self.last_progress = Instant::now();
+            (self.on_progress)(&self.map.progress());
+        }
+        if self.last_save.elapsed() >= MAP_SAVE_INTERVAL {
+            self.save_map()?;
+        }
+        Ok(ok)
+    }
+
+    /// Write the map, replacing the previous one at once so that a crash
+    /// cannot leave it half written
+    fn save_map(&mut self) -> Result<()> {
+        self.last_save = Instant::now();
+        let temp = self.map_path.with_extension("map.tmp");
+        std::fs::write(&temp, self.map.to_string())?;
+        std::fs::rename(&temp, self.map_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Reading raw sectors of block devices for the sector viewer
+//!
+//! Devices are opened read-only and without following symlinks, and the
+//! descriptor is refused unless it is a block device, so neither regular
+//! files nor links planted under /dev can be read through it. Reads go
+//! through `pread`, which leaves the file position alone.
+
+use crate::error::{Result, SysError};
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Seek, SeekFrom};
+use std::os::unix::fs::{FileExt, FileTypeExt, OpenOptionsExt};
+use storage_types::SectorRange;
+use storage_types::sectors::MAX_SECTOR_READ;
+
+/// Open a block device for reading, and nothing else
+fn open_read_only(device: &str) -> Result<File> {
+    let file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NOFOLLOW | libc::O_NOCTTY | libc::O_CLOEXEC)
+        .open(device)
+        .map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => {
+                SysError::PermissionDenied(format!("Cannot open {} for reading", device))
+            }
+            ErrorKind::NotFound => SysError::DeviceNotFound(device.to_string()),
+            _ => SysError::Io(e),
+        })?;
+
+    if !file.metadata()?.file_type().is_block_device() {
+        return Err(SysError::OperationFailed(format!(
+            "{} is not a block device",
+            device
+        )));
+    }
+    Ok(file)
+}
+
+/// Read `length` bytes at byte `offset` of a block device
+///
+/// At most [`MAX_SECTOR_READ`] bytes are read, and fewer at the end of the
+/// device.
+pub fn read_sectors(device: &str, offset: u64, length: u64) -> Result<SectorRange> {
+    let mut file = open_read_only(device)?;
+    let device_size = file.seek(SeekFrom::End(0))?;
+    if offset >= device_size {
+        return Err(SysError::OperationFailed(format!(
+            "Offset {} is beyond the end of {} ({} bytes)",
+            offset, device, device_size
+        )));
+    }
+
+    let length = length.min(MAX_SECTOR_READ).min(device_size - offset);
+    let mut data = vec![0u8; length as usize];
+    file.read_exact_at(&mut data, offset)?;
+
+    Ok(SectorRange {
+        offset,
+        device_size,
+        data,
+    })
+}
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! SELinux labels of mounted filesystems and relabeling with restorecon
+
+use crate::error::{Result, SysError};
+use std::ffi::CString;
+use std::io::Read;
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use storage_types::{SelinuxStatus, labeled_mounts};
+use tracing::info;
+
+const SELINUXFS: &str = "/sys/fs/selinux";
+
+/// Files restorecon counts between two progress marks
+const FILES_PER_MARK: u64 = 1000;
+
+/// SELinux state of the mounted block device filesystems; empty when
+/// SELinux is disabled
+pub fn selinux_statuses() -> Result<Vec<SelinuxStatus>> {
+    let Ok(enforce) = std::fs::read_to_string(Path::new(SELINUXFS).join("enforce")) else {
+        return Ok(Vec::new());
+    };
+    let enforcing = enforce.trim() == "1";
+    let mounts = std::fs::read_to_string("/proc/mounts")?;
+
+    Ok(labeled_mounts(&mounts)
+        .into_iter()
+        .map(|(mount_point, mount_context)| {
+            let path = Path::new(&mount_point);
+            SelinuxStatus {
+                enforcing,
+                current: file_context(path).unwrap_or_default(),
+                default: default_context(path),
+                mount_context,
+                mount_point,
+            }
+        })
+        .collect())
+}
+
+/// Label of `path`, from its security.selinux attribute
+fn file_context(path: &Path) -> Result<Option<String>> {
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| SysError::OperationFailed("Path contains a NUL byte".to_string()))?;
+    let name = c"security.selinux";
+    let mut value = [0u8; 256];
+    let len = unsafe {
+        libc::lgetxattr(
+            path.as_ptr(),
+            name.as_ptr(),
+            value.as_mut_ptr().cast(),
+            value.len(),
+        )
+    };
+    if len < 0 {
+        let error = std::io::Error::last_os_error();
+        return match error.raw_os_error() {
+            Some(libc::ENODATA) | Some(libc::ENOTSUP) => Ok(None),
+            _ => Err(error.into()),
+        };
+    }
+    let value = &value[..len as usize];
+    let value = value.strip_suffix(&[0]).unwrap_or(value);
+    Ok(Some(String::from_utf8_lossy(value).to_string()))
+}
+
+/// Label the policy gives `path`, as matchpathcon reports it
+fn default_context(path: &Path) -> Option<String> {
+    let output = Command::new("matchpathcon")
+        .arg("-n")
+        .arg(path)
+        .output()
+        .ok()?;
+    let context = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !context.is_empty()).then_some(context)
+}
+
+/// Files in use on the filesystem at `mount_point`, to tell how far a
+/// relabel got
+fn used_inodes(mount_point: &Path) -> Option<u64> {
+    let path = CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let stat = unsafe { stat.assume_init() };
+    Some(stat.f_files.saturating_sub(stat.f_ffree)).filter(|used| *used > 0)
+}
+
+/// Give every file under `mount_point` the label the policy gives it
+///
+/// `progress` is called with the files done so far and the files in use on
+/// the filesystem, when it can tell them.
+pub fn relabel(mount_point: &Path, mut progress: impl FnMut(u64, Option<u64>)) -> Result<()> {
+    if !Path::new(SELINUXFS).join("enforce").exists() {
+        return Err(SysError::OperationFailed(
+            "SELinux is not enabled".to_string(),
+        ));
+    }
+    let total = used_inodes(mount_point);
+
+    info!("Relabeling {}", mount_point.display());
+    // With -p, restorecon prints "\r<n>k" for every thousand files done
+    let mut child = Command::new("restorecon")
+        .args(["-R", "-p"])
+        .arg(mount_point)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to run restorecon: {e}")))?;
+
+    // Read the errors alongside, so that a long list of them can't block it
+    let errors = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut errors = String::new();
+            let _ = stderr.read_to_string(&mut errors);
+            errors
+        })
+    });
+
+    if let Some(mut stdout) = child.stdout.take() {
+        let mut buffer = [0u8; 256];
+        let mut pending = String::new();
+        while let Ok(read) = stdout.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            pending.push_str(&String::from_utf8_lossy(&buffer[..read]));
+            let marks = pending
+                .rsplit('\r')
+                .filter_map(|mark| mark.trim().strip_suffix('k')?.parse::<u64>().ok())
+                .next();
+            if let Some(marks) = marks {
+                progress(marks * FILES_PER_MARK, total);
+            }
+            if let Some(last) = pending.rfind('\r') {
+                pending.drain(..last);
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to run restorecon: {e}")))?;
+    let errors = errors
+        .and_then(|errors| errors.join().ok())
+        .unwrap_or_default();
+    if !status.success() {
+        return Err(SysError::OperationFailed(format!(
+            "restorecon failed: {}",
+            errors.trim()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,291 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! SMART data through smartctl
+//!
+//! Fallback for drives UDisks cannot query, most commonly SATA drives behind
+//! USB bridges that need SCSI/ATA Translation (SAT) pass-through. The JSON
+//! output of `smartctl` is mapped onto the same [`SmartInfo`] model the
+//! UDisks ATA and NVMe readers produce. Disks behind hardware RAID
+//! controllers are reached through the controller's pass-through.
+
+use crate::error::{Result, SysError};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::process::Command;
+use storage_types::hardware_raid::parse_smartctl_scan;
+use storage_types::{PhysicalDevice, SmartInfo, SmartSelfTestKind};
+use tracing::debug;
+
+/// smartctl exit status bits meaning no SMART data was read: command line
+/// not parsed, or device open failed
+const SMARTCTL_FATAL_BITS: i32 = 0b11;
+
+/// Whether smartctl is installed
+pub fn smartctl_available() -> bool {
+    which::which("smartctl").is_ok()
+}
+
+/// Read SMART data of `device` (e.g., "/dev/sdb") with smartctl
+///
+/// Lets smartctl pick the device type first and retries with SAT
+/// pass-through (`-d sat`) for USB bridges it does not recognise.
+pub fn smartctl_info(device: &str) -> Result<SmartInfo> {
+    match run_smartctl(device, None) {
+        Ok(info) => Ok(info),
+        Err(e) => {
+            debug!("smartctl on {} failed ({}), retrying with SAT", device, e);
+            run_smartctl(device, Some("sat"))
+        }
+    }
+}
+
+/// Start a SMART self-test on `device` with smartctl
+pub fn smartctl_start_selftest(device: &str, kind: SmartSelfTestKind) -> Result<()> {
+    let test = match kind {
+        SmartSelfTestKind::Short => "short",
+        SmartSelfTestKind::Extended => "long",
+    };
+    let args = ["--test", test];
+    match run_smartctl_with(device, None, &args) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            debug!("smartctl on {} failed ({}), retrying with SAT", device, e);
+            run_smartctl_with(device, Some("sat"), &args).map(|_| ())
+        }
+    }
+}
+
+/// Abort the SMART self-test running on `device` with smartctl
+pub fn smartctl_abort_selftest(device: &str) -> Result<()> {
+    let args = ["--abort"];
+    match run_smartctl_with(device, None, &args) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            debug!("smartctl on {} failed ({}), retrying with SAT", device, e);
+            run_smartctl_with(device, Some("sat"), &args).map(|_| ())
+        }
+    }
+}
+
+/// Disks behind hardware RAID controllers, with their SMART data
+///
+/// Disks whose data cannot be read are listed with the error.
+pub fn passthrough_devices() -> Result<Vec<PhysicalDevice>> {
+    let output = Command::new("smartctl")
+        .arg("--scan-open")
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute smartctl: {}", e)))?;
+    let scan = String::from_utf8_lossy(&output.stdout);
+
+    Ok(parse_smartctl_scan(&scan)
+        .into_iter()
+        .map(|(device, device_type)| {
+            let mut physical = PhysicalDevice {
+                device,
+                device_type,
+                ..PhysicalDevice::default()
+            };
+            match run_smartctl_with(&physical.device, Some(&physical.device_type), &["--all"]) {
+                Ok(output) => {
+                    (physical.model, physical.serial, physical.size) =
+                        parse_smartctl_identity(&output);
+                    match parse_smartctl_json(&output) {
+                        Ok(info) => physical.smart = Some(info),
+                        Err(e) => physical.error = Some(e.to_string()),
+                    }
+                }
+                Err(e) => physical.error = Some(e.to_string()),
+            }
+            physical
+        })
+        .collect())
+}
+
+fn run_smartctl(device: &str, device_type: Option<&str>) -> Result<SmartInfo> {
+    let output = run_smartctl_with(device, device_type, &["--all"])?;
+    parse_smartctl_json(&output)
+}
+
+/// Run `smartctl --json <args>` and return its JSON output
+fn run_smartctl_with(device: &str, device_type: Option<&str>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("smartctl");
+    command.arg("--json").args(args);
+    if let Some(device_type) = device_type {
+        command.args(["--device", device_type]);
+    }
+    let output = command
+        .arg(device)
+        .output()
+        .map_err(|e| SysError::OperationFailed(format!("Failed to execute smartctl: {}", e)))?;
+
+    // Higher bits report drive health, not errors of smartctl itself
+    if output
+        .status
+        .code()
+        .is_none_or(|code| code & SMARTCTL_FATAL_BITS != 0)
+    {
+        return Err(SysError::OperationFailed(format!(
+            "smartctl failed on {}: {}",
+            device,
+            smartctl_messages(&output.stdout)
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Error strings smartctl reports in its JSON output
+fn smartctl_messages(stdout: &[u8]) -> String {
+    serde_json::from_slice::<Value>(stdout)
+        .ok()
+        .and_then(|json| {
+            json["smartctl"]["messages"].as_array().map(|messages| {
+                messages
+                    .iter()
+                    .filter_map(|message| message["string"].as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Model, serial number and size from `smartctl --json --all` output
+fn parse_smartctl_identity(output: &str) -> (Option<String>, Option<String>, Option<u64>) {
+    let Ok(json) = serde_json::from_str::<Value>(output) else {
+        return (None, None, None);
+    };
+    let model = ["model_name", "scsi_model_name", "scsi_product"]
+        .iter()
+        .find_map(|key| json[key].as_str())
+        .map(|model| model.trim().to_string())
+        .filter(|model| !model.is_empty());
+    let serial = json["serial_number"].as_str().map(str::to_string);
+    let size = json["user_capacity"]["bytes"].as_u64();
+    (model, serial, size)
+}
+
+/// Map `smartctl --json --all` output to [`SmartInfo`]
+fn parse_smartctl_json(output: &str) -> Result<SmartInfo> {
+    let json: Value = serde_json::from_str(output)
+        .map_err(|e| SysError::OperationFailed(format!("Invalid smartctl output: {}", e)))?;
+
+    let protocol = json["device"]["protocol"].as_str().unwrap_or("ATA");
+    if json["smart_status"].is_null() && json["ata_smart_attributes"].is_null() {
+        return Err(SysError::OperationFailed(
+            "smartctl returned no SMART data".to_string(),
+        ));
+    }
+
+    let mut attributes = BTreeMap::new();
+    if let Some(table) = json["ata_smart_attributes"]["table"].as_array() {
+        for attribute in table {
+            if let (Some(name), Some(raw)) = (
+                attribute["name"].as_str(),
+                attribute["raw"]["value"].as_u64(),
+            ) {
+                attributes.insert(name.to_string(), raw.to_string());
+            }
+        }
+    }
+    if let Some(log) = json["nvme_smart_health_information_log"].as_object() {
+        for (key, value) in log {
+            if value.is_number() {
+                attributes.insert(key.clone(), value.to_string());
+            }
+        }
+    }
+    if let Some(passed) = json["smart_status"]["passed"].as_bool() {
+        attributes.insert(
+            "overall_health".to_string(),
+            if passed { "PASSED" } else { "FAILED" }.to_string(),
+        );
+    }
+
+    let selftest_status = json["ata_smart_data"]["self_test"]["status"]["string"]
+        .as_str()
+        .or_else(|| json["nvme_self_test_log"]["current_self_test_operation"]["string"].as_str())
+        .map(str::to_string)
+        .or_else(|| {
+            (json["smart_status"]["passed"].as_bool() == Some(false))
+                .then(|| "SMART overall-health self-assessment failed".to_string())
+        });
+
+    Ok(SmartInfo {
+        device_type: format!("{} (smartctl)", protocol),
+        updated_at: json["local_time"]["time_t"].as_u64(),
+        temperature_c: json["temperature"]["current"].as_u64(),
+        power_on_hours: json["power_on_time"]["hours"].as_u64(),
+        selftest_status,
+        attributes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ata_output() {
+        let output = r#"{
+            "device": {"name": "/dev/sdb", "type": "sat", "protocol": "ATA"},
+            "local_time": {"time_t": 1760572800},
+            "smart_status": {"passed": true},
+            "ata_smart_data": {"self_test": {"status": {"value": 0, "string": "completed without error"}}},
+            "ata_smart_attributes": {"table": [
+                {"id": 5, "name": "Reallocated_Sector_Ct", "raw": {"value": 0, "string": "0"}},
+                {"id": 12, "name": "Power_Cycle_Count", "raw": {"value": 1234, "string": "1234"}}
+            ]},
+            "power_on_time": {"hours": 4200},
+            "temperature": {"current": 34}
+        }"#;
+
+        let info = parse_smartctl_json(output).unwrap();
+        assert_eq!(info.device_type, "ATA (smartctl)");
+        assert_eq!(info.updated_at, Some(1760572800));
+        assert_eq!(info.temperature_c, Some(34));
+        assert_eq!(info.power_on_hours, Some(4200));
+        assert_eq!(
+            info.selftest_status.as_deref(),
+            Some("completed without error")
+        );
+        assert_eq!(info.attributes["Power_Cycle_Count"], "1234");
+        assert_eq!(info.attributes["overall_health"], "PASSED");
+    }
+
+    #[test]
+    fn parses_nvme_output_and_failures() {
+        let output = r#"{
+            "device": {"protocol": "NVMe"},
+            "smart_status": {"passed": false},
+            "nvme_smart_health_information_log": {"percentage_used": 7, "media_errors": 2}
+        }"#;
+
+        let info = parse_smartctl_json(output).unwrap();
+        assert_eq!(info.device_type, "NVMe (smartctl)");
+        assert_eq!(info.attributes["percentage_used"], "7");
+        assert_eq!(info.attributes["overall_health"], "FAILED");
+        assert!(info.selftest_status.unwrap().contains("failed"));
+
+        assert!(parse_smartctl_json(r#"{"device": {"protocol": "SCSI"}}"#).is_err());
+        assert!(parse_smartctl_json("not json").is_err());
+    }
+
+    #[test]
+    fn parses_identity_of_pass_through_disks() {
+        let output = r#"{
+            "device": {"name": "/dev/bus/0", "type": "megaraid,8", "protocol": "SCSI"},
+            "scsi_vendor": "SEAGATE",
+            "scsi_product": "ST4000NM0023",
+            "scsi_model_name": "SEAGATE ST4000NM0023",
+            "serial_number": "Z1Z3ABCD",
+            "user_capacity": {"blocks": 7814037168, "bytes": 4000787030016}
+        }"#;
+
+        let (model, serial, size) = parse_smartctl_identity(output);
+        assert_eq!(model.as_deref(), Some("SEAGATE ST4000NM0023"));
+        assert_eq!(serial.as_deref(), Some("Z1Z3ABCD"));
+        assert_eq!(size, Some(4000787030016));
+        assert_eq!(parse_smartctl_identity("not json"), (None, None, None));
+    }
+}
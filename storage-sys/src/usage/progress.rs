@@ -77,21 +77,9 @@ pub fn format_eta(eta: Option<Duration>) -> String {
     format!("{hours:02}:{minutes:02}:{seconds:02}")
 }
 
+/// Size in the process-wide byte format, see [`storage_types::byte_format`]
 pub fn format_bytes(bytes: u64) -> String {
-    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
-    let mut unit_index = 0;
-    let mut value = bytes as f64;
-
-    while value >= 1024.0 && unit_index < UNITS.len() - 1 {
-        value /= 1024.0;
-        unit_index += 1;
-    }
-
-    if unit_index == 0 {
-        format!("{} {}", bytes, UNITS[unit_index])
-    } else {
-        format!("{value:.1} {}", UNITS[unit_index])
-    }
+    storage_types::byte_format().format(bytes, false)
 }
 
 #[cfg(test)]
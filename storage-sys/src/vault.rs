@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Legacy eCryptfs and gocryptfs vaults
+//!
+//! Vaults are looked for where their tools put them: eCryptfs homes under
+//! `/home/.ecryptfs/<user>`, gocryptfs vaults (directories holding a
+//! `gocryptfs.conf`) a few levels below `/home`. They are only mounted
+//! read-only, as the point is to copy the files out. Migrating copies the
+//! decrypted view file by file into an encrypted directory or volume and
+//! compares every copy with its original.
+
+use crate::error::{Result, SysError};
+use crate::migration::run;
+use crate::rclone::unix_user::username_for_uid;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use storage_types::{
+    LegacyVault, VaultKind, VaultMigrationResult, parse_ecryptfs_sigs, vault_mounts,
+};
+use tracing::debug;
+
+const ECRYPTFS_HOMES: &str = "/home/.ecryptfs";
+
+const HOMES: &str = "/home";
+
+/// Directory levels below `/home` searched for gocryptfs vaults, enough
+/// for e.g. `/home/<user>/Private/<vault>`
+const SEARCH_DEPTH: usize = 3;
+
+/// Bytes compared at a time when verifying a copy
+const COMPARE_CHUNK: usize = 1024 * 1024;
+
+/// eCryptfs and gocryptfs vaults on this system, with where they are
+/// mounted
+pub fn find_legacy_vaults() -> Vec<LegacyVault> {
+    let mut vaults = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(ECRYPTFS_HOMES) {
+        for entry in entries.flatten() {
+            let home = entry.path();
+            let cipher_dir = home.join(".Private");
+            if cipher_dir.is_dir() && home.join(".ecryptfs/wrapped-passphrase").is_file() {
+                vaults.push(vault(VaultKind::Ecryptfs, &cipher_dir));
+            }
+        }
+    }
+
+    let mut gocryptfs = Vec::new();
+    find_gocryptfs(Path::new(HOMES), SEARCH_DEPTH, &mut gocryptfs);
+    vaults.extend(
+        gocryptfs
+            .iter()
+            .map(|cipher_dir| vault(VaultKind::Gocryptfs, cipher_dir)),
+    );
+
+    if let Ok(mounts) = std::fs::read_to_string("/proc/mounts") {
+        for (kind, source, mount_point) in vault_mounts(&mounts) {
+            // eCryptfs homes are mounted through the ~/.Private symlink
+            let source = std::fs::canonicalize(&source).unwrap_or_else(|_| PathBuf::from(source));
+            if let Some(vault) = vaults
+                .iter_mut()
+                .find(|vault| vault.kind == kind && Path::new(&vault.cipher_dir) == source)
+            {
+                vault.mount_point = Some(mount_point);
+            }
+        }
+    }
+    vaults
+}
+
+fn vault(kind: VaultKind, cipher_dir: &Path) -> LegacyVault {
+    let cipher_dir = std::fs::canonicalize(cipher_dir).unwrap_or_else(|_| cipher_dir.to_path_buf());
+    let owner_uid = std::fs::metadata(&cipher_dir).map_or(0, |metadata| metadata.uid());
+    LegacyVault {
+        kind,
+        cipher_dir: cipher_dir.to_string_lossy().into_owned(),
+        owner_uid,
+        owner: username_for_uid(owner_uid),
+        mount_point: None,
+    }
+}
+
+/// Collect gocryptfs vaults below `dir`, without descending into them,
+/// into symlinks or into eCryptfs homes
+fn find_gocryptfs(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+            || entry.file_name() == ".ecryptfs"
+        {
+            continue;
+        }
+        let path = entry.path();
+        if path.join("gocryptfs.conf").is_file() {
+            out.push(path);
+        } else if depth > 1 {
+            find_gocryptfs(&path, depth - 1, out);
+        }
+    }
+}
+
+/// Mount `vault` read-only at `mount_point`, unlocked with `passphrase`
+///
+/// eCryptfs homes take their owner's login passphrase, which unwraps the
+/// mount passphrase; gocryptfs vaults take their own password. Files keep
+/// their owners and permissions.
+pub fn mount_vault(vault: &LegacyVault, passphrase: &str, mount_point: &Path) -> Result<()> {
+    std::fs::create_dir_all(mount_point)?;
+    let cipher_dir = Path::new(&vault.cipher_dir);
+    let mounted = match vault.kind {
+        VaultKind::Ecryptfs => mount_ecryptfs(cipher_dir, passphrase, mount_point),
+        // allow_other lets the owner in; gocryptfs then checks permissions
+        // like any filesystem
+        VaultKind::Gocryptfs => run(
+            "gocryptfs",
+            &[
+                "-ro",
+                "-allow_other",
+                "-q",
+                &vault.cipher_dir,
+                &mount_point.to_string_lossy(),
+            ],
+            Some(&format!("{passphrase}\n")),
+        )
+        .map(drop),
+    };
+    if mounted.is_err() {
+        let _ = std::fs::remove_dir(mount_point);
+    }
+    mounted
+}
+
+fn mount_ecryptfs(cipher_dir: &Path, passphrase: &str, mount_point: &Path) -> Result<()> {
+    // The wrapped passphrase and the key signatures sit beside .Private
+    let config = cipher_dir.with_file_name(".ecryptfs");
+    let sigs = std::fs::read_to_string(config.join("Private.sig"))?;
+    let (sig, fnek_sig) = parse_ecryptfs_sigs(&sigs).ok_or_else(|| {
+        SysError::OperationFailed(format!(
+            "Invalid key signatures in {}",
+            config.join("Private.sig").display()
+        ))
+    })?;
+
+    let unwrapped = run(
+        "ecryptfs-unwrap-passphrase",
+        &[&config.join("wrapped-passphrase").to_string_lossy(), "-"],
+        Some(&format!("{passphrase}\n")),
+    )?;
+    let mount_passphrase = unwrapped
+        .lines()
+        .rfind(|line| !line.trim().is_empty())
+        .ok_or_else(|| SysError::OperationFailed("Failed to unwrap the passphrase".to_string()))?;
+
+    // no_sig_cache keeps mount.ecryptfs from asking to confirm the keys
+    let mut options = format!(
+        "ro,key=passphrase:passphrase_passwd_fd=0,ecryptfs_sig={sig},ecryptfs_cipher=aes,\
+         ecryptfs_key_bytes=16,ecryptfs_passthrough=n,no_sig_cache"
+    );
+    if let Some(fnek_sig) = fnek_sig {
+        options.push_str(&format!(
+            ",ecryptfs_enable_filename_crypto=y,ecryptfs_fnek_sig={fnek_sig}"
+        ));
+    }
+    run(
+        "mount",
+        &[
+            "-t",
+            "ecryptfs",
+            "-o",
+            &options,
+            &cipher_dir.to_string_lossy(),
+            &mount_point.to_string_lossy(),
+        ],
+        Some(&format!("{mount_passphrase}\n")),
+    )
+    .map(drop)
+}
+
+/// Unmount a vault mounted by [`mount_vault`] and remove its mount point
+pub fn unmount_vault(mount_point: &Path) -> Result<()> {
+    run("umount", &[&mount_point.to_string_lossy()], None)?;
+    let _ = std::fs::remove_dir(mount_point);
+    Ok(())
+}
+
+/// Copy the decrypted files of a mounted vault at `source` into the empty
+/// directory `target`, which must be encrypted itself
+///
+/// Owners, permissions, modification times and symlinks are kept; sockets
+/// and device nodes are skipped. Every file is compared with its original
+/// after copying. Progress is sent as bytes copied and the total.
+pub fn migrate_vault(
+    source: &Path,
+    target: &Path,
+    progress: Option<Sender<(u64, u64)>>,
+) -> Result<VaultMigrationResult> {
+    if std::fs::read_dir(target)?.next().is_some() {
+        return Err(SysError::OperationFailed(format!(
+            "{} is not empty",
+            target.display()
+        )));
+    }
+    if !encrypted_location(target) {
+        return Err(SysError::OperationFailed(format!(
+            "{} is neither an encrypted directory nor on an encrypted volume",
+            target.display()
+        )));
+    }
+
+    let total = tree_size(source)?;
+    let mut result = VaultMigrationResult::default();
+    copy_tree(source, target, total, &mut result, progress.as_ref())?;
+    debug!(
+        "Migrated {} files ({} bytes) from {} to {}",
+        result.files,
+        result.bytes,
+        source.display(),
+        target.display()
+    );
+    Ok(result)
+}
+
+fn tree_size(dir: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = std::fs::symlink_metadata(entry.path())?;
+        if metadata.is_dir() {
+            size += tree_size(&entry.path())?;
+        } else if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+fn copy_tree(
+    from: &Path,
+    to: &Path,
+    total: u64,
+    result: &mut VaultMigrationResult,
+    progress: Option<&Sender<(u64, u64)>>,
+) -> Result<()> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        let dest = to.join(entry.file_name());
+        let metadata = std::fs::symlink_metadata(&path)?;
+        let file_type = metadata.file_type();
+
+        if file_type.is_dir() {
+            std::fs::create_dir(&dest)?;
+            copy_tree(&path, &dest, total, result, progress)?;
+            result.directories += 1;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(&path)?, &dest)?;
+        } else if file_type.is_file() {
+            result.bytes += copy_file(&path, &dest)?;
+            result.files += 1;
+            if let Some(progress) = progress {
+                let _ = progress.send((result.bytes, total));
+            }
+        } else {
+            debug!("Skipping special file {}", path.display());
+            continue;
+        }
+
+        std::os::unix::fs::lchown(&dest, Some(metadata.uid()), Some(metadata.gid()))?;
+        if !file_type.is_symlink() {
+            std::fs::set_permissions(&dest, metadata.permissions())?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy `from` to `to` with its modification time and check that the
+/// copy reads back the same
+fn copy_file(from: &Path, to: &Path) -> Result<u64> {
+    let bytes = std::fs::copy(from, to)?;
+    let file = File::options().write(true).open(to)?;
+    file.set_modified(std::fs::metadata(from)?.modified()?)?;
+    file.sync_all()?;
+    drop(file);
+
+    if !same_contents(from, to)? {
+        return Err(SysError::OperationFailed(format!(
+            "{} differs from {} after copying",
+            to.display(),
+            from.display()
+        )));
+    }
+    Ok(bytes)
+}
+
+fn same_contents(a: &Path, b: &Path) -> Result<bool> {
+    let mut a = BufReader::with_capacity(COMPARE_CHUNK, File::open(a)?);
+    let mut b = BufReader::with_capacity(COMPARE_CHUNK, File::open(b)?);
+    loop {
+        let (left, right) = (a.fill_buf()?, b.fill_buf()?);
+        if left.is_empty() || right.is_empty() {
+            return Ok(left.is_empty() && right.is_empty());
+        }
+        let len = left.len().min(right.len());
+        if left[..len] != right[..len] {
+            return Ok(false);
+        }
+        a.consume(len);
+        b.consume(len);
+    }
+}
+
+/// Whether files written below `path` are encrypted: it is in an fscrypt
+/// directory, or its filesystem is on dm-crypt, directly or below LVM
+fn encrypted_location(path: &Path) -> bool {
+    if crate::fscrypt::is_encrypted(path) {
+        return true;
+    }
+    let Ok(path) = std::fs::canonicalize(path) else {
+        return false;
+    };
+    let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+        return false;
+    };
+    // The filesystem holding `path` is the one with the longest mount point
+    // above it
+    let source = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let source = fields.next()?;
+            let mount_point = PathBuf::from(fields.next()?.replace("\\040", " "));
+            path.starts_with(&mount_point)
+                .then_some((mount_point, source))
+        })
+        .max_by_key(|(mount_point, _)| mount_point.as_os_str().len())
+        .map(|(_, source)| source);
+    let Some(name) = source
+        .and_then(|source| std::fs::canonicalize(source).ok())
+        .and_then(|device| Some(device.file_name()?.to_string_lossy().into_owned()))
+    else {
+        return false;
+    };
+    crypt_backed(&name)
+}
+
+/// Whether the block device `name` (e.g. "dm-0") is a dm-crypt mapping or
+/// sits on one
+fn crypt_backed(name: &str) -> bool {
+    let device = Path::new("/sys/class/block").join(name);
+    if std::fs::read_to_string(device.join("dm/uuid")).is_ok_and(|uuid| uuid.starts_with("CRYPT-"))
+    {
+        return true;
+    }
+    std::fs::read_dir(device.join("slaves"))
+        .map(|slaves| {
+            slaves
+                .flatten()
+                .any(|slave| crypt_backed(&slave.file_name().to_string_lossy()))
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Hypervisor detection and trimming the filesystems of virtual disks
+//!
+//! Thin-provisioned disks only shrink on the host when the guest discards
+//! the blocks its filesystems freed, which `fstrim` does on demand.
+
+use crate::error::{Result, SysError};
+use crate::migration::run;
+use std::path::Path;
+use storage_types::virtualization::{hypervisor_from_dmi, parse_detect_virt, parse_fstrim_output};
+use tracing::info;
+
+/// DMI identity of the machine, readable without privileges
+const DMI_DIR: &str = "/sys/class/dmi/id";
+
+/// Hypervisor the system runs under, e.g. "kvm", or None on bare metal
+pub fn hypervisor() -> Option<String> {
+    // systemd-detect-virt exits non-zero on bare metal
+    match run("systemd-detect-virt", &["--vm"], None) {
+        Ok(output) => parse_detect_virt(&output),
+        Err(_) if Path::new("/run/systemd/system").exists() => None,
+        Err(_) => {
+            let read = |name: &str| {
+                std::fs::read_to_string(Path::new(DMI_DIR).join(name)).unwrap_or_default()
+            };
+            hypervisor_from_dmi(&read("sys_vendor"), &read("product_name"))
+        }
+    }
+}
+
+/// Discard the unused blocks of the filesystem mounted at `mount_point`,
+/// returning the bytes trimmed
+pub fn trim_filesystem(mount_point: &str) -> Result<u64> {
+    let output = run("fstrim", &["--verbose", mount_point], None)?;
+    let trimmed = parse_fstrim_output(&output).ok_or_else(|| {
+        SysError::OperationFailed(format!("Unexpected fstrim output: {}", output.trim()))
+    })?;
+    info!("Trimmed {} bytes from {}", trimmed, mount_point);
+    Ok(trimmed)
+}
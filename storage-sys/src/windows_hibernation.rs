@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Probing NTFS filesystems for Windows hibernation before mounting them
+
+use crate::error::{Result, SysError};
+use crate::partition_copy::filesystem_type;
+use std::process::{Command, Stdio};
+use storage_types::WindowsHibernation;
+use tracing::debug;
+
+/// Whether Windows left the NTFS filesystem on `device` hibernated, so that
+/// it must only be mounted read-only
+///
+/// Other filesystems are never hibernated. Errors when `ntfs-3g.probe` is
+/// not installed, as the state is then unknown.
+pub fn windows_hibernation(device: &str) -> Result<Option<WindowsHibernation>> {
+    if filesystem_type(device).as_deref() != Some("ntfs") {
+        return Ok(None);
+    }
+
+    let status = Command::new("ntfs-3g.probe")
+        .args(["--readwrite", device])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| {
+            SysError::OperationFailed(format!("Failed to execute ntfs-3g.probe: {}", e))
+        })?;
+    debug!("ntfs-3g.probe on {} exited with {}", device, status);
+    Ok(status
+        .code()
+        .and_then(WindowsHibernation::from_probe_status))
+}
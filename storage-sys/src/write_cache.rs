@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Volatile write cache of drives through hdparm and nvme-cli
+//!
+//! Persistent settings are kept in a udev rule per drive, next to the queue
+//! tuning rules of [`crate::io_tuning`].
+
+use crate::error::{Result, SysError};
+use crate::io_tuning::{RULES_DIR, udev_id};
+use crate::migration::run;
+use std::path::{Path, PathBuf};
+use storage_types::WriteCacheStatus;
+use storage_types::write_cache::{
+    parse_hdparm_write_cache, parse_nvme_write_cache, write_cache_command, write_cache_query,
+    write_cache_rule_file_name, write_cache_udev_rule,
+};
+use tracing::{info, warn};
+
+/// Where udev looks for the programs rules run
+const PROGRAM_DIRS: [&str; 4] = ["/usr/sbin", "/usr/bin", "/sbin", "/bin"];
+
+fn device_name(device: &str) -> &str {
+    device.strip_prefix("/dev/").unwrap_or(device)
+}
+
+fn is_nvme(device: &str) -> bool {
+    device_name(device).starts_with("nvme")
+}
+
+/// Absolute path of `program`; udev rules cannot rely on `PATH`
+pub(crate) fn program_path(program: &str) -> Option<PathBuf> {
+    PROGRAM_DIRS
+        .iter()
+        .map(|dir| Path::new(dir).join(program))
+        .find(|path| path.is_file())
+}
+
+/// Rules file of `device`, when it has a stable identifier
+fn rule_path(device: &str) -> Option<(PathBuf, &'static str, String)> {
+    let (key, value) = udev_id(device)?;
+    let path = Path::new(RULES_DIR).join(write_cache_rule_file_name(&value));
+    Some((path, key, value))
+}
+
+/// Write cache of a whole-disk `device` (e.g., "/dev/sda")
+///
+/// Drives whose cache cannot be queried, or that lack the tool to do so,
+/// are reported as not supported.
+pub fn write_cache_status(device: &str) -> WriteCacheStatus {
+    let nvme = is_nvme(device);
+    let (program, args) = write_cache_query(nvme, device);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let enabled = run(program, &args, None).ok().and_then(|output| {
+        if nvme {
+            parse_nvme_write_cache(&output)
+        } else {
+            parse_hdparm_write_cache(&output)
+        }
+    });
+    WriteCacheStatus {
+        supported: enabled.is_some(),
+        enabled: enabled.unwrap_or(false),
+        persistent: rule_path(device).is_some_and(|(path, _, _)| path.exists()),
+    }
+}
+
+/// Switch the write cache of a whole-disk `device`, and keep the setting in
+/// a udev rule when `persistent`, or drop the drive's rule otherwise
+pub fn set_write_cache(device: &str, enabled: bool, persistent: bool) -> Result<()> {
+    if !write_cache_status(device).supported {
+        return Err(SysError::OperationFailed(format!(
+            "{} does not support switching its write cache",
+            device
+        )));
+    }
+
+    let nvme = is_nvme(device);
+    let (program, args) = write_cache_command(nvme, device, enabled);
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    run(program, &arg_refs, None)?;
+    info!(
+        "Turned the write cache of {} {}",
+        device,
+        if enabled { "on" } else { "off" }
+    );
+    if enabled {
+        // The kernel must flush a cache it may have believed absent;
+        // libata notices on its own, other transports do not
+        let sysfs = Path::new("/sys/block")
+            .join(device_name(device))
+            .join("queue/write_cache");
+        if let Err(e) = std::fs::write(&sysfs, "write back") {
+            warn!("Failed to update {}: {}", sysfs.display(), e);
+        }
+    }
+
+    let rule = rule_path(device);
+    if persistent {
+        let (path, key, value) = rule.ok_or_else(|| {
+            SysError::OperationFailed(format!(
+                "{} has no WWN or serial number to keep its settings by",
+                device
+            ))
+        })?;
+        let program_path = program_path(program)
+            .ok_or_else(|| SysError::OperationFailed(format!("{} is not installed", program)))?;
+        let (_, args) = write_cache_command(nvme, "$devnode", enabled);
+        let rule = write_cache_udev_rule(key, &value, &program_path.to_string_lossy(), &args);
+        std::fs::write(&path, rule)?;
+        info!("Kept the write cache of {} in {}", device, path.display());
+    } else if let Some((path, _, _)) = rule.filter(|(path, _, _)| path.exists()) {
+        std::fs::remove_file(&path)?;
+        info!("Removed {}", path.display());
+    } else {
+        return Ok(());
+    }
+    run("udevadm", &["control", "--reload"], None)?;
+    Ok(())
+}
@@ -0,0 +1,496 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The demo dataset
+//!
+//! Five drives:
+//!
+//! - an NVMe system drive with an EFI System Partition, a boot partition
+//!   and an unlocked LUKS container holding the btrfs root
+//! - two hard disks mirrored in a RAID-1 array, one of them failing
+//! - the array itself, an LVM physical volume with two logical volumes
+//! - a USB stick with an unmounted exFAT partition
+
+use std::collections::BTreeMap;
+
+use storage_types::{
+    DiskHealthSummary, DiskInfo, PartitionInfo, SmartAttribute, SmartInfo, SmartStatus,
+    TemperatureThresholds, Usage, VolumeInfo, VolumeKind,
+};
+
+const MIB: u64 = 1 << 20;
+const GIB: u64 = 1 << 30;
+
+/// GPT partition type GUIDs used below
+const ESP_TYPE: &str = "c12a7328-f81f-11d2-ba4b-00a0c93ec93b";
+const LINUX_TYPE: &str = "0fc63daf-8483-4772-8e79-3d69d8477de4";
+const RAID_TYPE: &str = "a19d880f-05fc-4d3b-a006-743f0f84911e";
+const LVM_TYPE: &str = "e6d6d379-f507-44c2-a23c-238f2a3df928";
+
+/// SMART data of one demo drive
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoSmart {
+    pub status: SmartStatus,
+    pub attributes: Vec<SmartAttribute>,
+}
+
+impl DemoSmart {
+    /// The data as the service's health scoring reads it
+    pub fn info(&self) -> SmartInfo {
+        let mut attributes: BTreeMap<String, String> = self
+            .attributes
+            .iter()
+            .map(|attribute| (attribute.name.clone(), attribute.raw_value.to_string()))
+            .collect();
+        let health = if self.status.healthy {
+            "PASSED"
+        } else {
+            "FAILED"
+        };
+        attributes.insert("overall_health".to_string(), health.to_string());
+        SmartInfo {
+            device_type: if self.status.device.contains("nvme") {
+                "NVMe".to_string()
+            } else {
+                "ATA".to_string()
+            },
+            temperature_c: self.status.temperature_celsius.map(|c| c.max(0) as u64),
+            power_on_hours: self.status.power_on_hours,
+            attributes,
+            ..Default::default()
+        }
+    }
+}
+
+/// Drives, volumes and SMART data of a made-up machine
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoDataset {
+    pub disks: Vec<DiskInfo>,
+    /// All volumes, flat, each naming its parent in `parent_path` as the
+    /// service's ListVolumes does
+    pub volumes: Vec<VolumeInfo>,
+    pub partitions: Vec<PartitionInfo>,
+    /// SMART data, by drive device path
+    pub smart: BTreeMap<String, DemoSmart>,
+}
+
+impl DemoDataset {
+    /// Partitions on the drive `device`
+    pub fn partitions_of(&self, device: &str) -> Vec<PartitionInfo> {
+        self.partitions
+            .iter()
+            .filter(|partition| partition.parent_path == device)
+            .cloned()
+            .collect()
+    }
+
+    /// Health scores of the drives with SMART data, as of `now`
+    pub fn health(&self, now: u64) -> Vec<DiskHealthSummary> {
+        self.smart
+            .iter()
+            .map(|(device, smart)| {
+                DiskHealthSummary::compute(
+                    device,
+                    &smart.info(),
+                    &[],
+                    &[],
+                    &TemperatureThresholds::default(),
+                    now,
+                )
+            })
+            .collect()
+    }
+}
+
+fn disk(device: &str, model: &str, serial: &str, size: u64) -> DiskInfo {
+    DiskInfo {
+        device: device.to_string(),
+        id: format!("{}-{serial}", model.replace(' ', "_")),
+        model: model.to_string(),
+        serial: serial.to_string(),
+        vendor: String::new(),
+        revision: String::new(),
+        size,
+        connection_bus: "ata".to_string(),
+        rotation_rate: None,
+        logical_sector_size: 512,
+        physical_sector_size: 4096,
+        removable: false,
+        ejectable: false,
+        media_removable: false,
+        media_available: true,
+        optical: false,
+        optical_blank: false,
+        read_only: false,
+        can_power_off: false,
+        virtual_disk: false,
+        discard_supported: false,
+        rotational: false,
+        is_loop: false,
+        backing_file: None,
+        partition_table_type: Some("gpt".to_string()),
+        gpt_usable_range: None,
+    }
+}
+
+/// A partition at `offset` of `disk`, holding a filesystem or member of
+/// `id_type`
+fn partition(disk: &str, number: u32, offset: u64, size: u64, id_type: &str) -> VolumeInfo {
+    let separator = if disk.ends_with(|c: char| c.is_ascii_digit()) {
+        "p"
+    } else {
+        ""
+    };
+    VolumeInfo {
+        kind: VolumeKind::Partition,
+        label: String::new(),
+        size,
+        offset,
+        partition_number: number,
+        id_type: id_type.to_string(),
+        device_path: Some(format!("{disk}{separator}{number}")),
+        parent_path: Some(disk.to_string()),
+        has_filesystem: !id_type.ends_with("member") && id_type != "crypto_LUKS",
+        mount_points: Vec::new(),
+        usage: None,
+        locked: false,
+        children: Vec::new(),
+    }
+}
+
+/// A volume on top of `parent`, like an unlocked LUKS container's cleartext
+/// device or an LVM logical volume
+fn inner(kind: VolumeKind, device: &str, parent: &str, size: u64, id_type: &str) -> VolumeInfo {
+    VolumeInfo {
+        kind,
+        device_path: Some(device.to_string()),
+        parent_path: Some(parent.to_string()),
+        offset: 0,
+        partition_number: 0,
+        has_filesystem: true,
+        ..partition(parent, 0, 0, size, id_type)
+    }
+}
+
+fn labelled(volume: VolumeInfo, label: &str) -> VolumeInfo {
+    VolumeInfo {
+        label: label.to_string(),
+        ..volume
+    }
+}
+
+/// `volume` mounted at `mount_point`, `percent` full
+fn mounted(volume: VolumeInfo, mount_point: &str, percent: u32) -> VolumeInfo {
+    let blocks = volume.size / 4096;
+    let used = blocks * u64::from(percent) / 100;
+    VolumeInfo {
+        mount_points: vec![mount_point.to_string()],
+        usage: Some(Usage {
+            filesystem: volume.id_type.clone(),
+            blocks,
+            used,
+            available: blocks - used,
+            percent,
+            mount_point: mount_point.to_string(),
+        }),
+        ..volume
+    }
+}
+
+/// The partition table entry of a partition volume
+fn partition_info(volume: &VolumeInfo, type_id: &str, type_name: &str) -> PartitionInfo {
+    let disk = volume.parent_path.clone().unwrap_or_default();
+    PartitionInfo {
+        device: volume.device_path.clone().unwrap_or_default(),
+        number: volume.partition_number,
+        parent_path: disk,
+        size: volume.size,
+        offset: volume.offset,
+        type_id: type_id.to_string(),
+        type_name: type_name.to_string(),
+        flags: 0,
+        name: volume.label.clone(),
+        uuid: format!(
+            "5e1f0a3c-{:04x}-4d2b-9c6e-8a7b6c5d4e3f",
+            volume.offset / MIB
+        ),
+        table_type: "gpt".to_string(),
+        has_filesystem: volume.has_filesystem,
+        filesystem_type: volume.has_filesystem.then(|| volume.id_type.clone()),
+        mount_points: volume.mount_points.clone(),
+        usage: volume.usage.clone(),
+    }
+}
+
+fn attribute(id: u8, name: &str, current: u8, threshold: u8, raw_value: u64) -> SmartAttribute {
+    SmartAttribute {
+        id,
+        name: name.to_string(),
+        current,
+        worst: current,
+        threshold,
+        raw_value,
+        failing: current <= threshold,
+    }
+}
+
+fn smart(device: &str, healthy: bool, temperature: i16, hours: u64) -> SmartStatus {
+    SmartStatus {
+        device: device.to_string(),
+        healthy,
+        temperature_celsius: Some(temperature),
+        power_on_hours: Some(hours),
+        power_cycle_count: Some(hours / 30),
+        test_running: false,
+        test_percent_remaining: None,
+    }
+}
+
+/// The demo machine's storage
+pub fn demo_dataset() -> DemoDataset {
+    let nvme = DiskInfo {
+        vendor: "Samsung".to_string(),
+        revision: "4B2QJXD7".to_string(),
+        connection_bus: "nvme".to_string(),
+        discard_supported: true,
+        ..disk(
+            "/dev/nvme0n1",
+            "Samsung SSD 990 PRO 1TB",
+            "S6Z1NJ0W412345A",
+            1_000_204_886_016,
+        )
+    };
+    let hdd = |device: &str, serial: &str| DiskInfo {
+        vendor: "Western Digital".to_string(),
+        revision: "82.00A82".to_string(),
+        rotation_rate: Some(5400),
+        rotational: true,
+        ..disk(device, "WDC WD40EFRX-68N32N0", serial, 4_000_787_030_016)
+    };
+    let array = DiskInfo {
+        connection_bus: String::new(),
+        rotational: true,
+        ..disk(
+            "/dev/md127",
+            "RAID-1 Array",
+            "md-uuid-3f1c2b4a",
+            4_000_650_887_168,
+        )
+    };
+    let stick = DiskInfo {
+        vendor: "SanDisk".to_string(),
+        connection_bus: "usb".to_string(),
+        logical_sector_size: 512,
+        physical_sector_size: 512,
+        removable: true,
+        ejectable: true,
+        media_removable: true,
+        can_power_off: true,
+        partition_table_type: Some("dos".to_string()),
+        ..disk(
+            "/dev/sdc",
+            "SanDisk Ultra Fit",
+            "4C530001170919115372",
+            61_530_439_680,
+        )
+    };
+
+    // System drive: ESP, /boot and LUKS holding the btrfs root
+    let esp = mounted(
+        labelled(
+            partition("/dev/nvme0n1", 1, MIB, 600 * MIB, "vfat"),
+            "EFI System Partition",
+        ),
+        "/boot/efi",
+        4,
+    );
+    let boot = mounted(
+        partition("/dev/nvme0n1", 2, 601 * MIB, GIB, "ext4"),
+        "/boot",
+        31,
+    );
+    let luks_size = nvme.size - 2 * GIB - 2 * MIB;
+    let luks = partition("/dev/nvme0n1", 3, 601 * MIB + GIB, luks_size, "crypto_LUKS");
+    let luks = VolumeInfo {
+        kind: VolumeKind::CryptoContainer,
+        ..luks
+    };
+    let root = mounted(
+        labelled(
+            inner(
+                VolumeKind::Filesystem,
+                "/dev/dm-0",
+                "/dev/nvme0n1p3",
+                luks_size - 16 * MIB,
+                "btrfs",
+            ),
+            "fedora",
+        ),
+        "/",
+        58,
+    );
+
+    // Mirror members and the array's LVM volumes
+    let member_size = 4_000_785_104_896;
+    let member_a = partition("/dev/sda", 1, MIB, member_size, "linux_raid_member");
+    let member_b = partition("/dev/sdb", 1, MIB, member_size, "linux_raid_member");
+    let pv = VolumeInfo {
+        kind: VolumeKind::LvmPhysicalVolume,
+        ..partition("/dev/md127", 1, MIB, array.size - 2 * MIB, "LVM2_member")
+    };
+    let media = mounted(
+        labelled(
+            inner(
+                VolumeKind::LvmLogicalVolume,
+                "/dev/dm-1",
+                "/dev/md127p1",
+                2_600 * GIB,
+                "xfs",
+            ),
+            "vault-media",
+        ),
+        "/srv/media",
+        71,
+    );
+    let backup = mounted(
+        labelled(
+            inner(
+                VolumeKind::LvmLogicalVolume,
+                "/dev/dm-2",
+                "/dev/md127p1",
+                1_000 * GIB,
+                "ext4",
+            ),
+            "vault-backup",
+        ),
+        "/srv/backup",
+        38,
+    );
+
+    let exfat = labelled(
+        partition("/dev/sdc", 1, MIB, stick.size - MIB, "exfat"),
+        "SANDISK",
+    );
+
+    let partitions = vec![
+        partition_info(&esp, ESP_TYPE, "EFI System"),
+        partition_info(&boot, LINUX_TYPE, "Linux filesystem"),
+        partition_info(&luks, LINUX_TYPE, "Linux filesystem"),
+        partition_info(&member_a, RAID_TYPE, "Linux RAID"),
+        partition_info(&member_b, RAID_TYPE, "Linux RAID"),
+        partition_info(&pv, LVM_TYPE, "Linux LVM"),
+        PartitionInfo {
+            type_id: "0x07".to_string(),
+            type_name: "HPFS/NTFS/exFAT".to_string(),
+            table_type: "dos".to_string(),
+            ..partition_info(&exfat, "", "")
+        },
+    ];
+
+    let ssd_attributes = vec![
+        attribute(5, "Reallocated_Sector_Ct", 100, 10, 0),
+        attribute(9, "Power_On_Hours", 99, 0, 3_120),
+        attribute(177, "Wear_Leveling_Count", 97, 5, 3),
+        attribute(194, "Temperature_Celsius", 59, 0, 41),
+    ];
+    let hdd_attributes = |reallocated: u64, pending: u64, uncorrectable: u64| {
+        let health = |raw: u64| if raw > 0 { 1 } else { 200 };
+        vec![
+            attribute(1, "Raw_Read_Error_Rate", 200, 51, 0),
+            attribute(
+                5,
+                "Reallocated_Sector_Ct",
+                health(reallocated).min(140),
+                140,
+                reallocated,
+            ),
+            attribute(9, "Power_On_Hours", 41, 0, 43_410),
+            attribute(194, "Temperature_Celsius", 112, 0, 38),
+            attribute(197, "Current_Pending_Sector", health(pending), 0, pending),
+            attribute(
+                198,
+                "Offline_Uncorrectable",
+                health(uncorrectable),
+                0,
+                uncorrectable,
+            ),
+        ]
+    };
+    let smart = BTreeMap::from([
+        (
+            "/dev/nvme0n1".to_string(),
+            DemoSmart {
+                status: smart("/dev/nvme0n1", true, 41, 3_120),
+                attributes: ssd_attributes,
+            },
+        ),
+        (
+            "/dev/sda".to_string(),
+            DemoSmart {
+                status: smart("/dev/sda", true, 36, 43_410),
+                attributes: hdd_attributes(0, 0, 0),
+            },
+        ),
+        (
+            "/dev/sdb".to_string(),
+            DemoSmart {
+                status: smart("/dev/sdb", false, 38, 43_408),
+                attributes: hdd_attributes(1_432, 24, 8),
+            },
+        ),
+    ]);
+
+    DemoDataset {
+        disks: vec![
+            nvme,
+            hdd("/dev/sda", "WD-WCC7K4KX1234"),
+            hdd("/dev/sdb", "WD-WCC7K6DZ5678"),
+            array,
+            stick,
+        ],
+        volumes: vec![
+            esp, boot, luks, root, member_a, member_b, pv, media, backup, exfat,
+        ],
+        partitions,
+        smart,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storage_types::{HealthFactor, HealthLevel};
+
+    #[test]
+    fn volumes_hang_off_known_devices() {
+        let dataset = demo_dataset();
+        for volume in &dataset.volumes {
+            let parent = volume.parent_path.as_deref().unwrap();
+            let known = dataset.disks.iter().any(|disk| disk.device == parent)
+                || dataset
+                    .volumes
+                    .iter()
+                    .any(|other| other.device_path.as_deref() == Some(parent));
+            assert!(known, "{:?} has no parent", volume.device_path);
+            assert_ne!(volume.device_path.as_deref(), Some(parent));
+        }
+        assert_eq!(dataset.partitions_of("/dev/nvme0n1").len(), 3);
+    }
+
+    #[test]
+    fn one_drive_is_failing() {
+        let health = demo_dataset().health(0);
+        let failing: Vec<_> = health
+            .iter()
+            .filter(|summary| summary.level == HealthLevel::Critical)
+            .collect();
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].device, "/dev/sdb");
+        assert!(failing[0].factors.contains(&HealthFactor::SmartFailed));
+        assert!(
+            health
+                .iter()
+                .filter(|summary| summary.device != "/dev/sdb")
+                .all(|summary| summary.level == HealthLevel::Good)
+        );
+    }
+}
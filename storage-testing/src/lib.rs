@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Fabricated storage data for demos, screenshots and tests
+//!
+//! Contributors and documentation need drives in every state the app can
+//! show, which no single machine has. [`demo_dataset`] builds a fixed set
+//! of them out of the same `storage-types` models the service returns, so
+//! the app can show them with `--demo` without a running service.
+
+pub mod demo;
+
+pub use demo::{DemoDataset, DemoSmart, demo_dataset};
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Sector sizes and partition alignment
+//!
+//! Most drives today store 4096-byte physical sectors, and either expose
+//! them as they are (4Kn) or emulate 512-byte logical sectors on top (512e).
+//! A partition that does not start on a physical sector makes writes
+//! straddle two of them, which the drive turns into read-modify-write
+//! cycles: a silent slowdown that is worst on SMR drives.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ByteRange, GPT_ALIGNMENT_BYTES, PartitionLayout};
+
+/// Sector size assumed when the kernel does not report one
+pub const DEFAULT_SECTOR_SIZE: u64 = 512;
+
+/// How a drive's logical sectors map onto its physical ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SectorFormat {
+    /// 512-byte logical and physical sectors
+    Native512,
+    /// 512-byte logical sectors emulated on 4096-byte physical ones
+    Emulated512,
+    /// 4096-byte logical and physical sectors
+    Native4K,
+    /// Any other combination
+    Other,
+}
+
+impl SectorFormat {
+    pub fn from_sizes(logical: u64, physical: u64) -> Self {
+        match (logical, physical) {
+            (512, 512) => Self::Native512,
+            (512, 4096) => Self::Emulated512,
+            (4096, 4096) => Self::Native4K,
+            _ => Self::Other,
+        }
+    }
+
+    /// Short name, as drive datasheets use it
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::Native512 => Some("512n"),
+            Self::Emulated512 => Some("512e"),
+            Self::Native4K => Some("4Kn"),
+            Self::Other => None,
+        }
+    }
+}
+
+/// Whether `offset` starts a physical sector of `physical_sector_size` bytes
+pub fn is_aligned(offset: u64, physical_sector_size: u64) -> bool {
+    offset.is_multiple_of(physical_sector_size.max(DEFAULT_SECTOR_SIZE))
+}
+
+/// Where the partition at `range` can move to start on a 1 MiB boundary,
+/// which suits every physical sector size, while keeping its size
+///
+/// The closest boundary before it is preferred, then the one after it;
+/// `None` when neither leaves room next to the neighbouring partitions.
+pub fn realigned_start(layout: &PartitionLayout, range: &ByteRange) -> Option<u64> {
+    let space = layout.space_around(range);
+    let before = range.start - range.start % GPT_ALIGNMENT_BYTES;
+    let after = range.start.next_multiple_of(GPT_ALIGNMENT_BYTES);
+    [before, after]
+        .into_iter()
+        .find(|&start| start >= space.start && start + range.size() <= space.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn layout(partitions: Vec<ByteRange>) -> PartitionLayout {
+        PartitionLayout {
+            table_type: Some("gpt".to_string()),
+            sector_size: 512,
+            usable: ByteRange {
+                start: 34 * 512,
+                end: 100 * MIB,
+            },
+            partitions,
+        }
+    }
+
+    #[test]
+    fn sector_formats_are_named() {
+        assert_eq!(SectorFormat::from_sizes(512, 4096).name(), Some("512e"));
+        assert_eq!(SectorFormat::from_sizes(4096, 4096).name(), Some("4Kn"));
+        assert_eq!(SectorFormat::from_sizes(512, 512).name(), Some("512n"));
+        assert_eq!(SectorFormat::from_sizes(2048, 2048).name(), None);
+    }
+
+    #[test]
+    fn alignment_follows_the_physical_sector() {
+        assert!(is_aligned(MIB, 4096));
+        assert!(!is_aligned(63 * 512, 4096));
+        assert!(is_aligned(63 * 512, 512));
+        assert!(is_aligned(63 * 512, 0));
+    }
+
+    #[test]
+    fn misaligned_partitions_move_to_the_nearest_free_boundary() {
+        // The table takes the start of the disk, so a partition at the
+        // legacy sector 63 moves up to 1 MiB
+        let legacy = ByteRange {
+            start: 63 * 512,
+            end: 63 * 512 + 10 * MIB,
+        };
+        assert_eq!(realigned_start(&layout(vec![legacy]), &legacy), Some(MIB));
+
+        // Free space before the partition is used first
+        let shifted = ByteRange {
+            start: 20 * MIB + 512,
+            end: 30 * MIB + 512,
+        };
+        assert_eq!(
+            realigned_start(&layout(vec![shifted]), &shifted),
+            Some(20 * MIB)
+        );
+
+        // Hemmed in on both sides
+        let before = ByteRange {
+            start: MIB,
+            end: 20 * MIB + 512,
+        };
+        let after = ByteRange {
+            start: 30 * MIB + 512,
+            end: 40 * MIB,
+        };
+        assert_eq!(
+            realigned_start(&layout(vec![before, shifted, after]), &shifted),
+            None
+        );
+    }
+}
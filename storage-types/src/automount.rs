@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! systemd mount and automount units
+//!
+//! Instead of an fstab line, a volume or network remote can get a pair of
+//! units in /etc/systemd/system: a `.mount` unit saying what to mount where,
+//! and an `.automount` unit that mounts it on first access and unmounts it
+//! again after an idle timeout. Both are named after the escaped mount
+//! point, as systemd requires. Units written here start with
+//! [`AUTOMOUNT_MARKER`], so that only those are listed and removed.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// First line of the units written by this tool
+pub const AUTOMOUNT_MARKER: &str = "# Managed by COSMIC Storage; edits may be overwritten";
+
+/// Idle timeouts offered, in seconds; 0 keeps the mount until shutdown
+pub const IDLE_TIMEOUT_PRESETS: &[u32] = &[0, 60, 300, 600, 1800, 3600];
+
+/// A mount point mounted on access through a systemd automount unit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutomountUnit {
+    /// Device path, e.g. "/dev/disk/by-uuid/…", or remote, e.g. "media:"
+    pub what: String,
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    /// Comma-separated mount options
+    pub options: String,
+    /// Seconds without access after which it is unmounted, 0 for never
+    pub idle_timeout_secs: u32,
+    /// Needs the network, so waits for it and is unmounted before it goes
+    pub network: bool,
+}
+
+impl AutomountUnit {
+    /// Units for a filesystem, found by its UUID when it has one
+    pub fn for_volume(
+        device: &str,
+        uuid: Option<&str>,
+        fs_type: &str,
+        mount_point: PathBuf,
+    ) -> Self {
+        let what = match uuid.filter(|uuid| !uuid.is_empty()) {
+            Some(uuid) => format!("/dev/disk/by-uuid/{uuid}"),
+            None => device.to_string(),
+        };
+        Self {
+            what,
+            mount_point,
+            fs_type: fs_type.to_string(),
+            options: "nofail".to_string(),
+            idle_timeout_secs: 600,
+            network: false,
+        }
+    }
+
+    /// Units for a system-wide rclone remote, mounted by rclone as a mount
+    /// helper
+    pub fn for_rclone_remote(remote: &str, config_path: &Path, mount_point: PathBuf) -> Self {
+        Self {
+            what: format!("{remote}:"),
+            mount_point,
+            fs_type: "rclone".to_string(),
+            options: format!(
+                "rw,_netdev,allow_other,args2env,vfs-cache-mode=writes,config={}",
+                config_path.display()
+            ),
+            idle_timeout_secs: 600,
+            network: true,
+        }
+    }
+
+    /// Name of the units without the suffix, e.g. "mnt-data" for /mnt/data
+    pub fn unit_name(&self) -> String {
+        escape_unit_path(&self.mount_point)
+    }
+
+    /// Why systemd could not use these units, if it could not
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.mount_point.is_absolute() || self.mount_point == Path::new("/") {
+            return Err("The mount point must be an absolute path other than /".to_string());
+        }
+        if self.what.trim().is_empty() || self.fs_type.trim().is_empty() {
+            return Err("What to mount and its type are required".to_string());
+        }
+        let fields = [
+            self.what.as_str(),
+            self.fs_type.as_str(),
+            self.options.as_str(),
+            &self.mount_point.to_string_lossy(),
+        ];
+        if fields.iter().any(|field| field.contains(['\n', '\r'])) {
+            return Err("Unit settings can't span lines".to_string());
+        }
+        // systemd expands `%` specifiers in these settings
+        if fields.iter().any(|field| field.contains('%')) {
+            return Err("Unit settings can't contain %".to_string());
+        }
+        Ok(())
+    }
+
+    /// Contents of the `.mount` unit
+    pub fn mount_unit(&self) -> String {
+        let mut unit = format!(
+            "{AUTOMOUNT_MARKER}\n[Unit]\nDescription=Mount of {}\n",
+            self.what
+        );
+        if self.network {
+            unit.push_str("After=network-online.target\nWants=network-online.target\n");
+        }
+        unit.push_str(&format!(
+            "\n[Mount]\nWhat={}\nWhere={}\nType={}\n",
+            self.what,
+            self.mount_point.display(),
+            self.fs_type
+        ));
+        if !self.options.is_empty() {
+            unit.push_str(&format!("Options={}\n", self.options));
+        }
+        unit
+    }
+
+    /// Contents of the `.automount` unit
+    pub fn automount_unit(&self) -> String {
+        let wanted_by = if self.network {
+            "remote-fs.target"
+        } else {
+            "local-fs.target"
+        };
+        format!(
+            "{AUTOMOUNT_MARKER}\n[Unit]\nDescription=Automount of {}\n\n\
+             [Automount]\nWhere={}\nTimeoutIdleSec={}\n\n\
+             [Install]\nWantedBy={wanted_by}\n",
+            self.mount_point.display(),
+            self.mount_point.display(),
+            self.idle_timeout_secs
+        )
+    }
+
+    /// The units written by [`Self::mount_unit`] and
+    /// [`Self::automount_unit`]; None for units this tool did not write
+    pub fn parse(mount_unit: &str, automount_unit: &str) -> Option<Self> {
+        if !mount_unit.starts_with(AUTOMOUNT_MARKER)
+            || !automount_unit.starts_with(AUTOMOUNT_MARKER)
+        {
+            return None;
+        }
+        let value = |unit: &str, key: &str| {
+            unit.lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+                .map(str::to_string)
+        };
+        Some(Self {
+            what: value(mount_unit, "What")?,
+            mount_point: PathBuf::from(value(mount_unit, "Where")?),
+            fs_type: value(mount_unit, "Type")?,
+            options: value(mount_unit, "Options").unwrap_or_default(),
+            idle_timeout_secs: value(automount_unit, "TimeoutIdleSec")
+                .and_then(|secs| secs.parse().ok())
+                .unwrap_or(0),
+            network: mount_unit.contains("After=network-online.target"),
+        })
+    }
+}
+
+/// A path escaped as systemd names units after it, like `systemd-escape
+/// --path`
+pub fn escape_unit_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    let trimmed: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    if trimmed.is_empty() {
+        return "-".to_string();
+    }
+    let mut escaped = String::new();
+    for (index, byte) in trimmed.join("/").bytes().enumerate() {
+        match byte {
+            b'/' => escaped.push('-'),
+            b'.' if index == 0 => escaped.push_str("\\x2e"),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b':' | b'_' | b'.' => {
+                escaped.push(byte as char)
+            }
+            _ => escaped.push_str(&format!("\\x{byte:02x}")),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_are_named_after_the_mount_point_and_read_back() {
+        assert_eq!(escape_unit_path(Path::new("/mnt/data")), "mnt-data");
+        assert_eq!(
+            escape_unit_path(Path::new("/srv//Shared Files/")),
+            "srv-Shared\\x20Files"
+        );
+        assert_eq!(escape_unit_path(Path::new("/.hidden")), "\\x2ehidden");
+        assert_eq!(
+            escape_unit_path(Path::new("/media/usb-disk")),
+            "media-usb\\x2ddisk"
+        );
+
+        let remote = AutomountUnit::for_rclone_remote(
+            "media",
+            Path::new("/etc/rclone.conf"),
+            PathBuf::from("/mnt/media"),
+        );
+        assert_eq!(remote.unit_name(), "mnt-media");
+        assert!(remote.mount_unit().contains("Type=rclone\n"));
+        assert!(
+            remote
+                .automount_unit()
+                .contains("WantedBy=remote-fs.target")
+        );
+        assert_eq!(
+            AutomountUnit::parse(&remote.mount_unit(), &remote.automount_unit()),
+            Some(remote.clone())
+        );
+
+        let volume =
+            AutomountUnit::for_volume("/dev/sdb1", Some("1234-ABCD"), "exfat", "/mnt/x".into());
+        assert_eq!(volume.what, "/dev/disk/by-uuid/1234-ABCD");
+        assert!(volume.validate().is_ok());
+        assert!(
+            AutomountUnit {
+                mount_point: "relative".into(),
+                ..volume.clone()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            AutomountUnit {
+                options: "nofail,uid=%U".into(),
+                ..volume.clone()
+            }
+            .validate()
+            .is_err()
+        );
+        // Units written by hand are left alone
+        let foreign = volume.mount_unit().replacen(AUTOMOUNT_MARKER, "", 1);
+        assert_eq!(
+            AutomountUnit::parse(&foreign, &volume.automount_unit()),
+            None
+        );
+    }
+}
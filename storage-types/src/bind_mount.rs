@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Bind mounts
+//!
+//! A bind mount makes a directory, or a whole mounted filesystem, appear at a
+//! second place. The kernel does not mark them: in the mount table a bind of
+//! a subdirectory is a mount whose root within its filesystem is not the
+//! filesystem's root, and a bind of a whole filesystem is a second mount of
+//! the same device. Persistent ones are fstab entries with the "bind" option.
+
+use crate::user_mount::unescape;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A bind mount, mounted or configured in fstab
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BindMount {
+    /// Directory that is bound
+    pub source: PathBuf,
+    /// Where it appears
+    pub target: PathBuf,
+    pub read_only: bool,
+    /// Currently mounted
+    pub mounted: bool,
+    /// Configured in fstab, so mounted again at startup
+    pub persistent: bool,
+}
+
+/// A mount of a block device, from one line of mountinfo
+struct MountEntry {
+    device: String,
+    /// Directory within the filesystem that is mounted
+    root: PathBuf,
+    mount_point: PathBuf,
+    read_only: bool,
+    /// Whether this is how the filesystem itself is mounted, as opposed to
+    /// a bind of part of it
+    primary: bool,
+}
+
+fn parse_mountinfo_line(line: &str) -> Option<MountEntry> {
+    let (left, right) = line.split_once(" - ")?;
+    let mut left = left.split_whitespace();
+    let device = left.nth(2)?.to_string();
+    let root = PathBuf::from(unescape(left.next()?));
+    let mount_point = PathBuf::from(unescape(left.next()?));
+    let read_only = left.next()?.split(',').any(|option| option == "ro");
+
+    let mut right = right.split_whitespace();
+    let (fs_type, source, super_options) = (right.next()?, right.next()?, right.next()?);
+    // Binds of pseudo filesystems are made by systemd and container
+    // runtimes, not users
+    if !source.starts_with("/dev/") {
+        return None;
+    }
+
+    // A btrfs subvolume is mounted with its path as root
+    let subvolume = (fs_type == "btrfs")
+        .then(|| {
+            super_options
+                .split(',')
+                .find_map(|option| option.strip_prefix("subvol="))
+        })
+        .flatten();
+    let primary =
+        root == Path::new("/") || subvolume.is_some_and(|subvol| root == Path::new(subvol));
+
+    Some(MountEntry {
+        device,
+        root,
+        mount_point,
+        read_only,
+        primary,
+    })
+}
+
+/// Bind mounts in the contents of a mountinfo file and of fstab
+pub fn parse_bind_mounts(mountinfo: &str, fstab: &str) -> Vec<BindMount> {
+    let entries: Vec<MountEntry> = mountinfo.lines().filter_map(parse_mountinfo_line).collect();
+    let mut binds = Vec::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        // The first mount of a filesystem is the original, later ones of the
+        // same root are binds of it
+        let is_bind = !entry.primary
+            || entries[..index].iter().any(|earlier| {
+                earlier.primary && earlier.device == entry.device && earlier.root == entry.root
+            });
+        if !is_bind {
+            continue;
+        }
+        // The bound directory is found through the original mount that
+        // contains it, the one with the deepest root
+        let Some(origin) = entries
+            .iter()
+            .filter(|origin| {
+                origin.primary
+                    && origin.device == entry.device
+                    && entry.root.starts_with(&origin.root)
+                    && origin.mount_point != entry.mount_point
+            })
+            .max_by_key(|origin| origin.root.components().count())
+        else {
+            continue;
+        };
+        let Ok(relative) = entry.root.strip_prefix(&origin.root) else {
+            continue;
+        };
+        binds.push(BindMount {
+            source: origin.mount_point.join(relative),
+            target: entry.mount_point.clone(),
+            read_only: entry.read_only,
+            mounted: true,
+            persistent: false,
+        });
+    }
+
+    for line in fstab.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [source, target, _fs_type, options, ..] = fields[..] else {
+            continue;
+        };
+        let options: Vec<&str> = options.split(',').collect();
+        if !options
+            .iter()
+            .any(|option| matches!(*option, "bind" | "rbind"))
+        {
+            continue;
+        }
+        let target = PathBuf::from(unescape(target));
+        match binds.iter_mut().find(|bind| bind.target == target) {
+            Some(bind) => bind.persistent = true,
+            None => binds.push(BindMount {
+                source: PathBuf::from(unescape(source)),
+                target,
+                read_only: options.contains(&"ro"),
+                mounted: false,
+                persistent: true,
+            }),
+        }
+    }
+
+    binds.sort_by(|a, b| a.target.cmp(&b.target));
+    binds
+}
+
+/// The fstab line of a bind mount
+pub fn fstab_bind_line(source: &Path, target: &Path, read_only: bool) -> String {
+    let options = if read_only { "bind,ro" } else { "bind" };
+    format!(
+        "{} {} none {options} 0 0",
+        escape(&source.to_string_lossy()),
+        escape(&target.to_string_lossy())
+    )
+}
+
+/// `fstab` without the bind mount entries for `target`, or None when it
+/// has none
+pub fn remove_fstab_bind(fstab: &str, target: &Path) -> Option<String> {
+    let binds_target = |line: &str| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        !line.trim_start().starts_with('#')
+            && fields.len() >= 4
+            && Path::new(&unescape(fields[1])) == target
+            && fields[3]
+                .split(',')
+                .any(|option| matches!(option, "bind" | "rbind"))
+    };
+    if !fstab.lines().any(binds_target) {
+        return None;
+    }
+    let mut kept: String = fstab
+        .lines()
+        .filter(|line| !binds_target(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    kept.push('\n');
+    Some(kept)
+}
+
+/// Octal-escape the characters that separate fstab fields
+fn escape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            ' ' | '\t' | '\n' | '\\' => out.push_str(&format!("\\{:03o}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_binds_in_the_mount_table_and_fstab() {
+        let mountinfo = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+23 22 259:3 /@home /home rw,relatime shared:2 - btrfs /dev/nvme0n1p3 rw,subvol=/@home
+30 22 259:3 /@home/u/Shared\\040Files /srv/share ro,relatime shared:3 - btrfs /dev/nvme0n1p3 rw,subvol=/@home
+31 22 259:2 / /mnt/root-again rw,relatime shared:4 - ext4 /dev/nvme0n1p2 rw
+32 22 0:40 /credentials /run/credentials ro shared:5 - tmpfs tmpfs rw
+";
+        let fstab = "\
+# /srv/old /mnt/old none bind 0 0
+/home/u/Shared\\040Files /srv/share none bind,ro 0 0
+/data/music /home/u/Music none rbind 0 0
+UUID=1234 /data ext4 defaults 0 2
+";
+        let binds = parse_bind_mounts(mountinfo, fstab);
+        assert_eq!(
+            binds,
+            [
+                BindMount {
+                    source: PathBuf::from("/data/music"),
+                    target: PathBuf::from("/home/u/Music"),
+                    read_only: false,
+                    mounted: false,
+                    persistent: true,
+                },
+                BindMount {
+                    source: PathBuf::from("/"),
+                    target: PathBuf::from("/mnt/root-again"),
+                    read_only: false,
+                    mounted: true,
+                    persistent: false,
+                },
+                BindMount {
+                    source: PathBuf::from("/home/u/Shared Files"),
+                    target: PathBuf::from("/srv/share"),
+                    read_only: true,
+                    mounted: true,
+                    persistent: true,
+                },
+            ]
+        );
+
+        let line = fstab_bind_line(
+            Path::new("/home/u/Shared Files"),
+            Path::new("/srv/share"),
+            true,
+        );
+        assert_eq!(line, "/home/u/Shared\\040Files /srv/share none bind,ro 0 0");
+        let rest = remove_fstab_bind(fstab, Path::new("/srv/share")).unwrap();
+        assert!(!rest.contains("/srv/share"));
+        assert!(rest.contains("UUID=1234"));
+        assert_eq!(remove_fstab_bind(fstab, Path::new("/data")), None);
+    }
+}
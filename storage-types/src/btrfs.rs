@@ -42,3 +42,226 @@ pub struct DeletedSubvolume {
     pub id: u64,
     pub path: String,
 }
+
+/// Compression algorithms accepted for the `compression` property and the
+/// `compress=` mount option ("none" disables compression)
+pub const BTRFS_COMPRESSION_ALGORITHMS: &[&str] = &["none", "zstd", "lzo", "zlib"];
+
+/// Compression settings of a mounted BTRFS filesystem and a path on it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionInfo {
+    /// Path the property was read from
+    pub path: String,
+    /// Algorithm from the `compress=`/`compress-force=` mount option (e.g. "zstd:3")
+    pub mount_option: Option<String>,
+    /// Whether the mount uses `compress-force`
+    pub force: bool,
+    /// `compression` property of the path, if set
+    pub property: Option<String>,
+}
+
+/// Estimated effect of recompressing existing data below a path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionEstimate {
+    pub path: String,
+    /// Apparent size of all regular files
+    pub total_bytes: u64,
+    /// Current on-disk size (from compsize, if installed)
+    pub disk_bytes: Option<u64>,
+    /// Bytes of the sample that was test-compressed
+    pub sampled_bytes: u64,
+    /// Compressed size of the sample
+    pub sampled_compressed_bytes: u64,
+    /// Projected reduction in on-disk size
+    pub estimated_savings_bytes: u64,
+}
+
+/// No-copy-on-write (`chattr +C`) state of a directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NocowStatus {
+    pub path: String,
+    /// New files created in the directory are not copy-on-write
+    pub enabled: bool,
+    /// The directory already has entries, which keep their previous setting
+    pub non_empty: bool,
+}
+
+/// How a path changed between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SnapshotChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A single changed path, relative to the snapshot root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDiffEntry {
+    pub path: String,
+    pub kind: SnapshotChangeKind,
+}
+
+/// One page of the changes between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    /// Older snapshot (the base)
+    pub old_snapshot: String,
+    /// Newer snapshot
+    pub new_snapshot: String,
+    /// Changes in this page, sorted by path
+    pub entries: Vec<SnapshotDiffEntry>,
+    /// Index of the first entry in the full change list
+    pub offset: usize,
+    /// Number of changes in the full change list
+    pub total: usize,
+    pub added: usize,
+    pub modified: usize,
+    pub deleted: usize,
+}
+
+/// What to do when a restored path already exists in the live subvolume
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestoreConflictPolicy {
+    /// Leave the existing file alone
+    #[default]
+    Skip,
+    /// Replace the existing file with the snapshot copy
+    Overwrite,
+    /// Restore next to the existing file under a `.restored` name
+    KeepBoth,
+}
+
+impl RestoreConflictPolicy {
+    pub const ALL: [Self; 3] = [Self::Skip, Self::Overwrite, Self::KeepBoth];
+
+    /// Name used on the D-Bus interface
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Overwrite => "overwrite",
+            Self::KeepBoth => "keep-both",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.as_str() == value)
+    }
+}
+
+/// Outcome of restoring paths from a snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestoreResult {
+    /// Destination paths that were written
+    pub restored: Vec<String>,
+    /// Requested paths left alone because they already exist
+    pub skipped: Vec<String>,
+}
+
+/// Outcome of rolling a subvolume back to a snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollbackResult {
+    /// Read-only snapshot of the state before the rollback
+    pub safety_snapshot: String,
+    /// Path of the subvolume that now holds the snapshot contents
+    pub subvolume: String,
+    /// Where the replaced subvolume was moved, when it was in use and
+    /// could not be deleted
+    pub previous_moved_to: Option<String>,
+    /// The replaced subvolume is the running system's root
+    pub booted_root: bool,
+    /// Mounts of the replaced subvolume that keep the old contents until remounted
+    pub active_mounts: Vec<String>,
+    /// The default subvolume was switched to the restored one
+    pub default_updated: bool,
+    /// A reboot (or remount of `active_mounts`) is needed to use the restored contents
+    pub reboot_required: bool,
+}
+
+/// Automatic safety snapshots taken before destructive operations on btrfs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetySnapshotPolicy {
+    /// Take safety snapshots at all
+    pub enabled: bool,
+    /// Days a safety snapshot is kept before it is pruned
+    pub retention_days: u32,
+    /// Snapshot mounted btrfs filesystems before their partition or volume is resized
+    pub on_resize: bool,
+    /// Snapshot the containing subvolume before cleanup deletions
+    pub on_cleanup: bool,
+}
+
+impl Default for SafetySnapshotPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 7,
+            on_resize: true,
+            on_cleanup: true,
+        }
+    }
+}
+
+/// Error counters the kernel keeps for one device of a btrfs filesystem,
+/// from `btrfs device stats`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BtrfsDeviceStats {
+    pub device: String,
+    pub write_io_errs: u64,
+    pub read_io_errs: u64,
+    pub flush_io_errs: u64,
+    /// Blocks whose checksum did not match
+    pub corruption_errs: u64,
+    /// Blocks with an unexpected generation, e.g. after lost writes
+    pub generation_errs: u64,
+}
+
+impl BtrfsDeviceStats {
+    /// Errors of all kinds
+    pub fn total_errors(&self) -> u64 {
+        self.write_io_errs
+            + self.read_io_errs
+            + self.flush_io_errs
+            + self.corruption_errs
+            + self.generation_errs
+    }
+}
+
+/// One mount point of each mounted btrfs filesystem in the contents of
+/// `/proc/mounts`
+pub fn btrfs_mount_points(proc_mounts: &str) -> Vec<String> {
+    let mut devices: Vec<&str> = Vec::new();
+    let mut mount_points = Vec::new();
+    for line in proc_mounts.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(device), Some(mount_point), Some("btrfs")) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        // Subvolumes mounted elsewhere belong to the same filesystem
+        if !devices.contains(&device) {
+            devices.push(device);
+            mount_points.push(crate::user_mount::unescape(mount_point));
+        }
+    }
+    mount_points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_mount_point_per_filesystem() {
+        let mounts = "\
+/dev/sda2 / btrfs rw,subvol=/@ 0 0
+/dev/sda2 /home btrfs rw,subvol=/@home 0 0
+/dev/sda1 /boot/efi vfat rw 0 0
+/dev/sdb1 /mnt/my\\040data btrfs rw 0 0
+";
+        assert_eq!(btrfs_mount_points(mounts), ["/", "/mnt/my data"]);
+    }
+}
@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! How byte sizes are written
+//!
+//! Sizes are shown either in SI units (1 GB = 1000³ bytes, as drives are
+//! sold) or IEC units (1 GiB = 1024³ bytes, as most tools count), with the
+//! decimal and grouping separators of the user's locale.
+//!
+//! [`bytes_to_pretty`](crate::bytes_to_pretty) and the other helpers in
+//! [`crate::common`] format with the process-wide [`byte_format`], which the
+//! app sets from its settings. Processes that never set it, such as the
+//! service, write IEC units with English separators.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use anyhow::Result;
+use num_format::{Locale, ToFormattedString};
+use serde::{Deserialize, Serialize};
+
+const SI_LABELS: [&str; 9] = ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"];
+const IEC_LABELS: [&str; 9] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB", "ZiB", "YiB"];
+
+/// Unit system byte sizes are written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ByteUnits {
+    /// Powers of 1000: kB, MB, GB
+    Si,
+    /// Powers of 1024: KiB, MiB, GiB
+    #[default]
+    Iec,
+}
+
+impl ByteUnits {
+    pub fn to_index(self) -> usize {
+        match self {
+            Self::Si => 0,
+            Self::Iec => 1,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            0 => Self::Si,
+            _ => Self::Iec,
+        }
+    }
+
+    /// Bytes in one unit step, e.g. from MB to GB
+    pub fn base(self) -> f64 {
+        match self {
+            Self::Si => 1000.,
+            Self::Iec => 1024.,
+        }
+    }
+
+    /// Unit labels from bytes upwards
+    pub fn labels(self) -> &'static [&'static str] {
+        match self {
+            Self::Si => &SI_LABELS,
+            Self::Iec => &IEC_LABELS,
+        }
+    }
+}
+
+impl fmt::Display for ByteUnits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Si => "si",
+            Self::Iec => "iec",
+        })
+    }
+}
+
+impl FromStr for ByteUnits {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "si" => Ok(Self::Si),
+            "iec" => Ok(Self::Iec),
+            other => Err(format!("unknown byte units {other}, expected si or iec")),
+        }
+    }
+}
+
+/// Units and locale byte sizes are formatted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteFormat {
+    pub units: ByteUnits,
+    /// Decimal and digit grouping separators
+    pub locale: Locale,
+}
+
+impl Default for ByteFormat {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl ByteFormat {
+    const DEFAULT: Self = Self {
+        units: ByteUnits::Iec,
+        locale: Locale::en,
+    };
+
+    /// Format for `units` in the locale named `locale` (e.g. "de-DE" or
+    /// "de_DE.UTF-8"), falling back to the language and then to English
+    pub fn new(units: ByteUnits, locale: &str) -> Self {
+        let name = locale
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-");
+        let language = name.split('-').next().unwrap_or_default();
+        let locale = Locale::from_name(&name)
+            .or_else(|_| Locale::from_name(language))
+            .unwrap_or(Locale::en);
+        Self { units, locale }
+    }
+
+    /// `bytes` in the largest unit that keeps the value at least 1, with the
+    /// index of that unit
+    fn scaled(&self, bytes: u64) -> (f64, usize) {
+        let base = self.units.base();
+        let last = self.units.labels().len() - 1;
+        let mut value = bytes as f64;
+        let mut steps = 0;
+        while value >= base && steps < last {
+            value /= base;
+            steps += 1;
+        }
+        (value, steps)
+    }
+
+    /// Human-readable size, e.g. "1.50 GiB", optionally followed by the exact
+    /// count, e.g. "1.50 GiB (1,610,612,736 bytes)"
+    pub fn format(&self, bytes: u64, add_bytes: bool) -> String {
+        let (value, steps) = self.scaled(bytes);
+        let value = format!("{value:.2}").replace('.', self.locale.decimal());
+        let unit = self.units.labels()[steps];
+        if add_bytes {
+            let exact = bytes.to_formatted_string(&self.locale);
+            format!("{value} {unit} ({exact} bytes)")
+        } else {
+            format!("{value} {unit}")
+        }
+    }
+
+    /// Parse a size written by [`Self::format`] or typed by the user, e.g.
+    /// "1.5 GB" or "1,5 GiB"; SI units count in powers of 1000 and IEC units
+    /// in powers of 1024 whichever units are preferred
+    pub fn parse(&self, text: &str) -> Result<u64> {
+        let mut words = text.split_whitespace();
+        let number = words
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Invalid input"))?;
+        let unit = words.last().unwrap_or("B");
+
+        let number = number
+            .replace(self.locale.separator(), "")
+            .replace(self.locale.decimal(), ".");
+        let value: f64 = number.parse()?;
+
+        let (base, steps) = [ByteUnits::Iec, ByteUnits::Si]
+            .into_iter()
+            .find_map(|units| {
+                let steps = units
+                    .labels()
+                    .iter()
+                    .position(|label| label.eq_ignore_ascii_case(unit))?;
+                Some((units.base(), steps))
+            })
+            .ok_or_else(|| anyhow::anyhow!("Invalid unit: {}", unit))?;
+
+        Ok((value * base.powi(steps as i32)) as u64)
+    }
+
+    /// Value [`Self::format`] shows for `bytes`, without its unit
+    pub fn numeric(&self, bytes: u64) -> f64 {
+        self.scaled(bytes).0
+    }
+
+    /// Bytes in the unit [`Self::format`] shows `bytes` in, a decent step
+    /// for numeric inputs
+    pub fn step(&self, bytes: u64) -> f64 {
+        let (_, steps) = self.scaled(bytes);
+        self.units.base().powi(steps as i32)
+    }
+}
+
+static CURRENT: RwLock<ByteFormat> = RwLock::new(ByteFormat::DEFAULT);
+
+/// Byte format of this process
+pub fn byte_format() -> ByteFormat {
+    *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Change the byte format of this process
+pub fn set_byte_format(format: ByteFormat) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = format;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_follow_the_units() {
+        let iec = ByteFormat::new(ByteUnits::Iec, "en-US");
+        let si = ByteFormat::new(ByteUnits::Si, "en-US");
+        assert_eq!(iec.format(1_610_612_736, false), "1.50 GiB");
+        assert_eq!(si.format(1_610_612_736, false), "1.61 GB");
+        assert_eq!(si.format(999, false), "999.00 B");
+        assert_eq!(iec.format(1024, false), "1.00 KiB");
+        assert_eq!(
+            iec.format(1_610_612_736, true),
+            "1.50 GiB (1,610,612,736 bytes)"
+        );
+    }
+
+    #[test]
+    fn sizes_follow_the_locale() {
+        let german = ByteFormat::new(ByteUnits::Iec, "de_DE.UTF-8");
+        assert_eq!(
+            german.format(1_610_612_736, true),
+            "1,50 GiB (1.610.612.736 bytes)"
+        );
+        assert_eq!(german.parse("1,5 GiB").unwrap(), 1_610_612_736);
+        assert_eq!(ByteFormat::new(ByteUnits::Iec, "xx").locale, Locale::en);
+    }
+
+    #[test]
+    fn parsing_respects_each_unit_system() {
+        let format = ByteFormat::default();
+        assert_eq!(format.parse("1.5 GB").unwrap(), 1_500_000_000);
+        assert_eq!(format.parse("1.5 GiB").unwrap(), 1_610_612_736);
+        assert_eq!(format.parse("2 kb").unwrap(), 2000);
+        assert_eq!(format.parse("512").unwrap(), 512);
+        assert!(format.parse("1 parsec").is_err());
+    }
+
+    #[test]
+    fn steps_follow_the_shown_unit() {
+        let si = ByteFormat::new(ByteUnits::Si, "en");
+        assert_eq!(si.step(5_000_000), 1_000_000.);
+        assert!((si.numeric(5_500_000) - 5.5).abs() < f64::EPSILON);
+        assert_eq!(ByteUnits::from_str("SI"), Ok(ByteUnits::Si));
+    }
+}
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Capacity planning for mounted filesystems
+//!
+//! The service samples the used space of every mounted filesystem over
+//! time. A straight line fitted through the samples of the last weeks gives
+//! the growth rate, and the free space divided by it the days left until the
+//! filesystem runs full. Spikes such as a large download that was deleted
+//! again weigh in like any other sample; the projection is rough by design.
+
+use serde::{Deserialize, Serialize};
+
+/// Used space of a filesystem at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapacitySample {
+    /// Seconds since epoch (UTC)
+    pub taken_at: u64,
+    pub used: u64,
+    pub size: u64,
+}
+
+/// Only samples this recent shape the trend, in seconds
+pub const TREND_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+/// Samples must cover at least this long before a trend is given, in seconds
+pub const MIN_TREND_SPAN: u64 = 24 * 60 * 60;
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// How fast a mounted filesystem fills up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityForecast {
+    /// Mounted device, e.g. "/dev/sda1"
+    pub device: String,
+    pub mount_point: String,
+    pub size: u64,
+    pub available: u64,
+    /// Change of the used space in bytes per day; negative when it shrinks
+    pub growth_per_day: i64,
+    /// Days until the filesystem is full at the current growth; `None` when
+    /// it does not grow
+    pub days_until_full: Option<u64>,
+}
+
+impl CapacityForecast {
+    /// Forecast for the filesystem mounted from `device` at `mount_point`
+    /// from its samples, oldest first, or `None` while they span too short
+    /// a time for a trend
+    pub fn from_samples(
+        device: &str,
+        mount_point: &str,
+        samples: &[CapacitySample],
+    ) -> Option<Self> {
+        let latest = samples.last()?;
+        let growth = growth_per_day(samples)?;
+        let available = latest.size.saturating_sub(latest.used);
+        let days_until_full = (growth >= 1.0).then(|| (available as f64 / growth).floor() as u64);
+        Some(Self {
+            device: device.to_string(),
+            mount_point: mount_point.to_string(),
+            size: latest.size,
+            available,
+            growth_per_day: growth.round() as i64,
+            days_until_full,
+        })
+    }
+
+    /// Whether the filesystem runs full within `days` at its current growth
+    pub fn full_within(&self, days: u64) -> bool {
+        self.days_until_full.is_some_and(|left| left <= days)
+    }
+}
+
+/// Least-squares slope of the used space over the samples of the last
+/// [`TREND_WINDOW`], in bytes per day
+pub fn growth_per_day(samples: &[CapacitySample]) -> Option<f64> {
+    let latest = samples.last()?.taken_at;
+    let window: Vec<&CapacitySample> = samples
+        .iter()
+        .filter(|sample| sample.taken_at + TREND_WINDOW >= latest)
+        .collect();
+    let first = window.first()?.taken_at;
+    if latest - first < MIN_TREND_SPAN {
+        return None;
+    }
+
+    // Relative to the first sample, to keep the sums small
+    let points: Vec<(f64, f64)> = window
+        .iter()
+        .map(|sample| {
+            (
+                (sample.taken_at - first) as f64 / SECONDS_PER_DAY,
+                sample.used as f64,
+            )
+        })
+        .collect();
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+    const GB: u64 = 1_000_000_000;
+
+    fn sample(day: u64, used: u64) -> CapacitySample {
+        CapacitySample {
+            taken_at: 1_700_000_000 + day * DAY,
+            used,
+            size: 100 * GB,
+        }
+    }
+
+    #[test]
+    fn steady_growth_runs_full() {
+        let samples: Vec<_> = (0..10).map(|day| sample(day, 50 * GB + day * GB)).collect();
+        let forecast = CapacityForecast::from_samples("/dev/sda1", "/home", &samples).unwrap();
+        assert_eq!(forecast.growth_per_day, GB as i64);
+        assert_eq!(forecast.available, 41 * GB);
+        assert_eq!(forecast.days_until_full, Some(41));
+        assert!(forecast.full_within(45));
+        assert!(!forecast.full_within(30));
+    }
+
+    #[test]
+    fn shrinking_or_flat_filesystems_never_run_full() {
+        let shrinking: Vec<_> = (0..5).map(|day| sample(day, 50 * GB - day * GB)).collect();
+        let forecast = CapacityForecast::from_samples("/dev/sda1", "/", &shrinking).unwrap();
+        assert!(forecast.growth_per_day < 0);
+        assert_eq!(forecast.days_until_full, None);
+
+        let flat = [sample(0, 10 * GB), sample(3, 10 * GB)];
+        let forecast = CapacityForecast::from_samples("/dev/sda1", "/", &flat).unwrap();
+        assert_eq!(forecast.days_until_full, None);
+    }
+
+    #[test]
+    fn trends_need_enough_recent_history() {
+        let mut hours = sample(0, GB);
+        hours.taken_at += 6 * 60 * 60;
+        assert_eq!(growth_per_day(&[sample(0, 0), hours]), None);
+        assert_eq!(growth_per_day(&[]), None);
+
+        // Growth from before the window is ignored
+        let samples = [
+            sample(0, 0),
+            sample(60, 80 * GB),
+            sample(61, 80 * GB),
+            sample(62, 80 * GB),
+        ];
+        assert_eq!(growth_per_day(&samples), Some(0.0));
+    }
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Drive comparison
+//!
+//! Key metrics of several drives in common units, for a side-by-side view
+//! that helps decide which drive to retire.
+
+use serde::{Deserialize, Serialize};
+
+use crate::health::HealthLevel;
+use crate::volume::VolumeInfo;
+
+/// Negotiated speed of the link a drive is attached through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceSpeed {
+    /// "sata", "nvme" or "usb"
+    pub bus: String,
+
+    /// Link as reported by the kernel (e.g., "SATA 6.0 Gb/s", "8.0 GT/s PCIe x4")
+    pub description: String,
+
+    /// Usable megabits per second
+    pub mbps: u64,
+}
+
+/// Metrics of one drive in a comparison
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DriveComparison {
+    /// Device path (e.g., "/dev/sda")
+    pub device: String,
+
+    pub model: String,
+    pub serial: String,
+
+    /// Total size in bytes
+    pub capacity_bytes: u64,
+
+    /// Bytes used by mounted filesystems; `None` when none is mounted
+    pub used_bytes: Option<u64>,
+
+    /// Health score (0-100); `None` without SMART data
+    pub health_score: Option<u8>,
+    pub health_level: HealthLevel,
+
+    /// Current temperature in degrees Celsius
+    pub temperature_c: Option<u64>,
+
+    pub power_on_hours: Option<u64>,
+
+    pub interface: Option<InterfaceSpeed>,
+}
+
+/// Bytes used by the mounted filesystems among `volumes` and their children
+///
+/// Returns `None` when no filesystem is mounted.
+pub fn mounted_used_bytes(volumes: &[VolumeInfo]) -> Option<u64> {
+    volumes.iter().fold(None, |total, volume| {
+        let own = volume.usage.as_ref().map(|usage| usage.used_bytes());
+        let children = mounted_used_bytes(&volume.children);
+        match (total, own, children) {
+            (None, None, None) => None,
+            (total, own, children) => {
+                Some(total.unwrap_or(0) + own.unwrap_or(0) + children.unwrap_or(0))
+            }
+        }
+    })
+}
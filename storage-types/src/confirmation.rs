@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! How strictly destructive operations are confirmed
+//!
+//! Every dialog that destroys data asks for confirmation; the level in the
+//! settings decides what that takes beyond pressing OK: typing the name of
+//! the target, typing back a token shown in the dialog, and waiting for a
+//! countdown, so that a reflexive click cannot wipe the wrong drive.
+//!
+//! All of this happens in the app; the service never sees the level, and
+//! its destructive methods are guarded by polkit alone.
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds a paranoid confirmation waits before its OK button works
+pub const CONFIRMATION_COUNTDOWN_SECS: u32 = 5;
+
+/// Characters in a confirmation token
+pub const CONFIRMATION_TOKEN_LENGTH: usize = 6;
+
+/// Strictness of destructive confirmations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationLevel {
+    /// OK is enough
+    Relaxed,
+    /// Whole-disk operations also ask for the device name
+    #[default]
+    Standard,
+    /// Every destructive operation asks for the target's name, a token
+    /// and a countdown
+    Paranoid,
+}
+
+impl ConfirmationLevel {
+    pub const ALL: [ConfirmationLevel; 3] = [
+        ConfirmationLevel::Relaxed,
+        ConfirmationLevel::Standard,
+        ConfirmationLevel::Paranoid,
+    ];
+
+    pub fn to_index(self) -> usize {
+        match self {
+            Self::Relaxed => 0,
+            Self::Standard => 1,
+            Self::Paranoid => 2,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            0 => Self::Relaxed,
+            2 => Self::Paranoid,
+            _ => Self::Standard,
+        }
+    }
+
+    /// What confirming an operation takes at this level
+    pub fn requirements(self, whole_disk: bool) -> ConfirmationRequirements {
+        match self {
+            Self::Relaxed => ConfirmationRequirements::default(),
+            Self::Standard => ConfirmationRequirements {
+                type_name: whole_disk,
+                ..Default::default()
+            },
+            Self::Paranoid => ConfirmationRequirements {
+                type_name: true,
+                token: true,
+                countdown_secs: CONFIRMATION_COUNTDOWN_SECS,
+            },
+        }
+    }
+}
+
+/// Steps of a confirmation beyond pressing OK
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfirmationRequirements {
+    /// Type the name of the target, e.g. "sdb" for /dev/sdb
+    pub type_name: bool,
+    /// Type back a token shown in the dialog
+    pub token: bool,
+    /// Seconds before OK can be pressed
+    pub countdown_secs: u32,
+}
+
+/// Whether `typed` names `target`
+///
+/// Device paths may be typed with or without "/dev/"; surrounding spaces
+/// are ignored.
+pub fn confirmation_name_matches(target: &str, typed: &str) -> bool {
+    let typed = typed.trim();
+    !typed.is_empty()
+        && (typed == target
+            || target
+                .strip_prefix("/dev/")
+                .is_some_and(|name| typed == name))
+}
+
+/// A new token for the user to type back
+pub fn confirmation_token() -> String {
+    uuid::Uuid::new_v4()
+        .simple()
+        .to_string()
+        .to_uppercase()
+        .chars()
+        .take(CONFIRMATION_TOKEN_LENGTH)
+        .collect()
+}
+
+/// Whether `typed` is `token`, ignoring case and surrounding spaces
+pub fn confirmation_token_matches(token: &str, typed: &str) -> bool {
+    token.eq_ignore_ascii_case(typed.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_add_steps() {
+        assert_eq!(
+            ConfirmationLevel::Relaxed.requirements(true),
+            ConfirmationRequirements::default()
+        );
+        assert!(ConfirmationLevel::Standard.requirements(true).type_name);
+        assert!(!ConfirmationLevel::Standard.requirements(false).type_name);
+        let paranoid = ConfirmationLevel::Paranoid.requirements(false);
+        assert!(paranoid.type_name && paranoid.token);
+        assert_eq!(paranoid.countdown_secs, CONFIRMATION_COUNTDOWN_SECS);
+
+        for level in ConfirmationLevel::ALL {
+            assert_eq!(ConfirmationLevel::from_index(level.to_index()), level);
+        }
+
+        assert!(confirmation_name_matches("/dev/sdb", "sdb"));
+        assert!(confirmation_name_matches("/dev/sdb", " /dev/sdb "));
+        assert!(!confirmation_name_matches("/dev/sdb", "sdb1"));
+        assert!(!confirmation_name_matches("Backup", ""));
+
+        let token = confirmation_token();
+        assert_eq!(token.len(), CONFIRMATION_TOKEN_LENGTH);
+        assert!(confirmation_token_matches(
+            &token,
+            &format!(" {} ", token.to_lowercase())
+        ));
+        assert!(!confirmation_token_matches(&token, ""));
+    }
+}
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Diagnostic bundles for bug reports
+//!
+//! When an operation fails, the user can collect the state of the system
+//! around the failure into an archive to attach to an issue: the layout of
+//! the devices, what UDisks knows about the device involved, its SMART
+//! data and the last lines of the logs. Since such a report is posted
+//! publicly, identifying details (serial numbers, UUIDs, labels, user and
+//! host names) are replaced before anything is written.
+
+use serde::{Deserialize, Serialize};
+
+/// Replacement of redacted text
+pub const REDACTED: &str = "[redacted]";
+
+/// Replacement of UUIDs
+pub const REDACTED_UUID: &str = "[uuid]";
+
+/// Shortest text redacted; shorter strings such as a one-letter label would
+/// also match unrelated words
+pub const MIN_REDACTED_LEN: usize = 3;
+
+/// A file of a diagnostic bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticFile {
+    /// File name inside the archive, e.g. "layout.json"
+    pub name: String,
+    pub contents: String,
+}
+
+/// The files of a diagnostic report
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticBundle {
+    pub files: Vec<DiagnosticFile>,
+}
+
+impl DiagnosticBundle {
+    /// Add a file; a source that could not be read is recorded with the
+    /// reason instead of being left out silently
+    pub fn add(&mut self, name: &str, contents: Result<String, String>) {
+        let contents = contents.unwrap_or_else(|e| format!("Unavailable: {e}\n"));
+        self.files.push(DiagnosticFile {
+            name: name.to_string(),
+            contents,
+        });
+    }
+
+    /// Replace every occurrence of `secrets` and every UUID in all files
+    pub fn redact(&mut self, secrets: &[String]) {
+        for file in &mut self.files {
+            file.contents = redact(&file.contents, secrets);
+        }
+    }
+}
+
+/// Identifying values in the output of `lsblk --json --output-all`:
+/// serial numbers, world wide names and labels of all devices
+pub fn layout_secrets(lsblk_json: &str) -> Vec<String> {
+    const KEYS: [&str; 4] = ["serial", "wwn", "label", "partlabel"];
+
+    fn collect(value: &serde_json::Value, secrets: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    match value {
+                        serde_json::Value::String(s) if KEYS.contains(&key.as_str()) => {
+                            secrets.push(s.clone());
+                        }
+                        _ => collect(value, secrets),
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    collect(item, secrets);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut secrets = Vec::new();
+    if let Ok(value) = serde_json::from_str(lsblk_json) {
+        collect(&value, &mut secrets);
+    }
+    secrets
+}
+
+/// `text` with each of `secrets` replaced by [`REDACTED`] and each UUID by
+/// [`REDACTED_UUID`]
+///
+/// Longer secrets are replaced first, so that a serial number is not left
+/// half visible after replacing a label it contains. Empty and very short
+/// secrets are ignored.
+pub fn redact(text: &str, secrets: &[String]) -> String {
+    let mut secrets: Vec<&str> = secrets
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| s.len() >= MIN_REDACTED_LEN && *s != REDACTED && *s != REDACTED_UUID)
+        .collect();
+    secrets.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    secrets.dedup();
+
+    let mut text = redact_uuids(text);
+    for secret in secrets {
+        text = text.replace(secret, REDACTED);
+    }
+    text
+}
+
+/// `text` with UUIDs such as "0f8fad5b-d9cb-469f-a165-70867728950e"
+/// replaced by [`REDACTED_UUID`], in either case
+fn redact_uuids(text: &str) -> String {
+    const GROUPS: [usize; 5] = [8, 4, 4, 4, 12];
+    const LEN: usize = 36;
+
+    let is_uuid = |candidate: &[u8]| {
+        let mut pos = 0;
+        GROUPS.iter().enumerate().all(|(i, &len)| {
+            let group_ok = candidate[pos..pos + len].iter().all(u8::is_ascii_hexdigit);
+            pos += len;
+            let separator_ok = i == GROUPS.len() - 1 || candidate[pos] == b'-';
+            pos += 1;
+            group_ok && separator_ok
+        })
+    };
+
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i + LEN <= bytes.len() {
+        let bounded = (i == 0 || !bytes[i - 1].is_ascii_alphanumeric())
+            && bytes
+                .get(i + LEN)
+                .is_none_or(|next| !next.is_ascii_alphanumeric());
+        if bounded && is_uuid(&bytes[i..i + LEN]) {
+            out.push_str(&text[copied..i]);
+            out.push_str(REDACTED_UUID);
+            i += LEN;
+            copied = i;
+        } else {
+            i += 1;
+        }
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_secrets_and_uuids() {
+        let text = "sda serial=S3Z9NB0K123456 label=alice-backup \
+                    uuid=0F8FAD5B-d9cb-469f-a165-70867728950e mounted at /home/alice";
+        let secrets = vec![
+            "S3Z9NB0K123456".to_string(),
+            "alice".to_string(),
+            "alice-backup".to_string(),
+            "ab".to_string(),
+            String::new(),
+        ];
+        assert_eq!(
+            redact(text, &secrets),
+            "sda serial=[redacted] label=[redacted] uuid=[uuid] mounted at /home/[redacted]"
+        );
+    }
+
+    #[test]
+    fn keeps_hex_that_is_not_a_uuid() {
+        // Too long a last group, and a UUID embedded in a longer word
+        let text = "0f8fad5b-d9cb-469f-a165-70867728950e0 x0f8fad5b-d9cb-469f-a165-70867728950e";
+        assert_eq!(redact(text, &[]), text);
+        assert_eq!(
+            redact("(0f8fad5b-d9cb-469f-a165-70867728950e)", &[]),
+            "([uuid])"
+        );
+    }
+
+    #[test]
+    fn finds_layout_secrets() {
+        let json = r#"{"blockdevices": [
+            {"name": "sda", "serial": "S3Z9NB0K", "wwn": "0x5002538e4", "label": null,
+             "children": [{"name": "sda1", "label": "photos", "partlabel": "EFI", "size": 512}]}
+        ]}"#;
+        let mut secrets = layout_secrets(json);
+        secrets.sort();
+        assert_eq!(secrets, ["0x5002538e4", "EFI", "S3Z9NB0K", "photos"]);
+        assert!(layout_secrets("not json").is_empty());
+    }
+
+    #[test]
+    fn records_unavailable_sources() {
+        let mut bundle = DiagnosticBundle::default();
+        bundle.add("smart.json", Err("SMART is not supported".to_string()));
+        assert_eq!(
+            bundle.files[0].contents,
+            "Unavailable: SMART is not supported\n"
+        );
+    }
+}
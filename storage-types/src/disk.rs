@@ -5,7 +5,12 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::ByteRange;
+use crate::alignment::{DEFAULT_SECTOR_SIZE, SectorFormat};
+use crate::{ByteRange, VolumeInfo};
+
+fn default_sector_size() -> u64 {
+    DEFAULT_SECTOR_SIZE
+}
 
 /// Complete disk information (single source of truth)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -39,6 +44,14 @@ pub struct DiskInfo {
     /// Rotation rate in RPM (None for SSDs or unknown)
     pub rotation_rate: Option<u16>,
 
+    /// Logical sector size in bytes, the unit partition tables count in
+    #[serde(default = "default_sector_size")]
+    pub logical_sector_size: u64,
+
+    /// Physical sector size in bytes, the unit the drive writes in
+    #[serde(default = "default_sector_size")]
+    pub physical_sector_size: u64,
+
     // === Media Properties ===
     /// Whether the disk is removable
     pub removable: bool,
@@ -58,9 +71,30 @@ pub struct DiskInfo {
     /// Whether optical media is blank
     pub optical_blank: bool,
 
+    /// Whether the kernel marks the disk read-only, e.g. an SD card with its
+    /// lock switch set
+    #[serde(default)]
+    pub read_only: bool,
+
     /// Whether the drive can be powered off
     pub can_power_off: bool,
 
+    // === Virtualization ===
+    /// Whether the disk is provided by a hypervisor (virtio, or an emulated
+    /// SCSI/ATA disk)
+    #[serde(default)]
+    pub virtual_disk: bool,
+
+    /// Whether the disk accepts discard (TRIM/UNMAP) requests, which lets a
+    /// thin disk hand freed space back to its host
+    #[serde(default)]
+    pub discard_supported: bool,
+
+    /// Whether the kernel treats the disk as rotational; for virtual disks
+    /// this is what the hypervisor reports, not what backs the disk
+    #[serde(default)]
+    pub rotational: bool,
+
     // === Loop Device Specific ===
     /// Whether this is a loop device
     pub is_loop: bool,
@@ -80,8 +114,9 @@ impl DiskInfo {
     /// Check if the drive supports power management (spin down/standby).
     /// Returns true for spinning disks (rotation_rate > 0), false for SSDs and NVMe drives.
     pub fn supports_power_management(&self) -> bool {
-        // Loop devices don't support power management
-        if self.is_loop {
+        // Loop devices don't support power management, and virtual disks
+        // have no platters of their own to spin down
+        if self.is_loop || self.virtual_disk {
             return false;
         }
 
@@ -90,6 +125,12 @@ impl DiskInfo {
         matches!(self.rotation_rate, Some(rpm) if rpm > 0)
     }
 
+    /// Whether SMART data can be read; loop devices and virtual disks have
+    /// none of their own
+    pub fn supports_smart(&self) -> bool {
+        !self.is_loop && !self.virtual_disk
+    }
+
     /// Get a human-readable display name for the disk
     pub fn display_name(&self) -> String {
         if !self.model.is_empty() {
@@ -104,6 +145,35 @@ impl DiskInfo {
                 .to_string()
         }
     }
+
+    /// How the logical sectors map onto the physical ones (512n, 512e, 4Kn)
+    pub fn sector_format(&self) -> SectorFormat {
+        SectorFormat::from_sizes(self.logical_sector_size, self.physical_sector_size)
+    }
+
+    /// Whether a partition at `offset` starts on a physical sector
+    pub fn is_aligned(&self, offset: u64) -> bool {
+        crate::alignment::is_aligned(offset, self.physical_sector_size)
+    }
+
+    /// Whether the drive holds nothing yet and can be set up from scratch:
+    /// no partition table, and neither a filesystem nor any other content
+    /// on the whole drive
+    ///
+    /// `volumes` is the flat volume list of the service.
+    pub fn needs_setup(&self, volumes: &[VolumeInfo]) -> bool {
+        if self.is_loop || self.optical || self.read_only || !self.media_available {
+            return false;
+        }
+        if self.partition_table_type.is_some() {
+            return false;
+        }
+        !volumes.iter().any(|volume| {
+            let on_drive = volume.device_path.as_deref() == Some(self.device.as_str())
+                || volume.parent_path.as_deref() == Some(self.device.as_str());
+            on_drive && (volume.has_filesystem || !volume.id_type.is_empty())
+        })
+    }
 }
 
 /// SMART health status for a disk
@@ -172,10 +242,10 @@ pub enum DiskEvent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::VolumeKind;
 
-    #[test]
-    fn test_disk_info_serialization() {
-        let disk = DiskInfo {
+    fn disk() -> DiskInfo {
+        DiskInfo {
             device: "/dev/sda".to_string(),
             id: "ata-Samsung_SSD_970_EVO_S1234567890".to_string(),
             model: "Samsung SSD 970 EVO".to_string(),
@@ -185,18 +255,29 @@ mod tests {
             size: 1000000000000,
             connection_bus: "nvme".to_string(),
             rotation_rate: None,
+            logical_sector_size: 512,
+            physical_sector_size: 4096,
             removable: false,
             ejectable: false,
             media_removable: false,
             media_available: true,
             optical: false,
             optical_blank: false,
+            read_only: false,
             can_power_off: false,
+            virtual_disk: false,
+            discard_supported: false,
+            rotational: false,
             is_loop: false,
             backing_file: None,
             partition_table_type: Some("gpt".to_string()),
             gpt_usable_range: None,
-        };
+        }
+    }
+
+    #[test]
+    fn test_disk_info_serialization() {
+        let disk = disk();
 
         let json = serde_json::to_string(&disk).unwrap();
         let deserialized: DiskInfo = serde_json::from_str(&json).unwrap();
@@ -239,4 +320,66 @@ mod tests {
 
         assert_eq!(attr, deserialized);
     }
+
+    #[test]
+    fn sector_sizes_default_for_older_data() {
+        let mut json = serde_json::to_value(disk()).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        fields.remove("logical_sector_size");
+        fields.remove("physical_sector_size");
+        let disk: DiskInfo = serde_json::from_value(json).unwrap();
+
+        assert_eq!(disk.sector_format(), SectorFormat::Native512);
+        assert!(disk.is_aligned(63 * 512));
+    }
+
+    #[test]
+    fn virtual_disks_hide_hardware_actions() {
+        let hdd = DiskInfo {
+            rotation_rate: Some(7200),
+            ..disk()
+        };
+        assert!(hdd.supports_power_management());
+        assert!(hdd.supports_smart());
+
+        let virtual_disk = DiskInfo {
+            virtual_disk: true,
+            ..hdd
+        };
+        assert!(!virtual_disk.supports_power_management());
+        assert!(!virtual_disk.supports_smart());
+    }
+
+    #[test]
+    fn only_drives_without_content_need_setup() {
+        let blank = DiskInfo {
+            partition_table_type: None,
+            ..disk()
+        };
+        assert!(blank.needs_setup(&[]));
+        assert!(!disk().needs_setup(&[]));
+
+        let whole_disk_filesystem = VolumeInfo {
+            kind: VolumeKind::Filesystem,
+            label: String::new(),
+            size: blank.size,
+            offset: 0,
+            partition_number: 0,
+            id_type: "vfat".to_string(),
+            device_path: Some(blank.device.clone()),
+            parent_path: None,
+            has_filesystem: true,
+            mount_points: Vec::new(),
+            usage: None,
+            locked: false,
+            children: Vec::new(),
+        };
+        assert!(!blank.needs_setup(&[whole_disk_filesystem]));
+
+        let no_media = DiskInfo {
+            media_available: false,
+            ..blank
+        };
+        assert!(!no_media.needs_setup(&[]));
+    }
 }
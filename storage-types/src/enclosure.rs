@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Identifying drives by the LED of their enclosure slot
+//!
+//! Drives in SES enclosures link to their slot in sysfs, whose `locate`
+//! attribute blinks the slot LED. Backplanes driven through other
+//! controllers (e.g. Intel VMD or NPEM) are reached with `ledctl` instead.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest time a slot LED is left blinking
+pub const IDENTIFY_MAX_SECONDS: u32 = 300;
+
+/// Prefix of the sysfs links from a SCSI device to its enclosure slot
+const ENCLOSURE_LINK_PREFIX: &str = "enclosure_device:";
+
+/// How the slot LED of a drive is blinked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IdentifyMethod {
+    /// The `locate` attribute of the SES enclosure slot
+    Ses,
+    /// `ledctl locate=`
+    Ledctl,
+}
+
+/// Slot LED of a drive that can be blinked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentifySupport {
+    pub method: IdentifyMethod,
+    /// Name of the slot the enclosure reports, e.g. "Slot 05"
+    pub slot: Option<String>,
+}
+
+/// Slot name of a sysfs device entry linking to an enclosure slot, e.g.
+/// "enclosure_device:Slot 05"
+pub fn enclosure_slot(entry_name: &str) -> Option<&str> {
+    entry_name
+        .strip_prefix(ENCLOSURE_LINK_PREFIX)
+        .filter(|slot| !slot.is_empty())
+}
+
+/// Blink duration within what the service accepts
+pub fn identify_duration(seconds: u32) -> u32 {
+    seconds.clamp(1, IDENTIFY_MAX_SECONDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_names_come_from_enclosure_links() {
+        assert_eq!(enclosure_slot("enclosure_device:Slot 05"), Some("Slot 05"));
+        assert_eq!(enclosure_slot("enclosure_device:"), None);
+        assert_eq!(enclosure_slot("block"), None);
+    }
+
+    #[test]
+    fn durations_are_bounded() {
+        assert_eq!(identify_duration(0), 1);
+        assert_eq!(identify_duration(30), 30);
+        assert_eq!(identify_duration(86_400), IDENTIFY_MAX_SECONDS);
+    }
+}
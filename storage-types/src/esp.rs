@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Keeping a secondary EFI System Partition in sync with the primary one
+//!
+//! Systems with mirrored boot drives need a copy of the ESP on each drive
+//! to boot from either. The firmware cannot read RAID, so the copies are
+//! kept alike by syncing files, rsync style: changed files are copied and
+//! files gone from the primary are deleted from the secondary.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// GPT partition type of an EFI System Partition
+pub const ESP_GPT_TYPE: &str = "c12a7328-f81f-11d2-ba4b-00a0c93ec93b";
+
+/// DOS partition type of an EFI System Partition
+pub const ESP_DOS_TYPE: &str = "0xef";
+
+/// FAT stores modification times in steps of two seconds
+const FAT_TIME_RESOLUTION: u64 = 2;
+
+/// Whether a partition of type `type_id` is an EFI System Partition
+pub fn is_esp(type_id: &str) -> bool {
+    type_id.eq_ignore_ascii_case(ESP_GPT_TYPE) || type_id.eq_ignore_ascii_case(ESP_DOS_TYPE)
+}
+
+/// A secondary ESP kept in sync with a primary one; both are identified by
+/// partition UUID, which survives device renames
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EspSyncPair {
+    pub primary: String,
+    pub secondary: String,
+    /// Days between scheduled syncs; `None` syncs only on request
+    pub interval_days: Option<u32>,
+    /// Seconds since epoch of the last successful sync
+    pub last_synced: Option<u64>,
+}
+
+impl EspSyncPair {
+    /// Whether a scheduled sync is due at `now` (seconds since epoch)
+    pub fn is_due(&self, now: u64) -> bool {
+        self.interval_days.is_some_and(|days| {
+            self.last_synced
+                .is_none_or(|last| now.saturating_sub(last) >= u64::from(days) * 24 * 60 * 60)
+        })
+    }
+}
+
+/// What a sync changed on the secondary ESP
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EspSyncResult {
+    /// Files copied from the primary
+    pub copied: u64,
+    /// Files and directories deleted from the secondary
+    pub deleted: u64,
+    /// Bytes copied
+    pub bytes: u64,
+}
+
+/// A file or directory of an ESP, by path relative to its root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EspEntry {
+    pub is_dir: bool,
+    pub size: u64,
+    /// Seconds since epoch
+    pub modified: u64,
+}
+
+/// A change that brings the secondary ESP in line with the primary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EspSyncAction {
+    Delete(PathBuf),
+    CreateDir(PathBuf),
+    Copy(PathBuf),
+}
+
+/// Changes that make `target` match `source`, in the order to apply them:
+/// deletions first, deepest paths first, then directories before the
+/// files in them
+pub fn sync_actions(
+    source: &BTreeMap<PathBuf, EspEntry>,
+    target: &BTreeMap<PathBuf, EspEntry>,
+) -> Vec<EspSyncAction> {
+    let mut actions: Vec<EspSyncAction> = target
+        .iter()
+        .rev()
+        .filter(|(path, entry)| {
+            source
+                .get(*path)
+                .is_none_or(|wanted| wanted.is_dir != entry.is_dir)
+        })
+        .map(|(path, _)| EspSyncAction::Delete(path.clone()))
+        .collect();
+
+    for (path, entry) in source {
+        let existing = target
+            .get(path)
+            .filter(|existing| existing.is_dir == entry.is_dir);
+        if entry.is_dir {
+            if existing.is_none() {
+                actions.push(EspSyncAction::CreateDir(path.clone()));
+            }
+        } else if existing.is_none_or(|existing| {
+            existing.size != entry.size
+                || existing.modified.abs_diff(entry.modified) >= FAT_TIME_RESOLUTION
+        }) {
+            actions.push(EspSyncAction::Copy(path.clone()));
+        }
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(size: u64, modified: u64) -> EspEntry {
+        EspEntry {
+            is_dir: false,
+            size,
+            modified,
+        }
+    }
+
+    const DIR: EspEntry = EspEntry {
+        is_dir: true,
+        size: 0,
+        modified: 0,
+    };
+
+    #[test]
+    fn esp_types_are_recognized() {
+        assert!(is_esp("C12A7328-F81F-11D2-BA4B-00A0C93EC93B"));
+        assert!(is_esp("0xef"));
+        assert!(!is_esp("0fc63daf-8483-4772-8e79-3d69d8477de4"));
+    }
+
+    #[test]
+    fn sync_copies_changes_and_deletes_leftovers() {
+        let source = BTreeMap::from([
+            (PathBuf::from("EFI"), DIR),
+            (PathBuf::from("EFI/BOOT"), DIR),
+            (PathBuf::from("EFI/BOOT/BOOTX64.EFI"), file(100, 1000)),
+            (PathBuf::from("EFI/systemd"), DIR),
+            (
+                PathBuf::from("EFI/systemd/systemd-bootx64.efi"),
+                file(200, 2000),
+            ),
+        ]);
+        let target = BTreeMap::from([
+            (PathBuf::from("EFI"), DIR),
+            (PathBuf::from("EFI/BOOT"), DIR),
+            // FAT rounded the time, which is not a change
+            (PathBuf::from("EFI/BOOT/BOOTX64.EFI"), file(100, 1001)),
+            (PathBuf::from("EFI/old"), DIR),
+            (PathBuf::from("EFI/old/grubx64.efi"), file(50, 500)),
+        ]);
+
+        assert_eq!(
+            sync_actions(&source, &target),
+            vec![
+                EspSyncAction::Delete(PathBuf::from("EFI/old/grubx64.efi")),
+                EspSyncAction::Delete(PathBuf::from("EFI/old")),
+                EspSyncAction::CreateDir(PathBuf::from("EFI/systemd")),
+                EspSyncAction::Copy(PathBuf::from("EFI/systemd/systemd-bootx64.efi")),
+            ]
+        );
+        assert!(sync_actions(&source, &source).is_empty());
+    }
+
+    #[test]
+    fn scheduled_syncs_fall_due() {
+        let day = 24 * 60 * 60;
+        let mut pair = EspSyncPair {
+            primary: "a".to_string(),
+            secondary: "b".to_string(),
+            interval_days: Some(1),
+            last_synced: None,
+        };
+        assert!(pair.is_due(day));
+        pair.last_synced = Some(day);
+        assert!(!pair.is_due(day + 60));
+        assert!(pair.is_due(2 * day));
+        pair.interval_days = None;
+        assert!(!pair.is_due(10 * day));
+    }
+}
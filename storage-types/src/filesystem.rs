@@ -3,7 +3,8 @@
 //! Types for filesystem management: formatting, mounting, checking, etc.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
 
 /// Filesystem information
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -39,6 +40,7 @@ impl FilesystemInfo {
 
 /// Options for formatting a filesystem
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct FormatOptions {
     /// Filesystem label
     pub label: String,
@@ -53,7 +55,67 @@ pub struct FormatOptions {
     pub discard: bool,
 
     /// Filesystem-specific options (key-value pairs)
+    ///
+    /// Keys are symbolic (e.g. "features", "reflink", "cluster_size") and
+    /// described by [`crate::format_option_schema`]; the service validates and
+    /// translates them to mkfs arguments, never passing them verbatim.
     pub fs_specific: HashMap<String, String>,
+
+    /// Translated mkfs arguments, filled in by the service only
+    #[serde(skip)]
+    pub mkfs_args: Vec<String>,
+}
+
+impl FormatOptions {
+    /// Built-in format presets for a filesystem type
+    ///
+    /// Presets are told apart by their stable `id`; the app looks up the
+    /// translated name and description by it.
+    pub fn presets(fs_type: &str) -> Vec<FormatPreset> {
+        let preset = |id: &str, options: &[(&str, &str)]| FormatPreset {
+            id: id.to_string(),
+            fs_type: fs_type.to_string(),
+            fs_specific: options
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        };
+
+        match fs_type {
+            "ext4" => vec![
+                preset("ext4-fast-commit", &[("features", "fast_commit")]),
+                preset("ext4-data-disk", &[("reserved_percent", "0")]),
+            ],
+            "xfs" => vec![preset("xfs-reflink", &[("reflink", "1")])],
+            "btrfs" => vec![preset("btrfs-zstd", &[("compress", "zstd:3")])],
+            "vfat" => vec![preset("vfat-32k-clusters", &[("cluster_size", "32768")])],
+            "exfat" => vec![preset("exfat-128k-clusters", &[("cluster_size", "131072")])],
+            "ntfs" => vec![preset("ntfs-64k-clusters", &[("cluster_size", "65536")])],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replace the filesystem-specific options with those of a preset
+    pub fn apply_preset(&mut self, preset: &FormatPreset) {
+        self.fs_specific = preset
+            .fs_specific
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+    }
+}
+
+/// A named set of filesystem-specific format options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormatPreset {
+    /// Stable identifier (e.g. "xfs-reflink")
+    pub id: String,
+
+    /// Filesystem type the preset applies to
+    pub fs_type: String,
+
+    /// Filesystem-specific options applied by the preset
+    pub fs_specific: BTreeMap<String, String>,
 }
 
 /// Options for mounting a filesystem
@@ -70,6 +132,68 @@ pub struct MountOptions {
 
     /// Other mount options as strings
     pub other: Vec<String>,
+
+    /// Where to place the mount when no explicit mount point is given
+    #[serde(default)]
+    pub path_policy: Option<MountPathPolicy>,
+}
+
+/// Keys of `MountOptions::other` a caller may pass, with or without a value
+///
+/// Options that loosen `nosuid`/`nodev`/`noexec` or hand the files to another
+/// user (`suid`, `dev`, `exec`, `defaults`, `uid=`, `context=`, ...) are not
+/// among them.
+const ALLOWED_MOUNT_OPTIONS: &[&str] = &[
+    "ro",
+    "rw",
+    "noexec",
+    "nosuid",
+    "nodev",
+    "sync",
+    "async",
+    "dirsync",
+    "atime",
+    "noatime",
+    "nodiratime",
+    "relatime",
+    "strictatime",
+    "lazytime",
+    "discard",
+    "nodiscard",
+    "flush",
+    "utf8",
+    "windows_names",
+    "umask",
+    "dmask",
+    "fmask",
+    "iocharset",
+    "codepage",
+    "shortname",
+    "errors",
+    "commit",
+    "data",
+    "compress",
+    "compress-force",
+    "subvol",
+    "subvolid",
+];
+
+impl MountOptions {
+    /// Check that every option in `other` is on the allow-list
+    pub fn check_other(&self) -> Result<(), String> {
+        for option in &self.other {
+            let key = option
+                .split_once('=')
+                .map_or(option.as_str(), |(key, _)| key);
+            if matches!(key, "suid" | "dev" | "exec") {
+                return Err(format!("Mount option {key} is not allowed"));
+            }
+            if !ALLOWED_MOUNT_OPTIONS.contains(&key) || option.contains(',') {
+                return Err(format!("Unsupported mount option: {option}"));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Persistent mount options (fstab-style configuration)
@@ -229,3 +353,292 @@ pub struct FilesystemToolInfo {
     /// Whether the tool is currently available on this system
     pub available: bool,
 }
+
+/// On-disk features of a filesystem, as reported by its own tools
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilesystemFeatures {
+    /// Filesystem type (e.g. "ext4", "xfs", "btrfs")
+    pub fs_type: String,
+
+    /// Raw feature names (ext feature list, XFS/btrfs flags), sorted
+    pub features: Vec<String>,
+
+    /// Copy-on-write file clones are supported
+    pub reflink: bool,
+
+    /// Metadata is checksummed
+    pub metadata_checksums: bool,
+
+    /// Active compression (mount option or btrfs incompat flag), if any
+    pub compression: Option<String>,
+
+    /// The filesystem can be shrunk at all (possibly only while unmounted)
+    pub can_shrink: bool,
+
+    /// The filesystem can be grown while mounted
+    pub can_grow_online: bool,
+}
+
+impl FilesystemFeatures {
+    /// Whether a named feature is present
+    pub fn has(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+}
+
+/// Fragmentation estimate for a filesystem or directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentationReport {
+    /// Filesystem type
+    pub fs_type: String,
+
+    /// Path that was analyzed
+    pub target: String,
+
+    /// Fragmentation score in percent (None if the filesystem has no scorer)
+    pub score_percent: Option<u32>,
+
+    /// Summary line from the scoring tool
+    pub summary: String,
+}
+
+impl FragmentationReport {
+    /// Score from which defragmentation is worth running (matches e4defrag's guidance)
+    pub const RECOMMENDED_THRESHOLD: u32 = 30;
+
+    /// Whether defragmentation is recommended
+    pub fn recommended(&self) -> bool {
+        self.score_percent
+            .is_some_and(|score| score >= Self::RECOMMENDED_THRESHOLD)
+    }
+}
+
+/// Outcome of a defragmentation run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefragResult {
+    /// Filesystem type
+    pub fs_type: String,
+
+    /// Path that was defragmented
+    pub target: String,
+
+    /// Number of files the tool reported as processed
+    pub files_processed: u64,
+
+    /// Fragmentation score after the run, when available
+    pub score_after: Option<u32>,
+}
+
+/// Naming template applied to service-managed mount directories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MountNamingScheme {
+    /// Filesystem label (falls back to UUID, then device name)
+    #[default]
+    Label,
+
+    /// Filesystem UUID (falls back to device name)
+    Uuid,
+
+    /// Kernel device name (e.g. "sdb1")
+    DeviceName,
+}
+
+impl MountNamingScheme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Label => "label",
+            Self::Uuid => "uuid",
+            Self::DeviceName => "device_name",
+        }
+    }
+
+    pub fn to_index(self) -> usize {
+        match self {
+            Self::Label => 0,
+            Self::Uuid => 1,
+            Self::DeviceName => 2,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            1 => Self::Uuid,
+            2 => Self::DeviceName,
+            _ => Self::Label,
+        }
+    }
+}
+
+/// Per-user preference for where the service places automatic mounts
+///
+/// An empty `base_dir` keeps the UDisks default (`/run/media/$USER/<label>`).
+/// `$USER` inside `base_dir` is expanded to the caller's username by the service.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountPathPolicy {
+    /// Base directory mount points are created under
+    pub base_dir: String,
+
+    /// How the mount directory name is derived
+    pub naming: MountNamingScheme,
+}
+
+impl MountPathPolicy {
+    /// Upper bound on `-N` suffixes tried when resolving a collision
+    pub const MAX_COLLISION_SUFFIX: u32 = 100;
+
+    /// Whether this policy defers to the UDisks default location
+    pub fn is_default(&self) -> bool {
+        self.base_dir.trim().is_empty()
+    }
+
+    /// Per-user directories explicit mount points must lie inside
+    pub fn media_base_dirs(username: &str) -> [PathBuf; 2] {
+        [
+            Path::new("/run/media").join(username),
+            Path::new("/media").join(username),
+        ]
+    }
+
+    /// Whether `mount_point` lies strictly inside one of the caller's
+    /// [`media_base_dirs`](Self::media_base_dirs), without `..` components
+    pub fn is_in_media_base(mount_point: &Path, username: &str) -> bool {
+        let plain_username =
+            !username.is_empty() && username != "." && username != ".." && !username.contains('/');
+        plain_username
+            && mount_point.is_absolute()
+            && mount_point
+                .components()
+                .all(|component| matches!(component, Component::RootDir | Component::Normal(_)))
+            && Self::media_base_dirs(username)
+                .iter()
+                .any(|base| mount_point.starts_with(base) && mount_point != base)
+    }
+
+    /// Expand `$USER` in the base directory
+    pub fn expand_base_dir(&self, username: &str) -> PathBuf {
+        PathBuf::from(self.base_dir.trim().replace("$USER", username))
+    }
+
+    /// Directory name for a volume according to the naming scheme
+    ///
+    /// Falls back through label → UUID → device name when the preferred
+    /// source is empty, and strips characters that are unsafe in a path component.
+    pub fn mount_name(&self, label: &str, uuid: &str, device: &str) -> String {
+        let device_name = device.rsplit('/').next().unwrap_or(device);
+        let candidates: &[&str] = match self.naming {
+            MountNamingScheme::Label => &[label, uuid, device_name],
+            MountNamingScheme::Uuid => &[uuid, device_name],
+            MountNamingScheme::DeviceName => &[device_name],
+        };
+
+        candidates
+            .iter()
+            .map(|candidate| sanitize_mount_name(candidate))
+            .find(|name| !name.is_empty())
+            .unwrap_or_else(|| "disk".to_string())
+    }
+
+    /// Resolve a free mount point under `base_dir`
+    ///
+    /// `is_taken` reports whether a candidate path is already in use (mounted,
+    /// non-empty, or not a directory). Colliding names get a `-2`, `-3`, … suffix.
+    /// Returns `None` if no free candidate is found.
+    pub fn resolve_mount_point(
+        &self,
+        base_dir: &Path,
+        label: &str,
+        uuid: &str,
+        device: &str,
+        is_taken: impl Fn(&Path) -> bool,
+    ) -> Option<PathBuf> {
+        let name = self.mount_name(label, uuid, device);
+
+        let first = base_dir.join(&name);
+        if !is_taken(&first) {
+            return Some(first);
+        }
+
+        (2..=Self::MAX_COLLISION_SUFFIX)
+            .map(|suffix| base_dir.join(format!("{name}-{suffix}")))
+            .find(|candidate| !is_taken(candidate))
+    }
+}
+
+/// Strip path separators, control characters, and leading dots from a mount name
+fn sanitize_mount_name(raw: &str) -> String {
+    let cleaned: String = raw
+        .trim()
+        .chars()
+        .map(|c| if c == '/' || c.is_control() { '_' } else { c })
+        .collect();
+
+    cleaned.trim_start_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mount_name_falls_back_when_label_is_empty() {
+        let policy = MountPathPolicy {
+            base_dir: "/mnt".into(),
+            naming: MountNamingScheme::Label,
+        };
+        assert_eq!(policy.mount_name("Data", "1234", "/dev/sdb1"), "Data");
+        assert_eq!(policy.mount_name("", "1234", "/dev/sdb1"), "1234");
+        assert_eq!(policy.mount_name("", "", "/dev/sdb1"), "sdb1");
+        assert_eq!(policy.mount_name("../etc", "", "/dev/sdb1"), "_etc");
+    }
+
+    #[test]
+    fn resolve_mount_point_appends_suffix_on_collision() {
+        let policy = MountPathPolicy {
+            base_dir: "/media/$USER".into(),
+            naming: MountNamingScheme::DeviceName,
+        };
+        let base = policy.expand_base_dir("alice");
+        assert_eq!(base, PathBuf::from("/media/alice"));
+
+        let taken = [PathBuf::from("/media/alice/sdb1")];
+        let resolved = policy.resolve_mount_point(&base, "Data", "", "/dev/sdb1", |p| {
+            taken.iter().any(|t| t == p)
+        });
+        assert_eq!(resolved, Some(PathBuf::from("/media/alice/sdb1-2")));
+    }
+
+    #[test]
+    fn explicit_mount_points_stay_in_media_base() {
+        let inside = |path: &str| MountPathPolicy::is_in_media_base(Path::new(path), "alice");
+        assert!(inside("/run/media/alice/Data"));
+        assert!(inside("/media/alice/usb/nested"));
+        assert!(!inside("/run/media/alice"));
+        assert!(!inside("/run/media/bob/Data"));
+        assert!(!inside("/run/media/alice/../../../etc"));
+        assert!(!inside("/etc/cron.d"));
+        assert!(!inside("media/alice/Data"));
+        assert!(!MountPathPolicy::is_in_media_base(
+            Path::new("/run/media/Data"),
+            ""
+        ));
+    }
+
+    #[test]
+    fn other_mount_options_are_allow_listed() {
+        let options = |other: &[&str]| MountOptions {
+            other: other.iter().map(|option| option.to_string()).collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            options(&["ro", "noatime", "umask=077"]).check_other(),
+            Ok(())
+        );
+        assert!(options(&["suid"]).check_other().is_err());
+        assert!(options(&["dev"]).check_other().is_err());
+        assert!(options(&["exec"]).check_other().is_err());
+        assert!(options(&["uid=0"]).check_other().is_err());
+        assert!(options(&["defaults"]).check_other().is_err());
+        assert!(options(&["ro,suid"]).check_other().is_err());
+    }
+}
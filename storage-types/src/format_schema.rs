@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Schema for filesystem-specific format options
+//!
+//! Each filesystem exposes a small, fixed set of symbolic option keys. The UI
+//! renders its advanced format form from this schema and the service validates
+//! requests against it before anything is translated into mkfs arguments.
+
+use crate::FormatOptions;
+
+/// Value type and constraints of a format option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatOptionKind {
+    /// Boolean flag, "0" or "1"
+    Flag,
+
+    /// Unsigned integer within an inclusive range
+    Integer {
+        min: u64,
+        max: u64,
+        power_of_two: bool,
+    },
+
+    /// Comma-separated feature names, each optionally prefixed with '^' to disable
+    FeatureList(&'static [&'static str]),
+
+    /// One of a fixed set of values
+    Choice(&'static [&'static str]),
+}
+
+/// A single filesystem-specific format option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptionSpec {
+    /// Key used in `FormatOptions::fs_specific`
+    pub key: &'static str,
+
+    /// Short display label
+    pub label: &'static str,
+
+    /// One-line help text
+    pub help: &'static str,
+
+    /// Value type and constraints
+    pub kind: FormatOptionKind,
+}
+
+impl FormatOptionSpec {
+    /// Check a value against this option's constraints
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let valid = match self.kind {
+            FormatOptionKind::Flag => matches!(value, "0" | "1"),
+            FormatOptionKind::Integer {
+                min,
+                max,
+                power_of_two,
+            } => value
+                .parse::<u64>()
+                .is_ok_and(|n| (min..=max).contains(&n) && (!power_of_two || n.is_power_of_two())),
+            FormatOptionKind::FeatureList(allowed) => {
+                !value.is_empty()
+                    && value.split(',').all(|feature| {
+                        allowed.contains(&feature.strip_prefix('^').unwrap_or(feature))
+                    })
+            }
+            FormatOptionKind::Choice(choices) => choices.contains(&value),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("Invalid value for {}: {}", self.key, value))
+        }
+    }
+}
+
+const EXT4_FEATURES: &[&str] = &[
+    "64bit",
+    "casefold",
+    "dir_index",
+    "encrypt",
+    "extent",
+    "fast_commit",
+    "huge_file",
+    "inline_data",
+    "large_dir",
+    "metadata_csum",
+    "orphan_file",
+    "project",
+    "quota",
+    "verity",
+];
+
+const EXT_OPTIONS: &[FormatOptionSpec] = &[
+    FormatOptionSpec {
+        key: "features",
+        label: "Features",
+        help: "Comma-separated filesystem features, prefix with ^ to disable",
+        kind: FormatOptionKind::FeatureList(EXT4_FEATURES),
+    },
+    FormatOptionSpec {
+        key: "reserved_percent",
+        label: "Reserved blocks (%)",
+        help: "Share of blocks reserved for the root user",
+        kind: FormatOptionKind::Integer {
+            min: 0,
+            max: 50,
+            power_of_two: false,
+        },
+    },
+];
+
+const XFS_OPTIONS: &[FormatOptionSpec] = &[
+    FormatOptionSpec {
+        key: "reflink",
+        label: "Reflink",
+        help: "Allow copy-on-write file clones",
+        kind: FormatOptionKind::Flag,
+    },
+    FormatOptionSpec {
+        key: "crc",
+        label: "Metadata checksums",
+        help: "Checksum all metadata blocks",
+        kind: FormatOptionKind::Flag,
+    },
+];
+
+const BTRFS_OPTIONS: &[FormatOptionSpec] = &[
+    FormatOptionSpec {
+        key: "compress",
+        label: "Compression",
+        help: "Compress new files on the top-level subvolume",
+        kind: FormatOptionKind::Choice(&[
+            "none", "lzo", "zlib", "zstd", "zstd:1", "zstd:3", "zstd:6", "zstd:9", "zstd:15",
+        ]),
+    },
+    FormatOptionSpec {
+        key: "csum",
+        label: "Checksum algorithm",
+        help: "Algorithm used for data and metadata checksums",
+        kind: FormatOptionKind::Choice(&["crc32c", "xxhash", "sha256", "blake2"]),
+    },
+];
+
+const VFAT_OPTIONS: &[FormatOptionSpec] = &[FormatOptionSpec {
+    key: "cluster_size",
+    label: "Cluster size (bytes)",
+    help: "Allocation unit size, a power of two",
+    kind: FormatOptionKind::Integer {
+        min: 512,
+        max: 65536,
+        power_of_two: true,
+    },
+}];
+
+const EXFAT_OPTIONS: &[FormatOptionSpec] = &[FormatOptionSpec {
+    key: "cluster_size",
+    label: "Cluster size (bytes)",
+    help: "Allocation unit size, a power of two",
+    kind: FormatOptionKind::Integer {
+        min: 4096,
+        max: 32 * 1024 * 1024,
+        power_of_two: true,
+    },
+}];
+
+const NTFS_OPTIONS: &[FormatOptionSpec] = &[FormatOptionSpec {
+    key: "cluster_size",
+    label: "Cluster size (bytes)",
+    help: "Allocation unit size, a power of two",
+    kind: FormatOptionKind::Integer {
+        min: 512,
+        max: 2 * 1024 * 1024,
+        power_of_two: true,
+    },
+}];
+
+/// Filesystem-specific format options supported for a filesystem type
+pub fn format_option_schema(fs_type: &str) -> &'static [FormatOptionSpec] {
+    match fs_type {
+        "ext2" | "ext3" | "ext4" => EXT_OPTIONS,
+        "xfs" => XFS_OPTIONS,
+        "btrfs" => BTRFS_OPTIONS,
+        "vfat" => VFAT_OPTIONS,
+        "exfat" => EXFAT_OPTIONS,
+        "ntfs" => NTFS_OPTIONS,
+        _ => &[],
+    }
+}
+
+impl FormatOptions {
+    /// Validate the filesystem-specific options against the schema for `fs_type`
+    pub fn validate(&self, fs_type: &str) -> Result<(), String> {
+        let schema = format_option_schema(fs_type);
+
+        for (key, value) in &self.fs_specific {
+            let spec = schema
+                .iter()
+                .find(|spec| spec.key == key)
+                .ok_or_else(|| format!("Unsupported format option for {}: {}", fs_type, key))?;
+            spec.validate(value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_presets_match_schema() {
+        for fs_type in ["ext4", "xfs", "btrfs", "vfat", "exfat", "ntfs"] {
+            for preset in FormatOptions::presets(fs_type) {
+                let mut options = FormatOptions::default();
+                options.apply_preset(&preset);
+                assert_eq!(options.validate(fs_type), Ok(()), "preset {}", preset.id);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_unsafe_values() {
+        let mut options = FormatOptions::default();
+        options
+            .fs_specific
+            .insert("features".to_string(), "fast_commit,-F".to_string());
+        assert!(options.validate("ext4").is_err());
+
+        options.fs_specific.clear();
+        options
+            .fs_specific
+            .insert("cluster_size".to_string(), "3000".to_string());
+        assert!(options.validate("vfat").is_err());
+
+        options.fs_specific.clear();
+        options
+            .fs_specific
+            .insert("extra".to_string(), "--force".to_string());
+        assert!(options.validate("xfs").is_err());
+
+        options.fs_specific.clear();
+        options.fs_specific.insert(
+            "features".to_string(),
+            "^metadata_csum,fast_commit".to_string(),
+        );
+        assert!(options.validate("ext4").is_ok());
+    }
+}
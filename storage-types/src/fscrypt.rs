@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Directory encryption with fscrypt
+//!
+//! ext4 and f2fs can encrypt single directories instead of a whole volume,
+//! which suits home directories on a shared disk: each directory has its
+//! own key, unlocked with a protector (here a passphrase) while the rest of
+//! the filesystem stays readable. The `fscrypt` tool keeps its protectors
+//! and policies in a `.fscrypt` directory at the root of the filesystem.
+
+use serde::{Deserialize, Serialize};
+
+/// Whether `fs_type` supports encrypting directories
+pub fn fscrypt_capable(fs_type: &str) -> bool {
+    matches!(fs_type, "ext4" | "f2fs")
+}
+
+/// An encrypted directory and the state of its key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedDirectory {
+    pub path: String,
+    /// Whether its key is loaded, so its contents are readable
+    pub unlocked: bool,
+    /// Descriptions of the protectors, e.g. `custom protector "Home"`
+    pub protectors: Vec<String>,
+}
+
+/// Encrypted directories on one mounted filesystem
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FscryptStatus {
+    pub mount_point: String,
+    /// Whether `fscrypt` is installed
+    pub available: bool,
+    /// Whether the filesystem has fscrypt metadata; it is set up with the
+    /// first encrypted directory
+    pub set_up: bool,
+    /// Encrypted directories near the root of the filesystem
+    pub directories: Vec<EncryptedDirectory>,
+}
+
+/// The key state and protectors of a directory from `fscrypt status <dir>`
+///
+/// `None` for output not describing an encrypted directory. Partially
+/// unlocked directories (files still open after locking) count as unlocked.
+pub fn parse_directory_status(path: &str, output: &str) -> Option<EncryptedDirectory> {
+    let unlocked = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Unlocked:"))?
+        .trim();
+    let unlocked = !unlocked.starts_with("No");
+
+    // Protectors follow a "PROTECTOR  LINKED  DESCRIPTION" header, one per
+    // line until the next blank line
+    let protectors = output
+        .lines()
+        .skip_while(|line| !line.starts_with("PROTECTOR"))
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _descriptor = fields.next()?;
+            let _linked = fields.next()?;
+            let description = fields.collect::<Vec<_>>().join(" ");
+            (!description.is_empty()).then_some(description)
+        })
+        .collect();
+
+    Some(EncryptedDirectory {
+        path: path.to_string(),
+        unlocked,
+        protectors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_directory_status() {
+        let output = r#""/home/alex/private" is encrypted with fscrypt.
+
+Policy:   3a4c8d1e95e8b6f0a1d2c3b4e5f60718
+Options:  padding:32 contents:AES_256_XTS filenames:AES_256_CTS policy_version:2
+Unlocked: No
+
+Protected with 1 protector:
+PROTECTOR         LINKED  DESCRIPTION
+7626382168311a9d  No      custom protector "Private"
+"#;
+        let directory = parse_directory_status("/home/alex/private", output).unwrap();
+        assert!(!directory.unlocked);
+        assert_eq!(directory.protectors, [r#"custom protector "Private""#]);
+
+        let unlocked = output.replace("Unlocked: No", "Unlocked: Yes");
+        assert!(
+            parse_directory_status("/home/alex/private", &unlocked)
+                .unwrap()
+                .unlocked
+        );
+        assert_eq!(
+            parse_directory_status("/srv", "\"/srv\" is not encrypted\n"),
+            None
+        );
+    }
+}
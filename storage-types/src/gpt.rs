@@ -0,0 +1,446 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! GPT tables read and edited natively
+//!
+//! Some edits are awkward or impossible through UDisks: the full 64 bits
+//! of partition attributes, hybrid MBRs for firmware that only boots from
+//! MBR, and moving a partition boundary by single sectors. The service
+//! reads and writes those tables itself and refuses to edit one whose
+//! headers or partition arrays fail their checksums.
+//!
+//! Edits to several entries are checked and written together, so a raw
+//! editor can move two neighbouring boundaries at once without the table
+//! passing through an overlapping state.
+
+use crate::ByteRange;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// GPT attribute bit for partitions legacy BIOS may boot from
+pub const LEGACY_BIOS_BOOTABLE: u64 = 1 << 2;
+
+/// An MBR has four records and a hybrid one keeps one for the protective
+/// 0xEE entry
+pub const MAX_HYBRID_PARTITIONS: usize = 3;
+
+/// A GPT partition table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GptTable {
+    pub disk_guid: String,
+    /// Logical sector size, in bytes
+    pub sector_size: u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    /// Whether the primary header and partition array match their CRC32s
+    pub primary_valid: bool,
+    /// Whether the backup header and partition array match their CRC32s
+    pub backup_valid: bool,
+    pub entries: Vec<GptEntry>,
+}
+
+impl GptTable {
+    /// Whether both copies of the table are intact, so an edit can't
+    /// make a damaged one worse
+    pub fn is_valid(&self) -> bool {
+        self.primary_valid && self.backup_valid
+    }
+
+    pub fn entry(&self, number: u32) -> Option<&GptEntry> {
+        self.entries.iter().find(|entry| entry.number == number)
+    }
+}
+
+/// A used partition entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GptEntry {
+    /// Partition number, counted from 1
+    pub number: u32,
+    pub type_guid: String,
+    pub guid: String,
+    pub first_lba: u64,
+    /// Last sector, inclusive
+    pub last_lba: u64,
+    /// All 64 attribute bits, including the type-specific ones
+    pub attributes: u64,
+    pub name: String,
+}
+
+/// An edit to one partition entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GptEdit {
+    Attributes(u64),
+    Name(String),
+    TypeGuid(String),
+    /// Unique partition GUID, which PARTUUID= in fstab refers to
+    Guid(String),
+    /// New first and last sector, inclusive
+    Bounds {
+        first_lba: u64,
+        last_lba: u64,
+    },
+}
+
+/// An edit to partition `number`, one of a batch written together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GptEntryEdit {
+    pub number: u32,
+    pub edit: GptEdit,
+}
+
+/// Why `edit` can't be applied to partition `number` of `table`, if it
+/// can't
+pub fn check_edit(table: &GptTable, number: u32, edit: &GptEdit) -> Result<(), String> {
+    check_edits(
+        table,
+        &[GptEntryEdit {
+            number,
+            edit: edit.clone(),
+        }],
+    )
+}
+
+/// Why `edits` can't be applied to `table` together, if they can't
+pub fn check_edits(table: &GptTable, edits: &[GptEntryEdit]) -> Result<(), String> {
+    check_entries(table, &apply_edits(table, edits)?)
+}
+
+/// The entries of `table` with `edits` applied in order
+pub fn apply_edits(table: &GptTable, edits: &[GptEntryEdit]) -> Result<Vec<GptEntry>, String> {
+    let mut entries = table.entries.clone();
+    for GptEntryEdit { number, edit } in edits {
+        let entry = entries
+            .iter_mut()
+            .find(|entry| entry.number == *number)
+            .ok_or_else(|| format!("There is no partition {number}"))?;
+        match edit {
+            GptEdit::Attributes(attributes) => entry.attributes = *attributes,
+            GptEdit::Name(name) => entry.name = name.clone(),
+            GptEdit::TypeGuid(guid) => entry.type_guid = guid.to_uppercase(),
+            GptEdit::Guid(guid) => entry.guid = guid.to_uppercase(),
+            GptEdit::Bounds {
+                first_lba,
+                last_lba,
+            } => {
+                entry.first_lba = *first_lba;
+                entry.last_lba = *last_lba;
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Edits turning the entries of `table` into `entries`, matched by number
+pub fn diff_entries(table: &GptTable, entries: &[GptEntry]) -> Vec<GptEntryEdit> {
+    let mut edits = Vec::new();
+    for entry in entries {
+        let Some(old) = table.entry(entry.number) else {
+            continue;
+        };
+        let mut push = |edit| {
+            edits.push(GptEntryEdit {
+                number: entry.number,
+                edit,
+            })
+        };
+        if (entry.first_lba, entry.last_lba) != (old.first_lba, old.last_lba) {
+            push(GptEdit::Bounds {
+                first_lba: entry.first_lba,
+                last_lba: entry.last_lba,
+            });
+        }
+        if !entry.type_guid.eq_ignore_ascii_case(&old.type_guid) {
+            push(GptEdit::TypeGuid(entry.type_guid.clone()));
+        }
+        if !entry.guid.eq_ignore_ascii_case(&old.guid) {
+            push(GptEdit::Guid(entry.guid.clone()));
+        }
+        if entry.attributes != old.attributes {
+            push(GptEdit::Attributes(entry.attributes));
+        }
+        if entry.name != old.name {
+            push(GptEdit::Name(entry.name.clone()));
+        }
+    }
+    edits
+}
+
+/// A GUID the table can hold for a used entry
+fn parse_guid(guid: &str) -> Option<Uuid> {
+    Uuid::parse_str(guid).ok().filter(|guid| !guid.is_nil())
+}
+
+/// Why `entries` can't replace the entries of `table`, if they can't
+///
+/// GUIDs are only judged where they changed, so a table holding odd ones
+/// can still have its other fields edited.
+pub fn check_entries(table: &GptTable, entries: &[GptEntry]) -> Result<(), String> {
+    if !table.is_valid() {
+        return Err("The partition table fails its checksums; repair it first".to_string());
+    }
+
+    for entry in entries {
+        let number = entry.number;
+        let Some(old) = table.entry(number) else {
+            return Err(format!("There is no partition {number}"));
+        };
+        // 36 UTF-16 code units
+        if entry.name.encode_utf16().count() > 36 {
+            return Err(format!(
+                "The name of partition {number} is longer than 36 characters"
+            ));
+        }
+        if !entry.type_guid.eq_ignore_ascii_case(&old.type_guid)
+            && parse_guid(&entry.type_guid).is_none()
+        {
+            return Err(format!(
+                "The type of partition {number} is not a GUID: {}",
+                entry.type_guid
+            ));
+        }
+        if !entry.guid.eq_ignore_ascii_case(&old.guid) {
+            let Some(guid) = parse_guid(&entry.guid) else {
+                return Err(format!(
+                    "The GUID of partition {number} is not a GUID: {}",
+                    entry.guid
+                ));
+            };
+            let taken = entries
+                .iter()
+                .any(|other| other.number != number && parse_guid(&other.guid) == Some(guid));
+            if taken || parse_guid(&table.disk_guid) == Some(guid) {
+                return Err(format!("The GUID of partition {number} is not unique"));
+            }
+        }
+        if entry.first_lba > entry.last_lba {
+            return Err(format!("Partition {number} must end after it starts"));
+        }
+        if entry.first_lba < table.first_usable_lba || entry.last_lba > table.last_usable_lba {
+            return Err(format!(
+                "Partition {number} must lie between sectors {} and {}",
+                table.first_usable_lba, table.last_usable_lba
+            ));
+        }
+        let overlaps = entries.iter().find(|other| {
+            other.number != number
+                && other.first_lba <= entry.last_lba
+                && entry.first_lba <= other.last_lba
+        });
+        if let Some(other) = overlaps {
+            return Err(format!(
+                "Partition {number} would overlap partition {}",
+                other.number
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A GPT entry deleted through the service, kept for the rest of the
+/// session so it can be put back exactly as it was
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletedPartition {
+    /// Identifies the deletion until the service stops
+    pub id: u64,
+    /// Disk the entry was deleted from, e.g. "/dev/sda"
+    pub disk: String,
+    /// GUID of the table the entry was in, so nothing is offered for a
+    /// disk partitioned anew since
+    pub disk_guid: String,
+    pub sector_size: u64,
+    pub entry: GptEntry,
+    /// Unix time of the deletion, in seconds
+    pub deleted_at: u64,
+}
+
+impl DeletedPartition {
+    /// Bytes the partition spanned
+    pub fn range(&self) -> ByteRange {
+        ByteRange {
+            start: self.entry.first_lba * self.sector_size,
+            end: (self.entry.last_lba + 1) * self.sector_size,
+        }
+    }
+}
+
+/// Why `deleted` can't be put back into `table`, if it can't: the table
+/// was replaced, or its number, GUID or sectors were taken since
+pub fn check_restore(table: &GptTable, deleted: &DeletedPartition) -> Result<(), String> {
+    if !table.is_valid() {
+        return Err("The partition table fails its checksums; repair it first".to_string());
+    }
+    if !table.disk_guid.eq_ignore_ascii_case(&deleted.disk_guid)
+        || table.sector_size != deleted.sector_size
+    {
+        return Err("The disk was partitioned anew since".to_string());
+    }
+    let entry = &deleted.entry;
+    if table.entry(entry.number).is_some() {
+        return Err(format!("Partition number {} is in use again", entry.number));
+    }
+    if entry.first_lba < table.first_usable_lba || entry.last_lba > table.last_usable_lba {
+        return Err("The partition lies outside the usable sectors of the table".to_string());
+    }
+    for other in &table.entries {
+        if other.guid.eq_ignore_ascii_case(&entry.guid) {
+            return Err(format!(
+                "Partition {} has the GUID of the deleted one",
+                other.number
+            ));
+        }
+        if other.first_lba <= entry.last_lba && entry.first_lba <= other.last_lba {
+            return Err(format!(
+                "Its space is used by partition {} now",
+                other.number
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// MBR type for the hybrid record of a GPT partition type
+pub fn hybrid_mbr_type(type_guid: &str) -> u8 {
+    match type_guid.to_ascii_uppercase().as_str() {
+        // EFI System
+        "C12A7328-F81F-11D2-BA4B-00A0C93EC93B" => 0xEF,
+        // Microsoft basic data
+        "EBD0A0A2-B9E5-4433-87C0-68B6B72699C7" => 0x07,
+        // Linux swap
+        "0657FD6D-A4AB-43C4-84E5-0933C84B4F4F" => 0x82,
+        // Linux LVM
+        "E6D6D379-F507-44C2-A23C-238F2A3DF928" => 0x8E,
+        // Linux RAID
+        "A19D880F-05FC-4D3B-A006-743F0F84911E" => 0xFD,
+        _ => 0x83,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> GptTable {
+        let entry = |number, first_lba, last_lba| GptEntry {
+            number,
+            type_guid: "0FC63DAF-8483-4772-8E79-3D69D8477DE4".to_string(),
+            guid: String::new(),
+            first_lba,
+            last_lba,
+            attributes: 0,
+            name: String::new(),
+        };
+        GptTable {
+            disk_guid: String::new(),
+            sector_size: 512,
+            first_usable_lba: 34,
+            last_usable_lba: 10_000,
+            primary_valid: true,
+            backup_valid: true,
+            entries: vec![entry(1, 2048, 4095), entry(2, 4096, 8191)],
+        }
+    }
+
+    #[test]
+    fn checks_bounds_against_the_table() {
+        let table = table();
+        let bounds = |first_lba, last_lba| GptEdit::Bounds {
+            first_lba,
+            last_lba,
+        };
+        assert!(check_edit(&table, 1, &bounds(34, 4095)).is_ok());
+        assert!(check_edit(&table, 1, &bounds(2048, 4096)).is_err());
+        assert!(check_edit(&table, 2, &bounds(4096, 10_001)).is_err());
+        assert!(check_edit(&table, 2, &bounds(5000, 4096)).is_err());
+        assert!(check_edit(&table, 3, &bounds(8192, 9000)).is_err());
+    }
+
+    #[test]
+    fn refuses_edits_to_damaged_tables() {
+        let mut table = table();
+        table.backup_valid = false;
+        assert!(check_edit(&table, 1, &GptEdit::Attributes(LEGACY_BIOS_BOOTABLE)).is_err());
+    }
+
+    #[test]
+    fn checks_edits_together() {
+        let table = table();
+        let bounds = |number, first_lba, last_lba| GptEntryEdit {
+            number,
+            edit: GptEdit::Bounds {
+                first_lba,
+                last_lba,
+            },
+        };
+        // Growing 1 into 2 only works while 2 shrinks as well
+        assert!(check_edits(&table, &[bounds(1, 2048, 5000)]).is_err());
+        assert!(check_edits(&table, &[bounds(1, 2048, 5000), bounds(2, 5001, 8191)]).is_ok());
+
+        let guid = |number, guid: &str| GptEntryEdit {
+            number,
+            edit: GptEdit::Guid(guid.to_string()),
+        };
+        let unique = "8E2D0B3A-6C1F-4C55-9D1E-1F0A4B7E2C90";
+        assert!(check_edits(&table, &[guid(1, unique)]).is_ok());
+        assert!(check_edits(&table, &[guid(1, unique), guid(2, unique)]).is_err());
+        assert!(check_edits(&table, &[guid(1, "not-a-guid")]).is_err());
+    }
+
+    #[test]
+    fn diffs_edited_entries() {
+        let table = table();
+        let mut entries = table.entries.clone();
+        entries[0].name = "EFI".to_string();
+        entries[1].last_lba = 9000;
+        entries[1].attributes = LEGACY_BIOS_BOOTABLE;
+
+        let edits = diff_entries(&table, &entries);
+        assert_eq!(edits.len(), 3);
+        assert_eq!(apply_edits(&table, &edits).unwrap(), entries);
+        assert!(diff_entries(&table, &table.entries).is_empty());
+    }
+
+    #[test]
+    fn restores_only_into_unused_space() {
+        let mut table = table();
+        let root = GptEntry {
+            guid: "3B1F5E8C-2D47-4A9E-8F60-7C1D2E3F4A5B".to_string(),
+            ..table.entries.pop().unwrap()
+        };
+        let deleted = DeletedPartition {
+            id: 1,
+            disk: "/dev/sda".to_string(),
+            disk_guid: table.disk_guid.clone(),
+            sector_size: 512,
+            entry: root.clone(),
+            deleted_at: 0,
+        };
+        assert!(check_restore(&table, &deleted).is_ok());
+        assert_eq!(deleted.range().start, 4096 * 512);
+        assert_eq!(deleted.range().size(), 4096 * 512);
+
+        let mut reused = table.clone();
+        reused.entries.push(GptEntry {
+            number: 3,
+            first_lba: 8000,
+            last_lba: 9000,
+            ..root
+        });
+        assert!(check_restore(&reused, &deleted).is_err());
+
+        let mut replaced = table;
+        replaced.disk_guid = "8E2D0B3A-6C1F-4C55-9D1E-1F0A4B7E2C90".to_string();
+        assert!(check_restore(&replaced, &deleted).is_err());
+    }
+
+    #[test]
+    fn maps_types_for_hybrid_records() {
+        assert_eq!(
+            hybrid_mbr_type("c12a7328-f81f-11d2-ba4b-00a0c93ec93b"),
+            0xEF
+        );
+        assert_eq!(
+            hybrid_mbr_type("0FC63DAF-8483-4772-8E79-3D69D8477DE4"),
+            0x83
+        );
+    }
+}
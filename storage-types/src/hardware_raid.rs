@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Physical disks behind hardware RAID controllers
+//!
+//! A hardware RAID controller only exposes its logical drives as block
+//! devices. smartctl can still reach the member disks through the
+//! controller's pass-through (`-d megaraid,N`, `-d 3ware,N`, ...), which is
+//! enough to show their health, though not to partition them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::SmartInfo;
+
+/// smartctl device types of controllers with disk pass-through
+pub const PASSTHROUGH_CONTROLLERS: [&str; 5] = ["megaraid", "3ware", "areca", "cciss", "aacraid"];
+
+/// A disk behind a hardware RAID controller
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhysicalDevice {
+    /// Device smartctl opens to reach the controller, e.g. "/dev/bus/0"
+    pub device: String,
+    /// smartctl device type selecting the disk, e.g. "megaraid,8"
+    pub device_type: String,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub size: Option<u64>,
+    pub smart: Option<SmartInfo>,
+    /// Why SMART data could not be read
+    pub error: Option<String>,
+}
+
+impl PhysicalDevice {
+    /// Controller family, e.g. "megaraid"
+    pub fn controller(&self) -> &str {
+        self.device_type
+            .split_once(',')
+            .map_or(self.device_type.as_str(), |(controller, _)| controller)
+    }
+
+    /// Model, or the controller and slot when the disk does not report one
+    pub fn display_name(&self) -> String {
+        match &self.model {
+            Some(model) => model.clone(),
+            None => match self.device_type.split_once(',') {
+                Some((controller, slot)) => format!("{controller} disk {slot}"),
+                None => self.device_type.clone(),
+            },
+        }
+    }
+
+    /// SMART overall health, when read
+    pub fn health_passed(&self) -> Option<bool> {
+        let health = self.smart.as_ref()?.attributes.get("overall_health")?;
+        Some(health == "PASSED")
+    }
+}
+
+/// Pass-through disks in `smartctl --scan-open` output, as (device,
+/// device type) pairs
+///
+/// Lines look like
+/// "/dev/bus/0 -d megaraid,8 # /dev/bus/0 [megaraid_disk_08], SCSI device".
+pub fn parse_smartctl_scan(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next()?;
+            let mut words = line.split_whitespace();
+            let device = words.next()?;
+            if words.next()? != "-d" {
+                return None;
+            }
+            let device_type = words.next()?;
+            let (controller, _) = device_type.split_once(',')?;
+            PASSTHROUGH_CONTROLLERS
+                .contains(&controller)
+                .then(|| (device.to_string(), device_type.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_keeps_pass_through_disks() {
+        let output = "\
+/dev/sda -d scsi # /dev/sda, SCSI device
+/dev/bus/0 -d megaraid,8 # /dev/bus/0 [megaraid_disk_08], SCSI device
+/dev/bus/0 -d megaraid,9 # /dev/bus/0 [megaraid_disk_09], SCSI device
+/dev/twa0 -d 3ware,0 # /dev/twa0 [3ware_disk_00], ATA device
+/dev/nvme0 -d nvme # /dev/nvme0, NVMe device
+";
+        assert_eq!(
+            parse_smartctl_scan(output),
+            [
+                ("/dev/bus/0".to_string(), "megaraid,8".to_string()),
+                ("/dev/bus/0".to_string(), "megaraid,9".to_string()),
+                ("/dev/twa0".to_string(), "3ware,0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn disks_without_a_model_are_named_by_slot() {
+        let mut device = PhysicalDevice {
+            device: "/dev/bus/0".to_string(),
+            device_type: "megaraid,8".to_string(),
+            ..PhysicalDevice::default()
+        };
+        assert_eq!(device.controller(), "megaraid");
+        assert_eq!(device.display_name(), "megaraid disk 8");
+        assert_eq!(device.health_passed(), None);
+
+        device.model = Some("SEAGATE ST4000NM0023".to_string());
+        assert_eq!(device.display_name(), "SEAGATE ST4000NM0023");
+    }
+}
@@ -0,0 +1,684 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Disk health summary
+//!
+//! Condenses SMART attributes, their recent samples and the self-test
+//! history of a drive into a 0–100 score with the factors that lowered it.
+//! The samples also give per-week trends of the failure-predicting counters,
+//! which flag a drive whose pending sectors grow before any threshold trips.
+//! Device errors from the kernel log count as well, as they reveal failing
+//! drives, cables and controllers that SMART does not track, and so do
+//! the error counters of btrfs filesystems on the drive, which catch
+//! silently corrupted data through their checksums.
+
+use serde::{Deserialize, Serialize};
+
+use crate::btrfs::BtrfsDeviceStats;
+use crate::kernel_log::KernelDeviceError;
+use crate::smart::{SelfTestRecord, SmartInfo};
+use crate::temperature::{TemperatureLevel, TemperatureThresholds};
+
+/// Attribute names of the reallocated sector count (smartctl, UDisks)
+const REALLOCATED_KEYS: [&str; 2] = ["Reallocated_Sector_Ct", "reallocated-sector-count"];
+
+/// Attribute names of the pending sector count
+const PENDING_KEYS: [&str; 2] = ["Current_Pending_Sector", "current-pending-sector"];
+
+/// Attribute names of the uncorrectable sector count
+const UNCORRECTABLE_KEYS: [&str; 3] = [
+    "Offline_Uncorrectable",
+    "offline-uncorrectable",
+    "Reported_Uncorrect",
+];
+
+/// NVMe media and data integrity errors
+const MEDIA_ERROR_KEYS: [&str; 1] = ["media_errors"];
+
+/// NVMe estimate of the endurance used, in percent
+const WEAR_KEYS: [&str; 2] = ["percentage_used", "percent_used"];
+
+/// Samples older than this before the newest one do not count towards trends
+pub const TREND_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+/// Shortest sampled period a trend is computed over
+const MIN_TREND_SPAN: u64 = 24 * 60 * 60;
+
+const WEEK: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+/// Kernel errors older than this do not lower the score, in seconds
+pub const KERNEL_ERROR_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+/// Sector counter of a sample, its base penalty and the factor it reports
+type SectorCounter = (
+    fn(&SmartSample) -> Option<u64>,
+    u32,
+    fn(u64, bool) -> HealthFactor,
+);
+
+/// SMART values of a drive at one point in time
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SmartSample {
+    /// Seconds since epoch (UTC)
+    pub taken_at: u64,
+    pub temperature_c: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub uncorrectable_sectors: Option<u64>,
+    pub media_errors: Option<u64>,
+    pub percentage_used: Option<u64>,
+}
+
+impl SmartSample {
+    /// Extract the tracked values from SMART data
+    pub fn from_info(info: &SmartInfo, taken_at: u64) -> Self {
+        let attribute = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| info.attributes.get(*key))
+                .and_then(|value| value.parse().ok())
+        };
+
+        Self {
+            taken_at,
+            temperature_c: info.temperature_c,
+            reallocated_sectors: attribute(&REALLOCATED_KEYS),
+            pending_sectors: attribute(&PENDING_KEYS),
+            uncorrectable_sectors: attribute(&UNCORRECTABLE_KEYS),
+            media_errors: attribute(&MEDIA_ERROR_KEYS),
+            percentage_used: attribute(&WEAR_KEYS),
+        }
+    }
+}
+
+/// SMART counter tracked over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrendAttribute {
+    ReallocatedSectors,
+    PendingSectors,
+    UncorrectableSectors,
+    MediaErrors,
+    PercentageUsed,
+}
+
+impl TrendAttribute {
+    pub const ALL: [TrendAttribute; 5] = [
+        TrendAttribute::PendingSectors,
+        TrendAttribute::UncorrectableSectors,
+        TrendAttribute::ReallocatedSectors,
+        TrendAttribute::MediaErrors,
+        TrendAttribute::PercentageUsed,
+    ];
+
+    pub fn value(&self, sample: &SmartSample) -> Option<u64> {
+        match self {
+            Self::ReallocatedSectors => sample.reallocated_sectors,
+            Self::PendingSectors => sample.pending_sectors,
+            Self::UncorrectableSectors => sample.uncorrectable_sectors,
+            Self::MediaErrors => sample.media_errors,
+            Self::PercentageUsed => sample.percentage_used,
+        }
+    }
+}
+
+/// Change of a counter over the sampled period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartTrend {
+    pub attribute: TrendAttribute,
+
+    /// Latest value
+    pub current: u64,
+
+    /// Change per week between the first and last sample in [`TREND_WINDOW`]
+    pub per_week: f64,
+
+    /// Seconds between the first and last sample the rate is based on
+    pub span: u64,
+}
+
+impl SmartTrend {
+    pub fn is_rising(&self) -> bool {
+        self.per_week > 0.0
+    }
+}
+
+/// Per-week trends of `samples` (oldest first), for counters sampled over at
+/// least a day
+pub fn trends(samples: &[SmartSample]) -> Vec<SmartTrend> {
+    let Some(newest) = samples.last() else {
+        return Vec::new();
+    };
+    let since = newest.taken_at.saturating_sub(TREND_WINDOW);
+    let window: Vec<&SmartSample> = samples.iter().filter(|s| s.taken_at >= since).collect();
+
+    TrendAttribute::ALL
+        .into_iter()
+        .filter_map(|attribute| {
+            let mut values = window
+                .iter()
+                .filter_map(|s| attribute.value(s).map(|value| (s.taken_at, value)));
+            let (first_at, first) = values.next()?;
+            let (last_at, last) = values.next_back()?;
+            let span = last_at.checked_sub(first_at)?;
+            if span < MIN_TREND_SPAN {
+                return None;
+            }
+            Some(SmartTrend {
+                attribute,
+                current: last,
+                per_week: (last as f64 - first as f64) * WEEK / span as f64,
+                span,
+            })
+        })
+        .collect()
+}
+
+/// Overall health of a drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HealthLevel {
+    Good,
+    Warning,
+    Critical,
+    /// No SMART data
+    #[default]
+    Unknown,
+}
+
+/// A finding that lowered the health score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealthFactor {
+    /// The drive reports its SMART self-assessment as failed
+    SmartFailed,
+    /// The last finished self-test did not pass
+    SelfTestFailed {
+        result: String,
+    },
+    ReallocatedSectors {
+        count: u64,
+        rising: bool,
+    },
+    PendingSectors {
+        count: u64,
+        rising: bool,
+    },
+    UncorrectableSectors {
+        count: u64,
+        rising: bool,
+    },
+    MediaErrors {
+        count: u64,
+        rising: bool,
+    },
+    /// Rated endurance used, in percent
+    Wear {
+        percent: u64,
+    },
+    /// Highest recent temperature
+    Temperature {
+        celsius: u64,
+    },
+    /// Device errors in the kernel log over the last week
+    KernelErrors {
+        count: u64,
+    },
+    /// Errors btrfs counted on the drive's partitions since the counters
+    /// were last reset
+    BtrfsErrors {
+        /// Write, read and flush errors
+        io: u64,
+        /// Checksum and generation errors
+        corruption: u64,
+    },
+}
+
+impl std::fmt::Display for HealthFactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let trend = |rising: bool| if rising { ", rising" } else { "" };
+        match self {
+            Self::SmartFailed => write!(f, "SMART self-assessment failed"),
+            Self::SelfTestFailed { result } => write!(f, "Last self-test failed: {result}"),
+            Self::ReallocatedSectors { count, rising } => {
+                write!(f, "{count} reallocated sectors{}", trend(*rising))
+            }
+            Self::PendingSectors { count, rising } => {
+                write!(f, "{count} pending sectors{}", trend(*rising))
+            }
+            Self::UncorrectableSectors { count, rising } => {
+                write!(f, "{count} uncorrectable sectors{}", trend(*rising))
+            }
+            Self::MediaErrors { count, rising } => {
+                write!(f, "{count} media errors{}", trend(*rising))
+            }
+            Self::Wear { percent } => write!(f, "{percent}% of rated endurance used"),
+            Self::Temperature { celsius } => write!(f, "Reached {celsius} °C"),
+            Self::KernelErrors { count } => write!(f, "{count} device errors in the kernel log"),
+            Self::BtrfsErrors { io, corruption } => write!(
+                f,
+                "{io} I/O and {corruption} corruption errors counted by btrfs"
+            ),
+        }
+    }
+}
+
+/// Health score of a drive with its explanation
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DiskHealthSummary {
+    /// Device path (e.g., "/dev/sda")
+    pub device: String,
+
+    /// 100 for a drive without findings, 0 for a failing one
+    pub score: u8,
+
+    pub level: HealthLevel,
+
+    /// Findings that lowered the score, most severe first
+    pub factors: Vec<HealthFactor>,
+
+    /// Per-week trends of the tracked counters
+    #[serde(default)]
+    pub trends: Vec<SmartTrend>,
+}
+
+impl DiskHealthSummary {
+    /// Score `info`, read at `now`, against earlier `samples` (oldest
+    /// first), the self-test `history` and the temperature `thresholds` of
+    /// the drive
+    pub fn compute(
+        device: &str,
+        info: &SmartInfo,
+        samples: &[SmartSample],
+        history: &[SelfTestRecord],
+        thresholds: &TemperatureThresholds,
+        now: u64,
+    ) -> Self {
+        let current = SmartSample::from_info(info, now);
+        let mut sampled = samples.to_vec();
+        sampled.push(current.clone());
+        let trends = trends(&sampled);
+
+        let mut penalties: Vec<(u32, HealthFactor)> = Vec::new();
+
+        let failed = info
+            .attributes
+            .get("overall_health")
+            .is_some_and(|value| value == "FAILED");
+        if failed {
+            penalties.push((60, HealthFactor::SmartFailed));
+        }
+
+        if let Some(result) = history
+            .iter()
+            .rev()
+            .find_map(|record| record.result.as_deref())
+            .filter(|result| self_test_failed(result))
+        {
+            penalties.push((
+                40,
+                HealthFactor::SelfTestFailed {
+                    result: result.to_string(),
+                },
+            ));
+        }
+
+        // Sector counters: a base penalty, one point per sector up to a cap,
+        // and more when the count grew over the sampled period
+        let counters: [SectorCounter; 4] = [
+            (
+                |s| s.pending_sectors,
+                15,
+                |count, rising| HealthFactor::PendingSectors { count, rising },
+            ),
+            (
+                |s| s.uncorrectable_sectors,
+                10,
+                |count, rising| HealthFactor::UncorrectableSectors { count, rising },
+            ),
+            (
+                |s| s.reallocated_sectors,
+                10,
+                |count, rising| HealthFactor::ReallocatedSectors { count, rising },
+            ),
+            (
+                |s| s.media_errors,
+                10,
+                |count, rising| HealthFactor::MediaErrors { count, rising },
+            ),
+        ];
+        for (value, base, factor) in counters {
+            let Some(count) = value(&current).filter(|count| *count > 0) else {
+                continue;
+            };
+            let rising = samples
+                .iter()
+                .filter_map(value)
+                .min()
+                .is_some_and(|earliest| count > earliest);
+            let penalty = base + count.min(20) as u32 + if rising { 10 } else { 0 };
+            penalties.push((penalty, factor(count, rising)));
+        }
+
+        if let Some(percent) = current.percentage_used {
+            let penalty = match percent {
+                100.. => 40,
+                90.. => 20,
+                70.. => 10,
+                _ => 0,
+            };
+            if penalty > 0 {
+                penalties.push((penalty, HealthFactor::Wear { percent }));
+            }
+        }
+
+        let max_temperature = samples
+            .iter()
+            .filter_map(|sample| sample.temperature_c)
+            .chain(current.temperature_c)
+            .max();
+        if let Some(celsius) = max_temperature {
+            let penalty = match thresholds.level(celsius) {
+                TemperatureLevel::Critical => 20,
+                TemperatureLevel::Warning => 10,
+                TemperatureLevel::Normal => 0,
+            };
+            if penalty > 0 {
+                penalties.push((penalty, HealthFactor::Temperature { celsius }));
+            }
+        }
+
+        penalties.sort_by_key(|(penalty, _)| std::cmp::Reverse(*penalty));
+        let total: u32 = penalties.iter().map(|(penalty, _)| penalty).sum();
+        let score = 100u32.saturating_sub(total) as u8;
+        // Growing pending or uncorrectable sectors predict failure even
+        // while their counts are too low to cost many points
+        let predicts_failure = trends.iter().any(|trend| {
+            trend.is_rising()
+                && matches!(
+                    trend.attribute,
+                    TrendAttribute::PendingSectors | TrendAttribute::UncorrectableSectors
+                )
+        });
+        let level = match score {
+            _ if failed => HealthLevel::Critical,
+            0..50 => HealthLevel::Critical,
+            50..80 => HealthLevel::Warning,
+            _ if predicts_failure => HealthLevel::Warning,
+            _ => HealthLevel::Good,
+        };
+
+        Self {
+            device: device.to_string(),
+            score,
+            level,
+            factors: penalties.into_iter().map(|(_, factor)| factor).collect(),
+            trends,
+        }
+    }
+
+    /// Lower the score for device errors the kernel logged within
+    /// [`KERNEL_ERROR_WINDOW`] before `now`
+    ///
+    /// This also applies to drives without SMART support, whose summary
+    /// is then based on the kernel errors alone.
+    pub fn add_kernel_errors(&mut self, errors: &[KernelDeviceError], now: u64) {
+        let cutoff = now.saturating_sub(KERNEL_ERROR_WINDOW);
+        let count = errors.iter().filter(|e| e.timestamp >= cutoff).count() as u64;
+        if count == 0 {
+            return;
+        }
+
+        self.penalize(
+            10 + count.min(20) as u8,
+            HealthFactor::KernelErrors { count },
+        );
+    }
+
+    /// Lower the score for the error counters of the btrfs devices on the
+    /// drive; checksum errors mean damaged data and cost more than I/O
+    /// errors
+    pub fn add_btrfs_errors(&mut self, stats: &[BtrfsDeviceStats]) {
+        let io: u64 = stats
+            .iter()
+            .map(|device| device.write_io_errs + device.read_io_errs + device.flush_io_errs)
+            .sum();
+        let corruption: u64 = stats
+            .iter()
+            .map(|device| device.corruption_errs + device.generation_errs)
+            .sum();
+        if io + corruption == 0 {
+            return;
+        }
+
+        let mut penalty = 0;
+        if io > 0 {
+            penalty += 10 + io.min(20) as u8;
+        }
+        if corruption > 0 {
+            penalty += 20 + corruption.min(20) as u8;
+        }
+        self.penalize(penalty, HealthFactor::BtrfsErrors { io, corruption });
+    }
+
+    /// Apply a penalty found outside SMART, which also scores drives
+    /// without SMART support
+    fn penalize(&mut self, penalty: u8, factor: HealthFactor) {
+        if self.level == HealthLevel::Unknown {
+            self.score = 100;
+        }
+        self.score = self.score.saturating_sub(penalty);
+        self.factors.push(factor);
+        // Errors the drive itself reported are always worth a warning
+        self.level = match self.score {
+            _ if self.level == HealthLevel::Critical => HealthLevel::Critical,
+            0..50 => HealthLevel::Critical,
+            _ => HealthLevel::Warning,
+        };
+    }
+
+    /// Summary of a drive without SMART support
+    pub fn unknown(device: &str) -> Self {
+        Self {
+            device: device.to_string(),
+            score: 0,
+            level: HealthLevel::Unknown,
+            factors: Vec::new(),
+            trends: Vec::new(),
+        }
+    }
+}
+
+/// Whether a finished self-test status reports a failure
+fn self_test_failed(result: &str) -> bool {
+    let result = result.to_lowercase();
+    (result.contains("fail") || result.contains("error")) && !result.contains("without error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smart::SmartSelfTestKind;
+
+    fn info(attributes: &[(&str, &str)], temperature_c: Option<u64>) -> SmartInfo {
+        SmartInfo {
+            temperature_c,
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn healthy_drive_scores_full() {
+        let summary = DiskHealthSummary::compute(
+            "/dev/sda",
+            &info(&[("Reallocated_Sector_Ct", "0")], Some(35)),
+            &[],
+            &[],
+            &TemperatureThresholds::default(),
+            0,
+        );
+        assert_eq!(summary.score, 100);
+        assert_eq!(summary.level, HealthLevel::Good);
+        assert!(summary.factors.is_empty());
+    }
+
+    #[test]
+    fn rising_sector_counts_lower_the_score() {
+        let earlier = SmartSample {
+            reallocated_sectors: Some(2),
+            ..Default::default()
+        };
+        let summary = DiskHealthSummary::compute(
+            "/dev/sda",
+            &info(&[("Reallocated_Sector_Ct", "5")], Some(40)),
+            &[earlier],
+            &[],
+            &TemperatureThresholds::default(),
+            0,
+        );
+        assert_eq!(summary.score, 75);
+        assert_eq!(summary.level, HealthLevel::Warning);
+        assert_eq!(
+            summary.factors,
+            [HealthFactor::ReallocatedSectors {
+                count: 5,
+                rising: true
+            }]
+        );
+        assert_eq!(
+            summary.factors[0].to_string(),
+            "5 reallocated sectors, rising"
+        );
+    }
+
+    #[test]
+    fn failures_are_critical() {
+        let history = [SelfTestRecord {
+            kind: SmartSelfTestKind::Short,
+            started_at: 0,
+            scheduled: true,
+            result: Some("error_read".to_string()),
+        }];
+        let summary = DiskHealthSummary::compute(
+            "/dev/sda",
+            &info(&[("overall_health", "FAILED")], None),
+            &[],
+            &history,
+            &TemperatureThresholds::default(),
+            0,
+        );
+        assert_eq!(summary.score, 0);
+        assert_eq!(summary.level, HealthLevel::Critical);
+        assert_eq!(summary.factors[0], HealthFactor::SmartFailed);
+
+        assert!(!self_test_failed("completed without error"));
+        assert!(!self_test_failed("success"));
+    }
+
+    #[test]
+    fn kernel_errors_lower_the_score() {
+        let error = |timestamp| KernelDeviceError {
+            timestamp,
+            kind: crate::kernel_log::KernelErrorKind::Io,
+            message: "I/O error, dev sdb, sector 0".to_string(),
+        };
+        let now = 30 * 24 * 60 * 60;
+
+        // Drives without SMART get a score from their kernel errors
+        let mut summary = DiskHealthSummary::unknown("/dev/sdb");
+        summary.add_kernel_errors(&[error(0), error(now - 60), error(now - 30)], now);
+        assert_eq!(summary.score, 88);
+        assert_eq!(summary.level, HealthLevel::Warning);
+        assert_eq!(summary.factors, [HealthFactor::KernelErrors { count: 2 }]);
+
+        // Old errors are ignored
+        let mut summary = DiskHealthSummary::unknown("/dev/sdb");
+        summary.add_kernel_errors(&[error(0)], now);
+        assert_eq!(summary.level, HealthLevel::Unknown);
+        assert!(summary.factors.is_empty());
+    }
+
+    #[test]
+    fn btrfs_errors_lower_the_score() {
+        let stats = BtrfsDeviceStats {
+            device: "/dev/sdb1".to_string(),
+            read_io_errs: 2,
+            corruption_errs: 5,
+            ..BtrfsDeviceStats::default()
+        };
+        let mut summary = DiskHealthSummary::unknown("/dev/sdb");
+        summary.add_btrfs_errors(&[stats]);
+        assert_eq!(summary.score, 63);
+        assert_eq!(summary.level, HealthLevel::Warning);
+        assert_eq!(
+            summary.factors,
+            [HealthFactor::BtrfsErrors {
+                io: 2,
+                corruption: 5
+            }]
+        );
+
+        let mut summary = DiskHealthSummary::unknown("/dev/sdb");
+        summary.add_btrfs_errors(&[BtrfsDeviceStats::default()]);
+        assert_eq!(summary.level, HealthLevel::Unknown);
+    }
+
+    #[test]
+    fn trends_are_per_week_over_the_window() {
+        let day = 24 * 60 * 60;
+        let sample = |days: u64, pending: u64| SmartSample {
+            taken_at: days * day,
+            pending_sectors: Some(pending),
+            reallocated_sectors: Some(8),
+            ..Default::default()
+        };
+        // The first sample lies outside the window
+        let samples = [sample(0, 0), sample(40, 1), sample(54, 5)];
+
+        let trends = trends(&samples);
+        let pending = trends
+            .iter()
+            .find(|t| t.attribute == TrendAttribute::PendingSectors)
+            .unwrap();
+        assert_eq!(pending.current, 5);
+        assert_eq!(pending.span, 14 * day);
+        assert!((pending.per_week - 2.0).abs() < f64::EPSILON);
+        assert!(pending.is_rising());
+        let reallocated = trends
+            .iter()
+            .find(|t| t.attribute == TrendAttribute::ReallocatedSectors)
+            .unwrap();
+        assert!(!reallocated.is_rising());
+
+        // Less than a day of samples gives no trend
+        assert!(
+            super::trends(&[
+                sample(0, 0),
+                SmartSample {
+                    taken_at: 60,
+                    ..sample(0, 1)
+                }
+            ])
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn rising_pending_sectors_warn() {
+        let day = 24 * 60 * 60;
+        let summary = DiskHealthSummary::compute(
+            "/dev/sda",
+            &info(&[("Current_Pending_Sector", "1")], Some(30)),
+            &[SmartSample {
+                taken_at: 0,
+                pending_sectors: Some(0),
+                ..Default::default()
+            }],
+            &[],
+            &TemperatureThresholds::default(),
+            7 * day,
+        );
+        assert_eq!(summary.score, 100 - (15 + 1 + 10));
+        assert_eq!(summary.level, HealthLevel::Warning);
+        assert!(summary.trends[0].is_rising());
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Checking all drives at once
+//!
+//! A machine-wide check reads fresh SMART data of every drive, checks the
+//! unmounted filesystems without repairing them, and asks for the state
+//! of each RAID array and the error counters of each mounted btrfs
+//! filesystem. Every finding becomes a [`HealthCheckItem`], rated with the
+//! same [`HealthLevel`] as the drive health summary.
+
+use serde::{Deserialize, Serialize};
+
+use crate::btrfs::BtrfsDeviceStats;
+use crate::health::HealthLevel;
+use crate::raid::RaidDetail;
+
+/// What an item of the check looked at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HealthCheckKind {
+    Smart,
+    Filesystem,
+    Raid,
+    Btrfs,
+}
+
+/// The outcome of checking one drive, filesystem or array
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthCheckItem {
+    pub kind: HealthCheckKind,
+    /// Device path, or mount point of a btrfs filesystem
+    pub target: String,
+    /// Unknown when the check could not run
+    pub level: HealthLevel,
+    /// Findings, for the drill-down
+    pub details: Vec<String>,
+}
+
+/// How much attention a level asks for; a check that could not run is
+/// worth a look but is not a finding
+fn severity(level: HealthLevel) -> u8 {
+    match level {
+        HealthLevel::Good => 0,
+        HealthLevel::Unknown => 1,
+        HealthLevel::Warning => 2,
+        HealthLevel::Critical => 3,
+    }
+}
+
+/// All items of one check
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthCheckReport {
+    /// Unix time the check finished, in seconds
+    pub finished_at: u64,
+    /// Most severe first, then by kind and target
+    pub items: Vec<HealthCheckItem>,
+}
+
+impl HealthCheckReport {
+    pub fn new(mut items: Vec<HealthCheckItem>, finished_at: u64) -> Self {
+        items.sort_by(|a, b| {
+            severity(b.level)
+                .cmp(&severity(a.level))
+                .then(a.kind.cmp(&b.kind))
+                .then(a.target.cmp(&b.target))
+        });
+        Self { finished_at, items }
+    }
+
+    /// The most severe level of all items; Good for an empty report
+    pub fn overall(&self) -> HealthLevel {
+        self.items
+            .iter()
+            .map(|item| item.level)
+            .max_by_key(|level| severity(*level))
+            .unwrap_or(HealthLevel::Good)
+    }
+
+    /// Number of items at `level`
+    pub fn count(&self, level: HealthLevel) -> usize {
+        self.items.iter().filter(|item| item.level == level).count()
+    }
+}
+
+/// Rate a RAID array: missing members are critical, a running resync or
+/// inconsistent sectors need watching
+pub fn raid_level(detail: &RaidDetail) -> HealthLevel {
+    if detail.degraded > 0 || detail.array_state.contains("degraded") {
+        HealthLevel::Critical
+    } else if detail.mismatch_count.is_some_and(|count| count > 0)
+        || !matches!(detail.sync_action.as_str(), "" | "idle")
+    {
+        HealthLevel::Warning
+    } else {
+        HealthLevel::Good
+    }
+}
+
+/// Rate the devices of a btrfs filesystem: checksum and generation errors
+/// mean damaged data, I/O errors a failing device or cable
+pub fn btrfs_level(stats: &[BtrfsDeviceStats]) -> HealthLevel {
+    if stats
+        .iter()
+        .any(|device| device.corruption_errs > 0 || device.generation_errs > 0)
+    {
+        HealthLevel::Critical
+    } else if stats.iter().any(|device| device.total_errors() > 0) {
+        HealthLevel::Warning
+    } else {
+        HealthLevel::Good
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: HealthCheckKind, target: &str, level: HealthLevel) -> HealthCheckItem {
+        HealthCheckItem {
+            kind,
+            target: target.to_string(),
+            level,
+            details: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reports_put_the_worst_items_first() {
+        let report = HealthCheckReport::new(
+            vec![
+                item(HealthCheckKind::Smart, "/dev/sda", HealthLevel::Good),
+                item(
+                    HealthCheckKind::Filesystem,
+                    "/dev/sdb1",
+                    HealthLevel::Unknown,
+                ),
+                item(HealthCheckKind::Raid, "/dev/md0", HealthLevel::Critical),
+                item(HealthCheckKind::Smart, "/dev/sdb", HealthLevel::Warning),
+            ],
+            0,
+        );
+        let targets: Vec<&str> = report.items.iter().map(|i| i.target.as_str()).collect();
+        assert_eq!(targets, ["/dev/md0", "/dev/sdb", "/dev/sdb1", "/dev/sda"]);
+        assert_eq!(report.overall(), HealthLevel::Critical);
+        assert_eq!(report.count(HealthLevel::Good), 1);
+        assert_eq!(HealthCheckReport::default().overall(), HealthLevel::Good);
+    }
+
+    #[test]
+    fn arrays_and_btrfs_devices_are_rated() {
+        let mut detail = RaidDetail {
+            array_state: "clean".to_string(),
+            sync_action: "idle".to_string(),
+            ..RaidDetail::default()
+        };
+        assert_eq!(raid_level(&detail), HealthLevel::Good);
+        detail.mismatch_count = Some(8);
+        assert_eq!(raid_level(&detail), HealthLevel::Warning);
+        detail.degraded = 1;
+        assert_eq!(raid_level(&detail), HealthLevel::Critical);
+
+        let mut stats = BtrfsDeviceStats {
+            device: "/dev/sdc1".to_string(),
+            ..BtrfsDeviceStats::default()
+        };
+        assert_eq!(btrfs_level(std::slice::from_ref(&stats)), HealthLevel::Good);
+        stats.read_io_errs = 2;
+        assert_eq!(
+            btrfs_level(std::slice::from_ref(&stats)),
+            HealthLevel::Warning
+        );
+        stats.corruption_errs = 1;
+        assert_eq!(btrfs_level(&[stats]), HealthLevel::Critical);
+    }
+}
@@ -0,0 +1,674 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Superblocks and LUKS headers decoded field by field
+//!
+//! What a filesystem or LUKS container records about itself, read straight
+//! from the device: useful when a volume no longer mounts or unlocks, and
+//! to see what mkfs and cryptsetup actually wrote. Decoding works on the
+//! bytes read with the sector API and never touches the device itself.
+
+use chrono::DateTime;
+use uuid::Uuid;
+
+use crate::bytes_to_pretty;
+use crate::sectors::{SectorRange, SectorStructure};
+
+/// Byte ranges (offset, length) to read from a volume to inspect it: the
+/// first 64 KiB hold the ext and XFS superblocks and LUKS headers, the
+/// next 4 KiB the primary btrfs superblock
+pub const INSPECT_READS: [(u64, u64); 2] = [(0, 0x10000), (0x10000, 0x1000)];
+
+/// Volume types the inspector decodes, as reported by blkid
+pub const INSPECTABLE_TYPES: [&str; 6] = ["ext2", "ext3", "ext4", "btrfs", "xfs", "crypto_LUKS"];
+
+/// A decoded field, e.g. "mount count" = "12"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectedField {
+    /// Field name as in the structure's specification
+    pub name: &'static str,
+    pub value: String,
+}
+
+/// A superblock or header with its decoded fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectedStructure {
+    pub structure: SectorStructure,
+    /// Byte offset from the start of the volume
+    pub offset: u64,
+    pub fields: Vec<InspectedField>,
+}
+
+/// Superblocks and headers found in bytes read from a volume
+pub fn inspect(ranges: &[SectorRange]) -> Vec<InspectedStructure> {
+    let mut structures = Vec::new();
+    if let Some(header) = find(ranges, 0, 0x1000) {
+        structures.extend(inspect_luks(header, find(ranges, 0, 0x10000)));
+        structures.extend(inspect_xfs(header));
+    }
+    if let Some(superblock) = find(ranges, EXT_SUPERBLOCK, 1024) {
+        structures.extend(inspect_ext(superblock));
+    }
+    if let Some(superblock) = find(ranges, BTRFS_SUPERBLOCK, 0x1000) {
+        structures.extend(inspect_btrfs(superblock));
+    }
+    structures
+}
+
+/// Up to `length` bytes at `offset`, from the range holding `offset`;
+/// fields beyond what was read decode as zeros
+fn find(ranges: &[SectorRange], offset: u64, length: usize) -> Option<&[u8]> {
+    ranges.iter().find_map(|range| {
+        let start = usize::try_from(offset.checked_sub(range.offset)?).ok()?;
+        let end = start.saturating_add(length).min(range.data.len());
+        (start < end).then(|| &range.data[start..end])
+    })
+}
+
+/// Fields decoded from one structure
+struct Decoder<'a> {
+    data: &'a [u8],
+    fields: Vec<InspectedField>,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            fields: Vec::new(),
+        }
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> [u8; N] {
+        self.data
+            .get(offset..offset + N)
+            .and_then(|bytes| bytes.try_into().ok())
+            .unwrap_or([0; N])
+    }
+
+    fn le16(&self, offset: usize) -> u16 {
+        u16::from_le_bytes(self.bytes(offset))
+    }
+
+    fn le32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.bytes(offset))
+    }
+
+    fn le64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.bytes(offset))
+    }
+
+    fn be16(&self, offset: usize) -> u16 {
+        u16::from_be_bytes(self.bytes(offset))
+    }
+
+    fn be32(&self, offset: usize) -> u32 {
+        u32::from_be_bytes(self.bytes(offset))
+    }
+
+    fn be64(&self, offset: usize) -> u64 {
+        u64::from_be_bytes(self.bytes(offset))
+    }
+
+    /// NUL-padded text
+    fn text(&self, offset: usize, length: usize) -> String {
+        let bytes = self.data.get(offset..offset + length).unwrap_or_default();
+        let end = bytes
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+    }
+
+    fn uuid(&self, offset: usize) -> String {
+        Uuid::from_bytes(self.bytes(offset)).to_string()
+    }
+
+    fn push(&mut self, name: &'static str, value: impl ToString) {
+        self.fields.push(InspectedField {
+            name,
+            value: value.to_string(),
+        });
+    }
+
+    fn finish(self, structure: SectorStructure, offset: u64) -> InspectedStructure {
+        InspectedStructure {
+            structure,
+            offset,
+            fields: self.fields,
+        }
+    }
+}
+
+/// Seconds since the epoch as a UTC date, "never" for zero
+fn timestamp(seconds: u64) -> String {
+    if seconds == 0 {
+        return "never".to_string();
+    }
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| seconds.to_string())
+}
+
+/// Names of the bits set in `flags`, unknown ones in hex
+fn flag_names(flags: u64, names: &[(u64, &str)]) -> String {
+    let mut set = Vec::new();
+    let mut unknown = flags;
+    for (bit, name) in names {
+        if flags & bit != 0 {
+            set.push(name.to_string());
+            unknown &= !bit;
+        }
+    }
+    if unknown != 0 {
+        set.push(format!("{unknown:#x}"));
+    }
+    if set.is_empty() {
+        "none".to_string()
+    } else {
+        set.join(" ")
+    }
+}
+
+const EXT_SUPERBLOCK: u64 = 1024;
+const EXT_MAGIC: u16 = 0xEF53;
+
+const EXT_COMPAT: [(u64, &str); 9] = [
+    (0x1, "dir_prealloc"),
+    (0x2, "imagic_inodes"),
+    (0x4, "has_journal"),
+    (0x8, "ext_attr"),
+    (0x10, "resize_inode"),
+    (0x20, "dir_index"),
+    (0x200, "sparse_super2"),
+    (0x400, "fast_commit"),
+    (0x1000, "orphan_file"),
+];
+
+const EXT_INCOMPAT_EXTENTS: u32 = 0x40;
+const EXT_INCOMPAT_64BIT: u32 = 0x80;
+const EXT_INCOMPAT: [(u64, &str); 16] = [
+    (0x1, "compression"),
+    (0x2, "filetype"),
+    (0x4, "needs_recovery"),
+    (0x8, "journal_dev"),
+    (0x10, "meta_bg"),
+    (0x40, "extent"),
+    (0x80, "64bit"),
+    (0x100, "mmp"),
+    (0x200, "flex_bg"),
+    (0x400, "ea_inode"),
+    (0x1000, "dirdata"),
+    (0x2000, "metadata_csum_seed"),
+    (0x4000, "large_dir"),
+    (0x8000, "inline_data"),
+    (0x10000, "encrypt"),
+    (0x20000, "casefold"),
+];
+
+const EXT_RO_COMPAT: [(u64, &str); 13] = [
+    (0x1, "sparse_super"),
+    (0x2, "large_file"),
+    (0x8, "huge_file"),
+    (0x10, "uninit_bg"),
+    (0x20, "dir_nlink"),
+    (0x40, "extra_isize"),
+    (0x100, "quota"),
+    (0x200, "bigalloc"),
+    (0x400, "metadata_csum"),
+    (0x1000, "readonly"),
+    (0x2000, "project"),
+    (0x8000, "verity"),
+    (0x10000, "orphan_present"),
+];
+
+/// Features that only ext4 knows
+const EXT4_INCOMPAT: u32 = EXT_INCOMPAT_EXTENTS | EXT_INCOMPAT_64BIT | 0x200 | 0x8000;
+const EXT4_RO_COMPAT: u32 = 0x8 | 0x20 | 0x40 | 0x400;
+
+fn inspect_ext(superblock: &[u8]) -> Option<InspectedStructure> {
+    let mut sb = Decoder::new(superblock);
+    if sb.le16(56) != EXT_MAGIC {
+        return None;
+    }
+
+    let compat = sb.le32(92);
+    let incompat = sb.le32(96);
+    let ro_compat = sb.le32(100);
+    let version = if incompat & EXT4_INCOMPAT != 0 || ro_compat & EXT4_RO_COMPAT != 0 {
+        "ext4"
+    } else if compat & 0x4 != 0 {
+        "ext3"
+    } else {
+        "ext2"
+    };
+    let wide = incompat & EXT_INCOMPAT_64BIT != 0;
+    let high = |offset: usize| {
+        if wide {
+            u64::from(sb.le32(offset)) << 32
+        } else {
+            0
+        }
+    };
+    let block_count = u64::from(sb.le32(4)) | high(0x150);
+    let free_blocks = u64::from(sb.le32(12)) | high(0x158);
+    let block_size = 1024u64 << sb.le32(24).min(16);
+
+    let state = sb.le16(58);
+    let mut state_names = vec![if state & 0x1 != 0 {
+        "clean"
+    } else {
+        "not clean"
+    }];
+    if state & 0x2 != 0 {
+        state_names.push("with errors");
+    }
+    if state & 0x4 != 0 {
+        state_names.push("recovering orphans");
+    }
+    let max_mounts = sb.le16(54) as i16;
+
+    sb.push("magic", format!("{:#06x}", EXT_MAGIC));
+    sb.push("filesystem", version);
+    sb.push("UUID", sb.uuid(104));
+    sb.push("volume name", sb.text(120, 16));
+    sb.push("last mounted on", sb.text(136, 64));
+    sb.push("state", state_names.join(", "));
+    sb.push(
+        "errors behavior",
+        match sb.le16(60) {
+            1 => "continue",
+            2 => "remount read-only",
+            3 => "panic",
+            _ => "unknown",
+        },
+    );
+    sb.push("revision", sb.le32(76));
+    sb.push(
+        "creator OS",
+        match sb.le32(72) {
+            0 => "Linux",
+            1 => "Hurd",
+            2 => "Masix",
+            3 => "FreeBSD",
+            4 => "Lites",
+            _ => "unknown",
+        },
+    );
+    sb.push("block size", block_size);
+    sb.push("block count", block_count);
+    sb.push("free blocks", free_blocks);
+    sb.push(
+        "size",
+        bytes_to_pretty(&block_count.saturating_mul(block_size), false),
+    );
+    sb.push("inode count", sb.le32(0));
+    sb.push("free inodes", sb.le32(16));
+    sb.push("inode size", sb.le16(88));
+    sb.push("blocks per group", sb.le32(32));
+    sb.push("inodes per group", sb.le32(40));
+    sb.push("mount count", sb.le16(52));
+    sb.push(
+        "maximum mount count",
+        if max_mounts < 0 {
+            "unlimited".to_string()
+        } else {
+            max_mounts.to_string()
+        },
+    );
+    sb.push("created", timestamp(u64::from(sb.le32(0x108))));
+    sb.push("last mounted", timestamp(u64::from(sb.le32(44))));
+    sb.push("last written", timestamp(u64::from(sb.le32(48))));
+    sb.push("last checked", timestamp(u64::from(sb.le32(64))));
+    sb.push(
+        "compatible features",
+        flag_names(compat.into(), &EXT_COMPAT),
+    );
+    sb.push(
+        "incompatible features",
+        flag_names(incompat.into(), &EXT_INCOMPAT),
+    );
+    sb.push(
+        "read-only compatible features",
+        flag_names(ro_compat.into(), &EXT_RO_COMPAT),
+    );
+    Some(sb.finish(SectorStructure::Ext, EXT_SUPERBLOCK))
+}
+
+const BTRFS_SUPERBLOCK: u64 = 0x10000;
+
+const BTRFS_COMPAT_RO: [(u64, &str); 4] = [
+    (0x1, "free_space_tree"),
+    (0x2, "free_space_tree_valid"),
+    (0x4, "verity"),
+    (0x8, "block_group_tree"),
+];
+
+const BTRFS_INCOMPAT: [(u64, &str); 14] = [
+    (0x1, "mixed_backref"),
+    (0x2, "default_subvol"),
+    (0x4, "mixed_groups"),
+    (0x8, "compress_lzo"),
+    (0x10, "compress_zstd"),
+    (0x20, "big_metadata"),
+    (0x40, "extended_iref"),
+    (0x80, "raid56"),
+    (0x100, "skinny_metadata"),
+    (0x200, "no_holes"),
+    (0x400, "metadata_uuid"),
+    (0x800, "raid1c34"),
+    (0x1000, "zoned"),
+    (0x2000, "extent_tree_v2"),
+];
+
+/// Where the device item starts in the btrfs superblock
+const BTRFS_DEV_ITEM: usize = 0xc9;
+
+fn inspect_btrfs(superblock: &[u8]) -> Option<InspectedStructure> {
+    let mut sb = Decoder::new(superblock);
+    if sb.bytes::<8>(0x40) != *b"_BHRfS_M" {
+        return None;
+    }
+
+    sb.push("magic", "_BHRfS_M");
+    sb.push("label", sb.text(0x12b, 256));
+    sb.push("filesystem UUID", sb.uuid(0x20));
+    sb.push("generation", sb.le64(0x48));
+    sb.push("total size", bytes_to_pretty(&sb.le64(0x70), true));
+    sb.push("used", bytes_to_pretty(&sb.le64(0x78), true));
+    sb.push("devices", sb.le64(0x88));
+    sb.push("device ID", sb.le64(BTRFS_DEV_ITEM));
+    sb.push("device UUID", sb.uuid(BTRFS_DEV_ITEM + 0x42));
+    sb.push("sector size", sb.le32(0x90));
+    sb.push("node size", sb.le32(0x94));
+    sb.push("stripe size", sb.le32(0x9c));
+    sb.push(
+        "checksum type",
+        match sb.le16(0xc4) {
+            0 => "crc32c",
+            1 => "xxhash64",
+            2 => "sha256",
+            3 => "blake2b",
+            _ => "unknown",
+        },
+    );
+    sb.push(
+        "read-only compatible features",
+        flag_names(sb.le64(0xb4), &BTRFS_COMPAT_RO),
+    );
+    sb.push(
+        "incompatible features",
+        flag_names(sb.le64(0xbc), &BTRFS_INCOMPAT),
+    );
+    Some(sb.finish(SectorStructure::Btrfs, BTRFS_SUPERBLOCK))
+}
+
+const XFS_RO_COMPAT: [(u64, &str); 4] = [
+    (0x1, "finobt"),
+    (0x2, "rmapbt"),
+    (0x4, "reflink"),
+    (0x8, "inobtcount"),
+];
+
+const XFS_INCOMPAT: [(u64, &str); 7] = [
+    (0x1, "ftype"),
+    (0x2, "sparse_inodes"),
+    (0x4, "meta_uuid"),
+    (0x8, "bigtime"),
+    (0x10, "needsrepair"),
+    (0x20, "nrext64"),
+    (0x40, "exchange_range"),
+];
+
+fn inspect_xfs(superblock: &[u8]) -> Option<InspectedStructure> {
+    let mut sb = Decoder::new(superblock);
+    if sb.bytes::<4>(0) != *b"XFSB" {
+        return None;
+    }
+
+    let version = sb.be16(100) & 0xf;
+    let block_size = u64::from(sb.be32(4));
+    let blocks = sb.be64(8);
+
+    sb.push("magic", "XFSB");
+    sb.push("version", version);
+    sb.push("UUID", sb.uuid(32));
+    sb.push("filesystem name", sb.text(108, 12));
+    sb.push("block size", block_size);
+    sb.push("data blocks", blocks);
+    sb.push(
+        "size",
+        bytes_to_pretty(&blocks.saturating_mul(block_size), false),
+    );
+    sb.push("free data blocks", sb.be64(144));
+    sb.push("sector size", sb.be16(102));
+    sb.push("inode size", sb.be16(104));
+    sb.push("allocated inodes", sb.be64(128));
+    sb.push("free inodes", sb.be64(136));
+    sb.push("allocation groups", sb.be32(88));
+    sb.push("blocks per allocation group", sb.be32(84));
+    sb.push("log blocks", sb.be32(96));
+    if version >= 5 {
+        sb.push(
+            "read-only compatible features",
+            flag_names(sb.be32(212).into(), &XFS_RO_COMPAT),
+        );
+        sb.push(
+            "incompatible features",
+            flag_names(sb.be32(216).into(), &XFS_INCOMPAT),
+        );
+    }
+    Some(sb.finish(SectorStructure::Xfs, 0))
+}
+
+const LUKS_MAGIC: [u8; 6] = *b"LUKS\xba\xbe";
+
+/// Marks a LUKS1 key slot in use
+const LUKS1_KEY_ENABLED: u32 = 0x00AC71F3;
+
+/// `header` holds the first 4 KiB, `area` everything read from the start,
+/// which for LUKS2 includes the JSON metadata
+fn inspect_luks(header: &[u8], area: Option<&[u8]>) -> Option<InspectedStructure> {
+    let mut hdr = Decoder::new(header);
+    if hdr.bytes::<6>(0) != LUKS_MAGIC {
+        return None;
+    }
+
+    let version = hdr.be16(6);
+    hdr.push("magic", "LUKS\\xba\\xbe");
+    hdr.push("version", version);
+    hdr.push("UUID", hdr.text(168, 40));
+    match version {
+        1 => {
+            let slots = (0..8)
+                .filter(|slot| hdr.be32(208 + slot * 48) == LUKS1_KEY_ENABLED)
+                .map(|slot| slot.to_string())
+                .collect::<Vec<_>>();
+            hdr.push(
+                "cipher",
+                format!("{}-{}", hdr.text(8, 32), hdr.text(40, 32)),
+            );
+            hdr.push("hash", hdr.text(72, 32));
+            hdr.push("key size", format!("{} bits", hdr.be32(108) * 8));
+            hdr.push("payload offset", format!("{} sectors", hdr.be32(104)));
+            hdr.push("master key iterations", hdr.be32(164));
+            hdr.push("active key slots", slots.join(", "));
+        }
+        2 => {
+            let header_size = hdr.be64(8);
+            hdr.push("label", hdr.text(24, 48));
+            hdr.push("subsystem", hdr.text(208, 48));
+            hdr.push("header size", header_size);
+            hdr.push("sequence ID", hdr.be64(16));
+            hdr.push("checksum algorithm", hdr.text(72, 32));
+            if let Some(metadata) = area.and_then(|area| luks2_metadata(area, header_size)) {
+                hdr.fields.extend(metadata);
+            }
+        }
+        _ => {}
+    }
+    Some(hdr.finish(SectorStructure::Luks, 0))
+}
+
+/// Fields of the LUKS2 JSON metadata behind the binary header
+fn luks2_metadata(area: &[u8], header_size: u64) -> Option<Vec<InspectedField>> {
+    let end = usize::try_from(header_size).ok()?.min(area.len());
+    let json = area.get(4096..end)?;
+    let json = &json[..json
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(json.len())];
+    let metadata: serde_json::Value = serde_json::from_slice(json).ok()?;
+
+    let mut fields = Vec::new();
+    let mut field = |name, value: String| fields.push(InspectedField { name, value });
+    let segment = &metadata["segments"]["0"];
+    if let Some(encryption) = segment["encryption"].as_str() {
+        field("encryption", encryption.to_string());
+    }
+    if let Some(sector_size) = segment["sector_size"].as_u64() {
+        field("sector size", sector_size.to_string());
+    }
+    if let Some(offset) = segment["offset"].as_str() {
+        field("data offset", offset.to_string());
+    }
+    if let Some(keyslots) = metadata["keyslots"].as_object() {
+        let mut slots = keyslots.keys().cloned().collect::<Vec<_>>();
+        slots.sort_by_key(|slot| slot.parse::<u32>().unwrap_or(u32::MAX));
+        field("active key slots", slots.join(", "));
+        let kdfs = keyslots
+            .values()
+            .filter_map(|slot| slot["kdf"]["type"].as_str())
+            .collect::<std::collections::BTreeSet<_>>();
+        field(
+            "key derivation",
+            kdfs.into_iter().collect::<Vec<_>>().join(", "),
+        );
+    }
+    if let Some(tokens) = metadata["tokens"].as_object() {
+        field("tokens", tokens.len().to_string());
+    }
+    Some(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value<'a>(structure: &'a InspectedStructure, name: &str) -> &'a str {
+        structure
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.value.as_str())
+            .unwrap_or_else(|| panic!("no field {name}"))
+    }
+
+    fn range(offset: u64, data: Vec<u8>) -> SectorRange {
+        SectorRange {
+            offset,
+            device_size: 1 << 30,
+            data,
+        }
+    }
+
+    #[test]
+    fn ext4_superblock_is_decoded() {
+        let mut data = vec![0u8; 0x10000];
+        let sb = &mut data[1024..2048];
+        sb[0..4].copy_from_slice(&65536u32.to_le_bytes());
+        sb[4..8].copy_from_slice(&262144u32.to_le_bytes());
+        sb[24..28].copy_from_slice(&2u32.to_le_bytes());
+        sb[52..54].copy_from_slice(&7u16.to_le_bytes());
+        sb[54..56].copy_from_slice(&(-1i16).to_le_bytes());
+        sb[56..58].copy_from_slice(&EXT_MAGIC.to_le_bytes());
+        sb[58..60].copy_from_slice(&1u16.to_le_bytes());
+        sb[92..96].copy_from_slice(&0x3cu32.to_le_bytes());
+        sb[96..100].copy_from_slice(&0x2c2u32.to_le_bytes());
+        sb[120..124].copy_from_slice(b"home");
+
+        let structures = inspect(&[range(0, data)]);
+        assert_eq!(structures.len(), 1);
+        let ext = &structures[0];
+        assert_eq!(ext.structure, SectorStructure::Ext);
+        assert_eq!(value(ext, "filesystem"), "ext4");
+        assert_eq!(value(ext, "volume name"), "home");
+        assert_eq!(value(ext, "block size"), "4096");
+        assert_eq!(value(ext, "mount count"), "7");
+        assert_eq!(value(ext, "maximum mount count"), "unlimited");
+        assert_eq!(value(ext, "state"), "clean");
+        assert_eq!(value(ext, "last checked"), "never");
+        assert_eq!(
+            value(ext, "compatible features"),
+            "has_journal ext_attr resize_inode dir_index"
+        );
+        assert_eq!(
+            value(ext, "incompatible features"),
+            "filetype extent 64bit flex_bg"
+        );
+    }
+
+    #[test]
+    fn btrfs_superblock_is_found_in_its_own_read() {
+        let mut sb = vec![0u8; 0x1000];
+        sb[0x20..0x30].copy_from_slice(&[0x11; 16]);
+        sb[0x40..0x48].copy_from_slice(b"_BHRfS_M");
+        sb[0xbc..0xc4].copy_from_slice(&0x361u64.to_le_bytes());
+        sb[0x12b..0x12f].copy_from_slice(b"data");
+
+        let structures = inspect(&[range(0, vec![0u8; 0x10000]), range(0x10000, sb)]);
+        assert_eq!(structures.len(), 1);
+        let btrfs = &structures[0];
+        assert_eq!(btrfs.offset, BTRFS_SUPERBLOCK);
+        assert_eq!(value(btrfs, "label"), "data");
+        assert_eq!(
+            value(btrfs, "filesystem UUID"),
+            "11111111-1111-1111-1111-111111111111"
+        );
+        assert_eq!(
+            value(btrfs, "incompatible features"),
+            "mixed_backref big_metadata extended_iref skinny_metadata no_holes"
+        );
+    }
+
+    #[test]
+    fn luks2_metadata_is_decoded() {
+        let mut data = vec![0u8; 0x10000];
+        data[0..6].copy_from_slice(&LUKS_MAGIC);
+        data[6..8].copy_from_slice(&2u16.to_be_bytes());
+        data[8..16].copy_from_slice(&16384u64.to_be_bytes());
+        data[168..172].copy_from_slice(b"abcd");
+        let json = br#"{"keyslots":{"1":{"kdf":{"type":"argon2id"}},"0":{"kdf":{"type":"argon2id"}}},
+            "segments":{"0":{"offset":"16777216","encryption":"aes-xts-plain64","sector_size":4096}},
+            "tokens":{}}"#;
+        data[4096..4096 + json.len()].copy_from_slice(json);
+
+        let structures = inspect(&[range(0, data)]);
+        let luks = &structures[0];
+        assert_eq!(luks.structure, SectorStructure::Luks);
+        assert_eq!(value(luks, "version"), "2");
+        assert_eq!(value(luks, "UUID"), "abcd");
+        assert_eq!(value(luks, "encryption"), "aes-xts-plain64");
+        assert_eq!(value(luks, "active key slots"), "0, 1");
+        assert_eq!(value(luks, "key derivation"), "argon2id");
+        assert_eq!(value(luks, "tokens"), "0");
+    }
+
+    #[test]
+    fn xfs_superblock_is_decoded() {
+        let mut data = vec![0u8; 0x1000];
+        data[0..4].copy_from_slice(b"XFSB");
+        data[4..8].copy_from_slice(&4096u32.to_be_bytes());
+        data[8..16].copy_from_slice(&1024u64.to_be_bytes());
+        data[100..102].copy_from_slice(&0xb4a5u16.to_be_bytes());
+        data[216..220].copy_from_slice(&0x9u32.to_be_bytes());
+
+        let structures = inspect(&[range(0, data)]);
+        let xfs = &structures[0];
+        assert_eq!(value(xfs, "version"), "5");
+        assert_eq!(value(xfs, "size"), "4.00 MiB");
+        assert_eq!(value(xfs, "incompatible features"), "ftype bigtime");
+        assert_eq!(flag_names(0x100, &XFS_INCOMPAT), "0x100");
+    }
+}
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Filesystem compatibility with other operating systems
+//!
+//! Which systems can read and write a filesystem without extra drivers, and
+//! the size limits that come with it, so that formatting can explain a
+//! choice and suggest one for drives that move between computers.
+
+/// Largest file FAT32 can store: 4 GiB minus one byte
+pub const FAT32_MAX_FILE_SIZE: u64 = 4 * 1024 * 1024 * 1024 - 1;
+
+/// Largest FAT32 volume with 512-byte sectors, which is what other systems
+/// expect
+pub const FAT32_MAX_VOLUME_SIZE: u64 = 2 * 1024 * 1024 * 1024 * 1024;
+
+/// Filesystem suggested for removable drives shared with Windows and macOS
+pub const CROSS_PLATFORM_FILESYSTEM: &str = "exfat";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatingSystem {
+    Windows,
+    MacOs,
+    Linux,
+}
+
+impl OperatingSystem {
+    pub const ALL: [Self; 3] = [Self::Windows, Self::MacOs, Self::Linux];
+}
+
+/// What an operating system can do with a filesystem out of the box
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    ReadWrite,
+    ReadOnly,
+    None,
+}
+
+/// Built-in access of `os` to `fs_type`, or `None` for an unknown
+/// filesystem or one that holds no files (swap)
+pub fn access(fs_type: &str, os: OperatingSystem) -> Option<Access> {
+    use Access::*;
+    use OperatingSystem::*;
+
+    let access = match (fs_type, os) {
+        ("vfat" | "exfat", _) => ReadWrite,
+        ("ntfs", MacOs) => ReadOnly,
+        ("ntfs", _) => ReadWrite,
+        ("udf", MacOs) => ReadOnly,
+        ("udf", _) => ReadWrite,
+        ("ext4" | "ext3" | "xfs" | "btrfs" | "f2fs", Linux) => ReadWrite,
+        ("ext4" | "ext3" | "xfs" | "btrfs" | "f2fs", _) => None,
+        _ => return Option::None,
+    };
+    Some(access)
+}
+
+/// Whether every operating system can read and write `fs_type`
+pub fn is_cross_platform(fs_type: &str) -> bool {
+    OperatingSystem::ALL
+        .iter()
+        .all(|&os| access(fs_type, os) == Some(Access::ReadWrite))
+}
+
+/// Filesystem to suggest instead of `fs_type`, when the drive is removable
+/// and `fs_type` would lock out other systems
+pub fn suggested_filesystem(fs_type: &str, removable: bool) -> Option<&'static str> {
+    (removable && !is_cross_platform(fs_type)).then_some(CROSS_PLATFORM_FILESYSTEM)
+}
+
+/// Largest file `fs_type` can store, when that is small enough to matter
+pub fn max_file_size(fs_type: &str) -> Option<u64> {
+    match fs_type {
+        "vfat" => Some(FAT32_MAX_FILE_SIZE),
+        _ => None,
+    }
+}
+
+/// Largest volume `fs_type` can be formatted on, when other systems limit it
+pub fn max_volume_size(fs_type: &str) -> Option<u64> {
+    match fs_type {
+        "vfat" => Some(FAT32_MAX_VOLUME_SIZE),
+        _ => None,
+    }
+}
+
+/// Check that a volume of `size` bytes can be formatted as `fs_type`
+pub fn validate_volume_size(fs_type: &str, size: u64) -> Result<(), String> {
+    match max_volume_size(fs_type) {
+        Some(max) if size > max => Err(format!(
+            "{} volumes cannot be larger than {}",
+            fs_type,
+            crate::bytes_to_pretty(&max, false)
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_fat_filesystems_are_cross_platform() {
+        assert!(is_cross_platform("exfat"));
+        assert!(is_cross_platform("vfat"));
+        assert!(!is_cross_platform("ntfs"));
+        assert!(!is_cross_platform("ext4"));
+        assert_eq!(
+            access("ntfs", OperatingSystem::MacOs),
+            Some(Access::ReadOnly)
+        );
+        assert_eq!(access("swap", OperatingSystem::Linux), None);
+    }
+
+    #[test]
+    fn removable_drives_get_exfat_suggested() {
+        assert_eq!(suggested_filesystem("ext4", true), Some("exfat"));
+        assert_eq!(suggested_filesystem("ntfs", true), Some("exfat"));
+        assert_eq!(suggested_filesystem("vfat", true), None);
+        assert_eq!(suggested_filesystem("ext4", false), None);
+    }
+
+    #[test]
+    fn fat32_volume_size_is_limited() {
+        assert_eq!(validate_volume_size("vfat", FAT32_MAX_VOLUME_SIZE), Ok(()));
+        assert!(validate_volume_size("vfat", FAT32_MAX_VOLUME_SIZE + 1).is_err());
+        assert_eq!(validate_volume_size("exfat", u64::MAX), Ok(()));
+        assert_eq!(max_file_size("vfat"), Some(FAT32_MAX_FILE_SIZE));
+    }
+}
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Block-layer queue tuning of drives
+//!
+//! The I/O scheduler, readahead and request queue depth of a drive live
+//! under `/sys/block/<name>/queue` and reset whenever the drive appears. To
+//! keep them, a udev rule matching the drive by its WWN or serial sets them
+//! again on every boot and hotplug.
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the udev rules files written for tuned drives
+pub const QUEUE_RULE_PREFIX: &str = "60-cosmic-ext-storage-queue-";
+
+/// Queue settings of a drive as the kernel reports them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueSettings {
+    /// Active I/O scheduler, e.g. "bfq"
+    pub scheduler: String,
+    /// Schedulers the kernel offers for the drive
+    pub available_schedulers: Vec<String>,
+    pub read_ahead_kb: u64,
+    pub nr_requests: u64,
+    /// Whether a udev rule applies the settings when the drive appears
+    pub persistent: bool,
+}
+
+impl QueueSettings {
+    /// The settings as a tuning to apply
+    pub fn tuning(&self) -> QueueTuning {
+        QueueTuning {
+            scheduler: self.scheduler.clone(),
+            read_ahead_kb: self.read_ahead_kb,
+            nr_requests: self.nr_requests,
+        }
+    }
+}
+
+/// Queue settings to apply to a drive
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueTuning {
+    pub scheduler: String,
+    pub read_ahead_kb: u64,
+    pub nr_requests: u64,
+}
+
+impl QueueTuning {
+    /// udev rule applying the tuning to the drive whose udev property `key`
+    /// (e.g. "ID_WWN") is `value`
+    ///
+    /// The scheduler comes first: switching it resets `nr_requests`.
+    pub fn udev_rule(&self, key: &str, value: &str) -> String {
+        format!(
+            "# Written by COSMIC Storage; removed when the drive is no longer tuned\n\
+             ACTION==\"add|change\", SUBSYSTEM==\"block\", ENV{{DEVTYPE}}==\"disk\", \
+             ENV{{{key}}}==\"{value}\", ATTR{{queue/scheduler}}=\"{}\", \
+             ATTR{{queue/read_ahead_kb}}=\"{}\", ATTR{{queue/nr_requests}}=\"{}\"\n",
+            self.scheduler, self.read_ahead_kb, self.nr_requests
+        )
+    }
+}
+
+/// Active scheduler and all offered ones from `queue/scheduler`, e.g.
+/// "mq-deadline kyber [bfq] none"
+pub fn parse_scheduler(contents: &str) -> (String, Vec<String>) {
+    let mut active = String::new();
+    let available = contents
+        .split_whitespace()
+        .map(
+            |name| match name.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+                Some(name) => {
+                    active = name.to_string();
+                    active.clone()
+                }
+                None => name.to_string(),
+            },
+        )
+        .collect();
+    (active, available)
+}
+
+/// Name of the udev rules file of the drive identified by `value`
+pub fn queue_rule_file_name(value: &str) -> String {
+    format!("{QUEUE_RULE_PREFIX}{}.rules", rule_file_id(value))
+}
+
+/// `value` with everything but a safe set of characters replaced, to name
+/// files after it
+pub(crate) fn rule_file_id(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_scheduler_is_bracketed() {
+        let (active, available) = parse_scheduler("mq-deadline kyber [bfq] none\n");
+        assert_eq!(active, "bfq");
+        assert_eq!(available, ["mq-deadline", "kyber", "bfq", "none"]);
+
+        let (active, available) = parse_scheduler("[none]");
+        assert_eq!(active, "none");
+        assert_eq!(available, ["none"]);
+    }
+
+    #[test]
+    fn rule_sets_the_scheduler_before_the_queue_depth() {
+        let tuning = QueueTuning {
+            scheduler: "bfq".to_string(),
+            read_ahead_kb: 512,
+            nr_requests: 64,
+        };
+        let rule = tuning.udev_rule("ID_WWN", "0x5002538e40a1b2c3");
+        assert!(rule.contains("ENV{ID_WWN}==\"0x5002538e40a1b2c3\""));
+        assert!(rule.contains("ATTR{queue/read_ahead_kb}=\"512\""));
+        let scheduler = rule.find("ATTR{queue/scheduler}=\"bfq\"").unwrap();
+        let depth = rule.find("ATTR{queue/nr_requests}=\"64\"").unwrap();
+        assert!(scheduler < depth);
+    }
+
+    #[test]
+    fn rule_file_names_stay_in_the_rules_directory() {
+        assert_eq!(
+            queue_rule_file_name("Samsung_SSD 870/../x"),
+            "60-cosmic-ext-storage-queue-Samsung_SSD_870_.._x.rules"
+        );
+    }
+}
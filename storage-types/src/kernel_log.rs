@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Device errors in the kernel log
+//!
+//! Drives often fail in ways SMART does not record: a bad cable or port
+//! shows up as ATA exceptions, a hanging NVMe drive as command timeouts,
+//! and bad blocks on drives without SMART (USB sticks, SD cards) only as
+//! I/O errors. These messages are picked out of the kernel log here; they
+//! name a block device, an ATA port or an NVMe controller, which is then
+//! resolved to its drive.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KernelErrorKind {
+    /// A failed read or write request
+    Io,
+    /// An ATA command error or link reset
+    Ata,
+    /// An NVMe command timeout or controller reset
+    Nvme,
+}
+
+/// What a kernel error message names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KernelErrorSource {
+    /// A disk or partition, e.g. "sda" or "sda1"
+    Block(String),
+    /// An ATA port, e.g. 1 for "ata1"
+    AtaPort(u32),
+    /// An NVMe controller, e.g. "nvme0"
+    NvmeController(String),
+}
+
+/// A device error reported by the kernel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelDeviceError {
+    /// Unix time of the message, in seconds
+    pub timestamp: u64,
+    pub kind: KernelErrorKind,
+    pub message: String,
+}
+
+/// Microseconds since boot and message of a `/dev/kmsg` record such as
+/// "3,1234,5678901,-;blk_update_request: I/O error, dev sda, ..."
+///
+/// Continuation lines of a record (" SUBSYSTEM=block") give `None`.
+pub fn parse_kmsg_record(record: &str) -> Option<(u64, &str)> {
+    let (header, message) = record.split_once(';')?;
+    let timestamp = header.split(',').nth(2)?.parse().ok()?;
+    Some((timestamp, message.trim_end()))
+}
+
+/// The device a kernel log message reports an error for, or `None` for
+/// any other message
+///
+/// Each error is counted once: an ATA error is reported as several lines,
+/// of which only the "exception" line is taken.
+pub fn classify_kernel_error(message: &str) -> Option<(KernelErrorSource, KernelErrorKind)> {
+    // "blk_update_request: I/O error, dev sda, sector 2048 op 0x0:(READ) ...",
+    // "critical medium error, dev sdb, sector ...",
+    // "Buffer I/O error on dev sdc1, logical block 0, async page read"
+    for marker in ["error, dev ", "error on dev "] {
+        if let Some((_, rest)) = message.split_once(marker) {
+            let name = rest.split([',', ' ']).next().unwrap_or_default();
+            // Loop devices only pass on errors of their backing file
+            if name.is_empty() || name.starts_with("loop") {
+                return None;
+            }
+            return Some((
+                KernelErrorSource::Block(name.to_string()),
+                KernelErrorKind::Io,
+            ));
+        }
+    }
+
+    // "ata1.00: exception Emask 0x0 SAct 0x0 SErr 0x0 action 0x6 frozen",
+    // "ata3: COMRESET failed (errno=-16)"
+    if let Some(rest) = message.strip_prefix("ata")
+        && (message.contains(": exception Emask") || message.contains(": COMRESET failed"))
+    {
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        return Some((
+            KernelErrorSource::AtaPort(digits.parse().ok()?),
+            KernelErrorKind::Ata,
+        ));
+    }
+
+    // "nvme nvme0: I/O 123 QID 4 timeout, aborting",
+    // "nvme nvme1: controller is down; will reset: CSTS=0xffffffff"
+    if let Some(rest) = message.strip_prefix("nvme ")
+        && let Some((controller, detail)) = rest.split_once(": ")
+        && (detail.contains(" timeout") || detail.starts_with("controller is down"))
+    {
+        return Some((
+            KernelErrorSource::NvmeController(controller.to_string()),
+            KernelErrorKind::Nvme,
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_device_errors_in_the_kernel_log() {
+        let (timestamp, message) = parse_kmsg_record(
+            "3,1234,5678901,-;blk_update_request: I/O error, dev sda, sector 2048 op 0x0:(READ) flags 0x0",
+        )
+        .unwrap();
+        assert_eq!(timestamp, 5678901);
+        assert_eq!(
+            classify_kernel_error(message),
+            Some((KernelErrorSource::Block("sda".into()), KernelErrorKind::Io))
+        );
+        assert_eq!(parse_kmsg_record(" SUBSYSTEM=block"), None);
+
+        assert_eq!(
+            classify_kernel_error(
+                "Buffer I/O error on dev mmcblk0p1, logical block 0, async page read"
+            ),
+            Some((
+                KernelErrorSource::Block("mmcblk0p1".into()),
+                KernelErrorKind::Io
+            ))
+        );
+        assert_eq!(
+            classify_kernel_error("I/O error, dev loop3, sector 0 op 0x0:(READ)"),
+            None
+        );
+        assert_eq!(
+            classify_kernel_error("ata12.00: exception Emask 0x0 SAct 0x40 SErr 0x0 action 0x0"),
+            Some((KernelErrorSource::AtaPort(12), KernelErrorKind::Ata))
+        );
+        assert_eq!(
+            classify_kernel_error("ata12.00: failed command: READ FPDMA QUEUED"),
+            None
+        );
+        assert_eq!(
+            classify_kernel_error("nvme nvme0: I/O 123 QID 4 timeout, aborting"),
+            Some((
+                KernelErrorSource::NvmeController("nvme0".into()),
+                KernelErrorKind::Nvme
+            ))
+        );
+        assert_eq!(
+            classify_kernel_error("EXT4-fs (sda1): mounted filesystem with ordered data mode"),
+            None
+        );
+    }
+}
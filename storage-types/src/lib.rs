@@ -23,49 +23,190 @@
 //!
 //! This eliminates circular conversions and ensures data consistency across all components.
 
+pub mod alignment;
+pub mod automount;
+pub mod bind_mount;
 pub mod btrfs;
+pub mod byte_format;
 pub mod caller;
+pub mod capacity;
 pub mod common;
+pub mod comparison;
+pub mod confirmation;
+pub mod diagnostics;
 pub mod disk;
+pub mod enclosure;
 pub mod encryption;
+pub mod esp;
 pub mod filesystem;
+pub mod format_schema;
+pub mod fscrypt;
+pub mod gpt;
+pub mod hardware_raid;
+pub mod health;
+pub mod health_check;
+pub mod inspect;
+pub mod interop;
+pub mod io_tuning;
+pub mod kernel_log;
+pub mod live_usb;
+pub mod log;
+pub mod lost_partition;
+pub mod low_space;
 pub mod lvm;
+pub mod metrics;
+pub mod migration;
+pub mod mount_failure;
+pub mod mount_schema;
+pub mod mtp;
+pub mod optical;
 pub mod partition;
+pub mod partition_copy;
+pub mod partition_move;
 pub mod partition_types;
+pub mod placement;
+pub mod power;
+pub mod priority;
+pub mod probe;
+pub mod raid;
 pub mod rclone;
+pub mod read_only;
+pub mod rescue;
+pub mod resize;
+pub mod restrictions;
+pub mod sectors;
+pub mod selinux;
 pub mod smart;
+pub mod statistics;
+pub mod temperature;
+pub mod throughput;
 pub mod usage_scan;
+pub mod user_mount;
+pub mod vault;
+pub mod virtualization;
 pub mod volume;
+pub mod windows_hibernation;
+pub mod write_cache;
 
-pub use btrfs::{BtrfsSubvolume, DeletedSubvolume, FilesystemUsage, SubvolumeList};
+pub use alignment::{SectorFormat, realigned_start};
+pub use automount::{AUTOMOUNT_MARKER, AutomountUnit, IDLE_TIMEOUT_PRESETS, escape_unit_path};
+pub use bind_mount::{BindMount, fstab_bind_line, parse_bind_mounts, remove_fstab_bind};
+pub use btrfs::{
+    BTRFS_COMPRESSION_ALGORITHMS, BtrfsDeviceStats, BtrfsSubvolume, CompressionEstimate,
+    CompressionInfo, DeletedSubvolume, FilesystemUsage, NocowStatus, RestoreConflictPolicy,
+    RestoreResult, RollbackResult, SafetySnapshotPolicy, SnapshotChangeKind, SnapshotDiff,
+    SnapshotDiffEntry, SubvolumeList, btrfs_mount_points,
+};
+pub use byte_format::{ByteFormat, ByteUnits, byte_format, set_byte_format};
 pub use caller::CallerInfo;
+pub use capacity::{CapacityForecast, CapacitySample};
 pub use common::{
     ByteRange, GPT_ALIGNMENT_BYTES, Usage, bytes_to_pretty, get_numeric, get_step, pretty_to_bytes,
 };
+pub use comparison::{DriveComparison, InterfaceSpeed, mounted_used_bytes};
+pub use confirmation::{
+    CONFIRMATION_COUNTDOWN_SECS, CONFIRMATION_TOKEN_LENGTH, ConfirmationLevel,
+    ConfirmationRequirements, confirmation_name_matches, confirmation_token,
+    confirmation_token_matches,
+};
+pub use diagnostics::{DiagnosticBundle, DiagnosticFile, layout_secrets, redact};
 pub use disk::{DiskEvent, DiskInfo, SmartAttribute, SmartStatus};
+pub use enclosure::{IdentifyMethod, IdentifySupport};
 pub use encryption::{EncryptionOptionsSettings, LuksInfo, LuksVersion};
+pub use esp::{EspSyncPair, EspSyncResult, is_esp};
 pub use filesystem::{
-    CheckResult, FilesystemInfo, FilesystemToolInfo, FilesystemType, FormatOptions, KillResult,
-    MountOptions, MountOptionsSettings, ProcessInfo, UnmountResult,
+    CheckResult, DefragResult, FilesystemFeatures, FilesystemInfo, FilesystemToolInfo,
+    FilesystemType, FormatOptions, FormatPreset, FragmentationReport, KillResult,
+    MountNamingScheme, MountOptions, MountOptionsSettings, MountPathPolicy, ProcessInfo,
+    UnmountResult,
+};
+pub use format_schema::{FormatOptionKind, FormatOptionSpec, format_option_schema};
+pub use fscrypt::{EncryptedDirectory, FscryptStatus, fscrypt_capable, parse_directory_status};
+pub use gpt::{DeletedPartition, GptEdit, GptEntry, GptEntryEdit, GptTable};
+pub use hardware_raid::PhysicalDevice;
+pub use health::{
+    DiskHealthSummary, HealthFactor, HealthLevel, SmartSample, SmartTrend, TrendAttribute,
+};
+pub use health_check::{HealthCheckItem, HealthCheckKind, HealthCheckReport};
+pub use io_tuning::{QueueSettings, QueueTuning};
+pub use kernel_log::{
+    KernelDeviceError, KernelErrorKind, KernelErrorSource, classify_kernel_error, parse_kmsg_record,
 };
+pub use live_usb::{LiveIsoInfo, LiveUsbDistro, LiveUsbPlan};
+pub use log::{LogEntry, LogFilter, LogLevel, operation_excerpt};
+pub use lost_partition::{
+    LostPartition, LostPartitionConfidence, PartitionLayout, next_candidate_offset,
+};
+pub use low_space::{LowSpaceAction, LowSpaceRule, LowSpaceWarning};
 pub use lvm::{LogicalVolumeInfo, PhysicalVolumeInfo, VolumeGroupInfo};
+pub use metrics::{MetricFamily, MetricKind, MetricSample, MetricsConfig, encode_openmetrics};
+pub use migration::{MigratedPartition, MigrationPlan};
+pub use mount_failure::MountFailure;
+pub use mount_schema::{
+    MountLint, MountLintContext, MountLintSeverity, MountOption, MountOptionKind,
+    MountOptionPreset, MountOptionSpec, join_mount_options, lint_mount_options,
+    mount_option_presets, mount_option_spec, parse_mount_options,
+};
+pub use mtp::{
+    MtpDevice, MtpProtocol, MtpStorage, parse_gio_filesystem_info, parse_gio_mount_list,
+};
+pub use optical::{
+    OPTICAL_SECTOR_SIZE, OpticalMediaInfo, OpticalMediaStatus, parse_xorriso_media_info,
+    parse_xorriso_progress,
+};
 pub use partition::{
     CreatePartitionInfo, PartitionInfo, PartitionTableInfo, PartitionTableType,
     make_partition_flags_bits,
 };
+pub use partition_move::{MoveBounds, move_pieces};
 pub use partition_types::{
     COMMON_DOS_TYPES, COMMON_GPT_TYPES, PARTITION_TYPES, PartitionTypeInfo, PartitionTypeInfoFlags,
     get_all_partition_type_infos, get_valid_partition_names,
 };
+pub use placement::{place_partition, usable_range};
+pub use power::{
+    DeferralReason, DeferredJob, PowerPolicy, PowerSettings, PowerState, ScheduledJob,
+};
+pub use priority::{IoClass, OperationKind, OperationPriority, PrioritySettings};
+pub use probe::{FilesystemSignature, SIGNATURE_PROBE_SIZE, probe_filesystem_signature};
+pub use raid::{
+    GroupSpare, RaidDetail, RaidHealthEvent, RaidMember, RaidMemberCapabilities, RaidMemberState,
+    RaidReshape, SpareGroup, SparePoolConfig, level_migration_supported, spare_groups,
+};
 pub use rclone::{
-    ConfigScope, MountStatus, MountStatusResult, MountType, NetworkMount, RcloneProvider,
-    RcloneProviderOption, RcloneProviderOptionExample, RemoteConfig, RemoteConfigList, TestResult,
-    rclone_provider, rclone_providers, supported_remote_types,
+    ConfigEncryptionStatus, ConfigScope, ENCRYPTED_CONFIG_MARKER, LoginMountState,
+    LoginMountStatus, MOUNT_POINT_OPTION, MountStatus, MountStatusResult, MountType, NetworkMount,
+    RcloneProvider, RcloneProviderOption, RcloneProviderOptionExample, RemoteConfig,
+    RemoteConfigList, RemoteUsage, TestResult, TransferEndpoint, TransferJob, TransferMode,
+    TransferProgress, TransferRequest, TransferState, is_encrypted_config,
+    is_valid_bandwidth_limit, rclone_provider, rclone_providers, supported_remote_types,
+    validate_mount_point,
+};
+pub use read_only::{ForcedReadOnly, find_forced_read_only, parse_read_only_mounts};
+pub use rescue::{
+    RescueBlock, RescueMap, RescueOptions, RescuePhase, RescueProgress, RescueStatus,
+};
+pub use resize::{ResizeBlocker, ResizeBounds, ResizeStep, resize_bounds};
+pub use restrictions::{AllowedActions, RestrictionPolicy, RestrictionProfile};
+pub use sectors::{SectorAnnotation, SectorRange, SectorStructure};
+pub use selinux::{SelinuxStatus, context_type, labeled_mounts, mount_context_option};
+pub use smart::{
+    SelfTestRecord, SelfTestSchedule, SmartBackendKind, SmartBackendStatus, SmartInfo,
+    SmartSelfTestKind,
+};
+pub use statistics::{JournalEvent, OperationRecord, UsageStatistics};
+pub use temperature::{
+    TemperatureConfig, TemperatureLevel, TemperatureThresholds, TemperatureUnit,
 };
-pub use smart::{SmartInfo, SmartSelfTestKind};
+pub use throughput::{EtaRange, THROUGHPUT_SEGMENTS, ThroughputHistory, ThroughputRun};
 pub use usage_scan::{
     UsageCategory, UsageCategoryTopFiles, UsageCategoryTotal, UsageDeleteFailure,
     UsageDeleteResult, UsageScanParallelismPreset, UsageScanRequest, UsageScanResult,
     UsageTopFileEntry,
 };
+pub use user_mount::{UserMount, UserMountKind, UserMountTable, parse_user_mounts};
+pub use vault::{LegacyVault, VaultKind, VaultMigrationResult, parse_ecryptfs_sigs, vault_mounts};
+pub use virtualization::{TrimResult, hypervisor_name, is_virtual_disk};
 pub use volume::{VolumeInfo, VolumeKind, VolumeType};
+pub use windows_hibernation::WindowsHibernation;
+pub use write_cache::WriteCacheStatus;
@@ -0,0 +1,273 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Bootable USB drives made from distribution ISO images
+//!
+//! The hybrid ISO is written to the start of the drive as usual, then the
+//! free space after it gets a persistence partition, for distributions
+//! whose live system can keep changes on one, and an exFAT partition for
+//! data that any system can read.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ByteRange, GPT_ALIGNMENT_BYTES};
+
+/// Smallest persistence partition worth making
+pub const MIN_PERSISTENCE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Smallest data partition worth making
+pub const MIN_DATA_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Label of the exFAT data partition
+pub const DATA_LABEL: &str = "LIVEDATA";
+
+/// Sectors kept free at the end of the drive for a relocated backup GPT
+const GPT_BACKUP_SECTORS: u64 = 34;
+
+/// How a live system finds its persistence partition
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiveUsbDistro {
+    /// Ubuntu and its flavours
+    Casper,
+    /// Debian and distributions built with live-build
+    DebianLive,
+    /// Live systems without a known way to persist changes
+    #[default]
+    Unsupported,
+}
+
+impl LiveUsbDistro {
+    /// Filesystem label the live system looks for
+    pub fn persistence_label(self) -> Option<&'static str> {
+        match self {
+            Self::Casper => Some("writable"),
+            Self::DebianLive => Some("persistence"),
+            Self::Unsupported => None,
+        }
+    }
+
+    /// Boot parameter that turns persistence on, added at the boot menu
+    pub fn boot_parameter(self) -> Option<&'static str> {
+        match self {
+            Self::Casper => Some("persistent"),
+            Self::DebianLive => Some("persistence"),
+            Self::Unsupported => None,
+        }
+    }
+
+    /// Contents of the `persistence.conf` the live system needs at the root
+    /// of the partition, when it needs one
+    pub fn persistence_conf(self) -> Option<&'static str> {
+        match self {
+            Self::DebianLive => Some("/ union\n"),
+            Self::Casper | Self::Unsupported => None,
+        }
+    }
+}
+
+/// What the start of an ISO image says about it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveIsoInfo {
+    /// Image size in bytes
+    pub size: u64,
+    pub distro: LiveUsbDistro,
+}
+
+/// Whether an image whose first sector is `first_sector` carries a
+/// partition table, so that it boots from a USB drive and can have
+/// partitions added after it
+pub fn is_hybrid_image(first_sector: &[u8]) -> bool {
+    first_sector.get(510..512) == Some(&[0x55, 0xaa])
+}
+
+/// Extra partitions of a live USB drive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveUsbPlan {
+    /// Size of the persistence partition; 0 for none
+    pub persistence_bytes: u64,
+    /// Fill the rest of the drive with an exFAT data partition
+    pub data_partition: bool,
+}
+
+/// Where the extra partitions of a live USB drive go, in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiveUsbLayout {
+    pub persistence: Option<ByteRange>,
+    pub data: Option<ByteRange>,
+}
+
+impl LiveUsbLayout {
+    /// Partitions to add, in disk order
+    pub fn partitions(&self) -> impl Iterator<Item = ByteRange> {
+        self.persistence.into_iter().chain(self.data)
+    }
+
+    /// Input for `sfdisk --append` creating the partitions in a table
+    /// labelled `label` ("dos" or "gpt")
+    pub fn sfdisk_script(&self, label: &str, sector_size: u64) -> String {
+        let (linux, data) = if label == "gpt" {
+            (
+                "0FC63DAF-8483-4772-8E79-3D69D8477DE4",
+                "EBD0A0A2-B9E5-4433-87C0-68B6B72699C7",
+            )
+        } else {
+            ("83", "7")
+        };
+        let line = |range: &ByteRange, type_id: &str| {
+            format!(
+                "start={}, size={}, type={}\n",
+                range.start / sector_size,
+                range.size() / sector_size,
+                type_id
+            )
+        };
+        self.persistence
+            .iter()
+            .map(|range| line(range, linux))
+            .chain(self.data.iter().map(|range| line(range, data)))
+            .collect()
+    }
+}
+
+impl LiveUsbPlan {
+    /// Lay the extra partitions out after `iso` on a drive of `device_size`
+    /// bytes; the persistence partition comes first, the data partition
+    /// takes what is left
+    pub fn layout(
+        &self,
+        iso: &LiveIsoInfo,
+        device_size: u64,
+        sector_size: u64,
+    ) -> Result<LiveUsbLayout, String> {
+        if self.persistence_bytes == 0 && !self.data_partition {
+            return Err("Choose a persistence or a data partition to add".to_string());
+        }
+        if self.persistence_bytes > 0 && iso.distro.persistence_label().is_none() {
+            return Err("This image does not support a persistence partition".to_string());
+        }
+        if self.persistence_bytes > 0 && self.persistence_bytes < MIN_PERSISTENCE_BYTES {
+            return Err(format!(
+                "The persistence partition needs at least {}",
+                crate::bytes_to_pretty(&MIN_PERSISTENCE_BYTES, false)
+            ));
+        }
+
+        let start = iso.size.next_multiple_of(GPT_ALIGNMENT_BYTES);
+        let usable_end = device_size.saturating_sub(GPT_BACKUP_SECTORS * sector_size.max(512));
+        let end = usable_end - usable_end % GPT_ALIGNMENT_BYTES;
+        let too_small = || {
+            format!(
+                "The drive is too small: the image takes {} of its {}",
+                crate::bytes_to_pretty(&iso.size, false),
+                crate::bytes_to_pretty(&device_size, false)
+            )
+        };
+
+        let mut cursor = start;
+        let persistence = if self.persistence_bytes > 0 {
+            let size = self.persistence_bytes - self.persistence_bytes % GPT_ALIGNMENT_BYTES;
+            if cursor + size > end {
+                return Err(too_small());
+            }
+            cursor += size;
+            Some(ByteRange {
+                start: cursor - size,
+                end: cursor,
+            })
+        } else {
+            None
+        };
+        let data = if self.data_partition {
+            if end < cursor + MIN_DATA_BYTES {
+                return Err(too_small());
+            }
+            Some(ByteRange { start: cursor, end })
+        } else {
+            None
+        };
+        Ok(LiveUsbLayout { persistence, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+    const GIB: u64 = 1024 * MIB;
+
+    fn ubuntu() -> LiveIsoInfo {
+        LiveIsoInfo {
+            size: 5 * GIB + 300,
+            distro: LiveUsbDistro::Casper,
+        }
+    }
+
+    #[test]
+    fn partitions_follow_the_image() {
+        let plan = LiveUsbPlan {
+            persistence_bytes: 4 * GIB,
+            data_partition: true,
+        };
+        let layout = plan.layout(&ubuntu(), 32 * GIB, 512).unwrap();
+        let persistence = layout.persistence.unwrap();
+        let data = layout.data.unwrap();
+        assert_eq!(persistence.start, 5 * GIB + MIB);
+        assert_eq!(persistence.size(), 4 * GIB);
+        assert_eq!(data.start, persistence.end);
+        assert!(data.end <= 32 * GIB - 34 * 512);
+        assert_eq!(data.end % MIB, 0);
+        assert_eq!(layout.partitions().count(), 2);
+
+        assert_eq!(
+            layout.sfdisk_script("dos", 512),
+            format!(
+                "start={}, size={}, type=83\nstart={}, size={}, type=7\n",
+                persistence.start / 512,
+                persistence.size() / 512,
+                data.start / 512,
+                data.size() / 512
+            )
+        );
+        assert!(
+            layout
+                .sfdisk_script("gpt", 512)
+                .contains("type=EBD0A0A2-B9E5-4433-87C0-68B6B72699C7")
+        );
+    }
+
+    #[test]
+    fn plans_that_cannot_work_are_refused() {
+        let persistence = LiveUsbPlan {
+            persistence_bytes: 4 * GIB,
+            data_partition: false,
+        };
+        let fedora = LiveIsoInfo {
+            distro: LiveUsbDistro::Unsupported,
+            ..ubuntu()
+        };
+        assert!(persistence.layout(&fedora, 32 * GIB, 512).is_err());
+        assert!(persistence.layout(&ubuntu(), 8 * GIB, 512).is_err());
+        assert!(
+            LiveUsbPlan::default()
+                .layout(&ubuntu(), 32 * GIB, 512)
+                .is_err()
+        );
+
+        let data_only = LiveUsbPlan {
+            persistence_bytes: 0,
+            data_partition: true,
+        };
+        assert!(data_only.layout(&fedora, 32 * GIB, 512).is_ok());
+        assert!(data_only.layout(&fedora, 5 * GIB + 10 * MIB, 512).is_err());
+    }
+
+    #[test]
+    fn hybrid_images_have_a_boot_signature() {
+        let mut sector = [0u8; 512];
+        assert!(!is_hybrid_image(&sector));
+        sector[510] = 0x55;
+        sector[511] = 0xaa;
+        assert!(is_hybrid_image(&sector));
+        assert!(!is_hybrid_image(&sector[..100]));
+    }
+}
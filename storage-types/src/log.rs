@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Log lines of the app and the service
+//!
+//! Both write log lines in the `tracing_subscriber` text format:
+//!
+//! ```text
+//! 2026-10-16T09:01:02.123456Z  INFO cosmic_ext_storage::update: Mounted operation="mount"
+//! ```
+//!
+//! The app writes them to files; the service writes them to the journal,
+//! which prefixes each with its own timestamp and the unit. The log viewer
+//! parses both into [`LogEntry`] and filters them with [`LogFilter`].
+
+use serde::{Deserialize, Serialize};
+
+/// Severity of a log line, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn parse(level: &str) -> Option<Self> {
+        match level {
+            "ERROR" => Some(Self::Error),
+            "WARN" => Some(Self::Warn),
+            "INFO" => Some(Self::Info),
+            "DEBUG" => Some(Self::Debug),
+            "TRACE" => Some(Self::Trace),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        }
+    }
+}
+
+/// A parsed log line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Timestamp as written, e.g. "2026-10-16T09:01:02.123456Z"
+    pub timestamp: String,
+    pub level: LogLevel,
+    /// Module the line was logged from, e.g. "cosmic_ext_storage::update"
+    pub target: String,
+    /// Message and fields, with the spans it was logged in first
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Parse a line written by `tracing_subscriber`; color codes are
+    /// removed
+    ///
+    /// Lines that are not log lines, such as the continuation of a
+    /// multi-line message, give `None`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = strip_ansi(line);
+        let mut parts = line.trim().splitn(2, char::is_whitespace);
+        let timestamp = parts.next()?.to_string();
+        let rest = parts.next()?.trim_start();
+        let (level, rest) = rest.split_once(char::is_whitespace)?;
+        let level = LogLevel::parse(level)?;
+        if !timestamp.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        // Spans come before the target: "span{field=1}: target: message"
+        let mut spans = Vec::new();
+        let mut rest = rest.trim_start();
+        let target = loop {
+            let (segment, tail) = rest.split_once(": ")?;
+            if is_module_path(segment) {
+                rest = tail;
+                break segment.to_string();
+            }
+            spans.push(segment);
+            rest = tail;
+        };
+
+        let message = if spans.is_empty() {
+            rest.to_string()
+        } else {
+            format!("{}: {}", spans.join(": "), rest)
+        };
+        Some(Self {
+            timestamp,
+            level,
+            target,
+            message,
+        })
+    }
+
+    /// Parse a line of `journalctl --output short-iso`, e.g.
+    /// "2026-10-16T11:01:02+0200 host unit[123]: <log line>"
+    pub fn parse_journal(line: &str) -> Option<Self> {
+        let (_, logged) = line.split_once("]: ")?;
+        Self::parse(logged)
+    }
+
+    /// Whether the line mentions `text` in its module, message or fields
+    pub fn mentions(&self, text: &str) -> bool {
+        self.target.contains(text) || self.message.contains(text)
+    }
+
+    /// Operation whose start this line logs, from an `operation="..."`
+    /// field
+    pub fn operation(&self) -> Option<&str> {
+        let (_, rest) = self.message.split_once("operation=\"")?;
+        rest.split_once('"').map(|(operation, _)| operation)
+    }
+}
+
+/// What the log viewer shows; empty texts match everything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilter {
+    /// Least severe level shown
+    pub level: LogLevel,
+    /// Part of the module path, e.g. "update::volumes"
+    pub module: String,
+    /// Operation name, e.g. "mount"
+    pub operation: String,
+    /// Device, e.g. "/dev/sda1" or "sda"
+    pub device: String,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Info,
+            module: String::new(),
+            operation: String::new(),
+            device: String::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let module = self.module.trim();
+        let operation = self.operation.trim();
+        let device = self.device.trim();
+        entry.level <= self.level
+            && (module.is_empty() || entry.target.contains(module))
+            && (operation.is_empty() || entry.mentions(operation))
+            && (device.is_empty() || entry.mentions(device))
+    }
+}
+
+/// Lines logged while `operation` ran: from each line that starts it (one
+/// with `operation="<operation>"`) up to the line that starts the next
+/// operation
+///
+/// Operations that run at the same time interleave their lines, which
+/// end up in each other's excerpts.
+pub fn operation_excerpt(entries: &[LogEntry], operation: &str) -> Vec<LogEntry> {
+    let mut excerpt = Vec::new();
+    let mut inside = false;
+    for entry in entries {
+        if let Some(started) = entry.operation() {
+            inside = started == operation;
+        }
+        if inside {
+            excerpt.push(entry.clone());
+        }
+    }
+    excerpt
+}
+
+fn is_module_path(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+/// `text` without terminal color codes ("\x1b[32m")
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the sequence up to its final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_app_and_journal_lines() {
+        let entry = LogEntry::parse(
+            "2026-10-16T09:01:02.123456Z  WARN cosmic_ext_storage::update::volumes: \
+             mount failed: busy device=\"/dev/sdb1\"",
+        )
+        .unwrap();
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.target, "cosmic_ext_storage::update::volumes");
+        assert_eq!(entry.message, "mount failed: busy device=\"/dev/sdb1\"");
+
+        let entry = LogEntry::parse_journal(
+            "2026-10-16T11:01:02+0200 host cosmic-ext-storage-service[812]: \
+             \x1b[2m2026-10-16T09:01:02.1Z\x1b[0m \x1b[32m INFO\x1b[0m \
+             op{id=3}: storage_service::handlers::filesystem: Mounting /dev/sdb1",
+        )
+        .unwrap();
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.target, "storage_service::handlers::filesystem");
+        assert_eq!(entry.message, "op{id=3}: Mounting /dev/sdb1");
+
+        assert!(LogEntry::parse("    at src/main.rs:10").is_none());
+    }
+
+    #[test]
+    fn filters_entries() {
+        let entry = LogEntry::parse(
+            "2026-10-16T09:01:02Z DEBUG storage_service::handlers::filesystem: \
+             Authorization granted operation=\"mount\" device=/dev/sdb1",
+        )
+        .unwrap();
+        let mut filter = LogFilter::default();
+        assert!(!filter.matches(&entry));
+        filter.level = LogLevel::Debug;
+        assert!(filter.matches(&entry));
+        filter.module = "filesystem".to_string();
+        filter.operation = "mount".to_string();
+        filter.device = "sdb1".to_string();
+        assert!(filter.matches(&entry));
+        filter.device = "sda".to_string();
+        assert!(!filter.matches(&entry));
+    }
+
+    #[test]
+    fn excerpts_operation() {
+        let lines = [
+            "2026-10-16T09:00:00Z  INFO svc: Authorization granted operation=\"mount\"",
+            "2026-10-16T09:00:01Z ERROR svc: Mount failed",
+            "2026-10-16T09:00:02Z  INFO svc: Authorization granted operation=\"unmount\"",
+            "2026-10-16T09:00:03Z  INFO svc: Unmounted",
+            "2026-10-16T09:00:04Z  INFO svc: Authorization granted operation=\"mount\"",
+        ];
+        let entries: Vec<_> = lines.iter().filter_map(|l| LogEntry::parse(l)).collect();
+        let excerpt = operation_excerpt(&entries, "mount");
+        let messages: Vec<_> = excerpt.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Authorization granted operation=\"mount\"",
+                "Mount failed",
+                "Authorization granted operation=\"mount\""
+            ]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Lost partitions
+//!
+//! A deleted partition entry leaves the filesystem behind it intact. The
+//! free space of a disk is searched for filesystem superblocks and LUKS
+//! headers at the places partitions usually start, and each one found is
+//! offered as a partition to recreate. Nothing is written until the user
+//! picks which ones to restore.
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::{ByteRange, GPT_ALIGNMENT_BYTES};
+use crate::partition_types::{COMMON_DOS_TYPES, COMMON_GPT_TYPES};
+use crate::probe::FilesystemSignature;
+
+/// Start of the first partition on disks partitioned by older tools, at
+/// sector 63
+pub const LEGACY_PARTITION_START: u64 = 63 * 512;
+
+/// Free areas smaller than this are not searched
+pub const MIN_LOST_PARTITION_SIZE: u64 = GPT_ALIGNMENT_BYTES;
+
+/// How likely a found signature is a partition worth restoring
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LostPartitionConfidence {
+    /// The filesystem would extend into a partition in use; probably the
+    /// remains of an older layout
+    Low,
+    /// The filesystem fits, but its start is unaligned or its size unknown
+    Medium,
+    /// An aligned filesystem that fits in the free space
+    High,
+}
+
+/// A partition that can be recreated from a filesystem found in free space
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LostPartition {
+    /// Start on the disk, in bytes
+    pub offset: u64,
+    pub size: u64,
+    pub fs_type: String,
+    pub label: Option<String>,
+    pub confidence: LostPartitionConfidence,
+}
+
+impl LostPartition {
+    /// A partition for `signature` at `offset` in the free area ending at
+    /// `free_end`
+    ///
+    /// Without a recorded size (LUKS), the partition takes the rest of the
+    /// free area.
+    pub fn assess(offset: u64, signature: FilesystemSignature, free_end: u64) -> Self {
+        let size = signature
+            .size
+            .unwrap_or_else(|| free_end.saturating_sub(offset));
+        let confidence = if offset + size > free_end {
+            LostPartitionConfidence::Low
+        } else if signature.size.is_none() || !offset.is_multiple_of(GPT_ALIGNMENT_BYTES) {
+            LostPartitionConfidence::Medium
+        } else {
+            LostPartitionConfidence::High
+        };
+        Self {
+            offset,
+            size,
+            fs_type: signature.fs_type,
+            label: signature.label,
+            confidence,
+        }
+    }
+
+    pub fn range(&self) -> ByteRange {
+        ByteRange {
+            start: self.offset,
+            end: self.offset + self.size,
+        }
+    }
+
+    /// Partition type to recreate the partition with in a `table_type`
+    /// ("gpt" or "dos") table
+    ///
+    /// Types without a partition type of their own (LUKS, ext2) get the
+    /// Linux filesystem type.
+    pub fn partition_type(&self, table_type: &str) -> Option<String> {
+        let types = match table_type {
+            "gpt" => &*COMMON_GPT_TYPES,
+            "dos" => &*COMMON_DOS_TYPES,
+            _ => return None,
+        };
+        types
+            .iter()
+            .find(|info| info.filesystem_type == self.fs_type)
+            .or_else(|| types.iter().find(|info| info.table_subtype == "linux"))
+            .map(|info| info.ty.clone())
+    }
+}
+
+/// Partitions of a disk as `sfdisk --json` lists them, in bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionLayout {
+    /// Table type ("gpt" or "dos"), `None` for a disk without a table
+    pub table_type: Option<String>,
+    pub sector_size: u64,
+    /// Where partitions may be placed
+    pub usable: ByteRange,
+    pub partitions: Vec<ByteRange>,
+}
+
+impl PartitionLayout {
+    /// Layout of a disk of `disk_size` bytes without a partition table
+    pub fn unpartitioned(disk_size: u64) -> Self {
+        Self {
+            table_type: None,
+            sector_size: 512,
+            usable: ByteRange {
+                start: 0,
+                end: disk_size,
+            },
+            partitions: Vec::new(),
+        }
+    }
+
+    /// Read the output of `sfdisk --json` for a disk of `disk_size` bytes
+    pub fn from_sfdisk_json(json: &str, disk_size: u64) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid sfdisk output: {e}"))?;
+        let table = &value["partitiontable"];
+        let table_type = table["label"]
+            .as_str()
+            .ok_or("sfdisk output without a table label")?
+            .to_string();
+        let sector_size = table["sectorsize"].as_u64().unwrap_or(512);
+
+        // GPT tables list their usable area; DOS ones only keep the first
+        // sector for themselves
+        let usable = match (table["firstlba"].as_u64(), table["lastlba"].as_u64()) {
+            (Some(first), Some(last)) => ByteRange {
+                start: first * sector_size,
+                end: (last + 1) * sector_size,
+            },
+            _ => ByteRange {
+                start: sector_size,
+                end: disk_size,
+            },
+        };
+
+        let mut partitions: Vec<ByteRange> = table["partitions"]
+            .as_array()
+            .map(|partitions| {
+                partitions
+                    .iter()
+                    .filter_map(|partition| {
+                        let start = partition["start"].as_u64()? * sector_size;
+                        let size = partition["size"].as_u64()? * sector_size;
+                        Some(ByteRange {
+                            start,
+                            end: start + size,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        partitions.sort_by_key(|range| range.start);
+
+        Ok(Self {
+            table_type: Some(table_type),
+            sector_size,
+            usable,
+            partitions,
+        })
+    }
+
+    /// Unpartitioned areas of at least [`MIN_LOST_PARTITION_SIZE`], in order
+    pub fn free_regions(&self) -> Vec<ByteRange> {
+        let mut regions = Vec::new();
+        let mut start = self.usable.start;
+        for partition in &self.partitions {
+            if partition.start > start {
+                regions.push(ByteRange {
+                    start,
+                    end: partition.start.min(self.usable.end),
+                });
+            }
+            start = start.max(partition.end);
+        }
+        if self.usable.end > start {
+            regions.push(ByteRange {
+                start,
+                end: self.usable.end,
+            });
+        }
+        regions.retain(|region| region.size() >= MIN_LOST_PARTITION_SIZE);
+        regions
+    }
+
+    /// Whether `range` lies in a single free region
+    pub fn is_free(&self, range: &ByteRange) -> bool {
+        self.free_regions()
+            .iter()
+            .any(|region| region.start <= range.start && range.end <= region.end)
+    }
+
+    /// Where the partition at `range` may lie without overlapping its
+    /// neighbours: its own place and the free space on either side
+    pub fn space_around(&self, range: &ByteRange) -> ByteRange {
+        let others = self.partitions.iter().filter(|other| *other != range);
+        ByteRange {
+            start: others
+                .clone()
+                .filter(|other| other.end <= range.start)
+                .map(|other| other.end)
+                .fold(self.usable.start, u64::max),
+            end: others
+                .filter(|other| other.start >= range.end)
+                .map(|other| other.start)
+                .fold(self.usable.end, u64::min),
+        }
+    }
+}
+
+/// The first place after `after` in `region` where a partition may start:
+/// the start of the region, sector 63, or a 1 MiB boundary
+pub fn next_candidate_offset(after: Option<u64>, region: &ByteRange) -> Option<u64> {
+    let from = after
+        .map_or(region.start, |after| after + 1)
+        .max(region.start);
+    let aligned = from.next_multiple_of(GPT_ALIGNMENT_BYTES);
+    let candidate = if from == region.start {
+        from
+    } else if (from..aligned).contains(&LEGACY_PARTITION_START) {
+        LEGACY_PARTITION_START
+    } else {
+        aligned
+    };
+    (candidate < region.end).then_some(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn rates_found_partitions() {
+        let signature = |size| FilesystemSignature {
+            fs_type: "ext4".to_string(),
+            size,
+            label: None,
+            uuid: None,
+        };
+        let found = LostPartition::assess(MIB, signature(Some(10 * MIB)), 100 * MIB);
+        assert_eq!(found.confidence, LostPartitionConfidence::High);
+        assert_eq!(
+            found.partition_type("gpt").as_deref(),
+            Some("0fc63daf-8483-4772-8e79-3d69d8477de4")
+        );
+
+        let unaligned =
+            LostPartition::assess(LEGACY_PARTITION_START, signature(Some(MIB)), MIB * 2);
+        assert_eq!(unaligned.confidence, LostPartitionConfidence::Medium);
+
+        let overlapping = LostPartition::assess(MIB, signature(Some(200 * MIB)), 100 * MIB);
+        assert_eq!(overlapping.confidence, LostPartitionConfidence::Low);
+
+        let luks_like = LostPartition::assess(MIB, signature(None), 100 * MIB);
+        assert_eq!(luks_like.size, 99 * MIB);
+        assert_eq!(luks_like.confidence, LostPartitionConfidence::Medium);
+    }
+
+    #[test]
+    fn lists_free_space_and_candidate_starts() {
+        let json = r#"{
+            "partitiontable": {
+                "label": "gpt",
+                "unit": "sectors",
+                "firstlba": 34,
+                "lastlba": 409566,
+                "sectorsize": 512,
+                "partitions": [
+                    {"node": "/dev/sdb1", "start": 2048, "size": 102400, "type": "0FC63DAF-8483-4772-8E79-3D69D8477DE4"}
+                ]
+            }
+        }"#;
+        let layout = PartitionLayout::from_sfdisk_json(json, 200 * MIB).unwrap();
+        assert_eq!(layout.table_type.as_deref(), Some("gpt"));
+        // The gap before the first partition is under 1 MiB
+        let regions = layout.free_regions();
+        assert_eq!(
+            regions,
+            [ByteRange {
+                start: 51 * MIB,
+                end: 409567 * 512
+            }]
+        );
+        assert!(layout.is_free(&ByteRange {
+            start: 60 * MIB,
+            end: 70 * MIB
+        }));
+        assert!(!layout.is_free(&ByteRange {
+            start: 50 * MIB,
+            end: 70 * MIB
+        }));
+
+        let region = ByteRange {
+            start: 512,
+            end: 3 * MIB,
+        };
+        assert_eq!(next_candidate_offset(None, &region), Some(512));
+        assert_eq!(
+            next_candidate_offset(Some(512), &region),
+            Some(LEGACY_PARTITION_START)
+        );
+        assert_eq!(
+            next_candidate_offset(Some(LEGACY_PARTITION_START), &region),
+            Some(MIB)
+        );
+        assert_eq!(next_candidate_offset(Some(2 * MIB), &region), None);
+    }
+}
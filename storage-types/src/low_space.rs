@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Low free space alerts for mounted filesystems
+//!
+//! A rule sets, per mount point, the free space below which the service
+//! warns: a percentage of the filesystem size, an amount of bytes, or both,
+//! in which case the first one reached counts. A filesystem is warned about
+//! once when it drops below its rule, and again only after it recovered.
+
+use serde::{Deserialize, Serialize};
+
+use crate::UsageCategory;
+
+/// What the service does on its own when a filesystem runs low
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LowSpaceAction {
+    /// Only warn
+    #[default]
+    Warn,
+    /// Prune safety snapshots past their configured retention
+    Cleanup,
+    /// Scan the filesystem and report what takes up its space
+    UsageScan,
+}
+
+impl LowSpaceAction {
+    pub const ALL: [Self; 3] = [Self::Warn, Self::Cleanup, Self::UsageScan];
+}
+
+/// Free space thresholds of a mount point
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LowSpaceRule {
+    pub mount_point: String,
+    /// Warn when less than this percentage of the size is free
+    pub min_free_percent: Option<u8>,
+    /// Warn when less than this many bytes are free
+    pub min_free_bytes: Option<u64>,
+    pub action: LowSpaceAction,
+}
+
+impl LowSpaceRule {
+    /// Whether the rule has a threshold at all
+    pub fn is_set(&self) -> bool {
+        self.min_free_percent.is_some() || self.min_free_bytes.is_some()
+    }
+
+    /// Whether `available` free bytes of a filesystem of `size` bytes are
+    /// below a threshold
+    pub fn is_low(&self, available: u64, size: u64) -> bool {
+        let below_bytes = self.min_free_bytes.is_some_and(|min| available < min);
+        let below_percent = self.min_free_percent.is_some_and(|percent| {
+            u128::from(available) * 100 < u128::from(size) * u128::from(percent)
+        });
+        below_bytes || below_percent
+    }
+}
+
+/// A filesystem that dropped below its rule, and what the service did
+/// about it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LowSpaceWarning {
+    pub mount_point: String,
+    pub available: u64,
+    pub size: u64,
+    pub action: LowSpaceAction,
+    /// Safety snapshots pruned by [`LowSpaceAction::Cleanup`]
+    pub pruned_snapshots: usize,
+    /// Largest categories found by [`LowSpaceAction::UsageScan`], largest
+    /// first
+    pub largest: Vec<(UsageCategory, u64)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn either_threshold_makes_a_filesystem_low() {
+        let rule = LowSpaceRule {
+            mount_point: "/home".to_string(),
+            min_free_percent: Some(10),
+            min_free_bytes: Some(5 * GIB),
+            action: LowSpaceAction::Warn,
+        };
+        assert!(!rule.is_low(20 * GIB, 100 * GIB));
+        assert!(rule.is_low(9 * GIB, 100 * GIB));
+        assert!(rule.is_low(4 * GIB, 20 * GIB));
+        assert!(!rule.is_low(10 * GIB, 100 * GIB));
+    }
+
+    #[test]
+    fn rules_without_thresholds_never_fire() {
+        let rule = LowSpaceRule {
+            mount_point: "/".to_string(),
+            ..LowSpaceRule::default()
+        };
+        assert!(!rule.is_set());
+        assert!(!rule.is_low(0, 100 * GIB));
+    }
+}
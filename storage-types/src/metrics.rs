@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Metrics export
+//!
+//! The service can publish capacity, SMART health, RAID state and operation
+//! counters in the OpenMetrics text format, for Prometheus or the
+//! node_exporter textfile collector. Export is off unless enabled in
+//! [`MetricsConfig`].
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Metrics export settings, read from `/etc/cosmic-ext-storage/metrics.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+
+    /// Address to serve `GET /metrics` on (e.g., "127.0.0.1:9586"), if any
+    pub listen: Option<String>,
+
+    /// File to rewrite with the metrics after every collection, if any
+    pub textfile: Option<String>,
+
+    /// Seconds between two collections; SMART reads can wake sleeping drives
+    pub interval_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: Some("127.0.0.1:9586".to_string()),
+            textfile: None,
+            interval_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Gauge => "gauge",
+            Self::Counter => "counter",
+        }
+    }
+}
+
+/// One labelled value of a metric
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    pub labels: Vec<(&'static str, String)>,
+    pub value: f64,
+}
+
+/// A metric with all its samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricFamily {
+    /// Name without the `_total` suffix of counters
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+    pub samples: Vec<MetricSample>,
+}
+
+impl MetricFamily {
+    pub fn new(name: &'static str, kind: MetricKind, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind,
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, labels: Vec<(&'static str, String)>, value: f64) {
+        self.samples.push(MetricSample { labels, value });
+    }
+}
+
+/// Encode `families` in the OpenMetrics text format
+///
+/// Families without samples are left out.
+pub fn encode_openmetrics(families: &[MetricFamily]) -> String {
+    let mut out = String::new();
+    for family in families.iter().filter(|f| !f.samples.is_empty()) {
+        let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind.as_str());
+        let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+        let suffix = match family.kind {
+            MetricKind::Counter => "_total",
+            MetricKind::Gauge => "",
+        };
+        for sample in &family.samples {
+            out.push_str(family.name);
+            out.push_str(suffix);
+            if !sample.labels.is_empty() {
+                let labels: Vec<String> = sample
+                    .labels
+                    .iter()
+                    .map(|(name, value)| format!("{name}=\"{}\"", escape_label(value)))
+                    .collect();
+                let _ = write!(out, "{{{}}}", labels.join(","));
+            }
+            let _ = writeln!(out, " {}", sample.value);
+        }
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_families_with_escaped_labels() {
+        let mut size = MetricFamily::new(
+            "cosmic_storage_disk_size_bytes",
+            MetricKind::Gauge,
+            "Size of the disk",
+        );
+        size.push(
+            vec![
+                ("device", "/dev/sda".to_string()),
+                ("model", "A \"B\"".to_string()),
+            ],
+            512110190592.0,
+        );
+        let mut operations = MetricFamily::new(
+            "cosmic_storage_operations",
+            MetricKind::Counter,
+            "Authorized operations",
+        );
+        operations.push(vec![], 3.0);
+        let empty = MetricFamily::new("cosmic_storage_unused", MetricKind::Gauge, "Unused");
+
+        assert_eq!(
+            encode_openmetrics(&[size, operations, empty]),
+            "# TYPE cosmic_storage_disk_size_bytes gauge\n\
+             # HELP cosmic_storage_disk_size_bytes Size of the disk\n\
+             cosmic_storage_disk_size_bytes{device=\"/dev/sda\",model=\"A \\\"B\\\"\"} 512110190592\n\
+             # TYPE cosmic_storage_operations counter\n\
+             # HELP cosmic_storage_operations Authorized operations\n\
+             cosmic_storage_operations_total 3\n\
+             # EOF\n"
+        );
+        assert!(!MetricsConfig::default().enabled);
+    }
+}
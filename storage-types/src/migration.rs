@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Migrating a disk onto a larger one
+//!
+//! The target gets the source's partition table with every partition laid
+//! out again, so that the space the target adds is shared out among the
+//! partitions chosen to grow, in proportion to their sizes. Each partition
+//! is then copied and its filesystem grown. Partitions, and the table, keep
+//! their UUIDs, as the target is meant to replace the source.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ByteRange, GPT_ALIGNMENT_BYTES};
+
+/// Sectors a GPT keeps at each end of the disk, with 512-byte sectors
+const GPT_RESERVED_SECTORS: u64 = 34;
+
+/// One partition of a migration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigratedPartition {
+    /// Partition number, the same on both disks
+    pub number: u32,
+    /// Where the partition is on the source, in bytes
+    pub source: ByteRange,
+    /// Where the partition goes on the target, in bytes
+    pub target: ByteRange,
+}
+
+impl MigratedPartition {
+    /// Whether the copy is larger than the original, so that its filesystem
+    /// has to grow
+    pub fn grows(&self) -> bool {
+        self.target.size() > self.source.size()
+    }
+}
+
+/// Where each partition of a disk goes on the disk replacing it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationPlan {
+    pub partitions: Vec<MigratedPartition>,
+}
+
+impl MigrationPlan {
+    /// Lay out `partitions`, as (number, place on the source) in disk order,
+    /// on a target of `target_size` bytes
+    ///
+    /// Partitions keep their order and start on 1 MiB boundaries. The room
+    /// left over goes to the partitions with `grow` set, in proportion to
+    /// their sizes; with all of them set, every partition scales alike.
+    pub fn new(
+        partitions: &[(u32, ByteRange)],
+        grow: &[bool],
+        target_size: u64,
+        target_sector_size: u64,
+    ) -> Result<Self, String> {
+        let Some((_, first)) = partitions.first() else {
+            return Err("The source disk has no partitions to migrate".to_string());
+        };
+        let usable = usable_area(target_size, target_sector_size);
+        let start = first
+            .start
+            .max(usable.start)
+            .next_multiple_of(GPT_ALIGNMENT_BYTES);
+        let needed: u64 = partitions
+            .iter()
+            .map(|(_, range)| range.size().next_multiple_of(GPT_ALIGNMENT_BYTES))
+            .sum();
+        if start + needed > usable.end {
+            return Err(format!(
+                "The target disk is too small: the partitions need {} but it has {}",
+                crate::bytes_to_pretty(&(start + needed), false),
+                crate::bytes_to_pretty(&usable.end, false)
+            ));
+        }
+
+        let extra = (usable.end - start - needed) as u128;
+        let growing: u128 = partitions
+            .iter()
+            .zip(grow.iter().chain(std::iter::repeat(&false)))
+            .filter(|(_, grow)| **grow)
+            .map(|((_, range), _)| range.size() as u128)
+            .sum();
+
+        let mut cursor = start;
+        let mut migrated = Vec::with_capacity(partitions.len());
+        for (index, (number, source)) in partitions.iter().enumerate() {
+            let added = if grow.get(index).copied().unwrap_or(false) && growing > 0 {
+                let share = (extra * source.size() as u128 / growing) as u64;
+                share - share % GPT_ALIGNMENT_BYTES
+            } else {
+                0
+            };
+            let target = ByteRange {
+                start: cursor,
+                end: cursor + source.size() + added,
+            };
+            cursor = target.end.next_multiple_of(GPT_ALIGNMENT_BYTES);
+            migrated.push(MigratedPartition {
+                number: *number,
+                source: *source,
+                target,
+            });
+        }
+        Ok(Self {
+            partitions: migrated,
+        })
+    }
+
+    /// Bytes copied by the migration
+    pub fn total_bytes(&self) -> u64 {
+        self.partitions
+            .iter()
+            .map(|partition| partition.source.size())
+            .sum()
+    }
+
+    /// Check that the plan fits a target of `target_size` bytes with
+    /// `target_sector_size`-byte sectors, before anything is written
+    pub fn validate(&self, target_size: u64, target_sector_size: u64) -> Result<(), String> {
+        if self.partitions.is_empty() {
+            return Err("The migration has no partitions".to_string());
+        }
+        let usable = usable_area(target_size, target_sector_size);
+        let mut previous_end = usable.start;
+        for partition in &self.partitions {
+            let target = &partition.target;
+            if target.start < previous_end || target.end > usable.end {
+                return Err(format!(
+                    "Partition {} does not fit on the target disk",
+                    partition.number
+                ));
+            }
+            if target.size() < partition.source.size() {
+                return Err(format!(
+                    "Partition {} would be smaller than the original",
+                    partition.number
+                ));
+            }
+            if !target.start.is_multiple_of(target_sector_size)
+                || !target.size().is_multiple_of(target_sector_size)
+            {
+                return Err(format!(
+                    "Partition {} does not fit the {}-byte sectors of the target disk",
+                    partition.number, target_sector_size
+                ));
+            }
+            previous_end = target.end;
+        }
+        Ok(())
+    }
+
+    /// The `sfdisk --dump` output `dump` of the source, rewritten into a
+    /// script that creates the planned table on `target_disk`
+    ///
+    /// Partition types, names, attributes and UUIDs are kept; the usable
+    /// area is left out so sfdisk fits the table to the larger disk.
+    pub fn sfdisk_script(
+        &self,
+        dump: &str,
+        target_disk: &str,
+        target_sector_size: u64,
+    ) -> Result<String, String> {
+        let mut script = String::new();
+        let mut written = 0;
+        for line in dump.lines() {
+            let line = line.trim();
+            let Some((device, fields)) = line.split_once(" : ") else {
+                let keep = ["label:", "label-id:", "unit:"]
+                    .iter()
+                    .any(|header| line.starts_with(header));
+                if keep {
+                    script.push_str(line);
+                    script.push('\n');
+                }
+                continue;
+            };
+
+            let number = trailing_number(device)
+                .ok_or_else(|| format!("Unexpected partition line in the table: {line}"))?;
+            let partition = self
+                .partitions
+                .iter()
+                .find(|partition| partition.number == number)
+                .ok_or_else(|| format!("Partition {number} is missing from the migration"))?;
+            // Start and size come first, the remaining fields are kept
+            let rest = fields
+                .find("type=")
+                .map(|index| &fields[index..])
+                .ok_or_else(|| format!("Partition {number} has no type"))?;
+            script.push_str(&format!(
+                "{} : start={}, size={}, {}\n",
+                partition_device(target_disk, number),
+                partition.target.start / target_sector_size,
+                partition.target.size() / target_sector_size,
+                rest
+            ));
+            written += 1;
+        }
+        if written != self.partitions.len() {
+            return Err("The source partition table changed since planning".to_string());
+        }
+        Ok(format!("sector-size: {target_sector_size}\n{script}"))
+    }
+}
+
+/// Where partitions may go on a disk of `size` bytes with a new GPT
+fn usable_area(size: u64, sector_size: u64) -> ByteRange {
+    let reserved = GPT_RESERVED_SECTORS * sector_size.max(512);
+    ByteRange {
+        start: reserved,
+        end: size.saturating_sub(reserved),
+    }
+}
+
+fn trailing_number(device: &str) -> Option<u32> {
+    let digits = device.len() - device.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    device[device.len() - digits..].parse().ok()
+}
+
+/// Device node of partition `number`, e.g. "/dev/sda2" or "/dev/nvme0n1p2"
+pub fn partition_device(disk: &str, number: u32) -> String {
+    if disk.ends_with(|c: char| c.is_ascii_digit()) {
+        format!("{disk}p{number}")
+    } else {
+        format!("{disk}{number}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+    const GIB: u64 = 1024 * MIB;
+
+    fn source() -> Vec<(u32, ByteRange)> {
+        vec![
+            (
+                1,
+                ByteRange {
+                    start: MIB,
+                    end: 513 * MIB,
+                },
+            ),
+            (
+                2,
+                ByteRange {
+                    start: 513 * MIB,
+                    end: 10 * GIB + 513 * MIB,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn space_goes_to_the_growing_partitions() {
+        let plan = MigrationPlan::new(&source(), &[false, true], 100 * GIB, 512).unwrap();
+        let [boot, root] = &plan.partitions[..] else {
+            panic!("two partitions expected");
+        };
+        assert_eq!(boot.target, boot.source);
+        assert!(!boot.grows());
+        assert_eq!(root.target.start, 513 * MIB);
+        assert!(root.target.end > 99 * GIB && root.target.end <= 100 * GIB);
+        assert_eq!(plan.validate(100 * GIB, 512), Ok(()));
+        assert_eq!(plan.total_bytes(), 10 * GIB + 512 * MIB);
+
+        // Growing everything scales both partitions alike
+        let plan = MigrationPlan::new(&source(), &[true, true], 21 * GIB, 512).unwrap();
+        let boot = &plan.partitions[0];
+        assert!(boot.target.size() >= 2 * boot.source.size() - MIB);
+    }
+
+    #[test]
+    fn migration_needs_a_large_enough_target() {
+        assert!(MigrationPlan::new(&source(), &[true, true], 10 * GIB, 512).is_err());
+        assert!(MigrationPlan::new(&[], &[], 100 * GIB, 512).is_err());
+
+        let mut plan = MigrationPlan::new(&source(), &[true, true], 100 * GIB, 512).unwrap();
+        assert!(plan.validate(50 * GIB, 512).is_err());
+        plan.partitions[1].target.end = plan.partitions[1].target.start + MIB;
+        assert!(plan.validate(100 * GIB, 512).is_err());
+    }
+
+    #[test]
+    fn sfdisk_script_keeps_everything_but_the_placement() {
+        let dump = "label: gpt
+label-id: 0D6D4C6E-4B0E-4C53-9F34-7C1B9E0F8A11
+device: /dev/sda
+unit: sectors
+first-lba: 34
+last-lba: 41943006
+sector-size: 512
+
+/dev/sda1 : start=        2048, size=     1048576, type=C12A7328-F81F-11D2-BA4B-00A0C93EC93B, uuid=6A3F2B1C-1111-4E2A-9C3B-2F0A1B2C3D4E, name=\"EFI, boot\"
+/dev/sda2 : start=     1050624, size=    20971520, type=0FC63DAF-8483-4772-8E79-3D69D8477DE4, uuid=7B4F3C2D-2222-4F3B-8D4C-3F1B2C3D4E5F
+";
+        let plan = MigrationPlan::new(&source(), &[false, true], 100 * GIB, 512).unwrap();
+        let script = plan.sfdisk_script(dump, "/dev/nvme0n1", 512).unwrap();
+        let lines: Vec<&str> = script.lines().collect();
+        assert_eq!(
+            lines[..4],
+            [
+                "sector-size: 512",
+                "label: gpt",
+                "label-id: 0D6D4C6E-4B0E-4C53-9F34-7C1B9E0F8A11",
+                "unit: sectors"
+            ]
+        );
+        assert_eq!(
+            lines[4],
+            "/dev/nvme0n1p1 : start=2048, size=1048576, \
+             type=C12A7328-F81F-11D2-BA4B-00A0C93EC93B, \
+             uuid=6A3F2B1C-1111-4E2A-9C3B-2F0A1B2C3D4E, name=\"EFI, boot\""
+        );
+        assert!(lines[5].starts_with("/dev/nvme0n1p2 : start=1050624, size="));
+        assert!(!script.contains("last-lba"));
+
+        let partial = MigrationPlan {
+            partitions: plan.partitions[..1].to_vec(),
+        };
+        assert!(partial.sfdisk_script(dump, "/dev/sdb", 512).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Mount failures a filesystem check may fix
+//!
+//! A filesystem left dirty by an unclean shutdown or damaged by a write
+//! that never completed refuses to mount, and the error UDisks passes on
+//! from mount(8) or the kernel says so in a handful of known ways. Telling
+//! those apart from other failures lets the app offer to check and repair
+//! the filesystem, then retry the mount.
+
+use serde::{Deserialize, Serialize};
+
+/// Why a filesystem that failed to mount needs checking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MountFailure {
+    /// The filesystem was not cleanly unmounted, or is marked for a check
+    Dirty,
+    /// The filesystem's metadata is damaged
+    Corrupt,
+    /// Nothing says the filesystem is at fault, e.g. mount(8)'s generic
+    /// "wrong fs type, bad option, bad superblock" message
+    Unknown,
+}
+
+/// Error text of a filesystem not cleanly unmounted
+const DIRTY_MARKERS: &[&str] = &[
+    "unclean file system",
+    "volume is scheduled for check",
+    "run chkdsk",
+    "$mftmirr does not match $mft",
+    "dirty bit is set",
+    "needs to be checked",
+    "needs journal recovery",
+];
+
+/// Error text of a filesystem with damaged metadata, as the kernel or the
+/// filesystem's own tools put it
+const CORRUPT_MARKERS: &[&str] = &[
+    "structure needs cleaning",
+    "run fsck",
+    "ntfs is either inconsistent",
+];
+
+/// Error text of an NTFS filesystem Windows left hibernated, which a repair
+/// would damage; see `windows_hibernation`
+const HIBERNATION_MARKERS: &[&str] = &["hibernat", "windows cache", "fast restart"];
+
+impl MountFailure {
+    /// Why the mount that failed with `error` needs a filesystem check;
+    /// Unknown when a check won't help or may not
+    pub fn parse(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let mentions = |markers: &[&str]| markers.iter().any(|marker| error.contains(marker));
+        if mentions(HIBERNATION_MARKERS) {
+            Self::Unknown
+        } else if mentions(CORRUPT_MARKERS) {
+            Self::Corrupt
+        } else if mentions(DIRTY_MARKERS) {
+            Self::Dirty
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Whether checking and repairing the filesystem may let it mount
+    pub fn needs_check(self) -> bool {
+        self != Self::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mount_errors() {
+        assert_eq!(
+            MountFailure::parse(
+                "Failed to mount filesystem: Operation failed: Mount failed: Error mounting \
+                 /dev/sdb1 at /media/user/data: Structure needs cleaning"
+            ),
+            MountFailure::Corrupt
+        );
+        assert_eq!(
+            MountFailure::parse(
+                "wrong fs type, bad option, bad superblock on /dev/sdb1, missing codepage or \
+                 helper program, or other error"
+            ),
+            MountFailure::Unknown
+        );
+        assert_eq!(
+            MountFailure::parse("EXT4-fs (sdb1): filesystem needs journal recovery"),
+            MountFailure::Dirty
+        );
+        assert_eq!(
+            MountFailure::parse(
+                "mount: /mnt: cannot mount /dev/sdb1 read-only: filesystem needs journal \
+                 recovery"
+            ),
+            MountFailure::Dirty
+        );
+        assert_eq!(
+            MountFailure::parse("Filesystem has errors, please run fsck"),
+            MountFailure::Corrupt
+        );
+        assert_eq!(
+            MountFailure::parse("mount: /mnt: can't read superblock on /dev/sdb1."),
+            MountFailure::Unknown
+        );
+        assert_eq!(
+            MountFailure::parse("$MFTMirr does not match $MFT (record 3). Run chkdsk /f."),
+            MountFailure::Dirty
+        );
+        assert_eq!(
+            MountFailure::parse(
+                "The disk contains an unclean file system (0, 0).\nMetadata kept in Windows \
+                 cache, refused to mount."
+            ),
+            MountFailure::Unknown
+        );
+        assert_eq!(
+            MountFailure::parse("Error mounting /dev/sdb1: Not authorized to perform operation"),
+            MountFailure::Unknown
+        );
+    }
+}
@@ -0,0 +1,577 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Schema, presets and lints for persistent mount options
+//!
+//! The "other options" of an fstab entry are edited as a list of options the
+//! schema knows rather than as free text: presets replace the options they
+//! set, and lints point out options that contradict each other, do not apply
+//! to the filesystem or wear out the drive before the entry is saved.
+
+/// Value an option takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MountOptionKind {
+    /// Option without a value, e.g. "noatime"
+    Flag,
+
+    /// "name=value", where the value is one of the listed ones, or anything
+    /// non-empty when the list is empty
+    Value(&'static [&'static str]),
+}
+
+/// A mount option known to the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MountOptionSpec {
+    /// Option name, without any "=value"
+    pub name: &'static str,
+
+    /// One-line help text
+    pub help: &'static str,
+
+    /// Value type and constraints
+    pub kind: MountOptionKind,
+
+    /// Filesystem types the option applies to; empty for all of them
+    pub fs_types: &'static [&'static str],
+
+    /// Options of the same group override each other, e.g. "ro" and "rw"
+    pub group: Option<&'static str>,
+}
+
+const fn flag(
+    name: &'static str,
+    help: &'static str,
+    group: Option<&'static str>,
+) -> MountOptionSpec {
+    MountOptionSpec {
+        name,
+        help,
+        kind: MountOptionKind::Flag,
+        fs_types: &[],
+        group,
+    }
+}
+
+const EXT: &[&str] = &["ext2", "ext3", "ext4"];
+const BTRFS: &[&str] = &["btrfs"];
+const FAT: &[&str] = &["vfat", "exfat", "ntfs", "ntfs3"];
+const COMPRESS_ALGORITHMS: &[&str] = &[
+    "no", "lzo", "zlib", "zstd", "zstd:1", "zstd:3", "zstd:6", "zstd:9", "zstd:15",
+];
+
+/// Every mount option the app knows
+pub const MOUNT_OPTION_SCHEMA: &[MountOptionSpec] = &[
+    flag("defaults", "Default options of the filesystem", None),
+    flag("ro", "Mount read-only", Some("access")),
+    flag("rw", "Mount read-write", Some("access")),
+    flag("exec", "Allow running programs", Some("exec")),
+    flag("noexec", "Do not allow running programs", Some("exec")),
+    flag("suid", "Honour setuid and setgid bits", Some("suid")),
+    flag("nosuid", "Ignore setuid and setgid bits", Some("suid")),
+    flag("dev", "Interpret device files", Some("dev")),
+    flag("nodev", "Do not interpret device files", Some("dev")),
+    flag("atime", "Update access times on every read", Some("atime")),
+    flag("noatime", "Never update access times", Some("atime")),
+    flag(
+        "relatime",
+        "Update access times only when older than the modification time",
+        Some("atime"),
+    ),
+    flag("strictatime", "Always update access times", Some("atime")),
+    flag(
+        "nodiratime",
+        "Never update access times of directories",
+        None,
+    ),
+    flag(
+        "lazytime",
+        "Keep time stamps in memory until the inode is written",
+        None,
+    ),
+    flag(
+        "sync",
+        "Write every change to the drive at once",
+        Some("sync"),
+    ),
+    flag("async", "Buffer writes in memory", Some("sync")),
+    flag("auto", "Mount at startup", Some("auto")),
+    flag("noauto", "Do not mount at startup", Some("auto")),
+    flag(
+        "nofail",
+        "Do not fail startup when the drive is missing",
+        None,
+    ),
+    flag("user", "Let any user mount it", Some("user")),
+    flag("nouser", "Only let root mount it", Some("user")),
+    flag("users", "Let any user mount and unmount it", Some("user")),
+    flag(
+        "discard",
+        "Send TRIM requests as files are deleted",
+        Some("discard"),
+    ),
+    flag("nodiscard", "Do not send TRIM requests", Some("discard")),
+    MountOptionSpec {
+        name: "commit",
+        help: "Seconds between journal commits",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: &["ext3", "ext4", "btrfs"],
+        group: None,
+    },
+    MountOptionSpec {
+        name: "errors",
+        help: "What to do on filesystem errors",
+        kind: MountOptionKind::Value(&["continue", "remount-ro", "panic"]),
+        fs_types: EXT,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "data",
+        help: "How file data is journaled",
+        kind: MountOptionKind::Value(&["journal", "ordered", "writeback"]),
+        fs_types: &["ext3", "ext4"],
+        group: None,
+    },
+    MountOptionSpec {
+        name: "compress",
+        help: "Compress new files",
+        kind: MountOptionKind::Value(COMPRESS_ALGORITHMS),
+        fs_types: BTRFS,
+        group: Some("compress"),
+    },
+    MountOptionSpec {
+        name: "compress-force",
+        help: "Compress new files, even those that compress badly",
+        kind: MountOptionKind::Value(COMPRESS_ALGORITHMS),
+        fs_types: BTRFS,
+        group: Some("compress"),
+    },
+    MountOptionSpec {
+        name: "space_cache",
+        help: "Version of the free space cache",
+        kind: MountOptionKind::Value(&["v1", "v2"]),
+        fs_types: BTRFS,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "subvol",
+        help: "Subvolume to mount, by path",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: BTRFS,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "subvolid",
+        help: "Subvolume to mount, by id",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: BTRFS,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "autodefrag",
+        help: "Defragment files on small random writes",
+        kind: MountOptionKind::Flag,
+        fs_types: BTRFS,
+        group: Some("autodefrag"),
+    },
+    MountOptionSpec {
+        name: "noautodefrag",
+        help: "Do not defragment files automatically",
+        kind: MountOptionKind::Flag,
+        fs_types: BTRFS,
+        group: Some("autodefrag"),
+    },
+    MountOptionSpec {
+        name: "uid",
+        help: "Owner of all files",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: FAT,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "gid",
+        help: "Group of all files",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: FAT,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "umask",
+        help: "Permission bits cleared on files and directories",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: FAT,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "fmask",
+        help: "Permission bits cleared on files",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: FAT,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "dmask",
+        help: "Permission bits cleared on directories",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: FAT,
+        group: None,
+    },
+    MountOptionSpec {
+        name: "iocharset",
+        help: "Character set of file names",
+        kind: MountOptionKind::Value(&[]),
+        fs_types: &["vfat", "exfat", "ntfs"],
+        group: None,
+    },
+    MountOptionSpec {
+        name: "flush",
+        help: "Write data out early, for drives that are unplugged quickly",
+        kind: MountOptionKind::Flag,
+        fs_types: &["vfat"],
+        group: None,
+    },
+];
+
+/// The schema entry for an option name
+pub fn mount_option_spec(name: &str) -> Option<&'static MountOptionSpec> {
+    MOUNT_OPTION_SCHEMA.iter().find(|spec| spec.name == name)
+}
+
+/// One option of a comma-separated mount option string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountOption {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+impl std::fmt::Display for MountOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}={}", self.name, value),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// Split a mount option string such as "noatime,compress=zstd"
+pub fn parse_mount_options(options: &str) -> Vec<MountOption> {
+    options
+        .split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(|option| match option.split_once('=') {
+            Some((name, value)) => MountOption {
+                name: name.to_string(),
+                value: Some(value.to_string()),
+            },
+            None => MountOption {
+                name: option.to_string(),
+                value: None,
+            },
+        })
+        .collect()
+}
+
+/// Join options back into a mount option string
+pub fn join_mount_options(options: &[MountOption]) -> String {
+    options
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A curated set of mount options for a use of a filesystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MountOptionPreset {
+    /// Stable identifier (e.g. "btrfs-laptop")
+    pub id: &'static str,
+
+    /// Filesystem types the preset is meant for
+    pub fs_types: &'static [&'static str],
+
+    /// Short display name
+    pub name: &'static str,
+
+    /// One-line description
+    pub description: &'static str,
+
+    /// Options the preset sets
+    pub options: &'static str,
+}
+
+/// Every built-in mount option preset
+pub const MOUNT_OPTION_PRESETS: &[MountOptionPreset] = &[
+    MountOptionPreset {
+        id: "ext4-ssd",
+        fs_types: &["ext4"],
+        name: "SSD",
+        description: "No access time writes; TRIM is left to the weekly fstrim timer",
+        options: "noatime,errors=remount-ro",
+    },
+    MountOptionPreset {
+        id: "btrfs-laptop",
+        fs_types: BTRFS,
+        name: "Laptop",
+        description: "Compress new files with zstd and skip access time writes",
+        options: "noatime,compress=zstd:3,space_cache=v2",
+    },
+    MountOptionPreset {
+        id: "exfat-shared",
+        fs_types: &["exfat"],
+        name: "Shared drive",
+        description: "Every user can read and write; startup does not wait for the drive",
+        options: "nosuid,nodev,nofail,noatime,fmask=0111,dmask=0000",
+    },
+];
+
+/// Presets meant for a filesystem type
+pub fn mount_option_presets(fs_type: &str) -> impl Iterator<Item = &'static MountOptionPreset> {
+    MOUNT_OPTION_PRESETS
+        .iter()
+        .filter(move |preset| preset.fs_types.contains(&fs_type))
+}
+
+impl MountOptionPreset {
+    /// `options` with the preset's options set, replacing options of the
+    /// same name or group and keeping the rest
+    pub fn apply(&self, options: &str) -> String {
+        let preset = parse_mount_options(self.options);
+        let replaced = |option: &MountOption| {
+            preset.iter().any(|set| {
+                set.name == option.name
+                    || group_of(&set.name)
+                        .is_some_and(|group| group_of(&option.name) == Some(group))
+            })
+        };
+
+        let mut merged: Vec<MountOption> = parse_mount_options(options)
+            .into_iter()
+            .filter(|option| !replaced(option))
+            .collect();
+        merged.extend(preset);
+        join_mount_options(&merged)
+    }
+}
+
+fn group_of(name: &str) -> Option<&'static str> {
+    mount_option_spec(name).and_then(|spec| spec.group)
+}
+
+/// The drive a filesystem is on, as far as lints care
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MountLintContext {
+    /// Spinning disk rather than flash
+    pub rotational: bool,
+
+    /// USB stick, SD card or other removable flash, whose controllers
+    /// rarely handle continuous TRIM well
+    pub removable: bool,
+
+    /// The drive accepts discard requests
+    pub discard_supported: bool,
+}
+
+/// How bad a lint is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MountLintSeverity {
+    /// Works, but likely not as intended
+    Warning,
+
+    /// Contradictory or invalid; the entry is not saved
+    Error,
+}
+
+/// A problem with a set of mount options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountLint {
+    pub severity: MountLintSeverity,
+
+    /// Options the lint is about
+    pub options: Vec<String>,
+
+    pub message: String,
+}
+
+impl MountLint {
+    fn new(severity: MountLintSeverity, options: &[&MountOption], message: String) -> Self {
+        Self {
+            severity,
+            options: options.iter().map(ToString::to_string).collect(),
+            message,
+        }
+    }
+}
+
+/// Lint a mount option string for a filesystem type on a drive
+///
+/// Options starting with "x-" and "comment=" are for other programs and
+/// are not checked.
+pub fn lint_mount_options(
+    fs_type: &str,
+    options: &str,
+    context: &MountLintContext,
+) -> Vec<MountLint> {
+    let options: Vec<MountOption> = parse_mount_options(options)
+        .into_iter()
+        .filter(|option| !option.name.starts_with("x-") && option.name != "comment")
+        .collect();
+    let mut lints = Vec::new();
+
+    for (index, option) in options.iter().enumerate() {
+        let Some(spec) = mount_option_spec(&option.name) else {
+            lints.push(MountLint::new(
+                MountLintSeverity::Warning,
+                &[option],
+                format!("{} is not a known mount option", option.name),
+            ));
+            continue;
+        };
+        if !spec.fs_types.is_empty() && !spec.fs_types.contains(&fs_type) {
+            lints.push(MountLint::new(
+                MountLintSeverity::Warning,
+                &[option],
+                format!("{} does not apply to {fs_type}", option.name),
+            ));
+        }
+
+        let valid = match (spec.kind, option.value.as_deref()) {
+            (MountOptionKind::Flag, None) => true,
+            (MountOptionKind::Value(allowed), Some(value)) => {
+                !value.is_empty() && (allowed.is_empty() || allowed.contains(&value))
+            }
+            _ => false,
+        };
+        if !valid {
+            lints.push(MountLint::new(
+                MountLintSeverity::Error,
+                &[option],
+                format!("Invalid value for {}", option.name),
+            ));
+        }
+
+        for earlier in &options[..index] {
+            if earlier.name == option.name && earlier.value != option.value {
+                lints.push(MountLint::new(
+                    MountLintSeverity::Error,
+                    &[earlier, option],
+                    format!("{} is given twice with different values", option.name),
+                ));
+            } else if earlier.name != option.name
+                && spec.group.is_some()
+                && group_of(&earlier.name) == spec.group
+            {
+                lints.push(MountLint::new(
+                    MountLintSeverity::Error,
+                    &[earlier, option],
+                    format!("{earlier} contradicts {option}"),
+                ));
+            }
+        }
+    }
+
+    let flash = !context.rotational || context.removable;
+    for option in &options {
+        match option.name.as_str() {
+            "sync" if flash => lints.push(MountLint::new(
+                MountLintSeverity::Warning,
+                &[option],
+                "sync on flash memory makes writes much slower and wears out the drive".to_string(),
+            )),
+            "discard" if !context.discard_supported => lints.push(MountLint::new(
+                MountLintSeverity::Warning,
+                &[option],
+                "The drive does not accept discard requests".to_string(),
+            )),
+            "discard" if context.removable => lints.push(MountLint::new(
+                MountLintSeverity::Warning,
+                &[option],
+                "Continuous discard stalls writes on drives without queued TRIM; the weekly \
+                 fstrim timer is safer"
+                    .to_string(),
+            )),
+            _ => {}
+        }
+    }
+
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SSD: MountLintContext = MountLintContext {
+        rotational: false,
+        removable: false,
+        discard_supported: true,
+    };
+
+    #[test]
+    fn presets_pass_lints() {
+        for preset in MOUNT_OPTION_PRESETS {
+            for fs_type in preset.fs_types {
+                let options = preset.apply("nosuid,nodev,nofail");
+                assert_eq!(
+                    lint_mount_options(fs_type, &options, &SSD),
+                    Vec::new(),
+                    "preset {}",
+                    preset.id
+                );
+            }
+        }
+
+        let laptop = mount_option_presets("btrfs").next().unwrap();
+        assert_eq!(
+            laptop.apply("relatime,compress=lzo,x-systemd.automount"),
+            "x-systemd.automount,noatime,compress=zstd:3,space_cache=v2"
+        );
+    }
+
+    #[test]
+    fn flags_contradictions_and_flash_wear() {
+        let severities = |fs_type, options, context| {
+            lint_mount_options(fs_type, options, context)
+                .into_iter()
+                .map(|lint| lint.severity)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            severities("ext4", "ro,noatime,rw", &SSD),
+            [MountLintSeverity::Error]
+        );
+        assert_eq!(
+            severities("btrfs", "compress=zstd,compress=lzo", &SSD),
+            [MountLintSeverity::Error]
+        );
+        assert_eq!(
+            severities("ext4", "errors=explode", &SSD),
+            [MountLintSeverity::Error]
+        );
+        assert_eq!(
+            severities("ext4", "compress=zstd", &SSD),
+            [MountLintSeverity::Warning]
+        );
+        assert_eq!(
+            severities("ext4", "sync", &SSD),
+            [MountLintSeverity::Warning]
+        );
+        let disk = MountLintContext {
+            rotational: true,
+            ..Default::default()
+        };
+        assert_eq!(severities("ext4", "sync,x-gvfs-show", &disk), []);
+        assert_eq!(
+            severities("ext4", "discard", &disk),
+            [MountLintSeverity::Warning]
+        );
+        let stick = MountLintContext {
+            removable: true,
+            discard_supported: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            severities("exfat", "discard", &stick),
+            [MountLintSeverity::Warning]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Phones and cameras
+//!
+//! MTP and PTP devices are handled by gvfs: its volume monitors list them
+//! and mount them as `mtp://` or `gphoto2://` locations, each storage of the
+//! device (internal storage, SD card) being a folder of the mount. The app
+//! reads them from `gio mount -li` and `gio info -f`.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Protocol a device is accessed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MtpProtocol {
+    /// Media Transfer Protocol, used by phones and media players
+    Mtp,
+    /// Picture Transfer Protocol, used by cameras (through gphoto2)
+    Ptp,
+}
+
+impl MtpProtocol {
+    /// Protocol of a gvfs location, if it is an MTP or PTP one
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        match uri.split_once("://")?.0 {
+            "mtp" => Some(Self::Mtp),
+            "gphoto2" => Some(Self::Ptp),
+            _ => None,
+        }
+    }
+}
+
+/// One storage area of a device
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MtpStorage {
+    pub name: String,
+    /// Folder of the storage in the gvfs FUSE mount
+    pub path: PathBuf,
+    pub size: Option<u64>,
+    pub free: Option<u64>,
+}
+
+impl MtpStorage {
+    pub fn used(&self) -> Option<u64> {
+        Some(self.size?.saturating_sub(self.free?))
+    }
+}
+
+/// A connected phone or camera
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MtpDevice {
+    pub name: String,
+    pub protocol: MtpProtocol,
+    /// gvfs location of the device, e.g. "mtp://Google_Pixel_7_2A19/"
+    pub uri: String,
+    pub mounted: bool,
+    pub can_eject: bool,
+    /// Folder of the device in the gvfs FUSE mount, known while it is mounted
+    pub path: Option<PathBuf>,
+    /// Storages of the device, known while it is mounted
+    pub storages: Vec<MtpStorage>,
+}
+
+impl MtpDevice {
+    /// Folder of the device in the gvfs FUSE mount at `gvfs_root`, where
+    /// "mtp://Phone/" becomes "mtp:host=Phone"
+    pub fn fuse_path(&self, gvfs_root: &std::path::Path) -> Option<PathBuf> {
+        let (scheme, host) = self.uri.split_once("://")?;
+        let host = host.trim_end_matches('/');
+        Some(gvfs_root.join(format!("{scheme}:host={host}")))
+    }
+
+    /// Capacity and free space over all storages, if any reports them
+    pub fn capacity(&self) -> Option<(u64, u64)> {
+        self.storages
+            .iter()
+            .filter_map(|storage| Some((storage.size?, storage.free?)))
+            .reduce(|(size, free), (s, f)| (size + s, free + f))
+    }
+}
+
+/// Phones and cameras in the output of `gio mount -li`
+///
+/// Devices are listed as volumes of the gvfs MTP and gphoto2 volume
+/// monitors; a mount with the same location means the device is mounted.
+pub fn parse_gio_mount_list(output: &str) -> Vec<MtpDevice> {
+    let mut devices: Vec<MtpDevice> = Vec::new();
+    let mut mounted_uris = Vec::new();
+    // Volume being read: (name, activation root, can eject)
+    let mut volume: Option<(String, Option<String>, bool)> = None;
+
+    let mut finish = |volume: &mut Option<(String, Option<String>, bool)>| {
+        if let Some((name, Some(uri), can_eject)) = volume.take()
+            && let Some(protocol) = MtpProtocol::from_uri(&uri)
+        {
+            devices.push(MtpDevice {
+                name,
+                protocol,
+                uri,
+                mounted: false,
+                can_eject,
+                path: None,
+                storages: Vec::new(),
+            });
+        }
+    };
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = header(trimmed, "Volume(") {
+            finish(&mut volume);
+            volume = Some((rest.to_string(), None, false));
+        } else if let Some(rest) = header(trimmed, "Mount(") {
+            finish(&mut volume);
+            if let Some((_, uri)) = rest.rsplit_once(" -> ") {
+                mounted_uris.push(uri.to_string());
+            }
+        } else if header(trimmed, "Drive(").is_some() {
+            finish(&mut volume);
+        } else if let Some((_, uri, can_eject)) = volume.as_mut() {
+            if let Some(root) = trimmed.strip_prefix("activation_root=") {
+                *uri = Some(root.to_string());
+            } else if let Some(value) = trimmed.strip_prefix("can_eject=") {
+                *can_eject = value == "1";
+            }
+        }
+    }
+    finish(&mut volume);
+
+    for device in &mut devices {
+        device.mounted = mounted_uris.contains(&device.uri);
+    }
+    devices
+}
+
+/// Text after the "Kind(N): " prefix of a `gio mount -li` header line
+fn header<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(kind)?;
+    Some(rest.split_once("): ")?.1)
+}
+
+/// Size and free space in the output of `gio info -f`
+pub fn parse_gio_filesystem_info(output: &str) -> (Option<u64>, Option<u64>) {
+    let value = |key: &str| {
+        output.lines().find_map(|line| {
+            line.trim()
+                .strip_prefix(key)?
+                .strip_prefix(':')?
+                .trim()
+                .parse()
+                .ok()
+        })
+    };
+    (value("filesystem::size"), value("filesystem::free"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_devices_from_gio() {
+        let output = "\
+Drive(0): Samsung SSD 980
+  Type: GProxyDrive (GProxyVolumeMonitorUDisks2)
+  Volume(0): Data
+    Type: GProxyVolume (GProxyVolumeMonitorUDisks2)
+    activation_root=file:///media/u/Data
+Volume(0): Pixel 7
+  Type: GProxyVolume (GProxyVolumeMonitorMTP)
+  activation_root=mtp://Google_Pixel_7_2A19/
+  can_mount=1
+  can_eject=1
+Volume(1): Canon EOS
+  Type: GProxyVolume (GProxyVolumeMonitorGPhoto2)
+  activation_root=gphoto2://Canon_EOS/
+  can_eject=0
+Mount(0): Pixel 7 -> mtp://Google_Pixel_7_2A19/
+  Type: GProxyMount (GProxyVolumeMonitorMTP)
+";
+        let devices = parse_gio_mount_list(output);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].name, "Pixel 7");
+        assert_eq!(devices[0].protocol, MtpProtocol::Mtp);
+        assert!(devices[0].mounted && devices[0].can_eject);
+        assert_eq!(devices[1].protocol, MtpProtocol::Ptp);
+        assert!(!devices[1].mounted && !devices[1].can_eject);
+        assert_eq!(
+            devices[0].fuse_path(std::path::Path::new("/run/user/1000/gvfs")),
+            Some(PathBuf::from(
+                "/run/user/1000/gvfs/mtp:host=Google_Pixel_7_2A19"
+            ))
+        );
+
+        let info = "attributes:\n  filesystem::size: 119185342464\n  filesystem::free: 80530636800\n  filesystem::type: mtpfs\n";
+        assert_eq!(
+            parse_gio_filesystem_info(info),
+            (Some(119185342464), Some(80530636800))
+        );
+    }
+}
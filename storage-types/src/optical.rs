@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Optical media
+//!
+//! Discs are inspected, erased and burned with xorriso. Its reports on the
+//! loaded medium and its progress messages are parsed here.
+
+use serde::{Deserialize, Serialize};
+
+/// Sector size of data discs
+pub const OPTICAL_SECTOR_SIZE: u64 = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpticalMediaStatus {
+    /// Nothing written yet
+    Blank,
+    /// Written, with room for another session
+    Appendable,
+    /// Written and closed
+    Closed,
+}
+
+/// The disc in an optical drive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpticalMediaInfo {
+    /// Media type as the drive reports it, e.g. "CD-R" or "DVD+RW"
+    pub media_type: String,
+    pub status: OpticalMediaStatus,
+    /// Bytes of data on the disc
+    pub used_bytes: u64,
+    /// Bytes that can still be written, when the drive reports them
+    pub free_bytes: Option<u64>,
+}
+
+impl OpticalMediaInfo {
+    /// Whether the disc can be erased and written again
+    pub fn is_rewritable(&self) -> bool {
+        let media = self.media_type.to_ascii_uppercase();
+        ["-RW", "+RW", "-RE", "-RAM"]
+            .iter()
+            .any(|kind| media.contains(kind))
+    }
+
+    pub fn capacity_bytes(&self) -> Option<u64> {
+        Some(self.used_bytes + self.free_bytes?)
+    }
+
+    /// Whether an image of `size` bytes can be burned to the disc, erasing
+    /// it first if needed
+    pub fn can_burn(&self, size: u64) -> Result<(), String> {
+        if self.status != OpticalMediaStatus::Blank && !self.is_rewritable() {
+            return Err(format!(
+                "The {} in the drive is already written and cannot be erased",
+                self.media_type
+            ));
+        }
+        let capacity = if self.status == OpticalMediaStatus::Blank {
+            self.free_bytes
+        } else {
+            self.capacity_bytes()
+        };
+        match capacity {
+            Some(capacity) if size > capacity => Err(format!(
+                "The image needs {size} bytes but the {} holds {capacity}",
+                self.media_type
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The loaded disc in the output of `xorriso -outdev <drive> -toc
+/// -tell_media_space`, or `None` when the drive is empty
+pub fn parse_xorriso_media_info(output: &str) -> Option<OpticalMediaInfo> {
+    let field = |key: &str| {
+        output.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            (name.trim() == key).then(|| value.trim())
+        })
+    };
+
+    let media_type = field("Media current")?;
+    if media_type.is_empty() || media_type.starts_with("is not present") {
+        return None;
+    }
+    let status = field("Media status").unwrap_or_default();
+    let status = if status.contains("is blank") {
+        OpticalMediaStatus::Blank
+    } else if status.contains("is appendable") {
+        OpticalMediaStatus::Appendable
+    } else {
+        OpticalMediaStatus::Closed
+    };
+
+    // "1 session, 1024 data blocks, 2048k data, 4481m free"
+    let summary = field("Media summary").unwrap_or_default();
+    let summary_value = |unit: &str| {
+        summary
+            .split(',')
+            .find_map(|part| part.trim().strip_suffix(unit))
+            .map(str::trim)
+    };
+    let used_bytes = summary_value("data blocks")
+        .and_then(|blocks| blocks.parse::<u64>().ok())
+        .map(|blocks| blocks * OPTICAL_SECTOR_SIZE)
+        .unwrap_or_default();
+
+    // "Media space  : 2295104s", else the rounded free space of the summary
+    let free_bytes = field("Media space")
+        .and_then(|space| space.strip_suffix('s')?.parse::<u64>().ok())
+        .map(|blocks| blocks * OPTICAL_SECTOR_SIZE)
+        .or_else(|| summary_value("free").and_then(parse_xorriso_size));
+
+    Some(OpticalMediaInfo {
+        media_type: media_type.to_string(),
+        status,
+        used_bytes,
+        free_bytes,
+    })
+}
+
+/// A size like "4481m" as xorriso prints it
+fn parse_xorriso_size(size: &str) -> Option<u64> {
+    let (number, multiplier) = match size.char_indices().last()? {
+        (i, 'k') => (&size[..i], 1 << 10),
+        (i, 'm') => (&size[..i], 1 << 20),
+        (i, 'g') => (&size[..i], 1 << 30),
+        _ => (size, 1),
+    };
+    Some((number.trim().parse::<f64>().ok()? * multiplier as f64) as u64)
+}
+
+/// Share of the work done in an xorriso progress message, such as
+/// "xorriso : UPDATE : Writing:  32768s  12.5%  fifo 100%  buf  99%  4.0xD"
+/// or "xorriso : UPDATE : Blanking  ( 45.2% done in 12 seconds )"
+pub fn parse_xorriso_progress(line: &str) -> Option<f64> {
+    let update = line.split_once("UPDATE :")?.1;
+    let percent = update
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix('%')?.parse::<f64>().ok())?;
+    Some((percent / 100.0).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_media_from_xorriso() {
+        let blank = "\
+xorriso 1.5.6 : RockRidge filesystem manipulator, libburnia project.
+
+Drive current: -outdev '/dev/sr0'
+Media current: DVD+RW
+Media status : is blank
+Media summary: 0 sessions, 0 data blocks, 0 data, 4489m free
+Media space  : 2295104s
+";
+        let info = parse_xorriso_media_info(blank).unwrap();
+        assert_eq!(info.media_type, "DVD+RW");
+        assert_eq!(info.status, OpticalMediaStatus::Blank);
+        assert!(info.is_rewritable());
+        assert_eq!(info.free_bytes, Some(2295104 * 2048));
+        assert!(info.can_burn(700 << 20).is_ok());
+        assert!(info.can_burn(5 << 30).is_err());
+
+        let written = "\
+Media current: CD-R
+Media status : is written , is closed
+Media summary: 1 session, 358400 data blocks,  700m data,     0 free
+";
+        let info = parse_xorriso_media_info(written).unwrap();
+        assert_eq!(info.status, OpticalMediaStatus::Closed);
+        assert_eq!(info.used_bytes, 358400 * 2048);
+        assert_eq!(info.free_bytes, Some(0));
+        assert!(!info.is_rewritable());
+        assert!(info.can_burn(1 << 20).is_err());
+
+        let empty = "Drive current: -outdev '/dev/sr0'\nMedia current: is not present\n";
+        assert_eq!(parse_xorriso_media_info(empty), None);
+    }
+
+    #[test]
+    fn reads_xorriso_progress() {
+        assert_eq!(
+            parse_xorriso_progress(
+                "xorriso : UPDATE : Writing:      32768s   12.5%   fifo 100%  buf  99%    4.0xD"
+            ),
+            Some(0.125)
+        );
+        assert_eq!(
+            parse_xorriso_progress("xorriso : UPDATE : Blanking  ( 50.0% done in 12 seconds )"),
+            Some(0.5)
+        );
+        assert_eq!(parse_xorriso_progress("Media current: DVD+RW"), None);
+    }
+}
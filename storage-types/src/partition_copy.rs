@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Copying a partition onto another one
+//!
+//! The copy is a byte-for-byte clone, so both partitions end up with the
+//! same filesystem UUID, which confuses mounting by UUID while both are
+//! attached. After copying, the target gets a new UUID and its filesystem
+//! grows to fill the target where the filesystem supports it.
+
+/// Check that a partition of `target_size` bytes can hold a copy of one of
+/// `source_size` bytes
+pub fn validate_copy_target(source_size: u64, target_size: u64) -> Result<(), String> {
+    if target_size < source_size {
+        return Err(format!(
+            "The target partition ({}) is smaller than the source ({})",
+            crate::bytes_to_pretty(&target_size, false),
+            crate::bytes_to_pretty(&source_size, false)
+        ));
+    }
+    Ok(())
+}
+
+/// Whether a copied `fs_type` filesystem can be grown to fill a larger
+/// target partition
+pub fn grows_after_copy(fs_type: &str) -> bool {
+    matches!(fs_type, "ext2" | "ext3" | "ext4" | "xfs" | "btrfs" | "ntfs")
+}
+
+/// Whether a copied `fs_type` filesystem can be given a new UUID
+pub fn gets_new_uuid(fs_type: &str) -> bool {
+    grows_after_copy(fs_type) || matches!(fs_type, "vfat" | "exfat" | "swap")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_target_must_be_at_least_as_large() {
+        assert_eq!(validate_copy_target(1024, 1024), Ok(()));
+        assert_eq!(validate_copy_target(1024, 4096), Ok(()));
+        assert!(validate_copy_target(4096, 1024).is_err());
+    }
+
+    #[test]
+    fn fat_copies_keep_their_size() {
+        assert!(grows_after_copy("ext4"));
+        assert!(grows_after_copy("ntfs"));
+        assert!(!grows_after_copy("vfat"));
+        assert!(gets_new_uuid("vfat"));
+        assert!(!gets_new_uuid("iso9660"));
+    }
+}
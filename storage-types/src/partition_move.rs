@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Moving a partition to another place on its disk
+//!
+//! The partition keeps its size and contents; only its start changes,
+//! within the free space on either side of it. Its contents are copied in
+//! pieces first, then the table entry is rewritten.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ByteRange, GPT_ALIGNMENT_BYTES, PartitionLayout};
+
+/// Where a partition can move to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveBounds {
+    /// Partition device, e.g. "/dev/sda2"
+    pub partition: String,
+    /// Current start in bytes
+    pub start: u64,
+    pub size: u64,
+    pub sector_size: u64,
+    /// Earliest start, right after the previous partition
+    pub min_start: u64,
+    /// Latest start, leaving the partition right before the next one
+    pub max_start: u64,
+}
+
+impl MoveBounds {
+    /// Bounds of the `partition` at `range` on a disk laid out as `layout`
+    pub fn new(partition: &str, layout: &PartitionLayout, range: &ByteRange) -> Self {
+        let space = layout.space_around(range);
+        Self {
+            partition: partition.to_string(),
+            start: range.start,
+            size: range.size(),
+            sector_size: layout.sector_size,
+            min_start: space.start,
+            max_start: space.end.saturating_sub(range.size()).max(space.start),
+        }
+    }
+
+    /// Free space before the partition where it is now
+    pub fn free_before(&self) -> u64 {
+        self.start - self.min_start
+    }
+
+    /// Free space the partition can be moved across
+    pub fn free_total(&self) -> u64 {
+        self.max_start - self.min_start
+    }
+
+    /// Start that leaves about `free_before` bytes free before the
+    /// partition, on the 1 MiB boundary at or before it when there is one;
+    /// the current start for the space before it now
+    pub fn start_after(&self, free_before: u64) -> u64 {
+        let start = self.min_start + free_before.min(self.free_total());
+        let start = start - start % self.sector_size.max(1);
+        let aligned = start - start % GPT_ALIGNMENT_BYTES;
+        if start == self.start {
+            start
+        } else if aligned >= self.min_start {
+            aligned
+        } else {
+            start
+        }
+    }
+
+    /// Check that the partition can start at `new_start`
+    pub fn validate(&self, new_start: u64) -> Result<(), String> {
+        if !new_start.is_multiple_of(self.sector_size.max(1)) {
+            return Err(format!(
+                "The new start must be a multiple of the {}-byte sector size",
+                self.sector_size
+            ));
+        }
+        if new_start < self.min_start || new_start > self.max_start {
+            return Err(format!(
+                "The partition can only start between byte {} and byte {}",
+                self.min_start, self.max_start
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Pieces of at most `chunk` bytes, relative to the partition, in which to
+/// copy a partition of `size` bytes from `from` to `to`
+///
+/// Where the old and new places overlap, copying from the end nearest the
+/// destination first never overwrites a piece before it is copied.
+pub fn move_pieces(from: u64, to: u64, size: u64, chunk: u64) -> Vec<ByteRange> {
+    let chunk = chunk.max(1);
+    let mut pieces: Vec<ByteRange> = (0..size.div_ceil(chunk))
+        .map(|index| ByteRange {
+            start: index * chunk,
+            end: ((index + 1) * chunk).min(size),
+        })
+        .collect();
+    if to > from {
+        pieces.reverse();
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn bounds() -> MoveBounds {
+        let range = ByteRange {
+            start: 10 * MIB,
+            end: 20 * MIB,
+        };
+        let layout = PartitionLayout {
+            table_type: Some("gpt".to_string()),
+            sector_size: 512,
+            usable: ByteRange {
+                start: 34 * 512,
+                end: 100 * MIB,
+            },
+            partitions: vec![
+                ByteRange {
+                    start: MIB,
+                    end: 5 * MIB,
+                },
+                range,
+                ByteRange {
+                    start: 50 * MIB,
+                    end: 60 * MIB,
+                },
+            ],
+        };
+        MoveBounds::new("/dev/sda2", &layout, &range)
+    }
+
+    #[test]
+    fn bounds_span_the_free_space_around() {
+        let bounds = bounds();
+        assert_eq!(bounds.min_start, 5 * MIB);
+        assert_eq!(bounds.max_start, 40 * MIB);
+        assert_eq!(bounds.free_before(), 5 * MIB);
+        assert_eq!(bounds.free_total(), 35 * MIB);
+
+        assert_eq!(bounds.start_after(0), 5 * MIB);
+        assert_eq!(bounds.start_after(bounds.free_before()), bounds.start);
+        assert_eq!(bounds.start_after(3 * MIB + 700), 8 * MIB);
+        assert_eq!(bounds.start_after(u64::MAX), 40 * MIB);
+
+        assert_eq!(bounds.validate(8 * MIB), Ok(()));
+        assert!(bounds.validate(8 * MIB + 100).is_err());
+        assert!(bounds.validate(4 * MIB).is_err());
+        assert!(bounds.validate(41 * MIB).is_err());
+    }
+
+    #[test]
+    fn pieces_never_overwrite_what_is_still_to_copy() {
+        let forward = move_pieces(10, 4, 10, 4);
+        assert_eq!(
+            forward,
+            vec![
+                ByteRange { start: 0, end: 4 },
+                ByteRange { start: 4, end: 8 },
+                ByteRange { start: 8, end: 10 },
+            ]
+        );
+        let backward = move_pieces(4, 10, 10, 4);
+        assert_eq!(backward.first(), Some(&ByteRange { start: 8, end: 10 }));
+        assert_eq!(backward.last(), Some(&ByteRange { start: 0, end: 4 }));
+    }
+}
@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Where new partitions go
+//!
+//! GPT and DOS tables differ in where partitions may lie and how many they
+//! hold. A request for a new partition is moved to the next 1 MiB boundary
+//! and trimmed to the free space it starts in, the way UDisks and parted
+//! place partitions, so the segments the UI offers and the partitions the
+//! backends create agree.
+
+use crate::common::{ByteRange, GPT_ALIGNMENT_BYTES};
+use crate::disk::DiskInfo;
+use crate::partition::PartitionInfo;
+
+/// DOS tables leave the first MiB to the MBR and boot loaders
+pub const DOS_RESERVED_START_BYTES: u64 = GPT_ALIGNMENT_BYTES;
+
+/// DOS entries address 2^32 sectors of 512 bytes
+pub const DOS_MAX_END_BYTES: u64 = (1 << 32) * 512;
+
+/// Primary partitions of a DOS table; extended partitions aren't created
+pub const DOS_MAX_PARTITIONS: usize = 4;
+
+/// Entries of a standard GPT partition array
+pub const GPT_MAX_PARTITIONS: usize = 128;
+
+/// Partition sizes are kept to multiples of the largest common sector
+/// size, so they suit 4Kn disks too
+pub const PARTITION_SIZE_GRANULARITY: u64 = 4096;
+
+/// The part of a disk of `size` bytes partitions may occupy, by table type
+///
+/// GPT disks whose headers couldn't be read keep 1 MiB free at either end.
+pub fn usable_range(
+    size: u64,
+    table_type: &str,
+    gpt_usable_range: Option<ByteRange>,
+) -> Option<ByteRange> {
+    match table_type {
+        "gpt" => gpt_usable_range.or_else(|| {
+            (size > 2 * GPT_ALIGNMENT_BYTES).then(|| ByteRange {
+                start: GPT_ALIGNMENT_BYTES,
+                end: size - GPT_ALIGNMENT_BYTES,
+            })
+        }),
+        "dos" => (size > DOS_RESERVED_START_BYTES).then(|| ByteRange {
+            start: DOS_RESERVED_START_BYTES,
+            end: size.min(DOS_MAX_END_BYTES),
+        }),
+        _ => None,
+    }
+}
+
+/// The range a partition asked for at `offset` with `size` bytes gets
+///
+/// The start moves to the next 1 MiB boundary inside the usable range; the
+/// end stops at the next partition or the end of the usable range. Errors
+/// say why no partition fits.
+pub fn place_partition(
+    disk: &DiskInfo,
+    partitions: &[PartitionInfo],
+    offset: u64,
+    size: u64,
+) -> Result<ByteRange, String> {
+    let table_type = disk
+        .partition_table_type
+        .as_deref()
+        .ok_or_else(|| "The disk has no partition table".to_string())?;
+    let usable = usable_range(disk.size, table_type, disk.gpt_usable_range)
+        .ok_or_else(|| "The disk is too small for partitions".to_string())?;
+    let limit = match table_type {
+        "dos" => DOS_MAX_PARTITIONS,
+        _ => GPT_MAX_PARTITIONS,
+    };
+    if partitions.len() >= limit {
+        return Err(format!(
+            "A {table_type} partition table holds at most {limit} partitions"
+        ));
+    }
+
+    let start = offset
+        .max(usable.start)
+        .next_multiple_of(GPT_ALIGNMENT_BYTES);
+    if let Some(partition) = partitions
+        .iter()
+        .find(|p| p.offset <= start && start < p.offset.saturating_add(p.size))
+    {
+        return Err(format!(
+            "The space at {start} belongs to partition {}",
+            partition.number
+        ));
+    }
+    let free_end = partitions
+        .iter()
+        .map(|p| p.offset)
+        .filter(|p_offset| *p_offset > start)
+        .fold(usable.end, u64::min);
+    let end = offset.saturating_add(size).min(free_end);
+    let size = end.saturating_sub(start) / PARTITION_SIZE_GRANULARITY * PARTITION_SIZE_GRANULARITY;
+    if size == 0 {
+        return Err(format!("There is no free space at {start}"));
+    }
+    Ok(ByteRange {
+        start,
+        end: start + size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn disk(size: u64, table_type: &str) -> DiskInfo {
+        DiskInfo {
+            device: "/dev/sdz".to_string(),
+            id: String::new(),
+            model: String::new(),
+            serial: String::new(),
+            vendor: String::new(),
+            revision: String::new(),
+            size,
+            connection_bus: String::new(),
+            rotation_rate: None,
+            logical_sector_size: 512,
+            physical_sector_size: 512,
+            removable: false,
+            ejectable: false,
+            media_removable: false,
+            media_available: true,
+            optical: false,
+            optical_blank: false,
+            read_only: false,
+            can_power_off: false,
+            virtual_disk: false,
+            discard_supported: false,
+            rotational: false,
+            is_loop: false,
+            backing_file: None,
+            partition_table_type: Some(table_type.to_string()),
+            gpt_usable_range: None,
+        }
+    }
+
+    fn partition(number: u32, offset: u64, size: u64) -> PartitionInfo {
+        PartitionInfo {
+            device: format!("/dev/sdz{number}"),
+            number,
+            parent_path: "/dev/sdz".to_string(),
+            size,
+            offset,
+            type_id: String::new(),
+            type_name: String::new(),
+            flags: 0,
+            name: String::new(),
+            uuid: String::new(),
+            table_type: String::new(),
+            has_filesystem: false,
+            filesystem_type: None,
+            mount_points: Vec::new(),
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn aligns_and_trims_to_free_space() {
+        let disk = disk(100 * MIB, "gpt");
+        let partitions = [partition(1, 10 * MIB, 10 * MIB)];
+        let placed = place_partition(&disk, &partitions, 17 * 1024, 50 * MIB).unwrap();
+        assert_eq!(
+            placed,
+            ByteRange {
+                start: MIB,
+                end: 10 * MIB
+            }
+        );
+
+        let placed = place_partition(&disk, &partitions, 20 * MIB, u64::MAX).unwrap();
+        assert_eq!(
+            placed,
+            ByteRange {
+                start: 20 * MIB,
+                end: 99 * MIB
+            }
+        );
+
+        assert!(place_partition(&disk, &partitions, 15 * MIB, MIB).is_err());
+    }
+
+    #[test]
+    fn limits_dos_tables() {
+        let big = disk(3 * DOS_MAX_END_BYTES, "dos");
+        let placed = place_partition(&big, &[], 0, u64::MAX).unwrap();
+        assert_eq!(placed.start, DOS_RESERVED_START_BYTES);
+        assert_eq!(placed.end, DOS_MAX_END_BYTES);
+
+        let full: Vec<_> = (1..=4)
+            .map(|n| partition(n, n as u64 * 10 * MIB, MIB))
+            .collect();
+        assert!(place_partition(&disk(100 * MIB, "dos"), &full, 60 * MIB, MIB).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Deferring scheduled jobs on battery power
+//!
+//! Jobs the service starts on its own, like periodic SMART self-tests, wait
+//! while a laptop runs on battery, or only while its charge is low, as
+//! their [`PowerPolicy`] says. A deferred job is retried on the next check;
+//! the user can also run it anyway.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Kinds of jobs the service runs on a schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ScheduledJob {
+    /// Periodic SMART self-tests
+    SelfTest,
+    /// Syncing a secondary EFI System Partition
+    EspSync,
+}
+
+impl ScheduledJob {
+    pub const ALL: [ScheduledJob; 2] = [ScheduledJob::SelfTest, ScheduledJob::EspSync];
+}
+
+/// Power supply of the system, as UPower reports it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// Combined charge of all batteries, when there are any
+    pub percentage: Option<f64>,
+}
+
+/// When a kind of job waits for mains power
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerPolicy {
+    /// Wait while on battery at all
+    pub defer_on_battery: bool,
+    /// Otherwise, wait while on battery with less charge than this
+    pub min_charge_percent: Option<u8>,
+}
+
+impl Default for PowerPolicy {
+    fn default() -> Self {
+        Self {
+            defer_on_battery: true,
+            min_charge_percent: None,
+        }
+    }
+}
+
+impl PowerPolicy {
+    /// Why a job has to wait in `state`, if it does
+    ///
+    /// Jobs only ever wait while on battery: a low charge while plugged in
+    /// is going up.
+    pub fn deferral(&self, state: &PowerState) -> Option<DeferralReason> {
+        if !state.on_battery {
+            return None;
+        }
+        if self.defer_on_battery {
+            return Some(DeferralReason::OnBattery);
+        }
+        let min = self.min_charge_percent?;
+        let percentage = state.percentage?;
+        (percentage < f64::from(min)).then_some(DeferralReason::LowCharge {
+            percentage: percentage.round() as u8,
+        })
+    }
+}
+
+/// Power policies, by kind of job
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerSettings {
+    pub policies: BTreeMap<ScheduledJob, PowerPolicy>,
+}
+
+impl PowerSettings {
+    /// Policy of jobs of `job`
+    pub fn policy(&self, job: ScheduledJob) -> PowerPolicy {
+        self.policies.get(&job).copied().unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self
+            .policies
+            .values()
+            .find_map(|policy| policy.min_charge_percent.filter(|min| *min > 100))
+        {
+            Some(min) => Err(format!("Charge threshold {min}% is above 100%")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Why a scheduled job waits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeferralReason {
+    OnBattery,
+    /// On battery with this much charge left, below the policy's minimum
+    LowCharge {
+        percentage: u8,
+    },
+}
+
+/// A scheduled job that was due but waits for power
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredJob {
+    pub job: ScheduledJob,
+    /// What the job runs on, e.g. a drive ID or ESP partition UUID
+    pub target: String,
+    /// Display name of the target
+    pub label: String,
+    pub reason: DeferralReason,
+    /// Seconds since epoch when the job was first deferred
+    pub since: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defers_only_on_battery() {
+        let battery = |percentage| PowerState {
+            on_battery: true,
+            percentage: Some(percentage),
+        };
+        let ac = PowerState {
+            on_battery: false,
+            percentage: Some(5.0),
+        };
+
+        let default = PowerPolicy::default();
+        assert_eq!(default.deferral(&ac), None);
+        assert_eq!(
+            default.deferral(&battery(90.0)),
+            Some(DeferralReason::OnBattery)
+        );
+
+        let threshold = PowerPolicy {
+            defer_on_battery: false,
+            min_charge_percent: Some(50),
+        };
+        assert_eq!(threshold.deferral(&battery(80.0)), None);
+        assert_eq!(
+            threshold.deferral(&battery(42.4)),
+            Some(DeferralReason::LowCharge { percentage: 42 })
+        );
+        assert_eq!(threshold.deferral(&ac), None);
+
+        let settings: PowerSettings =
+            serde_json::from_str(r#"{"policies":{"EspSync":{"defer_on_battery":false}}}"#).unwrap();
+        assert!(!settings.policy(ScheduledJob::EspSync).defer_on_battery);
+        assert!(settings.policy(ScheduledJob::SelfTest).defer_on_battery);
+        assert!(settings.validate().is_ok());
+    }
+}
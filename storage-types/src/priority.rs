@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! CPU and I/O priority of long-running operations
+//!
+//! Imaging a drive or scanning a large filesystem can keep the disk busy for
+//! hours. Each kind of operation runs with its own nice value and I/O
+//! scheduling class, configurable in the service, and a single operation
+//! can override it when it is started.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Kinds of long-running operations with their own priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum OperationKind {
+    /// Drive and partition backups to an image file
+    ImageBackup,
+    /// Restoring an image onto a drive or partition
+    ImageRestore,
+    /// Copying a partition onto another one
+    PartitionCopy,
+    /// Disk usage scans
+    UsageScan,
+    /// Filesystem defragmentation
+    Defragment,
+}
+
+impl OperationKind {
+    pub const ALL: [OperationKind; 5] = [
+        OperationKind::ImageBackup,
+        OperationKind::ImageRestore,
+        OperationKind::PartitionCopy,
+        OperationKind::UsageScan,
+        OperationKind::Defragment,
+    ];
+
+    /// Priority used unless the settings say otherwise
+    ///
+    /// Work the user is not waiting on runs in the idle I/O class; restores
+    /// and copies, which the user waits for, keep the normal priority.
+    pub fn default_priority(self) -> OperationPriority {
+        match self {
+            OperationKind::ImageBackup | OperationKind::UsageScan | OperationKind::Defragment => {
+                OperationPriority::IDLE
+            }
+            OperationKind::ImageRestore | OperationKind::PartitionCopy => OperationPriority::NORMAL,
+        }
+    }
+}
+
+/// I/O scheduling class, as set with `ionice`
+///
+/// The realtime class is left out: it can starve the rest of the system,
+/// which is what the setting is meant to prevent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IoClass {
+    /// Share disk time by level, 0 (highest) to 7
+    BestEffort,
+    /// Only get disk time when no other process asks for it
+    Idle,
+}
+
+/// CPU nice value and I/O class of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationPriority {
+    /// -20 (highest) to 19 (lowest)
+    pub nice: i32,
+    pub io_class: IoClass,
+    /// Level within the best-effort class; ignored by the idle class
+    pub io_level: u8,
+}
+
+impl OperationPriority {
+    /// What processes get by default
+    pub const NORMAL: OperationPriority = OperationPriority {
+        nice: 0,
+        io_class: IoClass::BestEffort,
+        io_level: 4,
+    };
+
+    /// Below other work, but still getting disk time on a busy system
+    pub const LOW: OperationPriority = OperationPriority {
+        nice: 10,
+        io_class: IoClass::BestEffort,
+        io_level: 7,
+    };
+
+    /// Only when the disk is otherwise idle
+    pub const IDLE: OperationPriority = OperationPriority {
+        nice: 10,
+        io_class: IoClass::Idle,
+        io_level: 7,
+    };
+
+    /// The presets offered in the UI, from highest to lowest
+    pub const PRESETS: [OperationPriority; 3] = [
+        OperationPriority::NORMAL,
+        OperationPriority::LOW,
+        OperationPriority::IDLE,
+    ];
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-20..=19).contains(&self.nice) {
+            return Err(format!("Nice value {} is not within -20 to 19", self.nice));
+        }
+        if self.io_level > 7 {
+            return Err(format!("I/O level {} is not within 0 to 7", self.io_level));
+        }
+        Ok(())
+    }
+
+    /// The value `ioprio_set` takes: class in the top bits, level below
+    pub fn ioprio(&self) -> i32 {
+        const IOPRIO_CLASS_SHIFT: i32 = 13;
+        match self.io_class {
+            IoClass::BestEffort => (2 << IOPRIO_CLASS_SHIFT) | i32::from(self.io_level.min(7)),
+            IoClass::Idle => 3 << IOPRIO_CLASS_SHIFT,
+        }
+    }
+}
+
+/// Configured priorities, by kind of operation
+///
+/// Only kinds that differ from their default are stored.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrioritySettings {
+    pub priorities: BTreeMap<OperationKind, OperationPriority>,
+}
+
+impl PrioritySettings {
+    /// Priority of operations of `kind`
+    pub fn priority(&self, kind: OperationKind) -> OperationPriority {
+        self.priorities
+            .get(&kind)
+            .copied()
+            .unwrap_or_else(|| kind.default_priority())
+    }
+
+    /// Run operations of `kind` with `priority`
+    pub fn set_priority(&mut self, kind: OperationKind, priority: OperationPriority) {
+        if priority == kind.default_priority() {
+            self.priorities.remove(&kind);
+        } else {
+            self.priorities.insert(kind, priority);
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.priorities.values().try_for_each(|p| p.validate())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_priorities_and_ioprio() {
+        let mut settings = PrioritySettings::default();
+        assert_eq!(
+            settings.priority(OperationKind::ImageBackup).io_class,
+            IoClass::Idle
+        );
+        assert_eq!(
+            settings.priority(OperationKind::ImageRestore),
+            OperationPriority::NORMAL
+        );
+
+        settings.set_priority(OperationKind::ImageRestore, OperationPriority::LOW);
+        settings.set_priority(OperationKind::UsageScan, OperationPriority::IDLE);
+        assert_eq!(settings.priorities.len(), 1);
+        let json = serde_json::to_string(&settings).unwrap();
+        let parsed: PrioritySettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed.priority(OperationKind::ImageRestore),
+            OperationPriority::LOW
+        );
+
+        assert_eq!(OperationPriority::NORMAL.ioprio(), 0x4004);
+        assert_eq!(OperationPriority::IDLE.ioprio(), 0x6000);
+        let invalid = OperationPriority {
+            nice: 25,
+            ..OperationPriority::NORMAL
+        };
+        assert!(invalid.validate().is_err());
+        assert!(OperationPriority::LOW.validate().is_ok());
+    }
+}
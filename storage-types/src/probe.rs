@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Filesystem signatures
+//!
+//! Identifies the filesystem or LUKS container at the start of a device
+//! from its superblock, the way blkid does, without libblkid or root. Used
+//! to search free space for lost partitions, and to name the contents of
+//! devices UDisks hasn't probed yet, such as a partition formatted by
+//! another tool a moment ago.
+
+/// Bytes read from the start of a device, enough for the btrfs superblock
+/// at 64 KiB
+pub const SIGNATURE_PROBE_SIZE: usize = 0x10000 + 0x1000;
+
+/// A filesystem found by its superblock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilesystemSignature {
+    /// Type as blkid names it, e.g. "ext4" or "crypto_LUKS"
+    pub fs_type: String,
+    /// Size the filesystem records for itself, if any
+    pub size: Option<u64>,
+    pub label: Option<String>,
+    /// UUID or serial number, formatted as blkid shows it
+    pub uuid: Option<String>,
+}
+
+/// The filesystem or LUKS header at the start of `data`, the first
+/// [`SIGNATURE_PROBE_SIZE`] bytes of a device
+pub fn probe_filesystem_signature(data: &[u8]) -> Option<FilesystemSignature> {
+    probe_ext(data)
+        .or_else(|| probe_xfs(data))
+        .or_else(|| probe_btrfs(data))
+        .or_else(|| probe_ntfs(data))
+        .or_else(|| probe_exfat(data))
+        .or_else(|| probe_fat(data))
+        .or_else(|| probe_luks(data))
+        .or_else(|| probe_swap(data))
+        // Zeroed size fields are left from a wiped filesystem
+        .filter(|signature| signature.size != Some(0))
+}
+
+fn probe_ext(data: &[u8]) -> Option<FilesystemSignature> {
+    let sb = data.get(1024..2048)?;
+    if le_u16(sb, 0x38)? != 0xEF53 || le_u16(sb, 0x5A)? != 0 {
+        // Backup superblocks carry their block group number
+        return None;
+    }
+    let log_block_size = le_u32(sb, 0x18)?;
+    if log_block_size > 6 {
+        return None;
+    }
+    let incompat = le_u32(sb, 0x60)?;
+    let mut blocks = le_u32(sb, 0x04)? as u64;
+    if incompat & 0x80 != 0 {
+        blocks |= (le_u32(sb, 0x150)? as u64) << 32;
+    }
+    let fs_type = if incompat & (0x40 | 0x80 | 0x200) != 0 {
+        "ext4"
+    } else if le_u32(sb, 0x5C)? & 0x4 != 0 {
+        "ext3"
+    } else {
+        "ext2"
+    };
+    Some(FilesystemSignature {
+        fs_type: fs_type.to_string(),
+        size: Some(blocks * (1024 << log_block_size)),
+        label: text(sb.get(0x78..0x88)?),
+        uuid: uuid(sb.get(0x68..0x78)?),
+    })
+}
+
+fn probe_xfs(data: &[u8]) -> Option<FilesystemSignature> {
+    if data.get(0..4)? != b"XFSB" {
+        return None;
+    }
+    let block_size = be_u32(data, 4)? as u64;
+    let blocks = u64::from_be_bytes(data.get(8..16)?.try_into().ok()?);
+    Some(FilesystemSignature {
+        fs_type: "xfs".to_string(),
+        size: Some(blocks * block_size),
+        label: text(data.get(108..120)?),
+        uuid: uuid(data.get(32..48)?),
+    })
+}
+
+fn probe_btrfs(data: &[u8]) -> Option<FilesystemSignature> {
+    let sb = data.get(0x10000..0x11000)?;
+    if sb.get(0x40..0x48)? != b"_BHRfS_M" {
+        return None;
+    }
+    // Size of this device, from the device item, rather than the total
+    // of a multi-device filesystem
+    Some(FilesystemSignature {
+        fs_type: "btrfs".to_string(),
+        size: Some(le_u64(sb, 0xC9 + 8)?),
+        label: text(sb.get(0x12B..0x22B)?),
+        uuid: uuid(sb.get(0x20..0x30)?),
+    })
+}
+
+fn probe_ntfs(data: &[u8]) -> Option<FilesystemSignature> {
+    if data.get(3..11)? != b"NTFS    " || !boot_signature(data) {
+        return None;
+    }
+    let sector_size = le_u16(data, 0x0B)? as u64;
+    // The backup boot sector follows the last sector of the volume
+    let sectors = le_u64(data, 0x28)? + 1;
+    Some(FilesystemSignature {
+        fs_type: "ntfs".to_string(),
+        size: Some(sectors * sector_size),
+        // The label is a file in the MFT
+        label: None,
+        uuid: Some(format!("{:016X}", le_u64(data, 0x48)?)),
+    })
+}
+
+fn probe_exfat(data: &[u8]) -> Option<FilesystemSignature> {
+    if data.get(3..11)? != b"EXFAT   " || !boot_signature(data) {
+        return None;
+    }
+    let shift = *data.get(108)? as u32;
+    if !(9..=12).contains(&shift) {
+        return None;
+    }
+    Some(FilesystemSignature {
+        fs_type: "exfat".to_string(),
+        size: Some(le_u64(data, 72)? << shift),
+        // The label is an entry of the root directory
+        label: None,
+        uuid: Some(serial(le_u32(data, 100)?)),
+    })
+}
+
+fn probe_fat(data: &[u8]) -> Option<FilesystemSignature> {
+    // FAT32 and FAT12/16 boot sectors keep their labels and serials in
+    // different places
+    let (label, serial_offset) = if data.get(0x52..0x5A)? == b"FAT32   " {
+        (data.get(0x47..0x52)?, 0x43)
+    } else if data.get(0x36..0x39)? == b"FAT" {
+        (data.get(0x2B..0x36)?, 0x27)
+    } else {
+        return None;
+    };
+    if !boot_signature(data) {
+        return None;
+    }
+    let sector_size = le_u16(data, 0x0B)? as u64;
+    let sectors = match le_u16(data, 0x13)? {
+        0 => le_u32(data, 0x20)? as u64,
+        sectors => sectors as u64,
+    };
+    Some(FilesystemSignature {
+        fs_type: "vfat".to_string(),
+        size: Some(sectors * sector_size),
+        label: text(label).filter(|label| label != "NO NAME"),
+        uuid: Some(serial(le_u32(data, serial_offset)?)),
+    })
+}
+
+fn probe_luks(data: &[u8]) -> Option<FilesystemSignature> {
+    if data.get(0..6)? != b"LUKS\xba\xbe" {
+        return None;
+    }
+    // Only LUKS2 headers carry a label
+    let label = match u16::from_be_bytes(data.get(6..8)?.try_into().ok()?) {
+        2 => text(data.get(24..72)?),
+        _ => None,
+    };
+    Some(FilesystemSignature {
+        fs_type: "crypto_LUKS".to_string(),
+        size: None,
+        label,
+        // Both versions keep it as text
+        uuid: text(data.get(168..208)?),
+    })
+}
+
+fn probe_swap(data: &[u8]) -> Option<FilesystemSignature> {
+    let magic = data.get(4086..4096)?;
+    if magic != b"SWAPSPACE2" && magic != b"SWAP-SPACE" {
+        return None;
+    }
+    let last_page = le_u32(data, 1028)? as u64;
+    Some(FilesystemSignature {
+        fs_type: "swap".to_string(),
+        size: Some((last_page + 1) * 4096),
+        label: text(data.get(1052..1068)?),
+        uuid: uuid(data.get(1036..1052)?),
+    })
+}
+
+/// Whether a boot sector ends with 0x55AA
+fn boot_signature(data: &[u8]) -> bool {
+    data.get(510..512) == Some(&[0x55, 0xAA])
+}
+
+/// A NUL or space padded label, `None` when empty
+fn text(bytes: &[u8]) -> Option<String> {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    let text = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// A 16-byte UUID in its usual hyphenated form, `None` when all zero
+fn uuid(bytes: &[u8]) -> Option<String> {
+    if bytes.len() != 16 || bytes.iter().all(|b| *b == 0) {
+        return None;
+    }
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+/// A FAT or exFAT volume serial, e.g. "1A2B-3C4D"
+fn serial(value: u32) -> String {
+    format!("{:04X}-{:04X}", value >> 16, value & 0xFFFF)
+}
+
+fn le_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn le_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn finds_filesystem_signatures() {
+        let mut ext4 = vec![0u8; SIGNATURE_PROBE_SIZE];
+        ext4[1024 + 0x04..1024 + 0x08].copy_from_slice(&262144u32.to_le_bytes());
+        ext4[1024 + 0x18] = 2; // 4 KiB blocks
+        ext4[1024 + 0x38..1024 + 0x3A].copy_from_slice(&0xEF53u16.to_le_bytes());
+        ext4[1024 + 0x60] = 0x40; // extents
+        ext4[1024 + 0x68..1024 + 0x78].copy_from_slice(&[0xAB; 16]);
+        ext4[1024 + 0x78..1024 + 0x7C].copy_from_slice(b"data");
+        let signature = probe_filesystem_signature(&ext4).unwrap();
+        assert_eq!(signature.fs_type, "ext4");
+        assert_eq!(signature.size, Some(1024 * MIB));
+        assert_eq!(signature.label.as_deref(), Some("data"));
+        assert_eq!(
+            signature.uuid.as_deref(),
+            Some("abababab-abab-abab-abab-abababababab")
+        );
+
+        // A backup superblock is not the start of a filesystem
+        ext4[1024 + 0x5A] = 1;
+        assert_eq!(probe_filesystem_signature(&ext4), None);
+
+        let mut luks = vec![0u8; SIGNATURE_PROBE_SIZE];
+        luks[..8].copy_from_slice(b"LUKS\xba\xbe\x00\x02");
+        luks[24..30].copy_from_slice(b"secret");
+        let signature = probe_filesystem_signature(&luks).unwrap();
+        assert_eq!(signature.fs_type, "crypto_LUKS");
+        assert_eq!(signature.size, None);
+        assert_eq!(signature.label.as_deref(), Some("secret"));
+        assert_eq!(signature.uuid, None);
+
+        assert_eq!(
+            probe_filesystem_signature(&vec![0u8; SIGNATURE_PROBE_SIZE]),
+            None
+        );
+    }
+
+    #[test]
+    fn formats_fat_serials() {
+        let mut fat32 = vec![0u8; SIGNATURE_PROBE_SIZE];
+        fat32[0x0B..0x0D].copy_from_slice(&512u16.to_le_bytes());
+        fat32[0x20..0x24].copy_from_slice(&65536u32.to_le_bytes());
+        fat32[0x43..0x47].copy_from_slice(&0x1A2B_3C4Du32.to_le_bytes());
+        fat32[0x47..0x52].copy_from_slice(b"NO NAME    ");
+        fat32[0x52..0x5A].copy_from_slice(b"FAT32   ");
+        fat32[510..512].copy_from_slice(&[0x55, 0xAA]);
+        let signature = probe_filesystem_signature(&fat32).unwrap();
+        assert_eq!(signature.fs_type, "vfat");
+        assert_eq!(signature.size, Some(32 * MIB));
+        assert_eq!(signature.label, None);
+        assert_eq!(signature.uuid.as_deref(), Some("1A2B-3C4D"));
+    }
+}
@@ -0,0 +1,635 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! MD-RAID types
+//!
+//! Array health and synchronisation details read from `/sys/block/mdX/md`.
+
+use serde::{Deserialize, Serialize};
+
+/// State of one member device of an MD array
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RaidMemberState {
+    /// Active and fully synchronised
+    InSync,
+    /// Marked faulty, no longer used by the array
+    Faulty,
+    /// Hot spare without a slot
+    Spare,
+    /// Has a slot but is still being recovered
+    Rebuilding,
+    /// Any other combination of state flags
+    #[default]
+    Unknown,
+}
+
+/// A member device of an MD array
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RaidMember {
+    /// Member block device (e.g., "/dev/sda1")
+    pub device: String,
+
+    /// Role in the array, None for spares
+    pub slot: Option<u32>,
+
+    /// Summarised state
+    pub state: RaidMemberState,
+
+    /// Raw comma-separated flags from the member's `state` file
+    pub flags: Vec<String>,
+
+    /// Read errors corrected on this member
+    pub errors: u64,
+
+    /// Size of the member device in bytes, 0 if unknown
+    pub size: u64,
+}
+
+/// Detailed state of an MD-RAID array
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RaidDetail {
+    /// Array device (e.g., "/dev/md0")
+    pub device: String,
+
+    /// RAID level (e.g., "raid1", "raid5")
+    pub level: String,
+
+    /// `array_state` ("clean", "active", "degraded", ...)
+    pub array_state: String,
+
+    /// Current `sync_action` ("idle", "resync", "recover", "check", "repair", ...)
+    pub sync_action: String,
+
+    /// Progress of the running sync action in percent, None when idle
+    pub sync_completed_percent: Option<f64>,
+
+    /// Sectors found inconsistent by the last check or repair
+    pub mismatch_count: Option<u64>,
+
+    /// Write-intent bitmap location ("internal", a file, or an offset), None without a bitmap
+    pub bitmap: Option<String>,
+
+    /// Members the array should have
+    pub raid_disks: u32,
+
+    /// Chunk size in bytes, 0 for levels without striping
+    pub chunk_size: u64,
+
+    /// Space used on each member in bytes; a replacement must be at least this big
+    pub component_size: u64,
+
+    /// Missing or failed members
+    pub degraded: u32,
+
+    /// Member devices, active slots first
+    pub members: Vec<RaidMember>,
+}
+
+/// Member operations valid for one member in the array's current state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RaidMemberCapabilities {
+    /// Mark the member faulty (`mdadm --fail`)
+    pub can_fail: bool,
+    /// Take a faulty or spare member out of the array (`mdadm --remove`)
+    pub can_remove: bool,
+    /// Copy onto a new device, then fail the member (`mdadm --replace`)
+    pub can_replace: bool,
+}
+
+impl RaidDetail {
+    /// Whether a sync, recovery, check or reshape is running
+    pub fn is_syncing(&self) -> bool {
+        !matches!(self.sync_action.as_str(), "" | "idle" | "frozen")
+    }
+
+    /// Whether the level keeps data when a member fails
+    pub fn is_redundant(&self) -> bool {
+        matches!(
+            self.level.as_str(),
+            "raid1" | "raid4" | "raid5" | "raid6" | "raid10"
+        )
+    }
+
+    /// Member failures the array survives in the worst case
+    ///
+    /// raid10 is counted as surviving one failure, since a second one may
+    /// hit the same mirror.
+    pub fn failures_tolerated(&self) -> u32 {
+        match self.level.as_str() {
+            "raid1" => self.raid_disks.saturating_sub(1),
+            "raid4" | "raid5" | "raid10" => 1,
+            "raid6" => 2,
+            _ => 0,
+        }
+    }
+
+    /// Whether the array is running and accepts member changes
+    fn is_running(&self) -> bool {
+        !matches!(
+            self.array_state.as_str(),
+            "" | "clear" | "inactive" | "suspended" | "readonly"
+        )
+    }
+
+    /// Whether a device can be added (as a spare, or to rebuild a missing slot)
+    pub fn can_add_member(&self) -> bool {
+        self.is_running() && self.is_redundant()
+    }
+
+    /// Operations valid for `member` in the array's current state
+    pub fn member_capabilities(&self, member: &RaidMember) -> RaidMemberCapabilities {
+        if !self.is_running() {
+            return RaidMemberCapabilities::default();
+        }
+
+        let keeps_redundancy = self.degraded < self.failures_tolerated();
+        RaidMemberCapabilities {
+            can_fail: self.is_redundant()
+                && match member.state {
+                    RaidMemberState::InSync => keeps_redundancy,
+                    RaidMemberState::Rebuilding | RaidMemberState::Spare => true,
+                    RaidMemberState::Faulty | RaidMemberState::Unknown => false,
+                },
+            can_remove: matches!(
+                member.state,
+                RaidMemberState::Faulty | RaidMemberState::Spare
+            ),
+            can_replace: self.is_redundant()
+                && !self.is_syncing()
+                && member.state == RaidMemberState::InSync,
+        }
+    }
+
+    /// Health events between a `previous` observation of this array and now
+    pub fn health_events(&self, previous: &RaidDetail) -> Vec<RaidHealthEvent> {
+        let mut events = Vec::new();
+        if self.degraded > previous.degraded {
+            events.push(RaidHealthEvent::Degraded);
+        }
+        let newly_failed = self.members.iter().any(|member| {
+            member.state == RaidMemberState::Faulty
+                && previous
+                    .member(&member.device)
+                    .is_none_or(|before| before.state != RaidMemberState::Faulty)
+        });
+        if newly_failed {
+            events.push(RaidHealthEvent::MemberFailed);
+        }
+        if self.degraded == 0 && previous.degraded > 0 {
+            events.push(RaidHealthEvent::Recovered);
+        }
+        events
+    }
+
+    /// Find a member by device path
+    pub fn member(&self, device: &str) -> Option<&RaidMember> {
+        self.members.iter().find(|member| member.device == device)
+    }
+}
+
+/// Health change of an MD array between two observations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RaidHealthEvent {
+    /// The array lost redundancy (more members missing than before)
+    Degraded,
+    /// A member was marked faulty
+    MemberFailed,
+    /// All members are present again
+    Recovered,
+}
+
+impl RaidHealthEvent {
+    /// Stable identifier used in D-Bus signals and hook scripts
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Degraded => "degraded",
+            Self::MemberFailed => "member-failed",
+            Self::Recovered => "recovered",
+        }
+    }
+
+    /// Parse from [`Self::as_str`]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "degraded" => Some(Self::Degraded),
+            "member-failed" => Some(Self::MemberFailed),
+            "recovered" => Some(Self::Recovered),
+            _ => None,
+        }
+    }
+}
+
+/// A spare held by one array of a spare group
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct GroupSpare {
+    /// Spare device (e.g., "/dev/sde1")
+    pub device: String,
+
+    /// Size of the spare in bytes
+    pub size: u64,
+
+    /// Array the spare is currently attached to
+    pub held_by: String,
+
+    /// Arrays of the group whose members the spare is big enough to replace
+    pub can_serve: Vec<String>,
+}
+
+/// Arrays sharing their spares through an mdadm `spare-group`
+///
+/// `mdadm --monitor` moves a spare from one array of the group to another
+/// when that array loses a member.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SpareGroup {
+    /// Group name from mdadm.conf
+    pub name: String,
+
+    /// Member arrays
+    pub arrays: Vec<String>,
+
+    /// Spares available to the whole group
+    pub spares: Vec<GroupSpare>,
+}
+
+/// Spare pool configuration of the system
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SparePoolConfig {
+    /// Spare groups with their arrays and spares
+    pub groups: Vec<SpareGroup>,
+
+    /// Blank disks that are plugged in become spares (mdadm `POLICY action=spare`)
+    pub auto_add_new_disks: bool,
+}
+
+/// Build spare groups from array details and their `(array, group)` assignments
+pub fn spare_groups(details: &[RaidDetail], assignments: &[(String, String)]) -> Vec<SpareGroup> {
+    let mut groups: Vec<SpareGroup> = Vec::new();
+    for (array, name) in assignments {
+        let index = match groups.iter().position(|group| &group.name == name) {
+            Some(index) => index,
+            None => {
+                groups.push(SpareGroup {
+                    name: name.clone(),
+                    ..Default::default()
+                });
+                groups.len() - 1
+            }
+        };
+        groups[index].arrays.push(array.clone());
+    }
+
+    for group in &mut groups {
+        let arrays: Vec<&RaidDetail> = details
+            .iter()
+            .filter(|detail| group.arrays.contains(&detail.device))
+            .collect();
+        for array in &arrays {
+            for spare in array
+                .members
+                .iter()
+                .filter(|member| member.state == RaidMemberState::Spare)
+            {
+                group.spares.push(GroupSpare {
+                    device: spare.device.clone(),
+                    size: spare.size,
+                    held_by: array.device.clone(),
+                    can_serve: arrays
+                        .iter()
+                        .filter(|target| target.component_size <= spare.size)
+                        .map(|target| target.device.clone())
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    groups
+}
+
+/// A requested reshape of an MD array; `None` keeps the current value
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RaidReshape {
+    /// New number of active members
+    pub raid_devices: Option<u32>,
+
+    /// New RAID level (e.g., "raid6")
+    pub level: Option<String>,
+
+    /// New chunk size in KiB
+    pub chunk_kib: Option<u32>,
+
+    /// File outside the array holding the critical section during the reshape
+    pub backup_file: Option<String>,
+}
+
+/// Level changes `mdadm --grow --level` can perform in place
+const LEVEL_MIGRATIONS: &[(&str, &str)] = &[
+    ("raid0", "raid4"),
+    ("raid0", "raid5"),
+    ("raid0", "raid10"),
+    ("raid1", "raid5"),
+    ("raid4", "raid5"),
+    ("raid5", "raid1"),
+    ("raid5", "raid4"),
+    ("raid5", "raid6"),
+    ("raid6", "raid5"),
+    ("raid10", "raid0"),
+];
+
+/// Whether mdadm can migrate an array from one level to another
+pub fn level_migration_supported(from: &str, to: &str) -> bool {
+    LEVEL_MIGRATIONS.contains(&(from, to))
+}
+
+/// Fewest active members a level can run with
+fn min_raid_devices(level: &str) -> u32 {
+    match level {
+        "raid1" | "raid10" | "raid0" => 2,
+        "raid4" | "raid5" => 3,
+        "raid6" => 4,
+        _ => 1,
+    }
+}
+
+impl RaidDetail {
+    /// Spare members available to grow onto
+    pub fn spare_count(&self) -> u32 {
+        self.members
+            .iter()
+            .filter(|member| member.state == RaidMemberState::Spare)
+            .count() as u32
+    }
+
+    /// Whether the reshape restripes existing data, so an interruption can
+    /// corrupt the array unless the critical section is backed up
+    pub fn reshape_is_risky(&self, reshape: &RaidReshape) -> bool {
+        let level_changes = reshape
+            .level
+            .as_deref()
+            .is_some_and(|level| level != self.level);
+        let chunk_changes = reshape
+            .chunk_kib
+            .is_some_and(|kib| u64::from(kib) * 1024 != self.chunk_size);
+        level_changes || chunk_changes
+    }
+
+    /// Check a reshape against the array's current state
+    pub fn validate_reshape(&self, reshape: &RaidReshape) -> Result<(), String> {
+        if !self.is_running() {
+            return Err(format!("{} is not running", self.device));
+        }
+        if self.is_syncing() {
+            return Err(format!(
+                "{} is busy ({}); wait for it to finish",
+                self.device, self.sync_action
+            ));
+        }
+
+        let level = reshape.level.as_deref().unwrap_or(&self.level);
+        if level != self.level && !level_migration_supported(&self.level, level) {
+            return Err(format!(
+                "Changing {} to {} is not supported",
+                self.level, level
+            ));
+        }
+
+        let raid_devices = reshape.raid_devices.unwrap_or(self.raid_disks);
+        if raid_devices < min_raid_devices(level) {
+            return Err(format!(
+                "{} needs at least {} members",
+                level,
+                min_raid_devices(level)
+            ));
+        }
+        if raid_devices < self.raid_disks && level != "raid1" {
+            return Err(
+                "Reducing the number of members requires shrinking the array size first"
+                    .to_string(),
+            );
+        }
+        let added = raid_devices.saturating_sub(self.raid_disks);
+        if added > self.spare_count() {
+            return Err(format!(
+                "Growing to {} members needs {} spare devices, {} available",
+                raid_devices,
+                added,
+                self.spare_count()
+            ));
+        }
+
+        if let Some(kib) = reshape.chunk_kib {
+            if matches!(level, "raid1") {
+                return Err("raid1 has no chunk size".to_string());
+            }
+            if kib < 4 || !kib.is_power_of_two() {
+                return Err(format!("Invalid chunk size: {} KiB", kib));
+            }
+        }
+
+        if reshape.raid_devices.is_none()
+            && reshape.level.as_deref().is_none_or(|l| l == self.level)
+            && !self.reshape_is_risky(reshape)
+        {
+            return Err("The reshape does not change anything".to_string());
+        }
+
+        if self.reshape_is_risky(reshape) {
+            match reshape.backup_file.as_deref() {
+                None | Some("") => {
+                    return Err(
+                        "A backup file outside the array is required for this reshape".to_string(),
+                    );
+                }
+                Some(path) if !path.starts_with('/') => {
+                    return Err(format!("Backup file must be an absolute path: {}", path));
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(device: &str, state: RaidMemberState) -> RaidMember {
+        RaidMember {
+            device: device.to_string(),
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn gates_member_operations_on_array_state() {
+        let mut detail = RaidDetail {
+            level: "raid5".to_string(),
+            array_state: "clean".to_string(),
+            sync_action: "idle".to_string(),
+            raid_disks: 3,
+            members: vec![
+                member("/dev/sda1", RaidMemberState::InSync),
+                member("/dev/sdb1", RaidMemberState::InSync),
+                member("/dev/sdc1", RaidMemberState::Faulty),
+                member("/dev/sdd1", RaidMemberState::Spare),
+            ],
+            ..Default::default()
+        };
+        let sda = detail.members[0].clone();
+        let sdc = detail.members[2].clone();
+        let sdd = detail.members[3].clone();
+
+        assert!(detail.can_add_member());
+        let caps = detail.member_capabilities(&sda);
+        assert!(caps.can_fail && caps.can_replace && !caps.can_remove);
+        assert!(detail.member_capabilities(&sdc).can_remove);
+        assert!(detail.member_capabilities(&sdd).can_remove);
+
+        // A degraded raid5 must not lose another member
+        detail.degraded = 1;
+        assert!(!detail.member_capabilities(&sda).can_fail);
+
+        detail.sync_action = "recover".to_string();
+        assert!(!detail.member_capabilities(&sda).can_replace);
+
+        detail.level = "raid0".to_string();
+        assert!(!detail.can_add_member());
+        assert_eq!(
+            detail.member_capabilities(&sda),
+            RaidMemberCapabilities::default()
+        );
+
+        detail.array_state = "inactive".to_string();
+        assert!(!detail.member_capabilities(&sdc).can_remove);
+    }
+
+    #[test]
+    fn validates_reshapes() {
+        let mut detail = RaidDetail {
+            device: "/dev/md0".to_string(),
+            level: "raid5".to_string(),
+            array_state: "clean".to_string(),
+            sync_action: "idle".to_string(),
+            raid_disks: 3,
+            chunk_size: 512 * 1024,
+            members: vec![
+                member("/dev/sda1", RaidMemberState::InSync),
+                member("/dev/sdb1", RaidMemberState::InSync),
+                member("/dev/sdc1", RaidMemberState::InSync),
+                member("/dev/sdd1", RaidMemberState::Spare),
+            ],
+            ..Default::default()
+        };
+
+        let grow = RaidReshape {
+            raid_devices: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(detail.validate_reshape(&grow), Ok(()));
+        assert!(!detail.reshape_is_risky(&grow));
+        assert!(
+            detail
+                .validate_reshape(&RaidReshape {
+                    raid_devices: Some(5),
+                    ..Default::default()
+                })
+                .is_err()
+        );
+
+        let mut to_raid6 = RaidReshape {
+            raid_devices: Some(4),
+            level: Some("raid6".to_string()),
+            ..Default::default()
+        };
+        assert!(detail.reshape_is_risky(&to_raid6));
+        assert!(detail.validate_reshape(&to_raid6).is_err());
+        to_raid6.backup_file = Some("/root/md0-reshape.backup".to_string());
+        assert_eq!(detail.validate_reshape(&to_raid6), Ok(()));
+
+        let same_chunk = RaidReshape {
+            chunk_kib: Some(512),
+            ..Default::default()
+        };
+        assert!(detail.validate_reshape(&same_chunk).is_err());
+
+        assert!(
+            detail
+                .validate_reshape(&RaidReshape {
+                    level: Some("raid10".to_string()),
+                    backup_file: Some("/root/backup".to_string()),
+                    ..Default::default()
+                })
+                .is_err()
+        );
+
+        detail.sync_action = "check".to_string();
+        assert!(detail.validate_reshape(&grow).is_err());
+    }
+
+    #[test]
+    fn detects_health_transitions() {
+        let healthy = RaidDetail {
+            level: "raid1".to_string(),
+            raid_disks: 2,
+            members: vec![
+                member("/dev/sda1", RaidMemberState::InSync),
+                member("/dev/sdb1", RaidMemberState::InSync),
+            ],
+            ..Default::default()
+        };
+        let mut failed = healthy.clone();
+        failed.degraded = 1;
+        failed.members[1].state = RaidMemberState::Faulty;
+
+        assert!(healthy.health_events(&healthy).is_empty());
+        assert_eq!(
+            failed.health_events(&healthy),
+            [RaidHealthEvent::Degraded, RaidHealthEvent::MemberFailed]
+        );
+        assert!(failed.health_events(&failed).is_empty());
+        assert_eq!(healthy.health_events(&failed), [RaidHealthEvent::Recovered]);
+        assert_eq!(
+            RaidHealthEvent::parse(RaidHealthEvent::MemberFailed.as_str()),
+            Some(RaidHealthEvent::MemberFailed)
+        );
+    }
+
+    #[test]
+    fn builds_spare_groups() {
+        let mut spare = member("/dev/sde1", RaidMemberState::Spare);
+        spare.size = 500;
+        let details = vec![
+            RaidDetail {
+                device: "/dev/md0".to_string(),
+                component_size: 400,
+                members: vec![member("/dev/sda1", RaidMemberState::InSync), spare],
+                ..Default::default()
+            },
+            RaidDetail {
+                device: "/dev/md1".to_string(),
+                component_size: 800,
+                ..Default::default()
+            },
+            RaidDetail {
+                device: "/dev/md2".to_string(),
+                component_size: 300,
+                ..Default::default()
+            },
+        ];
+        let assignments = vec![
+            ("/dev/md0".to_string(), "pool".to_string()),
+            ("/dev/md1".to_string(), "pool".to_string()),
+            ("/dev/md2".to_string(), "other".to_string()),
+        ];
+
+        let groups = spare_groups(&details, &assignments);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].arrays, ["/dev/md0", "/dev/md1"]);
+        assert_eq!(groups[0].spares.len(), 1);
+        assert_eq!(groups[0].spares[0].held_by, "/dev/md0");
+        assert_eq!(groups[0].spares[0].can_serve, ["/dev/md0"]);
+        assert!(groups[1].spares.is_empty());
+    }
+}
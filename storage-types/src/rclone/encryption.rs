@@ -0,0 +1,54 @@
+use super::ConfigScope;
+use serde::{Deserialize, Serialize};
+
+/// Line rclone starts the encrypted part of a configuration file with
+pub const ENCRYPTED_CONFIG_MARKER: &str = "RCLONE_ENCRYPT_V0:";
+
+/// Whether the contents of an rclone configuration file are encrypted with
+/// `rclone config encryption`
+pub fn is_encrypted_config(contents: &str) -> bool {
+    contents
+        .lines()
+        .any(|line| line.trim() == ENCRYPTED_CONFIG_MARKER)
+}
+
+/// Encryption state of the rclone configuration of a scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigEncryptionStatus {
+    pub scope: ConfigScope,
+    pub encrypted: bool,
+    /// The service holds the password for the caller, so the remotes can be
+    /// read and changed
+    pub unlocked: bool,
+}
+
+impl ConfigEncryptionStatus {
+    /// Encrypted and the password is still needed
+    pub fn is_locked(&self) -> bool {
+        self.encrypted && !self.unlocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_encrypted_configs() {
+        let encrypted = "# Encrypted rclone configuration File\n\n\
+                         RCLONE_ENCRYPT_V0:\n\
+                         XkSvHIs6Hd2pzKsXsAoqg7QSx\n";
+        assert!(is_encrypted_config(encrypted));
+        assert!(!is_encrypted_config(
+            "[nas]\ntype = smb\nhost = nas.local\n"
+        ));
+        assert!(!is_encrypted_config(""));
+
+        let status = ConfigEncryptionStatus {
+            scope: ConfigScope::User,
+            encrypted: true,
+            unlocked: false,
+        };
+        assert!(status.is_locked());
+    }
+}
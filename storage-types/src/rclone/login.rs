@@ -0,0 +1,83 @@
+use super::ConfigScope;
+use serde::{Deserialize, Serialize};
+
+/// State of the unit that mounts a remote automatically: when the user's
+/// session starts for user remotes, when the system boots for system ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoginMountState {
+    /// The remote is not mounted automatically
+    #[default]
+    Disabled,
+    /// Enabled, but not running (e.g., stopped since the session started)
+    Inactive,
+    Starting,
+    Active,
+    Stopping,
+    Failed,
+}
+
+impl LoginMountState {
+    /// Derive the state from the `UnitFileState` and `ActiveState`
+    /// properties of the unit, as shown by `systemctl show`
+    pub fn from_systemd(unit_file_state: &str, active_state: &str) -> Self {
+        if !matches!(unit_file_state, "enabled" | "enabled-runtime") {
+            return Self::Disabled;
+        }
+        match active_state {
+            "active" => Self::Active,
+            "activating" | "reloading" => Self::Starting,
+            "deactivating" => Self::Stopping,
+            "failed" => Self::Failed,
+            _ => Self::Inactive,
+        }
+    }
+
+    pub fn is_enabled(self) -> bool {
+        self != Self::Disabled
+    }
+}
+
+/// Automatic mount of a configured remote
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoginMountStatus {
+    pub remote_name: String,
+    pub scope: ConfigScope,
+    pub state: LoginMountState,
+    /// Last message logged by the mount when it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_systemd_properties() {
+        assert_eq!(
+            LoginMountState::from_systemd("disabled", "failed"),
+            LoginMountState::Disabled
+        );
+        assert_eq!(
+            LoginMountState::from_systemd("", "inactive"),
+            LoginMountState::Disabled
+        );
+        assert_eq!(
+            LoginMountState::from_systemd("enabled", "active"),
+            LoginMountState::Active
+        );
+        assert_eq!(
+            LoginMountState::from_systemd("enabled-runtime", "activating"),
+            LoginMountState::Starting
+        );
+        assert_eq!(
+            LoginMountState::from_systemd("enabled", "failed"),
+            LoginMountState::Failed
+        );
+        assert_eq!(
+            LoginMountState::from_systemd("enabled", "inactive"),
+            LoginMountState::Inactive
+        );
+    }
+}
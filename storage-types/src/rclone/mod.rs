@@ -5,17 +5,30 @@
 //! This module defines the types used for RClone configuration and mount state
 //! across the storage-service, storage-sys, and storage-app crates.
 
+mod encryption;
+mod login;
 mod mount;
 mod provider_catalog;
 mod remote;
 mod results;
 mod scope;
+mod transfer;
+mod usage;
 
+pub use encryption::{ConfigEncryptionStatus, ENCRYPTED_CONFIG_MARKER, is_encrypted_config};
+pub use login::{LoginMountState, LoginMountStatus};
 pub use mount::{MountStatus, MountType};
 pub use provider_catalog::{
     RcloneProvider, RcloneProviderOption, RcloneProviderOptionExample, rclone_provider,
     rclone_providers, supported_remote_types,
 };
-pub use remote::{NetworkMount, RemoteConfig, RemoteConfigList};
+pub use remote::{
+    MOUNT_POINT_OPTION, NetworkMount, RemoteConfig, RemoteConfigList, validate_mount_point,
+};
 pub use results::{MountStatusResult, TestResult};
 pub use scope::ConfigScope;
+pub use transfer::{
+    TransferEndpoint, TransferJob, TransferMode, TransferProgress, TransferRequest, TransferState,
+    is_valid_bandwidth_limit,
+};
+pub use usage::RemoteUsage;
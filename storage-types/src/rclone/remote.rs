@@ -1,7 +1,11 @@
-use super::{ConfigScope, MountStatus, MountType};
+use super::{ConfigEncryptionStatus, ConfigScope, MountStatus, MountType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+
+/// Key of the custom mount point in a remote's rclone.conf section; rclone
+/// ignores options its backends do not know
+pub const MOUNT_POINT_OPTION: &str = "cosmic_mount_point";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteConfig {
@@ -12,6 +16,10 @@ pub struct RemoteConfig {
     pub options: HashMap<String, String>,
     #[serde(default)]
     pub has_secrets: bool,
+    /// Where the remote is mounted; the default location of its scope when
+    /// `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount_point: Option<PathBuf>,
 }
 
 impl RemoteConfig {
@@ -22,11 +30,22 @@ impl RemoteConfig {
             scope,
             options: HashMap::new(),
             has_secrets: false,
+            mount_point: None,
         }
     }
 
     pub fn mount_point(&self) -> PathBuf {
-        self.scope.mount_point(&self.name)
+        self.mount_point
+            .clone()
+            .unwrap_or_else(|| self.scope.mount_point(&self.name))
+    }
+
+    /// Move a custom mount point read from rclone.conf out of `options`
+    pub fn with_mount_point_option(mut self) -> Self {
+        if let Some(path) = self.options.remove(MOUNT_POINT_OPTION) {
+            self.mount_point = Some(PathBuf::from(path));
+        }
+        self
     }
 
     pub fn validate_name(&self) -> Result<(), String> {
@@ -47,6 +66,42 @@ impl RemoteConfig {
     }
 }
 
+/// Check that `mount_point` is usable next to the mount points of `others`
+/// (remote name and mount point): it must be absolute without `..`, and
+/// neither be, contain, nor lie inside the mount point of another remote
+pub fn validate_mount_point(
+    mount_point: &Path,
+    others: &[(String, PathBuf)],
+) -> Result<(), String> {
+    if !mount_point.is_absolute()
+        || mount_point
+            .components()
+            .any(|component| component == Component::ParentDir)
+    {
+        return Err(format!(
+            "Mount point must be an absolute path: {}",
+            mount_point.display()
+        ));
+    }
+
+    for (name, other) in others {
+        if mount_point == other {
+            return Err(format!(
+                "{} is already the mount point of {name}",
+                mount_point.display()
+            ));
+        }
+        if mount_point.starts_with(other) || other.starts_with(mount_point) {
+            return Err(format!(
+                "{} overlaps the mount point of {name} ({})",
+                mount_point.display(),
+                other.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkMount {
     pub remote_name: String,
@@ -73,6 +128,10 @@ pub struct RemoteConfigList {
     pub remotes: Vec<RemoteConfig>,
     pub user_config_path: Option<PathBuf>,
     pub system_config_path: Option<PathBuf>,
+    /// Encryption state of the configuration files that exist; the remotes
+    /// of locked ones are missing from `remotes`
+    #[serde(default)]
+    pub encryption: Vec<ConfigEncryptionStatus>,
 }
 
 impl RemoteConfigList {
@@ -81,6 +140,42 @@ impl RemoteConfigList {
             remotes: Vec::new(),
             user_config_path: None,
             system_config_path: None,
+            encryption: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_colliding_mount_points() {
+        let others = vec![
+            ("photos".to_string(), PathBuf::from("/home/u/mnt/photos")),
+            ("nas".to_string(), PathBuf::from("/srv/nas")),
+        ];
+
+        assert!(validate_mount_point(Path::new("/home/u/Cloud"), &others).is_ok());
+        assert!(validate_mount_point(Path::new("/home/u/mnt/photos2"), &others).is_ok());
+        assert!(validate_mount_point(Path::new("relative/dir"), &others).is_err());
+        assert!(validate_mount_point(Path::new("/home/u/../x"), &others).is_err());
+        assert!(validate_mount_point(Path::new("/srv/nas"), &others).is_err());
+        assert!(validate_mount_point(Path::new("/srv/nas/backup"), &others).is_err());
+        assert!(validate_mount_point(Path::new("/home/u/mnt"), &others).is_err());
+    }
+
+    #[test]
+    fn reads_custom_mount_point_from_options() {
+        let mut config =
+            RemoteConfig::new("nas".to_string(), "smb".to_string(), ConfigScope::System);
+        assert_eq!(config.mount_point(), PathBuf::from("/mnt/rclone/nas"));
+
+        config
+            .options
+            .insert(MOUNT_POINT_OPTION.to_string(), "/srv/nas".to_string());
+        let config = config.with_mount_point_option();
+        assert_eq!(config.mount_point(), PathBuf::from("/srv/nas"));
+        assert!(config.options.is_empty());
+    }
+}
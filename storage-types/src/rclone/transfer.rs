@@ -0,0 +1,262 @@
+use super::ConfigScope;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, PathBuf};
+
+/// How a transfer treats the destination
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferMode {
+    /// Copy new and changed files (`rclone copy`)
+    #[default]
+    Copy,
+    /// Make the destination identical to the source, deleting files that
+    /// are not in the source (`rclone sync`)
+    Sync,
+}
+
+impl TransferMode {
+    pub fn subcommand(self) -> &'static str {
+        match self {
+            Self::Copy => "copy",
+            Self::Sync => "sync",
+        }
+    }
+}
+
+/// Source or destination of a transfer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TransferEndpoint {
+    /// Directory on this machine
+    Local { path: PathBuf },
+    /// Path inside a configured remote; empty for its root
+    Remote { name: String, path: String },
+}
+
+impl TransferEndpoint {
+    /// The endpoint as rclone takes it on the command line
+    pub fn to_rclone_arg(&self) -> String {
+        match self {
+            Self::Local { path } => path.display().to_string(),
+            Self::Remote { name, path } => format!("{name}:{path}"),
+        }
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Self::Remote { .. })
+    }
+}
+
+impl std::fmt::Display for TransferEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_rclone_arg())
+    }
+}
+
+/// A copy or sync between a local path and a remote, or between two remotes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferRequest {
+    /// Configuration the remotes of the transfer belong to
+    pub scope: ConfigScope,
+    pub source: TransferEndpoint,
+    pub destination: TransferEndpoint,
+    #[serde(default)]
+    pub mode: TransferMode,
+    /// Bandwidth limit in rclone's `--bwlimit` format (e.g., "10M" or
+    /// "4M:1M" for upload:download), unlimited if unset
+    #[serde(default)]
+    pub bandwidth_limit: Option<String>,
+    /// Only report what would be transferred
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl TransferRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.source.is_remote() && !self.destination.is_remote() {
+            return Err("A transfer needs a remote as source or destination".to_string());
+        }
+        if self.source == self.destination {
+            return Err("Source and destination are the same".to_string());
+        }
+        for endpoint in [&self.source, &self.destination] {
+            match endpoint {
+                TransferEndpoint::Local { path } => {
+                    if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+                        return Err(format!("{} is not an absolute path", path.display()));
+                    }
+                }
+                TransferEndpoint::Remote { name, path } => {
+                    if name.is_empty() {
+                        return Err("Choose a remote".to_string());
+                    }
+                    if path.split('/').any(|part| part == "..") {
+                        return Err(format!("{path} may not contain \"..\""));
+                    }
+                }
+            }
+        }
+        if let Some(limit) = &self.bandwidth_limit
+            && !is_valid_bandwidth_limit(limit)
+        {
+            return Err(format!("{limit} is not a valid bandwidth limit"));
+        }
+        Ok(())
+    }
+}
+
+/// Whether `limit` is a single rclone bandwidth limit: "off", or a rate such
+/// as "512K", "1.5M" or "4M:1M" for separate upload and download rates
+pub fn is_valid_bandwidth_limit(limit: &str) -> bool {
+    let rate = |rate: &str| {
+        if rate.eq_ignore_ascii_case("off") {
+            return true;
+        }
+        let number = rate.trim_end_matches(|c: char| "bBkKmMgGtTpP".contains(c));
+        rate.len() - number.len() <= 1 && number.parse::<f64>().is_ok_and(|n| n >= 0.0)
+    };
+    let mut rates = limit.trim().split(':');
+    match (rates.next(), rates.next(), rates.next()) {
+        (Some(single), None, None) => rate(single),
+        (Some(upload), Some(download), None) => rate(upload) && rate(download),
+        _ => false,
+    }
+}
+
+/// Progress of a transfer, from rclone's statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransferProgress {
+    pub bytes: u64,
+    pub total_bytes: u64,
+    pub files: u64,
+    pub total_files: u64,
+    pub speed_bytes_per_sec: u64,
+    pub eta_secs: Option<u64>,
+    pub errors: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RcloneStats {
+    #[serde(default)]
+    bytes: u64,
+    #[serde(default)]
+    total_bytes: u64,
+    #[serde(default)]
+    transfers: u64,
+    #[serde(default)]
+    total_transfers: u64,
+    #[serde(default)]
+    speed: f64,
+    eta: Option<f64>,
+    #[serde(default)]
+    errors: u64,
+}
+
+#[derive(Deserialize)]
+struct RcloneLogLine {
+    stats: Option<RcloneStats>,
+}
+
+impl TransferProgress {
+    /// Statistics in a line of rclone's JSON log (`--use-json-log`), if the
+    /// line has any
+    pub fn from_json_log(line: &str) -> Option<Self> {
+        let stats = serde_json::from_str::<RcloneLogLine>(line).ok()?.stats?;
+        Some(Self {
+            bytes: stats.bytes,
+            total_bytes: stats.total_bytes,
+            files: stats.transfers,
+            total_files: stats.total_transfers,
+            speed_bytes_per_sec: stats.speed as u64,
+            eta_secs: stats.eta.map(|eta| eta as u64),
+            errors: stats.errors,
+        })
+    }
+
+    /// Share of the bytes transferred, once the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        (self.total_bytes > 0).then(|| (self.bytes as f64 / self.total_bytes as f64).min(1.0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl TransferState {
+    pub fn is_finished(self) -> bool {
+        self != Self::Running
+    }
+}
+
+/// A running or finished transfer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransferJob {
+    pub id: String,
+    pub request: TransferRequest,
+    pub state: TransferState,
+    pub progress: TransferProgress,
+    /// Seconds since epoch
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_stats_from_json_log() {
+        let line = r#"{"level":"info","msg":"Transferred: 1 MiB / 4 MiB, 25%","source":"accounting/stats.go:482","stats":{"bytes":1048576,"checks":0,"elapsedTime":2.1,"errors":0,"eta":6,"speed":524288.5,"totalBytes":4194304,"totalTransfers":3,"transfers":1},"time":"2026-10-16T10:00:00Z"}"#;
+        let progress = TransferProgress::from_json_log(line).unwrap();
+        assert_eq!(progress.bytes, 1048576);
+        assert_eq!(progress.files, 1);
+        assert_eq!(progress.total_files, 3);
+        assert_eq!(progress.speed_bytes_per_sec, 524288);
+        assert_eq!(progress.eta_secs, Some(6));
+        assert_eq!(progress.fraction(), Some(0.25));
+
+        let message = r#"{"level":"error","msg":"Failed to copy: permission denied"}"#;
+        assert_eq!(TransferProgress::from_json_log(message), None);
+    }
+
+    #[test]
+    fn validates_requests() {
+        let mut request = TransferRequest {
+            scope: ConfigScope::User,
+            source: TransferEndpoint::Local {
+                path: PathBuf::from("/home/u/Photos"),
+            },
+            destination: TransferEndpoint::Remote {
+                name: "nas".to_string(),
+                path: "backup/photos".to_string(),
+            },
+            mode: TransferMode::Sync,
+            bandwidth_limit: Some("4M:1.5M".to_string()),
+            dry_run: true,
+        };
+        assert_eq!(request.validate(), Ok(()));
+        assert_eq!(request.destination.to_rclone_arg(), "nas:backup/photos");
+
+        request.bandwidth_limit = Some("fast".to_string());
+        assert!(request.validate().is_err());
+        request.bandwidth_limit = None;
+
+        request.destination = TransferEndpoint::Local {
+            path: PathBuf::from("/tmp"),
+        };
+        assert!(request.validate().is_err());
+
+        assert!(is_valid_bandwidth_limit("off"));
+        assert!(is_valid_bandwidth_limit("512K"));
+        assert!(!is_valid_bandwidth_limit("-1M"));
+        assert!(!is_valid_bandwidth_limit("1M:2M:3M"));
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// Storage quota and usage of a remote, from `rclone about`
+///
+/// Backends report only what they know, so every size may be missing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteUsage {
+    /// Quota of the account, in bytes
+    pub total: Option<u64>,
+    pub used: Option<u64>,
+    pub free: Option<u64>,
+    /// Bytes in the trash
+    pub trashed: Option<u64>,
+    /// Bytes used by other services of the account (e.g., mail)
+    pub other: Option<u64>,
+    /// Number of objects stored
+    pub objects: Option<u64>,
+    /// Seconds since epoch the usage was queried at
+    #[serde(default)]
+    pub checked_at: u64,
+}
+
+impl RemoteUsage {
+    /// Parse the output of `rclone about --json`
+    pub fn from_about_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Bytes used, derived from the free space if not reported
+    pub fn used_bytes(&self) -> Option<u64> {
+        self.used
+            .or_else(|| Some(self.total?.saturating_sub(self.free?)))
+    }
+
+    /// Share of the quota in use, if the remote has a quota
+    pub fn used_fraction(&self) -> Option<f64> {
+        let total = self.total.filter(|&total| total > 0)?;
+        Some((self.used_bytes()? as f64 / total as f64).min(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_about_output() {
+        let usage = RemoteUsage::from_about_json(
+            r#"{"total":16106127360,"used":4026531840,"trashed":1048576,"other":0,"free":12079595520}"#,
+        )
+        .unwrap();
+        assert_eq!(usage.total, Some(16106127360));
+        assert_eq!(usage.objects, None);
+        assert_eq!(usage.used_fraction(), Some(0.25));
+
+        let derived = RemoteUsage::from_about_json(r#"{"total":100,"free":40}"#).unwrap();
+        assert_eq!(derived.used_bytes(), Some(60));
+
+        let unlimited = RemoteUsage::from_about_json(r#"{"used":512,"objects":3}"#).unwrap();
+        assert_eq!(unlimited.used_fraction(), None);
+    }
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Filesystems the kernel switched to read-only
+//!
+//! A filesystem that hits an error may be made read-only by the kernel to
+//! protect it (e.g., ext4 mounted with `errors=remount-ro`, or btrfs after
+//! an aborted transaction). Such mounts show up as `ro` in `/proc/mounts`,
+//! and the kernel log says why.
+
+use serde::{Deserialize, Serialize};
+
+use crate::user_mount::unescape;
+
+/// A mounted filesystem that went read-only after an error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForcedReadOnly {
+    /// Mounted device, e.g. "/dev/sda1"
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    /// The kernel message that triggered the switch, if still in the kernel
+    /// log
+    pub kernel_error: Option<String>,
+}
+
+/// Read-only mounts of block devices in the contents of `/proc/mounts`, as
+/// candidates whose cause is still to be found
+pub fn parse_read_only_mounts(proc_mounts: &str) -> Vec<ForcedReadOnly> {
+    proc_mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (device, mount_point, fs_type, options) = (
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+            );
+            (device.starts_with("/dev/") && options.split(',').any(|option| option == "ro")).then(
+                || ForcedReadOnly {
+                    device: unescape(device),
+                    mount_point: unescape(mount_point),
+                    fs_type: fs_type.to_string(),
+                    kernel_error: None,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Why the kernel made the filesystem on `device_name` (e.g., "sda1" or
+/// "dm-0") read-only, or `None` if the kernel log does not say it did
+///
+/// The last error reported for the device before the switch is returned,
+/// or the message of the switch itself when there is none.
+pub fn find_forced_read_only(kernel_log: &str, device_name: &str) -> Option<String> {
+    let mentions_device = |line: &str| {
+        line.contains(&format!("({device_name})"))
+            || line.contains(&format!("(device {device_name})"))
+            || line.contains(&format!("(device {device_name} "))
+    };
+
+    let mut last_error = None;
+    let mut cause = None;
+    for line in kernel_log.lines() {
+        // Drop the "[  123.456789] " timestamp
+        let message = match line.trim_start().strip_prefix('[') {
+            Some(rest) => rest.split_once(']').map_or(line, |(_, message)| message),
+            None => line,
+        }
+        .trim();
+        if !mentions_device(message) {
+            continue;
+        }
+
+        let lower = message.to_lowercase();
+        if lower.contains("remounting filesystem read-only") || lower.contains("forced readonly") {
+            cause = Some(last_error.take().unwrap_or_else(|| message.to_string()));
+        } else if lower.contains("error") {
+            last_error = Some(message.to_string());
+        }
+    }
+    cause
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_filesystems_forced_read_only() {
+        let mounts = "\
+/dev/sda2 / ext4 rw,relatime 0 0
+/dev/sdb1 /media/u/My\\040Disk ext4 ro,nosuid,nodev,relatime,errors=remount-ro 0 0
+/dev/sr0 /media/u/CDROM iso9660 ro,nosuid,nodev 0 0
+tmpfs /tmp tmpfs ro,nosuid 0 0
+";
+        let candidates = parse_read_only_mounts(mounts);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].device, "/dev/sdb1");
+        assert_eq!(candidates[0].mount_point, "/media/u/My Disk");
+        assert_eq!(candidates[0].fs_type, "ext4");
+
+        let log = "\
+[   12.000000] EXT4-fs (sdb1): mounted filesystem with ordered data mode
+[  812.123456] EXT4-fs error (device sdb1): ext4_lookup:1855: inode #2: comm ls: deleted inode referenced: 12
+[  812.123500] EXT4-fs (sdb1): Remounting filesystem read-only
+[  900.000000] EXT4-fs error (device sdb10): ext4_find_entry:1463: inode #2: comm ls: reading directory lblock 0
+";
+        assert_eq!(
+            find_forced_read_only(log, "sdb1").as_deref(),
+            Some(
+                "EXT4-fs error (device sdb1): ext4_lookup:1855: inode #2: comm ls: deleted inode referenced: 12"
+            )
+        );
+        assert_eq!(find_forced_read_only(log, "sdb10"), None);
+        assert_eq!(find_forced_read_only(log, "sr0"), None);
+
+        let btrfs = "[ 5.0] BTRFS info (device dm-0 state EA): forced readonly\n";
+        assert_eq!(
+            find_forced_read_only(btrfs, "dm-0").as_deref(),
+            Some("BTRFS info (device dm-0 state EA): forced readonly")
+        );
+    }
+}
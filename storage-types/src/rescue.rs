@@ -0,0 +1,417 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Rescue imaging of failing drives
+//!
+//! A rescue copies what can still be read and records the state of every
+//! area of the drive in a map, in the mapfile format of GNU ddrescue. A
+//! rescue can be resumed from its map, and continued with ddrescue.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// State of an area of the drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RescueStatus {
+    /// Not read yet
+    NonTried,
+    /// A read failed somewhere in the area; its edges are still to be read
+    NonTrimmed,
+    /// Still to be read sector by sector
+    NonScraped,
+    /// Failed to read sector by sector
+    BadSector,
+    /// Copied to the image
+    Finished,
+}
+
+impl RescueStatus {
+    /// Status character in ddrescue maps
+    pub fn as_char(self) -> char {
+        match self {
+            Self::NonTried => '?',
+            Self::NonTrimmed => '*',
+            Self::NonScraped => '/',
+            Self::BadSector => '-',
+            Self::Finished => '+',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '?' => Some(Self::NonTried),
+            '*' => Some(Self::NonTrimmed),
+            '/' => Some(Self::NonScraped),
+            '-' => Some(Self::BadSector),
+            '+' => Some(Self::Finished),
+            _ => None,
+        }
+    }
+}
+
+/// Stage a rescue is in, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RescuePhase {
+    /// Reading large blocks, skipping past errors
+    Copying,
+    /// Reading the edges of failed blocks
+    Trimming,
+    /// Reading the rest of failed blocks sector by sector
+    Scraping,
+    /// Reading bad sectors again
+    Retrying,
+    Finished,
+}
+
+impl RescuePhase {
+    /// Current status character in ddrescue maps
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Copying => '?',
+            Self::Trimming => '*',
+            Self::Scraping => '/',
+            Self::Retrying => '-',
+            Self::Finished => '+',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '?' => Some(Self::Copying),
+            '*' => Some(Self::Trimming),
+            '/' => Some(Self::Scraping),
+            '-' => Some(Self::Retrying),
+            // Filling and generating modes of ddrescue are done with the drive
+            '+' | 'F' | 'G' => Some(Self::Finished),
+            _ => None,
+        }
+    }
+}
+
+/// Settings of a rescue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RescueOptions {
+    /// Times bad sectors are read again after scraping, alternating direction
+    pub retry_passes: u32,
+    /// Read failed blocks sector by sector; this can take very long on a
+    /// drive with many errors
+    pub scrape: bool,
+}
+
+impl Default for RescueOptions {
+    fn default() -> Self {
+        Self {
+            retry_passes: 1,
+            scrape: true,
+        }
+    }
+}
+
+/// An area of the drive, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RescueBlock {
+    pub pos: u64,
+    pub size: u64,
+    pub status: RescueStatus,
+}
+
+impl RescueBlock {
+    pub fn end(&self) -> u64 {
+        self.pos + self.size
+    }
+}
+
+/// State of a rescue: where it is, and the status of every area of the
+/// drive, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RescueMap {
+    pub current_pos: u64,
+    pub phase: RescuePhase,
+    /// Pass within the phase, from 1
+    pub pass: u32,
+    pub blocks: Vec<RescueBlock>,
+}
+
+impl RescueMap {
+    /// Map of a drive of `size` bytes that was not read yet
+    pub fn new(size: u64) -> Self {
+        Self {
+            current_pos: 0,
+            phase: RescuePhase::Copying,
+            pass: 1,
+            blocks: vec![RescueBlock {
+                pos: 0,
+                size,
+                status: RescueStatus::NonTried,
+            }],
+        }
+    }
+
+    /// Read a ddrescue mapfile
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        // "0x00120000     ?               1", without the pass before 1.20
+        let status_line = lines.next().ok_or("The map is empty")?;
+        let mut fields = status_line.split_whitespace();
+        let current_pos = fields
+            .next()
+            .and_then(parse_number)
+            .ok_or_else(|| format!("Invalid status line: {status_line}"))?;
+        let phase = fields
+            .next()
+            .and_then(single_char)
+            .and_then(RescuePhase::from_char)
+            .ok_or_else(|| format!("Invalid status line: {status_line}"))?;
+        let pass = fields.next().and_then(|f| f.parse().ok()).unwrap_or(1);
+
+        let mut blocks: Vec<RescueBlock> = Vec::new();
+        for line in lines {
+            let block = parse_block(line).ok_or_else(|| format!("Invalid block line: {line}"))?;
+            if blocks.last().is_some_and(|last| block.pos < last.end()) {
+                return Err(format!("Overlapping block: {line}"));
+            }
+            blocks.push(block);
+        }
+
+        Ok(Self {
+            current_pos,
+            phase,
+            pass,
+            blocks,
+        })
+    }
+
+    /// Bytes covered by the map
+    pub fn size(&self) -> u64 {
+        self.blocks.iter().map(|block| block.size).sum()
+    }
+
+    /// Areas with `status`, as position and size
+    pub fn areas(&self, status: RescueStatus) -> Vec<(u64, u64)> {
+        self.blocks
+            .iter()
+            .filter(|block| block.status == status)
+            .map(|block| (block.pos, block.size))
+            .collect()
+    }
+
+    pub fn bytes(&self, status: RescueStatus) -> u64 {
+        self.blocks
+            .iter()
+            .filter(|block| block.status == status)
+            .map(|block| block.size)
+            .sum()
+    }
+
+    /// Give the `size` bytes at `pos` `status`, merging them with
+    /// neighbouring areas of the same status
+    pub fn set(&mut self, pos: u64, size: u64, status: RescueStatus) {
+        if size == 0 {
+            return;
+        }
+        let end = pos + size;
+        let first = self.blocks.partition_point(|block| block.end() <= pos);
+        let last = self.blocks.partition_point(|block| block.pos < end);
+
+        let mut replacement = Vec::with_capacity(3);
+        if first < last && self.blocks[first].pos < pos {
+            let block = self.blocks[first];
+            replacement.push(RescueBlock {
+                size: pos - block.pos,
+                ..block
+            });
+        }
+        replacement.push(RescueBlock { pos, size, status });
+        if first < last && self.blocks[last - 1].end() > end {
+            let block = self.blocks[last - 1];
+            replacement.push(RescueBlock {
+                pos: end,
+                size: block.end() - end,
+                status: block.status,
+            });
+        }
+        let inserted = replacement.len();
+        self.blocks.splice(first..last, replacement);
+
+        // Only the inserted blocks and their neighbours can merge
+        let mut i = first.saturating_sub(1);
+        let mut stop = (first + inserted + 1).min(self.blocks.len());
+        while i + 1 < stop {
+            let (a, b) = (self.blocks[i], self.blocks[i + 1]);
+            if a.status == b.status && a.end() == b.pos {
+                self.blocks[i].size += b.size;
+                self.blocks.remove(i + 1);
+                stop -= 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    pub fn progress(&self) -> RescueProgress {
+        RescueProgress {
+            phase: self.phase,
+            pass: self.pass,
+            current_pos: self.current_pos,
+            total_bytes: self.size(),
+            rescued_bytes: self.bytes(RescueStatus::Finished),
+            non_tried_bytes: self.bytes(RescueStatus::NonTried),
+            unreadable_bytes: self.bytes(RescueStatus::NonTrimmed)
+                + self.bytes(RescueStatus::NonScraped)
+                + self.bytes(RescueStatus::BadSector),
+            bad_sector_bytes: self.bytes(RescueStatus::BadSector),
+        }
+    }
+}
+
+/// Writes the map as a ddrescue mapfile
+impl fmt::Display for RescueMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Mapfile. Created by cosmic-ext-storage")?;
+        writeln!(f, "# current_pos  current_status  current_pass")?;
+        writeln!(
+            f,
+            "{:#010x}     {}               {}",
+            self.current_pos,
+            self.phase.as_char(),
+            self.pass
+        )?;
+        writeln!(f, "#      pos        size  status")?;
+        for block in &self.blocks {
+            writeln!(
+                f,
+                "{:#010x}  {:#010x}  {}",
+                block.pos,
+                block.size,
+                block.status.as_char()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How far a rescue got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RescueProgress {
+    pub phase: RescuePhase,
+    pub pass: u32,
+    pub current_pos: u64,
+    pub total_bytes: u64,
+    /// Copied to the image
+    pub rescued_bytes: u64,
+    /// Not read yet
+    pub non_tried_bytes: u64,
+    /// Failed to read so far, including areas still to be trimmed, scraped
+    /// or retried
+    pub unreadable_bytes: u64,
+    /// Failed to read sector by sector; what remains lost unless a retry
+    /// succeeds
+    pub bad_sector_bytes: u64,
+}
+
+impl RescueProgress {
+    /// Share of the drive that was read, successfully or not
+    pub fn fraction_tried(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 1.0;
+        }
+        1.0 - self.non_tried_bytes as f64 / self.total_bytes as f64
+    }
+}
+
+/// A "pos size status" line of a ddrescue map
+fn parse_block(line: &str) -> Option<RescueBlock> {
+    let mut fields = line.split_whitespace();
+    let block = RescueBlock {
+        pos: parse_number(fields.next()?)?,
+        size: parse_number(fields.next()?)?,
+        status: RescueStatus::from_char(single_char(fields.next()?)?)?,
+    };
+    fields.next().is_none().then_some(block)
+}
+
+/// A number in a ddrescue map, hexadecimal with "0x" or decimal
+fn parse_number(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    let c = chars.next()?;
+    chars.next().is_none().then_some(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_writes_ddrescue_maps() {
+        let text = "\
+# Mapfile. Created by GNU ddrescue version 1.27
+# Command line: ddrescue /dev/sdb sdb.img sdb.map
+# current_pos  current_status  current_pass
+0x00120000     *               1
+#      pos        size  status
+0x00000000  0x00100000  +
+0x00100000  0x00020000  *
+0x00120000  0x000E0000  ?
+";
+        let map = RescueMap::parse(text).unwrap();
+        assert_eq!(map.current_pos, 0x120000);
+        assert_eq!(map.phase, RescuePhase::Trimming);
+        assert_eq!(map.size(), 0x200000);
+        assert_eq!(map.areas(RescueStatus::NonTrimmed), [(0x100000, 0x20000)]);
+        assert_eq!(RescueMap::parse(&map.to_string()).unwrap(), map);
+
+        // Maps of ddrescue before 1.20 have no pass
+        let old = RescueMap::parse("0x0 ? \n0 512 ?\n").unwrap();
+        assert_eq!(old.pass, 1);
+        assert_eq!(old.size(), 512);
+
+        assert!(RescueMap::parse("0x0 ?\n0x0 0x200 +\n0x100 0x200 -\n").is_err());
+    }
+
+    #[test]
+    fn marks_areas_and_merges_them() {
+        let mut map = RescueMap::new(4096);
+        map.set(0, 1024, RescueStatus::Finished);
+        map.set(2048, 512, RescueStatus::BadSector);
+        assert_eq!(map.blocks.len(), 4);
+        assert_eq!(map.bytes(RescueStatus::NonTried), 2560);
+
+        map.set(1024, 1024, RescueStatus::Finished);
+        map.set(2048, 512, RescueStatus::Finished);
+        assert_eq!(
+            map.blocks,
+            [
+                RescueBlock {
+                    pos: 0,
+                    size: 2560,
+                    status: RescueStatus::Finished
+                },
+                RescueBlock {
+                    pos: 2560,
+                    size: 1536,
+                    status: RescueStatus::NonTried
+                }
+            ]
+        );
+
+        map.set(3072, 512, RescueStatus::NonScraped);
+        let progress = map.progress();
+        assert_eq!(progress.rescued_bytes, 2560);
+        assert_eq!(progress.unreadable_bytes, 512);
+        assert_eq!(progress.non_tried_bytes, 1024);
+        assert_eq!(map.size(), 4096);
+    }
+}
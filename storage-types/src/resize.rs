@@ -0,0 +1,421 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Resizing a partition together with its filesystem
+//!
+//! A filesystem has to shrink before its partition does, and can only grow
+//! once the partition has. Which filesystems can do either, and whether they
+//! have to be mounted or unmounted for it, decides the sizes offered for a
+//! partition and the steps that take it there.
+
+use crate::common::GPT_ALIGNMENT_BYTES;
+use crate::disk::DiskInfo;
+use crate::partition::PartitionInfo;
+use crate::placement::{PARTITION_SIZE_GRANULARITY, usable_range};
+use serde::{Deserialize, Serialize};
+
+/// Share of its minimum size a shrunk filesystem keeps free, so it isn't
+/// left completely full
+pub const SHRINK_HEADROOM_PERCENT: u64 = 5;
+
+/// Whether a filesystem can change size, and in which state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeSupport {
+    Unsupported,
+    /// Only while unmounted
+    Offline,
+    /// Mounted or not; unmounted filesystems are mounted for it
+    Online,
+}
+
+/// How the `fs_type` filesystem can shrink
+pub fn shrink_support(fs_type: &str) -> ResizeSupport {
+    match fs_type {
+        "ext2" | "ext3" | "ext4" | "ntfs" => ResizeSupport::Offline,
+        "btrfs" => ResizeSupport::Online,
+        _ => ResizeSupport::Unsupported,
+    }
+}
+
+/// How the `fs_type` filesystem can grow
+pub fn grow_support(fs_type: &str) -> ResizeSupport {
+    match fs_type {
+        "ext3" | "ext4" | "xfs" | "btrfs" => ResizeSupport::Online,
+        "ext2" | "ntfs" => ResizeSupport::Offline,
+        _ => ResizeSupport::Unsupported,
+    }
+}
+
+/// Why a filesystem can't change size right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResizeBlocker {
+    /// The filesystem can't do it at all
+    Unsupported,
+    /// The filesystem has to be unmounted first
+    Mounted,
+    /// The filesystem couldn't tell how small it can get
+    UnknownMinimum,
+}
+
+fn blocker(support: ResizeSupport, mounted: bool) -> Option<ResizeBlocker> {
+    match support {
+        ResizeSupport::Unsupported => Some(ResizeBlocker::Unsupported),
+        ResizeSupport::Offline if mounted => Some(ResizeBlocker::Mounted),
+        _ => None,
+    }
+}
+
+/// Whether the `fs_type` filesystem can shrink in its current state, and so
+/// is worth asking for its minimum size
+pub fn can_shrink(fs_type: &str, mounted: bool) -> bool {
+    blocker(shrink_support(fs_type), mounted).is_none()
+}
+
+/// Sizes a partition can be resized to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResizeBounds {
+    /// Device path of the partition
+    pub partition: String,
+    pub filesystem_type: Option<String>,
+    /// Where the filesystem is mounted, if it is
+    pub mount_point: Option<String>,
+    pub current_bytes: u64,
+    /// Smallest size, the current one when the filesystem can't shrink
+    pub min_bytes: u64,
+    /// Largest size, up to the next partition or the end of the usable area
+    pub max_bytes: u64,
+    /// Why the partition can't shrink
+    pub shrink_blocker: Option<ResizeBlocker>,
+    /// Why the filesystem won't grow with the partition
+    pub grow_blocker: Option<ResizeBlocker>,
+}
+
+/// One step of a resize, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResizeStep {
+    ShrinkFilesystem {
+        size: u64,
+    },
+    ResizePartition {
+        size: u64,
+    },
+    /// Grow the filesystem to fill the partition
+    GrowFilesystem,
+}
+
+impl ResizeStep {
+    /// Phase reported while the step runs
+    pub fn phase(&self) -> &'static str {
+        match self {
+            Self::ShrinkFilesystem { .. } => "shrinking_filesystem",
+            Self::ResizePartition { .. } => "resizing",
+            Self::GrowFilesystem => "growing_filesystem",
+        }
+    }
+}
+
+/// Sizes `partition` of `disk` can take
+///
+/// `filesystem_min` is the smallest size the filesystem on it can shrink
+/// to, as its tools report it; the partition keeps some headroom on top.
+pub fn resize_bounds(
+    disk: &DiskInfo,
+    partitions: &[PartitionInfo],
+    partition: &PartitionInfo,
+    filesystem_min: Option<u64>,
+) -> ResizeBounds {
+    let usable_end = disk
+        .partition_table_type
+        .as_deref()
+        .and_then(|table_type| usable_range(disk.size, table_type, disk.gpt_usable_range))
+        .map_or(disk.size, |usable| usable.end);
+    let end = partitions
+        .iter()
+        .map(|p| p.offset)
+        .filter(|offset| *offset > partition.offset)
+        .fold(usable_end, u64::min);
+    let max_bytes = (end.saturating_sub(partition.offset) / PARTITION_SIZE_GRANULARITY
+        * PARTITION_SIZE_GRANULARITY)
+        .max(partition.size);
+
+    let mount_point = partition.mount_points.first().cloned();
+    let mounted = mount_point.is_some();
+    let filesystem_type = partition.filesystem_type.clone();
+    let (shrink_blocker, grow_blocker) = match filesystem_type.as_deref() {
+        Some(fs_type) => (
+            blocker(shrink_support(fs_type), mounted).or_else(|| {
+                filesystem_min
+                    .is_none()
+                    .then_some(ResizeBlocker::UnknownMinimum)
+            }),
+            blocker(grow_support(fs_type), mounted),
+        ),
+        None => (None, None),
+    };
+    let min_bytes = match (filesystem_type.as_deref(), shrink_blocker) {
+        (_, Some(_)) => partition.size,
+        (None, None) => GPT_ALIGNMENT_BYTES.min(partition.size),
+        (Some(_), None) => {
+            let minimum = filesystem_min.unwrap_or(partition.size);
+            (minimum + minimum * SHRINK_HEADROOM_PERCENT / 100)
+                .next_multiple_of(GPT_ALIGNMENT_BYTES)
+                .min(partition.size)
+        }
+    };
+
+    ResizeBounds {
+        partition: partition.device.clone(),
+        filesystem_type,
+        mount_point,
+        current_bytes: partition.size,
+        min_bytes,
+        max_bytes,
+        shrink_blocker,
+        grow_blocker,
+    }
+}
+
+impl ResizeBounds {
+    /// Steps that take the partition to `new_size`, rounded down to whole
+    /// sectors; none when it already has that size
+    pub fn plan(&self, new_size: u64) -> Result<Vec<ResizeStep>, String> {
+        let size = new_size / PARTITION_SIZE_GRANULARITY * PARTITION_SIZE_GRANULARITY;
+        if size < self.min_bytes || size > self.max_bytes {
+            return Err(format!(
+                "The new size must be between {} and {}",
+                crate::bytes_to_pretty(&self.min_bytes, false),
+                crate::bytes_to_pretty(&self.max_bytes, false)
+            ));
+        }
+        let has_filesystem = self.filesystem_type.is_some();
+        let grow = has_filesystem && self.grow_blocker.is_none();
+
+        let mut steps = Vec::new();
+        if size < self.current_bytes {
+            if has_filesystem {
+                steps.push(ResizeStep::ShrinkFilesystem { size });
+            }
+            steps.push(ResizeStep::ResizePartition { size });
+        } else if size > self.current_bytes {
+            steps.push(ResizeStep::ResizePartition { size });
+        } else {
+            return Ok(steps);
+        }
+        // Also fills what the partition gained from rounding to its alignment
+        if grow {
+            steps.push(ResizeStep::GrowFilesystem);
+        }
+        Ok(steps)
+    }
+}
+
+/// Blocks an ext filesystem can shrink to, from `resize2fs -P`
+pub fn parse_resize2fs_minimum(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Estimated minimum size of the filesystem:"))
+        .and_then(|blocks| blocks.trim().parse().ok())
+}
+
+/// Block size of an ext filesystem, from `dumpe2fs -h`
+pub fn parse_ext_block_size(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Block size:"))
+        .and_then(|size| size.trim().parse().ok())
+}
+
+/// Bytes an NTFS filesystem can shrink to, from `ntfsresize --info`
+pub fn parse_ntfsresize_minimum(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("You might resize at "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|bytes| bytes.parse().ok())
+}
+
+/// Bytes a btrfs device can shrink to, from `btrfs inspect-internal
+/// min-dev-size`
+pub fn parse_btrfs_min_dev_size(output: &str) -> Option<u64> {
+    output
+        .split_whitespace()
+        .next()
+        .and_then(|bytes| bytes.parse().ok())
+}
+
+/// ID of a device within its btrfs filesystem, from `btrfs inspect-internal
+/// dump-super`
+pub fn parse_btrfs_devid(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("dev_item.devid"))
+        .and_then(|id| id.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ByteRange;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn disk(size: u64) -> DiskInfo {
+        DiskInfo {
+            device: "/dev/sdz".to_string(),
+            id: String::new(),
+            model: String::new(),
+            serial: String::new(),
+            vendor: String::new(),
+            revision: String::new(),
+            size,
+            connection_bus: String::new(),
+            rotation_rate: None,
+            logical_sector_size: 512,
+            physical_sector_size: 512,
+            removable: false,
+            ejectable: false,
+            media_removable: false,
+            media_available: true,
+            optical: false,
+            optical_blank: false,
+            read_only: false,
+            can_power_off: false,
+            virtual_disk: false,
+            discard_supported: false,
+            rotational: false,
+            is_loop: false,
+            backing_file: None,
+            partition_table_type: Some("gpt".to_string()),
+            gpt_usable_range: Some(ByteRange {
+                start: 17 * 1024,
+                end: size - 17 * 1024,
+            }),
+        }
+    }
+
+    fn partition(number: u32, offset: u64, size: u64, fs_type: Option<&str>) -> PartitionInfo {
+        PartitionInfo {
+            device: format!("/dev/sdz{number}"),
+            number,
+            parent_path: "/dev/sdz".to_string(),
+            size,
+            offset,
+            type_id: String::new(),
+            type_name: String::new(),
+            flags: 0,
+            name: String::new(),
+            uuid: String::new(),
+            table_type: "gpt".to_string(),
+            has_filesystem: fs_type.is_some(),
+            filesystem_type: fs_type.map(str::to_string),
+            mount_points: Vec::new(),
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn bounds_stop_at_the_next_partition_and_the_gpt_end() {
+        let disk = disk(100 * MIB);
+        let first = partition(1, MIB, 10 * MIB, Some("ext4"));
+        let last = partition(2, 50 * MIB, 10 * MIB, Some("ext4"));
+        let partitions = [first.clone(), last.clone()];
+
+        let bounds = resize_bounds(&disk, &partitions, &first, Some(4 * MIB));
+        assert_eq!(bounds.max_bytes, 49 * MIB);
+        assert_eq!(bounds.min_bytes, 5 * MIB);
+        assert_eq!(bounds.shrink_blocker, None);
+
+        let bounds = resize_bounds(&disk, &partitions, &last, Some(4 * MIB));
+        assert_eq!(bounds.max_bytes, 50 * MIB - 20 * 1024);
+        assert_eq!(bounds.max_bytes % PARTITION_SIZE_GRANULARITY, 0);
+    }
+
+    #[test]
+    fn mounted_and_unsupported_filesystems_keep_their_size() {
+        let disk = disk(100 * MIB);
+        let mut ext4 = partition(1, MIB, 10 * MIB, Some("ext4"));
+        ext4.mount_points = vec!["/mnt".to_string()];
+        let bounds = resize_bounds(&disk, std::slice::from_ref(&ext4), &ext4, Some(MIB));
+        assert_eq!(bounds.shrink_blocker, Some(ResizeBlocker::Mounted));
+        assert_eq!(bounds.grow_blocker, None);
+        assert_eq!(bounds.min_bytes, 10 * MIB);
+
+        let xfs = partition(1, MIB, 10 * MIB, Some("xfs"));
+        let bounds = resize_bounds(&disk, std::slice::from_ref(&xfs), &xfs, None);
+        assert_eq!(bounds.shrink_blocker, Some(ResizeBlocker::Unsupported));
+        assert_eq!(bounds.min_bytes, 10 * MIB);
+
+        let btrfs = partition(1, MIB, 10 * MIB, Some("btrfs"));
+        let bounds = resize_bounds(&disk, std::slice::from_ref(&btrfs), &btrfs, None);
+        assert_eq!(bounds.shrink_blocker, Some(ResizeBlocker::UnknownMinimum));
+
+        let mut btrfs = btrfs;
+        btrfs.mount_points = vec!["/home".to_string()];
+        let bounds = resize_bounds(&disk, std::slice::from_ref(&btrfs), &btrfs, Some(MIB));
+        assert_eq!(bounds.shrink_blocker, None);
+        assert_eq!(bounds.min_bytes, 2 * MIB);
+    }
+
+    #[test]
+    fn plans_shrink_before_and_grow_after_the_partition() {
+        let disk = disk(100 * MIB);
+        let ext4 = partition(1, MIB, 20 * MIB, Some("ext4"));
+        let bounds = resize_bounds(&disk, std::slice::from_ref(&ext4), &ext4, Some(4 * MIB));
+
+        assert_eq!(
+            bounds.plan(10 * MIB + 100).unwrap(),
+            vec![
+                ResizeStep::ShrinkFilesystem { size: 10 * MIB },
+                ResizeStep::ResizePartition { size: 10 * MIB },
+                ResizeStep::GrowFilesystem,
+            ]
+        );
+        assert_eq!(
+            bounds.plan(30 * MIB).unwrap(),
+            vec![
+                ResizeStep::ResizePartition { size: 30 * MIB },
+                ResizeStep::GrowFilesystem,
+            ]
+        );
+        assert!(bounds.plan(20 * MIB).unwrap().is_empty());
+        assert!(bounds.plan(MIB).is_err());
+        assert!(bounds.plan(disk.size).is_err());
+
+        let vfat = partition(1, MIB, 20 * MIB, Some("vfat"));
+        let bounds = resize_bounds(&disk, std::slice::from_ref(&vfat), &vfat, None);
+        assert_eq!(
+            bounds.plan(30 * MIB).unwrap(),
+            vec![ResizeStep::ResizePartition { size: 30 * MIB }]
+        );
+    }
+
+    #[test]
+    fn parses_minimum_sizes() {
+        assert_eq!(
+            parse_resize2fs_minimum(
+                "resize2fs 1.47.0 (5-Feb-2023)\nEstimated minimum size of the filesystem: 81234\n"
+            ),
+            Some(81234)
+        );
+        assert_eq!(
+            parse_ext_block_size(
+                "Block count:              262144\nBlock size:               4096\n"
+            ),
+            Some(4096)
+        );
+        assert_eq!(
+            parse_ntfsresize_minimum(
+                "Checking filesystem consistency ...\nYou might resize at 52428800 bytes or 53 MB (freeing 971 MB).\n"
+            ),
+            Some(52428800)
+        );
+        assert_eq!(
+            parse_btrfs_min_dev_size("1130364928 bytes (1.05GiB)\n"),
+            Some(1130364928)
+        );
+        assert_eq!(
+            parse_btrfs_devid("dev_item.total_bytes\t1073741824\ndev_item.devid\t\t1\n"),
+            Some(1)
+        );
+        assert_eq!(parse_resize2fs_minimum("resize2fs: Bad magic number"), None);
+    }
+}
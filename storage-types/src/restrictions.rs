@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Restriction profiles
+//!
+//! On shared workstations an administrator can limit what members of some
+//! groups may do, whatever Polkit would let them: e.g. students may only
+//! mount and unmount. A [`RestrictionPolicy`] maps groups to the actions
+//! they are allowed; the service checks it before asking Polkit, and the
+//! app hides what the user is not allowed to do.
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the service's Polkit action IDs
+pub const ACTION_PREFIX: &str = "org.cosmic.ext.storage.service.";
+
+/// Actions that only read, which restricted users keep so that the app
+/// can still show their drives
+fn is_read_action(action: &str) -> bool {
+    let name = short_name(action);
+    name.ends_with("-read") || name == "result-pages"
+}
+
+/// An action ID without [`ACTION_PREFIX`], e.g. "filesystem-mount"
+pub fn short_name(action: &str) -> &str {
+    action.strip_prefix(ACTION_PREFIX).unwrap_or(action)
+}
+
+/// Whether the `pattern` of a profile covers `action`; patterns are short
+/// names or full action IDs, and a trailing `*` matches any rest
+fn pattern_matches(pattern: &str, action: &str) -> bool {
+    let pattern = short_name(pattern.trim());
+    let action = short_name(action);
+    match pattern.strip_suffix('*') {
+        Some(prefix) => action.starts_with(prefix),
+        None => pattern == action,
+    }
+}
+
+/// Actions allowed to the members of some groups
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestrictionProfile {
+    /// Shown in the service log when an action is refused
+    pub name: String,
+    /// Group names the profile applies to
+    pub groups: Vec<String>,
+    /// Allowed actions, e.g. "filesystem-mount" or "luks-*"
+    pub allowed_actions: Vec<String>,
+}
+
+/// Restriction profiles, read from `/etc/cosmic-ext-storage/restrictions.json`
+///
+/// Users in none of the profiles' groups are not restricted. Users in
+/// several are allowed what any of their profiles allows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestrictionPolicy {
+    pub profiles: Vec<RestrictionProfile>,
+}
+
+impl RestrictionPolicy {
+    /// The profiles applying to a member of `groups`
+    pub fn profiles_for<'a>(
+        &'a self,
+        groups: &'a [String],
+    ) -> impl Iterator<Item = &'a RestrictionProfile> + 'a {
+        self.profiles
+            .iter()
+            .filter(|profile| profile.groups.iter().any(|group| groups.contains(group)))
+    }
+
+    /// Whether a member of `groups` is restricted at all
+    pub fn restricts(&self, groups: &[String]) -> bool {
+        self.profiles_for(groups).next().is_some()
+    }
+
+    /// Whether a member of `groups` may run `action`
+    pub fn allows(&self, groups: &[String], action: &str) -> bool {
+        !self.restricts(groups)
+            || is_read_action(action)
+            || self.profiles_for(groups).any(|profile| {
+                profile
+                    .allowed_actions
+                    .iter()
+                    .any(|pattern| pattern_matches(pattern, action))
+            })
+    }
+
+    /// What a member of `groups` may do, for the app
+    pub fn allowed_actions(&self, groups: &[String]) -> AllowedActions {
+        if !self.restricts(groups) {
+            return AllowedActions::default();
+        }
+        let mut actions: Vec<String> = self
+            .profiles_for(groups)
+            .flat_map(|profile| profile.allowed_actions.iter())
+            .map(|pattern| short_name(pattern.trim()).to_string())
+            .collect();
+        actions.sort();
+        actions.dedup();
+        AllowedActions {
+            restricted: true,
+            actions,
+        }
+    }
+}
+
+/// What the calling user may do, as returned by GetAllowedActions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowedActions {
+    /// Whether a restriction profile applies to the user; if not, Polkit
+    /// alone decides
+    pub restricted: bool,
+    /// Allowed action patterns, as short names
+    pub actions: Vec<String>,
+}
+
+impl AllowedActions {
+    /// Whether the user may run `action`, given as a short name or full ID
+    pub fn allows(&self, action: &str) -> bool {
+        !self.restricted
+            || is_read_action(action)
+            || self
+                .actions
+                .iter()
+                .any(|pattern| pattern_matches(pattern, action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RestrictionPolicy {
+        serde_json::from_str(
+            r#"{"profiles": [
+                {"name": "students", "groups": ["students"],
+                 "allowed_actions": ["filesystem-mount"]},
+                {"name": "lab", "groups": ["lab"],
+                 "allowed_actions": ["org.cosmic.ext.storage.service.luks-*"]}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn profiles_allow_only_their_actions() {
+        let policy = policy();
+        let students = ["students".to_string(), "users".to_string()];
+        assert!(policy.allows(&students, "org.cosmic.ext.storage.service.filesystem-mount"));
+        assert!(policy.allows(&students, "org.cosmic.ext.storage.service.disk-read"));
+        assert!(!policy.allows(&students, "org.cosmic.ext.storage.service.format"));
+
+        let both = ["students".to_string(), "lab".to_string()];
+        assert!(policy.allows(&both, "org.cosmic.ext.storage.service.luks-unlock"));
+        assert!(!policy.allows(&both, "org.cosmic.ext.storage.service.partition-modify"));
+
+        let staff = ["staff".to_string()];
+        assert!(!policy.restricts(&staff));
+        assert!(policy.allows(&staff, "org.cosmic.ext.storage.service.format"));
+    }
+
+    #[test]
+    fn allowed_actions_match_the_policy() {
+        let policy = policy();
+        let lab = policy.allowed_actions(&["lab".to_string()]);
+        assert_eq!(lab.actions, vec!["luks-*".to_string()]);
+        assert!(lab.allows("luks-lock"));
+        assert!(lab.allows("filesystem-read"));
+        assert!(!lab.allows("filesystem-mount"));
+        assert!(AllowedActions::default().allows("format"));
+    }
+}
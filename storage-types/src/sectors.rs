@@ -0,0 +1,581 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Raw sectors of block devices
+//!
+//! The sector viewer pages through a drive or partition a few sectors at a
+//! time and marks the on-disk structures it recognizes in the bytes read:
+//! the MBR, GPT headers and partition entries, LUKS headers and filesystem
+//! superblocks. A structure is only recognized when its signature is on
+//! the page, so GPT entries on pages after the header stay unmarked.
+
+use serde::{Deserialize, Serialize};
+
+/// Most bytes read from a device at once
+pub const MAX_SECTOR_READ: u64 = 64 * 1024;
+
+/// Bytes shown per row of a hex dump
+pub const HEX_ROW_BYTES: usize = 16;
+
+/// Bytes read from a device, starting at `offset`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectorRange {
+    pub offset: u64,
+    /// Size of the whole device, for paging
+    pub device_size: u64,
+    pub data: Vec<u8>,
+}
+
+impl SectorRange {
+    /// Structures recognized in the bytes read
+    pub fn annotations(&self) -> Vec<SectorAnnotation> {
+        annotate(self.offset, &self.data)
+    }
+}
+
+/// On-disk structure a range of bytes belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SectorStructure {
+    Mbr,
+    GptHeader,
+    GptEntry,
+    Luks,
+    Ext,
+    Btrfs,
+    Xfs,
+    Fat,
+    Ntfs,
+    Swap,
+}
+
+/// A field of an on-disk structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectorAnnotation {
+    pub structure: SectorStructure,
+    /// Field name as in the structure's specification, e.g. "header CRC32"
+    pub field: String,
+    /// Byte offset from the start of the device
+    pub offset: u64,
+    pub length: u64,
+}
+
+impl SectorAnnotation {
+    /// Whether the field covers any of the `length` bytes at `offset`
+    pub fn overlaps(&self, offset: u64, length: u64) -> bool {
+        self.offset < offset.saturating_add(length) && offset < self.offset + self.length
+    }
+}
+
+/// Bytes read at a device offset, looked up by device offset
+struct Page<'a> {
+    offset: u64,
+    data: &'a [u8],
+    annotations: Vec<SectorAnnotation>,
+}
+
+impl Page<'_> {
+    fn get(&self, offset: u64, length: usize) -> Option<&[u8]> {
+        let start = usize::try_from(offset.checked_sub(self.offset)?).ok()?;
+        self.data.get(start..start.checked_add(length)?)
+    }
+
+    fn has(&self, offset: u64, bytes: &[u8]) -> bool {
+        self.get(offset, bytes.len()) == Some(bytes)
+    }
+
+    fn u32_at(&self, offset: u64) -> Option<u32> {
+        Some(u32::from_le_bytes(self.get(offset, 4)?.try_into().ok()?))
+    }
+
+    fn u64_at(&self, offset: u64) -> Option<u64> {
+        Some(u64::from_le_bytes(self.get(offset, 8)?.try_into().ok()?))
+    }
+
+    /// Mark the fields of a structure starting at `base`, given as
+    /// (field, offset in the structure, length)
+    fn mark(&mut self, structure: SectorStructure, base: u64, fields: &[(&str, u64, u64)]) {
+        for (field, offset, length) in fields {
+            self.annotations.push(SectorAnnotation {
+                structure,
+                field: field.to_string(),
+                offset: base + offset,
+                length: *length,
+            });
+        }
+    }
+}
+
+/// Fields of the structures recognized in `data`, read at byte `offset` of
+/// a device, ordered by offset; only fields with bytes in `data` are kept
+pub fn annotate(offset: u64, data: &[u8]) -> Vec<SectorAnnotation> {
+    let mut page = Page {
+        offset,
+        data,
+        annotations: Vec::new(),
+    };
+
+    let boot_sector = mark_fat(&mut page) || mark_ntfs(&mut page);
+    if !boot_sector {
+        mark_mbr(&mut page);
+    }
+    mark_gpt(&mut page);
+    mark_luks(&mut page);
+    mark_ext(&mut page);
+    mark_btrfs(&mut page);
+    mark_xfs(&mut page);
+    mark_swap(&mut page);
+
+    let length = data.len() as u64;
+    let mut annotations = page.annotations;
+    annotations.retain(|annotation| annotation.overlaps(offset, length));
+    annotations.sort_by_key(|annotation| annotation.offset);
+    annotations
+}
+
+const BOOT_SIGNATURE: [u8; 2] = [0x55, 0xAA];
+
+fn mark_mbr(page: &mut Page) {
+    if !page.has(510, &BOOT_SIGNATURE) {
+        return;
+    }
+    page.mark(
+        SectorStructure::Mbr,
+        0,
+        &[
+            ("boot code", 0, 440),
+            ("disk signature", 440, 4),
+            ("partition record 1", 446, 16),
+            ("partition record 2", 462, 16),
+            ("partition record 3", 478, 16),
+            ("partition record 4", 494, 16),
+            ("boot signature", 510, 2),
+        ],
+    );
+}
+
+fn mark_fat(page: &mut Page) -> bool {
+    let fat32 = page.has(82, b"FAT32");
+    let fat16 = page.has(54, b"FAT1");
+    if !(fat32 || fat16) || !page.has(510, &BOOT_SIGNATURE) {
+        return false;
+    }
+    // FAT32 moves the extended boot record behind its larger header
+    let ebr = if fat32 { 28 } else { 0 };
+    page.mark(
+        SectorStructure::Fat,
+        0,
+        &[
+            ("jump instruction", 0, 3),
+            ("OEM name", 3, 8),
+            ("bytes per sector", 11, 2),
+            ("sectors per cluster", 13, 1),
+            ("reserved sectors", 14, 2),
+            ("FAT count", 16, 1),
+            ("volume ID", 39 + ebr, 4),
+            ("volume label", 43 + ebr, 11),
+            ("filesystem type", 54 + ebr, 8),
+            ("boot signature", 510, 2),
+        ],
+    );
+    true
+}
+
+fn mark_ntfs(page: &mut Page) -> bool {
+    if !page.has(3, b"NTFS    ") {
+        return false;
+    }
+    page.mark(
+        SectorStructure::Ntfs,
+        0,
+        &[
+            ("OEM ID", 3, 8),
+            ("bytes per sector", 11, 2),
+            ("sectors per cluster", 13, 1),
+            ("total sectors", 40, 8),
+            ("$MFT cluster", 48, 8),
+            ("$MFTMirr cluster", 56, 8),
+            ("volume serial number", 72, 8),
+            ("boot signature", 510, 2),
+        ],
+    );
+    true
+}
+
+/// Most GPT entries marked, beyond any array in use
+const MAX_GPT_ENTRIES: u64 = 1024;
+
+fn mark_gpt(page: &mut Page) {
+    let first = page.offset.div_ceil(512) * 512;
+    let end = page.offset + page.data.len() as u64;
+    for header in (first..end).step_by(512) {
+        if !page.has(header, b"EFI PART") {
+            continue;
+        }
+        page.mark(
+            SectorStructure::GptHeader,
+            header,
+            &[
+                ("signature", 0, 8),
+                ("revision", 8, 4),
+                ("header size", 12, 4),
+                ("header CRC32", 16, 4),
+                ("current LBA", 24, 8),
+                ("backup LBA", 32, 8),
+                ("first usable LBA", 40, 8),
+                ("last usable LBA", 48, 8),
+                ("disk GUID", 56, 16),
+                ("partition entries LBA", 72, 8),
+                ("number of entries", 80, 4),
+                ("entry size", 84, 4),
+                ("entries CRC32", 88, 4),
+            ],
+        );
+
+        // The header's own LBA gives the sector size
+        let (Some(current_lba), Some(entries_lba), Some(count), Some(entry_size)) = (
+            page.u64_at(header + 24),
+            page.u64_at(header + 72),
+            page.u32_at(header + 80),
+            page.u32_at(header + 84),
+        ) else {
+            continue;
+        };
+        if current_lba == 0 || header % current_lba != 0 || entry_size < 128 {
+            continue;
+        }
+        let sector_size = header / current_lba;
+        let Some(array) = entries_lba.checked_mul(sector_size) else {
+            continue;
+        };
+        let entry_size = u64::from(entry_size);
+        for index in 0..u64::from(count).min(MAX_GPT_ENTRIES) {
+            let entry = array + index * entry_size;
+            let Some(type_guid) = page.get(entry, 16) else {
+                continue;
+            };
+            if type_guid.iter().all(|byte| *byte == 0) {
+                continue;
+            }
+            page.annotations.push(SectorAnnotation {
+                structure: SectorStructure::GptEntry,
+                field: format!("entry {}", index + 1),
+                offset: entry,
+                length: entry_size,
+            });
+        }
+    }
+}
+
+const LUKS_MAGIC: [u8; 6] = *b"LUKS\xba\xbe";
+const LUKS2_SECONDARY_MAGIC: [u8; 6] = *b"SKUL\xba\xbe";
+
+/// Offsets the secondary LUKS2 header may start at
+const LUKS2_SECONDARY_OFFSETS: [u64; 9] = [
+    0x4000, 0x8000, 0x10000, 0x20000, 0x40000, 0x80000, 0x100000, 0x200000, 0x400000,
+];
+
+fn mark_luks(page: &mut Page) {
+    if page.has(0, &LUKS_MAGIC) {
+        match page.get(6, 2) {
+            Some([0, 1]) => mark_luks1(page),
+            Some([0, 2]) => mark_luks2(page, 0),
+            _ => {}
+        }
+    }
+    for header in LUKS2_SECONDARY_OFFSETS {
+        if page.has(header, &LUKS2_SECONDARY_MAGIC) && page.has(header + 6, &[0, 2]) {
+            mark_luks2(page, header);
+        }
+    }
+}
+
+fn mark_luks1(page: &mut Page) {
+    page.mark(
+        SectorStructure::Luks,
+        0,
+        &[
+            ("magic", 0, 6),
+            ("version", 6, 2),
+            ("cipher name", 8, 32),
+            ("cipher mode", 40, 32),
+            ("hash spec", 72, 32),
+            ("payload offset", 104, 4),
+            ("key bytes", 108, 4),
+            ("master key digest", 112, 20),
+            ("master key digest salt", 132, 32),
+            ("master key digest iterations", 164, 4),
+            ("UUID", 168, 40),
+        ],
+    );
+    for slot in 0..8 {
+        page.annotations.push(SectorAnnotation {
+            structure: SectorStructure::Luks,
+            field: format!("key slot {slot}"),
+            offset: 208 + slot * 48,
+            length: 48,
+        });
+    }
+}
+
+fn mark_luks2(page: &mut Page, header: u64) {
+    page.mark(
+        SectorStructure::Luks,
+        header,
+        &[
+            ("magic", 0, 6),
+            ("version", 6, 2),
+            ("header size", 8, 8),
+            ("sequence ID", 16, 8),
+            ("label", 24, 48),
+            ("checksum algorithm", 72, 32),
+            ("salt", 104, 64),
+            ("UUID", 168, 40),
+            ("subsystem", 208, 48),
+            ("header offset", 256, 8),
+            ("checksum", 448, 64),
+        ],
+    );
+    if let Some(size) = page.get(header + 8, 8) {
+        let size = u64::from_be_bytes(size.try_into().unwrap_or_default());
+        if size > 4096 {
+            page.mark(
+                SectorStructure::Luks,
+                header,
+                &[("JSON metadata", 4096, size - 4096)],
+            );
+        }
+    }
+}
+
+/// Where ext2, ext3 and ext4 keep their superblock
+const EXT_SUPERBLOCK: u64 = 1024;
+
+fn mark_ext(page: &mut Page) {
+    if !page.has(EXT_SUPERBLOCK + 56, &[0x53, 0xEF]) {
+        return;
+    }
+    page.mark(
+        SectorStructure::Ext,
+        EXT_SUPERBLOCK,
+        &[
+            ("inode count", 0, 4),
+            ("block count", 4, 4),
+            ("free block count", 12, 4),
+            ("free inode count", 16, 4),
+            ("log block size", 24, 4),
+            ("magic", 56, 2),
+            ("state", 58, 2),
+            ("compatible features", 92, 4),
+            ("incompatible features", 96, 4),
+            ("read-only compatible features", 100, 4),
+            ("UUID", 104, 16),
+            ("volume name", 120, 16),
+            ("last mounted on", 136, 64),
+        ],
+    );
+}
+
+/// Where btrfs keeps its primary superblock
+const BTRFS_SUPERBLOCK: u64 = 0x10000;
+
+fn mark_btrfs(page: &mut Page) {
+    if !page.has(BTRFS_SUPERBLOCK + 64, b"_BHRfS_M") {
+        return;
+    }
+    page.mark(
+        SectorStructure::Btrfs,
+        BTRFS_SUPERBLOCK,
+        &[
+            ("checksum", 0, 32),
+            ("filesystem UUID", 32, 16),
+            ("superblock offset", 48, 8),
+            ("flags", 56, 8),
+            ("magic", 64, 8),
+            ("generation", 72, 8),
+            ("root tree address", 80, 8),
+            ("total bytes", 112, 8),
+            ("bytes used", 120, 8),
+            ("label", 299, 256),
+        ],
+    );
+}
+
+fn mark_xfs(page: &mut Page) {
+    if !page.has(0, b"XFSB") {
+        return;
+    }
+    page.mark(
+        SectorStructure::Xfs,
+        0,
+        &[
+            ("magic", 0, 4),
+            ("block size", 4, 4),
+            ("data blocks", 8, 8),
+            ("UUID", 32, 16),
+            ("filesystem name", 108, 12),
+        ],
+    );
+}
+
+/// Page sizes a swap signature may end
+const SWAP_PAGE_SIZES: [u64; 4] = [4096, 8192, 16384, 65536];
+
+fn mark_swap(page: &mut Page) {
+    for page_size in SWAP_PAGE_SIZES {
+        let signature = page_size - 10;
+        if !page.has(signature, b"SWAPSPACE2") {
+            continue;
+        }
+        page.mark(
+            SectorStructure::Swap,
+            1024,
+            &[
+                ("version", 0, 4),
+                ("last page", 4, 4),
+                ("bad page count", 8, 4),
+                ("UUID", 12, 16),
+                ("label", 28, 16),
+            ],
+        );
+        page.mark(SectorStructure::Swap, signature, &[("signature", 0, 10)]);
+    }
+}
+
+/// `bytes` in hex, with a wider gap after the first eight
+pub fn hex_row(bytes: &[u8]) -> String {
+    let mut row = String::with_capacity(HEX_ROW_BYTES * 3 + 1);
+    for index in 0..HEX_ROW_BYTES {
+        if index > 0 {
+            row.push(' ');
+        }
+        if index == HEX_ROW_BYTES / 2 {
+            row.push(' ');
+        }
+        match bytes.get(index) {
+            Some(byte) => row.push_str(&format!("{byte:02x}")),
+            None => row.push_str("  "),
+        }
+    }
+    row
+}
+
+/// `bytes` as ASCII, with a dot for each byte that is not printable
+pub fn ascii_row(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                *byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// Byte offset typed by the user: decimal bytes, hex bytes ("0x1000") or a
+/// sector number ("LBA 34")
+pub fn parse_offset(text: &str, sector_size: u64) -> Option<u64> {
+    let text = text.trim();
+    let lower = text.to_ascii_lowercase();
+    if let Some(lba) = lower.strip_prefix("lba") {
+        return lba.trim().parse::<u64>().ok()?.checked_mul(sector_size);
+    }
+    if let Some(hex) = lower.strip_prefix("0x") {
+        return u64::from_str_radix(hex, 16).ok();
+    }
+    text.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(annotations: &[SectorAnnotation], structure: SectorStructure) -> Vec<&str> {
+        annotations
+            .iter()
+            .filter(|annotation| annotation.structure == structure)
+            .map(|annotation| annotation.field.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn gpt_header_and_entries_are_marked() {
+        let mut disk = vec![0u8; 4096];
+        disk[510..512].copy_from_slice(&BOOT_SIGNATURE);
+        disk[512..520].copy_from_slice(b"EFI PART");
+        disk[512 + 24..512 + 32].copy_from_slice(&1u64.to_le_bytes());
+        disk[512 + 72..512 + 80].copy_from_slice(&2u64.to_le_bytes());
+        disk[512 + 80..512 + 84].copy_from_slice(&128u32.to_le_bytes());
+        disk[512 + 84..512 + 88].copy_from_slice(&128u32.to_le_bytes());
+        // Second entry in use
+        disk[1024 + 128] = 0x28;
+
+        let annotations = annotate(0, &disk);
+        assert!(fields(&annotations, SectorStructure::Mbr).contains(&"partition record 1"));
+        assert!(fields(&annotations, SectorStructure::GptHeader).contains(&"header CRC32"));
+        assert_eq!(fields(&annotations, SectorStructure::GptEntry), ["entry 2"]);
+        let entry = annotations
+            .iter()
+            .find(|annotation| annotation.structure == SectorStructure::GptEntry)
+            .unwrap();
+        assert_eq!((entry.offset, entry.length), (1152, 128));
+
+        // A page past the header only has the bytes it shows
+        assert!(annotate(4096, &[0u8; 512]).is_empty());
+    }
+
+    #[test]
+    fn boot_sectors_replace_the_mbr() {
+        let mut fat = vec![0u8; 512];
+        fat[3..11].copy_from_slice(b"mkfs.fat");
+        fat[82..90].copy_from_slice(b"FAT32   ");
+        fat[510..512].copy_from_slice(&BOOT_SIGNATURE);
+
+        let annotations = annotate(0, &fat);
+        assert!(fields(&annotations, SectorStructure::Mbr).is_empty());
+        let label = annotations
+            .iter()
+            .find(|annotation| annotation.field == "volume label")
+            .unwrap();
+        assert_eq!(label.offset, 71);
+    }
+
+    #[test]
+    fn superblocks_are_marked_where_they_live() {
+        let mut ext = vec![0u8; 2048];
+        ext[1080..1082].copy_from_slice(&[0x53, 0xEF]);
+        let annotations = annotate(0, &ext);
+        assert!(fields(&annotations, SectorStructure::Ext).contains(&"volume name"));
+
+        let mut luks = vec![0u8; 8192];
+        luks[0..6].copy_from_slice(&LUKS_MAGIC);
+        luks[6..8].copy_from_slice(&[0, 2]);
+        luks[8..16].copy_from_slice(&16384u64.to_be_bytes());
+        let annotations = annotate(0, &luks);
+        let json = annotations
+            .iter()
+            .find(|annotation| annotation.field == "JSON metadata")
+            .unwrap();
+        assert_eq!((json.offset, json.length), (4096, 12288));
+
+        // Without the header on the page its metadata is not recognized
+        let annotations = annotate(4096, &luks[4096..]);
+        assert!(annotations.is_empty());
+    }
+
+    #[test]
+    fn rows_and_offsets_are_formatted() {
+        let bytes: Vec<u8> = (0x41..0x51).collect();
+        assert_eq!(
+            hex_row(&bytes),
+            "41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50"
+        );
+        assert_eq!(hex_row(&[0xff]).trim_end(), "ff");
+        assert_eq!(ascii_row(b"EFI PART\0\x01"), "EFI PART..");
+
+        assert_eq!(parse_offset("0x200", 512), Some(512));
+        assert_eq!(parse_offset("LBA 34", 4096), Some(34 * 4096));
+        assert_eq!(parse_offset(" 1024 ", 512), Some(1024));
+        assert_eq!(parse_offset("sector", 512), None);
+    }
+}
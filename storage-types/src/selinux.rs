@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! SELinux labels of mounted filesystems
+//!
+//! A filesystem formatted or copied outside the policy's view has no labels
+//! or the wrong ones, and confined services are then denied access even
+//! though the permissions look right; the classic case is a /home moved to a
+//! new disk. Such a filesystem is relabeled with `restorecon`.
+
+use serde::{Deserialize, Serialize};
+
+/// Types of files the policy knows nothing about
+const UNLABELED_TYPES: &[&str] = &["unlabeled_t", "file_t"];
+
+/// SELinux state of a mounted filesystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelinuxStatus {
+    pub mount_point: String,
+    /// The policy is enforced rather than only logging denials
+    pub enforcing: bool,
+    /// Context of the mount point; None when it has no label
+    pub current: Option<String>,
+    /// Context the policy gives the mount point
+    pub default: Option<String>,
+    /// Context given to the whole filesystem with the context= mount option,
+    /// which takes the place of the labels on it
+    pub mount_context: Option<String>,
+}
+
+impl SelinuxStatus {
+    /// The mount point is unlabeled or labeled with another type than the
+    /// policy gives it
+    pub fn needs_relabel(&self) -> bool {
+        if self.mount_context.is_some() {
+            return false;
+        }
+        match (
+            self.current.as_deref().and_then(context_type),
+            self.default.as_deref().and_then(context_type),
+        ) {
+            (None, Some(_)) => true,
+            (Some(current), _) if UNLABELED_TYPES.contains(&current) => true,
+            (Some(current), Some(default)) => current != default,
+            _ => false,
+        }
+    }
+}
+
+/// Type of a context such as "system_u:object_r:home_root_t:s0"
+pub fn context_type(context: &str) -> Option<&str> {
+    context.split(':').nth(2).filter(|kind| !kind.is_empty())
+}
+
+/// Context set with the context= option in comma-separated mount options
+///
+/// The context is quoted when its level has a comma, as in
+/// `context="system_u:object_r:tmp_t:s0:c0,c1"`.
+pub fn mount_context_option(options: &str) -> Option<String> {
+    let start = options
+        .match_indices("context=")
+        .find(|(index, _)| *index == 0 || options[..*index].ends_with(','))?
+        .0
+        + "context=".len();
+    let rest = &options[start..];
+    let context = match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next()?,
+        None => rest.split(',').next()?,
+    };
+    (!context.is_empty()).then(|| context.to_string())
+}
+
+/// Mount points of the block device filesystems in /proc/mounts, with the
+/// context= option each was mounted with
+pub fn labeled_mounts(proc_mounts: &str) -> Vec<(String, Option<String>)> {
+    proc_mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (source, mount_point) = (fields.next()?, fields.next()?);
+            let options = fields.nth(1)?;
+            source.starts_with("/dev/").then(|| {
+                (
+                    crate::user_mount::unescape(mount_point),
+                    mount_context_option(options),
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(current: Option<&str>, mount_context: Option<&str>) -> SelinuxStatus {
+        SelinuxStatus {
+            mount_point: "/home".to_string(),
+            enforcing: true,
+            current: current.map(str::to_string),
+            default: Some("system_u:object_r:home_root_t:s0".to_string()),
+            mount_context: mount_context.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn unlabeled_or_mislabeled_mount_points_need_relabeling() {
+        assert!(!status(Some("system_u:object_r:home_root_t:s0"), None).needs_relabel());
+        assert!(status(None, None).needs_relabel());
+        assert!(status(Some("system_u:object_r:unlabeled_t:s0"), None).needs_relabel());
+        assert!(status(Some("system_u:object_r:mnt_t:s0"), None).needs_relabel());
+        // Labels are ignored under a context= mount option
+        assert!(!status(None, Some("system_u:object_r:home_root_t:s0")).needs_relabel());
+    }
+
+    #[test]
+    fn reads_the_context_mount_option() {
+        assert_eq!(
+            mount_context_option("rw,context=system_u:object_r:tmp_t:s0,seclabel").as_deref(),
+            Some("system_u:object_r:tmp_t:s0")
+        );
+        assert_eq!(
+            mount_context_option(r#"context="system_u:object_r:tmp_t:s0:c0,c1",rw"#).as_deref(),
+            Some("system_u:object_r:tmp_t:s0:c0,c1")
+        );
+        assert_eq!(
+            mount_context_option("rw,fscontext=system_u:object_r:tmp_t:s0"),
+            None
+        );
+        assert_eq!(mount_context_option("rw,relatime,seclabel"), None);
+
+        let mounts = "\
+/dev/nvme0n1p2 / ext4 rw,seclabel,relatime 0 0
+tmpfs /tmp tmpfs rw,seclabel 0 0
+/dev/sdb1 /mnt/My\\040Disk exfat rw,context=system_u:object_r:mnt_t:s0 0 0
+";
+        assert_eq!(
+            labeled_mounts(mounts),
+            vec![
+                ("/".to_string(), None),
+                (
+                    "/mnt/My Disk".to_string(),
+                    Some("system_u:object_r:mnt_t:s0".to_string())
+                ),
+            ]
+        );
+    }
+}
@@ -58,3 +58,156 @@ pub struct SmartInfo {
     #[serde(default)]
     pub attributes: BTreeMap<String, String>,
 }
+
+/// Source of SMART data for a drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SmartBackendKind {
+    /// UDisks2 `Drive.Ata` / `NVMe.Controller` interfaces
+    UDisks,
+    /// `smartctl --json`, covering USB bridges and some RAID controllers
+    Smartctl,
+}
+
+impl SmartBackendKind {
+    /// Every backend, in automatic selection order
+    pub const ALL: [Self; 2] = [Self::UDisks, Self::Smartctl];
+
+    /// Convert to string representation
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UDisks => "udisks",
+            Self::Smartctl => "smartctl",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "udisks" => Some(Self::UDisks),
+            "smartctl" => Some(Self::Smartctl),
+            _ => None,
+        }
+    }
+}
+
+/// SMART backend selection of a drive
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SmartBackendStatus {
+    /// Device path (e.g., "/dev/sda")
+    pub device: String,
+
+    /// Backend forced for this drive, None for automatic selection
+    pub override_backend: Option<SmartBackendKind>,
+
+    /// Backend that last returned SMART data for this drive
+    pub active: Option<SmartBackendKind>,
+
+    /// Backends installed on the system
+    pub available: Vec<SmartBackendKind>,
+}
+
+/// Periodic SMART self-tests of a drive; `None` disables a test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SelfTestSchedule {
+    /// Days between short self-tests
+    pub short_interval_days: Option<u32>,
+
+    /// Days between extended self-tests
+    pub extended_interval_days: Option<u32>,
+}
+
+impl SelfTestSchedule {
+    /// Whether any test is scheduled
+    pub fn is_enabled(&self) -> bool {
+        self.short_interval_days.is_some() || self.extended_interval_days.is_some()
+    }
+
+    /// Test due at `now` (seconds since epoch) given the drive's history
+    ///
+    /// An extended test also counts as a short one, and wins when both are due.
+    pub fn due(&self, history: &[SelfTestRecord], now: u64) -> Option<SmartSelfTestKind> {
+        let last = |kinds: &[SmartSelfTestKind]| {
+            history
+                .iter()
+                .filter(|record| kinds.contains(&record.kind))
+                .map(|record| record.started_at)
+                .max()
+        };
+        let is_due = |interval_days: Option<u32>, last: Option<u64>| {
+            interval_days.is_some_and(|days| {
+                last.is_none_or(|last| now.saturating_sub(last) >= u64::from(days) * 24 * 60 * 60)
+            })
+        };
+
+        if is_due(
+            self.extended_interval_days,
+            last(&[SmartSelfTestKind::Extended]),
+        ) {
+            Some(SmartSelfTestKind::Extended)
+        } else if is_due(
+            self.short_interval_days,
+            last(&[SmartSelfTestKind::Short, SmartSelfTestKind::Extended]),
+        ) {
+            Some(SmartSelfTestKind::Short)
+        } else {
+            None
+        }
+    }
+}
+
+/// A self-test run on a drive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfTestRecord {
+    /// Test kind
+    pub kind: SmartSelfTestKind,
+
+    /// Seconds since epoch (UTC) when the test was started
+    pub started_at: u64,
+
+    /// Started by the schedule rather than by the user
+    pub scheduled: bool,
+
+    /// Self-test status reported once the test finished, None while running
+    pub result: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn record(kind: SmartSelfTestKind, started_at: u64) -> SelfTestRecord {
+        SelfTestRecord {
+            kind,
+            started_at,
+            scheduled: true,
+            result: None,
+        }
+    }
+
+    #[test]
+    fn schedules_due_self_tests() {
+        let schedule = SelfTestSchedule {
+            short_interval_days: Some(7),
+            extended_interval_days: Some(30),
+        };
+        let now = 100 * DAY;
+
+        assert_eq!(schedule.due(&[], now), Some(SmartSelfTestKind::Extended));
+
+        let history = [record(SmartSelfTestKind::Extended, now - 10 * DAY)];
+        assert_eq!(schedule.due(&history, now), Some(SmartSelfTestKind::Short));
+
+        let history = [
+            record(SmartSelfTestKind::Extended, now - 10 * DAY),
+            record(SmartSelfTestKind::Short, now - 2 * DAY),
+        ];
+        assert_eq!(schedule.due(&history, now), None);
+
+        let history = [record(SmartSelfTestKind::Extended, now - DAY)];
+        assert_eq!(schedule.due(&history, now), None);
+
+        assert_eq!(SelfTestSchedule::default().due(&[], now), None);
+    }
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Local usage statistics
+//!
+//! Users who turn statistics on get a journal of the operations they ran
+//! through the service, kept on their machine only and never transmitted.
+//! [`UsageStatistics`] sums it up: how many operations ran and of which
+//! kind, how many bytes were backed up, and how many scans and snapshots
+//! were made.
+
+use serde::{Deserialize, Serialize};
+
+/// Service methods that scan drives or filesystems
+pub const SCAN_OPERATIONS: [&str; 2] = ["get_usage_scan", "scan_lost_partitions"];
+
+/// Service methods that take a snapshot
+pub const SNAPSHOT_OPERATIONS: [&str; 1] = ["create_snapshot"];
+
+/// What a journal record notes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// A service method was called and authorized
+    Started { operation: String },
+    /// A drive or partition backup completed
+    BackedUp { bytes: u64 },
+}
+
+/// A line of the operation journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationRecord {
+    /// Unix time, in seconds
+    pub time: u64,
+    /// User the operation ran for
+    pub uid: u32,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+impl OperationRecord {
+    /// The records of a journal with one JSON record per line; lines that
+    /// cannot be read, e.g. one cut short by a crash, are skipped
+    pub fn parse_journal(text: &str) -> Vec<Self> {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+}
+
+/// What a user did with the tool, from the operation journal
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageStatistics {
+    /// Whether operations of the user are recorded
+    pub enabled: bool,
+    /// Unix time of the first record, if any
+    pub since: Option<u64>,
+    pub operations: u64,
+    /// Count of each operation, most frequent first
+    pub by_operation: Vec<(String, u64)>,
+    pub backups: u64,
+    pub bytes_backed_up: u64,
+    pub scans: u64,
+    pub snapshots: u64,
+}
+
+impl UsageStatistics {
+    /// Statistics of the user `uid` from all journal records
+    pub fn from_records(records: &[OperationRecord], uid: u32, enabled: bool) -> Self {
+        let mut stats = Self {
+            enabled,
+            ..Self::default()
+        };
+        let mut counts: std::collections::BTreeMap<&str, u64> = Default::default();
+
+        for record in records.iter().filter(|record| record.uid == uid) {
+            stats.since = Some(stats.since.map_or(record.time, |t| t.min(record.time)));
+            match &record.event {
+                JournalEvent::Started { operation } => {
+                    stats.operations += 1;
+                    *counts.entry(operation).or_default() += 1;
+                    if SCAN_OPERATIONS.contains(&operation.as_str()) {
+                        stats.scans += 1;
+                    }
+                    if SNAPSHOT_OPERATIONS.contains(&operation.as_str()) {
+                        stats.snapshots += 1;
+                    }
+                }
+                JournalEvent::BackedUp { bytes } => {
+                    stats.backups += 1;
+                    stats.bytes_backed_up += bytes;
+                }
+            }
+        }
+
+        stats.by_operation = counts
+            .into_iter()
+            .map(|(operation, count)| (operation.to_string(), count))
+            .collect();
+        // Stable, so equal counts stay in name order
+        stats
+            .by_operation
+            .sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_up_user_records() {
+        let journal = r#"{"time":200,"uid":1000,"event":"started","operation":"create_snapshot"}
+{"time":100,"uid":1000,"event":"started","operation":"get_usage_scan"}
+{"time":150,"uid":1001,"event":"started","operation":"mount"}
+{"time":300,"uid":1000,"event":"started","operation":"create_snapshot"}
+{"time":400,"uid":1000,"event":"backed_up","bytes":4096}
+{"time":500,"uid":1000,"event":"sta"#;
+        let records = OperationRecord::parse_journal(journal);
+        assert_eq!(records.len(), 5);
+
+        let stats = UsageStatistics::from_records(&records, 1000, true);
+        assert_eq!(stats.since, Some(100));
+        assert_eq!(stats.operations, 3);
+        assert_eq!(
+            stats.by_operation,
+            [
+                ("create_snapshot".to_string(), 2),
+                ("get_usage_scan".to_string(), 1)
+            ]
+        );
+        assert_eq!((stats.scans, stats.snapshots), (1, 2));
+        assert_eq!((stats.backups, stats.bytes_backed_up), (1, 4096));
+
+        let stats = UsageStatistics::from_records(&records, 1002, false);
+        assert_eq!(stats, UsageStatistics::default());
+    }
+
+    #[test]
+    fn writes_one_line_records() {
+        let record = OperationRecord {
+            time: 1,
+            uid: 1000,
+            event: JournalEvent::BackedUp { bytes: 10 },
+        };
+        let line = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            line,
+            r#"{"time":1,"uid":1000,"event":"backed_up","bytes":10}"#
+        );
+        assert_eq!(OperationRecord::parse_journal(&line), [record]);
+    }
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Drive temperatures
+//!
+//! Drives report temperatures in degrees Celsius. Everything that shows one
+//! to the user converts it with [`TemperatureUnit::format`], and everything
+//! that judges one goes through [`TemperatureThresholds::level`], so that
+//! the unit preference and per-drive thresholds apply the same way to the
+//! SMART dialog, the health score and the temperature alerts.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Unit temperatures are displayed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    pub fn to_index(self) -> usize {
+        match self {
+            Self::Celsius => 0,
+            Self::Fahrenheit => 1,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            1 => Self::Fahrenheit,
+            _ => Self::Celsius,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Celsius => "°C",
+            Self::Fahrenheit => "°F",
+        }
+    }
+
+    /// Convert a temperature in degrees Celsius to this unit
+    pub fn convert(self, celsius: f64) -> f64 {
+        match self {
+            Self::Celsius => celsius,
+            Self::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// Format a temperature in degrees Celsius in this unit (e.g., "95 °F")
+    pub fn format(self, celsius: i64) -> String {
+        format!("{:.0} {}", self.convert(celsius as f64), self.symbol())
+    }
+}
+
+/// How hot a drive runs relative to its thresholds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TemperatureLevel {
+    #[default]
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl TemperatureLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "normal" => Some(Self::Normal),
+            "warning" => Some(Self::Warning),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Temperatures, in degrees Celsius, from which a drive runs too hot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemperatureThresholds {
+    pub warning_c: u64,
+    pub critical_c: u64,
+}
+
+impl Default for TemperatureThresholds {
+    fn default() -> Self {
+        Self {
+            warning_c: 60,
+            critical_c: 70,
+        }
+    }
+}
+
+impl TemperatureThresholds {
+    /// Whether the warning threshold lies below the critical one
+    pub fn is_valid(&self) -> bool {
+        self.warning_c < self.critical_c
+    }
+
+    pub fn level(&self, celsius: u64) -> TemperatureLevel {
+        if celsius >= self.critical_c {
+            TemperatureLevel::Critical
+        } else if celsius >= self.warning_c {
+            TemperatureLevel::Warning
+        } else {
+            TemperatureLevel::Normal
+        }
+    }
+}
+
+/// Per-drive temperature thresholds, by drive ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemperatureConfig {
+    #[serde(default)]
+    pub drives: BTreeMap<String, TemperatureThresholds>,
+}
+
+impl TemperatureConfig {
+    /// Thresholds of a drive, or the defaults
+    pub fn thresholds(&self, drive_id: &str) -> TemperatureThresholds {
+        self.drives.get(drive_id).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_in_the_preferred_unit() {
+        assert_eq!(TemperatureUnit::Celsius.format(35), "35 °C");
+        assert_eq!(TemperatureUnit::Fahrenheit.format(35), "95 °F");
+        assert_eq!(TemperatureUnit::Fahrenheit.format(-40), "-40 °F");
+    }
+
+    #[test]
+    fn levels_follow_per_drive_thresholds() {
+        let mut config = TemperatureConfig::default();
+        config.drives.insert(
+            "nvme".to_string(),
+            TemperatureThresholds {
+                warning_c: 70,
+                critical_c: 80,
+            },
+        );
+
+        assert_eq!(
+            config.thresholds("sata").level(65),
+            TemperatureLevel::Warning
+        );
+        assert_eq!(
+            config.thresholds("nvme").level(65),
+            TemperatureLevel::Normal
+        );
+        assert_eq!(
+            config.thresholds("nvme").level(80),
+            TemperatureLevel::Critical
+        );
+        assert!(
+            !TemperatureThresholds {
+                warning_c: 60,
+                critical_c: 60
+            }
+            .is_valid()
+        );
+    }
+}
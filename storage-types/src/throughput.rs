@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Learned throughput of long-running operations
+//!
+//! A drive seldom copies at one speed: hard disks slow down toward their
+//! inner tracks and SSDs drop once their write cache is full. Completed
+//! operations record their speed over each tenth of the way, by kind of
+//! operation and device, and later operations of the same kind on the same
+//! device estimate their remaining time from those runs instead of from
+//! their average speed so far.
+
+use serde::{Deserialize, Serialize};
+
+/// Parts an operation's progress is split into when recording its speed
+pub const THROUGHPUT_SEGMENTS: usize = 10;
+
+/// Speed of one completed operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputRun {
+    /// Bytes per second over each of the [`THROUGHPUT_SEGMENTS`] parts
+    pub segment_speeds: Vec<f64>,
+    /// Seconds since epoch when the operation completed
+    pub finished_at: u64,
+}
+
+impl ThroughputRun {
+    /// The run of an operation that reached each segment end after the
+    /// given seconds, or None when it did not record all of them
+    pub fn from_segment_ends(
+        total_bytes: u64,
+        segment_ends: &[f64],
+        finished_at: u64,
+    ) -> Option<Self> {
+        if total_bytes == 0 || segment_ends.len() != THROUGHPUT_SEGMENTS {
+            return None;
+        }
+        let segment_bytes = total_bytes as f64 / THROUGHPUT_SEGMENTS as f64;
+        let mut start = 0.0;
+        let mut segment_speeds = Vec::with_capacity(THROUGHPUT_SEGMENTS);
+        for &end in segment_ends {
+            // Segments done between two progress updates share their time
+            let seconds = (end - start).max(0.001);
+            segment_speeds.push(segment_bytes / seconds);
+            start = end;
+        }
+        Some(Self {
+            segment_speeds,
+            finished_at,
+        })
+    }
+
+    /// Seconds this run took from fraction `from` to fraction `to` of the way
+    fn seconds_between(&self, total_bytes: u64, from: f64, to: f64) -> f64 {
+        self.segment_speeds
+            .iter()
+            .enumerate()
+            .map(|(index, speed)| {
+                let start = index as f64 / THROUGHPUT_SEGMENTS as f64;
+                let end = (index + 1) as f64 / THROUGHPUT_SEGMENTS as f64;
+                let overlap = (end.min(to) - start.max(from)).max(0.0);
+                overlap * total_bytes as f64 / speed.max(1.0)
+            })
+            .sum()
+    }
+}
+
+/// Least and most time an operation is expected to still take
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EtaRange {
+    pub min_secs: u64,
+    pub max_secs: u64,
+}
+
+/// Recent runs of one kind of operation on one device
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThroughputHistory {
+    /// Oldest first
+    pub runs: Vec<ThroughputRun>,
+}
+
+impl ThroughputHistory {
+    /// Runs kept per kind of operation and device
+    pub const MAX_RUNS: usize = 5;
+
+    pub fn push(&mut self, run: ThroughputRun) {
+        self.runs.push(run);
+        if self.runs.len() > Self::MAX_RUNS {
+            self.runs.drain(..self.runs.len() - Self::MAX_RUNS);
+        }
+    }
+
+    /// Remaining time of an operation that copied `bytes_completed` of
+    /// `total_bytes` in `elapsed_secs`
+    ///
+    /// Each past run gives an estimate, scaled by how much faster or slower
+    /// this operation got this far than that run did; the range spans those
+    /// estimates. Without past runs, the average speed so far is carried on.
+    pub fn estimate(
+        &self,
+        bytes_completed: u64,
+        total_bytes: u64,
+        elapsed_secs: f64,
+    ) -> Option<EtaRange> {
+        if total_bytes == 0 || bytes_completed == 0 || elapsed_secs <= 0.0 {
+            return None;
+        }
+        let done = (bytes_completed as f64 / total_bytes as f64).min(1.0);
+
+        if self.runs.is_empty() {
+            let secs = elapsed_secs * (1.0 - done) / done;
+            return Some(EtaRange {
+                min_secs: secs.round() as u64,
+                max_secs: secs.round() as u64,
+            });
+        }
+
+        let estimates = self.runs.iter().map(|run| {
+            let past = run.seconds_between(total_bytes, 0.0, done);
+            let factor = if past > 0.0 {
+                (elapsed_secs / past).clamp(0.25, 4.0)
+            } else {
+                1.0
+            };
+            run.seconds_between(total_bytes, done, 1.0) * factor
+        });
+        let (min, max) = estimates.fold((f64::MAX, 0.0_f64), |(min, max), secs| {
+            (min.min(secs), max.max(secs))
+        });
+        Some(EtaRange {
+            min_secs: min.round() as u64,
+            max_secs: max.round() as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_from_past_runs() {
+        const GIB: u64 = 1 << 30;
+        // Fast for the first half, half as fast for the second
+        let ends: Vec<f64> = (1..=THROUGHPUT_SEGMENTS)
+            .map(|i| {
+                if i <= 5 {
+                    i as f64
+                } else {
+                    5.0 + 2.0 * (i - 5) as f64
+                }
+            })
+            .collect();
+        let run = ThroughputRun::from_segment_ends(10 * GIB, &ends, 0).unwrap();
+        assert_eq!(run.segment_speeds[0], 2.0 * run.segment_speeds[9]);
+
+        let mut history = ThroughputHistory::default();
+        history.push(run.clone());
+        // Halfway after 5 s, like the recorded run: 10 s left, where the
+        // average speed so far would say 5 s
+        assert_eq!(
+            history.estimate(5 * GIB, 10 * GIB, 5.0),
+            Some(EtaRange {
+                min_secs: 10,
+                max_secs: 10
+            })
+        );
+        // Twice as slow so far: twice as long to go
+        assert_eq!(
+            history.estimate(5 * GIB, 10 * GIB, 10.0).unwrap().max_secs,
+            20
+        );
+
+        let faster = ThroughputRun {
+            segment_speeds: vec![2.0 * run.segment_speeds[0]; THROUGHPUT_SEGMENTS],
+            finished_at: 1,
+        };
+        history.push(faster);
+        let range = history.estimate(5 * GIB, 10 * GIB, 5.0).unwrap();
+        assert_eq!((range.min_secs, range.max_secs), (5, 10));
+
+        assert_eq!(
+            ThroughputHistory::default().estimate(GIB, 4 * GIB, 10.0),
+            Some(EtaRange {
+                min_secs: 30,
+                max_secs: 30
+            })
+        );
+        assert!(ThroughputRun::from_segment_ends(GIB, &[1.0, 2.0], 0).is_none());
+    }
+}
@@ -51,6 +51,16 @@ impl UsageScanParallelismPreset {
             _ => UsageScanParallelismPreset::Balanced,
         }
     }
+
+    /// Scanner threads for a machine with `cpu_count` CPUs
+    pub fn threads(self, cpu_count: usize) -> usize {
+        let cpus = cpu_count.max(1);
+        match self {
+            UsageScanParallelismPreset::Low => cpus.div_ceil(4).max(1),
+            UsageScanParallelismPreset::Balanced => cpus.div_ceil(2).max(1),
+            UsageScanParallelismPreset::High => cpus,
+        }
+    }
 }
 
 impl FromStr for UsageScanParallelismPreset {
@@ -151,6 +161,14 @@ pub struct UsageScanResult {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parallelism_presets_scale_with_cpus() {
+        assert_eq!(UsageScanParallelismPreset::Low.threads(0), 1);
+        assert_eq!(UsageScanParallelismPreset::Low.threads(6), 2);
+        assert_eq!(UsageScanParallelismPreset::Balanced.threads(7), 4);
+        assert_eq!(UsageScanParallelismPreset::High.threads(16), 16);
+    }
+
     #[test]
     fn usage_scan_request_and_delete_result_roundtrip() {
         let request = UsageScanRequest {
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Mounts made in user space
+//!
+//! FUSE filesystems a user mounted themselves (sshfs, shares opened in the
+//! file manager through gvfs, ...) are managed by neither udisks nor rclone.
+//! They are read from the mount table and can be unmounted by their owner.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Filesystem type of the FUSE mount gvfs exposes its shares through
+pub const GVFS_FUSE_TYPE: &str = "fuse.gvfsd-fuse";
+
+/// rclone mounts are listed with their remotes instead
+const RCLONE_FUSE_TYPE: &str = "fuse.rclone";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserMountKind {
+    /// Share mounted by gvfs (e.g., MTP or SMB), a directory of the gvfs
+    /// FUSE mount
+    Gvfs,
+    /// Any other FUSE filesystem
+    Fuse,
+}
+
+/// A filesystem mounted in user space
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserMount {
+    pub mount_point: PathBuf,
+    /// Filesystem type, e.g. "fuse.sshfs"
+    pub fs_type: String,
+    /// Mount source, or the share name for gvfs
+    pub source: String,
+    pub kind: UserMountKind,
+}
+
+impl UserMount {
+    /// The gvfs share named `name` in the gvfs FUSE mount at `gvfs_root`
+    pub fn gvfs_share(gvfs_root: &Path, name: &str) -> Self {
+        Self {
+            mount_point: gvfs_root.join(name),
+            fs_type: GVFS_FUSE_TYPE.to_string(),
+            source: name.to_string(),
+            kind: UserMountKind::Gvfs,
+        }
+    }
+
+    /// Name to show for the mount
+    ///
+    /// gvfs shares are named like "smb-share:server=nas,share=media" or
+    /// "mtp:host=Phone_1234"; other mounts go by their mount point.
+    pub fn display_name(&self) -> String {
+        if self.kind == UserMountKind::Gvfs
+            && let Some((scheme, params)) = self.source.split_once(':')
+        {
+            let param = |key: &str| {
+                params
+                    .split(',')
+                    .find_map(|p| p.strip_prefix(key)?.strip_prefix('='))
+            };
+            return match (scheme, param("host").or(param("server")), param("share")) {
+                ("mtp" | "gphoto2", Some(device), _) => device.replace('_', " "),
+                (_, Some(server), Some(share)) => format!("{share} on {server}"),
+                (_, Some(host), None) => format!("{host} ({scheme})"),
+                _ => self.source.clone(),
+            };
+        }
+        self.mount_point
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.mount_point.display().to_string())
+    }
+}
+
+/// FUSE mounts of one user, from `/proc/self/mountinfo`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserMountTable {
+    /// Mounts other than the gvfs FUSE mount and rclone
+    pub mounts: Vec<UserMount>,
+    /// Where gvfs exposes its shares, if it runs for the user
+    pub gvfs_root: Option<PathBuf>,
+}
+
+/// FUSE mounts owned by `uid` in the contents of a mountinfo file
+pub fn parse_user_mounts(mountinfo: &str, uid: u32) -> UserMountTable {
+    let mut table = UserMountTable::default();
+    let owner = format!("user_id={uid}");
+
+    for line in mountinfo.lines() {
+        let Some((left, right)) = line.split_once(" - ") else {
+            continue;
+        };
+        let Some(mount_point) = left.split_whitespace().nth(4) else {
+            continue;
+        };
+        let mut right = right.split_whitespace();
+        let (Some(fs_type), Some(source), Some(options)) =
+            (right.next(), right.next(), right.next())
+        else {
+            continue;
+        };
+        if !(fs_type == "fuse" || fs_type.starts_with("fuse."))
+            || !options.split(',').any(|option| option == owner)
+        {
+            continue;
+        }
+
+        let mount_point = PathBuf::from(unescape(mount_point));
+        match fs_type {
+            GVFS_FUSE_TYPE => table.gvfs_root = Some(mount_point),
+            RCLONE_FUSE_TYPE => {}
+            _ => table.mounts.push(UserMount {
+                mount_point,
+                fs_type: fs_type.to_string(),
+                source: unescape(source),
+                kind: UserMountKind::Fuse,
+            }),
+        }
+    }
+
+    table
+}
+
+/// Undo the octal escapes of spaces, tabs, newlines and backslashes
+pub(crate) fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let escaped = rest.get(pos + 1..pos + 4);
+        match escaped.and_then(|digits| u8::from_str_radix(digits, 8).ok()) {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_fuse_mounts_of_the_user() {
+        let mountinfo = "\
+22 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw
+80 22 0:50 / /run/user/1000/gvfs rw,nosuid,nodev relatime shared:45 - fuse.gvfsd-fuse gvfsd-fuse rw,user_id=1000,group_id=1000
+81 22 0:51 / /home/u/My\\040Server rw,nosuid,nodev shared:46 - fuse.sshfs u@server:/srv rw,user_id=1000,group_id=1000
+82 22 0:52 / /home/u/nas rw,nosuid,nodev shared:47 - fuse.rclone nas: rw,user_id=1000,group_id=1000
+83 22 0:53 / /home/v/remote rw,nosuid,nodev shared:48 - fuse.sshfs v@server:/srv rw,user_id=1001,group_id=1001
+84 22 8:17 / /media/u/USB rw,nosuid shared:49 - fuseblk /dev/sdb1 rw,user_id=0,group_id=0
+";
+        let table = parse_user_mounts(mountinfo, 1000);
+        assert_eq!(table.gvfs_root, Some(PathBuf::from("/run/user/1000/gvfs")));
+        assert_eq!(table.mounts.len(), 1);
+        assert_eq!(
+            table.mounts[0].mount_point,
+            PathBuf::from("/home/u/My Server")
+        );
+        assert_eq!(table.mounts[0].display_name(), "My Server");
+
+        let root = Path::new("/run/user/1000/gvfs");
+        assert_eq!(
+            UserMount::gvfs_share(root, "smb-share:server=nas.local,share=media").display_name(),
+            "media on nas.local"
+        );
+        assert_eq!(
+            UserMount::gvfs_share(root, "mtp:host=Google_Pixel_7").display_name(),
+            "Google Pixel 7"
+        );
+        assert_eq!(
+            UserMount::gvfs_share(root, "sftp:host=server,user=u").display_name(),
+            "server (sftp)"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Legacy encrypted home vaults
+//!
+//! eCryptfs (the "encrypted home" of older Ubuntu installs) and gocryptfs
+//! encrypt files one by one on top of another filesystem. eCryptfs is
+//! deprecated and barely maintained, so their vaults are found, mounted
+//! read-only to get at the files, and copied into a LUKS volume or an
+//! fscrypt directory.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The stack a vault was made with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VaultKind {
+    Ecryptfs,
+    Gocryptfs,
+}
+
+impl VaultKind {
+    /// Filesystem type of the decrypted view in `/proc/mounts`
+    pub fn mount_type(self) -> &'static str {
+        match self {
+            VaultKind::Ecryptfs => "ecryptfs",
+            VaultKind::Gocryptfs => "fuse.gocryptfs",
+        }
+    }
+}
+
+/// An eCryptfs or gocryptfs vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegacyVault {
+    pub kind: VaultKind,
+    /// Directory holding the encrypted files
+    pub cipher_dir: String,
+    pub owner_uid: u32,
+    pub owner: Option<String>,
+    /// Where the decrypted files are mounted, if they are
+    pub mount_point: Option<String>,
+}
+
+impl LegacyVault {
+    /// Name of the directory the vault is mounted at for access, unique
+    /// per owner and vault, e.g. `alex-Private`
+    pub fn mount_name(&self) -> String {
+        let name = Path::new(&self.cipher_dir)
+            .file_name()
+            .map(|name| name.to_string_lossy().trim_start_matches('.').to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "vault".to_string());
+        match &self.owner {
+            Some(owner) => format!("{owner}-{name}"),
+            None => format!("{}-{name}", self.owner_uid),
+        }
+    }
+}
+
+/// What copying a vault into its new home did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultMigrationResult {
+    /// Files copied and compared with the originals
+    pub files: u64,
+    pub directories: u64,
+    pub bytes: u64,
+}
+
+/// The key signatures in an eCryptfs `Private.sig`: the one of the file
+/// contents key and, if file names are encrypted too, of the file name key
+pub fn parse_ecryptfs_sigs(contents: &str) -> Option<(String, Option<String>)> {
+    let mut sigs = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let data = sigs.next()?.to_string();
+    if !data.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let names = sigs.next().map(str::to_string);
+    Some((data, names))
+}
+
+/// Decrypted vault views in `/proc/mounts`, as kind, encrypted source
+/// directory and mount point
+pub fn vault_mounts(proc_mounts: &str) -> Vec<(VaultKind, String, String)> {
+    proc_mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let source = fields.next()?;
+            let mount_point = fields.next()?;
+            let fs_type = fields.next()?;
+            let kind = [VaultKind::Ecryptfs, VaultKind::Gocryptfs]
+                .into_iter()
+                .find(|kind| kind.mount_type() == fs_type)?;
+            // Spaces in mount points are escaped as octal
+            Some((
+                kind,
+                source.replace("\\040", " "),
+                mount_point.replace("\\040", " "),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vault_mounts_and_sigs() {
+        let mounts = "\
+/dev/sda2 / ext4 rw,relatime 0 0
+/home/alex/.Private /home/alex ecryptfs rw,ecryptfs_sig=0123abcd 0 0
+/home/sam/Vault\\040Enc /run/media/sam/Vault fuse.gocryptfs ro,nosuid 0 0
+";
+        assert_eq!(
+            vault_mounts(mounts),
+            [
+                (
+                    VaultKind::Ecryptfs,
+                    "/home/alex/.Private".to_string(),
+                    "/home/alex".to_string()
+                ),
+                (
+                    VaultKind::Gocryptfs,
+                    "/home/sam/Vault Enc".to_string(),
+                    "/run/media/sam/Vault".to_string()
+                ),
+            ]
+        );
+
+        assert_eq!(
+            parse_ecryptfs_sigs("0123abcd\n4567ef01\n"),
+            Some(("0123abcd".to_string(), Some("4567ef01".to_string())))
+        );
+        assert_eq!(
+            parse_ecryptfs_sigs("0123abcd\n"),
+            Some(("0123abcd".to_string(), None))
+        );
+        assert_eq!(parse_ecryptfs_sigs("not a sig\n"), None);
+
+        let vault = LegacyVault {
+            kind: VaultKind::Ecryptfs,
+            cipher_dir: "/home/.ecryptfs/alex/.Private".to_string(),
+            owner_uid: 1000,
+            owner: Some("alex".to_string()),
+            mount_point: None,
+        };
+        assert_eq!(vault.mount_name(), "alex-Private");
+    }
+}
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Disks of virtual machines
+//!
+//! Inside a guest, the disks are whatever the hypervisor emulates: SMART
+//! and standby mean nothing, and whether space freed by deleting files
+//! returns to the host depends on the disk passing discard requests on.
+//! The rotational flag is what the hypervisor was configured to report,
+//! not a property of the storage behind it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::VolumeInfo;
+
+/// Kernel drivers of paravirtual disks, as linked from `device/driver`
+const VIRTUAL_DISK_DRIVERS: [&str; 3] = ["virtio_blk", "vbd", "xen_blkfront"];
+
+/// Vendor and model fragments of disks emulated by hypervisors and clouds
+const VIRTUAL_DISK_MODELS: [&str; 6] = [
+    "qemu",
+    "vbox",
+    "vmware",
+    "virtual disk",
+    "amazon elastic block store",
+    "google persistentdisk",
+];
+
+/// Whether a disk is provided by a hypervisor rather than hardware, from
+/// its kernel driver (when known) and the vendor and model it reports
+pub fn is_virtual_disk(driver: Option<&str>, vendor: &str, model: &str) -> bool {
+    if driver.is_some_and(|driver| VIRTUAL_DISK_DRIVERS.contains(&driver)) {
+        return true;
+    }
+    let identity = format!("{} {}", vendor.trim(), model.trim()).to_lowercase();
+    VIRTUAL_DISK_MODELS
+        .iter()
+        .any(|fragment| identity.contains(fragment))
+}
+
+/// Hypervisor identifier from `systemd-detect-virt --vm` output, e.g.
+/// "kvm", or None on bare metal
+pub fn parse_detect_virt(output: &str) -> Option<String> {
+    let id = output.trim();
+    (!id.is_empty() && id != "none").then(|| id.to_string())
+}
+
+/// Hypervisor identifier from the DMI system vendor and product name, for
+/// systems without systemd-detect-virt
+pub fn hypervisor_from_dmi(sys_vendor: &str, product_name: &str) -> Option<String> {
+    let identity = format!("{} {}", sys_vendor.trim(), product_name.trim()).to_lowercase();
+    let id = if identity.contains("qemu") || identity.contains("kvm") {
+        "kvm"
+    } else if identity.contains("vmware") {
+        "vmware"
+    } else if identity.contains("virtualbox") || identity.contains("innotek") {
+        "oracle"
+    } else if identity.contains("microsoft") && identity.contains("virtual") {
+        "microsoft"
+    } else if identity.contains("xen") {
+        "xen"
+    } else if identity.contains("amazon ec2") {
+        "amazon"
+    } else if identity.contains("google compute engine") {
+        "google"
+    } else {
+        return None;
+    };
+    Some(id.to_string())
+}
+
+/// Readable name of a systemd-detect-virt hypervisor identifier
+pub fn hypervisor_name(id: &str) -> String {
+    match id {
+        "kvm" => "KVM",
+        "qemu" => "QEMU",
+        "vmware" => "VMware",
+        "oracle" => "VirtualBox",
+        "microsoft" => "Hyper-V",
+        "xen" => "Xen",
+        "bhyve" => "bhyve",
+        "parallels" => "Parallels",
+        "amazon" => "Amazon EC2",
+        "google" => "Google Compute Engine",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Space handed back to the disk by trimming one mounted filesystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrimResult {
+    pub mount_point: String,
+    pub trimmed_bytes: u64,
+}
+
+/// Mount points of the filesystems on a drive's volumes, each once
+pub fn trim_targets(volumes: &[VolumeInfo]) -> Vec<String> {
+    fn collect(volumes: &[VolumeInfo], targets: &mut Vec<String>) {
+        for volume in volumes {
+            if let Some(mount_point) = volume.mount_points.first()
+                && !targets.contains(mount_point)
+            {
+                targets.push(mount_point.clone());
+            }
+            collect(&volume.children, targets);
+        }
+    }
+    let mut targets = Vec::new();
+    collect(volumes, &mut targets);
+    targets
+}
+
+/// Bytes trimmed from `fstrim --verbose` output, e.g.
+/// "/home: 12.3 GiB (13207024640 bytes) trimmed"
+pub fn parse_fstrim_output(output: &str) -> Option<u64> {
+    let (_, rest) = output.split_once('(')?;
+    let (bytes, _) = rest.split_once(" bytes)")?;
+    bytes.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn virtual_disks_are_recognized() {
+        assert!(is_virtual_disk(Some("virtio_blk"), "", ""));
+        assert!(is_virtual_disk(Some("sd"), "QEMU", "QEMU HARDDISK"));
+        assert!(is_virtual_disk(None, "", "VBOX HARDDISK"));
+        assert!(is_virtual_disk(None, "Msft", "Virtual Disk"));
+        assert!(!is_virtual_disk(
+            Some("sd"),
+            "ATA",
+            "Samsung SSD 870 EVO 1TB"
+        ));
+    }
+
+    #[test]
+    fn hypervisors_are_detected() {
+        assert_eq!(parse_detect_virt("kvm\n"), Some("kvm".to_string()));
+        assert_eq!(parse_detect_virt("none\n"), None);
+        assert_eq!(
+            hypervisor_from_dmi("innotek GmbH", "VirtualBox"),
+            Some("oracle".to_string())
+        );
+        assert_eq!(hypervisor_from_dmi("Dell Inc.", "PowerEdge R740"), None);
+        assert_eq!(hypervisor_name("microsoft"), "Hyper-V");
+    }
+
+    #[test]
+    fn fstrim_output_is_parsed() {
+        assert_eq!(
+            parse_fstrim_output("/home: 12.3 GiB (13207024640 bytes) trimmed\n"),
+            Some(13_207_024_640)
+        );
+        assert_eq!(
+            parse_fstrim_output("/: 0 B (0 bytes) trimmed on /dev/vda2\n"),
+            Some(0)
+        );
+        assert_eq!(
+            parse_fstrim_output("fstrim: /boot: the discard operation is not supported"),
+            None
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! NTFS filesystems Windows left hibernated
+//!
+//! Windows hibernates with its NTFS filesystems still in use, and Fast
+//! Startup does the same on every shutdown. Writing to such a filesystem
+//! from another system and resuming Windows afterwards corrupts it, which is
+//! what dual-boot users hit; mounted read-only, it is safe to look at.
+//! `ntfs-3g.probe` tells by its exit status.
+
+use serde::{Deserialize, Serialize};
+
+/// `ntfs-3g.probe` exit status of a hibernated filesystem
+pub const NTFS_PROBE_HIBERNATED: i32 = 14;
+
+/// `ntfs-3g.probe` exit status of a filesystem not cleanly unmounted, with
+/// metadata kept in the Windows cache as Fast Startup leaves it
+pub const NTFS_PROBE_UNCLEAN: i32 = 15;
+
+/// Why an NTFS filesystem must not be written to until Windows shuts down
+/// fully
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowsHibernation {
+    /// Windows is hibernated
+    Hibernated,
+    /// Windows was shut down with Fast Startup enabled
+    FastStartup,
+}
+
+impl WindowsHibernation {
+    /// The state `ntfs-3g.probe --readwrite` reported with `status`; None
+    /// when the filesystem can be written to, or the probe failed for
+    /// another reason
+    pub fn from_probe_status(status: i32) -> Option<Self> {
+        match status {
+            NTFS_PROBE_HIBERNATED => Some(Self::Hibernated),
+            NTFS_PROBE_UNCLEAN => Some(Self::FastStartup),
+            _ => None,
+        }
+    }
+}
+
+/// Whether mount `options` (as in "ro,noexec") ask for a read-only mount,
+/// which a hibernated filesystem is safe to get
+pub fn mounts_read_only(read_only: bool, options: &[String]) -> bool {
+    read_only || options.iter().any(|option| option == "ro")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_probe_status() {
+        assert_eq!(
+            WindowsHibernation::from_probe_status(14),
+            Some(WindowsHibernation::Hibernated)
+        );
+        assert_eq!(
+            WindowsHibernation::from_probe_status(15),
+            Some(WindowsHibernation::FastStartup)
+        );
+        assert_eq!(WindowsHibernation::from_probe_status(0), None);
+        assert_eq!(WindowsHibernation::from_probe_status(12), None);
+
+        assert!(mounts_read_only(true, &[]));
+        assert!(mounts_read_only(false, &["noexec".into(), "ro".into()]));
+        assert!(!mounts_read_only(false, &["rw".into()]));
+    }
+}
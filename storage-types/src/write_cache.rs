@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Volatile write cache of drives
+//!
+//! ATA drives switch their cache with `hdparm -W`, NVMe drives through the
+//! Volatile Write Cache feature. Neither setting reliably survives a power
+//! cycle, so a persistent one is kept in a udev rule per drive that switches
+//! the cache again whenever the drive appears.
+
+use serde::{Deserialize, Serialize};
+
+use crate::io_tuning::rule_file_id;
+
+/// Prefix of the udev rules files written for drives with a fixed cache
+pub const WRITE_CACHE_RULE_PREFIX: &str = "61-cosmic-ext-storage-write-cache-";
+
+/// NVMe feature identifier of the volatile write cache
+const NVME_VWC_FEATURE: &str = "--feature-id=6";
+
+/// Volatile write cache of a drive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteCacheStatus {
+    /// Whether the drive reports a cache that can be switched
+    pub supported: bool,
+    pub enabled: bool,
+    /// Whether a udev rule sets the cache when the drive appears
+    pub persistent: bool,
+}
+
+/// Program and arguments switching the cache of `device`
+pub fn write_cache_command(nvme: bool, device: &str, enabled: bool) -> (&'static str, Vec<String>) {
+    let value = u8::from(enabled);
+    if nvme {
+        (
+            "nvme",
+            vec![
+                "set-feature".to_string(),
+                device.to_string(),
+                NVME_VWC_FEATURE.to_string(),
+                format!("--value={value}"),
+            ],
+        )
+    } else {
+        (
+            "hdparm",
+            vec!["-W".to_string(), value.to_string(), device.to_string()],
+        )
+    }
+}
+
+/// Program and arguments reading the cache state of `device`
+pub fn write_cache_query(nvme: bool, device: &str) -> (&'static str, Vec<String>) {
+    if nvme {
+        (
+            "nvme",
+            vec![
+                "get-feature".to_string(),
+                device.to_string(),
+                NVME_VWC_FEATURE.to_string(),
+            ],
+        )
+    } else {
+        ("hdparm", vec!["-W".to_string(), device.to_string()])
+    }
+}
+
+/// Cache state from `hdparm -W`, e.g. " write-caching =  1 (on)"; `None`
+/// when the drive does not support switching it
+pub fn parse_hdparm_write_cache(output: &str) -> Option<bool> {
+    let value = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("write-caching"))?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim();
+    match value.split_whitespace().next()? {
+        "1" => Some(true),
+        "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Cache state from `nvme get-feature`, e.g.
+/// "get-feature:0x06 (Volatile Write Cache), Current value:0x00000001"
+pub fn parse_nvme_write_cache(output: &str) -> Option<bool> {
+    let value = output.split("Current value:").nth(1)?.trim();
+    let value = value.split_whitespace().next()?;
+    let value = u32::from_str_radix(value.trim_start_matches("0x"), 16).ok()?;
+    Some(value & 1 == 1)
+}
+
+/// udev rule running `program` with `args` on the drive whose udev property
+/// `key` (e.g. "ID_WWN") is `value`; `$devnode` in `args` is its device node
+pub fn write_cache_udev_rule(key: &str, value: &str, program: &str, args: &[String]) -> String {
+    format!(
+        "# Written by COSMIC Storage; removed when the cache is left to the drive\n\
+         ACTION==\"add\", SUBSYSTEM==\"block\", ENV{{DEVTYPE}}==\"disk\", \
+         ENV{{{key}}}==\"{value}\", RUN+=\"{program} {}\"\n",
+        args.join(" ")
+    )
+}
+
+/// Name of the udev rules file of the drive identified by `value`
+pub fn write_cache_rule_file_name(value: &str) -> String {
+    format!("{WRITE_CACHE_RULE_PREFIX}{}.rules", rule_file_id(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hdparm_reports_the_cache_state() {
+        assert_eq!(
+            parse_hdparm_write_cache("\n/dev/sda:\n write-caching =  1 (on)\n"),
+            Some(true)
+        );
+        assert_eq!(
+            parse_hdparm_write_cache("/dev/sdb:\n write-caching =  0 (off)\n"),
+            Some(false)
+        );
+        assert_eq!(
+            parse_hdparm_write_cache("/dev/sdc:\n write-caching = not supported\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn nvme_reports_the_cache_state() {
+        assert_eq!(
+            parse_nvme_write_cache(
+                "get-feature:0x06 (Volatile Write Cache), Current value:0x00000001\n"
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            parse_nvme_write_cache(
+                "get-feature:0x6 (Volatile Write Cache), Current value:00000000"
+            ),
+            Some(false)
+        );
+        assert_eq!(parse_nvme_write_cache("NVMe status: Invalid Field"), None);
+    }
+
+    #[test]
+    fn rule_switches_the_cache_of_the_matching_drive() {
+        let (program, args) = write_cache_command(false, "$devnode", false);
+        let rule = write_cache_udev_rule("ID_SERIAL", "WDC_WD40EFRX", "/usr/sbin/hdparm", &args);
+        assert_eq!(program, "hdparm");
+        assert!(rule.contains("ENV{ID_SERIAL}==\"WDC_WD40EFRX\""));
+        assert!(rule.contains("RUN+=\"/usr/sbin/hdparm -W 0 $devnode\""));
+
+        let (program, args) = write_cache_command(true, "/dev/nvme0n1", true);
+        assert_eq!(program, "nvme");
+        assert_eq!(args.last().map(String::as_str), Some("--value=1"));
+    }
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Parsing mount and crypt options, from a typical fstab entry to the
+//! pathological strings device metadata may carry
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use storage_udisks::{merge_other_with_managed, normalize_options, option_tokens};
+
+fn inputs() -> Vec<(&'static str, String)> {
+    let typical =
+        "nofail, noauto,x-udisks-auth ,x-gvfs-show,x-gvfs-name=Backup,uid=1000".to_string();
+    let duplicated = (0..10_000)
+        .map(|i| format!("opt{}", i % 100))
+        .collect::<Vec<_>>()
+        .join(",");
+    let separators = ", ,".repeat(100_000);
+    vec![
+        ("typical", typical),
+        ("duplicated", duplicated),
+        ("separators", separators),
+    ]
+}
+
+fn options(c: &mut Criterion) {
+    let mut group = c.benchmark_group("options");
+    for (name, input) in inputs() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("tokens", name), &input, |b, input| {
+            b.iter(|| option_tokens(black_box(input)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("normalize", name), &input, |b, input| {
+            b.iter(|| normalize_options(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("merge", name), &input, |b, input| {
+            b.iter(|| {
+                merge_other_with_managed(
+                    black_box(input),
+                    vec!["noauto".to_string(), "x-udisks-auth".to_string()],
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, options);
+criterion_main!(benches);
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! UDisks2 implementation of the storage-contracts adapters
+//!
+//! The default backend of the service: each method forwards to the
+//! device-path APIs of this crate.
+
+use async_trait::async_trait;
+use std::os::fd::OwnedFd;
+use storage_contracts::{ImageOpsAdapter, PartitionOpsAdapter, StorageError, StorageErrorKind};
+use storage_types::{CreatePartitionInfo, DiskInfo, PartitionInfo};
+
+use crate::error::DiskError;
+use crate::manager::DiskManager;
+
+/// Partitioning and imaging through UDisks2
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UdisksBackend;
+
+impl From<DiskError> for StorageError {
+    fn from(err: DiskError) -> Self {
+        let kind = match &err {
+            DiskError::DeviceNotFound(_) => StorageErrorKind::NotFound,
+            DiskError::ResourceBusy { .. } => StorageErrorKind::Busy,
+            DiskError::InvalidPath(_) => StorageErrorKind::InvalidInput,
+            DiskError::NotConnected(_)
+            | DiskError::ConnectionFailed(_)
+            | DiskError::DBusError(_)
+            | DiskError::ZbusError(_) => StorageErrorKind::Unavailable,
+            DiskError::OperationFailed(_) => StorageErrorKind::Internal,
+        };
+        StorageError::new(kind, err.to_string())
+    }
+}
+
+fn internal(err: anyhow::Error) -> StorageError {
+    StorageError::new(StorageErrorKind::Internal, err.to_string())
+}
+
+#[async_trait]
+impl PartitionOpsAdapter for UdisksBackend {
+    async fn list_disks_with_partitions(
+        &self,
+    ) -> Result<Vec<(DiskInfo, Vec<PartitionInfo>)>, StorageError> {
+        let manager = DiskManager::new().await.map_err(|e| {
+            StorageError::new(
+                StorageErrorKind::Unavailable,
+                format!("Failed to initialize disk manager: {e}"),
+            )
+        })?;
+        crate::get_disks_with_partitions(&manager)
+            .await
+            .map_err(internal)
+    }
+
+    async fn resolve_block_path_for_device(&self, device: &str) -> Result<String, StorageError> {
+        Ok(crate::block_object_path_for_device(device).await?)
+    }
+
+    async fn create_partition_table(
+        &self,
+        block_path: &str,
+        table_type: &str,
+    ) -> Result<(), StorageError> {
+        Ok(crate::create_partition_table(block_path, table_type).await?)
+    }
+
+    async fn create_partition(
+        &self,
+        block_path: &str,
+        offset: u64,
+        size: u64,
+        type_id: &str,
+    ) -> Result<String, StorageError> {
+        Ok(crate::create_partition(block_path, offset, size, type_id).await?)
+    }
+
+    async fn create_partition_with_filesystem(
+        &self,
+        block_path: &str,
+        info: &CreatePartitionInfo,
+    ) -> Result<String, StorageError> {
+        Ok(crate::create_partition_with_filesystem(block_path, info).await?)
+    }
+
+    async fn delete_partition(&self, partition_path: &str) -> Result<(), StorageError> {
+        Ok(crate::delete_partition(partition_path).await?)
+    }
+
+    async fn resize_partition(
+        &self,
+        partition_path: &str,
+        new_size: u64,
+    ) -> Result<(), StorageError> {
+        Ok(crate::resize_partition(partition_path, new_size).await?)
+    }
+
+    async fn set_partition_type(
+        &self,
+        partition_path: &str,
+        type_id: &str,
+    ) -> Result<(), StorageError> {
+        Ok(crate::set_partition_type(partition_path, type_id).await?)
+    }
+
+    async fn set_partition_flags(
+        &self,
+        partition_path: &str,
+        flags: u64,
+    ) -> Result<(), StorageError> {
+        Ok(crate::set_partition_flags(partition_path, flags).await?)
+    }
+
+    async fn set_partition_name(
+        &self,
+        partition_path: &str,
+        name: &str,
+    ) -> Result<(), StorageError> {
+        Ok(crate::set_partition_name(partition_path, name).await?)
+    }
+}
+
+#[async_trait]
+impl ImageOpsAdapter for UdisksBackend {
+    async fn open_for_backup_by_device(&self, device: &str) -> Result<OwnedFd, StorageError> {
+        crate::open_for_backup_by_device(device)
+            .await
+            .map_err(internal)
+    }
+
+    async fn open_for_restore_by_device(&self, device: &str) -> Result<OwnedFd, StorageError> {
+        crate::open_for_restore_by_device(device)
+            .await
+            .map_err(internal)
+    }
+
+    async fn loop_setup_device_path(&self, image_path: &str) -> Result<String, StorageError> {
+        crate::loop_setup_device_path(image_path)
+            .await
+            .map_err(internal)
+    }
+}
@@ -1,7 +1,16 @@
+use std::borrow::Cow;
+
 use zbus::zvariant::{OwnedValue, Value};
 
+/// NUL-terminated bytestring of `value`, as UDisks stores paths and
+/// options.
+///
+/// Anything after an embedded NUL is dropped, as readers of the
+/// bytestring would never see it.
 pub fn encode_bytestring(value: &str) -> Vec<u8> {
-    let mut bytes = value.as_bytes().to_vec();
+    let value = value.split('\0').next().unwrap_or_default();
+    let mut bytes = Vec::with_capacity(value.len() + 1);
+    bytes.extend_from_slice(value.as_bytes());
     bytes.push(0);
     bytes
 }
@@ -17,13 +26,19 @@ pub fn owned_value_to_bytestring(value: &OwnedValue) -> Option<String> {
     Some(decode_c_string_bytes(&bytes))
 }
 
-pub fn decode_c_string_bytes(bytes: &[u8]) -> String {
+/// Text of a bytestring up to its first NUL, borrowed when it is valid
+/// UTF-8; invalid sequences become U+FFFD.
+pub fn decode_c_string(bytes: &[u8]) -> Cow<'_, str> {
     let raw = match bytes.split(|b| *b == 0).next() {
         Some(v) => v,
         None => bytes,
     };
 
-    String::from_utf8_lossy(raw).to_string()
+    String::from_utf8_lossy(raw)
+}
+
+pub fn decode_c_string_bytes(bytes: &[u8]) -> String {
+    decode_c_string(bytes).into_owned()
 }
 
 pub fn decode_mount_points(mount_points: Vec<Vec<u8>>) -> Vec<String> {
@@ -50,6 +65,14 @@ mod tests {
         assert_eq!(decode_c_string_bytes(bytes), "/run/media/user/DISK");
     }
 
+    #[test]
+    fn bytestrings_round_trip_up_to_nul() {
+        assert_eq!(encode_bytestring("/dev/sda1"), b"/dev/sda1\0");
+        assert_eq!(encode_bytestring("ro\0rw"), b"ro\0");
+        assert!(matches!(decode_c_string(b"/mnt\0"), Cow::Borrowed("/mnt")));
+        assert_eq!(decode_c_string(b"caf\xe9\0"), "caf\u{fffd}");
+    }
+
     #[test]
     fn decode_mount_points_filters_empty_entries() {
         let decoded = decode_mount_points(vec![
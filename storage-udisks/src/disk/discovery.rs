@@ -3,6 +3,7 @@
 //! Disk and volume discovery - builds storage_types::DiskInfo and VolumeInfo directly from UDisks2.
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -166,6 +167,61 @@ async fn block_device_path(
     })
 }
 
+/// The kernel's read-only flag of a block device, set for example by the
+/// lock switch of an SD card
+fn sysfs_read_only(device_path: &str) -> bool {
+    let Some(name) = Path::new(device_path).file_name() else {
+        return false;
+    };
+    std::fs::read_to_string(Path::new("/sys/class/block").join(name).join("ro"))
+        .is_ok_and(|ro| ro.trim() == "1")
+}
+
+/// A sector size of a block device from its sysfs queue, e.g.
+/// `logical_block_size`
+fn sysfs_sector_size(device_path: &str, attribute: &str) -> u64 {
+    Path::new(device_path)
+        .file_name()
+        .and_then(|name| {
+            std::fs::read_to_string(
+                Path::new("/sys/class/block")
+                    .join(name)
+                    .join("queue")
+                    .join(attribute),
+            )
+            .ok()
+        })
+        .and_then(|size| size.trim().parse().ok())
+        .unwrap_or(storage_types::alignment::DEFAULT_SECTOR_SIZE)
+}
+
+/// A numeric attribute of a block device's sysfs queue, e.g. `rotational`
+fn sysfs_queue_value(device_path: &str, attribute: &str) -> Option<u64> {
+    let name = Path::new(device_path).file_name()?;
+    std::fs::read_to_string(
+        Path::new("/sys/class/block")
+            .join(name)
+            .join("queue")
+            .join(attribute),
+    )
+    .ok()?
+    .trim()
+    .parse()
+    .ok()
+}
+
+/// Name of the kernel driver bound to a block device, e.g. "virtio_blk"
+fn sysfs_driver(device_path: &str) -> Option<String> {
+    let name = Path::new(device_path).file_name()?;
+    let driver = std::fs::read_link(
+        Path::new("/sys/class/block")
+            .join(name)
+            .join("device/driver"),
+    )
+    .ok()?;
+    Some(driver.file_name()?.to_string_lossy().into_owned())
+}
+
 async fn build_disk_info(
     connection: &Connection,
     drive_path: Option<&OwnedObjectPath>,
@@ -255,6 +311,14 @@ async fn build_disk_info(
     };
 
     let connection_bus = infer_connection_bus(&device_path, &model, &vendor, is_loop, optical);
+    // Optical drives are written by burning, which the flag does not reflect
+    let read_only = !optical && sysfs_read_only(&device_path);
+    let logical_sector_size = sysfs_sector_size(&device_path, "logical_block_size");
+    let physical_sector_size = sysfs_sector_size(&device_path, "physical_block_size");
+    let virtual_disk = !is_loop
+        && storage_types::is_virtual_disk(sysfs_driver(&device_path).as_deref(), &vendor, &model);
+    let discard_supported = sysfs_queue_value(&device_path, "discard_max_bytes").unwrap_or(0) > 0;
+    let rotational = sysfs_queue_value(&device_path, "rotational") == Some(1);
 
     let rotation_rate = if rotation_rate > 0 {
         Some(rotation_rate as u16)
@@ -272,13 +336,19 @@ async fn build_disk_info(
         size,
         connection_bus,
         rotation_rate,
+        logical_sector_size,
+        physical_sector_size,
         removable,
         ejectable,
         media_removable,
         media_available,
         optical,
         optical_blank,
+        read_only,
         can_power_off,
+        virtual_disk,
+        discard_supported,
+        rotational,
         is_loop,
         backing_file,
         partition_table_type: None,
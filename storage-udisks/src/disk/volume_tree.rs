@@ -74,7 +74,16 @@ pub(crate) async fn probe_basic_block(
         None => None,
     };
 
-    let id_type = block_proxy.id_type().await.map_err(anyhow::Error::msg)?;
+    let mut id_type = block_proxy.id_type().await.map_err(anyhow::Error::msg)?;
+    // UDisks probes a device only when udev reports a change, so a
+    // filesystem made by another tool may not be known yet
+    if id_type.is_empty()
+        && let Some(signature) = device_path
+            .as_deref()
+            .and_then(crate::infra::probe::probe_device)
+    {
+        id_type = signature.fs_type;
+    }
     let size = block_proxy.size().await.map_err(anyhow::Error::msg)?;
 
     // Get partition offset and number if this is a partition
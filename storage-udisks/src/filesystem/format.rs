@@ -56,6 +56,11 @@ pub async fn format_filesystem(
         format_opts.insert("discard", Value::from(true));
     }
 
+    // Extra mkfs arguments (validated by the caller; needs UDisks 2.10+)
+    if !options.mkfs_args.is_empty() {
+        format_opts.insert("mkfs-args", Value::from(options.mkfs_args.clone()));
+    }
+
     block_proxy
         .format(fs_type, format_opts)
         .await
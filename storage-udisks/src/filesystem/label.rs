@@ -33,6 +33,58 @@ pub async fn get_filesystem_label(device: &str) -> Result<String, DiskError> {
     Ok(label)
 }
 
+/// Get filesystem UUID for a device
+///
+/// # Arguments
+/// * `device` - Device path (e.g., "/dev/sda1")
+///
+/// # Returns
+/// The filesystem UUID (may be empty string for filesystems without one)
+pub async fn get_filesystem_uuid(device: &str) -> Result<String, DiskError> {
+    let connection = Connection::system().await.map_err(|e| {
+        DiskError::ConnectionFailed(format!("Failed to connect to system bus: {}", e))
+    })?;
+
+    let block_path = crate::disk::resolve::block_object_path_for_device(device).await?;
+
+    let block_proxy = BlockProxy::builder(&connection)
+        .path(&block_path)
+        .map_err(|e| DiskError::InvalidPath(format!("Invalid block path: {}", e)))?
+        .build()
+        .await
+        .map_err(|e| DiskError::DBusError(e.to_string()))?;
+
+    let uuid = block_proxy.id_uuid().await.unwrap_or_default();
+
+    Ok(uuid)
+}
+
+/// Get filesystem type for a device
+///
+/// # Arguments
+/// * `device` - Device path (e.g., "/dev/sda1")
+///
+/// # Returns
+/// The filesystem type as probed by udisks (e.g., "ext4"; empty if unknown)
+pub async fn get_filesystem_type(device: &str) -> Result<String, DiskError> {
+    let connection = Connection::system().await.map_err(|e| {
+        DiskError::ConnectionFailed(format!("Failed to connect to system bus: {}", e))
+    })?;
+
+    let block_path = crate::disk::resolve::block_object_path_for_device(device).await?;
+
+    let block_proxy = BlockProxy::builder(&connection)
+        .path(&block_path)
+        .map_err(|e| DiskError::InvalidPath(format!("Invalid block path: {}", e)))?
+        .build()
+        .await
+        .map_err(|e| DiskError::DBusError(e.to_string()))?;
+
+    let id_type = block_proxy.id_type().await.unwrap_or_default();
+
+    Ok(id_type)
+}
+
 /// Set filesystem label
 pub async fn set_filesystem_label(device_path: &str, label: &str) -> Result<(), DiskError> {
     let connection = Connection::system()
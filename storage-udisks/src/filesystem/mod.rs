@@ -19,6 +19,8 @@ pub use config::{MountOptionsSettings, get_mount_options, reset_mount_options, s
 
 pub use check::{check_filesystem, repair_filesystem};
 pub use format::format_filesystem;
-pub use label::{get_filesystem_label, set_filesystem_label};
+pub use label::{
+    get_filesystem_label, get_filesystem_type, get_filesystem_uuid, set_filesystem_label,
+};
 pub use mount::{get_mount_point, mount_filesystem, unmount_filesystem};
 pub use ownership::take_filesystem_ownership;
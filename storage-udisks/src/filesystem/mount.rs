@@ -5,6 +5,7 @@
 use crate::error::DiskError;
 use std::collections::HashMap;
 use storage_types::MountOptions;
+use udisks2::block::BlockProxy;
 use udisks2::filesystem::FilesystemProxy;
 use zbus::{Connection, zvariant::Value};
 
@@ -24,19 +25,27 @@ fn get_username_from_uid(uid: u32) -> Option<String> {
     }
 }
 
+/// Filesystems without POSIX ownership that need `uid=`/`gid=` to be usable by the caller
+const OWNERLESS_FILESYSTEMS: &[&str] = &["vfat", "exfat", "ntfs", "ntfs3", "msdos", "iso9660"];
+
 /// Mount a filesystem
 ///
 /// # Arguments
 /// * `device_path` - Device path (e.g., "/dev/sda1")
-/// * `_mount_point` - Mount point (unused when auto-mounting)
+/// * `mount_point` - Explicit mount point, or empty to let UDisks2 choose one
 /// * `options` - Mount options
 /// * `caller_uid` - Optional UID to mount as (important for proper file ownership and mount path)
 pub async fn mount_filesystem(
     device_path: &str,
-    _mount_point: &str,
+    mount_point: &str,
     options: MountOptions,
     caller_uid: Option<u32>,
 ) -> Result<String, DiskError> {
+    options.check_other().map_err(DiskError::OperationFailed)?;
+    if !mount_point.is_empty() {
+        return mount_filesystem_at(device_path, mount_point, options, caller_uid).await;
+    }
+
     let connection = Connection::system()
         .await
         .map_err(|e| DiskError::ConnectionFailed(e.to_string()))?;
@@ -92,6 +101,94 @@ pub async fn mount_filesystem(
     Ok(mount_point_bytes)
 }
 
+/// Mount a filesystem at an explicit directory
+///
+/// UDisks2 always chooses its own mount location, so explicit targets are
+/// mounted with `mount(8)`, always `nosuid` and `nodev` like UDisks2 does. A
+/// missing directory is created and chowned to the caller so it can be
+/// removed again after unmount; an existing one is left as it is.
+async fn mount_filesystem_at(
+    device_path: &str,
+    mount_point: &str,
+    options: MountOptions,
+    caller_uid: Option<u32>,
+) -> Result<String, DiskError> {
+    let connection = Connection::system()
+        .await
+        .map_err(|e| DiskError::ConnectionFailed(e.to_string()))?;
+
+    let block_path = crate::disk::resolve::block_object_path_for_device(device_path).await?;
+    let block_proxy = BlockProxy::builder(&connection)
+        .path(&block_path)
+        .map_err(|e| DiskError::DBusError(e.to_string()))?
+        .build()
+        .await
+        .map_err(|e| DiskError::DBusError(e.to_string()))?;
+    let fs_type = block_proxy.id_type().await.unwrap_or_default();
+
+    let mut options_vec: Vec<String> = Vec::new();
+    if options.read_only {
+        options_vec.push("ro".to_string());
+    }
+    if options.no_exec {
+        options_vec.push("noexec".to_string());
+    }
+    options_vec.push("nosuid".to_string());
+    options_vec.push("nodev".to_string());
+    if let Some(uid) = caller_uid
+        && OWNERLESS_FILESYSTEMS.contains(&fs_type.as_str())
+    {
+        let gid = get_primary_gid_from_uid(uid).unwrap_or(uid);
+        options_vec.push(format!("uid={uid}"));
+        options_vec.push(format!("gid={gid}"));
+    }
+    options_vec.extend(options.other.iter().cloned());
+
+    let created = std::fs::symlink_metadata(mount_point).is_err();
+    if created {
+        std::fs::create_dir_all(mount_point).map_err(|e| {
+            DiskError::OperationFailed(format!("Failed to create mount point {mount_point}: {e}"))
+        })?;
+        if let Some(uid) = caller_uid {
+            let gid = get_primary_gid_from_uid(uid);
+            let _ = std::os::unix::fs::lchown(mount_point, Some(uid), gid);
+        }
+    }
+
+    let output = tokio::process::Command::new("mount")
+        .arg("-o")
+        .arg(options_vec.join(","))
+        .arg("--")
+        .arg(device_path)
+        .arg(mount_point)
+        .output()
+        .await
+        .map_err(|e| DiskError::OperationFailed(format!("Failed to run mount: {e}")))?;
+
+    if !output.status.success() {
+        if created {
+            let _ = std::fs::remove_dir(mount_point);
+        }
+        return Err(DiskError::OperationFailed(format!(
+            "Mount failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(mount_point.to_string())
+}
+
+/// Get primary group ID from UID
+fn get_primary_gid_from_uid(uid: u32) -> Option<u32> {
+    // SAFETY: getpwuid is thread-safe in POSIX
+    let pw = unsafe { libc::getpwuid(uid) };
+    if pw.is_null() {
+        return None;
+    }
+    // SAFETY: pw is valid
+    Some(unsafe { (*pw).pw_gid })
+}
+
 /// Unmount a filesystem
 pub async fn unmount_filesystem(device_or_mount: &str, force: bool) -> Result<(), DiskError> {
     let connection = Connection::system()
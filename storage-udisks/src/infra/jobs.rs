@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! UDisks jobs, the long operations UDisks runs on behalf of a method call
+
+use crate::error::DiskError;
+use std::collections::HashMap;
+use zbus::Connection;
+use zbus::fdo::ObjectManagerProxy;
+use zbus::zvariant::{OwnedObjectPath, Value};
+use zbus_macros::proxy;
+
+const JOB_INTERFACE: &str = "org.freedesktop.UDisks2.Job";
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Job"
+)]
+trait UDisks2Job {
+    fn cancel(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    /// Objects the job works on
+    #[zbus(property)]
+    fn objects(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    #[zbus(property)]
+    fn cancelable(&self) -> zbus::Result<bool>;
+}
+
+/// Cancel the jobs running on `device_path`, such as the format of the
+/// filesystem on it
+///
+/// Returns the number of jobs cancelled; jobs that can't be cancelled are
+/// left running.
+pub async fn cancel_device_jobs(device_path: &str) -> Result<usize, DiskError> {
+    let connection = Connection::system()
+        .await
+        .map_err(|e| DiskError::ConnectionFailed(e.to_string()))?;
+    let block_path = crate::disk::resolve::block_object_path_for_device(device_path).await?;
+
+    let manager = ObjectManagerProxy::builder(&connection)
+        .destination("org.freedesktop.UDisks2")?
+        .path("/org/freedesktop/UDisks2")?
+        .build()
+        .await
+        .map_err(|e| DiskError::DBusError(e.to_string()))?;
+    let objects = manager
+        .get_managed_objects()
+        .await
+        .map_err(|e| DiskError::DBusError(e.to_string()))?;
+
+    let mut cancelled = 0;
+    for (path, interfaces) in objects {
+        if !interfaces.keys().any(|name| name.as_str() == JOB_INTERFACE) {
+            continue;
+        }
+        let job = UDisks2JobProxy::builder(&connection)
+            .path(path)?
+            .build()
+            .await
+            .map_err(|e| DiskError::DBusError(e.to_string()))?;
+        let on_device = job
+            .objects()
+            .await
+            .is_ok_and(|objects| objects.contains(&block_path));
+        if !on_device || !job.cancelable().await.unwrap_or(false) {
+            continue;
+        }
+
+        job.cancel(HashMap::new())
+            .await
+            .map_err(|e| DiskError::OperationFailed(format!("Failed to cancel job: {e}")))?;
+        cancelled += 1;
+    }
+    Ok(cancelled)
+}
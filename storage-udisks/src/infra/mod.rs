@@ -1,4 +1,6 @@
+pub mod jobs;
 pub mod options;
+pub mod probe;
 pub mod process;
 pub mod udisks_block_config;
 pub mod usage;
@@ -1,17 +1,26 @@
 use std::collections::HashSet;
 
-/// Split a comma-delimited mount/crypt options string into trimmed tokens.
+/// Tokens of a comma-delimited mount/crypt options string, borrowed from
+/// `input`.
 ///
+/// - Stops at the first NUL, as the bytestring UDisks stores would
 /// - Splits on `,`
 /// - Trims whitespace
-/// - Drops empty tokens
-pub fn split_options(input: &str) -> Vec<String> {
+/// - Drops empty tokens and tokens with control characters, which would
+///   break the fstab/crypttab line they are written to
+pub fn option_tokens(input: &str) -> impl Iterator<Item = &str> {
+    let input = input.split('\0').next().unwrap_or_default();
     input
         .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .map(ToOwned::to_owned)
-        .collect()
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !s.contains(char::is_control))
+}
+
+/// Split a comma-delimited mount/crypt options string into trimmed tokens.
+///
+/// Owned form of [`option_tokens`].
+pub fn split_options(input: &str) -> Vec<String> {
+    option_tokens(input).map(ToOwned::to_owned).collect()
 }
 
 /// Join tokens into a comma-delimited option string.
@@ -107,6 +116,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn split_drops_control_characters_and_stops_at_nul() {
+        assert_eq!(
+            split_options("ro,\nnoauto\n/dev/sda1 / ext4,nofail\0,x-udisks-auth"),
+            vec!["ro".to_string(), "nofail".to_string()]
+        );
+        assert_eq!(option_tokens("\0ro").count(), 0);
+    }
+
     #[test]
     fn stable_dedup_preserves_first_seen() {
         assert_eq!(
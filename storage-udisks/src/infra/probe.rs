@@ -0,0 +1,28 @@
+use std::fs::File;
+use std::io::Read;
+
+use storage_types::{FilesystemSignature, SIGNATURE_PROBE_SIZE, probe_filesystem_signature};
+use tracing::debug;
+
+/// The filesystem on `device` read from its superblock, for devices UDisks
+/// hasn't probed yet
+///
+/// Needs only read access to the device node; `None` when it can't be read
+/// or holds nothing recognized.
+pub fn probe_device(device: &str) -> Option<FilesystemSignature> {
+    let mut data = Vec::with_capacity(SIGNATURE_PROBE_SIZE);
+    let read = File::open(device).and_then(|file| {
+        file.take(SIGNATURE_PROBE_SIZE as u64)
+            .read_to_end(&mut data)
+    });
+    if let Err(e) = read {
+        debug!("cannot probe {device}: {e}");
+        return None;
+    }
+    let signature = probe_filesystem_signature(&data)?;
+    debug!(
+        "probed {device}: {} label={:?} uuid={:?}",
+        signature.fs_type, signature.label, signature.uuid
+    );
+    Some(signature)
+}
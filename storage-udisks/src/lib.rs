@@ -1,6 +1,9 @@
 mod dbus;
 mod infra;
 
+// storage-contracts adapters
+pub mod adapter;
+
 // Error types
 pub mod error;
 
@@ -46,6 +49,8 @@ pub use smart::{SmartInfo, SmartSelfTestKind};
 // Re-export error types
 pub use error::DiskError;
 
+pub use adapter::UdisksBackend;
+
 // Re-export configuration types
 pub use encryption::config::EncryptionOptionsSettings;
 pub use filesystem::MountOptionsSettings;
@@ -64,6 +69,7 @@ pub use disk::{
     },
 };
 pub use gpt::{fallback_gpt_usable_range_bytes, probe_gpt_usable_range_bytes};
+pub use infra::jobs::cancel_device_jobs;
 pub use infra::process::{find_processes_using_mount, kill_processes};
 pub use lvm::list_lvs_for_pv;
 
@@ -75,9 +81,10 @@ pub use partition::{
 
 // Filesystem operations (from new filesystem module)
 pub use filesystem::{
-    check_filesystem, format_filesystem, get_filesystem_label, get_mount_options, get_mount_point,
-    mount_filesystem, repair_filesystem, reset_mount_options, set_filesystem_label,
-    set_mount_options, take_filesystem_ownership, unmount_filesystem,
+    check_filesystem, format_filesystem, get_filesystem_label, get_filesystem_type,
+    get_filesystem_uuid, get_mount_options, get_mount_point, mount_filesystem, repair_filesystem,
+    reset_mount_options, set_filesystem_label, set_mount_options, take_filesystem_ownership,
+    unmount_filesystem,
 };
 
 // Encryption operations (from new encryption module)
@@ -89,12 +96,20 @@ pub use encryption::{
 };
 
 // SMART operations (from new smart module)
-pub use smart::{get_smart_info_by_device, start_drive_smart_selftest_by_device};
+pub use smart::{
+    abort_drive_smart_selftest_by_device, get_smart_info_by_device,
+    start_drive_smart_selftest_by_device,
+};
 
 // Explicit exports from options module (mount/encryption option parsing)
 pub use infra::options::{
-    join_options, merge_other_with_managed, normalize_options, remove_prefixed, remove_token,
-    set_prefixed_value, set_token_present, split_options, stable_dedup,
+    join_options, merge_other_with_managed, normalize_options, option_tokens, remove_prefixed,
+    remove_token, set_prefixed_value, set_token_present, split_options, stable_dedup,
+};
+
+// Explicit exports from bytestring module (UDisks paths and options)
+pub use dbus::bytestring::{
+    decode_c_string, decode_c_string_bytes, decode_mount_points, encode_bytestring,
 };
 
 // Explicit exports from usage module (filesystem usage statistics)
@@ -11,6 +11,7 @@ pub mod types;
 
 pub use info::{get_drive_smart_info, get_smart_info_by_device};
 pub use test::{
-    abort_drive_smart_selftest, start_drive_smart_selftest, start_drive_smart_selftest_by_device,
+    abort_drive_smart_selftest, abort_drive_smart_selftest_by_device, start_drive_smart_selftest,
+    start_drive_smart_selftest_by_device,
 };
 pub use types::*;
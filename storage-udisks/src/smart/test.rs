@@ -51,6 +51,14 @@ pub async fn start_drive_smart_selftest(
     }
 }
 
+/// Abort the SMART self-test running on a drive by device path (e.g. "/dev/sda")
+pub async fn abort_drive_smart_selftest_by_device(device: &str) -> Result<()> {
+    let drive_path = crate::disk::resolve::drive_object_path_for_device(device)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    abort_drive_smart_selftest(drive_path).await
+}
+
 /// Abort a SMART self-test on a drive
 ///
 /// Tries NVMe interface first, falls back to ATA if not supported.